@@ -99,6 +99,7 @@ export interface PropertyEditorValue {
   key?: string;
   value: unknown;
   isFromExternalSource: boolean;
+  revision: number;
 }
 
 export interface PropertyEditorValues {
@@ -202,13 +202,12 @@ export const useComponentAttributesStore = (componentId: ComponentId) => {
 
           // If the valueid for this update does not exist in the values tree,
           // we shouldn't perform the update!
-          if (
-            this.currentValueForValueId(
-              isInsert
-                ? updatePayload.insert.parentAttributeValueId
-                : updatePayload.update.attributeValueId,
-            ) === undefined
-          ) {
+          const currentValue = this.currentValueForValueId(
+            isInsert
+              ? updatePayload.insert.parentAttributeValueId
+              : updatePayload.update.attributeValueId,
+          );
+          if (currentValue === undefined) {
             return;
           }
 
@@ -223,13 +222,23 @@ export const useComponentAttributesStore = (componentId: ComponentId) => {
               ? "component/insert_property_editor_value"
               : "component/update_property_editor_value",
             params: {
-              ...(isInsert ? updatePayload.insert : updatePayload.update),
+              ...(isInsert
+                ? updatePayload.insert
+                : {
+                    ...updatePayload.update,
+                    // the update is rejected if someone else changed the value since we loaded it
+                    expectedRevision: currentValue.revision,
+                  }),
               ...visibilityParams,
             },
             // onSuccess() {},
-            onFail() {
+            onFail: (response) => {
               // may not work exactly right with concurrent updates... but I dont think will be a problem
               statusStore.cancelUpdateStarted();
+              // someone else changed the value first, reload it so the user can redo their edit on top
+              if (response?.error?.code === "CONFLICT") {
+                this.FETCH_PROPERTY_EDITOR_VALUES();
+              }
             },
           });
         },
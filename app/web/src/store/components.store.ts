@@ -542,6 +542,7 @@ export const useComponentsStore = (forceChangeSetId?: ChangeSetId) => {
           return new ApiRequest<{
             componentId: ComponentId;
             nodeId: ComponentNodeId;
+            frameAttachFailed: boolean;
           }>({
             method: "post",
             url: "diagram/create_node",
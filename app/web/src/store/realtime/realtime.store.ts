@@ -4,7 +4,7 @@ import ReconnectingWebSocket from "reconnecting-websocket";
 import { computed, reactive, ref, watch } from "vue";
 import { API_WS_URL } from "@/store/apis";
 import { useAuthStore } from "../auth.store";
-import { WsEventPayloadMap } from "./realtime_events";
+import { PresenceUpdate, WsEventPayloadMap } from "./realtime_events";
 
 type RawConnectionStatus = "open" | "closed";
 
@@ -35,6 +35,8 @@ type TrackedSubscription = EventTypeAndCallback & {
 type RealtimeEventMetadata = {
   version: number;
   workspace_pk: string;
+  // echoes the clientRequestId sent with the request which caused the event, if any
+  client_request_id?: string;
 };
 
 export const useRealtimeStore = defineStore("realtime", () => {
@@ -184,6 +186,12 @@ export const useRealtimeStore = defineStore("realtime", () => {
       _.omit(messageEventData, "payload") as RealtimeEventMetadata,
     );
   });
+  // share where we are and what we are looking at with the other users of the workspace
+  function sendPresence(update: PresenceUpdate) {
+    if (rawConnectionStatus.value !== "open") return;
+    socket.send(JSON.stringify({ kind: "PresenceUpdate", data: update }));
+  }
+
   socket.addEventListener("error", (errorEvent) => {
     /* eslint-disable-next-line no-console */
     console.log("ws error", errorEvent.error, errorEvent.message);
@@ -194,5 +202,6 @@ export const useRealtimeStore = defineStore("realtime", () => {
     // subscriptions, // can expose here to show in devtools
     subscribe,
     unsubscribe,
+    sendPresence,
   };
 });
@@ -11,15 +11,76 @@ import {
   StatusUpdatePk,
 } from "../status.store";
 
+type CommentTarget =
+  | { kind: "changeSet"; changeSetPk: string }
+  | { kind: "component"; componentId: string }
+  | { kind: "prop"; componentId: string; propPath: string };
+
+type ComponentLifecycleState =
+  | "Applying"
+  | "Degraded"
+  | "Destroyed"
+  | "Destroying"
+  | "Draft"
+  | "Failed"
+  | "Provisioned"
+  | "Qualified";
+
+export type PresenceUpdate = {
+  changeSetPk?: string;
+  selectedComponentIds: ComponentId[];
+  cursor?: { x: number; y: number };
+};
+
+export type UserPresence = PresenceUpdate & {
+  sessionId: string;
+  userPk: string;
+  lastSeenAt: string;
+};
+
+type ChangeSetApplySchedulePayload = {
+  changeSetPk: string;
+  schedulePk: string;
+  applyAt: string;
+  status: "Applied" | "Canceled" | "Failed" | "Scheduled" | "Started";
+  failure?: string;
+};
+
 // TODO: a few of these use the same id objects (ex: componentId)
 // but in a few cases the changeset ID may have been accidentally left out?
 // once things are working again, we should do a big review of all the realtime events coming from the backend...
 
 export type WsEventPayloadMap = {
+  AttributeValueUpdated: {
+    componentId: ComponentId;
+    attributeValueId: AttributeValueId;
+    propId: string;
+    propPath: string;
+    oldValue: unknown;
+    newValue: unknown;
+    redacted: boolean;
+    truncated: boolean;
+  };
   ChangeSetCreated: string;
   ChangeSetApplied: string;
   ChangeSetWritten: string;
   ChangeSetCancelled: string;
+  ChangeSetReviewRequested: {
+    changeSetPk: string;
+    reviewPk: string;
+    reviewerUserPk: string;
+    status: "Approved" | "Pending" | "Rejected";
+  };
+  ChangeSetReviewed: {
+    changeSetPk: string;
+    reviewPk: string;
+    reviewerUserPk: string;
+    status: "Approved" | "Pending" | "Rejected";
+  };
+  ChangeSetApplyScheduled: ChangeSetApplySchedulePayload;
+  ChangeSetApplyScheduleStarted: ChangeSetApplySchedulePayload;
+  ChangeSetApplyScheduleFailed: ChangeSetApplySchedulePayload;
+  ChangeSetApplyScheduleCanceled: ChangeSetApplySchedulePayload;
 
   CheckedQualifications: {
     prototypeId: string;
@@ -30,6 +91,23 @@ export type WsEventPayloadMap = {
     componentId: string;
   };
 
+  CommentCreated: {
+    commentId: string;
+    target: CommentTarget;
+  };
+  CommentUpdated: {
+    commentId: string;
+    target: CommentTarget;
+  };
+  CommentDeleted: {
+    commentId: string;
+    target: CommentTarget;
+  };
+
+  PresenceJoined: UserPresence;
+  PresenceUpdated: UserPresence;
+  PresenceLeft: UserPresence;
+
   // NOT CURRENTLY USED - but leaving here so we remember these events exist
   // SecretCreated: number;
   ResourceRefreshed: {
@@ -61,9 +139,32 @@ export type WsEventPayloadMap = {
     id: string;
     status: FixStatus;
   };
+  FixPlanUpdated: {
+    planPk: string;
+    status: "Completed" | "Failed" | "Paused" | "Pending" | "Running";
+    nextPhase: number;
+    phaseCount: number;
+  };
   ComponentCreated: {
     success: boolean;
   };
+  ComponentLabelChanged: {
+    componentId: string;
+    key: string;
+    value: string | null;
+  };
+  ComponentLifecycleChanged: {
+    componentId: string;
+    state: ComponentLifecycleState;
+    previousState: ComponentLifecycleState;
+  };
+  NodesMoved: {
+    positions: { nodeId: string; x: string; y: string }[];
+  };
+  NodesDeleted: {
+    nodeIds: string[];
+    componentIds: string[];
+  };
 
   // Old fake status update
   // UpdateStatus: {
@@ -50,6 +50,16 @@ export type ResolverFunctionResult =
 export interface ResolverFunctionResultSuccess extends ResultSuccess {
   data: unknown;
   unset: boolean;
+  artifacts?: Array<Artifact>;
+}
+
+// A named file returned by a code generation function under `artifacts`, whose content is sent
+// base64 encoded.
+export interface Artifact {
+  name: string;
+  mimeType: string;
+  contentBase64: string;
+  metadata: Record<string, string>;
 }
 
 export interface ResolverFunctionResultFailure extends ResultFailure {
@@ -118,9 +128,60 @@ const isCodeGeneration = (value: unknown): TypeCheckResult => {
     };
   }
 
+  if ("artifacts" in value && !_.isUndefined(value.artifacts)) {
+    return isArtifacts(value.artifacts);
+  }
+
   return { valid: true };
 };
 
+const isArtifacts = (value: unknown): TypeCheckResult => {
+  if (!_.isArray(value)) {
+    return { valid: false, message: "The artifacts field type must be an array" };
+  }
+
+  const names = new Set<string>();
+  for (const artifact of value) {
+    if (typeof artifact !== 'object' || !artifact) {
+      return { valid: false, message: "Every artifact must be an object" };
+    }
+    if (!("name" in artifact) || !_.isString(artifact.name) || artifact.name === "") {
+      return { valid: false, message: "The artifact name field type must be a non-empty string" };
+    }
+    if (names.has(artifact.name)) {
+      return { valid: false, message: `Artifact names must be unique, found '${artifact.name}' twice` };
+    }
+    names.add(artifact.name);
+    if (!("mimeType" in artifact) || !_.isString(artifact.mimeType)) {
+      return { valid: false, message: "The artifact mimeType field type must be a string" };
+    }
+    if (!("content" in artifact) || !(_.isString(artifact.content) || artifact.content instanceof Uint8Array)) {
+      return { valid: false, message: "The artifact content field type must be a string or a Buffer" };
+    }
+    if ("metadata" in artifact && !_.isUndefined(artifact.metadata)) {
+      if (!_.isPlainObject(artifact.metadata) || !_.every(artifact.metadata, _.isString)) {
+        return { valid: false, message: "The artifact metadata field must be an object of strings" };
+      }
+    }
+  }
+
+  return { valid: true };
+};
+
+// Moves the artifacts out of a code generation result, encoding their content for the trip back
+// to the caller.
+const extractArtifacts = (value: Record<string, unknown>): Array<Artifact> => {
+  const artifacts = (value.artifacts ?? []) as Array<Record<string, unknown>>;
+  delete value.artifacts;
+
+  return artifacts.map((artifact) => ({
+    name: artifact.name as string,
+    mimeType: artifact.mimeType as string,
+    contentBase64: Buffer.from(artifact.content as string | Uint8Array).toString("base64"),
+    metadata: (artifact.metadata ?? {}) as Record<string, string>,
+  }));
+};
+
 const qualificationStatuses = ["warning", "failure", "success", "unknown"];
 const isQualification = (value: unknown): TypeCheckResult => {
   if (typeof value !== 'object' || !value) {
@@ -249,12 +310,16 @@ async function execute(
   if (validationFunc) {
     const validationResult = validationFunc(resolverFunctionResult);
     if (validationResult.valid === true) {
+      const artifacts = responseType === FuncBackendResponseType.CodeGeneration
+        ? extractArtifacts(resolverFunctionResult)
+        : [];
       return {
         protocol: "result",
         status: "success",
         executionId,
         data: resolverFunctionResult,
         unset: false,
+        artifacts,
       };
     } else {
       return {
@@ -63,6 +63,10 @@ pub(crate) struct Args {
     /// back to an instance of a Pinga service.
     #[arg(long)]
     pub(crate) instance_id: Option<String>,
+
+    /// Enables a feature flag, may be given more than once [example: new_attribute_engine]
+    #[arg(long = "enable-feature")]
+    pub(crate) enable_features: Vec<String>,
 }
 
 impl TryFrom<Args> for Config {
@@ -97,6 +101,9 @@ impl TryFrom<Args> for Config {
             if let Some(instance_id) = args.instance_id {
                 config_map.set("instance_id", instance_id);
             }
+            for feature in args.enable_features {
+                config_map.set(format!("features.{feature}"), true);
+            }
 
             config_map.set("pg.application_name", NAME);
         })?
@@ -51,7 +51,9 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     start_tracing_level_signal_handler_task(&telemetry)?;
 
-    Server::from_config(config).await?.run().await?;
+    let mut server = Server::from_config(config).await?;
+    server.set_telemetry_client(telemetry);
+    server.run().await?;
 
     Ok(())
 }
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{builder::PossibleValuesParser, ArgAction, Parser};
+use clap::{builder::PossibleValuesParser, ArgAction, Parser, Subcommand};
 use sdf_server::{Config, ConfigError, ConfigFile, MigrationMode, StandardConfigFile};
 
 const NAME: &str = "sdf";
@@ -51,6 +51,10 @@ pub(crate) struct Args {
     #[arg(long, value_parser = PossibleValuesParser::new(MigrationMode::variants()))]
     pub(crate) migration_mode: Option<String>,
 
+    /// Builtins to migrate, all of them if unset [example: docker,coreos]
+    #[arg(long, value_delimiter = ',')]
+    pub(crate) builtins: Option<Vec<String>>,
+
     /// Disable OpenTelemetry on startup
     #[arg(long)]
     pub(crate) disable_opentelemetry: bool,
@@ -73,6 +77,30 @@ pub(crate) struct Args {
 
     /// Location on disk of available packages
     pub(crate) pkgs_path: Option<String>,
+
+    /// Enables a feature flag, may be given more than once [example: new_attribute_engine]
+    #[arg(long = "enable-feature")]
+    pub(crate) enable_features: Vec<String>,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum Command {
+    /// Runs the data migrations after the database migrations, then exits
+    Migrate(MigrateArgs),
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct MigrateArgs {
+    /// Data migration version to migrate to, reverting newer migrations [default: latest]
+    #[arg(long)]
+    pub(crate) target_version: Option<i64>,
+
+    /// Reports the rows each data migration would affect, without committing any changes
+    #[arg(long)]
+    pub(crate) dry_run: bool,
 }
 
 impl TryFrom<Args> for Config {
@@ -98,6 +126,9 @@ impl TryFrom<Args> for Config {
             if let Some(migration_mode) = args.migration_mode {
                 config_map.set("migration_mode", migration_mode);
             }
+            if let Some(builtins) = args.builtins {
+                config_map.set("builtins", builtins);
+            }
             if let Some(url) = args.nats_url {
                 config_map.set("nats.url", url);
             }
@@ -107,6 +138,9 @@ impl TryFrom<Args> for Config {
             if let Some(pkgs_path) = args.pkgs_path {
                 config_map.set("pkgs_path", pkgs_path);
             }
+            for feature in args.enable_features {
+                config_map.set(format!("features.{feature}"), true);
+            }
 
             config_map.set("pg.application_name", NAME);
         })?
@@ -69,6 +69,7 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         return Ok(());
     }
 
+    let command = args.command.clone();
     let config = Config::try_from(args)?;
 
     let encryption_key = Server::load_encryption_key(config.cyclone_encryption_key_path()).await?;
@@ -81,15 +82,29 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     let (_resource_job_client, resource_job_processor) = JobProcessor::connect(&config).await?;
     let (_, status_receiver_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, audit_log_pruner_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, blob_garbage_collector_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, history_event_pruner_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, qualification_rechecker_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, notifier_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, webhook_dispatcher_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, workspace_backup_scheduler_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, usage_aggregation_scheduler_job_processor) = JobProcessor::connect(&config).await?;
 
     let pg_pool = Server::create_pg_pool(config.pg_pool()).await?;
 
-    let veritech = Server::create_veritech_client(nats.clone());
+    let veritech = Server::create_veritech_client(config.veritech(), nats.clone()).await?;
 
     let pkgs_path: PathBuf = config.pkgs_path().try_into()?;
 
     let module_index_url = config.module_index_url().to_string();
 
+    let history_event_retention = config.history_event_retention();
+
+    let workspace_backups = config.workspace_backups();
+
+    let smtp = config.smtp().cloned();
+
     if let MigrationMode::Run | MigrationMode::RunAndQuit = config.migration_mode() {
         Server::migrate_database(
             &pg_pool,
@@ -99,6 +114,7 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
             &encryption_key,
             pkgs_path.to_owned(),
             module_index_url.clone(),
+            config.builtins().cloned(),
         )
         .await?;
         if let MigrationMode::RunAndQuit = config.migration_mode() {
@@ -112,10 +128,39 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         trace!("migration mode is skip, not running migrations");
     }
 
+    if let Some(args::Command::Migrate(migrate_args)) = command {
+        let reports = Server::migrate_data(
+            &pg_pool,
+            &nats,
+            job_processor,
+            veritech,
+            &encryption_key,
+            migrate_args.target_version,
+            migrate_args.dry_run,
+        )
+        .await?;
+        if reports.is_empty() {
+            println!("No data migrations to run");
+        }
+        for report in reports {
+            println!(
+                "{} {} {}: {} row(s){}",
+                report.direction,
+                report.version,
+                report.name,
+                report.affected_rows,
+                if report.dry_run { " (dry run)" } else { "" },
+            );
+        }
+        return Ok(());
+    }
+
     start_tracing_level_signal_handler_task(&telemetry)?;
 
     let posthog_client = Server::start_posthog(config.posthog()).await?;
 
+    let ws_nats_credentials = Server::load_ws_nats_credentials(&config).await?;
+
     match config.incoming_stream() {
         IncomingStream::HTTPSocket(_) => {
             let (server, initial_shutdown_broadcast_rx) = Server::http(
@@ -129,8 +174,17 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 posthog_client,
                 pkgs_path,
                 module_index_url,
+                ws_nats_credentials,
             )?;
             let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let third_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fourth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fifth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let sixth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let seventh_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let eighth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let ninth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let tenth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
             Server::start_resource_refresh_scheduler(
                 pg_pool.clone(),
@@ -143,15 +197,98 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
             .await;
 
             Server::start_status_updater(
-                pg_pool,
-                nats,
+                pg_pool.clone(),
+                nats.clone(),
                 status_receiver_job_processor,
-                veritech,
+                veritech.clone(),
                 encryption_key,
                 second_shutdown_broadcast_rx,
             )
             .await?;
 
+            Server::start_audit_log_pruner(
+                pg_pool.clone(),
+                nats.clone(),
+                audit_log_pruner_job_processor,
+                veritech.clone(),
+                encryption_key,
+                third_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_blob_garbage_collector(
+                pg_pool.clone(),
+                nats.clone(),
+                blob_garbage_collector_job_processor,
+                veritech.clone(),
+                encryption_key,
+                eighth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_history_event_pruner(
+                pg_pool.clone(),
+                nats.clone(),
+                history_event_pruner_job_processor,
+                veritech.clone(),
+                encryption_key,
+                history_event_retention,
+                fifth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_workspace_backup_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                workspace_backup_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                workspace_backups,
+                ninth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_usage_aggregation_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                usage_aggregation_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                tenth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_qualification_rechecker(
+                pg_pool.clone(),
+                nats.clone(),
+                qualification_rechecker_job_processor,
+                veritech.clone(),
+                encryption_key,
+                fourth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_webhook_dispatcher(
+                pg_pool.clone(),
+                nats.clone(),
+                webhook_dispatcher_job_processor,
+                veritech.clone(),
+                encryption_key,
+                seventh_shutdown_broadcast_rx,
+            )
+            .await?;
+
+            Server::start_notifier(
+                pg_pool.clone(),
+                nats.clone(),
+                notifier_job_processor,
+                veritech,
+                encryption_key,
+                smtp,
+                sixth_shutdown_broadcast_rx,
+            )
+            .await?;
+
             server.run().await?;
         }
         IncomingStream::UnixDomainSocket(_) => {
@@ -166,9 +303,18 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 posthog_client,
                 pkgs_path,
                 module_index_url,
+                ws_nats_credentials,
             )
             .await?;
             let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let third_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fourth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fifth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let sixth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let seventh_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let eighth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let ninth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let tenth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
             Server::start_resource_refresh_scheduler(
                 pg_pool.clone(),
@@ -181,15 +327,98 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
             .await;
 
             Server::start_status_updater(
-                pg_pool,
-                nats,
+                pg_pool.clone(),
+                nats.clone(),
                 status_receiver_job_processor,
-                veritech,
+                veritech.clone(),
                 encryption_key,
                 second_shutdown_broadcast_rx,
             )
             .await?;
 
+            Server::start_audit_log_pruner(
+                pg_pool.clone(),
+                nats.clone(),
+                audit_log_pruner_job_processor,
+                veritech.clone(),
+                encryption_key,
+                third_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_blob_garbage_collector(
+                pg_pool.clone(),
+                nats.clone(),
+                blob_garbage_collector_job_processor,
+                veritech.clone(),
+                encryption_key,
+                eighth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_history_event_pruner(
+                pg_pool.clone(),
+                nats.clone(),
+                history_event_pruner_job_processor,
+                veritech.clone(),
+                encryption_key,
+                history_event_retention,
+                fifth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_workspace_backup_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                workspace_backup_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                workspace_backups,
+                ninth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_usage_aggregation_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                usage_aggregation_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                tenth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_qualification_rechecker(
+                pg_pool.clone(),
+                nats.clone(),
+                qualification_rechecker_job_processor,
+                veritech.clone(),
+                encryption_key,
+                fourth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_webhook_dispatcher(
+                pg_pool.clone(),
+                nats.clone(),
+                webhook_dispatcher_job_processor,
+                veritech.clone(),
+                encryption_key,
+                seventh_shutdown_broadcast_rx,
+            )
+            .await?;
+
+            Server::start_notifier(
+                pg_pool.clone(),
+                nats.clone(),
+                notifier_job_processor,
+                veritech,
+                encryption_key,
+                smtp,
+                sixth_shutdown_broadcast_rx,
+            )
+            .await?;
+
             server.run().await?;
         }
     }
@@ -199,5 +428,13 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         error!("Failed to close job client: {err}");
     }
 
+    // Only close the connections once in-flight requests have drained and queued jobs have been
+    // flushed by closing the job processor client above
+    info!("Closing the NATS connection and database pool");
+    if let Err(err) = nats.close().await {
+        error!("Failed to close NATS connection: {err}");
+    }
+    pg_pool.close();
+
     Ok(())
 }
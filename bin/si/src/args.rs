@@ -1,4 +1,6 @@
 use clap::{builder::PossibleValuesParser, Parser, Subcommand};
+use si_cli::CONTAINER_NAMES;
+use std::path::PathBuf;
 use std::str::FromStr;
 use strum::{Display, EnumString, EnumVariantNames};
 
@@ -50,7 +52,7 @@ pub(crate) struct Args {
     /// Show a preview of what the System Initiative Launcher will do
     #[arg(long, short = 'p', default_value = "false")]
     pub is_preview: bool,
-    
+
     /// Allows starting the web service and binding to a specific IP
     #[arg(long = "web-host", env = "SI_WEB_ADDRESS", default_value = "127.0.0.1")]
     pub web_host: String,
@@ -59,7 +61,8 @@ pub(crate) struct Args {
     #[arg(long = "web-port", env = "SI_WEB_PORT", default_value = "8080")]
     pub web_port: u32,
 
-    /// The engine in which to launch System Initiate Containers
+    /// The engine in which to launch System Initiate Containers. `compose` generates a
+    /// docker-compose file in the SI data dir and runs it with `docker compose`
     #[arg(value_parser = PossibleValuesParser::new(Engine::variants()))]
     #[arg(long, short, env = "SI_CONTAINER_ENGINE", default_value = "docker")]
     engine: String,
@@ -92,8 +95,16 @@ pub(crate) enum Commands {
     Delete(DeleteArgs),
     /// Updates the System Initiative CLI Launcher
     Update(UpdateArgs),
+    /// Upgrades the System Initiative components to newer images, keeping their data
+    Upgrade(UpgradeArgs),
     /// Checks the status of the specified installation mode
     Status(StatusArgs),
+    /// Shows the logs of the System Initiative components
+    Logs(LogsArgs),
+    /// Backs up the System Initiative database, keys and packages to an archive
+    Backup(BackupArgs),
+    /// Restores the System Initiative database, keys and packages from a backup archive
+    Restore(RestoreArgs),
     // Reports an error to System Initiative.
     // Report(ReportArgs),
 }
@@ -103,7 +114,6 @@ pub(crate) struct LaunchArgs {
     /// Allows the launching of the metrics collection endpoint
     #[clap(long)]
     pub metrics: bool,
-    
 }
 
 // #[derive(Debug, clap::Args)]
@@ -130,9 +140,27 @@ pub(crate) struct StatusArgs {
 }
 
 #[derive(Debug, clap::Args)]
-pub(crate) struct StartArgs {
+pub(crate) struct LogsArgs {
+    /// The components to show the logs of, all of them if none are given
+    #[arg(value_parser = PossibleValuesParser::new(CONTAINER_NAMES))]
+    pub services: Vec<String>,
+
+    /// Keeps streaming new log lines as they are written
+    #[arg(long, short)]
+    pub follow: bool,
+
+    /// Only shows the logs written since this long ago, such as `30s`, `10m` or `2h`
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// The number of log lines to show from the end of each component's logs
+    #[arg(long, default_value = "200")]
+    pub tail: usize,
 }
 
+#[derive(Debug, clap::Args)]
+pub(crate) struct StartArgs {}
+
 #[derive(Debug, clap::Args)]
 pub(crate) struct RestartArgs {}
 
@@ -159,6 +187,29 @@ pub(crate) struct UpdateArgs {
     pub binary: bool,
 }
 
+#[derive(Debug, clap::Args)]
+pub(crate) struct UpgradeArgs {
+    /// The image tag to upgrade the components to
+    #[arg(long, default_value = "stable", conflicts_with = "rollback")]
+    pub version: String,
+    /// Restores the images the components were running before the last upgrade
+    #[arg(long)]
+    pub rollback: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct BackupArgs {
+    /// The archive to write, `si-backup-<timestamp>.tar.zst` in the current directory by default
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct RestoreArgs {
+    /// The archive written by `si backup` to restore
+    pub file: PathBuf,
+}
+
 #[derive(Debug, clap::Args)]
 pub(crate) struct InstallArgs {
     /// Skip the system check as part of the install command
@@ -184,6 +235,8 @@ pub enum Mode {
 
 #[derive(Clone, Copy, Debug, Display, EnumString, EnumVariantNames)]
 pub enum Engine {
+    #[strum(serialize = "compose")]
+    Compose,
     #[strum(serialize = "docker")]
     Docker,
     #[strum(serialize = "podman")]
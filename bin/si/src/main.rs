@@ -1,6 +1,6 @@
 use crate::args::{Commands, Engine};
 use color_eyre::{eyre::eyre, Result};
-use si_cli::{state::AppState, DockerClient};
+use si_cli::{engine::EngineKind, state::AppState, DockerClient};
 use std::sync::Arc;
 use telemetry_application::{prelude::*, TelemetryConfig};
 use tokio::sync::oneshot::Sender;
@@ -41,14 +41,32 @@ async fn main() -> Result<()> {
 
     tokio::spawn(wait_for_posthog_flush(ph_done_sender, ph_sender));
 
-    let docker_socket_candidates = vec![
-        #[allow(clippy::disallowed_methods)] // Used to determine a path relative to users's home
-        std::path::Path::new(&std::env::var("HOME")?)
-            .join(".docker")
-            .join("run")
-            .join("docker.sock"),
-        std::path::Path::new("/var/run/docker.sock").to_path_buf(),
-    ];
+    let engine = args.engine();
+    let docker_socket_candidates = match engine {
+        Engine::Docker | Engine::Compose => vec![
+            #[allow(clippy::disallowed_methods)]
+            // Used to determine a path relative to users's home
+            std::path::Path::new(&std::env::var("HOME")?)
+                .join(".docker")
+                .join("run")
+                .join("docker.sock"),
+            std::path::Path::new("/var/run/docker.sock").to_path_buf(),
+        ],
+        Engine::Podman => {
+            let mut candidates = Vec::new();
+            #[allow(clippy::disallowed_methods)] // Used to find the rootless Podman socket
+            let runtime_dir = std::env::var("XDG_RUNTIME_DIR");
+            if let Ok(runtime_dir) = runtime_dir {
+                candidates.push(
+                    std::path::Path::new(&runtime_dir)
+                        .join("podman")
+                        .join("podman.sock"),
+                );
+            }
+            candidates.push(std::path::Path::new("/run/podman/podman.sock").to_path_buf());
+            candidates
+        }
+    };
 
     let docker: DockerClient;
     if let "" = docker_sock.as_str() {
@@ -56,7 +74,7 @@ async fn main() -> Result<()> {
             .iter()
             .find(|candidate| candidate.exists())
             .ok_or(eyre!(
-            "failed to determine Docker socket location. Set a custom location using `--docker-sock` \
+            "failed to determine {engine} socket location. Set a custom location using `--docker-sock` \
             or `SI_DOCKER_SOCK`; candidates={docker_socket_candidates:?}"
         ))?;
         docker = DockerClient::unix(socket)
@@ -73,6 +91,11 @@ async fn main() -> Result<()> {
         is_preview,
         web_host,
         web_port,
+        match engine {
+            Engine::Compose => EngineKind::Compose,
+            Engine::Docker => EngineKind::Docker,
+            Engine::Podman => EngineKind::Podman,
+        },
     );
 
     println!(
@@ -108,11 +131,6 @@ async fn main() -> Result<()> {
         }
     }
 
-    if let Engine::Podman = args.engine() {
-        println!("Podman isn't supported as an engine at this time! It's coming soon though...");
-        return Ok(());
-    }
-
     if is_preview {
         println!("Preview mode... System Initiative would have taken the following actions");
     }
@@ -157,6 +175,20 @@ async fn main() -> Result<()> {
             state
                 .status(&docker, args.show_logs, args.log_lines)
                 .await?;
+        }
+        Commands::Upgrade(args) => {
+            state.upgrade(&docker, args.version, args.rollback).await?;
+        }
+        Commands::Logs(args) => {
+            state
+                .logs(&docker, args.services, args.follow, args.since, args.tail)
+                .await?;
+        }
+        Commands::Backup(args) => {
+            state.backup(&docker, args.output).await?;
+        }
+        Commands::Restore(args) => {
+            state.restore(&docker, args.file).await?;
         } // Commands::Report(_args) => {
           //     state.report().await?;
           // }
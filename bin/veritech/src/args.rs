@@ -21,6 +21,14 @@ pub(crate) struct Args {
     #[arg(long, short = 'u')]
     pub(crate) nats_url: Option<String>,
 
+    /// Serve Prometheus metrics over HTTP on this socket address [example: 0.0.0.0:5158]
+    #[arg(long)]
+    pub(crate) metrics_socket_addr: Option<String>,
+
+    /// The number of function requests that can be executed concurrently [default: 64]
+    #[arg(long)]
+    pub(crate) concurrency: Option<u32>,
+
     /// Disable OpenTelemetry on startup
     #[arg(long)]
     pub(crate) disable_opentelemetry: bool,
@@ -34,6 +42,12 @@ impl TryFrom<Args> for Config {
             if let Some(url) = args.nats_url {
                 config_map.set("nats.url", url);
             }
+            if let Some(metrics_socket_addr) = args.metrics_socket_addr {
+                config_map.set("metrics_socket_addr", metrics_socket_addr);
+            }
+            if let Some(concurrency) = args.concurrency {
+                config_map.set("concurrency_limit", i64::from(concurrency));
+            }
         })?
         .try_into()
     }
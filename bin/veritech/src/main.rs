@@ -37,10 +37,14 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     match config.cyclone_spec() {
         CycloneSpec::LocalHttp(_) => {
-            Server::for_cyclone_http(config).await?.run().await?;
+            let mut server = Server::for_cyclone_http(config).await?;
+            server.set_telemetry_client(telemetry);
+            server.run().await?;
         }
         CycloneSpec::LocalUds(_) => {
-            Server::for_cyclone_uds(config).await?.run().await?;
+            let mut server = Server::for_cyclone_uds(config).await?;
+            server.set_telemetry_client(telemetry);
+            server.run().await?;
         }
     }
 
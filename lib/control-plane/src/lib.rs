@@ -0,0 +1,272 @@
+//! This crate contains the control plane of the SI services which process work off NATS, so that
+//! their configuration can be changed while they run rather than by restarting them.
+//!
+//! Every instance of a [`ControlService`] subscribes to its [control
+//! subject](nats_control_subject) (outside of any queue group, so that every instance sees every
+//! command), applies each [`ControlCommand`] it receives and replies with a [`ControlAck`]. A
+//! command which can't be applied safely is rejected, leaving the configuration as it was.
+
+#![warn(
+    clippy::unwrap_in_result,
+    clippy::unwrap_used,
+    clippy::panic,
+    clippy::missing_panics_doc,
+    clippy::panic_in_result_fn
+)]
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+use telemetry::{ClientError, TelemetryClient, Verbosity};
+use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const NATS_CONTROL_DEFAULT_SUBJECT: &str = "si.control";
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ControlError {
+    #[error("invalid limit {0}, it must be between 1 and {}", u32::MAX)]
+    InvalidLimit(usize),
+    #[error("error updating telemetry: {0}")]
+    Telemetry(#[from] ClientError),
+    #[error("{0} does not support {1}")]
+    Unsupported(ControlService, &'static str),
+}
+
+pub type ControlResult<T> = Result<T, ControlError>;
+
+/// A service which takes [`ControlCommands`](ControlCommand).
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ControlService {
+    Pinga,
+    Veritech,
+}
+
+impl ControlService {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pinga => "pinga",
+            Self::Veritech => "veritech",
+        }
+    }
+}
+
+impl fmt::Display for ControlService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The subject on which every instance of a service takes [`ControlCommands`](ControlCommand).
+pub fn nats_control_subject(prefix: Option<&str>, service: ControlService) -> String {
+    let suffix = format!("{NATS_CONTROL_DEFAULT_SUBJECT}.{service}");
+    match prefix {
+        Some(prefix) => format!("{prefix}.{suffix}"),
+        None => suffix,
+    }
+}
+
+/// A change to the configuration of a running service.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ControlCommand {
+    /// Sets how many jobs (for pinga) or function executions (for veritech) an instance runs at
+    /// once. Lowering the limit lets the work in flight complete rather than interrupting it.
+    #[serde(rename_all = "camelCase")]
+    SetConcurrencyLimit { limit: usize },
+    /// Sets how many Cyclone instances a veritech instance keeps in its pool.
+    #[serde(rename_all = "camelCase")]
+    SetCyclonePoolSize { size: usize },
+    /// Sets which traces are logged.
+    #[serde(rename_all = "camelCase")]
+    SetLogLevel { level: LogLevel },
+}
+
+impl ControlCommand {
+    /// The name of the command, for logs and rejections.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SetConcurrencyLimit { .. } => "setConcurrencyLimit",
+            Self::SetCyclonePoolSize { .. } => "setCyclonePoolSize",
+            Self::SetLogLevel { .. } => "setLogLevel",
+        }
+    }
+}
+
+/// The traces a service logs, either as the verbosity of its `-v` flags or as `tracing`
+/// directives.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Directives(String),
+    Verbosity(u8),
+}
+
+impl LogLevel {
+    /// Applies the log level to the tracing setup of the process.
+    pub async fn apply(self, telemetry: &mut impl TelemetryClient) -> ControlResult<()> {
+        match self {
+            Self::Directives(directives) => telemetry.set_custom_tracing(directives).await?,
+            Self::Verbosity(verbosity) => {
+                telemetry.set_verbosity(Verbosity::from(verbosity)).await?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The reply of a service instance to a [`ControlCommand`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlAck {
+    pub service: ControlService,
+    pub instance_id: String,
+    pub outcome: ControlOutcome,
+}
+
+impl ControlAck {
+    pub fn new(
+        service: ControlService,
+        instance_id: impl Into<String>,
+        result: ControlResult<()>,
+    ) -> Self {
+        Self {
+            service,
+            instance_id: instance_id.into(),
+            outcome: match result {
+                Ok(()) => ControlOutcome::Applied,
+                Err(err) => ControlOutcome::Rejected {
+                    reason: err.to_string(),
+                },
+            },
+        }
+    }
+}
+
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ControlOutcome {
+    Applied,
+    Rejected { reason: String },
+}
+
+/// A limit on how much work runs at once, which can be changed while work is running.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+    limit: Arc<Mutex<usize>>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit: Arc::new(Mutex::new(limit)),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        *self.limit.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Waits until more work can run without going over the limit. The work holds the returned
+    /// permit for as long as it runs.
+    pub async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.semaphore.clone().acquire_owned().await.ok()
+    }
+
+    /// Changes the limit. Raising it admits waiting work right away, while lowering it takes
+    /// effect as the work over the new limit completes.
+    pub fn set(&self, limit: usize) -> ControlResult<()> {
+        if limit == 0 || u32::try_from(limit).is_err() {
+            return Err(ControlError::InvalidLimit(limit));
+        }
+
+        let mut current = self.limit.lock().unwrap_or_else(PoisonError::into_inner);
+        match limit.cmp(&*current) {
+            Ordering::Greater => self.semaphore.add_permits(limit - *current),
+            Ordering::Less => {
+                let excess = u32::try_from(*current - limit).unwrap_or(u32::MAX);
+                let semaphore = self.semaphore.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(excess).await {
+                        permits.forget();
+                    }
+                });
+            }
+            Ordering::Equal => {}
+        }
+        *current = limit;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrency_limit_changes_while_work_runs() {
+        let limit = ConcurrencyLimit::new(2);
+        let running = limit.acquire().await.expect("failed to acquire");
+
+        limit.set(4).expect("failed to raise limit");
+        assert_eq!(3, limit.semaphore.available_permits());
+
+        limit.set(1).expect("failed to lower limit");
+        tokio::task::yield_now().await;
+        assert_eq!(0, limit.semaphore.available_permits());
+        drop(running);
+        tokio::task::yield_now().await;
+        assert_eq!(1, limit.semaphore.available_permits());
+
+        assert!(matches!(limit.set(0), Err(ControlError::InvalidLimit(0))));
+        assert_eq!(1, limit.limit());
+    }
+
+    #[test]
+    fn commands_and_acks_serialize_with_their_kind() {
+        let command: ControlCommand = serde_json::from_value(serde_json::json!({
+            "kind": "setLogLevel",
+            "level": { "verbosity": 2 },
+        }))
+        .expect("failed to deserialize command");
+        assert_eq!(
+            ControlCommand::SetLogLevel {
+                level: LogLevel::Verbosity(2)
+            },
+            command
+        );
+
+        let ack = ControlAck::new(
+            ControlService::Pinga,
+            "pinga-1",
+            Err(ControlError::Unsupported(
+                ControlService::Pinga,
+                "setCyclonePoolSize",
+            )),
+        );
+        assert_eq!(
+            serde_json::json!({
+                "service": "pinga",
+                "instanceId": "pinga-1",
+                "outcome": {
+                    "status": "rejected",
+                    "reason": "pinga does not support setCyclonePoolSize",
+                },
+            }),
+            serde_json::to_value(&ack).expect("failed to serialize ack")
+        );
+    }
+}
@@ -26,12 +26,16 @@ use hyper::{
     Body, Method, Request, Response, StatusCode, Uri,
 };
 use hyperlocal::{UnixClientExt, UnixConnector, UnixStream};
+use telemetry::propagation::TraceContext;
 use thiserror::Error;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     net::TcpStream,
 };
-use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest, handshake::client::Request as WebSocketRequest},
+    WebSocketStream,
+};
 
 use crate::{execution, ping, watch, Execution, PingExecution, Watch};
 
@@ -375,7 +379,7 @@ where
         Ok(Request::builder().uri(uri))
     }
 
-    fn new_ws_request<P>(&self, path_and_query: P) -> Result<Uri>
+    fn new_ws_request<P>(&self, path_and_query: P) -> Result<WebSocketRequest>
     where
         P: TryInto<PathAndQuery, Error = InvalidUri>,
     {
@@ -390,7 +394,13 @@ where
         //    .body(())
         //    .map_err(ClientError::Request)?;
 
-        Ok(uri)
+        let mut request = uri
+            .into_client_request()
+            .map_err(ClientError::WebsocketConnection)?;
+        // Let the execution continue the trace of the current span
+        TraceContext::current().inject_into_http_headers(request.headers_mut());
+
+        Ok(request)
     }
 
     async fn get<P>(&self, path_and_query: P) -> Result<Response<Body>>
@@ -418,8 +428,8 @@ where
             .call(self.uri.clone())
             .await
             .map_err(|err| ClientError::Connect(err.into()))?;
-        let uri = self.new_ws_request(path_and_query)?;
-        let (websocket_stream, response) = tokio_tungstenite::client_async(uri, stream)
+        let request = self.new_ws_request(path_and_query)?;
+        let (websocket_stream, response) = tokio_tungstenite::client_async(request, stream)
             .await
             .map_err(ClientError::WebsocketConnection)?;
 
@@ -0,0 +1,23 @@
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+
+/// A named file produced by a function alongside its result, such as a rendered template or an
+/// archive too large or too binary to hold in a code generation string.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub name: String,
+    pub mime_type: String,
+    pub content_base64: String,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Artifact {
+    /// Decodes the content of the artifact.
+    pub fn content(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        general_purpose::STANDARD.decode(&self.content_base64)
+    }
+}
@@ -12,6 +12,7 @@
 )]
 
 mod action_run;
+mod artifact;
 mod canonical_command;
 mod component_view;
 mod encryption_key;
@@ -26,6 +27,7 @@ mod sensitive_container;
 mod validation;
 
 pub use action_run::{ActionRunRequest, ActionRunResultSuccess, ResourceStatus};
+pub use artifact::Artifact;
 pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView};
 pub use encryption_key::{EncryptionKey, EncryptionKeyError};
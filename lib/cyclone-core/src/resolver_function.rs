@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::ComponentView;
+use crate::{Artifact, ComponentView};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -50,5 +50,13 @@ pub struct ResolverFunctionResultSuccess {
     pub execution_id: String,
     pub data: Value,
     pub unset: bool,
+    /// Files produced by the function alongside its data, currently only by code generation
+    /// functions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+    /// Set when the function was given decrypted secrets, in which case its data may be derived
+    /// from them and should not be stored in plaintext.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
     pub timestamp: u64,
 }
@@ -328,6 +328,15 @@ where
                 }
             }
         }
+        // A result computed from secrets is flagged so it is not stored in plaintext. Results which
+        // have no use for the flag ignore it.
+        if !credentials.is_empty() {
+            if let Value::Object(object) = &mut value {
+                if object.get("status") == Some(&Value::String("success".to_owned())) {
+                    object.insert("sensitive".to_owned(), Value::Bool(true));
+                }
+            }
+        }
         let mut filtered_result: LangServerResult<LangServerSuccess> =
             serde_json::from_value(value).map_err(ExecutionError::JSONDeserialize)?;
         std::mem::swap(result, &mut filtered_result);
@@ -10,6 +10,7 @@ use axum::{
         ws::{self, WebSocket},
         Extension, State, WebSocketUpgrade,
     },
+    http::HeaderMap,
     response::IntoResponse,
 };
 use cyclone_core::{
@@ -21,6 +22,7 @@ use cyclone_core::{
 use hyper::StatusCode;
 use serde::{de::DeserializeOwned, Serialize};
 use telemetry::prelude::*;
+use telemetry::propagation::TraceContext;
 
 use super::extract::LimitRequestGuard;
 use crate::{
@@ -86,6 +88,7 @@ pub async fn ws_execute_resolver(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    headers: HeaderMap,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -95,6 +98,7 @@ pub async fn ws_execute_resolver(
         let success: PhantomData<ResolverFunctionResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            TraceContext::from_http_headers(&headers),
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -113,6 +117,7 @@ pub async fn ws_execute_validation(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    headers: HeaderMap,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -122,6 +127,7 @@ pub async fn ws_execute_validation(
         let success: PhantomData<ValidationResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            TraceContext::from_http_headers(&headers),
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -140,6 +146,7 @@ pub async fn ws_execute_action_run(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    headers: HeaderMap,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -149,6 +156,7 @@ pub async fn ws_execute_action_run(
         let success: PhantomData<ActionRunResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            TraceContext::from_http_headers(&headers),
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -167,6 +175,7 @@ pub async fn ws_execute_reconciliation(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    headers: HeaderMap,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -176,6 +185,7 @@ pub async fn ws_execute_reconciliation(
         let success: PhantomData<ReconciliationResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            TraceContext::from_http_headers(&headers),
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -194,6 +204,7 @@ pub async fn ws_execute_schema_variant_definition(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    headers: HeaderMap,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -203,6 +214,7 @@ pub async fn ws_execute_schema_variant_definition(
         let success: PhantomData<SchemaVariantDefinitionResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            TraceContext::from_http_headers(&headers),
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -216,8 +228,10 @@ pub async fn ws_execute_schema_variant_definition(
 }
 
 #[allow(clippy::too_many_arguments)]
+#[instrument(name = "cyclone.execute", skip_all, fields(sub_command = %sub_command))]
 async fn handle_socket<Request, LangServerSuccess, Success>(
     mut socket: WebSocket,
+    trace_context: TraceContext,
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<crate::DecryptionKey>,
@@ -231,6 +245,8 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     Success: Serialize + Unpin + fmt::Debug,
     LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
 {
+    // The parent must be set before the execution starts any span of its own
+    trace_context.apply_to(&Span::current());
     let proto = {
         let execution: Execution<Request, LangServerSuccess, Success> =
             execution::new(lang_server_path, lang_server_debugging, key, sub_command);
@@ -1,4 +1,4 @@
-use cyclone_core::ResolverFunctionResultSuccess;
+use cyclone_core::{Artifact, ResolverFunctionResultSuccess};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -9,6 +9,10 @@ pub struct LangServerResolverFunctionResultSuccess {
     #[serde(default)]
     pub data: Value,
     pub unset: bool,
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
+    #[serde(default)]
+    pub sensitive: bool,
 }
 
 impl From<LangServerResolverFunctionResultSuccess> for ResolverFunctionResultSuccess {
@@ -17,6 +21,8 @@ impl From<LangServerResolverFunctionResultSuccess> for ResolverFunctionResultSuc
             execution_id: value.execution_id,
             data: value.data,
             unset: value.unset,
+            artifacts: value.artifacts,
+            sensitive: value.sensitive,
             timestamp: crate::timestamp(),
         }
     }
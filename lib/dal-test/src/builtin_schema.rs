@@ -0,0 +1,146 @@
+//! This module provides a harness for asserting what a builtin schema produces, driven by the
+//! [`builtin_schema_test!`](crate::builtin_schema_test) macro.
+//!
+//! Each test has a fixture, `<name>.json`, in the `tests/builtin_schemas` directory of the crate
+//! invoking the macro. The fixture names the schema and the attribute values to apply to a new
+//! component:
+//!
+//! ```json
+//! {
+//!   "schema": "Docker Image",
+//!   "attributes": [
+//!     { "jsonPointer": "/root/domain/image", "value": "nginx" }
+//!   ]
+//! }
+//! ```
+//!
+//! The resulting [`ComponentView`] and generated code are compared against the golden files
+//! `<name>.view.json` and `<name>.code.json` next to the fixture. Qualifications and the time the
+//! resource was last synced are left out of the view, since they can change from run to run.
+//! Running the tests with `SI_TEST_BLESS=1` writes the golden files instead, which are then
+//! committed alongside the schema change that altered them.
+
+use std::{env, fs, path::Path};
+
+use dal::{
+    component::AttributeUpdate, Component, ComponentView, ComponentViewProperties, DalContext,
+    Schema, StandardModel,
+};
+use serde::Deserialize;
+
+const ENV_VAR_BLESS: &str = "SI_TEST_BLESS";
+
+/// The component to create for a builtin schema test.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltinSchemaFixture {
+    /// The name of the schema, whose default variant the component is created from.
+    pub schema: String,
+    #[serde(default)]
+    pub attributes: Vec<AttributeUpdate>,
+}
+
+/// Defines a test which creates a component from the fixture `tests/builtin_schemas/<name>.json`
+/// and checks its view and generated code against the golden files next to the fixture.
+///
+/// ```ignore
+/// builtin_schema_test!("kubernetes_deployment");
+/// ```
+#[macro_export]
+macro_rules! builtin_schema_test {
+    ($name:literal) => {
+        $crate::paste::paste! {
+            #[$crate::test]
+            async fn [<builtin_schema_ $name>](ctx: &::dal::DalContext) {
+                $crate::builtin_schema::assert_builtin_schema(
+                    ctx,
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/builtin_schemas"),
+                    $name,
+                )
+                .await;
+            }
+        }
+    };
+}
+
+/// Creates a component from the fixture `<name>.json` in `fixtures_dir` and checks its view and
+/// generated code against the golden files, or writes them when blessing.
+pub async fn assert_builtin_schema(ctx: &DalContext, fixtures_dir: &str, name: &str) {
+    let fixtures_dir = Path::new(fixtures_dir);
+    let fixture_path = fixtures_dir.join(format!("{name}.json"));
+    let fixture: BuiltinSchemaFixture = serde_json::from_str(
+        &fs::read_to_string(&fixture_path)
+            .unwrap_or_else(|err| panic!("cannot read fixture {}: {err}", fixture_path.display())),
+    )
+    .unwrap_or_else(|err| panic!("cannot parse fixture {}: {err}", fixture_path.display()));
+
+    let schema = Schema::find_by_name(ctx, &fixture.schema)
+        .await
+        .unwrap_or_else(|err| {
+            panic!(
+                "cannot find schema {}, is it migrated? {err}",
+                fixture.schema
+            )
+        });
+    let (component, _) = Component::new_for_default_variant_from_schema(ctx, name, *schema.id())
+        .await
+        .expect("cannot create component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    Component::update_attributes_bulk(ctx, *component.id(), fixture.attributes)
+        .await
+        .expect("cannot apply fixture attributes");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let component_view = ComponentView::new(ctx, *component.id())
+        .await
+        .expect("could not create component view");
+    let code = component_view
+        .properties
+        .get("code")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    let view = ComponentViewProperties::try_from(component_view)
+        .expect("could not convert component view to component view properties")
+        .drop_code()
+        .drop_qualification()
+        .drop_resource_last_synced()
+        .to_value()
+        .expect("could not convert to value");
+
+    assert_golden(&fixtures_dir.join(format!("{name}.view.json")), &view);
+    assert_golden(&fixtures_dir.join(format!("{name}.code.json")), &code);
+}
+
+// Environment variables are used exclusively in test and all are prefixed with `SI_TEST_`
+#[allow(clippy::disallowed_methods)]
+fn assert_golden(path: &Path, actual: &serde_json::Value) {
+    if env::var(ENV_VAR_BLESS).is_ok() {
+        let actual = serde_json::to_string_pretty(actual).expect("cannot serialize golden value");
+        fs::write(path, format!("{actual}\n"))
+            .unwrap_or_else(|err| panic!("cannot write golden file {}: {err}", path.display()));
+        return;
+    }
+
+    if !path.exists() {
+        panic!(
+            "no golden file at {}, run this test with {ENV_VAR_BLESS}=1 to write it",
+            path.display()
+        );
+    }
+    let expected: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("cannot read golden file {}: {err}", path.display())),
+    )
+    .unwrap_or_else(|err| panic!("cannot parse golden file {}: {err}", path.display()));
+    assert_eq!(
+        &expected,
+        actual,
+        "{} changed, run this test with {ENV_VAR_BLESS}=1 and commit the golden file",
+        path.display()
+    );
+}
@@ -10,14 +10,23 @@ use dal::{
 };
 use jwt_simple::algorithms::RSAKeyPairLike;
 use jwt_simple::{claims::Claims, reexports::coarsetime::Duration};
-use names::{Generator, Name};
+use names::{ADJECTIVES, NOUNS};
+use rand::{seq::SliceRandom, Rng};
 
 use crate::jwt_private_signing_key;
 
 pub mod component_bag;
-
-pub fn generate_fake_name() -> String {
-    Generator::with_naming(Name::Numbered).next().unwrap()
+pub mod faults;
+pub mod graph_fixture;
+
+/// Generates a name such as `quiet-otter-0042`, drawn from the seeded
+/// [`RandomSource`](dal::RandomSource) of the context.
+pub fn generate_fake_name(ctx: &DalContext) -> String {
+    ctx.random_source().with_rng(|rng| {
+        let adjective = ADJECTIVES.choose(rng).expect("no adjectives");
+        let noun = NOUNS.choose(rng).expect("no nouns");
+        format!("{adjective}-{noun}-{:04}", rng.gen_range(1..10000))
+    })
 }
 
 pub async fn create_auth_token(claim: UserClaim) -> String {
@@ -37,7 +46,7 @@ pub async fn workspace_signup(ctx: &DalContext) -> Result<(WorkspaceSignup, Stri
 
     let mut ctx = ctx.clone_with_head();
 
-    let workspace_name = generate_fake_name();
+    let workspace_name = generate_fake_name(&ctx);
     let user_name = format!("frank {workspace_name}");
     let user_email = format!("{workspace_name}@example.com");
 
@@ -47,13 +56,14 @@ pub async fn workspace_signup(ctx: &DalContext) -> Result<(WorkspaceSignup, Stri
     let auth_token = create_auth_token(UserClaim {
         user_pk: nw.user.pk(),
         workspace_pk: *nw.workspace.pk(),
+        api_token_pk: None,
     })
     .await;
     Ok((nw, auth_token))
 }
 
 pub async fn create_user(ctx: &DalContext) -> User {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     User::new(
         ctx,
         UserPk::generate(),
@@ -66,7 +76,7 @@ pub async fn create_user(ctx: &DalContext) -> User {
 }
 
 pub async fn create_change_set(ctx: &DalContext) -> ChangeSet {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     ChangeSet::new(ctx, &name, None)
         .await
         .expect("cannot create change_set")
@@ -125,3 +135,77 @@ pub async fn setup_identity_func(
         *identity_func_identity_arg.id(),
     )
 }
+
+/// Counts the rows of the object left in its table, in any change set, whether soft deleted or
+/// not.
+pub async fn count_rows_for_id<T: StandardModel>(ctx: &DalContext, id: &T::Id) -> i64 {
+    let row = ctx
+        .txns()
+        .await
+        .expect("could not get transactions")
+        .pg()
+        .query_one(
+            &format!(
+                "SELECT count(*) AS count FROM {} WHERE id = $1",
+                T::table_name()
+            ),
+            &[id],
+        )
+        .await
+        .expect("could not count rows");
+    row.get("count")
+}
+
+/// Counts the rows of the belongs to and many to many tables which refer to the object.
+pub async fn count_relationships_for_id<T: StandardModel>(ctx: &DalContext, id: &T::Id) -> i64 {
+    let txns = ctx.txns().await.expect("could not get transactions");
+    let relationship_tables = txns
+        .pg()
+        .query(
+            "SELECT table_name, table_type FROM standard_models
+             WHERE table_type IN ('belongs_to', 'many_to_many')",
+            &[],
+        )
+        .await
+        .expect("could not list relationship tables");
+
+    let mut count = 0;
+    for relationship_table in relationship_tables {
+        let table_name: String = relationship_table.get("table_name");
+        let table_type: String = relationship_table.get("table_type");
+        let (left_column, right_column) = match table_type.as_str() {
+            "belongs_to" => ("object_id", "belongs_to_id"),
+            _ => ("left_object_id", "right_object_id"),
+        };
+        let row = txns
+            .pg()
+            .query_one(
+                &format!(
+                    "SELECT count(*) AS count FROM {table_name}
+                     WHERE {left_column} = $1 OR {right_column} = $1"
+                ),
+                &[id],
+            )
+            .await
+            .expect("could not count relationships");
+        count += row.get::<_, i64>("count");
+    }
+    count
+}
+
+/// Asserts that the object was garbage collected: none of its rows are left, in any change set,
+/// and nothing refers to it anymore.
+pub async fn assert_garbage_collected<T: StandardModel>(ctx: &DalContext, id: &T::Id) {
+    assert_eq!(
+        0,
+        count_rows_for_id::<T>(ctx, id).await,
+        "rows left for {} {id}",
+        T::table_name()
+    );
+    assert_eq!(
+        0,
+        count_relationships_for_id::<T>(ctx, id).await,
+        "relationships left for {} {id}",
+        T::table_name()
+    );
+}
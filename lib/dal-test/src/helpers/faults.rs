@@ -0,0 +1,21 @@
+//! This module contains helpers for injecting faults into the database and messaging operations
+//! of a test, such as serialization failures on commit or slow publishes, so that the handling of
+//! failures can be tested.
+//!
+//! Faults are shared by everything using the pools of the test, including the servers running
+//! alongside it, and last until the end of the test or until they are cleared.
+
+use dal::DalContext;
+
+pub use si_data_nats::{NatsFault, NatsFaultTrigger, NatsFaults, NatsOperation};
+pub use si_data_pg::{PgFault, PgFaultTrigger, PgFaults, PgOperation, SqlState};
+
+/// Gets the faults injected into the database statements and commits of the test.
+pub fn pg_faults(ctx: &DalContext) -> &PgFaults {
+    ctx.pg_pool().faults()
+}
+
+/// Gets the faults injected into the messages published and the requests made by the test.
+pub fn nats_faults(ctx: &DalContext) -> &NatsFaults {
+    ctx.nats_conn().faults()
+}
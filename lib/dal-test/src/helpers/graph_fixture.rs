@@ -0,0 +1,276 @@
+//! This module contains [`GraphFixture`], which declares a graph of
+//! [`Components`](dal::Component) and the edges between them, and creates all of it at once.
+//!
+//! ```ignore
+//! let graph = GraphFixture::new(ctx)
+//!     .component("app", "fallout")
+//!     .component("img", "Docker Image")
+//!     .configures("img", "app")
+//!     .build()
+//!     .await;
+//! let app_bag = graph.component("app");
+//! ```
+
+use std::collections::HashMap;
+
+use dal::{
+    edge::EdgeKind,
+    socket::{SocketEdgeKind, SocketKind},
+    ComponentType, Connection, DalContext, Socket, StandardModel,
+};
+
+use crate::helpers::component_bag::{ComponentBag, ComponentBagger};
+
+/// An edge declared on a [`GraphFixture`], between the [`Components`](dal::Component) named
+/// `tail` and `head`.
+#[derive(Debug)]
+enum FixtureEdge {
+    /// Connects every output socket of `tail` to the input socket of `head` with the same name.
+    Configures { tail: String, head: String },
+    /// Connects the output socket of `tail` named `socket` to the input socket of `head` with the
+    /// same name.
+    ConfiguresThrough {
+        tail: String,
+        head: String,
+        socket: String,
+    },
+    /// Places `tail` inside `head`, which becomes a configuration frame.
+    Inside { tail: String, head: String },
+}
+
+/// A builder for a graph of [`Components`](dal::Component) and the edges between them.
+///
+/// Components are referred to by the name they are declared with, which is also the name they
+/// are created with. Nothing is created until [`Self::build()`], which panics if the graph
+/// cannot be created, as is customary for test helpers.
+#[derive(Debug)]
+pub struct GraphFixture<'a> {
+    ctx: &'a DalContext,
+    components: Vec<(String, String)>,
+    edges: Vec<FixtureEdge>,
+}
+
+impl<'a> GraphFixture<'a> {
+    pub fn new(ctx: &'a DalContext) -> Self {
+        Self {
+            ctx,
+            components: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Declares a [`Component`](dal::Component) named `name`, created from the default
+    /// [`SchemaVariant`](dal::SchemaVariant) of the [`Schema`](dal::Schema) named `schema_name`.
+    pub fn component(mut self, name: impl Into<String>, schema_name: impl Into<String>) -> Self {
+        self.components.push((name.into(), schema_name.into()));
+        self
+    }
+
+    /// Declares that `tail` configures `head`: each output socket of `tail` is connected to the
+    /// input socket of `head` with the same name, of which there must be at least one.
+    pub fn configures(mut self, tail: impl Into<String>, head: impl Into<String>) -> Self {
+        self.edges.push(FixtureEdge::Configures {
+            tail: tail.into(),
+            head: head.into(),
+        });
+        self
+    }
+
+    /// Declares that `tail` configures `head` through the sockets named `socket` only.
+    pub fn configures_through(
+        mut self,
+        tail: impl Into<String>,
+        socket: impl Into<String>,
+        head: impl Into<String>,
+    ) -> Self {
+        self.edges.push(FixtureEdge::ConfiguresThrough {
+            tail: tail.into(),
+            head: head.into(),
+            socket: socket.into(),
+        });
+        self
+    }
+
+    /// Declares that `child` is placed inside `frame`, which is made a configuration frame.
+    pub fn inside(mut self, child: impl Into<String>, frame: impl Into<String>) -> Self {
+        self.edges.push(FixtureEdge::Inside {
+            tail: child.into(),
+            head: frame.into(),
+        });
+        self
+    }
+
+    /// Creates the declared [`Components`](dal::Component) and edges, in the order they were
+    /// declared, and commits, running the jobs which propagate values along the edges.
+    pub async fn build(self) -> Graph {
+        let ctx = self.ctx;
+        let mut bagger = ComponentBagger::new();
+        let mut components = HashMap::new();
+        for (name, schema_name) in self.components {
+            let bag = bagger.create_component(ctx, &name, schema_name).await;
+            if components.insert(name.clone(), bag).is_some() {
+                panic!("component {name} declared more than once");
+            }
+        }
+        let graph = Graph {
+            components,
+            connections: Vec::new(),
+        };
+
+        let mut connections = Vec::new();
+        for edge in self.edges {
+            match edge {
+                FixtureEdge::Configures { tail, head } => {
+                    let tail_bag = graph.component(&tail);
+                    let head_bag = graph.component(&head);
+                    let mut connected = false;
+                    for tail_socket in Socket::list_for_component(ctx, tail_bag.component_id)
+                        .await
+                        .expect("could not list sockets for component")
+                    {
+                        if *tail_socket.kind() != SocketKind::Provider
+                            || *tail_socket.edge_kind() != SocketEdgeKind::ConfigurationOutput
+                        {
+                            continue;
+                        }
+                        if let Some(head_socket) = Socket::find_by_name_for_edge_kind_and_node(
+                            ctx,
+                            tail_socket.name(),
+                            SocketEdgeKind::ConfigurationInput,
+                            head_bag.node_id,
+                        )
+                        .await
+                        .expect("could not find socket by name")
+                        {
+                            connections.push(
+                                connect(ctx, tail_bag, &tail_socket, head_bag, &head_socket).await,
+                            );
+                            connected = true;
+                        }
+                    }
+                    if !connected {
+                        panic!("no output socket of {tail} matches an input socket of {head}");
+                    }
+                }
+                FixtureEdge::ConfiguresThrough { tail, head, socket } => {
+                    let tail_bag = graph.component(&tail);
+                    let head_bag = graph.component(&head);
+                    let tail_socket = Socket::find_by_name_for_edge_kind_and_node(
+                        ctx,
+                        &socket,
+                        SocketEdgeKind::ConfigurationOutput,
+                        tail_bag.node_id,
+                    )
+                    .await
+                    .expect("could not find socket by name")
+                    .unwrap_or_else(|| panic!("no output socket {socket} on {tail}"));
+                    let head_socket = Socket::find_by_name_for_edge_kind_and_node(
+                        ctx,
+                        &socket,
+                        SocketEdgeKind::ConfigurationInput,
+                        head_bag.node_id,
+                    )
+                    .await
+                    .expect("could not find socket by name")
+                    .unwrap_or_else(|| panic!("no input socket {socket} on {head}"));
+                    connections
+                        .push(connect(ctx, tail_bag, &tail_socket, head_bag, &head_socket).await);
+                }
+                FixtureEdge::Inside { tail, head } => {
+                    let child_bag = graph.component(&tail);
+                    let frame_bag = graph.component(&head);
+                    frame_bag
+                        .component(ctx)
+                        .await
+                        .set_type(ctx, ComponentType::ConfigurationFrame)
+                        .await
+                        .expect("could not set component type");
+                    let from_socket = Socket::find_frame_socket_for_node(
+                        ctx,
+                        child_bag.node_id,
+                        SocketEdgeKind::ConfigurationOutput,
+                    )
+                    .await
+                    .expect("could not find frame socket for child");
+                    let to_socket = Socket::find_frame_socket_for_node(
+                        ctx,
+                        frame_bag.node_id,
+                        SocketEdgeKind::ConfigurationInput,
+                    )
+                    .await
+                    .expect("could not find frame socket for frame");
+                    connections.push(
+                        Connection::new(
+                            ctx,
+                            child_bag.node_id,
+                            *from_socket.id(),
+                            frame_bag.node_id,
+                            *to_socket.id(),
+                            EdgeKind::Symbolic,
+                        )
+                        .await
+                        .expect("could not connect to frame"),
+                    );
+                }
+            }
+        }
+
+        ctx.blocking_commit()
+            .await
+            .expect("could not commit & run jobs");
+
+        Graph {
+            connections,
+            ..graph
+        }
+    }
+}
+
+async fn connect(
+    ctx: &DalContext,
+    tail_bag: &ComponentBag,
+    tail_socket: &Socket,
+    head_bag: &ComponentBag,
+    head_socket: &Socket,
+) -> Connection {
+    Connection::new(
+        ctx,
+        tail_bag.node_id,
+        *tail_socket.id(),
+        head_bag.node_id,
+        *head_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection")
+}
+
+/// The graph created by [`GraphFixture::build()`].
+#[derive(Debug)]
+pub struct Graph {
+    components: HashMap<String, ComponentBag>,
+    /// The connections created for the declared edges, in the order they were declared.
+    pub connections: Vec<Connection>,
+}
+
+impl Graph {
+    /// Returns the [`ComponentBag`] for the [`Component`](dal::Component) declared as `name`.
+    pub fn component(&self, name: &str) -> &ComponentBag {
+        self.components
+            .get(name)
+            .unwrap_or_else(|| panic!("no component {name} in graph"))
+    }
+
+    /// Returns the [`Connections`](Connection) from `tail` to `head`.
+    pub fn connections_between(&self, tail: &str, head: &str) -> Vec<&Connection> {
+        let tail_node_id = self.component(tail).node_id;
+        let head_node_id = self.component(head).node_id;
+        self.connections
+            .iter()
+            .filter(|connection| {
+                connection.source.node_id == tail_node_id
+                    && connection.destination.node_id == head_node_id
+            })
+            .collect()
+    }
+}
@@ -12,7 +12,8 @@ use buck2_resources::Buck2Resources;
 use dal::{
     builtins::SelectedTestBuiltinSchemas,
     job::processor::{JobQueueProcessor, NatsProcessor},
-    DalContext, JwtPublicSigningKey, ServicesContext,
+    Builtin, BuiltinsResult, DalContext, JwtPublicSigningKey, RandomSource, ServicesContext,
+    TestClock,
 };
 use derive_builder::Builder;
 use jwt_simple::prelude::RS256KeyPair;
@@ -23,17 +24,20 @@ use si_std::ResultExt;
 use telemetry::prelude::*;
 use tokio::{fs::File, io::AsyncReadExt, sync::Mutex};
 use uuid::Uuid;
-use veritech_client::EncryptionKey;
+use veritech_client::{EncryptionKey, RecordingMode, Recordings};
 use veritech_server::StandardConfig;
 
 pub use color_eyre::{
     self,
     eyre::{eyre, Result, WrapErr},
 };
+#[doc(hidden)]
+pub use paste;
 pub use si_test_macros::{dal_test as test, sdf_test};
 pub use telemetry;
 pub use tracing_subscriber;
 
+pub mod builtin_schema;
 pub mod helpers;
 pub mod test_harness;
 
@@ -42,6 +46,11 @@ const ENV_VAR_MODULE_INDEX_URL: &str = "SI_TEST_MODULE_INDEX_URL";
 const ENV_VAR_PG_HOSTNAME: &str = "SI_TEST_PG_HOSTNAME";
 const ENV_VAR_PG_DBNAME: &str = "SI_TEST_PG_DBNAME";
 const ENV_VAR_BUILTIN_SCHEMAS: &str = "SI_TEST_BUILTIN_SCHEMAS";
+const ENV_VAR_BUILTINS: &str = "SI_TEST_BUILTINS";
+const ENV_VAR_COMPONENT_VIEW_CACHE: &str = "SI_TEST_COMPONENT_VIEW_CACHE";
+const ENV_VAR_SEED: &str = "SI_TEST_SEED";
+const ENV_VAR_VERITECH_RECORDING: &str = "SI_TEST_VERITECH_RECORDING";
+const ENV_VAR_VERITECH_RECORDINGS_DIR: &str = "SI_TEST_VERITECH_RECORDINGS_DIR";
 
 pub static COLOR_EYRE_INIT: Once = Once::new();
 
@@ -88,6 +97,20 @@ pub struct Config {
     jwt_signing_private_key_path: String,
     #[builder(default)]
     pkgs_path: Option<PathBuf>,
+    /// Whether [`ComponentViews`](dal::ComponentView) are cached. Off by default so that tests
+    /// always see freshly built views.
+    #[builder(default)]
+    component_view_cache: bool,
+    /// The seed of the [`RandomSource`] of each test, drawn from entropy when unset.
+    #[builder(default)]
+    seed: Option<u64>,
+    /// Whether veritech results are recorded, or replayed without executing functions. Neither
+    /// when unset.
+    ///
+    /// Requests carry the names generated during a test, so recordings are only found again when
+    /// they are replayed with the `SI_TEST_SEED` they were recorded with.
+    #[builder(default)]
+    veritech_recordings: Option<Recordings>,
 }
 
 impl Config {
@@ -114,6 +137,39 @@ impl Config {
             config.module_index_url = value;
         }
 
+        if let Ok(value) = env::var(ENV_VAR_COMPONENT_VIEW_CACHE) {
+            config.component_view_cache = value == "true";
+        }
+
+        if let Ok(value) = env::var(ENV_VAR_VERITECH_RECORDING) {
+            let mode = match value.as_str() {
+                "record" => RecordingMode::Record,
+                "replay" => RecordingMode::Replay,
+                _ => {
+                    return Err(eyre!(
+                        "{ENV_VAR_VERITECH_RECORDING} must be record or replay, not {value}"
+                    ))
+                }
+            };
+            // Each crate keeps the recordings of its own tests
+            let dir = match env::var(ENV_VAR_VERITECH_RECORDINGS_DIR) {
+                Ok(dir) => PathBuf::from(dir),
+                Err(_) => PathBuf::from(env::var("CARGO_MANIFEST_DIR").wrap_err_with(|| {
+                    format!("{ENV_VAR_VERITECH_RECORDINGS_DIR} must be set outside of cargo")
+                })?)
+                .join("tests/veritech_recordings"),
+            };
+            config.veritech_recordings = Some(Recordings::new(dir, mode));
+        }
+
+        if let Ok(value) = env::var(ENV_VAR_SEED) {
+            config.seed = Some(
+                value
+                    .parse()
+                    .wrap_err_with(|| format!("{ENV_VAR_SEED} is not a u64: {value}"))?,
+            );
+        }
+
         Ok(config)
     }
 }
@@ -163,6 +219,10 @@ pub struct TestContext {
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     /// A key for re-recrypting messages to the function execution system.
     encryption_key: Arc<EncryptionKey>,
+    /// The clock of the services, which tests advance to move time forward.
+    clock: TestClock,
+    /// The seeded source of the random parts of the names generated during the test.
+    random_source: RandomSource,
 }
 
 impl TestContext {
@@ -226,9 +286,12 @@ impl TestContext {
 
     /// Creates a new [`ServicesContext`].
     pub async fn create_services_context(&self) -> ServicesContext {
-        let veritech = veritech_client::Client::new(self.nats_conn.clone());
+        let mut veritech = veritech_client::Client::new(self.nats_conn.clone());
+        if let Some(recordings) = &self.config.veritech_recordings {
+            veritech = veritech.with_recordings(recordings.clone());
+        }
 
-        ServicesContext::new(
+        let mut services_context = ServicesContext::new(
             self.pg_pool.clone(),
             self.nats_conn.clone(),
             self.job_processor.clone(),
@@ -236,7 +299,17 @@ impl TestContext {
             self.encryption_key.clone(),
             self.config.pkgs_path.to_owned(),
             None,
-        )
+        );
+        services_context.set_component_view_cache_enabled(self.config.component_view_cache);
+        services_context.set_clock(Arc::new(self.clock.clone()));
+        services_context.set_random_source(self.random_source.clone());
+
+        services_context
+    }
+
+    /// Gets the [`TestClock`] shared by the services contexts of this test.
+    pub fn clock(&self) -> TestClock {
+        self.clock.clone()
     }
 
     /// Gets a reference to the NATS configuration.
@@ -305,12 +378,20 @@ impl TestContextBuilder {
         let job_processor = Box::new(NatsProcessor::new(nats_conn.clone()))
             as Box<dyn JobQueueProcessor + Send + Sync>;
 
+        // As with the test database below, the seed is printed so that it is displayed for
+        // failing tests, which generate the same names when run again with `SI_TEST_SEED` set to
+        // it.
+        let seed = self.config.seed.unwrap_or_else(rand::random);
+        println!("Test seed: {seed}");
+
         Ok(TestContext {
             config,
             pg_pool,
             nats_conn,
             job_processor,
             encryption_key: self.encryption_key.clone(),
+            clock: TestClock::new(),
+            random_source: RandomSource::seeded(seed),
         })
     }
 
@@ -365,6 +446,10 @@ impl TestContextBuilder {
 }
 
 /// Generates a new pseudo-random NATS subject prefix.
+///
+/// Unlike the names generated during a test, this never comes from the seed: it isolates the
+/// test from every other test running against the same NATS server and database, including runs
+/// with the same seed.
 pub fn random_identifier_string() -> String {
     Uuid::new_v4().as_simple().to_string()
 }
@@ -525,6 +610,8 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
     // Check if the user would like to skip migrating schemas. This is helpful for boosting
     // performance when running integration tests that do not rely on builtin schemas.
     let selected_test_builtin_schemas = determine_selected_test_builtin_schemas();
+    let selected_builtins =
+        determine_selected_builtins().wrap_err("failed to parse selected builtins")?;
 
     info!("creating builtins");
     dal::migrate_builtins(
@@ -540,6 +627,7 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
             .to_owned()
             .expect("no pkgs path configured"),
         test_context.config.module_index_url.clone(),
+        selected_builtins,
     )
     .await
     .wrap_err("failed to run builtin migrations")?;
@@ -598,6 +686,20 @@ fn determine_selected_test_builtin_schemas() -> SelectedTestBuiltinSchemas {
     }
 }
 
+/// Narrows down which builtins are migrated when all builtin schemas are, such as with
+/// `SI_TEST_BUILTINS=docker,coreos`.
+fn determine_selected_builtins() -> BuiltinsResult<Option<Vec<Builtin>>> {
+    #[allow(clippy::disallowed_methods)] // Environment variables are used exclusively in test and
+    // all are prefixed with `SI_TEST_`
+    match env::var(ENV_VAR_BUILTINS) {
+        Ok(found_value) => {
+            let names: Vec<&str> = found_value.split(',').collect();
+            Ok(Some(Builtin::parse_names(&names)?))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
 async fn drop_old_test_databases(pg_pool: &PgPool) -> Result<()> {
     let name_prefix = format!("{}_%", pg_pool.db_name());
     let pg_conn = pg_pool.get().await?;
@@ -10,14 +10,11 @@ use dal::{
     Secret, SecretKind, SecretObjectType, StandardModel, User, UserPk, Visibility, Workspace,
     WorkspacePk,
 };
-use names::{Generator, Name};
 
-pub fn generate_fake_name() -> String {
-    Generator::with_naming(Name::Numbered).next().unwrap()
-}
+pub use crate::helpers::generate_fake_name;
 
 pub async fn create_change_set(ctx: &DalContext) -> ChangeSet {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     ChangeSet::new(ctx, &name, None)
         .await
         .expect("cannot create change_set")
@@ -32,21 +29,21 @@ pub fn create_visibility_head() -> Visibility {
 }
 
 pub async fn create_workspace(ctx: &mut DalContext) -> Workspace {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     Workspace::new(ctx, WorkspacePk::generate(), &name)
         .await
         .expect("cannot create workspace")
 }
 
 pub async fn create_key_pair(ctx: &DalContext) -> KeyPair {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     KeyPair::new(ctx, &name)
         .await
         .expect("cannot create key_pair")
 }
 
 pub async fn create_user(ctx: &DalContext) -> User {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     User::new(
         ctx,
         UserPk::generate(),
@@ -59,7 +56,7 @@ pub async fn create_user(ctx: &DalContext) -> User {
 }
 
 pub async fn create_schema(ctx: &DalContext) -> Schema {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     Schema::new(ctx, &name, &ComponentKind::Standard)
         .await
         .expect("cannot create schema")
@@ -73,7 +70,7 @@ pub async fn create_schema_variant_with_root(
     ctx: &DalContext,
     schema_id: SchemaId,
 ) -> (schema::SchemaVariant, schema::RootProp) {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     let (variant, root) = schema::SchemaVariant::new(ctx, schema_id, name)
         .await
         .expect("cannot create schema variant");
@@ -112,7 +109,7 @@ pub async fn create_component_and_schema(ctx: &DalContext) -> Component {
         .finalize(ctx, None)
         .await
         .expect("unable to finalize schema variant");
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     let (component, _) = Component::new(ctx, &name, *schema_variant.id())
         .await
         .expect("cannot create component");
@@ -123,7 +120,7 @@ pub async fn create_component_for_schema_variant(
     ctx: &DalContext,
     schema_variant_id: &SchemaVariantId,
 ) -> Component {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     let (component, _) = Component::new(ctx, &name, *schema_variant_id)
         .await
         .expect("cannot create component");
@@ -131,7 +128,7 @@ pub async fn create_component_for_schema_variant(
 }
 
 pub async fn create_component_for_schema(ctx: &DalContext, schema_id: &SchemaId) -> Component {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     let (component, _) = Component::new_for_default_variant_from_schema(ctx, &name, *schema_id)
         .await
         .expect("cannot create component");
@@ -143,7 +140,7 @@ pub async fn create_node(ctx: &DalContext, node_kind: &NodeKind) -> Node {
 }
 
 pub async fn create_func(ctx: &DalContext) -> Func {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     Func::new(
         ctx,
         name,
@@ -182,7 +179,7 @@ pub async fn encrypt_message(
 }
 
 pub async fn create_secret(ctx: &DalContext, key_pair_pk: KeyPairPk) -> Secret {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     EncryptedSecret::new(
         ctx,
         &name,
@@ -202,7 +199,7 @@ pub async fn create_secret_with_message(
     key_pair_pk: KeyPairPk,
     message: &serde_json::Value,
 ) -> Secret {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
     EncryptedSecret::new(
         ctx,
         &name,
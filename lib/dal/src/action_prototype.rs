@@ -308,7 +308,7 @@ impl ActionPrototype {
         component_id: ComponentId,
         trigger_dependent_values_update: bool,
     ) -> ActionPrototypeResult<Option<ActionRunResult>> {
-        let component_view = ComponentView::new(ctx, component_id).await?;
+        let component_view = ComponentView::cached(ctx, component_id).await?;
         let (_, return_value) = FuncBinding::create_and_execute(
             ctx,
             serde_json::to_value(component_view)?,
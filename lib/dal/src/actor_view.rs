@@ -54,6 +54,15 @@ impl ActorView {
                     label: user.name().to_string(),
                 })
             }
+            HistoryActor::ApiToken { user_pk, .. } => {
+                let user = User::get_by_pk(ctx, user_pk)
+                    .await?
+                    .ok_or(StandardModelError::UserNotFound(user_pk))?;
+                Ok(Self::User {
+                    pk: user.pk(),
+                    label: format!("{} (API token)", user.name()),
+                })
+            }
             HistoryActor::SystemInit => Ok(Self::System {
                 label: Self::system_label(),
             }),
@@ -0,0 +1,435 @@
+//! This module contains [`ApiToken`], a long-lived credential which allows automation to call
+//! SI on behalf of a [`User`](crate::User), limited to a set of [`ApiTokenScopes`](ApiTokenScope).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use sodiumoxide::crypto::hash::sha256;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, AuditAction, AuditLog, AuditLogError,
+    AuditTarget, DalContext, StandardModelError, Timestamp, TransactionsError, UserPk, WorkspacePk,
+};
+
+const API_TOKEN_GET_BY_PK: &str = include_str!("queries/api_token/get_by_pk.sql");
+const API_TOKEN_FIND_BY_SECRET_HASH: &str =
+    include_str!("queries/api_token/find_by_secret_hash.sql");
+const API_TOKEN_LIST_FOR_WORKSPACE: &str = include_str!("queries/api_token/list_for_workspace.sql");
+
+/// Every raw API token starts with this prefix, which lets callers tell them apart from JWTs.
+pub const API_TOKEN_PREFIX: &str = "si_token_";
+
+/// The number of random bytes that make up the secret portion of a raw API token.
+const API_TOKEN_SECRET_BYTES: usize = 32;
+
+/// The number of characters of the raw token which are persisted for display purposes.
+const API_TOKEN_DISPLAY_PREFIX_LEN: usize = API_TOKEN_PREFIX.len() + 6;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApiTokenError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("api token has expired: {0}")]
+    Expired(ApiTokenPk),
+    #[error("malformed api token")]
+    Malformed,
+    #[error("api token {0} is missing required scope: {1}")]
+    MissingScope(ApiTokenPk, ApiTokenScope),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("api token not found: {0}")]
+    NotFound(ApiTokenPk),
+    #[error("api token not found for secret")]
+    NotFoundForSecret,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("api token has been revoked: {0}")]
+    Revoked(ApiTokenPk),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ApiTokenResult<T> = Result<T, ApiTokenError>;
+
+pk!(ApiTokenPk);
+
+/// The set of permissions that can be granted to an [`ApiToken`]. Scopes are grouped by the area
+/// of SI they apply to and whether they allow reading or writing.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+pub enum ApiTokenScope {
+    #[serde(rename = "application:read")]
+    #[strum(serialize = "application:read")]
+    ApplicationRead,
+    #[serde(rename = "audit:read")]
+    #[strum(serialize = "audit:read")]
+    AuditRead,
+    #[serde(rename = "blueprint:read")]
+    #[strum(serialize = "blueprint:read")]
+    BlueprintRead,
+    #[serde(rename = "blueprint:write")]
+    #[strum(serialize = "blueprint:write")]
+    BlueprintWrite,
+    #[serde(rename = "change_set:read")]
+    #[strum(serialize = "change_set:read")]
+    ChangeSetRead,
+    #[serde(rename = "change_set:write")]
+    #[strum(serialize = "change_set:write")]
+    ChangeSetWrite,
+    #[serde(rename = "component:read")]
+    #[strum(serialize = "component:read")]
+    ComponentRead,
+    #[serde(rename = "component:write")]
+    #[strum(serialize = "component:write")]
+    ComponentWrite,
+    #[serde(rename = "diagram:read")]
+    #[strum(serialize = "diagram:read")]
+    DiagramRead,
+    #[serde(rename = "diagram:write")]
+    #[strum(serialize = "diagram:write")]
+    DiagramWrite,
+    #[serde(rename = "fix:read")]
+    #[strum(serialize = "fix:read")]
+    FixRead,
+    #[serde(rename = "fix:write")]
+    #[strum(serialize = "fix:write")]
+    FixWrite,
+    #[serde(rename = "func:read")]
+    #[strum(serialize = "func:read")]
+    FuncRead,
+    #[serde(rename = "func:write")]
+    #[strum(serialize = "func:write")]
+    FuncWrite,
+    #[serde(rename = "pkg:read")]
+    #[strum(serialize = "pkg:read")]
+    PkgRead,
+    #[serde(rename = "pkg:write")]
+    #[strum(serialize = "pkg:write")]
+    PkgWrite,
+    #[serde(rename = "provider:read")]
+    #[strum(serialize = "provider:read")]
+    ProviderRead,
+    #[serde(rename = "qualification:read")]
+    #[strum(serialize = "qualification:read")]
+    QualificationRead,
+    #[serde(rename = "saved_view:read")]
+    #[strum(serialize = "saved_view:read")]
+    SavedViewRead,
+    #[serde(rename = "saved_view:write")]
+    #[strum(serialize = "saved_view:write")]
+    SavedViewWrite,
+    #[serde(rename = "schema:read")]
+    #[strum(serialize = "schema:read")]
+    SchemaRead,
+    #[serde(rename = "schema:write")]
+    #[strum(serialize = "schema:write")]
+    SchemaWrite,
+    #[serde(rename = "secret:read")]
+    #[strum(serialize = "secret:read")]
+    SecretRead,
+    #[serde(rename = "secret:write")]
+    #[strum(serialize = "secret:write")]
+    SecretWrite,
+    #[serde(rename = "slack:read")]
+    #[strum(serialize = "slack:read")]
+    SlackRead,
+    #[serde(rename = "slack:write")]
+    #[strum(serialize = "slack:write")]
+    SlackWrite,
+    #[serde(rename = "status:read")]
+    #[strum(serialize = "status:read")]
+    StatusRead,
+    #[serde(rename = "variant_def:read")]
+    #[strum(serialize = "variant_def:read")]
+    VariantDefRead,
+    #[serde(rename = "variant_def:write")]
+    #[strum(serialize = "variant_def:write")]
+    VariantDefWrite,
+    #[serde(rename = "webhook:read")]
+    #[strum(serialize = "webhook:read")]
+    WebhookRead,
+    #[serde(rename = "webhook:write")]
+    #[strum(serialize = "webhook:write")]
+    WebhookWrite,
+    #[serde(rename = "workspace_settings:read")]
+    #[strum(serialize = "workspace_settings:read")]
+    WorkspaceSettingsRead,
+    #[serde(rename = "workspace_settings:write")]
+    #[strum(serialize = "workspace_settings:write")]
+    WorkspaceSettingsWrite,
+}
+
+impl ApiTokenScope {
+    /// Returns the scope required to access the given area of SI (e.g. `"component"`), or `None`
+    /// if the area cannot be accessed with an [`ApiToken`] at all.
+    pub fn for_area(area: impl AsRef<str>, write: bool) -> Option<Self> {
+        let scope = match (area.as_ref(), write) {
+            ("application", false) => Self::ApplicationRead,
+            ("audit", false) => Self::AuditRead,
+            ("blueprint", false) => Self::BlueprintRead,
+            ("blueprint", true) => Self::BlueprintWrite,
+            ("change_set", false) => Self::ChangeSetRead,
+            ("change_set", true) => Self::ChangeSetWrite,
+            ("component", false) => Self::ComponentRead,
+            ("component", true) => Self::ComponentWrite,
+            ("diagram", false) => Self::DiagramRead,
+            ("diagram", true) => Self::DiagramWrite,
+            ("fix", false) => Self::FixRead,
+            ("fix", true) => Self::FixWrite,
+            ("func", false) => Self::FuncRead,
+            ("func", true) => Self::FuncWrite,
+            ("pkg", false) => Self::PkgRead,
+            ("pkg", true) => Self::PkgWrite,
+            ("provider", false) => Self::ProviderRead,
+            ("qualification", false) => Self::QualificationRead,
+            ("saved_view", false) => Self::SavedViewRead,
+            ("saved_view", true) => Self::SavedViewWrite,
+            ("schema", false) => Self::SchemaRead,
+            ("schema", true) => Self::SchemaWrite,
+            ("schema_variant", false) => Self::SchemaRead,
+            ("secret", false) => Self::SecretRead,
+            ("secret", true) => Self::SecretWrite,
+            ("slack", false) => Self::SlackRead,
+            ("slack", true) => Self::SlackWrite,
+            ("status", false) => Self::StatusRead,
+            ("variant_def", false) => Self::VariantDefRead,
+            ("variant_def", true) => Self::VariantDefWrite,
+            ("webhook", false) => Self::WebhookRead,
+            ("webhook", true) => Self::WebhookWrite,
+            ("workspace_settings", false) => Self::WorkspaceSettingsRead,
+            ("workspace_settings", true) => Self::WorkspaceSettingsWrite,
+            _ => return None,
+        };
+        Some(scope)
+    }
+}
+
+/// A long-lived, revocable credential belonging to a [`User`](crate::User) in a
+/// [`Workspace`](crate::Workspace).
+///
+/// Only a hash of the secret is persisted: the raw token is returned exactly once, from
+/// [`ApiToken::new`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiToken {
+    pk: ApiTokenPk,
+    name: String,
+    workspace_pk: WorkspacePk,
+    user_pk: UserPk,
+    token_prefix: String,
+    scopes: Vec<ApiTokenScope>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl ApiToken {
+    pub fn pk(&self) -> ApiTokenPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(name, String);
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(user_pk, UserPk);
+    standard_model_accessor_ro!(token_prefix, String);
+    standard_model_accessor_ro!(scopes, Vec<ApiTokenScope>);
+    standard_model_accessor_ro!(expires_at, Option<DateTime<Utc>>);
+    standard_model_accessor_ro!(last_used_at, Option<DateTime<Utc>>);
+    standard_model_accessor_ro!(revoked_at, Option<DateTime<Utc>>);
+
+    /// Issues a new [`ApiToken`] for the given [`User`](crate::User) in the workspace of the
+    /// current tenancy, returning the persisted token alongside the raw token string. The raw
+    /// token cannot be recovered later.
+    #[instrument(skip_all)]
+    pub async fn new(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        name: impl AsRef<str>,
+        scopes: Vec<ApiTokenScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ApiTokenResult<(Self, String)> {
+        let name = name.as_ref();
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(ApiTokenError::NoWorkspaceInTenancy)?;
+
+        let secret = sodiumoxide::randombytes::randombytes(API_TOKEN_SECRET_BYTES);
+        let raw_token = format!("{API_TOKEN_PREFIX}{}", hex::encode(secret));
+        let token_prefix = &raw_token[..API_TOKEN_DISPLAY_PREFIX_LEN];
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM api_token_create_v1($1, $2, $3, $4, $5, $6, $7)",
+                &[
+                    &name,
+                    &workspace_pk,
+                    &user_pk,
+                    &token_prefix,
+                    &hash_raw_token(&raw_token),
+                    &serde_json::to_value(&scopes)?,
+                    &expires_at,
+                ],
+            )
+            .await?;
+        let object: Self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::ApiTokenCreate,
+            Some(AuditTarget::new(
+                "api_token",
+                object.pk,
+                Some(object.name.clone()),
+            )),
+            None,
+            Some(serde_json::json![{ "scopes": object.scopes, "expiresAt": object.expires_at }]),
+        )
+        .await?;
+
+        Ok((object, raw_token))
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: ApiTokenPk) -> ApiTokenResult<Option<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(ApiTokenError::NoWorkspaceInTenancy)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(API_TOKEN_GET_BY_PK, &[&pk, &workspace_pk])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists all [`ApiTokens`](ApiToken) (including expired and revoked ones) in the workspace of
+    /// the current tenancy.
+    pub async fn list(ctx: &DalContext) -> ApiTokenResult<Vec<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(ApiTokenError::NoWorkspaceInTenancy)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(API_TOKEN_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Looks up the [`ApiToken`] matching a raw token string, ensuring that it is usable and
+    /// grants the required [`ApiTokenScope`]. On success, the token's last used time is updated.
+    ///
+    /// This lookup is not bound to a tenancy, since the token itself determines the workspace.
+    #[instrument(skip_all)]
+    pub async fn authenticate(
+        ctx: &DalContext,
+        raw_token: impl AsRef<str>,
+        required_scope: ApiTokenScope,
+    ) -> ApiTokenResult<Self> {
+        let raw_token = raw_token.as_ref();
+        if !raw_token.starts_with(API_TOKEN_PREFIX) {
+            return Err(ApiTokenError::Malformed);
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(API_TOKEN_FIND_BY_SECRET_HASH, &[&hash_raw_token(raw_token)])
+            .await?;
+        let token: Self =
+            standard_model::option_object_from_row(row)?.ok_or(ApiTokenError::NotFoundForSecret)?;
+
+        token.ensure_usable(ctx.now())?;
+        if !token.scopes.contains(&required_scope) {
+            return Err(ApiTokenError::MissingScope(token.pk, required_scope));
+        }
+
+        token.touch(ctx).await
+    }
+
+    /// Returns an error if the token has been revoked or has expired as of `now`.
+    pub fn ensure_usable(&self, now: DateTime<Utc>) -> ApiTokenResult<()> {
+        if self.revoked_at.is_some() {
+            return Err(ApiTokenError::Revoked(self.pk));
+        }
+        if matches!(self.expires_at, Some(expires_at) if expires_at <= now) {
+            return Err(ApiTokenError::Expired(self.pk));
+        }
+        Ok(())
+    }
+
+    /// Revokes the token. Revoking an already revoked token is a no-op.
+    #[instrument(skip_all)]
+    pub async fn revoke(&mut self, ctx: &DalContext) -> ApiTokenResult<()> {
+        let before = serde_json::json![{ "revokedAt": self.revoked_at }];
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one("SELECT object FROM api_token_revoke_v1($1)", &[&self.pk])
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::ApiTokenRevoke,
+            Some(AuditTarget::new(
+                "api_token",
+                self.pk,
+                Some(self.name.clone()),
+            )),
+            Some(before),
+            Some(serde_json::json![{ "revokedAt": self.revoked_at }]),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn touch(&self, ctx: &DalContext) -> ApiTokenResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one("SELECT object FROM api_token_touch_v1($1)", &[&self.pk])
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+}
+
+fn hash_raw_token(raw_token: &str) -> String {
+    hex::encode(sha256::hash(raw_token.as_bytes()))
+}
@@ -13,6 +13,10 @@
 //! [`InternalProviderId`], or an [`ExternalProviderId`]. However, you can only provide one and only
 //! one for an [`AttributeContext`] since they are at the same "level" in the order of precedence.
 //!
+//! [`AttributeContexts`](AttributeContext) can only be built through the builder, and are checked
+//! again when deserialized, so that an invalid combination of fields fails with an
+//! [`AttributeContextBuilderError`] rather than with a mismatch in the database.
+//!
 //! ## `AttributeContext` vs. `AttributeReadContext`
 //!
 //! While the [`AttributeContext`] can be used for both read and write queries, the
@@ -48,13 +52,18 @@ pub enum AttributeContextLeastSpecificFieldKind {
 pub enum AttributeContextError {
     #[error("attribute context builder error: {0}")]
     AttributeContextBuilder(#[from] AttributeContextBuilderError),
+    #[error("key {1:?} given without a parent attribute value in context {0:?}: keys only identify the entries of maps, which always have a parent")]
+    KeyWithoutParent(AttributeContext, String),
     #[error("could not find least specific field")]
     LeastSpecificFieldKindNotFound,
+    #[error("context {0:?} is for a provider, whose values cannot have a parent attribute value or a key: use a prop context")]
+    NestedProviderContext(AttributeContext),
 }
 
 pub type AttributeContextResult<T> = Result<T, AttributeContextError>;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "AttributeContextFields")]
 pub struct AttributeContext {
     #[serde(rename = "attribute_context_prop_id")]
     prop_id: PropId,
@@ -66,6 +75,34 @@ pub struct AttributeContext {
     component_id: ComponentId,
 }
 
+/// The fields of an [`AttributeContext`] as they are stored, which are checked by the
+/// [`AttributeContextBuilder`] before becoming an [`AttributeContext`].
+#[derive(Deserialize)]
+struct AttributeContextFields {
+    #[serde(rename = "attribute_context_prop_id")]
+    prop_id: PropId,
+    #[serde(rename = "attribute_context_internal_provider_id")]
+    internal_provider_id: InternalProviderId,
+    #[serde(rename = "attribute_context_external_provider_id")]
+    external_provider_id: ExternalProviderId,
+    #[serde(rename = "attribute_context_component_id")]
+    component_id: ComponentId,
+}
+
+impl TryFrom<AttributeContextFields> for AttributeContext {
+    type Error = AttributeContextBuilderError;
+
+    fn try_from(fields: AttributeContextFields) -> AttributeContextBuilderResult<Self> {
+        AttributeContextBuilder {
+            prop_id: fields.prop_id,
+            internal_provider_id: fields.internal_provider_id,
+            external_provider_id: fields.external_provider_id,
+            component_id: fields.component_id,
+        }
+        .to_context()
+    }
+}
+
 impl From<AttributeContext> for AttributeContextBuilder {
     fn from(from_context: AttributeContext) -> AttributeContextBuilder {
         AttributeContextBuilder {
@@ -239,6 +276,28 @@ impl AttributeContext {
         }
     }
 
+    /// Checks that a value in [`Self`] can have the given key and parent
+    /// [`AttributeValue`](crate::AttributeValue). Only the values of [`Props`](crate::Prop) are
+    /// nested, and only the entries of maps, which always have a parent, have keys.
+    pub fn check_nesting(
+        &self,
+        key: Option<&str>,
+        has_parent_attribute_value: bool,
+    ) -> AttributeContextResult<()> {
+        if (key.is_some() || has_parent_attribute_value)
+            && !self.is_least_specific_field_kind_prop()?
+        {
+            return Err(AttributeContextError::NestedProviderContext(*self));
+        }
+        if let (Some(key), false) = (key, has_parent_attribute_value) {
+            return Err(AttributeContextError::KeyWithoutParent(
+                *self,
+                key.to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Returns the [`AttributeContextLeastSpecificFieldKind`] that is "set" for [`Self`].
     pub fn least_specific_field_kind(
         &self,
@@ -260,12 +319,10 @@ impl AttributeContext {
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum AttributeContextBuilderError {
-    #[error(
-        "cannot specify more than one field at the lowest level in the order of precedence: {0:?}"
-    )]
-    MultipleLeastSpecificFieldsSpecified(AttributeContextBuilder),
-    #[error("for builder {0:?}, the following fields must be set: {1:?}")]
-    PrerequisteFieldsUnset(AttributeContextBuilder, Vec<&'static str>),
+    #[error("only one of a prop, an internal provider or an external provider can be set, but {1:?} are set in {0:?}")]
+    MultipleLeastSpecificFieldsSpecified(AttributeContextBuilder, Vec<&'static str>),
+    #[error("one of a prop, an internal provider or an external provider must be set, even when a component is, but none is set in {0:?}")]
+    NoLeastSpecificField(AttributeContextBuilder),
 }
 
 pub type AttributeContextBuilderResult<T> = Result<T, AttributeContextBuilderError>;
@@ -301,34 +358,32 @@ impl AttributeContextBuilder {
     /// Converts [`Self`] to [`AttributeContext`]. This method will
     /// fail if the order of precedence is broken (i.e. more-specific
     /// fields are set, but one-to-all less-specific fields are unset)
-    /// or if the field of least specificity, [`PropId`], is unset.
+    /// or if more than one field of least specificity ([`PropId`],
+    /// [`InternalProviderId`] or [`ExternalProviderId`]) is set.
     pub fn to_context(&self) -> AttributeContextBuilderResult<AttributeContext> {
-        let mut unset_prerequisite_fields = Vec::new();
-
-        // The lowest level in the order of precedence must always be set.
-        if self.prop_id == PropId::NONE
-            && self.internal_provider_id == InternalProviderId::NONE
-            && self.external_provider_id == ExternalProviderId::NONE
-        {
-            unset_prerequisite_fields.push("PropId or InternalProviderId or ExternalProviderId");
+        let mut least_specific_fields = Vec::new();
+        if self.prop_id != PropId::NONE {
+            least_specific_fields.push("prop");
         }
-
-        // Only one field at the lowest level in the order of precedence can be set.
-        #[allow(clippy::nonminimal_bool)]
-        if (self.prop_id != PropId::NONE && self.internal_provider_id != InternalProviderId::NONE)
-            || (self.prop_id != PropId::NONE
-                && self.external_provider_id != ExternalProviderId::NONE)
-            || (self.internal_provider_id != InternalProviderId::NONE
-                && self.external_provider_id != ExternalProviderId::NONE)
-        {
-            return Err(AttributeContextBuilderError::MultipleLeastSpecificFieldsSpecified(*self));
+        if self.internal_provider_id != InternalProviderId::NONE {
+            least_specific_fields.push("internal provider");
+        }
+        if self.external_provider_id != ExternalProviderId::NONE {
+            least_specific_fields.push("external provider");
         }
 
-        if !unset_prerequisite_fields.is_empty() {
-            return Err(AttributeContextBuilderError::PrerequisteFieldsUnset(
-                *self,
-                unset_prerequisite_fields,
-            ));
+        // Exactly one field at the lowest level in the order of precedence must be set.
+        match least_specific_fields.len() {
+            0 => return Err(AttributeContextBuilderError::NoLeastSpecificField(*self)),
+            1 => {}
+            _ => {
+                return Err(
+                    AttributeContextBuilderError::MultipleLeastSpecificFieldsSpecified(
+                        *self,
+                        least_specific_fields,
+                    ),
+                )
+            }
         }
 
         Ok(AttributeContext {
@@ -493,6 +548,72 @@ mod tests {
         assert!(new_context.is_least_specific());
     }
 
+    #[test]
+    fn builder_errors() {
+        let mut builder = AttributeContextBuilder::new();
+        builder.set_component_id(ComponentId::generate());
+        assert!(matches!(
+            builder.to_context(),
+            Err(AttributeContextBuilderError::NoLeastSpecificField(_))
+        ));
+
+        builder
+            .set_prop_id(PropId::generate())
+            .set_external_provider_id(ExternalProviderId::generate());
+        match builder.to_context() {
+            Err(AttributeContextBuilderError::MultipleLeastSpecificFieldsSpecified(_, fields)) => {
+                assert_eq!(vec!["prop", "external provider"], fields)
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialize_checks_invariants() {
+        let context = AttributeContextBuilder::new()
+            .set_prop_id(PropId::generate())
+            .set_component_id(ComponentId::generate())
+            .to_context()
+            .expect("cannot build attribute context");
+        let json = serde_json::to_value(context).expect("cannot serialize attribute context");
+        assert_eq!(
+            context,
+            serde_json::from_value(json).expect("cannot deserialize attribute context")
+        );
+
+        let json = serde_json::json!({
+            "attribute_context_prop_id": PropId::NONE,
+            "attribute_context_internal_provider_id": InternalProviderId::NONE,
+            "attribute_context_external_provider_id": ExternalProviderId::NONE,
+            "attribute_context_component_id": ComponentId::generate(),
+        });
+        assert!(serde_json::from_value::<AttributeContext>(json).is_err());
+    }
+
+    #[test]
+    fn check_nesting() {
+        let prop_context = AttributeContextBuilder::new()
+            .set_prop_id(PropId::generate())
+            .to_context()
+            .expect("cannot build attribute context");
+        assert!(prop_context.check_nesting(None, false).is_ok());
+        assert!(prop_context.check_nesting(Some("key"), true).is_ok());
+        assert!(matches!(
+            prop_context.check_nesting(Some("key"), false),
+            Err(AttributeContextError::KeyWithoutParent(..))
+        ));
+
+        let provider_context = AttributeContextBuilder::new()
+            .set_internal_provider_id(InternalProviderId::generate())
+            .to_context()
+            .expect("cannot build attribute context");
+        assert!(provider_context.check_nesting(None, false).is_ok());
+        assert!(matches!(
+            provider_context.check_nesting(None, true),
+            Err(AttributeContextError::NestedProviderContext(_))
+        ));
+    }
+
     #[test]
     fn builder_new() {
         let prop_id = PropId::generate();
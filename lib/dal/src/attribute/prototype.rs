@@ -24,7 +24,9 @@ use crate::{
     func::FuncId,
     func::{
         binding::{FuncBindingError, FuncBindingId},
-        binding_return_value::{FuncBindingReturnValueError, FuncBindingReturnValueId},
+        binding_return_value::{
+            FuncBindingReturnValue, FuncBindingReturnValueError, FuncBindingReturnValueId,
+        },
     },
     impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_has_many,
     AttributePrototypeArgument, AttributePrototypeArgumentError, AttributeReadContext, ComponentId,
@@ -138,6 +140,11 @@ pub struct AttributePrototype {
     func_id: FuncId,
     /// An optional key used for tracking parentage.
     pub key: Option<String>,
+    /// How often, in seconds, the values produced by this prototype should be re-computed even
+    /// if their inputs have not changed. Used to re-check qualifications whose results depend on
+    /// the real world.
+    #[serde(default)]
+    recheck_interval_seconds: Option<i64>,
 }
 
 /// This object is used for
@@ -169,6 +176,8 @@ impl AttributePrototype {
         key: Option<String>,
         parent_attribute_value_id: Option<AttributeValueId>,
     ) -> AttributePrototypeResult<Self> {
+        context.check_nesting(key.as_deref(), parent_attribute_value_id.is_some())?;
+
         let row = ctx.txns().await?.pg().query_one(
             "SELECT new_attribute_prototype AS object FROM attribute_prototype_new_v1($1, $2, $3, $4, $5, $6, $7, $8)",
             &[
@@ -196,6 +205,8 @@ impl AttributePrototype {
         parent_attribute_value_id: Option<AttributeValueId>,
         attribute_value_id: AttributeValueId,
     ) -> AttributePrototypeResult<Self> {
+        context.check_nesting(key.as_deref(), parent_attribute_value_id.is_some())?;
+
         let row = ctx
             .txns()
             .await?
@@ -249,6 +260,11 @@ impl AttributePrototype {
 
     standard_model_accessor!(func_id, Pk(FuncId), AttributePrototypeResult);
     standard_model_accessor!(key, Option<String>, AttributePrototypeResult);
+    standard_model_accessor!(
+        recheck_interval_seconds,
+        OptionBigInt<i64>,
+        AttributePrototypeResult
+    );
     standard_model_has_many!(
         lookup_fn: attribute_values,
         table: "attribute_value_belongs_to_attribute_prototype",
@@ -665,7 +681,32 @@ impl AttributePrototype {
             )
             .await?;
 
-        Ok(standard_model::objects_from_rows(rows)?)
+        let mut argument_values: Vec<AttributePrototypeArgumentValues> =
+            standard_model::objects_from_rows(rows)?;
+        for argument_value in &mut argument_values {
+            for (value, func_binding_return_value_id) in argument_value
+                .values
+                .iter_mut()
+                .zip(&argument_value.sensitive_func_binding_return_value_ids)
+            {
+                let Some(func_binding_return_value_id) = func_binding_return_value_id else {
+                    continue;
+                };
+                let mut func_binding_return_value =
+                    FuncBindingReturnValue::get_by_id(ctx, func_binding_return_value_id)
+                        .await?
+                        .ok_or(FuncBindingReturnValueError::NotFound(
+                            *func_binding_return_value_id,
+                        ))?;
+                func_binding_return_value.decrypt(ctx).await?;
+                *value = func_binding_return_value
+                    .value()
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+            }
+        }
+
+        Ok(argument_values)
     }
 
     /// List [`AttributeValues`](crate::AttributeValue) that belong to a provided [`AttributePrototypeId`](Self)
@@ -915,4 +956,18 @@ pub struct AttributePrototypeArgumentValues {
     pub attribute_prototype_id: AttributePrototypeId,
     pub argument_name: String,
     pub values: Vec<serde_json::Value>,
+    /// Positional with `values`: the sealed
+    /// [`FuncBindingReturnValue`](crate::FuncBindingReturnValue) each value was decrypted from,
+    /// if any.
+    #[serde(default)]
+    pub sensitive_func_binding_return_value_ids: Vec<Option<FuncBindingReturnValueId>>,
+}
+
+impl AttributePrototypeArgumentValues {
+    /// Returns true if any of the values were derived from secrets.
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive_func_binding_return_value_ids
+            .iter()
+            .any(Option::is_some)
+    }
 }
@@ -40,7 +40,7 @@
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use telemetry::prelude::*;
 use thiserror::Error;
 
@@ -61,14 +61,18 @@ use crate::{
     impl_standard_model,
     job::definition::DependentValuesUpdate,
     pk,
+    property_editor::schema::WidgetKind,
+    socket::SocketError,
     standard_model::{self, TypeHint},
     standard_model_accessor, standard_model_belongs_to, standard_model_has_many,
     AttributeContextError, AttributePrototypeArgumentError, Component, ComponentId, DalContext,
-    Func, FuncBinding, FuncError, HistoryEventError, IndexMap, InternalProvider,
-    InternalProviderId, Prop, PropError, PropId, PropKind, StandardModel, StandardModelError,
-    Tenancy, Timestamp, TransactionsError, Visibility, WsEventError,
+    Func, FuncBinding, FuncError, FuncVersion, FuncVersionError, HistoryEventError, IndexMap,
+    InternalProvider, InternalProviderId, Prop, PropError, PropId, PropKind, StandardModel,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, Visibility, WsEvent, WsEventError,
+    WsPayload,
 };
 
+pub mod provenance;
 pub mod view;
 
 const CHILD_ATTRIBUTE_VALUES_FOR_CONTEXT: &str =
@@ -112,6 +116,8 @@ pub enum AttributeValueError {
     ComponentNotFoundById(ComponentId),
     #[error(transparent)]
     Council(#[from] council_server::client::Error),
+    #[error("edge error: {0}")]
+    Edge(String),
     #[error("empty attribute prototype arguments for group name: {0}")]
     EmptyAttributePrototypeArgumentsForGroup(String),
     #[error("external provider error: {0}")]
@@ -134,6 +140,8 @@ pub enum AttributeValueError {
     FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
     #[error("FuncBindingReturnValue not found for AttributeValue: {0}")]
     FuncBindingReturnValueNotFound(AttributeValueId, Visibility),
+    #[error("func version error: {0}")]
+    FuncVersion(#[from] FuncVersionError),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
     #[error("{0}")]
@@ -194,6 +202,13 @@ pub enum AttributeValueError {
     Prop(#[from] Box<PropError>),
     #[error("Prop not found: {0}")]
     PropNotFound(PropId),
+    #[error("attribute value {attribute_value_id} is at revision {current_revision}, expected revision {expected_revision}")]
+    RevisionConflict {
+        attribute_value_id: AttributeValueId,
+        expected_revision: i64,
+        current_revision: i64,
+        current_value: Option<serde_json::Value>,
+    },
     #[error("schema missing in context")]
     SchemaMissing,
     #[error("schema not found for component id: {0}")]
@@ -204,6 +219,8 @@ pub enum AttributeValueError {
     SchemaVariantNotFoundForComponent(ComponentId),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("socket error: {0}")]
+    Socket(#[from] SocketError),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
     #[error(transparent)]
@@ -246,6 +263,8 @@ pub struct AttributeValue {
     pub key: Option<String>,
     #[serde(flatten)]
     pub context: AttributeContext,
+    /// Bumped on every write to this [`AttributeValue`], as of when it was read.
+    revision: i64,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -344,6 +363,10 @@ impl AttributeValue {
         self.index_map.as_mut()
     }
 
+    pub fn revision(&self) -> i64 {
+        self.revision
+    }
+
     /// Returns the *unprocessed* [`serde_json::Value`] within the [`FuncBindingReturnValue`](crate::FuncBindingReturnValue)
     /// corresponding to the field on [`Self`].
     pub async fn get_unprocessed_value(
@@ -351,7 +374,8 @@ impl AttributeValue {
         ctx: &DalContext,
     ) -> AttributeValueResult<Option<serde_json::Value>> {
         match FuncBindingReturnValue::get_by_id(ctx, &self.func_binding_return_value_id).await? {
-            Some(func_binding_return_value) => {
+            Some(mut func_binding_return_value) => {
+                func_binding_return_value.decrypt(ctx).await?;
                 Ok(func_binding_return_value.unprocessed_value().cloned())
             }
             None => Err(AttributeValueError::MissingFuncBindingReturnValue),
@@ -365,7 +389,10 @@ impl AttributeValue {
         ctx: &DalContext,
     ) -> AttributeValueResult<Option<serde_json::Value>> {
         match FuncBindingReturnValue::get_by_id(ctx, &self.func_binding_return_value_id).await? {
-            Some(func_binding_return_value) => Ok(func_binding_return_value.value().cloned()),
+            Some(mut func_binding_return_value) => {
+                func_binding_return_value.decrypt(ctx).await?;
+                Ok(func_binding_return_value.value().cloned())
+            }
             None => Err(AttributeValueError::MissingFuncBindingReturnValue),
         }
     }
@@ -459,6 +486,23 @@ impl AttributeValue {
         Ok(standard_model::option_object_from_row(row)?)
     }
 
+    /// Lists the [`AttributeValues`](crate::AttributeValue) set specifically for any of the given
+    /// [`Components`](crate::Component), in a single query. Values which the components inherit
+    /// from their [`SchemaVariant`](crate::SchemaVariant) are left out.
+    pub async fn list_for_components(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+    ) -> AttributeValueResult<Vec<Self>> {
+        let component_ids: Vec<&ComponentId> = component_ids.iter().collect();
+        Ok(standard_model::find_by_attr_in(
+            ctx,
+            "attribute_values",
+            "attribute_context_component_id",
+            &component_ids,
+        )
+        .await?)
+    }
+
     /// List [`AttributeValues`](crate::AttributeValue) for a provided
     /// [`AttributeReadContext`](crate::AttributeReadContext).
     ///
@@ -578,8 +622,11 @@ impl AttributeValue {
         let mut result = Vec::new();
         for row in rows.into_iter() {
             let func_binding_return_value_json: serde_json::Value = row.try_get("object")?;
-            let func_binding_return_value: Option<FuncBindingReturnValue> =
+            let mut func_binding_return_value: Option<FuncBindingReturnValue> =
                 serde_json::from_value(func_binding_return_value_json)?;
+            if let Some(func_binding_return_value) = &mut func_binding_return_value {
+                func_binding_return_value.decrypt(ctx).await?;
+            }
 
             let prop_json: serde_json::Value = row.try_get("prop_object")?;
             let prop: Prop = serde_json::from_value(prop_json)?;
@@ -637,8 +684,11 @@ impl AttributeValue {
         let mut result = Vec::new();
         for row in rows.into_iter() {
             let func_binding_return_value_json: serde_json::Value = row.try_get("object")?;
-            let func_binding_return_value: Option<FuncBindingReturnValue> =
+            let mut func_binding_return_value: Option<FuncBindingReturnValue> =
                 serde_json::from_value(func_binding_return_value_json)?;
+            if let Some(func_binding_return_value) = &mut func_binding_return_value {
+                func_binding_return_value.decrypt(ctx).await?;
+            }
 
             let prop_json: serde_json::Value = row.try_get("prop_object")?;
             let prop: Prop = serde_json::from_value(prop_json)?;
@@ -695,6 +745,47 @@ impl AttributeValue {
         .await
     }
 
+    /// Like [`Self::update_for_context`], but only if the [`AttributeValue`] has not been written
+    /// since the caller read it at `expected_revision`. Otherwise, fails with
+    /// [`AttributeValueError::RevisionConflict`] carrying the current value, so the caller can
+    /// merge it with their change and retry.
+    pub async fn update_for_context_at_revision(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        parent_attribute_value_id: Option<AttributeValueId>,
+        context: AttributeContext,
+        value: Option<serde_json::Value>,
+        key: Option<String>,
+        expected_revision: i64,
+    ) -> AttributeValueResult<(Option<serde_json::Value>, AttributeValueId)> {
+        let current_revision =
+            standard_model::revision_for_update(ctx, Self::table_name(), &attribute_value_id)
+                .await?;
+        if current_revision != expected_revision {
+            let current_value = Self::get_by_id(ctx, &attribute_value_id)
+                .await?
+                .ok_or(AttributeValueError::MissingForId(attribute_value_id))?
+                .get_value(ctx)
+                .await?;
+            return Err(AttributeValueError::RevisionConflict {
+                attribute_value_id,
+                expected_revision,
+                current_revision,
+                current_value,
+            });
+        }
+
+        Self::update_for_context(
+            ctx,
+            attribute_value_id,
+            parent_attribute_value_id,
+            context,
+            value,
+            key,
+        )
+        .await
+    }
+
     pub async fn update_for_context_without_propagating_dependent_values(
         ctx: &DalContext,
         attribute_value_id: AttributeValueId,
@@ -751,9 +842,22 @@ impl AttributeValue {
         create_child_proxies: bool,
         propagate_dependent_values: bool,
     ) -> AttributeValueResult<(Option<serde_json::Value>, AttributeValueId)> {
+        context.check_nesting(key.as_deref(), parent_attribute_value_id.is_some())?;
+
         // TODO(nick,paulo,zack,jacob): ensure we do not _have_ to do this in the future.
         let ctx = &ctx.clone_without_deleted_visibility();
 
+        // Only changes to the values of components are reported to the UI
+        let old_value = if Self::reports_updates_for(&context) {
+            Self::get_by_id(ctx, &attribute_value_id)
+                .await?
+                .ok_or(AttributeValueError::MissingForId(attribute_value_id))?
+                .get_value(ctx)
+                .await?
+        } else {
+            None
+        };
+
         let row = ctx.txns()
             .await?
             .pg()
@@ -776,6 +880,25 @@ impl AttributeValue {
         // TODO(fnichol): we might want to fire off a status even at this point, however we've
         // already updated the initial attribute value, so is there much value?
 
+        if !context.is_component_unset() {
+            ctx.component_view_cache()
+                .invalidate(context.component_id())
+                .await;
+        }
+
+        if Self::reports_updates_for(&context) {
+            WsEvent::attribute_value_updated(
+                ctx,
+                context,
+                new_attribute_value_id,
+                old_value,
+                value.clone(),
+            )
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        }
+
         if propagate_dependent_values {
             ctx.enqueue_job(DependentValuesUpdate::new(
                 ctx.access_builder(),
@@ -842,6 +965,8 @@ impl AttributeValue {
         key: Option<String>,
         create_child_proxies: bool,
     ) -> AttributeValueResult<AttributeValueId> {
+        item_attribute_context.check_nesting(key.as_deref(), true)?;
+
         let row = ctx.txns().await?.pg().query_one(
             "SELECT new_attribute_value_id FROM attribute_value_insert_for_context_raw_v1($1, $2, $3, $4, $5, $6, $7)",
             &[
@@ -857,6 +982,25 @@ impl AttributeValue {
 
         let new_attribute_value_id: AttributeValueId = row.try_get("new_attribute_value_id")?;
 
+        if !item_attribute_context.is_component_unset() {
+            ctx.component_view_cache()
+                .invalidate(item_attribute_context.component_id())
+                .await;
+        }
+
+        if Self::reports_updates_for(&item_attribute_context) {
+            WsEvent::attribute_value_updated(
+                ctx,
+                item_attribute_context,
+                new_attribute_value_id,
+                None,
+                value,
+            )
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        }
+
         ctx.enqueue_job(DependentValuesUpdate::new(
             ctx.access_builder(),
             *ctx.visibility(),
@@ -867,6 +1011,13 @@ impl AttributeValue {
         Ok(new_attribute_value_id)
     }
 
+    /// Whether updates to [`AttributeValues`](Self) in the given
+    /// [`context`](crate::AttributeContext) are reported with
+    /// [`WsEvent::attribute_value_updated()`].
+    fn reports_updates_for(context: &AttributeContext) -> bool {
+        !context.is_component_unset() && context.prop_id() != PropId::NONE
+    }
+
     #[instrument(skip_all, level = "debug")]
     pub async fn update_parent_index_map(&self, ctx: &DalContext) -> AttributeValueResult<()> {
         let _row = ctx
@@ -907,6 +1058,46 @@ impl AttributeValue {
         Ok(())
     }
 
+    /// Seals the values of all descendants of [`self`](Self), which were populated from a sealed
+    /// value in the database. Descendants without a value have nothing to seal and are skipped.
+    async fn seal_child_values(&self, ctx: &DalContext) -> AttributeValueResult<()> {
+        let read_context = AttributeReadContext {
+            prop_id: None,
+            ..AttributeReadContext::from(self.context)
+        };
+
+        let mut work_queue = VecDeque::from([self.id]);
+        while let Some(parent_attribute_value_id) = work_queue.pop_front() {
+            for child_attribute_value in Self::child_attribute_values_for_context(
+                ctx,
+                parent_attribute_value_id,
+                read_context,
+            )
+            .await?
+            {
+                let mut func_binding_return_value = FuncBindingReturnValue::get_by_id(
+                    ctx,
+                    &child_attribute_value.func_binding_return_value_id,
+                )
+                .await?
+                .ok_or(AttributeValueError::MissingFuncBindingReturnValue)?;
+                let has_value = [
+                    func_binding_return_value.value(),
+                    func_binding_return_value.unprocessed_value(),
+                ]
+                .into_iter()
+                .any(|value| value.map_or(false, |value| !value.is_null()));
+                if has_value && !func_binding_return_value.is_sensitive() {
+                    func_binding_return_value.seal(ctx).await?;
+                }
+
+                work_queue.push_back(child_attribute_value.id);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Convenience method to determine if this [`AttributeValue`](Self) is for the implicit
     /// [`InternalProvider`](crate::InternalProvider) that represents the "snapshot" of the entire
     /// [`Component`](crate::Component). This means that the [`Prop`](crate::Prop) that the
@@ -1070,12 +1261,17 @@ impl AttributeValue {
             AttributeValueError::AttributePrototypeNotFound(self.id, *ctx.visibility())
         })?;
 
-        let mut func_binding_args: HashMap<String, Option<serde_json::Value>> = HashMap::new();
-        for mut argument_data in attribute_prototype
+        let argument_values = attribute_prototype
             .argument_values(ctx, self.context)
             .await
-            .map_err(|e| AttributeValueError::AttributePrototype(e.to_string()))?
-        {
+            .map_err(|e| AttributeValueError::AttributePrototype(e.to_string()))?;
+        // A value computed from values derived from secrets is sealed as well.
+        let sensitive_arguments = argument_values
+            .iter()
+            .any(|argument_data| argument_data.is_sensitive());
+
+        let mut func_binding_args: HashMap<String, Option<serde_json::Value>> = HashMap::new();
+        for mut argument_data in argument_values {
             match argument_data.values.len() {
                 1 => {
                     let argument = argument_data.values.pop().ok_or_else(|| {
@@ -1106,32 +1302,39 @@ impl AttributeValue {
         }
 
         let func_id = attribute_prototype.func_id();
-        let (func_binding, mut func_binding_return_value) = match FuncBinding::create_and_execute(
-            ctx,
-            serde_json::to_value(func_binding_args.clone())?,
-            attribute_prototype.func_id(),
-        )
-        .instrument(debug_span!(
-            "Func execution",
-            "func.id" = %func_id,
-            ?func_binding_args,
-        ))
-        .await
-        {
-            Ok(function_return_value) => function_return_value,
-            Err(FuncBindingError::FuncBackendResultFailure {
-                kind,
-                message,
-                backend,
-            }) => {
-                return Err(AttributeValueError::FuncBackendResultFailure {
+        // The func may be pinned to a version for the schema variant or the workspace of the
+        // component, while a new version is being rolled out.
+        let pinned_version =
+            FuncVersion::pinned_for_component(ctx, func_id, self.context.component_id()).await?;
+        let (func_binding, mut func_binding_return_value) =
+            match FuncBinding::create_and_execute_at_version(
+                ctx,
+                serde_json::to_value(func_binding_args.clone())?,
+                func_id,
+                pinned_version.as_ref(),
+            )
+            .instrument(debug_span!(
+                "Func execution",
+                "func.id" = %func_id,
+                "func.version" = ?pinned_version.as_ref().map(|version| *version.version()),
+                ?func_binding_args,
+            ))
+            .await
+            {
+                Ok(function_return_value) => function_return_value,
+                Err(FuncBindingError::FuncBackendResultFailure {
                     kind,
                     message,
                     backend,
-                })
-            }
-            Err(err) => Err(err)?,
-        };
+                }) => {
+                    return Err(AttributeValueError::FuncBackendResultFailure {
+                        kind,
+                        message,
+                        backend,
+                    })
+                }
+                Err(err) => Err(err)?,
+            };
 
         self.set_func_binding_id(ctx, *func_binding.id()).await?;
         self.set_func_binding_return_value_id(ctx, *func_binding_return_value.id())
@@ -1161,6 +1364,10 @@ impl AttributeValue {
                 .set_value(ctx, processed_value)
                 .await?;
         };
+        // Sealing again after changing the processed value leaves no plaintext behind.
+        if sensitive_arguments || func_binding_return_value.is_sensitive() {
+            func_binding_return_value.seal(ctx).await?;
+        }
         // If they are different from each other, then we know
         // that we need to fully process the deep data structure, populating
         // AttributeValues for the child Props.
@@ -1197,6 +1404,9 @@ impl AttributeValue {
                             unprocessed_value,
                         )
                         .await?;
+                        if func_binding_return_value.is_sensitive() {
+                            self.seal_child_values(ctx).await?;
+                        }
                     }
                 }
             }
@@ -1261,3 +1471,65 @@ impl AttributeValuePayload {
         }
     }
 }
+
+/// The largest serialized size, in bytes, of a value sent with
+/// [`WsEvent::attribute_value_updated()`]. Larger values are left out, and clients fetch them if
+/// they need them.
+const ATTRIBUTE_VALUE_UPDATED_MAX_VALUE_BYTES: usize = 16 * 1024;
+
+/// The payload of [`WsEvent::attribute_value_updated()`], which lets the UI update the single
+/// value that changed rather than fetching the whole [`Component`](crate::Component) again.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueUpdatedPayload {
+    component_id: ComponentId,
+    attribute_value_id: AttributeValueId,
+    prop_id: PropId,
+    /// The json pointer of the [`Prop`](crate::Prop), such as "/root/domain/image".
+    prop_path: String,
+    old_value: Option<serde_json::Value>,
+    new_value: Option<serde_json::Value>,
+    /// Set when the values were left out because the [`Prop`](crate::Prop) holds a secret.
+    redacted: bool,
+    /// Set when the values were left out because they were too large to be sent.
+    truncated: bool,
+}
+
+impl WsEvent {
+    pub async fn attribute_value_updated(
+        ctx: &DalContext,
+        context: AttributeContext,
+        attribute_value_id: AttributeValueId,
+        old_value: Option<serde_json::Value>,
+        new_value: Option<serde_json::Value>,
+    ) -> AttributeValueResult<Self> {
+        let prop = Prop::get_by_id(ctx, &context.prop_id())
+            .await?
+            .ok_or_else(|| AttributeValueError::PropNotFound(context.prop_id()))?;
+
+        let redacted = *prop.widget_kind() == WidgetKind::SecretSelect;
+        let truncated = !redacted
+            && [&old_value, &new_value]
+                .into_iter()
+                .flatten()
+                .any(|value| value.to_string().len() > ATTRIBUTE_VALUE_UPDATED_MAX_VALUE_BYTES);
+        let (old_value, new_value) = if redacted || truncated {
+            (None, None)
+        } else {
+            (old_value, new_value)
+        };
+
+        let payload = AttributeValueUpdatedPayload {
+            component_id: context.component_id(),
+            attribute_value_id,
+            prop_id: *prop.id(),
+            prop_path: format!("/{}", prop.path().with_replaced_sep("/")),
+            old_value,
+            new_value,
+            redacted,
+            truncated,
+        };
+
+        Ok(WsEvent::new(ctx, WsPayload::AttributeValueUpdated(payload)).await?)
+    }
+}
@@ -0,0 +1,209 @@
+//! This module contains [`AttributeValueProvenance`], which explains where the value of an
+//! [`AttributeValue`](crate::AttributeValue) came from: the [`Func`](crate::Func) which produced
+//! it, the arguments it was executed with and the upstream values (and the
+//! [`Sockets`](crate::Socket) and [`Edges`](crate::Edge) they flowed through) which fed those
+//! arguments. This object does not exist in the database.
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use telemetry::prelude::*;
+
+use crate::{
+    attribute::prototype::argument::AttributePrototypeArgument,
+    edge::EdgeId,
+    func::{argument::FuncArgument, intrinsics::IntrinsicFunc},
+    AttributeReadContext, AttributeValue, AttributeValueError, AttributeValueId,
+    AttributeValueResult, ComponentId, DalContext, Edge, ExternalProviderId, Func, FuncBackendKind,
+    FuncBinding, FuncId, InternalProvider, InternalProviderId, PropId, Socket, SocketId,
+    StandardModel,
+};
+
+/// Where the value of an [`AttributeValue`](crate::AttributeValue) came from.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueProvenance {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub value: Option<serde_json::Value>,
+    /// The less specific [`AttributeValue`](crate::AttributeValue) this one stands in for, if it
+    /// is a proxy.
+    pub proxy_for_attribute_value_id: Option<AttributeValueId>,
+    pub func: ProvenanceFunc,
+    /// The arguments the [`Func`](crate::Func) was last executed with.
+    pub func_binding_args: serde_json::Value,
+    /// Set when the value was set directly (or unset) rather than computed from inputs.
+    pub set_directly: bool,
+    pub inputs: Vec<ProvenanceInput>,
+}
+
+/// The [`Func`](crate::Func) which produced a value.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceFunc {
+    pub id: FuncId,
+    pub name: String,
+    pub backend_kind: FuncBackendKind,
+}
+
+/// An upstream value feeding an argument of the [`Func`](crate::Func) which produced a value.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceInput {
+    pub argument_name: String,
+    /// The [`Component`](crate::Component) the upstream value belongs to, which is another
+    /// [`Component`](crate::Component) when the value flows through an [`Edge`](crate::Edge).
+    pub source_component_id: ComponentId,
+    pub internal_provider_id: Option<InternalProviderId>,
+    pub external_provider_id: Option<ExternalProviderId>,
+    /// The [`Prop`](crate::Prop) the upstream value mirrors, if the input is another
+    /// [`Prop`](crate::Prop) rather than a [`Socket`](crate::Socket).
+    pub prop_id: Option<PropId>,
+    pub attribute_value_id: Option<AttributeValueId>,
+    pub value: Option<serde_json::Value>,
+    pub socket_ids: Vec<SocketId>,
+    pub edge_ids: Vec<EdgeId>,
+}
+
+impl AttributeValue {
+    /// Explains where the value of the [`AttributeValue`](crate::AttributeValue) came from, to
+    /// answer "why is this value X?".
+    #[instrument(skip(ctx))]
+    pub async fn provenance(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+    ) -> AttributeValueResult<AttributeValueProvenance> {
+        let attribute_value = Self::get_by_id(ctx, &attribute_value_id)
+            .await?
+            .ok_or(AttributeValueError::MissingForId(attribute_value_id))?;
+        let component_id = attribute_value.context.component_id();
+
+        let attribute_prototype = attribute_value.attribute_prototype(ctx).await?.ok_or(
+            AttributeValueError::AttributePrototypeNotFound(attribute_value_id, *ctx.visibility()),
+        )?;
+        let func = Func::get_by_id(ctx, &attribute_prototype.func_id())
+            .await?
+            .ok_or_else(|| {
+                AttributeValueError::MissingFunc(attribute_prototype.func_id().to_string())
+            })?;
+        let func_binding = FuncBinding::get_by_id(ctx, &attribute_value.func_binding_id)
+            .await?
+            .ok_or(AttributeValueError::MissingFuncBinding(
+                attribute_value.func_binding_id,
+            ))?;
+        let set_directly = matches!(
+            IntrinsicFunc::iter().find(|intrinsic| intrinsic.name() == func.name()),
+            Some(
+                IntrinsicFunc::SetArray
+                    | IntrinsicFunc::SetBoolean
+                    | IntrinsicFunc::SetInteger
+                    | IntrinsicFunc::SetMap
+                    | IntrinsicFunc::SetObject
+                    | IntrinsicFunc::SetString
+                    | IntrinsicFunc::Unset
+            )
+        );
+
+        let edges = Edge::list_for_component(ctx, component_id)
+            .await
+            .map_err(|e| AttributeValueError::Edge(e.to_string()))?;
+
+        let mut inputs = Vec::new();
+        for argument in
+            AttributePrototypeArgument::list_for_attribute_prototype(ctx, *attribute_prototype.id())
+                .await?
+        {
+            // Inter component arguments are shared by the prototype of every component, so only
+            // the ones headed at this component fed its value.
+            let inter_component = argument.tail_component_id() != ComponentId::NONE;
+            if inter_component && argument.head_component_id() != component_id {
+                continue;
+            }
+            let source_component_id = if inter_component {
+                argument.tail_component_id()
+            } else {
+                component_id
+            };
+
+            let argument_name = FuncArgument::get_by_id(ctx, &argument.func_argument_id())
+                .await?
+                .map(|func_argument| func_argument.name().to_string())
+                .unwrap_or_default();
+
+            let (internal_provider_id, external_provider_id, prop_id, sockets) = if argument
+                .is_internal_provider_unset()
+            {
+                let external_provider_id = argument.external_provider_id();
+                let sockets = Socket::find_for_external_provider(ctx, external_provider_id).await?;
+                (None, Some(external_provider_id), None, sockets)
+            } else {
+                let internal_provider_id = argument.internal_provider_id();
+                let prop_id = InternalProvider::get_by_id(ctx, &internal_provider_id)
+                    .await?
+                    .map(|internal_provider| *internal_provider.prop_id())
+                    .filter(|prop_id| *prop_id != PropId::NONE);
+                let sockets = Socket::find_for_internal_provider(ctx, internal_provider_id).await?;
+                (Some(internal_provider_id), None, prop_id, sockets)
+            };
+            let socket_ids: Vec<SocketId> = sockets.iter().map(|socket| *socket.id()).collect();
+
+            let upstream = Self::find_for_context(
+                ctx,
+                AttributeReadContext {
+                    prop_id: Some(PropId::NONE),
+                    internal_provider_id: Some(
+                        internal_provider_id.unwrap_or(InternalProviderId::NONE),
+                    ),
+                    external_provider_id: Some(
+                        external_provider_id.unwrap_or(ExternalProviderId::NONE),
+                    ),
+                    component_id: Some(source_component_id),
+                },
+            )
+            .await?;
+            let value = match &upstream {
+                Some(upstream) => upstream.get_value(ctx).await?,
+                None => None,
+            };
+
+            let edge_ids = edges
+                .iter()
+                .filter(|edge| {
+                    let through_socket = socket_ids.contains(&edge.head_socket_id())
+                        || socket_ids.contains(&edge.tail_socket_id());
+                    let from_source = !inter_component
+                        || ComponentId::from(edge.tail_object_id()) == source_component_id
+                        || ComponentId::from(edge.head_object_id()) == source_component_id;
+                    through_socket && from_source
+                })
+                .map(|edge| *edge.id())
+                .collect();
+
+            inputs.push(ProvenanceInput {
+                argument_name,
+                source_component_id,
+                internal_provider_id,
+                external_provider_id,
+                prop_id,
+                attribute_value_id: upstream.map(|upstream| upstream.id),
+                value,
+                socket_ids,
+                edge_ids,
+            });
+        }
+
+        Ok(AttributeValueProvenance {
+            attribute_value_id,
+            component_id,
+            value: attribute_value.get_value(ctx).await?,
+            proxy_for_attribute_value_id: attribute_value.proxy_for_attribute_value_id,
+            func: ProvenanceFunc {
+                id: *func.id(),
+                name: func.name().to_string(),
+                backend_kind: *func.backend_kind(),
+            },
+            func_binding_args: func_binding.args().clone(),
+            set_directly,
+            inputs,
+        })
+    }
+}
@@ -19,6 +19,8 @@ pub struct AttributeView {
     /// The value that was generated from [`Self::new()`]. This can also be referred to as the
     /// "properties" or "tree" of the view.
     value: Value,
+    /// Set when any of the values the view was generated from were derived from secrets.
+    sensitive: bool,
 }
 
 impl AttributeView {
@@ -53,6 +55,14 @@ impl AttributeView {
             }
         };
 
+        let sensitive = initial_work.iter().any(|avp| {
+            avp.func_binding_return_value
+                .as_ref()
+                .map_or(false, |func_binding_return_value| {
+                    func_binding_return_value.is_sensitive()
+                })
+        });
+
         // When we have a parent AttributeValueId (K: AttributeValueId), we need to know where in
         // the structure we need to insert the value we are working with (V: String).
         let mut json_pointer_for_attribute_value_id: HashMap<AttributeValueId, String> =
@@ -220,7 +230,10 @@ impl AttributeView {
                         so the \"properties\" object is empty ({:?}), and does not contain a key matching \
                         our prop's name (root attribute value ({:?}) and root prop ({:?}))", properties, root_attribute_value, root_prop
                     );
-                    return Ok(Self { value: Value::Null });
+                    return Ok(Self {
+                        value: Value::Null,
+                        sensitive,
+                    });
                 }
             };
 
@@ -229,15 +242,21 @@ impl AttributeView {
                 .ok_or(AttributeValueError::NoValueForJsonPointer)?;
             return Ok(Self {
                 value: properties.clone(),
+                sensitive,
             });
         }
 
         Ok(Self {
             value: properties.clone(),
+            sensitive,
         })
     }
 
     pub fn value(&self) -> &serde_json::Value {
         &self.value
     }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
 }
@@ -0,0 +1,387 @@
+//! This module contains the audit log: a queryable record of privileged operations, such as
+//! applying change sets or deleting components. Audit entries are stored as
+//! [`HistoryEvents`](crate::HistoryEvent) with additional structured columns.
+
+use chrono::{DateTime, Duration, Utc};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    history_event::HistoryEventPk, standard_model, DalContext, HistoryActor, StandardModelError,
+    Tenancy, Timestamp, TransactionsError,
+};
+
+const AUDIT_LOG_LIST: &str = include_str!("queries/audit_log/list.sql");
+
+/// The default number of entries returned in a single page of [`AuditLog::list`].
+pub const DEFAULT_AUDIT_LOG_PAGE_SIZE: u32 = 50;
+
+/// The maximum number of entries that can be returned in a single page of [`AuditLog::list`].
+pub const MAX_AUDIT_LOG_PAGE_SIZE: u32 = 500;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum AuditLogError {
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type AuditLogResult<T> = Result<T, AuditLogError>;
+
+/// The privileged operations which are recorded in the audit log.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize,
+)]
+pub enum AuditAction {
+    #[serde(rename = "api_token.create")]
+    #[strum(serialize = "api_token.create")]
+    ApiTokenCreate,
+    #[serde(rename = "api_token.revoke")]
+    #[strum(serialize = "api_token.revoke")]
+    ApiTokenRevoke,
+    #[serde(rename = "change_set.apply")]
+    #[strum(serialize = "change_set.apply")]
+    ChangeSetApply,
+    #[serde(rename = "change_set.apply_schedule")]
+    #[strum(serialize = "change_set.apply_schedule")]
+    ChangeSetApplySchedule,
+    #[serde(rename = "change_set.apply_schedule_cancel")]
+    #[strum(serialize = "change_set.apply_schedule_cancel")]
+    ChangeSetApplyScheduleCancel,
+    #[serde(rename = "change_set.review")]
+    #[strum(serialize = "change_set.review")]
+    ChangeSetReview,
+    #[serde(rename = "change_set.review_request")]
+    #[strum(serialize = "change_set.review_request")]
+    ChangeSetReviewRequest,
+    #[serde(rename = "component.delete")]
+    #[strum(serialize = "component.delete")]
+    ComponentDelete,
+    #[serde(rename = "component.restore")]
+    #[strum(serialize = "component.restore")]
+    ComponentRestore,
+    #[serde(rename = "component.restore_snapshot")]
+    #[strum(serialize = "component.restore_snapshot")]
+    ComponentRestoreSnapshot,
+    #[serde(rename = "secret.create")]
+    #[strum(serialize = "secret.create")]
+    SecretCreate,
+    #[serde(rename = "secret.update")]
+    #[strum(serialize = "secret.update")]
+    SecretUpdate,
+    #[serde(rename = "session.revoke_all")]
+    #[strum(serialize = "session.revoke_all")]
+    SessionRevokeAll,
+    #[serde(rename = "slack_integration.delete")]
+    #[strum(serialize = "slack_integration.delete")]
+    SlackIntegrationDelete,
+    #[serde(rename = "slack_integration.update")]
+    #[strum(serialize = "slack_integration.update")]
+    SlackIntegrationUpdate,
+    #[serde(rename = "webhook.create")]
+    #[strum(serialize = "webhook.create")]
+    WebhookCreate,
+    #[serde(rename = "webhook.delete")]
+    #[strum(serialize = "webhook.delete")]
+    WebhookDelete,
+    #[serde(rename = "webhook.update")]
+    #[strum(serialize = "webhook.update")]
+    WebhookUpdate,
+    #[serde(rename = "workspace.restore_backup")]
+    #[strum(serialize = "workspace.restore_backup")]
+    WorkspaceRestoreBackup,
+    #[serde(rename = "workspace_settings.update")]
+    #[strum(serialize = "workspace_settings.update")]
+    WorkspaceSettingsUpdate,
+}
+
+impl AuditAction {
+    /// A human readable description of the action.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::ApiTokenCreate => "API token created",
+            Self::ApiTokenRevoke => "API token revoked",
+            Self::ChangeSetApply => "Change Set applied",
+            Self::ChangeSetApplySchedule => "Change Set apply scheduled",
+            Self::ChangeSetApplyScheduleCancel => "Change Set scheduled apply canceled",
+            Self::ChangeSetReview => "Change Set reviewed",
+            Self::ChangeSetReviewRequest => "Change Set review requested",
+            Self::ComponentDelete => "Component deleted",
+            Self::ComponentRestore => "Component restored",
+            Self::ComponentRestoreSnapshot => "Component restored from snapshot",
+            Self::SecretCreate => "Secret created",
+            Self::SecretUpdate => "Secret updated",
+            Self::SessionRevokeAll => "All sessions revoked",
+            Self::SlackIntegrationDelete => "Slack integration deleted",
+            Self::SlackIntegrationUpdate => "Slack integration updated",
+            Self::WebhookCreate => "Webhook created",
+            Self::WebhookDelete => "Webhook deleted",
+            Self::WebhookUpdate => "Webhook updated",
+            Self::WorkspaceRestoreBackup => "Workspace restored from backup",
+            Self::WorkspaceSettingsUpdate => "Workspace settings updated",
+        }
+    }
+}
+
+/// The object that an audited action was performed on.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditTarget {
+    /// The kind of object, such as `"component"` or `"change_set"`.
+    pub kind: String,
+    /// The id or pk of the object.
+    pub id: String,
+    /// A display name for the object, if it has one.
+    pub name: Option<String>,
+}
+
+impl AuditTarget {
+    pub fn new(kind: impl Into<String>, id: impl ToString, name: Option<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            id: id.to_string(),
+            name,
+        }
+    }
+}
+
+/// A single entry in the audit log.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    pk: HistoryEventPk,
+    #[serde(rename = "audit_action")]
+    action: AuditAction,
+    actor: HistoryActor,
+    message: String,
+    #[serde(rename = "audit_target")]
+    target: Option<AuditTarget>,
+    /// A summary of the target before the action was performed.
+    #[serde(rename = "audit_before")]
+    before: Option<serde_json::Value>,
+    /// A summary of the target after the action was performed.
+    #[serde(rename = "audit_after")]
+    after: Option<serde_json::Value>,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+/// Filters applied when listing the audit log. Every field is optional and filters are combined.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogFilter {
+    pub actor: Option<HistoryActor>,
+    pub action: Option<AuditAction>,
+    /// Only include entries created at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only include entries created before this time.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A page of audit log entries, ordered from newest to oldest.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditLog>,
+    /// Pass this as the cursor to fetch the next page. `None` when there are no more entries.
+    pub next_cursor: Option<HistoryEventPk>,
+}
+
+/// How long audit log entries are kept for before being pruned.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditRetentionPolicy {
+    pub max_age_days: u32,
+}
+
+impl Default for AuditRetentionPolicy {
+    fn default() -> Self {
+        Self { max_age_days: 365 }
+    }
+}
+
+impl AuditRetentionPolicy {
+    /// Returns the time before which entries are pruned, relative to `now`.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.max_age_days.into())
+    }
+}
+
+impl AuditLog {
+    pub fn pk(&self) -> HistoryEventPk {
+        self.pk
+    }
+
+    pub fn action(&self) -> AuditAction {
+        self.action
+    }
+
+    pub fn actor(&self) -> &HistoryActor {
+        &self.actor
+    }
+
+    pub fn target(&self) -> Option<&AuditTarget> {
+        self.target.as_ref()
+    }
+
+    pub fn before(&self) -> Option<&serde_json::Value> {
+        self.before.as_ref()
+    }
+
+    pub fn after(&self) -> Option<&serde_json::Value> {
+        self.after.as_ref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.timestamp.created_at
+    }
+
+    /// Records an audited action performed by the current [`HistoryActor`] in the current
+    /// tenancy. The entry is also published as a regular history event.
+    #[instrument(skip(ctx, before, after))]
+    pub async fn record(
+        ctx: &DalContext,
+        action: AuditAction,
+        target: Option<AuditTarget>,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) -> AuditLogResult<Self> {
+        let actor = serde_json::to_value(ctx.history_actor())?;
+        let target = target.map(serde_json::to_value).transpose()?;
+        let txns = ctx.txns().await?;
+        let row = txns
+            .pg()
+            .query_one(
+                "SELECT object FROM history_event_create_audit_v1($1, $2, $3, $4, $5, $6, $7)",
+                &[
+                    &action.as_ref(),
+                    &actor,
+                    &action.message(),
+                    &target,
+                    &before,
+                    &after,
+                    ctx.tenancy(),
+                ],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        txns.nats().publish("historyEvent", &json).await?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Lists a page of audit log entries in the workspace of the current tenancy, newest first.
+    /// Pass the `next_cursor` of a previous page to continue from where it left off.
+    #[instrument(skip(ctx))]
+    pub async fn list(
+        ctx: &DalContext,
+        filter: &AuditLogFilter,
+        cursor: Option<HistoryEventPk>,
+        page_size: Option<u32>,
+    ) -> AuditLogResult<AuditLogPage> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(AuditLogError::NoWorkspaceInTenancy)?;
+        let page_size = page_size
+            .unwrap_or(DEFAULT_AUDIT_LOG_PAGE_SIZE)
+            .clamp(1, MAX_AUDIT_LOG_PAGE_SIZE);
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+        let action = filter.action.map(|action| action.to_string());
+
+        // Fetch one extra row to learn whether there is another page
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                AUDIT_LOG_LIST,
+                &[
+                    &workspace_pk,
+                    &actor,
+                    &action,
+                    &filter.from,
+                    &filter.to,
+                    &cursor,
+                    &(i64::from(page_size) + 1),
+                ],
+            )
+            .await?;
+        let mut entries: Vec<Self> = standard_model::objects_from_rows(rows)?;
+
+        let next_cursor = if entries.len() > page_size as usize {
+            entries.truncate(page_size as usize);
+            entries.last().map(|entry| entry.pk)
+        } else {
+            None
+        };
+
+        Ok(AuditLogPage {
+            entries,
+            next_cursor,
+        })
+    }
+
+    /// Streams every audit log entry in the workspace of the current tenancy matching the filter,
+    /// newest first, fetching them from the database in batches. Pass the pk of the last entry
+    /// received to continue after it.
+    #[instrument(skip(ctx))]
+    pub async fn stream(
+        ctx: &DalContext,
+        filter: &AuditLogFilter,
+        cursor: Option<HistoryEventPk>,
+    ) -> AuditLogResult<impl Stream<Item = AuditLogResult<Self>> + Send + 'static> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(AuditLogError::NoWorkspaceInTenancy)?;
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+        let action = filter.action.map(|action| action.to_string());
+
+        standard_model::stream_objects(
+            ctx,
+            AUDIT_LOG_LIST,
+            &[
+                &workspace_pk,
+                &actor,
+                &action,
+                &filter.from,
+                &filter.to,
+                &cursor,
+                &None::<i64>,
+            ],
+        )
+        .await
+    }
+
+    /// Deletes audit log entries older than the retention policy allows, returning the number of
+    /// entries removed. An empty tenancy prunes entries across all workspaces.
+    #[instrument(skip(ctx))]
+    pub async fn prune(ctx: &DalContext, policy: AuditRetentionPolicy) -> AuditLogResult<i64> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT pruned FROM history_event_prune_audit_v1($1, $2)",
+                &[ctx.tenancy(), &policy.cutoff(ctx.now())],
+            )
+            .await?;
+        Ok(row.try_get("pruned")?)
+    }
+}
@@ -0,0 +1,66 @@
+//! This module contains [`Blob`], which keeps large values, such as generated code, in the content
+//! addressed [`BlobStore`](si_data_pg::BlobStore) of the database rather than in the rows holding
+//! them. Those rows hold the [reference](BlobHash::to_reference()) of the blob instead.
+
+use chrono::{DateTime, Utc};
+use si_data_pg::{BlobError as BlobStoreError, BlobStore, PgBlobStore, PgError};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DalContext, TransactionsError};
+
+pub use si_data_pg::BlobHash;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum BlobError {
+    #[error("blob not found: {0}")]
+    NotFound(BlobHash),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("blob store error: {0}")]
+    Store(#[from] BlobStoreError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type BlobResult<T> = Result<T, BlobError>;
+
+/// Stores and loads blobs within the transactions of a [`DalContext`].
+pub struct Blob;
+
+impl Blob {
+    /// Stores the content, unless it is already stored, and returns its hash.
+    #[instrument(skip_all, level = "debug", fields(blob.size = content.len()))]
+    pub async fn store(ctx: &DalContext, content: &[u8]) -> BlobResult<BlobHash> {
+        let txns = ctx.txns().await?;
+        Ok(PgBlobStore::new(txns.pg()).put(content).await?)
+    }
+
+    /// Loads the content of the blob with the given hash.
+    pub async fn load(ctx: &DalContext, hash: BlobHash) -> BlobResult<Vec<u8>> {
+        let txns = ctx.txns().await?;
+        PgBlobStore::new(txns.pg())
+            .get(&hash)
+            .await?
+            .ok_or(BlobError::NotFound(hash))
+    }
+
+    /// Deletes the blobs stored before the given time which are no longer referred to, returning
+    /// how many were deleted.
+    pub async fn collect_garbage(
+        ctx: &DalContext,
+        stored_before: DateTime<Utc>,
+    ) -> BlobResult<i64> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT deleted FROM blob_collect_garbage_v1($1)",
+                &[&stored_before],
+            )
+            .await?;
+        Ok(row.try_get("deleted")?)
+    }
+}
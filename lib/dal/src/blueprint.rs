@@ -0,0 +1,791 @@
+//! This module contains [`Blueprint`], a reusable group of [`Components`](Component) captured
+//! from a diagram, along with the edges between them and the values set on them, which can be
+//! instantiated again into any change set of the workspace.
+//!
+//! Blueprints are parameterized with [`BlueprintVariables`](BlueprintVariable): the names of the
+//! components and their string values may contain `{{variable}}` placeholders, which are replaced
+//! with the value given for each variable when the blueprint is instantiated. A value made of a
+//! single placeholder is replaced with the value of the variable as is, so that variables are not
+//! limited to strings.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::component::view::ComponentViewError;
+use crate::component::AttributeUpdate;
+use crate::edge::EdgeKind;
+use crate::socket::{SocketEdgeKind, SocketError};
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, AttributeReadContext, AttributeValue,
+    AttributeValueError, Component, ComponentError, ComponentId, ComponentType, Connection,
+    DalContext, DependentValuesUpdate, DiagramError, Edge, EdgeError, ExternalProvider,
+    ExternalProviderError, FuncError, NodeError, NodeId, PropError, Schema, SchemaError, Socket,
+    SocketId, StandardModel, StandardModelError, Timestamp, TransactionsError, WorkspacePk,
+};
+
+const BLUEPRINT_DELETE: &str = include_str!("queries/blueprint/delete.sql");
+const BLUEPRINT_GET_BY_PK: &str = include_str!("queries/blueprint/get_by_pk.sql");
+const BLUEPRINT_LIST_FOR_WORKSPACE: &str = include_str!("queries/blueprint/list_for_workspace.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum BlueprintError {
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("component view error: {0}")]
+    ComponentView(#[from] ComponentViewError),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] DiagramError),
+    #[error("duplicate component key in blueprint: {0}")]
+    DuplicateComponentKey(String),
+    #[error("duplicate variable in blueprint: {0}")]
+    DuplicateVariable(String),
+    #[error("edge error: {0}")]
+    Edge(#[from] EdgeError),
+    #[error("blueprint has no components")]
+    Empty,
+    #[error("external provider error: {0}")]
+    ExternalProvider(#[from] ExternalProviderError),
+    #[error("external provider not found for socket {0}")]
+    ExternalProviderNotFound(String),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("invalid variable name: {0:?}")]
+    InvalidVariableName(String),
+    #[error("no value given for variable {0}")]
+    MissingVariable(String),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("node error: {0}")]
+    Node(#[from] NodeError),
+    #[error("node not found for component {0}")]
+    NodeNotFound(ComponentId),
+    #[error("blueprint not found: {0}")]
+    NotFound(BlueprintPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
+    #[error("schema error: {0}")]
+    Schema(#[from] SchemaError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("socket error: {0}")]
+    Socket(#[from] SocketError),
+    #[error("socket not found: {0}")]
+    SocketNotFound(SocketId),
+    #[error("socket {1} not found on blueprint component {0}")]
+    SocketNotFoundForComponent(String, String),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("unknown component key in blueprint edge: {0}")]
+    UnknownComponentKey(String),
+    #[error("unknown variable: {0}")]
+    UnknownVariable(String),
+}
+
+pub type BlueprintResult<T> = Result<T, BlueprintError>;
+
+pk!(BlueprintPk);
+
+/// A parameter of a [`Blueprint`], referred to as `{{name}}` in its names and values.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueprintVariable {
+    pub name: String,
+    /// The value used when none is given, or `None` if a value must be given.
+    pub default: Option<Value>,
+}
+
+/// A [`Component`] of a [`Blueprint`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueprintComponent {
+    /// Identifies the component within the blueprint, for its edges.
+    pub key: String,
+    pub name: String,
+    /// The [`Schema`] of the component, whose default variant is instantiated.
+    pub schema_name: String,
+    pub component_type: ComponentType,
+    /// The position of the component, relative to the top left of the blueprint.
+    pub x: f64,
+    pub y: f64,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    /// The values set by hand on the domain of the component.
+    pub values: Vec<AttributeUpdate>,
+}
+
+/// A configuration edge between two [`BlueprintComponents`](BlueprintComponent), from an output
+/// socket to an input socket, both found by name.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueprintEdge {
+    pub from_key: String,
+    pub from_socket: String,
+    pub to_key: String,
+    pub to_socket: String,
+}
+
+/// What a [`Blueprint`] creates when it is instantiated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueprintSpec {
+    pub variables: Vec<BlueprintVariable>,
+    pub components: Vec<BlueprintComponent>,
+    pub edges: Vec<BlueprintEdge>,
+}
+
+/// A [`Component`] created by instantiating a [`Blueprint`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiatedComponent {
+    /// The key of the [`BlueprintComponent`] it was created from.
+    pub key: String,
+    pub component_id: ComponentId,
+    pub node_id: NodeId,
+}
+
+/// A reusable group of [`Components`](Component) of a [`Workspace`](crate::Workspace).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Blueprint {
+    pk: BlueprintPk,
+    workspace_pk: WorkspacePk,
+    name: String,
+    description: Option<String>,
+    spec: BlueprintSpec,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl Blueprint {
+    pub fn pk(&self) -> BlueprintPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(name, String);
+    standard_model_accessor_ro!(description, Option<String>);
+    standard_model_accessor_ro!(spec, BlueprintSpec);
+
+    /// Creates a new [`Blueprint`] in the workspace of the current tenancy.
+    #[instrument(skip(ctx, spec))]
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        description: Option<String>,
+        spec: BlueprintSpec,
+    ) -> BlueprintResult<Self> {
+        spec.validate()?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM blueprint_create_v1($1, $2, $3, $4)",
+                &[
+                    &workspace_pk(ctx)?,
+                    &name.as_ref(),
+                    &description,
+                    &serde_json::to_value(&spec)?,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    /// Creates a new [`Blueprint`] from the [`Components`](Component) of the change set of the
+    /// [`DalContext`], along with the configuration edges between them. Wherever the default of a
+    /// variable appears in their names and values, it is replaced with the variable.
+    #[instrument(skip(ctx))]
+    pub async fn capture(
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        description: Option<String>,
+        component_ids: &[ComponentId],
+        variables: Vec<BlueprintVariable>,
+    ) -> BlueprintResult<Self> {
+        let spec = BlueprintSpec::capture(ctx, component_ids, variables).await?;
+        Self::new(ctx, name, description, spec).await
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: BlueprintPk) -> BlueprintResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(BLUEPRINT_GET_BY_PK, &[&pk, &workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the [`Blueprints`](Blueprint) of the workspace of the current tenancy, by name.
+    pub async fn list(ctx: &DalContext) -> BlueprintResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(BLUEPRINT_LIST_FOR_WORKSPACE, &[&workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Replaces the name, description and spec of the blueprint, such as to add variables to a
+    /// captured blueprint.
+    #[instrument(skip(self, ctx, spec))]
+    pub async fn update(
+        &mut self,
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        description: Option<String>,
+        spec: BlueprintSpec,
+    ) -> BlueprintResult<()> {
+        spec.validate()?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM blueprint_update_v1($1, $2, $3, $4)",
+                &[
+                    &self.pk,
+                    &name.as_ref(),
+                    &description,
+                    &serde_json::to_value(&spec)?,
+                ],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn delete(self, ctx: &DalContext) -> BlueprintResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(BLUEPRINT_DELETE, &[&self.pk, &self.workspace_pk])
+            .await?;
+        Ok(())
+    }
+
+    /// Creates the [`Components`](Component) of the blueprint in the change set of the
+    /// [`DalContext`], as described in [`BlueprintSpec::instantiate`].
+    #[instrument(skip(self, ctx), fields(blueprint.pk = %self.pk))]
+    pub async fn instantiate(
+        &self,
+        ctx: &DalContext,
+        values: HashMap<String, Value>,
+        x: f64,
+        y: f64,
+    ) -> BlueprintResult<Vec<InstantiatedComponent>> {
+        self.spec.instantiate(ctx, values, x, y).await
+    }
+}
+
+impl BlueprintSpec {
+    /// Creates the [`Components`](Component) of the spec in the change set of the
+    /// [`DalContext`], with their top left at `(x, y)`, and wires the edges between them. Each
+    /// variable takes the value given for it, or its default.
+    #[instrument(skip(self, ctx))]
+    pub async fn instantiate(
+        &self,
+        ctx: &DalContext,
+        values: HashMap<String, Value>,
+        x: f64,
+        y: f64,
+    ) -> BlueprintResult<Vec<InstantiatedComponent>> {
+        let variables = resolve_variables(&self.variables, values)?;
+
+        let mut instantiated = Vec::with_capacity(self.components.len());
+        for template in &self.components {
+            let schema_variant_id =
+                Schema::default_schema_variant_id_for_name(ctx, &template.schema_name).await?;
+            let name = render_str(&template.name, &variables);
+            let (component, mut node) = Component::new(ctx, name, schema_variant_id).await?;
+            node.set_geometry(
+                ctx,
+                format!("{}", x + template.x),
+                format!("{}", y + template.y),
+                template.width.as_deref(),
+                template.height.as_deref(),
+            )
+            .await?;
+            component.set_type(ctx, template.component_type).await?;
+
+            let updates = template
+                .values
+                .iter()
+                .map(|update| {
+                    AttributeUpdate::new(
+                        update.json_pointer.clone(),
+                        update.value.as_ref().map(|value| render(value, &variables)),
+                    )
+                })
+                .collect();
+            Component::update_attributes_bulk(ctx, *component.id(), updates).await?;
+
+            instantiated.push(InstantiatedComponent {
+                key: template.key.clone(),
+                component_id: *component.id(),
+                node_id: *node.id(),
+            });
+        }
+
+        let nodes: HashMap<&str, (ComponentId, NodeId)> = instantiated
+            .iter()
+            .map(|i| (i.key.as_str(), (i.component_id, i.node_id)))
+            .collect();
+        for edge in &self.edges {
+            let (from_component_id, from_node_id) = *nodes
+                .get(edge.from_key.as_str())
+                .ok_or_else(|| BlueprintError::UnknownComponentKey(edge.from_key.clone()))?;
+            let (_, to_node_id) = *nodes
+                .get(edge.to_key.as_str())
+                .ok_or_else(|| BlueprintError::UnknownComponentKey(edge.to_key.clone()))?;
+            let from_socket = find_socket(
+                ctx,
+                &edge.from_key,
+                &edge.from_socket,
+                SocketEdgeKind::ConfigurationOutput,
+                from_node_id,
+            )
+            .await?;
+            let to_socket = find_socket(
+                ctx,
+                &edge.to_key,
+                &edge.to_socket,
+                SocketEdgeKind::ConfigurationInput,
+                to_node_id,
+            )
+            .await?;
+            Connection::new(
+                ctx,
+                from_node_id,
+                *from_socket.id(),
+                to_node_id,
+                *to_socket.id(),
+                EdgeKind::Configuration,
+            )
+            .await?;
+
+            // Values flow through the new edge once the output socket is updated
+            let external_provider = ExternalProvider::find_for_socket(ctx, *from_socket.id())
+                .await?
+                .ok_or_else(|| {
+                    BlueprintError::ExternalProviderNotFound(edge.from_socket.clone())
+                })?;
+            let context = AttributeReadContext {
+                external_provider_id: Some(*external_provider.id()),
+                component_id: Some(from_component_id),
+                ..Default::default()
+            };
+            if let Some(attribute_value) = AttributeValue::find_for_context(ctx, context).await? {
+                ctx.enqueue_job(DependentValuesUpdate::new(
+                    ctx.access_builder(),
+                    *ctx.visibility(),
+                    vec![*attribute_value.id()],
+                ))
+                .await?;
+            }
+        }
+
+        Ok(instantiated)
+    }
+
+    /// Builds the spec of the [`Components`](Component), as described in [`Blueprint::capture`].
+    #[instrument(skip(ctx))]
+    pub async fn capture(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+        variables: Vec<BlueprintVariable>,
+    ) -> BlueprintResult<Self> {
+        let mut components = Vec::with_capacity(component_ids.len());
+        let mut sockets_nodes = HashMap::new();
+        for component_id in component_ids {
+            let component = Component::get_by_id(ctx, component_id)
+                .await?
+                .ok_or(BlueprintError::ComponentNotFound(*component_id))?;
+            let schema = component
+                .schema(ctx)
+                .await?
+                .ok_or(ComponentError::NoSchema(*component_id))?;
+            let node = component
+                .node(ctx)
+                .await?
+                .pop()
+                .ok_or(BlueprintError::NodeNotFound(*component_id))?;
+            sockets_nodes.insert(*node.id(), component_id.to_string());
+
+            components.push(BlueprintComponent {
+                key: component_id.to_string(),
+                name: parameterize_str(&component.name(ctx).await?, &variables),
+                schema_name: schema.name().to_owned(),
+                component_type: component.get_type(ctx).await?,
+                x: node.x().parse().unwrap_or_default(),
+                y: node.y().parse().unwrap_or_default(),
+                width: node.width().map(ToOwned::to_owned),
+                height: node.height().map(ToOwned::to_owned),
+                values: Component::list_values_set_by_hand(ctx, *component_id)
+                    .await?
+                    .into_iter()
+                    .map(|update| AttributeUpdate {
+                        value: update.value.map(|value| parameterize(&value, &variables)),
+                        ..update
+                    })
+                    .collect(),
+            });
+        }
+
+        // Positions are kept relative to the top left of the captured components
+        let min_x = components.iter().map(|c| c.x).fold(f64::INFINITY, f64::min);
+        let min_y = components.iter().map(|c| c.y).fold(f64::INFINITY, f64::min);
+        for component in &mut components {
+            component.x -= min_x;
+            component.y -= min_y;
+        }
+
+        let mut edges = Vec::new();
+        for edge in Edge::list_for_components(ctx, component_ids).await? {
+            if edge.kind() != &EdgeKind::Configuration {
+                continue;
+            }
+            let (Some(from_key), Some(to_key)) = (
+                sockets_nodes.get(&edge.tail_node_id()),
+                sockets_nodes.get(&edge.head_node_id()),
+            ) else {
+                continue;
+            };
+            let edge = BlueprintEdge {
+                from_key: from_key.clone(),
+                from_socket: socket_name(ctx, edge.tail_socket_id()).await?,
+                to_key: to_key.clone(),
+                to_socket: socket_name(ctx, edge.head_socket_id()).await?,
+            };
+            // Edges touching two captured components are listed for both of them
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+
+        let spec = Self {
+            variables,
+            components,
+            edges,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Checks that the spec has components, that its keys and variables are unique, and that its
+    /// edges refer to its components.
+    pub fn validate(&self) -> BlueprintResult<()> {
+        if self.components.is_empty() {
+            return Err(BlueprintError::Empty);
+        }
+
+        let mut names = HashSet::new();
+        for variable in &self.variables {
+            let valid = !variable.name.is_empty()
+                && variable
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(BlueprintError::InvalidVariableName(variable.name.clone()));
+            }
+            if !names.insert(variable.name.as_str()) {
+                return Err(BlueprintError::DuplicateVariable(variable.name.clone()));
+            }
+        }
+
+        let mut keys = HashSet::new();
+        for component in &self.components {
+            if !keys.insert(component.key.as_str()) {
+                return Err(BlueprintError::DuplicateComponentKey(component.key.clone()));
+            }
+        }
+        for edge in &self.edges {
+            for key in [&edge.from_key, &edge.to_key] {
+                if !keys.contains(key.as_str()) {
+                    return Err(BlueprintError::UnknownComponentKey(key.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> BlueprintResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(BlueprintError::NoWorkspaceInTenancy)
+}
+
+async fn socket_name(ctx: &DalContext, socket_id: SocketId) -> BlueprintResult<String> {
+    let socket = Socket::get_by_id(ctx, &socket_id)
+        .await?
+        .ok_or(BlueprintError::SocketNotFound(socket_id))?;
+    Ok(socket.name().to_owned())
+}
+
+async fn find_socket(
+    ctx: &DalContext,
+    key: &str,
+    name: &str,
+    edge_kind: SocketEdgeKind,
+    node_id: NodeId,
+) -> BlueprintResult<Socket> {
+    Socket::find_by_name_for_edge_kind_and_node(ctx, name, edge_kind, node_id)
+        .await?
+        .ok_or_else(|| BlueprintError::SocketNotFoundForComponent(key.to_owned(), name.to_owned()))
+}
+
+/// Returns the value of every variable: the one given, or its default.
+fn resolve_variables(
+    variables: &[BlueprintVariable],
+    mut values: HashMap<String, Value>,
+) -> BlueprintResult<HashMap<String, Value>> {
+    if let Some(name) = values
+        .keys()
+        .find(|name| !variables.iter().any(|v| &v.name == *name))
+    {
+        return Err(BlueprintError::UnknownVariable(name.clone()));
+    }
+
+    let mut resolved = HashMap::with_capacity(variables.len());
+    for variable in variables {
+        let value = values
+            .remove(&variable.name)
+            .or_else(|| variable.default.clone())
+            .ok_or_else(|| BlueprintError::MissingVariable(variable.name.clone()))?;
+        resolved.insert(variable.name.clone(), value);
+    }
+    Ok(resolved)
+}
+
+/// Replaces the placeholders of the value with the values of the variables.
+fn render(value: &Value, variables: &HashMap<String, Value>) -> Value {
+    match value {
+        Value::String(string) => {
+            let whole = string
+                .strip_prefix("{{")
+                .and_then(|rest| rest.strip_suffix("}}"))
+                .and_then(|name| variables.get(name));
+            match whole {
+                Some(variable) => variable.clone(),
+                None => Value::String(render_str(string, variables)),
+            }
+        }
+        Value::Array(values) => Value::Array(values.iter().map(|v| render(v, variables)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), render(value, variables)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+fn render_str(string: &str, variables: &HashMap<String, Value>) -> String {
+    let mut rendered = string.to_owned();
+    for (name, value) in variables {
+        let value = match value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        rendered = rendered.replace(&format!("{{{{{name}}}}}"), &value);
+    }
+    rendered
+}
+
+/// Replaces the defaults of the variables found in the value with their placeholders.
+fn parameterize(value: &Value, variables: &[BlueprintVariable]) -> Value {
+    match value {
+        Value::String(string) => Value::String(parameterize_str(string, variables)),
+        Value::Array(values) => {
+            Value::Array(values.iter().map(|v| parameterize(v, variables)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), parameterize(value, variables)))
+                .collect(),
+        ),
+        value => match variables
+            .iter()
+            .find(|variable| variable.default.as_ref() == Some(value))
+        {
+            Some(variable) => Value::String(format!("{{{{{}}}}}", variable.name)),
+            None => value.clone(),
+        },
+    }
+}
+
+fn parameterize_str(string: &str, variables: &[BlueprintVariable]) -> String {
+    let mut parameterized = string.to_owned();
+    for variable in variables {
+        if let Some(Value::String(default)) = &variable.default {
+            if !default.is_empty() {
+                parameterized =
+                    parameterized.replace(default, &format!("{{{{{}}}}}", variable.name));
+            }
+        }
+    }
+    parameterized
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn variable(name: &str, default: Option<Value>) -> BlueprintVariable {
+        BlueprintVariable {
+            name: name.to_owned(),
+            default,
+        }
+    }
+
+    #[test]
+    fn parameterize_then_render_round_trips() {
+        let variables = vec![
+            variable("env", Some(json!("prod"))),
+            variable("replicas", Some(json!(3))),
+        ];
+        let value = json!({
+            "name": "prod-web",
+            "replicas": 3,
+            "tags": ["prod", "web"],
+            "port": 80,
+        });
+
+        let parameterized = parameterize(&value, &variables);
+        assert_eq!(
+            json!({
+                "name": "{{env}}-web",
+                "replicas": "{{replicas}}",
+                "tags": ["{{env}}", "web"],
+                "port": 80,
+            }),
+            parameterized
+        );
+
+        let defaults = resolve_variables(&variables, HashMap::new()).expect("defaults resolve");
+        assert_eq!(value, render(&parameterized, &defaults));
+
+        let staging = resolve_variables(
+            &variables,
+            HashMap::from([
+                ("env".to_owned(), json!("staging")),
+                ("replicas".to_owned(), json!(1)),
+            ]),
+        )
+        .expect("values resolve");
+        assert_eq!(
+            json!({
+                "name": "staging-web",
+                "replicas": 1,
+                "tags": ["staging", "web"],
+                "port": 80,
+            }),
+            render(&parameterized, &staging)
+        );
+        assert_eq!("web-1", render_str("web-{{replicas}}", &staging));
+    }
+
+    #[test]
+    fn resolve_variables_checks_names() {
+        let variables = vec![variable("env", None)];
+        assert!(matches!(
+            resolve_variables(&variables, HashMap::new()),
+            Err(BlueprintError::MissingVariable(name)) if name == "env"
+        ));
+        assert!(matches!(
+            resolve_variables(
+                &variables,
+                HashMap::from([
+                    ("env".to_owned(), json!("prod")),
+                    ("region".to_owned(), json!("us-east-2")),
+                ])
+            ),
+            Err(BlueprintError::UnknownVariable(name)) if name == "region"
+        ));
+    }
+
+    #[test]
+    fn validate_spec() {
+        let component = |key: &str| BlueprintComponent {
+            key: key.to_owned(),
+            name: "web".to_owned(),
+            schema_name: "Docker Image".to_owned(),
+            component_type: ComponentType::Component,
+            x: 0.0,
+            y: 0.0,
+            width: None,
+            height: None,
+            values: vec![],
+        };
+        let edge = BlueprintEdge {
+            from_key: "a".to_owned(),
+            from_socket: "Container Image".to_owned(),
+            to_key: "b".to_owned(),
+            to_socket: "Container Image".to_owned(),
+        };
+        let spec = BlueprintSpec {
+            variables: vec![variable("env", None)],
+            components: vec![component("a"), component("b")],
+            edges: vec![edge.clone()],
+        };
+        assert!(spec.validate().is_ok());
+
+        assert!(matches!(
+            BlueprintSpec::default().validate(),
+            Err(BlueprintError::Empty)
+        ));
+        assert!(matches!(
+            BlueprintSpec {
+                components: vec![component("a")],
+                ..spec.clone()
+            }
+            .validate(),
+            Err(BlueprintError::UnknownComponentKey(key)) if key == "b"
+        ));
+        assert!(matches!(
+            BlueprintSpec {
+                components: vec![component("a"), component("a")],
+                edges: vec![],
+                ..spec.clone()
+            }
+            .validate(),
+            Err(BlueprintError::DuplicateComponentKey(_))
+        ));
+        assert!(matches!(
+            BlueprintSpec {
+                variables: vec![variable("my env", None)],
+                ..spec.clone()
+            }
+            .validate(),
+            Err(BlueprintError::InvalidVariableName(_))
+        ));
+        assert!(matches!(
+            BlueprintSpec {
+                variables: vec![variable("env", None), variable("env", None)],
+                ..spec
+            }
+            .validate(),
+            Err(BlueprintError::DuplicateVariable(_))
+        ));
+    }
+}
@@ -3,10 +3,13 @@
 //! [migrate()](crate::builtins::migrate()) function. However, they may have some functionality
 //! exposed for "dev mode" use cases.
 
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::collections::HashSet;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use si_data_pg::PgError;
 use si_pkg::{SiPkgError, SpecError};
 
 use crate::func::argument::FuncArgumentError;
@@ -27,11 +30,13 @@ use crate::{
 };
 
 // Private builtins modules.
+mod fingerprint;
 mod func;
 pub mod schema;
 
 pub const SI_AWS_PKG: &str = "si-aws-2023-07-06.sipkg";
 pub const SI_AWS_EC2_PKG: &str = "si-aws-ec2-2023-07-07.sipkg";
+pub const SI_AWS_S3_PKG: &str = "si-aws-s3-2023-07-20.sipkg";
 pub const SI_DOCKER_IMAGE_PKG: &str = "si-docker-image-2023-07-06.sipkg";
 pub const SI_COREOS_PKG: &str = "si-coreos-2023-07-06.sipkg";
 pub const SI_GENERIC_FRAME_PKG: &str = "si-generic-frame-2023-07-06.sipkg";
@@ -89,6 +94,8 @@ pub enum BuiltinsError {
     MissingAttributePrototypeForExternalProvider(ExternalProviderId),
     #[error("no packages path configured")]
     MissingPkgsPath,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
     #[error(transparent)]
     Pkg(#[from] PkgError),
     #[error("prop error: {0}")]
@@ -119,12 +126,66 @@ pub enum BuiltinsError {
     StandardModel(#[from] StandardModelError),
     #[error("error creating new transactions")]
     Transactions(#[from] TransactionsError),
+    #[error("unknown builtin: {0}")]
+    UnknownBuiltin(String),
     #[error("validation prototype error: {0}")]
     ValidationPrototype(#[from] ValidationPrototypeError),
 }
 
 pub type BuiltinsResult<T> = Result<T, BuiltinsError>;
 
+/// The builtins migrated for production use, which can be selected by name, such as `"docker"`.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    DeserializeFromStr,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    SerializeDisplay,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Builtin {
+    Aws,
+    AwsEc2,
+    AwsS3,
+    Coreos,
+    Docker,
+    GenericFrame,
+}
+
+impl Builtin {
+    /// Returns the package the builtin is imported from.
+    pub fn pkg_filename(&self) -> &'static str {
+        match self {
+            Self::Aws => SI_AWS_PKG,
+            Self::AwsEc2 => SI_AWS_EC2_PKG,
+            Self::AwsS3 => SI_AWS_S3_PKG,
+            Self::Coreos => SI_COREOS_PKG,
+            Self::Docker => SI_DOCKER_IMAGE_PKG,
+            Self::GenericFrame => SI_GENERIC_FRAME_PKG,
+        }
+    }
+
+    /// Parses a list of builtin names, failing on the first one which is not a known builtin.
+    pub fn parse_names(names: &[&str]) -> BuiltinsResult<Vec<Self>> {
+        names
+            .iter()
+            .map(|name| {
+                name.trim()
+                    .parse()
+                    .map_err(|_| BuiltinsError::UnknownBuiltin((*name).to_owned()))
+            })
+            .collect()
+    }
+}
+
 /// This enum drives what builtin [`Schemas`](crate::Schema) to migrate for tests.
 ///
 /// This enum _should not_ be used outside of tests!
@@ -146,9 +207,13 @@ pub enum SelectedTestBuiltinSchemas {
 /// 1. [`Funcs`](crate::Func)
 /// 1. [`Schemas`](crate::Schema)
 /// 1. ['ActionPrototypes'](crate::ActionPrototype)
+///
+/// Only the `selected_builtins` are migrated, if given. Builtins which have not changed since they
+/// were last migrated are skipped.
 pub async fn migrate(
     ctx: &DalContext,
     selected_test_builtin_schemas: Option<SelectedTestBuiltinSchemas>,
+    selected_builtins: Option<&[Builtin]>,
 ) -> BuiltinsResult<()> {
     info!("migrating intrinsic functions");
     func::migrate_intrinsics(ctx).await?;
@@ -157,13 +222,21 @@ pub async fn migrate(
 
     match selected_test_builtin_schemas {
         Some(found_selected_test_builtin_schemas) => {
-            schema::migrate_for_tests(ctx, found_selected_test_builtin_schemas).await?;
+            schema::migrate_for_tests(ctx, found_selected_test_builtin_schemas, selected_builtins)
+                .await?;
         }
         None => {
-            schema::migrate_for_production(ctx).await?;
+            schema::migrate_for_production(ctx, selected_builtins).await?;
         }
     }
 
     info!("completed migrating functions, workflows and schemas");
     Ok(())
 }
+
+/// Migrate only the named builtins, such as `&["docker", "coreos"]`, along with the builtin
+/// functions.
+pub async fn migrate_builtins_only(ctx: &DalContext, names: &[&str]) -> BuiltinsResult<()> {
+    let builtins = Builtin::parse_names(names)?;
+    migrate(ctx, None, Some(&builtins)).await
+}
@@ -0,0 +1,34 @@
+//! Content hashes of the builtins which have been migrated, used to skip the builtins which have
+//! not changed since.
+
+use object_tree::Hash;
+
+use crate::{BuiltinsResult, DalContext};
+
+const FIND: &str = include_str!("../queries/builtin_fingerprint/find.sql");
+const RECORD: &str = include_str!("../queries/builtin_fingerprint/record.sql");
+
+/// Returns whether the builtin was last migrated with the same fingerprint.
+pub async fn is_unchanged(
+    ctx: &DalContext,
+    name: &str,
+    fingerprint: &Hash,
+) -> BuiltinsResult<bool> {
+    let row = ctx.txns().await?.pg().query_opt(FIND, &[&name]).await?;
+
+    Ok(match row {
+        Some(row) => row.try_get::<_, String>("fingerprint")? == fingerprint.to_string(),
+        None => false,
+    })
+}
+
+/// Records the fingerprint the builtin has been migrated with.
+pub async fn record(ctx: &DalContext, name: &str, fingerprint: &Hash) -> BuiltinsResult<()> {
+    ctx.txns()
+        .await?
+        .pg()
+        .execute(RECORD, &[&name, &fingerprint.to_string()])
+        .await?;
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use base64::engine::general_purpose;
 use base64::Engine;
+use object_tree::Hash;
 use serde::{Deserialize, Serialize};
 use si_pkg::SiPkg;
 use telemetry::prelude::*;
@@ -15,6 +16,11 @@ use crate::{
     StandardModel,
 };
 
+use super::fingerprint;
+
+/// The name the fingerprint of the builtin functions is recorded under.
+const FUNCS_FINGERPRINT_NAME: &str = "funcs";
+
 #[derive(Deserialize, Serialize, Debug)]
 struct FunctionMetadataArgument {
     name: String,
@@ -71,6 +77,17 @@ pub async fn migrate_intrinsics(ctx: &DalContext) -> BuiltinsResult<()> {
 }
 
 pub async fn migrate(ctx: &DalContext) -> BuiltinsResult<()> {
+    let mut contents = Vec::new();
+    for builtin_func_file in ASSETS.iter() {
+        contents.extend_from_slice(builtin_func_file.relative_path.as_bytes());
+        contents.extend_from_slice(builtin_func_file.contents_str.as_bytes());
+    }
+    let fingerprint = Hash::new(&contents);
+    if fingerprint::is_unchanged(ctx, FUNCS_FINGERPRINT_NAME, &fingerprint).await? {
+        info!("skipping unchanged builtin functions");
+        return Ok(());
+    }
+
     for builtin_func_file in ASSETS.iter() {
         let builtin_path = std::path::Path::new(builtin_func_file.relative_path);
         match builtin_path.extension() {
@@ -187,6 +204,7 @@ pub async fn migrate(ctx: &DalContext) -> BuiltinsResult<()> {
         }
         ctx.blocking_commit().await?;
     }
+    fingerprint::record(ctx, FUNCS_FINGERPRINT_NAME, &fingerprint).await?;
 
     Ok(())
 }
@@ -1,9 +1,12 @@
+use object_tree::Hash;
 use serde_json::Value;
 use si_pkg::SiPkg;
 use std::collections::{HashMap, HashSet};
-use strum::{AsRefStr, Display, EnumIter, EnumString};
+use strum::{AsRefStr, Display, EnumIter, EnumString, IntoEnumIterator};
 use telemetry::prelude::*;
 
+use super::fingerprint;
+
 use crate::func::argument::{FuncArgument, FuncArgumentId};
 use crate::installed_pkg::InstalledPkg;
 use crate::pkg::{import_pkg_from_pkg, ImportOptions};
@@ -12,26 +15,51 @@ use crate::{
         binding::{FuncBinding, FuncBindingId},
         binding_return_value::FuncBindingReturnValueId,
     },
-    BuiltinsError, BuiltinsResult, DalContext, Func, FuncError, FuncId, SchemaError,
+    Builtin, BuiltinsError, BuiltinsResult, DalContext, Func, FuncError, FuncId, SchemaError,
     SelectedTestBuiltinSchemas, StandardModel,
 };
 
 mod test_exclusive_fallout;
 mod test_exclusive_starfield;
 
-/// Migrate [`Schemas`](crate::Schema) for production use.
-pub async fn migrate_for_production(ctx: &DalContext) -> BuiltinsResult<()> {
+/// Migrate [`Schemas`](crate::Schema) for production use, or only the `selected_builtins` if
+/// given.
+pub async fn migrate_for_production(
+    ctx: &DalContext,
+    selected_builtins: Option<&[Builtin]>,
+) -> BuiltinsResult<()> {
     info!("migrating schemas");
 
-    migrate_pkg(ctx, super::SI_AWS_PKG, None).await?;
-    migrate_pkg(ctx, super::SI_AWS_EC2_PKG, None).await?;
-    migrate_pkg(ctx, super::SI_DOCKER_IMAGE_PKG, None).await?;
-    migrate_pkg(ctx, super::SI_COREOS_PKG, None).await?;
-    migrate_pkg(ctx, super::SI_GENERIC_FRAME_PKG, None).await?;
+    for builtin in Builtin::iter() {
+        if selected_builtins.map_or(true, |selected| selected.contains(&builtin)) {
+            migrate_builtin(ctx, builtin).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrate a single [`Builtin`], unless it has not changed since it was last migrated.
+pub async fn migrate_builtin(ctx: &DalContext, builtin: Builtin) -> BuiltinsResult<()> {
+    let fingerprint = builtin_fingerprint(ctx, builtin).await?;
+    if fingerprint::is_unchanged(ctx, builtin.as_ref(), &fingerprint).await? {
+        info!(%builtin, "skipping unchanged builtin");
+        return Ok(());
+    }
+
+    migrate_pkg(ctx, builtin.pkg_filename(), None).await?;
+    fingerprint::record(ctx, builtin.as_ref(), &fingerprint).await?;
 
     Ok(())
 }
 
+/// Hashes the package a [`Builtin`] is imported from.
+async fn builtin_fingerprint(ctx: &DalContext, builtin: Builtin) -> BuiltinsResult<Hash> {
+    let pkgs_path = ctx.pkgs_path().ok_or(BuiltinsError::MissingPkgsPath)?;
+    let contents = tokio::fs::read(pkgs_path.join(builtin.pkg_filename())).await?;
+    Ok(Hash::new(&contents))
+}
+
 #[remain::sorted]
 #[derive(Debug, Copy, Clone, AsRefStr, Display, EnumIter, EnumString, Eq, PartialEq)]
 pub enum BuiltinSchema {
@@ -65,10 +93,12 @@ pub async fn migrate_schema(
     Ok(())
 }
 
-/// Migrate [`Schemas`](crate::Schema) for use in tests.
+/// Migrate [`Schemas`](crate::Schema) for use in tests. When migrating everything, only the
+/// `selected_builtins` are migrated if given.
 pub async fn migrate_for_tests(
     ctx: &DalContext,
     selected_test_builtin_schemas: SelectedTestBuiltinSchemas,
+    selected_builtins: Option<&[Builtin]>,
 ) -> BuiltinsResult<()> {
     // Determine what to migrate based on the selected test builtin schemas provided.
     let (migrate_all, migrate_test_exclusive, specific_builtin_schemas) =
@@ -97,10 +127,17 @@ pub async fn migrate_for_tests(
     ctx.blocking_commit().await?;
 
     if migrate_all {
-        migrate_pkg(ctx, super::SI_AWS_PKG, None).await?;
-        migrate_pkg(ctx, super::SI_AWS_EC2_PKG, None).await?;
-        migrate_pkg(ctx, super::SI_COREOS_PKG, None).await?;
-        migrate_pkg(ctx, super::SI_DOCKER_IMAGE_PKG, None).await?;
+        for builtin in [
+            Builtin::Aws,
+            Builtin::AwsEc2,
+            Builtin::AwsS3,
+            Builtin::Coreos,
+            Builtin::Docker,
+        ] {
+            if selected_builtins.map_or(true, |selected| selected.contains(&builtin)) {
+                migrate_builtin(ctx, builtin).await?;
+            }
+        }
         for test_schema in [BuiltinSchema::Starfield, BuiltinSchema::Fallout] {
             migrate_schema(ctx, test_schema, &driver).await?;
             ctx.blocking_commit().await?;
@@ -117,6 +154,7 @@ pub async fn migrate_for_tests(
             .collect();
         migrate_pkg(ctx, super::SI_AWS_PKG, Some(schemas.to_owned())).await?;
         migrate_pkg(ctx, super::SI_AWS_EC2_PKG, Some(schemas.to_owned())).await?;
+        migrate_pkg(ctx, super::SI_AWS_S3_PKG, Some(schemas.to_owned())).await?;
         migrate_pkg(ctx, super::SI_COREOS_PKG, Some(schemas.to_owned())).await?;
         migrate_pkg(ctx, super::SI_DOCKER_IMAGE_PKG, Some(schemas.to_owned())).await?;
         for test_schema in [BuiltinSchema::Starfield, BuiltinSchema::Fallout] {
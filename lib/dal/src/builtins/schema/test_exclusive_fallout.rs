@@ -1,13 +1,12 @@
 use si_pkg::{
     ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, FuncArgumentSpec, FuncSpec,
-    FuncSpecBackendKind, FuncSpecBackendResponseType, LeafFunctionSpec, PkgSpec, PropSpec,
-    SchemaSpec, SchemaVariantSpec, SiPkg, SocketSpec, SocketSpecKind, ValidationSpec,
-    ValidationSpecKind,
+    FuncSpecBackendKind, FuncSpecBackendResponseType, LeafFunctionSpec, PropSpec, SchemaSpec,
+    SchemaVariantSpec, SocketSpec, SocketSpecKind, ValidationSpec, ValidationSpecKind,
 };
 
 use crate::func::argument::FuncArgumentKind;
 use crate::func::intrinsics::IntrinsicFunc;
-use crate::pkg::import_pkg_from_pkg;
+use crate::pkg::SchemaBundle;
 use crate::schema::variant::leaves::LeafInputLocation;
 use crate::schema::variant::leaves::LeafKind;
 use crate::{builtins::schema::MigrationDriver, prop::PropPath, ActionKind, PropKind};
@@ -15,13 +14,6 @@ use crate::{BuiltinsResult, DalContext};
 
 impl MigrationDriver {
     pub async fn migrate_test_exclusive_fallout(&self, ctx: &DalContext) -> BuiltinsResult<()> {
-        let mut fallout_builder = PkgSpec::builder();
-
-        fallout_builder
-            .name("fallout")
-            .version("2023-05-23")
-            .created_by("System Initiative");
-
         let identity_func_spec = IntrinsicFunc::Identity.to_spec()?;
 
         let fallout_confirmation_code = "async function exists(input) {
@@ -189,26 +181,14 @@ impl MigrationDriver {
             )
             .build()?;
 
-        let fallout_spec = fallout_builder
+        SchemaBundle::new(fallout_schema, "2023-05-23")
             .func(identity_func_spec)
             .func(fallout_create_action_func)
             .func(fallout_confirmation_func)
             .func(fallout_authoring_schema_func)
             .func(fallout_resource_payload_to_value_func)
-            .schema(fallout_schema)
-            .build()?;
-
-        let fallout_pkg = SiPkg::load_from_spec(fallout_spec)?;
-        import_pkg_from_pkg(
-            ctx,
-            &fallout_pkg,
-            "test:fallout",
-            Some(crate::pkg::ImportOptions {
-                schemas: Some(vec!["fallout".into()]),
-                ..Default::default()
-            }),
-        )
-        .await?;
+            .create(ctx)
+            .await?;
 
         Ok(())
     }
@@ -1,12 +1,12 @@
 use si_pkg::{
     ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, FuncArgumentSpec, FuncSpec,
-    FuncSpecBackendKind, FuncSpecBackendResponseType, LeafFunctionSpec, PkgSpec, PropSpec,
-    SchemaSpec, SchemaVariantSpec, SiPkg, SocketSpec, SocketSpecKind,
+    FuncSpecBackendKind, FuncSpecBackendResponseType, LeafFunctionSpec, PropSpec, SchemaSpec,
+    SchemaVariantSpec, SocketSpec, SocketSpecKind,
 };
 
 use crate::func::argument::FuncArgumentKind;
 use crate::func::intrinsics::IntrinsicFunc;
-use crate::pkg::import_pkg_from_pkg;
+use crate::pkg::SchemaBundle;
 use crate::schema::variant::leaves::LeafInputLocation;
 use crate::schema::variant::leaves::LeafKind;
 use crate::{builtins::schema::MigrationDriver, prop::PropPath, ActionKind};
@@ -14,13 +14,6 @@ use crate::{BuiltinsResult, DalContext, PropKind};
 
 impl MigrationDriver {
     pub async fn migrate_test_exclusive_starfield(&self, ctx: &DalContext) -> BuiltinsResult<()> {
-        let mut starfield_builder = PkgSpec::builder();
-
-        starfield_builder
-            .name("starfield")
-            .version("2023-05-23")
-            .created_by("System Initiative");
-
         let identity_func_spec = IntrinsicFunc::Identity
             .to_spec()
             .expect("create identity func spec");
@@ -268,7 +261,7 @@ impl MigrationDriver {
             )
             .build()?;
 
-        let starfield_spec = starfield_builder
+        SchemaBundle::new(starfield_schema, "2023-05-23")
             .func(identity_func_spec)
             .func(starfield_refresh_action_func)
             .func(starfield_create_action_func)
@@ -276,20 +269,8 @@ impl MigrationDriver {
             .func(fallout_entries_to_galaxies_transform_func)
             .func(starfield_authoring_schema_func)
             .func(starfield_resource_payload_to_value_func)
-            .schema(starfield_schema)
-            .build()?;
-
-        let starfield_pkg = SiPkg::load_from_spec(starfield_spec)?;
-        import_pkg_from_pkg(
-            ctx,
-            &starfield_pkg,
-            "test:starfield",
-            Some(crate::pkg::ImportOptions {
-                schemas: Some(vec!["starfield".into()]),
-                ..Default::default()
-            }),
-        )
-        .await?;
+            .create(ctx)
+            .await?;
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
@@ -6,21 +6,33 @@ use strum::{Display, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::change_set::review::{ChangeSetReview, ChangeSetReviewError};
+use crate::change_set::schedule::{ChangeSetApplySchedule, ChangeSetApplyScheduleError};
 use crate::label_list::LabelList;
-use crate::standard_model::object_option_from_row_option;
+use crate::standard_model::{object_option_from_row_option, objects_from_rows};
 use crate::ws_event::{WsEvent, WsEventError, WsPayload};
 use crate::{
-    pk, HistoryEvent, HistoryEventError, LabelListError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, UserError, UserPk, Visibility,
+    pk, AuditAction, AuditLog, AuditLogError, AuditTarget, HistoryEvent, HistoryEventError,
+    LabelListError, StandardModelError, Tenancy, Timestamp, TransactionsError, UserError, UserPk,
+    Visibility,
 };
 use crate::{Component, ComponentError, DalContext, WsEventResult};
 
+pub mod qualification_summary;
+pub mod review;
+pub mod schedule;
+
 const CHANGE_SET_OPEN_LIST: &str = include_str!("queries/change_set/open_list.sql");
 const CHANGE_SET_GET_BY_PK: &str = include_str!("queries/change_set/get_by_pk.sql");
+const CHANGE_SET_LIST_OPEN_OBJECTS: &str = include_str!("queries/change_set/list_open_objects.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ChangeSetError {
+    #[error(transparent)]
+    ApplySchedule(#[from] ChangeSetApplyScheduleError),
+    #[error(transparent)]
+    AuditLog(#[from] AuditLogError),
     #[error(transparent)]
     Component(#[from] ComponentError),
     #[error(transparent)]
@@ -31,9 +43,13 @@ pub enum ChangeSetError {
     LabelList(#[from] LabelListError),
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
+    Review(#[from] ChangeSetReviewError),
+    #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
@@ -65,6 +81,9 @@ pub struct ChangeSet {
     pub name: String,
     pub note: Option<String>,
     pub status: ChangeSetStatus,
+    /// The [`User`](crate::User) who created the change set, if it was not created by the system.
+    #[serde(default)]
+    pub created_by_user_pk: Option<UserPk>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -85,12 +104,13 @@ impl ChangeSet {
             .await?
             .pg()
             .query_one(
-                "SELECT object FROM change_set_create_v1($1, $2, $3, $4)",
+                "SELECT object FROM change_set_create_v2($1, $2, $3, $4, $5)",
                 &[
                     &name,
                     &note,
                     &ChangeSetStatus::Open.to_string(),
                     ctx.tenancy(),
+                    &ctx.history_actor().user_pk(),
                 ],
             )
             .await?;
@@ -115,7 +135,10 @@ impl ChangeSet {
         ctx: &mut DalContext,
         run_confirmations: bool,
     ) -> ChangeSetResult<()> {
+        ChangeSetReview::ensure_approved(ctx, self.pk).await?;
+
         let actor = serde_json::to_value(ctx.history_actor())?;
+        let before = serde_json::json![{ "status": &self.status }];
         let row = ctx
             .txns()
             .await?
@@ -128,11 +151,16 @@ impl ChangeSet {
         let updated_at: DateTime<Utc> = row.try_get("timestamp_updated_at")?;
         self.timestamp.updated_at = updated_at;
         self.status = ChangeSetStatus::Applied;
-        let _history_event = HistoryEvent::new(
+        AuditLog::record(
             ctx,
-            "change_set.apply",
-            "Change Set applied",
-            &serde_json::json![{ "pk": &self.pk }],
+            AuditAction::ChangeSetApply,
+            Some(AuditTarget::new(
+                "change_set",
+                self.pk,
+                Some(self.name.clone()),
+            )),
+            Some(before),
+            Some(serde_json::json![{ "status": &self.status }]),
         )
         .await?;
 
@@ -158,6 +186,17 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Schedules an apply of the open [`ChangeSet`](Self) at `at`, such as during a maintenance
+    /// window. See [`ChangeSetApplySchedule`] for how the apply is performed.
+    #[instrument(skip(ctx))]
+    pub async fn schedule_apply(
+        &self,
+        ctx: &DalContext,
+        at: DateTime<FixedOffset>,
+    ) -> ChangeSetResult<ChangeSetApplySchedule> {
+        Ok(ChangeSetApplySchedule::new(ctx, self.pk, at).await?)
+    }
+
     #[instrument(skip_all)]
     pub async fn list_open(ctx: &DalContext) -> ChangeSetResult<LabelList<ChangeSetPk>> {
         let rows = ctx
@@ -170,6 +209,19 @@ impl ChangeSet {
         Ok(results)
     }
 
+    /// Lists the open [`ChangeSets`](Self) of the tenancy, most recently created first. Unlike
+    /// [`list_open()`](Self::list_open), the whole change sets are returned.
+    #[instrument(skip_all)]
+    pub async fn list_open_objects(ctx: &DalContext) -> ChangeSetResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(CHANGE_SET_LIST_OPEN_OBJECTS, &[ctx.tenancy()])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
     #[instrument(skip_all)]
     pub async fn get_by_pk(
         ctx: &DalContext,
@@ -0,0 +1,80 @@
+//! This module rolls the qualifications of every [`Component`](crate::Component) of a
+//! [`ChangeSet`] (or head) up into a [`QualificationSummary`], computed by a single query rather
+//! than by listing the qualifications of each component.
+//!
+//! Once values have been updated, [`ChangeSet::publish_qualification_summary()`] records the
+//! summary and publishes it with a [`WsEvent`] if it changed, so that clients can update their
+//! badges without polling.
+
+use telemetry::prelude::*;
+
+use crate::qualification::{QualificationSummary, QualificationSummaryForComponent};
+use crate::standard_model::objects_from_rows;
+use crate::ws_event::{WsEvent, WsPayload};
+use crate::{DalContext, WsEventResult};
+
+use super::{ChangeSet, ChangeSetError, ChangeSetResult};
+
+const QUALIFICATION_SUMMARY: &str = include_str!("../queries/change_set/qualification_summary.sql");
+
+impl ChangeSet {
+    /// Summarizes the qualifications of every component in the visibility of the context.
+    #[instrument(skip_all)]
+    pub async fn qualification_summary(ctx: &DalContext) -> ChangeSetResult<QualificationSummary> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(QUALIFICATION_SUMMARY, &[ctx.tenancy(), ctx.visibility()])
+            .await?;
+        let components: Vec<QualificationSummaryForComponent> = objects_from_rows(rows)?;
+        Ok(QualificationSummary::from_components(components))
+    }
+
+    /// Computes the qualification summary of the visibility of the context and, if it differs
+    /// from the one recorded last, records it and publishes it on commit. Returns the summary if
+    /// it was published.
+    #[instrument(skip_all)]
+    pub async fn publish_qualification_summary(
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Option<QualificationSummary>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(ChangeSetError::NoWorkspaceInTenancy)?;
+        let summary = Self::qualification_summary(ctx).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT changed FROM qualification_summary_record_v1($1, $2, $3)",
+                &[
+                    &workspace_pk,
+                    &ctx.visibility().change_set_pk,
+                    &serde_json::to_value(&summary)?,
+                ],
+            )
+            .await?;
+        let changed: bool = row.try_get("changed")?;
+        if !changed {
+            return Ok(None);
+        }
+
+        WsEvent::qualification_summary_updated(ctx, summary.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        Ok(Some(summary))
+    }
+}
+
+impl WsEvent {
+    pub async fn qualification_summary_updated(
+        ctx: &DalContext,
+        summary: QualificationSummary,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::QualificationSummaryUpdated(summary)).await
+    }
+}
@@ -0,0 +1,413 @@
+//! This module contains [`ChangeSetReview`], the decision of a reviewer on a
+//! [`ChangeSet`](crate::ChangeSet). When the [`Workspace`](crate::Workspace) requires approvals,
+//! a [`ChangeSet`](crate::ChangeSet) can only be applied once enough reviewers have approved it
+//! and none have rejected it.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::ws_event::{WsEvent, WsEventError, WsPayload};
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, AuditAction, AuditLog, AuditLogError,
+    AuditTarget, ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus, DalContext,
+    StandardModelError, Timestamp, TransactionsError, User, UserError, UserPk, Workspace,
+    WorkspaceError, WorkspacePk, WsEventResult,
+};
+
+const LIST_FOR_CHANGE_SET: &str =
+    include_str!("../queries/change_set_review/list_for_change_set.sql");
+const FIND_FOR_REVIEWER: &str = include_str!("../queries/change_set_review/find_for_reviewer.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ChangeSetReviewError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
+    #[error("change set not found: {0}")]
+    ChangeSetNotFound(ChangeSetPk),
+    #[error("change set {0} is {1}, only open change sets can be reviewed")]
+    ChangeSetNotOpen(ChangeSetPk, ChangeSetStatus),
+    #[error("change set {0} has {1} of the {2} required approvals")]
+    MissingApprovals(ChangeSetPk, usize, i64),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("no reviewers requested")]
+    NoReviewers,
+    #[error("only users can review change sets")]
+    NoUserActor,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("user {1} is not a reviewer of change set {0}")]
+    NotReviewer(ChangeSetPk, UserPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("change set {0} has been rejected by user {1}")]
+    Rejected(ChangeSetPk, UserPk),
+    #[error("user {1} created change set {0} and can't review it")]
+    ReviewerIsAuthor(ChangeSetPk, UserPk),
+    #[error("user {1} requested a review of change set {0} and can't review it")]
+    ReviewerIsRequester(ChangeSetPk, UserPk),
+    #[error("reviewer not found: {0}")]
+    ReviewerNotFound(UserPk),
+    #[error("reviewer {0} does not belong to workspace {1}")]
+    ReviewerNotInWorkspace(UserPk, WorkspacePk),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("user error: {0}")]
+    User(#[from] UserError),
+    #[error("workspace error: {0}")]
+    Workspace(#[from] WorkspaceError),
+    #[error("workspace not found: {0}")]
+    WorkspaceNotFound(WorkspacePk),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type ChangeSetReviewResult<T> = Result<T, ChangeSetReviewError>;
+
+pk!(ChangeSetReviewPk);
+
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+pub enum ChangeSetReviewStatus {
+    Approved,
+    Pending,
+    Rejected,
+}
+
+/// The review of a [`ChangeSet`](crate::ChangeSet) by a single reviewer.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSetReview {
+    pk: ChangeSetReviewPk,
+    workspace_pk: WorkspacePk,
+    change_set_pk: ChangeSetPk,
+    reviewer_user_pk: UserPk,
+    requested_by_user_pk: Option<UserPk>,
+    status: ChangeSetReviewStatus,
+    comment: Option<String>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl ChangeSetReview {
+    pub fn pk(&self) -> ChangeSetReviewPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(change_set_pk, ChangeSetPk);
+    standard_model_accessor_ro!(reviewer_user_pk, UserPk);
+    standard_model_accessor_ro!(requested_by_user_pk, Option<UserPk>);
+    standard_model_accessor_ro!(status, ChangeSetReviewStatus);
+    standard_model_accessor_ro!(comment, Option<String>);
+
+    /// Requests a review of the open [`ChangeSet`](crate::ChangeSet) for `change_set_pk` from
+    /// each of the `reviewer_user_pks`, who must belong to the [`Workspace`](crate::Workspace).
+    /// Neither the requester nor the creator of the [`ChangeSet`](crate::ChangeSet) can review
+    /// it. Requesting a review again from someone who approved the
+    /// [`ChangeSet`](crate::ChangeSet) resets their approval, but a rejection stands.
+    #[instrument(skip(ctx))]
+    pub async fn request(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        reviewer_user_pks: Vec<UserPk>,
+    ) -> ChangeSetReviewResult<Vec<Self>> {
+        let workspace_pk = workspace_pk(ctx)?;
+        if reviewer_user_pks.is_empty() {
+            return Err(ChangeSetReviewError::NoReviewers);
+        }
+        let change_set = find_open_change_set(ctx, change_set_pk).await?;
+        let requested_by_user_pk = ctx.history_actor().user_pk();
+        let members: HashSet<UserPk> = User::list_for_workspace(ctx, workspace_pk)
+            .await?
+            .iter()
+            .map(User::pk)
+            .collect();
+
+        let mut reviews = Vec::with_capacity(reviewer_user_pks.len());
+        for reviewer_user_pk in reviewer_user_pks {
+            if requested_by_user_pk == Some(reviewer_user_pk) {
+                return Err(ChangeSetReviewError::ReviewerIsRequester(
+                    change_set_pk,
+                    reviewer_user_pk,
+                ));
+            }
+            if change_set.created_by_user_pk == Some(reviewer_user_pk) {
+                return Err(ChangeSetReviewError::ReviewerIsAuthor(
+                    change_set_pk,
+                    reviewer_user_pk,
+                ));
+            }
+            if User::get_by_pk(ctx, reviewer_user_pk).await?.is_none() {
+                return Err(ChangeSetReviewError::ReviewerNotFound(reviewer_user_pk));
+            }
+            if !members.contains(&reviewer_user_pk) {
+                return Err(ChangeSetReviewError::ReviewerNotInWorkspace(
+                    reviewer_user_pk,
+                    workspace_pk,
+                ));
+            }
+
+            let row = ctx
+                .txns()
+                .await?
+                .pg()
+                .query_one(
+                    "SELECT object FROM change_set_review_request_v2($1, $2, $3, $4)",
+                    &[
+                        &workspace_pk,
+                        &change_set_pk,
+                        &reviewer_user_pk,
+                        &requested_by_user_pk,
+                    ],
+                )
+                .await?;
+            let review: Self = standard_model::object_from_row(row)?;
+
+            AuditLog::record(
+                ctx,
+                AuditAction::ChangeSetReviewRequest,
+                Some(AuditTarget::new(
+                    "change_set",
+                    change_set_pk,
+                    Some(change_set.name.clone()),
+                )),
+                None,
+                Some(serde_json::json![{ "reviewerUserPk": reviewer_user_pk }]),
+            )
+            .await?;
+            WsEvent::change_set_review_requested(ctx, &review)
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+
+            reviews.push(review);
+        }
+
+        Ok(reviews)
+    }
+
+    /// Approves the [`ChangeSet`](crate::ChangeSet) for `change_set_pk` as the current
+    /// [`User`](crate::User), who must have been requested as a reviewer.
+    pub async fn approve(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        comment: Option<String>,
+    ) -> ChangeSetReviewResult<Self> {
+        Self::submit(ctx, change_set_pk, ChangeSetReviewStatus::Approved, comment).await
+    }
+
+    /// Rejects the [`ChangeSet`](crate::ChangeSet) for `change_set_pk` as the current
+    /// [`User`](crate::User), who must have been requested as a reviewer. A rejected
+    /// [`ChangeSet`](crate::ChangeSet) cannot be applied until the reviewer approves it.
+    pub async fn reject(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        comment: Option<String>,
+    ) -> ChangeSetReviewResult<Self> {
+        Self::submit(ctx, change_set_pk, ChangeSetReviewStatus::Rejected, comment).await
+    }
+
+    #[instrument(skip(ctx, comment))]
+    async fn submit(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        status: ChangeSetReviewStatus,
+        comment: Option<String>,
+    ) -> ChangeSetReviewResult<Self> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let reviewer_user_pk = ctx
+            .history_actor()
+            .user_pk()
+            .ok_or(ChangeSetReviewError::NoUserActor)?;
+        let change_set = find_open_change_set(ctx, change_set_pk).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                FIND_FOR_REVIEWER,
+                &[&workspace_pk, &change_set_pk, &reviewer_user_pk],
+            )
+            .await?;
+        let review: Self = standard_model::option_object_from_row(row)?.ok_or(
+            ChangeSetReviewError::NotReviewer(change_set_pk, reviewer_user_pk),
+        )?;
+
+        let before = serde_json::json![{ "status": review.status }];
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM change_set_review_submit_v1($1, $2, $3)",
+                &[&review.pk, &status.as_ref(), &comment],
+            )
+            .await?;
+        let review: Self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::ChangeSetReview,
+            Some(AuditTarget::new(
+                "change_set",
+                change_set_pk,
+                Some(change_set.name),
+            )),
+            Some(before),
+            Some(serde_json::json![{ "status": review.status, "comment": review.comment }]),
+        )
+        .await?;
+        WsEvent::change_set_reviewed(ctx, &review)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(review)
+    }
+
+    /// Lists the [`ChangeSetReviews`](ChangeSetReview) of the [`ChangeSet`](crate::ChangeSet) for
+    /// `change_set_pk`, in the order they were requested.
+    pub async fn list_for_change_set(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> ChangeSetReviewResult<Vec<Self>> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_CHANGE_SET, &[&workspace_pk, &change_set_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Returns an error if the [`Workspace`](crate::Workspace) requires approvals and the
+    /// [`ChangeSet`](crate::ChangeSet) for `change_set_pk` has been rejected or does not have
+    /// enough of them. Without a workspace in the tenancy, there is no policy to enforce.
+    pub async fn ensure_approved(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> ChangeSetReviewResult<()> {
+        let Some(workspace_pk) = ctx.tenancy().workspace_pk() else {
+            return Ok(());
+        };
+        let workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+            .await?
+            .ok_or(ChangeSetReviewError::WorkspaceNotFound(workspace_pk))?;
+        let required_approvals = *workspace.required_change_set_approvals();
+        if required_approvals <= 0 {
+            return Ok(());
+        }
+
+        let reviews = Self::list_for_change_set(ctx, change_set_pk).await?;
+        if let Some(rejection) = reviews
+            .iter()
+            .find(|review| review.status == ChangeSetReviewStatus::Rejected)
+        {
+            return Err(ChangeSetReviewError::Rejected(
+                change_set_pk,
+                rejection.reviewer_user_pk,
+            ));
+        }
+
+        let approvals = reviews
+            .iter()
+            .filter(|review| review.status == ChangeSetReviewStatus::Approved)
+            .count();
+        if (approvals as i64) < required_approvals {
+            return Err(ChangeSetReviewError::MissingApprovals(
+                change_set_pk,
+                approvals,
+                required_approvals,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> ChangeSetReviewResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(ChangeSetReviewError::NoWorkspaceInTenancy)
+}
+
+async fn find_open_change_set(
+    ctx: &DalContext,
+    change_set_pk: ChangeSetPk,
+) -> ChangeSetReviewResult<ChangeSet> {
+    let change_set = ChangeSet::get_by_pk(ctx, &change_set_pk)
+        .await
+        .map_err(Box::new)?
+        .ok_or(ChangeSetReviewError::ChangeSetNotFound(change_set_pk))?;
+    if change_set.status != ChangeSetStatus::Open {
+        return Err(ChangeSetReviewError::ChangeSetNotOpen(
+            change_set_pk,
+            change_set.status,
+        ));
+    }
+    Ok(change_set)
+}
+
+/// The payload of the [`WsEvents`](crate::WsEvent) sent when a review is requested and submitted.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetReviewPayload {
+    change_set_pk: ChangeSetPk,
+    review_pk: ChangeSetReviewPk,
+    reviewer_user_pk: UserPk,
+    status: ChangeSetReviewStatus,
+}
+
+impl ChangeSetReviewPayload {
+    pub fn change_set_pk(&self) -> ChangeSetPk {
+        self.change_set_pk
+    }
+
+    pub fn reviewer_user_pk(&self) -> UserPk {
+        self.reviewer_user_pk
+    }
+}
+
+impl From<&ChangeSetReview> for ChangeSetReviewPayload {
+    fn from(review: &ChangeSetReview) -> Self {
+        Self {
+            change_set_pk: review.change_set_pk,
+            review_pk: review.pk,
+            reviewer_user_pk: review.reviewer_user_pk,
+            status: review.status,
+        }
+    }
+}
+
+impl WsEvent {
+    pub async fn change_set_review_requested(
+        ctx: &DalContext,
+        review: &ChangeSetReview,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetReviewRequested(review.into())).await
+    }
+
+    pub async fn change_set_reviewed(
+        ctx: &DalContext,
+        review: &ChangeSetReview,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetReviewed(review.into())).await
+    }
+}
@@ -0,0 +1,551 @@
+//! This module contains [`ChangeSetApplySchedule`], an apply of a [`ChangeSet`](crate::ChangeSet)
+//! queued for a later time, such as a maintenance window. When the time comes, the
+//! [`ChangeSetApplyScheduler`](crate::tasks::ChangeSetApplyScheduler) runs the qualifications of
+//! the changed [`Components`](crate::Component) again and applies the
+//! [`ChangeSet`](crate::ChangeSet) if they pass.
+
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::change_status::{ChangeStatusError, ComponentChangeStatus};
+use crate::job::definition::DependentValuesUpdate;
+use crate::qualification::QualificationSubCheckStatus;
+use crate::ws_event::{WsEvent, WsEventError, WsPayload};
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, AuditAction, AuditLog, AuditLogError,
+    AuditTarget, ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus, Component,
+    ComponentError, ComponentId, DalContext, HistoryActor, QualificationGatingPolicy,
+    RootPropChild, StandardModelError, Timestamp, TransactionsError, UserPk, Visibility,
+    WorkspacePk, WorkspaceSettings, WorkspaceSettingsError, WsEventResult,
+};
+
+const CLAIM_DUE: &str = include_str!("../queries/change_set_apply_schedule/claim_due.sql");
+const FIND_PENDING_FOR_CHANGE_SET: &str =
+    include_str!("../queries/change_set_apply_schedule/find_pending_for_change_set.sql");
+const LIST_PENDING: &str = include_str!("../queries/change_set_apply_schedule/list_pending.sql");
+const LOCK: &str = include_str!("../queries/change_set_apply_schedule/lock.sql");
+const LOCK_STARTED: &str = include_str!("../queries/change_set_apply_schedule/lock_started.sql");
+
+/// How long a schedule may stay [`Started`](ChangeSetApplyScheduleStatus::Started) before another
+/// scheduler claims it, assuming the one which started it stopped.
+const STARTED_SCHEDULE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ChangeSetApplyScheduleError {
+    #[error("change set {0} already has a scheduled apply")]
+    AlreadyScheduled(ChangeSetPk),
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
+    #[error("change set not found: {0}")]
+    ChangeSetNotFound(ChangeSetPk),
+    #[error("change set {0} is {1}, only open change sets can be applied")]
+    ChangeSetNotOpen(ChangeSetPk, ChangeSetStatus),
+    #[error("change status error: {0}")]
+    ChangeStatus(#[from] ChangeStatusError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("cannot schedule an apply in the past: {0}")]
+    InPast(DateTime<FixedOffset>),
+    #[error("invalid utc offset: {0} seconds")]
+    InvalidUtcOffset(i32),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("apply schedule {0} is {1}, only scheduled applies can be canceled")]
+    NotScheduled(ChangeSetApplySchedulePk, ChangeSetApplyScheduleStatus),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("qualifications are failing for: {0}")]
+    QualificationsFailing(String),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace settings error: {0}")]
+    WorkspaceSettings(#[from] WorkspaceSettingsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type ChangeSetApplyScheduleResult<T> = Result<T, ChangeSetApplyScheduleError>;
+
+pk!(ChangeSetApplySchedulePk);
+
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+pub enum ChangeSetApplyScheduleStatus {
+    Applied,
+    Canceled,
+    Failed,
+    Scheduled,
+    Started,
+}
+
+/// A scheduled apply of a [`ChangeSet`](crate::ChangeSet). A change set has at most one
+/// [`Scheduled`](ChangeSetApplyScheduleStatus::Scheduled) or
+/// [`Started`](ChangeSetApplyScheduleStatus::Started) apply at a time.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSetApplySchedule {
+    pk: ChangeSetApplySchedulePk,
+    workspace_pk: WorkspacePk,
+    change_set_pk: ChangeSetPk,
+    apply_at: DateTime<Utc>,
+    utc_offset_seconds: i32,
+    scheduled_by_user_pk: Option<UserPk>,
+    status: ChangeSetApplyScheduleStatus,
+    failure: Option<String>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl ChangeSetApplySchedule {
+    pub fn pk(&self) -> ChangeSetApplySchedulePk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(change_set_pk, ChangeSetPk);
+    standard_model_accessor_ro!(apply_at, DateTime<Utc>);
+    standard_model_accessor_ro!(scheduled_by_user_pk, Option<UserPk>);
+    standard_model_accessor_ro!(status, ChangeSetApplyScheduleStatus);
+    standard_model_accessor_ro!(failure, Option<String>);
+
+    /// Returns when the apply is due, in the time zone it was scheduled with.
+    pub fn apply_at_local(&self) -> ChangeSetApplyScheduleResult<DateTime<FixedOffset>> {
+        let offset = FixedOffset::east_opt(self.utc_offset_seconds).ok_or(
+            ChangeSetApplyScheduleError::InvalidUtcOffset(self.utc_offset_seconds),
+        )?;
+        Ok(self.apply_at.with_timezone(&offset))
+    }
+
+    /// Schedules an apply of the open [`ChangeSet`](crate::ChangeSet) for `change_set_pk` at
+    /// `apply_at`, which must be in the future. The offset of `apply_at` is kept, so that the
+    /// schedule can be shown in the time zone it was made in.
+    #[instrument(skip(ctx))]
+    pub async fn new(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        apply_at: DateTime<FixedOffset>,
+    ) -> ChangeSetApplyScheduleResult<Self> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let change_set = find_open_change_set(ctx, change_set_pk).await?;
+        if apply_at.with_timezone(&Utc) <= ctx.now() {
+            return Err(ChangeSetApplyScheduleError::InPast(apply_at));
+        }
+        if Self::find_pending_for_change_set(ctx, change_set_pk)
+            .await?
+            .is_some()
+        {
+            return Err(ChangeSetApplyScheduleError::AlreadyScheduled(change_set_pk));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM change_set_apply_schedule_create_v1($1, $2, $3, $4, $5)",
+                &[
+                    &workspace_pk,
+                    &change_set_pk,
+                    &apply_at.with_timezone(&Utc),
+                    &apply_at.offset().local_minus_utc(),
+                    &ctx.history_actor().user_pk(),
+                ],
+            )
+            .await?;
+        let schedule: Self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::ChangeSetApplySchedule,
+            Some(AuditTarget::new(
+                "change_set",
+                change_set_pk,
+                Some(change_set.name),
+            )),
+            None,
+            Some(serde_json::json![{ "applyAt": apply_at }]),
+        )
+        .await?;
+        WsEvent::change_set_apply_scheduled(ctx, &schedule)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(schedule)
+    }
+
+    /// Finds the [`Scheduled`](ChangeSetApplyScheduleStatus::Scheduled) or
+    /// [`Started`](ChangeSetApplyScheduleStatus::Started) apply of the
+    /// [`ChangeSet`](crate::ChangeSet) for `change_set_pk`, if any.
+    pub async fn find_pending_for_change_set(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> ChangeSetApplyScheduleResult<Option<Self>> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                FIND_PENDING_FOR_CHANGE_SET,
+                &[&workspace_pk, &change_set_pk],
+            )
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the [`Scheduled`](ChangeSetApplyScheduleStatus::Scheduled) and
+    /// [`Started`](ChangeSetApplyScheduleStatus::Started) applies of the workspace, soonest first.
+    pub async fn list_pending(ctx: &DalContext) -> ChangeSetApplyScheduleResult<Vec<Self>> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_PENDING, &[&workspace_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Cancels the apply, which must not have started yet.
+    #[instrument(skip(ctx))]
+    pub async fn cancel(&mut self, ctx: &DalContext) -> ChangeSetApplyScheduleResult<()> {
+        let workspace_pk = workspace_pk(ctx)?;
+        // Waits for a scheduler which is claiming the schedule, and sees whether it started it
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(LOCK, &[&self.pk, &workspace_pk])
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        if self.status != ChangeSetApplyScheduleStatus::Scheduled {
+            return Err(ChangeSetApplyScheduleError::NotScheduled(
+                self.pk,
+                self.status,
+            ));
+        }
+
+        let change_set_name = ChangeSet::get_by_pk(ctx, &self.change_set_pk)
+            .await
+            .map_err(Box::new)?
+            .map(|change_set| change_set.name);
+        self.set_status(ctx, ChangeSetApplyScheduleStatus::Canceled, None)
+            .await?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::ChangeSetApplyScheduleCancel,
+            Some(AuditTarget::new(
+                "change_set",
+                self.change_set_pk,
+                change_set_name,
+            )),
+            Some(serde_json::json![{ "applyAt": self.apply_at }]),
+            None,
+        )
+        .await?;
+        WsEvent::change_set_apply_schedule_canceled(ctx, self)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks the applies which are due at `now` as
+    /// [`Started`](ChangeSetApplyScheduleStatus::Started) and returns them, along with those a
+    /// stopped scheduler left started, for every workspace.
+    pub async fn claim_due(
+        ctx: &DalContext,
+        now: DateTime<Utc>,
+    ) -> ChangeSetApplyScheduleResult<Vec<Self>> {
+        let stale_before = now
+            - chrono::Duration::from_std(STARTED_SCHEDULE_TIMEOUT)
+                .unwrap_or_else(|_| chrono::Duration::zero());
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(CLAIM_DUE, &[&now, &stale_before])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Enqueues the qualifications of the [`Components`](crate::Component) added or modified by
+    /// the [`ChangeSet`](crate::ChangeSet) to run again, so that the apply is gated on fresh
+    /// results. The [`DalContext`] must be in the workspace and change set of the schedule, and
+    /// should be committed with [`blocking_commit()`](DalContext::blocking_commit) before calling
+    /// [`perform()`](Self::perform).
+    #[instrument(skip(ctx))]
+    pub async fn rerun_qualifications(&self, ctx: &DalContext) -> ChangeSetApplyScheduleResult<()> {
+        let mut attribute_value_ids = Vec::new();
+        for component_id in changed_component_ids(ctx).await? {
+            // Qualifications depend on the domain, so updating its dependents runs them again
+            let domain = Component::root_prop_child_attribute_value_for_component(
+                ctx,
+                component_id,
+                RootPropChild::Domain,
+            )
+            .await?;
+            attribute_value_ids.push(*domain.id());
+        }
+        if !attribute_value_ids.is_empty() {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                *ctx.visibility(),
+                attribute_value_ids,
+            ))
+            .await?;
+        }
+
+        WsEvent::change_set_apply_schedule_started(ctx, self)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        Ok(())
+    }
+
+    /// Applies the [`ChangeSet`](crate::ChangeSet) of the started schedule, as the
+    /// [`User`](crate::User) who scheduled it. The [`DalContext`] must be in the workspace of the
+    /// schedule; it is on head once the change set is applied.
+    ///
+    /// The apply fails, leaving the change set open, if it was closed in the meantime, if it
+    /// lacks the approvals the workspace requires, or if qualifications of its changed
+    /// [`Components`](crate::Component) fail and the [`QualificationGatingPolicy`] of the
+    /// workspace does not ignore them. Nobody is around to acknowledge failures when the apply
+    /// runs, so [`Warn`](QualificationGatingPolicy::Warn) blocks it as well.
+    ///
+    /// Returns `false`, doing nothing, if another scheduler is performing the apply or it is no
+    /// longer started.
+    #[instrument(skip(ctx))]
+    pub async fn perform(&mut self, ctx: &mut DalContext) -> ChangeSetApplyScheduleResult<bool> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(LOCK_STARTED, &[&self.pk, &workspace_pk])
+            .await?;
+        match standard_model::option_object_from_row(row)? {
+            Some(schedule) => *self = schedule,
+            None => return Ok(false),
+        }
+
+        if let Some(user_pk) = self.scheduled_by_user_pk {
+            ctx.update_history_actor(HistoryActor::User(user_pk));
+        }
+
+        let savepoint = ctx.savepoint().await?;
+        match self.apply_change_set(ctx).await {
+            Ok(()) => {
+                ctx.release_savepoint(savepoint).await?;
+                self.set_status(ctx, ChangeSetApplyScheduleStatus::Applied, None)
+                    .await?;
+            }
+            Err(err) => {
+                ctx.rollback_to_savepoint(savepoint).await?;
+                warn!(error = ?err, schedule_pk = %self.pk, "scheduled change set apply failed");
+                self.fail(ctx, err.to_string()).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Marks the apply as [`Failed`](ChangeSetApplyScheduleStatus::Failed) because of `failure`.
+    pub async fn fail(
+        &mut self,
+        ctx: &DalContext,
+        failure: String,
+    ) -> ChangeSetApplyScheduleResult<()> {
+        self.set_status(ctx, ChangeSetApplyScheduleStatus::Failed, Some(failure))
+            .await?;
+        WsEvent::change_set_apply_schedule_failed(ctx, self)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        Ok(())
+    }
+
+    async fn apply_change_set(&self, ctx: &mut DalContext) -> ChangeSetApplyScheduleResult<()> {
+        let mut change_set = find_open_change_set(ctx, self.change_set_pk).await?;
+        ctx.update_visibility(Visibility::new(self.change_set_pk, None));
+
+        let policy = WorkspaceSettings::get(ctx).await?.qualification_gating;
+        if policy != QualificationGatingPolicy::Ignore {
+            let failing = failing_component_names(ctx).await?;
+            if !failing.is_empty() {
+                return Err(ChangeSetApplyScheduleError::QualificationsFailing(
+                    failing.join(", "),
+                ));
+            }
+        }
+
+        change_set.apply(ctx).await.map_err(Box::new)?;
+        Ok(())
+    }
+
+    async fn set_status(
+        &mut self,
+        ctx: &DalContext,
+        status: ChangeSetApplyScheduleStatus,
+        failure: Option<String>,
+    ) -> ChangeSetApplyScheduleResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM change_set_apply_schedule_set_status_v1($1, $2, $3)",
+                &[&self.pk, &status.as_ref(), &failure],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        Ok(())
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> ChangeSetApplyScheduleResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(ChangeSetApplyScheduleError::NoWorkspaceInTenancy)
+}
+
+async fn find_open_change_set(
+    ctx: &DalContext,
+    change_set_pk: ChangeSetPk,
+) -> ChangeSetApplyScheduleResult<ChangeSet> {
+    let change_set = ChangeSet::get_by_pk(ctx, &change_set_pk)
+        .await
+        .map_err(Box::new)?
+        .ok_or(ChangeSetApplyScheduleError::ChangeSetNotFound(
+            change_set_pk,
+        ))?;
+    if change_set.status != ChangeSetStatus::Open {
+        return Err(ChangeSetApplyScheduleError::ChangeSetNotOpen(
+            change_set_pk,
+            change_set.status,
+        ));
+    }
+    Ok(change_set)
+}
+
+/// Lists the [`Components`](crate::Component) added or modified in the change set of the
+/// [`DalContext`].
+async fn changed_component_ids(ctx: &DalContext) -> ChangeSetApplyScheduleResult<Vec<ComponentId>> {
+    let mut component_ids: Vec<ComponentId> = ComponentChangeStatus::list_added(ctx)
+        .await?
+        .into_iter()
+        .map(|group| group.component_id)
+        .collect();
+    component_ids.extend(
+        ComponentChangeStatus::list_modified(ctx)
+            .await?
+            .into_iter()
+            .map(|group| group.component_id),
+    );
+    Ok(component_ids)
+}
+
+/// Lists the names of the changed [`Components`](crate::Component) with failing qualifications.
+async fn failing_component_names(ctx: &DalContext) -> ChangeSetApplyScheduleResult<Vec<String>> {
+    let mut names = Vec::new();
+    for component_id in changed_component_ids(ctx).await? {
+        let failing = Component::list_qualifications(ctx, component_id)
+            .await?
+            .iter()
+            .any(|qualification| {
+                qualification.result.as_ref().map(|result| result.status)
+                    == Some(QualificationSubCheckStatus::Failure)
+            });
+        if failing {
+            names.push(Component::find_name(ctx, component_id).await?);
+        }
+    }
+    Ok(names)
+}
+
+/// The payload of the [`WsEvents`](crate::WsEvent) sent as a scheduled apply progresses.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApplySchedulePayload {
+    change_set_pk: ChangeSetPk,
+    schedule_pk: ChangeSetApplySchedulePk,
+    apply_at: DateTime<Utc>,
+    status: ChangeSetApplyScheduleStatus,
+    failure: Option<String>,
+}
+
+impl ChangeSetApplySchedulePayload {
+    pub fn change_set_pk(&self) -> ChangeSetPk {
+        self.change_set_pk
+    }
+}
+
+impl From<&ChangeSetApplySchedule> for ChangeSetApplySchedulePayload {
+    fn from(schedule: &ChangeSetApplySchedule) -> Self {
+        Self {
+            change_set_pk: schedule.change_set_pk,
+            schedule_pk: schedule.pk,
+            apply_at: schedule.apply_at,
+            status: schedule.status,
+            failure: schedule.failure.clone(),
+        }
+    }
+}
+
+impl WsEvent {
+    pub async fn change_set_apply_scheduled(
+        ctx: &DalContext,
+        schedule: &ChangeSetApplySchedule,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetApplyScheduled(schedule.into())).await
+    }
+
+    pub async fn change_set_apply_schedule_canceled(
+        ctx: &DalContext,
+        schedule: &ChangeSetApplySchedule,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetApplyScheduleCanceled(schedule.into()),
+        )
+        .await
+    }
+
+    pub async fn change_set_apply_schedule_started(
+        ctx: &DalContext,
+        schedule: &ChangeSetApplySchedule,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetApplyScheduleStarted(schedule.into()),
+        )
+        .await
+    }
+
+    pub async fn change_set_apply_schedule_failed(
+        ctx: &DalContext,
+        schedule: &ChangeSetApplySchedule,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetApplyScheduleFailed(schedule.into()),
+        )
+        .await
+    }
+}
@@ -0,0 +1,120 @@
+//! This module contains [`Clock`], the source of the current time for logic which depends on how
+//! much time has passed, such as token expiry, staleness checks and retention cutoffs.
+//!
+//! The [`ServicesContext`](crate::ServicesContext) holds the clock, which is the [`SystemClock`]
+//! unless another one is set. Tests set a [`TestClock`] instead, so that they can move time
+//! forward without waiting:
+//!
+//! ```ignore
+//! let clock = TestClock::new();
+//! services_context.set_clock(Arc::new(clock.clone()));
+//! clock.advance(Duration::days(31));
+//! ```
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// A source of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The [`Clock`] which tells the time of the system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A [`Clock`] for tests, which can be moved forward without waiting. Clones share the same
+/// time.
+///
+/// The clock follows the time of the system, shifted by however far it was advanced, so that it
+/// agrees with the timestamps written by the database. Once [set](Self::set()) to a time, it is
+/// stopped and only moves when advanced.
+#[derive(Clone, Debug)]
+pub struct TestClock {
+    state: Arc<Mutex<TestClockState>>,
+}
+
+#[derive(Debug)]
+struct TestClockState {
+    stopped_at: Option<DateTime<Utc>>,
+    offset: Duration,
+}
+
+impl TestClock {
+    /// Creates a clock which follows the time of the system.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TestClockState {
+                stopped_at: None,
+                offset: Duration::zero(),
+            })),
+        }
+    }
+
+    /// Moves the clock forward by `duration`, or backward if it is negative.
+    pub fn advance(&self, duration: Duration) {
+        let mut guard = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = &mut *guard;
+        match state.stopped_at.as_mut() {
+            Some(stopped_at) => *stopped_at = *stopped_at + duration,
+            None => state.offset = state.offset + duration,
+        }
+    }
+
+    /// Moves the clock to `now` and stops it there.
+    pub fn set(&self, now: DateTime<Utc>) {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .stopped_at = Some(now);
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .stopped_at
+            .unwrap_or_else(|| Utc::now() + state.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_advances_past_the_system_time() {
+        let clock = TestClock::new();
+        clock.clone().advance(Duration::days(31));
+        assert!(clock.now() >= Utc::now() + Duration::days(30));
+    }
+
+    #[test]
+    fn test_clock_stops_when_set() {
+        let clock = TestClock::new();
+        let start = Utc::now() - Duration::days(1);
+        clock.set(start);
+        assert_eq!(start, clock.now());
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(start + Duration::minutes(5), clock.now());
+    }
+}
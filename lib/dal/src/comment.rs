@@ -0,0 +1,282 @@
+//! This module contains [`Comment`], a message left by a [`User`](crate::User) on a
+//! [`Component`](crate::Component), one of its [`Props`](crate::Prop) or a
+//! [`ChangeSet`](crate::ChangeSet), so that a model can be discussed where it lives.
+
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::ws_event::{WsEvent, WsEventError, WsPayload};
+use crate::{
+    impl_standard_model, pk, standard_model, standard_model_accessor, ChangeSet, ChangeSetError,
+    ChangeSetPk, Component, ComponentError, ComponentId, DalContext, HistoryActor,
+    HistoryEventError, StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError,
+    UserPk, Visibility, WsEventResult,
+};
+
+const LIST_FOR_TARGET: &str = include_str!("queries/comment/list_for_target.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum CommentError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("change set not found: {0}")]
+    ChangeSetNotFound(ChangeSetPk),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("comment body cannot be empty")]
+    EmptyBody,
+    #[error("history event error: {0}")]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid prop path, expected a json pointer starting with \"/root\": {0}")]
+    InvalidPropPath(String),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("only users can comment")]
+    NoUserActor,
+    #[error("only the author of comment {0} can change it")]
+    NotAuthor(CommentId),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type CommentResult<T> = Result<T, CommentError>;
+
+/// What a [`Comment`] is about. Comments on a [`Prop`](crate::Prop) are scoped to a
+/// [`Component`](crate::Component) and refer to the [`Prop`](crate::Prop) by the same json pointer
+/// as [`Prop::json_pointer()`](crate::Prop::json_pointer()), such as "/root/domain/image".
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum CommentTarget {
+    #[serde(rename_all = "camelCase")]
+    ChangeSet { change_set_pk: ChangeSetPk },
+    #[serde(rename_all = "camelCase")]
+    Component { component_id: ComponentId },
+    #[serde(rename_all = "camelCase")]
+    Prop {
+        component_id: ComponentId,
+        prop_path: String,
+    },
+}
+
+pk!(CommentPk);
+pk!(CommentId);
+
+impl_standard_model! {
+    model: Comment,
+    pk: CommentPk,
+    id: CommentId,
+    table_name: "comments",
+    history_event_label_base: "comment",
+    history_event_message_name: "Comment"
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pk: CommentPk,
+    id: CommentId,
+    target: CommentTarget,
+    author_user_pk: Option<UserPk>,
+    body: String,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    visibility: Visibility,
+}
+
+impl Comment {
+    /// Leaves a [`Comment`] on the `target`, authored by the current [`User`](crate::User).
+    #[instrument(skip(ctx, body))]
+    pub async fn new(
+        ctx: &DalContext,
+        target: CommentTarget,
+        body: impl AsRef<str>,
+    ) -> CommentResult<Self> {
+        let body = body.as_ref();
+        if body.trim().is_empty() {
+            return Err(CommentError::EmptyBody);
+        }
+        let author_user_pk = ctx
+            .history_actor()
+            .user_pk()
+            .ok_or(CommentError::NoUserActor)?;
+        target.ensure_exists(ctx).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM comment_create_v1($1, $2, $3, $4, $5)",
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &serde_json::to_value(&target)?,
+                    &author_user_pk,
+                    &body,
+                ],
+            )
+            .await?;
+        let object: Self = standard_model::finish_create_from_row(ctx, row).await?;
+
+        WsEvent::comment_created(ctx, &object)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(object)
+    }
+
+    standard_model_accessor!(body, String, CommentResult);
+
+    pub fn target(&self) -> &CommentTarget {
+        &self.target
+    }
+
+    pub fn author_user_pk(&self) -> Option<UserPk> {
+        self.author_user_pk
+    }
+
+    /// Lists the [`Comments`](Comment) on the `target`, oldest first.
+    pub async fn list_for_target(
+        ctx: &DalContext,
+        target: &CommentTarget,
+    ) -> CommentResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_FOR_TARGET,
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &serde_json::to_value(target)?,
+                ],
+            )
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Replaces the body of the [`Comment`]. Only its author can edit it.
+    #[instrument(skip(ctx, body))]
+    pub async fn edit(&mut self, ctx: &DalContext, body: impl AsRef<str>) -> CommentResult<()> {
+        let body = body.as_ref();
+        if body.trim().is_empty() {
+            return Err(CommentError::EmptyBody);
+        }
+        self.ensure_author(ctx)?;
+
+        self.set_body(ctx, body).await?;
+
+        WsEvent::comment_updated(ctx, self)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the [`Comment`]. Only its author can delete it.
+    #[instrument(skip(ctx))]
+    pub async fn remove(&mut self, ctx: &DalContext) -> CommentResult<()> {
+        self.ensure_author(ctx)?;
+
+        self.delete_by_id(ctx).await?;
+
+        WsEvent::comment_deleted(ctx, self)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    fn ensure_author(&self, ctx: &DalContext) -> CommentResult<()> {
+        match ctx.history_actor() {
+            HistoryActor::ApiToken { user_pk, .. } | HistoryActor::User(user_pk)
+                if Some(*user_pk) == self.author_user_pk =>
+            {
+                Ok(())
+            }
+            _ => Err(CommentError::NotAuthor(self.id)),
+        }
+    }
+}
+
+impl CommentTarget {
+    async fn ensure_exists(&self, ctx: &DalContext) -> CommentResult<()> {
+        match self {
+            Self::ChangeSet { change_set_pk } => {
+                ChangeSet::get_by_pk(ctx, change_set_pk)
+                    .await?
+                    .ok_or(CommentError::ChangeSetNotFound(*change_set_pk))?;
+            }
+            Self::Component { component_id } => {
+                Component::get_by_id(ctx, component_id)
+                    .await?
+                    .ok_or(CommentError::ComponentNotFound(*component_id))?;
+            }
+            Self::Prop {
+                component_id,
+                prop_path,
+            } => {
+                if prop_path != "/root" && !prop_path.starts_with("/root/") {
+                    return Err(CommentError::InvalidPropPath(prop_path.clone()));
+                }
+                Component::get_by_id(ctx, component_id)
+                    .await?
+                    .ok_or(CommentError::ComponentNotFound(*component_id))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The payload of the [`WsEvents`](crate::WsEvent) sent when a [`Comment`] is created, edited or
+/// deleted, so that everyone in the workspace sees it live.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentPayload {
+    comment_id: CommentId,
+    target: CommentTarget,
+}
+
+impl From<&Comment> for CommentPayload {
+    fn from(comment: &Comment) -> Self {
+        Self {
+            comment_id: comment.id,
+            target: comment.target.clone(),
+        }
+    }
+}
+
+impl WsEvent {
+    pub async fn comment_created(ctx: &DalContext, comment: &Comment) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::CommentCreated(comment.into())).await
+    }
+
+    pub async fn comment_updated(ctx: &DalContext, comment: &Comment) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::CommentUpdated(comment.into())).await
+    }
+
+    pub async fn comment_deleted(ctx: &DalContext, comment: &Comment) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::CommentDeleted(comment.into())).await
+    }
+}
@@ -1,7 +1,10 @@
 //! This module contains [`Component`], which is an instance of a
 //! [`SchemaVariant`](crate::SchemaVariant) and a _model_ of a "real world resource".
 
+use std::collections::{HashMap, HashSet, VecDeque};
+
 use chrono::{DateTime, Utc};
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use si_data_nats::NatsError;
@@ -13,6 +16,8 @@ use thiserror::Error;
 use crate::attribute::context::AttributeContextBuilder;
 use crate::attribute::value::AttributeValue;
 use crate::attribute::value::AttributeValueError;
+use crate::blob::BlobError;
+use crate::change_set::ChangeSetError;
 use crate::code_view::CodeViewError;
 use crate::func::binding::FuncBindingError;
 use crate::func::binding_return_value::{FuncBindingReturnValueError, FuncBindingReturnValueId};
@@ -29,27 +34,46 @@ use crate::{
     standard_model, standard_model_accessor, standard_model_belongs_to, standard_model_has_many,
     ActionPrototypeError, AttributeContext, AttributeContextBuilderError, AttributeContextError,
     AttributePrototype, AttributePrototypeArgument, AttributePrototypeArgumentError,
-    AttributePrototypeError, AttributePrototypeId, AttributeReadContext, ComponentType, DalContext,
-    EdgeError, ExternalProvider, ExternalProviderError, ExternalProviderId, FixError, FixId, Func,
-    FuncBackendKind, FuncError, HistoryActor, HistoryEventError, InternalProvider,
-    InternalProviderId, Node, NodeError, PropError, PropId, RootPropChild, Schema, SchemaError,
-    SchemaId, Socket, StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError,
-    UserPk, ValidationPrototypeError, ValidationResolverError, Visibility, WorkspaceError, WsEvent,
+    AttributePrototypeError, AttributePrototypeId, AttributeReadContext, AuditAction, AuditLog,
+    AuditLogError, AuditTarget, ComponentType, DalContext, EdgeError, ExternalProvider,
+    ExternalProviderError, ExternalProviderId, FixError, FixId, Func, FuncBackendKind, FuncError,
+    HistoryEventError, InternalProvider, InternalProviderId, Node, NodeError, PropError, PropId,
+    RootPropChild, Schema, SchemaError, SchemaId, Socket, StandardModel, StandardModelError,
+    Tenancy, Timestamp, TransactionsError, Usage, UsageError, UsageMetric, UserPk,
+    ValidationPrototypeError, ValidationResolverError, Visibility, WorkspaceError, WsEvent,
     WsEventResult, WsPayload,
 };
 use crate::{AttributeValueId, QualificationError};
 use crate::{Edge, FixResolverError, NodeKind};
 
+pub mod bulk_update;
 pub mod code;
 pub mod confirmation;
 pub mod diff;
+pub mod label;
+pub mod lifecycle;
 pub mod qualification;
 pub mod resource;
+pub mod resource_conflict;
+pub mod snapshot;
 pub mod status;
 pub mod validation;
 pub mod view;
 
-pub use view::{ComponentView, ComponentViewError, ComponentViewProperties};
+pub use bulk_update::AttributeUpdate;
+pub use label::{
+    ComponentLabel, ComponentLabelError, ComponentLabelId, ComponentLabelPk, ComponentLabelResult,
+    LabelRequirement, LabelSelector,
+};
+pub use lifecycle::{
+    ComponentLifecycle, ComponentLifecycleError, ComponentLifecycleEvent, ComponentLifecycleState,
+};
+pub use resource_conflict::{ResourceConflictPolicy, ResourceConflictResolution, ResourceDrift};
+pub use snapshot::{
+    ComponentSnapshot, ComponentSnapshotError, ComponentSnapshotPk, ComponentSnapshotResult,
+    ComponentSnapshotTrigger,
+};
+pub use view::{ComponentView, ComponentViewCache, ComponentViewError, ComponentViewProperties};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -69,10 +93,18 @@ pub enum ComponentError {
     AttributeValue(#[from] AttributeValueError),
     #[error("attribute value not found for context: {0:?}")]
     AttributeValueNotFoundForContext(AttributeReadContext),
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("blob error: {0}")]
+    Blob(#[from] BlobError),
     #[error("cannot update the resource tree when in a change set")]
     CannotUpdateResourceTreeInChangeSet,
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
     #[error(transparent)]
     CodeView(#[from] CodeViewError),
+    #[error(transparent)]
+    ComponentLifecycle(#[from] ComponentLifecycleError),
     #[error("component marked as protected: {0}")]
     ComponentProtected(ComponentId),
     /// No "protected" boolean was found for the appropriate
@@ -91,6 +123,8 @@ pub enum ComponentError {
     ConfirmationView(String),
     #[error(transparent)]
     ContextTransaction(#[from] TransactionsError),
+    #[error("base64 decode error: {0}")]
+    Decode(#[from] base64::DecodeError),
     #[error("edge error: {0}")]
     Edge(#[from] EdgeError),
     /// Found an [`ExternalProviderError`](crate::ExternalProviderError).
@@ -125,6 +159,8 @@ pub enum ComponentError {
     InvalidContextForDiff,
     #[error("invalid func backend kind (0:?) for checking validations (need validation kind)")]
     InvalidFuncBackendKindForValidations(FuncBackendKind),
+    #[error("invalid json pointer: {0}")]
+    InvalidJsonPointer(String),
     #[error("attribute value does not have a prototype: {0}")]
     MissingAttributePrototype(AttributeValueId),
     #[error("attribute prototype does not have a function: {0}")]
@@ -171,6 +207,8 @@ pub enum ComponentError {
     Socket(#[from] SocketError),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
+    #[error("usage error: {0}")]
+    Usage(#[from] UsageError),
     #[error("validation error: {0}")]
     Validation(#[from] ValidationConstructorError),
     #[error("validation prototype error: {0}")]
@@ -188,10 +226,12 @@ pub type ComponentResult<T> = Result<T, ComponentError>;
 const FIND_FOR_NODE: &str = include_str!("queries/component/find_for_node.sql");
 const FIND_SI_CHILD_PROP_ATTRIBUTE_VALUE: &str =
     include_str!("queries/component/find_si_child_attribute_value.sql");
+const LIST_AFTER: &str = include_str!("queries/component/list_after.sql");
 const LIST_FOR_SCHEMA_VARIANT: &str = include_str!("queries/component/list_for_schema_variant.sql");
 const LIST_SOCKETS_FOR_SOCKET_EDGE_KIND: &str =
     include_str!("queries/component/list_sockets_for_socket_edge_kind.sql");
 const FIND_NAME: &str = include_str!("queries/component/find_name.sql");
+const SCHEMA_VARIANT_IDS: &str = include_str!("queries/component/schema_variant_ids.sql");
 const ROOT_CHILD_ATTRIBUTE_VALUE_FOR_COMPONENT: &str =
     include_str!("queries/component/root_child_attribute_value_for_component.sql");
 const LIST_CONNECTED_INPUT_SOCKETS_FOR_ATTRIBUTE_VALUE: &str =
@@ -245,6 +285,8 @@ pub struct Component {
     kind: ComponentKind,
     pub deletion_user_pk: Option<UserPk>,
     needs_destroy: bool,
+    #[serde(default)]
+    resource_conflict_policy: ResourceConflictPolicy,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -289,10 +331,7 @@ impl Component {
             .schema(ctx)
             .await?
             .ok_or(SchemaVariantError::MissingSchema(schema_variant_id))?;
-        let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
-            _ => None,
-        };
+        let actor_user_pk = ctx.history_actor().user_pk();
 
         let row = ctx
             .txns()
@@ -320,6 +359,7 @@ impl Component {
         let node = Node::new(ctx, &NodeKind::Configuration).await?;
         node.set_component(ctx, component.id()).await?;
         component.set_name(ctx, Some(name.as_ref())).await?;
+        Usage::record(ctx, UsageMetric::ComponentsCreated, 1.0).await?;
 
         // Ensure we have an attribute value and prototype for the resource tree in our exact
         // context. We need this in order to run confirmations upon applying a change set.
@@ -387,6 +427,11 @@ impl Component {
 
     standard_model_accessor!(kind, Enum(ComponentKind), ComponentResult);
     standard_model_accessor!(needs_destroy, bool, ComponentResult);
+    standard_model_accessor!(
+        resource_conflict_policy,
+        Enum(ResourceConflictPolicy),
+        ComponentResult
+    );
 
     standard_model_belongs_to!(
         lookup_fn: schema,
@@ -537,6 +582,17 @@ impl Component {
         Ok(results)
     }
 
+    /// Streams every [`Component`] visible to the context, ordered by id, fetching them from the
+    /// database in batches. Pass the id of the last component received to continue after it.
+    #[instrument(skip_all)]
+    pub async fn stream(
+        ctx: &DalContext,
+        after: Option<ComponentId>,
+    ) -> ComponentResult<impl Stream<Item = ComponentResult<Self>> + Send + 'static> {
+        standard_model::stream_objects(ctx, LIST_AFTER, &[ctx.tenancy(), ctx.visibility(), &after])
+            .await
+    }
+
     /// Sets the "/root/si/name" for [`self`](Self).
     #[instrument(skip_all)]
     pub async fn set_name<T: Serialize + std::fmt::Debug + std::clone::Clone>(
@@ -746,6 +802,42 @@ impl Component {
         Ok(row.try_get("schema_variant_id")?)
     }
 
+    /// Finds the [`SchemaVariantIds`](crate::SchemaVariantId) of many
+    /// [`Components`](crate::Component) at once, keyed by component.
+    pub async fn schema_variant_ids(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+    ) -> ComponentResult<HashMap<ComponentId, SchemaVariantId>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                SCHEMA_VARIANT_IDS,
+                &[ctx.tenancy(), ctx.visibility(), &component_ids],
+            )
+            .await?;
+
+        let mut schema_variant_ids = HashMap::with_capacity(rows.len());
+        for row in rows {
+            schema_variant_ids.insert(
+                row.try_get("component_id")?,
+                row.try_get("schema_variant_id")?,
+            );
+        }
+        Ok(schema_variant_ids)
+    }
+
+    /// Gets many [`Components`](Self) by id in a single query. Ids which are not visible are left
+    /// out.
+    pub async fn list_by_ids(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+    ) -> ComponentResult<Vec<Self>> {
+        let component_ids: Vec<&ComponentId> = component_ids.iter().collect();
+        Ok(standard_model::find_by_attr_in(ctx, "components", "id", &component_ids).await?)
+    }
+
     /// Find the [`SchemaId`](crate::SchemaId) that belongs to the provided
     /// [`Component`](crate::Component).
     pub async fn schema_id(
@@ -1014,6 +1106,96 @@ impl Component {
         Ok(())
     }
 
+    /// Walks the configuration edges into the frame `frame_id`, descending into nested frames, and
+    /// returns every [`Component`] found inside it.
+    pub async fn list_frame_descendants(
+        ctx: &DalContext,
+        frame_id: ComponentId,
+    ) -> ComponentResult<Vec<ComponentId>> {
+        let mut found = Vec::new();
+        let mut seen = HashSet::from([frame_id]);
+        let mut queue = VecDeque::from([frame_id]);
+
+        while let Some(frame_id) = queue.pop_front() {
+            let frame = Self::get_by_id(ctx, &frame_id)
+                .await?
+                .ok_or(ComponentError::NotFound(frame_id))?;
+            if frame.get_type(ctx).await? == ComponentType::Component {
+                continue;
+            }
+            let frame_node = frame
+                .node(ctx)
+                .await?
+                .pop()
+                .ok_or(ComponentError::NodeNotFoundForComponent(frame_id))?;
+            let frame_socket = Socket::find_frame_socket_for_node(
+                ctx,
+                *frame_node.id(),
+                SocketEdgeKind::ConfigurationInput,
+            )
+            .await?;
+
+            for edge in Edge::list_for_component(ctx, frame_id).await? {
+                if edge.head_socket_id() != *frame_socket.id() {
+                    continue;
+                }
+                let child_id: ComponentId = edge.tail_object_id().into();
+                if seen.insert(child_id) {
+                    found.push(child_id);
+                    queue.push_back(child_id);
+                }
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Returns every frame that `component_id` is configured into, directly or through nested
+    /// frames, starting with the innermost one.
+    pub async fn list_enclosing_frames(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<ComponentId>> {
+        let mut found = Vec::new();
+        let mut seen = HashSet::from([component_id]);
+        let mut queue = VecDeque::from([component_id]);
+
+        while let Some(child_id) = queue.pop_front() {
+            for edge in Edge::list_for_component(ctx, child_id).await? {
+                let parent_id: ComponentId = edge.head_object_id().into();
+                if ComponentId::from(edge.tail_object_id()) != child_id || seen.contains(&parent_id)
+                {
+                    continue;
+                }
+                let parent = match Self::get_by_id(ctx, &parent_id).await? {
+                    Some(parent) => parent,
+                    None => continue,
+                };
+                if parent.get_type(ctx).await? == ComponentType::Component {
+                    continue;
+                }
+                let parent_node = parent
+                    .node(ctx)
+                    .await?
+                    .pop()
+                    .ok_or(ComponentError::NodeNotFoundForComponent(parent_id))?;
+                let frame_socket = Socket::find_frame_socket_for_node(
+                    ctx,
+                    *parent_node.id(),
+                    SocketEdgeKind::ConfigurationInput,
+                )
+                .await?;
+                if edge.head_socket_id() == *frame_socket.id() {
+                    seen.insert(parent_id);
+                    found.push(parent_id);
+                    queue.push_back(parent_id);
+                }
+            }
+        }
+
+        Ok(found)
+    }
+
     pub async fn delete_and_propagate(&mut self, ctx: &DalContext) -> ComponentResult<()> {
         // Block deletion of frames with children
         if self.get_type(ctx).await? != ComponentType::Component {
@@ -1038,16 +1220,14 @@ impl Component {
             }
         }
 
+        let name = self.name(ctx).await?;
         self.set_deleted_at(ctx, Some(Utc::now())).await?;
 
         if self.get_protected(ctx).await? {
             return Err(ComponentError::ComponentProtected(self.id));
         }
 
-        let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
-            _ => None,
-        };
+        let actor_user_pk = ctx.history_actor().user_pk();
 
         let has_resource = self.resource(ctx).await?.payload.is_some();
         let rows = ctx
@@ -1080,6 +1260,15 @@ impl Component {
         ))
         .await?;
 
+        AuditLog::record(
+            ctx,
+            AuditAction::ComponentDelete,
+            Some(AuditTarget::new("component", self.id, Some(name))),
+            Some(serde_json::json![{ "hasResource": has_resource }]),
+            None,
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -1157,6 +1346,16 @@ impl Component {
         ))
         .await?;
 
+        let name = Self::find_name(ctx, component_id).await?;
+        AuditLog::record(
+            ctx,
+            AuditAction::ComponentRestore,
+            Some(AuditTarget::new("component", component_id, Some(name))),
+            None,
+            None,
+        )
+        .await?;
+
         Ok(Component::get_by_id(ctx, &component_id).await?)
     }
 
@@ -0,0 +1,179 @@
+//! This module contains the ability to update many "fields" of a [`Component`] at once.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+
+use crate::attribute::value::AttributeValue;
+use crate::component::{ComponentError, ComponentResult};
+use crate::func::intrinsics::IntrinsicFunc;
+use crate::job::definition::DependentValuesUpdate;
+use crate::prop::PropPath;
+use crate::{
+    AttributeContext, AttributeReadContext, AttributeValueId, Component, ComponentId,
+    ComponentView, DalContext, Func, FuncError, Prop, StandardModel,
+};
+
+/// The [`Funcs`](Func) which set a value by hand. Only values set with them are worth setting
+/// again, as the others are computed.
+const SETTER_FUNCS: &[IntrinsicFunc] = &[
+    IntrinsicFunc::SetArray,
+    IntrinsicFunc::SetBoolean,
+    IntrinsicFunc::SetInteger,
+    IntrinsicFunc::SetMap,
+    IntrinsicFunc::SetObject,
+    IntrinsicFunc::SetString,
+];
+
+/// A new value for the [`Prop`] found at `json_pointer`, such as "/root/domain/image".
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeUpdate {
+    pub json_pointer: String,
+    pub value: Option<Value>,
+}
+
+impl AttributeUpdate {
+    pub fn new(json_pointer: impl Into<String>, value: Option<Value>) -> Self {
+        Self {
+            json_pointer: json_pointer.into(),
+            value,
+        }
+    }
+
+    /// Converts the "json_pointer" into a [`PropPath`], following the format of
+    /// [`Prop::json_pointer()`](crate::Prop::json_pointer()).
+    fn prop_path(&self) -> ComponentResult<PropPath> {
+        let parts = self
+            .json_pointer
+            .strip_prefix('/')
+            .ok_or_else(|| ComponentError::InvalidJsonPointer(self.json_pointer.clone()))?
+            .split('/')
+            .map(|part| part.replace("~1", "/").replace("~0", "~"))
+            .collect::<Vec<String>>();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(ComponentError::InvalidJsonPointer(
+                self.json_pointer.clone(),
+            ));
+        }
+
+        Ok(PropPath::new(parts))
+    }
+}
+
+impl Component {
+    /// Sets the value of each [`Prop`] in `updates` for the given [`Component`](Self), in order,
+    /// and returns the updated [`AttributeValueIds`](crate::AttributeValue).
+    ///
+    /// Unlike updating each value on its own, the dependent values (and with them, validations,
+    /// code generation and qualifications) are only updated once, after every value has been set.
+    ///
+    /// _Note:_ the "json_pointers" must point to [`Props`](crate::Prop), so elements of arrays and
+    /// maps cannot be updated this way.
+    #[instrument(skip_all)]
+    pub async fn update_attributes_bulk(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        updates: Vec<AttributeUpdate>,
+    ) -> ComponentResult<Vec<AttributeValueId>> {
+        let schema_variant_id = Self::schema_variant_id(ctx, component_id).await?;
+
+        let mut updated_attribute_value_ids = Vec::with_capacity(updates.len());
+        for update in updates {
+            let prop =
+                Prop::find_prop_by_path(ctx, schema_variant_id, &update.prop_path()?).await?;
+            let attribute_context = AttributeContext::builder()
+                .set_component_id(component_id)
+                .set_prop_id(*prop.id())
+                .to_context()?;
+
+            // Found anew for each update, as setting an object creates new values for its
+            // children.
+            let attribute_value = AttributeValue::find_for_context(ctx, attribute_context.into())
+                .await?
+                .ok_or(ComponentError::AttributeValueNotFoundForContext(
+                    attribute_context.into(),
+                ))?;
+            let parent_attribute_value = attribute_value
+                .parent_attribute_value(ctx)
+                .await?
+                .ok_or_else(|| {
+                    ComponentError::ParentAttributeValueNotFound(*attribute_value.id())
+                })?;
+
+            let (_, attribute_value_id) =
+                AttributeValue::update_for_context_without_propagating_dependent_values(
+                    ctx,
+                    *attribute_value.id(),
+                    Some(*parent_attribute_value.id()),
+                    attribute_context,
+                    update.value,
+                    None,
+                )
+                .await?;
+            updated_attribute_value_ids.push(attribute_value_id);
+        }
+
+        if !updated_attribute_value_ids.is_empty() {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                *ctx.visibility(),
+                updated_attribute_value_ids.clone(),
+            ))
+            .await?;
+        }
+
+        Ok(updated_attribute_value_ids)
+    }
+    /// Returns the values set by hand on the domain of the [`Component`], for each of the
+    /// [`Props`](Prop) directly underneath "/root/domain", as the updates which would set them
+    /// again. The value of an object, array or map comes with all of its children.
+    pub async fn list_values_set_by_hand(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<AttributeUpdate>> {
+        let schema_variant_id = Self::schema_variant_id(ctx, component_id).await?;
+        let domain =
+            Prop::find_prop_by_path(ctx, schema_variant_id, &PropPath::new(["root", "domain"]))
+                .await?;
+        let properties = ComponentView::new(ctx, component_id).await?.properties;
+
+        let mut values = Vec::new();
+        for prop in domain.child_props(ctx).await? {
+            let context = AttributeReadContext {
+                prop_id: Some(*prop.id()),
+                component_id: Some(component_id),
+                ..Default::default()
+            };
+            let attribute_value = match AttributeValue::find_for_context(ctx, context).await? {
+                Some(attribute_value) => attribute_value,
+                None => continue,
+            };
+            // Values inherited from the schema variant come with the component
+            if attribute_value.context.is_component_unset() {
+                continue;
+            }
+            let func_id = match attribute_value.attribute_prototype(ctx).await? {
+                Some(prototype) => prototype.func_id(),
+                None => continue,
+            };
+            let func = Func::get_by_id(ctx, &func_id)
+                .await?
+                .ok_or(FuncError::NotFound(func_id))?;
+            if !SETTER_FUNCS
+                .iter()
+                .any(|setter| setter.name() == func.name())
+            {
+                continue;
+            }
+
+            let json_pointer = prop.json_pointer(ctx).await?;
+            let view_pointer = json_pointer.strip_prefix("/root").unwrap_or(&json_pointer);
+            match properties.pointer(view_pointer) {
+                None | Some(Value::Null) => {}
+                Some(value) => values.push(AttributeUpdate::new(json_pointer, Some(value.clone()))),
+            }
+        }
+        Ok(values)
+    }
+}
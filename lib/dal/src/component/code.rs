@@ -1,10 +1,14 @@
+use base64::{engine::general_purpose, Engine};
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use telemetry::prelude::*;
+use veritech_client::Artifact;
 
 use crate::attribute::value::AttributeValue;
 use crate::attribute::value::AttributeValueError;
+use crate::blob::{Blob, BlobHash, BlobResult};
+use crate::component::diff::unified_diff;
 use crate::component::ComponentResult;
 use crate::{
     AttributeReadContext, AttributeValueId, CodeLanguage, CodeView, ComponentError, ComponentId,
@@ -13,10 +17,235 @@ use crate::{
 use crate::{Component, SchemaVariant};
 use crate::{RootPropChild, WsEventResult};
 
+/// Generated code and artifact contents larger than this are kept in the [blob store](Blob), with
+/// their "/root/code" entry holding their blob reference instead.
+const CODE_BLOB_THRESHOLD_BYTES: usize = 4 * 1024;
+/// The number of unchanged lines shown around each change of a [`CodeGeneratedDiff`].
+const DIFF_CONTEXT_LINES: usize = 3;
+
 #[derive(Deserialize, Debug)]
 struct CodeGenerationEntry {
     pub code: Option<String>,
     pub format: Option<String>,
+    #[serde(default)]
+    pub artifacts: HashMap<String, CodeArtifactEntry>,
+}
+
+/// An artifact generated alongside the code of a code generation [`Func`](crate::Func), as stored
+/// under the "artifacts" map of its "/root/code" entry, keyed by the name of the artifact.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeArtifactEntry {
+    pub mime_type: String,
+    pub content_base64: String,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl From<Artifact> for CodeArtifactEntry {
+    fn from(artifact: Artifact) -> Self {
+        Self {
+            mime_type: artifact.mime_type,
+            content_base64: artifact.content_base64,
+            metadata: artifact.metadata,
+        }
+    }
+}
+
+/// Describes an artifact generated for a [`Component`], without its content.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeArtifactView {
+    /// The key of the "/root/code" entry the artifact was generated for.
+    pub code_generation: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size: usize,
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// An artifact generated for a [`Component`], with its decoded content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeArtifact {
+    pub name: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+}
+
+/// Identifies what a code generation [`Func`](crate::Func) generated for a [`Component`]: the code
+/// of a "/root/code" entry, or one of the artifacts generated alongside it.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeGeneratedKey {
+    /// The key of the "/root/code" entry.
+    pub code_generation: String,
+    /// The name of the artifact, or `None` for the code itself.
+    pub artifact: Option<String>,
+}
+
+impl CodeGeneratedKey {
+    fn path(&self) -> String {
+        match &self.artifact {
+            Some(artifact) => format!("{}/{artifact}", self.code_generation),
+            None => self.code_generation.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CodeGeneratedContent {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl CodeGeneratedContent {
+    fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text.as_str()),
+            Self::Binary(_) => None,
+        }
+    }
+}
+
+/// How something generated for a [`Component`] changed between head and a change set.
+#[remain::sorted]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeGeneratedDiffStatus {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// The change of the code, or of an artifact, generated for a [`Component`] between head and a
+/// change set. Generated by [`CodeGenerated::diff_for_component()`].
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeGeneratedDiff {
+    #[serde(flatten)]
+    pub key: CodeGeneratedKey,
+    pub status: CodeGeneratedDiffStatus,
+    /// The unified diff from head to the change set, or `None` if either side is binary.
+    pub diff: Option<String>,
+}
+
+/// Everything the code generation [`Funcs`](crate::Func) of a [`Component`] generated for it:
+/// their code and the artifacts generated alongside it. Artifacts which are not valid UTF-8 are
+/// considered binary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodeGenerated {
+    contents: BTreeMap<CodeGeneratedKey, CodeGeneratedContent>,
+}
+
+impl CodeGenerated {
+    /// Gathers what was generated for a given [`ComponentId`](Component) in the current
+    /// [`Visibility`](crate::Visibility). Code which has yet to be generated is left out.
+    pub async fn for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Self> {
+        let mut contents = BTreeMap::new();
+        for (code_generation, entry) in
+            Component::code_generation_entries(ctx, component_id).await?
+        {
+            if let (Some(code), Some(_)) = (entry.code, &entry.format) {
+                contents.insert(
+                    CodeGeneratedKey {
+                        code_generation: code_generation.clone(),
+                        artifact: None,
+                    },
+                    CodeGeneratedContent::Text(code),
+                );
+            }
+            for (name, artifact) in entry.artifacts {
+                let content = general_purpose::STANDARD.decode(&artifact.content_base64)?;
+                let content = match String::from_utf8(content) {
+                    Ok(text) => CodeGeneratedContent::Text(text),
+                    Err(err) => CodeGeneratedContent::Binary(err.into_bytes()),
+                };
+                contents.insert(
+                    CodeGeneratedKey {
+                        code_generation: code_generation.clone(),
+                        artifact: Some(name),
+                    },
+                    content,
+                );
+            }
+        }
+        Ok(Self { contents })
+    }
+
+    /// Diffs what was generated for a given [`ComponentId`](Component) on head, as seen by
+    /// `head_ctx`, against what was generated for it in the change set of `ctx`. Only the code
+    /// and artifacts which changed are returned, ordered by [`CodeGeneratedKey`]. Everything is
+    /// added if the [`Component`] is new to the change set.
+    #[instrument(skip(ctx, head_ctx))]
+    pub async fn diff_for_component(
+        ctx: &DalContext,
+        head_ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<CodeGeneratedDiff>> {
+        if ctx.visibility().is_head()
+            || ctx.visibility().deleted_at.is_some()
+            || !head_ctx.visibility().is_head()
+        {
+            return Err(ComponentError::InvalidContextForDiff);
+        }
+
+        let curr = Self::for_component(ctx, component_id).await?;
+        let prev = if Component::get_by_id(head_ctx, &component_id)
+            .await?
+            .is_some()
+        {
+            Self::for_component(head_ctx, component_id).await?
+        } else {
+            Self::default()
+        };
+        Ok(prev.diff(&curr))
+    }
+
+    /// Diffs what was generated before, `self`, against what was generated after.
+    fn diff(&self, after: &Self) -> Vec<CodeGeneratedDiff> {
+        let keys: BTreeSet<&CodeGeneratedKey> =
+            self.contents.keys().chain(after.contents.keys()).collect();
+
+        let mut diffs = Vec::new();
+        for key in keys {
+            let prev = self.contents.get(key);
+            let curr = after.contents.get(key);
+            let status = match (prev, curr) {
+                (Some(prev), Some(curr)) if prev == curr => continue,
+                (Some(_), Some(_)) => CodeGeneratedDiffStatus::Modified,
+                (None, Some(_)) => CodeGeneratedDiffStatus::Added,
+                (Some(_), None) => CodeGeneratedDiffStatus::Removed,
+                (None, None) => continue,
+            };
+
+            let binary = prev
+                .into_iter()
+                .chain(curr)
+                .any(|content| content.text().is_none());
+            let diff = if binary {
+                None
+            } else {
+                let path = key.path();
+                unified_diff(
+                    &format!("a/{path}"),
+                    prev.and_then(CodeGeneratedContent::text),
+                    &format!("b/{path}"),
+                    curr.and_then(CodeGeneratedContent::text),
+                    DIFF_CONTEXT_LINES,
+                )
+            };
+
+            diffs.push(CodeGeneratedDiff {
+                key: key.clone(),
+                status,
+                diff,
+            });
+        }
+        diffs
+    }
 }
 
 impl Component {
@@ -27,6 +256,95 @@ impl Component {
         ctx: &DalContext,
         component_id: ComponentId,
     ) -> ComponentResult<Vec<CodeView>> {
+        let mut code_views: Vec<CodeView> = Vec::new();
+        for entry in Self::code_generation_entries(ctx, component_id)
+            .await?
+            .values()
+        {
+            // When a new code gen function is craeted the code/format entries will not yet be
+            // set, so just ignore them in the loop here. Function return value type checking
+            // should ensure that the executed function does not unset these itself.
+            if entry.format.is_none() || entry.code.is_none() {
+                continue;
+            }
+
+            // Safe unwraps because of the above check
+            let format = entry.format.as_ref().unwrap();
+            let code = entry.code.as_ref().unwrap();
+
+            let language = if format.is_empty() {
+                CodeLanguage::Unknown
+            } else {
+                CodeLanguage::try_from(format.to_owned())?
+            };
+
+            // NOTE(nick): we may need to determine how we handle empty code generation or
+            // generation in progress. Maybe we never need to? Just re-run?
+            let code = if code.is_empty() {
+                None
+            } else {
+                Some(code.clone())
+            };
+
+            code_views.push(CodeView::new(language, code));
+        }
+        Ok(code_views)
+    }
+
+    /// List the [`artifacts`](CodeArtifactView) generated alongside the code of the "code
+    /// generation" [`leaves`](crate::schema::variant::leaves) for a given
+    /// [`ComponentId`](Self).
+    pub async fn list_code_artifacts(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<CodeArtifactView>> {
+        let mut artifact_views = Vec::new();
+        for (code_generation, entry) in Self::code_generation_entries(ctx, component_id).await? {
+            for (name, artifact) in entry.artifacts {
+                artifact_views.push(CodeArtifactView {
+                    code_generation: code_generation.clone(),
+                    name,
+                    size: general_purpose::STANDARD
+                        .decode(&artifact.content_base64)?
+                        .len(),
+                    mime_type: artifact.mime_type,
+                    metadata: artifact.metadata,
+                });
+            }
+        }
+        artifact_views
+            .sort_by(|a, b| (&a.code_generation, &a.name).cmp(&(&b.code_generation, &b.name)));
+        Ok(artifact_views)
+    }
+
+    /// Find the [`artifact`](CodeArtifact) named `name` generated alongside the code of the
+    /// `code_generation` entry of "/root/code" for a given [`ComponentId`](Self).
+    pub async fn find_code_artifact(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        code_generation: &str,
+        name: &str,
+    ) -> ComponentResult<Option<CodeArtifact>> {
+        let artifact = Self::code_generation_entries(ctx, component_id)
+            .await?
+            .remove(code_generation)
+            .and_then(|mut entry| entry.artifacts.remove(name));
+        match artifact {
+            Some(artifact) => Ok(Some(CodeArtifact {
+                name: name.to_owned(),
+                content: general_purpose::STANDARD.decode(&artifact.content_base64)?,
+                mime_type: artifact.mime_type,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the "/root/code" map for a given [`ComponentId`](Self), keyed by the name of each
+    /// entry.
+    async fn code_generation_entries(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<HashMap<String, CodeGenerationEntry>> {
         let component = Self::get_by_id(ctx, &component_id)
             .await?
             .ok_or(ComponentError::NotFound(component_id))?;
@@ -35,8 +353,7 @@ impl Component {
             .await?
             .ok_or(ComponentError::NoSchemaVariant(component_id))?;
 
-        // Prepare to assemble code views and access the "/root/code" prop tree.
-        let mut code_views: Vec<CodeView> = Vec::new();
+        // Access the "/root/code" prop tree.
         let code_map_implicit_internal_provider =
             SchemaVariant::find_root_child_implicit_internal_provider(
                 ctx,
@@ -55,43 +372,15 @@ impl Component {
                 .ok_or(AttributeValueError::NotFoundForReadContext(
                     code_map_attribute_read_context,
                 ))?;
-        let maybe_code_map_value = code_map_attribute_value.get_value(ctx).await?;
-
-        // If the map has been populated, we need to see if there are code views to generate.
-        if let Some(code_map_value) = maybe_code_map_value {
-            let code_map: HashMap<String, CodeGenerationEntry> =
-                serde_json::from_value(code_map_value)?;
-
-            for entry in code_map.values() {
-                // When a new code gen function is craeted the code/format entries will not yet be
-                // set, so just ignore them in the loop here. Function return value type checking
-                // should ensure that the executed function does not unset these itself.
-                if entry.format.is_none() || entry.code.is_none() {
-                    continue;
-                }
-
-                // Safe unwraps because of the above check
-                let format = entry.format.as_ref().unwrap();
-                let code = entry.code.as_ref().unwrap();
-
-                let language = if format.is_empty() {
-                    CodeLanguage::Unknown
-                } else {
-                    CodeLanguage::try_from(format.to_owned())?
-                };
-
-                // NOTE(nick): we may need to determine how we handle empty code generation or
-                // generation in progress. Maybe we never need to? Just re-run?
-                let code = if code.is_empty() {
-                    None
-                } else {
-                    Some(code.clone())
-                };
 
-                code_views.push(CodeView::new(language, code));
+        // The map is only populated once there are code views to generate.
+        match code_map_attribute_value.get_value(ctx).await? {
+            Some(mut code_map_value) => {
+                load_code_blobs(ctx, &mut code_map_value).await?;
+                Ok(serde_json::from_value(code_map_value)?)
             }
+            None => Ok(HashMap::new()),
         }
-        Ok(code_views)
     }
 
     // TODO(nick): big query potential.
@@ -135,6 +424,100 @@ impl Component {
     }
 }
 
+/// Returns the size of the code and artifact contents of the result of a code generation
+/// [`Func`](crate::Func), in bytes. Artifact contents are counted decoded, as they are stored.
+pub(crate) fn generated_size(code_generation: &serde_json::Value) -> usize {
+    let code = code_generation
+        .get("code")
+        .and_then(serde_json::Value::as_str)
+        .map_or(0, str::len);
+    let artifacts = code_generation
+        .get("artifacts")
+        .and_then(serde_json::Value::as_object)
+        .map_or(0, |artifacts| {
+            artifacts
+                .values()
+                .filter_map(|artifact| artifact.get("contentBase64")?.as_str())
+                .map(|content_base64| {
+                    general_purpose::STANDARD
+                        .decode(content_base64)
+                        .map_or(content_base64.len(), |content| content.len())
+                })
+                .sum()
+        });
+    code + artifacts
+}
+
+/// Moves the large code and artifact contents of the result of a code generation
+/// [`Func`](crate::Func) to the [blob store](Blob), replacing them with their blob references.
+/// Artifact contents are stored decoded.
+pub(crate) async fn store_code_blobs(
+    ctx: &DalContext,
+    code_generation: &mut serde_json::Value,
+) -> BlobResult<()> {
+    let Some(entry) = code_generation.as_object_mut() else {
+        return Ok(());
+    };
+
+    if let Some(serde_json::Value::String(code)) = entry.get_mut("code") {
+        if code.len() > CODE_BLOB_THRESHOLD_BYTES {
+            *code = Blob::store(ctx, code.as_bytes()).await?.to_reference();
+        }
+    }
+
+    if let Some(serde_json::Value::Object(artifacts)) = entry.get_mut("artifacts") {
+        for artifact in artifacts.values_mut() {
+            let Some(serde_json::Value::String(content_base64)) = artifact.get_mut("contentBase64")
+            else {
+                continue;
+            };
+            if content_base64.len() <= CODE_BLOB_THRESHOLD_BYTES {
+                continue;
+            }
+            // Content which is not valid base64 is left for readers to report.
+            if let Ok(content) = general_purpose::STANDARD.decode(content_base64.as_str()) {
+                *content_base64 = Blob::store(ctx, &content).await?.to_reference();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the blob references of a "/root/code" map, keyed by the name of each entry, with the
+/// code and artifact contents they refer to. See [`store_code_blobs()`].
+pub(crate) async fn load_code_blobs(
+    ctx: &DalContext,
+    code_map: &mut serde_json::Value,
+) -> BlobResult<()> {
+    let Some(entries) = code_map.as_object_mut() else {
+        return Ok(());
+    };
+
+    for entry in entries.values_mut() {
+        if let Some(serde_json::Value::String(code)) = entry.get_mut("code") {
+            if let Some(hash) = BlobHash::from_reference(code) {
+                *code = String::from_utf8_lossy(&Blob::load(ctx, hash).await?).into_owned();
+            }
+        }
+
+        if let Some(serde_json::Value::Object(artifacts)) = entry.get_mut("artifacts") {
+            for artifact in artifacts.values_mut() {
+                if let Some(serde_json::Value::String(content_base64)) =
+                    artifact.get_mut("contentBase64")
+                {
+                    if let Some(hash) = BlobHash::from_reference(content_base64) {
+                        *content_base64 =
+                            general_purpose::STANDARD.encode(Blob::load(ctx, hash).await?);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // NOTE(nick): consider moving this somewhere else.
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -155,3 +538,83 @@ impl WsEvent {
         .await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn code_generated(contents: Vec<(&str, Option<&str>, CodeGeneratedContent)>) -> CodeGenerated {
+        CodeGenerated {
+            contents: contents
+                .into_iter()
+                .map(|(code_generation, artifact, content)| {
+                    (
+                        CodeGeneratedKey {
+                            code_generation: code_generation.to_owned(),
+                            artifact: artifact.map(ToOwned::to_owned),
+                        },
+                        content,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diff_code_and_artifacts() {
+        let text = |text: &str| CodeGeneratedContent::Text(text.to_owned());
+        let prev = code_generated(vec![
+            ("awsEc2", None, text("{\n  \"a\": 1\n}")),
+            ("awsEc2", Some("userdata.sh"), text("echo hi")),
+            (
+                "awsEc2",
+                Some("logo.png"),
+                CodeGeneratedContent::Binary(vec![0xff]),
+            ),
+        ]);
+        let curr = code_generated(vec![
+            ("awsEc2", None, text("{\n  \"a\": 2\n}")),
+            ("awsEc2", Some("userdata.sh"), text("echo hi")),
+            (
+                "awsEc2",
+                Some("logo.png"),
+                CodeGeneratedContent::Binary(vec![0xfe]),
+            ),
+            ("docker", None, text("image: nginx")),
+        ]);
+
+        let diffs = prev.diff(&curr);
+        assert_eq!(3, diffs.len());
+
+        assert_eq!(None, diffs[0].key.artifact);
+        assert_eq!(CodeGeneratedDiffStatus::Modified, diffs[0].status);
+        assert_eq!(
+            Some(
+                [
+                    "--- a/awsEc2",
+                    "+++ b/awsEc2",
+                    "@@ -1,3 +1,3 @@",
+                    " {",
+                    "-  \"a\": 1",
+                    "+  \"a\": 2",
+                    " }",
+                ]
+                .join("\n")
+            ),
+            diffs[0].diff
+        );
+
+        assert_eq!(Some("logo.png".to_owned()), diffs[1].key.artifact);
+        assert_eq!(CodeGeneratedDiffStatus::Modified, diffs[1].status);
+        assert_eq!(None, diffs[1].diff);
+
+        assert_eq!("docker", diffs[2].key.code_generation);
+        assert_eq!(CodeGeneratedDiffStatus::Added, diffs[2].status);
+        assert_eq!(
+            Some("--- /dev/null\n+++ b/docker\n@@ -0,0 +1 @@\n+image: nginx".to_owned()),
+            diffs[2].diff
+        );
+
+        assert!(curr.diff(&curr).is_empty());
+    }
+}
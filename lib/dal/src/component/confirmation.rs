@@ -1,7 +1,6 @@
 //! This module contains operations related to working with the "/root/confirmation" subtree
 //! in relation to [`Components`](Component).
 
-use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use telemetry::prelude::*;
@@ -198,7 +197,7 @@ impl Component {
         let fixes = Fix::find_by_attr_null(ctx, "finished_at").await?;
         let mut running_fixes = Vec::new();
         for fix in fixes {
-            if Utc::now().signed_duration_since(fix.timestamp().created_at)
+            if ctx.now().signed_duration_since(fix.timestamp().created_at)
                 < chrono::Duration::minutes(5)
             {
                 running_fixes.push(fix);
@@ -3,7 +3,6 @@
 
 #![warn(missing_docs, clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
-use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use telemetry::prelude::*;
@@ -231,7 +230,7 @@ impl ConfirmationView {
 
                     // Refresh running fixes.
                     // FIXME(paulo,fletcher,nick,paul): hardcoding 5 minutes timeout to avoid permanently fix results
-                    if Utc::now().signed_duration_since(fix.timestamp().created_at)
+                    if ctx.now().signed_duration_since(fix.timestamp().created_at)
                         > chrono::Duration::minutes(5)
                     {
                         None
@@ -1,4 +1,7 @@
-//! This module contains [`ComponentDiff`].
+//! This module contains [`ComponentDiff`], along with the line diffing shared with the diffs of
+//! generated code (see [`CodeGenerated`](crate::component::code::CodeGenerated)).
+
+use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
@@ -44,7 +47,7 @@ impl ComponentDiff {
             return Err(ComponentError::InvalidContextForDiff);
         }
 
-        let curr_component_view = ComponentView::new(ctx, component_id).await?;
+        let curr_component_view = ComponentView::cached(ctx, component_id).await?;
         if curr_component_view.properties.is_null() {
             return Ok(Self {
                 component_id,
@@ -63,7 +66,7 @@ impl ComponentDiff {
             .await?
             .is_some()
         {
-            let prev_component_view = ComponentView::new(&head_ctx, component_id).await?;
+            let prev_component_view = ComponentView::cached(&head_ctx, component_id).await?;
             if prev_component_view.properties.is_null() {
                 return Ok(Self {
                     component_id,
@@ -77,15 +80,10 @@ impl ComponentDiff {
 
             let prev_json = serde_json::to_string_pretty(&prev_component_view)?;
 
-            let mut lines = Vec::new();
-            for diff_object in diff::lines(&prev_json, &curr_json) {
-                let line = match diff_object {
-                    diff::Result::Left(left) => format!("-{left}"),
-                    diff::Result::Both(unchanged, _) => format!(" {unchanged}"),
-                    diff::Result::Right(right) => format!("+{right}"),
-                };
-                lines.push(line);
-            }
+            let lines: Vec<String> = diff_lines(&prev_json, &curr_json)
+                .iter()
+                .map(DiffLine::to_string)
+                .collect();
 
             // FIXME(nick): generate multiple code views if there are multiple code views.
             let diff = CodeView::new(CodeLanguage::Diff, Some(lines.join(NEWLINE)));
@@ -101,3 +99,156 @@ impl ComponentDiff {
         })
     }
 }
+
+/// A line of a diff between two texts, as found by [`diff_lines()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Removed(&'a str),
+    Unchanged(&'a str),
+    Added(&'a str),
+}
+
+impl DiffLine<'_> {
+    fn is_change(&self) -> bool {
+        !matches!(self, Self::Unchanged(_))
+    }
+}
+
+impl fmt::Display for DiffLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Removed(line) => write!(f, "-{line}"),
+            Self::Unchanged(line) => write!(f, " {line}"),
+            Self::Added(line) => write!(f, "+{line}"),
+        }
+    }
+}
+
+/// Diffs two texts line by line, keeping every line of both.
+pub fn diff_lines<'a>(prev: &'a str, curr: &'a str) -> Vec<DiffLine<'a>> {
+    diff::lines(prev, curr)
+        .into_iter()
+        .map(|diff_object| match diff_object {
+            diff::Result::Left(left) => DiffLine::Removed(left),
+            diff::Result::Both(unchanged, _) => DiffLine::Unchanged(unchanged),
+            diff::Result::Right(right) => DiffLine::Added(right),
+        })
+        .collect()
+}
+
+/// Renders the unified diff between two texts, with `context` unchanged lines around each change.
+/// A missing text is rendered as `/dev/null`, as for created or deleted files. Returns `None` if
+/// the texts are the same.
+pub fn unified_diff(
+    prev_name: &str,
+    prev: Option<&str>,
+    curr_name: &str,
+    curr: Option<&str>,
+    context: usize,
+) -> Option<String> {
+    let lines = diff_lines(prev.unwrap_or_default(), curr.unwrap_or_default());
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.is_change())
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() && prev.is_some() == curr.is_some() {
+        return None;
+    }
+
+    // The number of lines of each text before every line of the diff.
+    let mut line_numbers = Vec::with_capacity(lines.len() + 1);
+    let (mut prev_line, mut curr_line) = (0, 0);
+    for line in &lines {
+        line_numbers.push((prev_line, curr_line));
+        match line {
+            DiffLine::Removed(_) => prev_line += 1,
+            DiffLine::Unchanged(_) => {
+                prev_line += 1;
+                curr_line += 1;
+            }
+            DiffLine::Added(_) => curr_line += 1,
+        }
+    }
+    line_numbers.push((prev_line, curr_line));
+
+    // Changes closer than twice the context share a hunk.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for index in changes {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut rendered = vec![
+        format!("--- {}", prev.map_or("/dev/null", |_| prev_name)),
+        format!("+++ {}", curr.map_or("/dev/null", |_| curr_name)),
+    ];
+    for (start, end) in hunks {
+        let (prev_start, curr_start) = line_numbers[start];
+        let (prev_end, curr_end) = line_numbers[end];
+        rendered.push(format!(
+            "@@ -{} +{} @@",
+            hunk_range(prev_start, prev_end - prev_start),
+            hunk_range(curr_start, curr_end - curr_start)
+        ));
+        rendered.extend(lines[start..end].iter().map(DiffLine::to_string));
+    }
+    Some(rendered.join(NEWLINE))
+}
+
+/// Renders the range of a hunk, whose lines start after the `before` first lines of the text.
+fn hunk_range(before: usize, count: usize) -> String {
+    // An empty range names the line it is after, rather than its first line.
+    let start = if count == 0 { before } else { before + 1 };
+    if count == 1 {
+        start.to_string()
+    } else {
+        format!("{start},{count}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unified_diff_hunks() {
+        let prev = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj";
+        let curr = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk";
+        assert_eq!(
+            Some(
+                [
+                    "--- a/code",
+                    "+++ b/code",
+                    "@@ -1,3 +1,3 @@",
+                    " a",
+                    "-b",
+                    "+B",
+                    " c",
+                    "@@ -10 +10,2 @@",
+                    " j",
+                    "+k",
+                ]
+                .join(NEWLINE)
+            ),
+            unified_diff("a/code", Some(prev), "b/code", Some(curr), 1)
+        );
+    }
+
+    #[test]
+    fn unified_diff_of_created_text() {
+        assert_eq!(
+            Some(["--- /dev/null", "+++ b/code", "@@ -0,0 +1,2 @@", "+a", "+b"].join(NEWLINE)),
+            unified_diff("a/code", None, "b/code", Some("a\nb"), 3)
+        );
+        assert_eq!(
+            None,
+            unified_diff("a/code", Some("a"), "b/code", Some("a"), 3)
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! This module contains [`ComponentLabel`], an arbitrary key/value pair set on a
+//! [`Component`](crate::Component) to organize large workspaces beyond frames, and
+//! [`LabelSelector`], which selects the [`Components`](crate::Component) by their labels.
+//!
+//! Selectors are made of requirements joined with `AND`:
+//!
+//! ```text
+//! label.env=prod AND label.team!=payments AND label.owner AND !label.deprecated
+//! ```
+//!
+//! A `!=` requirement also matches the [`Components`](crate::Component) without the label, which
+//! `!label.<key>` requires.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::ws_event::{WsEvent, WsEventError, WsPayload};
+use crate::{
+    impl_standard_model, pk, standard_model, standard_model_accessor, Component, ComponentError,
+    ComponentId, DalContext, HistoryEventError, StandardModel, StandardModelError, Tenancy,
+    Timestamp, TransactionsError, Visibility, WsEventResult,
+};
+
+const FIND_FOR_COMPONENT_AND_KEY: &str =
+    include_str!("../queries/component_label/find_for_component_and_key.sql");
+const LIST_FOR_COMPONENT: &str = include_str!("../queries/component_label/list_for_component.sql");
+
+/// The prefix of the label keys in a [`LabelSelector`].
+const SELECTOR_KEY_PREFIX: &str = "label.";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ComponentLabelError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("label selector cannot be empty")]
+    EmptySelector,
+    #[error("history event error: {0}")]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid label key {0:?}: use letters, digits, '-', '_', '.' and '/'")]
+    InvalidKey(String),
+    #[error("invalid label selector requirement {0:?}: expected label.<key>=<value>, label.<key>!=<value>, label.<key> or !label.<key>")]
+    InvalidSelectorRequirement(String),
+    #[error("invalid label value {0:?}: use letters, digits, '-', '_', '.' and '/'")]
+    InvalidValue(String),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type ComponentLabelResult<T> = Result<T, ComponentLabelError>;
+
+pk!(ComponentLabelPk);
+pk!(ComponentLabelId);
+
+impl_standard_model! {
+    model: ComponentLabel,
+    pk: ComponentLabelPk,
+    id: ComponentLabelId,
+    table_name: "component_labels",
+    history_event_label_base: "component_label",
+    history_event_message_name: "Component Label"
+}
+
+/// A key/value pair set on a [`Component`](crate::Component). A component has at most one value
+/// per key.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ComponentLabel {
+    pk: ComponentLabelPk,
+    id: ComponentLabelId,
+    component_id: ComponentId,
+    key: String,
+    value: String,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    visibility: Visibility,
+}
+
+impl ComponentLabel {
+    /// Sets the label `key` of the [`Component`](crate::Component) for `component_id` to `value`,
+    /// replacing its current value, if any.
+    #[instrument(skip(ctx))]
+    pub async fn set(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: impl AsRef<str> + fmt::Debug,
+        value: impl AsRef<str> + fmt::Debug,
+    ) -> ComponentLabelResult<Self> {
+        let (key, value) = (key.as_ref(), value.as_ref());
+        if !is_valid_label_part(key) {
+            return Err(ComponentLabelError::InvalidKey(key.to_string()));
+        }
+        if !is_valid_label_part(value) {
+            return Err(ComponentLabelError::InvalidValue(value.to_string()));
+        }
+        Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(ComponentLabelError::ComponentNotFound(component_id))?;
+
+        let label = match Self::find_for_component_and_key(ctx, component_id, key).await? {
+            Some(mut label) => {
+                if label.value != value {
+                    label.set_value(ctx, value).await?;
+                }
+                label
+            }
+            None => {
+                let row = ctx
+                    .txns()
+                    .await?
+                    .pg()
+                    .query_one(
+                        "SELECT object FROM component_label_create_v1($1, $2, $3, $4, $5)",
+                        &[ctx.tenancy(), ctx.visibility(), &component_id, &key, &value],
+                    )
+                    .await?;
+                standard_model::finish_create_from_row(ctx, row).await?
+            }
+        };
+
+        WsEvent::component_label_changed(ctx, component_id, key, Some(&label.value))
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(label)
+    }
+
+    standard_model_accessor!(value, String, ComponentLabelResult);
+
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Removes the label `key` from the [`Component`](crate::Component) for `component_id`.
+    /// Returns `false` if the component had no such label.
+    #[instrument(skip(ctx))]
+    pub async fn remove(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: impl AsRef<str> + fmt::Debug,
+    ) -> ComponentLabelResult<bool> {
+        let key = key.as_ref();
+        let Some(mut label) = Self::find_for_component_and_key(ctx, component_id, key).await?
+        else {
+            return Ok(false);
+        };
+        label.delete_by_id(ctx).await?;
+
+        WsEvent::component_label_changed(ctx, component_id, key, None)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Lists the labels of the [`Component`](crate::Component) for `component_id`, by key.
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentLabelResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_FOR_COMPONENT,
+                &[ctx.tenancy(), ctx.visibility(), &component_id],
+            )
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Lists the labels of every [`Component`](crate::Component), by component.
+    pub async fn list_by_component(
+        ctx: &DalContext,
+    ) -> ComponentLabelResult<HashMap<ComponentId, HashMap<String, String>>> {
+        let mut labels: HashMap<ComponentId, HashMap<String, String>> = HashMap::new();
+        for label in Self::list(ctx).await? {
+            labels
+                .entry(label.component_id)
+                .or_default()
+                .insert(label.key, label.value);
+        }
+        Ok(labels)
+    }
+
+    async fn find_for_component_and_key(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: &str,
+    ) -> ComponentLabelResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                FIND_FOR_COMPONENT_AND_KEY,
+                &[ctx.tenancy(), ctx.visibility(), &component_id, &key],
+            )
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+}
+
+fn is_valid_label_part(part: &str) -> bool {
+    !part.is_empty()
+        && part
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+/// A single requirement of a [`LabelSelector`].
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum LabelRequirement {
+    /// The label is not set.
+    Absent { key: String },
+    /// The label is set to the value.
+    Equals { key: String, value: String },
+    /// The label is set, to any value.
+    Exists { key: String },
+    /// The label is not set, or set to another value.
+    NotEquals { key: String, value: String },
+}
+
+impl LabelRequirement {
+    fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        match self {
+            Self::Absent { key } => !labels.contains_key(key),
+            Self::Equals { key, value } => labels.get(key) == Some(value),
+            Self::Exists { key } => labels.contains_key(key),
+            Self::NotEquals { key, value } => labels.get(key) != Some(value),
+        }
+    }
+}
+
+impl FromStr for LabelRequirement {
+    type Err = ComponentLabelError;
+
+    fn from_str(requirement: &str) -> Result<Self, Self::Err> {
+        let invalid = || ComponentLabelError::InvalidSelectorRequirement(requirement.to_string());
+        let key = |key: &str| -> ComponentLabelResult<String> {
+            let key = key
+                .trim()
+                .strip_prefix(SELECTOR_KEY_PREFIX)
+                .ok_or_else(invalid)?;
+            if !is_valid_label_part(key) {
+                return Err(ComponentLabelError::InvalidKey(key.to_string()));
+            }
+            Ok(key.to_string())
+        };
+        let value = |value: &str| -> ComponentLabelResult<String> {
+            let value = value.trim();
+            if !is_valid_label_part(value) {
+                return Err(ComponentLabelError::InvalidValue(value.to_string()));
+            }
+            Ok(value.to_string())
+        };
+
+        if let Some((k, v)) = requirement.split_once("!=") {
+            Ok(Self::NotEquals {
+                key: key(k)?,
+                value: value(v)?,
+            })
+        } else if let Some((k, v)) = requirement.split_once('=') {
+            Ok(Self::Equals {
+                key: key(k)?,
+                value: value(v)?,
+            })
+        } else if let Some(k) = requirement.trim().strip_prefix('!') {
+            Ok(Self::Absent { key: key(k)? })
+        } else {
+            Ok(Self::Exists {
+                key: key(requirement)?,
+            })
+        }
+    }
+}
+
+impl fmt::Display for LabelRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Absent { key } => write!(f, "!{SELECTOR_KEY_PREFIX}{key}"),
+            Self::Equals { key, value } => write!(f, "{SELECTOR_KEY_PREFIX}{key}={value}"),
+            Self::Exists { key } => write!(f, "{SELECTOR_KEY_PREFIX}{key}"),
+            Self::NotEquals { key, value } => write!(f, "{SELECTOR_KEY_PREFIX}{key}!={value}"),
+        }
+    }
+}
+
+/// Selects the [`Components`](crate::Component) whose labels meet every requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelSelector {
+    requirements: Vec<LabelRequirement>,
+}
+
+impl LabelSelector {
+    pub fn requirements(&self) -> &[LabelRequirement] {
+        &self.requirements
+    }
+
+    /// Whether a [`Component`](crate::Component) with the `labels` is selected.
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        self.requirements
+            .iter()
+            .all(|requirement| requirement.matches(labels))
+    }
+
+    /// Lists the [`Components`](crate::Component) selected in the current visibility.
+    #[instrument(skip(ctx))]
+    pub async fn select(&self, ctx: &DalContext) -> ComponentLabelResult<Vec<ComponentId>> {
+        let labels = ComponentLabel::list_by_component(ctx).await?;
+        let no_labels = HashMap::new();
+
+        let mut selected = Vec::new();
+        for component in Component::list(ctx).await? {
+            if self.matches(labels.get(component.id()).unwrap_or(&no_labels)) {
+                selected.push(*component.id());
+            }
+        }
+        Ok(selected)
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = ComponentLabelError;
+
+    /// Parses requirements joined with `AND` (in any case). Whitespace around the operators is
+    /// ignored, so that `label.env = prod` is the same as `label.env=prod`.
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        let mut requirements = Vec::new();
+        let mut requirement = String::new();
+        for token in selector.split_whitespace() {
+            if token.eq_ignore_ascii_case("and") {
+                requirements.push(requirement.parse()?);
+                requirement.clear();
+            } else {
+                if !requirement.is_empty() {
+                    requirement.push(' ');
+                }
+                requirement.push_str(token);
+            }
+        }
+        if !requirement.is_empty() {
+            requirements.push(requirement.parse()?);
+        } else if !requirements.is_empty() {
+            // A trailing "AND"
+            return Err(ComponentLabelError::InvalidSelectorRequirement(
+                selector.to_string(),
+            ));
+        }
+
+        if requirements.is_empty() {
+            return Err(ComponentLabelError::EmptySelector);
+        }
+        Ok(Self { requirements })
+    }
+}
+
+impl fmt::Display for LabelSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, requirement) in self.requirements.iter().enumerate() {
+            if index > 0 {
+                write!(f, " AND ")?;
+            }
+            write!(f, "{requirement}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The payload of the [`WsEvent`](crate::WsEvent) sent when a label of a
+/// [`Component`](crate::Component) is set or removed.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentLabelChangedPayload {
+    component_id: ComponentId,
+    key: String,
+    /// The new value of the label, or [`None`] if it was removed.
+    value: Option<String>,
+}
+
+impl WsEvent {
+    pub async fn component_label_changed(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: &str,
+        value: Option<&str>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ComponentLabelChanged(ComponentLabelChangedPayload {
+                component_id,
+                key: key.to_string(),
+                value: value.map(ToString::to_string),
+            }),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_selectors() {
+        let selector: LabelSelector = "label.env = prod and label.team!=payments AND !label.old"
+            .parse()
+            .expect("could not parse selector");
+        assert_eq!(
+            &[
+                LabelRequirement::Equals {
+                    key: "env".to_string(),
+                    value: "prod".to_string()
+                },
+                LabelRequirement::NotEquals {
+                    key: "team".to_string(),
+                    value: "payments".to_string()
+                },
+                LabelRequirement::Absent {
+                    key: "old".to_string()
+                },
+            ],
+            selector.requirements()
+        );
+        assert_eq!(
+            "label.env=prod AND label.team!=payments AND !label.old",
+            selector.to_string()
+        );
+
+        assert!(matches!(
+            "".parse::<LabelSelector>(),
+            Err(ComponentLabelError::EmptySelector)
+        ));
+        assert!(matches!(
+            "label.env=prod AND".parse::<LabelSelector>(),
+            Err(ComponentLabelError::InvalidSelectorRequirement(_))
+        ));
+        assert!(matches!(
+            "env=prod".parse::<LabelSelector>(),
+            Err(ComponentLabelError::InvalidSelectorRequirement(_))
+        ));
+        assert!(matches!(
+            "label.env=pr od".parse::<LabelSelector>(),
+            Err(ComponentLabelError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn matches_labels() {
+        let selector: LabelSelector = "label.env=prod AND label.team!=payments AND label.owner"
+            .parse()
+            .expect("could not parse selector");
+
+        assert!(selector.matches(&labels(&[("env", "prod"), ("owner", "ops")])));
+        assert!(selector.matches(&labels(&[
+            ("env", "prod"),
+            ("owner", "ops"),
+            ("team", "search")
+        ])));
+        assert!(!selector.matches(&labels(&[
+            ("env", "prod"),
+            ("owner", "ops"),
+            ("team", "payments")
+        ])));
+        assert!(!selector.matches(&labels(&[("env", "prod")])));
+        assert!(!selector.matches(&labels(&[])));
+    }
+}
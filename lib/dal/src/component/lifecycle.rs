@@ -0,0 +1,413 @@
+//! This module contains [`ComponentLifecycle`], the lifecycle state of a
+//! [`Component`](crate::Component). The state is moved on by a state machine, fed with the results
+//! of the qualifications of the [`Component`](crate::Component), the [`Fixes`](crate::Fix) running
+//! its actions and the health of its resource:
+//!
+//! - "Draft" and "Qualified" follow the qualifications, until the component is applied
+//! - a create action moves "Draft", "Qualified", "Failed" and "Destroyed" to "Applying", then
+//!   to "Provisioned" or "Failed"
+//! - a delete action moves "Provisioned", "Degraded" and "Failed" to "Destroying", then to
+//!   "Destroyed", or back to "Degraded" if it failed
+//! - an unhealthy resource moves "Provisioned" to "Degraded", and a healthy one moves it back
+//!
+//! Lifecycles are tracked per [`Workspace`](crate::Workspace), regardless of the
+//! [`Visibility`](crate::Visibility), since they follow the resource of the
+//! [`Component`](crate::Component) rather than its model.
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::component::resource::ResourceHealth;
+use crate::qualification::{QualificationSubCheckStatus, QualificationView};
+use crate::ws_event::{WsEvent, WsEventError, WsPayload};
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, ActionKind, ComponentId, DalContext,
+    StandardModelError, Timestamp, TransactionsError, WorkspacePk, WsEventResult,
+};
+
+const FIND_FOR_COMPONENT: &str =
+    include_str!("../queries/component_lifecycle/find_for_component.sql");
+const LIST: &str = include_str!("../queries/component_lifecycle/list.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ComponentLifecycleError {
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type ComponentLifecycleResult<T> = Result<T, ComponentLifecycleError>;
+
+pk!(ComponentLifecyclePk);
+
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+pub enum ComponentLifecycleState {
+    /// The action creating the resource is running.
+    Applying,
+    /// The resource exists, but its health is a warning or an error, or deleting it failed.
+    Degraded,
+    /// The resource was deleted.
+    Destroyed,
+    /// The action deleting the resource is running.
+    Destroying,
+    /// The component has not passed its qualifications yet.
+    #[default]
+    Draft,
+    /// The action creating the resource failed.
+    Failed,
+    /// The resource exists and is healthy.
+    Provisioned,
+    /// The component passed its qualifications and can be applied.
+    Qualified,
+}
+
+/// Something that happened to a [`Component`](crate::Component) which may move its lifecycle on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComponentLifecycleEvent {
+    QualificationsFailed,
+    QualificationsPassed,
+    ApplyStarted(ActionKind),
+    ApplySucceeded(ActionKind),
+    ApplyFailed(ActionKind),
+    ResourceHealthChanged(ResourceHealth),
+}
+
+impl ComponentLifecycleEvent {
+    /// Summarizes the results of the qualifications of a [`Component`](crate::Component).
+    /// Warnings do not fail a component. Returns [`None`] while a qualification has no result
+    /// yet.
+    pub fn from_qualifications(qualifications: &[QualificationView]) -> Option<Self> {
+        let mut passed = true;
+        for qualification in qualifications {
+            match qualification.result.as_ref().map(|result| result.status) {
+                Some(QualificationSubCheckStatus::Failure) => passed = false,
+                Some(
+                    QualificationSubCheckStatus::Success | QualificationSubCheckStatus::Warning,
+                ) => {}
+                Some(QualificationSubCheckStatus::Unknown) | None => return None,
+            }
+        }
+        Some(if passed {
+            Self::QualificationsPassed
+        } else {
+            Self::QualificationsFailed
+        })
+    }
+}
+
+impl ComponentLifecycleState {
+    /// Returns the state the `event` moves this state to, or [`None`] if the event does not
+    /// apply to this state.
+    pub fn transition(self, event: ComponentLifecycleEvent) -> Option<Self> {
+        use ComponentLifecycleEvent as Event;
+        use ComponentLifecycleState as State;
+
+        let next = match (self, event) {
+            (State::Draft, Event::QualificationsPassed) => State::Qualified,
+            (State::Qualified, Event::QualificationsFailed) => State::Draft,
+
+            (
+                State::Draft | State::Qualified | State::Failed | State::Destroyed,
+                Event::ApplyStarted(ActionKind::Create),
+            ) => State::Applying,
+            (State::Applying, Event::ApplySucceeded(ActionKind::Create)) => State::Provisioned,
+            (State::Applying, Event::ApplyFailed(ActionKind::Create)) => State::Failed,
+
+            (
+                State::Provisioned | State::Degraded | State::Failed,
+                Event::ApplyStarted(ActionKind::Delete),
+            ) => State::Destroying,
+            (State::Destroying, Event::ApplySucceeded(ActionKind::Delete)) => State::Destroyed,
+            (State::Destroying, Event::ApplyFailed(ActionKind::Delete)) => State::Degraded,
+
+            (
+                State::Provisioned,
+                Event::ResourceHealthChanged(ResourceHealth::Warning | ResourceHealth::Error),
+            ) => State::Degraded,
+            (State::Degraded, Event::ResourceHealthChanged(ResourceHealth::Ok)) => {
+                State::Provisioned
+            }
+
+            _ => return None,
+        };
+        Some(next)
+    }
+}
+
+/// The lifecycle state of a [`Component`](crate::Component) which left the "Draft" state at
+/// least once.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ComponentLifecycle {
+    pk: ComponentLifecyclePk,
+    workspace_pk: WorkspacePk,
+    component_id: ComponentId,
+    state: ComponentLifecycleState,
+    previous_state: ComponentLifecycleState,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl ComponentLifecycle {
+    pub fn pk(&self) -> ComponentLifecyclePk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(component_id, ComponentId);
+    standard_model_accessor_ro!(state, ComponentLifecycleState);
+    standard_model_accessor_ro!(previous_state, ComponentLifecycleState);
+
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    /// Returns the lifecycle state of the [`Component`](crate::Component) for `component_id`.
+    #[instrument(skip(ctx))]
+    pub async fn state_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentLifecycleResult<ComponentLifecycleState> {
+        Ok(Self::find_for_component(ctx, component_id)
+            .await?
+            .map(|lifecycle| lifecycle.state)
+            .unwrap_or_default())
+    }
+
+    /// Lists the lifecycles of the workspace. [`Components`](crate::Component) without one are
+    /// in the "Draft" state.
+    #[instrument(skip_all)]
+    pub async fn list(ctx: &DalContext) -> ComponentLifecycleResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST, &[&workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Moves the lifecycle of the [`Component`](crate::Component) for `component_id` on with
+    /// `event`. Returns the new lifecycle, or [`None`] if the event does not apply to the current
+    /// state.
+    #[instrument(skip(ctx))]
+    pub async fn record(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        event: ComponentLifecycleEvent,
+    ) -> ComponentLifecycleResult<Option<Self>> {
+        // Components are qualified and fixed outside of workspaces when building the builtins
+        let Some(workspace_pk) = ctx.tenancy().workspace_pk() else {
+            return Ok(None);
+        };
+        let previous_state = Self::find_for_component(ctx, component_id)
+            .await?
+            .map(|lifecycle| lifecycle.state)
+            .unwrap_or_default();
+        let Some(state) = previous_state.transition(event) else {
+            return Ok(None);
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM component_lifecycle_upsert_v1($1, $2, $3, $4)",
+                &[
+                    &workspace_pk,
+                    &component_id,
+                    &state.as_ref(),
+                    &previous_state.as_ref(),
+                ],
+            )
+            .await?;
+        let lifecycle: Self = standard_model::object_from_row(row)?;
+
+        WsEvent::component_lifecycle_changed(ctx, &lifecycle)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        Ok(Some(lifecycle))
+    }
+
+    /// Finds the lifecycle of the [`Component`](crate::Component) for `component_id`, locking
+    /// it so that concurrent events move it on one after the other.
+    async fn find_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentLifecycleResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(FIND_FOR_COMPONENT, &[&workspace_pk(ctx)?, &component_id])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> ComponentLifecycleResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(ComponentLifecycleError::NoWorkspaceInTenancy)
+}
+
+/// The payload of the [`WsEvent`](crate::WsEvent) sent when the lifecycle of a
+/// [`Component`](crate::Component) moves on.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentLifecycleChangedPayload {
+    component_id: ComponentId,
+    state: ComponentLifecycleState,
+    previous_state: ComponentLifecycleState,
+}
+
+impl From<&ComponentLifecycle> for ComponentLifecycleChangedPayload {
+    fn from(lifecycle: &ComponentLifecycle) -> Self {
+        Self {
+            component_id: lifecycle.component_id,
+            state: lifecycle.state,
+            previous_state: lifecycle.previous_state,
+        }
+    }
+}
+
+impl WsEvent {
+    pub async fn component_lifecycle_changed(
+        ctx: &DalContext,
+        lifecycle: &ComponentLifecycle,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ComponentLifecycleChanged(lifecycle.into())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ComponentLifecycleEvent as Event;
+    use ComponentLifecycleState as State;
+
+    fn run(events: &[Event]) -> State {
+        events.iter().fold(State::default(), |state, event| {
+            state.transition(*event).unwrap_or(state)
+        })
+    }
+
+    #[test]
+    fn provisions_qualified_components() {
+        assert_eq!(
+            State::Provisioned,
+            run(&[
+                Event::QualificationsPassed,
+                Event::ApplyStarted(ActionKind::Create),
+                Event::ApplySucceeded(ActionKind::Create),
+            ])
+        );
+        assert_eq!(
+            State::Failed,
+            run(&[
+                Event::QualificationsPassed,
+                Event::ApplyStarted(ActionKind::Create),
+                Event::ApplyFailed(ActionKind::Create),
+            ])
+        );
+        assert_eq!(
+            State::Draft,
+            run(&[Event::QualificationsPassed, Event::QualificationsFailed])
+        );
+    }
+
+    #[test]
+    fn follows_resource_health_and_deletion() {
+        let provisioned = [
+            Event::ApplyStarted(ActionKind::Create),
+            Event::ApplySucceeded(ActionKind::Create),
+        ];
+        let degraded = [
+            &provisioned[..],
+            &[Event::ResourceHealthChanged(ResourceHealth::Error)],
+        ]
+        .concat();
+
+        assert_eq!(State::Degraded, run(&degraded));
+        assert_eq!(
+            State::Provisioned,
+            run(&[
+                &degraded[..],
+                &[Event::ResourceHealthChanged(ResourceHealth::Ok)],
+            ]
+            .concat())
+        );
+        assert_eq!(
+            State::Destroyed,
+            run(&[
+                &degraded[..],
+                &[
+                    Event::ApplyStarted(ActionKind::Delete),
+                    Event::ApplySucceeded(ActionKind::Delete),
+                ],
+            ]
+            .concat())
+        );
+        assert_eq!(
+            State::Degraded,
+            run(&[
+                &provisioned[..],
+                &[
+                    Event::ApplyStarted(ActionKind::Delete),
+                    Event::ApplyFailed(ActionKind::Delete),
+                ],
+            ]
+            .concat())
+        );
+    }
+
+    #[test]
+    fn ignores_events_which_do_not_apply() {
+        assert_eq!(
+            None,
+            State::Draft.transition(Event::ApplyStarted(ActionKind::Delete))
+        );
+        assert_eq!(
+            None,
+            State::Provisioned.transition(Event::QualificationsFailed)
+        );
+        assert_eq!(
+            None,
+            State::Applying.transition(Event::ResourceHealthChanged(ResourceHealth::Error))
+        );
+        assert_eq!(
+            None,
+            State::Provisioned.transition(Event::ApplyStarted(ActionKind::Refresh))
+        );
+    }
+}
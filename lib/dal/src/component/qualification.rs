@@ -4,6 +4,7 @@ use telemetry::prelude::*;
 
 use crate::attribute::value::AttributeValue;
 use crate::attribute::value::AttributeValueError;
+use crate::component::lifecycle::{ComponentLifecycle, ComponentLifecycleEvent};
 use crate::component::ComponentResult;
 use crate::qualification::{
     QualificationResult, QualificationSubCheck, QualificationSubCheckStatus, QualificationView,
@@ -141,6 +142,23 @@ impl Component {
         Ok(results)
     }
 
+    /// Moves the [`lifecycle`](ComponentLifecycle) of the [`Component`] on with the results of
+    /// its qualifications, once every qualification has a result.
+    #[instrument(skip(ctx))]
+    pub async fn record_qualifications_lifecycle(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Option<ComponentLifecycle>> {
+        if Self::get_by_id(ctx, &component_id).await?.is_none() {
+            return Ok(None);
+        }
+        let qualifications = Self::list_qualifications(ctx, component_id).await?;
+        match ComponentLifecycleEvent::from_qualifications(&qualifications) {
+            Some(event) => Ok(ComponentLifecycle::record(ctx, component_id, event).await?),
+            None => Ok(None),
+        }
+    }
+
     /// An ephemeral qualification (not present in the
     /// [`prop tree`](crate::schema::variant::leaves)) that qualifies if all validations passed.
     #[instrument(skip_all)]
@@ -201,6 +219,7 @@ impl Component {
                 sub_checks,
             }),
             qualification_name: name.to_string(),
+            last_checked: None,
         })
     }
 }
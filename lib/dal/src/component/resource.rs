@@ -3,11 +3,13 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use strum::{AsRefStr, Display, EnumString};
 use veritech_client::ResourceStatus;
 
 use crate::attribute::context::AttributeContextBuilder;
 use crate::attribute::value::AttributeValue;
 use crate::attribute::value::AttributeValueError;
+use crate::component::lifecycle::{ComponentLifecycle, ComponentLifecycleEvent};
 use crate::component::ComponentResult;
 use crate::func::binding_return_value::FuncBindingReturnValue;
 use crate::ws_event::WsEvent;
@@ -18,6 +20,56 @@ use crate::{
 };
 use crate::{RootPropChild, WsEventResult};
 
+/// The normalized health of a real world resource, computed from the result of the last
+/// [`ActionKind::Refresh`] (or other action) run for a [`Component`].
+///
+/// Variants are ordered from least to most severe, which is what [`Self::rollup`] relies upon.
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ResourceHealth {
+    /// There is no resource, or it has never been synced.
+    #[default]
+    Unknown,
+    Ok,
+    Warning,
+    Error,
+}
+
+impl ResourceHealth {
+    /// Computes the health of a resource from the status returned by its action function.
+    pub fn from_status(status: ResourceStatus, payload: Option<&Value>) -> Self {
+        match status {
+            ResourceStatus::Ok if payload.map_or(true, Value::is_null) => Self::Unknown,
+            ResourceStatus::Ok => Self::Ok,
+            ResourceStatus::Warning => Self::Warning,
+            ResourceStatus::Error => Self::Error,
+        }
+    }
+
+    /// Combines the health of many resources into the most severe one. An empty iterator, or one
+    /// made up of only [`Unknown`](Self::Unknown) resources, rolls up to
+    /// [`Unknown`](Self::Unknown).
+    pub fn rollup(healths: impl IntoIterator<Item = Self>) -> Self {
+        healths.into_iter().max().unwrap_or_default()
+    }
+}
+
 impl Component {
     /// Calls [`Self::resource_by_id`] using the [`ComponentId`](Component) off [`Component`].
     pub async fn resource(&self, ctx: &DalContext) -> ComponentResult<ActionRunResult> {
@@ -31,7 +83,7 @@ impl Component {
     ) -> ComponentResult<ActionRunResult> {
         let attribute_value = Self::resource_attribute_value_by_id(ctx, component_id).await?;
 
-        let func_binding_return_value =
+        let mut func_binding_return_value =
             FuncBindingReturnValue::get_by_id(ctx, &attribute_value.func_binding_return_value_id())
                 .await?
                 .ok_or_else(|| {
@@ -39,6 +91,7 @@ impl Component {
                         attribute_value.func_binding_return_value_id(),
                     )
                 })?;
+        func_binding_return_value.decrypt(ctx).await?;
 
         let value = func_binding_return_value
             .value()
@@ -59,6 +112,25 @@ impl Component {
         Ok(result)
     }
 
+    /// Returns the [`ResourceHealth`] of the [`Component`]'s own resource.
+    pub async fn resource_health(&self, ctx: &DalContext) -> ComponentResult<ResourceHealth> {
+        Ok(self.resource(ctx).await?.health)
+    }
+
+    /// Returns the [`ResourceHealth`] of a [`Component`] rolled up with every [`Component`]
+    /// configured into it, descending through nested frames. For a regular component this is
+    /// the health of its own resource.
+    pub async fn resource_health_rollup(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<ResourceHealth> {
+        let mut healths = vec![Self::resource_by_id(ctx, component_id).await?.health];
+        for descendant_id in Self::list_frame_descendants(ctx, component_id).await? {
+            healths.push(Self::resource_by_id(ctx, descendant_id).await?.health);
+        }
+        Ok(ResourceHealth::rollup(healths))
+    }
+
     pub async fn resource_attribute_value_by_id(
         ctx: &DalContext,
         component_id: ComponentId,
@@ -88,6 +160,9 @@ impl Component {
     /// Sets the "string" field, "/root/resource" with a given value. After that, ensure dependent
     /// [`AttributeValues`](crate::AttributeValue) are updated.
     ///
+    /// If the [`ResourceHealth`] changes, a [`WsEvent`] is published for the [`Component`] and
+    /// for every frame it is configured into.
+    ///
     /// Returns "true" if the resource tree has been updated. Returns "false" if the cached
     /// value is used.
     pub async fn set_resource(
@@ -102,6 +177,9 @@ impl Component {
             return Err(ComponentError::CannotUpdateResourceTreeInChangeSet);
         }
 
+        let previous_health = self.resource_health(ctx).await?;
+        let health = result.health;
+
         let resource_attribute_value = Component::root_prop_child_attribute_value_for_component(
             ctx,
             self.id,
@@ -144,6 +222,27 @@ impl Component {
             )
             .await?;
         }
+
+        if health != previous_health {
+            WsEvent::resource_health_changed(ctx, self.id, health)
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+            ComponentLifecycle::record(
+                ctx,
+                self.id,
+                ComponentLifecycleEvent::ResourceHealthChanged(health),
+            )
+            .await?;
+            for frame_id in Self::list_enclosing_frames(ctx, self.id).await? {
+                let frame_health = Self::resource_health_rollup(ctx, frame_id).await?;
+                WsEvent::resource_health_changed(ctx, frame_id, frame_health)
+                    .await?
+                    .publish_on_commit(ctx)
+                    .await?;
+            }
+        }
+
         Ok(true)
     }
 
@@ -177,6 +276,7 @@ impl Component {
 #[serde(rename_all = "camelCase")]
 pub struct ResourceView {
     pub status: ResourceStatus,
+    pub health: ResourceHealth,
     pub message: Option<String>,
     pub data: Option<Value>,
     pub logs: Vec<String>,
@@ -189,6 +289,7 @@ impl ResourceView {
             data: result.payload,
             message: result.message,
             status: result.status,
+            health: result.health,
             logs: result.logs,
             last_synced: result.last_synced,
         }
@@ -219,6 +320,13 @@ pub struct ResourceRefreshedPayload {
     component_id: ComponentId,
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceHealthChangedPayload {
+    component_id: ComponentId,
+    health: ResourceHealth,
+}
+
 impl WsEvent {
     pub async fn resource_refreshed(
         ctx: &DalContext,
@@ -230,4 +338,19 @@ impl WsEvent {
         )
         .await
     }
+
+    pub async fn resource_health_changed(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        health: ResourceHealth,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ResourceHealthChanged(ResourceHealthChangedPayload {
+                component_id,
+                health,
+            }),
+        )
+        .await
+    }
 }
@@ -0,0 +1,320 @@
+//! This module contains [`ResourceConflictPolicy`], which decides what happens to the model of a
+//! [`Component`] when a refresh finds that its resource was changed out-of-band.
+//!
+//! The model and the resource are compared through the [`Props`](crate::Prop) underneath
+//! "/root/resource_value" which refer to a [`Prop`](crate::Prop) underneath "/root/domain", using
+//! the diff [`Func`](crate::Func) of each of them.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+
+use crate::attribute::value::AttributeValueError;
+use crate::component::{AttributeUpdate, ComponentResult};
+use crate::func::backend::js_reconciliation::{ReconciliationDiff, ReconciliationDiffDomain};
+use crate::{
+    AttributeReadContext, AttributeValue, AttributeView, ChangeSet, ChangeSetPk, Component,
+    ComponentError, ComponentId, DalContext, ExternalProviderId, FuncBinding, InternalProviderId,
+    Prop, PropError, PropId, StandardModel, Visibility, WsEvent, WsEventResult, WsPayload,
+};
+
+/// What to do with the model of a [`Component`] whose resource no longer matches it.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ResourceConflictPolicy {
+    /// Opens a change set holding the values of the resource, to be reviewed and applied (or
+    /// abandoned) like any other change set.
+    Manual,
+    /// Keeps the model as it is and flags the drift, so that the next apply brings the resource
+    /// back in line with the model.
+    #[default]
+    PreferModel,
+    /// Overwrites the model with the values of the resource.
+    PreferResource,
+}
+
+/// A field of the resource which no longer matches the model.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDrift {
+    /// The path of the [`Prop`](crate::Prop), such as "/root/domain/region".
+    pub json_pointer: String,
+    pub model: Value,
+    pub resource: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct DiffValue {
+    diff: bool,
+    new_value: Option<Value>,
+}
+
+/// What was done with a [`Component`] after comparing it with its resource.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ResourceConflictResolution {
+    /// The drift was written to a change set, for review.
+    ChangeSetOpened {
+        change_set_pk: ChangeSetPk,
+        drift: Vec<ResourceDrift>,
+    },
+    /// The drift was reported, leaving the model untouched.
+    Flagged { drift: Vec<ResourceDrift> },
+    /// The model and the resource match.
+    InSync,
+    /// The model was overwritten with the values of the resource.
+    Overwritten { drift: Vec<ResourceDrift> },
+}
+
+impl Component {
+    /// Compares the domain of the [`Component`] with its resource, returning the diff of each
+    /// [`Prop`](crate::Prop) underneath "/root/resource_value" whose value no longer matches the
+    /// [`Prop`](crate::Prop) it refers to, keyed by the path of the resource value
+    /// [`Prop`](crate::Prop).
+    ///
+    /// [`Props`](crate::Prop) without a diff [`Func`](crate::Func) cannot be compared, and are
+    /// skipped.
+    #[instrument(skip_all)]
+    pub async fn resource_domain_diff(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<HashMap<String, ReconciliationDiff>> {
+        let schema_variant_id = Self::schema_variant_id(ctx, component_id).await?;
+        let props = Prop::find_by_attr(ctx, "schema_variant_id", &schema_variant_id).await?;
+
+        let view_context = AttributeReadContext {
+            prop_id: None,
+            internal_provider_id: Some(InternalProviderId::NONE),
+            external_provider_id: Some(ExternalProviderId::NONE),
+            component_id: Some(component_id),
+        };
+
+        let mut diff = HashMap::new();
+        for prop in props {
+            let (domain_prop_id, resource_prop_id) = match prop.refers_to_prop_id() {
+                None => continue,
+                Some(prop_id) => (*prop_id, *prop.id()),
+            };
+            let diff_func_id = match prop.diff_func_id() {
+                Some(func_id) => *func_id,
+                None => {
+                    warn!(
+                        "Prop {} does not have diff functions set, therefore can't be diffed with prop {domain_prop_id:?}",
+                        prop.path().as_str()
+                    );
+                    continue;
+                }
+            };
+
+            let resource_prop_av =
+                Self::prop_attribute_value(ctx, component_id, resource_prop_id).await?;
+            let resource_prop_view =
+                AttributeView::new(ctx, view_context, Some(*resource_prop_av.id())).await?;
+            let domain_prop_av =
+                Self::prop_attribute_value(ctx, component_id, domain_prop_id).await?;
+            let domain_prop_view =
+                AttributeView::new(ctx, view_context, Some(*domain_prop_av.id())).await?;
+
+            let (_, func_binding_return_value) = FuncBinding::create_and_execute(
+                ctx,
+                serde_json::json!({
+                    "first": domain_prop_view.value(),
+                    "second": resource_prop_view.value(),
+                }),
+                diff_func_id,
+            )
+            .await?;
+            let diff_value =
+                DiffValue::deserialize(func_binding_return_value.value().unwrap_or(&Value::Null))?;
+
+            // TODO: Should we treat unset as equal or not?
+            if diff_value.diff {
+                diff.insert(
+                    prop.path().with_replaced_sep("/"),
+                    ReconciliationDiff {
+                        normalized_resource: diff_value.new_value,
+                        resource: resource_prop_view.value().clone(),
+                        domain: ReconciliationDiffDomain {
+                            id: *domain_prop_av.id(),
+                            value: domain_prop_view.value().clone(),
+                        },
+                    },
+                );
+            }
+        }
+        Ok(diff)
+    }
+
+    async fn prop_attribute_value(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        prop_id: PropId,
+    ) -> ComponentResult<AttributeValue> {
+        let context = AttributeReadContext {
+            prop_id: Some(prop_id),
+            internal_provider_id: Some(InternalProviderId::NONE),
+            external_provider_id: Some(ExternalProviderId::NONE),
+            component_id: Some(component_id),
+        };
+        AttributeValue::find_for_context(ctx, context)
+            .await?
+            .ok_or(ComponentError::AttributeValueNotFoundForContext(context))
+    }
+
+    /// Returns the [`Props`](crate::Prop) of the domain whose value no longer matches the
+    /// resource, along with the value of the resource, normalized by the diff
+    /// [`Func`](crate::Func) when it provides one.
+    #[instrument(skip_all)]
+    pub async fn resource_drift(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<ResourceDrift>> {
+        if Self::resource_by_id(ctx, component_id)
+            .await?
+            .payload
+            .is_none()
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut drift = Vec::new();
+        for diff in Self::resource_domain_diff(ctx, component_id)
+            .await?
+            .into_values()
+        {
+            let attribute_value = AttributeValue::get_by_id(ctx, &diff.domain.id)
+                .await?
+                .ok_or(AttributeValueError::NotFound(
+                    diff.domain.id,
+                    *ctx.visibility(),
+                ))?;
+            let prop_id = attribute_value.context.prop_id();
+            let prop = Prop::get_by_id(ctx, &prop_id)
+                .await?
+                .ok_or(PropError::NotFound(prop_id, *ctx.visibility()))?;
+            drift.push(ResourceDrift {
+                json_pointer: prop.json_pointer(ctx).await?,
+                model: diff.domain.value,
+                resource: diff.normalized_resource.unwrap_or(diff.resource),
+            });
+        }
+        drift.sort_by(|a, b| a.json_pointer.cmp(&b.json_pointer));
+        Ok(drift)
+    }
+
+    /// Applies the [`ResourceConflictPolicy`] of the [`Component`] to the drift between its model
+    /// and its resource. Meant to be called on head, right after the resource has been refreshed.
+    #[instrument(skip_all)]
+    pub async fn resolve_resource_conflicts(
+        &self,
+        ctx: &DalContext,
+    ) -> ComponentResult<ResourceConflictResolution> {
+        let ctx = &ctx.clone_without_deleted_visibility();
+
+        let drift = Self::resource_drift(ctx, self.id).await?;
+        if drift.is_empty() {
+            return Ok(ResourceConflictResolution::InSync);
+        }
+
+        WsEvent::resource_drifted(ctx, self.id, drift.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        let updates: Vec<AttributeUpdate> = drift
+            .iter()
+            .map(|drift| AttributeUpdate::new(&drift.json_pointer, Some(drift.resource.clone())))
+            .collect();
+
+        match self.resource_conflict_policy {
+            ResourceConflictPolicy::PreferModel => {
+                Ok(ResourceConflictResolution::Flagged { drift })
+            }
+            ResourceConflictPolicy::PreferResource => {
+                Self::update_attributes_bulk(ctx, self.id, updates).await?;
+                Ok(ResourceConflictResolution::Overwritten { drift })
+            }
+            ResourceConflictPolicy::Manual => {
+                // Refreshes keep finding the same drift until it is resolved, so an open change
+                // set from an earlier refresh is reused rather than opening a new one each time
+                let name = format!("Resource changes: {}", self.name(ctx).await?);
+                let change_set = match ChangeSet::list_open_objects(ctx)
+                    .await
+                    .map_err(Box::new)?
+                    .into_iter()
+                    .find(|change_set| change_set.name == name)
+                {
+                    Some(change_set) => change_set,
+                    None => ChangeSet::new(ctx, &name, None).await.map_err(Box::new)?,
+                };
+
+                let change_set_ctx =
+                    ctx.clone_with_new_visibility(Visibility::new_change_set(change_set.pk, false));
+                Self::update_attributes_bulk(&change_set_ctx, self.id, updates).await?;
+                WsEvent::change_set_written(&change_set_ctx)
+                    .await?
+                    .publish_on_commit(&change_set_ctx)
+                    .await?;
+
+                Ok(ResourceConflictResolution::ChangeSetOpened {
+                    change_set_pk: change_set.pk,
+                    drift,
+                })
+            }
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDriftedPayload {
+    component_id: ComponentId,
+    drift: Vec<ResourceDrift>,
+}
+
+impl ResourceDriftedPayload {
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    pub fn drift(&self) -> &[ResourceDrift] {
+        &self.drift
+    }
+}
+
+impl WsEvent {
+    pub async fn resource_drifted(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        drift: Vec<ResourceDrift>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ResourceDrifted(ResourceDriftedPayload {
+                component_id,
+                drift,
+            }),
+        )
+        .await
+    }
+}
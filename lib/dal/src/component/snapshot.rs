@@ -0,0 +1,281 @@
+//! This module contains [`ComponentSnapshot`], a point-in-time copy of the attribute values of a
+//! [`Component`], from which the component can be restored with
+//! [`Component::restore_from_snapshot`].
+//!
+//! A snapshot keeps the whole attribute value tree of the component, to browse it, and the values
+//! set by hand on its domain, which are the ones a restore sets again: every other value is
+//! computed from them. Snapshots belong to the workspace, so a component can be restored in any
+//! change set, from a snapshot taken in another one.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::component::{AttributeUpdate, ComponentView, ComponentViewError};
+use crate::schema::variant::SchemaVariantId;
+use crate::{
+    pk, standard_model, AttributeValueId, AuditAction, AuditLog, AuditLogError, AuditTarget,
+    ChangeSetPk, Component, ComponentError, ComponentId, ComponentType, DalContext, StandardModel,
+    StandardModelError, TransactionsError, UserPk, WorkspacePk,
+};
+
+const GET_BY_PK: &str = include_str!("../queries/component_snapshot/get_by_pk.sql");
+const LIST_FOR_COMPONENT: &str =
+    include_str!("../queries/component_snapshot/list_for_component.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ComponentSnapshotError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("component view error: {0}")]
+    ComponentView(#[from] ComponentViewError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("component snapshot not found: {0}")]
+    NotFound(ComponentSnapshotPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("component {0} changed from schema variant {1} to {2} since the snapshot")]
+    SchemaVariantChanged(ComponentId, SchemaVariantId, SchemaVariantId),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ComponentSnapshotResult<T> = Result<T, ComponentSnapshotError>;
+
+pk!(ComponentSnapshotPk);
+
+/// Why a [`ComponentSnapshot`] was taken.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+pub enum ComponentSnapshotTrigger {
+    /// Taken right before the component was restored from another snapshot, so that the restore
+    /// can be undone.
+    BeforeRestore,
+    /// Taken on demand.
+    Manual,
+}
+
+/// The attribute values of a [`Component`] at a point in time.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ComponentSnapshot {
+    pk: ComponentSnapshotPk,
+    created_at: DateTime<Utc>,
+    workspace_pk: WorkspacePk,
+    change_set_pk: ChangeSetPk,
+    component_id: ComponentId,
+    schema_variant_id: SchemaVariantId,
+    trigger: ComponentSnapshotTrigger,
+    actor_user_pk: Option<UserPk>,
+    name: String,
+    component_type: ComponentType,
+    properties: Value,
+    values: Vec<AttributeUpdate>,
+}
+
+impl ComponentSnapshot {
+    /// Takes a snapshot of the [`Component`] as seen in the change set of the [`DalContext`].
+    #[instrument(skip(ctx))]
+    pub async fn capture(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        trigger: ComponentSnapshotTrigger,
+    ) -> ComponentSnapshotResult<Self> {
+        let component = Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(ComponentSnapshotError::ComponentNotFound(component_id))?;
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        let properties = ComponentView::new(ctx, component_id).await?.properties;
+        let values = Component::list_values_set_by_hand(ctx, component_id).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM component_snapshot_create_v1($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                &[
+                    &workspace_pk(ctx)?,
+                    &ctx.visibility().change_set_pk,
+                    &component_id,
+                    &schema_variant_id,
+                    &trigger.as_ref(),
+                    &ctx.history_actor().user_pk(),
+                    &component.name(ctx).await?,
+                    &component.get_type(ctx).await?.as_ref(),
+                    &properties,
+                    &serde_json::to_value(&values)?,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    pub async fn get_by_pk(
+        ctx: &DalContext,
+        pk: ComponentSnapshotPk,
+    ) -> ComponentSnapshotResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[&pk, &workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the snapshots of the [`Component`], from newest to oldest.
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentSnapshotResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_COMPONENT, &[&workspace_pk(ctx)?, &component_id])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    pub fn pk(&self) -> ComponentSnapshotPk {
+        self.pk
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// The change set the [`Component`] was seen in, which is [`ChangeSetPk::NONE`] for head.
+    pub fn change_set_pk(&self) -> ChangeSetPk {
+        self.change_set_pk
+    }
+
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    pub fn schema_variant_id(&self) -> SchemaVariantId {
+        self.schema_variant_id
+    }
+
+    pub fn trigger(&self) -> ComponentSnapshotTrigger {
+        self.trigger
+    }
+
+    pub fn actor_user_pk(&self) -> Option<UserPk> {
+        self.actor_user_pk
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn component_type(&self) -> ComponentType {
+        self.component_type
+    }
+
+    /// The whole attribute value tree of the [`Component`], as found in its
+    /// [`ComponentView`].
+    pub fn properties(&self) -> &Value {
+        &self.properties
+    }
+
+    /// The values set by hand on the domain of the [`Component`].
+    pub fn values(&self) -> &[AttributeUpdate] {
+        &self.values
+    }
+}
+
+impl Component {
+    /// Sets the name, type and domain of the [`Component`] of the snapshot back to what they were
+    /// when it was taken, in the change set of the [`DalContext`], and returns the updated
+    /// [`AttributeValueIds`](crate::AttributeValue).
+    ///
+    /// The values set by hand since the snapshot are unset, and the current state of the
+    /// component is snapshotted first, so that the restore can be undone by restoring that
+    /// snapshot in turn.
+    #[instrument(skip(ctx))]
+    pub async fn restore_from_snapshot(
+        ctx: &DalContext,
+        snapshot_pk: ComponentSnapshotPk,
+    ) -> ComponentSnapshotResult<Vec<AttributeValueId>> {
+        let snapshot = ComponentSnapshot::get_by_pk(ctx, snapshot_pk)
+            .await?
+            .ok_or(ComponentSnapshotError::NotFound(snapshot_pk))?;
+        let component_id = snapshot.component_id;
+        let component = Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(ComponentSnapshotError::ComponentNotFound(component_id))?;
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        if schema_variant_id != snapshot.schema_variant_id {
+            return Err(ComponentSnapshotError::SchemaVariantChanged(
+                component_id,
+                snapshot.schema_variant_id,
+                schema_variant_id,
+            ));
+        }
+
+        let before =
+            ComponentSnapshot::capture(ctx, component_id, ComponentSnapshotTrigger::BeforeRestore)
+                .await?;
+
+        if before.name != snapshot.name {
+            component.set_name(ctx, Some(&snapshot.name)).await?;
+        }
+        if before.component_type != snapshot.component_type {
+            component.set_type(ctx, snapshot.component_type).await?;
+        }
+
+        let mut updates: Vec<AttributeUpdate> = before
+            .values
+            .iter()
+            .filter(|current| {
+                !snapshot
+                    .values
+                    .iter()
+                    .any(|value| value.json_pointer == current.json_pointer)
+            })
+            .map(|current| AttributeUpdate::new(current.json_pointer.clone(), None))
+            .collect();
+        updates.extend(snapshot.values.iter().cloned());
+        let attribute_value_ids =
+            Component::update_attributes_bulk(ctx, component_id, updates).await?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::ComponentRestoreSnapshot,
+            Some(AuditTarget::new(
+                "component",
+                component_id,
+                Some(snapshot.name.clone()),
+            )),
+            Some(serde_json::json!({ "snapshotPk": before.pk })),
+            Some(serde_json::json!({ "snapshotPk": snapshot.pk })),
+        )
+        .await?;
+
+        Ok(attribute_value_ids)
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> ComponentSnapshotResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(ComponentSnapshotError::NoWorkspaceInTenancy)
+}
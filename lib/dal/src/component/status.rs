@@ -138,9 +138,6 @@ impl ComponentStatus {
     }
 
     fn user_pk(history_actor: &HistoryActor) -> Option<UserPk> {
-        match history_actor {
-            HistoryActor::User(user_pk) => Some(*user_pk),
-            _ => None,
-        }
+        history_actor.user_pk()
     }
 }
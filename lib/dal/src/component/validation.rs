@@ -53,7 +53,10 @@ impl Component {
                 )
                 .await?
                 {
-                    Some(func_binding_return_value) => func_binding_return_value.value().cloned(),
+                    Some(mut func_binding_return_value) => {
+                        func_binding_return_value.decrypt(ctx).await?;
+                        func_binding_return_value.value().cloned()
+                    }
                     None => None,
                 };
 
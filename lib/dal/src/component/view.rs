@@ -1,19 +1,28 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use si_data_pg::PgError;
 use std::str::FromStr;
 use thiserror::Error;
 
 use crate::{
-    component::ComponentKind, func::binding_return_value::FuncBindingReturnValueId,
-    AttributeReadContext, AttributeValue, AttributeValueError, Component, ComponentId, DalContext,
-    EncryptedSecret, FuncBindingReturnValue, InternalProvider, InternalProviderError, PropError,
-    PropId, SchemaVariantId, SecretError, SecretId, StandardModel, StandardModelError,
+    blob::BlobError,
+    component::{code, ComponentKind},
+    func::binding_return_value::{
+        FuncBindingReturnValue, FuncBindingReturnValueError, FuncBindingReturnValueId,
+    },
+    AttributeReadContext, AttributeValueError, ComponentId, DalContext, EncryptedSecret,
+    InternalProviderError, InternalProviderId, PropError, PropId, SchemaVariantId, SecretError,
+    SecretId, StandardModel, StandardModelError, TransactionsError,
 };
 
+pub mod cache;
 pub mod properties;
 
+pub use cache::ComponentViewCache;
 pub use properties::ComponentViewProperties;
 
+const VIEW_FOR_COMPONENT: &str = include_str!("../queries/component/view_for_component.sql");
+
 type ComponentViewResult<T> = Result<T, ComponentViewError>;
 
 #[remain::sorted]
@@ -21,8 +30,14 @@ type ComponentViewResult<T> = Result<T, ComponentViewError>;
 pub enum ComponentViewError {
     #[error(transparent)]
     AttributeValue(#[from] AttributeValueError),
+    #[error(transparent)]
+    Blob(#[from] BlobError),
     #[error("component error: {0}")]
     Component(String),
+    #[error("invalid component kind: {0}")]
+    ComponentKind(#[from] strum::ParseError),
+    #[error(transparent)]
+    FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
     #[error("func binding return value not found {0}")]
     FuncBindingReturnValueNotFound(FuncBindingReturnValueId),
     #[error(transparent)]
@@ -40,6 +55,8 @@ pub enum ComponentViewError {
     #[error("component not found {0}")]
     NotFound(ComponentId),
     #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
     Prop(#[from] PropError),
     #[error(transparent)]
     Secret(#[from] SecretError),
@@ -50,6 +67,8 @@ pub enum ComponentViewError {
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
 }
 
@@ -69,55 +88,90 @@ impl Default for ComponentView {
 }
 
 impl ComponentView {
+    /// Builds the view of a [`Component`](crate::Component) from the value of its root
+    /// [`Prop`](crate::Prop), which holds the whole tree of its values, in a single round trip to
+    /// the database.
     pub async fn new(
         ctx: &DalContext,
         component_id: ComponentId,
     ) -> ComponentViewResult<ComponentView> {
         let deleted_ctx = &ctx.clone_with_delete_visibility();
-        let component = Component::get_by_id(deleted_ctx, &component_id)
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                VIEW_FOR_COMPONENT,
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    deleted_ctx.visibility(),
+                    &component_id,
+                ],
+            )
             .await?
             .ok_or(ComponentViewError::NotFound(component_id))?;
-        let schema_variant = component
-            .schema_variant(ctx)
-            .await
-            .map_err(|e| ComponentViewError::Component(e.to_string()))?
-            .ok_or_else(|| ComponentViewError::NoSchemaVariant(*component.id()))?;
 
-        let root_prop_id = schema_variant
-            .root_prop_id()
-            .ok_or(ComponentViewError::NoRootProp(*schema_variant.id()))?;
-        let implicit_provider = InternalProvider::find_for_prop(ctx, *root_prop_id)
-            .await?
-            .ok_or_else(|| ComponentViewError::NoInternalProvider(*root_prop_id))?;
+        let schema_variant_id: SchemaVariantId = row
+            .try_get::<_, Option<SchemaVariantId>>("schema_variant_id")?
+            .ok_or(ComponentViewError::NoSchemaVariant(component_id))?;
+        let root_prop_id: PropId = row
+            .try_get::<_, Option<PropId>>("root_prop_id")?
+            .ok_or(ComponentViewError::NoRootProp(schema_variant_id))?;
+        let internal_provider_id: InternalProviderId = row
+            .try_get::<_, Option<InternalProviderId>>("internal_provider_id")?
+            .ok_or(ComponentViewError::NoInternalProvider(root_prop_id))?;
 
         let value_context = AttributeReadContext {
-            internal_provider_id: Some(*implicit_provider.id()),
+            internal_provider_id: Some(internal_provider_id),
             component_id: Some(component_id),
             ..AttributeReadContext::default()
         };
-
-        let attribute_value = AttributeValue::find_for_context(ctx, value_context)
-            .await?
+        let func_binding_return_value_id: FuncBindingReturnValueId = row
+            .try_get::<_, Option<FuncBindingReturnValueId>>("func_binding_return_value_id")?
             .ok_or(ComponentViewError::NoAttributeValue(value_context))?;
+        if !row.try_get::<_, bool>("func_binding_return_value_found")? {
+            return Err(ComponentViewError::FuncBindingReturnValueNotFound(
+                func_binding_return_value_id,
+            ));
+        }
 
-        let properties_func_binding_return_value =
-            FuncBindingReturnValue::get_by_id(ctx, &attribute_value.func_binding_return_value_id())
-                .await?
-                .ok_or_else(|| {
-                    ComponentViewError::FuncBindingReturnValueNotFound(
-                        attribute_value.func_binding_return_value_id(),
-                    )
-                })?;
-        let properties = properties_func_binding_return_value
-            .value()
-            .unwrap_or(&Value::Null);
+        let kind: String = row.try_get("kind")?;
+        let properties: Option<Value> = if row.try_get("sensitive")? {
+            let mut func_binding_return_value =
+                FuncBindingReturnValue::get_by_id(ctx, &func_binding_return_value_id)
+                    .await?
+                    .ok_or(ComponentViewError::FuncBindingReturnValueNotFound(
+                        func_binding_return_value_id,
+                    ))?;
+            func_binding_return_value.decrypt(ctx).await?;
+            func_binding_return_value.value().cloned()
+        } else {
+            row.try_get("properties")?
+        };
+        let mut properties = properties.unwrap_or(Value::Null);
+        // Functions get the generated code and artifacts themselves, not their blob references.
+        if let Some(code_map) = properties.get_mut("code") {
+            code::load_code_blobs(ctx, code_map).await?;
+        }
 
         Ok(ComponentView {
-            kind: *component.kind(),
-            properties: properties.clone(),
+            kind: ComponentKind::from_str(&kind)?,
+            properties,
         })
     }
 
+    /// Returns the view of the [`Component`](crate::Component) from the
+    /// [`ComponentViewCache`], building it only if it changed since it was last built.
+    pub async fn cached(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentViewResult<ComponentView> {
+        ctx.component_view_cache()
+            .get_or_build(ctx, component_id)
+            .await
+    }
+
     pub async fn reencrypt_secrets(
         ctx: &DalContext,
         component: &mut veritech_client::ComponentView,
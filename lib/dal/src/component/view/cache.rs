@@ -0,0 +1,156 @@
+//! This module provides [`ComponentViewCache`], which keeps the [`ComponentViews`](ComponentView)
+//! built for functions, so that the ones of components which did not change since are not built
+//! again on every function execution.
+//!
+//! An entry is only used while the versions of the rows of the
+//! [`AttributeValues`](crate::AttributeValue) of its component, as seen by the [`DalContext`], are
+//! the same as when the entry was built. Postgres creates a new version of a row on every write,
+//! committed or not and in whichever order transactions commit, so a write made anywhere is never
+//! missed. Writes to attribute values also invalidate the entries of their component, which keeps
+//! the cache from holding on to views which can no longer be used.
+
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::Mutex;
+
+use crate::component::view::ComponentViewResult;
+use crate::{ChangeSetPk, ComponentId, ComponentView, DalContext, WorkspacePk};
+
+const VIEW_STAMP: &str = include_str!("../../queries/component/view_stamp.sql");
+
+/// How many views are kept before the cache starts over.
+const MAX_ENTRIES: usize = 4096;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct ComponentViewCacheKey {
+    component_id: ComponentId,
+    workspace_pk: Option<WorkspacePk>,
+    change_set_pk: ChangeSetPk,
+    deleted: bool,
+}
+
+impl ComponentViewCacheKey {
+    fn new(ctx: &DalContext, component_id: ComponentId) -> Self {
+        Self {
+            component_id,
+            workspace_pk: ctx.tenancy().workspace_pk(),
+            change_set_pk: ctx.visibility().change_set_pk,
+            deleted: ctx.visibility().deleted_at.is_some(),
+        }
+    }
+}
+
+/// A fingerprint of the versions of the rows of the [`AttributeValues`](crate::AttributeValue) of
+/// a component, which tells whether a cached view is still up to date.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ComponentViewStamp(Option<String>);
+
+impl ComponentViewStamp {
+    async fn for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentViewResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                VIEW_STAMP,
+                &[ctx.tenancy(), ctx.visibility(), &component_id],
+            )
+            .await?;
+
+        Ok(Self(row.try_get("stamp")?))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CachedComponentView {
+    stamp: ComponentViewStamp,
+    view: ComponentView,
+}
+
+/// A cache of [`ComponentViews`](ComponentView), shared by every [`DalContext`] built from the same
+/// [`ServicesContext`](crate::ServicesContext).
+#[derive(Clone, Debug)]
+pub struct ComponentViewCache {
+    enabled: bool,
+    entries: Arc<Mutex<HashMap<ComponentViewCacheKey, CachedComponentView>>>,
+}
+
+impl ComponentViewCache {
+    /// Creates an empty cache. A disabled cache builds views every time and never keeps them.
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            entries: Default::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the view of the component as seen by the [`DalContext`], building it only if it is
+    /// not cached or if the component was written to since it was.
+    pub async fn get_or_build(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentViewResult<ComponentView> {
+        if !self.enabled {
+            return ComponentView::new(ctx, component_id).await;
+        }
+
+        let key = ComponentViewCacheKey::new(ctx, component_id);
+        let stamp = ComponentViewStamp::for_component(ctx, component_id).await?;
+        if let Some(cached) = self.entries.lock().await.get(&key) {
+            if cached.stamp == stamp {
+                return Ok(cached.view.clone());
+            }
+        }
+
+        let view = ComponentView::new(ctx, component_id).await?;
+
+        let mut entries = self.entries.lock().await;
+        if entries.len() >= MAX_ENTRIES {
+            entries.clear();
+        }
+        entries.insert(
+            key,
+            CachedComponentView {
+                stamp,
+                view: view.clone(),
+            },
+        );
+
+        Ok(view)
+    }
+
+    /// Forgets the views of the component, in every change set.
+    pub async fn invalidate(&self, component_id: ComponentId) {
+        if !self.enabled {
+            return;
+        }
+
+        self.entries
+            .lock()
+            .await
+            .retain(|key, _| key.component_id != component_id);
+    }
+
+    /// Returns how many views are cached.
+    pub async fn len(&self) -> usize {
+        self.entries.lock().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.lock().await.is_empty()
+    }
+}
+
+impl Default for ComponentViewCache {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
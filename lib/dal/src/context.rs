@@ -1,6 +1,8 @@
-use std::{mem, path::PathBuf, sync::Arc};
+use std::{mem, path::PathBuf, sync::Arc, time::Duration};
 
+use chrono::{DateTime, Utc};
 use futures::Future;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use si_data_nats::{NatsClient, NatsError, NatsTxn};
 use si_data_pg::{InstrumentedClient, PgError, PgPool, PgPoolError, PgPoolResult, PgTxn};
@@ -14,9 +16,25 @@ use crate::{
         processor::{JobQueueProcessor, JobQueueProcessorError},
         producer::{BlockingJobError, BlockingJobResult, JobProducer},
     },
-    HistoryActor, StandardModel, Tenancy, TenancyError, Visibility,
+    Clock, ComponentViewCache, FeatureFlagCache, FeatureFlagResult, HistoryActor, RandomSource,
+    StandardModel, SystemClock, Tenancy, TenancyError, Visibility, WorkspaceSettings,
+    WorkspaceSettingsCache, WorkspaceSettingsResult,
 };
 
+/// How many times [`DalContext::run_with_retries()`] runs its closure before giving up on a
+/// transaction conflict.
+pub const TRANSACTION_CONFLICT_MAX_ATTEMPTS: u32 = 5;
+
+const TRANSACTION_CONFLICT_BASE_BACKOFF: Duration = Duration::from_millis(25);
+
+/// Doubles the wait after each failed attempt, with some jitter so that the conflicting
+/// transactions don't retry in lockstep.
+fn transaction_conflict_backoff(attempt: u32) -> Duration {
+    let backoff = TRANSACTION_CONFLICT_BASE_BACKOFF * 2u32.pow(attempt - 1);
+    let jitter = rand::thread_rng().gen_range(0..=backoff.as_millis() as u64 / 2);
+    backoff + Duration::from_millis(jitter)
+}
+
 /// A context type which contains handles to common core service dependencies.
 ///
 /// These services are typically used by most DAL objects, such as a database connection pool, a
@@ -37,6 +55,16 @@ pub struct ServicesContext {
     pkgs_path: Option<PathBuf>,
     /// The URL of the module index
     module_index_url: Option<String>,
+    /// The [`ComponentViews`](crate::ComponentView) built for functions.
+    component_view_cache: ComponentViewCache,
+    /// The feature flag toggles looked up by the services.
+    feature_flag_cache: FeatureFlagCache,
+    /// The workspace settings looked up by the services.
+    workspace_settings_cache: WorkspaceSettingsCache,
+    /// The source of the current time for expiry and staleness checks.
+    clock: Arc<dyn Clock>,
+    /// The source of the random parts of generated names.
+    random_source: RandomSource,
 }
 
 impl ServicesContext {
@@ -58,14 +86,69 @@ impl ServicesContext {
             encryption_key,
             pkgs_path,
             module_index_url,
+            component_view_cache: ComponentViewCache::default(),
+            feature_flag_cache: FeatureFlagCache::default(),
+            workspace_settings_cache: WorkspaceSettingsCache::default(),
+            clock: Arc::new(SystemClock),
+            random_source: RandomSource::default(),
         }
     }
 
+    /// Replaces the [`ComponentViewCache`] with an empty one, enabled or not.
+    pub fn set_component_view_cache_enabled(&mut self, enabled: bool) {
+        self.component_view_cache = ComponentViewCache::new(enabled);
+    }
+
+    /// Gets a reference to the [`ComponentViewCache`].
+    pub fn component_view_cache(&self) -> &ComponentViewCache {
+        &self.component_view_cache
+    }
+
+    /// Replaces the [`FeatureFlagCache`] with an empty one, whose flags fall back to the given
+    /// defaults when they were never toggled.
+    pub fn set_feature_flag_defaults(
+        &mut self,
+        defaults: impl IntoIterator<Item = (String, bool)>,
+    ) {
+        self.feature_flag_cache = FeatureFlagCache::new(defaults);
+    }
+
+    /// Gets a reference to the [`FeatureFlagCache`].
+    pub fn feature_flag_cache(&self) -> &FeatureFlagCache {
+        &self.feature_flag_cache
+    }
+
+    /// Gets a reference to the [`WorkspaceSettingsCache`].
+    pub fn workspace_settings_cache(&self) -> &WorkspaceSettingsCache {
+        &self.workspace_settings_cache
+    }
+
+    /// Replaces the [`Clock`], which is the [`SystemClock`] by default.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Gets a reference to the [`Clock`].
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Replaces the [`RandomSource`], which draws from entropy by default.
+    pub fn set_random_source(&mut self, random_source: RandomSource) {
+        self.random_source = random_source;
+    }
+
+    /// Gets a reference to the [`RandomSource`].
+    pub fn random_source(&self) -> &RandomSource {
+        &self.random_source
+    }
+
     /// Consumes and returns [`DalContextBuilder`].
     pub fn into_builder(self, blocking: bool) -> DalContextBuilder {
         DalContextBuilder {
             services_context: self,
             blocking,
+            intent: ConnectionIntent::default(),
         }
     }
 
@@ -95,13 +178,34 @@ impl ServicesContext {
 
     /// Builds and returns a new [`Connections`].
     pub async fn connections(&self) -> PgPoolResult<Connections> {
-        let pg_conn = self.pg_pool.get().await?;
+        self.connections_for(ConnectionIntent::ReadWrite).await
+    }
+
+    /// Builds and returns a new [`Connections`], whose PostgreSQL connection may come from a read
+    /// replica if the intent is [`ConnectionIntent::ReadOnly`].
+    pub async fn connections_for(&self, intent: ConnectionIntent) -> PgPoolResult<Connections> {
+        let pg_conn = match intent {
+            ConnectionIntent::ReadOnly => self.pg_pool.get_read().await?,
+            ConnectionIntent::ReadWrite => self.pg_pool.get().await?,
+        };
         let nats_conn = self.nats_conn.clone();
         let job_processor = self.job_processor.clone();
         Ok(Connections::new(pg_conn, nats_conn, job_processor))
     }
 }
 
+/// Whether the [`DalContexts`](DalContext) built by a [`DalContextBuilder`] write to the database.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConnectionIntent {
+    /// Only reads, which can be served by a read replica. Replicas lag behind the primary, so
+    /// recent writes may not be visible yet, and any write fails.
+    ReadOnly,
+    /// Reads and writes, served by the primary.
+    #[default]
+    ReadWrite,
+}
+
 #[remain::sorted]
 #[derive(Debug)]
 enum ConnectionState {
@@ -202,6 +306,10 @@ pub struct DalContext {
     /// This is useful to ensure child jobs of blocking jobs also block so there is no race-condition in the DAL.
     /// And also for SDF routes to block the HTTP request until the jobs get executed, so SDF tests don't race.
     blocking: bool,
+    /// An identifier chosen by the client for the request being served, echoed in the
+    /// [`WsEvents`](crate::WsEvent) it causes so that the client can reconcile its optimistic
+    /// updates.
+    client_request_id: Option<String>,
 }
 
 impl DalContext {
@@ -211,6 +319,7 @@ impl DalContext {
         DalContextBuilder {
             services_context,
             blocking,
+            intent: ConnectionIntent::default(),
         }
     }
 
@@ -256,6 +365,86 @@ impl DalContext {
         Ok(())
     }
 
+    /// Marks the current state of the inner transactions, so that the changes made afterwards can
+    /// be discarded with [`Self::rollback_to_savepoint()`] while keeping the ones made before.
+    pub async fn savepoint(&self) -> Result<Savepoint, TransactionsError> {
+        self.txns().await?.savepoint().await
+    }
+
+    /// Discards the changes made since the [`Savepoint`] was taken, including the WsEvents
+    /// published and the jobs enqueued since then.
+    pub async fn rollback_to_savepoint(
+        &self,
+        savepoint: Savepoint,
+    ) -> Result<(), TransactionsError> {
+        self.txns().await?.rollback_to_savepoint(savepoint).await
+    }
+
+    /// Keeps the changes made since the [`Savepoint`] was taken, forgetting about the savepoint.
+    pub async fn release_savepoint(&self, savepoint: Savepoint) -> Result<(), TransactionsError> {
+        self.txns().await?.release_savepoint(savepoint).await
+    }
+
+    /// Runs `fun` within a [`Savepoint`], keeping its changes if it succeeds and discarding only
+    /// them if it fails, so that the caller can retry or skip a sub-step without losing the rest
+    /// of its transaction.
+    pub async fn run_in_savepoint<F, Fut, T, E>(&self, fun: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<TransactionsError>,
+    {
+        let savepoint = self.savepoint().await?;
+        match fun().await {
+            Ok(value) => {
+                self.release_savepoint(savepoint).await?;
+                Ok(value)
+            }
+            Err(err) => {
+                self.rollback_to_savepoint(savepoint).await?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs `fun` with a clone of this context, rolling the transactions back and running it again
+    /// when it fails because of a serialization failure or a deadlock, waiting a little longer
+    /// each time, up to [`TRANSACTION_CONFLICT_MAX_ATTEMPTS`] attempts.
+    ///
+    /// As a retry starts over from fresh transactions, `fun` must do all the work of the request,
+    /// leaving the commit to the caller. Anything done with the context before calling this is
+    /// rolled back along with the failed attempt.
+    pub async fn run_with_retries<F, Fut, T, E>(&self, mut fun: F) -> Result<T, E>
+    where
+        F: FnMut(DalContext) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<TransactionsError> + std::error::Error + 'static,
+    {
+        let job_queue_len = self.job_processor().queue_len().await;
+        let mut attempt = 1;
+        loop {
+            match fun(self.clone()).await {
+                Err(err)
+                    if attempt < TRANSACTION_CONFLICT_MAX_ATTEMPTS
+                        && si_data_pg::is_transaction_conflict(&err) =>
+                {
+                    let backoff = transaction_conflict_backoff(attempt);
+                    warn!(
+                        error = ?err,
+                        attempt,
+                        ?backoff,
+                        "transaction conflict, retrying"
+                    );
+                    self.rollback().await?;
+                    self.job_processor().truncate_queue(job_queue_len).await;
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Updates this context with a new [`HistoryActor`].
     pub fn update_history_actor(&mut self, history_actor: HistoryActor) {
         self.history_actor = history_actor;
@@ -268,6 +457,11 @@ impl DalContext {
         new
     }
 
+    /// Updates this context with the identifier the client chose for the request being served.
+    pub fn set_client_request_id(&mut self, client_request_id: Option<String>) {
+        self.client_request_id = client_request_id;
+    }
+
     /// Updates this context with a new [`Visibility`].
     pub fn update_access_builder(&mut self, access_builder: AccessBuilder) {
         self.tenancy = access_builder.tenancy;
@@ -413,6 +607,47 @@ impl DalContext {
         self.services_context.job_processor.clone()
     }
 
+    /// Gets a reference to the [`ComponentViewCache`] shared by the contexts of the services.
+    pub fn component_view_cache(&self) -> &ComponentViewCache {
+        &self.services_context.component_view_cache
+    }
+
+    /// Gets a reference to the [`FeatureFlagCache`] shared by the contexts of the services.
+    pub fn feature_flag_cache(&self) -> &FeatureFlagCache {
+        &self.services_context.feature_flag_cache
+    }
+
+    /// Gets a reference to the [`WorkspaceSettingsCache`] shared by the contexts of the services.
+    pub fn workspace_settings_cache(&self) -> &WorkspaceSettingsCache {
+        &self.services_context.workspace_settings_cache
+    }
+
+    /// Gets a reference to the [`Clock`] shared by the contexts of the services.
+    pub fn clock(&self) -> &dyn Clock {
+        self.services_context.clock.as_ref()
+    }
+
+    /// Returns the current time, as told by the [`Clock`] of the services.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.services_context.clock.now()
+    }
+
+    /// Gets a reference to the [`RandomSource`] shared by the contexts of the services.
+    pub fn random_source(&self) -> &RandomSource {
+        &self.services_context.random_source
+    }
+
+    /// Returns whether the [`FeatureFlag`](crate::FeatureFlag) is enabled for the workspace of the
+    /// context.
+    pub async fn feature_enabled(&self, name: &str) -> FeatureFlagResult<bool> {
+        self.feature_flag_cache().is_enabled(self, name).await
+    }
+
+    /// Returns the [`WorkspaceSettings`] of the workspace of the context.
+    pub async fn workspace_settings(&self) -> WorkspaceSettingsResult<WorkspaceSettings> {
+        self.workspace_settings_cache().get(self).await
+    }
+
     /// Gets a reference to the DAL context's Postgres pool.
     pub fn pg_pool(&self) -> &PgPool {
         &self.services_context.pg_pool
@@ -448,6 +683,11 @@ impl DalContext {
         &self.history_actor
     }
 
+    /// Gets the identifier the client chose for the request being served, if any.
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.client_request_id.as_deref()
+    }
+
     /// Gets an optional reference to the dal context's pkgs path
     pub fn pkgs_path(&self) -> Option<&PathBuf> {
         self.services_context.pkgs_path.as_ref()
@@ -556,6 +796,8 @@ pub struct DalContextBuilder {
     /// This is useful to ensure child jobs of blocking jobs also block so there is no race-condition in the DAL.
     /// And also for SDF routes to block the HTTP request until the jobs get executed, so SDF tests don't race.
     blocking: bool,
+    /// Determines whether the connections of built contexts may come from a read replica.
+    intent: ConnectionIntent,
 }
 
 impl DalContextBuilder {
@@ -569,6 +811,7 @@ impl DalContextBuilder {
             tenancy: Tenancy::new_empty(),
             visibility: Visibility::new_head(false),
             history_actor: HistoryActor::SystemInit,
+            client_request_id: None,
         })
     }
 
@@ -585,6 +828,7 @@ impl DalContextBuilder {
             tenancy: access_builder.tenancy,
             history_actor: access_builder.history_actor,
             visibility: Visibility::new_head(false),
+            client_request_id: None,
         })
     }
 
@@ -601,6 +845,7 @@ impl DalContextBuilder {
             tenancy: request_context.tenancy,
             visibility: request_context.visibility,
             history_actor: request_context.history_actor,
+            client_request_id: None,
         })
     }
 
@@ -618,9 +863,9 @@ impl DalContextBuilder {
         self.services_context.job_processor.clone()
     }
 
-    /// Builds and returns a new [`Connections`].
+    /// Builds and returns a new [`Connections`] for the intent of this builder.
     pub async fn connections(&self) -> PgPoolResult<Connections> {
-        self.services_context.connections().await
+        self.services_context.connections_for(self.intent).await
     }
 
     /// Returns the location on disk where packages are stored (if one was provided)
@@ -637,6 +882,12 @@ impl DalContextBuilder {
     pub fn set_blocking(&mut self) {
         self.blocking = true;
     }
+
+    /// Marks the contexts built from here on as read-only, letting their queries be served by a
+    /// read replica. Only use this for requests which never write.
+    pub fn set_read_only(&mut self) {
+        self.intent = ConnectionIntent::ReadOnly;
+    }
 }
 
 #[remain::sorted]
@@ -705,6 +956,23 @@ impl Connections {
     }
 }
 
+/// A point within [`Transactions`] which they can be rolled back to, discarding what happened
+/// afterwards. It must be either rolled back to or released.
+#[must_use]
+#[derive(Debug)]
+pub struct Savepoint {
+    name: String,
+    nats_pending_len: usize,
+    job_queue_len: usize,
+}
+
+impl Savepoint {
+    /// Gets the name of the PostgreSQL savepoint.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 // A set of atomically-related transactions.
 //
 // Ideally, all of these inner transactions would be committed or rolled back together, hence the
@@ -742,6 +1010,52 @@ impl Transactions {
         &self.nats_txn
     }
 
+    /// Takes a [`Savepoint`] in the PostgreSQL transaction, remembering how many NATS messages
+    /// and jobs were pending at that point.
+    pub async fn savepoint(&self) -> Result<Savepoint, TransactionsError> {
+        let name = format!("dal_savepoint_{}", ulid::Ulid::new());
+        self.pg_txn
+            .batch_execute(&format!("SAVEPOINT {name}"))
+            .await?;
+
+        Ok(Savepoint {
+            name,
+            nats_pending_len: self.nats_txn.pending_len().await,
+            job_queue_len: self.job_processor.queue_len().await,
+        })
+    }
+
+    /// Rolls the inner transactions back to the [`Savepoint`], discarding the changes, NATS
+    /// messages and jobs which came after it.
+    pub async fn rollback_to_savepoint(
+        &self,
+        savepoint: Savepoint,
+    ) -> Result<(), TransactionsError> {
+        self.pg_txn
+            .batch_execute(&format!(
+                "ROLLBACK TO SAVEPOINT {name}; RELEASE SAVEPOINT {name}",
+                name = savepoint.name
+            ))
+            .await?;
+        self.nats_txn
+            .truncate_pending(savepoint.nats_pending_len)
+            .await;
+        self.job_processor
+            .truncate_queue(savepoint.job_queue_len)
+            .await;
+
+        Ok(())
+    }
+
+    /// Releases the [`Savepoint`], keeping everything which came after it.
+    pub async fn release_savepoint(&self, savepoint: Savepoint) -> Result<(), TransactionsError> {
+        self.pg_txn
+            .batch_execute(&format!("RELEASE SAVEPOINT {}", savepoint.name))
+            .await?;
+
+        Ok(())
+    }
+
     /// Consumes all inner transactions, committing all changes made within them, and returns
     /// underlying connections.
     pub async fn commit_into_conns(self) -> Result<Connections, TransactionsError> {
@@ -0,0 +1,248 @@
+//! This module contains the data migrations, which backfill or reshape existing rows once the
+//! schema migrations in `src/migrations` have run.
+//!
+//! Each data migration lives in its own numbered module, such as
+//! `data_migration/v0001_backfill_something.rs`, implements [`DataMigration`] and is registered in
+//! [`DataMigrator::builtin`]. The applied migrations are recorded in the `data_migrations` table,
+//! so that each one is applied once and in version order, and reverted in the reverse order.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DalContext, StandardModelError, TransactionsError};
+
+const LIST_APPLIED: &str = include_str!("queries/data_migration/list_applied.sql");
+const RECORD_APPLIED: &str = include_str!("queries/data_migration/record_applied.sql");
+const REMOVE_APPLIED: &str = include_str!("queries/data_migration/remove_applied.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum DataMigrationError {
+    #[error("data migration version {0} is registered more than once")]
+    DuplicateVersion(i64),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("data migration {0} ({1}) is applied but unknown to this version of the dal")]
+    UnknownApplied(i64, String),
+}
+
+pub type DataMigrationResult<T> = Result<T, DataMigrationError>;
+
+/// A versioned, reversible change to existing data.
+#[async_trait]
+pub trait DataMigration: Send + Sync {
+    /// The version of the migration, which orders it among the other migrations.
+    fn version(&self) -> i64;
+
+    /// A short, human readable name for the migration.
+    fn name(&self) -> &'static str;
+
+    /// Applies the migration, returning the number of rows affected.
+    async fn up(&self, ctx: &DalContext) -> DataMigrationResult<u64>;
+
+    /// Reverts the migration, returning the number of rows affected.
+    async fn down(&self, ctx: &DalContext) -> DataMigrationResult<u64>;
+}
+
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum DataMigrationDirection {
+    Down,
+    Up,
+}
+
+/// What running a single data migration did, or would have done for a dry run.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataMigrationReport {
+    pub version: i64,
+    pub name: String,
+    pub direction: DataMigrationDirection,
+    pub affected_rows: u64,
+    /// Whether the changes were rolled back rather than committed.
+    pub dry_run: bool,
+}
+
+/// A data migration recorded in the `data_migrations` table.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedDataMigration {
+    pub version: i64,
+    pub name: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+/// Applies and reverts a set of [`DataMigrations`](DataMigration), ordered by version.
+pub struct DataMigrator {
+    migrations: BTreeMap<i64, Box<dyn DataMigration>>,
+}
+
+impl std::fmt::Debug for DataMigrator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataMigrator")
+            .field("versions", &self.migrations.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl DataMigrator {
+    pub fn new(migrations: Vec<Box<dyn DataMigration>>) -> DataMigrationResult<Self> {
+        let mut by_version = BTreeMap::new();
+        for migration in migrations {
+            let version = migration.version();
+            if by_version.insert(version, migration).is_some() {
+                return Err(DataMigrationError::DuplicateVersion(version));
+            }
+        }
+
+        Ok(Self {
+            migrations: by_version,
+        })
+    }
+
+    /// Returns a migrator for the data migrations shipped with the dal.
+    pub fn builtin() -> DataMigrationResult<Self> {
+        Self::new(Vec::new())
+    }
+
+    /// Lists the data migrations which have been applied, in version order.
+    pub async fn applied(ctx: &DalContext) -> DataMigrationResult<Vec<AppliedDataMigration>> {
+        let rows = ctx.txns().await?.pg().query(LIST_APPLIED, &[]).await?;
+
+        let mut applied = Vec::with_capacity(rows.len());
+        for row in rows {
+            applied.push(AppliedDataMigration {
+                version: row.try_get("version")?,
+                name: row.try_get("name")?,
+                applied_at: row.try_get("applied_at")?,
+            });
+        }
+
+        Ok(applied)
+    }
+
+    /// Migrates the data to `target_version`, or to the latest version if `None`.
+    ///
+    /// Applied migrations newer than the target are reverted, newest first, then the pending
+    /// migrations up to the target are applied, oldest first. Each migration is committed on its
+    /// own. With `dry_run`, each migration is rolled back instead, so the reported row counts of
+    /// later migrations do not account for the changes of earlier ones.
+    #[instrument(skip(self, ctx))]
+    pub async fn migrate_to(
+        &self,
+        ctx: &DalContext,
+        target_version: Option<i64>,
+        dry_run: bool,
+    ) -> DataMigrationResult<Vec<DataMigrationReport>> {
+        let applied = Self::applied(ctx).await?;
+        let mut reports = Vec::new();
+
+        if let Some(target_version) = target_version {
+            for applied in applied.iter().rev() {
+                if applied.version <= target_version {
+                    continue;
+                }
+                let migration = self.migrations.get(&applied.version).ok_or_else(|| {
+                    DataMigrationError::UnknownApplied(applied.version, applied.name.clone())
+                })?;
+                reports.push(
+                    Self::run(
+                        ctx,
+                        migration.as_ref(),
+                        DataMigrationDirection::Down,
+                        dry_run,
+                    )
+                    .await?,
+                );
+            }
+        }
+
+        for (version, migration) in &self.migrations {
+            if target_version.map_or(false, |target_version| *version > target_version) {
+                break;
+            }
+            if applied.iter().any(|applied| applied.version == *version) {
+                continue;
+            }
+            reports.push(
+                Self::run(ctx, migration.as_ref(), DataMigrationDirection::Up, dry_run).await?,
+            );
+        }
+
+        Ok(reports)
+    }
+
+    async fn run(
+        ctx: &DalContext,
+        migration: &dyn DataMigration,
+        direction: DataMigrationDirection,
+        dry_run: bool,
+    ) -> DataMigrationResult<DataMigrationReport> {
+        let version = migration.version();
+        let name = migration.name();
+        info!(version, name, %direction, dry_run, "running data migration");
+
+        let affected_rows = match Self::run_inner(ctx, migration, direction).await {
+            Ok(affected_rows) => affected_rows,
+            Err(err) => {
+                ctx.rollback().await?;
+                return Err(err);
+            }
+        };
+        if dry_run {
+            ctx.rollback().await?;
+        } else {
+            ctx.commit().await?;
+        }
+        info!(version, name, %direction, dry_run, affected_rows, "ran data migration");
+
+        Ok(DataMigrationReport {
+            version,
+            name: name.to_owned(),
+            direction,
+            affected_rows,
+            dry_run,
+        })
+    }
+
+    async fn run_inner(
+        ctx: &DalContext,
+        migration: &dyn DataMigration,
+        direction: DataMigrationDirection,
+    ) -> DataMigrationResult<u64> {
+        let version = migration.version();
+        match direction {
+            DataMigrationDirection::Up => {
+                let affected_rows = migration.up(ctx).await?;
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(RECORD_APPLIED, &[&version, &migration.name()])
+                    .await?;
+                Ok(affected_rows)
+            }
+            DataMigrationDirection::Down => {
+                let affected_rows = migration.down(ctx).await?;
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(REMOVE_APPLIED, &[&version])
+                    .await?;
+                Ok(affected_rows)
+            }
+        }
+    }
+}
@@ -14,13 +14,14 @@ use crate::edge::EdgeKind;
 use crate::provider::external::ExternalProviderError;
 use crate::provider::internal::InternalProviderError;
 use crate::schema::variant::SchemaVariantError;
-use crate::socket::SocketError;
+use crate::socket::{SocketError, SocketTypeMismatch};
 use crate::{
     AttributeContextBuilderError, AttributePrototypeArgumentError, AttributeValueError,
     ChangeSetPk, ComponentError, ComponentId, DalContext, Edge, EdgeError, Node, NodeError, NodeId,
-    NodeKind, PropError, SchemaError, SocketId, StandardModel, StandardModelError,
+    NodeKind, PropError, SchemaError, SocketId, StandardModel, StandardModelError, WsEventError,
 };
 
+pub mod bulk;
 pub mod connection;
 pub mod node;
 
@@ -55,6 +56,8 @@ pub enum DiagramError {
     ExternalProvider(#[from] ExternalProviderError),
     #[error("external provider not found for socket id: {0}")]
     ExternalProviderNotFoundForSocket(SocketId),
+    #[error("cannot connect socket {0} to socket {1}: {2}")]
+    IncompatibleSockets(SocketId, SocketId, SocketTypeMismatch),
     #[error("internal provider error: {0}")]
     InternalProvider(#[from] InternalProviderError),
     #[error("internal provider not found for socket id: {0}")]
@@ -65,6 +68,8 @@ pub enum DiagramError {
     NodeNotFound,
     #[error("no node positions found for node ({0}) and kind ({1})")]
     NoNodePositionsFound(NodeId, NodeKind),
+    #[error("no nodes selected")]
+    NoNodesSelected,
     #[error(transparent)]
     ParseFloat(#[from] ParseFloatError),
     #[error(transparent)]
@@ -89,6 +94,8 @@ pub enum DiagramError {
     SocketNotFound,
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
 }
 
 pub type DiagramResult<T> = Result<T, DiagramError>;
@@ -0,0 +1,159 @@
+//! Operations on several [`Nodes`](crate::Node) of a [`Diagram`](crate::Diagram) at once, so that
+//! a multi-selection is moved or deleted within the transactions of a single
+//! [`DalContext`](crate::DalContext) and announced with a single [`WsEvent`](crate::WsEvent).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use telemetry::prelude::*;
+
+use crate::diagram::{Diagram, DiagramError, DiagramResult};
+use crate::{
+    Component, ComponentError, ComponentId, DalContext, Node, NodeError, NodeId, StandardModel,
+    WsEvent, WsEventResult, WsPayload,
+};
+
+/// The position of a [`Node`](crate::Node) after it was moved.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodePosition {
+    pub node_id: NodeId,
+    pub x: String,
+    pub y: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodesMovedPayload {
+    positions: Vec<NodePosition>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodesDeletedPayload {
+    node_ids: Vec<NodeId>,
+    component_ids: Vec<ComponentId>,
+}
+
+impl Diagram {
+    /// Moves every [`Node`](crate::Node) by the same delta and publishes a single
+    /// [`NodesMoved`](crate::WsPayload::NodesMoved) event with their new positions. Node ids
+    /// selected twice are only moved once.
+    #[instrument(skip(ctx))]
+    pub async fn move_nodes(
+        ctx: &DalContext,
+        node_ids: &[NodeId],
+        delta_x: f64,
+        delta_y: f64,
+    ) -> DiagramResult<Vec<NodePosition>> {
+        let node_ids = dedup(node_ids)?;
+
+        let mut positions = Vec::with_capacity(node_ids.len());
+        for node_id in node_ids {
+            let mut node = Node::get_by_id(ctx, &node_id)
+                .await?
+                .ok_or(NodeError::NotFound(node_id))?;
+            let x = offset(node.x(), delta_x)?;
+            let y = offset(node.y(), delta_y)?;
+            node.set_x(ctx, &x).await?;
+            node.set_y(ctx, &y).await?;
+            positions.push(NodePosition { node_id, x, y });
+        }
+
+        WsEvent::nodes_moved(ctx, positions.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(positions)
+    }
+
+    /// Deletes the [`Components`](crate::Component) of every [`Node`](crate::Node) and publishes a
+    /// single [`NodesDeleted`](crate::WsPayload::NodesDeleted) event.
+    ///
+    /// A frame can only be deleted once nothing is attached to it, so frames are deleted after the
+    /// selected components attached to them. Deleting a frame whose attached components are not
+    /// all selected fails.
+    #[instrument(skip(ctx))]
+    pub async fn delete_nodes(
+        ctx: &DalContext,
+        node_ids: &[NodeId],
+    ) -> DiagramResult<Vec<ComponentId>> {
+        let node_ids = dedup(node_ids)?;
+
+        let mut pending = Vec::with_capacity(node_ids.len());
+        for node_id in &node_ids {
+            let component = Component::find_for_node(ctx, *node_id)
+                .await?
+                .ok_or(DiagramError::ComponentNotFound)?;
+            pending.push(component);
+        }
+
+        let mut component_ids = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let deleted_before = component_ids.len();
+            let mut deferred = Vec::new();
+            for mut component in pending {
+                match component.delete_and_propagate(ctx).await {
+                    Ok(()) => component_ids.push(*component.id()),
+                    Err(ComponentError::FrameHasAttachedComponents) => deferred.push(component),
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            if component_ids.len() == deleted_before {
+                // Nothing was deleted in this pass: the frames left have attached components which
+                // are not selected.
+                return Err(ComponentError::FrameHasAttachedComponents.into());
+            }
+            pending = deferred;
+        }
+
+        WsEvent::nodes_deleted(ctx, node_ids, component_ids.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(component_ids)
+    }
+}
+
+impl WsEvent {
+    pub async fn nodes_moved(
+        ctx: &DalContext,
+        positions: Vec<NodePosition>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::NodesMoved(NodesMovedPayload { positions })).await
+    }
+
+    pub async fn nodes_deleted(
+        ctx: &DalContext,
+        node_ids: Vec<NodeId>,
+        component_ids: Vec<ComponentId>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::NodesDeleted(NodesDeletedPayload {
+                node_ids,
+                component_ids,
+            }),
+        )
+        .await
+    }
+}
+
+/// Drops the node ids selected twice, keeping the order of the selection.
+fn dedup(node_ids: &[NodeId]) -> DiagramResult<Vec<NodeId>> {
+    if node_ids.is_empty() {
+        return Err(DiagramError::NoNodesSelected);
+    }
+    let mut seen = HashSet::new();
+    Ok(node_ids
+        .iter()
+        .copied()
+        .filter(|node_id| seen.insert(*node_id))
+        .collect())
+}
+
+/// Offsets a coordinate, which nodes store as a string.
+fn offset(coordinate: &str, delta: f64) -> DiagramResult<String> {
+    Ok((coordinate.parse::<f64>()? + delta).to_string())
+}
@@ -5,8 +5,11 @@ use crate::edge::{Edge, EdgeId, EdgeKind};
 use crate::change_status::ChangeStatus;
 use crate::diagram::node::HistoryEventMetadata;
 use crate::diagram::DiagramResult;
-use crate::socket::SocketId;
-use crate::{node::NodeId, ActorView, DalContext, DiagramError, HistoryActor, StandardModel, User};
+use crate::socket::{SocketEdgeKind, SocketId, SocketKind};
+use crate::{
+    node::NodeId, ActorView, DalContext, DiagramError, HistoryActor, Node, Socket, StandardModel,
+    User,
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -36,6 +39,18 @@ impl Connection {
         to_socket_id: SocketId,
         edge_kind: EdgeKind,
     ) -> DiagramResult<Self> {
+        let from_socket = Socket::get_by_id(ctx, &from_socket_id)
+            .await?
+            .ok_or(DiagramError::SocketNotFound)?;
+        let to_socket = Socket::get_by_id(ctx, &to_socket_id)
+            .await?
+            .ok_or(DiagramError::SocketNotFound)?;
+        from_socket
+            .check_compatible(&to_socket)
+            .map_err(|mismatch| {
+                DiagramError::IncompatibleSockets(from_socket_id, to_socket_id, mismatch)
+            })?;
+
         let edge = Edge::new_for_connection(
             ctx,
             to_node_id,
@@ -48,6 +63,52 @@ impl Connection {
         Ok(Connection::from_edge(&edge))
     }
 
+    /// Lists the [`Sockets`](Socket) of the other [`Nodes`](Node) on the diagram that the
+    /// [`Socket`] for `socket_id` can be connected to, in either direction.
+    pub async fn list_compatible_sockets(
+        ctx: &DalContext,
+        node_id: NodeId,
+        socket_id: SocketId,
+    ) -> DiagramResult<Vec<Vertex>> {
+        let socket = Socket::get_by_id(ctx, &socket_id)
+            .await?
+            .ok_or(DiagramError::SocketNotFound)?;
+
+        let mut compatible = Vec::new();
+        for node in Node::list(ctx).await? {
+            if *node.id() == node_id {
+                continue;
+            }
+            let component = match node.component(ctx).await? {
+                Some(component) => component,
+                None => continue,
+            };
+
+            for candidate in Socket::list_for_component(ctx, *component.id()).await? {
+                if candidate.ui_hidden() || *candidate.kind() == SocketKind::Frame {
+                    continue;
+                }
+                let is_compatible = match (socket.edge_kind(), candidate.edge_kind()) {
+                    (SocketEdgeKind::ConfigurationOutput, SocketEdgeKind::ConfigurationInput) => {
+                        socket.check_compatible(&candidate).is_ok()
+                    }
+                    (SocketEdgeKind::ConfigurationInput, SocketEdgeKind::ConfigurationOutput) => {
+                        candidate.check_compatible(&socket).is_ok()
+                    }
+                    _ => false,
+                };
+                if is_compatible {
+                    compatible.push(Vertex {
+                        node_id: *node.id(),
+                        socket_id: *candidate.id(),
+                    });
+                }
+            }
+        }
+
+        Ok(compatible)
+    }
+
     pub async fn list(ctx: &DalContext) -> DiagramResult<Vec<Self>> {
         let edges = Edge::list(ctx).await?;
         let connections = edges.iter().map(Self::from_edge).collect::<Vec<Self>>();
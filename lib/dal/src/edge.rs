@@ -16,8 +16,8 @@ use crate::standard_model::objects_from_rows;
 use crate::{
     impl_standard_model, pk, socket::SocketId, standard_model, standard_model_accessor,
     AttributeReadContext, AttributeValue, AttributeValueError, ComponentId, ExternalProviderError,
-    Func, FuncError, HistoryActor, HistoryEventError, InternalProviderError, Node, PropId, Socket,
-    StandardModel, StandardModelError, Tenancy, Timestamp, UserPk, Visibility,
+    Func, FuncError, HistoryEventError, InternalProviderError, Node, PropId, Socket, StandardModel,
+    StandardModelError, Tenancy, Timestamp, UserPk, Visibility,
 };
 use crate::{
     AttributePrototypeArgument, AttributePrototypeArgumentError, Component, DalContext,
@@ -28,6 +28,7 @@ use crate::{
 const LIST_PARENTS_FOR_COMPONENT: &str =
     include_str!("queries/edge/list_parents_for_component.sql");
 const LIST_FOR_COMPONENT: &str = include_str!("queries/edge/list_for_component.sql");
+const LIST_FOR_COMPONENTS: &str = include_str!("queries/edge/list_for_components.sql");
 const LIST_FOR_KIND: &str = include_str!("queries/edge/list_for_kind.sql");
 const FIND_DELETED_EQUIVALENT: &str = include_str!("queries/edge/find_deleted_equivalent.sql");
 
@@ -183,10 +184,7 @@ impl Edge {
         tail_object_id: EdgeObjectId,
         tail_socket_id: SocketId,
     ) -> EdgeResult<Self> {
-        let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
-            _ => None,
-        };
+        let actor_user_pk = ctx.history_actor().user_pk();
 
         let row = ctx
             .txns()
@@ -287,6 +285,25 @@ impl Edge {
                 *tail_component.id(),
             )
             .await?;
+
+            // Let the value of the tail flow into the head right away, rather than once the tail
+            // changes.
+            let read_context = AttributeReadContext {
+                prop_id: Some(PropId::NONE),
+                internal_provider_id: Some(InternalProviderId::NONE),
+                external_provider_id: Some(*tail_external_provider.id()),
+                component_id: Some(*tail_component.id()),
+            };
+            let attr_value = AttributeValue::find_for_context(ctx, read_context)
+                .await?
+                .ok_or(EdgeError::AttributeValueNotFound)?;
+
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                *ctx.visibility(),
+                vec![*attr_value.id()],
+            ))
+            .await?;
         }
 
         // NOTE(nick): a lot of hardcoded values here that'll likely need to be adjusted.
@@ -354,6 +371,24 @@ impl Edge {
         Ok(objects_from_rows(rows)?)
     }
 
+    /// Lists the [`Edges`](Self) connected to any of the given [`Components`](Component), in a
+    /// single query.
+    pub async fn list_for_components(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+    ) -> EdgeResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_FOR_COMPONENTS,
+                &[ctx.tenancy(), ctx.visibility(), &component_ids],
+            )
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
     /// List [`Edges`](Self) for a given [`kind`](EdgeKind).
     pub async fn list_for_kind(ctx: &DalContext, kind: EdgeKind) -> EdgeResult<Vec<Self>> {
         let rows = ctx
@@ -430,10 +465,7 @@ impl Edge {
 
         edge_argument.delete_by_id(ctx).await?;
 
-        let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
-            _ => None,
-        };
+        let actor_user_pk = ctx.history_actor().user_pk();
         let _rows = ctx
             .txns()
             .await?
@@ -0,0 +1,200 @@
+//! This module implements [`CategorizedError`] for the biggest error enums of the DAL, so that
+//! the job processor can tell which failed jobs are worth retrying and sdf can pick a status code
+//! without matching on every variant.
+//!
+//! Variants which mean something on their own are categorized explicitly. Variants wrapping
+//! another error delegate to it, and the rest look for a categorized error in the chain of errors
+//! which caused them, falling back to [`ErrorCategory::Invariant`].
+
+use std::error::Error;
+use std::io;
+
+use si_data_nats::NatsError;
+
+pub use si_error::{CategorizedError, ErrorCategory};
+
+use crate::job::consumer::JobConsumerError;
+use crate::job::processor::JobQueueProcessorError;
+use crate::{
+    AttributePrototypeError, ComponentError, EdgeError, PropError, StandardModelError,
+    TransactionsError,
+};
+
+/// Categorizes an error by the first error of its chain, starting with itself, which has a
+/// category.
+pub fn categorize(err: &(dyn Error + 'static)) -> ErrorCategory {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if let Some(category) = known_category(err) {
+            return category;
+        }
+        current = err.source();
+    }
+    ErrorCategory::Invariant
+}
+
+/// Categorizes an error by the errors which caused it.
+fn category_from_sources(err: &(dyn Error + 'static)) -> ErrorCategory {
+    err.source().map_or(ErrorCategory::Invariant, categorize)
+}
+
+fn known_category(err: &(dyn Error + 'static)) -> Option<ErrorCategory> {
+    if let Some(err) = err.downcast_ref::<ComponentError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<EdgeError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<AttributePrototypeError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<JobConsumerError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<StandardModelError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<TransactionsError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<NatsError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<io::Error>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<serde_json::Error>() {
+        Some(err.category())
+    } else {
+        si_data_pg::error_category(err)
+    }
+}
+
+impl CategorizedError for TransactionsError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            // Transport errors are boxed away from their chain, so look into them directly
+            Self::JobQueueProcessor(JobQueueProcessorError::Transport(err)) => {
+                categorize(err.as_ref())
+            }
+            Self::JobQueueProcessor(err) => category_from_sources(err),
+            Self::Nats(err) => err.category(),
+            Self::Pg(err) => err.category(),
+            Self::PgPool(err) => err.category(),
+            Self::SerdeJson(err) => err.category(),
+            Self::Tenancy(_) | Self::TxnCommit | Self::TxnRollback | Self::TxnStart(_) => {
+                ErrorCategory::Invariant
+            }
+        }
+    }
+}
+
+impl CategorizedError for StandardModelError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ModelMissing(..) | Self::UserNotFound(_) => ErrorCategory::NotFound,
+            Self::Nats(err) => err.category(),
+            Self::Pg(err) => err.category(),
+            Self::SerdeJson(err) => err.category(),
+            Self::Transactions(err) => err.category(),
+            Self::HistoryEvent(_) | Self::User(_) => category_from_sources(self),
+        }
+    }
+}
+
+impl CategorizedError for ComponentError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::NodeNotFoundForComponent(_)
+            | Self::NotFound(_)
+            | Self::Prop(PropError::NotFoundAtPath(..)) => ErrorCategory::NotFound,
+            Self::InvalidContextForDiff | Self::InvalidJsonPointer(_) => ErrorCategory::User,
+            Self::ComponentProtected(_) | Self::FrameHasAttachedComponents => {
+                ErrorCategory::Conflict
+            }
+            Self::AttributePrototype(err) => err.category(),
+            Self::ContextTransaction(err) => err.category(),
+            Self::Edge(err) => err.category(),
+            Self::Nats(err) => err.category(),
+            Self::Pg(err) => err.category(),
+            Self::PgPool(err) => err.category(),
+            Self::SerdeJson(err) => err.category(),
+            Self::StandardModelError(err) => err.category(),
+            _ => category_from_sources(self),
+        }
+    }
+}
+
+impl CategorizedError for EdgeError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ComponentNotFoundForNode(_)
+            | Self::EdgeNotFound(_)
+            | Self::NodeNotFound(_)
+            | Self::SocketNotFound(_) => ErrorCategory::NotFound,
+            Self::RestoringAnEdgeToDeletedNode(..) | Self::RestoringNonDeletedEdge(_) => {
+                ErrorCategory::Conflict
+            }
+            Self::Nats(err) => err.category(),
+            Self::Pg(err) => err.category(),
+            Self::SerdeJson(err) => err.category(),
+            Self::StandardModel(err) => err.category(),
+            Self::Transactions(err) => err.category(),
+            _ => category_from_sources(self),
+        }
+    }
+}
+
+impl CategorizedError for AttributePrototypeError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::NotFound(..) => ErrorCategory::NotFound,
+            Self::InvalidPropValue(..)
+            | Self::LeastSpecificContextPrototypeRemovalNotAllowed(_)
+            | Self::LeastSpecificContextValueRemovalNotAllowed(_) => ErrorCategory::User,
+            Self::Nats(err) => err.category(),
+            Self::Pg(err) => err.category(),
+            Self::SerdeJson(err) => err.category(),
+            Self::StandardModelError(err) => err.category(),
+            Self::Transactions(err) => err.category(),
+            _ => category_from_sources(self),
+        }
+    }
+}
+
+impl CategorizedError for JobConsumerError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::NatsUnavailable => ErrorCategory::Transient,
+            Self::ComponentNotFound(_) | Self::NoSchemaFound(_) | Self::NoSchemaVariantFound(_) => {
+                ErrorCategory::NotFound
+            }
+            Self::Component(err) => err.category(),
+            Self::Io(err) => err.category(),
+            Self::Nats(err) => err.category(),
+            Self::PgPool(err) => err.category(),
+            Self::SerdeJson(err) => err.category(),
+            Self::StandardModel(err) => err.category(),
+            Self::Transactions(err) => err.category(),
+            _ => category_from_sources(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use si_data_pg::{PgError, SqlState};
+
+    use super::*;
+    use crate::ComponentId;
+
+    #[test]
+    fn wrapped_errors_keep_their_category() {
+        let conflict = ComponentError::Edge(EdgeError::Transactions(TransactionsError::Pg(
+            PgError::Injected(SqlState::T_R_SERIALIZATION_FAILURE),
+        )));
+        assert_eq!(ErrorCategory::Transient, conflict.category());
+        assert!(JobConsumerError::Component(conflict).is_retryable());
+
+        let not_found = JobConsumerError::Component(ComponentError::NotFound(ComponentId::NONE));
+        assert_eq!(ErrorCategory::NotFound, not_found.category());
+        assert!(!not_found.is_retryable());
+
+        assert_eq!(
+            ErrorCategory::Invariant,
+            categorize(&ComponentError::CannotUpdateResourceTreeInChangeSet)
+        );
+    }
+}
@@ -0,0 +1,4 @@
+//! This module contains exporters which turn modeled [`Components`](crate::Component) into
+//! artifacts that can be run outside of SI.
+
+pub mod docker_compose;
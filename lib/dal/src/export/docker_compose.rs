@@ -0,0 +1,237 @@
+//! Exports the containers modeled inside an application frame as a
+//! [Docker Compose](https://docs.docker.com/compose/compose-file/) document.
+//!
+//! Every "Docker Image" [`Component`] configured into the application (directly, or through
+//! nested frames) becomes a compose service named after the component, using its image, exposed
+//! ports and environment.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::component::view::ComponentViewError;
+use crate::{
+    Component, ComponentError, ComponentId, ComponentType, ComponentView, DalContext, SchemaError,
+    StandardModel, StandardModelError,
+};
+
+/// The name of the [`Schema`](crate::Schema) whose [`Components`](Component) become services.
+pub const DOCKER_IMAGE_SCHEMA_NAME: &str = "Docker Image";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum DockerComposeExportError {
+    #[error(transparent)]
+    Component(#[from] ComponentError),
+    #[error(transparent)]
+    ComponentView(#[from] ComponentViewError),
+    #[error("component {0} is not a frame and cannot be exported as an application")]
+    NotAnApplication(ComponentId),
+    #[error("component not found: {0}")]
+    NotFound(ComponentId),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("error serializing yaml: {0}")]
+    SerdeYaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+}
+
+pub type DockerComposeExportResult<T> = Result<T, DockerComposeExportError>;
+
+/// A Docker Compose document.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComposeDocument {
+    pub services: BTreeMap<String, ComposeService>,
+}
+
+/// A single service in a [`ComposeDocument`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComposeService {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, String>,
+}
+
+/// Builds the [`ComposeDocument`] for the application frame `application_id`.
+pub async fn build(
+    ctx: &DalContext,
+    application_id: ComponentId,
+) -> DockerComposeExportResult<ComposeDocument> {
+    let application = Component::get_by_id(ctx, &application_id)
+        .await?
+        .ok_or(DockerComposeExportError::NotFound(application_id))?;
+    if application.get_type(ctx).await? == ComponentType::Component {
+        return Err(DockerComposeExportError::NotAnApplication(application_id));
+    }
+
+    let mut document = ComposeDocument::default();
+    for component_id in Component::list_frame_descendants(ctx, application_id).await? {
+        let component = Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(DockerComposeExportError::NotFound(component_id))?;
+        let is_docker_image = match component.schema(ctx).await? {
+            Some(schema) => schema.name() == DOCKER_IMAGE_SCHEMA_NAME,
+            None => false,
+        };
+        if !is_docker_image {
+            continue;
+        }
+
+        let view = ComponentView::new(ctx, component_id).await?;
+        let base_name = service_name(&component.name(ctx).await?);
+        let mut name = base_name.clone();
+        let mut suffix = 2;
+        while document.services.contains_key(&name) {
+            name = format!("{base_name}-{suffix}");
+            suffix += 1;
+        }
+        document
+            .services
+            .insert(name, service_from_properties(&view.properties));
+    }
+
+    Ok(document)
+}
+
+/// Exports the application frame `application_id` as a Docker Compose YAML document.
+pub async fn export(
+    ctx: &DalContext,
+    application_id: ComponentId,
+) -> DockerComposeExportResult<String> {
+    let document = build(ctx, application_id).await?;
+    Ok(serde_yaml::to_string(&document)?)
+}
+
+fn service_from_properties(properties: &Value) -> ComposeService {
+    let domain = &properties["domain"];
+    let image = domain["image"].as_str().map(ToOwned::to_owned);
+    let ports = domain["ExposedPorts"]
+        .as_array()
+        .map(|ports| {
+            ports
+                .iter()
+                .filter_map(Value::as_str)
+                .filter_map(compose_port)
+                .collect()
+        })
+        .unwrap_or_default();
+    let environment = compose_environment(&domain["env"]);
+
+    ComposeService {
+        image,
+        ports,
+        environment,
+    }
+}
+
+/// Converts the environment of a container, either a map of variable names to values or a list of
+/// `NAME=value` entries, into the variables of a compose service.
+fn compose_environment(env: &Value) -> BTreeMap<String, String> {
+    match env {
+        Value::Object(variables) => variables
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    Value::Null => return None,
+                    value => value.to_string(),
+                };
+                Some((name.clone(), value))
+            })
+            .collect(),
+        Value::Array(entries) => entries
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(|entry| {
+                let (name, value) = entry.split_once('=').unwrap_or((entry, ""));
+                let name = name.trim();
+                (!name.is_empty()).then(|| (name.to_owned(), value.to_owned()))
+            })
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Converts an exposed port such as `80/tcp` into a compose port mapping such as `80:80`.
+fn compose_port(exposed_port: &str) -> Option<String> {
+    let (port, protocol) = match exposed_port.split_once('/') {
+        Some((port, protocol)) => (port.trim(), Some(protocol.trim())),
+        None => (exposed_port.trim(), None),
+    };
+    port.parse::<u16>().ok()?;
+
+    Some(match protocol {
+        None | Some("tcp") => format!("{port}:{port}"),
+        Some(protocol) => format!("{port}:{port}/{protocol}"),
+    })
+}
+
+/// Compose service names may only contain lowercase letters, digits, dashes and underscores.
+fn service_name(component_name: &str) -> String {
+    let name: String = component_name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "service".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_from_properties_maps_the_environment() {
+        let service = service_from_properties(&serde_json::json!({
+            "domain": {
+                "image": "docker.io/library/postgres",
+                "ExposedPorts": ["5432/tcp"],
+                "env": {
+                    "POSTGRES_DB": "si",
+                    "POSTGRES_PORT": 5432,
+                    "POSTGRES_PASSWORD": null,
+                },
+            },
+        }));
+        assert_eq!(
+            ComposeService {
+                image: Some("docker.io/library/postgres".to_owned()),
+                ports: vec!["5432:5432".to_owned()],
+                environment: BTreeMap::from([
+                    ("POSTGRES_DB".to_owned(), "si".to_owned()),
+                    ("POSTGRES_PORT".to_owned(), "5432".to_owned()),
+                ]),
+            },
+            service
+        );
+
+        let service = service_from_properties(&serde_json::json!({
+            "domain": { "env": ["POSTGRES_DB=si", "EMPTY=", "=nameless", "FLAG"] },
+        }));
+        assert_eq!(
+            BTreeMap::from([
+                ("EMPTY".to_owned(), "".to_owned()),
+                ("FLAG".to_owned(), "".to_owned()),
+                ("POSTGRES_DB".to_owned(), "si".to_owned()),
+            ]),
+            service.environment
+        );
+    }
+}
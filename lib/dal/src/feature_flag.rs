@@ -0,0 +1,206 @@
+//! This module contains [`FeatureFlag`], a toggle gating a feature which is still being rolled
+//! out, such as a new attribute engine or a new kind of diagram.
+//!
+//! A flag can be toggled globally and for a single workspace, in which case the workspace toggle
+//! takes precedence. Flags which were never toggled fall back to the defaults of the
+//! [`FeatureFlagCache`], which services load from their configuration, and are otherwise disabled.
+//! Use [`DalContext::feature_enabled`] to check whether a flag is enabled for the workspace of a
+//! context.
+
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, DalContext, StandardModelError, Timestamp,
+    TransactionsError, WorkspacePk,
+};
+
+mod cache;
+
+pub use cache::FeatureFlagCache;
+
+const FEATURE_FLAG_ENABLED: &str = include_str!("queries/feature_flag/enabled.sql");
+const FEATURE_FLAG_LIST_FOR_WORKSPACE: &str =
+    include_str!("queries/feature_flag/list_for_workspace.sql");
+const FEATURE_FLAG_UNSET: &str = include_str!("queries/feature_flag/unset.sql");
+
+/// The NATS subject on which toggling a flag is announced, so that every service forgets what it
+/// cached about the flag.
+pub const FEATURE_FLAG_CHANGED_SUBJECT: &str = "si.feature_flag.changed";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum FeatureFlagError {
+    #[error("invalid feature flag name: {0}")]
+    InvalidName(String),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type FeatureFlagResult<T> = Result<T, FeatureFlagError>;
+
+pk!(FeatureFlagPk);
+
+/// The global or workspace toggle of a flag.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeatureFlag {
+    pk: FeatureFlagPk,
+    name: String,
+    /// The workspace the toggle applies to, or `None` for the global toggle.
+    workspace_pk: Option<WorkspacePk>,
+    enabled: bool,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+/// Announced on [`FEATURE_FLAG_CHANGED_SUBJECT`] when a flag is toggled.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagChanged {
+    pub name: String,
+    pub workspace_pk: Option<WorkspacePk>,
+}
+
+impl FeatureFlag {
+    pub fn pk(&self) -> FeatureFlagPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(name, String);
+    standard_model_accessor_ro!(workspace_pk, Option<WorkspacePk>);
+    standard_model_accessor_ro!(enabled, bool);
+
+    /// Toggles a flag for a workspace, or globally if no workspace is given. Other services learn
+    /// about the change when the context is committed.
+    #[instrument(skip(ctx))]
+    pub async fn set(
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        workspace_pk: Option<WorkspacePk>,
+        enabled: bool,
+    ) -> FeatureFlagResult<Self> {
+        let name = name.as_ref();
+        validate_name(name)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM feature_flag_set_v1($1, $2, $3)",
+                &[&name, &workspace_pk, &enabled],
+            )
+            .await?;
+        let flag: Self = standard_model::object_from_row(row)?;
+
+        announce_change(ctx, name, workspace_pk).await?;
+
+        Ok(flag)
+    }
+
+    /// Removes the toggle of a flag for a workspace, or the global one if no workspace is given,
+    /// returning whether there was one. The flag falls back to the global toggle or the default.
+    #[instrument(skip(ctx))]
+    pub async fn unset(
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        workspace_pk: Option<WorkspacePk>,
+    ) -> FeatureFlagResult<bool> {
+        let name = name.as_ref();
+
+        let removed = ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(FEATURE_FLAG_UNSET, &[&name, &workspace_pk])
+            .await?;
+
+        announce_change(ctx, name, workspace_pk).await?;
+
+        Ok(removed > 0)
+    }
+
+    /// Lists the global toggles and the toggles of the workspace of the current tenancy.
+    pub async fn list(ctx: &DalContext) -> FeatureFlagResult<Vec<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(FeatureFlagError::NoWorkspaceInTenancy)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(FEATURE_FLAG_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Looks up whether a flag is toggled on for the workspace of the current tenancy, bypassing
+    /// the [`FeatureFlagCache`]. Returns `None` if the flag was never toggled.
+    pub async fn toggled(ctx: &DalContext, name: &str) -> FeatureFlagResult<Option<bool>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                FEATURE_FLAG_ENABLED,
+                &[&name, &ctx.tenancy().workspace_pk()],
+            )
+            .await?;
+        Ok(match row {
+            Some(row) => Some(row.try_get("enabled")?),
+            None => None,
+        })
+    }
+}
+
+/// Forgets what this service cached about the flag right away, so that the context sees its own
+/// change, and tells the other services to do the same once the context is committed.
+async fn announce_change(
+    ctx: &DalContext,
+    name: &str,
+    workspace_pk: Option<WorkspacePk>,
+) -> FeatureFlagResult<()> {
+    ctx.feature_flag_cache().invalidate(name).await;
+
+    let changed = FeatureFlagChanged {
+        name: name.to_string(),
+        workspace_pk,
+    };
+    ctx.txns()
+        .await?
+        .nats()
+        .publish(
+            FEATURE_FLAG_CHANGED_SUBJECT,
+            &serde_json::to_value(changed)?,
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Flag names match the ones services accept in their configuration.
+fn validate_name(name: &str) -> FeatureFlagResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(FeatureFlagError::InvalidName(name.to_string()))
+    }
+}
@@ -0,0 +1,144 @@
+//! This module provides [`FeatureFlagCache`], which keeps the toggles of the flags looked up by a
+//! service so that checking a flag does not hit the database every time.
+//!
+//! Toggling a flag is announced on [`FEATURE_FLAG_CHANGED_SUBJECT`], which makes every service
+//! listening forget what it cached about the flag. Entries also expire after a short while, which
+//! bounds how long a missed announcement goes unnoticed.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use telemetry::prelude::*;
+use tokio::sync::Mutex;
+
+use super::{FeatureFlag, FeatureFlagChanged, FeatureFlagResult, FEATURE_FLAG_CHANGED_SUBJECT};
+use crate::{DalContext, WorkspacePk};
+
+/// How long a toggle is kept before it is looked up again.
+const ENTRY_TTL: Duration = Duration::from_secs(60);
+
+type Entries = HashMap<(String, Option<WorkspacePk>), CachedToggle>;
+
+#[derive(Clone, Copy, Debug)]
+struct CachedToggle {
+    /// `None` if the flag was never toggled, in which case the default applies.
+    toggled: Option<bool>,
+    cached_at: Instant,
+}
+
+/// A cache of feature flag toggles, shared by every [`DalContext`] built from the same
+/// [`ServicesContext`](crate::ServicesContext).
+#[derive(Clone, Debug, Default)]
+pub struct FeatureFlagCache {
+    defaults: Arc<HashMap<String, bool>>,
+    entries: Arc<Mutex<Entries>>,
+    listening: Arc<AtomicBool>,
+}
+
+impl FeatureFlagCache {
+    /// Creates an empty cache whose flags fall back to the given defaults when they were never
+    /// toggled.
+    pub fn new(defaults: impl IntoIterator<Item = (String, bool)>) -> Self {
+        Self {
+            defaults: Arc::new(defaults.into_iter().collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether the flag is enabled for the workspace of the [`DalContext`].
+    pub async fn is_enabled(&self, ctx: &DalContext, name: &str) -> FeatureFlagResult<bool> {
+        self.ensure_listening(ctx).await;
+
+        let key = (name.to_string(), ctx.tenancy().workspace_pk());
+        let cached = self
+            .entries
+            .lock()
+            .await
+            .get(&key)
+            .filter(|cached| cached.cached_at.elapsed() < ENTRY_TTL)
+            .map(|cached| cached.toggled);
+        let toggled = match cached {
+            Some(toggled) => toggled,
+            None => {
+                let toggled = FeatureFlag::toggled(ctx, name).await?;
+                self.entries.lock().await.insert(
+                    key,
+                    CachedToggle {
+                        toggled,
+                        cached_at: Instant::now(),
+                    },
+                );
+                toggled
+            }
+        };
+
+        Ok(toggled.unwrap_or_else(|| self.default_for(name)))
+    }
+
+    /// Returns whether the flag is enabled when it was never toggled.
+    pub fn default_for(&self, name: &str) -> bool {
+        self.defaults.get(name).copied().unwrap_or(false)
+    }
+
+    /// Forgets the toggles of the flag, for every workspace.
+    pub async fn invalidate(&self, name: &str) {
+        self.entries
+            .lock()
+            .await
+            .retain(|(cached_name, _), _| cached_name != name);
+    }
+
+    /// Forgets every toggle.
+    pub async fn clear(&self) {
+        self.entries.lock().await.clear();
+    }
+
+    /// Subscribes to the announcements of toggled flags, unless the cache already does. Should
+    /// the subscription fail or end, the next lookup subscribes again.
+    async fn ensure_listening(&self, ctx: &DalContext) {
+        if self.listening.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut subscription = match ctx
+            .nats_conn()
+            .subscribe(FEATURE_FLAG_CHANGED_SUBJECT)
+            .await
+        {
+            Ok(subscription) => subscription,
+            Err(err) => {
+                warn!(error = ?err, "failed to subscribe to feature flag changes");
+                self.listening.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+
+        let cache = self.clone();
+        tokio::spawn(async move {
+            while let Some(message) = subscription.next().await {
+                match message.map_err(|err| err.to_string()).and_then(|message| {
+                    serde_json::from_slice::<FeatureFlagChanged>(message.data())
+                        .map_err(|err| err.to_string())
+                }) {
+                    Ok(changed) => cache.invalidate(&changed.name).await,
+                    Err(err) => {
+                        // We cannot tell which flag changed, so forget all of them
+                        warn!(error = %err, "failed to read feature flag change");
+                        cache.clear().await;
+                    }
+                }
+            }
+
+            // The entries may have missed changes while the subscription was going away
+            cache.clear().await;
+            cache.listening.store(false, Ordering::SeqCst);
+        });
+    }
+}
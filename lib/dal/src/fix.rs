@@ -8,6 +8,7 @@ use strum::{AsRefStr, Display, EnumIter, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::component::resource::ResourceHealth;
 use crate::fix::batch::FixBatchId;
 use crate::func::binding_return_value::FuncBindingReturnValueError;
 use crate::schema::SchemaUiMenu;
@@ -22,6 +23,7 @@ use crate::{
 use veritech_client::ResourceStatus;
 
 pub mod batch;
+pub mod plan;
 pub mod resolver;
 
 /// The completion status of a [`Fix`] or [`FixBatch`](crate::FixBatch).
@@ -350,6 +352,7 @@ impl Fix {
                 if batch_timed_out {
                     Some(ActionRunResult {
                         status: ResourceStatus::Error,
+                        health: ResourceHealth::Error,
                         payload: None,
                         message: Some("Execution timed-out".to_owned()),
                         // TODO: add proper logs here
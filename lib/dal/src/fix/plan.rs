@@ -0,0 +1,550 @@
+//! This module contains [`FixPlan`], the execution plan of a set of [`Fixes`](crate::Fix). The
+//! fixes are grouped into phases by the dependencies between their
+//! [`Components`](crate::Component), so that a network is created before the instance running
+//! in it (and deleted after it). Each phase runs as a [`FixBatch`](crate::FixBatch), after the
+//! previous one finished.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::fix::batch::FixBatchId;
+use crate::job::definition::{FixItem, FixesJob};
+use crate::ws_event::{WsEvent, WsEventError, WsPayload};
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, ActionKind, ActionPrototype,
+    ActionPrototypeError, ActionPrototypeId, AttributeValueId, Component, ComponentError,
+    ComponentId, DalContext, Edge, EdgeError, Fix, FixBatch, FixCompletionStatus, FixError,
+    StandardModel, StandardModelError, Timestamp, TransactionsError, WorkspacePk, WsEventResult,
+};
+
+const FIND_FOR_RUNNING_FIX_BATCH: &str =
+    include_str!("../queries/fix_plan/find_for_running_fix_batch.sql");
+const GET_BY_PK: &str = include_str!("../queries/fix_plan/get_by_pk.sql");
+const LOCK: &str = include_str!("../queries/fix_plan/lock.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum FixPlanError {
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] ActionPrototypeError),
+    #[error("action prototype not found: {0}")]
+    ActionPrototypeNotFound(ActionPrototypeId),
+    #[error("cannot move fix plan {0} from {1} to {2}")]
+    CannotTransition(FixPlanPk, FixPlanStatus, FixPlanStatus),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("components depend on each other: {0:?}")]
+    DependencyCycle(Vec<ComponentId>),
+    #[error("edge error: {0}")]
+    Edge(#[from] EdgeError),
+    #[error("fix error: {0}")]
+    Fix(#[from] FixError),
+    #[error("no fixes to plan")]
+    NoFixes,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type FixPlanResult<T> = Result<T, FixPlanError>;
+
+pk!(FixPlanPk);
+
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+pub enum FixPlanStatus {
+    /// Every phase finished successfully.
+    Completed,
+    /// A phase finished with failed fixes, so the phases after it were not run.
+    Failed,
+    /// The plan was paused: the running phase finishes, but the next one is not started until the
+    /// plan is resumed.
+    Paused,
+    /// The plan has not been started.
+    Pending,
+    Running,
+}
+
+/// A fix to run as part of a [`FixPlan`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixPlanItem {
+    /// The [`AttributeValue`](crate::AttributeValue) of the confirmation recommending the fix.
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub action_prototype_id: ActionPrototypeId,
+    pub action_kind: ActionKind,
+}
+
+impl FixPlanItem {
+    /// Describes the fix running the [`ActionPrototype`] for `action_prototype_id` on the
+    /// [`Component`] for `component_id`.
+    pub async fn new(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        component_id: ComponentId,
+        action_prototype_id: ActionPrototypeId,
+    ) -> FixPlanResult<Self> {
+        let action_prototype = ActionPrototype::get_by_id(ctx, &action_prototype_id)
+            .await?
+            .ok_or(FixPlanError::ActionPrototypeNotFound(action_prototype_id))?;
+        let component_name =
+            Component::find_name(&ctx.clone_with_delete_visibility(), component_id).await?;
+        Ok(Self {
+            attribute_value_id,
+            component_id,
+            component_name,
+            action_prototype_id,
+            action_kind: *action_prototype.kind(),
+        })
+    }
+}
+
+/// Fixes which run together, once the fixes of the previous phases have finished.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixPlanPhase {
+    pub items: Vec<FixPlanItem>,
+    /// The [`FixBatch`](crate::FixBatch) running the phase, once it started.
+    pub fix_batch_id: Option<FixBatchId>,
+}
+
+/// Fixes grouped into phases, which run one after the other.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FixPlan {
+    pk: FixPlanPk,
+    workspace_pk: WorkspacePk,
+    author: String,
+    status: FixPlanStatus,
+    phases: Vec<FixPlanPhase>,
+    next_phase: i32,
+    running_fix_batch_id: Option<FixBatchId>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl FixPlan {
+    pub fn pk(&self) -> FixPlanPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(author, String);
+    standard_model_accessor_ro!(status, FixPlanStatus);
+    standard_model_accessor_ro!(phases, Vec<FixPlanPhase>);
+    standard_model_accessor_ro!(running_fix_batch_id, Option<FixBatchId>);
+
+    /// Groups the fixes into phases without saving them, so that the plan can be reviewed.
+    ///
+    /// A component depends on the components connected to its input sockets, directly or through
+    /// components without fixes. Deletions come first, each phase deleting the components which
+    /// nothing left depends on. Creations follow, each phase creating the components whose
+    /// dependencies are created, and other actions come last, in the same order.
+    #[instrument(skip_all)]
+    pub async fn preview(
+        ctx: &DalContext,
+        items: Vec<FixPlanItem>,
+    ) -> FixPlanResult<Vec<FixPlanPhase>> {
+        if items.is_empty() {
+            return Err(FixPlanError::NoFixes);
+        }
+        let parents = list_parents(ctx, &items).await?;
+
+        let (deletions, items): (Vec<_>, Vec<_>) = items
+            .into_iter()
+            .partition(|item| item.action_kind == ActionKind::Delete);
+        let (creations, others): (Vec<_>, Vec<_>) = items
+            .into_iter()
+            .partition(|item| item.action_kind == ActionKind::Create);
+
+        let mut phases = Vec::new();
+        for (items, reverse) in [(deletions, true), (creations, false), (others, false)] {
+            let component_ids: HashSet<ComponentId> =
+                items.iter().map(|item| item.component_id).collect();
+            let mut dependencies = planned_dependencies(&component_ids, &parents);
+            if reverse {
+                dependencies = invert(&dependencies);
+            }
+            let component_phases =
+                group_into_phases(&dependencies).map_err(FixPlanError::DependencyCycle)?;
+
+            for component_ids in component_phases {
+                phases.push(FixPlanPhase {
+                    items: items
+                        .iter()
+                        .filter(|item| component_ids.contains(&item.component_id))
+                        .cloned()
+                        .collect(),
+                    fix_batch_id: None,
+                });
+            }
+        }
+        Ok(phases)
+    }
+
+    /// Plans the fixes, as [`previewed`](Self::preview), without starting them.
+    #[instrument(skip(ctx, items))]
+    pub async fn new(
+        ctx: &DalContext,
+        author: impl AsRef<str> + std::fmt::Debug,
+        items: Vec<FixPlanItem>,
+    ) -> FixPlanResult<Self> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let phases = Self::preview(ctx, items).await?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM fix_plan_create_v1($1, $2, $3)",
+                &[
+                    &workspace_pk,
+                    &author.as_ref(),
+                    &serde_json::to_value(&phases)?,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: FixPlanPk) -> FixPlanResult<Option<Self>> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[&pk, &workspace_pk])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Starts the first phase of a [`Pending`](FixPlanStatus::Pending) plan.
+    #[instrument(skip(ctx))]
+    pub async fn start(&mut self, ctx: &DalContext) -> FixPlanResult<()> {
+        self.lock(ctx).await?;
+        self.ensure_status(FixPlanStatus::Pending, FixPlanStatus::Running)?;
+        self.status = FixPlanStatus::Running;
+        self.start_next_phase(ctx).await
+    }
+
+    /// Pauses a [`Running`](FixPlanStatus::Running) plan. The running phase finishes, but the
+    /// next one only starts once the plan is [`resumed`](Self::resume).
+    #[instrument(skip(ctx))]
+    pub async fn pause(&mut self, ctx: &DalContext) -> FixPlanResult<()> {
+        self.lock(ctx).await?;
+        self.ensure_status(FixPlanStatus::Running, FixPlanStatus::Paused)?;
+        self.status = FixPlanStatus::Paused;
+        self.update(ctx).await
+    }
+
+    /// Resumes a [`Paused`](FixPlanStatus::Paused) plan, starting the next phase if the one
+    /// running when it was paused has finished.
+    #[instrument(skip(ctx))]
+    pub async fn resume(&mut self, ctx: &DalContext) -> FixPlanResult<()> {
+        self.lock(ctx).await?;
+        self.ensure_status(FixPlanStatus::Paused, FixPlanStatus::Running)?;
+        self.status = FixPlanStatus::Running;
+        if self.running_fix_batch_id.is_some() {
+            self.update(ctx).await
+        } else {
+            self.start_next_phase(ctx).await
+        }
+    }
+
+    /// Moves the plan running the [`FixBatch`](crate::FixBatch) for `fix_batch_id` on, if any,
+    /// now that the batch finished. The plan fails if any fix of the batch did not succeed, since
+    /// the next phases depend on it.
+    #[instrument(skip(ctx))]
+    pub async fn fix_batch_finished(
+        ctx: &DalContext,
+        fix_batch_id: FixBatchId,
+        completion_status: FixCompletionStatus,
+    ) -> FixPlanResult<()> {
+        // Fix batches run outside of the plans when the tenancy has no workspace
+        let Some(workspace_pk) = ctx.tenancy().workspace_pk() else {
+            return Ok(());
+        };
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(FIND_FOR_RUNNING_FIX_BATCH, &[&workspace_pk, &fix_batch_id])
+            .await?;
+        let Some(mut plan) = standard_model::option_object_from_row::<Self>(row)? else {
+            return Ok(());
+        };
+
+        plan.running_fix_batch_id = None;
+        if completion_status != FixCompletionStatus::Success {
+            plan.status = FixPlanStatus::Failed;
+            plan.update(ctx).await
+        } else if plan.status == FixPlanStatus::Running {
+            plan.start_next_phase(ctx).await
+        } else {
+            plan.update(ctx).await
+        }
+    }
+
+    async fn start_next_phase(&mut self, ctx: &DalContext) -> FixPlanResult<()> {
+        let Some(phase) = self.phases.get_mut(self.next_phase as usize) else {
+            self.status = FixPlanStatus::Completed;
+            return self.update(ctx).await;
+        };
+
+        let batch = FixBatch::new(ctx, &self.author).await?;
+        let mut fixes = Vec::with_capacity(phase.items.len());
+        for item in &phase.items {
+            let fix = Fix::new(
+                ctx,
+                *batch.id(),
+                item.attribute_value_id,
+                item.component_id,
+                item.action_prototype_id,
+            )
+            .await?;
+            fixes.push(FixItem {
+                id: *fix.id(),
+                attribute_value_id: item.attribute_value_id,
+                component_id: item.component_id,
+                action_prototype_id: item.action_prototype_id,
+            });
+        }
+        ctx.enqueue_job(FixesJob::new(ctx, fixes, *batch.id()))
+            .await?;
+
+        phase.fix_batch_id = Some(*batch.id());
+        self.running_fix_batch_id = Some(*batch.id());
+        self.next_phase += 1;
+        self.update(ctx).await
+    }
+
+    /// Reloads the plan, locking it until the end of the transaction.
+    async fn lock(&mut self, ctx: &DalContext) -> FixPlanResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(LOCK, &[&self.pk, &self.workspace_pk])
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        Ok(())
+    }
+
+    fn ensure_status(&self, from: FixPlanStatus, to: FixPlanStatus) -> FixPlanResult<()> {
+        if self.status != from {
+            return Err(FixPlanError::CannotTransition(self.pk, self.status, to));
+        }
+        Ok(())
+    }
+
+    async fn update(&mut self, ctx: &DalContext) -> FixPlanResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM fix_plan_update_v1($1, $2, $3, $4, $5)",
+                &[
+                    &self.pk,
+                    &self.status.as_ref(),
+                    &serde_json::to_value(&self.phases)?,
+                    &self.next_phase,
+                    &self.running_fix_batch_id,
+                ],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+
+        WsEvent::fix_plan_updated(ctx, self)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        Ok(())
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> FixPlanResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(FixPlanError::NoWorkspaceInTenancy)
+}
+
+/// Lists the components connected to the input sockets of the components of the items, and of
+/// the components connected to theirs, and so on. Deleted components are included, since their
+/// deletion depends on the components they were connected to.
+async fn list_parents(
+    ctx: &DalContext,
+    items: &[FixPlanItem],
+) -> FixPlanResult<HashMap<ComponentId, Vec<ComponentId>>> {
+    let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+    let mut parents = HashMap::new();
+    let mut queue: VecDeque<ComponentId> = items.iter().map(|item| item.component_id).collect();
+    while let Some(component_id) = queue.pop_front() {
+        if parents.contains_key(&component_id) {
+            continue;
+        }
+        let component_parents =
+            Edge::list_parents_for_component(ctx_with_deleted, component_id).await?;
+        queue.extend(component_parents.iter().copied());
+        parents.insert(component_id, component_parents);
+    }
+    Ok(parents)
+}
+
+/// Returns, for each of the `nodes`, the other `nodes` it depends on, directly or through nodes
+/// which are not part of `nodes`.
+fn planned_dependencies<T: Copy + Eq + Hash>(
+    nodes: &HashSet<T>,
+    parents: &HashMap<T, Vec<T>>,
+) -> HashMap<T, HashSet<T>> {
+    let mut dependencies = HashMap::with_capacity(nodes.len());
+    for node in nodes {
+        let mut node_dependencies = HashSet::new();
+        let mut visited = HashSet::from([*node]);
+        let mut queue: VecDeque<T> = parents.get(node).cloned().unwrap_or_default().into();
+        while let Some(parent) = queue.pop_front() {
+            if !visited.insert(parent) {
+                continue;
+            }
+            if nodes.contains(&parent) {
+                // Whatever the parent depends on is ordered before the parent itself
+                node_dependencies.insert(parent);
+            } else if let Some(grandparents) = parents.get(&parent) {
+                queue.extend(grandparents.iter().copied());
+            }
+        }
+        dependencies.insert(*node, node_dependencies);
+    }
+    dependencies
+}
+
+/// Turns "depends on" into "is depended on by".
+fn invert<T: Copy + Eq + Hash>(dependencies: &HashMap<T, HashSet<T>>) -> HashMap<T, HashSet<T>> {
+    let mut inverted: HashMap<T, HashSet<T>> = dependencies
+        .keys()
+        .map(|node| (*node, HashSet::new()))
+        .collect();
+    for (node, node_dependencies) in dependencies {
+        for dependency in node_dependencies {
+            inverted.entry(*dependency).or_default().insert(*node);
+        }
+    }
+    inverted
+}
+
+/// Groups the nodes into phases, each node coming after every node it depends on. Nodes within a
+/// phase are sorted, for a stable plan. Returns the nodes left unplanned if they depend on each
+/// other.
+fn group_into_phases<T: Copy + Eq + Hash + Ord>(
+    dependencies: &HashMap<T, HashSet<T>>,
+) -> Result<Vec<Vec<T>>, Vec<T>> {
+    let mut phases = Vec::new();
+    let mut planned = HashSet::with_capacity(dependencies.len());
+    while planned.len() < dependencies.len() {
+        let mut phase: Vec<T> = dependencies
+            .iter()
+            .filter(|(node, node_dependencies)| {
+                !planned.contains(*node) && node_dependencies.is_subset(&planned)
+            })
+            .map(|(node, _)| *node)
+            .collect();
+        if phase.is_empty() {
+            let mut unplanned: Vec<T> = dependencies
+                .keys()
+                .filter(|node| !planned.contains(*node))
+                .copied()
+                .collect();
+            unplanned.sort();
+            return Err(unplanned);
+        }
+        phase.sort();
+        planned.extend(phase.iter().copied());
+        phases.push(phase);
+    }
+    Ok(phases)
+}
+
+/// The payload of the [`WsEvent`](crate::WsEvent) sent when a [`FixPlan`] moves on.
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixPlanPayload {
+    plan_pk: FixPlanPk,
+    status: FixPlanStatus,
+    next_phase: i32,
+    phase_count: usize,
+}
+
+impl From<&FixPlan> for FixPlanPayload {
+    fn from(plan: &FixPlan) -> Self {
+        Self {
+            plan_pk: plan.pk,
+            status: plan.status,
+            next_phase: plan.next_phase,
+            phase_count: plan.phases.len(),
+        }
+    }
+}
+
+impl WsEvent {
+    pub async fn fix_plan_updated(ctx: &DalContext, plan: &FixPlan) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::FixPlanUpdated(plan.into())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parents(edges: &[(u8, u8)]) -> HashMap<u8, Vec<u8>> {
+        let mut parents: HashMap<u8, Vec<u8>> = HashMap::new();
+        for (child, parent) in edges {
+            parents.entry(*child).or_default().push(*parent);
+        }
+        parents
+    }
+
+    #[test]
+    fn phases_follow_dependencies_through_unplanned_nodes() {
+        // 4 runs in 3, which runs in 2, which runs in 1; 2 has nothing to fix
+        let parents = parents(&[(4, 3), (3, 2), (2, 1), (5, 1)]);
+        let nodes = HashSet::from([1, 3, 4, 5]);
+
+        let dependencies = planned_dependencies(&nodes, &parents);
+        assert_eq!(HashSet::from([1]), dependencies[&3]);
+        assert_eq!(
+            Ok(vec![vec![1], vec![3, 5], vec![4]]),
+            group_into_phases(&dependencies)
+        );
+        assert_eq!(
+            Ok(vec![vec![4, 5], vec![3], vec![1]]),
+            group_into_phases(&invert(&dependencies))
+        );
+    }
+
+    #[test]
+    fn phases_reject_cycles() {
+        let parents = parents(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let nodes = HashSet::from([1, 2, 3, 4]);
+
+        let dependencies = planned_dependencies(&nodes, &parents);
+        assert_eq!(Err(vec![1, 2, 3, 4]), group_into_phases(&dependencies));
+    }
+}
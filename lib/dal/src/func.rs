@@ -26,6 +26,7 @@ pub mod description;
 pub mod execution;
 pub mod identity;
 pub mod intrinsics;
+pub mod version;
 
 pub fn is_intrinsic(name: &str) -> bool {
     intrinsics::IntrinsicFunc::iter().any(|intrinsic| intrinsic.name() == name)
@@ -158,7 +159,7 @@ impl Func {
         // Generate a unique name and make sure it's not in use
         let mut new_unique_name;
         loop {
-            new_unique_name = format!("{}{}", self.name(), generate_unique_id(4));
+            new_unique_name = format!("{}{}", self.name(), generate_unique_id(ctx, 4));
             if Self::find_by_name(&ctx, &new_unique_name).await?.is_none() {
                 break;
             };
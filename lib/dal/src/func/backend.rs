@@ -1,3 +1,8 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
 use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
@@ -91,6 +96,20 @@ pub enum FuncBackendKind {
     Validation,
 }
 
+impl FuncBackendKind {
+    /// Whether functions of this kind are executed by veritech, rather than within the dal.
+    pub fn runs_in_veritech(&self) -> bool {
+        matches!(
+            self,
+            Self::JsAction
+                | Self::JsAttribute
+                | Self::JsReconciliation
+                | Self::JsSchemaVariantDefinition
+                | Self::JsValidation
+        )
+    }
+}
+
 #[remain::sorted]
 #[derive(
     Deserialize,
@@ -181,6 +200,7 @@ impl ToLabelList for FuncBackendKind {}
 pub struct FuncDispatchContext {
     pub veritech: VeritechClient,
     pub output_tx: mpsc::Sender<OutputStream>,
+    pub sensitivity: FuncDispatchSensitivity,
 }
 
 impl FuncDispatchContext {
@@ -190,6 +210,7 @@ impl FuncDispatchContext {
             Self {
                 veritech: ctx.veritech().clone(),
                 output_tx,
+                sensitivity: FuncDispatchSensitivity::default(),
             },
             rx,
         )
@@ -200,6 +221,22 @@ impl FuncDispatchContext {
     }
 }
 
+/// Records whether the result of a dispatched function was derived from secrets. Clones share
+/// the record, so it can be read after the [`FuncDispatchContext`] has been consumed (and its
+/// output channel closed).
+#[derive(Debug, Clone, Default)]
+pub struct FuncDispatchSensitivity(Arc<AtomicBool>);
+
+impl FuncDispatchSensitivity {
+    pub fn mark(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 #[async_trait]
 pub trait FuncDispatch: std::fmt::Debug {
     type Args: DeserializeOwned + Send + std::fmt::Debug;
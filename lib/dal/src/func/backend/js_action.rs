@@ -6,6 +6,7 @@ use veritech_client::{
     ActionRunRequest, ActionRunResultSuccess, FunctionResult, OutputStream, ResourceStatus,
 };
 
+use crate::component::resource::ResourceHealth;
 use crate::func::backend::{
     ExtractPayload, FuncBackendError, FuncBackendResult, FuncDispatch, FuncDispatchContext,
 };
@@ -76,6 +77,8 @@ impl FuncDispatch for FuncBackendJsAction {
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ActionRunResult {
     pub status: ResourceStatus,
+    #[serde(default)]
+    pub health: ResourceHealth,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub payload: Option<serde_json::Value>,
@@ -93,6 +96,7 @@ impl ExtractPayload for ActionRunResultSuccess {
 
     fn extract(self) -> FuncBackendResult<Self::Payload> {
         Ok(ActionRunResult {
+            health: ResourceHealth::from_status(self.status, self.payload.as_ref()),
             payload: self.payload,
             status: self.status,
             message: self.message.or(self.error),
@@ -5,6 +5,7 @@ use veritech_client::{
     ResolverFunctionResponseType, ResolverFunctionResultSuccess,
 };
 
+use crate::component::code::CodeArtifactEntry;
 use crate::func::backend::{ExtractPayload, FuncBackendResult, FuncDispatch, FuncDispatchContext};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -44,10 +45,16 @@ impl FuncDispatch for FuncBackendJsAttribute {
     }
 
     async fn dispatch(self: Box<Self>) -> FuncBackendResult<FunctionResult<Self::Output>> {
+        let sensitivity = self.context.sensitivity.clone();
         let (veritech, output_tx) = self.context.into_inner();
         let value = veritech
             .execute_resolver_function(output_tx, &self.request)
             .await?;
+        if let FunctionResult::Success(success) = &value {
+            if success.sensitive {
+                sensitivity.mark();
+            }
+        }
         Ok(value)
     }
 }
@@ -56,6 +63,18 @@ impl ExtractPayload for ResolverFunctionResultSuccess {
     type Payload = serde_json::Value;
 
     fn extract(self) -> FuncBackendResult<Self::Payload> {
-        Ok(self.data)
+        let mut data = self.data;
+        // Artifacts are stored beside the generated code they came with, keyed by their names
+        if let (false, Some(object)) = (self.artifacts.is_empty(), data.as_object_mut()) {
+            let mut artifacts = serde_json::Map::new();
+            for artifact in self.artifacts {
+                artifacts.insert(
+                    artifact.name.clone(),
+                    serde_json::to_value(CodeArtifactEntry::from(artifact))?,
+                );
+            }
+            object.insert("artifacts".to_owned(), serde_json::Value::Object(artifacts));
+        }
+        Ok(data)
     }
 }
@@ -1,12 +1,17 @@
+use std::time::Instant;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use telemetry::metrics::{Counter, Histogram};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::mpsc;
 use veritech_client::{OutputStream, ResolverFunctionComponent};
 
+use crate::blob::BlobError;
+use crate::component::code;
 use crate::func::execution::FuncExecutionPk;
 use crate::FuncError;
 use crate::{
@@ -31,20 +36,32 @@ use crate::{
 };
 use crate::{
     impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_belongs_to,
-    Func, FuncBackendError, FuncBackendKind, HistoryEventError, StandardModel, StandardModelError,
-    Timestamp, Visibility,
+    Func, FuncBackendError, FuncBackendKind, FuncBackendResponseType, HistoryEventError,
+    StandardModel, StandardModelError, Timestamp, Usage, UsageError, UsageMetric, Visibility,
 };
 use crate::{DalContext, Tenancy};
 
 use super::{
     binding_return_value::{FuncBindingReturnValue, FuncBindingReturnValueError},
     execution::{FuncExecution, FuncExecutionError},
+    version::FuncVersion,
     FuncId,
 };
 
+static FUNC_EXECUTIONS: Counter = Counter::new(
+    "si_func_executions_total",
+    "Total number of function executions, by backend kind and outcome",
+);
+static FUNC_EXECUTION_DURATION: Histogram = Histogram::new(
+    "si_func_execution_duration_seconds",
+    "Time spent executing functions, by backend kind",
+);
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum FuncBindingError {
+    #[error("blob error: {0}")]
+    Blob(#[from] BlobError),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
     #[error("func backend error: {0}")]
@@ -69,16 +86,22 @@ pub enum FuncBindingError {
     JsFuncNotFound(FuncBindingPk),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
+    #[error("func binding has never been executed, so there is nothing to replay: {0}")]
+    NoExecutionToReplay(FuncBindingId),
     #[error("func binding not found: {0}")]
     NotFound(FuncBindingId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("replaying {0} functions is unsupported, as they have side effects")]
+    ReplayUnsupported(FuncBackendKind),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
+    #[error("usage error: {0}")]
+    Usage(#[from] UsageError),
 }
 
 pub type FuncBindingResult<T> = Result<T, FuncBindingError>;
@@ -103,6 +126,23 @@ pub struct FuncBinding {
     visibility: Visibility,
 }
 
+/// The outcome of [`replaying`](FuncBinding::replay()) an execution of a [`FuncBinding`] against
+/// the inputs it was recorded with.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncReplay {
+    pub func_execution_pk: FuncExecutionPk,
+    /// Whether the code of the [`Func`](crate::Func) has changed since the recorded execution.
+    pub code_changed: bool,
+    pub recorded_value: Option<serde_json::Value>,
+    pub replayed_value: Option<serde_json::Value>,
+    /// Why the replay failed, if the function failed when executed again.
+    pub replay_failure: Option<String>,
+    /// A line diff from the recorded value to the replayed value, or `None` if they match.
+    pub diff: Option<String>,
+    pub output_stream: Vec<OutputStream>,
+}
+
 impl_standard_model! {
     model: FuncBinding,
     pk: FuncBindingPk,
@@ -124,7 +164,19 @@ impl FuncBinding {
         let func = Func::get_by_id(ctx, &func_id)
             .await?
             .ok_or(FuncBindingError::FuncNotFound(FuncBindingPk::NONE))?;
+        Self::new_for_func(ctx, args, &func, backend_kind).await
+    }
 
+    /// Creates a [`FuncBinding`](Self) recording the code of the given [`Func`](crate::Func),
+    /// which may differ from the code it has in the database, such as a pinned
+    /// [`FuncVersion`](crate::func::version::FuncVersion).
+    async fn new_for_func(
+        ctx: &DalContext,
+        args: serde_json::Value,
+        func: &Func,
+        backend_kind: FuncBackendKind,
+    ) -> FuncBindingResult<Self> {
+        let func_id = *func.id();
         let row = ctx
             .txns()
             .await?
@@ -180,12 +232,27 @@ impl FuncBinding {
         args: serde_json::Value,
         func_id: FuncId,
     ) -> FuncBindingResult<(Self, FuncBindingReturnValue)> {
-        let func = Func::get_by_id(ctx, &func_id)
+        Self::create_and_execute_at_version(ctx, args, func_id, None).await
+    }
+
+    /// Runs [`Self::create_and_execute()`] with the code of a
+    /// [`FuncVersion`](crate::func::version::FuncVersion) of the [`Func`](crate::Func), or with
+    /// its current code if no version is given.
+    pub async fn create_and_execute_at_version(
+        ctx: &DalContext,
+        args: serde_json::Value,
+        func_id: FuncId,
+        version: Option<&FuncVersion>,
+    ) -> FuncBindingResult<(Self, FuncBindingReturnValue)> {
+        let mut func = Func::get_by_id(ctx, &func_id)
             .await?
             .ok_or(FuncError::NotFound(func_id))?;
-        let func_binding = Self::new(ctx, args, func_id, func.backend_kind).await?;
+        if let Some(version) = version {
+            version.apply_to(&mut func);
+        }
+        let func_binding = Self::new_for_func(ctx, args, &func, func.backend_kind).await?;
 
-        let func_binding_return_value: FuncBindingReturnValue = func_binding.execute(ctx).await?;
+        let func_binding_return_value = func_binding.execute_func(ctx, func).await?;
 
         Ok((func_binding, func_binding_return_value))
     }
@@ -206,16 +273,144 @@ impl FuncBinding {
 
     // For a given [`FuncBinding`](Self), execute using veritech.
     pub async fn execute(&self, ctx: &DalContext) -> FuncBindingResult<FuncBindingReturnValue> {
-        let (func, execution, context, mut rx) = self.prepare_execution(ctx).await?;
-        let value = self.execute_critical_section(func.clone(), context).await?;
+        let func: Func = self
+            .func(ctx)
+            .await?
+            .ok_or(FuncBindingError::FuncNotFound(self.pk))?;
+        self.execute_func(ctx, func).await
+    }
+
+    /// Executes with the code of the given [`Func`](crate::Func) rather than the code it has in
+    /// the database.
+    async fn execute_func(
+        &self,
+        ctx: &DalContext,
+        func: Func,
+    ) -> FuncBindingResult<FuncBindingReturnValue> {
+        let (func, execution, context, mut rx) = self.prepare_execution_for_func(ctx, func).await?;
+        let sensitivity = context.sensitivity.clone();
+        let value = self.execute_metered(ctx, func.clone(), context).await?;
 
         let mut output = Vec::new();
         while let Some(output_stream) = rx.recv().await {
             output.push(output_stream);
         }
 
-        self.postprocess_execution(ctx, output, &func, value, execution)
-            .await
+        self.postprocess_execution(
+            ctx,
+            output,
+            &func,
+            value,
+            execution,
+            sensitivity.is_sensitive(),
+        )
+        .await
+    }
+
+    /// Executes the most recent execution of a [`FuncBinding`](Self) again, with the arguments,
+    /// handler and code it was recorded with rather than the current code of its
+    /// [`Func`](crate::Func), and compares the outputs.
+    ///
+    /// Nothing is recorded for the replay, so it leaves no
+    /// [`FuncExecution`](crate::func::execution::FuncExecution) or
+    /// [`FuncBindingReturnValue`](crate::FuncBindingReturnValue) behind. Actions are never
+    /// replayed, since executing one again would act on real resources.
+    pub async fn replay(
+        ctx: &DalContext,
+        func_binding_id: FuncBindingId,
+    ) -> FuncBindingResult<FuncReplay> {
+        let func_binding = Self::get_by_id(ctx, &func_binding_id)
+            .await?
+            .ok_or(FuncBindingError::NotFound(func_binding_id))?;
+        let execution = FuncExecution::find_latest_for_func_binding(ctx, func_binding_id)
+            .await?
+            .ok_or(FuncBindingError::NoExecutionToReplay(func_binding_id))?;
+        if execution.backend_kind() == FuncBackendKind::JsAction {
+            return Err(FuncBindingError::ReplayUnsupported(
+                execution.backend_kind(),
+            ));
+        }
+
+        let mut func = func_binding
+            .func(ctx)
+            .await?
+            .ok_or(FuncBindingError::FuncNotFound(func_binding.pk))?;
+        let code_changed = func.code_sha256() != execution.code_sha256();
+        func.backend_response_type = execution.backend_response_type();
+        func.handler = execution.handler().map(ToOwned::to_owned);
+        func.code_base64 = execution.code_base64().map(ToOwned::to_owned);
+
+        let recorded_binding = Self {
+            args: execution.func_binding_args().clone(),
+            backend_kind: execution.backend_kind(),
+            ..func_binding
+        };
+        let (replayed_value, replay_failure, output_stream) =
+            recorded_binding.execute_unrecorded(ctx, func).await?;
+        let recorded_value = execution.unprocessed_value().cloned();
+        let diff = if recorded_value == replayed_value {
+            None
+        } else {
+            Some(value_diff(
+                recorded_value.as_ref(),
+                replayed_value.as_ref(),
+            )?)
+        };
+
+        Ok(FuncReplay {
+            func_execution_pk: execution.pk(),
+            code_changed,
+            recorded_value,
+            replayed_value,
+            replay_failure,
+            diff,
+            output_stream,
+        })
+    }
+
+    /// Executes with the code of the given [`Func`](crate::Func) without recording anything,
+    /// returning the unprocessed value, why the function failed if it did, and its output.
+    pub(crate) async fn execute_unrecorded(
+        &self,
+        ctx: &DalContext,
+        func: Func,
+    ) -> FuncBindingResult<(Option<serde_json::Value>, Option<String>, Vec<OutputStream>)> {
+        let (context, mut rx) = FuncDispatchContext::new(ctx);
+        let result = self.execute_metered(ctx, func, context).await;
+
+        let mut output_stream = Vec::new();
+        while let Some(output) = rx.recv().await {
+            output_stream.push(output);
+        }
+
+        match result {
+            Ok((unprocessed_value, _)) => Ok((unprocessed_value, None, output_stream)),
+            Err(FuncBindingError::FuncBackendResultFailure { kind, message, .. }) => {
+                Ok((None, Some(format!("{kind}: {message}")), output_stream))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Executes like [`execute_critical_section`](Self::execute_critical_section), recording the
+    /// time spent executing in veritech as [`Usage`] of the workspace.
+    async fn execute_metered(
+        &self,
+        ctx: &DalContext,
+        func: Func,
+        context: FuncDispatchContext,
+    ) -> FuncBindingResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
+        let started_at = Instant::now();
+        let result = self.execute_critical_section(func, context).await;
+        if self.backend_kind().runs_in_veritech() {
+            Usage::record(
+                ctx,
+                UsageMetric::FuncExecutionSeconds,
+                started_at.elapsed().as_secs_f64(),
+            )
+            .await?;
+        }
+        result
     }
 
     /// Perform function execution to veritech for a given [`Func`](crate::Func) and
@@ -226,6 +421,7 @@ impl FuncBinding {
         context: FuncDispatchContext,
     ) -> FuncBindingResult<(Option<serde_json::Value>, Option<serde_json::Value>)> {
         // TODO: encrypt components
+        let started_at = Instant::now();
         let execution_result = match self.backend_kind() {
             FuncBackendKind::JsValidation => {
                 FuncBackendJsValidation::create_and_execute(context, &func, &self.args).await
@@ -276,6 +472,21 @@ impl FuncBinding {
             }
         };
 
+        let backend_kind: &str = self.backend_kind().as_ref();
+        FUNC_EXECUTION_DURATION
+            .observe_duration(&[("backend_kind", backend_kind)], started_at.elapsed());
+        FUNC_EXECUTIONS.increment(&[
+            ("backend_kind", backend_kind),
+            (
+                "outcome",
+                if execution_result.is_ok() {
+                    "success"
+                } else {
+                    "failure"
+                },
+            ),
+        ]);
+
         match execution_result {
             Ok(value) => Ok(value),
             Err(FuncBackendError::ResultFailure {
@@ -296,15 +507,35 @@ impl FuncBinding {
         ctx: &DalContext,
         output_stream: Vec<OutputStream>,
         func: &Func,
-        (unprocessed_value, processed_value): (
+        (mut unprocessed_value, mut processed_value): (
             Option<serde_json::Value>,
             Option<serde_json::Value>,
         ),
         mut execution: FuncExecution,
+        sensitive: bool,
     ) -> FuncBindingResult<FuncBindingReturnValue> {
         execution.set_output_stream(ctx, output_stream).await?;
 
-        let func_binding_return_value = FuncBindingReturnValue::new(
+        if *func.backend_response_type() == FuncBackendResponseType::CodeGeneration {
+            if let Some(value) = processed_value.as_ref().or(unprocessed_value.as_ref()) {
+                let size = code::generated_size(value);
+                if size > 0 {
+                    Usage::record(ctx, UsageMetric::CodeArtifactBytes, size as f64).await?;
+                }
+            }
+        }
+
+        // Large generated code and artifacts go to the blob store, unless they have to be sealed.
+        if *func.backend_response_type() == FuncBackendResponseType::CodeGeneration && !sensitive {
+            for value in [&mut unprocessed_value, &mut processed_value]
+                .into_iter()
+                .flatten()
+            {
+                code::store_code_blobs(ctx, value).await?;
+            }
+        }
+
+        let mut func_binding_return_value = FuncBindingReturnValue::new(
             ctx,
             unprocessed_value,
             processed_value,
@@ -313,6 +544,10 @@ impl FuncBinding {
             execution.pk(),
         )
         .await?;
+        // Values derived from secrets are sealed before being recorded anywhere else.
+        if sensitive {
+            func_binding_return_value.seal(ctx).await?;
+        }
 
         execution
             .process_return_value(ctx, &func_binding_return_value)
@@ -337,7 +572,19 @@ impl FuncBinding {
             .func(ctx)
             .await?
             .ok_or(FuncBindingError::FuncNotFound(self.pk))?;
+        self.prepare_execution_for_func(ctx, func).await
+    }
 
+    async fn prepare_execution_for_func(
+        &self,
+        ctx: &DalContext,
+        func: Func,
+    ) -> FuncBindingResult<(
+        Func,
+        FuncExecution,
+        FuncDispatchContext,
+        mpsc::Receiver<OutputStream>,
+    )> {
         let mut execution = FuncExecution::new(ctx, &func, self).await?;
 
         match self.backend_kind() {
@@ -373,3 +620,23 @@ impl FuncBinding {
         Ok((func, execution, context, rx))
     }
 }
+
+/// Renders a line diff between the pretty printed forms of two values, in the format used by
+/// [`ComponentDiff`](crate::component::diff::ComponentDiff).
+fn value_diff(
+    recorded: Option<&serde_json::Value>,
+    replayed: Option<&serde_json::Value>,
+) -> FuncBindingResult<String> {
+    let recorded = serde_json::to_string_pretty(&recorded)?;
+    let replayed = serde_json::to_string_pretty(&replayed)?;
+
+    let lines: Vec<String> = diff::lines(&recorded, &replayed)
+        .into_iter()
+        .map(|line| match line {
+            diff::Result::Left(left) => format!("-{left}"),
+            diff::Result::Both(unchanged, _) => format!(" {unchanged}"),
+            diff::Result::Right(right) => format!("+{right}"),
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
@@ -1,8 +1,10 @@
 use crate::{Func, Tenancy, TransactionsError};
+use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use sodiumoxide::crypto::sealedbox;
 use telemetry::prelude::*;
 use thiserror::Error;
 use veritech_client::OutputStream;
@@ -11,14 +13,18 @@ use crate::func::FuncMetadataView;
 use crate::{
     func::binding::FuncBindingId,
     func::execution::{FuncExecution, FuncExecutionError, FuncExecutionPk},
-    impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_accessor_ro,
-    DalContext, FuncId, HistoryEventError, StandardModel, StandardModelError, Timestamp,
-    Visibility,
+    impl_standard_model,
+    key_pair::KeyPairPk,
+    pk, standard_model, standard_model_accessor, standard_model_accessor_ro, DalContext, FuncId,
+    HistoryEventError, KeyPair, KeyPairError, StandardModel, StandardModelError, Timestamp,
+    Visibility, WorkspacePk,
 };
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum FuncBindingReturnValueError {
+    #[error("could not decrypt sealed func binding return value: {0}")]
+    DecryptionFailed(FuncBindingReturnValueId),
     #[error("func binding error: {0}")]
     FuncBinding(String),
     #[error("function execution error: {0}")]
@@ -27,8 +33,12 @@ pub enum FuncBindingReturnValueError {
     FuncNotFound(FuncId),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("key pair error: {0}")]
+    KeyPair(#[from] KeyPairError),
     #[error("missing func binding return value")]
     Missing,
+    #[error("sealed func binding return value has no crypted values: {0}")]
+    MissingCryptedValues(FuncBindingReturnValueId),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
     #[error("not found: {0}")]
@@ -41,6 +51,8 @@ pub enum FuncBindingReturnValueError {
     StandardModel(#[from] StandardModelError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
+    #[error("func binding return value {0} was sealed for workspace {1}, cannot decrypt it in workspace {2:?}")]
+    Unauthorized(FuncBindingReturnValueId, WorkspacePk, Option<WorkspacePk>),
 }
 
 pub type FuncBindingReturnValueResult<T> = Result<T, FuncBindingReturnValueError>;
@@ -66,6 +78,14 @@ pub struct FuncBindingReturnValue {
     func_binding_id: FuncBindingId,
     /// Function Execution IDs can be attached later for lookup and are optional.
     func_execution_pk: FuncExecutionPk,
+    /// Set when the values were derived from secrets. They are then only stored sealed, in
+    /// `crypted_values`, and have to be [decrypted](Self::decrypt()) after being read.
+    #[serde(default)]
+    sensitive: bool,
+    /// The [`KeyPair`](crate::KeyPair) the values were sealed with.
+    key_pair_pk: Option<KeyPairPk>,
+    /// Base64 encoded, sealed `unprocessed_value` and `value`.
+    crypted_values: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -74,6 +94,14 @@ pub struct FuncBindingReturnValue {
     visibility: Visibility,
 }
 
+/// The plaintext of the `crypted_values` of a sealed [`FuncBindingReturnValue`].
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedValues {
+    unprocessed_value: Option<JsonValue>,
+    value: Option<JsonValue>,
+}
+
 impl_standard_model! {
     model: FuncBindingReturnValue,
     pk: FuncBindingReturnValuePk,
@@ -129,6 +157,114 @@ impl FuncBindingReturnValue {
     standard_model_accessor!(value, OptionJson<JsonValue>, FuncBindingReturnValueResult);
     standard_model_accessor_ro!(func_id, FuncId);
 
+    /// Returns true if the values were derived from secrets and are only stored sealed.
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    /// Seals the values with the current [`KeyPair`](crate::KeyPair) of the workspace, leaving
+    /// them out of the database in plaintext. They are kept in [`self`](Self), so the values of a
+    /// sealed return value can be changed and sealed again.
+    pub async fn seal(&mut self, ctx: &DalContext) -> FuncBindingReturnValueResult<()> {
+        let key_pair = KeyPair::get_current(ctx).await?;
+        let values = SealedValues {
+            unprocessed_value: self.unprocessed_value.clone(),
+            value: self.value.clone(),
+        };
+        let crypted = general_purpose::STANDARD_NO_PAD.encode(sealedbox::seal(
+            &serde_json::to_vec(&values)?,
+            key_pair.public_key(),
+        ));
+
+        let table = Self::table_name();
+        standard_model::update(
+            ctx,
+            table,
+            "crypted_values",
+            self.id(),
+            &crypted,
+            standard_model::TypeHint::Text,
+        )
+        .await?;
+        standard_model::update(
+            ctx,
+            table,
+            "key_pair_pk",
+            self.id(),
+            &key_pair.pk(),
+            standard_model::TypeHint::Ident,
+        )
+        .await?;
+        standard_model::update(
+            ctx,
+            table,
+            "sensitive",
+            self.id(),
+            &true,
+            standard_model::TypeHint::Boolean,
+        )
+        .await?;
+        for column in ["unprocessed_value", "value"] {
+            self.timestamp.updated_at = standard_model::update(
+                ctx,
+                table,
+                column,
+                self.id(),
+                &Option::<JsonValue>::None,
+                standard_model::TypeHint::JsonB,
+            )
+            .await?;
+        }
+
+        self.sensitive = true;
+        self.key_pair_pk = Some(key_pair.pk());
+        self.crypted_values = Some(crypted);
+        Ok(())
+    }
+
+    /// Decrypts the values of a sealed return value in place. Only contexts in the workspace
+    /// which sealed them are authorized to do so. Does nothing if the values are not sealed.
+    pub async fn decrypt(&mut self, ctx: &DalContext) -> FuncBindingReturnValueResult<()> {
+        if !self.sensitive {
+            return Ok(());
+        }
+        let (key_pair_pk, crypted) = match (self.key_pair_pk, &self.crypted_values) {
+            (Some(key_pair_pk), Some(crypted)) => (key_pair_pk, crypted),
+            _ => return Err(FuncBindingReturnValueError::MissingCryptedValues(self.id)),
+        };
+
+        let key_pair = KeyPair::get_by_pk(ctx, key_pair_pk).await?;
+        let workspace_pk = ctx.tenancy().workspace_pk();
+        if workspace_pk != Some(*key_pair.workspace_pk()) {
+            return Err(FuncBindingReturnValueError::Unauthorized(
+                self.id,
+                *key_pair.workspace_pk(),
+                workspace_pk,
+            ));
+        }
+
+        let crypted = general_purpose::STANDARD_NO_PAD
+            .decode(crypted)
+            .map_err(|_| FuncBindingReturnValueError::DecryptionFailed(self.id))?;
+        let plaintext = sealedbox::open(&crypted, key_pair.public_key(), key_pair.secret_key())
+            .map_err(|_| FuncBindingReturnValueError::DecryptionFailed(self.id))?;
+        let values: SealedValues = serde_json::from_slice(&plaintext)?;
+
+        self.unprocessed_value = values.unprocessed_value;
+        self.value = values.value;
+        Ok(())
+    }
+
+    /// Gets many [`FuncBindingReturnValues`](Self) by id in a single query. Ids which are not
+    /// visible are left out.
+    pub async fn list_by_ids(
+        ctx: &DalContext,
+        ids: &[FuncBindingReturnValueId],
+    ) -> FuncBindingReturnValueResult<Vec<Self>> {
+        let ids: Vec<&FuncBindingReturnValueId> = ids.iter().collect();
+        Ok(standard_model::find_by_attr_in(ctx, "func_binding_return_values", "id", &ids).await?)
+    }
+
     pub async fn get_output_stream(
         &self,
         ctx: &DalContext,
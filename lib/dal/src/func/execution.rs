@@ -7,7 +7,7 @@ use thiserror::Error;
 use tokio::sync::mpsc::Receiver;
 use veritech_client::{FunctionResultFailure, OutputStream};
 
-use crate::standard_model::object_from_row;
+use crate::standard_model::{object_from_row, option_object_from_row};
 use crate::{
     pk, DalContext, Func, FuncBackendKind, FuncBackendResponseType, HistoryEventError,
     StandardModel, StandardModelError, Timestamp,
@@ -38,6 +38,9 @@ pub enum FuncExecutionError {
 
 pub type FuncExecutionResult<T> = Result<T, FuncExecutionError>;
 
+const FIND_LATEST_FOR_FUNC_BINDING: &str =
+    include_str!("../queries/func_execution/find_latest_for_func_binding.sql");
+
 pk!(FuncExecutionPk);
 
 // Are these the right states? -- Adam
@@ -61,6 +64,9 @@ pub enum FuncExecutionState {
 /// It's not part of the [`standard model`](crate::standard_model) as it doesn't participate in
 /// [`change sets`](crate::ChangeSet), and is only used for reference. Essentially, this is the
 /// [`Func`](crate::Func) equivalent of a [`HistoryEvent`](crate::HistoryEvent).
+///
+/// The arguments, handler and code recorded here are everything the execution was dispatched with,
+/// which lets [`FuncBinding::replay()`](crate::FuncBinding::replay) execute it again later.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct FuncExecution {
     pk: FuncExecutionPk,
@@ -73,6 +79,7 @@ pub struct FuncExecution {
     func_binding_return_value_id: Option<FuncBindingReturnValueId>,
     handler: Option<String>,
     code_base64: Option<String>,
+    code_sha256: String,
     unprocessed_value: Option<serde_json::Value>,
     value: Option<serde_json::Value>,
     output_stream: Option<Vec<OutputStream>>,
@@ -207,6 +214,15 @@ impl FuncExecution {
         ctx: &DalContext,
         func_binding_return_value: &FuncBindingReturnValue,
     ) -> FuncExecutionResult<()> {
+        // Sealed values are only stored, encrypted, on the return value itself.
+        let (value, unprocessed_value) = if func_binding_return_value.is_sensitive() {
+            (None, None)
+        } else {
+            (
+                func_binding_return_value.value(),
+                func_binding_return_value.unprocessed_value(),
+            )
+        };
         let row = ctx
             .txns()
             .await?
@@ -216,8 +232,8 @@ impl FuncExecution {
                 &[
                     &self.pk,
                     &func_binding_return_value.id(),
-                    &func_binding_return_value.value(),
-                    &func_binding_return_value.unprocessed_value(),
+                    &value,
+                    &unprocessed_value,
                 ],
             )
             .await?;
@@ -253,6 +269,24 @@ impl FuncExecution {
         Ok(object)
     }
 
+    /// Finds the most recent execution of a [`FuncBinding`](crate::FuncBinding), if it has been
+    /// executed.
+    pub async fn find_latest_for_func_binding(
+        ctx: &DalContext,
+        func_binding_id: FuncBindingId,
+    ) -> FuncExecutionResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                FIND_LATEST_FOR_FUNC_BINDING,
+                &[ctx.tenancy(), &func_binding_id],
+            )
+            .await?;
+        Ok(option_object_from_row(maybe_row)?)
+    }
+
     pub async fn get_latest_execution_by_func_id(
         ctx: &DalContext,
         func_id: &FuncId,
@@ -269,6 +303,32 @@ impl FuncExecution {
         Ok(object_from_row(row)?)
     }
 
+    pub fn func_binding_args(&self) -> &serde_json::Value {
+        &self.func_binding_args
+    }
+
+    pub fn backend_kind(&self) -> FuncBackendKind {
+        self.backend_kind
+    }
+
+    pub fn backend_response_type(&self) -> FuncBackendResponseType {
+        self.backend_response_type
+    }
+
+    pub fn handler(&self) -> Option<&str> {
+        self.handler.as_deref()
+    }
+
+    pub fn code_base64(&self) -> Option<&str> {
+        self.code_base64.as_deref()
+    }
+
+    /// The hash of the code the execution ran with, comparable to
+    /// [`Func::code_sha256()`](crate::Func::code_sha256).
+    pub fn code_sha256(&self) -> &str {
+        &self.code_sha256
+    }
+
     pub fn func_binding_return_value_id(&self) -> Option<FuncBindingReturnValueId> {
         self.func_binding_return_value_id
     }
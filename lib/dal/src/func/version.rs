@@ -0,0 +1,358 @@
+//! This module contains [`FuncVersion`], an immutable snapshot of the code of a [`Func`], and
+//! [`FuncVersionPin`], which rolls a version out to the [`Components`](crate::Component) of a
+//! [`SchemaVariant`](crate::SchemaVariant), of a [`Workspace`](crate::Workspace), or both.
+//!
+//! Without a pin, a [`Func`] executes its current code, so updating a builtin changes the values
+//! of every [`Component`](crate::Component) at once. To roll out a change gradually, pin
+//! everyone to the version of the current code before changing it, snapshot the new code as
+//! another version, [`compare`](FuncVersion::compare) the two, and then pin schema variants and
+//! workspaces to the new version one at a time. Pins take effect the next time a value is
+//! computed: values computed before the pin keep the result of the version they were computed
+//! with.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, AttributeValue, AttributeValueId, ComponentId,
+    DalContext, Func, FuncBackendResponseType, FuncBinding, FuncBindingError, FuncId,
+    SchemaVariantId, StandardModel, StandardModelError, Timestamp, TransactionsError, WorkspacePk,
+};
+
+const GET: &str = include_str!("../queries/func_version/get.sql");
+const LIST_ATTRIBUTE_VALUES_FOR_FUNC: &str =
+    include_str!("../queries/func_version/list_attribute_values_for_func.sql");
+const LIST_FOR_FUNC: &str = include_str!("../queries/func_version/list_for_func.sql");
+const LIST_PINS_FOR_FUNC: &str = include_str!("../queries/func_version/list_pins_for_func.sql");
+const PINNED_FOR_COMPONENT: &str = include_str!("../queries/func_version/pinned_for_component.sql");
+const UNPIN: &str = include_str!("../queries/func_version/unpin.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum FuncVersionError {
+    #[error("func binding error: {0}")]
+    FuncBinding(#[from] FuncBindingError),
+    #[error("func binding not found for attribute value: {0}")]
+    FuncBindingNotFound(AttributeValueId),
+    #[error("func not found: {0}")]
+    FuncNotFound(FuncId),
+    #[error("func {0} has no version {1}")]
+    NotFound(FuncId, i64),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type FuncVersionResult<T> = Result<T, FuncVersionError>;
+
+pk!(FuncVersionPk);
+pk!(FuncVersionPinPk);
+
+/// An immutable snapshot of the code of a [`Func`]. Versions are numbered from 1 for each
+/// [`Func`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FuncVersion {
+    pk: FuncVersionPk,
+    func_id: FuncId,
+    version: i64,
+    backend_response_type: FuncBackendResponseType,
+    handler: Option<String>,
+    code_base64: Option<String>,
+    code_sha256: String,
+    created_at: DateTime<Utc>,
+}
+
+/// Pins a [`Func`] to a [`FuncVersion`] for a [`SchemaVariant`](crate::SchemaVariant), a
+/// [`Workspace`](crate::Workspace), both, or everyone if neither is given. The most specific pin
+/// wins: the schema variant and workspace pin, then the workspace pin, then the schema variant
+/// pin.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FuncVersionPin {
+    pk: FuncVersionPinPk,
+    func_id: FuncId,
+    schema_variant_id: Option<SchemaVariantId>,
+    workspace_pk: Option<WorkspacePk>,
+    version: i64,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+/// The results of two versions of a [`Func`] for the values it computes in a
+/// [`Workspace`](crate::Workspace).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncVersionComparison {
+    pub func_id: FuncId,
+    /// The version compared from, or `None` for the current code of the [`Func`].
+    pub from_version: Option<i64>,
+    pub to_version: i64,
+    /// How many values the versions computed differently.
+    pub changed_count: usize,
+    pub values: Vec<FuncVersionComparisonValue>,
+}
+
+/// The results of two versions of a [`Func`] for one value, computed with the arguments the
+/// value was last computed with.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncVersionComparisonValue {
+    pub component_id: ComponentId,
+    pub schema_variant_id: SchemaVariantId,
+    pub attribute_value_id: AttributeValueId,
+    pub from_value: Option<serde_json::Value>,
+    pub from_failure: Option<String>,
+    pub to_value: Option<serde_json::Value>,
+    pub to_failure: Option<String>,
+    pub changed: bool,
+}
+
+impl FuncVersion {
+    pub fn pk(&self) -> FuncVersionPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(func_id, FuncId);
+    standard_model_accessor_ro!(version, i64);
+    standard_model_accessor_ro!(backend_response_type, FuncBackendResponseType);
+    standard_model_accessor_ro!(handler, Option<String>);
+    standard_model_accessor_ro!(code_base64, Option<String>);
+    standard_model_accessor_ro!(code_sha256, String);
+    standard_model_accessor_ro!(created_at, DateTime<Utc>);
+
+    /// Snapshots the current code of the [`Func`], as found in the tenancy of the
+    /// [`DalContext`], as its next version.
+    #[instrument(skip(ctx))]
+    pub async fn new(ctx: &DalContext, func_id: FuncId) -> FuncVersionResult<Self> {
+        let func = Func::get_by_id(ctx, &func_id)
+            .await?
+            .ok_or(FuncVersionError::FuncNotFound(func_id))?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM func_version_create_v1($1, $2, $3, $4, $5)",
+                &[
+                    &func_id,
+                    &func.backend_response_type().as_ref(),
+                    &func.handler(),
+                    &func.code_base64(),
+                    &func.code_sha256(),
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    pub async fn get(
+        ctx: &DalContext,
+        func_id: FuncId,
+        version: i64,
+    ) -> FuncVersionResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET, &[&func_id, &version])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the versions of the [`Func`], oldest first.
+    pub async fn list_for_func(ctx: &DalContext, func_id: FuncId) -> FuncVersionResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_FUNC, &[&func_id])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Finds the version the [`Func`] is pinned to for a [`Component`](crate::Component) in the
+    /// workspace of the current tenancy, if any.
+    pub async fn pinned_for_component(
+        ctx: &DalContext,
+        func_id: FuncId,
+        component_id: ComponentId,
+    ) -> FuncVersionResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                PINNED_FOR_COMPONENT,
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &func_id,
+                    &component_id,
+                    &ctx.tenancy().workspace_pk(),
+                ],
+            )
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Replaces the code of a copy of the [`Func`] with the code of this version.
+    pub(crate) fn apply_to(&self, func: &mut Func) {
+        func.backend_response_type = self.backend_response_type;
+        func.handler = self.handler.clone();
+        func.code_base64 = self.code_base64.clone();
+        func.code_sha256 = self.code_sha256.clone();
+    }
+
+    /// Executes two versions of the [`Func`] for every value it computes in the change set of the
+    /// [`DalContext`], optionally only for the [`Components`](crate::Component) of a
+    /// [`SchemaVariant`](crate::SchemaVariant), and reports which results would change. Each
+    /// value is computed with the arguments it was last computed with.
+    ///
+    /// Like [`FuncBinding::replay()`], nothing is recorded, so the values are left untouched.
+    #[instrument(skip(ctx))]
+    pub async fn compare(
+        ctx: &DalContext,
+        func_id: FuncId,
+        from_version: Option<i64>,
+        to_version: i64,
+        schema_variant_id: Option<SchemaVariantId>,
+    ) -> FuncVersionResult<FuncVersionComparison> {
+        let func = Func::get_by_id(ctx, &func_id)
+            .await?
+            .ok_or(FuncVersionError::FuncNotFound(func_id))?;
+        let mut from_func = func.clone();
+        if let Some(from_version) = from_version {
+            Self::get(ctx, func_id, from_version)
+                .await?
+                .ok_or(FuncVersionError::NotFound(func_id, from_version))?
+                .apply_to(&mut from_func);
+        }
+        let mut to_func = func;
+        Self::get(ctx, func_id, to_version)
+            .await?
+            .ok_or(FuncVersionError::NotFound(func_id, to_version))?
+            .apply_to(&mut to_func);
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_ATTRIBUTE_VALUES_FOR_FUNC,
+                &[ctx.tenancy(), ctx.visibility(), &func_id],
+            )
+            .await?;
+
+        let mut values = Vec::new();
+        for row in rows {
+            let value_schema_variant_id: SchemaVariantId = row.try_get("schema_variant_id")?;
+            if schema_variant_id.map_or(false, |filter| filter != value_schema_variant_id) {
+                continue;
+            }
+            let attribute_value: AttributeValue = serde_json::from_value(row.try_get("object")?)?;
+            let func_binding = FuncBinding::get_by_id(ctx, &attribute_value.func_binding_id())
+                .await?
+                .ok_or(FuncVersionError::FuncBindingNotFound(*attribute_value.id()))?;
+
+            let (from_value, from_failure, _) = func_binding
+                .execute_unrecorded(ctx, from_func.clone())
+                .await?;
+            let (to_value, to_failure, _) = func_binding
+                .execute_unrecorded(ctx, to_func.clone())
+                .await?;
+
+            values.push(FuncVersionComparisonValue {
+                component_id: attribute_value.context.component_id(),
+                schema_variant_id: value_schema_variant_id,
+                attribute_value_id: *attribute_value.id(),
+                changed: from_value != to_value || from_failure != to_failure,
+                from_value,
+                from_failure,
+                to_value,
+                to_failure,
+            });
+        }
+
+        Ok(FuncVersionComparison {
+            func_id,
+            from_version,
+            to_version,
+            changed_count: values.iter().filter(|value| value.changed).count(),
+            values,
+        })
+    }
+}
+
+impl FuncVersionPin {
+    pub fn pk(&self) -> FuncVersionPinPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(func_id, FuncId);
+    standard_model_accessor_ro!(schema_variant_id, Option<SchemaVariantId>);
+    standard_model_accessor_ro!(workspace_pk, Option<WorkspacePk>);
+    standard_model_accessor_ro!(version, i64);
+
+    /// Pins the [`Func`] to a version for a schema variant, a workspace, both, or everyone if
+    /// neither is given, replacing the version previously pinned for the same scope.
+    #[instrument(skip(ctx))]
+    pub async fn set(
+        ctx: &DalContext,
+        func_id: FuncId,
+        schema_variant_id: Option<SchemaVariantId>,
+        workspace_pk: Option<WorkspacePk>,
+        version: i64,
+    ) -> FuncVersionResult<Self> {
+        if FuncVersion::get(ctx, func_id, version).await?.is_none() {
+            return Err(FuncVersionError::NotFound(func_id, version));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM func_version_pin_set_v1($1, $2, $3, $4)",
+                &[&func_id, &schema_variant_id, &workspace_pk, &version],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    /// Removes the pin of the [`Func`] for a scope, returning whether there was one. The scope
+    /// falls back to a less specific pin, or to the current code of the [`Func`].
+    #[instrument(skip(ctx))]
+    pub async fn unset(
+        ctx: &DalContext,
+        func_id: FuncId,
+        schema_variant_id: Option<SchemaVariantId>,
+        workspace_pk: Option<WorkspacePk>,
+    ) -> FuncVersionResult<bool> {
+        let removed = ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(UNPIN, &[&func_id, &schema_variant_id, &workspace_pk])
+            .await?;
+        Ok(removed > 0)
+    }
+
+    /// Lists the pins of the [`Func`], the ones for every workspace first.
+    pub async fn list_for_func(ctx: &DalContext, func_id: FuncId) -> FuncVersionResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_PINS_FOR_FUNC, &[&func_id])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+}
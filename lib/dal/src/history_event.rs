@@ -1,4 +1,6 @@
 use crate::{Tenancy, TransactionsError};
+use chrono::{DateTime, Duration, Utc};
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 use strum::Display as StrumDisplay;
 use thiserror::Error;
@@ -7,13 +9,15 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 
-use crate::{pk, DalContext, Timestamp, UserPk};
+use crate::{pk, standard_model, ApiTokenPk, DalContext, Timestamp, UserPk};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum HistoryEventError {
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("error serializing/deserializing json: {0}")]
@@ -24,9 +28,22 @@ pub enum HistoryEventError {
 
 pub type HistoryEventResult<T> = Result<T, HistoryEventError>;
 
+const HISTORY_EVENT_LIST: &str = include_str!("queries/history_event/list.sql");
+
+/// The default number of events returned in a single page of [`HistoryEvent::list`].
+pub const DEFAULT_HISTORY_EVENT_PAGE_SIZE: u32 = 50;
+
+/// The maximum number of events that can be returned in a single page of [`HistoryEvent::list`].
+pub const MAX_HISTORY_EVENT_PAGE_SIZE: u32 = 500;
+
 #[remain::sorted]
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, StrumDisplay, Clone, Copy)]
 pub enum HistoryActor {
+    /// Changes made through an [`ApiToken`](crate::ApiToken), on behalf of the user who owns it.
+    ApiToken {
+        pk: ApiTokenPk,
+        user_pk: UserPk,
+    },
     SystemInit,
     User(UserPk),
 }
@@ -34,10 +51,20 @@ pub enum HistoryActor {
 impl HistoryActor {
     pub fn distinct_id(&self) -> String {
         match self {
-            HistoryActor::User(pk) => pk.to_string(),
+            HistoryActor::ApiToken { user_pk, .. } | HistoryActor::User(user_pk) => {
+                user_pk.to_string()
+            }
             HistoryActor::SystemInit => "unknown-backend".to_string(),
         }
     }
+
+    /// Returns the user the changes are made by or on behalf of, if any.
+    pub fn user_pk(&self) -> Option<UserPk> {
+        match self {
+            HistoryActor::ApiToken { user_pk, .. } | HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        }
+    }
 }
 
 impl From<UserPk> for HistoryActor {
@@ -64,6 +91,57 @@ pub struct HistoryEvent {
     pub timestamp: Timestamp,
 }
 
+/// Filters applied when listing history events. Every field is optional and filters are combined.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventFilter {
+    pub actor: Option<HistoryActor>,
+    /// Only include events about this kind of entity, such as `"component"` or `"edge"`: the
+    /// `history_event_label_base` of a [`StandardModel`](crate::StandardModel).
+    pub entity_type: Option<String>,
+    /// Only include events about the entity with this id or pk.
+    pub entity_id: Option<String>,
+    /// Only include events created at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only include events created before this time.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A page of history events, ordered from newest to oldest.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventPage {
+    pub events: Vec<HistoryEvent>,
+    /// Pass this as the cursor to fetch the next page. `None` when there are no more events.
+    pub next_cursor: Option<HistoryEventPk>,
+}
+
+/// How long history events are kept for, and whether they are archived rather than deleted once
+/// they are pruned. [`AuditLog`](crate::AuditLog) entries follow their own
+/// [`AuditRetentionPolicy`](crate::AuditRetentionPolicy).
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct HistoryEventRetentionPolicy {
+    pub max_age_days: u32,
+    pub archive: bool,
+}
+
+impl Default for HistoryEventRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: 90,
+            archive: true,
+        }
+    }
+}
+
+impl HistoryEventRetentionPolicy {
+    /// Returns the time before which events are pruned, relative to `now`.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.max_age_days.into())
+    }
+}
+
 impl HistoryEvent {
     #[instrument(skip(ctx, label, message))]
     pub async fn new(
@@ -89,4 +167,112 @@ impl HistoryEvent {
         let object: HistoryEvent = serde_json::from_value(json)?;
         Ok(object)
     }
+
+    /// Lists a page of history events in the workspace of the current tenancy, newest first.
+    /// Pass the `next_cursor` of a previous page to continue from where it left off.
+    #[instrument(skip(ctx))]
+    pub async fn list(
+        ctx: &DalContext,
+        filter: &HistoryEventFilter,
+        cursor: Option<HistoryEventPk>,
+        page_size: Option<u32>,
+    ) -> HistoryEventResult<HistoryEventPage> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(HistoryEventError::NoWorkspaceInTenancy)?;
+        let page_size = page_size
+            .unwrap_or(DEFAULT_HISTORY_EVENT_PAGE_SIZE)
+            .clamp(1, MAX_HISTORY_EVENT_PAGE_SIZE);
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+
+        // Fetch one extra row to learn whether there is another page
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                HISTORY_EVENT_LIST,
+                &[
+                    &workspace_pk,
+                    &actor,
+                    &filter.entity_type,
+                    &filter.entity_id,
+                    &filter.from,
+                    &filter.to,
+                    &cursor,
+                    &(i64::from(page_size) + 1),
+                ],
+            )
+            .await?;
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            events.push(serde_json::from_value::<Self>(json)?);
+        }
+
+        let next_cursor = if events.len() > page_size as usize {
+            events.truncate(page_size as usize);
+            events.last().map(|event| event.pk)
+        } else {
+            None
+        };
+
+        Ok(HistoryEventPage {
+            events,
+            next_cursor,
+        })
+    }
+
+    /// Streams every history event in the workspace of the current tenancy matching the filter,
+    /// newest first, fetching them from the database in batches. Pass the pk of the last event
+    /// received to continue after it.
+    #[instrument(skip(ctx))]
+    pub async fn stream(
+        ctx: &DalContext,
+        filter: &HistoryEventFilter,
+        cursor: Option<HistoryEventPk>,
+    ) -> HistoryEventResult<impl Stream<Item = HistoryEventResult<Self>> + Send + 'static> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(HistoryEventError::NoWorkspaceInTenancy)?;
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+
+        standard_model::stream_objects(
+            ctx,
+            HISTORY_EVENT_LIST,
+            &[
+                &workspace_pk,
+                &actor,
+                &filter.entity_type,
+                &filter.entity_id,
+                &filter.from,
+                &filter.to,
+                &cursor,
+                &None::<i64>,
+            ],
+        )
+        .await
+    }
+
+    /// Removes history events older than the retention policy allows, archiving them if the
+    /// policy says so, and returns the number of events removed. An empty tenancy prunes events
+    /// across all workspaces.
+    #[instrument(skip(ctx))]
+    pub async fn prune(
+        ctx: &DalContext,
+        policy: HistoryEventRetentionPolicy,
+    ) -> HistoryEventResult<i64> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT pruned FROM history_event_prune_v1($1, $2, $3)",
+                &[ctx.tenancy(), &policy.cutoff(ctx.now()), &policy.archive],
+            )
+            .await?;
+        Ok(row.try_get("pruned")?)
+    }
 }
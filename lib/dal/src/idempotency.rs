@@ -0,0 +1,157 @@
+//! This module contains [`IdempotencyRecord`], the stored response of a mutating request made
+//! with a client-supplied idempotency key. Retrying the request with the same key returns the
+//! stored response instead of repeating the mutation.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, DalContext, StandardModelError, Timestamp,
+    TransactionsError, WorkspacePk,
+};
+
+const FIND_UNEXPIRED: &str = include_str!("queries/idempotency/find_unexpired.sql");
+
+/// How long a stored response is returned for retries of the same request.
+const IDEMPOTENCY_RECORD_LIFETIME_HOURS: i64 = 24;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum IdempotencyError {
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type IdempotencyResult<T> = Result<T, IdempotencyError>;
+
+pk!(IdempotencyRecordPk);
+
+/// The response of a request, stored under the idempotency key and endpoint it was made with in
+/// the workspace of the current tenancy.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    pk: IdempotencyRecordPk,
+    workspace_pk: WorkspacePk,
+    idempotency_key: String,
+    endpoint: String,
+    response: serde_json::Value,
+    expires_at: DateTime<Utc>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl IdempotencyRecord {
+    pub fn pk(&self) -> IdempotencyRecordPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(idempotency_key, String);
+    standard_model_accessor_ro!(endpoint, String);
+    standard_model_accessor_ro!(response, serde_json::Value);
+    standard_model_accessor_ro!(expires_at, DateTime<Utc>);
+
+    /// Finds the unexpired record stored for an idempotency key and endpoint, if any.
+    #[instrument(skip(ctx))]
+    pub async fn find(
+        ctx: &DalContext,
+        idempotency_key: &str,
+        endpoint: &str,
+    ) -> IdempotencyResult<Option<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(IdempotencyError::NoWorkspaceInTenancy)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                FIND_UNEXPIRED,
+                &[&workspace_pk, &endpoint, &idempotency_key],
+            )
+            .await?;
+
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Finds the response stored for an idempotency key and endpoint, if any.
+    pub async fn find_response<T: DeserializeOwned>(
+        ctx: &DalContext,
+        idempotency_key: &str,
+        endpoint: &str,
+    ) -> IdempotencyResult<Option<T>> {
+        Self::find(ctx, idempotency_key, endpoint)
+            .await?
+            .map(|record| serde_json::from_value(record.response))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Stores the response for an idempotency key and endpoint, pruning the expired records of
+    /// the workspace along the way.
+    ///
+    /// If a concurrent request with the same key stored its response first, that record is
+    /// returned unchanged; compare [`Self::response`] against the given response to tell.
+    #[instrument(skip(ctx, response))]
+    pub async fn store(
+        ctx: &DalContext,
+        idempotency_key: &str,
+        endpoint: &str,
+        response: &serde_json::Value,
+    ) -> IdempotencyResult<Self> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(IdempotencyError::NoWorkspaceInTenancy)?;
+        let expires_at = ctx.now() + Duration::hours(IDEMPOTENCY_RECORD_LIFETIME_HOURS);
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM idempotency_record_store_v1($1, $2, $3, $4, $5)",
+                &[
+                    &workspace_pk,
+                    &idempotency_key,
+                    &endpoint,
+                    response,
+                    &expires_at,
+                ],
+            )
+            .await?;
+
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    /// Stores the response for an idempotency key and endpoint. Returns the response of a
+    /// concurrent request which stored its response first, if any, in which case the caller
+    /// should roll back its own changes and return that response instead.
+    pub async fn store_response<T: Serialize + DeserializeOwned>(
+        ctx: &DalContext,
+        idempotency_key: &str,
+        endpoint: &str,
+        response: &T,
+    ) -> IdempotencyResult<Option<T>> {
+        let response = serde_json::to_value(response)?;
+        let record = Self::store(ctx, idempotency_key, endpoint, &response).await?;
+
+        if record.response == response {
+            Ok(None)
+        } else {
+            Ok(Some(serde_json::from_value(record.response)?))
+        }
+    }
+}
@@ -4,15 +4,20 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use si_data_nats::NatsError;
 use si_data_pg::PgPoolError;
+use telemetry::propagation::TraceContext;
 use thiserror::Error;
 use tokio::task::JoinError;
 
 use crate::{
-    fix::FixError, func::binding_return_value::FuncBindingReturnValueError,
-    job::producer::BlockingJobError, job::producer::JobProducerError, status::StatusUpdaterError,
+    fix::{plan::FixPlanError, FixError},
+    func::binding_return_value::FuncBindingReturnValueError,
+    job::producer::BlockingJobError,
+    job::producer::JobProducerError,
+    status::StatusUpdaterError,
     AccessBuilder, ActionPrototypeError, ActionPrototypeId, AttributeValueError, ComponentError,
-    ComponentId, DalContext, DalContextBuilder, FixBatchId, FixResolverError, StandardModelError,
-    TransactionsError, Visibility, WsEventError,
+    ComponentId, ComponentLifecycleError, DalContext, DalContextBuilder, FixBatchId,
+    FixResolverError, StandardModelError, TransactionsError, UsageError, Visibility, WebhookError,
+    WsEventError,
 };
 
 #[remain::sorted]
@@ -32,6 +37,8 @@ pub enum JobConsumerError {
     BlockingJob(#[from] BlockingJobError),
     #[error(transparent)]
     Component(#[from] ComponentError),
+    #[error(transparent)]
+    ComponentLifecycle(#[from] ComponentLifecycleError),
     #[error("component {0} not found")]
     ComponentNotFound(ComponentId),
     #[error(transparent)]
@@ -41,6 +48,8 @@ pub enum JobConsumerError {
     #[error(transparent)]
     Fix(#[from] FixError),
     #[error(transparent)]
+    FixPlan(#[from] FixPlanError),
+    #[error(transparent)]
     FixResolver(#[from] FixResolverError),
     #[error(transparent)]
     FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
@@ -75,6 +84,10 @@ pub enum JobConsumerError {
     #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
     #[error(transparent)]
+    Usage(#[from] UsageError),
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
+    #[error(transparent)]
     WsEvent(#[from] WsEventError),
 }
 
@@ -95,6 +108,10 @@ pub struct JobInfo {
     pub access_builder: AccessBuilder,
     pub visibility: Visibility,
     pub blocking: bool,
+    /// The trace context of the span which dispatched the job, which the span processing it
+    /// continues.
+    #[serde(default)]
+    pub trace_context: TraceContext,
 }
 
 #[async_trait]
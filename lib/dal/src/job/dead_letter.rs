@@ -0,0 +1,199 @@
+//! This module contains [`DeadLetteredJob`], a job which failed when `pinga` executed it. Rather
+//! than vanishing, the job is kept along with what it failed with, so that it can be looked into
+//! and replayed once whatever made it fail has been dealt with.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::job::consumer::JobInfo;
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, DalContext, StandardModelError, Timestamp,
+    TransactionsError, WorkspacePk,
+};
+
+const DEAD_LETTERED_JOB_GET_BY_PK: &str =
+    include_str!("../queries/dead_lettered_job/get_by_pk.sql");
+const DEAD_LETTERED_JOB_LIST_ALL: &str = include_str!("../queries/dead_lettered_job/list_all.sql");
+const DEAD_LETTERED_JOB_LIST_FOR_WORKSPACE: &str =
+    include_str!("../queries/dead_lettered_job/list_for_workspace.sql");
+const DEAD_LETTERED_JOB_STATS: &str = include_str!("../queries/dead_lettered_job/stats.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum DeadLetteredJobError {
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type DeadLetteredJobResult<T> = Result<T, DeadLetteredJobError>;
+
+pk!(DeadLetteredJobPk);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DeadLetteredJob {
+    pk: DeadLetteredJobPk,
+    workspace_pk: Option<WorkspacePk>,
+    job_id: String,
+    kind: String,
+    job_info: JobInfo,
+    error: String,
+    attempts: i64,
+    first_failed_at: DateTime<Utc>,
+    last_failed_at: DateTime<Utc>,
+    replayed_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+/// How many jobs of a kind were dead lettered, across every workspace.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetteredJobStats {
+    pub kind: String,
+    pub total: i64,
+    /// How many of the jobs were not replayed yet.
+    pub not_replayed: i64,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+impl DeadLetteredJob {
+    pub fn pk(&self) -> DeadLetteredJobPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, Option<WorkspacePk>);
+    standard_model_accessor_ro!(job_id, String);
+    standard_model_accessor_ro!(kind, String);
+    standard_model_accessor_ro!(job_info, JobInfo);
+    standard_model_accessor_ro!(error, String);
+    standard_model_accessor_ro!(attempts, i64);
+    standard_model_accessor_ro!(first_failed_at, DateTime<Utc>);
+    standard_model_accessor_ro!(last_failed_at, DateTime<Utc>);
+    standard_model_accessor_ro!(replayed_at, Option<DateTime<Utc>>);
+
+    /// Dead letters a job which failed, in the workspace of the given context. A replayed job
+    /// which fails again is recorded against its existing [`DeadLetteredJob`], counting one more
+    /// attempt.
+    #[instrument(skip_all)]
+    pub async fn record(
+        ctx: &DalContext,
+        job_info: &JobInfo,
+        error: impl AsRef<str>,
+    ) -> DeadLetteredJobResult<Self> {
+        let error = error.as_ref();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM dead_lettered_job_record_v1($1, $2, $3)",
+                &[
+                    &ctx.tenancy().workspace_pk(),
+                    &serde_json::to_value(job_info)?,
+                    &error,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    pub async fn get_by_pk(
+        ctx: &DalContext,
+        pk: DeadLetteredJobPk,
+    ) -> DeadLetteredJobResult<Option<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(DeadLetteredJobError::NoWorkspaceInTenancy)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(DEAD_LETTERED_JOB_GET_BY_PK, &[&pk, &workspace_pk])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the [`DeadLetteredJobs`](Self) of the workspace, most recently failed first.
+    pub async fn list(ctx: &DalContext) -> DeadLetteredJobResult<Vec<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(DeadLetteredJobError::NoWorkspaceInTenancy)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(DEAD_LETTERED_JOB_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Lists the [`DeadLetteredJobs`](Self) of every workspace, or of the given one, most recently
+    /// failed first, regardless of the tenancy of the context.
+    pub async fn list_all(
+        ctx: &DalContext,
+        workspace_pk: Option<WorkspacePk>,
+    ) -> DeadLetteredJobResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(DEAD_LETTERED_JOB_LIST_ALL, &[&workspace_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Counts the dead lettered jobs of every workspace, by kind.
+    pub async fn stats(ctx: &DalContext) -> DeadLetteredJobResult<Vec<DeadLetteredJobStats>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(DEAD_LETTERED_JOB_STATS, &[])
+            .await?;
+
+        let mut stats = Vec::with_capacity(rows.len());
+        for row in rows {
+            stats.push(DeadLetteredJobStats {
+                kind: row.try_get("kind")?,
+                total: row.try_get("total")?,
+                not_replayed: row.try_get("not_replayed")?,
+                last_failed_at: row.try_get("last_failed_at")?,
+            });
+        }
+        Ok(stats)
+    }
+
+    /// Enqueues the job again, with the same id, arguments, tenancy and visibility as when it
+    /// failed. The job is dispatched when the context is committed.
+    #[instrument(skip_all)]
+    pub async fn replay(&mut self, ctx: &DalContext) -> DeadLetteredJobResult<()> {
+        ctx.enqueue_job(Box::new(self.job_info.clone())).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM dead_lettered_job_mark_replayed_v1($1)",
+                &[&self.pk],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,13 @@
 mod dependent_values_update;
 mod fix;
+mod garbage_collection;
 mod refresh;
+mod usage_aggregation;
+mod webhook_delivery;
 
 pub use dependent_values_update::DependentValuesUpdate;
 pub use fix::{FixItem, FixesJob};
+pub use garbage_collection::{GarbageCollectionJob, DEFAULT_GARBAGE_COLLECTION_RETENTION_DAYS};
 pub use refresh::RefreshJob;
+pub use usage_aggregation::UsageAggregationJob;
+pub use webhook_delivery::WebhookDeliveryJob;
@@ -13,7 +13,7 @@ use crate::{
     },
     job::producer::{JobProducer, JobProducerResult},
     AccessBuilder, AttributeValue, AttributeValueError, AttributeValueId, AttributeValueResult,
-    DalContext, StandardModel, StatusUpdater, Visibility, WsEvent,
+    ChangeSet, Component, DalContext, StandardModel, StatusUpdater, Visibility, WsEvent,
 };
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -320,6 +320,27 @@ impl DependentValuesUpdate {
 
         status_updater.finish(ctx).await;
 
+        // The qualifications of the components may have run again, which moves their lifecycles
+        let mut component_ids = HashSet::new();
+        for attribute_value_id in original_dependency_graph.keys() {
+            if let Some(attribute_value) =
+                AttributeValue::get_by_id(ctx, attribute_value_id).await?
+            {
+                if !attribute_value.context.is_component_unset() {
+                    component_ids.insert(attribute_value.context.component_id());
+                }
+            }
+        }
+        for component_id in component_ids {
+            if let Err(err) = Component::record_qualifications_lifecycle(ctx, component_id).await {
+                warn!(error = ?err, %component_id, "could not record component lifecycle");
+            }
+        }
+
+        if let Err(err) = ChangeSet::publish_qualification_summary(ctx).await {
+            warn!(error = ?err, "could not publish qualification summary");
+        }
+
         WsEvent::change_set_written(ctx)
             .await?
             .publish_on_commit(ctx)
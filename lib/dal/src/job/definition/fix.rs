@@ -12,8 +12,9 @@ use crate::{
         producer::{JobProducer, JobProducerResult},
     },
     AccessBuilder, ActionKind, ActionPrototype, ActionPrototypeId, AttributeValueId, Component,
-    ComponentId, DalContext, DependentValuesUpdate, Fix, FixBatch, FixBatchId, FixCompletionStatus,
-    FixId, FixResolver, RootPropChild, StandardModel, Visibility, WsEvent,
+    ComponentId, ComponentLifecycle, ComponentLifecycleEvent, DalContext, DependentValuesUpdate,
+    Fix, FixBatch, FixBatchId, FixCompletionStatus, FixId, FixPlan, FixResolver, RootPropChild,
+    StandardModel, Visibility, WsEvent,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,11 +134,27 @@ impl JobConsumer for FixesJob {
         let mut fix = Fix::get_by_id(ctx, &fix_item.id)
             .await?
             .ok_or(FixError::MissingFix(fix_item.id))?;
+
+        // Commit the lifecycle change, so that the component shows as applying while the fix runs
+        ComponentLifecycle::record(
+            ctx,
+            fix_item.component_id,
+            ComponentLifecycleEvent::ApplyStarted(*action.kind()),
+        )
+        .await?;
+        ctx.commit().await?;
+
         let resource = fix.run(ctx, &action).await?;
         let completion_status: FixCompletionStatus = *fix
             .completion_status()
             .ok_or(FixError::EmptyCompletionStatus)?;
 
+        let lifecycle_event = match completion_status {
+            FixCompletionStatus::Success => ComponentLifecycleEvent::ApplySucceeded(*action.kind()),
+            _ => ComponentLifecycleEvent::ApplyFailed(*action.kind()),
+        };
+        ComponentLifecycle::record(ctx, fix_item.component_id, lifecycle_event).await?;
+
         // Upsert the fix resolver.
         FixResolver::upsert(
             ctx,
@@ -234,6 +251,7 @@ async fn finish_batch(ctx: &DalContext, id: FixBatchId) -> JobConsumerResult<()>
         .await?
         .ok_or(JobConsumerError::MissingFixBatch(id))?;
     let batch_completion_status = batch.stamp_finished(ctx).await?;
+    FixPlan::fix_batch_finished(ctx, id, batch_completion_status).await?;
     WsEvent::fix_batch_return(ctx, *batch.id(), batch_completion_status)
         .await?
         .publish_on_commit(ctx)
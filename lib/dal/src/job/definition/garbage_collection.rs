@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    standard_model, AccessBuilder, DalContext, Visibility,
+};
+
+/// How long soft deleted objects are kept, so that they can still be restored, before
+/// [`GarbageCollectionJob`] hard deletes them.
+pub const DEFAULT_GARBAGE_COLLECTION_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct GarbageCollectionJobArgs {
+    retention_days: i64,
+}
+
+impl From<GarbageCollectionJob> for GarbageCollectionJobArgs {
+    fn from(value: GarbageCollectionJob) -> Self {
+        Self {
+            retention_days: value.retention_days,
+        }
+    }
+}
+
+/// Hard deletes the objects of a workspace which were soft deleted longer ago than the retention
+/// window.
+#[derive(Clone, Debug, Serialize)]
+pub struct GarbageCollectionJob {
+    retention_days: i64,
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl GarbageCollectionJob {
+    pub fn new(access_builder: AccessBuilder, retention_days: i64) -> Box<Self> {
+        Box::new(Self {
+            retention_days,
+            access_builder,
+            visibility: Visibility::new_head(false),
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for GarbageCollectionJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(GarbageCollectionJobArgs::from(
+            self.clone(),
+        ))?)
+    }
+}
+
+impl JobConsumerMetadata for GarbageCollectionJob {
+    fn type_name(&self) -> String {
+        "GarbageCollectionJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for GarbageCollectionJob {
+    #[instrument(
+        name = "garbage_collection_job.run",
+        skip_all,
+        level = "info",
+        fields(
+            retention_days = self.retention_days,
+        )
+    )]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        let deleted_before = ctx.now() - Duration::days(self.retention_days);
+        let hard_deleted = standard_model::garbage_collect(ctx, deleted_before).await?;
+        info!(hard_deleted, %deleted_before, "garbage collected soft deleted objects");
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for GarbageCollectionJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        let args = GarbageCollectionJobArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            retention_days: args.retention_days,
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
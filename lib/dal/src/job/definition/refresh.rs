@@ -91,6 +91,12 @@ impl JobConsumer for RefreshJob {
                 .ok_or(JobConsumerError::ComponentNotFound(*component_id))?;
             component.act(ctx, ActionKind::Refresh).await?;
 
+            // Components deleted from the model keep their resource until it is destroyed, but
+            // there is no model left to reconcile it with
+            if component.visibility().deleted_at.is_none() {
+                component.resolve_resource_conflicts(ctx).await?;
+            }
+
             WsEvent::resource_refreshed(ctx, *component.id())
                 .await?
                 .publish_on_commit(ctx)
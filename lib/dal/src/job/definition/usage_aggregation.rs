@@ -0,0 +1,89 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    AccessBuilder, DalContext, Usage, Visibility,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct UsageAggregationJobArgs {}
+
+impl From<UsageAggregationJob> for UsageAggregationJobArgs {
+    fn from(_value: UsageAggregationJob) -> Self {
+        Self {}
+    }
+}
+
+/// Sums up the [`Usage`] recorded so far, in every workspace, into hourly totals.
+#[derive(Clone, Debug, Serialize)]
+pub struct UsageAggregationJob {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl UsageAggregationJob {
+    pub fn new(access_builder: AccessBuilder) -> Box<Self> {
+        Box::new(Self {
+            access_builder,
+            visibility: Visibility::new_head(false),
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for UsageAggregationJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(UsageAggregationJobArgs::from(
+            self.clone(),
+        ))?)
+    }
+}
+
+impl JobConsumerMetadata for UsageAggregationJob {
+    fn type_name(&self) -> String {
+        "UsageAggregationJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for UsageAggregationJob {
+    #[instrument(name = "usage_aggregation_job.run", skip_all, level = "info")]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        let aggregated = Usage::aggregate(ctx, ctx.now()).await?;
+        info!(aggregated, "aggregated usage events");
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for UsageAggregationJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        UsageAggregationJobArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
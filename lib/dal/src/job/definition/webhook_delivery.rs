@@ -0,0 +1,106 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    AccessBuilder, DalContext, Visibility, WebhookDelivery, WebhookDeliveryPk,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct WebhookDeliveryJobArgs {
+    webhook_delivery_pk: WebhookDeliveryPk,
+}
+
+impl From<WebhookDeliveryJob> for WebhookDeliveryJobArgs {
+    fn from(value: WebhookDeliveryJob) -> Self {
+        Self {
+            webhook_delivery_pk: value.webhook_delivery_pk,
+        }
+    }
+}
+
+/// Makes one attempt at a [`WebhookDelivery`].
+#[derive(Clone, Debug, Serialize)]
+pub struct WebhookDeliveryJob {
+    webhook_delivery_pk: WebhookDeliveryPk,
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl WebhookDeliveryJob {
+    pub fn new(access_builder: AccessBuilder, webhook_delivery_pk: WebhookDeliveryPk) -> Box<Self> {
+        Box::new(Self {
+            webhook_delivery_pk,
+            access_builder,
+            visibility: Visibility::new_head(false),
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for WebhookDeliveryJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(WebhookDeliveryJobArgs::from(
+            self.clone(),
+        ))?)
+    }
+}
+
+impl JobConsumerMetadata for WebhookDeliveryJob {
+    fn type_name(&self) -> String {
+        "WebhookDeliveryJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for WebhookDeliveryJob {
+    #[instrument(
+        name = "webhook_delivery_job.run",
+        skip_all,
+        level = "info",
+        fields(
+            webhook_delivery_pk = %self.webhook_delivery_pk,
+        )
+    )]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        // The delivery is gone with its webhook if the webhook was deleted meanwhile
+        match WebhookDelivery::get_by_pk(ctx, self.webhook_delivery_pk).await? {
+            Some(mut delivery) => delivery.attempt(ctx).await?,
+            None => debug!("webhook delivery not found, skipping"),
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for WebhookDeliveryJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        let args = WebhookDeliveryJobArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            webhook_delivery_pk: args.webhook_delivery_pk,
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
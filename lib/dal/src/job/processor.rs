@@ -35,6 +35,11 @@ pub trait JobQueueProcessor: std::fmt::Debug + DynClone {
     ) -> BlockingJobResult;
     async fn process_queue(&self) -> JobQueueProcessorResult<()>;
     async fn blocking_process_queue(&self) -> JobQueueProcessorResult<()>;
+    /// Returns the number of jobs waiting to be dispatched.
+    async fn queue_len(&self) -> usize;
+    /// Discards the jobs which were enqueued after the first `len` ones, such as when rolling
+    /// back to a savepoint.
+    async fn truncate_queue(&self, len: usize);
 }
 
 dyn_clone::clone_trait_object!(JobQueueProcessor);
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use futures::StreamExt;
 use si_data_nats::NatsClient;
+use telemetry::metrics::Counter;
 use telemetry::prelude::*;
 use tokio::task::JoinSet;
 
@@ -17,6 +18,11 @@ use super::{JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult};
 
 const NATS_JOB_QUEUE: &str = "pinga-jobs";
 
+static JOBS_ENQUEUED: Counter = Counter::new(
+    "si_jobs_enqueued_total",
+    "Total number of jobs dispatched to pinga, by job kind",
+);
+
 #[derive(Clone, Debug)]
 pub struct NatsProcessor {
     client: NatsClient,
@@ -51,6 +57,7 @@ impl NatsProcessor {
                 error!("Nats job push failed, some jobs will be dropped");
                 return Err(JobQueueProcessorError::Transport(Box::new(err)));
             }
+            JOBS_ENQUEUED.increment(&[("job_kind", &job_info.kind)]);
         }
         Ok(())
     }
@@ -83,6 +90,7 @@ impl JobQueueProcessor for NatsProcessor {
             )
             .await
             .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+        JOBS_ENQUEUED.increment(&[("job_kind", &job_info.kind)]);
 
         match reply_subscription.next().await {
             Some(Ok(message)) => serde_json::from_slice::<BlockingJobResult>(message.data())
@@ -106,7 +114,10 @@ impl JobQueueProcessor for NatsProcessor {
         // Fan out, dispatching all queued jobs to pinga over nats.
         for job in jobs {
             let job_processor = Self::new(self.client.clone());
-            dispatched_jobs.spawn(async move { job_processor.block_on_job(job).await });
+            // Keep the current span, so the dispatched jobs continue its trace
+            dispatched_jobs.spawn(
+                async move { job_processor.block_on_job(job).await }.instrument(Span::current()),
+            );
         }
 
         let mut results = Vec::new();
@@ -154,4 +165,12 @@ impl JobQueueProcessor for NatsProcessor {
 
         Ok(())
     }
+
+    async fn queue_len(&self) -> usize {
+        self.queue.len().await
+    }
+
+    async fn truncate_queue(&self, len: usize) {
+        self.queue.truncate(len).await
+    }
 }
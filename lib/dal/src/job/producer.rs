@@ -1,9 +1,11 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use telemetry::propagation::TraceContext;
 use thiserror::Error;
 use ulid::Ulid;
 
 use super::consumer::{JobConsumerMetadata, JobInfo};
+use crate::{AccessBuilder, Visibility};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -18,6 +20,12 @@ pub type JobProducerResult<T> = Result<T, JobProducerError>;
 
 pub trait JobProducer: std::fmt::Debug + Send + JobConsumerMetadata {
     fn arg(&self) -> JobProducerResult<serde_json::Value>;
+
+    /// The id to dispatch the job with. Jobs get a new id unless they are being dispatched again,
+    /// such as when a [`DeadLetteredJob`](crate::job::dead_letter::DeadLetteredJob) is replayed.
+    fn job_id(&self) -> Option<String> {
+        None
+    }
 }
 
 pub type BlockingJobResult = Result<(), BlockingJobError>;
@@ -42,13 +50,16 @@ pub enum BlockingJobError {
 impl JobInfo {
     pub fn new(job_producer: Box<dyn JobProducer + Send + Sync>) -> JobProducerResult<Self> {
         Ok(Self {
-            id: Ulid::new().to_string(),
+            id: job_producer
+                .job_id()
+                .unwrap_or_else(|| Ulid::new().to_string()),
             kind: job_producer.type_name(),
             created_at: Utc::now(),
             arg: job_producer.arg()?,
             access_builder: job_producer.access_builder(),
             visibility: job_producer.visibility(),
             blocking: false,
+            trace_context: TraceContext::current(),
         })
     }
 
@@ -56,13 +67,40 @@ impl JobInfo {
         job_producer: Box<dyn JobProducer + Send + Sync>,
     ) -> JobProducerResult<Self> {
         Ok(Self {
-            id: Ulid::new().to_string(),
+            id: job_producer
+                .job_id()
+                .unwrap_or_else(|| Ulid::new().to_string()),
             kind: job_producer.type_name(),
             created_at: Utc::now(),
             arg: job_producer.arg()?,
             access_builder: job_producer.access_builder(),
             visibility: job_producer.visibility(),
             blocking: true,
+            trace_context: TraceContext::current(),
         })
     }
 }
+
+impl JobConsumerMetadata for JobInfo {
+    fn type_name(&self) -> String {
+        self.kind.clone()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+impl JobProducer for JobInfo {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(self.arg.clone())
+    }
+
+    fn job_id(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+}
@@ -28,6 +28,15 @@ impl JobQueue {
         std::mem::take(&mut *self.queue.lock().await)
     }
 
+    pub async fn len(&self) -> usize {
+        self.queue.lock().await.len()
+    }
+
+    /// Discards the jobs which were enqueued after the first `len` ones.
+    pub async fn truncate(&self, len: usize) {
+        self.queue.lock().await.truncate(len);
+    }
+
     pub async fn is_empty(&self) -> bool {
         self.queue.lock().await.is_empty()
     }
@@ -4,7 +4,6 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use rand::Rng;
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use si_data_nats::{NatsClient, NatsError};
 use si_data_pg::{PgError, PgPool, PgPoolError};
@@ -19,19 +18,30 @@ use crate::builtins::SelectedTestBuiltinSchemas;
 
 pub mod action_prototype;
 pub mod actor_view;
+pub mod api_token;
 pub mod attribute;
+pub mod audit_log;
+pub mod blob;
+pub mod blueprint;
 pub mod builtins;
 pub mod change_set;
 pub mod change_status;
+pub mod clock;
 pub mod code_view;
+pub mod comment;
 pub mod component;
 pub mod context;
 pub mod cyclone_key_pair;
+pub mod data_migration;
 pub mod diagram;
 pub mod edge;
+pub mod error_category;
+pub mod export;
+pub mod feature_flag;
 pub mod fix;
 pub mod func;
 pub mod history_event;
+pub mod idempotency;
 pub mod index_map;
 pub mod installed_pkg;
 pub mod job;
@@ -41,6 +51,7 @@ pub mod key_pair;
 pub mod label_list;
 pub mod node;
 pub mod node_menu;
+pub mod notification;
 pub mod pkg;
 pub mod prop;
 pub mod prop_tree;
@@ -49,27 +60,38 @@ pub mod prototype_context;
 pub mod prototype_list_for_func;
 pub mod provider;
 pub mod qualification;
+pub mod random;
 pub mod reconciliation_prototype;
+pub mod saved_view;
 pub mod schema;
 pub mod secret;
+pub mod session;
 pub mod socket;
 pub mod standard_accessors;
 pub mod standard_model;
 pub mod standard_pk;
 pub mod status;
+pub mod suggestion_prototype;
 pub mod tasks;
 pub mod tenancy;
 pub mod timestamp;
+pub mod usage;
 pub mod user;
 pub mod validation;
 pub mod visibility;
+pub mod webhook;
 pub mod workspace;
+pub mod workspace_backup;
+pub mod workspace_settings;
 pub mod ws_event;
 
 pub use action_prototype::{
     ActionKind, ActionPrototype, ActionPrototypeContext, ActionPrototypeError, ActionPrototypeId,
 };
 pub use actor_view::ActorView;
+pub use api_token::{
+    ApiToken, ApiTokenError, ApiTokenPk, ApiTokenResult, ApiTokenScope, API_TOKEN_PREFIX,
+};
 pub use attribute::value::view::AttributeView;
 pub use attribute::{
     context::{
@@ -84,27 +106,65 @@ pub use attribute::{
         AttributePrototype, AttributePrototypeError, AttributePrototypeId, AttributePrototypeResult,
     },
     value::{
+        provenance::{AttributeValueProvenance, ProvenanceFunc, ProvenanceInput},
         AttributeValue, AttributeValueError, AttributeValueId, AttributeValuePayload,
         AttributeValueResult,
     },
 };
-pub use builtins::{BuiltinsError, BuiltinsResult};
+pub use audit_log::{
+    AuditAction, AuditLog, AuditLogError, AuditLogFilter, AuditLogPage, AuditLogResult,
+    AuditRetentionPolicy, AuditTarget,
+};
+pub use blob::{Blob, BlobError, BlobHash, BlobResult};
+pub use blueprint::{
+    Blueprint, BlueprintComponent, BlueprintEdge, BlueprintError, BlueprintPk, BlueprintResult,
+    BlueprintSpec, BlueprintVariable, InstantiatedComponent,
+};
+pub use builtins::{migrate_builtins_only, Builtin, BuiltinsError, BuiltinsResult};
+pub use change_set::review::{
+    ChangeSetReview, ChangeSetReviewError, ChangeSetReviewPk, ChangeSetReviewResult,
+    ChangeSetReviewStatus,
+};
+pub use change_set::schedule::{
+    ChangeSetApplySchedule, ChangeSetApplyScheduleError, ChangeSetApplySchedulePk,
+    ChangeSetApplyScheduleResult, ChangeSetApplyScheduleStatus,
+};
 pub use change_set::{ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus};
+pub use clock::{Clock, SystemClock, TestClock};
 pub use code_view::{CodeLanguage, CodeView};
+pub use comment::{Comment, CommentError, CommentId, CommentPk, CommentResult, CommentTarget};
 pub use component::{
-    resource::ResourceView, status::ComponentStatus, status::HistoryActorTimestamp, Component,
-    ComponentError, ComponentId, ComponentView, ComponentViewProperties,
+    resource::ResourceHealth, resource::ResourceView, status::ComponentStatus,
+    status::HistoryActorTimestamp, Component, ComponentError, ComponentId, ComponentLabel,
+    ComponentLabelError, ComponentLabelId, ComponentLabelPk, ComponentLabelResult,
+    ComponentLifecycle, ComponentLifecycleError, ComponentLifecycleEvent, ComponentLifecycleState,
+    ComponentSnapshot, ComponentSnapshotError, ComponentSnapshotPk, ComponentSnapshotResult,
+    ComponentSnapshotTrigger, ComponentView, ComponentViewCache, ComponentViewProperties,
+    LabelRequirement, LabelSelector, ResourceConflictPolicy, ResourceConflictResolution,
+    ResourceDrift,
 };
 pub use context::{
-    AccessBuilder, Connections, DalContext, DalContextBuilder, RequestContext, ServicesContext,
-    Transactions, TransactionsError,
+    AccessBuilder, ConnectionIntent, Connections, DalContext, DalContextBuilder, RequestContext,
+    Savepoint, ServicesContext, Transactions, TransactionsError, TRANSACTION_CONFLICT_MAX_ATTEMPTS,
 };
 pub use cyclone_key_pair::CycloneKeyPair;
+pub use data_migration::{
+    AppliedDataMigration, DataMigration, DataMigrationDirection, DataMigrationError,
+    DataMigrationReport, DataMigrationResult, DataMigrator,
+};
 pub use diagram::{
-    connection::Connection, connection::DiagramEdgeView, Diagram, DiagramError, DiagramKind,
+    bulk::NodePosition, connection::Connection, connection::DiagramEdgeView, Diagram, DiagramError,
+    DiagramKind,
 };
 pub use edge::{Edge, EdgeError, EdgeResult};
+pub use error_category::{CategorizedError, ErrorCategory};
+pub use feature_flag::{
+    FeatureFlag, FeatureFlagCache, FeatureFlagError, FeatureFlagPk, FeatureFlagResult,
+};
 pub use fix::batch::{FixBatch, FixBatchId};
+pub use fix::plan::{
+    FixPlan, FixPlanError, FixPlanItem, FixPlanPhase, FixPlanPk, FixPlanResult, FixPlanStatus,
+};
 pub use fix::resolver::{FixResolver, FixResolverError, FixResolverId};
 pub use fix::{Fix, FixCompletionStatus, FixError, FixId};
 pub use func::argument::FuncArgument;
@@ -113,11 +173,25 @@ pub use func::description::FuncDescription;
 pub use func::description::FuncDescriptionContents;
 pub use func::{
     backend::{FuncBackendError, FuncBackendKind, FuncBackendResponseType},
-    binding::{FuncBinding, FuncBindingError, FuncBindingId},
+    binding::{FuncBinding, FuncBindingError, FuncBindingId, FuncReplay},
+    version::{
+        FuncVersion, FuncVersionComparison, FuncVersionComparisonValue, FuncVersionError,
+        FuncVersionPin,
+    },
     Func, FuncError, FuncId, FuncResult,
 };
-pub use history_event::{HistoryActor, HistoryEvent, HistoryEventError};
+pub use history_event::{
+    HistoryActor, HistoryEvent, HistoryEventError, HistoryEventFilter, HistoryEventPage,
+    HistoryEventRetentionPolicy,
+};
+pub use idempotency::{
+    IdempotencyError, IdempotencyRecord, IdempotencyRecordPk, IdempotencyResult,
+};
 pub use index_map::IndexMap;
+pub use job::dead_letter::{
+    DeadLetteredJob, DeadLetteredJobError, DeadLetteredJobPk, DeadLetteredJobResult,
+    DeadLetteredJobStats,
+};
 pub use job::definition::DependentValuesUpdate;
 pub use job::processor::{JobQueueProcessor, NatsProcessor};
 pub use job_failure::{JobFailure, JobFailureError, JobFailureResult};
@@ -127,6 +201,9 @@ pub use label_list::{LabelEntry, LabelList, LabelListError};
 pub use node::NodeId;
 pub use node::{Node, NodeError, NodeKind};
 pub use node_menu::NodeMenuError;
+pub use notification::{
+    Notification, NotificationError, NotificationKind, NotificationPreferences, NotificationResult,
+};
 pub use prop::{Prop, PropError, PropId, PropKind, PropPk, PropResult};
 pub use prototype_context::HasPrototypeContext;
 pub use prototype_list_for_func::{
@@ -135,10 +212,14 @@ pub use prototype_list_for_func::{
 pub use provider::external::{ExternalProvider, ExternalProviderError, ExternalProviderId};
 pub use provider::internal::{InternalProvider, InternalProviderError, InternalProviderId};
 pub use qualification::{QualificationError, QualificationView};
+pub use random::RandomSource;
 pub use reconciliation_prototype::{
     ReconciliationPrototype, ReconciliationPrototypeContext, ReconciliationPrototypeError,
     ReconciliationPrototypeId,
 };
+pub use saved_view::{
+    SavedView, SavedViewError, SavedViewPk, SavedViewResolution, SavedViewResult,
+};
 pub use schema::variant::leaves::LeafInput;
 pub use schema::variant::leaves::LeafInputLocation;
 pub use schema::variant::leaves::LeafKind;
@@ -146,18 +227,28 @@ pub use schema::variant::root_prop::component_type::ComponentType;
 pub use schema::variant::root_prop::RootProp;
 pub use schema::variant::root_prop::RootPropChild;
 pub use schema::variant::SchemaVariantError;
-pub use schema::{Schema, SchemaError, SchemaId, SchemaPk, SchemaVariant, SchemaVariantId};
+pub use schema::{
+    Schema, SchemaCategoryRule, SchemaCategoryRuleKind, SchemaCategoryRules, SchemaError, SchemaId,
+    SchemaPk, SchemaVariant, SchemaVariantId,
+};
 pub use secret::{
     DecryptedSecret, EncryptedSecret, Secret, SecretAlgorithm, SecretError, SecretId, SecretKind,
     SecretObjectType, SecretPk, SecretResult, SecretVersion,
 };
-pub use socket::{Socket, SocketArity, SocketId};
+pub use session::{SessionError, SessionResult, SessionRevocation};
+pub use socket::{Socket, SocketArity, SocketId, SocketType, SocketTypeMismatch};
 pub use standard_model::{StandardModel, StandardModelError, StandardModelResult};
 pub use status::{
     StatusUpdate, StatusUpdateError, StatusUpdateResult, StatusUpdater, StatusUpdaterError,
 };
+pub use suggestion_prototype::{
+    SuggestionPrototype, SuggestionPrototypeError, SuggestionPrototypeId, SuggestionPrototypeResult,
+};
 pub use tenancy::{Tenancy, TenancyError};
 pub use timestamp::{Timestamp, TimestampError};
+pub use usage::{
+    Usage, UsageBucket, UsageError, UsageMetric, UsageReport, UsageReportBucket, UsageResult,
+};
 pub use user::{User, UserClaim, UserError, UserPk, UserResult};
 pub use validation::prototype::{
     context::ValidationPrototypeContext, ValidationPrototype, ValidationPrototypeError,
@@ -167,7 +258,19 @@ pub use validation::resolver::{
     ValidationResolver, ValidationResolverError, ValidationResolverId, ValidationStatus,
 };
 pub use visibility::{Visibility, VisibilityError};
+pub use webhook::{
+    Webhook, WebhookDelivery, WebhookDeliveryPk, WebhookDeliveryStatus, WebhookError, WebhookPk,
+    WebhookResult,
+};
 pub use workspace::{Workspace, WorkspaceError, WorkspacePk, WorkspaceResult, WorkspaceSignup};
+pub use workspace_backup::{
+    WorkspaceBackup, WorkspaceBackupError, WorkspaceBackupPk, WorkspaceBackupPolicy,
+    WorkspaceBackupResult, WorkspaceBackupTrigger,
+};
+pub use workspace_settings::{
+    QualificationGatingPolicy, WorkspaceSettingKey, WorkspaceSettings, WorkspaceSettingsCache,
+    WorkspaceSettingsError, WorkspaceSettingsResult,
+};
 pub use ws_event::{WsEvent, WsEventError, WsEventResult, WsPayload};
 
 #[remain::sorted]
@@ -191,13 +294,13 @@ mod embedded {
     embed_migrations!("./src/migrations");
 }
 
-const NAME_CHARSET: &[u8] = b"0123456789";
-
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ModelError {
     #[error("builtins error: {0}")]
     Builtins(#[from] BuiltinsError),
+    #[error("data migration error: {0}")]
+    DataMigration(#[from] DataMigrationError),
     #[error(transparent)]
     Migration(#[from] PgPoolError),
     #[error(transparent)]
@@ -212,6 +315,7 @@ pub enum ModelError {
 
 pub type ModelResult<T> = Result<T, ModelError>;
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all)]
 pub async fn migrate_all(
     pg: &PgPool,
@@ -221,6 +325,7 @@ pub async fn migrate_all(
     encryption_key: &EncryptionKey,
     pkgs_path: PathBuf,
     module_index_url: String,
+    selected_builtins: Option<Vec<Builtin>>,
 ) -> ModelResult<()> {
     migrate(pg).await?;
     migrate_builtins(
@@ -232,11 +337,13 @@ pub async fn migrate_all(
         None,
         pkgs_path,
         module_index_url,
+        selected_builtins,
     )
     .await?;
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all)]
 pub async fn migrate_all_with_progress(
     pg: &PgPool,
@@ -246,6 +353,7 @@ pub async fn migrate_all_with_progress(
     encryption_key: &EncryptionKey,
     pkgs_path: PathBuf,
     module_index_url: String,
+    selected_builtins: Option<Vec<Builtin>>,
 ) -> ModelResult<()> {
     let mut interval = time::interval(Duration::from_secs(5));
     let instant = Instant::now();
@@ -257,6 +365,7 @@ pub async fn migrate_all_with_progress(
         encryption_key,
         pkgs_path,
         module_index_url,
+        selected_builtins,
     );
     tokio::pin!(migrate_all);
 
@@ -294,6 +403,7 @@ pub async fn migrate_builtins(
     selected_test_builtin_schemas: Option<SelectedTestBuiltinSchemas>,
     pkgs_path: PathBuf,
     module_index_url: String,
+    selected_builtins: Option<Vec<Builtin>>,
 ) -> ModelResult<()> {
     let services_context = ServicesContext::new(
         pg.clone(),
@@ -311,25 +421,54 @@ pub async fn migrate_builtins(
     ctx.update_tenancy(Tenancy::new(*workspace.pk()));
     ctx.blocking_commit().await?;
 
-    builtins::migrate(&ctx, selected_test_builtin_schemas).await?;
+    builtins::migrate(
+        &ctx,
+        selected_test_builtin_schemas,
+        selected_builtins.as_deref(),
+    )
+    .await?;
 
     ctx.blocking_commit().await?;
 
     Ok(())
 }
 
-pub fn generate_unique_id(length: usize) -> String {
-    let mut rng = rand::thread_rng();
-    (0..length)
-        .map(|_| {
-            let idx = rng.gen_range(0..NAME_CHARSET.len());
-            NAME_CHARSET[idx] as char
-        })
-        .collect()
+/// Migrates the data to `target_version` with the [`DataMigrations`](DataMigration) shipped with
+/// the dal, or to the latest version if `None`. See [`DataMigrator::migrate_to`].
+#[instrument(skip_all)]
+pub async fn migrate_data(
+    pg: &PgPool,
+    nats: &NatsClient,
+    job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+    veritech: veritech_client::Client,
+    encryption_key: &EncryptionKey,
+    target_version: Option<i64>,
+    dry_run: bool,
+) -> ModelResult<Vec<DataMigrationReport>> {
+    let services_context = ServicesContext::new(
+        pg.clone(),
+        nats.clone(),
+        job_processor,
+        veritech,
+        Arc::new(*encryption_key),
+        None,
+        None,
+    );
+    let ctx = services_context.into_builder(false).build_default().await?;
+
+    Ok(DataMigrator::builtin()?
+        .migrate_to(&ctx, target_version, dry_run)
+        .await?)
+}
+
+/// Generates a string of `length` digits, drawn from the [`RandomSource`] of the context.
+pub fn generate_unique_id(ctx: &DalContext, length: usize) -> String {
+    ctx.random_source().unique_id(length)
 }
 
-pub fn generate_name() -> String {
-    let unique_id = generate_unique_id(4);
+/// Generates a name such as `si-1234`, drawn from the [`RandomSource`] of the context.
+pub fn generate_name(ctx: &DalContext) -> String {
+    let unique_id = generate_unique_id(ctx, 4);
     format!("si-{unique_id}")
 }
 
@@ -0,0 +1,75 @@
+CREATE TABLE api_tokens
+(
+    pk                          ident primary key default ident_create_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    name                        text                     NOT NULL,
+    workspace_pk                ident                    NOT NULL,
+    user_pk                     ident                    NOT NULL,
+    token_prefix                text                     NOT NULL,
+    secret_hash                 text                     NOT NULL,
+    scopes                      jsonb                    NOT NULL,
+    expires_at                  timestamp with time zone,
+    last_used_at                timestamp with time zone,
+    revoked_at                  timestamp with time zone
+);
+CREATE UNIQUE INDEX ON api_tokens (pk);
+CREATE UNIQUE INDEX ON api_tokens (secret_hash);
+CREATE INDEX ON api_tokens (workspace_pk);
+CREATE INDEX ON api_tokens (visibility_deleted_at NULLS FIRST);
+
+CREATE OR REPLACE FUNCTION api_token_create_v1(
+    this_name text,
+    this_workspace_pk ident,
+    this_user_pk ident,
+    this_token_prefix text,
+    this_secret_hash text,
+    this_scopes jsonb,
+    this_expires_at timestamp with time zone,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row           api_tokens%ROWTYPE;
+BEGIN
+    INSERT INTO api_tokens (name, workspace_pk, user_pk, token_prefix, secret_hash, scopes, expires_at)
+    VALUES (this_name, this_workspace_pk, this_user_pk, this_token_prefix, this_secret_hash, this_scopes,
+            this_expires_at)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION api_token_touch_v1(
+    this_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row       api_tokens%ROWTYPE;
+BEGIN
+    UPDATE api_tokens
+    SET last_used_at = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION api_token_revoke_v1(
+    this_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row       api_tokens%ROWTYPE;
+BEGIN
+    UPDATE api_tokens
+    SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP()),
+        updated_at = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,81 @@
+CREATE TABLE refresh_tokens
+(
+    pk                          ident primary key default ident_create_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    user_pk                     ident                    NOT NULL,
+    workspace_pk                ident                    NOT NULL,
+    secret_hash                 text                     NOT NULL,
+    expires_at                  timestamp with time zone NOT NULL,
+    revoked_at                  timestamp with time zone
+);
+CREATE UNIQUE INDEX ON refresh_tokens (pk);
+CREATE UNIQUE INDEX ON refresh_tokens (secret_hash);
+CREATE INDEX ON refresh_tokens (user_pk);
+CREATE INDEX ON refresh_tokens (visibility_deleted_at NULLS FIRST);
+
+CREATE OR REPLACE FUNCTION refresh_token_create_v1(
+    this_user_pk ident,
+    this_workspace_pk ident,
+    this_secret_hash text,
+    this_expires_at timestamp with time zone,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row           refresh_tokens%ROWTYPE;
+BEGIN
+    INSERT INTO refresh_tokens (user_pk, workspace_pk, secret_hash, expires_at)
+    VALUES (this_user_pk, this_workspace_pk, this_secret_hash, this_expires_at)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION refresh_token_revoke_v1(
+    this_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row       refresh_tokens%ROWTYPE;
+BEGIN
+    UPDATE refresh_tokens
+    SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP()),
+        updated_at = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Any session token for a user issued before `revoked_before` is no longer valid.
+CREATE TABLE session_revocations
+(
+    user_pk                     ident primary key,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    revoked_before              timestamp with time zone NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION session_revoke_all_for_user_v1(
+    this_user_pk ident,
+    OUT this_revoked_before timestamp with time zone) AS
+$$
+BEGIN
+    this_revoked_before := CLOCK_TIMESTAMP();
+
+    INSERT INTO session_revocations (user_pk, revoked_before)
+    VALUES (this_user_pk, this_revoked_before)
+    ON CONFLICT (user_pk) DO UPDATE
+        SET revoked_before = EXCLUDED.revoked_before,
+            updated_at     = CLOCK_TIMESTAMP();
+
+    UPDATE refresh_tokens
+    SET revoked_at = this_revoked_before,
+        updated_at = CLOCK_TIMESTAMP()
+    WHERE user_pk = this_user_pk
+      AND revoked_at IS NULL;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,56 @@
+ALTER TABLE history_events
+    ADD COLUMN audit_action text,
+    ADD COLUMN audit_target jsonb,
+    ADD COLUMN audit_before jsonb,
+    ADD COLUMN audit_after  jsonb;
+
+CREATE INDEX ON history_events (tenancy_workspace_pk, pk DESC) WHERE audit_action IS NOT NULL;
+CREATE INDEX ON history_events (tenancy_workspace_pk, audit_action, pk DESC) WHERE audit_action IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION history_event_create_audit_v1(this_action text,
+                                                         this_actor jsonb,
+                                                         this_message text,
+                                                         this_target jsonb,
+                                                         this_before jsonb,
+                                                         this_after jsonb,
+                                                         this_tenancy jsonb,
+                                                         OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        history_events%ROWTYPE;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+
+    INSERT INTO history_events (label, actor, message, data, tenancy_workspace_pk,
+                                audit_action, audit_target, audit_before, audit_after)
+    VALUES (this_action, this_actor, this_message,
+            jsonb_build_object('target', this_target, 'before', this_before, 'after', this_after),
+            this_tenancy_record.tenancy_workspace_pk,
+            this_action, this_target, this_before, this_after)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION history_event_prune_audit_v1(this_tenancy jsonb,
+                                                        this_older_than timestamp with time zone,
+                                                        OUT pruned bigint) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+
+    WITH deleted AS (
+        DELETE FROM history_events
+        WHERE audit_action IS NOT NULL
+          AND created_at < this_older_than
+          AND (this_tenancy_record.tenancy_workspace_pk IS NULL
+               OR tenancy_workspace_pk = this_tenancy_record.tenancy_workspace_pk)
+        RETURNING pk
+    )
+    SELECT count(*) INTO pruned FROM deleted;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1 @@
+ALTER TABLE attribute_prototypes ADD COLUMN recheck_interval_seconds bigint;
@@ -0,0 +1,45 @@
+-- The responses of mutating requests, keyed by the client-supplied `Idempotency-Key`, so that
+-- retried requests return the original result instead of repeating the mutation.
+CREATE TABLE idempotency_records
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    idempotency_key             text                     NOT NULL,
+    endpoint                    text                     NOT NULL,
+    response                    jsonb                    NOT NULL,
+    expires_at                  timestamp with time zone NOT NULL
+);
+CREATE UNIQUE INDEX ON idempotency_records (workspace_pk, endpoint, idempotency_key);
+CREATE INDEX ON idempotency_records (expires_at);
+
+CREATE OR REPLACE FUNCTION idempotency_record_store_v1(
+    this_workspace_pk ident,
+    this_idempotency_key text,
+    this_endpoint text,
+    this_response jsonb,
+    this_expires_at timestamp with time zone,
+    OUT object json) AS
+$$
+DECLARE
+    this_row               idempotency_records%ROWTYPE;
+BEGIN
+    DELETE FROM idempotency_records
+    WHERE workspace_pk = this_workspace_pk
+      AND expires_at <= CLOCK_TIMESTAMP();
+
+    -- If a concurrent request already stored a response for this key, it wins
+    INSERT INTO idempotency_records (workspace_pk, idempotency_key, endpoint, response, expires_at)
+    VALUES (this_workspace_pk, this_idempotency_key, this_endpoint, this_response, this_expires_at)
+    ON CONFLICT (workspace_pk, endpoint, idempotency_key) DO NOTHING;
+
+    SELECT * INTO this_row
+    FROM idempotency_records
+    WHERE workspace_pk = this_workspace_pk
+      AND endpoint = this_endpoint
+      AND idempotency_key = this_idempotency_key;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,152 @@
+-- Every standard model row carries a revision which is bumped on each write, so that callers can
+-- detect that a row changed since they last read it (optimistic concurrency).
+DO
+$$
+    DECLARE
+        this_table_name text;
+    BEGIN
+        FOR this_table_name IN
+            SELECT DISTINCT information_schema.columns.table_name
+            FROM information_schema.columns
+            WHERE information_schema.columns.table_schema = 'public'
+              AND information_schema.columns.column_name = 'visibility_change_set_pk'
+            LOOP
+                EXECUTE format('ALTER TABLE %1$I ADD COLUMN IF NOT EXISTS revision bigint NOT NULL DEFAULT 0',
+                               this_table_name);
+            END LOOP;
+    END;
+$$;
+
+-- Tables set up after this migration get the revision column too
+ALTER FUNCTION standard_model_table_constraints_v1(text) RENAME TO standard_model_table_constraints_without_revision_v1;
+
+CREATE OR REPLACE FUNCTION standard_model_table_constraints_v1(this_table_name text) RETURNS VOID AS
+$$
+BEGIN
+    PERFORM standard_model_table_constraints_without_revision_v1(this_table_name);
+    EXECUTE format('ALTER TABLE %1$I ADD COLUMN IF NOT EXISTS revision bigint NOT NULL DEFAULT 0',
+                   this_table_name);
+END;
+$$ LANGUAGE plpgsql VOLATILE;
+
+-- Identical to the previous version, except that the revision is bumped on every update, including
+-- the first update in a change set, which copies the head row.
+CREATE OR REPLACE FUNCTION update_by_id_v1(
+    this_table text,
+    this_column text,
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_id ident,
+    this_value text,
+    OUT updated_at timestamp with time zone)
+AS
+$$
+DECLARE
+    this_visibility_row          visibility_record_v1;
+    this_tenancy_record          tenancy_record_v1;
+    copy_change_set_column_names text;
+    debugging_record_info        record;
+BEGIN
+    this_visibility_row = visibility_json_to_columns_v1(this_visibility);
+    this_tenancy_record = tenancy_json_to_columns_v1(this_tenancy);
+
+    /* First, try the update - if it works, we're all set. */
+    EXECUTE format('UPDATE %1$I SET %2$I = %6$L, updated_at = clock_timestamp(), revision = %1$I.revision + 1 '
+                   ' WHERE id = %5$L '
+                   '  AND in_tenancy_v1(%3$L, %1$I.tenancy_workspace_pk) '
+                   '  AND %1$I.visibility_change_set_pk = %4$L::ident '
+                   '  AND CASE WHEN %7$L IS NULL THEN %1$I.visibility_deleted_at IS NULL ELSE TRUE END '
+                   ' RETURNING updated_at',
+                   this_table,
+                   this_column,
+                   this_tenancy,
+                   this_visibility_row.visibility_change_set_pk,
+                   this_id,
+                   this_value, this_visibility_row.visibility_deleted_at) INTO updated_at;
+
+    /* If updated_at is still null, that is because the update found no rows. We need to first copy the last known
+       good data, and then update it. */
+    IF updated_at IS NULL THEN
+        /* Check if we are doing an update to the change-set visibility. If we aren't, then we need to
+           copy the head row. If it doesn't exist, that is an error. */
+        IF this_visibility_row.visibility_change_set_pk != ident_nil_v1() THEN
+
+            SELECT string_agg(information_schema.columns.column_name::text, ',')
+            FROM information_schema.columns
+            WHERE information_schema.columns.table_name = this_table
+              AND information_schema.columns.column_name NOT IN
+                  (
+                   this_column,
+                   'visibility_change_set_pk',
+                   'pk',
+                   'created_at',
+                   'updated_at',
+                   'tenancy_workspace_pk',
+                   'revision'
+                      )
+              AND information_schema.columns.is_generated = 'NEVER'
+            INTO copy_change_set_column_names;
+            EXECUTE format('INSERT INTO %1$I (%2$s, visibility_change_set_pk, tenancy_workspace_pk, revision, %3$s) '
+                           '   SELECT %4$L, %5$L, %8$L, %1$I.revision + 1, %3$s '
+                           '   FROM %1$I '
+                           '   WHERE %1$I.id = %6$L '
+                           '         AND in_tenancy_v1(%7$L, %1$I.tenancy_workspace_pk) '
+                           '         AND %1$I.visibility_change_set_pk = ident_nil_v1() '
+                           '         AND CASE WHEN %9$L IS NULL THEN %1$I.visibility_deleted_at IS NULL ELSE %1$I.visibility_deleted_at IS NOT NULL END '
+                           ' ON CONFLICT (id, tenancy_workspace_pk, visibility_change_set_pk) DO NOTHING '
+                           ' RETURNING updated_at',
+                           this_table,
+                           this_column,
+                           copy_change_set_column_names,
+                           this_value,
+                           this_visibility_row.visibility_change_set_pk,
+                           this_id,
+                           this_tenancy,
+                           this_tenancy_record.tenancy_workspace_pk,
+                           this_visibility_row.visibility_deleted_at) INTO updated_at;
+        END IF;
+
+        -- If updated_at is still null, then there is a provided tenancy
+        -- that is not suitable--this could be an application bug!
+        IF updated_at IS NULL THEN
+            EXECUTE format('SELECT * FROM %1$I WHERE id = %2$L', this_table, this_id)
+                INTO debugging_record_info;
+            RAISE EXCEPTION
+                'update_by_id_v1: cannot update column % on table % of record % to value % (likely a tenancy issue). Tenancy(%), Visibility(%), %(%)',
+                this_column,
+                this_table,
+                this_id,
+                this_value,
+                this_tenancy,
+                this_visibility,
+                this_table,
+                debugging_record_info;
+
+        END IF;
+    END IF;
+END ;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Locks a row against other revision-checked writers until the end of the transaction, and returns
+-- its current revision as seen from the given visibility (NULL if it is not visible).
+CREATE OR REPLACE FUNCTION revision_for_update_by_id_v1(this_table_text text,
+                                                        this_tenancy jsonb,
+                                                        this_visibility jsonb,
+                                                        this_id ident,
+                                                        OUT revision bigint)
+AS
+$$
+DECLARE
+    this_table regclass;
+BEGIN
+    this_table := this_table_text::regclass;
+    PERFORM pg_advisory_xact_lock(hashtextextended(format('%s:%s:%s',
+                                                          this_table_text,
+                                                          this_id,
+                                                          this_visibility ->> 'visibility_change_set_pk'), 0));
+    EXECUTE format('SELECT table_alias.revision '
+                   ' FROM %1$I_v1(%3$L, %4$L) AS table_alias '
+                   ' WHERE table_alias.id = %2$L', this_table, this_id, this_tenancy, this_visibility)
+        INTO revision;
+END ;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,9 @@
+-- A content hash per builtin, recorded once the builtin has been migrated, so that builtins which
+-- have not changed since can be skipped on the next boot.
+CREATE TABLE builtin_fingerprints
+(
+    name                        text primary key,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    fingerprint                 text                     NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Data migrations which have been applied, see `dal::data_migration`.
+CREATE TABLE data_migrations
+(
+    version    bigint primary key,
+    name       text                     NOT NULL,
+    applied_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
@@ -0,0 +1 @@
+ALTER TABLE schema_variants ADD COLUMN cloned_from_schema_variant_id ident;
@@ -0,0 +1 @@
+ALTER TABLE sockets ADD COLUMN socket_type jsonb;
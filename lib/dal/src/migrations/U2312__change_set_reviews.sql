@@ -0,0 +1,85 @@
+-- The number of approvals a change set needs before it can be applied. Zero means change sets can
+-- be applied without review.
+ALTER TABLE workspaces ADD COLUMN required_change_set_approvals bigint NOT NULL DEFAULT 0;
+
+CREATE OR REPLACE FUNCTION workspace_set_required_change_set_approvals_v1(
+    this_pk ident,
+    this_required_change_set_approvals bigint,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row       workspaces%ROWTYPE;
+BEGIN
+    UPDATE workspaces
+    SET required_change_set_approvals = this_required_change_set_approvals,
+        updated_at = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- One row per reviewer requested for a change set, holding their decision.
+CREATE TABLE change_set_reviews
+(
+    pk                          ident primary key default ident_create_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    change_set_pk               ident                    NOT NULL,
+    reviewer_user_pk            ident                    NOT NULL,
+    requested_by_user_pk        ident,
+    status                      text                     NOT NULL,
+    comment                     text
+);
+CREATE UNIQUE INDEX ON change_set_reviews (change_set_pk, reviewer_user_pk)
+    WHERE visibility_deleted_at IS NULL;
+CREATE INDEX ON change_set_reviews (workspace_pk, change_set_pk);
+
+CREATE OR REPLACE FUNCTION change_set_review_request_v1(
+    this_workspace_pk ident,
+    this_change_set_pk ident,
+    this_reviewer_user_pk ident,
+    this_requested_by_user_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_row               change_set_reviews%ROWTYPE;
+BEGIN
+    -- Requesting a review again resets the previous decision of the reviewer
+    INSERT INTO change_set_reviews (workspace_pk, change_set_pk, reviewer_user_pk,
+                                    requested_by_user_pk, status)
+    VALUES (this_workspace_pk, this_change_set_pk, this_reviewer_user_pk,
+            this_requested_by_user_pk, 'Pending')
+    ON CONFLICT (change_set_pk, reviewer_user_pk) WHERE visibility_deleted_at IS NULL
+        DO UPDATE SET requested_by_user_pk = EXCLUDED.requested_by_user_pk,
+                      status               = 'Pending',
+                      comment              = NULL,
+                      updated_at           = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_review_submit_v1(
+    this_pk ident,
+    this_status text,
+    this_comment text,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row       change_set_reviews%ROWTYPE;
+BEGIN
+    UPDATE change_set_reviews
+    SET status = this_status,
+        comment = this_comment,
+        updated_at = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,50 @@
+CREATE TABLE comments
+(
+    pk                          ident primary key default ident_create_v1(),
+    id                          ident not null default ident_create_v1(),
+    tenancy_workspace_pk        ident                    NOT NULL,
+    visibility_change_set_pk    ident                    NOT NULL DEFAULT ident_nil_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    target                      jsonb                    NOT NULL,
+    author_user_pk              ident,
+    body                        text                     NOT NULL
+);
+CREATE INDEX ON comments (target);
+SELECT standard_model_table_constraints_v1('comments');
+
+INSERT INTO standard_models (table_name, table_type, history_event_label_base, history_event_message_name)
+VALUES ('comments', 'model', 'comment', 'Comment');
+
+CREATE OR REPLACE FUNCTION comment_create_v1(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_target jsonb,
+    this_author_user_pk ident,
+    this_body text,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           comments%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO comments (tenancy_workspace_pk,
+                          visibility_change_set_pk,
+                          target,
+                          author_user_pk,
+                          body)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_target,
+            this_author_user_pk,
+            this_body)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,59 @@
+CREATE TABLE dead_lettered_jobs
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident,
+    job_id                      text                     NOT NULL,
+    kind                        text                     NOT NULL,
+    job_info                    jsonb                    NOT NULL,
+    error                       text                     NOT NULL,
+    attempts                    bigint                   NOT NULL DEFAULT 1,
+    first_failed_at             timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    last_failed_at              timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    replayed_at                 timestamp with time zone
+);
+CREATE UNIQUE INDEX ON dead_lettered_jobs (job_id);
+CREATE INDEX ON dead_lettered_jobs (workspace_pk);
+
+-- Dead letters a job, or counts one more failed attempt if the job was replayed and failed again.
+CREATE OR REPLACE FUNCTION dead_lettered_job_record_v1(
+    this_workspace_pk ident,
+    this_job_info jsonb,
+    this_error text,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row           dead_lettered_jobs%ROWTYPE;
+BEGIN
+    INSERT INTO dead_lettered_jobs (workspace_pk, job_id, kind, job_info, error)
+    VALUES (this_workspace_pk, this_job_info ->> 'id', this_job_info ->> 'kind', this_job_info, this_error)
+    ON CONFLICT (job_id) DO UPDATE
+        SET job_info       = EXCLUDED.job_info,
+            error          = EXCLUDED.error,
+            attempts       = dead_lettered_jobs.attempts + 1,
+            last_failed_at = CLOCK_TIMESTAMP(),
+            replayed_at    = NULL,
+            updated_at     = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION dead_lettered_job_mark_replayed_v1(
+    this_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row       dead_lettered_jobs%ROWTYPE;
+BEGIN
+    UPDATE dead_lettered_jobs
+    SET replayed_at = CLOCK_TIMESTAMP(),
+        updated_at  = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
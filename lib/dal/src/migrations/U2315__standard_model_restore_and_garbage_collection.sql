@@ -0,0 +1,119 @@
+-- Restores an object which was soft deleted in the change set of the visibility.
+CREATE OR REPLACE FUNCTION restore_by_id_v1(this_table_text text,
+                                            this_tenancy jsonb,
+                                            this_visibility jsonb,
+                                            this_id ident,
+                                            OUT updated_at timestamp with time zone)
+AS
+$$
+DECLARE
+    this_table regclass;
+BEGIN
+    this_table := this_table_text::regclass;
+    EXECUTE format('UPDATE %1$I SET visibility_deleted_at = NULL, updated_at = clock_timestamp() '
+                   'WHERE id = %4$L '
+                   '  AND in_tenancy_v1(%2$L, %1$I.tenancy_workspace_pk) '
+                   '  AND visibility_change_set_pk = (%3$L::jsonb ->> ''visibility_change_set_pk'')::ident '
+                   '  AND visibility_deleted_at IS NOT NULL '
+                   ' RETURNING updated_at',
+                   this_table, this_tenancy, this_visibility, this_id) INTO updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Hard deletes the rows of a table which were soft deleted before the given time. The rows of
+-- belongs to and many to many tables which refer to objects which no longer have any row are hard
+-- deleted along with them. Returns how many rows were hard deleted.
+CREATE OR REPLACE FUNCTION garbage_collect_table_v1(this_table_text text,
+                                                    this_tenancy jsonb,
+                                                    this_deleted_before timestamp with time zone,
+                                                    OUT hard_deleted bigint)
+AS
+$$
+DECLARE
+    this_table           regclass;
+    purged_ids           ident[];
+    gone_ids             ident[];
+    relationship         record;
+    relationship_deleted bigint;
+BEGIN
+    this_table := this_table_text::regclass;
+
+    EXECUTE format('WITH purged AS (DELETE FROM %1$I '
+                   '                WHERE in_tenancy_v1($1, %1$I.tenancy_workspace_pk) '
+                   '                  AND visibility_deleted_at < $2 '
+                   '                RETURNING id) '
+                   'SELECT count(*), COALESCE(array_agg(DISTINCT id), ''{}'') FROM purged',
+                   this_table)
+        INTO hard_deleted, purged_ids
+        USING this_tenancy, this_deleted_before;
+
+    IF cardinality(purged_ids) = 0 THEN
+        RETURN;
+    END IF;
+
+    -- Objects soft deleted in a change set may still be alive in other change sets or on head
+    EXECUTE format('SELECT COALESCE(array_agg(purged_id), ''{}'') '
+                   'FROM unnest($1) AS purged_id '
+                   'WHERE NOT EXISTS (SELECT 1 FROM %1$I WHERE %1$I.id = purged_id)',
+                   this_table)
+        INTO gone_ids
+        USING purged_ids;
+
+    IF cardinality(gone_ids) = 0 THEN
+        RETURN;
+    END IF;
+
+    FOR relationship IN SELECT table_name, table_type
+                        FROM standard_models
+                        WHERE table_type IN ('belongs_to', 'many_to_many')
+        LOOP
+            IF relationship.table_type = 'belongs_to' THEN
+                EXECUTE format('WITH deleted AS (DELETE FROM %1$I '
+                               '                 WHERE object_id = ANY ($1) OR belongs_to_id = ANY ($1) '
+                               '                 RETURNING 1) '
+                               'SELECT count(*) FROM deleted',
+                               relationship.table_name)
+                    INTO relationship_deleted
+                    USING gone_ids;
+            ELSE
+                EXECUTE format('WITH deleted AS (DELETE FROM %1$I '
+                               '                 WHERE left_object_id = ANY ($1) OR right_object_id = ANY ($1) '
+                               '                 RETURNING 1) '
+                               'SELECT count(*) FROM deleted',
+                               relationship.table_name)
+                    INTO relationship_deleted
+                    USING gone_ids;
+            END IF;
+            hard_deleted := hard_deleted + relationship_deleted;
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Garbage collects every standard model table of the tenancy, models first so that the rows of
+-- relationships to the objects they hard delete go with them. Tables whose rows are still referenced
+-- by foreign keys are skipped.
+CREATE OR REPLACE FUNCTION garbage_collect_v1(this_tenancy jsonb,
+                                              this_deleted_before timestamp with time zone,
+                                              OUT hard_deleted bigint)
+AS
+$$
+DECLARE
+    standard_model record;
+BEGIN
+    hard_deleted := 0;
+
+    FOR standard_model IN SELECT table_name
+                          FROM standard_models
+                          ORDER BY table_type <> 'model', table_name
+        LOOP
+            BEGIN
+                hard_deleted := hard_deleted + garbage_collect_table_v1(standard_model.table_name,
+                                                                        this_tenancy,
+                                                                        this_deleted_before);
+            EXCEPTION
+                WHEN foreign_key_violation THEN
+                    RAISE WARNING 'skipping garbage collection of %: %', standard_model.table_name, SQLERRM;
+            END;
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
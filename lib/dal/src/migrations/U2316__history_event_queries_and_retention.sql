@@ -0,0 +1,55 @@
+CREATE INDEX ON history_events (tenancy_workspace_pk, pk DESC);
+CREATE INDEX ON history_events (tenancy_workspace_pk, actor, pk DESC);
+CREATE INDEX ON history_events (created_at) WHERE audit_action IS NULL;
+
+-- History events which outlived the retention policy, kept out of the way of the live table when
+-- archival is enabled.
+CREATE TABLE history_events_archive
+(
+    LIKE history_events INCLUDING DEFAULTS,
+    archived_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (pk)
+);
+
+CREATE INDEX ON history_events_archive (tenancy_workspace_pk, pk DESC);
+
+-- Removes the history events created before the given time, moving them to the archive table if
+-- asked to. Audit log entries have their own retention policy and are left alone. An empty tenancy
+-- prunes events across all workspaces.
+CREATE OR REPLACE FUNCTION history_event_prune_v1(this_tenancy jsonb,
+                                                  this_older_than timestamp with time zone,
+                                                  this_archive bool,
+                                                  OUT pruned bigint) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+
+    IF this_archive THEN
+        WITH deleted AS (
+            DELETE FROM history_events
+            WHERE audit_action IS NULL
+              AND created_at < this_older_than
+              AND (this_tenancy_record.tenancy_workspace_pk IS NULL
+                   OR tenancy_workspace_pk = this_tenancy_record.tenancy_workspace_pk)
+            RETURNING *
+        ), archived AS (
+            INSERT INTO history_events_archive
+            SELECT deleted.*, clock_timestamp() FROM deleted
+            RETURNING pk
+        )
+        SELECT count(*) INTO pruned FROM archived;
+    ELSE
+        WITH deleted AS (
+            DELETE FROM history_events
+            WHERE audit_action IS NULL
+              AND created_at < this_older_than
+              AND (this_tenancy_record.tenancy_workspace_pk IS NULL
+                   OR tenancy_workspace_pk = this_tenancy_record.tenancy_workspace_pk)
+            RETURNING pk
+        )
+        SELECT count(*) INTO pruned FROM deleted;
+    END IF;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
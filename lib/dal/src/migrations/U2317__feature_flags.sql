@@ -0,0 +1,42 @@
+CREATE TABLE feature_flags
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    name                        text                     NOT NULL,
+    -- NULL for the global toggle of the flag
+    workspace_pk                ident,
+    enabled                     bool                     NOT NULL
+);
+CREATE UNIQUE INDEX ON feature_flags (name) WHERE workspace_pk IS NULL;
+CREATE UNIQUE INDEX ON feature_flags (name, workspace_pk) WHERE workspace_pk IS NOT NULL;
+
+-- Toggles a flag globally, or for a single workspace when given one.
+CREATE OR REPLACE FUNCTION feature_flag_set_v1(
+    this_name text,
+    this_workspace_pk ident,
+    this_enabled bool,
+    OUT object json) AS
+$$
+DECLARE
+    this_row feature_flags%ROWTYPE;
+BEGIN
+    IF this_workspace_pk IS NULL THEN
+        INSERT INTO feature_flags (name, workspace_pk, enabled)
+        VALUES (this_name, NULL, this_enabled)
+        ON CONFLICT (name) WHERE workspace_pk IS NULL DO UPDATE
+            SET enabled    = EXCLUDED.enabled,
+                updated_at = CLOCK_TIMESTAMP()
+        RETURNING * INTO this_row;
+    ELSE
+        INSERT INTO feature_flags (name, workspace_pk, enabled)
+        VALUES (this_name, this_workspace_pk, this_enabled)
+        ON CONFLICT (name, workspace_pk) WHERE workspace_pk IS NOT NULL DO UPDATE
+            SET enabled    = EXCLUDED.enabled,
+                updated_at = CLOCK_TIMESTAMP()
+        RETURNING * INTO this_row;
+    END IF;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
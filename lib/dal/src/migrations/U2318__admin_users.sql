@@ -0,0 +1,7 @@
+-- Users holding the admin permission, which grants access to the operational endpoints of sdf
+-- across every workspace. The first admin has to be granted directly in the database.
+CREATE TABLE admin_users
+(
+    user_pk    ident PRIMARY KEY,
+    created_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
@@ -0,0 +1,4 @@
+-- Records the hash of the code each function execution ran with, matching the hash kept by funcs,
+-- so that a replay can tell whether the code of its function has changed since.
+ALTER TABLE func_executions
+    ADD COLUMN code_sha256 text GENERATED ALWAYS AS (COALESCE(ENCODE(DIGEST(code_base64, 'sha256'), 'hex'), '0')) STORED;
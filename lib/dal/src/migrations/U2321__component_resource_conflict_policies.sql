@@ -0,0 +1,3 @@
+-- How a component reconciles its model with its resource when a refresh finds they no longer match.
+ALTER TABLE components
+    ADD COLUMN resource_conflict_policy text NOT NULL DEFAULT 'preferModel';
@@ -0,0 +1,31 @@
+CREATE TABLE workspace_settings
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    key                         text                     NOT NULL,
+    value                       jsonb                    NOT NULL
+);
+CREATE UNIQUE INDEX ON workspace_settings (workspace_pk, key);
+
+-- Sets a setting of a workspace, replacing its previous value.
+CREATE OR REPLACE FUNCTION workspace_setting_set_v1(
+    this_workspace_pk ident,
+    this_key text,
+    this_value jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_row workspace_settings%ROWTYPE;
+BEGIN
+    INSERT INTO workspace_settings (workspace_pk, key, value)
+    VALUES (this_workspace_pk, this_key, this_value)
+    ON CONFLICT (workspace_pk, key) DO UPDATE
+        SET value      = EXCLUDED.value,
+            updated_at = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,32 @@
+-- The email notifications each user wants to receive, across every workspace. Users without a row
+-- receive every notification.
+CREATE TABLE notification_preferences
+(
+    user_pk                     ident PRIMARY KEY,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    email_enabled               bool                     NOT NULL,
+    -- The kinds of notifications the user does not want, as an array of strings
+    muted_kinds                 jsonb                    NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION notification_preferences_set_v1(
+    this_user_pk ident,
+    this_email_enabled bool,
+    this_muted_kinds jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_row notification_preferences%ROWTYPE;
+BEGIN
+    INSERT INTO notification_preferences (user_pk, email_enabled, muted_kinds)
+    VALUES (this_user_pk, this_email_enabled, this_muted_kinds)
+    ON CONFLICT (user_pk) DO UPDATE
+        SET email_enabled = EXCLUDED.email_enabled,
+            muted_kinds   = EXCLUDED.muted_kinds,
+            updated_at    = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,119 @@
+-- The endpoints which are sent the events of a workspace
+CREATE TABLE webhooks
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    url                         text                     NOT NULL,
+    -- The key deliveries are signed with, which receivers use to check where they come from
+    secret                      text                     NOT NULL,
+    -- The kinds of events sent to the webhook, as an array of strings, or every kind when empty
+    event_kinds                 jsonb                    NOT NULL,
+    enabled                     bool                     NOT NULL DEFAULT TRUE
+);
+CREATE INDEX ON webhooks (workspace_pk);
+
+CREATE TABLE webhook_deliveries
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    webhook_pk                  ident                    NOT NULL REFERENCES webhooks (pk) ON DELETE CASCADE,
+    workspace_pk                ident                    NOT NULL,
+    event_kind                  text                     NOT NULL,
+    payload                     jsonb                    NOT NULL,
+    status                      text                     NOT NULL DEFAULT 'pending',
+    attempts                    integer                  NOT NULL DEFAULT 0,
+    response_status             integer,
+    last_error                  text,
+    last_attempted_at           timestamp with time zone,
+    -- When a pending delivery is due to be retried, or NULL once its attempt has been enqueued
+    next_attempt_at             timestamp with time zone
+);
+CREATE INDEX ON webhook_deliveries (webhook_pk, created_at DESC);
+CREATE INDEX ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
+
+CREATE OR REPLACE FUNCTION webhook_create_v1(
+    this_workspace_pk ident,
+    this_url text,
+    this_secret text,
+    this_event_kinds jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row webhooks%ROWTYPE;
+BEGIN
+    INSERT INTO webhooks (workspace_pk, url, secret, event_kinds)
+    VALUES (this_workspace_pk, this_url, this_secret, this_event_kinds)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION webhook_update_v1(
+    this_pk ident,
+    this_url text,
+    this_event_kinds jsonb,
+    this_enabled bool,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row webhooks%ROWTYPE;
+BEGIN
+    UPDATE webhooks
+    SET url         = this_url,
+        event_kinds = this_event_kinds,
+        enabled     = this_enabled,
+        updated_at  = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION webhook_delivery_create_v1(
+    this_webhook_pk ident,
+    this_workspace_pk ident,
+    this_event_kind text,
+    this_payload jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row webhook_deliveries%ROWTYPE;
+BEGIN
+    INSERT INTO webhook_deliveries (webhook_pk, workspace_pk, event_kind, payload)
+    VALUES (this_webhook_pk, this_workspace_pk, this_event_kind, this_payload)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION webhook_delivery_record_attempt_v1(
+    this_pk ident,
+    this_status text,
+    this_response_status integer,
+    this_last_error text,
+    this_next_attempt_at timestamp with time zone,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row webhook_deliveries%ROWTYPE;
+BEGIN
+    UPDATE webhook_deliveries
+    SET status            = this_status,
+        attempts          = attempts + 1,
+        response_status   = this_response_status,
+        last_error        = this_last_error,
+        last_attempted_at = CLOCK_TIMESTAMP(),
+        next_attempt_at   = this_next_attempt_at,
+        updated_at        = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,44 @@
+-- The Slack integration of each workspace: where its notifications are posted, which of them, and
+-- with which message templates.
+CREATE TABLE slack_integrations
+(
+    workspace_pk                ident PRIMARY KEY,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    -- Either an incoming webhook url, or a bot token and the channel it posts to
+    webhook_url                 text,
+    bot_token                   text,
+    channel                     text,
+    -- The kinds of notifications posted, as an array of strings
+    kinds                       jsonb                    NOT NULL,
+    -- The message templates overriding the default ones, by kind of notification
+    templates                   jsonb                    NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION slack_integration_set_v1(
+    this_workspace_pk ident,
+    this_webhook_url text,
+    this_bot_token text,
+    this_channel text,
+    this_kinds jsonb,
+    this_templates jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_row slack_integrations%ROWTYPE;
+BEGIN
+    INSERT INTO slack_integrations (workspace_pk, webhook_url, bot_token, channel, kinds, templates)
+    VALUES (this_workspace_pk, this_webhook_url, this_bot_token, this_channel, this_kinds,
+            this_templates)
+    ON CONFLICT (workspace_pk) DO UPDATE
+        SET webhook_url = EXCLUDED.webhook_url,
+            bot_token   = EXCLUDED.bot_token,
+            channel     = EXCLUDED.channel,
+            kinds       = EXCLUDED.kinds,
+            templates   = EXCLUDED.templates,
+            updated_at  = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,53 @@
+-- Reusable groups of components, captured from a diagram and instantiated into change sets
+CREATE TABLE blueprints
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    name                        text                     NOT NULL,
+    description                 text,
+    -- The variables, components and edges of the blueprint
+    spec                        jsonb                    NOT NULL
+);
+CREATE INDEX ON blueprints (workspace_pk);
+
+CREATE OR REPLACE FUNCTION blueprint_create_v1(
+    this_workspace_pk ident,
+    this_name text,
+    this_description text,
+    this_spec jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row blueprints%ROWTYPE;
+BEGIN
+    INSERT INTO blueprints (workspace_pk, name, description, spec)
+    VALUES (this_workspace_pk, this_name, this_description, this_spec)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION blueprint_update_v1(
+    this_pk ident,
+    this_name text,
+    this_description text,
+    this_spec jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row blueprints%ROWTYPE;
+BEGIN
+    UPDATE blueprints
+    SET name        = this_name,
+        description = this_description,
+        spec        = this_spec,
+        updated_at  = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
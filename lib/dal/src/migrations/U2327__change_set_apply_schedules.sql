@@ -0,0 +1,61 @@
+-- Applies of change sets queued for a later time, such as a maintenance window
+CREATE TABLE change_set_apply_schedules
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    change_set_pk               ident                    NOT NULL,
+    apply_at                    timestamp with time zone NOT NULL,
+    -- The offset from UTC the apply was scheduled with, in seconds, to show it in the time zone
+    -- of the user who scheduled it
+    utc_offset_seconds          integer                  NOT NULL,
+    scheduled_by_user_pk        ident,
+    status                      text                     NOT NULL,
+    -- Why the apply failed, when it did
+    failure                     text
+);
+CREATE UNIQUE INDEX ON change_set_apply_schedules (change_set_pk)
+    WHERE status IN ('Scheduled', 'Started');
+CREATE INDEX ON change_set_apply_schedules (status, apply_at);
+
+CREATE OR REPLACE FUNCTION change_set_apply_schedule_create_v1(
+    this_workspace_pk ident,
+    this_change_set_pk ident,
+    this_apply_at timestamp with time zone,
+    this_utc_offset_seconds integer,
+    this_scheduled_by_user_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row change_set_apply_schedules%ROWTYPE;
+BEGIN
+    INSERT INTO change_set_apply_schedules (workspace_pk, change_set_pk, apply_at, utc_offset_seconds,
+                                            scheduled_by_user_pk, status)
+    VALUES (this_workspace_pk, this_change_set_pk, this_apply_at, this_utc_offset_seconds,
+            this_scheduled_by_user_pk, 'Scheduled')
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_apply_schedule_set_status_v1(
+    this_pk ident,
+    this_status text,
+    this_failure text,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row change_set_apply_schedules%ROWTYPE;
+BEGIN
+    UPDATE change_set_apply_schedules
+    SET status     = this_status,
+        failure    = this_failure,
+        updated_at = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,58 @@
+-- Fixes grouped into phases by the dependencies between their components, run one phase at a time
+CREATE TABLE fix_plans
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    -- The author of the fix batches created for each phase
+    author                      text                     NOT NULL,
+    status                      text                     NOT NULL,
+    -- The fixes of each phase, along with the fix batch running them once the phase started
+    phases                      jsonb                    NOT NULL,
+    next_phase                  integer                  NOT NULL DEFAULT 0,
+    running_fix_batch_id        ident
+);
+CREATE INDEX ON fix_plans (workspace_pk);
+CREATE INDEX ON fix_plans (running_fix_batch_id);
+
+CREATE OR REPLACE FUNCTION fix_plan_create_v1(
+    this_workspace_pk ident,
+    this_author text,
+    this_phases jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row fix_plans%ROWTYPE;
+BEGIN
+    INSERT INTO fix_plans (workspace_pk, author, status, phases)
+    VALUES (this_workspace_pk, this_author, 'Pending', this_phases)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION fix_plan_update_v1(
+    this_pk ident,
+    this_status text,
+    this_phases jsonb,
+    this_next_phase integer,
+    this_running_fix_batch_id ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row fix_plans%ROWTYPE;
+BEGIN
+    UPDATE fix_plans
+    SET status               = this_status,
+        phases               = this_phases,
+        next_phase           = this_next_phase,
+        running_fix_batch_id = this_running_fix_batch_id,
+        updated_at           = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,36 @@
+-- The lifecycle state of each component, moved on by its qualifications, its fixes and the health
+-- of its resource. Components without a row are in the "Draft" state.
+CREATE TABLE component_lifecycles
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    component_id                ident                    NOT NULL,
+    state                       text                     NOT NULL,
+    previous_state              text                     NOT NULL,
+    UNIQUE (workspace_pk, component_id)
+);
+CREATE INDEX ON component_lifecycles (workspace_pk, state);
+
+CREATE OR REPLACE FUNCTION component_lifecycle_upsert_v1(
+    this_workspace_pk ident,
+    this_component_id ident,
+    this_state text,
+    this_previous_state text,
+    OUT object json) AS
+$$
+DECLARE
+    this_row component_lifecycles%ROWTYPE;
+BEGIN
+    INSERT INTO component_lifecycles (workspace_pk, component_id, state, previous_state)
+    VALUES (this_workspace_pk, this_component_id, this_state, this_previous_state)
+    ON CONFLICT (workspace_pk, component_id) DO UPDATE
+        SET state          = this_state,
+            previous_state = this_previous_state,
+            updated_at     = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,51 @@
+CREATE TABLE component_labels
+(
+    pk                          ident primary key default ident_create_v1(),
+    id                          ident not null default ident_create_v1(),
+    tenancy_workspace_pk        ident                    NOT NULL,
+    visibility_change_set_pk    ident                    NOT NULL DEFAULT ident_nil_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    component_id                ident                    NOT NULL,
+    key                         text                     NOT NULL,
+    value                       text                     NOT NULL
+);
+CREATE INDEX ON component_labels (component_id);
+CREATE INDEX ON component_labels (key, value);
+SELECT standard_model_table_constraints_v1('component_labels');
+
+INSERT INTO standard_models (table_name, table_type, history_event_label_base, history_event_message_name)
+VALUES ('component_labels', 'model', 'component_label', 'Component Label');
+
+CREATE OR REPLACE FUNCTION component_label_create_v1(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_component_id ident,
+    this_key text,
+    this_value text,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           component_labels%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO component_labels (tenancy_workspace_pk,
+                                  visibility_change_set_pk,
+                                  component_id,
+                                  key,
+                                  value)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_component_id,
+            this_key,
+            this_value)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
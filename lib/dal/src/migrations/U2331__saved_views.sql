@@ -0,0 +1,60 @@
+-- Named label selectors for the diagram and component lists, private to their owner unless shared
+CREATE TABLE saved_views
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    owner_user_pk               ident                    NOT NULL,
+    name                        text                     NOT NULL,
+    selector                    text                     NOT NULL,
+    -- An opaque reference to the diagram layout the view was saved with, owned by the client
+    layout_snapshot_ref         text,
+    shared                      bool                     NOT NULL DEFAULT false
+);
+CREATE INDEX ON saved_views (workspace_pk, owner_user_pk);
+
+CREATE OR REPLACE FUNCTION saved_view_create_v1(
+    this_workspace_pk ident,
+    this_owner_user_pk ident,
+    this_name text,
+    this_selector text,
+    this_layout_snapshot_ref text,
+    this_shared bool,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row saved_views%ROWTYPE;
+BEGIN
+    INSERT INTO saved_views (workspace_pk, owner_user_pk, name, selector, layout_snapshot_ref, shared)
+    VALUES (this_workspace_pk, this_owner_user_pk, this_name, this_selector, this_layout_snapshot_ref,
+            this_shared)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION saved_view_update_v1(
+    this_pk ident,
+    this_name text,
+    this_selector text,
+    this_layout_snapshot_ref text,
+    this_shared bool,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row saved_views%ROWTYPE;
+BEGIN
+    UPDATE saved_views
+    SET name                = this_name,
+        selector            = this_selector,
+        layout_snapshot_ref = this_layout_snapshot_ref,
+        shared              = this_shared,
+        updated_at          = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
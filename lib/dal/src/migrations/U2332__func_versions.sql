@@ -0,0 +1,96 @@
+-- Immutable snapshots of the code of a func. Builtin funcs share their ids across workspaces, so
+-- versions (and the pins rolling them out) are global.
+CREATE TABLE func_versions
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    func_id                     ident                    NOT NULL,
+    version                     bigint                   NOT NULL,
+    backend_response_type       text                     NOT NULL,
+    handler                     text,
+    code_base64                 text,
+    code_sha256                 text                     NOT NULL,
+    UNIQUE (func_id, version)
+);
+
+CREATE OR REPLACE FUNCTION func_version_immutable_v1()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    RAISE EXCEPTION 'func_versions are immutable: cannot update version % of func %', OLD.version, OLD.func_id;
+END;
+$$ LANGUAGE PLPGSQL;
+
+CREATE TRIGGER func_versions_immutable
+    BEFORE UPDATE
+    ON func_versions
+    FOR EACH ROW
+EXECUTE FUNCTION func_version_immutable_v1();
+
+-- Pins a func to a version for a schema variant, a workspace, both or (with neither) everyone. The
+-- most specific pin wins: schema variant and workspace, then workspace, then schema variant.
+CREATE TABLE func_version_pins
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    func_id                     ident                    NOT NULL,
+    -- NULL for every schema variant
+    schema_variant_id           ident,
+    -- NULL for every workspace
+    workspace_pk                ident,
+    version                     bigint                   NOT NULL,
+    FOREIGN KEY (func_id, version) REFERENCES func_versions (func_id, version)
+);
+CREATE UNIQUE INDEX ON func_version_pins (func_id,
+                                          COALESCE(schema_variant_id, ident_nil_v1()),
+                                          COALESCE(workspace_pk, ident_nil_v1()));
+
+-- Snapshots the given code as the next version of the func.
+CREATE OR REPLACE FUNCTION func_version_create_v1(
+    this_func_id ident,
+    this_backend_response_type text,
+    this_handler text,
+    this_code_base64 text,
+    this_code_sha256 text,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row func_versions%ROWTYPE;
+BEGIN
+    INSERT INTO func_versions (func_id, version, backend_response_type, handler, code_base64, code_sha256)
+    SELECT this_func_id,
+           COALESCE(MAX(func_versions.version), 0) + 1,
+           this_backend_response_type,
+           this_handler,
+           this_code_base64,
+           this_code_sha256
+    FROM func_versions
+    WHERE func_versions.func_id = this_func_id
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION func_version_pin_set_v1(
+    this_func_id ident,
+    this_schema_variant_id ident,
+    this_workspace_pk ident,
+    this_version bigint,
+    OUT object json) AS
+$$
+DECLARE
+    this_row func_version_pins%ROWTYPE;
+BEGIN
+    INSERT INTO func_version_pins (func_id, schema_variant_id, workspace_pk, version)
+    VALUES (this_func_id, this_schema_variant_id, this_workspace_pk, this_version)
+    ON CONFLICT (func_id, COALESCE(schema_variant_id, ident_nil_v1()), COALESCE(workspace_pk, ident_nil_v1()))
+        DO UPDATE
+        SET version    = EXCLUDED.version,
+            updated_at = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,6 @@
+-- Return values derived from secrets are sealed with the key pair of their workspace: the values
+-- are stored only in crypted_values and value and unprocessed_value are left NULL.
+ALTER TABLE func_binding_return_values
+    ADD COLUMN sensitive      bool NOT NULL DEFAULT false,
+    ADD COLUMN key_pair_pk    ident,
+    ADD COLUMN crypted_values text;
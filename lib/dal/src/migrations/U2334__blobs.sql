@@ -0,0 +1,28 @@
+-- Content addressed blobs, see si_data_pg::PgBlobStore. Rows refer to a blob with the
+-- "blake3:<hash>" reference of its content.
+CREATE TABLE blobs
+(
+    hash      text PRIMARY KEY,
+    content   bytea                    NOT NULL,
+    size      bigint                   NOT NULL,
+    -- Refreshed every time the blob is stored again.
+    stored_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+
+-- Deletes the blobs no func binding return value refers to anymore, in any workspace or change
+-- set. Only blobs stored before the given time are considered, leaving alone the recent ones which
+-- may be referred to by transactions that have not committed yet.
+CREATE OR REPLACE FUNCTION blob_collect_garbage_v1(this_stored_before timestamp with time zone,
+                                                   OUT deleted bigint) AS
+$$
+BEGIN
+    DELETE
+    FROM blobs
+    WHERE blobs.stored_at < this_stored_before
+      AND blobs.hash NOT IN (SELECT found.reference[1]
+                             FROM func_binding_return_values AS fbrv,
+                                  regexp_matches(concat(fbrv.unprocessed_value::text, ' ', fbrv.value::text),
+                                                 'blake3:([0-9a-f]{64})', 'g') AS found(reference));
+    GET DIAGNOSTICS deleted = ROW_COUNT;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,135 @@
+-- Reading head through the <table>_v1 function of a standard model table resolves visibility with a
+-- join against change set versions and a DISTINCT ON, even though head holds at most a single
+-- live row per id and workspace. The hottest tables get a partial index over their live head rows
+-- and a <table>_head_v1 function reading them directly, which the standard model helpers use for
+-- head reads which do not look at deleted objects.
+CREATE OR REPLACE FUNCTION standard_model_head_fast_path_create_v1(this_table_name text) RETURNS VOID AS
+$$
+DECLARE
+    create_query text;
+BEGIN
+    create_query := format('CREATE INDEX %1$s_head ON %1$I (tenancy_workspace_pk, id) '
+                           '    WHERE visibility_change_set_pk = ident_nil_v1() '
+                           '      AND visibility_deleted_at IS NULL; '
+                           'CREATE FUNCTION %1$I_head_v1( '
+                           '    this_tenancy jsonb '
+                           ') '
+                           'RETURNS SETOF %1$I '
+                           'LANGUAGE sql '
+                           'STABLE PARALLEL SAFE CALLED ON NULL INPUT '
+                           'AS $table_head_fn$ '
+                           '    SELECT %1$I.* '
+                           '    FROM %1$I '
+                           '    WHERE %1$I.tenancy_workspace_pk = (this_tenancy ->> ''tenancy_workspace_pk'')::ident '
+                           '      AND %1$I.visibility_change_set_pk = ident_nil_v1() '
+                           '      AND %1$I.visibility_deleted_at IS NULL '
+                           '$table_head_fn$; ',
+                           this_table_name);
+    RAISE DEBUG 'head fast path query: %', create_query;
+    EXECUTE create_query;
+END;
+$$ LANGUAGE plpgsql VOLATILE;
+
+SELECT standard_model_head_fast_path_create_v1('attribute_values');
+SELECT standard_model_head_fast_path_create_v1('components');
+SELECT standard_model_head_fast_path_create_v1('edges');
+
+CREATE OR REPLACE FUNCTION get_by_id_head_v1(this_table_text text, this_tenancy jsonb, this_id ident)
+    RETURNS TABLE
+            (
+                id                       ident,
+                visibility_change_set_pk ident,
+                visibility_deleted_at    timestamp with time zone,
+                object                   json
+            )
+AS
+$$
+DECLARE
+    this_table regclass;
+BEGIN
+    this_table := this_table_text::regclass;
+    RETURN QUERY EXECUTE format('SELECT '
+                                '   table_alias.id, '
+                                '   table_alias.visibility_change_set_pk, '
+                                '   table_alias.visibility_deleted_at, '
+                                '   row_to_json(table_alias.*) AS object '
+                                ' FROM %1$I_head_v1(%3$L) AS table_alias '
+                                ' WHERE table_alias.id = %2$L '
+        , this_table, this_id, this_tenancy);
+END ;
+$$ LANGUAGE PLPGSQL STABLE;
+
+CREATE OR REPLACE FUNCTION find_by_attr_head_v1(this_table_text text, this_tenancy jsonb,
+                                                this_attr_name text, this_value text)
+    RETURNS TABLE
+            (
+                id                       ident,
+                visibility_change_set_pk ident,
+                object                   json
+            )
+AS
+$$
+DECLARE
+    this_table regclass;
+BEGIN
+    this_table := this_table_text::regclass;
+    RETURN QUERY EXECUTE format('SELECT '
+                                '   table_alias.id, '
+                                '   table_alias.visibility_change_set_pk, '
+                                '   row_to_json(table_alias.*) AS object '
+                                ' FROM %1$I_head_v1(%2$L) AS table_alias '
+                                ' WHERE table_alias.%3$I = %4$L '
+                                ' ORDER BY id '
+        , this_table, this_tenancy, this_attr_name, this_value);
+END ;
+$$ LANGUAGE PLPGSQL STABLE;
+
+CREATE OR REPLACE FUNCTION find_by_attr_in_head_v1(this_table_text text, this_tenancy jsonb,
+                                                   this_attr_name text, this_value text[])
+    RETURNS TABLE
+            (
+                id                       ident,
+                visibility_change_set_pk ident,
+                object                   json
+            )
+AS
+$$
+DECLARE
+    this_table regclass;
+BEGIN
+    this_table := this_table_text::regclass;
+    RETURN QUERY EXECUTE format('SELECT '
+                                '   table_alias.id, '
+                                '   table_alias.visibility_change_set_pk, '
+                                '   row_to_json(table_alias.*) AS object '
+                                ' FROM %1$I_head_v1(%2$L) AS table_alias '
+                                ' WHERE table_alias.%3$I = ANY (%4$L) '
+                                ' ORDER BY id '
+        , this_table, this_tenancy, this_attr_name, this_value);
+END ;
+$$ LANGUAGE PLPGSQL STABLE;
+
+CREATE OR REPLACE FUNCTION list_models_head_v1(this_table_text text, this_tenancy jsonb)
+    RETURNS TABLE
+            (
+                id                       ident,
+                visibility_change_set_pk ident,
+                visibility_deleted_at    timestamp with time zone,
+                object                   json
+            )
+AS
+$$
+DECLARE
+    this_table regclass;
+BEGIN
+    this_table := this_table_text::regclass;
+    RETURN QUERY EXECUTE format('SELECT '
+                                '   table_alias.id, '
+                                '   table_alias.visibility_change_set_pk, '
+                                '   table_alias.visibility_deleted_at, '
+                                '   row_to_json(table_alias.*) AS object '
+                                ' FROM %1$I_head_v1(%2$L) AS table_alias '
+                                ' ORDER BY id '
+        , this_table, this_tenancy);
+END ;
+$$ LANGUAGE PLPGSQL STABLE;
@@ -0,0 +1,34 @@
+-- Scopes which schemas a workspace sees by the category of their ui menu. A workspace with allow
+-- rules only sees the schemas in an allowed category, and never sees those in a denied category.
+-- A rule for a category also applies to the categories nested under it.
+CREATE TABLE schema_category_rules
+(
+    pk           ident primary key default ident_create_v1(),
+    created_at   timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at   timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk ident                    NOT NULL,
+    category     text                     NOT NULL,
+    kind         text                     NOT NULL CHECK (kind IN ('allow', 'deny')),
+    UNIQUE (workspace_pk, category)
+);
+
+CREATE OR REPLACE FUNCTION schema_category_rule_set_v1(
+    this_workspace_pk ident,
+    this_category text,
+    this_kind text,
+    OUT object json) AS
+$$
+DECLARE
+    this_row schema_category_rules%ROWTYPE;
+BEGIN
+    INSERT INTO schema_category_rules (workspace_pk, category, kind)
+    VALUES (this_workspace_pk, this_category, this_kind)
+    ON CONFLICT (workspace_pk, category)
+        DO UPDATE
+        SET kind       = EXCLUDED.kind,
+            updated_at = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,30 @@
+-- The last qualification summary published for head or a change set of a workspace, so that a
+-- summary is only published again once it changes.
+CREATE TABLE qualification_summaries
+(
+    workspace_pk  ident                    NOT NULL,
+    change_set_pk ident                    NOT NULL,
+    updated_at    timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    summary       jsonb                    NOT NULL,
+    PRIMARY KEY (workspace_pk, change_set_pk)
+);
+
+-- Records the summary, returning whether it differs from the one recorded before.
+CREATE OR REPLACE FUNCTION qualification_summary_record_v1(
+    this_workspace_pk ident,
+    this_change_set_pk ident,
+    this_summary jsonb,
+    OUT changed bool) AS
+$$
+BEGIN
+    INSERT INTO qualification_summaries (workspace_pk, change_set_pk, summary)
+    VALUES (this_workspace_pk, this_change_set_pk, this_summary)
+    ON CONFLICT (workspace_pk, change_set_pk)
+        DO UPDATE
+        SET summary    = EXCLUDED.summary,
+            updated_at = CLOCK_TIMESTAMP()
+        WHERE qualification_summaries.summary IS DISTINCT FROM EXCLUDED.summary;
+
+    changed := FOUND;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,50 @@
+-- Point-in-time copies of the attribute values of components, from which a component can be
+-- restored. Snapshots belong to the workspace rather than to a change set, so they outlive the
+-- change set they were taken in and the component itself.
+CREATE TABLE component_snapshots
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    -- The change set the component was seen in, or nil for head
+    change_set_pk               ident                    NOT NULL,
+    component_id                ident                    NOT NULL,
+    schema_variant_id           ident                    NOT NULL,
+    trigger                     text                     NOT NULL,
+    actor_user_pk               ident,
+    name                        text                     NOT NULL,
+    component_type              text                     NOT NULL,
+    -- The whole attribute value tree of the component, as seen in its component view
+    properties                  jsonb                    NOT NULL,
+    -- The values set by hand, which a restore sets again
+    values                      jsonb                    NOT NULL
+);
+CREATE INDEX ON component_snapshots (workspace_pk, component_id, created_at DESC);
+
+CREATE OR REPLACE FUNCTION component_snapshot_create_v1(
+    this_workspace_pk ident,
+    this_change_set_pk ident,
+    this_component_id ident,
+    this_schema_variant_id ident,
+    this_trigger text,
+    this_actor_user_pk ident,
+    this_name text,
+    this_component_type text,
+    this_properties jsonb,
+    this_values jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row component_snapshots%ROWTYPE;
+BEGIN
+    INSERT INTO component_snapshots (workspace_pk, change_set_pk, component_id, schema_variant_id,
+                                     trigger, actor_user_pk, name, component_type, properties,
+                                     values)
+    VALUES (this_workspace_pk, this_change_set_pk, this_component_id, this_schema_variant_id,
+            this_trigger, this_actor_user_pk, this_name, this_component_type, this_properties,
+            this_values)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
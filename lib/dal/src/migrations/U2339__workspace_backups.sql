@@ -0,0 +1,77 @@
+-- Model level backups of whole workspaces, taken from head: the schema variants and funcs of the
+-- workspace, as a module kept in the blobs table, and its components, as a blueprint spec. A
+-- backup is restored into a new workspace, leaving the original alone.
+CREATE TABLE workspace_backups
+(
+    pk                          ident primary key default ident_create_v1(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    trigger                     text                     NOT NULL,
+    actor_user_pk               ident,
+    -- The "blake3:<hash>" reference of the module blob
+    module_reference            text                     NOT NULL,
+    module_size                 bigint                   NOT NULL,
+    -- The components of head, with the edges between them and their values set by hand
+    spec                        jsonb                    NOT NULL
+);
+CREATE INDEX ON workspace_backups (workspace_pk, created_at DESC);
+
+CREATE OR REPLACE FUNCTION workspace_backup_create_v1(
+    this_workspace_pk ident,
+    this_trigger text,
+    this_actor_user_pk ident,
+    this_module_reference text,
+    this_module_size bigint,
+    this_spec jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row workspace_backups%ROWTYPE;
+BEGIN
+    INSERT INTO workspace_backups (workspace_pk, trigger, actor_user_pk, module_reference,
+                                   module_size, spec)
+    VALUES (this_workspace_pk, this_trigger, this_actor_user_pk, this_module_reference,
+            this_module_size, this_spec)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Deletes the backups taken before the given time, in every workspace, except for the newest
+-- ones of each workspace, so that a workspace whose backups stopped being taken keeps some.
+CREATE OR REPLACE FUNCTION workspace_backup_prune_v1(this_cutoff timestamp with time zone,
+                                                     this_keep_last bigint,
+                                                     OUT pruned bigint) AS
+$$
+BEGIN
+    DELETE
+    FROM workspace_backups
+    WHERE workspace_backups.created_at < this_cutoff
+      AND workspace_backups.pk NOT IN (SELECT newest.pk
+                                       FROM (SELECT wb.pk,
+                                                    row_number() OVER (PARTITION BY wb.workspace_pk
+                                                        ORDER BY wb.created_at DESC) AS position
+                                             FROM workspace_backups AS wb) AS newest
+                                       WHERE newest.position <= this_keep_last);
+    GET DIAGNOSTICS pruned = ROW_COUNT;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Backup modules are blobs too, which are kept for as long as their backup.
+CREATE OR REPLACE FUNCTION blob_collect_garbage_v1(this_stored_before timestamp with time zone,
+                                                   OUT deleted bigint) AS
+$$
+BEGIN
+    DELETE
+    FROM blobs
+    WHERE blobs.stored_at < this_stored_before
+      AND blobs.hash NOT IN (SELECT found.reference[1]
+                             FROM func_binding_return_values AS fbrv,
+                                  regexp_matches(concat(fbrv.unprocessed_value::text, ' ', fbrv.value::text),
+                                                 'blake3:([0-9a-f]{64})', 'g') AS found(reference))
+      AND blobs.hash NOT IN (SELECT substring(workspace_backups.module_reference FROM 8)
+                             FROM workspace_backups);
+    GET DIAGNOSTICS deleted = ROW_COUNT;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
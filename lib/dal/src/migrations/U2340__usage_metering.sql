@@ -0,0 +1,51 @@
+-- What each workspace uses, as it is used: one row per metered event, recorded in the transaction
+-- of the work being metered so that work which is rolled back is not metered either.
+CREATE TABLE usage_events
+(
+    pk                          ident primary key default ident_create_v1(),
+    recorded_at                 timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    workspace_pk                ident                    NOT NULL,
+    metric                      text                     NOT NULL,
+    quantity                    double precision         NOT NULL
+);
+CREATE INDEX ON usage_events (workspace_pk, recorded_at);
+CREATE INDEX ON usage_events (recorded_at);
+
+-- The events of each workspace summed up by metric and by hour, in UTC.
+CREATE TABLE usage_hourly
+(
+    workspace_pk                ident                    NOT NULL,
+    metric                      text                     NOT NULL,
+    hour                        timestamp with time zone NOT NULL,
+    quantity                    double precision         NOT NULL,
+    PRIMARY KEY (workspace_pk, metric, hour)
+);
+
+-- Moves the events recorded before the given time into the hourly totals. Events committed late
+-- are added to the totals of their hour by a later run.
+CREATE OR REPLACE FUNCTION usage_aggregate_v1(this_recorded_before timestamp with time zone,
+                                              OUT aggregated bigint) AS
+$$
+BEGIN
+    WITH moved AS (
+        DELETE
+        FROM usage_events
+        WHERE usage_events.recorded_at < this_recorded_before
+        RETURNING usage_events.workspace_pk, usage_events.metric, usage_events.quantity,
+            usage_events.recorded_at
+    ), totals AS (
+        INSERT INTO usage_hourly (workspace_pk, metric, hour, quantity)
+        SELECT moved.workspace_pk,
+               moved.metric,
+               date_trunc('hour', moved.recorded_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+               sum(moved.quantity)
+        FROM moved
+        GROUP BY 1, 2, 3
+        ON CONFLICT (workspace_pk, metric, hour)
+            DO UPDATE SET quantity = usage_hourly.quantity + excluded.quantity
+    )
+    SELECT count(*)
+    INTO aggregated
+    FROM moved;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,20 @@
+-- Refresh tokens could not be redeemed for a session, which only the auth api can sign, so they
+-- are no longer issued.
+CREATE OR REPLACE FUNCTION session_revoke_all_for_user_v1(
+    this_user_pk ident,
+    OUT this_revoked_before timestamp with time zone) AS
+$$
+BEGIN
+    this_revoked_before := CLOCK_TIMESTAMP();
+
+    INSERT INTO session_revocations (user_pk, revoked_before)
+    VALUES (this_user_pk, this_revoked_before)
+    ON CONFLICT (user_pk) DO UPDATE
+        SET revoked_before = EXCLUDED.revoked_before,
+            updated_at     = CLOCK_TIMESTAMP();
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+DROP FUNCTION IF EXISTS refresh_token_create_v1(ident, ident, text, timestamp with time zone);
+DROP FUNCTION IF EXISTS refresh_token_revoke_v1(ident);
+DROP TABLE IF EXISTS refresh_tokens;
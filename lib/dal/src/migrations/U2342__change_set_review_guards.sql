@@ -0,0 +1,54 @@
+-- The user who created a change set, who may not review it. Change sets created before this
+-- column, or by the system, have none.
+ALTER TABLE change_sets ADD COLUMN created_by_user_pk ident;
+
+CREATE OR REPLACE FUNCTION change_set_create_v2(this_name text,
+                                                this_note text,
+                                                this_status text,
+                                                this_tenancy jsonb,
+                                                this_created_by_user_pk ident,
+                                                OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        change_sets%ROWTYPE;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+    INSERT INTO change_sets (name, note, status, tenancy_workspace_pk, created_by_user_pk)
+    VALUES (this_name, this_note, this_status, this_tenancy_record.tenancy_workspace_pk,
+            this_created_by_user_pk)
+    RETURNING * INTO this_new_row;
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_review_request_v2(
+    this_workspace_pk ident,
+    this_change_set_pk ident,
+    this_reviewer_user_pk ident,
+    this_requested_by_user_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_row               change_set_reviews%ROWTYPE;
+BEGIN
+    -- Requesting a review again resets an approval, but a rejection stands until the reviewer
+    -- approves the change set themselves
+    INSERT INTO change_set_reviews (workspace_pk, change_set_pk, reviewer_user_pk,
+                                    requested_by_user_pk, status)
+    VALUES (this_workspace_pk, this_change_set_pk, this_reviewer_user_pk,
+            this_requested_by_user_pk, 'Pending')
+    ON CONFLICT (change_set_pk, reviewer_user_pk) WHERE visibility_deleted_at IS NULL
+        DO UPDATE SET requested_by_user_pk = EXCLUDED.requested_by_user_pk,
+                      status               = CASE change_set_reviews.status
+                                                 WHEN 'Rejected' THEN change_set_reviews.status
+                                                 ELSE 'Pending' END,
+                      comment              = CASE change_set_reviews.status
+                                                 WHEN 'Rejected' THEN change_set_reviews.comment
+                                                 END,
+                      updated_at           = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_row;
+
+    object := row_to_json(this_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
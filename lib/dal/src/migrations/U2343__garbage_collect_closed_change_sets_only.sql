@@ -0,0 +1,72 @@
+-- Only hard deletes rows on head and in applied or abandoned change sets. A row soft deleted in an
+-- open change set hides the row of the same object on head, so the object would reappear in the
+-- change set if the row were hard deleted.
+CREATE OR REPLACE FUNCTION garbage_collect_table_v1(this_table_text text,
+                                                    this_tenancy jsonb,
+                                                    this_deleted_before timestamp with time zone,
+                                                    OUT hard_deleted bigint)
+AS
+$$
+DECLARE
+    this_table           regclass;
+    purged_ids           ident[];
+    gone_ids             ident[];
+    relationship         record;
+    relationship_deleted bigint;
+BEGIN
+    this_table := this_table_text::regclass;
+
+    EXECUTE format('WITH purged AS (DELETE FROM %1$I '
+                   '                WHERE in_tenancy_v1($1, %1$I.tenancy_workspace_pk) '
+                   '                  AND visibility_deleted_at < $2 '
+                   '                  AND (visibility_change_set_pk = ident_nil_v1() '
+                   '                       OR visibility_change_set_pk IN '
+                   '                          (SELECT pk FROM change_sets '
+                   '                           WHERE status IN (''Applied'', ''Abandoned''))) '
+                   '                RETURNING id) '
+                   'SELECT count(*), COALESCE(array_agg(DISTINCT id), ''{}'') FROM purged',
+                   this_table)
+        INTO hard_deleted, purged_ids
+        USING this_tenancy, this_deleted_before;
+
+    IF cardinality(purged_ids) = 0 THEN
+        RETURN;
+    END IF;
+
+    -- Objects soft deleted in a change set may still be alive in other change sets or on head
+    EXECUTE format('SELECT COALESCE(array_agg(purged_id), ''{}'') '
+                   'FROM unnest($1) AS purged_id '
+                   'WHERE NOT EXISTS (SELECT 1 FROM %1$I WHERE %1$I.id = purged_id)',
+                   this_table)
+        INTO gone_ids
+        USING purged_ids;
+
+    IF cardinality(gone_ids) = 0 THEN
+        RETURN;
+    END IF;
+
+    FOR relationship IN SELECT table_name, table_type
+                        FROM standard_models
+                        WHERE table_type IN ('belongs_to', 'many_to_many')
+        LOOP
+            IF relationship.table_type = 'belongs_to' THEN
+                EXECUTE format('WITH deleted AS (DELETE FROM %1$I '
+                               '                 WHERE object_id = ANY ($1) OR belongs_to_id = ANY ($1) '
+                               '                 RETURNING 1) '
+                               'SELECT count(*) FROM deleted',
+                               relationship.table_name)
+                    INTO relationship_deleted
+                    USING gone_ids;
+            ELSE
+                EXECUTE format('WITH deleted AS (DELETE FROM %1$I '
+                               '                 WHERE left_object_id = ANY ($1) OR right_object_id = ANY ($1) '
+                               '                 RETURNING 1) '
+                               'SELECT count(*) FROM deleted',
+                               relationship.table_name)
+                    INTO relationship_deleted
+                    USING gone_ids;
+            END IF;
+            hard_deleted := hard_deleted + relationship_deleted;
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -1,6 +1,7 @@
 //! This module is responsible for creating NodeMenus. At the moment, it only really makes
 //! the node add menu. It creates a tree for the menu, and can create it from the
-//! [`Schema`](crate::Schema)'s menu items based on the diagram context for the menu.
+//! [`Schema`](crate::Schema)'s menu items based on the diagram context for the menu. The
+//! [`SchemaCatalog`] describes the same schemas grouped by category, for richer menus.
 
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
@@ -8,26 +9,53 @@ use std::cell::RefCell;
 use std::sync::Arc;
 use thiserror::Error;
 
-use crate::schema::SchemaUiMenu;
-use crate::DalContext;
+use crate::schema::variant::definition::SchemaVariantDefinitionError;
+use crate::schema::variant::SchemaVariantError;
+use crate::schema::{SchemaCategoryRules, SchemaUiMenu};
+use crate::{
+    ComponentError, ComponentId, ComponentType, DalContext, ExternalProviderError,
+    InternalProviderError, SchemaVariantId, TransactionsError,
+};
 use crate::{SchemaError, SchemaId, StandardModel, StandardModelError};
 
+pub mod catalog;
+
+pub use catalog::{SchemaCatalog, SchemaCatalogCategory, SchemaCatalogEntry, SchemaCatalogFilter};
+
 #[allow(clippy::large_enum_variant)]
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum NodeMenuError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("external provider error: {0}")]
+    ExternalProvider(#[from] ExternalProviderError),
+    #[error("internal provider error: {0}")]
+    InternalProvider(#[from] InternalProviderError),
     #[error("cannot get inner category for non category menu item")]
     NoInnerCategory,
+    #[error("parent component is not a frame: {0:?}")]
+    ParentNotAFrame(ComponentType),
     #[error("cannot find menu entry; path does not exist: {0}")]
     PathDoesNotExist(String),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("schema error: {0}")]
     Schema(#[from] SchemaError),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] SchemaVariantError),
+    #[error("schema variant definition error: {0}")]
+    SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
+    #[error("schema variant not found: {0}")]
+    SchemaVariantNotFound(SchemaVariantId),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("standard model: {0}")]
     StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
 }
 
 pub type NodeMenuResult<T> = Result<T, NodeMenuError>;
@@ -210,9 +238,11 @@ pub struct GenerateMenuItem {
 }
 
 impl GenerateMenuItem {
-    /// Generates raw items and initializes menu items as an empty vec.
+    /// Generates raw items and initializes menu items as an empty vec. Only the schemas the
+    /// workspace sees, as scoped by its [`SchemaCategoryRules`], are included.
     pub async fn new(ctx: &DalContext, include_ui_hidden: bool) -> NodeMenuResult<Self> {
         let mut item_list = Vec::new();
+        let rules = SchemaCategoryRules::for_tenancy(ctx).await?;
 
         // NOTE(nick): currently, we only generate ui menus for schemas.
         let mut ui_menus = SchemaUiMenu::list(ctx).await?;
@@ -222,6 +252,9 @@ impl GenerateMenuItem {
         ui_menus.sort_by(|a, b| a.category().cmp(b.category()));
 
         for ui_menu in ui_menus.into_iter() {
+            if !rules.allows_ui_menu(&ui_menu) {
+                continue;
+            }
             if let Some(schema) = ui_menu.schema(ctx).await? {
                 if !include_ui_hidden && schema.ui_hidden() {
                     continue;
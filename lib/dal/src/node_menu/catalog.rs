@@ -0,0 +1,277 @@
+//! This module contains [`SchemaCatalog`], the schemas a workspace can add to its diagram grouped
+//! by category, along with what the node add menu shows about them: an icon, a description, a
+//! color and how many [`Components`](crate::Component) already use them.
+//!
+//! The catalog can be searched, and narrowed down to the schemas which fit in a parent frame:
+//! an aggregation frame only holds [`Components`](crate::Component) of its own schema, and a
+//! configuration frame holds the schemas consuming at least one of its outputs (or anything, if
+//! it has none).
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::schema::variant::definition::SchemaVariantDefinition;
+use crate::schema::{SchemaCategoryRules, SchemaUiMenu};
+use crate::{
+    Component, ComponentId, ComponentType, DalContext, ExternalProvider, InternalProvider,
+    SchemaId, SchemaVariant, SchemaVariantId, StandardModel,
+};
+
+use super::{NodeMenuError, NodeMenuResult};
+
+const SCHEMA_USAGE_COUNTS: &str = include_str!("../queries/node_menu/schema_usage_counts.sql");
+
+/// The name of the providers every [`SchemaVariant`] has to be put in frames, which say nothing
+/// about whether it fits in a frame.
+const FRAME_PROVIDER_NAME: &str = "Frame";
+/// The icon of schemas whose category has no icon of its own.
+const DEFAULT_ICON: &str = "logo-si";
+
+/// What to include in a [`SchemaCatalog`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCatalogFilter {
+    /// Only include the schemas whose name, category or description contains every
+    /// whitespace-separated term, ignoring case.
+    pub search: Option<String>,
+    /// Only include the schemas which fit in this frame.
+    pub parent_component_id: Option<ComponentId>,
+    /// Include the schemas hidden from the ui.
+    pub include_ui_hidden: bool,
+}
+
+/// The schemas a workspace can add to its diagram, grouped by category.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCatalog {
+    pub categories: Vec<SchemaCatalogCategory>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCatalogCategory {
+    pub name: String,
+    pub icon: String,
+    pub entries: Vec<SchemaCatalogEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCatalogEntry {
+    pub schema_id: SchemaId,
+    /// The [`SchemaVariant`] a node added from the entry uses.
+    pub schema_variant_id: SchemaVariantId,
+    pub name: String,
+    pub category: String,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub color: Option<String>,
+    /// How many [`Components`](crate::Component) of the schema exist in the change set.
+    pub usage_count: i64,
+}
+
+/// Which schemas fit in the parent frame of a [`SchemaCatalogFilter`].
+enum ParentFit {
+    Any,
+    Schema(SchemaId),
+    ConsumingAnyOf(HashSet<String>),
+}
+
+impl SchemaCatalog {
+    /// Assembles the catalog of the schemas the workspace of the current tenancy sees, as scoped by
+    /// its [`SchemaCategoryRules`]. Schemas without a default [`SchemaVariant`] cannot be added to
+    /// the diagram, and so are left out.
+    #[instrument(skip(ctx))]
+    pub async fn assemble(ctx: &DalContext, filter: &SchemaCatalogFilter) -> NodeMenuResult<Self> {
+        let rules = SchemaCategoryRules::for_tenancy(ctx).await?;
+        let parent_fit = match filter.parent_component_id {
+            Some(parent_component_id) => parent_fit(ctx, parent_component_id).await?,
+            None => ParentFit::Any,
+        };
+        let search_terms: Vec<String> = filter
+            .search
+            .iter()
+            .flat_map(|search| search.split_whitespace())
+            .map(str::to_lowercase)
+            .collect();
+        let usage_counts = usage_counts(ctx).await?;
+
+        let mut categories: Vec<SchemaCatalogCategory> = Vec::new();
+        for ui_menu in SchemaUiMenu::list(ctx).await? {
+            if !rules.allows_ui_menu(&ui_menu) {
+                continue;
+            }
+            let Some(schema) = ui_menu.schema(ctx).await? else {
+                continue;
+            };
+            if !filter.include_ui_hidden && schema.ui_hidden() {
+                continue;
+            }
+            let Some(schema_variant_id) = schema.default_schema_variant_id().copied() else {
+                continue;
+            };
+            let schema_variant = SchemaVariant::get_by_id(ctx, &schema_variant_id)
+                .await?
+                .ok_or(NodeMenuError::SchemaVariantNotFound(schema_variant_id))?;
+
+            let description =
+                SchemaVariantDefinition::get_by_schema_variant_id(ctx, &schema_variant_id)
+                    .await?
+                    .and_then(|definition| definition.description().map(ToOwned::to_owned));
+            if !matches_search(&search_terms, &ui_menu, description.as_deref()) {
+                continue;
+            }
+            if !fits_parent(ctx, &parent_fit, *schema.id(), schema_variant_id).await? {
+                continue;
+            }
+
+            let entry = SchemaCatalogEntry {
+                schema_id: *schema.id(),
+                schema_variant_id,
+                name: ui_menu.name().to_owned(),
+                category: ui_menu.category().to_owned(),
+                description,
+                link: schema_variant.link().map(ToOwned::to_owned),
+                color: schema_variant.color(ctx).await?,
+                usage_count: usage_counts.get(schema.id()).copied().unwrap_or(0),
+            };
+            match categories
+                .iter_mut()
+                .find(|category| category.name == ui_menu.category())
+            {
+                Some(category) => category.entries.push(entry),
+                None => categories.push(SchemaCatalogCategory {
+                    name: ui_menu.category().to_owned(),
+                    icon: icon_for_category(ui_menu.category()).to_owned(),
+                    entries: vec![entry],
+                }),
+            }
+        }
+
+        categories.sort_by(|a, b| a.name.cmp(&b.name));
+        for category in &mut categories {
+            category.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Ok(Self { categories })
+    }
+}
+
+/// The icon shown for the schemas of a category, which is the icon of the closest category it is
+/// nested under that has one.
+pub fn icon_for_category(category: &str) -> &'static str {
+    let mut category = category;
+    loop {
+        let icon = match category {
+            "AWS" | "AWS EC2" => Some("logo-aws"),
+            "CoreOS" => Some("logo-coreos"),
+            "Docker" => Some("logo-docker"),
+            "Kubernetes" => Some("logo-k8s"),
+            _ => None,
+        };
+        if let Some(icon) = icon {
+            return icon;
+        }
+        match category.rsplit_once('.') {
+            Some((parent, _)) => category = parent,
+            None => return DEFAULT_ICON,
+        }
+    }
+}
+
+fn matches_search(terms: &[String], ui_menu: &SchemaUiMenu, description: Option<&str>) -> bool {
+    let haystack = format!(
+        "{}\n{}\n{}",
+        ui_menu.name(),
+        ui_menu.category(),
+        description.unwrap_or_default()
+    )
+    .to_lowercase();
+    terms.iter().all(|term| haystack.contains(term.as_str()))
+}
+
+async fn parent_fit(
+    ctx: &DalContext,
+    parent_component_id: ComponentId,
+) -> NodeMenuResult<ParentFit> {
+    let parent = Component::get_by_id(ctx, &parent_component_id)
+        .await?
+        .ok_or(NodeMenuError::ComponentNotFound(parent_component_id))?;
+
+    match parent.get_type(ctx).await? {
+        ComponentType::AggregationFrame => {
+            let schema = parent
+                .schema(ctx)
+                .await?
+                .ok_or(NodeMenuError::ComponentNotFound(parent_component_id))?;
+            Ok(ParentFit::Schema(*schema.id()))
+        }
+        ComponentType::ConfigurationFrame => {
+            let schema_variant = parent
+                .schema_variant(ctx)
+                .await?
+                .ok_or(NodeMenuError::ComponentNotFound(parent_component_id))?;
+            let outputs: HashSet<String> =
+                ExternalProvider::list_for_schema_variant(ctx, *schema_variant.id())
+                    .await?
+                    .iter()
+                    .map(|provider| provider.name().to_owned())
+                    .filter(|name| name != FRAME_PROVIDER_NAME)
+                    .collect();
+            if outputs.is_empty() {
+                Ok(ParentFit::Any)
+            } else {
+                Ok(ParentFit::ConsumingAnyOf(outputs))
+            }
+        }
+        component_type => Err(NodeMenuError::ParentNotAFrame(component_type)),
+    }
+}
+
+async fn fits_parent(
+    ctx: &DalContext,
+    parent_fit: &ParentFit,
+    schema_id: SchemaId,
+    schema_variant_id: SchemaVariantId,
+) -> NodeMenuResult<bool> {
+    Ok(match parent_fit {
+        ParentFit::Any => true,
+        ParentFit::Schema(parent_schema_id) => *parent_schema_id == schema_id,
+        ParentFit::ConsumingAnyOf(outputs) => {
+            InternalProvider::list_explicit_for_schema_variant(ctx, schema_variant_id)
+                .await?
+                .iter()
+                .any(|provider| outputs.contains(provider.name()))
+        }
+    })
+}
+
+async fn usage_counts(ctx: &DalContext) -> NodeMenuResult<HashMap<SchemaId, i64>> {
+    let rows = ctx
+        .txns()
+        .await?
+        .pg()
+        .query(SCHEMA_USAGE_COUNTS, &[ctx.tenancy(), ctx.visibility()])
+        .await?;
+
+    let mut usage_counts = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let schema_id: SchemaId = row.try_get("schema_id")?;
+        let usage_count: i64 = row.try_get("usage_count")?;
+        usage_counts.insert(schema_id, usage_count);
+    }
+    Ok(usage_counts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn icons_fall_back_to_parent_categories() {
+        assert_eq!("logo-aws", icon_for_category("AWS EC2"));
+        assert_eq!("logo-k8s", icon_for_category("Kubernetes.Workloads"));
+        assert_eq!(DEFAULT_ICON, icon_for_category("test exclusive"));
+    }
+}
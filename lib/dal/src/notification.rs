@@ -0,0 +1,334 @@
+//! This module contains [`Notification`], which tells the users of a workspace about something
+//! they may have missed, such as a failing qualification or a change set waiting for their review,
+//! and [`NotificationPreferences`], which decides who is told about what.
+//!
+//! Notifications are built from the [`WsEvents`](WsEvent) published by the services, and are
+//! sent by the [`Notifier`](crate::tasks::Notifier) over every [`NotificationChannel`]: as
+//! [`Emails`](Email) through the [`EmailChannel`], and as Slack messages through the
+//! [`SlackChannel`].
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::qualification::QualificationSubCheckStatus;
+use crate::{
+    standard_model, standard_model_accessor_ro, ChangeSet, ChangeSetError, ChangeSetPk, Component,
+    ComponentError, DalContext, StandardModelError, Timestamp, TransactionsError, User, UserError,
+    UserPk, WsEvent, WsPayload,
+};
+
+pub mod email;
+pub mod slack;
+
+pub use email::{
+    CollectingEmailSender, Email, EmailError, EmailResult, EmailSender, SmtpConfig, SmtpEmailSender,
+};
+pub use slack::{SlackChannel, SlackError, SlackIntegration, SlackResult};
+
+const NOTIFICATION_PREFERENCES_GET: &str = include_str!("queries/notification_preferences/get.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum NotificationError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("change set not found: {0}")]
+    ChangeSetNotFound(ChangeSetPk),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("email error: {0}")]
+    Email(#[from] EmailError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("slack error: {0}")]
+    Slack(#[from] SlackError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("user error: {0}")]
+    User(#[from] UserError),
+}
+
+pub type NotificationResult<T> = Result<T, NotificationError>;
+
+/// What a [`Notification`] is about. Users can mute each kind on its own.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum NotificationKind {
+    /// A change set was applied to head.
+    ChangeSetApplied,
+    /// The user was asked to review a change set.
+    ChangeSetReviewRequested,
+    /// Qualifications of a component are failing.
+    QualificationFailed,
+    /// The resource of a component no longer matches its model.
+    ResourceDrifted,
+}
+
+impl NotificationKind {
+    /// Returns the kind of [`Notification`] users may be told about for the event, if any.
+    pub fn for_payload(payload: &WsPayload) -> Option<Self> {
+        match payload {
+            WsPayload::ChangeSetApplied(_) => Some(Self::ChangeSetApplied),
+            WsPayload::ChangeSetReviewRequested(_) => Some(Self::ChangeSetReviewRequested),
+            WsPayload::CheckedQualifications(_) => Some(Self::QualificationFailed),
+            WsPayload::ResourceDrifted(_) => Some(Self::ResourceDrifted),
+            _ => None,
+        }
+    }
+}
+
+/// The notifications a user wants to receive, across every workspace. Users who never set their
+/// preferences receive every notification.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotificationPreferences {
+    user_pk: UserPk,
+    email_enabled: bool,
+    muted_kinds: Vec<NotificationKind>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl NotificationPreferences {
+    standard_model_accessor_ro!(user_pk, UserPk);
+    standard_model_accessor_ro!(email_enabled, bool);
+    standard_model_accessor_ro!(muted_kinds, Vec<NotificationKind>);
+
+    /// Returns the preferences of the user, or `None` if they were never set.
+    pub async fn get(ctx: &DalContext, user_pk: UserPk) -> NotificationResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(NOTIFICATION_PREFERENCES_GET, &[&user_pk])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Sets the preferences of the user, replacing the previous ones.
+    #[instrument(skip(ctx))]
+    pub async fn set(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        email_enabled: bool,
+        muted_kinds: Vec<NotificationKind>,
+    ) -> NotificationResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM notification_preferences_set_v1($1, $2, $3)",
+                &[
+                    &user_pk,
+                    &email_enabled,
+                    &serde_json::to_value(&muted_kinds)?,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    /// Returns whether the user wants to be emailed about the kind of notification.
+    pub fn wants_email(&self, kind: NotificationKind) -> bool {
+        self.email_enabled && !self.muted_kinds.contains(&kind)
+    }
+}
+
+/// Something the users of a workspace are told about.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub kind: NotificationKind,
+    /// The name of the change set or component the notification is about.
+    pub name: String,
+    pub subject: String,
+    pub body: String,
+    /// The users to tell, or `None` to tell every user of the workspace.
+    pub user_pks: Option<Vec<UserPk>>,
+}
+
+impl Notification {
+    /// Builds the [`Notification`] for the event, or returns `None` if users are not told about
+    /// it. The [`DalContext`] must be in the workspace and change set of the event.
+    #[instrument(skip_all)]
+    pub async fn for_event(ctx: &DalContext, event: &WsEvent) -> NotificationResult<Option<Self>> {
+        let notification = match event.payload() {
+            WsPayload::ChangeSetApplied(change_set_pk) => {
+                let name = change_set_name(ctx, *change_set_pk).await?;
+                Self {
+                    kind: NotificationKind::ChangeSetApplied,
+                    name: name.clone(),
+                    subject: format!("Change set applied: {name}"),
+                    body: format!("The change set \"{name}\" was applied."),
+                    user_pks: None,
+                }
+            }
+            WsPayload::ChangeSetReviewRequested(payload) => {
+                let name = change_set_name(ctx, payload.change_set_pk()).await?;
+                Self {
+                    kind: NotificationKind::ChangeSetReviewRequested,
+                    name: name.clone(),
+                    subject: format!("Review requested: {name}"),
+                    body: format!(
+                        "You were asked to review the change set \"{name}\" before it is applied."
+                    ),
+                    user_pks: Some(vec![payload.reviewer_user_pk()]),
+                }
+            }
+            WsPayload::CheckedQualifications(payload) => {
+                let component_id = payload.component_id();
+                let failing: Vec<String> = Component::list_qualifications(ctx, component_id)
+                    .await?
+                    .into_iter()
+                    .filter(|qualification| {
+                        qualification.result.as_ref().map(|result| result.status)
+                            == Some(QualificationSubCheckStatus::Failure)
+                    })
+                    .map(|qualification| qualification.title)
+                    .collect();
+                if failing.is_empty() {
+                    return Ok(None);
+                }
+
+                let name = Component::find_name(ctx, component_id).await?;
+                let mut body = format!("These qualifications of \"{name}\" are failing:\n\n");
+                for title in failing {
+                    body.push_str(&format!("- {title}\n"));
+                }
+                Self {
+                    kind: NotificationKind::QualificationFailed,
+                    subject: format!("Qualifications failing: {name}"),
+                    name,
+                    body,
+                    user_pks: None,
+                }
+            }
+            WsPayload::ResourceDrifted(payload) => {
+                let name = Component::find_name(ctx, payload.component_id()).await?;
+                let mut body =
+                    format!("The resource of \"{name}\" no longer matches its model at:\n\n");
+                for drift in payload.drift() {
+                    body.push_str(&format!("- {}\n", drift.json_pointer));
+                }
+                Self {
+                    kind: NotificationKind::ResourceDrifted,
+                    subject: format!("Resource drifted: {name}"),
+                    name,
+                    body,
+                    user_pks: None,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(notification))
+    }
+
+    /// Builds an [`Email`] for each user told about the [`Notification`] who wants to be emailed
+    /// about it.
+    #[instrument(skip_all)]
+    pub async fn emails(&self, ctx: &DalContext) -> NotificationResult<Vec<Email>> {
+        let users = match &self.user_pks {
+            Some(user_pks) => {
+                let mut users = Vec::with_capacity(user_pks.len());
+                for user_pk in user_pks {
+                    if let Some(user) = User::get_by_pk(ctx, *user_pk).await? {
+                        users.push(user);
+                    }
+                }
+                users
+            }
+            None => {
+                let workspace_pk = ctx
+                    .tenancy()
+                    .workspace_pk()
+                    .ok_or(NotificationError::NoWorkspaceInTenancy)?;
+                User::list_for_workspace(ctx, workspace_pk).await?
+            }
+        };
+
+        let mut emails = Vec::new();
+        for user in users {
+            let wants_email = match NotificationPreferences::get(ctx, user.pk()).await? {
+                Some(preferences) => preferences.wants_email(self.kind),
+                None => true,
+            };
+            if wants_email {
+                emails.push(Email {
+                    to: vec![user.email().clone()],
+                    subject: self.subject.clone(),
+                    body: self.body.clone(),
+                });
+            }
+        }
+        Ok(emails)
+    }
+}
+
+/// Somewhere [`Notifications`](Notification) are sent, such as email or Slack.
+#[async_trait]
+pub trait NotificationChannel: fmt::Debug + Send + Sync {
+    /// Sends the [`Notification`] to whoever wants it on this channel. The [`DalContext`] must be
+    /// in the workspace of the notification.
+    async fn send(&self, ctx: &DalContext, notification: &Notification) -> NotificationResult<()>;
+}
+
+/// Emails [`Notifications`](Notification) to the users who want them, as decided by
+/// [`Notification::emails()`].
+#[derive(Clone, Debug)]
+pub struct EmailChannel {
+    sender: Arc<dyn EmailSender>,
+}
+
+impl EmailChannel {
+    pub fn new(sender: Arc<dyn EmailSender>) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    async fn send(&self, ctx: &DalContext, notification: &Notification) -> NotificationResult<()> {
+        for email in notification.emails(ctx).await? {
+            self.sender.send(&email).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn change_set_name(
+    ctx: &DalContext,
+    change_set_pk: ChangeSetPk,
+) -> NotificationResult<String> {
+    let change_set = ChangeSet::get_by_pk(ctx, &change_set_pk)
+        .await?
+        .ok_or(NotificationError::ChangeSetNotFound(change_set_pk))?;
+    Ok(change_set.name)
+}
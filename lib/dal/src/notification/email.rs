@@ -0,0 +1,408 @@
+//! This module contains [`EmailSender`], which sends the emails of
+//! [`Notifications`](super::Notification), along with its implementations: [`SmtpEmailSender`],
+//! which hands emails to an SMTP relay, and [`CollectingEmailSender`], which keeps them in memory
+//! for tests.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum EmailError {
+    #[error("invalid email address: {0:?}")]
+    InvalidAddress(String),
+    #[error("smtp io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("email has no recipients")]
+    NoRecipients,
+    #[error("smtp server replied {1:?} to {0}")]
+    UnexpectedReply(String, String),
+}
+
+pub type EmailResult<T> = Result<T, EmailError>;
+
+/// A plain text email.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Email {
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Sends [`Emails`](Email) on behalf of SI.
+#[async_trait]
+pub trait EmailSender: fmt::Debug + Send + Sync {
+    async fn send(&self, email: &Email) -> EmailResult<()>;
+}
+
+/// How to reach the SMTP relay which sends the emails of SI.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    /// The address emails are sent from.
+    pub from: String,
+    /// Credentials for `AUTH PLAIN`, if the relay requires them.
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+fn default_smtp_port() -> u16 {
+    25
+}
+
+impl fmt::Debug for SmtpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmtpConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("from", &self.from)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "..."))
+            .finish()
+    }
+}
+
+/// Sends [`Emails`](Email) through an SMTP relay.
+///
+/// The connection is not encrypted, so the relay is expected to run next to the service, such as
+/// a sidecar forwarding to the actual mail provider.
+#[derive(Clone, Debug)]
+pub struct SmtpEmailSender {
+    config: SmtpConfig,
+}
+
+impl SmtpEmailSender {
+    pub fn new(config: SmtpConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpEmailSender {
+    async fn send(&self, email: &Email) -> EmailResult<()> {
+        if email.to.is_empty() {
+            return Err(EmailError::NoRecipients);
+        }
+        validate_address(&self.config.from)?;
+        for to in &email.to {
+            validate_address(to)?;
+        }
+
+        let stream = TcpStream::connect((self.config.host.as_str(), self.config.port)).await?;
+        let mut stream = BufReader::new(stream);
+
+        expect_reply(&mut stream, "greeting", &[220]).await?;
+        let hello_name = self
+            .config
+            .from
+            .rsplit_once('@')
+            .map(|(_, domain)| domain)
+            .unwrap_or("localhost");
+        command(&mut stream, &format!("EHLO {hello_name}"), &[250]).await?;
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            let token = general_purpose::STANDARD.encode(format!("\0{username}\0{password}"));
+            command(&mut stream, &format!("AUTH PLAIN {token}"), &[235]).await?;
+        }
+        command(
+            &mut stream,
+            &format!("MAIL FROM:<{}>", self.config.from),
+            &[250],
+        )
+        .await?;
+        for to in &email.to {
+            command(&mut stream, &format!("RCPT TO:<{to}>"), &[250, 251]).await?;
+        }
+        command(&mut stream, "DATA", &[354]).await?;
+        let message = format_message(&self.config.from, email, Utc::now());
+        stream.get_mut().write_all(message.as_bytes()).await?;
+        command(&mut stream, ".", &[250]).await?;
+
+        // The email was accepted, so a relay hanging up without a goodbye is not worth failing for
+        let _ = command(&mut stream, "QUIT", &[221]).await;
+
+        Ok(())
+    }
+}
+
+/// Keeps the [`Emails`](Email) it is asked to send, so that tests can check them.
+#[derive(Clone, Debug, Default)]
+pub struct CollectingEmailSender {
+    sent: Arc<Mutex<Vec<Email>>>,
+}
+
+impl CollectingEmailSender {
+    /// Returns the [`Emails`](Email) sent so far, in the order they were sent.
+    pub async fn sent(&self) -> Vec<Email> {
+        self.sent.lock().await.clone()
+    }
+}
+
+#[async_trait]
+impl EmailSender for CollectingEmailSender {
+    async fn send(&self, email: &Email) -> EmailResult<()> {
+        if email.to.is_empty() {
+            return Err(EmailError::NoRecipients);
+        }
+        for to in &email.to {
+            validate_address(to)?;
+        }
+        self.sent.lock().await.push(email.clone());
+        Ok(())
+    }
+}
+
+/// Rejects addresses which could smuggle commands or headers into the conversation with the relay.
+fn validate_address(address: &str) -> EmailResult<()> {
+    let valid = match address.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !address
+                    .chars()
+                    .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ','))
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(EmailError::InvalidAddress(address.to_owned()))
+    }
+}
+
+async fn command(
+    stream: &mut BufReader<TcpStream>,
+    line: &str,
+    expected: &[u16],
+) -> EmailResult<()> {
+    stream
+        .get_mut()
+        .write_all(format!("{line}\r\n").as_bytes())
+        .await?;
+    // Only the verb is reported, since the arguments may hold credentials
+    let verb = line.split(' ').next().unwrap_or(line);
+    expect_reply(stream, verb, expected).await
+}
+
+/// Reads a reply, which spans several lines when every line but the last has a dash after the
+/// code, such as the extensions listed in reply to `EHLO`.
+async fn expect_reply(
+    stream: &mut BufReader<TcpStream>,
+    context: &str,
+    expected: &[u16],
+) -> EmailResult<()> {
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(EmailError::UnexpectedReply(
+                context.to_owned(),
+                "connection closed".to_owned(),
+            ));
+        }
+        let line = line.trim_end();
+        reply.push_str(line);
+        if line.as_bytes().get(3) != Some(&b'-') {
+            break;
+        }
+        reply.push('\n');
+    }
+
+    let code = reply.get(..3).and_then(|code| code.parse::<u16>().ok());
+    match code {
+        Some(code) if expected.contains(&code) => Ok(()),
+        _ => Err(EmailError::UnexpectedReply(context.to_owned(), reply)),
+    }
+}
+
+/// Formats the email as the contents of `DATA`, up to and excluding the final dot.
+fn format_message(from: &str, email: &Email, date: DateTime<Utc>) -> String {
+    let mut message = String::new();
+    message.push_str(&format!("From: {from}\r\n"));
+    message.push_str(&format!("To: {}\r\n", email.to.join(", ")));
+    message.push_str(&format!("Subject: {}\r\n", encode_header(&email.subject)));
+    message.push_str(&format!("Date: {}\r\n", date.to_rfc2822()));
+    message.push_str("MIME-Version: 1.0\r\n");
+    message.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+    message.push_str("Content-Transfer-Encoding: 8bit\r\n");
+    message.push_str("\r\n");
+    for line in email.body.lines() {
+        // A line holding a single dot would end the message early
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+/// Header values are ASCII on a single line, so anything else is sent as an encoded word.
+fn encode_header(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .collect();
+    if value.is_ascii() {
+        value
+    } else {
+        format!(
+            "=?utf-8?B?{}?=",
+            general_purpose::STANDARD.encode(value.as_bytes())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn email() -> Email {
+        Email {
+            to: vec!["bobo@example.com".to_owned()],
+            subject: "Qualifications failed\r\nBcc: eve@example.com".to_owned(),
+            body: "Poop canoe:\n.\n..is failing".to_owned(),
+        }
+    }
+
+    #[test]
+    fn formats_message() {
+        let date = Utc.with_ymd_and_hms(2023, 7, 1, 12, 0, 0).unwrap();
+        let message = format_message("si@example.com", &email(), date);
+        assert_eq!(
+            "From: si@example.com\r\n\
+             To: bobo@example.com\r\n\
+             Subject: Qualifications failed  Bcc: eve@example.com\r\n\
+             Date: Sat, 1 Jul 2023 12:00:00 +0000\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             Content-Transfer-Encoding: 8bit\r\n\
+             \r\n\
+             Poop canoe:\r\n\
+             ..\r\n\
+             ...is failing\r\n",
+            message
+        );
+        assert_eq!("=?utf-8?B?Q2Fub8OrIQ==?=", encode_header("Canoë!"));
+    }
+
+    #[test]
+    fn validates_addresses() {
+        validate_address("bobo@example.com").expect("address is valid");
+        for address in ["bobo", "@example.com", "bobo@", "bobo@example.com>\r\nDATA"] {
+            assert!(validate_address(address).is_err(), "{address} is valid");
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_through_relay() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("cannot bind listener");
+        let port = listener.local_addr().expect("no local addr").port();
+
+        let relay = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("cannot accept");
+            let mut stream = BufReader::new(stream);
+            stream
+                .get_mut()
+                .write_all(b"220 relay ready\r\n")
+                .await
+                .expect("cannot write");
+
+            let mut transcript = Vec::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.expect("cannot read") == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_owned();
+                let reply: Option<&[u8]> = if in_data {
+                    in_data = line != ".";
+                    (!in_data).then_some(b"250 queued\r\n")
+                } else if line.starts_with("EHLO") {
+                    Some(b"250-relay\r\n250 AUTH PLAIN\r\n")
+                } else if line.starts_with("AUTH") {
+                    Some(b"235 ok\r\n")
+                } else if line == "DATA" {
+                    in_data = true;
+                    Some(b"354 go ahead\r\n")
+                } else if line == "QUIT" {
+                    Some(b"221 bye\r\n")
+                } else {
+                    Some(b"250 ok\r\n")
+                };
+                let quit = line == "QUIT";
+                transcript.push(line);
+                if let Some(reply) = reply {
+                    stream
+                        .get_mut()
+                        .write_all(reply)
+                        .await
+                        .expect("cannot write");
+                }
+                if quit {
+                    break;
+                }
+            }
+            transcript
+        });
+
+        let sender = SmtpEmailSender::new(SmtpConfig {
+            host: "127.0.0.1".to_owned(),
+            port,
+            from: "si@example.com".to_owned(),
+            username: Some("si".to_owned()),
+            password: Some("hunter2".to_owned()),
+        });
+        sender.send(&email()).await.expect("cannot send email");
+
+        let transcript = relay.await.expect("relay failed");
+        let transcript: Vec<&str> = transcript
+            .iter()
+            .map(String::as_str)
+            .filter(|line| !line.starts_with("Date: "))
+            .collect();
+        assert_eq!(
+            vec![
+                "EHLO example.com",
+                "AUTH PLAIN AHNpAGh1bnRlcjI=",
+                "MAIL FROM:<si@example.com>",
+                "RCPT TO:<bobo@example.com>",
+                "DATA",
+                "From: si@example.com",
+                "To: bobo@example.com",
+                "Subject: Qualifications failed  Bcc: eve@example.com",
+                "MIME-Version: 1.0",
+                "Content-Type: text/plain; charset=utf-8",
+                "Content-Transfer-Encoding: 8bit",
+                "",
+                "Poop canoe:",
+                "..",
+                "...is failing",
+                ".",
+                "QUIT",
+            ],
+            transcript
+        );
+    }
+}
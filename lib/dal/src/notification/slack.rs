@@ -0,0 +1,393 @@
+//! This module contains [`SlackIntegration`], the Slack configuration of a workspace, and
+//! [`SlackChannel`], which posts [`Notifications`](Notification) to Slack as configured.
+//!
+//! Messages are posted either through a Slack incoming webhook, or with a bot token to the
+//! `chat.postMessage` API. Each kind of notification has a message template, in which
+//! `{{name}}`, `{{subject}}` and `{{body}}` are replaced with those of the notification.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+use url::Url;
+
+use super::{Notification, NotificationChannel, NotificationKind, NotificationResult};
+use crate::{
+    standard_model, standard_model_accessor_ro, AuditAction, AuditLog, AuditLogError, AuditTarget,
+    DalContext, StandardModelError, Timestamp, TransactionsError, WorkspacePk,
+};
+
+const SLACK_INTEGRATION_DELETE: &str = include_str!("../queries/slack_integration/delete.sql");
+const SLACK_INTEGRATION_GET: &str = include_str!("../queries/slack_integration/get.sql");
+
+/// The Slack API method used to post messages with a bot token.
+const SLACK_POST_MESSAGE_URL: &str = "https://slack.com/api/chat.postMessage";
+
+/// How long Slack is given to answer a message.
+const SLACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The kinds of notifications posted by a new integration when none are given.
+pub const DEFAULT_SLACK_KINDS: &[NotificationKind] = &[
+    NotificationKind::ChangeSetApplied,
+    NotificationKind::QualificationFailed,
+    NotificationKind::ResourceDrifted,
+];
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SlackError {
+    #[error("slack api error: {0}")]
+    Api(String),
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("invalid slack integration: {0}")]
+    InvalidIntegration(String),
+    #[error("no slack integration in workspace")]
+    NoIntegration,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("slack request error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SlackResult<T> = Result<T, SlackError>;
+
+/// Where, and how, the [`Notifications`](Notification) of a [`Workspace`](crate::Workspace) are
+/// posted to Slack.
+///
+/// The webhook url and the bot token are credentials, so they are never serialized.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SlackIntegration {
+    workspace_pk: WorkspacePk,
+    #[serde(skip_serializing)]
+    webhook_url: Option<String>,
+    #[serde(skip_serializing)]
+    bot_token: Option<String>,
+    /// The channel the bot token posts to.
+    channel: Option<String>,
+    /// The kinds of notifications posted.
+    kinds: Vec<NotificationKind>,
+    /// The message templates overriding the default ones, by kind of notification.
+    templates: HashMap<NotificationKind, String>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl SlackIntegration {
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(channel, Option<String>);
+    standard_model_accessor_ro!(kinds, Vec<NotificationKind>);
+    standard_model_accessor_ro!(templates, HashMap<NotificationKind, String>);
+
+    /// Returns whether messages are posted with a bot token, rather than an incoming webhook.
+    pub fn uses_bot_token(&self) -> bool {
+        self.bot_token.is_some()
+    }
+
+    /// Returns the integration of the workspace of the current tenancy, if it has one.
+    pub async fn get(ctx: &DalContext) -> SlackResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(SLACK_INTEGRATION_GET, &[&workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Sets the integration of the workspace of the current tenancy, replacing the previous one.
+    /// Messages are posted through the incoming webhook url if one is given, and otherwise with
+    /// the bot token to the channel.
+    #[instrument(skip(ctx, webhook_url, bot_token))]
+    pub async fn set(
+        ctx: &DalContext,
+        webhook_url: Option<String>,
+        bot_token: Option<String>,
+        channel: Option<String>,
+        kinds: Vec<NotificationKind>,
+        templates: HashMap<NotificationKind, String>,
+    ) -> SlackResult<Self> {
+        validate(&webhook_url, &bot_token, &channel)?;
+        let workspace_pk = workspace_pk(ctx)?;
+        let before = Self::get(ctx)
+            .await?
+            .map(|integration| integration.audit_state());
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM slack_integration_set_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    &workspace_pk,
+                    &webhook_url,
+                    &bot_token,
+                    &channel,
+                    &serde_json::to_value(&kinds)?,
+                    &serde_json::to_value(&templates)?,
+                ],
+            )
+            .await?;
+        let object: Self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::SlackIntegrationUpdate,
+            Some(object.audit_target()),
+            before,
+            Some(object.audit_state()),
+        )
+        .await?;
+
+        Ok(object)
+    }
+
+    /// Deletes the integration, so that nothing is posted to Slack anymore.
+    #[instrument(skip_all)]
+    pub async fn delete(self, ctx: &DalContext) -> SlackResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(SLACK_INTEGRATION_DELETE, &[&self.workspace_pk])
+            .await?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::SlackIntegrationDelete,
+            Some(self.audit_target()),
+            Some(self.audit_state()),
+            None,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether notifications of the kind are posted.
+    pub fn wants(&self, kind: NotificationKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    /// Renders the message posted for the [`Notification`], with the template of its kind.
+    pub fn message(&self, notification: &Notification) -> String {
+        let template = self
+            .templates
+            .get(&notification.kind)
+            .map(String::as_str)
+            .unwrap_or_else(|| default_template(notification.kind));
+        template
+            .replace("{{name}}", &notification.name)
+            .replace("{{subject}}", &notification.subject)
+            .replace("{{body}}", notification.body.trim_end())
+    }
+
+    /// Posts a message, so that admins can check the integration works.
+    #[instrument(skip_all)]
+    pub async fn send_test_message(&self) -> SlackResult<()> {
+        self.post(":wave: This is a test message from System Initiative.")
+            .await
+    }
+
+    /// Posts the text to Slack, as configured.
+    async fn post(&self, text: &str) -> SlackResult<()> {
+        let client = reqwest::Client::new();
+        match (&self.webhook_url, &self.bot_token, &self.channel) {
+            (Some(webhook_url), _, _) => {
+                let response = client
+                    .post(webhook_url)
+                    .timeout(SLACK_TIMEOUT)
+                    .json(&serde_json::json!({ "text": text }))
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(SlackError::Api(format!("{status}: {body}")));
+                }
+            }
+            (None, Some(bot_token), Some(channel)) => {
+                let response: serde_json::Value = client
+                    .post(SLACK_POST_MESSAGE_URL)
+                    .timeout(SLACK_TIMEOUT)
+                    .bearer_auth(bot_token)
+                    .json(&serde_json::json!({ "channel": channel, "text": text }))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                // The API answers 200 even when it fails, with the reason in the body
+                if response["ok"].as_bool() != Some(true) {
+                    let reason = response["error"].as_str().unwrap_or("unknown error");
+                    return Err(SlackError::Api(reason.to_owned()));
+                }
+            }
+            _ => {
+                return Err(SlackError::InvalidIntegration(
+                    "missing webhook url or bot token".to_owned(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn audit_target(&self) -> AuditTarget {
+        AuditTarget::new("slack_integration", self.workspace_pk, None)
+    }
+
+    fn audit_state(&self) -> serde_json::Value {
+        serde_json::json![{
+            "usesBotToken": self.uses_bot_token(),
+            "channel": self.channel,
+            "kinds": self.kinds,
+            "templates": self.templates,
+        }]
+    }
+}
+
+/// Posts [`Notifications`](Notification) to the Slack of their workspace, if it has a
+/// [`SlackIntegration`] which wants them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SlackChannel;
+
+#[async_trait]
+impl NotificationChannel for SlackChannel {
+    async fn send(&self, ctx: &DalContext, notification: &Notification) -> NotificationResult<()> {
+        match SlackIntegration::get(ctx).await? {
+            Some(integration) if integration.wants(notification.kind) => {
+                integration.post(&integration.message(notification)).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Returns the message template of the kind used when the integration does not override it.
+pub fn default_template(kind: NotificationKind) -> &'static str {
+    match kind {
+        NotificationKind::ChangeSetApplied => {
+            ":white_check_mark: The change set *{{name}}* was applied."
+        }
+        NotificationKind::ChangeSetReviewRequested => {
+            ":eyes: The change set *{{name}}* is waiting for a review."
+        }
+        NotificationKind::QualificationFailed => ":x: *{{subject}}*\n{{body}}",
+        NotificationKind::ResourceDrifted => ":warning: *{{subject}}*\n{{body}}",
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> SlackResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(SlackError::NoWorkspaceInTenancy)
+}
+
+fn validate(
+    webhook_url: &Option<String>,
+    bot_token: &Option<String>,
+    channel: &Option<String>,
+) -> SlackResult<()> {
+    match (webhook_url, bot_token, channel) {
+        (Some(webhook_url), None, _) => {
+            let parsed = Url::parse(webhook_url)
+                .map_err(|err| SlackError::InvalidIntegration(format!("webhook url: {err}")))?;
+            if parsed.scheme() != "https" {
+                return Err(SlackError::InvalidIntegration(
+                    "webhook url must use https".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        (Some(_), Some(_), _) => Err(SlackError::InvalidIntegration(
+            "give either a webhook url or a bot token, not both".to_owned(),
+        )),
+        (None, Some(_), Some(channel)) if !channel.is_empty() => Ok(()),
+        (None, Some(_), _) => Err(SlackError::InvalidIntegration(
+            "a bot token needs a channel to post to".to_owned(),
+        )),
+        (None, None, _) => Err(SlackError::InvalidIntegration(
+            "give a webhook url or a bot token".to_owned(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integration(templates: HashMap<NotificationKind, String>) -> SlackIntegration {
+        SlackIntegration {
+            workspace_pk: WorkspacePk::NONE,
+            webhook_url: Some("https://hooks.slack.com/services/T0/B0/x".to_owned()),
+            bot_token: None,
+            channel: None,
+            kinds: DEFAULT_SLACK_KINDS.to_vec(),
+            templates,
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    #[test]
+    fn message_renders_templates() {
+        let notification = Notification {
+            kind: NotificationKind::ChangeSetApplied,
+            name: "poulet".to_owned(),
+            subject: "Change set applied: poulet".to_owned(),
+            body: "The change set \"poulet\" was applied.\n".to_owned(),
+            user_pks: None,
+        };
+        assert_eq!(
+            ":white_check_mark: The change set *poulet* was applied.",
+            integration(HashMap::new()).message(&notification)
+        );
+
+        let templates = HashMap::from([(
+            NotificationKind::ChangeSetApplied,
+            "{{subject}} | {{body}}".to_owned(),
+        )]);
+        assert_eq!(
+            "Change set applied: poulet | The change set \"poulet\" was applied.",
+            integration(templates).message(&notification)
+        );
+    }
+
+    #[test]
+    fn validate_integration() {
+        let some = |s: &str| Some(s.to_owned());
+        assert!(validate(
+            &some("https://hooks.slack.com/services/T0/B0/x"),
+            &None,
+            &None
+        )
+        .is_ok());
+        assert!(validate(&None, &some("xoxb-token"), &some("#deploys")).is_ok());
+
+        for (webhook_url, bot_token, channel) in [
+            (some("http://hooks.slack.com/services/T0/B0/x"), None, None),
+            (some("not a url"), None, None),
+            (some("https://hooks.slack.com"), some("xoxb-token"), None),
+            (None, some("xoxb-token"), None),
+            (None, some("xoxb-token"), some("")),
+            (None, None, some("#deploys")),
+        ] {
+            assert!(matches!(
+                validate(&webhook_url, &bot_token, &channel),
+                Err(SlackError::InvalidIntegration(_))
+            ));
+        }
+    }
+}
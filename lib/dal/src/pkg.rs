@@ -1,12 +1,18 @@
 use thiserror::Error;
 use url::ParseError;
 
+mod clone;
 mod export;
 mod import;
+mod schema_bundle;
+mod uninstall;
 
+pub use clone::clone_schema_variant;
 pub use export::export_pkg_as_bytes;
 pub use export::get_component_type;
 pub use import::{import_pkg, import_pkg_from_pkg, ImportOptions};
+pub use schema_bundle::SchemaBundle;
+pub use uninstall::uninstall_pkg;
 
 use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
 
@@ -16,16 +22,16 @@ use crate::{
         argument::{FuncArgumentError, FuncArgumentId},
         binding::FuncBindingError,
     },
-    installed_pkg::InstalledPkgError,
+    installed_pkg::{InstalledPkgError, InstalledPkgId},
     prop_tree::PropTreeError,
     schema::variant::definition::SchemaVariantDefinitionError,
     socket::SocketError,
     ActionPrototypeError, AttributeContextBuilderError, AttributePrototypeArgumentError,
     AttributePrototypeArgumentId, AttributePrototypeError, AttributePrototypeId,
-    AttributeReadContext, AttributeValueError, ExternalProviderError, ExternalProviderId,
-    FuncBackendKind, FuncBackendResponseType, FuncError, FuncId, InternalProviderError,
-    InternalProviderId, PropError, PropId, PropKind, SchemaError, SchemaId, SchemaVariantError,
-    SchemaVariantId, StandardModelError, ValidationPrototypeError,
+    AttributeReadContext, AttributeValueError, ComponentError, ExternalProviderError,
+    ExternalProviderId, FuncBackendKind, FuncBackendResponseType, FuncError, FuncId,
+    InternalProviderError, InternalProviderId, PropError, PropId, PropKind, SchemaError, SchemaId,
+    SchemaVariantError, SchemaVariantId, StandardModelError, ValidationPrototypeError,
 };
 
 #[remain::sorted]
@@ -57,6 +63,8 @@ pub enum PkgError {
     ),
     #[error(transparent)]
     AttributeValue(#[from] AttributeValueError),
+    #[error(transparent)]
+    Component(#[from] ComponentError),
     #[error("map item prop {0} has both custom key prototypes and custom prop only prototype")]
     ConflictingMapKeyPrototypes(PropId),
     #[error("Cannot find Socket for explicit InternalProvider {0}")]
@@ -75,6 +83,8 @@ pub enum PkgError {
     InstalledFuncMissing(FuncId),
     #[error(transparent)]
     InstalledPkg(#[from] InstalledPkgError),
+    #[error("Installed package {0} not found")]
+    InstalledPkgNotFound(InstalledPkgId),
     #[error("Installed schema id {0} does not exist")]
     InstalledSchemaMissing(SchemaId),
     #[error("Installed schema variant definition {0} does not exist")]
@@ -85,6 +95,8 @@ pub enum PkgError {
     InternalProvider(#[from] InternalProviderError),
     #[error("Missing Prop {1} for InternalProvider {1}")]
     InternalProviderMissingProp(InternalProviderId, PropId),
+    #[error("Func {0} cannot be executed: {1}")]
+    InvalidFunc(String, String),
     #[error("Leaf Function {0} has invalid argument {1}")]
     InvalidLeafArgument(FuncId, String),
     #[error("Missing AttributePrototype {0} for explicit InternalProvider {1}")]
@@ -133,6 +145,8 @@ pub enum PkgError {
     SchemaVariant(#[from] SchemaVariantError),
     #[error(transparent)]
     SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
+    #[error("schema variant {0} is still in use by components")]
+    SchemaVariantInUse(SchemaVariantId),
     #[error("schema variant not found: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
     #[error("json serialization error: {0}")]
@@ -0,0 +1,40 @@
+use crate::{DalContext, SchemaVariantId, StandardModel};
+
+use super::{
+    export::{build_variant_clone_pkg, get_schema_and_variant},
+    import::create_schema_variant,
+    PkgError, PkgResult,
+};
+
+/// Copies the [`SchemaVariant`](crate::SchemaVariant) for `variant_id` into a new variant named
+/// `name` of the same [`Schema`](crate::Schema), by exporting it to a package and importing it
+/// back. The props, sockets and prototypes are copied, while the [`Funcs`](crate::Func) are shared
+/// with the original. The default variant of the [`Schema`](crate::Schema) is left untouched.
+pub async fn clone_schema_variant(
+    ctx: &DalContext,
+    variant_id: SchemaVariantId,
+    name: impl Into<String>,
+) -> PkgResult<SchemaVariantId> {
+    let (_, mut schema) = get_schema_and_variant(ctx, variant_id).await?;
+    let previous_default_variant_id = schema.default_schema_variant_id().copied();
+
+    let (pkg, func_map) = build_variant_clone_pkg(ctx, variant_id, name).await?;
+    let schema_spec = pkg
+        .schemas()?
+        .pop()
+        .ok_or(PkgError::InstalledSchemaMissing(*schema.id()))?;
+    let variant_spec = schema_spec
+        .variants()?
+        .pop()
+        .ok_or(PkgError::InstalledSchemaVariantMissing(variant_id))?;
+
+    let cloned_variant_id =
+        create_schema_variant(ctx, &mut schema, variant_spec, None, &func_map).await?;
+
+    // Importing a variant makes it the default one
+    schema
+        .set_default_schema_variant_id(ctx, previous_default_variant_id)
+        .await?;
+
+    Ok(cloned_variant_id)
+}
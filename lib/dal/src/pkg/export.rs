@@ -96,6 +96,58 @@ async fn build_pkg(
     Ok(pkg)
 }
 
+/// Builds a package holding only the [`SchemaVariant`] for `variant_id`, renamed to
+/// `variant_name`, so that it can be imported again as a copy. The [`Funcs`](Func) used by the
+/// variant are returned by their unique id in the package, so that the copy can share them rather
+/// than importing them anew.
+pub(super) async fn build_variant_clone_pkg(
+    ctx: &DalContext,
+    variant_id: SchemaVariantId,
+    variant_name: impl Into<String>,
+) -> PkgResult<(SiPkg, HashMap<FuncUniqueId, Func>)> {
+    let variant_name = variant_name.into();
+
+    let mut pkg_spec_builder = PkgSpec::builder();
+    pkg_spec_builder
+        .name(&variant_name)
+        .version("0.0.0")
+        .created_by("System Initiative");
+
+    let mut func_specs = FuncSpecMap::new();
+    let mut funcs_by_unique_id = HashMap::new();
+
+    for intrinsic in crate::func::intrinsics::IntrinsicFunc::iter() {
+        let intrinsic_name = intrinsic.name();
+        let intrinsic_func = Func::find_by_name(ctx, intrinsic_name)
+            .await?
+            .ok_or(PkgError::MissingIntrinsicFunc(intrinsic_name.to_string()))?;
+        let intrinsic_spec = intrinsic.to_spec()?;
+        func_specs.insert(*intrinsic_func.id(), intrinsic_spec.clone());
+        funcs_by_unique_id.insert(intrinsic_spec.unique_id, intrinsic_func);
+        pkg_spec_builder.func(intrinsic_spec);
+    }
+
+    for func in SchemaVariant::all_funcs(ctx, variant_id).await? {
+        if !func_specs.contains_key(func.id()) {
+            let arguments = FuncArgument::list_for_func(ctx, *func.id()).await?;
+            let func_spec = build_func_spec(&func, &arguments)?;
+            func_specs.insert(*func.id(), func_spec.clone());
+            funcs_by_unique_id.insert(func_spec.unique_id, func);
+            pkg_spec_builder.func(func_spec);
+        }
+    }
+
+    let mut schema_spec = build_schema_spec(ctx, variant_id, &func_specs).await?;
+    for variant_spec in schema_spec.variants.iter_mut() {
+        variant_spec.name = variant_name.clone();
+    }
+    pkg_spec_builder.schema(schema_spec);
+
+    let pkg = SiPkg::load_from_spec(pkg_spec_builder.build()?)?;
+
+    Ok((pkg, funcs_by_unique_id))
+}
+
 fn build_func_spec(func: &Func, args: &[FuncArgument]) -> PkgResult<FuncSpec> {
     let mut func_spec_builder = FuncSpec::builder();
 
@@ -571,7 +623,7 @@ async fn build_variant_spec(
     Ok(variant_spec)
 }
 
-async fn get_schema_and_variant(
+pub(super) async fn get_schema_and_variant(
     ctx: &DalContext,
     variant_id: SchemaVariantId,
 ) -> PkgResult<(SchemaVariant, Schema)> {
@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose, Engine};
 use std::path::Path;
 use telemetry::prelude::*;
 use tokio::sync::Mutex;
@@ -25,14 +26,16 @@ use crate::{
     validation::{create_validation, Validation, ValidationKind},
     ActionPrototype, ActionPrototypeContext, AttributeContextBuilder, AttributePrototypeArgument,
     AttributeReadContext, AttributeValue, AttributeValueError, DalContext, ExternalProvider,
-    ExternalProviderId, Func, FuncArgument, FuncDescription, FuncDescriptionContents, FuncError,
-    FuncId, InternalProvider, Prop, PropId, PropKind, Schema, SchemaId, SchemaVariant,
-    SchemaVariantError, SchemaVariantId, StandardModel,
+    ExternalProviderId, Func, FuncArgument, FuncBackendKind, FuncDescription,
+    FuncDescriptionContents, FuncError, FuncId, InternalProvider, Prop, PropId, PropKind, Schema,
+    SchemaId, SchemaVariant, SchemaVariantError, SchemaVariantId, StandardModel,
 };
 
 use super::{PkgError, PkgResult};
 
-type FuncMap = std::collections::HashMap<FuncUniqueId, Func>;
+mod js_syntax;
+
+pub(super) type FuncMap = std::collections::HashMap<FuncUniqueId, Func>;
 
 #[derive(Clone, Debug, Default)]
 pub struct ImportOptions {
@@ -100,6 +103,7 @@ pub async fn import_pkg_from_pkg(
 
             func.to_owned()
         } else {
+            validate_func(&func_spec)?;
             create_func(ctx, func_spec, installed_pkg_id).await?
         };
 
@@ -143,6 +147,36 @@ pub async fn import_pkg(ctx: &DalContext, pkg_file_path: impl AsRef<Path>) -> Pk
     Ok(pkg)
 }
 
+/// Ensures that a func from a package can be executed by veritech: javascript funcs need a
+/// handler and code which decodes to a well formed script declaring it.
+fn validate_func(func_spec: &SiPkgFunc<'_>) -> PkgResult<()> {
+    let is_js = matches!(
+        FuncBackendKind::from(func_spec.backend_kind()),
+        FuncBackendKind::JsAction
+            | FuncBackendKind::JsAttribute
+            | FuncBackendKind::JsReconciliation
+            | FuncBackendKind::JsSchemaVariantDefinition
+            | FuncBackendKind::JsValidation
+    );
+    if !is_js {
+        return Ok(());
+    }
+
+    let invalid = |reason: &str| PkgError::InvalidFunc(func_spec.name().to_owned(), reason.into());
+
+    let handler = func_spec.handler();
+    if handler.trim().is_empty() {
+        return Err(invalid("missing handler"));
+    }
+    let code = general_purpose::STANDARD_NO_PAD
+        .decode(func_spec.code_base64())
+        .map_err(|_| invalid("code is not valid base64"))?;
+    let code = String::from_utf8(code).map_err(|_| invalid("code is not valid utf-8"))?;
+    js_syntax::check(&code, handler).map_err(|reason| invalid(&reason))?;
+
+    Ok(())
+}
+
 async fn create_func(
     ctx: &DalContext,
     func_spec: SiPkgFunc<'_>,
@@ -169,7 +203,7 @@ async fn create_func(
             let mut func = Func::new(
                 ctx,
                 name,
-                func_spec.backend_kind().into(),
+                FuncBackendKind::from(func_spec.backend_kind()),
                 func_spec.response_type().into(),
             )
             .await?;
@@ -605,7 +639,7 @@ async fn create_props(
     ))
 }
 
-async fn create_schema_variant(
+pub(super) async fn create_schema_variant(
     ctx: &DalContext,
     schema: &mut Schema,
     variant_spec: SiPkgSchemaVariant<'_>,
@@ -0,0 +1,283 @@
+//! A lexical check of the javascript (and typescript) code of funcs in packages. It is not a
+//! full parser, but it rejects the code veritech could never run: unterminated strings, template
+//! literals, comments and regular expressions, unbalanced brackets and handlers which are not
+//! declared.
+
+/// Checks that `code` is lexically well formed and declares `handler` at its top level, as a
+/// function, class or variable. Returns a description of the first problem found otherwise.
+pub(super) fn check(code: &str, handler: &str) -> Result<(), String> {
+    let mut lexer = Lexer {
+        chars: code.chars().collect(),
+        pos: 0,
+        line: 1,
+        brackets: Vec::new(),
+    };
+    let declared = lexer.run(handler)?;
+    if !declared {
+        return Err(format!("handler `{handler}` is not declared"));
+    }
+
+    Ok(())
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    /// The open brackets, with the line each was opened on. A `$` stands for the substitution of
+    /// a template literal, which resumes the literal once closed.
+    brackets: Vec<(char, usize)>,
+}
+
+impl Lexer {
+    /// Lexes the whole code, returning whether `handler` was declared at the top level.
+    fn run(&mut self, handler: &str) -> Result<bool, String> {
+        // Whether a `/` starts a regular expression rather than being a division, which depends
+        // on the token before it.
+        let mut regex_allowed = true;
+        // Whether the previous top level token was a keyword introducing a declaration.
+        let mut declaring = false;
+        let mut declared = false;
+
+        while let Some(c) = self.peek(0) {
+            let top_level = self.brackets.is_empty();
+            match c {
+                '\n' => {
+                    self.line += 1;
+                    self.pos += 1;
+                }
+                c if c.is_whitespace() => self.pos += 1,
+                '/' if self.peek(1) == Some('/') => self.skip_line_comment(),
+                '/' if self.peek(1) == Some('*') => self.skip_block_comment()?,
+                '/' if regex_allowed => {
+                    self.skip_regex()?;
+                    regex_allowed = false;
+                    declaring = false;
+                }
+                '\'' | '"' => {
+                    self.skip_string(c)?;
+                    regex_allowed = false;
+                    declaring = false;
+                }
+                '`' => {
+                    self.pos += 1;
+                    self.skip_template()?;
+                    regex_allowed = false;
+                    declaring = false;
+                }
+                '(' | '[' | '{' => {
+                    self.brackets.push((c, self.line));
+                    self.pos += 1;
+                    regex_allowed = true;
+                    declaring = false;
+                }
+                ')' | ']' | '}' => {
+                    let open = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    self.pos += 1;
+                    match self.brackets.pop() {
+                        Some(('$', _)) if c == '}' => self.skip_template()?,
+                        Some((bracket, _)) if bracket == open => {}
+                        _ => return Err(format!("unexpected `{c}` on line {}", self.line)),
+                    }
+                    regex_allowed = c == '}';
+                    declaring = false;
+                }
+                c if c.is_alphabetic() || c == '_' || c == '$' => {
+                    let word = self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '$');
+                    if top_level {
+                        declared |= declaring && word == handler;
+                        declaring = matches!(
+                            word.as_str(),
+                            "function" | "class" | "const" | "let" | "var"
+                        );
+                    }
+                    regex_allowed = matches!(
+                        word.as_str(),
+                        "await"
+                            | "case"
+                            | "delete"
+                            | "do"
+                            | "else"
+                            | "in"
+                            | "instanceof"
+                            | "new"
+                            | "of"
+                            | "return"
+                            | "throw"
+                            | "typeof"
+                            | "void"
+                            | "yield"
+                    );
+                }
+                c if c.is_ascii_digit() => {
+                    self.take_while(|c| c.is_alphanumeric() || c == '.' || c == '_');
+                    regex_allowed = false;
+                    declaring = false;
+                }
+                _ => {
+                    self.pos += 1;
+                    regex_allowed = true;
+                    // Generator functions are declared as `function* name`
+                    declaring &= c == '*';
+                }
+            }
+        }
+
+        match self.brackets.last() {
+            Some(('$', line)) => Err(format!("unterminated template literal on line {line}")),
+            Some((bracket, line)) => Err(format!("unclosed `{bracket}` opened on line {line}")),
+            None => Ok(declared),
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek(0).map_or(false, &predicate) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn skip_line_comment(&mut self) {
+        while self.peek(0).map_or(false, |c| c != '\n') {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_block_comment(&mut self) -> Result<(), String> {
+        let line = self.line;
+        self.pos += 2;
+        loop {
+            match self.peek(0) {
+                Some('*') if self.peek(1) == Some('/') => {
+                    self.pos += 2;
+                    return Ok(());
+                }
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    self.pos += 1;
+                }
+                None => return Err(format!("unterminated comment on line {line}")),
+            }
+        }
+    }
+
+    fn skip_string(&mut self, quote: char) -> Result<(), String> {
+        self.pos += 1;
+        loop {
+            match self.peek(0) {
+                Some('\\') => self.pos += 2,
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some('\n') | None => {
+                    return Err(format!("unterminated string literal on line {}", self.line))
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    /// Skips the rest of a template literal, or up to the start of its next substitution, whose
+    /// end resumes the literal.
+    fn skip_template(&mut self) -> Result<(), String> {
+        let line = self.line;
+        loop {
+            match self.peek(0) {
+                Some('\\') => self.pos += 2,
+                Some('`') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some('$') if self.peek(1) == Some('{') => {
+                    self.pos += 2;
+                    self.brackets.push(('$', line));
+                    return Ok(());
+                }
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    self.pos += 1;
+                }
+                None => return Err(format!("unterminated template literal on line {line}")),
+            }
+        }
+    }
+
+    fn skip_regex(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        let mut in_class = false;
+        loop {
+            match self.peek(0) {
+                Some('\\') => self.pos += 2,
+                Some('[') => {
+                    in_class = true;
+                    self.pos += 1;
+                }
+                Some(']') => {
+                    in_class = false;
+                    self.pos += 1;
+                }
+                Some('/') if !in_class => {
+                    self.pos += 1;
+                    // Flags
+                    self.take_while(|c| c.is_alphabetic());
+                    return Ok(());
+                }
+                Some('\n') | None => {
+                    return Err(format!(
+                        "unterminated regular expression on line {}",
+                        self.line
+                    ))
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+
+    #[test]
+    fn accepts_well_formed_code() {
+        let code = "// The handler
+            async function main(input: Input): Promise<Output> {
+                const re = /[}\\/]+/g;
+                const half = input.value / 2;
+                return { value: `${input.name.replace(re, '}')} is ${half > 1 ? `${half}` : \"\"}` };
+            }";
+        assert_eq!(Ok(()), check(code, "main"));
+        assert_eq!(Ok(()), check("const main = async () => true;", "main"));
+    }
+
+    #[test]
+    fn rejects_broken_code() {
+        assert!(check("function main() { return true;", "main").is_err());
+        assert!(check("function main() { return true; })", "main").is_err());
+        assert!(check("function main() { return \"true; }", "main").is_err());
+        assert!(check("function main() { return `${true`; }", "main").is_err());
+        assert!(check("function main() { /* return true; }", "main").is_err());
+        assert!(check("function main() { return /true; }", "main").is_err());
+    }
+
+    #[test]
+    fn rejects_undeclared_handlers() {
+        assert!(check("function main() { return true; }", "other").is_err());
+        assert!(check("function other() { const main = 1; }", "main").is_err());
+        assert!(check("// function main() {}", "main").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use si_pkg::{FuncSpec, PkgSpec, SchemaSpec, SiPkg};
+use telemetry::prelude::*;
+
+use crate::{DalContext, SchemaVariantId};
+
+use super::{import_pkg_from_pkg, ImportOptions, PkgResult};
+
+/// The author recorded on packages built from a [`SchemaBundle`].
+const SCHEMA_BUNDLE_CREATED_BY: &str = "System Initiative";
+
+/// A declarative definition of a single schema: the [`SchemaSpec`] (props, sockets, leaf
+/// functions such as qualifications and code generation, and action funcs) along with every
+/// [`FuncSpec`] it references.
+///
+/// Bundles can be built in code or deserialized from JSON, and are materialized through the same
+/// importer as installed packages, so builtins and user packages share one code path.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaBundle {
+    pub schema: SchemaSpec,
+    pub version: String,
+    #[serde(default)]
+    pub funcs: Vec<FuncSpec>,
+}
+
+impl SchemaBundle {
+    pub fn new(schema: SchemaSpec, version: impl Into<String>) -> Self {
+        Self {
+            schema,
+            version: version.into(),
+            funcs: Vec::new(),
+        }
+    }
+
+    /// Adds a func referenced by the schema to the bundle.
+    pub fn func(mut self, func: FuncSpec) -> Self {
+        self.funcs.push(func);
+        self
+    }
+
+    /// Creates the schema and its variants, returning the ids of the installed
+    /// [`SchemaVariants`](crate::SchemaVariant). The installation is recorded under the schema's
+    /// name, so creating an identical bundle twice is refused.
+    #[instrument(skip_all, fields(schema = %self.schema.name))]
+    pub async fn create(self, ctx: &DalContext) -> PkgResult<Vec<SchemaVariantId>> {
+        let name = self.schema.name.clone();
+
+        let spec = PkgSpec::builder()
+            .name(&name)
+            .version(self.version)
+            .created_by(SCHEMA_BUNDLE_CREATED_BY)
+            .schema(self.schema)
+            .funcs(self.funcs)
+            .build()?;
+        let pkg = SiPkg::load_from_spec(spec)?;
+
+        let (_, schema_variant_ids) = import_pkg_from_pkg(
+            ctx,
+            &pkg,
+            &name,
+            Some(ImportOptions {
+                schemas: Some(vec![name.to_lowercase()]),
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+        Ok(schema_variant_ids)
+    }
+}
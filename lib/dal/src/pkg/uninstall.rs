@@ -0,0 +1,87 @@
+use telemetry::prelude::*;
+
+use crate::{
+    installed_pkg::{InstalledPkg, InstalledPkgAsset, InstalledPkgAssetTyped, InstalledPkgId},
+    schema::variant::definition::SchemaVariantDefinition,
+    Component, DalContext, Func, Schema, SchemaVariant, StandardModel,
+};
+
+use super::{PkgError, PkgResult};
+
+/// Removes an installed package and every asset it installed. Assets which are shared with
+/// another installed package (because both packages contained an identical asset) are left in
+/// place. Uninstalling fails if any [`Component`] still uses a schema variant from the package.
+#[instrument(skip(ctx))]
+pub async fn uninstall_pkg(ctx: &DalContext, installed_pkg_id: InstalledPkgId) -> PkgResult<()> {
+    let mut installed_pkg = InstalledPkg::get_by_id(ctx, &installed_pkg_id)
+        .await?
+        .ok_or(PkgError::InstalledPkgNotFound(installed_pkg_id))?;
+
+    let assets = InstalledPkgAsset::list_for_installed_pkg_id(ctx, installed_pkg_id).await?;
+
+    // Refuse to pull schema variants out from under components that use them
+    for asset in &assets {
+        if let InstalledPkgAssetTyped::SchemaVariant { id, .. } =
+            InstalledPkgAssetTyped::from(asset)
+        {
+            if !Component::list_for_schema_variant(ctx, id)
+                .await?
+                .is_empty()
+            {
+                return Err(PkgError::SchemaVariantInUse(id));
+            }
+        }
+    }
+
+    for mut asset in assets {
+        if !is_shared_asset(ctx, &asset, installed_pkg_id).await? {
+            match InstalledPkgAssetTyped::from(&asset) {
+                InstalledPkgAssetTyped::Func { id, .. } => {
+                    if let Some(mut func) = Func::get_by_id(ctx, &id).await? {
+                        func.delete_by_id(ctx).await?;
+                    }
+                }
+                InstalledPkgAssetTyped::Schema { id, .. } => {
+                    if let Some(mut schema) = Schema::get_by_id(ctx, &id).await? {
+                        schema.delete_by_id(ctx).await?;
+                    }
+                }
+                InstalledPkgAssetTyped::SchemaVariant { id, .. } => {
+                    if let Some(mut schema_variant) = SchemaVariant::get_by_id(ctx, &id).await? {
+                        schema_variant.delete_by_id(ctx).await?;
+                    }
+                }
+                InstalledPkgAssetTyped::SchemaVariantDefinition { id, .. } => {
+                    if let Some(mut definition) =
+                        SchemaVariantDefinition::get_by_id(ctx, &id).await?
+                    {
+                        definition.delete_by_id(ctx).await?;
+                    }
+                }
+            }
+        }
+
+        asset.delete_by_id(ctx).await?;
+    }
+
+    info!("uninstalled package '{}'", installed_pkg.name());
+    installed_pkg.delete_by_id(ctx).await?;
+
+    Ok(())
+}
+
+/// Returns `true` if another installed package also installed the same asset.
+async fn is_shared_asset(
+    ctx: &DalContext,
+    asset: &InstalledPkgAsset,
+    installed_pkg_id: InstalledPkgId,
+) -> PkgResult<bool> {
+    Ok(
+        InstalledPkgAsset::list_for_kind_and_hash(ctx, *asset.asset_kind(), asset.asset_hash())
+            .await?
+            .iter()
+            .any(|other| {
+                other.installed_pkg_id() != installed_pkg_id && other.asset_id() == asset.asset_id()
+            }),
+    )
+}
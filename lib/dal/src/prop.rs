@@ -22,10 +22,10 @@ use crate::{
     pk,
     property_editor::schema::WidgetKind,
     standard_model, standard_model_accessor, standard_model_belongs_to, standard_model_has_many,
-    AttributeContext, AttributeContextBuilder, AttributeContextBuilderError,
-    AttributePrototypeError, AttributeReadContext, DalContext, Func, FuncError, FuncId,
-    HistoryEventError, SchemaVariantId, StandardModel, StandardModelError, Tenancy, Timestamp,
-    Visibility,
+    suggestion_prototype, AttributeContext, AttributeContextBuilder, AttributeContextBuilderError,
+    AttributePrototypeError, AttributeReadContext, ComponentId, DalContext, Func, FuncError,
+    FuncId, HistoryEventError, SchemaVariantId, StandardModel, StandardModelError,
+    SuggestionPrototype, SuggestionPrototypeError, Tenancy, Timestamp, Visibility,
 };
 use crate::{AttributeValueError, AttributeValueId, FuncBackendResponseType, TransactionsError};
 
@@ -145,6 +145,8 @@ pub enum PropError {
     SetDefaultForNonScalar(PropKind),
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
+    #[error("suggestion prototype error: {0}")]
+    SuggestionPrototype(#[from] SuggestionPrototypeError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
 }
@@ -320,6 +322,10 @@ impl Prop {
         self.path.to_owned().into()
     }
 
+    pub fn schema_variant_id(&self) -> SchemaVariantId {
+        self.schema_variant_id
+    }
+
     // TODO(nick): replace this table with a foreign key relationship.
     standard_model_belongs_to!(
         lookup_fn: parent_prop,
@@ -475,6 +481,19 @@ impl Prop {
         ))
     }
 
+    /// Lists the [`Props`](Self) in the trees of any of the given
+    /// [`SchemaVariants`](crate::SchemaVariant), in a single query.
+    pub async fn list_for_schema_variants(
+        ctx: &DalContext,
+        schema_variant_ids: &[SchemaVariantId],
+    ) -> PropResult<Vec<Self>> {
+        let schema_variant_ids: Vec<&SchemaVariantId> = schema_variant_ids.iter().collect();
+        Ok(
+            standard_model::find_by_attr_in(ctx, "props", "schema_variant_id", &schema_variant_ids)
+                .await?,
+        )
+    }
+
     pub async fn create_default_prototypes_and_values(
         ctx: &DalContext,
         prop_id: PropId,
@@ -611,4 +630,18 @@ impl Prop {
             .ok_or(PropError::DefaultDiffFunctionNotFound)?;
         self.set_diff_func_id(ctx, Some(*func.id())).await
     }
+
+    /// Returns the values suggested for the [`Prop`] on the [`Component`](crate::Component) by
+    /// its [`SuggestionPrototypes`](SuggestionPrototype) which start with `prefix`, ignoring case.
+    /// A [`Prop`] without any [`SuggestionPrototype`] has no suggestions.
+    pub async fn suggestions(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        prop_id: PropId,
+        prefix: &str,
+    ) -> PropResult<Vec<Value>> {
+        let candidates =
+            SuggestionPrototype::candidates_for_prop(ctx, component_id, prop_id).await?;
+        Ok(suggestion_prototype::filter_candidates(&candidates, prefix))
+    }
 }
@@ -77,6 +77,7 @@ impl PropertyEditorValues {
                         .and_then(|f| f.value().cloned())
                         .unwrap_or(Value::Null),
                     is_from_external_source,
+                    revision: work.attribute_value.revision(),
                 },
             );
             if let Some(parent_id) = work.parent_attribute_value_id {
@@ -109,6 +110,8 @@ pub struct PropertyEditorValue {
     pub key: Option<String>,
     value: Value,
     is_from_external_source: bool,
+    /// The revision of the underlying [`AttributeValue`], to be sent back when updating it.
+    revision: i64,
 }
 
 impl PropertyEditorValue {
@@ -124,6 +127,10 @@ impl PropertyEditorValue {
         self.prop_id.into()
     }
 
+    pub fn revision(&self) -> i64 {
+        self.revision
+    }
+
     /// Returns the [`Prop`](crate::Prop) corresponding to the "prop_id" field.
     pub async fn prop(&self, ctx: &DalContext) -> PropertyEditorResult<Prop> {
         let prop = Prop::get_by_id(ctx, &self.prop_id.into())
@@ -13,3 +13,4 @@
 
 pub mod external;
 pub mod internal;
+pub mod value;
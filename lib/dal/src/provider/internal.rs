@@ -75,7 +75,7 @@ use thiserror::Error;
 use crate::attribute::context::AttributeContextBuilder;
 use crate::func::backend::identity::FuncBackendIdentityArgs;
 use crate::func::binding::{FuncBindingError, FuncBindingId};
-use crate::func::binding_return_value::FuncBindingReturnValueId;
+use crate::func::binding_return_value::{FuncBindingReturnValueError, FuncBindingReturnValueId};
 use crate::socket::{Socket, SocketArity, SocketEdgeKind, SocketError, SocketId, SocketKind};
 use crate::standard_model::object_option_from_row_option;
 use crate::{
@@ -130,6 +130,8 @@ pub enum InternalProviderError {
     Func(#[from] FuncError),
     #[error("func binding error: {0}")]
     FuncBinding(#[from] FuncBindingError),
+    #[error("func binding return value error: {0}")]
+    FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
     #[error("func not found for id: {0}")]
     FuncNotFound(FuncId),
     #[error("history event error: {0}")]
@@ -451,7 +453,7 @@ impl InternalProvider {
             Some(*source_attribute_value.id()),
         )
         .await?;
-        let (func_binding, func_binding_return_value) = FuncBinding::create_and_execute(
+        let (func_binding, mut func_binding_return_value) = FuncBinding::create_and_execute(
             ctx,
             serde_json::to_value(FuncBackendIdentityArgs {
                 identity: Some(found_attribute_view.value().clone()),
@@ -459,6 +461,10 @@ impl InternalProvider {
             *func.id(),
         )
         .await?;
+        // The view carries values derived from secrets along, so it must stay sealed.
+        if found_attribute_view.is_sensitive() {
+            func_binding_return_value.seal(ctx).await?;
+        }
 
         target_attribute_value
             .set_func_binding_id(ctx, *func_binding.id())
@@ -0,0 +1,191 @@
+//! This module contains "value providers": [`ExternalProviders`](crate::ExternalProvider) which
+//! provide the value of a [`Prop`](crate::Prop) through an _output_ [`Socket`](crate::Socket) and
+//! explicit [`InternalProviders`](crate::InternalProvider) which set the value of a
+//! [`Prop`](crate::Prop) from an _input_ [`Socket`](crate::Socket).
+//!
+//! Once the [`Sockets`](crate::Socket) of two [`Components`](crate::Component) are connected,
+//! the selected part of the source [`Component`](crate::Component) flows into the bound
+//! [`Prop`](crate::Prop) of the target [`Component`](crate::Component), and is re-resolved by the
+//! [`DependentValuesUpdate`](crate::DependentValuesUpdate) job whenever the source changes.
+
+use thiserror::Error;
+
+use crate::func::argument::{FuncArgument, FuncArgumentError};
+use crate::func::binding::{FuncBinding, FuncBindingError};
+use crate::prop::PropPath;
+use crate::socket::SocketArity;
+use crate::{
+    AttributePrototypeArgument, AttributePrototypeArgumentError, AttributePrototypeError,
+    AttributeReadContext, AttributeValue, AttributeValueError, DalContext, ExternalProvider,
+    ExternalProviderError, ExternalProviderId, Func, FuncBindingReturnValue, FuncError,
+    InternalProvider, InternalProviderError, Prop, PropError, PropId, SchemaId, SchemaVariantId,
+    Socket, StandardModel, StandardModelError,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ValueProviderError {
+    #[error("attribute prototype error: {0}")]
+    AttributePrototype(#[from] AttributePrototypeError),
+    #[error("attribute prototype argument error: {0}")]
+    AttributePrototypeArgument(#[from] AttributePrototypeArgumentError),
+    #[error("attribute prototype not found for external provider: {0}")]
+    AttributePrototypeNotFoundForExternalProvider(ExternalProviderId),
+    #[error("attribute prototype not found for prop: {0}")]
+    AttributePrototypeNotFoundForProp(PropId),
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("attribute value not found for prop: {0}")]
+    AttributeValueNotFoundForProp(PropId),
+    #[error("external provider error: {0}")]
+    ExternalProvider(#[from] ExternalProviderError),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("func argument error: {0}")]
+    FuncArgument(#[from] FuncArgumentError),
+    #[error("func binding error: {0}")]
+    FuncBinding(#[from] FuncBindingError),
+    #[error("internal provider error: {0}")]
+    InternalProvider(#[from] InternalProviderError),
+    #[error("implicit internal provider not found for prop: {0}")]
+    InternalProviderNotFoundForProp(PropId),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+}
+
+pub type ValueProviderResult<T> = Result<T, ValueProviderError>;
+
+impl ExternalProvider {
+    /// Creates an [`ExternalProvider`] with an _output_ [`Socket`](crate::Socket) whose value is
+    /// the value of the [`Prop`](crate::Prop) found at `prop_path`, such as
+    /// "/root/domain/image".
+    pub async fn new_for_prop(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+        schema_variant_id: SchemaVariantId,
+        name: impl AsRef<str>,
+        prop_path: &PropPath,
+        arity: SocketArity,
+    ) -> ValueProviderResult<(Self, Socket)> {
+        let prop = Prop::find_prop_by_path(ctx, schema_variant_id, prop_path).await?;
+        let prop_internal_provider = InternalProvider::find_for_prop(ctx, *prop.id())
+            .await?
+            .ok_or(ValueProviderError::InternalProviderNotFoundForProp(
+                *prop.id(),
+            ))?;
+
+        let (identity_func, identity_func_binding, identity_fbrv, identity_func_argument) =
+            identity(ctx).await?;
+        let (external_provider, socket) = Self::new_with_socket(
+            ctx,
+            schema_id,
+            schema_variant_id,
+            name,
+            None,
+            *identity_func.id(),
+            *identity_func_binding.id(),
+            *identity_fbrv.id(),
+            arity,
+            false,
+        )
+        .await?;
+
+        let attribute_prototype_id = external_provider.attribute_prototype_id().ok_or(
+            ValueProviderError::AttributePrototypeNotFoundForExternalProvider(
+                *external_provider.id(),
+            ),
+        )?;
+        AttributePrototypeArgument::new_for_intra_component(
+            ctx,
+            *attribute_prototype_id,
+            *identity_func_argument.id(),
+            *prop_internal_provider.id(),
+        )
+        .await?;
+
+        Ok((external_provider, socket))
+    }
+}
+
+impl InternalProvider {
+    /// Creates an explicit [`InternalProvider`] with an _input_ [`Socket`](crate::Socket) and
+    /// binds the [`Prop`](crate::Prop) found at `prop_path`, such as "/root/domain/image", to it.
+    /// Whatever the function of the [`Prop`](crate::Prop) was, its value is replaced by the
+    /// value flowing into the [`Socket`](crate::Socket).
+    pub async fn new_explicit_for_prop(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        name: impl AsRef<str>,
+        prop_path: &PropPath,
+        arity: SocketArity,
+    ) -> ValueProviderResult<(Self, Socket)> {
+        let prop = Prop::find_prop_by_path(ctx, schema_variant_id, prop_path).await?;
+
+        let (identity_func, identity_func_binding, identity_fbrv, identity_func_argument) =
+            identity(ctx).await?;
+        let (explicit_internal_provider, socket) = Self::new_explicit_with_socket(
+            ctx,
+            schema_variant_id,
+            name,
+            *identity_func.id(),
+            *identity_func_binding.id(),
+            *identity_fbrv.id(),
+            arity,
+            false,
+        )
+        .await?;
+
+        let attribute_value = AttributeValue::find_for_context(
+            ctx,
+            AttributeReadContext::default_with_prop(*prop.id()),
+        )
+        .await?
+        .ok_or(ValueProviderError::AttributeValueNotFoundForProp(
+            *prop.id(),
+        ))?;
+        let mut attribute_prototype = attribute_value.attribute_prototype(ctx).await?.ok_or(
+            ValueProviderError::AttributePrototypeNotFoundForProp(*prop.id()),
+        )?;
+
+        attribute_prototype
+            .set_func_id(ctx, *identity_func.id())
+            .await?;
+        for mut argument in
+            AttributePrototypeArgument::list_for_attribute_prototype(ctx, *attribute_prototype.id())
+                .await?
+        {
+            argument.delete_by_id(ctx).await?;
+        }
+        AttributePrototypeArgument::new_for_intra_component(
+            ctx,
+            *attribute_prototype.id(),
+            *identity_func_argument.id(),
+            *explicit_internal_provider.id(),
+        )
+        .await?;
+
+        Ok((explicit_internal_provider, socket))
+    }
+}
+
+/// Finds the identity [`Func`](crate::Func) and its argument, along with a binding for it.
+async fn identity(
+    ctx: &DalContext,
+) -> ValueProviderResult<(Func, FuncBinding, FuncBindingReturnValue, FuncArgument)> {
+    let (identity_func, identity_func_argument) = Func::identity_with_argument(ctx).await?;
+    let (identity_func_binding, identity_fbrv) = FuncBinding::create_and_execute(
+        ctx,
+        serde_json::json![{ "identity": null }],
+        *identity_func.id(),
+    )
+    .await?;
+
+    Ok((
+        identity_func,
+        identity_func_binding,
+        identity_fbrv,
+        identity_func_argument,
+    ))
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
 use strum::{AsRefStr, Display, EnumIter, EnumString};
@@ -9,11 +10,11 @@ use crate::func::binding_return_value::FuncBindingReturnValueId;
 use crate::{
     func::binding_return_value::{FuncBindingReturnValue, FuncBindingReturnValueError},
     ws_event::{WsEvent, WsPayload},
-    Component, ComponentError, ComponentId, DalContext, FuncId, StandardModel, StandardModelError,
+    ChangeSet, ChangeSetError, ComponentError, ComponentId, DalContext, FuncId, StandardModelError,
     WsEventResult,
 };
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct QualificationSummaryForComponent {
     component_id: ComponentId,
@@ -24,7 +25,33 @@ pub struct QualificationSummaryForComponent {
     failed: i64,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+impl QualificationSummaryForComponent {
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    pub fn component_name(&self) -> &str {
+        &self.component_name
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    pub fn warned(&self) -> i64 {
+        self.warned
+    }
+
+    pub fn succeeded(&self) -> i64 {
+        self.succeeded
+    }
+
+    pub fn failed(&self) -> i64 {
+        self.failed
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct QualificationSummary {
     total: i64,
@@ -38,6 +65,8 @@ pub struct QualificationSummary {
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum QualificationSummaryError {
+    #[error(transparent)]
+    ChangeSet(#[from] ChangeSetError),
     #[error(transparent)]
     Component(#[from] ComponentError),
     #[error(transparent)]
@@ -49,66 +78,57 @@ pub enum QualificationSummaryError {
 pub type QualificationSummaryResult<T> = Result<T, QualificationSummaryError>;
 
 impl QualificationSummary {
-    // TODO(nick): turn this into a query for performance. The original version leveraged a query,
-    // but since qualifications are now on the prop tree, there is no longer a relevant query
-    // to help here. I'd write it, but the PR replacing the prototypes and resolvers with the prop
-    // tree is getting huge.
     #[instrument(skip_all)]
     pub async fn get_summary(ctx: &DalContext) -> QualificationSummaryResult<QualificationSummary> {
-        let mut component_summaries = Vec::new();
+        Ok(ChangeSet::qualification_summary(ctx).await?)
+    }
+
+    /// Rolls the summaries of individual components up. Components count as failed if any of
+    /// their qualifications failed, as warned if any warned, and as succeeded otherwise.
+    pub fn from_components(components: Vec<QualificationSummaryForComponent>) -> Self {
         let mut components_succeeded = 0;
         let mut components_warned = 0;
         let mut components_failed = 0;
         let mut total = 0;
 
-        for component in Component::list(ctx).await? {
-            let component_id = *component.id();
-            let qualifications = Component::list_qualifications(ctx, component_id).await?;
-
-            let individual_total = qualifications.len() as i64;
-            let mut succeeded = 0;
-            let mut warned = 0;
-            let mut failed = 0;
-            for qualification in qualifications {
-                if let Some(result) = qualification.result {
-                    match result.status {
-                        QualificationSubCheckStatus::Success => succeeded += 1,
-                        QualificationSubCheckStatus::Warning => warned += 1,
-                        QualificationSubCheckStatus::Failure => failed += 1,
-                        QualificationSubCheckStatus::Unknown => {}
-                    }
-                }
-            }
-
-            let individual_summary = QualificationSummaryForComponent {
-                component_id,
-                component_name: component.name(ctx).await?,
-                total: individual_total,
-                succeeded,
-                warned,
-                failed,
-            };
-
-            // Update counters for all components.
-            if failed > 0 {
+        for component in &components {
+            if component.failed > 0 {
                 components_failed += 1;
-            } else if warned > 0 {
+            } else if component.warned > 0 {
                 components_warned += 1;
             } else {
                 components_succeeded += 1;
             }
-            total += individual_total;
-
-            component_summaries.push(individual_summary);
+            total += component.total;
         }
 
-        Ok(QualificationSummary {
+        QualificationSummary {
             total,
             succeeded: components_succeeded,
             warned: components_warned,
             failed: components_failed,
-            components: component_summaries,
-        })
+            components,
+        }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    pub fn succeeded(&self) -> i64 {
+        self.succeeded
+    }
+
+    pub fn warned(&self) -> i64 {
+        self.warned
+    }
+
+    pub fn failed(&self) -> i64 {
+        self.failed
+    }
+
+    pub fn components(&self) -> &[QualificationSummaryForComponent] {
+        &self.components
     }
 }
 
@@ -151,6 +171,9 @@ pub struct QualificationView {
     pub link: Option<String>,
     pub result: Option<QualificationResult>,
     pub qualification_name: String,
+    /// When the qualification was last run. Ephemeral qualifications are never "checked" and
+    /// have no timestamp.
+    pub last_checked: Option<DateTime<Utc>>,
 }
 
 impl PartialOrd for QualificationView {
@@ -226,6 +249,7 @@ impl QualificationView {
             output,
             result,
             qualification_name: qualification_name.to_string(),
+            last_checked: Some(func_binding_return_value.timestamp().created_at),
         }))
     }
 }
@@ -267,6 +291,12 @@ pub struct QualificationCheckPayload {
     component_id: ComponentId,
 }
 
+impl QualificationCheckPayload {
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+}
+
 impl WsEvent {
     pub async fn checked_qualifications(
         ctx: &DalContext,
@@ -0,0 +1,4 @@
+SELECT row_to_json(api_tokens.*) AS object
+FROM api_tokens
+WHERE api_tokens.secret_hash = $1
+  AND api_tokens.visibility_deleted_at IS NULL
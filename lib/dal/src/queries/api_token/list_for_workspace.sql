@@ -0,0 +1,5 @@
+SELECT row_to_json(api_tokens.*) AS object
+FROM api_tokens
+WHERE api_tokens.workspace_pk = $1
+  AND api_tokens.visibility_deleted_at IS NULL
+ORDER BY api_tokens.created_at DESC
@@ -11,7 +11,18 @@ FROM (SELECT attribute_prototype_id,
              array_agg(CASE
                            WHEN internal_provider_data.internal_provider_id IS NOT NULL
                                THEN internal_provider_data.value
-                           ELSE external_provider_data.value END) AS values
+                           ELSE external_provider_data.value END) AS values,
+             -- Positional with values: the id of the sealed return value each value was decrypted
+             -- from, or NULL if it was not sealed.
+             array_agg(CASE
+                           WHEN internal_provider_data.internal_provider_id IS NOT NULL
+                               THEN CASE
+                                        WHEN internal_provider_data.sensitive
+                                            THEN internal_provider_data.func_binding_return_value_id END
+                           ELSE CASE
+                                    WHEN external_provider_data.sensitive
+                                        THEN external_provider_data.func_binding_return_value_id END
+                 END)                                             AS sensitive_func_binding_return_value_ids
       FROM (SELECT apa.attribute_prototype_id,
                    fa.name,
                    apa.internal_provider_id,
@@ -42,7 +53,9 @@ FROM (SELECT attribute_prototype_id,
                LEFT JOIN LATERAL (
           SELECT DISTINCT ON (attribute_context_internal_provider_id) attribute_context_internal_provider_id AS internal_provider_id,
                                                                       attribute_context_component_id         AS component_id,
-                                                                      fbrv.value
+                                                                      fbrv.value,
+                                                                      fbrv.id                                AS func_binding_return_value_id,
+                                                                      fbrv.sensitive
           FROM attribute_values_v1($1, $2) AS av
                    INNER JOIN func_binding_return_values_v1($1, $2) AS fbrv
                               ON av.func_binding_return_value_id = fbrv.id
@@ -89,7 +102,9 @@ FROM (SELECT attribute_prototype_id,
                LEFT JOIN LATERAL (
           SELECT DISTINCT ON (attribute_context_external_provider_id) attribute_context_external_provider_id AS external_provider_id,
                                                                       attribute_context_component_id         AS component_id,
-                                                                      value
+                                                                      fbrv.value,
+                                                                      fbrv.id                                AS func_binding_return_value_id,
+                                                                      fbrv.sensitive
           FROM attribute_values_v1($1, $2) AS av
                    INNER JOIN func_binding_return_values_v1($1, $2) AS fbrv
                               ON av.func_binding_return_value_id = fbrv.id
@@ -0,0 +1,11 @@
+SELECT row_to_json(history_events.*) AS object
+FROM history_events
+WHERE history_events.audit_action IS NOT NULL
+  AND history_events.tenancy_workspace_pk = $1
+  AND ($2::jsonb IS NULL OR history_events.actor = $2::jsonb)
+  AND ($3::text IS NULL OR history_events.audit_action = $3::text)
+  AND ($4::timestamp with time zone IS NULL OR history_events.created_at >= $4)
+  AND ($5::timestamp with time zone IS NULL OR history_events.created_at < $5)
+  AND ($6::ident IS NULL OR history_events.pk < $6::ident)
+ORDER BY history_events.pk DESC
+LIMIT $7
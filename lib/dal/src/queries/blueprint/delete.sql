@@ -0,0 +1,4 @@
+DELETE
+FROM blueprints
+WHERE blueprints.pk = $1
+  AND blueprints.workspace_pk = $2
@@ -0,0 +1,4 @@
+SELECT row_to_json(blueprints.*) AS object
+FROM blueprints
+WHERE blueprints.pk = $1
+  AND blueprints.workspace_pk = $2
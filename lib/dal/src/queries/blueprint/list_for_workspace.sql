@@ -0,0 +1,4 @@
+SELECT row_to_json(blueprints.*) AS object
+FROM blueprints
+WHERE blueprints.workspace_pk = $1
+ORDER BY blueprints.name
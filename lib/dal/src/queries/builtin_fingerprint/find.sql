@@ -0,0 +1,3 @@
+SELECT fingerprint
+FROM builtin_fingerprints
+WHERE name = $1
@@ -0,0 +1,4 @@
+INSERT INTO builtin_fingerprints (name, fingerprint)
+VALUES ($1, $2)
+ON CONFLICT (name) DO UPDATE SET fingerprint = EXCLUDED.fingerprint,
+                                 updated_at  = CLOCK_TIMESTAMP()
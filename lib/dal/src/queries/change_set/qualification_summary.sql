@@ -0,0 +1,96 @@
+-- The qualification summary of every component: the qualifications which have run, counted by
+-- status, plus the ephemeral "All fields are valid" qualification, which fails as soon as one of
+-- the values of the component fails validation.
+WITH components AS (SELECT components.id                                  AS component_id,
+                           component_belongs_to_schema_variant.belongs_to_id AS schema_variant_id
+                    FROM components_v1($1, $2) AS components
+                             INNER JOIN component_belongs_to_schema_variant_v1($1, $2)
+                        AS component_belongs_to_schema_variant
+                                        ON component_belongs_to_schema_variant.object_id = components.id),
+     -- "/root/si/name" and the entries of "/root/qualification" for every schema variant
+     summary_props AS (SELECT schema_variants.id AS schema_variant_id,
+                              root_children.name AS root_child_name,
+                              props.id           AS prop_id
+                       FROM schema_variants_v1($1, $2) AS schema_variants
+                                INNER JOIN prop_belongs_to_prop_v1($1, $2) AS root_children_belong
+                                           ON root_children_belong.belongs_to_id = schema_variants.root_prop_id
+                                INNER JOIN props_v1($1, $2) AS root_children
+                                           ON root_children.id = root_children_belong.object_id
+                                INNER JOIN prop_belongs_to_prop_v1($1, $2) AS props_belong
+                                           ON props_belong.belongs_to_id = root_children.id
+                                INNER JOIN props_v1($1, $2) AS props
+                                           ON props.id = props_belong.object_id
+                       WHERE root_children.name = 'qualification'
+                          OR (root_children.name = 'si' AND props.name = 'name')),
+     -- The values of the component win over the values of its schema variant
+     summary_values AS (SELECT DISTINCT ON (components.component_id, summary_props.prop_id, attribute_values.key)
+                               components.component_id,
+                               summary_props.root_child_name,
+                               func_binding_return_values.func_id = attribute_prototypes.func_id AS has_run,
+                               func_binding_return_values.value                                  AS value,
+                               func_binding_return_values.unprocessed_value ->> 'result'         AS status
+                        FROM components
+                                 INNER JOIN summary_props
+                                            ON summary_props.schema_variant_id = components.schema_variant_id
+                                 INNER JOIN attribute_values_v1($1, $2) AS attribute_values
+                                            ON attribute_values.attribute_context_prop_id = summary_props.prop_id
+                                                AND attribute_values.attribute_context_internal_provider_id = ident_nil_v1()
+                                                AND attribute_values.attribute_context_external_provider_id = ident_nil_v1()
+                                                AND attribute_values.attribute_context_component_id IN
+                                                    (components.component_id, ident_nil_v1())
+                                 INNER JOIN attribute_value_belongs_to_attribute_prototype_v1($1, $2)
+                            AS attribute_value_belongs_to_attribute_prototype
+                                            ON attribute_value_belongs_to_attribute_prototype.object_id =
+                                               attribute_values.id
+                                 INNER JOIN attribute_prototypes_v1($1, $2) AS attribute_prototypes
+                                            ON attribute_prototypes.id =
+                                               attribute_value_belongs_to_attribute_prototype.belongs_to_id
+                                 INNER JOIN func_binding_return_values_v1($1, $2) AS func_binding_return_values
+                                            ON func_binding_return_values.id =
+                                               attribute_values.func_binding_return_value_id
+                        ORDER BY components.component_id,
+                                 summary_props.prop_id,
+                                 attribute_values.key,
+                                 attribute_values.attribute_context_component_id DESC),
+     invalid_components AS (SELECT DISTINCT attribute_values.attribute_context_component_id AS component_id
+                            FROM attribute_values_v1($1, $2) AS attribute_values
+                                     INNER JOIN validation_resolvers_v1($1, $2) AS validation_resolvers
+                                                ON validation_resolvers.attribute_value_id = attribute_values.id
+                                                    AND
+                                                   validation_resolvers.attribute_value_func_binding_return_value_id =
+                                                   attribute_values.func_binding_return_value_id
+                                     INNER JOIN func_binding_return_values_v1($1, $2) AS func_binding_return_values
+                                                ON func_binding_return_values.func_binding_id =
+                                                   validation_resolvers.validation_func_binding_id
+                            WHERE attribute_values.attribute_context_component_id != ident_nil_v1()
+                              AND jsonb_typeof(func_binding_return_values.value) = 'array'
+                              AND jsonb_array_length(func_binding_return_values.value) > 0),
+     summaries AS (SELECT components.component_id,
+                          COALESCE(MAX(summary_values.value #>> '{}')
+                                   FILTER (WHERE summary_values.root_child_name = 'si'), '') AS component_name,
+                          (invalid_components.component_id IS NULL)                           AS valid,
+                          COUNT(*) FILTER (WHERE summary_values.root_child_name = 'qualification'
+                              AND summary_values.has_run)                                     AS run,
+                          COUNT(*) FILTER (WHERE summary_values.root_child_name = 'qualification'
+                              AND summary_values.has_run
+                              AND summary_values.status = 'success')                          AS succeeded,
+                          COUNT(*) FILTER (WHERE summary_values.root_child_name = 'qualification'
+                              AND summary_values.has_run
+                              AND summary_values.status = 'warning')                          AS warned,
+                          COUNT(*) FILTER (WHERE summary_values.root_child_name = 'qualification'
+                              AND summary_values.has_run
+                              AND summary_values.status = 'failure')                          AS failed
+                   FROM components
+                            LEFT JOIN summary_values ON summary_values.component_id = components.component_id
+                            LEFT JOIN invalid_components ON invalid_components.component_id = components.component_id
+                   GROUP BY components.component_id, invalid_components.component_id)
+SELECT jsonb_build_object(
+               'componentId', summaries.component_id,
+               'componentName', summaries.component_name,
+               'total', 1 + summaries.run,
+               'succeeded', summaries.valid::int + summaries.succeeded,
+               'warned', summaries.warned,
+               'failed', (NOT summaries.valid)::int + summaries.failed
+           ) AS object
+FROM summaries
+ORDER BY summaries.component_name, summaries.component_id
@@ -0,0 +1,10 @@
+-- Schedules left started for too long belong to a scheduler which stopped before applying them
+UPDATE change_set_apply_schedules
+SET status     = 'Started',
+    updated_at = CLOCK_TIMESTAMP()
+WHERE change_set_apply_schedules.pk IN (SELECT due.pk
+                                        FROM change_set_apply_schedules AS due
+                                        WHERE (due.status = 'Scheduled' AND due.apply_at <= $1)
+                                           OR (due.status = 'Started' AND due.updated_at <= $2)
+                                            FOR UPDATE SKIP LOCKED)
+RETURNING row_to_json(change_set_apply_schedules.*) AS object
@@ -0,0 +1,5 @@
+SELECT row_to_json(change_set_apply_schedules.*) AS object
+FROM change_set_apply_schedules
+WHERE change_set_apply_schedules.pk = $1
+  AND change_set_apply_schedules.workspace_pk = $2
+    FOR UPDATE
@@ -0,0 +1,6 @@
+SELECT row_to_json(change_set_reviews.*) AS object
+FROM change_set_reviews
+WHERE change_set_reviews.workspace_pk = $1
+  AND change_set_reviews.change_set_pk = $2
+  AND change_set_reviews.reviewer_user_pk = $3
+  AND change_set_reviews.visibility_deleted_at IS NULL
@@ -0,0 +1,4 @@
+SELECT row_to_json(comments.*) AS object
+FROM comments_v1($1, $2) AS comments
+WHERE comments.target = $3
+ORDER BY comments.created_at
@@ -0,0 +1,4 @@
+SELECT row_to_json(c.*) AS object
+FROM components_v1($1, $2) AS c
+WHERE ($3::ident IS NULL OR c.id > $3::ident)
+ORDER BY c.id;
@@ -0,0 +1,3 @@
+SELECT object_id AS component_id, belongs_to_id AS schema_variant_id
+FROM component_belongs_to_schema_variant_v1($1, $2)
+WHERE object_id = ANY ($3)
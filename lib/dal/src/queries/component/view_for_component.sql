@@ -0,0 +1,34 @@
+SELECT components.kind                                AS kind,
+       schema_variants.id                             AS schema_variant_id,
+       schema_variants.root_prop_id                   AS root_prop_id,
+       internal_providers.id                          AS internal_provider_id,
+       attribute_values.id                            AS attribute_value_id,
+       attribute_values.func_binding_return_value_id  AS func_binding_return_value_id,
+       func_binding_return_values.id IS NOT NULL      AS func_binding_return_value_found,
+       func_binding_return_values.value               AS properties,
+       COALESCE(func_binding_return_values.sensitive, false) AS sensitive
+FROM components_v1($1, $3) AS components
+         LEFT JOIN component_belongs_to_schema_variant_v1($1, $2) AS cbtsv
+                   ON cbtsv.object_id = components.id
+         LEFT JOIN schema_variants_v1($1, $2) AS schema_variants
+                   ON schema_variants.id = cbtsv.belongs_to_id
+         LEFT JOIN internal_providers_v1($1, $2) AS internal_providers
+                   ON internal_providers.prop_id = schema_variants.root_prop_id
+         LEFT JOIN LATERAL (
+    SELECT av.id, av.func_binding_return_value_id
+    FROM attribute_values_v1($1, $2) AS av
+    WHERE in_attribute_context_v1(
+                  attribute_context_build_from_parts_v1(
+                          ident_nil_v1(), -- PropId
+                          internal_providers.id, -- InternalProviderId
+                          ident_nil_v1(), -- ExternalProviderId
+                          components.id -- ComponentId
+                      ),
+                  av
+              )
+    ORDER BY av.attribute_context_component_id DESC
+    LIMIT 1
+    ) AS attribute_values ON TRUE
+         LEFT JOIN func_binding_return_values_v1($1, $2) AS func_binding_return_values
+                   ON func_binding_return_values.id = attribute_values.func_binding_return_value_id
+WHERE components.id = $4
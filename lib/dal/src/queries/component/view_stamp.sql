@@ -0,0 +1,11 @@
+-- Every row of the component which the visibility can see or be hidden by, deleted or not, so that
+-- any write to them changes the stamp. A write creates a new version of the row, at a new location
+-- (ctid) and inserted by the writing transaction (xmin), and transaction ids are never handed out
+-- twice, even to transactions which roll back.
+SELECT md5(string_agg(attribute_values.pk::text || ':' || attribute_values.xmin::text || ':'
+                          || attribute_values.ctid::text,
+                      ',' ORDER BY attribute_values.pk)) AS stamp
+FROM attribute_values
+WHERE in_tenancy_v1($1, attribute_values.tenancy_workspace_pk)
+  AND attribute_values.visibility_change_set_pk IN (ident_nil_v1(), ($2 ->> 'visibility_change_set_pk')::ident)
+  AND attribute_values.attribute_context_component_id = $3
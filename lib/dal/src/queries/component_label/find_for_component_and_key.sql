@@ -0,0 +1,4 @@
+SELECT row_to_json(component_labels.*) AS object
+FROM component_labels_v1($1, $2) AS component_labels
+WHERE component_labels.component_id = $3
+  AND component_labels.key = $4
@@ -0,0 +1,5 @@
+SELECT row_to_json(component_lifecycles.*) AS object
+FROM component_lifecycles
+WHERE component_lifecycles.workspace_pk = $1
+  AND component_lifecycles.component_id = $2
+    FOR UPDATE
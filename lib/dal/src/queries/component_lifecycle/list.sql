@@ -0,0 +1,4 @@
+SELECT row_to_json(component_lifecycles.*) AS object
+FROM component_lifecycles
+WHERE component_lifecycles.workspace_pk = $1
+ORDER BY component_lifecycles.component_id
@@ -0,0 +1,4 @@
+SELECT row_to_json(component_snapshots.*) AS object
+FROM component_snapshots
+WHERE component_snapshots.pk = $1
+  AND component_snapshots.workspace_pk = $2
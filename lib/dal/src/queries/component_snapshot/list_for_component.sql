@@ -0,0 +1,5 @@
+SELECT row_to_json(component_snapshots.*) AS object
+FROM component_snapshots
+WHERE component_snapshots.workspace_pk = $1
+  AND component_snapshots.component_id = $2
+ORDER BY component_snapshots.created_at DESC
@@ -0,0 +1,3 @@
+SELECT version, name, applied_at
+FROM data_migrations
+ORDER BY version
@@ -0,0 +1,2 @@
+INSERT INTO data_migrations (version, name)
+VALUES ($1, $2)
@@ -0,0 +1,3 @@
+DELETE
+FROM data_migrations
+WHERE version = $1
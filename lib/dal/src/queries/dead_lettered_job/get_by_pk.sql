@@ -0,0 +1,4 @@
+SELECT row_to_json(dead_lettered_jobs.*) AS object
+FROM dead_lettered_jobs
+WHERE dead_lettered_jobs.pk = $1
+  AND dead_lettered_jobs.workspace_pk = $2
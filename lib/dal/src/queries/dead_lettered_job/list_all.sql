@@ -0,0 +1,4 @@
+SELECT row_to_json(dead_lettered_jobs.*) AS object
+FROM dead_lettered_jobs
+WHERE ($1::ident IS NULL OR dead_lettered_jobs.workspace_pk = $1::ident)
+ORDER BY dead_lettered_jobs.last_failed_at DESC
@@ -0,0 +1,4 @@
+SELECT row_to_json(dead_lettered_jobs.*) AS object
+FROM dead_lettered_jobs
+WHERE dead_lettered_jobs.workspace_pk = $1
+ORDER BY dead_lettered_jobs.last_failed_at DESC
@@ -0,0 +1,7 @@
+SELECT dead_lettered_jobs.kind,
+       count(*)                                                       AS total,
+       count(*) FILTER (WHERE dead_lettered_jobs.replayed_at IS NULL) AS not_replayed,
+       max(dead_lettered_jobs.last_failed_at)                         AS last_failed_at
+FROM dead_lettered_jobs
+GROUP BY dead_lettered_jobs.kind
+ORDER BY dead_lettered_jobs.kind
@@ -0,0 +1,7 @@
+-- The toggle of a workspace takes precedence over the global one
+SELECT feature_flags.enabled
+FROM feature_flags
+WHERE feature_flags.name = $1
+  AND (feature_flags.workspace_pk IS NULL OR feature_flags.workspace_pk = $2)
+ORDER BY feature_flags.workspace_pk NULLS LAST
+LIMIT 1
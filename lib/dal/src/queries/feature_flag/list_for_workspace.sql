@@ -0,0 +1,5 @@
+SELECT row_to_json(feature_flags.*) AS object
+FROM feature_flags
+WHERE feature_flags.workspace_pk IS NULL
+   OR feature_flags.workspace_pk = $1
+ORDER BY feature_flags.name, feature_flags.workspace_pk NULLS FIRST
@@ -0,0 +1,4 @@
+DELETE
+FROM feature_flags
+WHERE feature_flags.name = $1
+  AND feature_flags.workspace_pk IS NOT DISTINCT FROM $2
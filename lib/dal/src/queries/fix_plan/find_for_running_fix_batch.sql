@@ -0,0 +1,5 @@
+SELECT row_to_json(fix_plans.*) AS object
+FROM fix_plans
+WHERE fix_plans.workspace_pk = $1
+  AND fix_plans.running_fix_batch_id = $2
+    FOR UPDATE
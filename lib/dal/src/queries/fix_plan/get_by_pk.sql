@@ -0,0 +1,4 @@
+SELECT row_to_json(fix_plans.*) AS object
+FROM fix_plans
+WHERE fix_plans.pk = $1
+  AND fix_plans.workspace_pk = $2
@@ -0,0 +1,6 @@
+SELECT row_to_json(fe.*) AS object
+FROM func_executions fe
+WHERE fe.func_binding_id = $2
+  AND in_tenancy_v1($1, fe.tenancy_workspace_pk)
+ORDER BY fe.created_at DESC
+LIMIT 1;
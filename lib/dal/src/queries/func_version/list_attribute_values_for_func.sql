@@ -0,0 +1,13 @@
+-- The attribute values of components whose value was computed by a prototype using the func
+SELECT row_to_json(av.*)                               AS object,
+       component_belongs_to_schema_variant.belongs_to_id AS schema_variant_id
+FROM attribute_values_v1($1, $2) AS av
+         INNER JOIN attribute_value_belongs_to_attribute_prototype_v1($1, $2)
+    AS attribute_value_belongs_to_attribute_prototype
+                    ON attribute_value_belongs_to_attribute_prototype.object_id = av.id
+         INNER JOIN attribute_prototypes_v1($1, $2) AS ap
+                    ON ap.id = attribute_value_belongs_to_attribute_prototype.belongs_to_id
+         INNER JOIN component_belongs_to_schema_variant_v1($1, $2) AS component_belongs_to_schema_variant
+                    ON component_belongs_to_schema_variant.object_id = av.attribute_context_component_id
+WHERE ap.func_id = $3
+ORDER BY av.attribute_context_component_id, av.id
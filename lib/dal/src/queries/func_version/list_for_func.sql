@@ -0,0 +1,4 @@
+SELECT row_to_json(func_versions.*) AS object
+FROM func_versions
+WHERE func_versions.func_id = $1
+ORDER BY func_versions.version
@@ -0,0 +1,4 @@
+SELECT row_to_json(func_version_pins.*) AS object
+FROM func_version_pins
+WHERE func_version_pins.func_id = $1
+ORDER BY func_version_pins.workspace_pk NULLS FIRST, func_version_pins.schema_variant_id NULLS FIRST
@@ -0,0 +1,16 @@
+-- The most specific pin wins: schema variant and workspace, then workspace, then schema variant,
+-- then the pin for everyone
+SELECT row_to_json(func_versions.*) AS object
+FROM func_version_pins
+         INNER JOIN func_versions
+                    ON func_versions.func_id = func_version_pins.func_id
+                        AND func_versions.version = func_version_pins.version
+WHERE func_version_pins.func_id = $3
+  AND (func_version_pins.workspace_pk IS NULL OR func_version_pins.workspace_pk = $5)
+  AND (func_version_pins.schema_variant_id IS NULL
+    OR func_version_pins.schema_variant_id IN (SELECT component_belongs_to_schema_variant.belongs_to_id
+                                               FROM component_belongs_to_schema_variant_v1($1, $2)
+                                                        AS component_belongs_to_schema_variant
+                                               WHERE component_belongs_to_schema_variant.object_id = $4))
+ORDER BY func_version_pins.workspace_pk IS NULL, func_version_pins.schema_variant_id IS NULL
+LIMIT 1
@@ -0,0 +1,5 @@
+DELETE
+FROM func_version_pins
+WHERE func_version_pins.func_id = $1
+  AND func_version_pins.schema_variant_id IS NOT DISTINCT FROM $2
+  AND func_version_pins.workspace_pk IS NOT DISTINCT FROM $3
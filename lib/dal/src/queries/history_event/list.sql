@@ -0,0 +1,13 @@
+SELECT row_to_json(history_events.*) AS object
+FROM history_events
+WHERE history_events.tenancy_workspace_pk = $1
+  AND ($2::jsonb IS NULL OR history_events.actor = $2::jsonb)
+  AND ($3::text IS NULL OR starts_with(history_events.label, $3::text || '.'))
+  AND ($4::text IS NULL
+    OR history_events.data ->> 'id' = $4::text
+    OR history_events.data ->> 'pk' = $4::text)
+  AND ($5::timestamp with time zone IS NULL OR history_events.created_at >= $5)
+  AND ($6::timestamp with time zone IS NULL OR history_events.created_at < $6)
+  AND ($7::ident IS NULL OR history_events.pk < $7::ident)
+ORDER BY history_events.pk DESC
+LIMIT $8
@@ -0,0 +1,6 @@
+SELECT row_to_json(idempotency_records.*) AS object
+FROM idempotency_records
+WHERE idempotency_records.workspace_pk = $1
+  AND idempotency_records.endpoint = $2
+  AND idempotency_records.idempotency_key = $3
+  AND idempotency_records.expires_at > CLOCK_TIMESTAMP()
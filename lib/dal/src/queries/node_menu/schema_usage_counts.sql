@@ -0,0 +1,6 @@
+SELECT component_belongs_to_schema.belongs_to_id AS schema_id,
+       COUNT(*)                                  AS usage_count
+FROM components_v1($1, $2) AS components
+         INNER JOIN component_belongs_to_schema_v1($1, $2) AS component_belongs_to_schema
+                    ON component_belongs_to_schema.object_id = components.id
+GROUP BY component_belongs_to_schema.belongs_to_id
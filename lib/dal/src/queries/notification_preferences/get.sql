@@ -0,0 +1,3 @@
+SELECT row_to_json(notification_preferences.*) AS object
+FROM notification_preferences
+WHERE notification_preferences.user_pk = $1
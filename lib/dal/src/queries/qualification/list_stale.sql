@@ -0,0 +1,20 @@
+SELECT DISTINCT ON (attribute_values.id) attribute_values.id                   AS attribute_value_id,
+                                         attribute_values.tenancy_workspace_pk AS workspace_pk
+FROM attribute_values
+         INNER JOIN attribute_value_belongs_to_attribute_prototype avbtap
+                    ON avbtap.object_id = attribute_values.id
+                        AND is_visible_v1($1, avbtap.visibility_change_set_pk, avbtap.visibility_deleted_at)
+         INNER JOIN attribute_prototypes
+                    ON attribute_prototypes.id = avbtap.belongs_to_id
+                        AND is_visible_v1($1, attribute_prototypes.visibility_change_set_pk,
+                                          attribute_prototypes.visibility_deleted_at)
+         INNER JOIN func_binding_return_values
+                    ON func_binding_return_values.id = attribute_values.func_binding_return_value_id
+                        AND is_visible_v1($1, func_binding_return_values.visibility_change_set_pk,
+                                          func_binding_return_values.visibility_deleted_at)
+WHERE is_visible_v1($1, attribute_values.visibility_change_set_pk, attribute_values.visibility_deleted_at)
+  AND attribute_values.attribute_context_component_id != ident_nil_v1()
+  AND attribute_prototypes.recheck_interval_seconds IS NOT NULL
+  AND func_binding_return_values.created_at
+          + make_interval(secs => attribute_prototypes.recheck_interval_seconds) <= $2
+ORDER BY attribute_values.id
@@ -0,0 +1,4 @@
+DELETE
+FROM saved_views
+WHERE saved_views.pk = $1
+  AND saved_views.workspace_pk = $2
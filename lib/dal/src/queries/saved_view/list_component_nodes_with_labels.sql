@@ -0,0 +1,10 @@
+SELECT components.id                                       AS component_id,
+       node_belongs_to_component.object_id                 AS node_id,
+       COALESCE(jsonb_object_agg(labels.key, labels.value)
+                FILTER (WHERE labels.key IS NOT NULL), '{}') AS labels
+FROM components_v1($1, $2) AS components
+         INNER JOIN node_belongs_to_component_v1($1, $2) AS node_belongs_to_component
+                    ON node_belongs_to_component.belongs_to_id = components.id
+         LEFT JOIN component_labels_v1($1, $2) AS labels
+                   ON labels.component_id = components.id
+GROUP BY components.id, node_belongs_to_component.object_id
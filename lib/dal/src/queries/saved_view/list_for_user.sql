@@ -0,0 +1,5 @@
+SELECT row_to_json(saved_views.*) AS object
+FROM saved_views
+WHERE saved_views.workspace_pk = $1
+  AND (saved_views.owner_user_pk = $2 OR saved_views.shared)
+ORDER BY saved_views.name
@@ -0,0 +1,4 @@
+SELECT row_to_json(schema_category_rules.*) AS object
+FROM schema_category_rules
+WHERE schema_category_rules.workspace_pk = $1
+ORDER BY schema_category_rules.category
@@ -0,0 +1,4 @@
+DELETE
+FROM schema_category_rules
+WHERE schema_category_rules.workspace_pk = $1
+  AND schema_category_rules.category = $2
@@ -0,0 +1,3 @@
+SELECT session_revocations.revoked_before AS revoked_before
+FROM session_revocations
+WHERE session_revocations.user_pk = $1
@@ -0,0 +1,3 @@
+DELETE
+FROM slack_integrations
+WHERE slack_integrations.workspace_pk = $1
@@ -0,0 +1,3 @@
+SELECT row_to_json(slack_integrations.*) AS object
+FROM slack_integrations
+WHERE slack_integrations.workspace_pk = $1
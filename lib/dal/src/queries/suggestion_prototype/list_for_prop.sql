@@ -0,0 +1,4 @@
+SELECT row_to_json(suggestion_prototypes.*) AS object
+FROM suggestion_prototypes_v1($1, $2) AS suggestion_prototypes
+WHERE suggestion_prototypes.prop_id = $3
+ORDER BY suggestion_prototypes.id;
@@ -0,0 +1,20 @@
+-- The usage of a workspace within [$2, $3), summed up by metric and by $4 ("hour" or "day"), out
+-- of both the hourly totals and the events which have not been aggregated yet.
+SELECT date_trunc($4, usage.hour AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS bucket,
+       usage.metric                                                     AS metric,
+       sum(usage.quantity)                                              AS quantity
+FROM (SELECT usage_hourly.hour, usage_hourly.metric, usage_hourly.quantity
+      FROM usage_hourly
+      WHERE usage_hourly.workspace_pk = $1
+        AND usage_hourly.hour >= $2
+        AND usage_hourly.hour < $3
+      UNION ALL
+      SELECT date_trunc('hour', usage_events.recorded_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+             usage_events.metric,
+             usage_events.quantity
+      FROM usage_events
+      WHERE usage_events.workspace_pk = $1
+        AND usage_events.recorded_at >= $2
+        AND usage_events.recorded_at < $3) AS usage
+GROUP BY 1, 2
+ORDER BY 1, 2
@@ -0,0 +1,4 @@
+DELETE
+FROM webhooks
+WHERE webhooks.pk = $1
+  AND webhooks.workspace_pk = $2
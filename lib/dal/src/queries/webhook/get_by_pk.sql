@@ -0,0 +1,4 @@
+SELECT row_to_json(webhooks.*) AS object
+FROM webhooks
+WHERE webhooks.pk = $1
+  AND webhooks.workspace_pk = $2
@@ -0,0 +1,9 @@
+UPDATE webhook_deliveries
+SET next_attempt_at = NULL,
+    updated_at      = CLOCK_TIMESTAMP()
+WHERE webhook_deliveries.pk IN (SELECT due.pk
+                                FROM webhook_deliveries AS due
+                                WHERE due.status = 'pending'
+                                  AND due.next_attempt_at <= $1
+                                    FOR UPDATE SKIP LOCKED)
+RETURNING row_to_json(webhook_deliveries.*) AS object
@@ -0,0 +1,4 @@
+SELECT row_to_json(webhook_deliveries.*) AS object
+FROM webhook_deliveries
+WHERE webhook_deliveries.pk = $1
+  AND webhook_deliveries.workspace_pk = $2
@@ -0,0 +1,4 @@
+SELECT row_to_json(w.*) AS object
+FROM workspaces AS w
+WHERE visibility_deleted_at IS NULL
+ORDER BY w.created_at
@@ -0,0 +1,4 @@
+SELECT row_to_json(workspace_backups.*) AS object
+FROM workspace_backups
+WHERE workspace_backups.pk = $1
+  AND workspace_backups.workspace_pk = $2
@@ -0,0 +1,4 @@
+SELECT row_to_json(workspace_backups.*) AS object
+FROM workspace_backups
+WHERE workspace_backups.workspace_pk = $1
+ORDER BY workspace_backups.created_at DESC
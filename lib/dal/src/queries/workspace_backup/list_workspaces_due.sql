@@ -0,0 +1,10 @@
+SELECT workspaces.pk AS workspace_pk
+FROM workspaces
+WHERE workspaces.visibility_deleted_at IS NULL
+  AND workspaces.pk != ident_nil_v1()
+  AND NOT EXISTS(SELECT 1
+                 FROM workspace_backups
+                 WHERE workspace_backups.workspace_pk = workspaces.pk
+                   AND workspace_backups.trigger = 'Scheduled'
+                   AND workspace_backups.created_at >= $1)
+ORDER BY workspaces.created_at
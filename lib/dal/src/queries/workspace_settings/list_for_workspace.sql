@@ -0,0 +1,4 @@
+SELECT workspace_settings.key, workspace_settings.value
+FROM workspace_settings
+WHERE workspace_settings.workspace_pk = $1
+ORDER BY workspace_settings.key
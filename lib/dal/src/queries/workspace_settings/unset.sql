@@ -0,0 +1,4 @@
+DELETE
+FROM workspace_settings
+WHERE workspace_settings.workspace_pk = $1
+  AND workspace_settings.key = $2
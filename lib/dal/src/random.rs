@@ -0,0 +1,64 @@
+//! This module contains [`RandomSource`], which generates the random parts of names and other
+//! identifiers chosen by the [`dal`](crate).
+//!
+//! The [`ServicesContext`](crate::ServicesContext) holds the source, which draws from entropy
+//! unless a seeded one is set. Tests set a seeded source, so that a failing test generates the
+//! same names when it is run again with the same seed.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+const NAME_CHARSET: &[u8] = b"0123456789";
+
+/// A source of random values, drawing from entropy or from a seed. Clones share the same
+/// sequence.
+#[derive(Clone, Debug, Default)]
+pub struct RandomSource {
+    seeded: Option<Arc<Mutex<StdRng>>>,
+}
+
+impl RandomSource {
+    /// Creates a source which draws from entropy.
+    pub fn entropy() -> Self {
+        Self::default()
+    }
+
+    /// Creates a source which always generates the same sequence for the same seed.
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seeded: Some(Arc::new(Mutex::new(StdRng::seed_from_u64(seed)))),
+        }
+    }
+
+    /// Runs `f` with the generator of the source.
+    pub fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        match &self.seeded {
+            Some(rng) => f(&mut *rng.lock().unwrap_or_else(PoisonError::into_inner)),
+            None => f(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Generates a string of `length` digits.
+    pub fn unique_id(&self, length: usize) -> String {
+        self.with_rng(|rng| {
+            (0..length)
+                .map(|_| NAME_CHARSET[rng.gen_range(0..NAME_CHARSET.len())] as char)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_sources_repeat_their_sequence() {
+        let first = RandomSource::seeded(42);
+        let second = RandomSource::seeded(42);
+        let ids: Vec<String> = (0..3).map(|_| first.unique_id(8)).collect();
+        assert_eq!(ids, (0..3).map(|_| second.unique_id(8)).collect::<Vec<_>>());
+        assert_ne!(ids[0], ids[1]);
+    }
+}
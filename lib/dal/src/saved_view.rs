@@ -0,0 +1,240 @@
+//! This module contains [`SavedView`], a named [`LabelSelector`](crate::LabelSelector) which a
+//! [`User`](crate::User) saves to filter the diagram and the component lists again without
+//! re-applying the same filters. Saved views are private to their owner, unless shared with the
+//! [`Workspace`](crate::Workspace).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, ComponentId, ComponentLabelError, DalContext,
+    LabelSelector, NodeId, StandardModelError, Timestamp, TransactionsError, UserPk, WorkspacePk,
+};
+
+const DELETE: &str = include_str!("queries/saved_view/delete.sql");
+const GET_BY_PK: &str = include_str!("queries/saved_view/get_by_pk.sql");
+const LIST_COMPONENT_NODES_WITH_LABELS: &str =
+    include_str!("queries/saved_view/list_component_nodes_with_labels.sql");
+const LIST_FOR_USER: &str = include_str!("queries/saved_view/list_for_user.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SavedViewError {
+    #[error("component label error: {0}")]
+    ComponentLabel(#[from] ComponentLabelError),
+    #[error("saved view name cannot be empty")]
+    EmptyName,
+    #[error("only users can save views")]
+    NoUserActor,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("only the owner of saved view {0} can change it")]
+    NotOwner(SavedViewPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SavedViewResult<T> = Result<T, SavedViewError>;
+
+pk!(SavedViewPk);
+
+/// A named [`LabelSelector`](crate::LabelSelector) of a [`User`](crate::User).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SavedView {
+    pk: SavedViewPk,
+    workspace_pk: WorkspacePk,
+    owner_user_pk: UserPk,
+    name: String,
+    selector: String,
+    layout_snapshot_ref: Option<String>,
+    shared: bool,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+/// The [`Components`](crate::Component) selected by a [`SavedView`], along with their
+/// [`Nodes`](crate::Node) on the diagram.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedViewResolution {
+    pub component_ids: Vec<ComponentId>,
+    pub node_ids: Vec<NodeId>,
+}
+
+impl SavedView {
+    pub fn pk(&self) -> SavedViewPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(owner_user_pk, UserPk);
+    standard_model_accessor_ro!(name, String);
+    standard_model_accessor_ro!(selector, String);
+    standard_model_accessor_ro!(layout_snapshot_ref, Option<String>);
+    standard_model_accessor_ro!(shared, bool);
+
+    /// Saves a view owned by the current [`User`](crate::User). The `layout_snapshot_ref` is not
+    /// interpreted: it refers to the diagram layout the client saved the view with.
+    #[instrument(skip(ctx))]
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        selector: impl AsRef<str> + std::fmt::Debug,
+        layout_snapshot_ref: Option<String>,
+        shared: bool,
+    ) -> SavedViewResult<Self> {
+        let owner_user_pk = user_pk(ctx)?;
+        let (name, selector) = validate(name.as_ref(), selector.as_ref())?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM saved_view_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    &workspace_pk(ctx)?,
+                    &owner_user_pk,
+                    &name,
+                    &selector,
+                    &layout_snapshot_ref,
+                    &shared,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    /// Finds a view of the current [`User`](crate::User), or one shared with them.
+    pub async fn get_by_pk(ctx: &DalContext, pk: SavedViewPk) -> SavedViewResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[&pk, &workspace_pk(ctx)?, &user_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the views of the current [`User`](crate::User) and the ones shared with them, by
+    /// name.
+    pub async fn list(ctx: &DalContext) -> SavedViewResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_USER, &[&workspace_pk(ctx)?, &user_pk(ctx)?])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Replaces the name, selector, layout reference and sharing of the view. Only its owner can
+    /// update it.
+    #[instrument(skip(self, ctx))]
+    pub async fn update(
+        &mut self,
+        ctx: &DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+        selector: impl AsRef<str> + std::fmt::Debug,
+        layout_snapshot_ref: Option<String>,
+        shared: bool,
+    ) -> SavedViewResult<()> {
+        self.ensure_owner(ctx)?;
+        let (name, selector) = validate(name.as_ref(), selector.as_ref())?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM saved_view_update_v1($1, $2, $3, $4, $5)",
+                &[&self.pk, &name, &selector, &layout_snapshot_ref, &shared],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        Ok(())
+    }
+
+    /// Deletes the view. Only its owner can delete it.
+    #[instrument(skip_all)]
+    pub async fn delete(self, ctx: &DalContext) -> SavedViewResult<()> {
+        self.ensure_owner(ctx)?;
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(DELETE, &[&self.pk, &self.workspace_pk])
+            .await?;
+        Ok(())
+    }
+
+    /// Resolves the view into the [`Components`](crate::Component) it selects in the change set
+    /// of the [`DalContext`], and their [`Nodes`](crate::Node), with a single query for the
+    /// components, nodes and labels.
+    #[instrument(skip_all, fields(saved_view.pk = %self.pk))]
+    pub async fn resolve(&self, ctx: &DalContext) -> SavedViewResult<SavedViewResolution> {
+        let selector: LabelSelector = self.selector.parse()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_COMPONENT_NODES_WITH_LABELS,
+                &[ctx.tenancy(), ctx.visibility()],
+            )
+            .await?;
+
+        let mut resolution = SavedViewResolution::default();
+        for row in rows {
+            let labels: HashMap<String, String> =
+                serde_json::from_value(row.try_get::<_, serde_json::Value>("labels")?)?;
+            if selector.matches(&labels) {
+                resolution.component_ids.push(row.try_get("component_id")?);
+                resolution.node_ids.push(row.try_get("node_id")?);
+            }
+        }
+        resolution.component_ids.sort();
+        resolution.node_ids.sort();
+        Ok(resolution)
+    }
+
+    fn ensure_owner(&self, ctx: &DalContext) -> SavedViewResult<()> {
+        if ctx.history_actor().user_pk() == Some(self.owner_user_pk) {
+            Ok(())
+        } else {
+            Err(SavedViewError::NotOwner(self.pk))
+        }
+    }
+}
+
+/// Trims the name and normalizes the selector, so that views are saved with a valid selector.
+fn validate(name: &str, selector: &str) -> SavedViewResult<(String, String)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(SavedViewError::EmptyName);
+    }
+    let selector: LabelSelector = selector.parse()?;
+    Ok((name.to_string(), selector.to_string()))
+}
+
+fn workspace_pk(ctx: &DalContext) -> SavedViewResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(SavedViewError::NoWorkspaceInTenancy)
+}
+
+fn user_pk(ctx: &DalContext) -> SavedViewResult<UserPk> {
+    ctx.history_actor()
+        .user_pk()
+        .ok_or(SavedViewError::NoUserActor)
+}
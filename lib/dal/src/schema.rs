@@ -19,10 +19,14 @@ use crate::{
 };
 use crate::{Tenancy, TransactionsError};
 
+pub use category_rule::{
+    SchemaCategoryRule, SchemaCategoryRuleKind, SchemaCategoryRulePk, SchemaCategoryRules,
+};
 pub use ui_menu::SchemaUiMenu;
 pub use variant::root_prop::RootProp;
 pub use variant::{SchemaVariant, SchemaVariantId};
 
+pub mod category_rule;
 pub mod ui_menu;
 pub mod variant;
 
@@ -49,12 +53,16 @@ pub enum SchemaError {
     HistoryEvent(#[from] HistoryEventError),
     #[error("internal provider error: {0}")]
     InternalProvider(#[from] InternalProviderError),
+    #[error("invalid schema category {0:?}: categories and the categories nested in them cannot be empty")]
+    InvalidCategory(String),
     #[error("missing a func in attribute update: {0} not found")]
     MissingFunc(String),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
     #[error("no default variant for schema id: {0}")]
     NoDefaultVariant(SchemaId),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error("schema not found: {0}")]
     NotFound(SchemaId),
     #[error("schema not found by name: {0}")]
@@ -195,6 +203,32 @@ impl Schema {
         }
     }
 
+    /// Lists the schemas the workspace of the current tenancy sees, as scoped by its
+    /// [`SchemaCategoryRules`]. Unlike [`StandardModel::list`], this is meant for what is shown to
+    /// users, such as schema pickers.
+    #[instrument(skip_all)]
+    pub async fn list_visible(ctx: &DalContext) -> SchemaResult<Vec<Self>> {
+        let schemas = Self::list(ctx).await?;
+        let rules = SchemaCategoryRules::for_tenancy(ctx).await?;
+        if rules.is_empty() {
+            return Ok(schemas);
+        }
+
+        let mut visible = Vec::with_capacity(schemas.len());
+        for schema in schemas {
+            let ui_menus = schema.ui_menus(ctx).await?;
+            let allowed = if ui_menus.is_empty() {
+                rules.allows(None)
+            } else {
+                ui_menus.iter().any(|ui_menu| rules.allows_ui_menu(ui_menu))
+            };
+            if allowed {
+                visible.push(schema);
+            }
+        }
+        Ok(visible)
+    }
+
     pub async fn find_by_name(ctx: &DalContext, name: impl AsRef<str>) -> SchemaResult<Schema> {
         let name = name.as_ref();
         let schemas = Schema::find_by_attr(ctx, "name", &name).await?;
@@ -0,0 +1,244 @@
+//! This module contains [`SchemaCategoryRule`], which scopes the [`Schemas`](crate::Schema) a
+//! [`Workspace`](crate::Workspace) sees by the category of their [`SchemaUiMenu`].
+//!
+//! A workspace without rules sees every schema. Once it has an
+//! [`Allow`](SchemaCategoryRuleKind::Allow) rule, it only sees the schemas in an allowed category,
+//! and it never sees the schemas in a [`Deny`](SchemaCategoryRuleKind::Deny) category. A rule for
+//! a category also applies to the categories nested under it, so a rule for `AWS` applies to
+//! `AWS.EC2`, but not to `AWS S3`.
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+
+use crate::{pk, standard_model, standard_model_accessor_ro, DalContext, Timestamp, WorkspacePk};
+
+use super::{SchemaError, SchemaResult, SchemaUiMenu};
+
+const LIST_FOR_WORKSPACE: &str =
+    include_str!("../queries/schema_category_rule/list_for_workspace.sql");
+const UNSET: &str = include_str!("../queries/schema_category_rule/unset.sql");
+
+pk!(SchemaCategoryRulePk);
+
+/// Whether a [`SchemaCategoryRule`] shows or hides the schemas in its category.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SchemaCategoryRuleKind {
+    /// The workspace only sees the schemas in allowed categories.
+    Allow,
+    /// The workspace never sees the schemas in the category.
+    Deny,
+}
+
+/// Shows or hides the schemas in a category, and the categories nested under it, for a
+/// [`Workspace`](crate::Workspace).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SchemaCategoryRule {
+    pk: SchemaCategoryRulePk,
+    workspace_pk: WorkspacePk,
+    category: String,
+    kind: SchemaCategoryRuleKind,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl SchemaCategoryRule {
+    pub fn pk(&self) -> SchemaCategoryRulePk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(category, String);
+    standard_model_accessor_ro!(kind, SchemaCategoryRuleKind);
+
+    /// Sets the rule of a workspace for a category, replacing the rule previously set for it.
+    #[instrument(skip(ctx))]
+    pub async fn set(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        category: impl AsRef<str> + std::fmt::Debug,
+        kind: SchemaCategoryRuleKind,
+    ) -> SchemaResult<Self> {
+        let category = category.as_ref();
+        validate_category(category)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM schema_category_rule_set_v1($1, $2, $3)",
+                &[&workspace_pk, &category, &kind.as_ref()],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    /// Removes the rule of a workspace for a category, returning whether there was one.
+    #[instrument(skip(ctx))]
+    pub async fn unset(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        category: impl AsRef<str> + std::fmt::Debug,
+    ) -> SchemaResult<bool> {
+        let removed = ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(UNSET, &[&workspace_pk, &category.as_ref()])
+            .await?;
+        Ok(removed > 0)
+    }
+
+    /// Lists the rules of a workspace, ordered by category.
+    pub async fn list_for_workspace(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+    ) -> SchemaResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Whether the rule applies to the category, being for it or for a category it is nested
+    /// under.
+    pub fn applies_to(&self, category: &str) -> bool {
+        category == self.category
+            || category
+                .strip_prefix(self.category.as_str())
+                .map_or(false, |rest| rest.starts_with('.'))
+    }
+}
+
+/// The [`SchemaCategoryRule`]s of a workspace, deciding which schemas it sees.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaCategoryRules {
+    rules: Vec<SchemaCategoryRule>,
+}
+
+impl SchemaCategoryRules {
+    pub fn new(rules: Vec<SchemaCategoryRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Loads the rules of the workspace of the current tenancy.
+    pub async fn for_tenancy(ctx: &DalContext) -> SchemaResult<Self> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(SchemaError::NoWorkspaceInTenancy)?;
+        Ok(Self::new(
+            SchemaCategoryRule::list_for_workspace(ctx, workspace_pk).await?,
+        ))
+    }
+
+    /// Whether the workspace sees every schema.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether the workspace sees the schemas in the category, or `None` for schemas without a
+    /// [`SchemaUiMenu`], which are only hidden by allow rules.
+    pub fn allows(&self, category: Option<&str>) -> bool {
+        let denied = category.map_or(false, |category| {
+            self.rules
+                .iter()
+                .any(|rule| rule.kind == SchemaCategoryRuleKind::Deny && rule.applies_to(category))
+        });
+        if denied {
+            return false;
+        }
+
+        let mut allow_rules = self
+            .rules
+            .iter()
+            .filter(|rule| rule.kind == SchemaCategoryRuleKind::Allow)
+            .peekable();
+        if allow_rules.peek().is_none() {
+            return true;
+        }
+        category.map_or(false, |category| {
+            allow_rules.any(|rule| rule.applies_to(category))
+        })
+    }
+
+    /// Whether the workspace sees the schemas of the menu.
+    pub fn allows_ui_menu(&self, ui_menu: &SchemaUiMenu) -> bool {
+        self.allows(Some(ui_menu.category()))
+    }
+}
+
+fn validate_category(category: &str) -> SchemaResult<()> {
+    if category.is_empty() || category.split('.').any(|part| part.trim().is_empty()) {
+        return Err(SchemaError::InvalidCategory(category.to_owned()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rule(category: &str, kind: SchemaCategoryRuleKind) -> SchemaCategoryRule {
+        SchemaCategoryRule {
+            pk: SchemaCategoryRulePk::generate(),
+            workspace_pk: WorkspacePk::generate(),
+            category: category.to_owned(),
+            kind,
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    #[test]
+    fn rules_apply_to_nested_categories() {
+        let rule = rule("AWS", SchemaCategoryRuleKind::Allow);
+        assert!(rule.applies_to("AWS"));
+        assert!(rule.applies_to("AWS.EC2"));
+        assert!(!rule.applies_to("AWS S3"));
+        assert!(!rule.applies_to("Docker"));
+    }
+
+    #[test]
+    fn without_rules_everything_is_allowed() {
+        let rules = SchemaCategoryRules::default();
+        assert!(rules.allows(Some("AWS")));
+        assert!(rules.allows(None));
+    }
+
+    #[test]
+    fn allow_and_deny_rules() {
+        let rules = SchemaCategoryRules::new(vec![
+            rule("AWS", SchemaCategoryRuleKind::Allow),
+            rule("AWS.IAM", SchemaCategoryRuleKind::Deny),
+        ]);
+        assert!(rules.allows(Some("AWS.EC2")));
+        assert!(!rules.allows(Some("AWS.IAM")));
+        assert!(!rules.allows(Some("Docker")));
+        assert!(!rules.allows(None));
+
+        let rules = SchemaCategoryRules::new(vec![rule("Docker", SchemaCategoryRuleKind::Deny)]);
+        assert!(!rules.allows(Some("Docker")));
+        assert!(rules.allows(Some("AWS")));
+        assert!(rules.allows(None));
+    }
+
+    #[test]
+    fn invalid_categories() {
+        for category in ["", ".", "AWS.", " .EC2"] {
+            assert!(matches!(
+                validate_category(category),
+                Err(SchemaError::InvalidCategory(_))
+            ));
+        }
+        assert!(validate_category("AWS.EC2").is_ok());
+    }
+}
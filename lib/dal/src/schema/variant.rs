@@ -9,7 +9,9 @@ use thiserror::Error;
 
 use crate::attribute::context::AttributeContextBuilder;
 use crate::func::binding_return_value::FuncBindingReturnValueError;
+use crate::pkg::PkgError;
 use crate::prop::PropPath;
+use crate::prop_tree::PropTreeError;
 use crate::provider::internal::InternalProviderError;
 use crate::schema::variant::definition::{SchemaVariantDefinitionError, SchemaVariantDefinitionId};
 use crate::schema::variant::root_prop::component_type::ComponentType;
@@ -40,6 +42,8 @@ use crate::{
 use self::leaves::{LeafInput, LeafInputLocation, LeafKind};
 
 pub mod definition;
+pub mod edit;
+pub mod json_schema;
 pub mod leaves;
 pub mod root_prop;
 
@@ -73,6 +77,8 @@ pub enum SchemaVariantError {
     AttributeValueNotFoundForContext(AttributeReadContext),
     #[error(transparent)]
     Builtins(#[from] Box<BuiltinsError>),
+    #[error("schema variants can only be cloned within a workspace")]
+    CloneRequiresWorkspace,
     #[error(transparent)]
     Component(#[from] Box<ComponentError>),
     #[error(transparent)]
@@ -99,6 +105,8 @@ pub enum SchemaVariantError {
     FuncBindingReturnValueNotFound(FuncBindingReturnValueId),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("schema variant {0} is used by components and cannot be edited")]
+    InUse(SchemaVariantId),
     #[error("internal provider error: {0}")]
     InternalProvider(#[from] InternalProviderError),
     #[error("must provide valid schema variant, found unset schema variant id")]
@@ -121,14 +129,24 @@ pub enum SchemaVariantError {
     MultipleDocLinksProvided(String),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
+    #[error("schema variant {0} is not a clone and cannot be edited")]
+    NotEditable(SchemaVariantId),
     #[error("schema variant not found: {0}")]
     NotFound(SchemaVariantId),
     #[error("parent prop not found for prop id: {0}")]
     ParentPropNotFound(PropId),
+    #[error("prop {0} is not an object and cannot have props added to it")]
+    ParentPropNotObject(PropId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error(transparent)]
+    Pkg(#[from] Box<PkgError>),
     #[error("prop error: {0}")]
     Prop(#[from] PropError),
+    #[error("props of kind {0} cannot be added to a cloned schema variant")]
+    PropKindNotEditable(PropKind),
+    #[error("prop {0} is not below /root/domain and cannot be edited")]
+    PropNotEditable(PropId),
     /// This variant indicates that a [`Prop`](crate::Prop) or [`PropId`](crate::Prop) was not
     /// found. However, it does not _describe_ the attempt to locate the object in question. The
     /// "json pointer" piece is purely meant to help describe the location.
@@ -138,6 +156,10 @@ pub enum SchemaVariantError {
     PropNotFoundAtPath(SchemaVariantId, String, Visibility),
     #[error("prop not found in cache for name ({0}) and parent prop id ({1})")]
     PropNotFoundInCache(String, PropId),
+    #[error("prop {0} does not belong to schema variant {1}")]
+    PropNotInSchemaVariant(PropId, SchemaVariantId),
+    #[error("prop tree error: {0}")]
+    PropTree(#[from] Box<PropTreeError>),
     #[error("reconciliation prototype: {0}")]
     ReconciliationPrototype(#[from] ReconciliationPrototypeError),
     #[error("schema error: {0}")]
@@ -185,6 +207,9 @@ pub struct SchemaVariant {
     // NOTE(nick): we may want to replace this with a better solution. We use this to ensure
     // components are not created unless the variant has been finalized at least once.
     finalized_once: bool,
+    /// The [`SchemaVariant`] this was cloned from with [`Self::clone_for_edit()`], if any. Only
+    /// clones can have their [`Props`](Prop) edited.
+    cloned_from_schema_variant_id: Option<SchemaVariantId>,
 }
 
 impl_standard_model! {
@@ -416,6 +441,11 @@ impl SchemaVariant {
         Option<Pk(SchemaVariantDefinitionId)>,
         SchemaVariantResult
     );
+    standard_model_accessor!(
+        cloned_from_schema_variant_id,
+        Option<Pk(SchemaVariantId)>,
+        SchemaVariantResult
+    );
 
     pub async fn color(&self, ctx: &DalContext) -> SchemaVariantResult<Option<String>> {
         let attribute_value = Component::find_si_child_attribute_value(
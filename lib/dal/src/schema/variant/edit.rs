@@ -0,0 +1,194 @@
+//! This module contains the ability to clone a [`SchemaVariant`](crate::SchemaVariant) and to edit
+//! the [`Props`](crate::Prop) of the clone, so that builtin variants can be customized within a
+//! workspace.
+
+use telemetry::prelude::*;
+
+use crate::pkg::clone_schema_variant;
+use crate::schema::variant::{SchemaVariantError, SchemaVariantResult};
+use crate::{
+    generate_unique_id, AttributeContext, AttributePrototype, Component, DalContext,
+    InternalProvider, Prop, PropId, PropKind, SchemaVariant, SchemaVariantId, StandardModel,
+    ValidationPrototype,
+};
+
+impl SchemaVariant {
+    /// Deep-copies the [`SchemaVariant`] for `schema_variant_id`, along with its
+    /// [`Props`](crate::Prop), [`Sockets`](crate::Socket) and prototypes, into a new variant of the
+    /// same [`Schema`](crate::Schema) that belongs to the current workspace. Unlike the original,
+    /// the clone can be edited with [`Self::add_prop()`] and [`Self::remove_prop()`].
+    #[instrument(skip_all)]
+    pub async fn clone_for_edit(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<Self> {
+        if ctx.tenancy().workspace_pk().is_none() {
+            return Err(SchemaVariantError::CloneRequiresWorkspace);
+        }
+
+        let original = Self::get_by_id(ctx, &schema_variant_id)
+            .await?
+            .ok_or(SchemaVariantError::NotFound(schema_variant_id))?;
+        let name = format!("{}-{}", original.name(), generate_unique_id(ctx, 4));
+
+        let cloned_id = clone_schema_variant(ctx, schema_variant_id, name)
+            .await
+            .map_err(Box::new)?;
+        let mut cloned = Self::get_by_id(ctx, &cloned_id)
+            .await?
+            .ok_or(SchemaVariantError::NotFound(cloned_id))?;
+        cloned
+            .set_cloned_from_schema_variant_id(ctx, Some(schema_variant_id))
+            .await?;
+
+        Ok(cloned)
+    }
+
+    /// Adds a [`Prop`](crate::Prop) to a [`SchemaVariant`] created with [`Self::clone_for_edit()`],
+    /// below the object [`Prop`](crate::Prop) for `parent_prop_id` within "/root/domain".
+    ///
+    /// _Note:_ arrays and maps cannot be added, as their element cannot be provided.
+    #[instrument(skip_all)]
+    pub async fn add_prop(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        name: impl AsRef<str>,
+        kind: PropKind,
+        parent_prop_id: PropId,
+    ) -> SchemaVariantResult<Prop> {
+        let schema_variant = Self::find_editable(ctx, schema_variant_id).await?;
+
+        if matches!(kind, PropKind::Array | PropKind::Map) {
+            return Err(SchemaVariantError::PropKindNotEditable(kind));
+        }
+        let (parent_prop, _) = schema_variant.find_domain_prop(ctx, parent_prop_id).await?;
+        if parent_prop.kind() != &PropKind::Object {
+            return Err(SchemaVariantError::ParentPropNotObject(parent_prop_id));
+        }
+
+        let prop = Prop::new(
+            ctx,
+            name,
+            kind,
+            None,
+            schema_variant_id,
+            Some(parent_prop_id),
+        )
+        .await?;
+
+        // These are idempotent, so only the new prop gets its prototype, value and provider.
+        Self::create_default_prototypes_and_values(ctx, schema_variant_id).await?;
+        Self::create_implicit_internal_providers(ctx, schema_variant_id).await?;
+
+        Ok(prop)
+    }
+
+    /// Removes the [`Prop`](crate::Prop) for `prop_id` and its descendants from a
+    /// [`SchemaVariant`] created with [`Self::clone_for_edit()`], along with their prototypes,
+    /// values, validations and implicit [`InternalProviders`](crate::InternalProvider). Only
+    /// [`Props`](crate::Prop) below "/root/domain" can be removed.
+    ///
+    /// _Note:_ functions of other [`Props`](crate::Prop) which take the removed ones as inputs are
+    /// not rebound.
+    #[instrument(skip_all)]
+    pub async fn remove_prop(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        prop_id: PropId,
+    ) -> SchemaVariantResult<()> {
+        let schema_variant = Self::find_editable(ctx, schema_variant_id).await?;
+
+        let (prop, depth) = schema_variant.find_domain_prop(ctx, prop_id).await?;
+        if depth == 0 {
+            return Err(SchemaVariantError::PropNotEditable(prop_id));
+        }
+
+        // Removing the prototype of the prop also removes the values (and the prototypes of the
+        // values) of its descendants.
+        let attribute_context = AttributeContext::builder()
+            .set_prop_id(prop_id)
+            .to_context()?;
+        for attribute_prototype in
+            AttributePrototype::find_for_context_and_key(ctx, attribute_context, &None).await?
+        {
+            AttributePrototype::remove(ctx, attribute_prototype.id(), true).await?;
+        }
+
+        let mut work_queue = vec![prop];
+        while let Some(mut prop) = work_queue.pop() {
+            work_queue.extend(prop.child_props(ctx).await?);
+
+            for mut validation_prototype in
+                ValidationPrototype::list_for_prop(ctx, *prop.id()).await?
+            {
+                validation_prototype.delete_by_id(ctx).await?;
+            }
+
+            if let Some(mut internal_provider) =
+                InternalProvider::find_for_prop(ctx, *prop.id()).await?
+            {
+                if let Some(attribute_prototype_id) = internal_provider.attribute_prototype_id() {
+                    AttributePrototype::remove(ctx, attribute_prototype_id, true).await?;
+                }
+                internal_provider.delete_by_id(ctx).await?;
+            }
+
+            prop.unset_parent_prop_do_not_use(ctx).await?;
+            prop.delete_by_id(ctx).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Finds the [`SchemaVariant`], ensuring it is a clone which no
+    /// [`Component`](crate::Component) uses yet.
+    async fn find_editable(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<Self> {
+        let schema_variant = Self::get_by_id(ctx, &schema_variant_id)
+            .await?
+            .ok_or(SchemaVariantError::NotFound(schema_variant_id))?;
+        if schema_variant.cloned_from_schema_variant_id().is_none() {
+            return Err(SchemaVariantError::NotEditable(schema_variant_id));
+        }
+
+        let components = Component::list_for_schema_variant(ctx, schema_variant_id)
+            .await
+            .map_err(Box::new)?;
+        if !components.is_empty() {
+            return Err(SchemaVariantError::InUse(schema_variant_id));
+        }
+
+        Ok(schema_variant)
+    }
+
+    /// Finds the [`Prop`](crate::Prop) for `prop_id`, ensuring it is "/root/domain" or one of its
+    /// descendants for [`self`](Self). The depth of the [`Prop`](crate::Prop) below
+    /// "/root/domain" is returned alongside it.
+    async fn find_domain_prop(
+        &self,
+        ctx: &DalContext,
+        prop_id: PropId,
+    ) -> SchemaVariantResult<(Prop, usize)> {
+        // Ordered from the root prop down to the prop itself
+        let mut ancestors = Prop::all_ancestor_props(ctx, prop_id).await?;
+        let root_prop_id = ancestors.first().map(|prop| prop.id());
+        if root_prop_id.is_none() || root_prop_id != self.root_prop_id() {
+            return Err(SchemaVariantError::PropNotInSchemaVariant(prop_id, self.id));
+        }
+        if ancestors
+            .get(1)
+            .map_or(true, |prop| prop.name() != "domain")
+        {
+            return Err(SchemaVariantError::PropNotEditable(prop_id));
+        }
+
+        let depth = ancestors.len() - 2;
+        let prop = ancestors
+            .pop()
+            .ok_or(SchemaVariantError::PropNotEditable(prop_id))?;
+
+        Ok((prop, depth))
+    }
+}
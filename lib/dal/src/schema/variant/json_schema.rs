@@ -0,0 +1,216 @@
+//! This module contains the ability to describe the [`Props`](crate::Prop) of a
+//! [`SchemaVariant`](crate::SchemaVariant) as a [JSON Schema](https://json-schema.org) document,
+//! so that tools outside of SI can validate the properties of its
+//! [`Components`](crate::Component).
+
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+use telemetry::prelude::*;
+
+use crate::func::backend::validation::FuncBackendValidationArgs;
+use crate::prop_tree::{PropTree, PropTreeNode};
+use crate::schema::variant::{SchemaVariantError, SchemaVariantResult};
+use crate::validation::Validation;
+use crate::{
+    AttributeContextBuilder, AttributeValue, DalContext, PropError, PropId, PropKind,
+    SchemaVariant, StandardModel, ValidationPrototype,
+};
+
+pub mod import;
+
+/// The dialect of the documents generated by [`SchemaVariant::to_json_schema()`].
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl SchemaVariant {
+    /// Describes the tree of [`Props`](crate::Prop) of the [`SchemaVariant`], from the root prop
+    /// down, as a draft 2020-12 JSON Schema document.
+    ///
+    /// Each prop is described by its kind, its name as the `title` and its doc link as the
+    /// `description`. The default values of props outside of arrays and maps are included, and
+    /// the builtin validations of a prop are translated to the matching keywords, a prop being
+    /// `required` by its parent object whenever one of them rejects a missing value. Validations
+    /// written as functions cannot be translated and are left out.
+    #[instrument(skip_all)]
+    pub async fn to_json_schema(&self, ctx: &DalContext) -> SchemaVariantResult<Value> {
+        let mut prop_tree = PropTree::new(ctx, true, Some(vec![*self.id()]), None)
+            .await
+            .map_err(Box::new)?;
+        let root = prop_tree
+            .root_props
+            .pop()
+            .ok_or(SchemaVariantError::PropNotFound("/root"))?;
+
+        let mut validations: HashMap<PropId, Vec<Validation>> = HashMap::new();
+        for prototype in ValidationPrototype::list_for_schema_variant(ctx, *self.id()).await? {
+            // Only the builtin validations have arguments we can translate
+            if let Ok(args) =
+                serde_json::from_value::<FuncBackendValidationArgs>(prototype.args().clone())
+            {
+                validations
+                    .entry(prototype.context().prop_id())
+                    .or_default()
+                    .push(args.validation);
+            }
+        }
+
+        // Default values are only set for props outside of arrays and maps, since the values
+        // inside of them are tracked by key or index
+        let mut defaults: HashMap<PropId, Value> = HashMap::new();
+        let mut stack: Vec<&PropTreeNode> = vec![&root];
+        while let Some(node) = stack.pop() {
+            match node.kind {
+                PropKind::Object => stack.extend(node.children.iter()),
+                PropKind::Array | PropKind::Map => {}
+                PropKind::Boolean | PropKind::Integer | PropKind::String => {
+                    let context = AttributeContextBuilder::new()
+                        .set_prop_id(node.prop_id)
+                        .to_context()?;
+                    if let Some(attribute_value) =
+                        AttributeValue::find_for_context(ctx, context.into()).await?
+                    {
+                        if let Some(value) = attribute_value.get_value(ctx).await? {
+                            defaults.insert(node.prop_id, value);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut document = match prop_json_schema(&root, &validations, &defaults)? {
+            Value::Object(document) => document,
+            _ => Map::new(),
+        };
+        document.insert("$schema".to_owned(), JSON_SCHEMA_DIALECT.into());
+        let schema = self
+            .schema(ctx)
+            .await?
+            .ok_or(SchemaVariantError::MissingSchema(*self.id()))?;
+        document.insert(
+            "title".to_owned(),
+            format!("{} {}", schema.name(), self.name()).into(),
+        );
+        Ok(Value::Object(document))
+    }
+}
+
+fn prop_json_schema(
+    node: &PropTreeNode,
+    validations: &HashMap<PropId, Vec<Validation>>,
+    defaults: &HashMap<PropId, Value>,
+) -> SchemaVariantResult<Value> {
+    let mut schema = Map::new();
+    schema.insert("title".to_owned(), node.name.clone().into());
+    if let Some(doc_link) = &node.doc_link {
+        schema.insert("description".to_owned(), doc_link.clone().into());
+    }
+
+    match node.kind {
+        PropKind::Boolean => {
+            schema.insert("type".to_owned(), "boolean".into());
+        }
+        PropKind::Integer => {
+            schema.insert("type".to_owned(), "integer".into());
+        }
+        PropKind::String => {
+            schema.insert("type".to_owned(), "string".into());
+        }
+        PropKind::Array => {
+            let element = node
+                .children
+                .first()
+                .ok_or(PropError::ArrayMissingElementChild(node.prop_id))?;
+            schema.insert("type".to_owned(), "array".into());
+            schema.insert(
+                "items".to_owned(),
+                prop_json_schema(element, validations, defaults)?,
+            );
+        }
+        PropKind::Map => {
+            let element = node
+                .children
+                .first()
+                .ok_or(PropError::MapMissingElementChild(node.prop_id))?;
+            schema.insert("type".to_owned(), "object".into());
+            schema.insert(
+                "additionalProperties".to_owned(),
+                prop_json_schema(element, validations, defaults)?,
+            );
+        }
+        PropKind::Object => {
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for child in &node.children {
+                properties.insert(
+                    child.name.clone(),
+                    prop_json_schema(child, validations, defaults)?,
+                );
+                if validations
+                    .get(&child.prop_id)
+                    .map_or(false, |v| v.iter().any(requires_value))
+                {
+                    required.push(Value::String(child.name.clone()));
+                }
+            }
+            schema.insert("type".to_owned(), "object".into());
+            schema.insert("properties".to_owned(), Value::Object(properties));
+            if !required.is_empty() {
+                schema.insert("required".to_owned(), Value::Array(required));
+            }
+            schema.insert("additionalProperties".to_owned(), false.into());
+        }
+    }
+
+    if let Some(default) = defaults.get(&node.prop_id) {
+        schema.insert("default".to_owned(), default.clone());
+    }
+    for validation in validations.get(&node.prop_id).into_iter().flatten() {
+        insert_validation_keywords(&mut schema, validation);
+    }
+
+    Ok(Value::Object(schema))
+}
+
+/// Returns whether the validation fails when the prop has no value, which makes the prop required.
+fn requires_value(validation: &Validation) -> bool {
+    !matches!(validation, Validation::StringIsHexColor { .. })
+}
+
+fn insert_validation_keywords(schema: &mut Map<String, Value>, validation: &Validation) {
+    match validation {
+        Validation::IntegerIsBetweenTwoIntegers {
+            lower_bound,
+            upper_bound,
+            ..
+        } => {
+            // The bounds themselves are rejected by the validation
+            schema.insert("exclusiveMinimum".to_owned(), (*lower_bound).into());
+            schema.insert("exclusiveMaximum".to_owned(), (*upper_bound).into());
+        }
+        Validation::IntegerIsNotEmpty { .. } => {}
+        Validation::StringEquals { expected, .. } => {
+            schema.insert("const".to_owned(), expected.clone().into());
+        }
+        Validation::StringHasPrefix { expected, .. } => {
+            schema.insert(
+                "pattern".to_owned(),
+                format!("^{}", regex::escape(expected)).into(),
+            );
+        }
+        Validation::StringInStringArray { expected, .. } => {
+            schema.insert("enum".to_owned(), expected.clone().into());
+        }
+        Validation::StringIsHexColor { .. } => {
+            schema.insert("pattern".to_owned(), r"^#[\dA-Fa-f]{6,8}$".into());
+        }
+        Validation::StringIsNotEmpty { .. } => {
+            schema.insert("minLength".to_owned(), 1.into());
+        }
+        Validation::StringIsValidIpAddr { .. } => {
+            schema.insert(
+                "anyOf".to_owned(),
+                json!([{ "format": "ipv4" }, { "format": "ipv6" }]),
+            );
+        }
+    }
+}
@@ -0,0 +1,839 @@
+//! This module contains the ability to create a [`SchemaVariant`](crate::SchemaVariant) from a
+//! [JSON Schema](https://json-schema.org) document, or from one of the component schemas of an
+//! [OpenAPI](https://www.openapis.org) document.
+//!
+//! A document is first converted to a [`SchemaVariantDefinitionJson`] by [`JsonSchemaImport`],
+//! which records the constructs that have no equivalent in a [`Prop`](crate::Prop) tree rather than
+//! failing on them. The definition is then installed like any other asset, with an asset function
+//! returning it, so that it can be edited afterwards.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use si_pkg::{
+    FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, PkgSpec, SiPkg, SiPkgError,
+    SpecError, ValidationSpec,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::component::ComponentKind;
+use crate::func::intrinsics::IntrinsicFunc;
+use crate::pkg::{import_pkg_from_pkg, ImportOptions, PkgError};
+use crate::schema::variant::definition::{
+    PropDefinition, SchemaVariantDefinition, SchemaVariantDefinitionError,
+    SchemaVariantDefinitionId, SchemaVariantDefinitionJson, SchemaVariantDefinitionMetadataJson,
+};
+use crate::{
+    DalContext, Func, FuncBackendKind, FuncBackendResponseType, FuncError, PropKind,
+    SchemaVariantId, StandardModel, StandardModelError, User, UserError,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum JsonSchemaImportError {
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("no schema variant was created from the document")]
+    NoSchemaVariantCreated,
+    #[error("no component schema named {0} in the OpenAPI document")]
+    OpenApiSchemaNotFound(String),
+    #[error("pkg error: {0}")]
+    Pkg(#[from] Box<PkgError>),
+    #[error("the root of the document must describe an object with properties")]
+    RootNotObject,
+    #[error("schema variant definition error: {0}")]
+    SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("si pkg error: {0}")]
+    SiPkg(#[from] SiPkgError),
+    #[error("spec error: {0}")]
+    Spec(#[from] SpecError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("user error: {0}")]
+    User(#[from] UserError),
+}
+
+pub type JsonSchemaImportResult<T> = Result<T, JsonSchemaImportError>;
+
+/// A construct of the imported document which was left out of the prop tree, or imported with a
+/// different meaning.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedConstruct {
+    /// The JSON pointer, within the document, of the schema holding the construct.
+    pub pointer: String,
+    pub message: String,
+}
+
+/// A [`SchemaVariantDefinitionJson`] converted from a JSON Schema document, along with the report
+/// of the constructs which could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonSchemaImport {
+    pub definition: SchemaVariantDefinitionJson,
+    pub unsupported: Vec<UnsupportedConstruct>,
+}
+
+impl JsonSchemaImport {
+    /// Converts a JSON Schema document whose root describes an object. Each property of the root
+    /// becomes a child of "/root/domain".
+    pub fn from_json_schema(document: &Value) -> JsonSchemaImportResult<Self> {
+        Converter::new(document).convert_root(document, "#".to_owned())
+    }
+
+    /// Converts the component schema named `schema_name` of an OpenAPI document, which may be an
+    /// OpenAPI 3 document or a Swagger 2 document. References to other component schemas are
+    /// followed.
+    pub fn from_openapi(document: &Value, schema_name: &str) -> JsonSchemaImportResult<Self> {
+        let escaped = schema_name.replace('~', "~0").replace('/', "~1");
+        let (pointer, schema) = ["/components/schemas", "/definitions"]
+            .iter()
+            .find_map(|base| {
+                let pointer = format!("{base}/{escaped}");
+                document.pointer(&pointer).map(|schema| (pointer, schema))
+            })
+            .ok_or_else(|| JsonSchemaImportError::OpenApiSchemaNotFound(schema_name.to_owned()))?;
+        Converter::new(document).convert_root(schema, format!("#{pointer}"))
+    }
+
+    /// Creates an asset returning the definition, named `name`, and installs it as a new
+    /// [`SchemaVariant`](crate::SchemaVariant).
+    #[instrument(skip_all)]
+    pub async fn create_schema_variant(
+        &self,
+        ctx: &DalContext,
+        name: &str,
+        category: &str,
+        color: &str,
+    ) -> JsonSchemaImportResult<(SchemaVariantDefinitionId, SchemaVariantId)> {
+        let code = format!(
+            "function createAsset() {{\n  return {};\n}}",
+            serde_json::to_string_pretty(&self.definition)?
+        );
+        let mut asset_func = Func::new(
+            ctx,
+            name,
+            FuncBackendKind::JsSchemaVariantDefinition,
+            FuncBackendResponseType::SchemaVariantDefinition,
+        )
+        .await?;
+        asset_func.set_handler(ctx, Some("createAsset")).await?;
+        asset_func.set_code_plaintext(ctx, Some(&code)).await?;
+
+        let variant_def = SchemaVariantDefinition::new(
+            ctx,
+            name.to_owned(),
+            None,
+            category.to_owned(),
+            None,
+            color.to_owned(),
+            ComponentKind::Standard,
+            None,
+            *asset_func.id(),
+        )
+        .await?;
+        let metadata: SchemaVariantDefinitionMetadataJson = variant_def.clone().into();
+
+        let user = match ctx.history_actor().user_pk() {
+            Some(user_pk) => User::get_by_pk(ctx, user_pk).await?,
+            None => None,
+        };
+        let user_email = user
+            .map(|user| user.email().to_owned())
+            .unwrap_or("unauthenticated user email".into());
+
+        let identity_func_spec = IntrinsicFunc::Identity.to_spec()?;
+        let mut asset_func_spec = FuncSpec::builder();
+        asset_func_spec.name(name);
+        asset_func_spec.backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition);
+        asset_func_spec.response_type(FuncSpecBackendResponseType::SchemaVariantDefinition);
+        asset_func_spec.code_plaintext(&code);
+        asset_func_spec.handler("createAsset");
+        let asset_func_spec = asset_func_spec.build()?;
+
+        let variant_spec = self.definition.to_spec(
+            metadata.clone(),
+            identity_func_spec.unique_id,
+            asset_func_spec.unique_id,
+        )?;
+        let schema_spec = metadata.to_spec(variant_spec)?;
+        let pkg_spec = PkgSpec::builder()
+            .name(name)
+            .created_by(&user_email)
+            .func(identity_func_spec)
+            .func(asset_func_spec.clone())
+            .schema(schema_spec)
+            .version("0.0.1")
+            .build()?;
+
+        let pkg = SiPkg::load_from_spec(pkg_spec)?;
+        let (_, schema_variant_ids) = import_pkg_from_pkg(
+            ctx,
+            &pkg,
+            name,
+            Some(ImportOptions {
+                schemas: None,
+                skip_import_funcs: Some(HashMap::from_iter([(
+                    asset_func_spec.unique_id,
+                    asset_func,
+                )])),
+                no_record: true,
+            }),
+        )
+        .await
+        .map_err(Box::new)?;
+
+        let schema_variant_id = schema_variant_ids
+            .first()
+            .copied()
+            .ok_or(JsonSchemaImportError::NoSchemaVariantCreated)?;
+        Ok((*variant_def.id(), schema_variant_id))
+    }
+}
+
+/// Walks a document, converting its schemas to [`PropDefinitions`](PropDefinition).
+struct Converter<'a> {
+    document: &'a Value,
+    /// The references being followed, to detect recursive schemas.
+    following: Vec<String>,
+    unsupported: Vec<UnsupportedConstruct>,
+}
+
+impl<'a> Converter<'a> {
+    fn new(document: &'a Value) -> Self {
+        Self {
+            document,
+            following: Vec::new(),
+            unsupported: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, pointer: &str, message: impl Into<String>) {
+        self.unsupported.push(UnsupportedConstruct {
+            pointer: pointer.to_owned(),
+            message: message.into(),
+        });
+    }
+
+    fn convert_root(
+        mut self,
+        schema: &Value,
+        pointer: String,
+    ) -> JsonSchemaImportResult<JsonSchemaImport> {
+        let (schema, pointer) = self
+            .resolve(schema, pointer)
+            .ok_or(JsonSchemaImportError::RootNotObject)?;
+        // The root may be referenced by its own properties
+        self.following.push(pointer.clone());
+        let schema = self.merge_all_of(&schema, &pointer);
+        if !schema.get("properties").map_or(false, Value::is_object) {
+            return Err(JsonSchemaImportError::RootNotObject);
+        }
+
+        let props = self.object_children(&schema, &pointer);
+        Ok(JsonSchemaImport {
+            definition: SchemaVariantDefinitionJson {
+                props,
+                resource_props: Vec::new(),
+                si_prop_value_froms: Vec::new(),
+                input_sockets: Vec::new(),
+                output_sockets: Vec::new(),
+                doc_links: None,
+            },
+            unsupported: self.unsupported,
+        })
+    }
+
+    /// Follows the reference of a schema, if it has one, returning the referenced schema and its
+    /// pointer. Returns [`None`] for references which cannot be followed, after reporting them.
+    fn resolve(&mut self, schema: &Value, pointer: String) -> Option<(Value, String)> {
+        let reference = match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference,
+            None => return Some((schema.clone(), pointer)),
+        };
+        let target = match reference.strip_prefix('#') {
+            Some(target) => target,
+            None => {
+                self.report(
+                    &pointer,
+                    format!("reference {reference} is not local to the document"),
+                );
+                return None;
+            }
+        };
+        match self.document.pointer(target) {
+            Some(resolved) => Some((resolved.clone(), reference.to_owned())),
+            None => {
+                self.report(&pointer, format!("reference {reference} does not resolve"));
+                None
+            }
+        }
+    }
+
+    /// Merges the members of `allOf` into the schema, when they all describe objects.
+    fn merge_all_of(&mut self, schema: &Value, pointer: &str) -> Value {
+        let members = match schema.get("allOf").and_then(Value::as_array) {
+            Some(members) => members.clone(),
+            None => return schema.clone(),
+        };
+        let mut merged = schema.as_object().cloned().unwrap_or_default();
+        merged.remove("allOf");
+
+        for (index, member) in members.iter().enumerate() {
+            let member_pointer = format!("{pointer}/allOf/{index}");
+            let reference = member.get("$ref").and_then(Value::as_str);
+            if let Some(reference) = reference.filter(|r| self.following.iter().any(|f| f == r)) {
+                self.report(
+                    &member_pointer,
+                    format!("recursive reference {reference} cannot be merged"),
+                );
+                continue;
+            }
+            let member = match self.resolve(member, member_pointer.clone()) {
+                Some((member, member_pointer)) => {
+                    self.following.extend(reference.map(ToOwned::to_owned));
+                    let member = self.merge_all_of(&member, &member_pointer);
+                    if reference.is_some() {
+                        self.following.pop();
+                    }
+                    member
+                }
+                None => continue,
+            };
+            let member = match member {
+                Value::Object(member) if member.contains_key("properties") => member,
+                _ => {
+                    self.report(
+                        &member_pointer,
+                        "allOf members which do not describe objects are ignored",
+                    );
+                    continue;
+                }
+            };
+
+            for (key, value) in member {
+                match (key.as_str(), merged.get_mut(&key)) {
+                    ("properties", Some(Value::Object(properties))) => {
+                        if let Value::Object(value) = value {
+                            properties.extend(value);
+                        }
+                    }
+                    ("required", Some(Value::Array(required))) => {
+                        if let Value::Array(value) = value {
+                            required.extend(value);
+                        }
+                    }
+                    (_, Some(_)) => {}
+                    (_, None) => {
+                        merged.insert(key, value);
+                    }
+                }
+            }
+        }
+        Value::Object(merged)
+    }
+
+    fn object_children(&mut self, schema: &Value, pointer: &str) -> Vec<PropDefinition> {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut children = Vec::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                let property_pointer = format!(
+                    "{pointer}/properties/{}",
+                    name.replace('~', "~0").replace('/', "~1")
+                );
+                if let Some(child) = self.prop(
+                    name,
+                    property,
+                    property_pointer,
+                    required.contains(&name.as_str()),
+                ) {
+                    children.push(child);
+                }
+            }
+        }
+        children
+    }
+
+    /// Converts the schema of a property to a [`PropDefinition`], returning [`None`] if it cannot
+    /// be converted at all.
+    fn prop(
+        &mut self,
+        name: &str,
+        schema: &Value,
+        pointer: String,
+        required: bool,
+    ) -> Option<PropDefinition> {
+        let reference = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        if let Some(reference) = &reference {
+            if self.following.contains(reference) {
+                self.report(
+                    &pointer,
+                    format!("recursive reference {reference} cannot be expanded into props"),
+                );
+                return None;
+            }
+        }
+        let (schema, pointer) = self.resolve(schema, pointer)?;
+
+        if let Some(reference) = reference {
+            self.following.push(reference);
+            let prop = self.resolved_prop(name, &schema, pointer, required);
+            self.following.pop();
+            prop
+        } else {
+            self.resolved_prop(name, &schema, pointer, required)
+        }
+    }
+
+    fn resolved_prop(
+        &mut self,
+        name: &str,
+        schema: &Value,
+        pointer: String,
+        required: bool,
+    ) -> Option<PropDefinition> {
+        let mut schema = self.merge_all_of(schema, &pointer);
+
+        for keyword in ["oneOf", "anyOf"] {
+            let members = match schema.get(keyword).and_then(Value::as_array) {
+                Some(members) => members.clone(),
+                None => continue,
+            };
+            let mut alternatives: Vec<&Value> = members
+                .iter()
+                .filter(|member| member.get("type").and_then(Value::as_str) != Some("null"))
+                .collect();
+            if let Some(object) = schema.as_object_mut() {
+                object.remove(keyword);
+            }
+            if alternatives.len() == 1 {
+                // A single alternative besides null is how a nullable schema is written
+                return self.prop(name, alternatives.remove(0), pointer, required);
+            }
+            self.report(
+                &pointer,
+                format!("{keyword} is not supported, only the rest of the schema is imported"),
+            );
+        }
+
+        let mut definition = PropDefinition {
+            name: name.to_owned(),
+            kind: self.kind(&schema, &pointer),
+            doc_link_ref: None,
+            doc_link: doc_link(&schema),
+            children: Vec::new(),
+            entry: None,
+            widget: None,
+            value_from: None,
+            hidden: None,
+            validations: None,
+            default_value: None,
+            map_key_funcs: None,
+        };
+
+        let mut validations = Vec::new();
+        match definition.kind {
+            PropKind::Object => {
+                definition.children = self.object_children(&schema, &pointer);
+            }
+            PropKind::Map => {
+                let entry_schema = match schema.get("additionalProperties") {
+                    Some(entry_schema @ Value::Object(_)) => entry_schema.clone(),
+                    _ => {
+                        self.report(
+                            &pointer,
+                            "free-form objects are imported as maps of strings",
+                        );
+                        Value::Object(Map::from_iter([("type".to_owned(), "string".into())]))
+                    }
+                };
+                definition.entry = self
+                    .prop(
+                        &format!("{name}Item"),
+                        &entry_schema,
+                        format!("{pointer}/additionalProperties"),
+                        false,
+                    )
+                    .map(Box::new);
+            }
+            PropKind::Array => {
+                let entry_schema = match schema.get("items") {
+                    Some(entry_schema @ Value::Object(_)) => entry_schema.clone(),
+                    _ => {
+                        self.report(
+                            &pointer,
+                            "arrays without items are imported as arrays of strings",
+                        );
+                        Value::Object(Map::from_iter([("type".to_owned(), "string".into())]))
+                    }
+                };
+                definition.entry = self
+                    .prop(
+                        &format!("{name}Item"),
+                        &entry_schema,
+                        format!("{pointer}/items"),
+                        false,
+                    )
+                    .map(Box::new);
+            }
+            PropKind::String => {
+                self.string_validations(&schema, &pointer, required, &mut validations)
+            }
+            PropKind::Integer => {
+                self.integer_validations(&schema, &pointer, required, &mut validations)
+            }
+            PropKind::Boolean => {}
+        }
+
+        if matches!(definition.kind, PropKind::Array | PropKind::Map) && definition.entry.is_none()
+        {
+            return None;
+        }
+        if required && !matches!(definition.kind, PropKind::Integer | PropKind::String) {
+            self.report(
+                &pointer,
+                "required is only enforced for strings and integers",
+            );
+        }
+
+        if let Some(default) = schema.get("default") {
+            let matches_kind = match definition.kind {
+                PropKind::Boolean => default.is_boolean(),
+                PropKind::Integer => default.is_i64(),
+                PropKind::String => default.is_string(),
+                PropKind::Array | PropKind::Map | PropKind::Object => false,
+            };
+            if matches_kind {
+                definition.default_value = Some(default.clone());
+            } else {
+                self.report(&pointer, format!("default {default} is not imported"));
+            }
+        }
+
+        if !validations.is_empty() {
+            definition.validations = Some(validations);
+        }
+        Some(definition)
+    }
+
+    fn kind(&mut self, schema: &Value, pointer: &str) -> PropKind {
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(kind)) => vec![kind.as_str()],
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|kind| *kind != "null")
+                .collect(),
+            _ => Vec::new(),
+        };
+        if types.len() > 1 {
+            self.report(
+                pointer,
+                format!("only the first of the types {types:?} is imported"),
+            );
+        }
+
+        let kind = match types.first() {
+            Some(kind) => *kind,
+            None if schema.get("properties").is_some() => "object",
+            None if schema.get("additionalProperties").is_some() => "object",
+            None if schema.get("items").is_some() => "array",
+            None if schema.get("enum").is_some() || schema.get("const").is_some() => "string",
+            None => {
+                self.report(pointer, "schemas without a type are imported as strings");
+                "string"
+            }
+        };
+        match kind {
+            "object" if schema.get("properties").is_some() => PropKind::Object,
+            "object" => PropKind::Map,
+            "array" => PropKind::Array,
+            "boolean" => PropKind::Boolean,
+            "integer" => PropKind::Integer,
+            "number" => {
+                self.report(
+                    pointer,
+                    "numbers are imported as integers, dropping their fractional part",
+                );
+                PropKind::Integer
+            }
+            "string" => PropKind::String,
+            other => {
+                self.report(pointer, format!("type {other} is imported as a string"));
+                PropKind::String
+            }
+        }
+    }
+
+    fn string_validations(
+        &mut self,
+        schema: &Value,
+        pointer: &str,
+        required: bool,
+        validations: &mut Vec<ValidationSpec>,
+    ) {
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            let expected: Vec<String> = values
+                .iter()
+                .filter_map(|value| value.as_str().map(ToOwned::to_owned))
+                .collect();
+            if expected.len() != values.len() {
+                self.report(pointer, "enum values which are not strings are ignored");
+            }
+            validations.push(ValidationSpec::StringInStringArray {
+                expected,
+                display_expected: true,
+            });
+        }
+        if let Some(expected) = schema.get("const").and_then(Value::as_str) {
+            validations.push(ValidationSpec::StringEquals {
+                expected: expected.to_owned(),
+            });
+        }
+        match schema.get("format").and_then(Value::as_str) {
+            Some("ipv4" | "ipv6") => validations.push(ValidationSpec::StringIsValidIpAddr),
+            Some(format) => self.report(pointer, format!("format {format} is not validated")),
+            None => {}
+        }
+        if schema.get("pattern").is_some() {
+            self.report(pointer, "pattern is not validated");
+        }
+        if schema.get("maxLength").is_some() {
+            self.report(pointer, "maxLength is not validated");
+        }
+        let min_length = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0);
+        if min_length > 1 {
+            self.report(pointer, "minLength is only validated as a non-empty string");
+        }
+        // Every string validation but the hex color one already rejects missing values
+        if (required || min_length > 0) && validations.is_empty() {
+            validations.push(ValidationSpec::StringIsNotEmpty);
+        }
+    }
+
+    fn integer_validations(
+        &mut self,
+        schema: &Value,
+        pointer: &str,
+        required: bool,
+        validations: &mut Vec<ValidationSpec>,
+    ) {
+        // The bounds of the validation are exclusive. OpenAPI 3.0 and JSON Schema draft 4 mark an
+        // exclusive bound with a boolean next to it, later drafts give the bound itself.
+        let minimum = schema.get("minimum").and_then(Value::as_f64);
+        let maximum = schema.get("maximum").and_then(Value::as_f64);
+        let lower_bound = match schema.get("exclusiveMinimum") {
+            Some(Value::Number(bound)) => bound.as_f64().map(f64::floor),
+            Some(Value::Bool(true)) => minimum.map(f64::floor),
+            _ => minimum.map(|minimum| minimum.ceil() - 1.0),
+        };
+        let upper_bound = match schema.get("exclusiveMaximum") {
+            Some(Value::Number(bound)) => bound.as_f64().map(f64::ceil),
+            Some(Value::Bool(true)) => maximum.map(f64::ceil),
+            _ => maximum.map(|maximum| maximum.floor() + 1.0),
+        };
+        if lower_bound.is_some() || upper_bound.is_some() {
+            // Casting saturates, so unbounded sides become the extremes of an i64
+            validations.push(ValidationSpec::IntegerIsBetweenTwoIntegers {
+                lower_bound: lower_bound.map_or(i64::MIN, |bound| bound as i64),
+                upper_bound: upper_bound.map_or(i64::MAX, |bound| bound as i64),
+            });
+        } else if required {
+            validations.push(ValidationSpec::IntegerIsNotEmpty);
+        }
+        if schema.get("enum").is_some() || schema.get("const").is_some() {
+            self.report(pointer, "enum and const are only validated for strings");
+        }
+        if schema.get("multipleOf").is_some() {
+            self.report(pointer, "multipleOf is not validated");
+        }
+    }
+}
+
+/// Returns the URL of the external documentation of a schema, or its description when it is a URL.
+fn doc_link(schema: &Value) -> Option<String> {
+    schema
+        .pointer("/externalDocs/url")
+        .or_else(|| schema.get("description"))
+        .and_then(Value::as_str)
+        .filter(|link| url::Url::parse(link).is_ok())
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn converts_json_schema_properties_to_props() {
+        let import = JsonSchemaImport::from_json_schema(&json!({
+            "type": "object",
+            "required": ["name", "port"],
+            "properties": {
+                "name": { "type": "string", "externalDocs": { "url": "https://example.com/name" } },
+                "port": { "type": "integer", "minimum": 1, "maximum": 65535 },
+                "protocol": { "type": "string", "enum": ["tcp", "udp"], "default": "tcp" },
+                "tags": { "type": "object", "additionalProperties": { "type": "string" } },
+                "addresses": { "type": "array", "items": { "type": "string", "format": "ipv4" } },
+                "ratio": { "type": "number" }
+            }
+        }))
+        .expect("could not convert document");
+
+        let props: HashMap<&str, &PropDefinition> = import
+            .definition
+            .props
+            .iter()
+            .map(|prop| (prop.name.as_str(), prop))
+            .collect();
+        assert_eq!(6, props.len());
+
+        assert_eq!(PropKind::String, props["name"].kind);
+        assert_eq!(
+            Some("https://example.com/name"),
+            props["name"].doc_link.as_deref()
+        );
+        assert_eq!(
+            Some(vec![ValidationSpec::StringIsNotEmpty]),
+            props["name"].validations
+        );
+        assert_eq!(
+            Some(vec![ValidationSpec::IntegerIsBetweenTwoIntegers {
+                lower_bound: 0,
+                upper_bound: 65536,
+            }]),
+            props["port"].validations
+        );
+        assert_eq!(Some(json!("tcp")), props["protocol"].default_value);
+        assert_eq!(
+            Some(vec![ValidationSpec::StringInStringArray {
+                expected: vec!["tcp".to_owned(), "udp".to_owned()],
+                display_expected: true,
+            }]),
+            props["protocol"].validations
+        );
+        assert_eq!(PropKind::Map, props["tags"].kind);
+        assert_eq!(
+            Some(PropKind::String),
+            props["tags"].entry.as_ref().map(|entry| entry.kind)
+        );
+        assert_eq!(PropKind::Array, props["addresses"].kind);
+        assert_eq!(
+            Some(Some(vec![ValidationSpec::StringIsValidIpAddr])),
+            props["addresses"]
+                .entry
+                .as_ref()
+                .map(|entry| entry.validations.clone())
+        );
+        assert_eq!(PropKind::Integer, props["ratio"].kind);
+
+        assert_eq!(
+            vec![UnsupportedConstruct {
+                pointer: "#/properties/ratio".to_owned(),
+                message: "numbers are imported as integers, dropping their fractional part"
+                    .to_owned(),
+            }],
+            import.unsupported
+        );
+    }
+
+    #[test]
+    fn converts_openapi_component_schemas_following_references() {
+        let document = json!({
+            "openapi": "3.0.3",
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "allOf": [
+                            { "$ref": "#/components/schemas/Named" },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "owner": { "$ref": "#/components/schemas/Owner" },
+                                    "kind": {
+                                        "oneOf": [{ "type": "string" }, { "type": "integer" }]
+                                    }
+                                }
+                            }
+                        ]
+                    },
+                    "Named": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": { "name": { "type": "string" } }
+                    },
+                    "Owner": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "pets": {
+                                "type": "array",
+                                "items": { "$ref": "#/components/schemas/Pet" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let import =
+            JsonSchemaImport::from_openapi(&document, "Pet").expect("could not convert document");
+        let names: Vec<&str> = import
+            .definition
+            .props
+            .iter()
+            .map(|prop| prop.name.as_str())
+            .collect();
+        assert_eq!(vec!["name", "owner", "kind"], names);
+
+        let owner = &import.definition.props[1];
+        assert_eq!(PropKind::Object, owner.kind);
+        assert_eq!(
+            vec!["name"],
+            owner
+                .children
+                .iter()
+                .map(|prop| prop.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                "#/components/schemas/Owner/properties/pets/items",
+                "#/components/schemas/Pet/properties/kind",
+                "#/components/schemas/Pet/properties/kind",
+            ],
+            import
+                .unsupported
+                .iter()
+                .map(|unsupported| unsupported.pointer.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        assert!(matches!(
+            JsonSchemaImport::from_openapi(&document, "Missing"),
+            Err(JsonSchemaImportError::OpenApiSchemaNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_documents_which_are_not_objects() {
+        assert!(matches!(
+            JsonSchemaImport::from_json_schema(&json!({ "type": "string" })),
+            Err(JsonSchemaImportError::RootNotObject)
+        ));
+    }
+}
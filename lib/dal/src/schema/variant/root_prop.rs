@@ -429,6 +429,17 @@ impl SchemaVariant {
         .await?;
         resource_status_prop.set_hidden(ctx, true).await?;
 
+        let mut resource_health_prop = Prop::new(
+            ctx,
+            "health",
+            PropKind::String,
+            None,
+            schema_variant_id,
+            Some(resource_prop_id),
+        )
+        .await?;
+        resource_health_prop.set_hidden(ctx, true).await?;
+
         let mut resource_message_prop = Prop::new(
             ctx,
             "message",
@@ -522,6 +533,65 @@ impl SchemaVariant {
         .await?;
         child_format_prop.set_hidden(ctx, true).await?;
 
+        // Artifacts generated alongside the code, keyed by name. See
+        // [`CodeArtifactEntry`](crate::component::code::CodeArtifactEntry) for their shape.
+        let mut child_artifacts_prop = Prop::new(
+            ctx,
+            "artifacts",
+            PropKind::Map,
+            None,
+            schema_variant_id,
+            Some(code_map_item_prop_id),
+        )
+        .await?;
+        child_artifacts_prop.set_hidden(ctx, true).await?;
+
+        let mut child_artifacts_item_prop = Prop::new(
+            ctx,
+            "artifactsItem",
+            PropKind::Object,
+            None,
+            schema_variant_id,
+            Some(*child_artifacts_prop.id()),
+        )
+        .await?;
+        child_artifacts_item_prop.set_hidden(ctx, true).await?;
+
+        for artifact_field_name in ["mimeType", "contentBase64"] {
+            let mut artifact_field_prop = Prop::new(
+                ctx,
+                artifact_field_name,
+                PropKind::String,
+                None,
+                schema_variant_id,
+                Some(*child_artifacts_item_prop.id()),
+            )
+            .await?;
+            artifact_field_prop.set_hidden(ctx, true).await?;
+        }
+
+        let mut artifact_metadata_prop = Prop::new(
+            ctx,
+            "metadata",
+            PropKind::Map,
+            None,
+            schema_variant_id,
+            Some(*child_artifacts_item_prop.id()),
+        )
+        .await?;
+        artifact_metadata_prop.set_hidden(ctx, true).await?;
+
+        let mut artifact_metadata_item_prop = Prop::new(
+            ctx,
+            "metadataItem",
+            PropKind::String,
+            None,
+            schema_variant_id,
+            Some(*artifact_metadata_prop.id()),
+        )
+        .await?;
+        artifact_metadata_item_prop.set_hidden(ctx, true).await?;
+
         Ok(code_map_prop_id)
     }
 
@@ -19,15 +19,19 @@ use crate::{
     key_pair::KeyPairPk,
     pk,
     standard_model::{self, TypeHint},
-    standard_model_accessor, standard_model_accessor_ro, DalContext, HistoryEvent,
-    HistoryEventError, KeyPair, KeyPairError, StandardModel, StandardModelError, Timestamp,
-    Visibility,
+    standard_model_accessor, standard_model_accessor_ro, AuditAction, AuditLog, AuditLogError,
+    AuditTarget, DalContext, HistoryEvent, HistoryEventError, KeyPair, KeyPairError, StandardModel,
+    StandardModelError, Timestamp, Visibility,
 };
 
+const KEY_PAIR_FIND_IN_WORKSPACE: &str = include_str!("queries/key_pair_find_in_workspace.sql");
+
 /// Error type for Secrets.
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SecretError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
     #[error("error when decrypting crypted secret")]
     DecryptionFailed,
     #[error("error deserializing message: {0}")]
@@ -108,6 +112,14 @@ impl Secret {
             &serde_json::json!({"pk": self.pk, "field": "name", "value": &value}),
         )
         .await?;
+        AuditLog::record(
+            ctx,
+            AuditAction::SecretUpdate,
+            Some(AuditTarget::new("secret", self.id, Some(value.clone()))),
+            Some(serde_json::json!({ "name": &self.name })),
+            Some(serde_json::json!({ "name": &value })),
+        )
+        .await?;
         self.timestamp.updated_at = updated_at;
         self.name = value;
 
@@ -211,6 +223,20 @@ impl EncryptedSecret {
     ) -> SecretResult<Secret> {
         let name = name.as_ref();
 
+        // A secret sealed for a key pair of another workspace could never be decrypted in this one
+        let key_pair = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                KEY_PAIR_FIND_IN_WORKSPACE,
+                &[&key_pair_pk, &ctx.tenancy().workspace_pk()],
+            )
+            .await?;
+        if key_pair.is_none() {
+            return Err(SecretError::KeyPairNotFound);
+        }
+
         let row = ctx
             .txns()
             .await?
@@ -232,6 +258,19 @@ impl EncryptedSecret {
             .await?;
         let object: Secret = standard_model::finish_create_from_row(ctx, row).await?;
 
+        AuditLog::record(
+            ctx,
+            AuditAction::SecretCreate,
+            Some(AuditTarget::new(
+                "secret",
+                object.id,
+                Some(object.name.clone()),
+            )),
+            None,
+            Some(serde_json::json!({ "kind": object.kind, "objectType": object.object_type })),
+        )
+        .await?;
+
         Ok(object)
     }
 
@@ -0,0 +1,101 @@
+//! This module contains the server-side session state for interactive users: the per-user
+//! [`SessionRevocation`] list which invalidates every outstanding session token for a user.
+
+use chrono::{DateTime, Utc};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    AuditAction, AuditLog, AuditLogError, AuditTarget, DalContext, StandardModelError,
+    TransactionsError, UserPk,
+};
+
+const REVOKED_BEFORE_FOR_USER: &str = include_str!("queries/session/revoked_before_for_user.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SessionResult<T> = Result<T, SessionError>;
+
+/// The per-user revocation list: session tokens issued to a user before the recorded time are
+/// rejected.
+pub struct SessionRevocation;
+
+impl SessionRevocation {
+    /// Invalidates every outstanding session token for the given user, returning the time before
+    /// which tokens are now considered revoked.
+    #[instrument(skip_all)]
+    pub async fn revoke_all_for_user(
+        ctx: &DalContext,
+        user_pk: UserPk,
+    ) -> SessionResult<DateTime<Utc>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT this_revoked_before FROM session_revoke_all_for_user_v1($1)",
+                &[&user_pk],
+            )
+            .await?;
+        let revoked_before: DateTime<Utc> = row.try_get("this_revoked_before")?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::SessionRevokeAll,
+            Some(AuditTarget::new("user", user_pk, None)),
+            None,
+            Some(serde_json::json![{ "revokedBefore": revoked_before }]),
+        )
+        .await?;
+
+        Ok(revoked_before)
+    }
+
+    /// Returns the time before which the user's session tokens are revoked, if any revocation
+    /// has been recorded.
+    pub async fn revoked_before(
+        ctx: &DalContext,
+        user_pk: UserPk,
+    ) -> SessionResult<Option<DateTime<Utc>>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(REVOKED_BEFORE_FOR_USER, &[&user_pk])
+            .await?;
+        match row {
+            Some(row) => Ok(Some(row.try_get("revoked_before")?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns `true` if a token issued at `issued_at` is revoked by `revoked_before`. Tokens
+    /// without an issued at time are considered revoked as soon as any revocation exists.
+    pub fn is_revoked(
+        revoked_before: Option<DateTime<Utc>>,
+        issued_at: Option<DateTime<Utc>>,
+    ) -> bool {
+        match (revoked_before, issued_at) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(revoked_before), Some(issued_at)) => issued_at < revoked_before,
+        }
+    }
+}
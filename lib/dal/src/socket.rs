@@ -14,6 +14,10 @@ use crate::{
     TransactionsError, Visibility,
 };
 
+pub mod socket_type;
+
+pub use socket_type::{SocketType, SocketTypeMismatch};
+
 const FIND_BY_NAME_FOR_EDGE_KIND_AND_NODE: &str =
     include_str!("queries/socket/find_by_name_for_edge_kind_and_node.sql");
 const FIND_FRAME_SOCKET_FOR_NODE: &str =
@@ -140,6 +144,8 @@ pub struct Socket {
     arity: SocketArity,
     required: bool,
     ui_hidden: bool,
+    /// The kind of value flowing through the [`Socket`], if it is typed.
+    socket_type: Option<SocketType>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -209,6 +215,17 @@ impl Socket {
     standard_model_accessor!(diagram_kind, Enum(DiagramKind), SocketResult);
     standard_model_accessor!(required, bool, SocketResult);
     standard_model_accessor!(ui_hidden, bool, SocketResult);
+    standard_model_accessor!(socket_type, OptionJson<SocketType>, SocketResult);
+
+    /// Checks that [`self`](Self), as an output, can be connected to the `input` [`Socket`] based
+    /// on their [`SocketTypes`](SocketType). Untyped [`Sockets`](Socket) are compatible with any
+    /// other.
+    pub fn check_compatible(&self, input: &Socket) -> Result<(), SocketTypeMismatch> {
+        match (self.socket_type(), input.socket_type()) {
+            (Some(output_type), Some(input_type)) => output_type.check_compatible(input_type),
+            _ => Ok(()),
+        }
+    }
 
     standard_model_many_to_many!(
         lookup_fn: types,
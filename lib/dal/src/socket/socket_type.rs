@@ -0,0 +1,224 @@
+//! This module contains [`SocketType`], which describes the values flowing through a
+//! [`Socket`](crate::Socket) and decides which [`Sockets`](crate::Socket) can be connected.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Explains why an output [`Socket`](crate::Socket) cannot be connected to an input
+/// [`Socket`](crate::Socket), as found by [`SocketType::check_compatible()`].
+#[remain::sorted]
+#[derive(Error, Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum SocketTypeMismatch {
+    /// The input requires a property that the output does not provide.
+    #[error("the input requires {path}, which the output does not provide")]
+    MissingProperty { path: String },
+    /// The [`SocketTypes`](SocketType) are for different kinds of providers.
+    #[error("the input expects a {expected} but the output provides a {found}")]
+    ProviderKind { expected: String, found: String },
+    /// The value at `path` is of a different JSON type in each shape.
+    #[error("the input expects {path} to be of type {expected} but the output provides {found}")]
+    ShapeType {
+        path: String,
+        expected: String,
+        found: String,
+    },
+}
+
+/// The kind of value a [`Socket`](crate::Socket) provides or consumes. Output sockets can only be
+/// connected to input sockets whose [`SocketType`] is compatible, while sockets without one can be
+/// connected to anything.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketType {
+    /// What the value is, such as "Docker Image" or "Region". Only sockets of the same provider
+    /// kind can be connected.
+    pub provider_kind: String,
+    /// A JSON schema describing the shape of the value, checked in addition to the provider kind
+    /// when both sockets have one.
+    pub shape: Option<Value>,
+}
+
+impl SocketType {
+    pub fn new(provider_kind: impl Into<String>, shape: Option<Value>) -> Self {
+        Self {
+            provider_kind: provider_kind.into(),
+            shape,
+        }
+    }
+
+    /// Checks that the value of an output socket of [`self`](Self) can flow into an input socket
+    /// of the `input` [`SocketType`]. Only the "type", "properties", "required" and "items"
+    /// keywords of the shapes are taken into account.
+    pub fn check_compatible(&self, input: &SocketType) -> Result<(), SocketTypeMismatch> {
+        if self.provider_kind != input.provider_kind {
+            return Err(SocketTypeMismatch::ProviderKind {
+                expected: input.provider_kind.clone(),
+                found: self.provider_kind.clone(),
+            });
+        }
+
+        match (&self.shape, &input.shape) {
+            (Some(output_shape), Some(input_shape)) => check_shape(output_shape, input_shape, ""),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn check_shape(found: &Value, expected: &Value, path: &str) -> Result<(), SocketTypeMismatch> {
+    let display_path = || {
+        if path.is_empty() {
+            "/".to_string()
+        } else {
+            path.to_string()
+        }
+    };
+
+    let expected_type = expected.get("type").and_then(Value::as_str);
+    let found_type = found.get("type").and_then(Value::as_str);
+    if let (Some(expected_type), Some(found_type)) = (expected_type, found_type) {
+        // Integers are numbers too
+        let compatible =
+            expected_type == found_type || (expected_type == "number" && found_type == "integer");
+        if !compatible {
+            return Err(SocketTypeMismatch::ShapeType {
+                path: display_path(),
+                expected: expected_type.to_string(),
+                found: found_type.to_string(),
+            });
+        }
+    }
+
+    let found_properties = found.get("properties").and_then(Value::as_object);
+    if let Some(required) = expected.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !found_properties.map_or(false, |properties| properties.contains_key(name)) {
+                return Err(SocketTypeMismatch::MissingProperty {
+                    path: format!("{path}/{name}"),
+                });
+            }
+        }
+    }
+    if let (Some(expected_properties), Some(found_properties)) = (
+        expected.get("properties").and_then(Value::as_object),
+        found_properties,
+    ) {
+        for (name, expected_property) in expected_properties {
+            if let Some(found_property) = found_properties.get(name) {
+                check_shape(found_property, expected_property, &format!("{path}/{name}"))?;
+            }
+        }
+    }
+
+    if let (Some(expected_items), Some(found_items)) = (expected.get("items"), found.get("items")) {
+        check_shape(found_items, expected_items, &format!("{path}/items"))?;
+    }
+
+    Ok(())
+}
+
+impl postgres_types::ToSql for SocketType {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut postgres_types::private::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        let json = serde_json::to_value(self)?;
+        postgres_types::ToSql::to_sql(&json, ty, out)
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool
+    where
+        Self: Sized,
+    {
+        ty == &postgres_types::Type::JSONB
+    }
+
+    fn to_sql_checked(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut postgres_types::private::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        postgres_types::ToSql::to_sql(&self, ty, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_kinds_must_match() {
+        let output = SocketType::new("Docker Image", None);
+
+        assert_eq!(
+            Ok(()),
+            output.check_compatible(&SocketType::new("Docker Image", None))
+        );
+        assert_eq!(
+            Err(SocketTypeMismatch::ProviderKind {
+                expected: "Region".to_string(),
+                found: "Docker Image".to_string(),
+            }),
+            output.check_compatible(&SocketType::new("Region", None))
+        );
+    }
+
+    #[test]
+    fn shapes_must_match() {
+        let output = SocketType::new(
+            "Docker Image",
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "image": { "type": "string" },
+                    "ports": { "type": "array", "items": { "type": "integer" } },
+                },
+            })),
+        );
+
+        let input = SocketType::new(
+            "Docker Image",
+            Some(serde_json::json!({
+                "type": "object",
+                "required": ["image"],
+                "properties": {
+                    "ports": { "type": "array", "items": { "type": "number" } },
+                },
+            })),
+        );
+        assert_eq!(Ok(()), output.check_compatible(&input));
+
+        let input = SocketType::new(
+            "Docker Image",
+            Some(serde_json::json!({ "type": "object", "required": ["tag"] })),
+        );
+        assert_eq!(
+            Err(SocketTypeMismatch::MissingProperty {
+                path: "/tag".to_string()
+            }),
+            output.check_compatible(&input)
+        );
+
+        let input = SocketType::new(
+            "Docker Image",
+            Some(serde_json::json!({
+                "properties": {
+                    "ports": { "type": "array", "items": { "type": "string" } },
+                },
+            })),
+        );
+        assert_eq!(
+            Err(SocketTypeMismatch::ShapeType {
+                path: "/ports/items".to_string(),
+                expected: "string".to_string(),
+                found: "integer".to_string(),
+            }),
+            output.check_compatible(&input)
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{Tenancy, TransactionsError, UserError, UserPk};
 use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
 use postgres_types::ToSql;
 use serde::{de::DeserializeOwned, Serialize};
 use si_data_nats::NatsError;
@@ -34,6 +35,22 @@ pub enum StandardModelError {
 
 pub type StandardModelResult<T> = Result<T, StandardModelError>;
 
+/// The number of rows fetched at a time by [`stream_objects`].
+pub const STREAM_BATCH_SIZE: i32 = 500;
+
+/// The tables whose live head rows can be read directly, without resolving visibility across
+/// change sets. See [`reads_head_fast_path`].
+const HEAD_FAST_PATH_TABLES: &[&str] = &["attribute_values", "components", "edges"];
+
+/// Whether reads of the table can use its head fast path, which is the case for head reads which
+/// do not look at deleted objects.
+fn reads_head_fast_path(ctx: &DalContext, table: &str) -> bool {
+    let visibility = ctx.visibility();
+    visibility.is_head()
+        && visibility.deleted_at.is_none()
+        && HEAD_FAST_PATH_TABLES.contains(&table)
+}
+
 #[remain::sorted]
 #[derive(AsRefStr, Debug, Eq, PartialEq)]
 #[strum(serialize_all = "lowercase")]
@@ -76,15 +93,22 @@ pub async fn get_by_id<ID: Send + Sync + ToSql, OBJECT: DeserializeOwned>(
     table: &str,
     id: &ID,
 ) -> StandardModelResult<Option<OBJECT>> {
-    let row_option = ctx
-        .txns()
-        .await?
-        .pg()
-        .query_opt(
-            "SELECT * FROM get_by_id_v1($1, $2, $3, $4)",
-            &[&table, ctx.tenancy(), ctx.visibility(), &id],
-        )
-        .await?;
+    let txns = ctx.txns().await?;
+    let row_option = if reads_head_fast_path(ctx, table) {
+        txns.pg()
+            .query_opt(
+                "SELECT * FROM get_by_id_head_v1($1, $2, $3)",
+                &[&table, ctx.tenancy(), &id],
+            )
+            .await?
+    } else {
+        txns.pg()
+            .query_opt(
+                "SELECT * FROM get_by_id_v1($1, $2, $3, $4)",
+                &[&table, ctx.tenancy(), ctx.visibility(), &id],
+            )
+            .await?
+    };
     object_option_from_row_option(row_option)
 }
 
@@ -98,21 +122,28 @@ pub async fn find_by_attr<V: Send + Sync + ToString + Debug, OBJECT: Deserialize
     attr_name: &str,
     value: &V,
 ) -> StandardModelResult<Vec<OBJECT>> {
-    let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
-            "SELECT * FROM find_by_attr_v1($1, $2, $3, $4, $5)",
-            &[
-                &table,
-                ctx.tenancy(),
-                ctx.visibility(),
-                &attr_name,
-                &value.to_string(),
-            ],
-        )
-        .await?;
+    let txns = ctx.txns().await?;
+    let rows = if reads_head_fast_path(ctx, table) {
+        txns.pg()
+            .query(
+                "SELECT * FROM find_by_attr_head_v1($1, $2, $3, $4)",
+                &[&table, ctx.tenancy(), &attr_name, &value.to_string()],
+            )
+            .await?
+    } else {
+        txns.pg()
+            .query(
+                "SELECT * FROM find_by_attr_v1($1, $2, $3, $4, $5)",
+                &[
+                    &table,
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &attr_name,
+                    &value.to_string(),
+                ],
+            )
+            .await?
+    };
     objects_from_rows(rows)
 }
 
@@ -141,21 +172,23 @@ pub async fn find_by_attr_in<V: Send + Sync + ToString + Debug, OBJECT: Deserial
     attr_name: &str,
     value: &[&V],
 ) -> StandardModelResult<Vec<OBJECT>> {
-    let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
-            "SELECT * FROM find_by_attr_in_v1($1, $2, $3, $4, $5)",
-            &[
-                &table,
-                ctx.tenancy(),
-                ctx.visibility(),
-                &attr_name,
-                &value.iter().map(|i| i.to_string()).collect::<Vec<String>>(),
-            ],
-        )
-        .await?;
+    let values = value.iter().map(|i| i.to_string()).collect::<Vec<String>>();
+    let txns = ctx.txns().await?;
+    let rows = if reads_head_fast_path(ctx, table) {
+        txns.pg()
+            .query(
+                "SELECT * FROM find_by_attr_in_head_v1($1, $2, $3, $4)",
+                &[&table, ctx.tenancy(), &attr_name, &values],
+            )
+            .await?
+    } else {
+        txns.pg()
+            .query(
+                "SELECT * FROM find_by_attr_in_v1($1, $2, $3, $4, $5)",
+                &[&table, ctx.tenancy(), ctx.visibility(), &attr_name, &values],
+            )
+            .await?
+    };
     objects_from_rows(rows)
 }
 
@@ -453,6 +486,29 @@ pub fn objects_from_rows<OBJECT: DeserializeOwned>(
     Ok(result)
 }
 
+/// Runs a query whose rows hold an `object` column behind a server-side cursor, returning a stream
+/// of the deserialized objects which fetches [`STREAM_BATCH_SIZE`] rows at a time.
+///
+/// The stream holds the transactions of the context until it is dropped, so it must be consumed or
+/// dropped before the context is committed.
+#[instrument(level = "trace", skip(ctx, params))]
+pub async fn stream_objects<OBJECT, E>(
+    ctx: &DalContext,
+    query: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<impl Stream<Item = Result<OBJECT, E>> + Send + 'static, E>
+where
+    OBJECT: DeserializeOwned + 'static,
+    E: From<PgError> + From<serde_json::Error> + From<TransactionsError> + 'static,
+{
+    let pg = ctx.txns().await?.pg().clone();
+    let cursor = pg.cursor(query, params, STREAM_BATCH_SIZE).await?;
+    Ok(cursor.into_stream().map(|row| {
+        let json: serde_json::Value = row?.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }))
+}
+
 pub fn object_from_row<OBJECT: DeserializeOwned>(row: PgRow) -> StandardModelResult<OBJECT> {
     let json: serde_json::Value = row.try_get("object")?;
     let object: OBJECT = serde_json::from_value(json)?;
@@ -508,20 +564,51 @@ where
         .map_err(|_| StandardModelError::ModelMissing(table.to_string(), id.to_string()))
 }
 
+/// Returns the current revision of a row, locking it against other revision-checked writers until
+/// the transaction ends. Every [`update`] bumps the revision of the row it writes.
 #[instrument(level = "trace", skip(ctx))]
-pub async fn list<OBJECT: DeserializeOwned>(
+pub async fn revision_for_update<ID>(
     ctx: &DalContext,
     table: &str,
-) -> StandardModelResult<Vec<OBJECT>> {
-    let rows = ctx
+    id: &ID,
+) -> StandardModelResult<i64>
+where
+    ID: Send + Sync + ToSql + std::fmt::Display + Debug,
+{
+    let row = ctx
         .txns()
         .await?
         .pg()
-        .query(
-            "SELECT * FROM list_models_v1($1, $2, $3)",
-            &[&table, ctx.tenancy(), ctx.visibility()],
+        .query_one(
+            "SELECT revision FROM revision_for_update_by_id_v1($1, $2, $3, $4)",
+            &[&table, ctx.tenancy(), ctx.visibility(), &id],
         )
         .await?;
+    let revision: Option<i64> = row.try_get("revision")?;
+    revision.ok_or_else(|| StandardModelError::ModelMissing(table.to_string(), id.to_string()))
+}
+
+#[instrument(level = "trace", skip(ctx))]
+pub async fn list<OBJECT: DeserializeOwned>(
+    ctx: &DalContext,
+    table: &str,
+) -> StandardModelResult<Vec<OBJECT>> {
+    let txns = ctx.txns().await?;
+    let rows = if reads_head_fast_path(ctx, table) {
+        txns.pg()
+            .query(
+                "SELECT * FROM list_models_head_v1($1, $2)",
+                &[&table, ctx.tenancy()],
+            )
+            .await?
+    } else {
+        txns.pg()
+            .query(
+                "SELECT * FROM list_models_v1($1, $2, $3)",
+                &[&table, ctx.tenancy(), ctx.visibility()],
+            )
+            .await?
+    };
     objects_from_rows(rows)
 }
 
@@ -582,6 +669,48 @@ pub async fn undelete<PK: Send + Sync + ToSql + std::fmt::Display>(
         .map_err(|_| StandardModelError::ModelMissing(table.to_string(), pk.to_string()))
 }
 
+#[instrument(level = "trace", skip(ctx))]
+pub async fn restore_by_id<ID: Send + Sync + ToSql + std::fmt::Display>(
+    ctx: &DalContext,
+    table: &str,
+    id: ID,
+) -> StandardModelResult<DateTime<Utc>> {
+    let row = ctx
+        .txns()
+        .await?
+        .pg()
+        .query_one(
+            "SELECT updated_at FROM restore_by_id_v1($1, $2, $3, $4)",
+            &[&table, ctx.tenancy(), ctx.visibility(), &id],
+        )
+        .await?;
+    row.try_get("updated_at")
+        .map_err(|_| StandardModelError::ModelMissing(table.to_string(), id.to_string()))
+}
+
+/// Hard deletes every object of the tenancy which was soft deleted before `deleted_before`, on head
+/// or in an applied or abandoned change set, along with the relationships to the objects which no
+/// longer exist in any change set. Returns how many rows were hard deleted.
+///
+/// Objects soft deleted in open change sets are kept, as hard deleting them would bring back the
+/// objects of head they hide.
+#[instrument(level = "debug", skip(ctx))]
+pub async fn garbage_collect(
+    ctx: &DalContext,
+    deleted_before: DateTime<Utc>,
+) -> StandardModelResult<i64> {
+    let row = ctx
+        .txns()
+        .await?
+        .pg()
+        .query_one(
+            "SELECT hard_deleted FROM garbage_collect_v1($1, $2)",
+            &[ctx.tenancy(), &deleted_before],
+        )
+        .await?;
+    Ok(row.try_get("hard_deleted")?)
+}
+
 #[instrument(level = "trace", skip(ctx))]
 pub async fn hard_delete<PK: Send + Sync + ToSql + std::fmt::Display, OBJECT: DeserializeOwned>(
     ctx: &DalContext,
@@ -607,14 +736,18 @@ pub async fn finish_create_from_row<Object: Send + Sync + DeserializeOwned + Sta
     row: PgRow,
 ) -> StandardModelResult<Object> {
     let json: serde_json::Value = row.try_get("object")?;
+    let object: Object = serde_json::from_value(json)?;
     let _history_event = HistoryEvent::new(
         ctx,
         Object::history_event_label(vec!["create"]),
         Object::history_event_message("created"),
-        &serde_json::json![{ "visibility": ctx.visibility() }],
+        &serde_json::json![{
+            "pk": object.pk(),
+            "id": object.id(),
+            "visibility": ctx.visibility(),
+        }],
     )
     .await?;
-    let object: Object = serde_json::from_value(json)?;
     Ok(object)
 }
 
@@ -807,6 +940,33 @@ pub trait StandardModel {
         Ok(())
     }
 
+    /// Restores this object, soft deleted in the change set of the context, so that it is visible
+    /// again.
+    #[instrument(level = "trace", skip_all, fields(table = %Self::table_name(), id = %self.id()))]
+    async fn restore(&mut self, ctx: &DalContext) -> StandardModelResult<()>
+    where
+        Self: Send + Sync + Sized,
+    {
+        let updated_at: DateTime<Utc> =
+            crate::standard_model::restore_by_id(ctx, Self::table_name(), self.id()).await?;
+
+        self.visibility_mut().deleted_at = None;
+        self.timestamp_mut().updated_at = updated_at;
+
+        HistoryEvent::new(
+            ctx,
+            &Self::history_event_label(vec!["restored"]),
+            &Self::history_event_message("restored"),
+            &serde_json::json![{
+                "pk": self.pk(),
+                "id": self.id(),
+                "visibility": self.visibility(),
+            }],
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Permanently delete this object from the database. This is not reversible!
     /// However, we do store the object's json representation as a HistoryEvent.
     #[instrument(level = "trace", skip_all, fields(table = %Self::table_name(), pk = %self.pk()))]
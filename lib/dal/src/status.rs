@@ -893,9 +893,10 @@ impl StatusUpdaterInner {
         // TODO(nick,fletcher): this method should be deleted once status updater is fully moved
         // to the status receiver because the status receiver should have its own ability to
         // "immediately publish" events.
-        let subject = format!("si.workspace_pk.{}.event", ws_event.workspace_pk());
         let msg_bytes = serde_json::to_vec(&ws_event)?;
-        ctx.nats_conn().publish(subject, msg_bytes).await?;
+        ctx.nats_conn()
+            .publish_event(&ws_event.subject()?, msg_bytes)
+            .await?;
         Ok(())
     }
 }
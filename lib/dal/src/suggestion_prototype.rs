@@ -0,0 +1,211 @@
+//! This module contains [`SuggestionPrototype`], which joins a [`Func`] to the
+//! [`Prop`](crate::Prop) whose values it suggests, such as the known regions of a cloud provider
+//! or the tags of an image in a registry.
+//!
+//! A suggestion [`Func`] is executed by `veritech` with the properties of a
+//! [`Component`](crate::Component) and returns an array of candidate values. The candidates do not
+//! depend on what has been typed so far: they are filtered by prefix afterwards, with
+//! [`filter_candidates()`], so that callers can keep the candidates of a
+//! [`Component`](crate::Component) around while the user types.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    func::binding::{FuncBinding, FuncBindingError},
+    impl_standard_model, pk,
+    standard_model::{self, objects_from_rows},
+    standard_model_accessor, ComponentId, ComponentView, ComponentViewError, DalContext, Func,
+    FuncBackendKind, FuncBackendResponseType, FuncError, FuncId, HistoryEventError, PropId,
+    StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError, Visibility,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SuggestionPrototypeError {
+    #[error("component view error: {0}")]
+    ComponentView(#[from] ComponentViewError),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("func binding error: {0}")]
+    FuncBinding(#[from] FuncBindingError),
+    #[error("func {0} is not a suggestion func: it must be a JsAttribute func returning an Array")]
+    FuncNotSuggestion(FuncId),
+    #[error("history event error: {0}")]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("suggestion func {0} returned {1}, which is not an array of candidates")]
+    InvalidCandidates(FuncId, Value),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SuggestionPrototypeResult<T> = Result<T, SuggestionPrototypeError>;
+
+const LIST_FOR_PROP: &str = include_str!("queries/suggestion_prototype/list_for_prop.sql");
+
+pk!(SuggestionPrototypePk);
+pk!(SuggestionPrototypeId);
+
+/// A SuggestionPrototype joins a suggestion [`Func`] to the [`Prop`](crate::Prop) whose values it
+/// suggests.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuggestionPrototype {
+    pk: SuggestionPrototypePk,
+    id: SuggestionPrototypeId,
+    func_id: FuncId,
+    prop_id: PropId,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    visibility: Visibility,
+}
+
+impl_standard_model! {
+    model: SuggestionPrototype,
+    pk: SuggestionPrototypePk,
+    id: SuggestionPrototypeId,
+    table_name: "suggestion_prototypes",
+    history_event_label_base: "suggestion_prototype",
+    history_event_message_name: "Suggestion Prototype"
+}
+
+impl SuggestionPrototype {
+    /// Binds the [`Func`] to the [`Prop`](crate::Prop), failing if the [`Func`] is not a
+    /// `JsAttribute` [`Func`] returning an `Array`.
+    #[instrument(skip_all)]
+    pub async fn new(
+        ctx: &DalContext,
+        func_id: FuncId,
+        prop_id: PropId,
+    ) -> SuggestionPrototypeResult<Self> {
+        let func = Func::get_by_id(ctx, &func_id)
+            .await?
+            .ok_or(FuncError::NotFound(func_id))?;
+        if *func.backend_kind() != FuncBackendKind::JsAttribute
+            || *func.backend_response_type() != FuncBackendResponseType::Array
+        {
+            return Err(SuggestionPrototypeError::FuncNotSuggestion(func_id));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM suggestion_prototype_create_v1($1, $2, $3, $4)",
+                &[ctx.tenancy(), ctx.visibility(), &func_id, &prop_id],
+            )
+            .await?;
+        let object = standard_model::finish_create_from_row(ctx, row).await?;
+        Ok(object)
+    }
+
+    standard_model_accessor!(func_id, Pk(FuncId), SuggestionPrototypeResult);
+    standard_model_accessor!(prop_id, Pk(PropId), SuggestionPrototypeResult);
+
+    /// List all [`SuggestionPrototypes`](Self) for a given [`Prop`](crate::Prop).
+    #[instrument(skip_all)]
+    pub async fn list_for_prop(
+        ctx: &DalContext,
+        prop_id: PropId,
+    ) -> SuggestionPrototypeResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_PROP, &[ctx.tenancy(), ctx.visibility(), &prop_id])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Executes the suggestion [`Func`] with the properties of the
+    /// [`Component`](crate::Component), returning the candidates it suggests.
+    #[instrument(skip_all)]
+    pub async fn candidates(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> SuggestionPrototypeResult<Vec<Value>> {
+        let view = ComponentView::new(ctx, component_id).await?;
+        let args = serde_json::json!({ "properties": view.properties });
+        let (_, func_binding_return_value) =
+            FuncBinding::create_and_execute(ctx, args, self.func_id).await?;
+
+        match func_binding_return_value.value() {
+            Some(Value::Array(candidates)) => Ok(candidates.clone()),
+            None | Some(Value::Null) => Ok(Vec::new()),
+            Some(value) => Err(SuggestionPrototypeError::InvalidCandidates(
+                self.func_id,
+                value.clone(),
+            )),
+        }
+    }
+
+    /// Executes every [`SuggestionPrototype`] of the [`Prop`](crate::Prop) for the
+    /// [`Component`](crate::Component), returning their candidates, without duplicates, in the
+    /// order they were suggested.
+    pub async fn candidates_for_prop(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        prop_id: PropId,
+    ) -> SuggestionPrototypeResult<Vec<Value>> {
+        let mut candidates: Vec<Value> = Vec::new();
+        for prototype in Self::list_for_prop(ctx, prop_id).await? {
+            for candidate in prototype.candidates(ctx, component_id).await? {
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+        Ok(candidates)
+    }
+}
+
+/// Returns the candidates which start with `prefix`, ignoring case. Candidates which are not
+/// strings are matched by their JSON representation.
+pub fn filter_candidates(candidates: &[Value], prefix: &str) -> Vec<Value> {
+    let prefix = prefix.to_lowercase();
+    candidates
+        .iter()
+        .filter(|candidate| {
+            let text = match candidate {
+                Value::String(text) => text.to_lowercase(),
+                other => other.to_string().to_lowercase(),
+            };
+            text.starts_with(&prefix)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn filters_candidates_by_prefix_ignoring_case() {
+        let candidates = vec![
+            json!("us-east-1"),
+            json!("US-West-2"),
+            json!("eu-west-1"),
+            json!(42),
+        ];
+        assert_eq!(
+            vec![json!("us-east-1"), json!("US-West-2")],
+            filter_candidates(&candidates, "us-")
+        );
+        assert_eq!(vec![json!(42)], filter_candidates(&candidates, "4"));
+        assert_eq!(candidates, filter_candidates(&candidates, ""));
+    }
+}
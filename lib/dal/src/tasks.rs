@@ -2,9 +2,27 @@
 //! SI binaries that are dependent on the [`dal`](crate).
 
 // This modules should remain private! Add "pub use" statements to use their contents.
+mod audit_log_pruner;
+mod blob_garbage_collector;
+mod change_set_apply_scheduler;
+mod history_event_pruner;
+mod notifier;
+mod qualification_rechecker;
 mod resource_scheduler;
 mod status_receiver;
+mod usage_aggregation_scheduler;
+mod webhook_dispatcher;
+mod workspace_backup_scheduler;
 
+pub use audit_log_pruner::{AuditLogPruner, AuditLogPrunerError};
+pub use blob_garbage_collector::{BlobGarbageCollector, BlobGarbageCollectorError};
+pub use change_set_apply_scheduler::{ChangeSetApplyScheduler, ChangeSetApplySchedulerError};
+pub use history_event_pruner::{HistoryEventPruner, HistoryEventPrunerError};
+pub use notifier::{Notifier, NotifierError};
+pub use qualification_rechecker::{QualificationRechecker, QualificationRecheckerError};
 pub use resource_scheduler::{ResourceScheduler, ResourceSchedulerError};
 pub use status_receiver::client::StatusReceiverClient;
 pub use status_receiver::{StatusReceiver, StatusReceiverError, StatusReceiverRequest};
+pub use usage_aggregation_scheduler::{UsageAggregationScheduler, UsageAggregationSchedulerError};
+pub use webhook_dispatcher::{WebhookDispatcher, WebhookDispatcherError};
+pub use workspace_backup_scheduler::{WorkspaceBackupScheduler, WorkspaceBackupSchedulerError};
@@ -0,0 +1,79 @@
+//! This module contains [`AuditLogPruner`], which is a "long-running" task that removes
+//! [`audit log`](crate::audit_log) entries which have outlived the retention policy.
+
+use std::time::Duration;
+
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{AuditLog, AuditLogError, AuditRetentionPolicy, ServicesContext, TransactionsError};
+
+/// How often the pruner runs.
+const AUDIT_LOG_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum AuditLogPrunerError {
+    #[error(transparent)]
+    AuditLog(#[from] AuditLogError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type AuditLogPrunerResult<T> = Result<T, AuditLogPrunerError>;
+
+/// Prunes audit log entries across every workspace once a day, according to the
+/// [`AuditRetentionPolicy`].
+#[derive(Debug, Clone)]
+pub struct AuditLogPruner {
+    services_context: ServicesContext,
+    policy: AuditRetentionPolicy,
+}
+
+impl AuditLogPruner {
+    pub fn new(services_context: ServicesContext, policy: AuditRetentionPolicy) -> Self {
+        Self {
+            services_context,
+            policy,
+        }
+    }
+
+    /// Starts the pruner, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Audit Log Pruner received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Audit Log Pruner stopped");
+        });
+    }
+
+    #[instrument(name = "audit_log_pruner.run", skip_all, level = "debug")]
+    async fn run(&self) -> AuditLogPrunerResult<()> {
+        // An empty tenancy prunes entries across all workspaces
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+
+        let pruned = AuditLog::prune(&ctx, self.policy).await?;
+        ctx.commit().await?;
+
+        info!(%pruned, max_age_days = self.policy.max_age_days, "pruned audit log entries");
+        Ok(())
+    }
+
+    #[instrument(name = "audit_log_pruner.start_task", skip_all, level = "debug")]
+    async fn start_task(&self) {
+        let mut interval = time::interval(AUDIT_LOG_PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! This module contains [`BlobGarbageCollector`], which is a "long-running" task that removes
+//! [`blobs`](crate::blob) no longer referenced by any
+//! [`FuncBindingReturnValue`](crate::FuncBindingReturnValue).
+
+use std::time::Duration;
+
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{Blob, BlobError, ServicesContext, TransactionsError};
+
+/// How often the collector runs.
+const BLOB_GARBAGE_COLLECTION_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Blobs stored more recently than this are kept even when unreferenced, since the transaction
+/// referencing them may not have committed yet.
+const BLOB_GARBAGE_COLLECTION_GRACE_PERIOD_HOURS: i64 = 1;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum BlobGarbageCollectorError {
+    #[error(transparent)]
+    Blob(#[from] BlobError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type BlobGarbageCollectorResult<T> = Result<T, BlobGarbageCollectorError>;
+
+/// Deletes unreferenced blobs once a day.
+#[derive(Debug, Clone)]
+pub struct BlobGarbageCollector {
+    services_context: ServicesContext,
+}
+
+impl BlobGarbageCollector {
+    pub fn new(services_context: ServicesContext) -> Self {
+        Self { services_context }
+    }
+
+    /// Starts the collector, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Blob Garbage Collector received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Blob Garbage Collector stopped");
+        });
+    }
+
+    #[instrument(name = "blob_garbage_collector.run", skip_all, level = "debug")]
+    async fn run(&self) -> BlobGarbageCollectorResult<()> {
+        // Blobs are content-addressed and shared by every workspace
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+
+        let stored_before =
+            ctx.now() - chrono::Duration::hours(BLOB_GARBAGE_COLLECTION_GRACE_PERIOD_HOURS);
+        let deleted = Blob::collect_garbage(&ctx, stored_before).await?;
+        ctx.commit().await?;
+
+        info!(%deleted, "collected unreferenced blobs");
+        Ok(())
+    }
+
+    #[instrument(name = "blob_garbage_collector.start_task", skip_all, level = "debug")]
+    async fn start_task(&self) {
+        let mut interval = time::interval(BLOB_GARBAGE_COLLECTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! This module contains [`ChangeSetApplyScheduler`], which is a "long-running" task that applies
+//! the [`ChangeSets`](crate::ChangeSet) whose [`ChangeSetApplySchedule`] is due.
+
+use std::time::Duration;
+
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::watch, time};
+
+use crate::{
+    ChangeSetApplySchedule, ChangeSetApplyScheduleError, DalContextBuilder, ServicesContext,
+    Tenancy, TransactionsError, Visibility,
+};
+
+/// How often the scheduler looks for applies which are due.
+const CHANGE_SET_APPLY_SCHEDULER_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ChangeSetApplySchedulerError {
+    #[error(transparent)]
+    ChangeSetApplySchedule(#[from] ChangeSetApplyScheduleError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ChangeSetApplySchedulerResult<T> = Result<T, ChangeSetApplySchedulerError>;
+
+/// Claims the [`ChangeSetApplySchedules`](ChangeSetApplySchedule) which are due, runs the
+/// qualifications of their change sets again and applies the change sets whose qualifications
+/// pass.
+#[derive(Debug, Clone)]
+pub struct ChangeSetApplyScheduler {
+    services_context: ServicesContext,
+}
+
+impl ChangeSetApplyScheduler {
+    pub fn new(services_context: ServicesContext) -> Self {
+        Self { services_context }
+    }
+
+    /// Starts the scheduler, consuming itself. The spawned task stops when a shutdown is in
+    /// progress.
+    pub fn start(self, mut shutdown_watch_rx: watch::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_watch_rx.changed() => {
+                    info!("Change Set Apply Scheduler received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Change Set Apply Scheduler stopped");
+        });
+    }
+
+    #[instrument(
+        name = "change_set_apply_scheduler.start_task",
+        skip_all,
+        level = "debug"
+    )]
+    async fn start_task(&self) {
+        let mut interval = time::interval(CHANGE_SET_APPLY_SCHEDULER_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+
+    #[instrument(name = "change_set_apply_scheduler.run", skip_all, level = "debug")]
+    async fn run(&self) -> ChangeSetApplySchedulerResult<()> {
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+        // Claiming bypasses tenancy checks, so that every workspace is scheduled at once
+        let due = ChangeSetApplySchedule::claim_due(&ctx, ctx.now()).await?;
+        ctx.commit().await?;
+
+        for mut schedule in due {
+            if let Err(err) = Self::apply(&builder, &mut schedule).await {
+                warn!(
+                    error = ?err,
+                    schedule_pk = %schedule.pk(),
+                    "failed to perform scheduled change set apply"
+                );
+                if let Err(err) = Self::fail(&builder, &mut schedule, err).await {
+                    error!(error = ?err, "could not mark scheduled change set apply as failed");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the qualifications of the change set again, waiting for them, then applies it.
+    async fn apply(
+        builder: &DalContextBuilder,
+        schedule: &mut ChangeSetApplySchedule,
+    ) -> ChangeSetApplySchedulerResult<()> {
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(*schedule.workspace_pk()));
+        ctx.update_visibility(Visibility::new(*schedule.change_set_pk(), None));
+        schedule.rerun_qualifications(&ctx).await?;
+        ctx.blocking_commit().await?;
+
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(*schedule.workspace_pk()));
+        if schedule.perform(&mut ctx).await? {
+            info!(
+                schedule_pk = %schedule.pk(),
+                change_set_pk = %schedule.change_set_pk(),
+                status = %schedule.status(),
+                "performed scheduled change set apply"
+            );
+        }
+        ctx.commit().await?;
+        Ok(())
+    }
+
+    async fn fail(
+        builder: &DalContextBuilder,
+        schedule: &mut ChangeSetApplySchedule,
+        err: ChangeSetApplySchedulerError,
+    ) -> ChangeSetApplySchedulerResult<()> {
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(*schedule.workspace_pk()));
+        schedule.fail(&ctx, err.to_string()).await?;
+        ctx.commit().await?;
+        Ok(())
+    }
+}
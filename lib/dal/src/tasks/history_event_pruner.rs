@@ -0,0 +1,88 @@
+//! This module contains [`HistoryEventPruner`], which is a "long-running" task that removes
+//! [`HistoryEvents`](crate::HistoryEvent) which have outlived the retention policy, archiving them
+//! if the policy asks for it.
+
+use std::time::Duration;
+
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{
+    HistoryEvent, HistoryEventError, HistoryEventRetentionPolicy, ServicesContext,
+    TransactionsError,
+};
+
+/// How often the pruner runs.
+const HISTORY_EVENT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum HistoryEventPrunerError {
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type HistoryEventPrunerResult<T> = Result<T, HistoryEventPrunerError>;
+
+/// Prunes history events across every workspace once a day, according to the
+/// [`HistoryEventRetentionPolicy`].
+#[derive(Debug, Clone)]
+pub struct HistoryEventPruner {
+    services_context: ServicesContext,
+    policy: HistoryEventRetentionPolicy,
+}
+
+impl HistoryEventPruner {
+    pub fn new(services_context: ServicesContext, policy: HistoryEventRetentionPolicy) -> Self {
+        Self {
+            services_context,
+            policy,
+        }
+    }
+
+    /// Starts the pruner, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("History Event Pruner received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("History Event Pruner stopped");
+        });
+    }
+
+    #[instrument(name = "history_event_pruner.run", skip_all, level = "debug")]
+    async fn run(&self) -> HistoryEventPrunerResult<()> {
+        // An empty tenancy prunes events across all workspaces
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+
+        let pruned = HistoryEvent::prune(&ctx, self.policy).await?;
+        ctx.commit().await?;
+
+        info!(
+            %pruned,
+            max_age_days = self.policy.max_age_days,
+            archive = self.policy.archive,
+            "pruned history events"
+        );
+        Ok(())
+    }
+
+    #[instrument(name = "history_event_pruner.start_task", skip_all, level = "debug")]
+    async fn start_task(&self) {
+        let mut interval = time::interval(HISTORY_EVENT_PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+}
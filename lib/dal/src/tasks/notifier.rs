@@ -0,0 +1,190 @@
+//! This module contains [`Notifier`], which is a "long-running" task that listens to the
+//! [`WsEvents`](WsEvent) of every workspace over [NATS](https://nats.io) and sends the
+//! [`Notifications`](Notification) built from them, as decided by [`Notification::for_event()`],
+//! over each of its [`NotificationChannels`](NotificationChannel).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use nats_subscriber::{Request, SubscriberError, Subscription};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::sync::{broadcast, Mutex};
+
+use crate::notification::{Notification, NotificationChannel, NotificationError, NotificationKind};
+use crate::{
+    ChangeSetPk, ComponentId, DalContextBuilder, ServicesContext, Tenancy, TransactionsError,
+    Visibility, WsEvent, WsEventError, WsPayload,
+};
+
+/// The queue name for [NATS](https://nats.io), so that each event is handled by a single
+/// [`Notifier`] when several services run one.
+const NOTIFIER_QUEUE_NAME: &str = "notifier";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum NotifierError {
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+    #[error(transparent)]
+    Subscriber(#[from] SubscriberError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type NotifierResult<T> = Result<T, NotifierError>;
+
+/// The channels every notification is sent over.
+type Channels = Arc<Vec<Arc<dyn NotificationChannel>>>;
+
+/// The body of the last notification sent about a component, by change set and kind, for the
+/// components whose qualifications are failing or whose resource drifted.
+type ComponentNotifications =
+    Arc<Mutex<HashMap<(ChangeSetPk, ComponentId, NotificationKind), String>>>;
+
+/// Tells the users of a workspace about failing qualifications, drifted resources, requested
+/// reviews and applied change sets.
+#[derive(Debug)]
+pub struct Notifier {
+    services_context: ServicesContext,
+    channels: Channels,
+    events: Subscription<WsEvent>,
+}
+
+impl Notifier {
+    /// Creates a new [`Notifier`], which sends every notification over each of the given
+    /// [`NotificationChannels`](NotificationChannel).
+    pub async fn new(
+        services_context: ServicesContext,
+        channels: Vec<Arc<dyn NotificationChannel>>,
+    ) -> NotifierResult<Self> {
+        // The events of every workspace and change set
+        let subject = WsEvent::subscription_subject(None, None)?;
+        let events = Subscription::create(subject.to_string())
+            .queue_name(NOTIFIER_QUEUE_NAME)
+            .start(services_context.nats_conn())
+            .await?;
+        Ok(Self {
+            services_context,
+            channels: Arc::new(channels),
+            events,
+        })
+    }
+
+    /// Starts the notifier, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        info!("starting notifier");
+        tokio::spawn(Self::start_task(
+            self.services_context,
+            self.channels,
+            self.events,
+            shutdown_broadcast_rx,
+        ));
+    }
+
+    #[instrument(name = "notifier.start_task", skip_all, level = "debug")]
+    async fn start_task(
+        services_context: ServicesContext,
+        channels: Channels,
+        mut events: Subscription<WsEvent>,
+        mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let component_notifications = ComponentNotifications::default();
+        loop {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    trace!("the notifier task received shutdown");
+                    break;
+                }
+                event = events.next() => {
+                    match event {
+                        Some(Ok(event)) => {
+                            let builder = services_context.clone().into_builder(false);
+                            let channels = channels.clone();
+                            let component_notifications = component_notifications.clone();
+                            tokio::spawn(async move {
+                                if let Err(err) =
+                                    Self::process(builder, channels, component_notifications, event).await
+                                {
+                                    warn!(error = ?err, "failed to send notification");
+                                }
+                            });
+                        }
+                        Some(Err(err)) => {
+                            warn!(error = ?err, "next notifier event errored");
+                        }
+                        None => {
+                            trace!("notifier events subscriber stream has closed");
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Err(err) = events.unsubscribe().await {
+            error!("could not unsubscribe from nats: {:?}", err);
+        }
+    }
+
+    #[instrument(name = "notifier.process", skip_all, level = "debug")]
+    async fn process(
+        builder: DalContextBuilder,
+        channels: Channels,
+        component_notifications: ComponentNotifications,
+        request: Request<WsEvent>,
+    ) -> NotifierResult<()> {
+        let event = request.payload;
+        if NotificationKind::for_payload(event.payload()).is_none() {
+            return Ok(());
+        }
+
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(event.workspace_pk()));
+        ctx.update_visibility(Visibility::new_change_set(event.change_set_pk(), false));
+
+        let notification = Notification::for_event(&ctx, &event).await?;
+
+        // Qualifications are checked again, and resources refreshed, whenever the component
+        // changes, so the same failure or drift is only announced once, until it changes
+        let component_id = match event.payload() {
+            WsPayload::CheckedQualifications(payload) => Some(payload.component_id()),
+            WsPayload::ResourceDrifted(payload) => Some(payload.component_id()),
+            _ => None,
+        };
+        if let Some(component_id) = component_id {
+            let mut component_notifications = component_notifications.lock().await;
+            match &notification {
+                Some(notification) => {
+                    let key = (event.change_set_pk(), component_id, notification.kind);
+                    if component_notifications.get(&key) == Some(&notification.body) {
+                        return Ok(());
+                    }
+                    component_notifications.insert(key, notification.body.clone());
+                }
+                // Only passing qualifications build no notification
+                None => {
+                    component_notifications.remove(&(
+                        event.change_set_pk(),
+                        component_id,
+                        NotificationKind::QualificationFailed,
+                    ));
+                }
+            }
+        }
+
+        if let Some(notification) = notification {
+            // A channel failing does not keep the others from being told
+            for channel in channels.iter() {
+                if let Err(err) = channel.send(&ctx, &notification).await {
+                    warn!(error = ?err, ?channel, "failed to send notification over channel");
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+//! This module contains [`QualificationRechecker`], which is a "long-running" task that runs
+//! qualifications again once their results have outlived the re-check interval of their
+//! [`AttributePrototype`](crate::AttributePrototype).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::job::definition::DependentValuesUpdate;
+use crate::{
+    AttributeValue, AttributeValueError, AttributeValueId, ServicesContext, StandardModel,
+    StandardModelError, Tenancy, TransactionsError, Visibility, WorkspacePk,
+};
+
+const LIST_STALE: &str = include_str!("../queries/qualification/list_stale.sql");
+
+/// How often the rechecker looks for stale qualifications.
+const QUALIFICATION_RECHECK_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum QualificationRecheckerError {
+    #[error(transparent)]
+    AttributeValue(#[from] AttributeValueError),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type QualificationRecheckerResult<T> = Result<T, QualificationRecheckerError>;
+
+/// Looks for qualifications on head whose last run is older than the re-check interval of their
+/// prototype and runs them again, then enqueues a [`DependentValuesUpdate`] job per workspace for
+/// the values which depend on them.
+#[derive(Debug, Clone)]
+pub struct QualificationRechecker {
+    services_context: ServicesContext,
+}
+
+impl QualificationRechecker {
+    pub fn new(services_context: ServicesContext) -> Self {
+        Self { services_context }
+    }
+
+    /// Starts the rechecker, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Qualification Rechecker received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Qualification Rechecker stopped");
+        });
+    }
+
+    /// Runs the stale qualifications of every workspace again.
+    #[instrument(name = "qualification_rechecker.run", skip_all, level = "debug")]
+    pub async fn run(&self) -> QualificationRecheckerResult<()> {
+        for (workspace_pk, attribute_value_ids) in self.stale_qualifications().await? {
+            // A workspace failing to be re-checked must not hold back the others
+            if let Err(err) = self.recheck(workspace_pk, attribute_value_ids).await {
+                error!(%workspace_pk, "failed to re-check stale qualifications: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    #[instrument(
+        name = "qualification_rechecker.recheck",
+        skip(self, attribute_value_ids),
+        level = "debug"
+    )]
+    async fn recheck(
+        &self,
+        workspace_pk: WorkspacePk,
+        attribute_value_ids: Vec<AttributeValueId>,
+    ) -> QualificationRecheckerResult<()> {
+        let builder = self.services_context.clone().into_builder(false);
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(workspace_pk));
+
+        info!(
+            %workspace_pk,
+            count = attribute_value_ids.len(),
+            "re-checking stale qualifications"
+        );
+        // A DependentValuesUpdate considers the values it is given to be updated already, so the
+        // qualifications are run here and the job only updates the values depending on them
+        for attribute_value_id in &attribute_value_ids {
+            if let Some(mut attribute_value) =
+                AttributeValue::get_by_id(&ctx, attribute_value_id).await?
+            {
+                attribute_value.update_from_prototype_function(&ctx).await?;
+            }
+        }
+        ctx.enqueue_job(DependentValuesUpdate::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            attribute_value_ids,
+        ))
+        .await?;
+        ctx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "qualification_rechecker.start_task", skip_all, level = "debug")]
+    async fn start_task(&self) {
+        let mut interval = time::interval(QUALIFICATION_RECHECK_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+
+    /// Gets the stale qualification [`AttributeValues`](crate::AttributeValue) on head, grouped
+    /// by workspace.
+    #[instrument(skip_all, level = "debug")]
+    pub async fn stale_qualifications(
+        &self,
+    ) -> QualificationRecheckerResult<HashMap<WorkspacePk, Vec<AttributeValueId>>> {
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+
+        // We need to bypass tenancy checks, only qualifications on head are re-checked
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_STALE, &[&Visibility::new_head(false), &ctx.now()])
+            .await?;
+
+        let mut stale: HashMap<WorkspacePk, Vec<AttributeValueId>> = HashMap::new();
+        for row in rows {
+            let workspace_pk: Option<WorkspacePk> = row.try_get("workspace_pk")?;
+            let attribute_value_id: AttributeValueId = row.try_get("attribute_value_id")?;
+            if let Some(workspace_pk) = workspace_pk {
+                stale
+                    .entry(workspace_pk)
+                    .or_default()
+                    .push(attribute_value_id);
+            }
+        }
+
+        ctx.commit().await?;
+        Ok(stale)
+    }
+}
@@ -17,7 +17,7 @@ use tokio::sync::broadcast;
 use crate::{
     AttributeValue, AttributeValueError, AttributeValueId, Component, ComponentId, DalContext,
     DalContextBuilder, ServicesContext, StandardModel, StandardModelError, Tenancy,
-    TransactionsError, Visibility, WsEvent,
+    TransactionsError, Visibility, WsEvent, WsEventError,
 };
 
 pub mod client;
@@ -45,6 +45,8 @@ pub enum StatusReceiverError {
     Subscriber(#[from] SubscriberError),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
 }
 
 pub type StatusReceiverResult<T> = Result<T, StatusReceiverError>;
@@ -233,9 +235,10 @@ impl StatusReceiver {
     /// This method requires an owned [`WsEvent`](crate::WsEvent), despite it not needing to,
     //  because [`events`](crate::WsEvent) should likely not be reused.
     async fn publish_immediately(ctx: &DalContext, ws_event: WsEvent) -> StatusReceiverResult<()> {
-        let subject = format!("si.workspace_pk.{}.event", ws_event.workspace_pk());
         let msg_bytes = serde_json::to_vec(&ws_event)?;
-        ctx.nats_conn().publish(subject, msg_bytes).await?;
+        ctx.nats_conn()
+            .publish_event(&ws_event.subject()?, msg_bytes)
+            .await?;
         Ok(())
     }
 }
@@ -0,0 +1,76 @@
+//! This module contains [`UsageAggregationScheduler`], which is a "long-running" task that has
+//! pinga aggregate the [`Usage`](crate::Usage) of every workspace every hour.
+
+use std::time::Duration;
+
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{job::definition::UsageAggregationJob, ServicesContext, TransactionsError};
+
+/// How often usage is aggregated, which is also the size of the buckets it is aggregated into.
+const USAGE_AGGREGATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum UsageAggregationSchedulerError {
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type UsageAggregationSchedulerResult<T> = Result<T, UsageAggregationSchedulerError>;
+
+/// Enqueues a [`UsageAggregationJob`] every hour. Aggregating is safe to run concurrently, so
+/// every sdf instance enqueues its own.
+#[derive(Debug, Clone)]
+pub struct UsageAggregationScheduler {
+    services_context: ServicesContext,
+}
+
+impl UsageAggregationScheduler {
+    pub fn new(services_context: ServicesContext) -> Self {
+        Self { services_context }
+    }
+
+    /// Starts the scheduler, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Usage Aggregation Scheduler received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Usage Aggregation Scheduler stopped");
+        });
+    }
+
+    #[instrument(name = "usage_aggregation_scheduler.run", skip_all, level = "debug")]
+    async fn run(&self) -> UsageAggregationSchedulerResult<()> {
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+        ctx.enqueue_job(UsageAggregationJob::new(ctx.access_builder()))
+            .await?;
+        ctx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(
+        name = "usage_aggregation_scheduler.start_task",
+        skip_all,
+        level = "debug"
+    )]
+    async fn start_task(&self) {
+        let mut interval = time::interval(USAGE_AGGREGATION_INTERVAL);
+        // The first tick completes immediately, while there is little to aggregate
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+}
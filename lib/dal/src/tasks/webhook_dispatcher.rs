@@ -0,0 +1,173 @@
+//! This module contains [`WebhookDispatcher`], which is a "long-running" task that listens to the
+//! [`WsEvents`](WsEvent) of every workspace over [NATS](https://nats.io), creates a
+//! [`WebhookDelivery`] of each of them for the [`Webhooks`](crate::Webhook) which want it, and
+//! enqueues the deliveries whose retry is due.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::StreamExt;
+use nats_subscriber::{Request, SubscriberError, Subscription};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{
+    DalContextBuilder, ServicesContext, Tenancy, TransactionsError, Webhook, WebhookDelivery,
+    WebhookError, WorkspacePk, WsEvent, WsEventError,
+};
+
+/// The queue name for [NATS](https://nats.io), so that each event is dispatched by a single
+/// [`WebhookDispatcher`] when several services run one.
+const WEBHOOK_DISPATCHER_QUEUE_NAME: &str = "webhook_dispatcher";
+/// How often the dispatcher looks for deliveries whose retry is due.
+const WEBHOOK_RETRY_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WebhookDispatcherError {
+    #[error(transparent)]
+    Subscriber(#[from] SubscriberError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type WebhookDispatcherResult<T> = Result<T, WebhookDispatcherError>;
+
+/// Sends the events of every workspace to its [`Webhooks`](crate::Webhook).
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    services_context: ServicesContext,
+    events: Subscription<WsEvent>,
+}
+
+impl WebhookDispatcher {
+    pub async fn new(services_context: ServicesContext) -> WebhookDispatcherResult<Self> {
+        // The events of every workspace and change set
+        let subject = WsEvent::subscription_subject(None, None)?;
+        let events = Subscription::create(subject.to_string())
+            .queue_name(WEBHOOK_DISPATCHER_QUEUE_NAME)
+            .start(services_context.nats_conn())
+            .await?;
+        Ok(Self {
+            services_context,
+            events,
+        })
+    }
+
+    /// Starts the dispatcher, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        info!("starting webhook dispatcher");
+        tokio::spawn(Self::start_task(
+            self.services_context,
+            self.events,
+            shutdown_broadcast_rx,
+        ));
+    }
+
+    #[instrument(name = "webhook_dispatcher.start_task", skip_all, level = "debug")]
+    async fn start_task(
+        services_context: ServicesContext,
+        mut events: Subscription<WsEvent>,
+        mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let mut interval = time::interval(WEBHOOK_RETRY_POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    trace!("the webhook dispatcher task received shutdown");
+                    break;
+                }
+                _ = interval.tick() => {
+                    let builder = services_context.clone().into_builder(false);
+                    if let Err(err) = Self::retry_due(builder).await {
+                        warn!(error = ?err, "failed to retry webhook deliveries");
+                    }
+                }
+                event = events.next() => {
+                    match event {
+                        Some(Ok(event)) => {
+                            let builder = services_context.clone().into_builder(false);
+                            tokio::spawn(async move {
+                                if let Err(err) = Self::dispatch(builder, event).await {
+                                    warn!(error = ?err, "failed to dispatch event to webhooks");
+                                }
+                            });
+                        }
+                        Some(Err(err)) => {
+                            warn!(error = ?err, "next webhook dispatcher event errored");
+                        }
+                        None => {
+                            trace!("webhook dispatcher events subscriber stream has closed");
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Err(err) = events.unsubscribe().await {
+            error!("could not unsubscribe from nats: {:?}", err);
+        }
+    }
+
+    #[instrument(name = "webhook_dispatcher.dispatch", skip_all, level = "debug")]
+    async fn dispatch(
+        builder: DalContextBuilder,
+        request: Request<WsEvent>,
+    ) -> WebhookDispatcherResult<()> {
+        let event = request.payload;
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(event.workspace_pk()));
+
+        let deliveries = Webhook::dispatch(&ctx, &event).await?;
+        if !deliveries.is_empty() {
+            debug!(
+                workspace_pk = %event.workspace_pk(),
+                count = deliveries.len(),
+                "dispatched event to webhooks"
+            );
+        }
+
+        ctx.commit().await?;
+        Ok(())
+    }
+
+    /// Enqueues the deliveries whose retry is due, with the tenancy of their workspace. They are
+    /// claimed and enqueued in the same transaction, so that a failure in between leaves them
+    /// due.
+    #[instrument(name = "webhook_dispatcher.retry_due", skip_all, level = "debug")]
+    async fn retry_due(builder: DalContextBuilder) -> WebhookDispatcherResult<()> {
+        let mut ctx = builder.build_default().await?;
+        // Claiming bypasses tenancy checks, so that every workspace is retried at once
+        let due = WebhookDelivery::claim_due(&ctx, ctx.now()).await?;
+
+        let mut by_workspace: HashMap<WorkspacePk, Vec<WebhookDelivery>> = HashMap::new();
+        for delivery in due {
+            by_workspace
+                .entry(*delivery.workspace_pk())
+                .or_default()
+                .push(delivery);
+        }
+
+        for (workspace_pk, deliveries) in by_workspace {
+            // The jobs take the tenancy the context has when they are enqueued
+            ctx.update_tenancy(Tenancy::new(workspace_pk));
+            info!(
+                %workspace_pk,
+                count = deliveries.len(),
+                "retrying webhook deliveries"
+            );
+            for delivery in deliveries {
+                delivery.enqueue(&ctx).await?;
+            }
+        }
+        ctx.commit().await?;
+        Ok(())
+    }
+}
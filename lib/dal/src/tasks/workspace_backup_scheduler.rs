@@ -0,0 +1,149 @@
+//! This module contains [`WorkspaceBackupScheduler`], which is a "long-running" task that backs up
+//! every workspace periodically and prunes the backups which have outlived the
+//! [`WorkspaceBackupPolicy`].
+
+use std::time::Duration;
+
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{
+    ServicesContext, Tenancy, TransactionsError, WorkspaceBackup, WorkspaceBackupError,
+    WorkspaceBackupPolicy, WorkspaceBackupTrigger, WorkspacePk,
+};
+
+const LIST_WORKSPACES_DUE: &str =
+    include_str!("../queries/workspace_backup/list_workspaces_due.sql");
+
+/// How often the scheduler looks for workspaces due for a backup. Backups themselves are taken
+/// at the interval of the policy.
+const WORKSPACE_BACKUP_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceBackupSchedulerError {
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    WorkspaceBackup(#[from] WorkspaceBackupError),
+}
+
+pub type WorkspaceBackupSchedulerResult<T> = Result<T, WorkspaceBackupSchedulerError>;
+
+/// Backs up the workspaces whose last scheduled backup is older than the interval of the
+/// [`WorkspaceBackupPolicy`], then prunes the backups it no longer keeps.
+#[derive(Debug, Clone)]
+pub struct WorkspaceBackupScheduler {
+    services_context: ServicesContext,
+    policy: WorkspaceBackupPolicy,
+}
+
+impl WorkspaceBackupScheduler {
+    pub fn new(services_context: ServicesContext, policy: WorkspaceBackupPolicy) -> Self {
+        Self {
+            services_context,
+            policy,
+        }
+    }
+
+    /// Starts the scheduler, consuming itself. The spawned task stops when a shutdown request is
+    /// received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Workspace Backup Scheduler received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Workspace Backup Scheduler stopped");
+        });
+    }
+
+    #[instrument(name = "workspace_backup_scheduler.run", skip_all, level = "debug")]
+    async fn run(&self) -> WorkspaceBackupSchedulerResult<()> {
+        for workspace_pk in self.workspaces_due().await? {
+            // A workspace failing to be backed up must not hold back the others
+            if let Err(err) = self.back_up(workspace_pk).await {
+                error!(%workspace_pk, "failed to back up workspace: {err}");
+            }
+        }
+
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+        let pruned = WorkspaceBackup::prune(&ctx, self.policy).await?;
+        ctx.commit().await?;
+
+        info!(
+            %pruned,
+            max_age_days = self.policy.max_age_days,
+            keep_last = self.policy.keep_last,
+            "pruned workspace backups"
+        );
+        Ok(())
+    }
+
+    #[instrument(
+        name = "workspace_backup_scheduler.back_up",
+        skip(self),
+        level = "debug"
+    )]
+    async fn back_up(&self, workspace_pk: WorkspacePk) -> WorkspaceBackupSchedulerResult<()> {
+        let builder = self.services_context.clone().into_builder(false);
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(workspace_pk));
+
+        WorkspaceBackup::begin_consistent_read(&ctx).await?;
+        let backup = WorkspaceBackup::capture(&ctx, WorkspaceBackupTrigger::Scheduled).await?;
+        ctx.commit().await?;
+
+        info!(
+            %workspace_pk,
+            workspace_backup_pk = %backup.pk(),
+            module_size = backup.module_size(),
+            component_count = backup.spec().components.len(),
+            "backed up workspace"
+        );
+        Ok(())
+    }
+
+    #[instrument(
+        name = "workspace_backup_scheduler.start_task",
+        skip_all,
+        level = "debug"
+    )]
+    async fn start_task(&self) {
+        let mut interval = time::interval(WORKSPACE_BACKUP_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+
+    /// Gets the workspaces without a scheduled backup newer than the interval of the policy.
+    #[instrument(skip_all, level = "debug")]
+    pub async fn workspaces_due(&self) -> WorkspaceBackupSchedulerResult<Vec<WorkspacePk>> {
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_WORKSPACES_DUE, &[&self.policy.due_before(ctx.now())])
+            .await?;
+        let workspace_pks = rows
+            .into_iter()
+            .map(|row| row.try_get("workspace_pk"))
+            .collect::<Result<_, _>>()?;
+
+        ctx.commit().await?;
+        Ok(workspace_pks)
+    }
+}
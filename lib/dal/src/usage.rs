@@ -0,0 +1,210 @@
+//! This module contains [`Usage`], the metering of what each [`Workspace`](crate::Workspace)
+//! uses: the seconds its functions spend executing in veritech, the components created in it and
+//! the bytes of code and artifacts generated for it.
+//!
+//! Usage is recorded as it happens, within the transactions of the work being metered, and summed
+//! up into hourly totals by the
+//! [`UsageAggregationJob`](crate::job::definition::UsageAggregationJob), which the
+//! [`UsageAggregationScheduler`](crate::tasks::UsageAggregationScheduler) enqueues every hour.
+//! Reports read both, so that usage shows up before it is aggregated.
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DalContext, TransactionsError, WorkspacePk};
+
+const REPORT: &str = include_str!("queries/usage/report.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum UsageError {
+    #[error("invalid usage report range: {0} is not before {1}")]
+    InvalidRange(DateTime<Utc>, DateTime<Utc>),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("unknown usage metric: {0}")]
+    UnknownMetric(String),
+}
+
+pub type UsageResult<T> = Result<T, UsageError>;
+
+/// What is metered.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize,
+)]
+pub enum UsageMetric {
+    /// Bytes of code and artifact contents generated by code generation functions.
+    CodeArtifactBytes,
+    ComponentsCreated,
+    /// Seconds spent executing functions in veritech, whether they succeeded or not.
+    FuncExecutionSeconds,
+}
+
+/// How long each bucket of a [`UsageReport`] is, in UTC. Its name is the unit of `date_trunc`
+/// truncating times to the bucket.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum UsageBucket {
+    Day,
+    #[default]
+    Hour,
+}
+
+/// The usage of a [`Workspace`](crate::Workspace) over a range of time, by bucket. Buckets
+/// without any usage are left out.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub bucket: UsageBucket,
+    pub buckets: Vec<UsageReportBucket>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReportBucket {
+    pub start: DateTime<Utc>,
+    pub code_artifact_bytes: i64,
+    pub components_created: i64,
+    pub func_execution_seconds: f64,
+}
+
+/// Records and reports the usage of the workspace of the current tenancy.
+pub struct Usage;
+
+impl Usage {
+    /// Records that `quantity` of `metric` was used by the workspace of the current tenancy,
+    /// unless there is none, as happens when builtins are being set up.
+    #[instrument(skip(ctx), level = "debug")]
+    pub async fn record(ctx: &DalContext, metric: UsageMetric, quantity: f64) -> UsageResult<()> {
+        let Some(workspace_pk) = ctx.tenancy().workspace_pk() else {
+            return Ok(());
+        };
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "INSERT INTO usage_events (workspace_pk, metric, quantity) VALUES ($1, $2, $3)",
+                &[&workspace_pk, &metric.as_ref(), &quantity],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Moves the usage recorded before the given time, in every workspace, into the hourly
+    /// totals, and returns how many events were aggregated.
+    #[instrument(skip(ctx))]
+    pub async fn aggregate(ctx: &DalContext, recorded_before: DateTime<Utc>) -> UsageResult<i64> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT aggregated FROM usage_aggregate_v1($1)",
+                &[&recorded_before],
+            )
+            .await?;
+        Ok(row.try_get("aggregated")?)
+    }
+
+    /// Reports the usage of the workspace of the current tenancy from `from` until `to`, widened
+    /// to whole hours. The buckets at either end only cover the part of them within the range.
+    #[instrument(skip(ctx))]
+    pub async fn report(
+        ctx: &DalContext,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket: UsageBucket,
+    ) -> UsageResult<UsageReport> {
+        let workspace_pk = workspace_pk(ctx)?;
+        if from >= to {
+            return Err(UsageError::InvalidRange(from, to));
+        }
+        let (from, to) = (hour_floor(from), hour_ceil(to));
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(REPORT, &[&workspace_pk, &from, &to, &bucket.as_ref()])
+            .await?;
+
+        let mut buckets: Vec<UsageReportBucket> = Vec::new();
+        for row in rows {
+            let start: DateTime<Utc> = row.try_get("bucket")?;
+            let metric: String = row.try_get("metric")?;
+            let quantity: f64 = row.try_get("quantity")?;
+
+            if buckets.last().map(|bucket| bucket.start) != Some(start) {
+                buckets.push(UsageReportBucket {
+                    start,
+                    ..Default::default()
+                });
+            }
+            if let Some(bucket) = buckets.last_mut() {
+                match metric
+                    .parse::<UsageMetric>()
+                    .map_err(|_| UsageError::UnknownMetric(metric))?
+                {
+                    UsageMetric::CodeArtifactBytes => {
+                        bucket.code_artifact_bytes = quantity.round() as i64
+                    }
+                    UsageMetric::ComponentsCreated => {
+                        bucket.components_created = quantity.round() as i64
+                    }
+                    UsageMetric::FuncExecutionSeconds => bucket.func_execution_seconds = quantity,
+                }
+            }
+        }
+
+        Ok(UsageReport {
+            from,
+            to,
+            bucket,
+            buckets,
+        })
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> UsageResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(UsageError::NoWorkspaceInTenancy)
+}
+
+fn hour_floor(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.duration_trunc(Duration::hours(1)).unwrap_or(time)
+}
+
+fn hour_ceil(time: DateTime<Utc>) -> DateTime<Utc> {
+    let floor = hour_floor(time);
+    if floor == time {
+        time
+    } else {
+        floor + Duration::hours(1)
+    }
+}
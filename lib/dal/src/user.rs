@@ -1,3 +1,4 @@
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
@@ -6,11 +7,13 @@ use thiserror::Error;
 use tokio::task::JoinError;
 
 use crate::{
-    jwt_key::JwtKeyError, pk, standard_model_accessor_ro, DalContext, HistoryEvent,
-    HistoryEventError, JwtPublicSigningKey, Tenancy, Timestamp, TransactionsError, WorkspacePk,
+    jwt_key::JwtKeyError, pk, standard_model_accessor_ro, ApiToken, ApiTokenPk, DalContext,
+    HistoryActor, HistoryEvent, HistoryEventError, JwtPublicSigningKey, Tenancy, Timestamp,
+    TransactionsError, WorkspacePk,
 };
 
 const USER_GET_BY_PK: &str = include_str!("queries/user/get_by_pk.sql");
+const USER_LIST_FOR_WORKSPACE: &str = include_str!("queries/user/list_for_workspace.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -117,6 +120,52 @@ impl User {
         }
     }
 
+    /// Lists the users who belong to the workspace.
+    pub async fn list_for_workspace(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(USER_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        let mut users = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            users.push(serde_json::from_value(json)?);
+        }
+        Ok(users)
+    }
+
+    /// Returns whether the user holds the admin permission, which grants access to operational
+    /// tasks across every workspace.
+    pub async fn is_admin(ctx: &DalContext, user_pk: UserPk) -> UserResult<bool> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT user_pk FROM admin_users WHERE user_pk = $1",
+                &[&user_pk],
+            )
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// Grants the admin permission to the user, or takes it away.
+    #[instrument(skip(ctx))]
+    pub async fn set_admin(ctx: &DalContext, user_pk: UserPk, admin: bool) -> UserResult<()> {
+        let query = if admin {
+            "INSERT INTO admin_users (user_pk) VALUES ($1) ON CONFLICT (user_pk) DO NOTHING"
+        } else {
+            "DELETE FROM admin_users WHERE user_pk = $1"
+        };
+        ctx.txns().await?.pg().execute(query, &[&user_pk]).await?;
+        Ok(())
+    }
+
     pub async fn authorize(_ctx: &DalContext, _user_pk: &UserPk) -> UserResult<bool> {
         // TODO(paulo,theo): implement capabilities through auth0
         Ok(true)
@@ -143,6 +192,10 @@ impl User {
 pub struct UserClaim {
     pub user_pk: UserPk,
     pub workspace_pk: WorkspacePk,
+    /// The [`ApiToken`] the request was authenticated with, if it was not authenticated with a
+    /// session token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_token_pk: Option<ApiTokenPk>,
 }
 
 impl UserClaim {
@@ -150,6 +203,28 @@ impl UserClaim {
         UserClaim {
             user_pk,
             workspace_pk,
+            api_token_pk: None,
+        }
+    }
+
+    /// Creates the claim of a request authenticated with an [`ApiToken`], acting on behalf of the
+    /// user who owns the token.
+    pub fn for_api_token(api_token: &ApiToken) -> Self {
+        UserClaim {
+            user_pk: *api_token.user_pk(),
+            workspace_pk: *api_token.workspace_pk(),
+            api_token_pk: Some(api_token.pk()),
+        }
+    }
+
+    /// Returns the [`HistoryActor`] the changes made for this claim are attributed to.
+    pub fn history_actor(&self) -> HistoryActor {
+        match self.api_token_pk {
+            Some(pk) => HistoryActor::ApiToken {
+                pk,
+                user_pk: self.user_pk,
+            },
+            None => HistoryActor::User(self.user_pk),
         }
     }
 
@@ -160,4 +235,17 @@ impl UserClaim {
         let claims = crate::jwt_key::validate_bearer_token(public_key, &token).await?;
         Ok(claims.custom)
     }
+
+    /// Validates a bearer token like [`from_bearer_token`](Self::from_bearer_token), also
+    /// returning the time at which the token was issued, if the token declares one.
+    pub async fn from_bearer_token_with_issued_at(
+        public_key: JwtPublicSigningKey,
+        token: impl AsRef<str>,
+    ) -> UserResult<(UserClaim, Option<DateTime<Utc>>)> {
+        let claims = crate::jwt_key::validate_bearer_token(public_key, &token).await?;
+        let issued_at = claims
+            .issued_at
+            .and_then(|issued_at| Utc.timestamp_opt(issued_at.as_secs() as i64, 0).single());
+        Ok((claims.custom, issued_at))
+    }
 }
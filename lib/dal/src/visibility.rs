@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::ChangeSetPk;
 use serde_aux::field_attributes::deserialize_number_from_string;
@@ -19,8 +20,13 @@ pub enum VisibilityError {
 
 pub type VisibilityResult<T> = Result<T, VisibilityError>;
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+/// The change set a request reads from or writes to, and whether it looks at deleted objects.
+/// Most sdf requests flatten it into their query string or body.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct Visibility {
+    #[schema(value_type = String)]
+    #[param(value_type = String)]
     #[serde(
         rename = "visibility_change_set_pk",
         deserialize_with = "deserialize_number_from_string"
@@ -0,0 +1,444 @@
+//! This module contains [`Webhook`], an endpoint outside of SI which is sent the
+//! [`WsEvents`](WsEvent) of a workspace so that teams can drive their own automation, and
+//! [`WebhookDelivery`], the record of sending it one event.
+//!
+//! Every delivery is a `POST` of the event as JSON. Its body is signed with HMAC-SHA256, keyed
+//! with the secret of the webhook, and the hex encoded signature is sent in the
+//! [`WEBHOOK_SIGNATURE_HEADER`] (as `sha256=<signature>`), so that receivers can check that the
+//! delivery comes from SI.
+//!
+//! Webhooks are only sent deliveries at public addresses, so that they cannot be used to reach
+//! the network SI runs in.
+
+use std::net::{IpAddr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use sodiumoxide::crypto::auth::hmacsha256;
+use strum::VariantNames;
+use telemetry::prelude::*;
+use thiserror::Error;
+use url::{Host, Url};
+
+use crate::{
+    pk, standard_model, standard_model_accessor_ro, AuditAction, AuditLog, AuditLogError,
+    AuditTarget, DalContext, StandardModelError, Timestamp, TransactionsError, WorkspacePk,
+    WsEvent, WsPayload,
+};
+
+pub mod delivery;
+
+pub use delivery::{WebhookDelivery, WebhookDeliveryPk, WebhookDeliveryStatus};
+
+const WEBHOOK_DELETE: &str = include_str!("queries/webhook/delete.sql");
+const WEBHOOK_GET_BY_PK: &str = include_str!("queries/webhook/get_by_pk.sql");
+const WEBHOOK_LIST_FOR_WORKSPACE: &str = include_str!("queries/webhook/list_for_workspace.sql");
+
+/// Every webhook secret starts with this prefix, which makes them easy to spot.
+pub const WEBHOOK_SECRET_PREFIX: &str = "si_whsec_";
+
+/// The header carrying the signature of a delivery.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-SI-Signature-256";
+/// The header carrying the kind of the event delivered, such as `ChangeSetApplied`.
+pub const WEBHOOK_EVENT_HEADER: &str = "X-SI-Event";
+/// The header carrying the [`WebhookDeliveryPk`], which stays the same across retries.
+pub const WEBHOOK_DELIVERY_HEADER: &str = "X-SI-Delivery";
+
+/// The number of random bytes that make up a webhook secret.
+const WEBHOOK_SECRET_BYTES: usize = 32;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("webhook delivery not found: {0}")]
+    DeliveryNotFound(WebhookDeliveryPk),
+    #[error("unknown event kind: {0}")]
+    InvalidEventKind(String),
+    #[error("invalid webhook url {0}: {1}")]
+    InvalidUrl(String, String),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("webhook not found: {0}")]
+    NotFound(WebhookPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("cannot resolve the host of webhook url {0}: {1}")]
+    UnresolvableHost(String, String),
+}
+
+pub type WebhookResult<T> = Result<T, WebhookError>;
+
+pk!(WebhookPk);
+
+/// An endpoint which is sent the events of a [`Workspace`](crate::Workspace).
+///
+/// The secret deliveries are signed with is never serialized: it is returned once, from
+/// [`Webhook::new`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pk: WebhookPk,
+    workspace_pk: WorkspacePk,
+    url: String,
+    #[serde(skip_serializing)]
+    secret: String,
+    /// The kinds of events sent to the webhook, or every kind when empty.
+    event_kinds: Vec<String>,
+    enabled: bool,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl Webhook {
+    pub fn pk(&self) -> WebhookPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(url, String);
+    standard_model_accessor_ro!(event_kinds, Vec<String>);
+    standard_model_accessor_ro!(enabled, bool);
+
+    /// Creates a new [`Webhook`] in the workspace of the current tenancy, returning it alongside
+    /// its secret. The secret cannot be recovered later.
+    #[instrument(skip(ctx))]
+    pub async fn new(
+        ctx: &DalContext,
+        url: impl AsRef<str> + std::fmt::Debug,
+        event_kinds: Vec<String>,
+    ) -> WebhookResult<(Self, String)> {
+        let url = url.as_ref();
+        validate_url(url).await?;
+        validate_event_kinds(&event_kinds)?;
+        let workspace_pk = workspace_pk(ctx)?;
+
+        let secret = format!(
+            "{WEBHOOK_SECRET_PREFIX}{}",
+            hex::encode(sodiumoxide::randombytes::randombytes(WEBHOOK_SECRET_BYTES))
+        );
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM webhook_create_v1($1, $2, $3, $4)",
+                &[
+                    &workspace_pk,
+                    &url,
+                    &secret,
+                    &serde_json::to_value(&event_kinds)?,
+                ],
+            )
+            .await?;
+        let object: Self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::WebhookCreate,
+            Some(object.audit_target()),
+            None,
+            Some(object.audit_state()),
+        )
+        .await?;
+
+        Ok((object, secret))
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: WebhookPk) -> WebhookResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(WEBHOOK_GET_BY_PK, &[&pk, &workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists all [`Webhooks`](Webhook) (including disabled ones) in the workspace of the current
+    /// tenancy.
+    pub async fn list(ctx: &DalContext) -> WebhookResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WEBHOOK_LIST_FOR_WORKSPACE, &[&workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Changes where the webhook is sent events, which of them, and whether it is sent any.
+    #[instrument(skip(self, ctx))]
+    pub async fn update(
+        &mut self,
+        ctx: &DalContext,
+        url: impl AsRef<str> + std::fmt::Debug,
+        event_kinds: Vec<String>,
+        enabled: bool,
+    ) -> WebhookResult<()> {
+        let url = url.as_ref();
+        validate_url(url).await?;
+        validate_event_kinds(&event_kinds)?;
+
+        let before = self.audit_state();
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM webhook_update_v1($1, $2, $3, $4)",
+                &[
+                    &self.pk,
+                    &url,
+                    &serde_json::to_value(&event_kinds)?,
+                    &enabled,
+                ],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::WebhookUpdate,
+            Some(self.audit_target()),
+            Some(before),
+            Some(self.audit_state()),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the webhook, along with the history of its deliveries.
+    #[instrument(skip_all)]
+    pub async fn delete(self, ctx: &DalContext) -> WebhookResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(WEBHOOK_DELETE, &[&self.pk, &self.workspace_pk])
+            .await?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::WebhookDelete,
+            Some(self.audit_target()),
+            Some(self.audit_state()),
+            None,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the webhook is sent events of the kind.
+    pub fn wants(&self, event_kind: &str) -> bool {
+        self.enabled
+            && (self.event_kinds.is_empty() || self.event_kinds.iter().any(|k| k == event_kind))
+    }
+
+    /// Creates a [`WebhookDelivery`] of the event for every webhook of the workspace which wants
+    /// it, and enqueues their first attempt. The [`DalContext`] must be in the workspace of the
+    /// event.
+    #[instrument(skip_all)]
+    pub async fn dispatch(
+        ctx: &DalContext,
+        event: &WsEvent,
+    ) -> WebhookResult<Vec<WebhookDelivery>> {
+        let event_kind = event.payload().as_ref();
+        let mut deliveries = Vec::new();
+        for webhook in Self::list(ctx).await? {
+            if !webhook.wants(event_kind) {
+                continue;
+            }
+            let delivery =
+                WebhookDelivery::new(ctx, webhook.pk, event_kind, serde_json::to_value(event)?)
+                    .await?;
+            delivery.enqueue(ctx).await?;
+            deliveries.push(delivery);
+        }
+        Ok(deliveries)
+    }
+
+    /// Returns the signature of a delivery body, as sent in the [`WEBHOOK_SIGNATURE_HEADER`].
+    pub fn sign(&self, body: &[u8]) -> String {
+        sign(&self.secret, body)
+    }
+
+    fn audit_target(&self) -> AuditTarget {
+        AuditTarget::new("webhook", self.pk, Some(self.url.clone()))
+    }
+
+    fn audit_state(&self) -> serde_json::Value {
+        serde_json::json![{
+            "url": self.url,
+            "eventKinds": self.event_kinds,
+            "enabled": self.enabled,
+        }]
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> WebhookResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(WebhookError::NoWorkspaceInTenancy)
+}
+
+async fn validate_url(url: &str) -> WebhookResult<()> {
+    let parsed =
+        Url::parse(url).map_err(|err| WebhookError::InvalidUrl(url.to_owned(), err.to_string()))?;
+    match parsed.scheme() {
+        "http" | "https" => {}
+        scheme => {
+            return Err(WebhookError::InvalidUrl(
+                url.to_owned(),
+                format!("unsupported scheme {scheme}"),
+            ))
+        }
+    }
+    match public_addrs(&parsed).await {
+        // The host may not be resolvable yet, and every delivery checks it again anyway
+        Ok(_) | Err(WebhookError::UnresolvableHost(..)) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Resolves the host of the url, and returns its addresses unless one of them is not public. The
+/// deliveries are sent to these addresses, rather than to whatever resolving the host again
+/// would return.
+async fn public_addrs(url: &Url) -> WebhookResult<Vec<SocketAddr>> {
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| WebhookError::InvalidUrl(url.to_string(), "no port".to_owned()))?;
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|err| WebhookError::UnresolvableHost(url.to_string(), err.to_string()))?
+            .collect(),
+        None => {
+            return Err(WebhookError::InvalidUrl(
+                url.to_string(),
+                "no host".to_owned(),
+            ))
+        }
+    };
+    if addrs.is_empty() {
+        return Err(WebhookError::UnresolvableHost(
+            url.to_string(),
+            "no addresses".to_owned(),
+        ));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(WebhookError::InvalidUrl(
+            url.to_string(),
+            format!("{} is not a public address", addr.ip()),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// Returns whether the address is reachable from the internet at large, as opposed to a loopback,
+/// link-local, private or unspecified one.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_private()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(ip.into()),
+            None => {
+                let first_segment = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    // fe80::/10, link-local
+                    || first_segment & 0xffc0 == 0xfe80
+                    // fc00::/7, unique local
+                    || first_segment & 0xfe00 == 0xfc00)
+            }
+        },
+    }
+}
+
+fn validate_event_kinds(event_kinds: &[String]) -> WebhookResult<()> {
+    match event_kinds
+        .iter()
+        .find(|kind| !WsPayload::VARIANTS.contains(&kind.as_str()))
+    {
+        Some(kind) => Err(WebhookError::InvalidEventKind(kind.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Signs the body with HMAC-SHA256, keyed with the secret.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut state = hmacsha256::State::init(secret.as_bytes());
+    state.update(body);
+    format!("sha256={}", hex::encode(state.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_hmac_sha256() {
+        // Test case 2 of RFC 4231
+        assert_eq!(
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            sign("Jefe", b"what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        for ip in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public(ip.parse().expect("cannot parse ip")), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().expect("cannot parse ip")), "{ip}");
+        }
+    }
+
+    #[test]
+    fn validate() {
+        assert!(validate_url("https://example.com/hooks/si").is_ok());
+        assert!(matches!(
+            validate_url("ftp://example.com"),
+            Err(WebhookError::InvalidUrl(..))
+        ));
+        assert!(matches!(
+            validate_url("not a url"),
+            Err(WebhookError::InvalidUrl(..))
+        ));
+
+        assert!(validate_event_kinds(&["ChangeSetApplied".to_owned()]).is_ok());
+        assert!(matches!(
+            validate_event_kinds(&["ChangeSetExploded".to_owned()]),
+            Err(WebhookError::InvalidEventKind(kind)) if kind == "ChangeSetExploded"
+        ));
+    }
+}
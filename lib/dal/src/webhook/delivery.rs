@@ -0,0 +1,298 @@
+//! This module contains [`WebhookDelivery`], the record of sending one event to a
+//! [`Webhook`], which is kept as the delivery history of the webhook.
+//!
+//! Each attempt is made by a [`WebhookDeliveryJob`]. Failed attempts are retried with an
+//! exponential backoff, by the [`WebhookDispatcher`](crate::tasks::WebhookDispatcher) enqueuing
+//! the deliveries due again, until [`WEBHOOK_DELIVERY_MAX_ATTEMPTS`] is reached.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use url::Url;
+
+use super::{
+    public_addrs, workspace_pk, Webhook, WebhookError, WebhookPk, WebhookResult,
+    WEBHOOK_DELIVERY_HEADER, WEBHOOK_EVENT_HEADER, WEBHOOK_SIGNATURE_HEADER,
+};
+use crate::job::definition::WebhookDeliveryJob;
+use crate::{pk, standard_model, standard_model_accessor_ro, DalContext, Timestamp, WorkspacePk};
+
+const WEBHOOK_DELIVERY_CLAIM_DUE: &str = include_str!("../queries/webhook_delivery/claim_due.sql");
+const WEBHOOK_DELIVERY_GET_BY_PK: &str = include_str!("../queries/webhook_delivery/get_by_pk.sql");
+const WEBHOOK_DELIVERY_LIST_FOR_WEBHOOK: &str =
+    include_str!("../queries/webhook_delivery/list_for_webhook.sql");
+
+/// How many times a delivery is attempted before it is given up on.
+pub const WEBHOOK_DELIVERY_MAX_ATTEMPTS: i32 = 6;
+
+/// How many seconds the first retry of a delivery waits, doubled for every further retry.
+const WEBHOOK_DELIVERY_INITIAL_BACKOFF_SECONDS: i64 = 30;
+
+/// How long receivers are given to answer a delivery.
+const WEBHOOK_DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of deliveries listed when no limit is given.
+const DEFAULT_DELIVERY_LIST_LIMIT: u32 = 50;
+
+pk!(WebhookDeliveryPk);
+
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Every attempt failed, or the webhook was disabled.
+    Failed,
+    /// The delivery has yet to be attempted, or will be retried.
+    Pending,
+    /// The webhook answered with a successful status.
+    Succeeded,
+}
+
+/// One event sent, or to be sent, to a [`Webhook`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    pk: WebhookDeliveryPk,
+    webhook_pk: WebhookPk,
+    workspace_pk: WorkspacePk,
+    event_kind: String,
+    payload: serde_json::Value,
+    status: WebhookDeliveryStatus,
+    attempts: i32,
+    response_status: Option<i32>,
+    last_error: Option<String>,
+    last_attempted_at: Option<DateTime<Utc>>,
+    next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl WebhookDelivery {
+    pub fn pk(&self) -> WebhookDeliveryPk {
+        self.pk
+    }
+
+    standard_model_accessor_ro!(webhook_pk, WebhookPk);
+    standard_model_accessor_ro!(workspace_pk, WorkspacePk);
+    standard_model_accessor_ro!(event_kind, String);
+    standard_model_accessor_ro!(payload, serde_json::Value);
+    standard_model_accessor_ro!(status, WebhookDeliveryStatus);
+    standard_model_accessor_ro!(attempts, i32);
+    standard_model_accessor_ro!(response_status, Option<i32>);
+    standard_model_accessor_ro!(last_error, Option<String>);
+    standard_model_accessor_ro!(last_attempted_at, Option<DateTime<Utc>>);
+    standard_model_accessor_ro!(next_attempt_at, Option<DateTime<Utc>>);
+
+    /// Creates a pending delivery of the payload to the webhook. It is not attempted until it is
+    /// [enqueued](Self::enqueue).
+    pub async fn new(
+        ctx: &DalContext,
+        webhook_pk: WebhookPk,
+        event_kind: impl AsRef<str>,
+        payload: serde_json::Value,
+    ) -> WebhookResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM webhook_delivery_create_v1($1, $2, $3, $4)",
+                &[
+                    &webhook_pk,
+                    &workspace_pk(ctx)?,
+                    &event_kind.as_ref(),
+                    &payload,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: WebhookDeliveryPk) -> WebhookResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(WEBHOOK_DELIVERY_GET_BY_PK, &[&pk, &workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the most recent deliveries to the webhook, newest first.
+    pub async fn list_for_webhook(
+        ctx: &DalContext,
+        webhook_pk: WebhookPk,
+        limit: Option<u32>,
+    ) -> WebhookResult<Vec<Self>> {
+        let limit = i64::from(limit.unwrap_or(DEFAULT_DELIVERY_LIST_LIMIT));
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                WEBHOOK_DELIVERY_LIST_FOR_WEBHOOK,
+                &[&webhook_pk, &workspace_pk(ctx)?, &limit],
+            )
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Sends the payload of a past delivery to its webhook again, as a new delivery, so that the
+    /// history of the original one is kept.
+    #[instrument(skip(ctx))]
+    pub async fn redeliver(ctx: &DalContext, pk: WebhookDeliveryPk) -> WebhookResult<Self> {
+        let original = Self::get_by_pk(ctx, pk)
+            .await?
+            .ok_or(WebhookError::DeliveryNotFound(pk))?;
+        let delivery = Self::new(
+            ctx,
+            original.webhook_pk,
+            &original.event_kind,
+            original.payload,
+        )
+        .await?;
+        delivery.enqueue(ctx).await?;
+        Ok(delivery)
+    }
+
+    /// Enqueues a [`WebhookDeliveryJob`] to attempt the delivery, once the [`DalContext`] is
+    /// committed.
+    pub async fn enqueue(&self, ctx: &DalContext) -> WebhookResult<()> {
+        ctx.enqueue_job(WebhookDeliveryJob::new(ctx.access_builder(), self.pk))
+            .await?;
+        Ok(())
+    }
+
+    /// Sends the payload to the webhook, and records how it went. A failed attempt is scheduled
+    /// to be retried unless it was the last one.
+    #[instrument(skip_all, fields(webhook_delivery.pk = %self.pk))]
+    pub async fn attempt(&mut self, ctx: &DalContext) -> WebhookResult<()> {
+        if self.status != WebhookDeliveryStatus::Pending {
+            return Ok(());
+        }
+        let webhook = Webhook::get_by_pk(ctx, self.webhook_pk)
+            .await?
+            .ok_or(WebhookError::NotFound(self.webhook_pk))?;
+
+        let (response_status, last_error) = if *webhook.enabled() {
+            self.send(&webhook).await?
+        } else {
+            (None, Some("webhook is disabled".to_owned()))
+        };
+        let (status, next_attempt_at) = match &last_error {
+            None => (WebhookDeliveryStatus::Succeeded, None),
+            Some(_) if !*webhook.enabled() => (WebhookDeliveryStatus::Failed, None),
+            Some(_) => match retry_delay(self.attempts + 1) {
+                Some(delay) => (WebhookDeliveryStatus::Pending, Some(ctx.now() + delay)),
+                None => (WebhookDeliveryStatus::Failed, None),
+            },
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM webhook_delivery_record_attempt_v1($1, $2, $3, $4, $5)",
+                &[
+                    &self.pk,
+                    &status.as_ref(),
+                    &response_status,
+                    &last_error,
+                    &next_attempt_at,
+                ],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        Ok(())
+    }
+
+    /// Takes the pending deliveries, of every workspace, whose retry is due as of `now`. They are
+    /// taken at most once, so the caller must [enqueue](Self::enqueue) them before committing.
+    pub async fn claim_due(ctx: &DalContext, now: DateTime<Utc>) -> WebhookResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WEBHOOK_DELIVERY_CLAIM_DUE, &[&now])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Sends the payload, returning the status the webhook answered with, if any, and the reason
+    /// the attempt failed, if it did.
+    async fn send(&self, webhook: &Webhook) -> WebhookResult<(Option<i32>, Option<String>)> {
+        let body = serde_json::to_vec(&self.payload)?;
+        let client = match client_for(webhook.url()).await {
+            Ok(client) => client,
+            Err(reason) => return Ok((None, Some(reason))),
+        };
+        let result = client
+            .post(webhook.url())
+            .timeout(WEBHOOK_DELIVERY_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_SIGNATURE_HEADER, webhook.sign(&body))
+            .header(WEBHOOK_EVENT_HEADER, &self.event_kind)
+            .header(WEBHOOK_DELIVERY_HEADER, self.pk.to_string())
+            .body(body)
+            .send()
+            .await;
+
+        Ok(match result {
+            Ok(response) if response.status().is_success() => {
+                (Some(i32::from(response.status().as_u16())), None)
+            }
+            Ok(response) => (
+                Some(i32::from(response.status().as_u16())),
+                Some(format!("webhook answered with {}", response.status())),
+            ),
+            Err(err) => {
+                debug!(error = ?err, "webhook delivery failed");
+                (None, Some(err.to_string()))
+            }
+        })
+    }
+}
+
+/// Builds a client which only connects to the addresses the host of the url resolved to, once
+/// they are checked to be public, and which does not follow redirects, since they could lead
+/// anywhere. Returns the reason the delivery cannot be sent otherwise.
+async fn client_for(url: &str) -> Result<reqwest::Client, String> {
+    let parsed = Url::parse(url).map_err(|err| err.to_string())?;
+    let addrs = public_addrs(&parsed).await.map_err(|err| err.to_string())?;
+    let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+    if let Some(domain) = parsed.domain() {
+        builder = builder.resolve_to_addrs(domain, &addrs);
+    }
+    builder.build().map_err(|err| err.to_string())
+}
+
+/// Returns how long to wait before attempting a delivery again, once it failed its given number
+/// of attempts, or `None` if it should be given up on.
+fn retry_delay(attempts: i32) -> Option<chrono::Duration> {
+    if attempts >= WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = u32::try_from(attempts.max(1) - 1).unwrap_or_default();
+    Some(chrono::Duration::seconds(
+        WEBHOOK_DELIVERY_INITIAL_BACKOFF_SECONDS * 2i64.pow(exponent),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_exponentially() {
+        assert_eq!(Some(chrono::Duration::seconds(30)), retry_delay(1));
+        assert_eq!(Some(chrono::Duration::seconds(60)), retry_delay(2));
+        assert_eq!(Some(chrono::Duration::seconds(480)), retry_delay(5));
+        assert_eq!(None, retry_delay(WEBHOOK_DELIVERY_MAX_ATTEMPTS));
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
 
 const WORKSPACE_GET_BY_PK: &str = include_str!("queries/workspace/get_by_pk.sql");
 const WORKSPACE_FIND_BY_NAME: &str = include_str!("queries/workspace/find_by_name.sql");
+const WORKSPACE_LIST_ALL: &str = include_str!("queries/workspace/list_all.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -49,6 +50,9 @@ pub struct WorkspaceSignup {
 pub struct Workspace {
     pk: WorkspacePk,
     name: String,
+    /// How many reviewers must approve a [`ChangeSet`](crate::ChangeSet) before it can be
+    /// applied. Zero means no review is required.
+    required_change_set_approvals: i64,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -165,5 +169,38 @@ impl Workspace {
         }
     }
 
+    /// Lists every workspace, oldest first, regardless of the tenancy of the context.
+    pub async fn list_all(ctx: &DalContext) -> WorkspaceResult<Vec<Workspace>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WORKSPACE_LIST_ALL, &[])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Sets how many reviewers must approve a [`ChangeSet`](crate::ChangeSet) before it can be
+    /// applied, as enforced by [`ChangeSetReview::ensure_approved()`](crate::ChangeSetReview).
+    #[instrument(skip_all)]
+    pub async fn set_required_change_set_approvals(
+        &mut self,
+        ctx: &DalContext,
+        required_change_set_approvals: i64,
+    ) -> WorkspaceResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_set_required_change_set_approvals_v1($1, $2)",
+                &[&self.pk, &required_change_set_approvals],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+        Ok(())
+    }
+
     standard_model_accessor_ro!(name, String);
+    standard_model_accessor_ro!(required_change_set_approvals, i64);
 }
@@ -0,0 +1,331 @@
+//! This module contains [`WorkspaceBackup`], a model level backup of a whole [`Workspace`], taken
+//! from head, which can be restored into a new workspace for disaster recovery (or to rehearse
+//! it).
+//!
+//! A backup is made of a module of every [`SchemaVariant`] of the workspace, along with their
+//! funcs, in the format of [workspace exports](crate::pkg::export_pkg_as_bytes), kept as a
+//! [`Blob`], and of the [`Components`](Component) of head, with the edges between them and the
+//! values set on them, kept as a [`BlueprintSpec`]. Secrets are not part of backups.
+//!
+//! Backups are taken on demand, or periodically by the
+//! [`WorkspaceBackupScheduler`](crate::tasks::WorkspaceBackupScheduler), according to the
+//! [`WorkspaceBackupPolicy`].
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use si_pkg::{SiPkg, SiPkgError};
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::pkg::{export_pkg_as_bytes, import_pkg_from_pkg, PkgError};
+use crate::{
+    pk, standard_model, AuditAction, AuditLog, AuditLogError, AuditTarget, Blob, BlobError,
+    BlobHash, BlueprintError, BlueprintSpec, Component, DalContext, KeyPair, KeyPairError,
+    SchemaVariant, StandardModel, StandardModelError, TransactionsError, UserPk, Visibility,
+    Workspace, WorkspaceError, WorkspacePk,
+};
+
+const GET_BY_PK: &str = include_str!("queries/workspace_backup/get_by_pk.sql");
+const LIST_FOR_WORKSPACE: &str = include_str!("queries/workspace_backup/list_for_workspace.sql");
+
+/// The name of the module of a backup, as found in its metadata.
+const BACKUP_MODULE_NAME: &str = "Workspace Backup";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceBackupError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("blob error: {0}")]
+    Blob(#[from] BlobError),
+    #[error("blueprint error: {0}")]
+    Blueprint(#[from] BlueprintError),
+    #[error("invalid module reference for workspace backup {0}: {1}")]
+    InvalidModuleReference(WorkspaceBackupPk, String),
+    #[error("key pair error: {0}")]
+    KeyPair(#[from] KeyPairError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("workspace backup not found: {0}")]
+    NotFound(WorkspaceBackupPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pkg error: {0}")]
+    Pkg(#[from] PkgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("si pkg error: {0}")]
+    SiPkg(#[from] SiPkgError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace error: {0}")]
+    Workspace(#[from] WorkspaceError),
+}
+
+pub type WorkspaceBackupResult<T> = Result<T, WorkspaceBackupError>;
+
+pk!(WorkspaceBackupPk);
+
+/// How often workspaces are backed up, and how long their backups are kept for.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct WorkspaceBackupPolicy {
+    /// Whether workspaces are backed up periodically. Backups can be taken on demand either way.
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub max_age_days: u32,
+    /// How many of the newest backups of a workspace are kept regardless of their age.
+    pub keep_last: u32,
+}
+
+impl Default for WorkspaceBackupPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            max_age_days: 30,
+            keep_last: 7,
+        }
+    }
+}
+
+impl WorkspaceBackupPolicy {
+    /// Returns the time after which a workspace backed up on schedule is not due for another
+    /// backup, relative to `now`.
+    pub fn due_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::hours(self.interval_hours.into())
+    }
+
+    /// Returns the time before which backups are pruned, relative to `now`.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.max_age_days.into())
+    }
+}
+
+/// Why a [`WorkspaceBackup`] was taken.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+pub enum WorkspaceBackupTrigger {
+    /// Taken on demand.
+    Manual,
+    /// Taken by the [`WorkspaceBackupScheduler`](crate::tasks::WorkspaceBackupScheduler).
+    Scheduled,
+}
+
+/// A backup of the schema variants, funcs and components of a [`Workspace`] at a point in time.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct WorkspaceBackup {
+    pk: WorkspaceBackupPk,
+    created_at: DateTime<Utc>,
+    workspace_pk: WorkspacePk,
+    trigger: WorkspaceBackupTrigger,
+    actor_user_pk: Option<UserPk>,
+    module_reference: String,
+    module_size: i64,
+    spec: BlueprintSpec,
+}
+
+impl WorkspaceBackup {
+    /// Makes the transactions of the [`DalContext`] read a single snapshot of the database, so
+    /// that the module and the components of a backup agree with each other even when the
+    /// workspace changes while the backup is taken. It must be called before anything else uses
+    /// the transactions.
+    pub async fn begin_consistent_read(ctx: &DalContext) -> WorkspaceBackupResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ", &[])
+            .await?;
+        Ok(())
+    }
+
+    /// Backs up head of the workspace of the current tenancy.
+    #[instrument(skip(ctx))]
+    pub async fn capture(
+        ctx: &DalContext,
+        trigger: WorkspaceBackupTrigger,
+    ) -> WorkspaceBackupResult<Self> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let ctx = &ctx.clone_with_head();
+
+        let schema_variant_ids = SchemaVariant::list(ctx)
+            .await?
+            .iter()
+            .map(|sv| *sv.id())
+            .collect();
+        let module = export_pkg_as_bytes(
+            ctx,
+            BACKUP_MODULE_NAME,
+            ctx.now().format("%Y%m%d%H%M%S").to_string(),
+            Some(format!("Backup of workspace {workspace_pk}")),
+            ctx.history_actor().distinct_id(),
+            schema_variant_ids,
+        )
+        .await?;
+        let module_hash = Blob::store(ctx, &module).await?;
+
+        let component_ids: Vec<_> = Component::list(ctx)
+            .await?
+            .iter()
+            .map(|c| *c.id())
+            .collect();
+        let spec = if component_ids.is_empty() {
+            BlueprintSpec::default()
+        } else {
+            BlueprintSpec::capture(ctx, &component_ids, Vec::new()).await?
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_backup_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    &workspace_pk,
+                    &trigger.as_ref(),
+                    &ctx.history_actor().user_pk(),
+                    &module_hash.to_reference(),
+                    &(module.len() as i64),
+                    &serde_json::to_value(&spec)?,
+                ],
+            )
+            .await?;
+        Ok(standard_model::object_from_row(row)?)
+    }
+
+    pub async fn get_by_pk(
+        ctx: &DalContext,
+        pk: WorkspaceBackupPk,
+    ) -> WorkspaceBackupResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[&pk, &workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    /// Lists the backups of the workspace of the current tenancy, from newest to oldest.
+    pub async fn list(ctx: &DalContext) -> WorkspaceBackupResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_WORKSPACE, &[&workspace_pk(ctx)?])
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Deletes the backups of every workspace which are older than the policy allows, except for
+    /// the newest ones of each workspace, and returns the number of backups removed. Their modules
+    /// are left to [`Blob::collect_garbage`].
+    #[instrument(skip(ctx))]
+    pub async fn prune(
+        ctx: &DalContext,
+        policy: WorkspaceBackupPolicy,
+    ) -> WorkspaceBackupResult<i64> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT pruned FROM workspace_backup_prune_v1($1, $2)",
+                &[&policy.cutoff(ctx.now()), &i64::from(policy.keep_last)],
+            )
+            .await?;
+        Ok(row.try_get("pruned")?)
+    }
+
+    /// Creates a new [`Workspace`] named `name` out of the backup, and switches the tenancy of the
+    /// [`DalContext`] to it. The module of the backup is imported and its components are created
+    /// on head, keeping their layout relative to each other.
+    #[instrument(skip(self, ctx), fields(workspace_backup.pk = %self.pk))]
+    pub async fn restore_into_new_workspace(
+        &self,
+        ctx: &mut DalContext,
+        name: impl AsRef<str> + std::fmt::Debug,
+    ) -> WorkspaceBackupResult<Workspace> {
+        let module = Blob::load(ctx, self.module_hash()?).await?;
+        let pkg = SiPkg::load_from_bytes(module)?;
+
+        let workspace = Workspace::new(ctx, WorkspacePk::generate(), name).await?;
+        ctx.update_visibility(Visibility::new_head(false));
+        KeyPair::new(ctx, "default").await?;
+
+        import_pkg_from_pkg(ctx, &pkg, pkg.metadata()?.name(), None).await?;
+        if !self.spec.components.is_empty() {
+            self.spec.instantiate(ctx, HashMap::new(), 0.0, 0.0).await?;
+        }
+
+        AuditLog::record(
+            ctx,
+            AuditAction::WorkspaceRestoreBackup,
+            Some(AuditTarget::new(
+                "workspace",
+                workspace.pk(),
+                Some(workspace.name().to_owned()),
+            )),
+            Some(serde_json::json!({
+                "workspacePk": self.workspace_pk,
+                "workspaceBackupPk": self.pk,
+            })),
+            None,
+        )
+        .await?;
+
+        Ok(workspace)
+    }
+
+    pub fn pk(&self) -> WorkspaceBackupPk {
+        self.pk
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn trigger(&self) -> WorkspaceBackupTrigger {
+        self.trigger
+    }
+
+    pub fn actor_user_pk(&self) -> Option<UserPk> {
+        self.actor_user_pk
+    }
+
+    /// The hash of the [`Blob`] holding the module of the backup.
+    pub fn module_hash(&self) -> WorkspaceBackupResult<BlobHash> {
+        BlobHash::from_reference(&self.module_reference).ok_or_else(|| {
+            WorkspaceBackupError::InvalidModuleReference(self.pk, self.module_reference.clone())
+        })
+    }
+
+    /// The size of the module of the backup, in bytes.
+    pub fn module_size(&self) -> i64 {
+        self.module_size
+    }
+
+    /// The components of head when the backup was taken.
+    pub fn spec(&self) -> &BlueprintSpec {
+        &self.spec
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> WorkspaceBackupResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(WorkspaceBackupError::NoWorkspaceInTenancy)
+}
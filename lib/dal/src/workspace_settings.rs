@@ -0,0 +1,371 @@
+//! This module contains [`WorkspaceSettings`], the preferences of a workspace, such as how strictly
+//! failing qualifications gate applying a change set or how often resources are synced.
+//!
+//! Every setting has a [`WorkspaceSettingKey`], a default and a validation. Only the settings which
+//! were changed are stored, one row per key, so that settings added later apply their default to
+//! existing workspaces. Use [`DalContext::workspace_settings`] to read the settings of the
+//! workspace of a context through the [`WorkspaceSettingsCache`].
+
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    AuditAction, AuditLog, AuditLogError, AuditTarget, DalContext, TransactionsError, WorkspacePk,
+};
+
+mod cache;
+
+pub use cache::WorkspaceSettingsCache;
+
+const WORKSPACE_SETTINGS_LIST_FOR_WORKSPACE: &str =
+    include_str!("queries/workspace_settings/list_for_workspace.sql");
+const WORKSPACE_SETTINGS_UNSET: &str = include_str!("queries/workspace_settings/unset.sql");
+
+/// The NATS subject on which changing the settings of a workspace is announced, so that every
+/// service forgets what it cached about them.
+pub const WORKSPACE_SETTINGS_CHANGED_SUBJECT: &str = "si.workspace_settings.changed";
+
+/// The intervals, in seconds, at which resources may be synced.
+pub const RESOURCE_SYNC_INTERVAL_SECONDS_RANGE: RangeInclusive<u64> = 60..=86_400;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceSettingsError {
+    #[error("audit log error: {0}")]
+    AuditLog(#[from] AuditLogError),
+    #[error("invalid value {1} for workspace setting {0}: {2}")]
+    InvalidValue(WorkspaceSettingKey, Value, String),
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("unknown workspace setting: {0}")]
+    UnknownKey(String),
+}
+
+pub type WorkspaceSettingsResult<T> = Result<T, WorkspaceSettingsError>;
+
+/// The settings a workspace can change. Keys are stored and exchanged in camelCase, matching the
+/// fields of [`WorkspaceSettings`].
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WorkspaceSettingKey {
+    /// See [`QualificationGatingPolicy`].
+    QualificationGating,
+    /// How often, in seconds, the resources of the workspace are synced.
+    ResourceSyncIntervalSeconds,
+}
+
+impl WorkspaceSettingKey {
+    /// Checks that the value can be stored under the key.
+    pub fn validate(&self, value: &Value) -> WorkspaceSettingsResult<()> {
+        let invalid =
+            |reason: String| WorkspaceSettingsError::InvalidValue(*self, value.clone(), reason);
+
+        match self {
+            Self::QualificationGating => {
+                QualificationGatingPolicy::deserialize(value)
+                    .map_err(|err| invalid(err.to_string()))?;
+            }
+            Self::ResourceSyncIntervalSeconds => {
+                let seconds = value
+                    .as_u64()
+                    .ok_or_else(|| invalid("expected a whole number of seconds".to_owned()))?;
+                if !RESOURCE_SYNC_INTERVAL_SECONDS_RANGE.contains(&seconds) {
+                    return Err(invalid(format!(
+                        "expected between {} and {} seconds",
+                        RESOURCE_SYNC_INTERVAL_SECONDS_RANGE.start(),
+                        RESOURCE_SYNC_INTERVAL_SECONDS_RANGE.end()
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What happens when a change set is applied while some of its qualifications are failing.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum QualificationGatingPolicy {
+    /// The change set cannot be applied.
+    Block,
+    /// The change set is applied without mentioning the failures.
+    Ignore,
+    /// The change set can be applied once the failures are acknowledged.
+    #[default]
+    Warn,
+}
+
+/// The settings of a workspace, with the default of every setting it did not change.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSettings {
+    pub qualification_gating: QualificationGatingPolicy,
+    pub resource_sync_interval_seconds: u64,
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            qualification_gating: QualificationGatingPolicy::default(),
+            resource_sync_interval_seconds: 300,
+        }
+    }
+}
+
+/// Announced on [`WORKSPACE_SETTINGS_CHANGED_SUBJECT`] when the settings of a workspace change.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSettingsChanged {
+    pub workspace_pk: WorkspacePk,
+}
+
+impl WorkspaceSettings {
+    /// Looks up the settings of the workspace of the current tenancy, bypassing the
+    /// [`WorkspaceSettingsCache`].
+    ///
+    /// Stored values which are no longer valid, or whose key no longer exists, are skipped in
+    /// favor of the default.
+    #[instrument(skip_all)]
+    pub async fn get(ctx: &DalContext) -> WorkspaceSettingsResult<Self> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WORKSPACE_SETTINGS_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+
+        let mut settings = Self::default();
+        for row in rows {
+            let name: String = row.try_get("key")?;
+            let value: Value = row.try_get("value")?;
+            if let Err(err) = parse_key(&name).and_then(|key| settings.apply(key, value)) {
+                warn!(error = ?err, "skipping stored workspace setting");
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Changes the settings of the workspace of the current tenancy, returning the settings which
+    /// now apply. A `null` value resets the setting to its default.
+    ///
+    /// Every key and value is validated before any of them is stored, so an invalid change leaves
+    /// the settings untouched. The change is recorded in the [`AuditLog`], and other services
+    /// learn about it when the context is committed.
+    #[instrument(skip_all)]
+    pub async fn update(
+        ctx: &DalContext,
+        changes: &Map<String, Value>,
+    ) -> WorkspaceSettingsResult<Self> {
+        let workspace_pk = workspace_pk(ctx)?;
+
+        let mut parsed = Vec::with_capacity(changes.len());
+        for (name, value) in changes {
+            let key = parse_key(name)?;
+            if !value.is_null() {
+                key.validate(value)?;
+            }
+            parsed.push((key, value));
+        }
+
+        let before = Self::get(ctx).await?;
+        for (key, value) in parsed {
+            if value.is_null() {
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(WORKSPACE_SETTINGS_UNSET, &[&workspace_pk, &key.as_ref()])
+                    .await?;
+            } else {
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .query_one(
+                        "SELECT object FROM workspace_setting_set_v1($1, $2, $3)",
+                        &[&workspace_pk, &key.as_ref(), value],
+                    )
+                    .await?;
+            }
+        }
+
+        let after = Self::get(ctx).await?;
+
+        AuditLog::record(
+            ctx,
+            AuditAction::WorkspaceSettingsUpdate,
+            Some(AuditTarget::new("workspace", workspace_pk, None)),
+            Some(serde_json::to_value(&before)?),
+            Some(serde_json::to_value(&after)?),
+        )
+        .await?;
+        announce_change(ctx, workspace_pk).await?;
+
+        Ok(after)
+    }
+
+    /// Returns the value of the setting, as it is exchanged with clients.
+    pub fn value(&self, key: WorkspaceSettingKey) -> Value {
+        match key {
+            WorkspaceSettingKey::QualificationGating => {
+                Value::String(self.qualification_gating.to_string())
+            }
+            WorkspaceSettingKey::ResourceSyncIntervalSeconds => {
+                self.resource_sync_interval_seconds.into()
+            }
+        }
+    }
+
+    fn apply(&mut self, key: WorkspaceSettingKey, value: Value) -> WorkspaceSettingsResult<()> {
+        key.validate(&value)?;
+        match key {
+            WorkspaceSettingKey::QualificationGating => {
+                self.qualification_gating = serde_json::from_value(value)?;
+            }
+            WorkspaceSettingKey::ResourceSyncIntervalSeconds => {
+                self.resource_sync_interval_seconds = serde_json::from_value(value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> WorkspaceSettingsResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk()
+        .ok_or(WorkspaceSettingsError::NoWorkspaceInTenancy)
+}
+
+fn parse_key(name: &str) -> WorkspaceSettingsResult<WorkspaceSettingKey> {
+    WorkspaceSettingKey::from_str(name)
+        .map_err(|_| WorkspaceSettingsError::UnknownKey(name.to_owned()))
+}
+
+/// Forgets what this service cached about the settings right away, so that the context sees its
+/// own change, and tells the other services to do the same once the context is committed.
+async fn announce_change(
+    ctx: &DalContext,
+    workspace_pk: WorkspacePk,
+) -> WorkspaceSettingsResult<()> {
+    ctx.workspace_settings_cache()
+        .invalidate(workspace_pk)
+        .await;
+
+    let changed = WorkspaceSettingsChanged { workspace_pk };
+    ctx.txns()
+        .await?
+        .nats()
+        .publish(
+            WORKSPACE_SETTINGS_CHANGED_SUBJECT,
+            &serde_json::to_value(changed)?,
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn keys_match_settings_fields() {
+        let settings =
+            serde_json::to_value(WorkspaceSettings::default()).expect("cannot serialize settings");
+        let fields: Vec<&String> = settings
+            .as_object()
+            .expect("settings are not an object")
+            .keys()
+            .collect();
+        let keys: Vec<String> = WorkspaceSettingKey::iter()
+            .map(|key| key.to_string())
+            .collect();
+        assert_eq!(keys.iter().collect::<Vec<_>>(), fields);
+
+        for key in WorkspaceSettingKey::iter() {
+            let default = WorkspaceSettings::default().value(key);
+            assert_eq!(Some(&default), settings.get(key.as_ref()));
+            key.validate(&default).expect("default is not valid");
+        }
+    }
+
+    #[test]
+    fn validates_values() {
+        let key = WorkspaceSettingKey::QualificationGating;
+        key.validate(&json!("block")).expect("block is valid");
+        assert!(key.validate(&json!("sometimes")).is_err());
+
+        let key = WorkspaceSettingKey::ResourceSyncIntervalSeconds;
+        key.validate(&json!(3_600)).expect("an hour is valid");
+        assert!(key.validate(&json!(1)).is_err());
+        assert!(key.validate(&json!(90.5)).is_err());
+        assert!(key.validate(&json!("3600")).is_err());
+    }
+
+    #[test]
+    fn applies_stored_values() {
+        let mut settings = WorkspaceSettings::default();
+        settings
+            .apply(WorkspaceSettingKey::QualificationGating, json!("ignore"))
+            .expect("cannot apply setting");
+        assert!(settings
+            .apply(WorkspaceSettingKey::ResourceSyncIntervalSeconds, json!(0))
+            .is_err());
+        assert_eq!(
+            WorkspaceSettings {
+                qualification_gating: QualificationGatingPolicy::Ignore,
+                ..Default::default()
+            },
+            settings
+        );
+    }
+}
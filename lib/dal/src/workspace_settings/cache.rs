@@ -0,0 +1,124 @@
+//! This module provides [`WorkspaceSettingsCache`], which keeps the settings of the workspaces
+//! looked up by a service so that reading a setting does not hit the database every time.
+//!
+//! Changing the settings of a workspace is announced on [`WORKSPACE_SETTINGS_CHANGED_SUBJECT`],
+//! which makes every service listening forget what it cached about the workspace. Entries also
+//! expire after a short while, which bounds how long a missed announcement goes unnoticed.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use telemetry::prelude::*;
+use tokio::sync::Mutex;
+
+use super::{
+    workspace_pk, WorkspaceSettings, WorkspaceSettingsChanged, WorkspaceSettingsResult,
+    WORKSPACE_SETTINGS_CHANGED_SUBJECT,
+};
+use crate::{DalContext, WorkspacePk};
+
+/// How long the settings of a workspace are kept before they are looked up again.
+const ENTRY_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+struct CachedSettings {
+    settings: WorkspaceSettings,
+    cached_at: Instant,
+}
+
+/// A cache of workspace settings, shared by every [`DalContext`] built from the same
+/// [`ServicesContext`](crate::ServicesContext).
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceSettingsCache {
+    entries: Arc<Mutex<HashMap<WorkspacePk, CachedSettings>>>,
+    listening: Arc<AtomicBool>,
+}
+
+impl WorkspaceSettingsCache {
+    /// Returns the settings of the workspace of the [`DalContext`].
+    pub async fn get(&self, ctx: &DalContext) -> WorkspaceSettingsResult<WorkspaceSettings> {
+        self.ensure_listening(ctx).await;
+
+        let workspace_pk = workspace_pk(ctx)?;
+        let cached = self
+            .entries
+            .lock()
+            .await
+            .get(&workspace_pk)
+            .filter(|cached| cached.cached_at.elapsed() < ENTRY_TTL)
+            .map(|cached| cached.settings.clone());
+        match cached {
+            Some(settings) => Ok(settings),
+            None => {
+                let settings = WorkspaceSettings::get(ctx).await?;
+                self.entries.lock().await.insert(
+                    workspace_pk,
+                    CachedSettings {
+                        settings: settings.clone(),
+                        cached_at: Instant::now(),
+                    },
+                );
+                Ok(settings)
+            }
+        }
+    }
+
+    /// Forgets the settings of the workspace.
+    pub async fn invalidate(&self, workspace_pk: WorkspacePk) {
+        self.entries.lock().await.remove(&workspace_pk);
+    }
+
+    /// Forgets the settings of every workspace.
+    pub async fn clear(&self) {
+        self.entries.lock().await.clear();
+    }
+
+    /// Subscribes to the announcements of changed settings, unless the cache already does. Should
+    /// the subscription fail or end, the next lookup subscribes again.
+    async fn ensure_listening(&self, ctx: &DalContext) {
+        if self.listening.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut subscription = match ctx
+            .nats_conn()
+            .subscribe(WORKSPACE_SETTINGS_CHANGED_SUBJECT)
+            .await
+        {
+            Ok(subscription) => subscription,
+            Err(err) => {
+                warn!(error = ?err, "failed to subscribe to workspace settings changes");
+                self.listening.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+
+        let cache = self.clone();
+        tokio::spawn(async move {
+            while let Some(message) = subscription.next().await {
+                match message.map_err(|err| err.to_string()).and_then(|message| {
+                    serde_json::from_slice::<WorkspaceSettingsChanged>(message.data())
+                        .map_err(|err| err.to_string())
+                }) {
+                    Ok(changed) => cache.invalidate(changed.workspace_pk).await,
+                    Err(err) => {
+                        // We cannot tell which workspace changed, so forget all of them
+                        warn!(error = %err, "failed to read workspace settings change");
+                        cache.clear().await;
+                    }
+                }
+            }
+
+            // The entries may have missed changes while the subscription was going away
+            cache.clear().await;
+            cache.listening.store(false, Ordering::SeqCst);
+        });
+    }
+}
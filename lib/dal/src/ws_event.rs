@@ -1,14 +1,26 @@
 use serde::{Deserialize, Serialize};
-use si_data_nats::NatsError;
+use si_data_nats::{subject::NO_BILLING_ACCOUNT, EventSubject, NatsError, SubjectError};
 use si_data_pg::PgError;
+use strum::{AsRefStr, EnumVariantNames};
 use thiserror::Error;
 
+use crate::attribute::value::AttributeValueUpdatedPayload;
+use crate::change_set::review::ChangeSetReviewPayload;
+use crate::change_set::schedule::ChangeSetApplySchedulePayload;
+use crate::comment::CommentPayload;
 use crate::component::confirmation::ConfirmationsUpdatedPayload;
 use crate::component::ComponentCreatedPayload;
 use crate::{
-    component::{code::CodeGeneratedPayload, resource::ResourceRefreshedPayload},
-    fix::{batch::FixBatchReturn, FixReturn},
-    qualification::QualificationCheckPayload,
+    component::{
+        code::CodeGeneratedPayload,
+        label::ComponentLabelChangedPayload,
+        lifecycle::ComponentLifecycleChangedPayload,
+        resource::{ResourceHealthChangedPayload, ResourceRefreshedPayload},
+        resource_conflict::ResourceDriftedPayload,
+    },
+    diagram::bulk::{NodesDeletedPayload, NodesMovedPayload},
+    fix::{batch::FixBatchReturn, plan::FixPlanPayload, FixReturn},
+    qualification::{QualificationCheckPayload, QualificationSummary},
     status::StatusMessage,
     AttributeValueId, ChangeSetPk, ComponentId, DalContext, PropId, SchemaPk, SocketId,
     StandardModelError, TransactionsError, WorkspacePk,
@@ -27,27 +39,52 @@ pub enum WsEventError {
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
+    #[error("nats subject error: {0}")]
+    Subject(#[from] SubjectError),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
 }
 
 pub type WsEventResult<T> = Result<T, WsEventError>;
 
+/// The event kind token of the [`EventSubject`] every [`WsEvent`] is published on.
+pub const WS_EVENT_SUBJECT_KIND: &str = "event";
+
+/// The kind of a payload (e.g. `"ChangeSetApplied"`) is the name of its variant, which is also the
+/// `kind` it is serialized with.
 #[remain::sorted]
-#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(AsRefStr, Deserialize, EnumVariantNames, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(tag = "kind", content = "data")]
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
+    AttributeValueUpdated(AttributeValueUpdatedPayload),
     ChangeSetApplied(ChangeSetPk),
+    ChangeSetApplyScheduleCanceled(ChangeSetApplySchedulePayload),
+    ChangeSetApplyScheduleFailed(ChangeSetApplySchedulePayload),
+    ChangeSetApplyScheduleStarted(ChangeSetApplySchedulePayload),
+    ChangeSetApplyScheduled(ChangeSetApplySchedulePayload),
     ChangeSetCanceled(ChangeSetPk),
     ChangeSetCreated(ChangeSetPk),
+    ChangeSetReviewRequested(ChangeSetReviewPayload),
+    ChangeSetReviewed(ChangeSetReviewPayload),
     ChangeSetWritten(ChangeSetPk),
     CheckedQualifications(QualificationCheckPayload),
     CodeGenerated(CodeGeneratedPayload),
+    CommentCreated(CommentPayload),
+    CommentDeleted(CommentPayload),
+    CommentUpdated(CommentPayload),
     ComponentCreated(ComponentCreatedPayload),
+    ComponentLabelChanged(ComponentLabelChangedPayload),
+    ComponentLifecycleChanged(ComponentLifecycleChangedPayload),
     ConfirmationsUpdated(ConfirmationsUpdatedPayload),
     FixBatchReturn(FixBatchReturn),
+    FixPlanUpdated(FixPlanPayload),
     FixReturn(FixReturn),
+    NodesDeleted(NodesDeletedPayload),
+    NodesMoved(NodesMovedPayload),
+    QualificationSummaryUpdated(QualificationSummary),
+    ResourceDrifted(ResourceDriftedPayload),
+    ResourceHealthChanged(ResourceHealthChangedPayload),
     ResourceRefreshed(ResourceRefreshedPayload),
     SchemaCreated(SchemaPk),
     StatusUpdate(StatusMessage),
@@ -92,6 +129,9 @@ pub struct WsEvent {
     version: i64,
     workspace_pk: WorkspacePk,
     change_set_pk: ChangeSetPk,
+    /// The identifier the client chose for the request which caused this event, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    client_request_id: Option<String>,
     payload: WsPayload,
 }
 
@@ -109,6 +149,7 @@ impl WsEvent {
             version: 1,
             workspace_pk,
             change_set_pk,
+            client_request_id: ctx.client_request_id().map(ToOwned::to_owned),
             payload,
         })
     }
@@ -117,11 +158,52 @@ impl WsEvent {
         self.workspace_pk
     }
 
+    pub fn change_set_pk(&self) -> ChangeSetPk {
+        self.change_set_pk
+    }
+
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.client_request_id.as_deref()
+    }
+
+    pub fn payload(&self) -> &WsPayload {
+        &self.payload
+    }
+
+    /// The [`EventSubject`] the [`event`](Self) is published on.
+    pub fn subject(&self) -> WsEventResult<EventSubject> {
+        Ok(EventSubject::builder()
+            .billing_account(NO_BILLING_ACCOUNT)
+            .workspace(self.workspace_pk)
+            .change_set(self.change_set_pk)
+            .event_kind(WS_EVENT_SUBJECT_KIND)
+            .build()?)
+    }
+
+    /// The [`EventSubject`] to subscribe to for the [`events`](Self) of the given workspace and
+    /// change set, or of every workspace or change set when not given.
+    pub fn subscription_subject(
+        workspace_pk: Option<WorkspacePk>,
+        change_set_pk: Option<ChangeSetPk>,
+    ) -> WsEventResult<EventSubject> {
+        let mut builder = EventSubject::builder().event_kind(WS_EVENT_SUBJECT_KIND);
+        if let Some(workspace_pk) = workspace_pk {
+            builder = builder.workspace(workspace_pk);
+        }
+        if let Some(change_set_pk) = change_set_pk {
+            builder = builder.change_set(change_set_pk);
+        }
+        Ok(builder.build()?)
+    }
+
     /// Publishes the [`event`](Self) to the [`NatsTxn`](si_data_nats::NatsTxn). When the
     /// transaction is committed, the [`event`](Self) will be published for external use.
     pub async fn publish_on_commit(&self, ctx: &DalContext) -> WsEventResult<()> {
-        let subject = format!("si.workspace_pk.{}.event", self.workspace_pk);
-        ctx.txns().await?.nats().publish(subject, &self).await?;
+        ctx.txns()
+            .await?
+            .nats()
+            .publish_event(&self.subject()?, &self)
+            .await?;
         Ok(())
     }
 }
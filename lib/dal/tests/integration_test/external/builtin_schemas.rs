@@ -0,0 +1,3 @@
+use dal_test::builtin_schema_test;
+
+builtin_schema_test!("docker_image");
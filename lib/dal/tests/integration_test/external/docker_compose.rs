@@ -0,0 +1,109 @@
+use dal::edge::EdgeKind;
+use dal::export::docker_compose::{self, ComposeService, DockerComposeExportError};
+use dal::socket::SocketEdgeKind;
+use dal::{ComponentType, Connection, DalContext, Socket, StandardModel};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn docker_images_in_frame_become_services(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let frame_bag = bagger.create_component(ctx, "app", "fallout").await;
+    let nginx_bag = bagger.create_component(ctx, "nginx", "Docker Image").await;
+    frame_bag
+        .component(ctx)
+        .await
+        .set_type(ctx, ComponentType::ConfigurationFrame)
+        .await
+        .expect("could not set component type");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let docker_image_prop = nginx_bag.find_prop(ctx, &["root", "domain", "image"]).await;
+    let exposed_port_element_prop = nginx_bag
+        .find_prop(ctx, &["root", "domain", "ExposedPorts", "ExposedPort"])
+        .await;
+    let exposed_port_array_prop = exposed_port_element_prop
+        .parent_prop(ctx)
+        .await
+        .expect("could not perform parent lookup")
+        .expect("parent prop not found");
+
+    nginx_bag
+        .update_attribute_value_for_prop(
+            ctx,
+            *docker_image_prop.id(),
+            Some(serde_json::json!["docker.io/library/nginx"]),
+        )
+        .await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    for port in ["80/tcp", "53/udp"] {
+        nginx_bag
+            .insert_array_primitive_element(
+                ctx,
+                *exposed_port_array_prop.id(),
+                *exposed_port_element_prop.id(),
+                serde_json::json![port],
+            )
+            .await;
+    }
+
+    // Place the image inside the frame.
+    let from_socket = Socket::find_frame_socket_for_node(
+        ctx,
+        nginx_bag.node_id,
+        SocketEdgeKind::ConfigurationOutput,
+    )
+    .await
+    .expect("could not find frame socket for child");
+    let to_socket = Socket::find_frame_socket_for_node(
+        ctx,
+        frame_bag.node_id,
+        SocketEdgeKind::ConfigurationInput,
+    )
+    .await
+    .expect("could not find frame socket for frame");
+    Connection::new(
+        ctx,
+        nginx_bag.node_id,
+        *from_socket.id(),
+        frame_bag.node_id,
+        *to_socket.id(),
+        EdgeKind::Symbolic,
+    )
+    .await
+    .expect("could not connect to frame");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let document = docker_compose::build(ctx, frame_bag.component_id)
+        .await
+        .expect("could not build compose document");
+    assert_eq!(
+        Some(&ComposeService {
+            image: Some("docker.io/library/nginx".to_string()),
+            ports: vec!["80:80".to_string(), "53:53/udp".to_string()],
+            ..Default::default()
+        }), // expected
+        document.services.get("nginx"), // actual
+    );
+
+    let yaml = docker_compose::export(ctx, frame_bag.component_id)
+        .await
+        .expect("could not export compose yaml");
+    assert!(yaml.contains("image: docker.io/library/nginx"));
+
+    // Components which are not frames are not applications.
+    let result = docker_compose::export(ctx, nginx_bag.component_id).await;
+    assert!(matches!(
+        result,
+        Err(DockerComposeExportError::NotAnApplication(_))
+    ));
+}
@@ -1,3 +1,5 @@
 mod aws_region;
+mod builtin_schemas;
 mod coreos_butane;
+mod docker_compose;
 mod docker_image_intelligence;
@@ -1,7 +1,7 @@
 use pretty_assertions_sorted::assert_eq;
 
-use dal::action_prototype::ActionKind;
-use dal::{ActionPrototype, ActionPrototypeContext, DalContext, FuncId};
+use dal::action_prototype::{ActionKind, ActionPrototypeContextField};
+use dal::{ActionPrototype, ActionPrototypeContext, DalContext, FuncId, InternalProvider, Schema};
 use dal_test::test;
 
 #[test]
@@ -13,3 +13,32 @@ async fn new(ctx: &DalContext) {
     assert_eq!(*prototype.kind(), ActionKind::Create);
     assert_eq!(prototype.func_id(), FuncId::NONE);
 }
+
+#[test]
+async fn s3_bucket_has_resource_actions(ctx: &DalContext) {
+    let schema = Schema::find_by_name(ctx, "S3 Bucket")
+        .await
+        .expect("could not find schema");
+    let schema_variant_id = *schema
+        .default_schema_variant_id()
+        .expect("could not get default variant id");
+    let context = ActionPrototypeContext::new_for_context_field(
+        ActionPrototypeContextField::SchemaVariant(schema_variant_id),
+    );
+
+    for kind in [ActionKind::Create, ActionKind::Refresh, ActionKind::Delete] {
+        let prototypes = ActionPrototype::find_for_context_and_kind(ctx, kind, context)
+            .await
+            .expect("could not find action prototypes");
+        assert_eq!(1, prototypes.len());
+    }
+
+    let credential_socket = InternalProvider::find_explicit_for_schema_variant_and_name(
+        ctx,
+        schema_variant_id,
+        "AWS Credential",
+    )
+    .await
+    .expect("could not find explicit internal provider");
+    assert!(credential_socket.is_some());
+}
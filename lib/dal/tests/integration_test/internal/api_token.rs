@@ -0,0 +1,73 @@
+use chrono::{Duration, Utc};
+use dal::{ApiToken, ApiTokenError, ApiTokenScope, DalContext, WorkspaceSignup};
+use dal_test::test;
+
+#[test]
+async fn new_and_authenticate(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let (api_token, raw_token) = ApiToken::new(
+        ctx,
+        nw.user.pk(),
+        "ci",
+        vec![ApiTokenScope::ComponentRead],
+        None,
+    )
+    .await
+    .expect("cannot create api token");
+    assert!(raw_token.starts_with(dal::API_TOKEN_PREFIX));
+    assert!(raw_token.starts_with(api_token.token_prefix()));
+    assert!(api_token.last_used_at().is_none());
+
+    let authenticated = ApiToken::authenticate(ctx, &raw_token, ApiTokenScope::ComponentRead)
+        .await
+        .expect("cannot authenticate api token");
+    assert_eq!(api_token.pk(), authenticated.pk());
+    assert_eq!(*nw.workspace.pk(), *authenticated.workspace_pk());
+    assert!(authenticated.last_used_at().is_some());
+
+    let result = ApiToken::authenticate(ctx, &raw_token, ApiTokenScope::ComponentWrite).await;
+    assert!(matches!(result, Err(ApiTokenError::MissingScope(..))));
+
+    let result = ApiToken::authenticate(ctx, "si_token_bogus", ApiTokenScope::ComponentRead).await;
+    assert!(matches!(result, Err(ApiTokenError::NotFoundForSecret)));
+}
+
+#[test]
+async fn revoke(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let (mut api_token, raw_token) = ApiToken::new(
+        ctx,
+        nw.user.pk(),
+        "ci",
+        vec![ApiTokenScope::SchemaRead],
+        None,
+    )
+    .await
+    .expect("cannot create api token");
+
+    api_token
+        .revoke(ctx)
+        .await
+        .expect("cannot revoke api token");
+    assert!(api_token.revoked_at().is_some());
+
+    let result = ApiToken::authenticate(ctx, &raw_token, ApiTokenScope::SchemaRead).await;
+    assert!(matches!(result, Err(ApiTokenError::Revoked(_))));
+
+    let list = ApiToken::list(ctx).await.expect("cannot list api tokens");
+    assert!(list.iter().any(|token| token.pk() == api_token.pk()));
+}
+
+#[test]
+async fn expired(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let (_api_token, raw_token) = ApiToken::new(
+        ctx,
+        nw.user.pk(),
+        "ci",
+        vec![ApiTokenScope::SchemaRead],
+        Some(Utc::now() - Duration::minutes(1)),
+    )
+    .await
+    .expect("cannot create api token");
+
+    let result = ApiToken::authenticate(ctx, &raw_token, ApiTokenScope::SchemaRead).await;
+    assert!(matches!(result, Err(ApiTokenError::Expired(_))));
+}
@@ -62,6 +62,61 @@ async fn new_attribute_prototype(ctx: &DalContext) {
     .expect("cannot create new attribute prototype");
 }
 
+#[test]
+async fn set_recheck_interval(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger.create_component(ctx, "poop", "starfield").await;
+    let schema_variant = component_bag.schema_variant(ctx).await;
+
+    let func = Func::new(
+        ctx,
+        "test:setString",
+        FuncBackendKind::String,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create func");
+    let args = FuncBackendStringArgs::new("eldenring".to_string());
+    let (func_binding, func_binding_return_value) = FuncBinding::create_and_execute(
+        ctx,
+        serde_json::to_value(args).expect("cannot turn args into json"),
+        *func.id(),
+    )
+    .await
+    .expect("cannot create and execute function binding");
+
+    let root_prop_id = schema_variant
+        .root_prop_id()
+        .expect("no root prop for schema variant");
+    let context = AttributeContext::builder()
+        .set_prop_id(*root_prop_id)
+        .set_component_id(component_bag.component_id)
+        .to_context()
+        .expect("cannot create context");
+    let mut attribute_prototype = AttributePrototype::new(
+        ctx,
+        *func.id(),
+        *func_binding.id(),
+        *func_binding_return_value.id(),
+        context,
+        None,
+        None,
+    )
+    .await
+    .expect("cannot create new attribute prototype");
+    assert_eq!(None, attribute_prototype.recheck_interval_seconds());
+
+    attribute_prototype
+        .set_recheck_interval_seconds(ctx, Some(3600))
+        .await
+        .expect("could not set recheck interval");
+    let found = AttributePrototype::get_by_id(ctx, attribute_prototype.id())
+        .await
+        .expect("could not get attribute prototype")
+        .expect("attribute prototype not found");
+    assert_eq!(Some(&3600), found.recheck_interval_seconds());
+}
+
 #[test]
 async fn list_for_context_with_a_hash(ctx: &DalContext) {
     let mut schema = create_schema(ctx).await;
@@ -1,10 +1,13 @@
 use dal::{
-    attribute::context::AttributeContextBuilder, component::view::ComponentView, generate_name,
-    AttributeContext, AttributeReadContext, AttributeValue, Component, DalContext, Prop, PropKind,
-    StandardModel,
+    attribute::context::AttributeContextBuilder, component::view::ComponentView,
+    func::binding_return_value::FuncBindingReturnValue, generate_name,
+    provider::internal::InternalProvider, AttributeContext, AttributePrototypeArgument,
+    AttributeReadContext, AttributeValue, AttributeValueError, Component, DalContext, Prop,
+    PropKind, StandardModel,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::{
+    helpers::setup_identity_func,
     test,
     test_harness::{create_schema, create_schema_variant_with_root},
 };
@@ -167,6 +170,126 @@ async fn update_for_context_simple(ctx: &DalContext) {
     );
 }
 
+#[test]
+async fn update_for_context_at_revision(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+
+    let name_prop = Prop::new(
+        ctx,
+        "name_prop",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+
+    let (component, _) =
+        Component::new_for_default_variant_from_schema(ctx, "Basic component", *schema.id())
+            .await
+            .expect("Unable to create component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let base_attribute_read_context = AttributeReadContext {
+        prop_id: None,
+        component_id: Some(*component.id()),
+        ..AttributeReadContext::default()
+    };
+    let domain_value_id = *AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(root.domain_prop_id),
+            ..base_attribute_read_context
+        },
+    )
+    .await
+    .expect("cannot get domain AttributeValue")
+    .expect("domain AttributeValue not found")
+    .id();
+    let base_name_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*name_prop.id()),
+            ..base_attribute_read_context
+        },
+    )
+    .await
+    .expect("cannot get name AttributeValue")
+    .expect("name AttributeValue not found");
+    let update_context: AttributeContext =
+        AttributeContextBuilder::from(base_attribute_read_context)
+            .set_prop_id(*name_prop.id())
+            .to_context()
+            .expect("cannot build write AttributeContext");
+
+    let (_, name_value_id) = AttributeValue::update_for_context(
+        ctx,
+        *base_name_value.id(),
+        Some(domain_value_id),
+        update_context,
+        Some(serde_json::json!("Miles")),
+        None,
+    )
+    .await
+    .expect("cannot set value for context");
+    let read_revision = AttributeValue::get_by_id(ctx, &name_value_id)
+        .await
+        .expect("cannot get name AttributeValue")
+        .expect("name AttributeValue not found")
+        .revision();
+
+    // The first writer at the revision it read wins...
+    AttributeValue::update_for_context_at_revision(
+        ctx,
+        name_value_id,
+        Some(domain_value_id),
+        update_context,
+        Some(serde_json::json!("Iria")),
+        None,
+        read_revision,
+    )
+    .await
+    .expect("cannot update value at revision");
+
+    // ...and the second is told what it would have overwritten
+    let result = AttributeValue::update_for_context_at_revision(
+        ctx,
+        name_value_id,
+        Some(domain_value_id),
+        update_context,
+        Some(serde_json::json!("Nona")),
+        None,
+        read_revision,
+    )
+    .await;
+    match result {
+        Err(AttributeValueError::RevisionConflict {
+            attribute_value_id,
+            expected_revision,
+            current_revision,
+            current_value,
+        }) => {
+            assert_eq!(name_value_id, attribute_value_id);
+            assert_eq!(read_revision, expected_revision);
+            assert!(current_revision > read_revision);
+            assert_eq!(Some(serde_json::json!("Iria")), current_value);
+        }
+        other => panic!("expected a revision conflict, got {other:?}"),
+    }
+}
+
 #[test]
 async fn insert_for_context_simple(ctx: &DalContext) {
     let mut schema = create_schema(ctx).await;
@@ -671,7 +794,7 @@ async fn insert_for_context_creates_array_in_final_context(ctx: &DalContext) {
 #[test]
 async fn list_payload(ctx: &DalContext) {
     let mut bagger = ComponentBagger::new();
-    let name = generate_name();
+    let name = generate_name(ctx);
     let component_bag = bagger.create_component(ctx, &name, "starfield").await;
 
     ctx.blocking_commit()
@@ -730,3 +853,235 @@ async fn list_payload(ctx: &DalContext) {
     assert_eq!(found_name.replace('"', ""), name);
     assert_eq!(si_name_value, domain_name_value);
 }
+
+#[test]
+async fn provenance(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let starfield_bag = bagger.create_component(ctx, "13700KF", "starfield").await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let si_name_prop = starfield_bag.find_prop(ctx, &["root", "si", "name"]).await;
+    let domain_name_prop = starfield_bag
+        .find_prop(ctx, &["root", "domain", "name"])
+        .await;
+    let find_value = |prop: &Prop| {
+        AttributeValue::find_for_context(
+            ctx,
+            starfield_bag.attribute_read_context_with_prop(*prop.id()),
+        )
+    };
+
+    let si_name_value = find_value(&si_name_prop)
+        .await
+        .expect("could not find attribute value")
+        .expect("attribute value not found");
+    let provenance = AttributeValue::provenance(ctx, *si_name_value.id())
+        .await
+        .expect("could not get provenance");
+    assert_eq!("si:setString", provenance.func.name);
+    assert!(provenance.set_directly);
+    assert!(provenance.inputs.is_empty());
+
+    let domain_name_value = find_value(&domain_name_prop)
+        .await
+        .expect("could not find attribute value")
+        .expect("attribute value not found");
+    let provenance = AttributeValue::provenance(ctx, *domain_name_value.id())
+        .await
+        .expect("could not get provenance");
+    assert_eq!(Some(serde_json::json!("13700KF")), provenance.value);
+    assert_eq!("si:identity", provenance.func.name);
+    assert!(!provenance.set_directly);
+    assert_eq!(1, provenance.inputs.len());
+    let input = &provenance.inputs[0];
+    assert_eq!(Some(*si_name_prop.id()), input.prop_id);
+    assert_eq!(starfield_bag.component_id, input.source_component_id);
+    assert_eq!(Some(serde_json::json!("13700KF")), input.value);
+    assert!(input.edge_ids.is_empty());
+}
+
+#[test]
+async fn sealed_object_values_seal_their_children(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let schema_variant_id = *schema_variant.id();
+
+    // domain: Object
+    // ├─ source: Object
+    // │  └─ password: String
+    // └─ copy: Object
+    //    └─ password: String
+    let mut props = Vec::new();
+    for name in ["source", "copy"] {
+        let object_prop = Prop::new(
+            ctx,
+            name,
+            PropKind::Object,
+            None,
+            schema_variant_id,
+            Some(root.domain_prop_id),
+        )
+        .await
+        .expect("could not create prop");
+        let password_prop = Prop::new(
+            ctx,
+            "password",
+            PropKind::String,
+            None,
+            schema_variant_id,
+            Some(*object_prop.id()),
+        )
+        .await
+        .expect("could not create prop");
+        props.push((object_prop, password_prop));
+    }
+    let (source_prop, source_password_prop) = &props[0];
+    let (copy_prop, copy_password_prop) = &props[1];
+
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (component, _) = Component::new_for_default_variant_from_schema(ctx, "vault", *schema.id())
+        .await
+        .expect("unable to create component");
+    let base_attribute_read_context = AttributeReadContext {
+        prop_id: None,
+        component_id: Some(*component.id()),
+        ..AttributeReadContext::default()
+    };
+    let find_value = |prop_id| {
+        AttributeValue::find_for_context(
+            ctx,
+            AttributeReadContext {
+                prop_id: Some(prop_id),
+                ..base_attribute_read_context
+            },
+        )
+    };
+
+    let source_value = find_value(*source_prop.id())
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found");
+    let source_password_value = find_value(*source_password_prop.id())
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found");
+    let source_password_context = AttributeContextBuilder::from(base_attribute_read_context)
+        .set_prop_id(*source_password_prop.id())
+        .to_context()
+        .expect("could not convert builder to attribute context");
+    AttributeValue::update_for_context(
+        ctx,
+        *source_password_value.id(),
+        Some(*source_value.id()),
+        source_password_context,
+        Some(serde_json::json!("hunter2")),
+        None,
+    )
+    .await
+    .expect("cannot update value for context");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // Stand in for a source derived from secrets by sealing the value "source" emits.
+    let source_internal_provider = InternalProvider::find_for_prop(ctx, *source_prop.id())
+        .await
+        .expect("could not get internal provider")
+        .expect("internal provider not found");
+    let source_emit_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            internal_provider_id: Some(*source_internal_provider.id()),
+            component_id: Some(*component.id()),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("cannot get attribute value")
+    .expect("attribute value not found");
+    FuncBindingReturnValue::get_by_id(ctx, &source_emit_value.func_binding_return_value_id())
+        .await
+        .expect("could not get return value")
+        .expect("return value not found")
+        .seal(ctx)
+        .await
+        .expect("could not seal return value");
+
+    // Populate "copy" from "source" with the identity func.
+    let mut copy_value = find_value(*copy_prop.id())
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found");
+    let mut copy_prototype = copy_value
+        .attribute_prototype(ctx)
+        .await
+        .expect("cannot find attribute prototype")
+        .expect("attribute prototype not found");
+    let (identity_func_id, _, _, identity_func_identity_argument_id) =
+        setup_identity_func(ctx).await;
+    copy_prototype
+        .set_func_id(ctx, identity_func_id)
+        .await
+        .expect("could not set func id on attribute prototype");
+    AttributePrototypeArgument::new_for_intra_component(
+        ctx,
+        *copy_prototype.id(),
+        identity_func_identity_argument_id,
+        *source_internal_provider.id(),
+    )
+    .await
+    .expect("could not create attribute prototype argument");
+    copy_value
+        .update_from_prototype_function(ctx)
+        .await
+        .expect("could not update from prototype function");
+
+    let copy_password_value = find_value(*copy_password_prop.id())
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found");
+    assert_eq!(
+        Some(serde_json::json!("hunter2")),
+        copy_password_value
+            .get_value(ctx)
+            .await
+            .expect("could not get value")
+    );
+
+    let row = ctx
+        .txns()
+        .await
+        .expect("could not get transactions")
+        .pg()
+        .query_one(
+            "SELECT sensitive, value, unprocessed_value
+             FROM func_binding_return_values_v1($1, $2)
+             WHERE id = $3",
+            &[
+                ctx.tenancy(),
+                ctx.visibility(),
+                &copy_password_value.func_binding_return_value_id(),
+            ],
+        )
+        .await
+        .expect("could not read the return value row");
+    assert!(row.get::<_, bool>("sensitive"));
+    assert_eq!(None, row.get::<_, Option<serde_json::Value>>("value"));
+    assert_eq!(
+        None,
+        row.get::<_, Option<serde_json::Value>>("unprocessed_value")
+    );
+}
@@ -0,0 +1,98 @@
+use dal::{
+    AuditAction, AuditLog, AuditLogFilter, AuditRetentionPolicy, AuditTarget, DalContext,
+    HistoryActor,
+};
+use dal_test::test;
+
+#[test]
+async fn record_and_list(ctx: &DalContext) {
+    let entry = AuditLog::record(
+        ctx,
+        AuditAction::SecretUpdate,
+        Some(AuditTarget::new("secret", "1", Some("poop".to_string()))),
+        Some(serde_json::json![{ "name": "poop" }]),
+        Some(serde_json::json![{ "name": "canoe" }]),
+    )
+    .await
+    .expect("cannot record audit log entry");
+    assert_eq!(AuditAction::SecretUpdate, entry.action());
+    assert_eq!(ctx.history_actor(), entry.actor());
+    assert_eq!(
+        Some("poop"),
+        entry.target().and_then(|target| target.name.as_deref())
+    );
+
+    AuditLog::record(ctx, AuditAction::ChangeSetApply, None, None, None)
+        .await
+        .expect("cannot record audit log entry");
+
+    let filter = AuditLogFilter {
+        action: Some(AuditAction::SecretUpdate),
+        ..Default::default()
+    };
+    let page = AuditLog::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list audit log");
+    assert!(page.entries.iter().any(|e| e.pk() == entry.pk()));
+    assert!(page
+        .entries
+        .iter()
+        .all(|e| e.action() == AuditAction::SecretUpdate));
+
+    let filter = AuditLogFilter {
+        actor: Some(HistoryActor::SystemInit),
+        action: Some(AuditAction::SecretUpdate),
+        ..Default::default()
+    };
+    let page = AuditLog::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list audit log");
+    assert!(page.entries.iter().all(|e| e.pk() != entry.pk()));
+}
+
+#[test]
+async fn list_pages(ctx: &DalContext) {
+    for _ in 0..3 {
+        AuditLog::record(ctx, AuditAction::ComponentDelete, None, None, None)
+            .await
+            .expect("cannot record audit log entry");
+    }
+    let filter = AuditLogFilter {
+        action: Some(AuditAction::ComponentDelete),
+        ..Default::default()
+    };
+
+    let first = AuditLog::list(ctx, &filter, None, Some(2))
+        .await
+        .expect("cannot list audit log");
+    assert_eq!(2, first.entries.len());
+    let cursor = first.next_cursor.expect("first page should have a cursor");
+
+    let second = AuditLog::list(ctx, &filter, Some(cursor), Some(2))
+        .await
+        .expect("cannot list audit log");
+    assert!(!second.entries.is_empty());
+    assert!(second.entries.iter().all(|e| e.pk() < cursor));
+}
+
+#[test]
+async fn prune(ctx: &DalContext) {
+    AuditLog::record(ctx, AuditAction::ComponentRestore, None, None, None)
+        .await
+        .expect("cannot record audit log entry");
+
+    let pruned = AuditLog::prune(ctx, AuditRetentionPolicy::default())
+        .await
+        .expect("cannot prune audit log");
+    assert_eq!(0, pruned);
+
+    let pruned = AuditLog::prune(ctx, AuditRetentionPolicy { max_age_days: 0 })
+        .await
+        .expect("cannot prune audit log");
+    assert!(pruned > 0);
+
+    let page = AuditLog::list(ctx, &AuditLogFilter::default(), None, None)
+        .await
+        .expect("cannot list audit log");
+    assert!(page.entries.is_empty());
+}
@@ -0,0 +1,40 @@
+use chrono::{Duration, Utc};
+use dal::{Blob, BlobError, BlobHash, DalContext};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn store_load_and_collect_garbage(ctx: &DalContext) {
+    let content = "a".repeat(8 * 1024);
+    let hash = Blob::store(ctx, content.as_bytes())
+        .await
+        .expect("could not store blob");
+    assert_eq!(BlobHash::new(content.as_bytes()), hash);
+    assert_eq!(
+        hash,
+        Blob::store(ctx, content.as_bytes())
+            .await
+            .expect("could not store blob again")
+    );
+    assert_eq!(
+        content.as_bytes(),
+        Blob::load(ctx, hash)
+            .await
+            .expect("could not load blob")
+            .as_slice()
+    );
+
+    // Recently stored blobs are left alone, even when nothing refers to them.
+    Blob::collect_garbage(ctx, Utc::now() - Duration::hours(1))
+        .await
+        .expect("could not collect garbage");
+    Blob::load(ctx, hash)
+        .await
+        .expect("recent blob was collected");
+
+    Blob::collect_garbage(ctx, Utc::now() + Duration::hours(1))
+        .await
+        .expect("could not collect garbage");
+    let result = Blob::load(ctx, hash).await;
+    assert!(matches!(result, Err(BlobError::NotFound(_))));
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use dal::edge::EdgeKind;
+use dal::socket::SocketEdgeKind;
+use dal::{
+    Blueprint, BlueprintError, BlueprintVariable, Connection, DalContext, Edge, Socket,
+    StandardModel,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn capture_and_instantiate(ctx: &mut DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "source", "fallout").await;
+    let starfield_bag = bagger
+        .create_component(ctx, "destination", "starfield")
+        .await;
+
+    let from_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let to_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        *from_socket.id(),
+        starfield_bag.node_id,
+        *to_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    let rads_prop = fallout_bag
+        .find_prop(ctx, &["root", "domain", "rads"])
+        .await;
+    fallout_bag
+        .update_attribute_value_for_prop(ctx, *rads_prop.id(), Some(serde_json::json![2]))
+        .await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let blueprint = Blueprint::capture(
+        ctx,
+        "wasteland",
+        None,
+        &[fallout_bag.component_id, starfield_bag.component_id],
+        vec![BlueprintVariable {
+            name: "origin".to_owned(),
+            default: Some(serde_json::json!["source"]),
+        }],
+    )
+    .await
+    .expect("could not capture blueprint");
+
+    let spec = blueprint.spec();
+    assert_eq!(2, spec.components.len());
+    let fallout = &spec.components[0];
+    assert_eq!("{{origin}}", fallout.name);
+    assert_eq!("fallout", fallout.schema_name);
+    assert_eq!(
+        vec![dal::component::AttributeUpdate::new(
+            "/root/domain/rads",
+            Some(serde_json::json![2])
+        )],
+        fallout.values
+    );
+    assert_eq!(1, spec.edges.len());
+    assert_eq!(fallout.key, spec.edges[0].from_key);
+    assert_eq!(spec.components[1].key, spec.edges[0].to_key);
+
+    let result = blueprint
+        .instantiate(
+            ctx,
+            HashMap::from([("planet".to_owned(), serde_json::json!["mars"])]),
+            0.0,
+            0.0,
+        )
+        .await;
+    assert!(matches!(result, Err(BlueprintError::UnknownVariable(_))));
+
+    let instantiated = blueprint
+        .instantiate(
+            ctx,
+            HashMap::from([("origin".to_owned(), serde_json::json!["vault"])]),
+            100.0,
+            100.0,
+        )
+        .await
+        .expect("could not instantiate blueprint");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    assert_eq!(2, instantiated.len());
+    let new_fallout = &instantiated[0];
+    let new_starfield = &instantiated[1];
+    assert_ne!(fallout_bag.component_id, new_fallout.component_id);
+    assert_ne!(starfield_bag.component_id, new_starfield.component_id);
+
+    let edges = Edge::list_for_components(ctx, &[new_fallout.component_id])
+        .await
+        .expect("could not list edges");
+    assert_eq!(1, edges.len());
+    assert_eq!(new_fallout.node_id, edges[0].tail_node_id());
+    assert_eq!(new_starfield.node_id, edges[0].head_node_id());
+
+    let fallout_view = dal::ComponentView::new(ctx, new_fallout.component_id)
+        .await
+        .expect("could not get component view");
+    assert_eq!(
+        serde_json::json![{
+            "name": "vault",
+            "rads": 2,
+            "active": true,
+        }],
+        fallout_view.properties["domain"]
+    );
+    let starfield_view = dal::ComponentView::new(ctx, new_starfield.component_id)
+        .await
+        .expect("could not get component view");
+    assert_eq!(
+        serde_json::json!["vault-sun"],
+        starfield_view.properties["domain"]["universe"]["galaxies"][0]["sun"]
+    );
+}
@@ -0,0 +1,41 @@
+use dal::builtins::schema::migrate_builtin;
+use dal::{Builtin, BuiltinsError, DalContext, Schema, StandardModel};
+use dal_test::test;
+
+#[test]
+async fn parse_builtin_names(_ctx: &DalContext) {
+    let builtins = Builtin::parse_names(&["docker", " aws-ec2", "generic-frame"])
+        .expect("cannot parse builtin names");
+    assert_eq!(
+        vec![Builtin::Docker, Builtin::AwsEc2, Builtin::GenericFrame],
+        builtins
+    );
+
+    let err = Builtin::parse_names(&["docker", "kubernetes"])
+        .expect_err("unknown builtin should not parse");
+    assert!(matches!(err, BuiltinsError::UnknownBuiltin(name) if name == "kubernetes"));
+}
+
+#[test]
+async fn migrating_a_builtin_records_its_fingerprint(ctx: &DalContext) {
+    migrate_builtin(ctx, Builtin::AwsS3)
+        .await
+        .expect("cannot migrate builtin");
+    let schemas = Schema::find_by_attr(ctx, "name", &"S3 Bucket".to_string())
+        .await
+        .expect("cannot find schemas");
+    assert_eq!(1, schemas.len());
+
+    let row = ctx
+        .txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .query_opt(
+            "SELECT fingerprint FROM builtin_fingerprints WHERE name = $1",
+            &[&"aws-s3"],
+        )
+        .await
+        .expect("cannot query fingerprints");
+    assert!(row.is_some());
+}
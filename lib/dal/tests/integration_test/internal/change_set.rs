@@ -1,5 +1,15 @@
-use dal::{ChangeSet, ChangeSetStatus, DalContext, Visibility};
-use dal_test::{helpers::create_change_set, test, DalContextHeadMutRef, DalContextHeadRef};
+use chrono::{Duration, FixedOffset};
+use dal::{
+    ChangeSet, ChangeSetApplySchedule, ChangeSetApplyScheduleError, ChangeSetApplyScheduleStatus,
+    ChangeSetError, ChangeSetReview, ChangeSetReviewError, ChangeSetReviewStatus, ChangeSetStatus,
+    DalContext, HistoryActor, StandardModel, Visibility, WorkspaceSignup,
+};
+use dal_test::{
+    helpers::{create_change_set, create_user},
+    test,
+    test_harness::create_component_and_schema,
+    DalContextHeadMutRef, DalContextHeadRef,
+};
 
 #[test]
 async fn new(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
@@ -35,6 +45,237 @@ async fn apply(ctx: &mut DalContext) {
     ctx.update_visibility(Visibility::new_head(false));
 }
 
+#[test]
+async fn apply_requires_approvals(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let mut workspace = nw.workspace.clone();
+    workspace
+        .set_required_change_set_approvals(ctx, 1)
+        .await
+        .expect("could not set required approvals");
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not perform get by pk")
+        .expect("could not get change set");
+
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::Review(
+            ChangeSetReviewError::MissingApprovals(..)
+        ))
+    ));
+
+    nw.user
+        .associate_workspace(ctx, *nw.workspace.pk())
+        .await
+        .expect("could not associate user with workspace");
+    let reviews = ChangeSetReview::request(ctx, change_set.pk, vec![nw.user.pk()])
+        .await
+        .expect("could not request review");
+    assert_eq!(1, reviews.len());
+    assert_eq!(&ChangeSetReviewStatus::Pending, reviews[0].status());
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    ChangeSetReview::reject(ctx, change_set.pk, Some("not yet".to_string()))
+        .await
+        .expect("could not reject change set");
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::Review(ChangeSetReviewError::Rejected(..)))
+    ));
+
+    let review = ChangeSetReview::approve(ctx, change_set.pk, None)
+        .await
+        .expect("could not approve change set");
+    assert_eq!(&ChangeSetReviewStatus::Approved, review.status());
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply approved change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+
+    ctx.update_visibility(Visibility::new_head(false));
+}
+
+#[test]
+async fn reviewers_cannot_review_their_own_change_sets(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let author = create_user(ctx).await;
+    let requester = create_user(ctx).await;
+    for user in [&author, &requester] {
+        user.associate_workspace(ctx, *nw.workspace.pk())
+            .await
+            .expect("could not associate user with workspace");
+    }
+    ctx.update_history_actor(HistoryActor::User(author.pk()));
+    let change_set = create_change_set(ctx).await;
+    assert_eq!(Some(author.pk()), change_set.created_by_user_pk);
+
+    ctx.update_history_actor(HistoryActor::User(requester.pk()));
+    let result = ChangeSetReview::request(ctx, change_set.pk, vec![requester.pk()]).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetReviewError::ReviewerIsRequester(..))
+    ));
+    let result = ChangeSetReview::request(ctx, change_set.pk, vec![author.pk()]).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetReviewError::ReviewerIsAuthor(..))
+    ));
+
+    let outsider = create_user(ctx).await;
+    let result = ChangeSetReview::request(ctx, change_set.pk, vec![outsider.pk()]).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetReviewError::ReviewerNotInWorkspace(..))
+    ));
+
+    ctx.update_history_actor(HistoryActor::User(author.pk()));
+    let result = ChangeSetReview::approve(ctx, change_set.pk, None).await;
+    assert!(matches!(result, Err(ChangeSetReviewError::NotReviewer(..))));
+    assert!(ChangeSetReview::list_for_change_set(ctx, change_set.pk)
+        .await
+        .expect("could not list reviews")
+        .is_empty());
+}
+
+#[test]
+async fn requesting_a_review_again_keeps_a_rejection(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let mut workspace = nw.workspace.clone();
+    workspace
+        .set_required_change_set_approvals(ctx, 1)
+        .await
+        .expect("could not set required approvals");
+    let reviewer = create_user(ctx).await;
+    reviewer
+        .associate_workspace(ctx, *nw.workspace.pk())
+        .await
+        .expect("could not associate user with workspace");
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not perform get by pk")
+        .expect("could not get change set");
+
+    ChangeSetReview::request(ctx, change_set.pk, vec![reviewer.pk()])
+        .await
+        .expect("could not request review");
+    let requester = *ctx.history_actor();
+    ctx.update_history_actor(HistoryActor::User(reviewer.pk()));
+    ChangeSetReview::reject(ctx, change_set.pk, Some("not yet".to_string()))
+        .await
+        .expect("could not reject change set");
+
+    ctx.update_history_actor(requester);
+    let reviews = ChangeSetReview::request(ctx, change_set.pk, vec![reviewer.pk()])
+        .await
+        .expect("could not request review again");
+    assert_eq!(&ChangeSetReviewStatus::Rejected, reviews[0].status());
+    assert_eq!(&Some("not yet".to_string()), reviews[0].comment());
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::Review(ChangeSetReviewError::Rejected(..)))
+    ));
+
+    ctx.update_visibility(Visibility::new_head(false));
+}
+
+#[test]
+async fn schedule_apply(ctx: &mut DalContext) {
+    let change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not perform get by pk")
+        .expect("could not get change set");
+    let offset = FixedOffset::east_opt(2 * 3600).expect("invalid offset");
+
+    let result = change_set
+        .schedule_apply(ctx, (ctx.now() - Duration::hours(1)).with_timezone(&offset))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::ApplySchedule(
+            ChangeSetApplyScheduleError::InPast(_)
+        ))
+    ));
+
+    let apply_at = (ctx.now() + Duration::hours(1)).with_timezone(&offset);
+    let mut schedule = change_set
+        .schedule_apply(ctx, apply_at)
+        .await
+        .expect("could not schedule apply");
+    assert_eq!(&ChangeSetApplyScheduleStatus::Scheduled, schedule.status());
+    assert_eq!(
+        apply_at,
+        schedule.apply_at_local().expect("invalid utc offset")
+    );
+    assert_eq!(
+        offset,
+        *schedule
+            .apply_at_local()
+            .expect("invalid utc offset")
+            .offset()
+    );
+
+    let result = change_set.schedule_apply(ctx, apply_at).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::ApplySchedule(
+            ChangeSetApplyScheduleError::AlreadyScheduled(_)
+        ))
+    ));
+    let pending = ChangeSetApplySchedule::list_pending(ctx)
+        .await
+        .expect("could not list pending applies");
+    assert_eq!(vec![schedule.clone()], pending);
+
+    let due = ChangeSetApplySchedule::claim_due(ctx, ctx.now())
+        .await
+        .expect("could not claim due applies");
+    assert!(!due.iter().any(|due| due.pk() == schedule.pk()));
+
+    schedule
+        .cancel(ctx)
+        .await
+        .expect("could not cancel scheduled apply");
+    assert_eq!(&ChangeSetApplyScheduleStatus::Canceled, schedule.status());
+    assert!(
+        ChangeSetApplySchedule::find_pending_for_change_set(ctx, change_set.pk)
+            .await
+            .expect("could not find pending apply")
+            .is_none()
+    );
+
+    let schedule = change_set
+        .schedule_apply(ctx, apply_at)
+        .await
+        .expect("could not schedule apply again");
+    let mut due = ChangeSetApplySchedule::claim_due(ctx, ctx.now() + Duration::hours(2))
+        .await
+        .expect("could not claim due applies")
+        .into_iter()
+        .find(|due| due.pk() == schedule.pk())
+        .expect("scheduled apply is not due");
+    assert_eq!(&ChangeSetApplyScheduleStatus::Started, due.status());
+    let result = due.clone().cancel(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetApplyScheduleError::NotScheduled(..))
+    ));
+
+    assert!(due
+        .perform(ctx)
+        .await
+        .expect("could not perform scheduled apply"));
+    assert_eq!(&ChangeSetApplyScheduleStatus::Applied, due.status());
+    let change_set = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("could not perform get by pk")
+        .expect("could not get change set");
+    assert_eq!(&ChangeSetStatus::Applied, &change_set.status);
+
+    ctx.update_visibility(Visibility::new_head(false));
+}
+
 #[test]
 async fn list_open(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
     let a_change_set = create_change_set(ctx).await;
@@ -84,3 +325,37 @@ async fn get_by_pk(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
         .expect("change set pk should exist");
     assert_eq!(&change_set, &result);
 }
+
+#[test]
+async fn qualification_summary(ctx: &DalContext) {
+    let component = create_component_and_schema(ctx).await;
+    let component_name = component
+        .name(ctx)
+        .await
+        .expect("could not get component name");
+
+    let summary = ChangeSet::qualification_summary(ctx)
+        .await
+        .expect("could not get qualification summary");
+    let component_summary = summary
+        .components()
+        .iter()
+        .find(|summary| summary.component_id() == *component.id())
+        .expect("component not summarized");
+    assert_eq!(component_name, component_summary.component_name());
+    // Every component has the "All fields are valid" qualification
+    assert!(component_summary.total() >= 1);
+    assert_eq!(
+        summary.components().len() as i64,
+        summary.succeeded() + summary.warned() + summary.failed()
+    );
+
+    let published = ChangeSet::publish_qualification_summary(ctx)
+        .await
+        .expect("could not publish qualification summary");
+    assert_eq!(Some(&summary), published.as_ref());
+    let published = ChangeSet::publish_qualification_summary(ctx)
+        .await
+        .expect("could not publish qualification summary");
+    assert_eq!(None, published);
+}
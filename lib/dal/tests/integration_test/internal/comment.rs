@@ -0,0 +1,76 @@
+use dal::{
+    Comment, CommentError, CommentTarget, DalContext, HistoryActor, StandardModel, WorkspaceSignup,
+};
+use dal_test::{test, test_harness::create_component_and_schema};
+
+#[test]
+async fn new_edit_and_remove(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let component = create_component_and_schema(ctx).await;
+    let component_target = CommentTarget::Component {
+        component_id: *component.id(),
+    };
+    let prop_target = CommentTarget::Prop {
+        component_id: *component.id(),
+        prop_path: "/root/domain/image".to_string(),
+    };
+
+    ctx.update_history_actor(HistoryActor::SystemInit);
+    let result = Comment::new(ctx, component_target.clone(), "hello").await;
+    assert!(matches!(result, Err(CommentError::NoUserActor)));
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let result = Comment::new(ctx, component_target.clone(), "  ").await;
+    assert!(matches!(result, Err(CommentError::EmptyBody)));
+
+    let mut comment = Comment::new(ctx, component_target.clone(), "is this the right image?")
+        .await
+        .expect("could not create comment");
+    assert_eq!(Some(nw.user.pk()), comment.author_user_pk());
+    Comment::new(ctx, prop_target.clone(), "pin the tag")
+        .await
+        .expect("could not create comment");
+
+    let comments = Comment::list_for_target(ctx, &component_target)
+        .await
+        .expect("could not list comments");
+    assert_eq!(vec![comment.clone()], comments);
+    let comments = Comment::list_for_target(ctx, &prop_target)
+        .await
+        .expect("could not list comments");
+    assert_eq!(1, comments.len());
+    assert_eq!("pin the tag", comments[0].body());
+
+    comment
+        .edit(ctx, "it is the right image")
+        .await
+        .expect("could not edit comment");
+    assert_eq!("it is the right image", comment.body());
+
+    comment.remove(ctx).await.expect("could not remove comment");
+    let comments = Comment::list_for_target(ctx, &component_target)
+        .await
+        .expect("could not list comments");
+    assert!(comments.is_empty());
+}
+
+#[test]
+async fn only_author_can_edit(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let component = create_component_and_schema(ctx).await;
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let mut comment = Comment::new(
+        ctx,
+        CommentTarget::Component {
+            component_id: *component.id(),
+        },
+        "hello",
+    )
+    .await
+    .expect("could not create comment");
+
+    ctx.update_history_actor(HistoryActor::SystemInit);
+    let result = comment.edit(ctx, "goodbye").await;
+    assert!(matches!(result, Err(CommentError::NotAuthor(_))));
+    let result = comment.remove(ctx).await;
+    assert!(matches!(result, Err(CommentError::NotAuthor(_))));
+}
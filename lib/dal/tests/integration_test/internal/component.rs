@@ -2,10 +2,11 @@ use dal::edge::EdgeKind;
 use dal::schema::variant::root_prop::SiPropChild;
 use dal::socket::SocketEdgeKind;
 use dal::{
-    func::backend::js_action::ActionRunResult, generate_name, AttributePrototypeArgument,
-    AttributeReadContext, AttributeValue, ChangeSet, ChangeSetStatus, Component, ComponentType,
-    ComponentView, Connection, DalContext, Edge, ExternalProvider, InternalProvider, Prop, PropId,
-    PropKind, SchemaVariant, Socket, SocketArity, StandardModel, Visibility,
+    component::resource::ResourceHealth, func::backend::js_action::ActionRunResult, generate_name,
+    AttributePrototypeArgument, AttributeReadContext, AttributeValue, ChangeSet, ChangeSetStatus,
+    Component, ComponentType, ComponentView, Connection, DalContext, Edge, ExternalProvider,
+    InternalProvider, Prop, PropId, PropKind, SchemaVariant, Socket, SocketArity, StandardModel,
+    Visibility,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::{
@@ -19,10 +20,14 @@ use dal_test::{
 use pretty_assertions_sorted::assert_eq;
 use veritech_client::ResourceStatus;
 
+mod bulk_update;
 mod code;
 mod confirmation;
+mod label;
+mod lifecycle;
 mod qualification;
 mod resource;
+mod snapshot;
 mod validation;
 mod view;
 
@@ -93,11 +98,11 @@ async fn find_type_attribute_value_and_set_type(ctx: &mut DalContext) {
         .await
         .expect("cannot finalize schema variant");
 
-    let new_change_set = ChangeSet::new(ctx, generate_name(), None)
+    let new_change_set = ChangeSet::new(ctx, generate_name(ctx), None)
         .await
         .expect("could not create new change set");
     ctx.update_visibility(Visibility::new(new_change_set.pk, None));
-    let (component, _) = Component::new(ctx, generate_name(), *schema_variant.id())
+    let (component, _) = Component::new(ctx, generate_name(ctx), *schema_variant.id())
         .await
         .expect("could not create component");
     let component_id = *component.id();
@@ -336,7 +341,7 @@ async fn dependent_values_resource_intelligence(mut octx: DalContext) {
     .await
     .expect("could not create attribute prototype argument");
 
-    let new_change_set = ChangeSet::new(ctx, generate_name(), None)
+    let new_change_set = ChangeSet::new(ctx, generate_name(ctx), None)
         .await
         .expect("could not create new change set");
     ctx.update_visibility(Visibility::new(new_change_set.pk, None));
@@ -418,6 +423,7 @@ async fn dependent_values_resource_intelligence(mut octx: DalContext) {
             ctx,
             ActionRunResult {
                 status: ResourceStatus::Ok,
+                health: ResourceHealth::Ok,
                 payload: Some(serde_json::json![{ "quantum": true }]),
                 logs: Default::default(),
                 message: Default::default(),
@@ -447,6 +453,7 @@ async fn dependent_values_resource_intelligence(mut octx: DalContext) {
                 "protected": false
             },
             "resource": {
+                "health": "ok",
                 "logs": [],
                 "payload": { "quantum": true },
                 "status": "ok",
@@ -464,6 +471,7 @@ async fn dependent_values_resource_intelligence(mut octx: DalContext) {
             },
             "domain": {
                 "u12a": {
+                    "health": "ok",
                     "logs": [],
                     "payload": { "quantum": true },
                     "status": "ok",
@@ -495,6 +503,7 @@ async fn dependent_values_resource_intelligence(mut octx: DalContext) {
                 "protected": false
             },
             "resource": {
+                "health": "ok",
                 "logs": [],
                 "payload": { "quantum": true },
                 "status": "ok",
@@ -512,6 +521,7 @@ async fn dependent_values_resource_intelligence(mut octx: DalContext) {
             },
             "domain": {
                 "u12a": {
+                    "health": "ok",
                     "logs": [],
                     "payload": { "quantum": true },
                     "status": "ok",
@@ -529,7 +539,7 @@ async fn create_delete_and_restore_components(ctx: &mut DalContext) {
     // Restoration is only a well defined operation for objects that existed on HEAD at some point
     // so for this test, we need to create and merge the component before running delete and restore
 
-    let mut change_set = ChangeSet::new(ctx, generate_name(), None)
+    let mut change_set = ChangeSet::new(ctx, generate_name(ctx), None)
         .await
         .expect("could not create new change set");
     ctx.update_visibility(Visibility::new(change_set.pk, None));
@@ -648,7 +658,7 @@ async fn create_delete_and_restore_components(ctx: &mut DalContext) {
         .await
         .expect("could not commit & run jobs");
 
-    let change_set_2 = ChangeSet::new(ctx, generate_name(), None)
+    let change_set_2 = ChangeSet::new(ctx, generate_name(ctx), None)
         .await
         .expect("could not create new change set");
     ctx.update_visibility(Visibility::new(change_set_2.pk, None));
@@ -0,0 +1,78 @@
+use dal::component::AttributeUpdate;
+use dal::{Component, ComponentError, ComponentView, DalContext, StandardModel};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+use super::view::create_schema_with_object_and_string_prop;
+
+#[test]
+async fn update_attributes_bulk(ctx: &DalContext) {
+    let (_schema, schema_variant, _queen_prop, _killer_prop, _bohemian_prop, _root_prop) =
+        create_schema_with_object_and_string_prop(ctx).await;
+    let (component, _) = Component::new(ctx, "bulk", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+
+    let updated = Component::update_attributes_bulk(
+        ctx,
+        *component.id(),
+        vec![
+            AttributeUpdate::new("/root/domain/queen", Some(serde_json::json![{}])),
+            AttributeUpdate::new(
+                "/root/domain/queen/bohemian_rhapsody",
+                Some(serde_json::json!["Galileo"]),
+            ),
+            AttributeUpdate::new(
+                "/root/domain/queen/killer_queen",
+                Some(serde_json::json!["woohoo"]),
+            ),
+        ],
+    )
+    .await
+    .expect("could not update attributes");
+    assert_eq!(3, updated.len());
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let component_view = ComponentView::new(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        serde_json::json![{
+            "si": {
+                "name": "bulk",
+                "type": "component",
+                "protected": false,
+            },
+            "domain": {
+                "queen": {
+                    "killer_queen": "woohoo",
+                    "bohemian_rhapsody": "Galileo"
+                }
+            }
+        }], // expected
+        component_view.properties, // actual
+    );
+}
+
+#[test]
+async fn update_attributes_bulk_rejects_invalid_json_pointer(ctx: &DalContext) {
+    let (_schema, schema_variant, _queen_prop, _killer_prop, _bohemian_prop, _root_prop) =
+        create_schema_with_object_and_string_prop(ctx).await;
+    let (component, _) = Component::new(ctx, "bulk", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+
+    let result = Component::update_attributes_bulk(
+        ctx,
+        *component.id(),
+        vec![AttributeUpdate::new(
+            "root/domain/queen",
+            Some(serde_json::json![{}]),
+        )],
+    )
+    .await;
+    assert!(matches!(result, Err(ComponentError::InvalidJsonPointer(_))));
+}
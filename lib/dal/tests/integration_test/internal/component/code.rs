@@ -1,3 +1,4 @@
+use dal::component::code::CodeArtifactView;
 use dal::component::ComponentKind;
 use dal::func::argument::{FuncArgument, FuncArgumentKind};
 use dal::schema::variant::leaves::LeafKind;
@@ -159,6 +160,152 @@ async fn add_code_generation_and_list_code_views(ctx: &DalContext) {
     assert_eq!(Some("poop: canoe\n".to_string()), code_view.code);
 }
 
+#[test]
+async fn code_generation_artifacts(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let poop_prop = Prop::new(
+        ctx,
+        "poop",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+
+    let mut func = Func::new(
+        ctx,
+        "test:codeGenerationWithArtifacts",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::CodeGeneration,
+    )
+    .await
+    .expect("could not create func");
+    let code = "function generateWithArtifacts(input) {
+      return {
+        format: \"yaml\",
+        code: YAML.stringify(input.domain),
+        artifacts: [
+          {
+            name: \"poop.txt\",
+            mimeType: \"text/plain\",
+            content: input.domain.poop ?? \"\",
+            metadata: { flavor: \"plain\" },
+          },
+        ],
+      };
+    }";
+    func.set_code_plaintext(ctx, Some(code))
+        .await
+        .expect("set code");
+    func.set_handler(ctx, Some("generateWithArtifacts"))
+        .await
+        .expect("set handler");
+    let func_argument =
+        FuncArgument::new(ctx, "domain", FuncArgumentKind::Object, None, *func.id())
+            .await
+            .expect("could not create func argument");
+    SchemaVariant::add_leaf(
+        ctx,
+        *func.id(),
+        *schema_variant.id(),
+        None,
+        LeafKind::CodeGeneration,
+        vec![LeafInput {
+            location: LeafInputLocation::Domain,
+            func_argument_id: *func_argument.id(),
+        }],
+    )
+    .await
+    .expect("could not add code generation");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (component, _) = Component::new(ctx, "component", *schema_variant.id())
+        .await
+        .expect("cannot create component");
+
+    let read_context = AttributeReadContext {
+        prop_id: Some(*poop_prop.id()),
+        component_id: Some(*component.id()),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("could not perform find for context")
+        .expect("attribute value not found");
+    let parent_attribute_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await
+        .expect("could not perform find parent attribute value")
+        .expect("no parent attribute value found");
+    let context = AttributeContextBuilder::from(read_context)
+        .to_context()
+        .expect("could not convert builder to attribute context");
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        Some(*parent_attribute_value.id()),
+        context,
+        Some(serde_json::json!["canoe"]),
+        None,
+    )
+    .await
+    .expect("could not perform update for context");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let artifacts = Component::list_code_artifacts(ctx, *component.id())
+        .await
+        .expect("could not list code artifacts for component");
+    assert_eq!(
+        vec![CodeArtifactView {
+            code_generation: "test:codeGenerationWithArtifacts".to_owned(),
+            name: "poop.txt".to_owned(),
+            mime_type: "text/plain".to_owned(),
+            size: 5,
+            metadata: [("flavor".to_owned(), "plain".to_owned())].into(),
+        }],
+        artifacts
+    );
+
+    let artifact = Component::find_code_artifact(
+        ctx,
+        *component.id(),
+        "test:codeGenerationWithArtifacts",
+        "poop.txt",
+    )
+    .await
+    .expect("could not find code artifact")
+    .expect("code artifact not found");
+    assert_eq!("text/plain", artifact.mime_type);
+    assert_eq!(b"canoe".to_vec(), artifact.content);
+
+    assert!(Component::find_code_artifact(
+        ctx,
+        *component.id(),
+        "test:codeGenerationWithArtifacts",
+        "missing.txt",
+    )
+    .await
+    .expect("could not find code artifact")
+    .is_none());
+}
+
 #[test]
 async fn all_code_generation_attribute_values(ctx: &DalContext) {
     // Create two schemas and variants.
@@ -1,3 +1,4 @@
+use dal::component::resource::ResourceHealth;
 use dal::func::backend::js_action::ActionRunResult;
 use dal::job::definition::{FixItem, FixesJob};
 
@@ -25,7 +26,7 @@ async fn add_and_run_confirmations(mut octx: DalContext) {
         .default_schema_variant_id()
         .expect("could not get default variant id");
 
-    let new_change_set = ChangeSet::new(ctx, generate_name(), None)
+    let new_change_set = ChangeSet::new(ctx, generate_name(ctx), None)
         .await
         .expect("could not create new change set");
     ctx.update_visibility(Visibility::new(new_change_set.pk, None));
@@ -86,6 +87,7 @@ async fn add_and_run_confirmations(mut octx: DalContext) {
             ctx,
             ActionRunResult {
                 status: ResourceStatus::Ok,
+                health: ResourceHealth::Ok,
                 payload: Some(serde_json::json![{ "poop": true }]),
                 message: None,
                 logs: vec![],
@@ -116,6 +118,7 @@ async fn add_and_run_confirmations(mut octx: DalContext) {
                 "name": "component"
             },
             "resource": {
+                "health": "ok",
                 "logs": [],
                 "status": "ok",
                 "payload": { "poop": true },
@@ -137,6 +140,7 @@ async fn add_and_run_confirmations(mut octx: DalContext) {
             ctx,
             ActionRunResult {
                 status: ResourceStatus::Ok,
+                health: ResourceHealth::Unknown,
                 payload: None,
                 message: None,
                 logs: vec![],
@@ -167,6 +171,7 @@ async fn add_and_run_confirmations(mut octx: DalContext) {
                 "name": "component"
             },
             "resource": {
+                "health": "unknown",
                 "logs": [],
                 "status": "ok"
             },
@@ -197,7 +202,7 @@ async fn list_confirmations(mut octx: DalContext) {
         .default_schema_variant_id()
         .expect("could not get default variant id");
 
-    let new_change_set = ChangeSet::new(ctx, generate_name(), None)
+    let new_change_set = ChangeSet::new(ctx, generate_name(ctx), None)
         .await
         .expect("could not create new change set");
     ctx.update_visibility(Visibility::new(new_change_set.pk, None));
@@ -332,6 +337,7 @@ async fn list_confirmations(mut octx: DalContext) {
                 "name": "component",
             },
             "resource": {
+                "health": "unknown",
                 "logs": [],
                 "status": "ok"
             },
@@ -356,6 +362,7 @@ async fn list_confirmations(mut octx: DalContext) {
             ctx,
             ActionRunResult {
                 status: ResourceStatus::Ok,
+                health: ResourceHealth::Unknown,
                 payload: None,
                 message: None,
                 logs: vec![],
@@ -416,6 +423,7 @@ async fn list_confirmations(mut octx: DalContext) {
                 "name": "component",
             },
             "resource": {
+                "health": "unknown",
                 "logs": [],
                 "status": "ok"
             },
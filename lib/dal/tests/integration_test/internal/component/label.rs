@@ -0,0 +1,89 @@
+use dal::{ComponentLabel, ComponentLabelError, DalContext, LabelSelector, StandardModel};
+use dal_test::{test, test_harness::create_component_and_schema};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn set_and_remove(ctx: &DalContext) {
+    let component = create_component_and_schema(ctx).await;
+    let component_id = *component.id();
+
+    let result = ComponentLabel::set(ctx, component_id, "env", "in prod").await;
+    assert!(matches!(result, Err(ComponentLabelError::InvalidValue(_))));
+
+    ComponentLabel::set(ctx, component_id, "env", "staging")
+        .await
+        .expect("could not set label");
+    let label = ComponentLabel::set(ctx, component_id, "env", "prod")
+        .await
+        .expect("could not set label");
+    assert_eq!("prod", label.value());
+    ComponentLabel::set(ctx, component_id, "team", "payments")
+        .await
+        .expect("could not set label");
+
+    let labels: Vec<(String, String)> = ComponentLabel::list_for_component(ctx, component_id)
+        .await
+        .expect("could not list labels")
+        .into_iter()
+        .map(|label| (label.key().to_string(), label.value().to_string()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "payments".to_string()),
+        ],
+        labels
+    );
+
+    assert!(ComponentLabel::remove(ctx, component_id, "team")
+        .await
+        .expect("could not remove label"));
+    assert!(!ComponentLabel::remove(ctx, component_id, "team")
+        .await
+        .expect("could not remove label"));
+    assert_eq!(
+        1,
+        ComponentLabel::list_for_component(ctx, component_id)
+            .await
+            .expect("could not list labels")
+            .len()
+    );
+}
+
+#[test]
+async fn select(ctx: &DalContext) {
+    let payments = create_component_and_schema(ctx).await;
+    let search = create_component_and_schema(ctx).await;
+    let unlabeled = create_component_and_schema(ctx).await;
+    for component in [&payments, &search] {
+        ComponentLabel::set(ctx, *component.id(), "env", "prod")
+            .await
+            .expect("could not set label");
+    }
+    ComponentLabel::set(ctx, *payments.id(), "team", "payments")
+        .await
+        .expect("could not set label");
+    ComponentLabel::set(ctx, *search.id(), "team", "search")
+        .await
+        .expect("could not set label");
+
+    let select = |selector: &'static str| async move {
+        let mut selected = selector
+            .parse::<LabelSelector>()
+            .expect("could not parse selector")
+            .select(ctx)
+            .await
+            .expect("could not select components");
+        selected.sort();
+        selected
+    };
+
+    assert_eq!(
+        vec![*payments.id()],
+        select("label.env=prod AND label.team=payments").await
+    );
+    let mut expected = vec![*search.id(), *unlabeled.id()];
+    expected.sort();
+    assert_eq!(expected, select("label.team!=payments").await);
+    assert_eq!(vec![*unlabeled.id()], select("!label.env").await);
+}
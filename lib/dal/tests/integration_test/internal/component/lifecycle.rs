@@ -0,0 +1,102 @@
+use dal::component::resource::ResourceHealth;
+use dal::func::backend::js_action::ActionRunResult;
+use dal::{
+    ActionKind, ChangeSet, ComponentLifecycle, ComponentLifecycleEvent, ComponentLifecycleState,
+    DalContext, StandardModel,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use veritech_client::ResourceStatus;
+
+/// Recommendation: run this test with the following environment variable:
+/// ```shell
+/// SI_TEST_BUILTIN_SCHEMAS=test
+/// ```
+#[test]
+async fn lifecycle_follows_fixes_and_resource_health(mut octx: DalContext) {
+    let ctx = &mut octx;
+
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "fallout", "fallout").await;
+    let component_id = fallout_bag.component_id;
+    assert_eq!(
+        ComponentLifecycleState::Draft, // expected
+        ComponentLifecycle::state_for_component(ctx, component_id)
+            .await
+            .expect("could not get lifecycle state"), // actual
+    );
+
+    // Deleting a resource which was never created does not apply.
+    let lifecycle = ComponentLifecycle::record(
+        ctx,
+        component_id,
+        ComponentLifecycleEvent::ApplyStarted(ActionKind::Delete),
+    )
+    .await
+    .expect("could not record lifecycle event");
+    assert!(lifecycle.is_none());
+
+    ComponentLifecycle::record(
+        ctx,
+        component_id,
+        ComponentLifecycleEvent::ApplyStarted(ActionKind::Create),
+    )
+    .await
+    .expect("could not record lifecycle event")
+    .expect("create does not apply to a draft");
+    let lifecycle = ComponentLifecycle::record(
+        ctx,
+        component_id,
+        ComponentLifecycleEvent::ApplySucceeded(ActionKind::Create),
+    )
+    .await
+    .expect("could not record lifecycle event")
+    .expect("success does not apply while applying");
+    assert_eq!(ComponentLifecycleState::Provisioned, *lifecycle.state());
+    assert_eq!(
+        ComponentLifecycleState::Applying,
+        *lifecycle.previous_state()
+    );
+
+    // An unhealthy resource degrades the component.
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not fetch change set by pk")
+        .expect("no change set found for pk");
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+    fallout_bag
+        .component(ctx)
+        .await
+        .set_resource(
+            ctx,
+            ActionRunResult {
+                status: ResourceStatus::Error,
+                health: ResourceHealth::Error,
+                payload: Some(serde_json::json![{ "poop": true }]),
+                message: Some("the vault door is jammed".to_string()),
+                logs: vec![],
+                last_synced: Default::default(),
+            },
+            true,
+        )
+        .await
+        .expect("could not set resource");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let lifecycles = ComponentLifecycle::list(ctx)
+        .await
+        .expect("could not list lifecycles");
+    assert_eq!(1, lifecycles.len());
+    assert_eq!(component_id, *lifecycles[0].component_id());
+    assert_eq!(ComponentLifecycleState::Degraded, *lifecycles[0].state());
+    assert_eq!(
+        ComponentLifecycleState::Provisioned,
+        *lifecycles[0].previous_state()
+    );
+}
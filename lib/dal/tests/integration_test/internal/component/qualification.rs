@@ -1,11 +1,14 @@
+use chrono::{DateTime, Duration, Utc};
 use dal::func::argument::{FuncArgument, FuncArgumentKind};
 use dal::schema::variant::leaves::LeafKind;
+use dal::tasks::QualificationRechecker;
 use dal::{
     attribute::context::AttributeContextBuilder,
     qualification::QualificationSubCheckStatus,
     schema::variant::leaves::{LeafInput, LeafInputLocation},
-    AttributeReadContext, AttributeValue, Component, ComponentView, DalContext, Func,
+    AttributeReadContext, AttributeValue, Component, ComponentId, ComponentView, DalContext, Func,
     FuncBackendKind, FuncBackendResponseType, Prop, PropKind, SchemaVariant, StandardModel,
+    TestClock,
 };
 use dal_test::test;
 use dal_test::test_harness::{create_schema, create_schema_variant_with_root};
@@ -186,6 +189,10 @@ async fn add_and_list_qualifications(ctx: &DalContext) {
         all_fields_valid_qualification.expect("could not find all fields valid qualification");
     let test_qualification = test_qualification.expect("could not find test qualification");
 
+    // Only qualifications which have actually been run have been checked.
+    assert!(all_fields_valid_qualification.last_checked.is_none());
+    assert!(test_qualification.last_checked.is_some());
+
     assert_eq!(
         all_fields_valid_qualification
             .result
@@ -201,3 +208,111 @@ async fn add_and_list_qualifications(ctx: &DalContext) {
         QualificationSubCheckStatus::Success,
     );
 }
+
+#[test]
+async fn stale_qualifications_are_run_again(ctx: &DalContext, clock: &TestClock) {
+    // Only qualifications on head are re-checked
+    let ctx = &ctx.clone_with_head();
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, _) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    let schema_variant_id = *schema_variant.id();
+    schema
+        .set_default_schema_variant_id(ctx, Some(schema_variant_id))
+        .await
+        .expect("cannot set default schema variant");
+
+    let mut qualification_func = Func::new(
+        ctx,
+        "test:recheckedQualification",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Qualification,
+    )
+    .await
+    .expect("could not create func");
+    qualification_func
+        .set_code_plaintext(
+            ctx,
+            Some("function isQualified(input) { return { result: 'success' }; }"),
+        )
+        .await
+        .expect("set code");
+    qualification_func
+        .set_handler(ctx, Some("isQualified"))
+        .await
+        .expect("set handler");
+    let func_argument = FuncArgument::new(
+        ctx,
+        "domain",
+        FuncArgumentKind::Object,
+        None,
+        *qualification_func.id(),
+    )
+    .await
+    .expect("could not create func argument");
+    let (_, mut qualification_prototype) = SchemaVariant::add_leaf(
+        ctx,
+        *qualification_func.id(),
+        schema_variant_id,
+        None,
+        LeafKind::Qualification,
+        vec![LeafInput {
+            location: LeafInputLocation::Domain,
+            func_argument_id: *func_argument.id(),
+        }],
+    )
+    .await
+    .expect("could not add qualification");
+    qualification_prototype
+        .set_recheck_interval_seconds(ctx, Some(60))
+        .await
+        .expect("could not set recheck interval");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    let (component, _) = Component::new(ctx, "component", schema_variant_id)
+        .await
+        .expect("cannot create component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let checked_at = last_checked(ctx, *component.id(), "test:recheckedQualification").await;
+
+    let rechecker = QualificationRechecker::new(ctx.services_context());
+    let stale = rechecker
+        .stale_qualifications()
+        .await
+        .expect("could not get stale qualifications");
+    assert!(!stale.contains_key(&workspace_pk));
+
+    clock.advance(Duration::seconds(61));
+    let stale = rechecker
+        .stale_qualifications()
+        .await
+        .expect("could not get stale qualifications");
+    assert!(stale.contains_key(&workspace_pk));
+
+    rechecker
+        .run()
+        .await
+        .expect("could not re-check stale qualifications");
+    let rechecked_at = last_checked(ctx, *component.id(), "test:recheckedQualification").await;
+    assert!(rechecked_at > checked_at, "qualification was not run again");
+}
+
+async fn last_checked(ctx: &DalContext, component_id: ComponentId, title: &str) -> DateTime<Utc> {
+    Component::list_qualifications(ctx, component_id)
+        .await
+        .expect("cannot list qualifications")
+        .into_iter()
+        .find(|qualification| qualification.title == title)
+        .expect("could not find qualification")
+        .last_checked
+        .expect("qualification was never checked")
+}
@@ -1,5 +1,11 @@
+use dal::component::resource::ResourceHealth;
+use dal::edge::EdgeKind;
 use dal::func::backend::js_action::ActionRunResult;
-use dal::{ChangeSet, DalContext, ResourceView};
+use dal::socket::SocketEdgeKind;
+use dal::{
+    ChangeSet, Component, ComponentType, Connection, DalContext, ResourceConflictPolicy,
+    ResourceConflictResolution, ResourceView, Socket, StandardModel,
+};
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
@@ -27,6 +33,7 @@ async fn list_resources(mut octx: DalContext) {
         fallout_bag.component_id,
         ResourceView {
             status: ResourceStatus::Ok,
+            health: ResourceHealth::Unknown,
             message: None,
             data: None,
             logs: vec![],
@@ -37,6 +44,7 @@ async fn list_resources(mut octx: DalContext) {
         starfield_bag.component_id,
         ResourceView {
             status: ResourceStatus::Ok,
+            health: ResourceHealth::Unknown,
             message: None,
             data: None,
             logs: vec![],
@@ -66,6 +74,7 @@ async fn list_resources(mut octx: DalContext) {
             ctx,
             ActionRunResult {
                 status: ResourceStatus::Ok,
+                health: ResourceHealth::Ok,
                 payload: Some(serde_json::json![{ "poop": true }]),
                 message: None,
                 logs: vec![],
@@ -80,10 +89,11 @@ async fn list_resources(mut octx: DalContext) {
         .expect("could not commit & run jobs");
 
     // Check the resources again.
-    expected
+    let fallout_view = expected
         .get_mut(&fallout_bag.component_id)
-        .expect("resource view not found")
-        .data = Some(serde_json::json![{ "poop": true}]);
+        .expect("resource view not found");
+    fallout_view.data = Some(serde_json::json![{ "poop": true}]);
+    fallout_view.health = ResourceHealth::Ok;
     let actual = ResourceView::list_with_deleted(ctx)
         .await
         .expect("could not get resource view(s)");
@@ -98,6 +108,7 @@ async fn list_resources(mut octx: DalContext) {
             ctx,
             ActionRunResult {
                 status: ResourceStatus::Ok,
+                health: ResourceHealth::Unknown,
                 payload: None,
                 message: None,
                 logs: vec![],
@@ -112,10 +123,11 @@ async fn list_resources(mut octx: DalContext) {
         .expect("could not commit & run jobs");
 
     // Check the resources again.
-    expected
+    let fallout_view = expected
         .get_mut(&fallout_bag.component_id)
-        .expect("resource view not found")
-        .data = None;
+        .expect("resource view not found");
+    fallout_view.data = None;
+    fallout_view.health = ResourceHealth::Unknown;
     let actual = ResourceView::list_with_deleted(ctx)
         .await
         .expect("could not get resource view(s)");
@@ -124,3 +136,139 @@ async fn list_resources(mut octx: DalContext) {
         actual,   // actual
     );
 }
+
+/// Recommendation: run this test with the following environment variable:
+/// ```shell
+/// SI_TEST_BUILTIN_SCHEMAS=test
+/// ```
+#[test]
+async fn resource_health_rolls_up_to_frames(mut octx: DalContext) {
+    let ctx = &mut octx;
+
+    let mut bagger = ComponentBagger::new();
+    let frame_bag = bagger.create_component(ctx, "app", "fallout").await;
+    let child_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+    frame_bag
+        .component(ctx)
+        .await
+        .set_type(ctx, ComponentType::ConfigurationFrame)
+        .await
+        .expect("could not set component type");
+
+    let from_socket = Socket::find_frame_socket_for_node(
+        ctx,
+        child_bag.node_id,
+        SocketEdgeKind::ConfigurationOutput,
+    )
+    .await
+    .expect("could not find frame socket for child");
+    let to_socket = Socket::find_frame_socket_for_node(
+        ctx,
+        frame_bag.node_id,
+        SocketEdgeKind::ConfigurationInput,
+    )
+    .await
+    .expect("could not find frame socket for frame");
+    Connection::new(
+        ctx,
+        child_bag.node_id,
+        *from_socket.id(),
+        frame_bag.node_id,
+        *to_socket.id(),
+        EdgeKind::Symbolic,
+    )
+    .await
+    .expect("could not connect to frame");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not fetch change set by pk")
+        .expect("no change set found for pk");
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+
+    // Nothing has been synced yet.
+    assert_eq!(
+        ResourceHealth::Unknown, // expected
+        Component::resource_health_rollup(ctx, frame_bag.component_id)
+            .await
+            .expect("could not roll up resource health"), // actual
+    );
+
+    let child_component = child_bag.component(ctx).await;
+    for (status, health) in [
+        (ResourceStatus::Ok, ResourceHealth::Ok),
+        (ResourceStatus::Warning, ResourceHealth::Warning),
+        (ResourceStatus::Error, ResourceHealth::Error),
+    ] {
+        child_component
+            .set_resource(
+                ctx,
+                ActionRunResult {
+                    status,
+                    health,
+                    payload: Some(serde_json::json![{ "poop": true }]),
+                    message: None,
+                    logs: vec![],
+                    last_synced: Default::default(),
+                },
+                true,
+            )
+            .await
+            .expect("could not set resource");
+        ctx.blocking_commit()
+            .await
+            .expect("could not commit & run jobs");
+
+        assert_eq!(
+            health, // expected
+            child_component
+                .resource_health(ctx)
+                .await
+                .expect("could not get resource health"), // actual
+        );
+        assert_eq!(
+            health, // expected
+            Component::resource_health_rollup(ctx, frame_bag.component_id)
+                .await
+                .expect("could not roll up resource health"), // actual
+        );
+    }
+}
+
+#[test]
+async fn resource_conflict_policy(mut octx: DalContext) {
+    let ctx = &mut octx;
+
+    let mut bagger = ComponentBagger::new();
+    let starfield_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+    let mut component = starfield_bag.component(ctx).await;
+    assert_eq!(
+        ResourceConflictPolicy::PreferModel,   // expected
+        *component.resource_conflict_policy(), // actual
+    );
+
+    component
+        .set_resource_conflict_policy(ctx, ResourceConflictPolicy::Manual)
+        .await
+        .expect("could not set resource conflict policy");
+    let component = starfield_bag.component(ctx).await;
+    assert_eq!(
+        ResourceConflictPolicy::Manual,        // expected
+        *component.resource_conflict_policy(), // actual
+    );
+
+    // Without a resource, there is nothing for the model to conflict with
+    assert_eq!(
+        ResourceConflictResolution::InSync, // expected
+        component
+            .resolve_resource_conflicts(ctx)
+            .await
+            .expect("could not resolve resource conflicts"), // actual
+    );
+}
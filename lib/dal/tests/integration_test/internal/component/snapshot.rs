@@ -0,0 +1,99 @@
+use dal::component::AttributeUpdate;
+use dal::{
+    Component, ComponentSnapshot, ComponentSnapshotTrigger, ComponentView, DalContext,
+    StandardModel,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn capture_and_restore(ctx: &mut DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "vault", "fallout").await;
+    let rads_prop = fallout_bag
+        .find_prop(ctx, &["root", "domain", "rads"])
+        .await;
+    fallout_bag
+        .update_attribute_value_for_prop(ctx, *rads_prop.id(), Some(serde_json::json![2]))
+        .await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let snapshot = ComponentSnapshot::capture(
+        ctx,
+        fallout_bag.component_id,
+        ComponentSnapshotTrigger::Manual,
+    )
+    .await
+    .expect("could not capture snapshot");
+    assert_eq!("vault", snapshot.name());
+    assert_eq!(
+        &[AttributeUpdate::new(
+            "/root/domain/rads",
+            Some(serde_json::json![2])
+        )],
+        snapshot.values()
+    );
+    assert_eq!(
+        serde_json::json![2],
+        snapshot.properties()["domain"]["rads"]
+    );
+
+    let component = Component::get_by_id(ctx, &fallout_bag.component_id)
+        .await
+        .expect("could not get component")
+        .expect("component not found");
+    component
+        .set_name(ctx, Some("wasteland"))
+        .await
+        .expect("could not set name");
+    fallout_bag
+        .update_attribute_value_for_prop(ctx, *rads_prop.id(), Some(serde_json::json![5]))
+        .await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    Component::restore_from_snapshot(ctx, snapshot.pk())
+        .await
+        .expect("could not restore snapshot");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let view = ComponentView::new(ctx, fallout_bag.component_id)
+        .await
+        .expect("could not get component view");
+    assert_eq!(serde_json::json!["vault"], view.properties["si"]["name"]);
+    assert_eq!(serde_json::json![2], view.properties["domain"]["rads"]);
+
+    // The state replaced by the restore is kept, so that the restore can be undone
+    let snapshots = ComponentSnapshot::list_for_component(ctx, fallout_bag.component_id)
+        .await
+        .expect("could not list snapshots");
+    assert_eq!(2, snapshots.len());
+    let before_restore = &snapshots[0];
+    assert_eq!(
+        ComponentSnapshotTrigger::BeforeRestore,
+        before_restore.trigger()
+    );
+    assert_eq!("wasteland", before_restore.name());
+
+    Component::restore_from_snapshot(ctx, before_restore.pk())
+        .await
+        .expect("could not restore snapshot");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let view = ComponentView::new(ctx, fallout_bag.component_id)
+        .await
+        .expect("could not get component view");
+    assert_eq!(
+        serde_json::json!["wasteland"],
+        view.properties["si"]["name"]
+    );
+    assert_eq!(serde_json::json![5], view.properties["domain"]["rads"]);
+}
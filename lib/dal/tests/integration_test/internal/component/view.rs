@@ -1,6 +1,7 @@
 use dal::{
-    schema::RootProp, AttributeContext, AttributeValue, Component, ComponentView, DalContext, Prop,
-    PropKind, Schema, SchemaVariant, StandardModel,
+    component::ComponentViewError, schema::RootProp, AttributeContext, AttributeReadContext,
+    AttributeValue, Component, ComponentId, ComponentView, ComponentViewCache, DalContext,
+    InternalProvider, Prop, PropId, PropKind, Schema, SchemaVariant, StandardModel,
 };
 use dal_test::{
     test,
@@ -1520,3 +1521,267 @@ async fn complex_nested_array_of_objects_with_a_map(ctx: &DalContext) {
         component_view.properties, // actual
     );
 }
+
+#[test]
+async fn missing_component(ctx: &DalContext) {
+    let component_id = ComponentId::generate();
+    let result = ComponentView::new(ctx, component_id).await;
+    assert!(matches!(
+        result,
+        Err(ComponentViewError::NotFound(id)) if id == component_id
+    ));
+}
+
+#[test]
+async fn cache_rebuilds_written_views(ctx: &DalContext) {
+    let (_schema, schema_variant, bohemian_prop, _killer_prop, root_prop) =
+        create_schema_with_string_props(ctx).await;
+    let (component, _) = Component::new(ctx, "capoeira", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let cache = ComponentViewCache::new(true);
+    let component_view = cache
+        .get_or_build(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(1, cache.len().await);
+    let cached_view = cache
+        .get_or_build(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(component_view.properties, cached_view.properties);
+
+    let mut base_attribute_context = AttributeContext::builder();
+    base_attribute_context.set_component_id(*component.id());
+    let domain_context = base_attribute_context
+        .clone()
+        .set_prop_id(root_prop.domain_prop_id)
+        .to_context()
+        .expect("cannot create domain AttributeContext");
+    let domain_value = AttributeValue::find_for_context(ctx, domain_context.into())
+        .await
+        .expect("could not fetch domain AttributeValue")
+        .expect("could not find domain AttributeValue");
+    let bohemian_context = base_attribute_context
+        .clone()
+        .set_prop_id(*bohemian_prop.id())
+        .to_context()
+        .expect("cannot create bohemian AttributeContext");
+    let bohemian_value = AttributeValue::find_for_context(ctx, bohemian_context.into())
+        .await
+        .expect("could not retrieve bohemian AttributeValue")
+        .expect("could not find bohemian AttributeValue");
+    let (_, _) = AttributeValue::update_for_context(
+        ctx,
+        *bohemian_value.id(),
+        Some(*domain_value.id()),
+        bohemian_context,
+        Some(serde_json::json!["Galileo"]),
+        None,
+    )
+    .await
+    .expect("could not update bohemian prop value");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let component_view = cache
+        .get_or_build(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        Some(&serde_json::json!["Galileo"]),
+        component_view
+            .properties
+            .pointer("/domain/bohemian_rhapsody"),
+    );
+
+    cache.invalidate(*component.id()).await;
+    assert!(cache.is_empty().await);
+}
+
+#[test]
+async fn cache_sees_writes_committed_out_of_order(ctx: &DalContext) {
+    let (_schema, schema_variant, bohemian_prop, _killer_prop, root_prop) =
+        create_schema_with_string_props(ctx).await;
+    let (component, _) = Component::new(ctx, "capoeira", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    set_bohemian_rhapsody(
+        ctx,
+        *component.id(),
+        *bohemian_prop.id(),
+        &root_prop,
+        "Galileo",
+    )
+    .await;
+    let galileo_func_binding_return_value_id = *view_value(ctx, *component.id(), root_prop.prop_id)
+        .await
+        .func_binding_return_value_id();
+    set_bohemian_rhapsody(
+        ctx,
+        *component.id(),
+        *bohemian_prop.id(),
+        &root_prop,
+        "Figaro",
+    )
+    .await;
+
+    let cache = ComponentViewCache::new(true);
+    let bohemian_rhapsody = |view: ComponentView| {
+        view.properties
+            .pointer("/domain/bohemian_rhapsody")
+            .cloned()
+    };
+    let component_view = cache
+        .get_or_build(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        Some(serde_json::json!["Figaro"]),
+        bohemian_rhapsody(component_view)
+    );
+
+    // The first of two other transactions to write to the component is the last to commit, so the
+    // latest update time of its rows is the same before and after it commits.
+    let builder = ctx.services_context().into_builder(false);
+    let request_context = ctx.access_builder().build(*ctx.visibility());
+    let first_ctx = builder
+        .build(request_context.clone())
+        .await
+        .expect("cannot build context");
+    let second_ctx = builder
+        .build(request_context)
+        .await
+        .expect("cannot build context");
+
+    let root_value = view_value(ctx, *component.id(), root_prop.prop_id).await;
+    first_ctx
+        .txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .execute(
+            "UPDATE attribute_values
+             SET func_binding_return_value_id = $1, updated_at = CLOCK_TIMESTAMP()
+             WHERE id = $2 AND visibility_change_set_pk = $3",
+            &[
+                &galileo_func_binding_return_value_id,
+                root_value.id(),
+                &ctx.visibility().change_set_pk,
+            ],
+        )
+        .await
+        .expect("cannot write view value");
+    second_ctx
+        .txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .execute(
+            "UPDATE attribute_values
+             SET updated_at = CLOCK_TIMESTAMP()
+             WHERE attribute_context_component_id = $1 AND visibility_change_set_pk = $2
+               AND id != $3",
+            &[
+                component.id(),
+                &ctx.visibility().change_set_pk,
+                root_value.id(),
+            ],
+        )
+        .await
+        .expect("cannot touch component values");
+    second_ctx.commit().await.expect("cannot commit");
+
+    let component_view = cache
+        .get_or_build(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        Some(serde_json::json!["Figaro"]),
+        bohemian_rhapsody(component_view)
+    );
+
+    first_ctx.commit().await.expect("cannot commit");
+    let component_view = cache
+        .get_or_build(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        Some(serde_json::json!["Galileo"]),
+        bohemian_rhapsody(component_view)
+    );
+}
+
+async fn set_bohemian_rhapsody(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    bohemian_prop_id: PropId,
+    root_prop: &RootProp,
+    value: &str,
+) {
+    let mut base_attribute_context = AttributeContext::builder();
+    base_attribute_context.set_component_id(component_id);
+    let domain_context = base_attribute_context
+        .clone()
+        .set_prop_id(root_prop.domain_prop_id)
+        .to_context()
+        .expect("cannot create domain AttributeContext");
+    let domain_value = AttributeValue::find_for_context(ctx, domain_context.into())
+        .await
+        .expect("could not fetch domain AttributeValue")
+        .expect("could not find domain AttributeValue");
+    let bohemian_context = base_attribute_context
+        .set_prop_id(bohemian_prop_id)
+        .to_context()
+        .expect("cannot create bohemian AttributeContext");
+    let bohemian_value = AttributeValue::find_for_context(ctx, bohemian_context.into())
+        .await
+        .expect("could not retrieve bohemian AttributeValue")
+        .expect("could not find bohemian AttributeValue");
+    AttributeValue::update_for_context(
+        ctx,
+        *bohemian_value.id(),
+        Some(*domain_value.id()),
+        bohemian_context,
+        Some(serde_json::json![value]),
+        None,
+    )
+    .await
+    .expect("could not update bohemian prop value");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+}
+
+/// The value a [`ComponentView`] is read from, which is the one of the internal provider of the
+/// root prop.
+async fn view_value(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    root_prop_id: PropId,
+) -> AttributeValue {
+    let internal_provider = InternalProvider::find_for_prop(ctx, root_prop_id)
+        .await
+        .expect("could not fetch root InternalProvider")
+        .expect("could not find root InternalProvider");
+    AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            internal_provider_id: Some(*internal_provider.id()),
+            component_id: Some(component_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("could not fetch root AttributeValue")
+    .expect("could not find root AttributeValue")
+}
@@ -0,0 +1,178 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+};
+
+use dal::{Component, DalContext, StandardModel, TransactionsError};
+use dal_test::{
+    helpers::faults::{pg_faults, SqlState},
+    test,
+    test_harness::create_component_and_schema,
+};
+
+#[test]
+async fn read_only_builder_falls_back_to_primary(ctx: &DalContext) {
+    let mut builder = ctx.services_context().into_builder(false);
+    builder.set_read_only();
+    let read_ctx = builder
+        .build_default()
+        .await
+        .expect("cannot build read-only context");
+
+    // Without read replicas configured, reads are served by the primary
+    let row = read_ctx
+        .txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .query_one("SELECT 1 AS one", &[])
+        .await
+        .expect("cannot query read-only context");
+    let one: i32 = row.get("one");
+    assert_eq!(1, one);
+}
+
+#[test]
+async fn rollback_to_savepoint_keeps_earlier_changes(ctx: &DalContext) {
+    let kept = create_component_and_schema(ctx).await;
+
+    let savepoint = ctx.savepoint().await.expect("cannot take savepoint");
+    let nats_pending_len = ctx
+        .txns()
+        .await
+        .expect("cannot get transactions")
+        .nats()
+        .pending_len()
+        .await;
+    let discarded = create_component_and_schema(ctx).await;
+    ctx.rollback_to_savepoint(savepoint)
+        .await
+        .expect("cannot rollback to savepoint");
+
+    assert!(Component::get_by_id(ctx, kept.id())
+        .await
+        .expect("cannot get component")
+        .is_some());
+    assert!(Component::get_by_id(ctx, discarded.id())
+        .await
+        .expect("cannot get component")
+        .is_none());
+    assert_eq!(
+        nats_pending_len,
+        ctx.txns()
+            .await
+            .expect("cannot get transactions")
+            .nats()
+            .pending_len()
+            .await
+    );
+}
+
+#[test]
+async fn run_in_savepoint(ctx: &DalContext) {
+    let released = ctx
+        .run_in_savepoint(|| async {
+            Ok::<_, TransactionsError>(create_component_and_schema(ctx).await)
+        })
+        .await
+        .expect("cannot run in savepoint");
+
+    let mut discarded = None;
+    let result = ctx
+        .run_in_savepoint(|| async {
+            discarded = Some(create_component_and_schema(ctx).await);
+            Err::<(), _>(TransactionsError::TxnCommit)
+        })
+        .await;
+    assert!(matches!(result, Err(TransactionsError::TxnCommit)));
+    let discarded = discarded.expect("closure did not run");
+
+    assert!(Component::get_by_id(ctx, released.id())
+        .await
+        .expect("cannot get component")
+        .is_some());
+    assert!(Component::get_by_id(ctx, discarded.id())
+        .await
+        .expect("cannot get component")
+        .is_none());
+}
+
+#[test]
+async fn run_with_retries_retries_transaction_conflicts(ctx: &DalContext) {
+    let attempts = AtomicU32::new(0);
+    let attempts = &attempts;
+    let discarded = Mutex::new(None);
+    let discarded_ref = &discarded;
+
+    let kept = ctx
+        .run_with_retries(|ctx| async move {
+            let component = create_component_and_schema(&ctx).await;
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                *discarded_ref.lock().expect("poisoned lock") = Some(component.clone());
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .batch_execute(
+                        "DO $$ BEGIN RAISE EXCEPTION 'conflict' \
+                         USING ERRCODE = 'serialization_failure'; END $$",
+                    )
+                    .await?;
+            }
+            Ok::<_, TransactionsError>(component)
+        })
+        .await
+        .expect("cannot run with retries");
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+
+    let discarded = discarded
+        .into_inner()
+        .expect("poisoned lock")
+        .expect("first attempt did not run");
+    assert!(Component::get_by_id(ctx, discarded.id())
+        .await
+        .expect("cannot get component")
+        .is_none());
+    assert!(Component::get_by_id(ctx, kept.id())
+        .await
+        .expect("cannot get component")
+        .is_some());
+}
+
+#[test]
+async fn run_with_retries_gives_up_on_other_errors(ctx: &DalContext) {
+    let attempts = AtomicU32::new(0);
+    let attempts = &attempts;
+
+    let result = ctx
+        .run_with_retries(|_| async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(TransactionsError::TxnCommit)
+        })
+        .await;
+    assert!(matches!(result, Err(TransactionsError::TxnCommit)));
+    assert_eq!(1, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+async fn run_with_retries_retries_injected_conflicts(ctx: &DalContext) {
+    let attempts = AtomicU32::new(0);
+    let attempts = &attempts;
+
+    let value = ctx
+        .run_with_retries(|ctx| async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                pg_faults(&ctx).fail_next_queries(1, SqlState::T_R_SERIALIZATION_FAILURE);
+            }
+            let row = ctx
+                .txns()
+                .await?
+                .pg()
+                .query_one("SELECT 1 AS value", &[])
+                .await?;
+            Ok::<_, TransactionsError>(row.get::<_, i32>("value"))
+        })
+        .await
+        .expect("cannot run with retries");
+    assert_eq!(1, value);
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
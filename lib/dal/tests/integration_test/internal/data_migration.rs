@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use dal::{
+    DalContext, DataMigration, DataMigrationDirection, DataMigrationError, DataMigrationResult,
+    DataMigrator,
+};
+use dal_test::test;
+
+const VERSION: i64 = 9001;
+
+struct FlagRows;
+
+#[async_trait]
+impl DataMigration for FlagRows {
+    fn version(&self) -> i64 {
+        VERSION
+    }
+
+    fn name(&self) -> &'static str {
+        "flag rows"
+    }
+
+    async fn up(&self, ctx: &DalContext) -> DataMigrationResult<u64> {
+        Ok(ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE data_migration_test_rows SET flagged = true WHERE NOT flagged",
+                &[],
+            )
+            .await?)
+    }
+
+    async fn down(&self, ctx: &DalContext) -> DataMigrationResult<u64> {
+        Ok(ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE data_migration_test_rows SET flagged = false WHERE flagged",
+                &[],
+            )
+            .await?)
+    }
+}
+
+async fn flagged_rows(ctx: &DalContext) -> i64 {
+    ctx.txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .query_one(
+            "SELECT count(*) AS count FROM data_migration_test_rows WHERE flagged",
+            &[],
+        )
+        .await
+        .expect("cannot count flagged rows")
+        .get("count")
+}
+
+async fn is_applied(ctx: &DalContext) -> bool {
+    DataMigrator::applied(ctx)
+        .await
+        .expect("cannot list applied data migrations")
+        .iter()
+        .any(|applied| applied.version == VERSION)
+}
+
+#[test]
+async fn migrate_dry_run_up_and_down(ctx: &DalContext) {
+    let pg = ctx.txns().await.expect("cannot get transactions").pg();
+    pg.execute(
+        "CREATE TABLE data_migration_test_rows (flagged boolean NOT NULL DEFAULT false)",
+        &[],
+    )
+    .await
+    .expect("cannot create table");
+    pg.execute(
+        "INSERT INTO data_migration_test_rows DEFAULT VALUES, DEFAULT VALUES, DEFAULT VALUES",
+        &[],
+    )
+    .await
+    .expect("cannot insert rows");
+    ctx.commit().await.expect("cannot commit");
+
+    let migrator = DataMigrator::new(vec![Box::new(FlagRows)]).expect("cannot create migrator");
+
+    let reports = migrator
+        .migrate_to(ctx, None, true)
+        .await
+        .expect("cannot dry run data migrations");
+    assert_eq!(1, reports.len());
+    assert_eq!(DataMigrationDirection::Up, reports[0].direction);
+    assert_eq!(3, reports[0].affected_rows);
+    assert!(reports[0].dry_run);
+    assert_eq!(0, flagged_rows(ctx).await);
+    assert!(!is_applied(ctx).await);
+
+    let reports = migrator
+        .migrate_to(ctx, None, false)
+        .await
+        .expect("cannot run data migrations");
+    assert_eq!(1, reports.len());
+    assert_eq!(3, flagged_rows(ctx).await);
+    assert!(is_applied(ctx).await);
+
+    let reports = migrator
+        .migrate_to(ctx, None, false)
+        .await
+        .expect("cannot run data migrations again");
+    assert!(reports.is_empty());
+
+    let reports = migrator
+        .migrate_to(ctx, Some(VERSION - 1), false)
+        .await
+        .expect("cannot revert data migrations");
+    assert_eq!(1, reports.len());
+    assert_eq!(DataMigrationDirection::Down, reports[0].direction);
+    assert_eq!(3, reports[0].affected_rows);
+    assert_eq!(0, flagged_rows(ctx).await);
+    assert!(!is_applied(ctx).await);
+}
+
+#[test]
+async fn duplicate_versions_are_rejected(_ctx: &DalContext) {
+    let result = DataMigrator::new(vec![Box::new(FlagRows), Box::new(FlagRows)]);
+    assert!(matches!(
+        result,
+        Err(DataMigrationError::DuplicateVersion(VERSION))
+    ));
+}
@@ -0,0 +1,95 @@
+use chrono::Utc;
+use dal::{job::consumer::JobInfo, DalContext, DeadLetteredJob, WorkspacePk};
+use dal_test::test;
+
+#[test]
+async fn record_list_and_replay(ctx: &DalContext) {
+    let job_info = JobInfo {
+        id: "01H0000000000000000000JOB1".to_string(),
+        kind: "DependentValuesUpdate".to_string(),
+        created_at: Utc::now(),
+        arg: serde_json::json!([[]]),
+        access_builder: ctx.access_builder(),
+        visibility: *ctx.visibility(),
+        blocking: false,
+        trace_context: Default::default(),
+    };
+
+    let dead_lettered_job = DeadLetteredJob::record(ctx, &job_info, "the job blew up")
+        .await
+        .expect("could not dead letter job");
+    assert_eq!(
+        ctx.tenancy().workspace_pk(),
+        *dead_lettered_job.workspace_pk()
+    );
+    assert_eq!("DependentValuesUpdate", dead_lettered_job.kind());
+    assert_eq!(1, *dead_lettered_job.attempts());
+    assert!(dead_lettered_job.replayed_at().is_none());
+
+    let list = DeadLetteredJob::list(ctx)
+        .await
+        .expect("could not list dead lettered jobs");
+    assert_eq!(1, list.len());
+    let mut found = DeadLetteredJob::get_by_pk(ctx, dead_lettered_job.pk())
+        .await
+        .expect("could not get dead lettered job")
+        .expect("dead lettered job not found");
+
+    found
+        .replay(ctx)
+        .await
+        .expect("could not replay dead lettered job");
+    assert!(found.replayed_at().is_some());
+
+    // The replayed job keeps its id, so failing again counts one more attempt
+    let dead_lettered_job = DeadLetteredJob::record(ctx, &job_info, "the job blew up again")
+        .await
+        .expect("could not dead letter job");
+    assert_eq!(found.pk(), dead_lettered_job.pk());
+    assert_eq!(2, *dead_lettered_job.attempts());
+    assert_eq!("the job blew up again", dead_lettered_job.error());
+    assert!(dead_lettered_job.replayed_at().is_none());
+}
+
+#[test]
+async fn list_all_and_stats(ctx: &DalContext) {
+    let job_info = JobInfo {
+        id: "01H0000000000000000000JOB2".to_string(),
+        kind: "RefreshJob".to_string(),
+        created_at: Utc::now(),
+        arg: serde_json::json!([]),
+        access_builder: ctx.access_builder(),
+        visibility: *ctx.visibility(),
+        blocking: false,
+        trace_context: Default::default(),
+    };
+    let dead_lettered_job = DeadLetteredJob::record(ctx, &job_info, "the job blew up")
+        .await
+        .expect("could not dead letter job");
+
+    let list = DeadLetteredJob::list_all(ctx, ctx.tenancy().workspace_pk())
+        .await
+        .expect("could not list dead lettered jobs");
+    assert_eq!(
+        vec![dead_lettered_job.pk()],
+        list.iter().map(|job| job.pk()).collect::<Vec<_>>()
+    );
+    let list = DeadLetteredJob::list_all(ctx, None)
+        .await
+        .expect("could not list dead lettered jobs");
+    assert!(list.iter().any(|job| job.pk() == dead_lettered_job.pk()));
+    let list = DeadLetteredJob::list_all(ctx, Some(WorkspacePk::generate()))
+        .await
+        .expect("could not list dead lettered jobs");
+    assert!(list.is_empty());
+
+    let stats = DeadLetteredJob::stats(ctx)
+        .await
+        .expect("could not count dead lettered jobs");
+    let refresh_stats = stats
+        .iter()
+        .find(|stats| stats.kind == "RefreshJob")
+        .expect("no stats for dead lettered refresh jobs");
+    assert!(refresh_stats.total >= 1);
+    assert!(refresh_stats.not_replayed >= 1);
+}
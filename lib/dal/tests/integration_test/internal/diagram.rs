@@ -1,8 +1,9 @@
 use dal::change_status::ChangeStatus;
+use dal::diagram::connection::Vertex;
 use dal::edge::EdgeKind;
 use dal::{
-    socket::SocketEdgeKind, Connection, DalContext, Diagram, DiagramEdgeView, Node, Socket,
-    StandardModel,
+    socket::SocketEdgeKind, Component, Connection, DalContext, Diagram, DiagramEdgeView,
+    DiagramError, Node, NodePosition, Socket, SocketType, SocketTypeMismatch, StandardModel,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::test;
@@ -152,3 +153,161 @@ async fn get_diagram_and_create_and_delete_connection(ctx: &DalContext) {
     // Check that no connections exist on the diagram.
     assert_eq!(diagram.edges().len(), 0);
 }
+
+#[test]
+async fn connection_requires_compatible_socket_types(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+
+    let mut output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+    let mut input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+
+    output_socket
+        .set_socket_type(ctx, Some(SocketType::new("Publisher", None)))
+        .await
+        .expect("could not set socket type");
+    input_socket
+        .set_socket_type(ctx, Some(SocketType::new("Game Studio", None)))
+        .await
+        .expect("could not set socket type");
+
+    let compatible_sockets =
+        Connection::list_compatible_sockets(ctx, fallout_bag.node_id, *output_socket.id())
+            .await
+            .expect("could not list compatible sockets");
+    assert!(!compatible_sockets.contains(&Vertex {
+        node_id: starfield_bag.node_id,
+        socket_id: *input_socket.id(),
+    }));
+
+    let result = Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        *output_socket.id(),
+        starfield_bag.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await;
+    match result {
+        Err(DiagramError::IncompatibleSockets(_, _, mismatch)) => assert_eq!(
+            SocketTypeMismatch::ProviderKind {
+                expected: "Game Studio".to_string(),
+                found: "Publisher".to_string(),
+            },
+            mismatch
+        ),
+        other => panic!("expected incompatible sockets, got {other:?}"),
+    }
+
+    output_socket
+        .set_socket_type(ctx, Some(SocketType::new("Game Studio", None)))
+        .await
+        .expect("could not set socket type");
+
+    let compatible_sockets =
+        Connection::list_compatible_sockets(ctx, fallout_bag.node_id, *output_socket.id())
+            .await
+            .expect("could not list compatible sockets");
+    assert!(compatible_sockets.contains(&Vertex {
+        node_id: starfield_bag.node_id,
+        socket_id: *input_socket.id(),
+    }));
+
+    Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        *output_socket.id(),
+        starfield_bag.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+}
+
+#[test]
+async fn move_and_delete_nodes(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "fallout", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+    for (node_id, x, y) in [
+        (fallout_bag.node_id, "123", "-10"),
+        (starfield_bag.node_id, "124", "-11"),
+    ] {
+        Node::get_by_id(ctx, &node_id)
+            .await
+            .expect("could not find node")
+            .expect("node not found")
+            .set_geometry(ctx, x, y, None::<&str>, None::<&str>)
+            .await
+            .expect("cannot set node geometry");
+    }
+
+    let result = Diagram::move_nodes(ctx, &[], 1.0, 1.0).await;
+    assert!(matches!(result, Err(DiagramError::NoNodesSelected)));
+
+    let positions = Diagram::move_nodes(
+        ctx,
+        &[
+            fallout_bag.node_id,
+            starfield_bag.node_id,
+            fallout_bag.node_id,
+        ],
+        10.0,
+        -5.5,
+    )
+    .await
+    .expect("could not move nodes");
+    assert_eq!(
+        vec![
+            NodePosition {
+                node_id: fallout_bag.node_id,
+                x: "133".to_string(),
+                y: "-15.5".to_string(),
+            },
+            NodePosition {
+                node_id: starfield_bag.node_id,
+                x: "134".to_string(),
+                y: "-16.5".to_string(),
+            },
+        ],
+        positions
+    );
+    let node = Node::get_by_id(ctx, &starfield_bag.node_id)
+        .await
+        .expect("could not find node")
+        .expect("node not found");
+    assert_eq!(("134", "-16.5"), (node.x(), node.y()));
+
+    let component_ids = Diagram::delete_nodes(ctx, &[fallout_bag.node_id, starfield_bag.node_id])
+        .await
+        .expect("could not delete nodes");
+    assert_eq!(
+        vec![fallout_bag.component_id, starfield_bag.component_id],
+        component_ids
+    );
+    for component_id in component_ids {
+        assert!(Component::get_by_id(ctx, &component_id)
+            .await
+            .expect("could not get component")
+            .is_none());
+    }
+}
@@ -4,6 +4,7 @@ use dal::{
     Connection, DalContext, Edge, Socket, StandardModel,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::helpers::graph_fixture::GraphFixture;
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
 
@@ -363,3 +364,87 @@ async fn create_multiple_connections_and_delete(ctx: &DalContext) {
             .expect("could not convert to value") // actual
     );
 }
+
+#[test]
+async fn list_for_components(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+    let lonely_bag = bagger.create_component(ctx, "lonely", "fallout").await;
+
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+
+    let edge = Edge::new(
+        ctx,
+        EdgeKind::Configuration,
+        starfield_bag.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(starfield_bag.component_id),
+        *input_socket.id(),
+        fallout_bag.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(fallout_bag.component_id),
+        *output_socket.id(),
+    )
+    .await
+    .expect("cannot create new edge");
+
+    // Either end of the edge finds it, and it is only listed once.
+    let edges = Edge::list_for_components(
+        ctx,
+        &[
+            fallout_bag.component_id,
+            starfield_bag.component_id,
+            lonely_bag.component_id,
+        ],
+    )
+    .await
+    .expect("could not list edges for components");
+    assert_eq!(edges, vec![edge]);
+
+    let edges = Edge::list_for_components(ctx, &[lonely_bag.component_id])
+        .await
+        .expect("could not list edges for components");
+    assert!(edges.is_empty());
+}
+
+#[test]
+async fn graph_fixture_connects_matching_sockets(ctx: &DalContext) {
+    let graph = GraphFixture::new(ctx)
+        .component("tail", "fallout")
+        .component("head", "starfield")
+        .component("lonely", "starfield")
+        .configures("tail", "head")
+        .configures_through("tail", "bethesda", "lonely")
+        .build()
+        .await;
+
+    // Both of the sockets of fallout match an input socket of starfield.
+    assert_eq!(2, graph.connections_between("tail", "head").len());
+    assert_eq!(1, graph.connections_between("tail", "lonely").len());
+    assert!(graph.connections_between("head", "tail").is_empty());
+
+    for head in ["head", "lonely"] {
+        let parents = Edge::list_parents_for_component(ctx, graph.component(head).component_id)
+            .await
+            .expect("unable to find component's parents");
+        assert_eq!(vec![graph.component("tail").component_id], parents);
+    }
+}
@@ -0,0 +1,97 @@
+use dal::{DalContext, FeatureFlag, FeatureFlagCache, FeatureFlagError};
+use dal_test::test;
+
+#[test]
+async fn workspace_toggle_takes_precedence(ctx: &DalContext) {
+    let workspace_pk = ctx.tenancy().workspace_pk();
+
+    assert!(!ctx
+        .feature_enabled("poop_canoe")
+        .await
+        .expect("cannot check feature flag"));
+
+    FeatureFlag::set(ctx, "poop_canoe", None, true)
+        .await
+        .expect("cannot set global feature flag");
+    assert!(ctx
+        .feature_enabled("poop_canoe")
+        .await
+        .expect("cannot check feature flag"));
+
+    FeatureFlag::set(ctx, "poop_canoe", workspace_pk, false)
+        .await
+        .expect("cannot set workspace feature flag");
+    assert!(!ctx
+        .feature_enabled("poop_canoe")
+        .await
+        .expect("cannot check feature flag"));
+
+    let flags = FeatureFlag::list(ctx)
+        .await
+        .expect("cannot list feature flags");
+    let toggles: Vec<_> = flags
+        .iter()
+        .filter(|flag| flag.name() == "poop_canoe")
+        .map(|flag| (*flag.workspace_pk(), *flag.enabled()))
+        .collect();
+    assert_eq!(vec![(None, true), (workspace_pk, false)], toggles);
+
+    let removed = FeatureFlag::unset(ctx, "poop_canoe", workspace_pk)
+        .await
+        .expect("cannot unset workspace feature flag");
+    assert!(removed);
+    assert!(ctx
+        .feature_enabled("poop_canoe")
+        .await
+        .expect("cannot check feature flag"));
+}
+
+#[test]
+async fn set_invalidates_cache(ctx: &DalContext) {
+    let workspace_pk = ctx.tenancy().workspace_pk();
+
+    FeatureFlag::set(ctx, "cached_canoe", workspace_pk, true)
+        .await
+        .expect("cannot set workspace feature flag");
+    assert!(ctx
+        .feature_enabled("cached_canoe")
+        .await
+        .expect("cannot check feature flag"));
+
+    FeatureFlag::set(ctx, "cached_canoe", workspace_pk, false)
+        .await
+        .expect("cannot set workspace feature flag");
+    assert!(!ctx
+        .feature_enabled("cached_canoe")
+        .await
+        .expect("cannot check feature flag"));
+}
+
+#[test]
+async fn untoggled_flags_use_defaults(ctx: &DalContext) {
+    let cache = FeatureFlagCache::new([("default_canoe".to_string(), true)]);
+
+    assert!(cache
+        .is_enabled(ctx, "default_canoe")
+        .await
+        .expect("cannot check feature flag"));
+    assert!(!cache
+        .is_enabled(ctx, "other_canoe")
+        .await
+        .expect("cannot check feature flag"));
+
+    FeatureFlag::set(ctx, "default_canoe", None, false)
+        .await
+        .expect("cannot set global feature flag");
+    cache.invalidate("default_canoe").await;
+    assert!(!cache
+        .is_enabled(ctx, "default_canoe")
+        .await
+        .expect("cannot check feature flag"));
+}
+
+#[test]
+async fn set_rejects_invalid_names(ctx: &DalContext) {
+    let result = FeatureFlag::set(ctx, "Poop-Canoe", None, true).await;
+    assert!(matches!(result, Err(FeatureFlagError::InvalidName(_))));
+}
@@ -0,0 +1,138 @@
+use dal::edge::EdgeKind;
+use dal::socket::SocketEdgeKind;
+use dal::{
+    ActionKind, ActionPrototypeId, AttributeValueId, ComponentId, Connection, DalContext, FixPlan,
+    FixPlanError, FixPlanItem, FixPlanPhase, FixPlanStatus, Socket, StandardModel,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+fn item(component_id: ComponentId, action_kind: ActionKind) -> FixPlanItem {
+    FixPlanItem {
+        attribute_value_id: AttributeValueId::generate(),
+        component_id,
+        component_name: component_id.to_string(),
+        action_prototype_id: ActionPrototypeId::generate(),
+        action_kind,
+    }
+}
+
+fn actions(phase: &FixPlanPhase) -> Vec<(ComponentId, ActionKind)> {
+    phase
+        .items
+        .iter()
+        .map(|item| (item.component_id, item.action_kind))
+        .collect()
+}
+
+#[test]
+async fn preview_orders_phases_by_dependencies(ctx: &mut DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "network", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "instance", "starfield").await;
+    let other_starfield_bag = bagger.create_component(ctx, "loner", "starfield").await;
+
+    let from_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let to_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        *from_socket.id(),
+        starfield_bag.node_id,
+        *to_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    let network = fallout_bag.component_id;
+    let instance = starfield_bag.component_id;
+    let loner = other_starfield_bag.component_id;
+
+    let phases = FixPlan::preview(
+        ctx,
+        vec![
+            item(instance, ActionKind::Create),
+            item(network, ActionKind::Create),
+            item(instance, ActionKind::Other),
+        ],
+    )
+    .await
+    .expect("could not preview plan");
+    assert_eq!(
+        vec![
+            vec![(network, ActionKind::Create)],
+            vec![(instance, ActionKind::Create)],
+            vec![(instance, ActionKind::Other)],
+        ],
+        phases.iter().map(actions).collect::<Vec<_>>()
+    );
+
+    let phases = FixPlan::preview(
+        ctx,
+        vec![
+            item(network, ActionKind::Delete),
+            item(instance, ActionKind::Delete),
+            item(loner, ActionKind::Create),
+        ],
+    )
+    .await
+    .expect("could not preview plan");
+    assert_eq!(3, phases.len());
+    assert_eq!(vec![(instance, ActionKind::Delete)], actions(&phases[0]));
+    assert_eq!(vec![(network, ActionKind::Delete)], actions(&phases[1]));
+    assert_eq!(vec![(loner, ActionKind::Create)], actions(&phases[2]));
+
+    let result = FixPlan::preview(ctx, vec![]).await;
+    assert!(matches!(result, Err(FixPlanError::NoFixes)));
+}
+
+#[test]
+async fn new_plan_is_pending(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "network", "fallout").await;
+
+    let mut plan = FixPlan::new(
+        ctx,
+        "vault-dweller@example.com",
+        vec![item(fallout_bag.component_id, ActionKind::Create)],
+    )
+    .await
+    .expect("could not create plan");
+    assert_eq!(&FixPlanStatus::Pending, plan.status());
+    assert_eq!(1, plan.phases().len());
+    assert!(plan.phases()[0].fix_batch_id.is_none());
+
+    let fetched = FixPlan::get_by_pk(ctx, plan.pk())
+        .await
+        .expect("could not get plan")
+        .expect("plan not found");
+    assert_eq!(plan, fetched);
+
+    let result = plan.pause(ctx).await;
+    assert!(matches!(
+        result,
+        Err(FixPlanError::CannotTransition(
+            _,
+            FixPlanStatus::Pending,
+            FixPlanStatus::Paused
+        ))
+    ));
+}
@@ -3,15 +3,15 @@ use dal::{
         argument::{FuncArgument, FuncArgumentKind},
         backend::string::FuncBackendStringArgs,
         binding::FuncBinding,
-        binding_return_value::FuncBindingReturnValue,
+        binding_return_value::{FuncBindingReturnValue, FuncBindingReturnValueError},
         execution::FuncExecution,
     },
     generate_name, ChangeSetPk, DalContext, Func, FuncBackendKind, FuncBackendResponseType, FuncId,
-    StandardModel, Visibility,
+    StandardModel, Tenancy, Visibility,
 };
 use dal_test::{
     test,
-    test_harness::{create_func, create_func_binding},
+    test_harness::{create_func, create_func_binding, create_key_pair, create_workspace},
 };
 use strum::IntoEnumIterator;
 
@@ -65,6 +65,69 @@ async fn func_binding_return_value_new(ctx: &DalContext) {
     .expect("failed to create return value");
 }
 
+#[test]
+async fn func_binding_return_value_seal_and_decrypt(ctx: &mut DalContext) {
+    let workspace = create_workspace(ctx).await;
+    ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+    let _key_pair = create_key_pair(ctx).await;
+
+    let func = create_func(ctx).await;
+    let args = FuncBackendStringArgs::new("hunter2".to_string());
+    let args_json = serde_json::to_value(args).expect("cannot serialize args to json");
+    let func_binding = create_func_binding(ctx, args_json, *func.id(), *func.backend_kind()).await;
+    let execution = FuncExecution::new(ctx, &func, &func_binding)
+        .await
+        .expect("cannot create a new func execution");
+    let mut func_binding_return_value = FuncBindingReturnValue::new(
+        ctx,
+        Some(serde_json::json!({"password": "hunter2"})),
+        Some(serde_json::json!({})),
+        *func.id(),
+        *func_binding.id(),
+        execution.pk(),
+    )
+    .await
+    .expect("failed to create return value");
+
+    func_binding_return_value
+        .seal(ctx)
+        .await
+        .expect("could not seal return value");
+    assert!(func_binding_return_value.is_sensitive());
+    assert_eq!(
+        Some(&serde_json::json!({"password": "hunter2"})),
+        func_binding_return_value.unprocessed_value()
+    );
+
+    let mut stored = FuncBindingReturnValue::get_by_id(ctx, func_binding_return_value.id())
+        .await
+        .expect("could not get return value")
+        .expect("return value not found");
+    assert!(stored.is_sensitive());
+    assert_eq!(None, stored.unprocessed_value());
+    assert_eq!(None, stored.value());
+
+    let mut decrypted = stored.clone();
+    decrypted
+        .decrypt(ctx)
+        .await
+        .expect("could not decrypt return value");
+    assert_eq!(
+        Some(&serde_json::json!({"password": "hunter2"})),
+        decrypted.unprocessed_value()
+    );
+    assert_eq!(Some(&serde_json::json!({})), decrypted.value());
+
+    let other_workspace = create_workspace(ctx).await;
+    ctx.update_tenancy(Tenancy::new(*other_workspace.pk()));
+    let result = stored.decrypt(ctx).await;
+    assert!(matches!(
+        result,
+        Err(FuncBindingReturnValueError::Unauthorized(..))
+    ));
+    assert_eq!(None, stored.unprocessed_value());
+}
+
 #[test]
 async fn func_binding_execute(ctx: &DalContext) {
     let func = create_func(ctx).await;
@@ -86,7 +149,7 @@ async fn func_binding_execute(ctx: &DalContext) {
 
 #[test]
 async fn func_binding_execute_unset(ctx: &DalContext) {
-    let name = dal_test::test_harness::generate_fake_name();
+    let name = dal_test::test_harness::generate_fake_name(ctx);
     let func = Func::new(
         ctx,
         name,
@@ -124,7 +187,7 @@ async fn func_argument_new(ctx: &DalContext) {
 async fn func_argument_list_for_func(ctx: &DalContext) {
     let func_id = FuncId::generate();
     for kind in FuncArgumentKind::iter() {
-        FuncArgument::new(ctx, generate_name(), kind, None, func_id)
+        FuncArgument::new(ctx, generate_name(ctx), kind, None, func_id)
             .await
             .expect("Could not create function argument with null argument kind");
     }
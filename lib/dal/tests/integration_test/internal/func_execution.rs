@@ -3,7 +3,7 @@ use dal::{
         backend::string::FuncBackendStringArgs,
         execution::{FuncExecution, FuncExecutionState},
     },
-    DalContext, StandardModel,
+    DalContext, FuncBinding, FuncBindingError, StandardModel,
 };
 use dal_test::{
     test,
@@ -100,6 +100,36 @@ async fn process_return_value(ctx: &DalContext) {
     );
 }
 
+#[test]
+async fn replay(ctx: &DalContext) {
+    let func = create_func(ctx).await;
+    let args = FuncBackendStringArgs::new("slayer".to_string());
+    let args_json = serde_json::to_value(args).expect("cannot serialize args to json");
+    let func_binding = create_func_binding(ctx, args_json, *func.id(), *func.backend_kind()).await;
+
+    let error = FuncBinding::replay(ctx, *func_binding.id())
+        .await
+        .expect_err("replayed a func binding which was never executed");
+    assert!(matches!(error, FuncBindingError::NoExecutionToReplay(_)));
+
+    let func_binding_return_value = func_binding
+        .execute(ctx)
+        .await
+        .expect("cannot execute binding");
+
+    let replay = FuncBinding::replay(ctx, *func_binding.id())
+        .await
+        .expect("cannot replay func binding");
+    assert!(!replay.code_changed);
+    assert_eq!(
+        replay.recorded_value.as_ref(),
+        func_binding_return_value.unprocessed_value()
+    );
+    assert_eq!(replay.recorded_value, replay.replayed_value);
+    assert_eq!(None, replay.replay_failure);
+    assert_eq!(None, replay.diff);
+}
+
 // FIXME(nick,fletcher): re-add test once upsert is added.
 // #[test]
 // async fn execution_upserts_return_value() {
@@ -0,0 +1,85 @@
+use dal::{
+    Component, DalContext, Func, FuncVersion, FuncVersionError, FuncVersionPin, StandardModel,
+};
+use dal_test::{
+    helpers::generate_fake_name,
+    test,
+    test_harness::{create_func, create_schema, create_schema_variant},
+};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn versions_and_pins(ctx: &DalContext) {
+    let mut func = create_func(ctx).await;
+    func.set_code_plaintext(ctx, Some("function one() { return 1; }"))
+        .await
+        .expect("could not set code");
+    let one = FuncVersion::new(ctx, *func.id())
+        .await
+        .expect("could not create func version");
+    func.set_code_plaintext(ctx, Some("function two() { return 2; }"))
+        .await
+        .expect("could not set code");
+    let two = FuncVersion::new(ctx, *func.id())
+        .await
+        .expect("could not create func version");
+    assert_eq!((1, 2), (*one.version(), *two.version()));
+    assert_ne!(one.code_sha256(), two.code_sha256());
+    assert_eq!(
+        vec![one, two],
+        FuncVersion::list_for_func(ctx, *func.id())
+            .await
+            .expect("could not list func versions")
+    );
+
+    let schema = create_schema(ctx).await;
+    let mut schema_variant = create_schema_variant(ctx, *schema.id()).await;
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+    let (component, _) = Component::new(ctx, generate_fake_name(ctx), *schema_variant.id())
+        .await
+        .expect("cannot create component");
+    assert_eq!(None, pinned_version(ctx, &func, &component).await);
+
+    FuncVersionPin::set(ctx, *func.id(), Some(*schema_variant.id()), None, 1)
+        .await
+        .expect("could not pin func version");
+    assert_eq!(Some(1), pinned_version(ctx, &func, &component).await);
+
+    // The workspace pin is more specific than the schema variant pin.
+    let workspace_pk = ctx.tenancy().workspace_pk();
+    FuncVersionPin::set(ctx, *func.id(), None, workspace_pk, 2)
+        .await
+        .expect("could not pin func version");
+    assert_eq!(Some(2), pinned_version(ctx, &func, &component).await);
+    assert_eq!(
+        2,
+        FuncVersionPin::list_for_func(ctx, *func.id())
+            .await
+            .expect("could not list pins")
+            .len()
+    );
+
+    assert!(FuncVersionPin::unset(ctx, *func.id(), None, workspace_pk)
+        .await
+        .expect("could not unpin func version"));
+    assert_eq!(Some(1), pinned_version(ctx, &func, &component).await);
+
+    let result = FuncVersionPin::set(ctx, *func.id(), None, None, 3).await;
+    assert!(matches!(result, Err(FuncVersionError::NotFound(_, 3))));
+
+    let comparison = FuncVersion::compare(ctx, *func.id(), Some(1), 2, None)
+        .await
+        .expect("could not compare func versions");
+    assert_eq!(0, comparison.changed_count);
+    assert!(comparison.values.is_empty());
+}
+
+async fn pinned_version(ctx: &DalContext, func: &Func, component: &Component) -> Option<i64> {
+    FuncVersion::pinned_for_component(ctx, *func.id(), *component.id())
+        .await
+        .expect("could not find pinned func version")
+        .map(|version| *version.version())
+}
@@ -1,4 +1,8 @@
-use dal::{DalContext, HistoryActor, HistoryEvent};
+use dal::history_event::HistoryEventPk;
+use dal::{
+    ApiTokenPk, DalContext, HistoryActor, HistoryEvent, HistoryEventFilter,
+    HistoryEventRetentionPolicy, UserPk,
+};
 use dal_test::test;
 
 #[test]
@@ -17,3 +21,156 @@ async fn new(ctx: &DalContext) {
     assert_eq!(&history_event.data, &serde_json::json!({}));
     assert_eq!(&history_event.tenancy, ctx.tenancy());
 }
+
+#[test]
+async fn list(ctx: &DalContext) {
+    let first = HistoryEvent::new(
+        ctx,
+        "poop.canoe",
+        "poop canoed",
+        &serde_json::json!({ "id": "1" }),
+    )
+    .await
+    .expect("cannot create a new history event");
+    let second = HistoryEvent::new(
+        ctx,
+        "poop.canoe",
+        "poop canoed",
+        &serde_json::json!({ "id": "2" }),
+    )
+    .await
+    .expect("cannot create a new history event");
+    HistoryEvent::new(
+        ctx,
+        "poopy.canoe",
+        "poopy canoed",
+        &serde_json::json!({ "id": "1" }),
+    )
+    .await
+    .expect("cannot create a new history event");
+
+    let filter = HistoryEventFilter {
+        entity_type: Some("poop".to_string()),
+        ..Default::default()
+    };
+    let page = HistoryEvent::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list history events");
+    let pks: Vec<HistoryEventPk> = page.events.iter().map(|event| event.pk).collect();
+    assert_eq!(vec![second.pk, first.pk], pks);
+    assert_eq!(None, page.next_cursor);
+
+    let filter = HistoryEventFilter {
+        entity_type: Some("poop".to_string()),
+        entity_id: Some("1".to_string()),
+        ..Default::default()
+    };
+    let page = HistoryEvent::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list history events");
+    let pks: Vec<HistoryEventPk> = page.events.iter().map(|event| event.pk).collect();
+    assert_eq!(vec![first.pk], pks);
+
+    let filter = HistoryEventFilter {
+        actor: Some(HistoryActor::User(UserPk::generate())),
+        ..Default::default()
+    };
+    let page = HistoryEvent::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list history events");
+    assert!(page.events.is_empty());
+}
+
+#[test]
+async fn list_pages(ctx: &DalContext) {
+    for _ in 0..3 {
+        HistoryEvent::new(ctx, "poop.canoe", "poop canoed", &serde_json::json!({}))
+            .await
+            .expect("cannot create a new history event");
+    }
+    let filter = HistoryEventFilter {
+        entity_type: Some("poop".to_string()),
+        ..Default::default()
+    };
+
+    let first = HistoryEvent::list(ctx, &filter, None, Some(2))
+        .await
+        .expect("cannot list history events");
+    assert_eq!(2, first.events.len());
+    let cursor = first.next_cursor.expect("first page should have a cursor");
+
+    let second = HistoryEvent::list(ctx, &filter, Some(cursor), Some(2))
+        .await
+        .expect("cannot list history events");
+    assert_eq!(1, second.events.len());
+    assert!(second.events.iter().all(|event| event.pk < cursor));
+    assert_eq!(None, second.next_cursor);
+}
+
+#[test]
+async fn api_token_actor(ctx: &mut DalContext) {
+    let user_pk = UserPk::generate();
+    let actor = HistoryActor::ApiToken {
+        pk: ApiTokenPk::generate(),
+        user_pk,
+    };
+    ctx.update_history_actor(actor);
+    assert_eq!(Some(user_pk), actor.user_pk());
+
+    let history_event = HistoryEvent::new(ctx, "poop.canoe", "poop canoed", &serde_json::json!({}))
+        .await
+        .expect("cannot create a new history event");
+    assert_eq!(actor, history_event.actor);
+
+    let filter = HistoryEventFilter {
+        actor: Some(actor),
+        ..Default::default()
+    };
+    let page = HistoryEvent::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list history events");
+    let pks: Vec<HistoryEventPk> = page.events.iter().map(|event| event.pk).collect();
+    assert_eq!(vec![history_event.pk], pks);
+}
+
+#[test]
+async fn prune(ctx: &DalContext) {
+    let history_event = HistoryEvent::new(ctx, "poop.canoe", "poop canoed", &serde_json::json!({}))
+        .await
+        .expect("cannot create a new history event");
+
+    let pruned = HistoryEvent::prune(ctx, HistoryEventRetentionPolicy::default())
+        .await
+        .expect("cannot prune history events");
+    assert_eq!(0, pruned);
+
+    let pruned = HistoryEvent::prune(
+        ctx,
+        HistoryEventRetentionPolicy {
+            max_age_days: 0,
+            archive: true,
+        },
+    )
+    .await
+    .expect("cannot prune history events");
+    assert!(pruned > 0);
+
+    let page = HistoryEvent::list(ctx, &HistoryEventFilter::default(), None, None)
+        .await
+        .expect("cannot list history events");
+    assert!(page.events.iter().all(|event| event.pk != history_event.pk));
+
+    let row = ctx
+        .txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .query_one(
+            "SELECT count(*) AS count FROM history_events_archive WHERE pk = $1",
+            &[&history_event.pk],
+        )
+        .await
+        .expect("cannot count archived history events");
+    let archived: i64 = row.try_get("count").expect("cannot get count");
+    assert_eq!(1, archived);
+}
@@ -0,0 +1,47 @@
+use dal::{DalContext, IdempotencyRecord};
+use dal_test::test;
+
+#[test]
+async fn store_and_find_response(ctx: &DalContext) {
+    let response = serde_json::json!({ "nodeId": "01H00000000000000000000000" });
+
+    assert_eq!(
+        None,
+        IdempotencyRecord::find_response::<serde_json::Value>(ctx, "key", "diagram/create_node")
+            .await
+            .expect("cannot find response")
+    );
+
+    let original = IdempotencyRecord::store_response(ctx, "key", "diagram/create_node", &response)
+        .await
+        .expect("cannot store response");
+    assert_eq!(None, original);
+
+    assert_eq!(
+        Some(response.clone()),
+        IdempotencyRecord::find_response(ctx, "key", "diagram/create_node")
+            .await
+            .expect("cannot find response")
+    );
+    assert_eq!(
+        None,
+        IdempotencyRecord::find_response::<serde_json::Value>(
+            ctx,
+            "key",
+            "diagram/create_connection"
+        )
+        .await
+        .expect("cannot find response")
+    );
+
+    // A second response stored under the same key loses to the first one
+    let original = IdempotencyRecord::store_response(
+        ctx,
+        "key",
+        "diagram/create_node",
+        &serde_json::json!({ "nodeId": "01H11111111111111111111111" }),
+    )
+    .await
+    .expect("cannot store response");
+    assert_eq!(Some(response), original);
+}
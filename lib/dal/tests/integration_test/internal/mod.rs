@@ -1,29 +1,51 @@
 mod action_prototype;
+mod api_token;
 mod attribute;
+mod audit_log;
+mod blob;
+mod blueprint;
+mod builtins;
 mod change_set;
+mod comment;
 mod component;
+mod context;
+mod data_migration;
+mod dead_lettered_job;
 mod diagram;
 mod edge;
+mod feature_flag;
+mod fix_plan;
 mod func;
 mod func_execution;
+mod func_version;
 mod graph;
 mod history_event;
+mod idempotency;
 mod key_pair;
 mod node;
 mod node_menu;
+mod notification;
 mod pkg;
 mod prop;
 mod prop_tree;
 mod property_editor;
 mod provider;
+mod saved_view;
 mod schema;
 mod secret;
+mod session;
 mod socket;
 mod standard_model;
 mod status_update;
+mod suggestion_prototype;
 mod tenancy;
+mod usage;
 mod user;
 mod validation_prototype;
 mod validation_resolver;
 mod visibility;
+mod webhook;
 mod workspace;
+mod workspace_backup;
+mod workspace_settings;
+mod ws_event;
@@ -1,5 +1,13 @@
-use dal::{node_menu::GenerateMenuItem, DalContext};
-use dal_test::test;
+use dal::{
+    node_menu::{GenerateMenuItem, SchemaCatalog, SchemaCatalogFilter},
+    schema::SchemaUiMenu,
+    ComponentType, DalContext, NodeMenuError, StandardModel,
+};
+use dal_test::{
+    test,
+    test_harness::{create_component_for_schema, create_schema, create_schema_variant},
+};
+use pretty_assertions_sorted::assert_eq;
 
 /// Recommended to run with the following environment variable:
 /// ```shell
@@ -18,3 +26,77 @@ async fn get_node_menu(ctx: &DalContext) {
     });
     assert!(item.is_some());
 }
+
+#[test]
+async fn schema_catalog(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let mut schema_variant = create_schema_variant(ctx, *schema.id()).await;
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let ui_menu = SchemaUiMenu::new(ctx, "quokka", "Marsupials.Macropods")
+        .await
+        .expect("cannot create schema ui menu");
+    ui_menu
+        .set_schema(ctx, schema.id())
+        .await
+        .expect("cannot set schema");
+    let component = create_component_for_schema(ctx, schema.id()).await;
+
+    let filter = SchemaCatalogFilter {
+        search: Some("QUOKKA macropods".to_owned()),
+        ..Default::default()
+    };
+    let catalog = SchemaCatalog::assemble(ctx, &filter)
+        .await
+        .expect("could not assemble schema catalog");
+    assert_eq!(1, catalog.categories.len());
+    let category = &catalog.categories[0];
+    assert_eq!(
+        ("Marsupials.Macropods", "logo-si"),
+        (category.name.as_str(), category.icon.as_str())
+    );
+    assert_eq!(1, category.entries.len());
+    let entry = &category.entries[0];
+    assert_eq!(
+        (*schema.id(), *schema_variant.id(), 1),
+        (entry.schema_id, entry.schema_variant_id, entry.usage_count)
+    );
+
+    let filter = SchemaCatalogFilter {
+        search: Some("quokka wallaby".to_owned()),
+        ..Default::default()
+    };
+    let catalog = SchemaCatalog::assemble(ctx, &filter)
+        .await
+        .expect("could not assemble schema catalog");
+    assert!(catalog.categories.is_empty());
+
+    // A component which is not a frame cannot be a parent.
+    let filter = SchemaCatalogFilter {
+        parent_component_id: Some(*component.id()),
+        ..Default::default()
+    };
+    let result = SchemaCatalog::assemble(ctx, &filter).await;
+    assert!(matches!(result, Err(NodeMenuError::ParentNotAFrame(_))));
+
+    // An aggregation frame only holds components of its own schema.
+    component
+        .set_type(ctx, ComponentType::AggregationFrame)
+        .await
+        .expect("could not set component type");
+    let catalog = SchemaCatalog::assemble(ctx, &filter)
+        .await
+        .expect("could not assemble schema catalog");
+    let schema_ids: Vec<_> = catalog
+        .categories
+        .iter()
+        .flat_map(|category| category.entries.iter().map(|entry| entry.schema_id))
+        .collect();
+    assert_eq!(vec![*schema.id()], schema_ids);
+}
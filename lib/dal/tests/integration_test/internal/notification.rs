@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dal::notification::{
+    CollectingEmailSender, Email, EmailChannel, NotificationChannel, SlackChannel, SlackError,
+    SlackIntegration,
+};
+use dal::{
+    ChangeSet, DalContext, Notification, NotificationKind, NotificationPreferences,
+    WorkspaceSignup, WsEvent,
+};
+use dal_test::test;
+
+#[test]
+async fn preferences_set_and_get(ctx: &DalContext, nw: &WorkspaceSignup) {
+    assert!(NotificationPreferences::get(ctx, nw.user.pk())
+        .await
+        .expect("cannot get notification preferences")
+        .is_none());
+
+    let preferences = NotificationPreferences::set(
+        ctx,
+        nw.user.pk(),
+        true,
+        vec![NotificationKind::ChangeSetApplied],
+    )
+    .await
+    .expect("cannot set notification preferences");
+    assert!(!preferences.wants_email(NotificationKind::ChangeSetApplied));
+    assert!(preferences.wants_email(NotificationKind::QualificationFailed));
+
+    let preferences = NotificationPreferences::set(ctx, nw.user.pk(), false, vec![])
+        .await
+        .expect("cannot set notification preferences");
+    assert_eq!(
+        Some(&preferences),
+        NotificationPreferences::get(ctx, nw.user.pk())
+            .await
+            .expect("cannot get notification preferences")
+            .as_ref()
+    );
+    assert!(!preferences.wants_email(NotificationKind::QualificationFailed));
+}
+
+#[test]
+async fn change_set_applied_emails(ctx: &DalContext, nw: &WorkspaceSignup) {
+    nw.user
+        .associate_workspace(ctx, *nw.workspace.pk())
+        .await
+        .expect("cannot associate user with workspace");
+    let change_set = ChangeSet::new(ctx, "poulet", None)
+        .await
+        .expect("cannot create change set");
+    let event = WsEvent::change_set_applied(ctx, change_set.pk)
+        .await
+        .expect("cannot create event");
+
+    let notification = Notification::for_event(ctx, &event)
+        .await
+        .expect("cannot build notification")
+        .expect("no notification for applied change set");
+    assert_eq!(NotificationKind::ChangeSetApplied, notification.kind);
+    assert_eq!("Change set applied: poulet", notification.subject);
+
+    let sender = CollectingEmailSender::default();
+    EmailChannel::new(Arc::new(sender.clone()))
+        .send(ctx, &notification)
+        .await
+        .expect("cannot send notification");
+    assert_eq!(
+        vec![Email {
+            to: vec![nw.user.email().clone()],
+            subject: notification.subject.clone(),
+            body: notification.body.clone(),
+        }],
+        sender.sent().await
+    );
+
+    NotificationPreferences::set(
+        ctx,
+        nw.user.pk(),
+        true,
+        vec![NotificationKind::ChangeSetApplied],
+    )
+    .await
+    .expect("cannot set notification preferences");
+    assert!(notification
+        .emails(ctx)
+        .await
+        .expect("cannot build emails")
+        .is_empty());
+}
+
+#[test]
+async fn slack_integration_set_and_delete(ctx: &DalContext) {
+    assert!(SlackIntegration::get(ctx)
+        .await
+        .expect("cannot get slack integration")
+        .is_none());
+
+    let result = SlackIntegration::set(
+        ctx,
+        None,
+        Some("xoxb-token".to_owned()),
+        None,
+        vec![],
+        HashMap::new(),
+    )
+    .await;
+    assert!(matches!(result, Err(SlackError::InvalidIntegration(_))));
+
+    let integration = SlackIntegration::set(
+        ctx,
+        None,
+        Some("xoxb-token".to_owned()),
+        Some("#deploys".to_owned()),
+        vec![NotificationKind::ResourceDrifted],
+        HashMap::from([(
+            NotificationKind::ResourceDrifted,
+            "drifted: {{name}}".to_owned(),
+        )]),
+    )
+    .await
+    .expect("cannot set slack integration");
+    assert!(integration.uses_bot_token());
+    assert!(integration.wants(NotificationKind::ResourceDrifted));
+    assert!(!integration.wants(NotificationKind::ChangeSetApplied));
+    assert_eq!(
+        Some(&integration),
+        SlackIntegration::get(ctx)
+            .await
+            .expect("cannot get slack integration")
+            .as_ref()
+    );
+
+    // Kinds the integration does not want are not posted at all
+    let change_set = ChangeSet::new(ctx, "poulet", None)
+        .await
+        .expect("cannot create change set");
+    let event = WsEvent::change_set_applied(ctx, change_set.pk)
+        .await
+        .expect("cannot create event");
+    let notification = Notification::for_event(ctx, &event)
+        .await
+        .expect("cannot build notification")
+        .expect("no notification for applied change set");
+    SlackChannel
+        .send(ctx, &notification)
+        .await
+        .expect("cannot send notification");
+
+    integration
+        .delete(ctx)
+        .await
+        .expect("cannot delete slack integration");
+    assert!(SlackIntegration::get(ctx)
+        .await
+        .expect("cannot get slack integration")
+        .is_none());
+}
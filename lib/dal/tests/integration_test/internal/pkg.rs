@@ -416,3 +416,175 @@ async fn test_install_pkg(ctx: &DalContext) {
         .expect("func is there");
     assert_eq!(func.name(), "groucho");
 }
+
+#[test]
+async fn uninstall_pkg_removes_unshared_assets(ctx: &DalContext) {
+    let scaffold_func_spec = FuncSpec::builder()
+        .name("si:scaffoldFunc")
+        .code_plaintext("function createAsset() { return new AssetBuilder().build(); }")
+        .handler("createAsset")
+        .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+        .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+        .build()
+        .expect("could not build schema variant definition spec");
+
+    let schema_spec = SchemaSpec::builder()
+        .name("Tchitcherine")
+        .category("Banana Puddings")
+        .ui_hidden(false)
+        .variant(
+            SchemaVariantSpec::builder()
+                .name("Enzian")
+                .color("baddad")
+                .func_unique_id(scaffold_func_spec.unique_id)
+                .build()
+                .expect("able to make schema variant spec"),
+        )
+        .build()
+        .expect("able to make schema spec");
+
+    let spec = PkgSpec::builder()
+        .name("Gravity's Rainbow")
+        .version("0.1")
+        .created_by("Tyrone Slothrop")
+        .schema(schema_spec)
+        .func(scaffold_func_spec)
+        .build()
+        .expect("able to build package spec");
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load from spec");
+
+    let (installed_pkg_id, _) = import_pkg_from_pkg(ctx, &pkg, "gravitys_rainbow", None)
+        .await
+        .expect("able to install pkg");
+    let installed_pkg_id = installed_pkg_id.expect("install was recorded");
+
+    let schema = Schema::find_by_attr(ctx, "name", &"Tchitcherine")
+        .await
+        .expect("able to search for schema")
+        .pop()
+        .expect("schema was installed");
+
+    uninstall_pkg(ctx, installed_pkg_id)
+        .await
+        .expect("able to uninstall pkg");
+
+    assert!(Schema::get_by_id(ctx, schema.id())
+        .await
+        .expect("able to get schema")
+        .is_none());
+    assert!(InstalledPkg::get_by_id(ctx, &installed_pkg_id)
+        .await
+        .expect("able to get installed pkg")
+        .is_none());
+    assert!(
+        InstalledPkgAsset::list_for_installed_pkg_id(ctx, installed_pkg_id)
+            .await
+            .expect("able to list installed pkg assets")
+            .is_empty()
+    );
+
+    // Uninstalling frees the package to be installed again
+    import_pkg_from_pkg(ctx, &pkg, "gravitys_rainbow", None)
+        .await
+        .expect("able to reinstall pkg");
+}
+
+#[test]
+async fn install_pkg_rejects_func_without_handler(ctx: &DalContext) {
+    let func_spec = FuncSpec::builder()
+        .name("si:truthy")
+        .code_plaintext("function truth() { return true; }")
+        .handler("falsehood")
+        .backend_kind(FuncSpecBackendKind::JsAttribute)
+        .response_type(FuncSpecBackendResponseType::Boolean)
+        .build()
+        .expect("build func spec");
+
+    let spec = PkgSpec::builder()
+        .name("V.")
+        .version("0.1")
+        .created_by("Herbert Stencil")
+        .func(func_spec)
+        .build()
+        .expect("able to build package spec");
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load from spec");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, "v", None).await;
+    assert!(matches!(result, Err(PkgError::InvalidFunc(_, _))));
+}
+
+#[test]
+async fn install_pkg_rejects_func_with_broken_syntax(ctx: &DalContext) {
+    let func_spec = FuncSpec::builder()
+        .name("si:truthy")
+        .code_plaintext("function truth() { return { truth: true; }")
+        .handler("truth")
+        .backend_kind(FuncSpecBackendKind::JsAttribute)
+        .response_type(FuncSpecBackendResponseType::Boolean)
+        .build()
+        .expect("build func spec");
+
+    let spec = PkgSpec::builder()
+        .name("V.")
+        .version("0.1")
+        .created_by("Herbert Stencil")
+        .func(func_spec)
+        .build()
+        .expect("able to build package spec");
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load from spec");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, "v", None).await;
+    assert!(matches!(result, Err(PkgError::InvalidFunc(_, _))));
+}
+
+#[test]
+async fn schema_bundle_create(ctx: &DalContext) {
+    let scaffold_func_spec = FuncSpec::builder()
+        .name("si:scaffoldFunc")
+        .code_plaintext("function createAsset() { return new AssetBuilder().build(); }")
+        .handler("createAsset")
+        .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+        .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+        .build()
+        .expect("could not build schema variant definition spec");
+
+    let schema_spec = SchemaSpec::builder()
+        .name("Katje Borgesius")
+        .category("Banana Puddings")
+        .ui_hidden(false)
+        .variant(
+            SchemaVariantSpec::builder()
+                .name("v0")
+                .color("baddad")
+                .func_unique_id(scaffold_func_spec.unique_id)
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("octopus")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("able to make prop spec"),
+                )
+                .build()
+                .expect("able to make schema variant spec"),
+        )
+        .build()
+        .expect("able to make schema spec");
+
+    // Bundles survive a round trip through their serialized form
+    let bundle = SchemaBundle::new(schema_spec, "0.1").func(scaffold_func_spec);
+    let bundle: SchemaBundle =
+        serde_json::from_value(serde_json::to_value(bundle).expect("able to serialize bundle"))
+            .expect("able to deserialize bundle");
+
+    let schema_variant_ids = bundle.create(ctx).await.expect("able to create bundle");
+    assert_eq!(1, schema_variant_ids.len());
+
+    let schema_variant = SchemaVariant::get_by_id(
+        ctx,
+        schema_variant_ids.first().expect("has a schema variant"),
+    )
+    .await
+    .expect("able to get schema variant")
+    .expect("schema variant is there");
+    assert_eq!("v0", schema_variant.name());
+}
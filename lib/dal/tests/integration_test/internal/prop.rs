@@ -28,6 +28,35 @@ async fn new(ctx: &DalContext) {
     assert_eq!(prop.kind(), &PropKind::String);
 }
 
+#[test(cases(
+    string(PropKind::String),
+    integer(PropKind::Integer),
+    boolean(PropKind::Boolean),
+    object(PropKind::Object),
+))]
+async fn new_of_kind(ctx: &DalContext, #[case] kind: PropKind) {
+    let schema = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("could not find schema");
+    let schema_variant_id = *schema
+        .default_schema_variant_id()
+        .expect("could not get default variant id");
+    let domain_prop = SchemaVariant::find_prop_in_tree(ctx, schema_variant_id, &["root", "domain"])
+        .await
+        .expect("could not find prop");
+    let prop = Prop::new(
+        ctx,
+        "coolness",
+        kind,
+        None,
+        schema_variant_id,
+        Some(*domain_prop.id()),
+    )
+    .await
+    .expect("cannot create prop");
+    assert_eq!(prop.kind(), &kind);
+}
+
 #[test]
 async fn parent_props(ctx: &DalContext) {
     let schema = Schema::find_by_name(ctx, "starfield")
@@ -42,7 +71,7 @@ async fn parent_props(ctx: &DalContext) {
 
     let parent_prop = Prop::new(
         ctx,
-        generate_fake_name(),
+        generate_fake_name(ctx),
         PropKind::Object,
         None,
         schema_variant_id,
@@ -52,7 +81,7 @@ async fn parent_props(ctx: &DalContext) {
     .expect("cannot create prop");
     let child_prop = Prop::new(
         ctx,
-        generate_fake_name(),
+        generate_fake_name(ctx),
         PropKind::String,
         None,
         schema_variant_id,
@@ -89,7 +118,7 @@ async fn parent_props_wrong_prop_kinds(ctx: &DalContext) {
 
     let parent_prop = Prop::new(
         ctx,
-        generate_fake_name(),
+        generate_fake_name(ctx),
         PropKind::String,
         None,
         *schema_variant.id(),
@@ -99,7 +128,7 @@ async fn parent_props_wrong_prop_kinds(ctx: &DalContext) {
     .expect("cannot create prop");
     let result = Prop::new(
         ctx,
-        generate_fake_name(),
+        generate_fake_name(ctx),
         PropKind::Object,
         None,
         *schema_variant.id(),
@@ -109,7 +109,7 @@ async fn property_editor_schema(ctx: &DalContext) {
 #[test]
 async fn property_editor_value(ctx: &DalContext) {
     let mut bagger = ComponentBagger::new();
-    let name = generate_name();
+    let name = generate_name(ctx);
     let component_bag = bagger.create_component(ctx, &name, "starfield").await;
 
     ctx.blocking_commit()
@@ -1,7 +1,7 @@
 use dal::{
-    socket::SocketArity, AttributeContext, AttributePrototypeArgument, AttributeReadContext,
-    AttributeValue, Component, ComponentView, DalContext, Edge, ExternalProvider, InternalProvider,
-    Prop, PropId, PropKind, StandardModel,
+    edge::EdgeKind, prop::PropPath, socket::SocketArity, AttributeContext,
+    AttributePrototypeArgument, AttributeReadContext, AttributeValue, Component, ComponentView,
+    DalContext, Edge, ExternalProvider, InternalProvider, Prop, PropId, PropKind, StandardModel,
 };
 use dal_test::{
     helpers::{component_bag::ComponentBag, setup_identity_func},
@@ -310,6 +310,91 @@ async fn inter_component_identity_update(ctx: &DalContext) {
     );
 }
 
+#[test]
+async fn value_providers_flow_across_connection(ctx: &DalContext) {
+    let (esp_bag, source_prop_id, _) = setup_esp(ctx).await;
+    let (swings_bag, _) = setup_swings(ctx).await;
+
+    // Provide "/root/domain/object/source" of "esp" and bind "/root/domain/destination" of
+    // "swings" to it.
+    let (_, esp_output_socket) = ExternalProvider::new_for_prop(
+        ctx,
+        esp_bag.schema_id,
+        esp_bag.schema_variant_id,
+        "source",
+        &PropPath::new(["root", "domain", "object", "source"]),
+        SocketArity::Many,
+    )
+    .await
+    .expect("could not create output value provider");
+    let (_, swings_input_socket) = InternalProvider::new_explicit_for_prop(
+        ctx,
+        swings_bag.schema_variant_id,
+        "destination",
+        &PropPath::new(["root", "domain", "destination"]),
+        SocketArity::Many,
+    )
+    .await
+    .expect("could not create input value provider");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    Edge::new_for_connection(
+        ctx,
+        swings_bag.node_id,
+        *swings_input_socket.id(),
+        esp_bag.node_id,
+        *esp_output_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not connect sockets");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // The value flows as soon as the components are connected...
+    assert_eq!(
+        serde_json::json![{
+            "si": {
+                "name": "swings",
+                "type": "component",
+                "protected": false
+            },
+            "domain": {
+                "destination": "zero-source",
+            },
+        }], // expected
+        swings_bag.component_view_properties_raw(ctx).await // actual
+    );
+
+    esp_bag
+        .update_attribute_value_for_prop(ctx, source_prop_id, Some(serde_json::json!["one"]))
+        .await;
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // ... and again whenever the source changes.
+    assert_eq!(
+        serde_json::json![{
+            "si": {
+                "name": "swings",
+                "type": "component",
+                "protected": false
+            },
+            "domain": {
+                "destination": "one",
+            },
+        }], // expected
+        swings_bag.component_view_properties_raw(ctx).await // actual
+    );
+}
+
 // 38.805354552534816, -77.05091482877533
 async fn setup_esp(ctx: &DalContext) -> (ComponentBag, PropId, PropId) {
     let mut schema = create_schema(ctx).await;
@@ -0,0 +1,117 @@
+use dal::{
+    Component, ComponentLabel, DalContext, HistoryActor, SavedView, SavedViewError,
+    SavedViewResolution, StandardModel, User, UserPk, WorkspaceSignup,
+};
+use dal_test::{
+    helpers::generate_fake_name,
+    test,
+    test_harness::{create_schema, create_schema_variant},
+};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn new_update_and_delete(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::SystemInit);
+    let result = SavedView::new(ctx, "prod", "label.env=prod", None, false).await;
+    assert!(matches!(result, Err(SavedViewError::NoUserActor)));
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let result = SavedView::new(ctx, "  ", "label.env=prod", None, false).await;
+    assert!(matches!(result, Err(SavedViewError::EmptyName)));
+    let result = SavedView::new(ctx, "prod", "env=prod", None, false).await;
+    assert!(matches!(result, Err(SavedViewError::ComponentLabel(_))));
+
+    let mut private = SavedView::new(ctx, " prod ", "label.env = prod", None, false)
+        .await
+        .expect("could not create saved view");
+    assert_eq!("prod", private.name());
+    assert_eq!("label.env=prod", private.selector());
+    let shared = SavedView::new(
+        ctx,
+        "payments",
+        "label.team=payments",
+        Some("layout-1".to_string()),
+        true,
+    )
+    .await
+    .expect("could not create saved view");
+
+    let other = User::new(
+        ctx,
+        UserPk::generate(),
+        "other",
+        "other@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("could not create user");
+    ctx.update_history_actor(HistoryActor::User(other.pk()));
+    assert_eq!(
+        vec![shared.clone()],
+        SavedView::list(ctx)
+            .await
+            .expect("could not list saved views")
+    );
+    assert!(SavedView::get_by_pk(ctx, private.pk())
+        .await
+        .expect("could not get saved view")
+        .is_none());
+    let result = shared.clone().delete(ctx).await;
+    assert!(matches!(result, Err(SavedViewError::NotOwner(_))));
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    private
+        .update(ctx, "prod", "label.env=prod", None, true)
+        .await
+        .expect("could not update saved view");
+    assert!(*private.shared());
+    shared
+        .delete(ctx)
+        .await
+        .expect("could not delete saved view");
+    assert_eq!(
+        vec![private],
+        SavedView::list(ctx)
+            .await
+            .expect("could not list saved views")
+    );
+}
+
+#[test]
+async fn resolve(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let schema = create_schema(ctx).await;
+    let mut schema_variant = create_schema_variant(ctx, *schema.id()).await;
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+    let mut components = Vec::new();
+    for _ in 0..2 {
+        let name = generate_fake_name(ctx);
+        components.push(
+            Component::new(ctx, &name, *schema_variant.id())
+                .await
+                .expect("cannot create component"),
+        );
+    }
+    let (prod, prod_node) = &components[0];
+    ComponentLabel::set(ctx, *prod.id(), "env", "prod")
+        .await
+        .expect("could not set label");
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let saved_view = SavedView::new(ctx, "prod", "label.env=prod", None, false)
+        .await
+        .expect("could not create saved view");
+
+    assert_eq!(
+        SavedViewResolution {
+            component_ids: vec![*prod.id()],
+            node_ids: vec![*prod_node.id()],
+        },
+        saved_view
+            .resolve(ctx)
+            .await
+            .expect("could not resolve saved view")
+    );
+}
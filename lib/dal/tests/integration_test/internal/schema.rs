@@ -2,6 +2,7 @@ use dal::{component::ComponentKind, schema::SchemaUiMenu, DalContext, Schema, St
 
 use dal_test::{test, test_harness::create_schema};
 
+pub mod category_rule;
 pub mod ui_menu;
 pub mod variant;
 
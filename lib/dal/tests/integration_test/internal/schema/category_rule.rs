@@ -0,0 +1,102 @@
+use dal::{
+    node_menu::GenerateMenuItem, schema::SchemaUiMenu, DalContext, Schema, SchemaCategoryRule,
+    SchemaCategoryRuleKind, SchemaError, SchemaId, StandardModel,
+};
+use dal_test::{test, test_harness::create_schema};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn scope_visible_schemas(ctx: &DalContext) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let apple = create_schema_in_category(ctx, "apple", Some("Fruit.Apple")).await;
+    let pear = create_schema_in_category(ctx, "pear", Some("Fruit.Pear")).await;
+    let carrot = create_schema_in_category(ctx, "carrot", Some("Vegetables")).await;
+    let uncategorized = create_schema_in_category(ctx, "uncategorized", None).await;
+    let all = [apple, pear, carrot, uncategorized];
+    assert_eq!(all.to_vec(), visible(ctx, &all).await);
+
+    SchemaCategoryRule::set(ctx, workspace_pk, "Fruit", SchemaCategoryRuleKind::Allow)
+        .await
+        .expect("could not allow category");
+    assert_eq!(vec![apple, pear], visible(ctx, &all).await);
+
+    SchemaCategoryRule::set(
+        ctx,
+        workspace_pk,
+        "Fruit.Pear",
+        SchemaCategoryRuleKind::Deny,
+    )
+    .await
+    .expect("could not deny category");
+    assert_eq!(vec![apple], visible(ctx, &all).await);
+    assert_eq!(vec![apple], in_node_add_menu(ctx, &all).await);
+    assert_eq!(
+        2,
+        SchemaCategoryRule::list_for_workspace(ctx, workspace_pk)
+            .await
+            .expect("could not list rules")
+            .len()
+    );
+
+    assert!(SchemaCategoryRule::unset(ctx, workspace_pk, "Fruit")
+        .await
+        .expect("could not remove rule"));
+    assert_eq!(vec![apple, carrot, uncategorized], visible(ctx, &all).await);
+    assert_eq!(vec![apple, carrot], in_node_add_menu(ctx, &all).await);
+
+    let result =
+        SchemaCategoryRule::set(ctx, workspace_pk, "Fruit.", SchemaCategoryRuleKind::Allow).await;
+    assert!(matches!(result, Err(SchemaError::InvalidCategory(_))));
+}
+
+async fn create_schema_in_category(
+    ctx: &DalContext,
+    name: &str,
+    category: Option<&str>,
+) -> SchemaId {
+    let schema = create_schema(ctx).await;
+    if let Some(category) = category {
+        let ui_menu = SchemaUiMenu::new(ctx, name, category)
+            .await
+            .expect("cannot create schema ui menu");
+        ui_menu
+            .set_schema(ctx, schema.id())
+            .await
+            .expect("cannot set schema");
+    }
+    *schema.id()
+}
+
+/// The given schemas which the workspace sees, in the order they were given.
+async fn visible(ctx: &DalContext, schema_ids: &[SchemaId]) -> Vec<SchemaId> {
+    let visible: Vec<SchemaId> = Schema::list_visible(ctx)
+        .await
+        .expect("could not list visible schemas")
+        .iter()
+        .map(|schema| *schema.id())
+        .collect();
+    schema_ids
+        .iter()
+        .filter(|schema_id| visible.contains(schema_id))
+        .copied()
+        .collect()
+}
+
+/// The given schemas which are in the node add menu, in the order they were given.
+async fn in_node_add_menu(ctx: &DalContext, schema_ids: &[SchemaId]) -> Vec<SchemaId> {
+    let menu = GenerateMenuItem::new(ctx, true)
+        .await
+        .expect("cannot generate node add menu");
+    schema_ids
+        .iter()
+        .filter(|schema_id| {
+            menu.raw_items
+                .iter()
+                .any(|(_, item)| item.schema_id == **schema_id)
+        })
+        .copied()
+        .collect()
+}
@@ -1,9 +1,23 @@
 use dal::{
-    schema::{variant::leaves::LeafKind, SchemaVariant},
-    DalContext, InternalProvider, Prop, PropId, RootPropChild, Schema, StandardModel,
+    func::backend::validation::FuncBackendValidationArgs,
+    schema::{
+        variant::{
+            json_schema::{import::JsonSchemaImport, JSON_SCHEMA_DIALECT},
+            leaves::LeafKind,
+            SchemaVariantError,
+        },
+        SchemaVariant,
+    },
+    validation::Validation,
+    DalContext, Func, InternalProvider, Prop, PropId, PropKind, RootPropChild, Schema,
+    StandardModel, ValidationPrototype, ValidationPrototypeContext,
+};
+use dal_test::{
+    test,
+    test_harness::{create_schema, create_schema_variant_with_root},
 };
-use dal_test::{test, test_harness::create_schema};
 use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
 
 #[test]
 async fn new(ctx: &DalContext) {
@@ -249,3 +263,250 @@ async fn list_implicit_internal_providers_for_root_children(ctx: &DalContext) {
         );
     }
 }
+
+#[test]
+async fn clone_for_edit(ctx: &DalContext) {
+    let schema = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("could not find schema");
+    let original = schema
+        .default_variant(ctx)
+        .await
+        .expect("could not get default variant");
+    let original_freestar_prop = original
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await
+        .expect("could not find prop");
+
+    let cloned = SchemaVariant::clone_for_edit(ctx, *original.id())
+        .await
+        .expect("could not clone schema variant");
+    assert_ne!(original.id(), cloned.id());
+    assert_eq!(Some(original.id()), cloned.cloned_from_schema_variant_id());
+    assert_eq!(
+        Some(original.id()),
+        Schema::get_by_id(ctx, schema.id())
+            .await
+            .expect("could not get schema")
+            .expect("schema not found")
+            .default_schema_variant_id()
+    );
+
+    let cloned_freestar_prop = cloned
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await
+        .expect("could not find cloned prop");
+    assert_ne!(original_freestar_prop.id(), cloned_freestar_prop.id());
+
+    // Only the clone can be edited
+    let domain_prop = original
+        .find_prop(ctx, &["root", "domain"])
+        .await
+        .expect("could not find domain prop");
+    let result = SchemaVariant::add_prop(
+        ctx,
+        *original.id(),
+        "nebula",
+        PropKind::String,
+        *domain_prop.id(),
+    )
+    .await;
+    assert!(matches!(result, Err(SchemaVariantError::NotEditable(_))));
+
+    let cloned_domain_prop = cloned
+        .find_prop(ctx, &["root", "domain"])
+        .await
+        .expect("could not find cloned domain prop");
+    let nebula_prop = SchemaVariant::add_prop(
+        ctx,
+        *cloned.id(),
+        "nebula",
+        PropKind::String,
+        *cloned_domain_prop.id(),
+    )
+    .await
+    .expect("could not add prop");
+    assert_eq!(
+        nebula_prop.id(),
+        cloned
+            .find_prop(ctx, &["root", "domain", "nebula"])
+            .await
+            .expect("could not find added prop")
+            .id()
+    );
+    assert!(InternalProvider::find_for_prop(ctx, *nebula_prop.id())
+        .await
+        .expect("could not find internal provider")
+        .is_some());
+
+    SchemaVariant::remove_prop(ctx, *cloned.id(), *cloned_freestar_prop.id())
+        .await
+        .expect("could not remove prop");
+    assert!(cloned
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await
+        .is_err());
+    original
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await
+        .expect("the original prop should remain");
+
+    let result = SchemaVariant::remove_prop(ctx, *cloned.id(), *cloned_domain_prop.id()).await;
+    assert!(matches!(
+        result,
+        Err(SchemaVariantError::PropNotEditable(_))
+    ));
+}
+
+#[test]
+async fn to_json_schema(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    let schema_variant_id = *schema_variant.id();
+
+    let region_prop = Prop::new(
+        ctx,
+        "region",
+        PropKind::String,
+        None,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    let port_prop = Prop::new(
+        ctx,
+        "port",
+        PropKind::Integer,
+        None,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    let tags_prop = Prop::new(
+        ctx,
+        "tags",
+        PropKind::Array,
+        None,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    Prop::new(
+        ctx,
+        "tag",
+        PropKind::String,
+        None,
+        schema_variant_id,
+        Some(*tags_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+
+    let validation_func = Func::find_by_attr(ctx, "name", &"si:validation")
+        .await
+        .expect("could not find func")
+        .pop()
+        .expect("missing builtin function si:validation");
+    let mut builder = ValidationPrototypeContext::builder();
+    builder.set_prop_id(*region_prop.id());
+    ValidationPrototype::new(
+        ctx,
+        *validation_func.id(),
+        serde_json::to_value(FuncBackendValidationArgs::new(
+            Validation::StringInStringArray {
+                value: None,
+                expected: vec!["us-east-1".to_owned(), "eu-west-1".to_owned()],
+                display_expected: true,
+            },
+        ))
+        .expect("could not serialize validation args"),
+        builder
+            .to_context(ctx)
+            .await
+            .expect("could not convert builder to context"),
+    )
+    .await
+    .expect("could not create validation prototype");
+
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+    port_prop
+        .set_default_value(ctx, 8080)
+        .await
+        .expect("could not set default value");
+
+    let document = schema_variant
+        .to_json_schema(ctx)
+        .await
+        .expect("could not convert schema variant to json schema");
+
+    assert_eq!(json!(JSON_SCHEMA_DIALECT), document["$schema"]);
+    assert_eq!(json!("object"), document["type"]);
+    let domain = &document["properties"]["domain"];
+    assert_eq!(json!(["region"]), domain["required"]);
+    assert_eq!(
+        json!({
+            "title": "region",
+            "type": "string",
+            "enum": ["us-east-1", "eu-west-1"],
+        }),
+        domain["properties"]["region"]
+    );
+    assert_eq!(
+        json!({ "title": "port", "type": "integer", "default": 8080 }),
+        domain["properties"]["port"]
+    );
+    assert_eq!(
+        json!({
+            "title": "tags",
+            "type": "array",
+            "items": { "title": "tag", "type": "string" },
+        }),
+        domain["properties"]["tags"]
+    );
+}
+
+#[test]
+async fn import_json_schema(ctx: &DalContext) {
+    let import = JsonSchemaImport::from_json_schema(&json!({
+        "type": "object",
+        "required": ["region"],
+        "properties": {
+            "region": { "type": "string", "enum": ["us-east-1", "eu-west-1"] },
+            "port": { "type": "integer", "default": 8080 },
+            "tags": { "type": "array", "items": { "type": "string" } },
+        }
+    }))
+    .expect("could not convert json schema");
+    assert!(import.unsupported.is_empty());
+
+    let (_, schema_variant_id) = import
+        .create_schema_variant(ctx, "imported", "Imported", "#00b0bc")
+        .await
+        .expect("could not create schema variant");
+    let schema_variant = SchemaVariant::get_by_id(ctx, &schema_variant_id)
+        .await
+        .expect("could not get schema variant")
+        .expect("schema variant not found");
+
+    let document = schema_variant
+        .to_json_schema(ctx)
+        .await
+        .expect("could not convert schema variant to json schema");
+    let domain = &document["properties"]["domain"];
+    assert_eq!(json!(["region"]), domain["required"]);
+    assert_eq!(
+        json!(["us-east-1", "eu-west-1"]),
+        domain["properties"]["region"]["enum"]
+    );
+    assert_eq!(json!(8080), domain["properties"]["port"]["default"]);
+    assert_eq!(
+        json!("string"),
+        domain["properties"]["tags"]["items"]["type"]
+    );
+}
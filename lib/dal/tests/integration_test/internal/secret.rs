@@ -9,7 +9,7 @@ use dal_test::{
 
 #[test]
 async fn new_encrypted_secret(ctx: &DalContext, nw: &WorkspaceSignup) {
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
 
     let secret = EncryptedSecret::new(
         ctx,
@@ -78,7 +78,7 @@ async fn secret_update_name(ctx: &DalContext, nw: &WorkspaceSignup) {
 #[test]
 async fn encrypt_decrypt_round_trip(ctx: &DalContext, nw: &WorkspaceSignup) {
     let pkey = nw.key_pair.public_key();
-    let name = generate_fake_name();
+    let name = generate_fake_name(ctx);
 
     let message = serde_json::json!({"song": "Bar Round Here"});
     let crypted = sodiumoxide::crypto::sealedbox::seal(
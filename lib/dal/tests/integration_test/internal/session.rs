@@ -0,0 +1,34 @@
+use chrono::{Duration, Utc};
+use dal::{DalContext, SessionRevocation, WorkspaceSignup};
+use dal_test::test;
+
+#[test]
+async fn revoke_all_for_user(ctx: &DalContext, nw: &WorkspaceSignup) {
+    assert_eq!(
+        None,
+        SessionRevocation::revoked_before(ctx, nw.user.pk())
+            .await
+            .expect("cannot get revoked before")
+    );
+
+    let revoked_before = SessionRevocation::revoke_all_for_user(ctx, nw.user.pk())
+        .await
+        .expect("cannot revoke sessions");
+    assert_eq!(
+        Some(revoked_before),
+        SessionRevocation::revoked_before(ctx, nw.user.pk())
+            .await
+            .expect("cannot get revoked before")
+    );
+
+    assert!(SessionRevocation::is_revoked(
+        Some(revoked_before),
+        Some(revoked_before - Duration::seconds(1))
+    ));
+    assert!(!SessionRevocation::is_revoked(
+        Some(revoked_before),
+        Some(Utc::now() + Duration::seconds(1))
+    ));
+    assert!(SessionRevocation::is_revoked(Some(revoked_before), None));
+    assert!(!SessionRevocation::is_revoked(None, None));
+}
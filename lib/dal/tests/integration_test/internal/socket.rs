@@ -28,7 +28,7 @@ async fn new(ctx: &DalContext) {
 async fn set_required(ctx: &DalContext) {
     let mut socket = Socket::new(
         ctx,
-        generate_fake_name(),
+        generate_fake_name(ctx),
         SocketKind::Standalone,
         &SocketEdgeKind::ConfigurationInput,
         &SocketArity::One,
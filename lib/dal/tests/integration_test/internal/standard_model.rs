@@ -1,11 +1,17 @@
+use chrono::{Duration, Utc};
 use dal::socket::{SocketEdgeKind, SocketKind};
 use dal::{
-    standard_model, ChangeSet, ChangeSetPk, DalContext, DiagramKind, Func, FuncBackendKind, Schema,
-    SchemaVariant, SchemaVariantId, Socket, SocketArity, SocketId, StandardModel,
+    standard_model, ChangeSet, ChangeSetPk, Component, DalContext, DiagramKind, Func,
+    FuncBackendKind, Schema, SchemaVariant, SchemaVariantId, Socket, SocketArity, SocketId,
+    StandardModel,
 };
 use dal_test::{
+    helpers::{assert_garbage_collected, count_rows_for_id},
     test,
-    test_harness::{create_func, create_schema, create_schema_variant, create_visibility_head},
+    test_harness::{
+        create_component_and_schema, create_func, create_schema, create_schema_variant,
+        create_visibility_head,
+    },
 };
 use itertools::Itertools;
 
@@ -804,3 +810,149 @@ async fn find_by_attr_not_in(ctx: &mut DalContext) {
             .expect("could not find at most one func")
     );
 }
+
+#[test]
+async fn restore(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    schema
+        .delete_by_id(ctx)
+        .await
+        .expect("cannot delete schema");
+    assert!(Schema::get_by_id(ctx, schema.id())
+        .await
+        .expect("cannot get schema")
+        .is_none());
+
+    schema.restore(ctx).await.expect("cannot restore schema");
+    assert!(schema.visibility().deleted_at.is_none());
+
+    let restored = Schema::get_by_id(ctx, schema.id())
+        .await
+        .expect("cannot get schema")
+        .expect("schema not restored");
+    assert_eq!(schema.id(), restored.id());
+}
+
+#[test]
+async fn garbage_collect(ctx: &DalContext) {
+    let ctx = &ctx.clone_with_head();
+    let mut schema = create_schema(ctx).await;
+    let schema_variant = create_schema_variant(ctx, *schema.id()).await;
+    schema
+        .delete_by_id(ctx)
+        .await
+        .expect("cannot delete schema");
+
+    // Objects soft deleted within the retention window are kept
+    standard_model::garbage_collect(ctx, Utc::now() - Duration::days(1))
+        .await
+        .expect("cannot garbage collect");
+    assert_eq!(1, count_rows_for_id::<Schema>(ctx, schema.id()).await);
+
+    let hard_deleted = standard_model::garbage_collect(ctx, Utc::now() + Duration::minutes(1))
+        .await
+        .expect("cannot garbage collect");
+    assert!(hard_deleted >= 2, "schema and its variant relationship");
+    assert_garbage_collected::<Schema>(ctx, schema.id()).await;
+
+    // Objects which belonged to it are left alone
+    assert_eq!(
+        1,
+        count_rows_for_id::<SchemaVariant>(ctx, schema_variant.id()).await
+    );
+}
+
+#[test]
+async fn garbage_collect_keeps_deletions_in_open_change_sets(ctx: &DalContext) {
+    let head_ctx = ctx.clone_with_head();
+    let schema = create_schema(&head_ctx).await;
+
+    let mut change_set_schema = Schema::get_by_id(ctx, schema.id())
+        .await
+        .expect("cannot get schema")
+        .expect("schema not found in change set");
+    change_set_schema
+        .delete_by_id(ctx)
+        .await
+        .expect("cannot delete schema in change set");
+    assert!(Schema::get_by_id(ctx, schema.id())
+        .await
+        .expect("cannot get schema")
+        .is_none());
+
+    standard_model::garbage_collect(ctx, Utc::now() + Duration::minutes(1))
+        .await
+        .expect("cannot garbage collect");
+
+    assert!(
+        Schema::get_by_id(ctx, schema.id())
+            .await
+            .expect("cannot get schema")
+            .is_none(),
+        "schema deleted in an open change set came back"
+    );
+    assert!(Schema::get_by_id(&head_ctx, schema.id())
+        .await
+        .expect("cannot get schema")
+        .is_some());
+}
+
+#[test]
+async fn head_fast_path(ctx: &mut DalContext) {
+    let component = create_component_and_schema(ctx).await;
+    let head_ctx = ctx.clone_with_new_visibility(create_visibility_head());
+
+    let not_applied: Option<Component> =
+        standard_model::get_by_id(&head_ctx, "components", component.id())
+            .await
+            .expect("could not get component by id");
+    assert!(not_applied.is_none());
+
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not get change set")
+        .expect("change set not found");
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+
+    let at_head: Component = standard_model::get_by_id(&head_ctx, "components", component.id())
+        .await
+        .expect("could not get component by id")
+        .expect("component should be in head");
+    assert_eq!(component.id(), at_head.id());
+    assert_eq!(ChangeSetPk::NONE, at_head.visibility().change_set_pk);
+
+    let listed: Vec<Component> = standard_model::list(&head_ctx, "components")
+        .await
+        .expect("could not list components");
+    assert!(listed.iter().any(|listed| listed.id() == component.id()));
+
+    let found: Vec<Component> =
+        standard_model::find_by_attr_in(&head_ctx, "components", "id", &[component.id()])
+            .await
+            .expect("could not find components");
+    assert_eq!(
+        vec![*component.id()],
+        found.iter().map(|found| *found.id()).collect::<Vec<_>>()
+    );
+
+    // Deleted objects are only seen through the full visibility resolution.
+    standard_model::delete_by_id(&head_ctx, "components", *component.id())
+        .await
+        .expect("could not delete component");
+    let deleted: Option<Component> =
+        standard_model::get_by_id(&head_ctx, "components", component.id())
+            .await
+            .expect("could not get component by id");
+    assert!(deleted.is_none());
+    let deleted: Option<Component> = standard_model::get_by_id(
+        &ctx.clone_with_new_visibility(create_visibility_head().to_deleted()),
+        "components",
+        component.id(),
+    )
+    .await
+    .expect("could not get component by id");
+    assert!(deleted.is_some());
+}
@@ -0,0 +1,87 @@
+use dal::{
+    DalContext, Func, FuncBackendKind, FuncBackendResponseType, Prop, StandardModel,
+    SuggestionPrototype, SuggestionPrototypeError,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+async fn create_suggestion_func(ctx: &DalContext, response_type: FuncBackendResponseType) -> Func {
+    let mut func = Func::new(
+        ctx,
+        "test:suggestRegions",
+        FuncBackendKind::JsAttribute,
+        response_type,
+    )
+    .await
+    .expect("could not create func");
+    let code = "function suggestRegions(input) {
+        return ['us-east-1', 'us-west-2', 'eu-west-1', input.properties.si.name];
+    }";
+    func.set_code_plaintext(ctx, Some(code))
+        .await
+        .expect("could not set code");
+    func.set_handler(ctx, Some("suggestRegions"))
+        .await
+        .expect("could not set handler");
+    func
+}
+
+#[test]
+async fn suggestions_are_filtered_by_prefix(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger
+        .create_component(ctx, "us-central", "starfield")
+        .await;
+    let prop = component_bag
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await;
+    let func = create_suggestion_func(ctx, FuncBackendResponseType::Array).await;
+    SuggestionPrototype::new(ctx, *func.id(), *prop.id())
+        .await
+        .expect("could not create suggestion prototype");
+
+    let suggestions = Prop::suggestions(ctx, component_bag.component_id, *prop.id(), "US")
+        .await
+        .expect("could not get suggestions");
+    assert_eq!(
+        vec![json!("us-east-1"), json!("us-west-2"), json!("us-central")],
+        suggestions
+    );
+
+    let suggestions = Prop::suggestions(ctx, component_bag.component_id, *prop.id(), "")
+        .await
+        .expect("could not get suggestions");
+    assert_eq!(4, suggestions.len());
+}
+
+#[test]
+async fn props_without_prototypes_have_no_suggestions(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+    let prop = component_bag
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await;
+
+    let suggestions = Prop::suggestions(ctx, component_bag.component_id, *prop.id(), "")
+        .await
+        .expect("could not get suggestions");
+    assert!(suggestions.is_empty());
+}
+
+#[test]
+async fn new_rejects_funcs_not_returning_arrays(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+    let prop = component_bag
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await;
+    let func = create_suggestion_func(ctx, FuncBackendResponseType::String).await;
+
+    let result = SuggestionPrototype::new(ctx, *func.id(), *prop.id()).await;
+    assert!(matches!(
+        result,
+        Err(SuggestionPrototypeError::FuncNotSuggestion(func_id)) if func_id == *func.id()
+    ));
+}
@@ -0,0 +1,87 @@
+use chrono::{Duration, DurationRound, Utc};
+use dal::{DalContext, Usage, UsageBucket, UsageError, UsageMetric, UsageReport};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+/// The func execution seconds, code artifact bytes and components created of the whole report.
+fn totals(report: &UsageReport) -> (f64, i64, i64) {
+    report
+        .buckets
+        .iter()
+        .fold((0.0, 0, 0), |(seconds, bytes, components), bucket| {
+            (
+                seconds + bucket.func_execution_seconds,
+                bytes + bucket.code_artifact_bytes,
+                components + bucket.components_created,
+            )
+        })
+}
+
+#[test]
+async fn usage_is_reported_before_and_after_aggregation(ctx: &DalContext) {
+    let now = Utc::now();
+    let (from, to) = (now - Duration::hours(1), now + Duration::hours(1));
+
+    for (metric, quantity) in [
+        (UsageMetric::FuncExecutionSeconds, 1.5),
+        (UsageMetric::FuncExecutionSeconds, 2.0),
+        (UsageMetric::CodeArtifactBytes, 100.0),
+        (UsageMetric::ComponentsCreated, 1.0),
+    ] {
+        Usage::record(ctx, metric, quantity)
+            .await
+            .expect("could not record usage");
+    }
+
+    let before = Usage::report(ctx, from, to, UsageBucket::Hour)
+        .await
+        .expect("could not report usage");
+    assert_eq!((3.5, 100, 1), totals(&before));
+    for bucket in &before.buckets {
+        assert_eq!(
+            bucket.start,
+            bucket
+                .start
+                .duration_trunc(Duration::hours(1))
+                .expect("could not truncate bucket start")
+        );
+    }
+
+    let aggregated = Usage::aggregate(ctx, Utc::now() + Duration::seconds(1))
+        .await
+        .expect("could not aggregate usage");
+    assert!(aggregated >= 4);
+    let after = Usage::report(ctx, from, to, UsageBucket::Hour)
+        .await
+        .expect("could not report usage");
+    assert_eq!(before, after);
+
+    let daily = Usage::report(ctx, from, to, UsageBucket::Day)
+        .await
+        .expect("could not report usage");
+    assert_eq!((3.5, 100, 1), totals(&daily));
+
+    assert!(matches!(
+        Usage::report(ctx, to, from, UsageBucket::Hour).await,
+        Err(UsageError::InvalidRange(..))
+    ));
+}
+
+#[test]
+async fn creating_a_component_is_metered(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    bagger.create_component(ctx, "vault", "fallout").await;
+
+    let now = Utc::now();
+    let report = Usage::report(
+        ctx,
+        now - Duration::hours(1),
+        now + Duration::hours(1),
+        UsageBucket::Day,
+    )
+    .await
+    .expect("could not report usage");
+    let (_, _, components_created) = totals(&report);
+    assert_eq!(1, components_created);
+}
@@ -39,3 +39,30 @@ async fn authorize(ctx: &DalContext, nw: &WorkspaceSignup) {
     );
     */
 }
+
+#[test]
+async fn set_admin(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let user_pk = nw.user.pk();
+    assert!(!User::is_admin(ctx, user_pk)
+        .await
+        .expect("cannot check admin permission"));
+
+    User::set_admin(ctx, user_pk, true)
+        .await
+        .expect("cannot grant admin permission");
+    assert!(User::is_admin(ctx, user_pk)
+        .await
+        .expect("cannot check admin permission"));
+
+    // Granting the permission twice is fine
+    User::set_admin(ctx, user_pk, true)
+        .await
+        .expect("cannot grant admin permission");
+
+    User::set_admin(ctx, user_pk, false)
+        .await
+        .expect("cannot take admin permission away");
+    assert!(!User::is_admin(ctx, user_pk)
+        .await
+        .expect("cannot check admin permission"));
+}
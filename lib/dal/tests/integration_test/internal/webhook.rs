@@ -0,0 +1,115 @@
+use dal::{
+    ChangeSet, DalContext, Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookError, WsEvent,
+};
+use dal_test::test;
+
+#[test]
+async fn new_update_and_delete(ctx: &DalContext) {
+    let (mut webhook, secret) = Webhook::new(
+        ctx,
+        "https://example.com/hooks/si",
+        vec!["ChangeSetApplied".to_owned()],
+    )
+    .await
+    .expect("cannot create webhook");
+    assert!(secret.starts_with(dal::webhook::WEBHOOK_SECRET_PREFIX));
+    assert!(*webhook.enabled());
+    assert!(webhook.wants("ChangeSetApplied"));
+    assert!(!webhook.wants("ChangeSetCreated"));
+
+    let result = webhook
+        .update(
+            ctx,
+            "https://example.com/hooks/si",
+            vec!["ChangeSetExploded".to_owned()],
+            true,
+        )
+        .await;
+    assert!(matches!(result, Err(WebhookError::InvalidEventKind(_))));
+
+    webhook
+        .update(ctx, "https://example.com/hooks/all", vec![], false)
+        .await
+        .expect("cannot update webhook");
+    assert_eq!("https://example.com/hooks/all", webhook.url());
+    assert!(!webhook.wants("ChangeSetCreated"));
+
+    let list = Webhook::list(ctx).await.expect("cannot list webhooks");
+    assert_eq!(vec![webhook.clone()], list);
+
+    let pk = webhook.pk();
+    webhook.delete(ctx).await.expect("cannot delete webhook");
+    assert!(Webhook::get_by_pk(ctx, pk)
+        .await
+        .expect("cannot get webhook")
+        .is_none());
+}
+
+#[test]
+async fn dispatch_and_redeliver(ctx: &DalContext) {
+    let (webhook, _) = Webhook::new(
+        ctx,
+        "https://example.com/hooks/si",
+        vec!["ChangeSetApplied".to_owned()],
+    )
+    .await
+    .expect("cannot create webhook");
+    let change_set = ChangeSet::new(ctx, "poulet", None)
+        .await
+        .expect("cannot create change set");
+
+    let created = WsEvent::change_set_created(ctx, change_set.pk)
+        .await
+        .expect("cannot create event");
+    assert!(Webhook::dispatch(ctx, &created)
+        .await
+        .expect("cannot dispatch event")
+        .is_empty());
+
+    let applied = WsEvent::change_set_applied(ctx, change_set.pk)
+        .await
+        .expect("cannot create event");
+    let deliveries = Webhook::dispatch(ctx, &applied)
+        .await
+        .expect("cannot dispatch event");
+    assert_eq!(1, deliveries.len());
+    let delivery = &deliveries[0];
+    assert_eq!(webhook.pk(), *delivery.webhook_pk());
+    assert_eq!("ChangeSetApplied", delivery.event_kind());
+    assert_eq!(WebhookDeliveryStatus::Pending, *delivery.status());
+    assert_eq!(0, *delivery.attempts());
+    assert_eq!(
+        &serde_json::to_value(&applied).expect("cannot serialize event"),
+        delivery.payload()
+    );
+
+    let redelivery = WebhookDelivery::redeliver(ctx, delivery.pk())
+        .await
+        .expect("cannot redeliver");
+    assert_ne!(delivery.pk(), redelivery.pk());
+    assert_eq!(delivery.payload(), redelivery.payload());
+
+    let history = WebhookDelivery::list_for_webhook(ctx, webhook.pk(), None)
+        .await
+        .expect("cannot list deliveries");
+    assert_eq!(
+        vec![redelivery.pk(), delivery.pk()],
+        history.iter().map(WebhookDelivery::pk).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+async fn new_rejects_non_public_addresses(ctx: &DalContext) {
+    for url in [
+        "http://127.0.0.1:8080/hooks",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[::1]/hooks",
+        "http://localhost/hooks",
+    ] {
+        let result = Webhook::new(ctx, url, vec![]).await;
+        assert!(
+            matches!(result, Err(WebhookError::InvalidUrl(..))),
+            "{url} was accepted"
+        );
+    }
+}
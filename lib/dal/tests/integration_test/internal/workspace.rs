@@ -7,3 +7,15 @@ async fn new(ctx: &mut DalContext) {
         .await
         .expect("cannot create workspace");
 }
+
+#[test]
+async fn list_all(ctx: &mut DalContext) {
+    let workspace = Workspace::new(ctx, WorkspacePk::generate(), "iron maiden")
+        .await
+        .expect("cannot create workspace");
+
+    let workspaces = Workspace::list_all(ctx)
+        .await
+        .expect("cannot list workspaces");
+    assert!(workspaces.iter().any(|found| found.pk() == workspace.pk()));
+}
@@ -0,0 +1,91 @@
+use dal::component::AttributeUpdate;
+use dal::{
+    Blob, ChangeSet, Component, ComponentView, DalContext, StandardModel, Visibility,
+    WorkspaceBackup, WorkspaceBackupPolicy, WorkspaceBackupTrigger,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn capture_prune_and_restore(ctx: &mut DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "vault", "fallout").await;
+    let rads_prop = fallout_bag
+        .find_prop(ctx, &["root", "domain", "rads"])
+        .await;
+    fallout_bag
+        .update_attribute_value_for_prop(ctx, *rads_prop.id(), Some(serde_json::json![2]))
+        .await;
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not fetch change set by pk")
+        .expect("no change set found for pk");
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    ctx.update_visibility(Visibility::new_head(false));
+
+    let backup = WorkspaceBackup::capture(ctx, WorkspaceBackupTrigger::Manual)
+        .await
+        .expect("could not capture backup");
+    assert_eq!(WorkspaceBackupTrigger::Manual, backup.trigger());
+    let spec = backup.spec();
+    assert_eq!(1, spec.components.len());
+    assert_eq!("vault", spec.components[0].name);
+    assert_eq!("fallout", spec.components[0].schema_name);
+    assert_eq!(
+        vec![AttributeUpdate::new(
+            "/root/domain/rads",
+            Some(serde_json::json![2])
+        )],
+        spec.components[0].values
+    );
+    let module = Blob::load(ctx, backup.module_hash().expect("invalid module hash"))
+        .await
+        .expect("could not load backup module");
+    assert_eq!(backup.module_size(), module.len() as i64);
+
+    // The newest backups of a workspace outlive the retention period
+    WorkspaceBackup::prune(
+        ctx,
+        WorkspaceBackupPolicy {
+            max_age_days: 0,
+            keep_last: 1,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not prune backups");
+    assert_eq!(
+        vec![backup.clone()],
+        WorkspaceBackup::list(ctx)
+            .await
+            .expect("could not list backups")
+    );
+
+    let source_workspace_pk = backup.workspace_pk();
+    let workspace = backup
+        .restore_into_new_workspace(ctx, "vault 111")
+        .await
+        .expect("could not restore backup");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_ne!(source_workspace_pk, *workspace.pk());
+    assert_eq!(Some(*workspace.pk()), ctx.tenancy().workspace_pk());
+
+    let components = Component::list(ctx)
+        .await
+        .expect("could not list components");
+    assert_eq!(1, components.len());
+    let view = ComponentView::new(ctx, *components[0].id())
+        .await
+        .expect("could not get component view");
+    assert_eq!(serde_json::json!["vault"], view.properties["si"]["name"]);
+    assert_eq!(serde_json::json![2], view.properties["domain"]["rads"]);
+}
@@ -0,0 +1,86 @@
+use dal::{
+    AuditAction, AuditLog, AuditLogFilter, DalContext, QualificationGatingPolicy,
+    WorkspaceSettings, WorkspaceSettingsError,
+};
+use dal_test::test;
+use serde_json::json;
+
+#[test]
+async fn update_and_reset(ctx: &DalContext) {
+    assert_eq!(
+        WorkspaceSettings::default(),
+        ctx.workspace_settings()
+            .await
+            .expect("cannot get workspace settings")
+    );
+
+    let changes = json!({
+        "qualificationGating": "block",
+        "resourceSyncIntervalSeconds": 900,
+    });
+    let settings = WorkspaceSettings::update(ctx, changes.as_object().expect("not an object"))
+        .await
+        .expect("cannot update workspace settings");
+    assert_eq!(
+        QualificationGatingPolicy::Block,
+        settings.qualification_gating
+    );
+    assert_eq!(900, settings.resource_sync_interval_seconds);
+    assert_eq!(
+        settings,
+        ctx.workspace_settings()
+            .await
+            .expect("cannot get workspace settings")
+    );
+
+    let changes = json!({ "qualificationGating": null });
+    let settings = WorkspaceSettings::update(ctx, changes.as_object().expect("not an object"))
+        .await
+        .expect("cannot update workspace settings");
+    assert_eq!(
+        WorkspaceSettings {
+            resource_sync_interval_seconds: 900,
+            ..Default::default()
+        },
+        settings
+    );
+    assert_eq!(
+        settings,
+        ctx.workspace_settings()
+            .await
+            .expect("cannot get workspace settings")
+    );
+
+    let filter = AuditLogFilter {
+        action: Some(AuditAction::WorkspaceSettingsUpdate),
+        ..Default::default()
+    };
+    let page = AuditLog::list(ctx, &filter, None, None)
+        .await
+        .expect("cannot list audit log");
+    assert_eq!(2, page.entries.len());
+}
+
+#[test]
+async fn update_rejects_invalid_changes(ctx: &DalContext) {
+    let changes = json!({
+        "qualificationGating": "ignore",
+        "resourceSyncIntervalSeconds": 1,
+    });
+    let result = WorkspaceSettings::update(ctx, changes.as_object().expect("not an object")).await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceSettingsError::InvalidValue(..))
+    ));
+
+    let changes = json!({ "defaultCanoe": "poop" });
+    let result = WorkspaceSettings::update(ctx, changes.as_object().expect("not an object")).await;
+    assert!(matches!(result, Err(WorkspaceSettingsError::UnknownKey(_))));
+
+    assert_eq!(
+        WorkspaceSettings::default(),
+        WorkspaceSettings::get(ctx)
+            .await
+            .expect("cannot get workspace settings")
+    );
+}
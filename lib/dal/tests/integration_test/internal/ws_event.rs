@@ -0,0 +1,78 @@
+use dal::{
+    prop::PropPath, AttributeContext, AttributeValueId, DalContext, Prop, StandardModel, WsEvent,
+};
+use dal_test::{test, test_harness::create_component_and_schema};
+
+#[test]
+async fn echoes_client_request_id(ctx: &mut DalContext) {
+    let event = WsEvent::change_set_written(ctx)
+        .await
+        .expect("could not create ws event");
+    assert_eq!(None, event.client_request_id());
+
+    ctx.set_client_request_id(Some("create-node-42".to_string()));
+    let event = WsEvent::change_set_written(ctx)
+        .await
+        .expect("could not create ws event");
+    assert_eq!(Some("create-node-42"), event.client_request_id());
+
+    let serialized = serde_json::to_value(&event).expect("could not serialize ws event");
+    assert_eq!(
+        Some(&serde_json::json!("create-node-42")),
+        serialized.get("client_request_id")
+    );
+}
+
+#[test]
+async fn attribute_value_updated(ctx: &DalContext) {
+    let component = create_component_and_schema(ctx).await;
+    let schema_variant = component
+        .schema_variant(ctx)
+        .await
+        .expect("could not get schema variant")
+        .expect("schema variant not found");
+    let name_prop = Prop::find_prop_by_path(
+        ctx,
+        *schema_variant.id(),
+        &PropPath::new(["root", "si", "name"]),
+    )
+    .await
+    .expect("could not find name prop");
+    let context = AttributeContext::builder()
+        .set_prop_id(*name_prop.id())
+        .set_component_id(*component.id())
+        .to_context()
+        .expect("could not build attribute context");
+
+    let event = WsEvent::attribute_value_updated(
+        ctx,
+        context,
+        AttributeValueId::NONE,
+        Some(serde_json::json!("before")),
+        Some(serde_json::json!("after")),
+    )
+    .await
+    .expect("could not create ws event");
+    let data = serde_json::to_value(&event).expect("could not serialize ws event")["payload"]
+        ["data"]
+        .clone();
+    assert_eq!(serde_json::json!("/root/si/name"), data["propPath"]);
+    assert_eq!(serde_json::json!("before"), data["oldValue"]);
+    assert_eq!(serde_json::json!("after"), data["newValue"]);
+    assert_eq!(serde_json::json!(false), data["truncated"]);
+
+    let event = WsEvent::attribute_value_updated(
+        ctx,
+        context,
+        AttributeValueId::NONE,
+        None,
+        Some(serde_json::json!("x".repeat(64 * 1024))),
+    )
+    .await
+    .expect("could not create ws event");
+    let data = serde_json::to_value(&event).expect("could not serialize ws event")["payload"]
+        ["data"]
+        .clone();
+    assert_eq!(serde_json::Value::Null, data["newValue"]);
+    assert_eq!(serde_json::json!(true), data["truncated"]);
+}
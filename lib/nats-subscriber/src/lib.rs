@@ -15,8 +15,9 @@ use futures::{Stream, StreamExt};
 use futures_lite::future::FutureExt;
 use pin_project_lite::pin_project;
 use serde::de::DeserializeOwned;
-use si_data_nats::NatsError;
+use si_data_nats::{HeaderMap, NatsError};
 use telemetry::prelude::*;
+use telemetry::propagation::TraceContext;
 use thiserror::Error;
 
 pub use crate::builder::SubscriptionBuilder;
@@ -50,6 +51,8 @@ pub struct Request<T> {
     pub payload: T,
     /// An optional reply mailbox.
     pub reply_mailbox: Option<String>,
+    /// The headers of the NATS message, if it had any.
+    pub headers: Option<HeaderMap>,
 }
 
 impl<T> Request<T> {
@@ -57,6 +60,11 @@ impl<T> Request<T> {
     pub fn into_parts(self) -> (T, Option<String>) {
         (self.payload, self.reply_mailbox)
     }
+
+    /// The trace context the request was published with, to continue its trace.
+    pub fn trace_context(&self) -> TraceContext {
+        si_data_nats::trace_context_from_headers(self.headers.as_ref())
+    }
 }
 
 pin_project! {
@@ -78,12 +86,12 @@ impl<T> Subscription<T> {
         SubscriptionBuilder::new(subject)
     }
 
-    /// Create a new [`subscription`](Self) for a given request shape `T`.
+    /// Drain the subscription from [NATS](https://nats.io), unsubscribing once the messages
+    /// already delivered to it have been read.
     ///
     /// # Errors
     ///
-    /// Returns [`SubscriberError`] if a [`Subscription`] could not be created.
-    #[allow(dead_code)]
+    /// Returns [`SubscriberError`] if the [`Subscription`] does not successfully drain.
     pub async fn drain(&self) -> SubscriberResult<()> {
         self.inner.drain().await.map_err(SubscriberError::NatsDrain)
     }
@@ -136,6 +144,7 @@ where
                     }
                 }
 
+                let headers = nats_msg.headers().cloned();
                 let (data, reply) = nats_msg.into_parts();
                 let reply_mailbox = reply;
 
@@ -158,6 +167,7 @@ where
                 Poll::Ready(Some(Ok(Request {
                     payload,
                     reply_mailbox,
+                    headers,
                 })))
             }
             // A NATS error occurred (async error or other i/o)
@@ -5,9 +5,13 @@ use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
 use si_data_pg::PgPoolConfig;
-use si_settings::{CanonicalFile, CanonicalFileError};
+use si_settings::{
+    require_file, require_non_empty, require_non_zero, CanonicalFile, CanonicalFileError,
+    FeatureFlags,
+};
 use telemetry::prelude::*;
 use thiserror::Error;
+use veritech_client::VeritechClientConfig;
 
 pub use dal::CycloneKeyPair;
 pub use si_settings::{StandardConfig, StandardConfigFile};
@@ -44,6 +48,12 @@ pub struct Config {
     #[builder(default = "NatsConfig::default()")]
     nats: NatsConfig,
 
+    #[builder(default = "VeritechClientConfig::default()")]
+    veritech: VeritechClientConfig,
+
+    #[builder(default = "FeatureFlags::default()")]
+    features: FeatureFlags,
+
     cyclone_encryption_key_path: CanonicalFile,
 
     #[builder(default = "default_concurrency_limit()")]
@@ -70,6 +80,18 @@ impl Config {
         &self.nats
     }
 
+    /// Gets a reference to how veritech is reached.
+    #[must_use]
+    pub fn veritech(&self) -> &VeritechClientConfig {
+        &self.veritech
+    }
+
+    /// Gets a reference to the feature flags enabled by configuration.
+    #[must_use]
+    pub fn features(&self) -> &FeatureFlags {
+        &self.features
+    }
+
     /// Gets a reference to the config's subject prefix.
     pub fn subject_prefix(&self) -> Option<&str> {
         self.nats.subject_prefix.as_deref()
@@ -98,6 +120,10 @@ pub struct ConfigFile {
     pg: PgPoolConfig,
     #[serde(default)]
     nats: NatsConfig,
+    #[serde(default)]
+    veritech: VeritechClientConfig,
+    #[serde(default)]
+    features: FeatureFlags,
     #[serde(default = "default_cyclone_encryption_key_path")]
     cyclone_encryption_key_path: String,
     #[serde(default = "default_concurrency_limit")]
@@ -111,6 +137,8 @@ impl Default for ConfigFile {
         Self {
             pg: Default::default(),
             nats: Default::default(),
+            veritech: Default::default(),
+            features: Default::default(),
             cyclone_encryption_key_path: default_cyclone_encryption_key_path(),
             concurrency_limit: default_concurrency_limit(),
             instance_id: random_instance_id(),
@@ -120,6 +148,26 @@ impl Default for ConfigFile {
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self) -> Result<()> {
+        require_non_empty("pg.user", &self.pg.user)?;
+        require_non_empty("pg.dbname", &self.pg.dbname)?;
+        require_non_empty("pg.hostname", &self.pg.hostname)?;
+        require_non_zero("pg.port", self.pg.port)?;
+        require_non_zero("pg.pool_max_size", self.pg.pool_max_size)?;
+        require_non_empty("nats.url", &self.nats.url)?;
+        if let Some(veritech_nats) = &self.veritech.nats {
+            require_non_empty("veritech.nats.url", &veritech_nats.url)?;
+        }
+        self.features.validate("features")?;
+        require_non_empty(
+            "cyclone_encryption_key_path",
+            &self.cyclone_encryption_key_path,
+        )?;
+        require_non_zero("concurrency_limit", self.concurrency_limit)?;
+        require_non_empty("instance_id", &self.instance_id)?;
+        Ok(())
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
@@ -131,7 +179,12 @@ impl TryFrom<ConfigFile> for Config {
         let mut config = Config::builder();
         config.pg_pool(value.pg);
         config.nats(value.nats);
-        config.cyclone_encryption_key_path(value.cyclone_encryption_key_path.try_into()?);
+        config.veritech(value.veritech);
+        config.features(value.features);
+        config.cyclone_encryption_key_path(require_file(
+            "cyclone_encryption_key_path",
+            value.cyclone_encryption_key_path,
+        )?);
         config.concurrency(value.concurrency_limit);
         config.instance_id(value.instance_id);
         config.build().map_err(Into::into)
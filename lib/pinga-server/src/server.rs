@@ -1,20 +1,37 @@
-use std::{io, path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    io,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use control_plane::{
+    nats_control_subject, ConcurrencyLimit, ControlAck, ControlCommand, ControlError,
+    ControlResult, ControlService,
+};
 use dal::{
     job::{
         consumer::{JobConsumer, JobConsumerError, JobInfo},
-        definition::{FixesJob, RefreshJob},
+        definition::{
+            FixesJob, GarbageCollectionJob, RefreshJob, UsageAggregationJob, WebhookDeliveryJob,
+        },
         producer::BlockingJobError,
     },
-    DalContext, DalContextBuilder, DependentValuesUpdate, InitializationError, JobFailure,
-    JobFailureError, JobQueueProcessor, NatsProcessor, ServicesContext, TransactionsError,
+    tasks::ChangeSetApplyScheduler,
+    DalContext, DalContextBuilder, DeadLetteredJob, DeadLetteredJobError, DependentValuesUpdate,
+    InitializationError, JobFailure, JobFailureError, JobQueueProcessor, NatsProcessor,
+    ServicesContext, TransactionsError,
 };
 use futures::{FutureExt, Stream, StreamExt};
 use nats_subscriber::{Request, SubscriberError, Subscription};
 use si_data_nats::{NatsClient, NatsConfig, NatsError};
 use si_data_pg::{PgPool, PgPoolConfig, PgPoolError};
+use si_error::CategorizedError;
 use stream_cancel::StreamExt as StreamCancelStreamExt;
+use telemetry::metrics::{Gauge, Histogram};
 use telemetry::prelude::*;
+use telemetry::ApplicationTelemetryClient;
 use thiserror::Error;
 use tokio::{
     signal::unix,
@@ -24,14 +41,31 @@ use tokio::{
     },
     task,
 };
-use tokio_stream::wrappers::UnboundedReceiverStream;
-use veritech_client::{Client as VeritechClient, EncryptionKey, EncryptionKeyError};
+use veritech_client::{
+    Client as VeritechClient, EncryptionKey, EncryptionKeyError, VeritechClientConfig,
+};
 
 use crate::{nats_jobs_subject, Config, NATS_JOBS_DEFAULT_QUEUE};
 
+static JOB_QUEUE_DEPTH: Gauge = Gauge::new(
+    "si_job_queue_depth",
+    "Number of received jobs waiting for an available concurrent task",
+);
+static JOB_PROCESSING_DURATION: Histogram = Histogram::new(
+    "si_job_processing_duration_seconds",
+    "Time spent processing jobs, by job kind and outcome",
+);
+
+/// How many times a job failing with a transient error is run before its failure is recorded.
+const JOB_RETRY_MAX_ATTEMPTS: u32 = 3;
+/// The wait before the first retry of a job, doubled before each of the following ones.
+const JOB_RETRY_BASE_BACKOFF: Duration = Duration::from_millis(250);
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ServerError {
+    #[error(transparent)]
+    DeadLetteredJob(#[from] Box<DeadLetteredJobError>),
     #[error("error when loading encryption key: {0}")]
     EncryptionKey(#[from] EncryptionKeyError),
     #[error(transparent)]
@@ -62,6 +96,12 @@ impl From<PgPoolError> for ServerError {
     }
 }
 
+impl From<DeadLetteredJobError> for ServerError {
+    fn from(e: DeadLetteredJobError) -> Self {
+        Self::DeadLetteredJob(Box::new(e))
+    }
+}
+
 impl From<JobFailureError> for ServerError {
     fn from(e: JobFailureError) -> Self {
         Self::JobFailure(Box::new(e))
@@ -83,6 +123,10 @@ pub struct Server {
     pg_pool: PgPool,
     veritech: VeritechClient,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+    /// Whether feature flags which were never toggled are enabled.
+    feature_flag_defaults: HashMap<String, bool>,
+    /// The telemetry client of the process, used to change its log level at runtime.
+    telemetry: Option<ApplicationTelemetryClient>,
     /// An internal shutdown watch receiver handle which can be provided to internal tasks which
     /// want to be notified when a shutdown event is in progress.
     shutdown_watch_rx: watch::Receiver<()>,
@@ -104,10 +148,10 @@ impl Server {
             Self::load_encryption_key(config.cyclone_encryption_key_path()).await?;
         let nats = Self::connect_to_nats(config.nats()).await?;
         let pg_pool = Self::create_pg_pool(config.pg_pool()).await?;
-        let veritech = Self::create_veritech_client(nats.clone());
+        let veritech = Self::create_veritech_client(config.veritech(), nats.clone()).await?;
         let job_processor = Self::create_job_processor(nats.clone());
 
-        Self::from_services(
+        let mut server = Self::from_services(
             config.instance_id().to_string(),
             config.concurrency(),
             encryption_key,
@@ -115,7 +159,15 @@ impl Server {
             pg_pool,
             veritech,
             job_processor,
-        )
+        )?;
+        server.set_feature_flag_defaults(
+            config
+                .features()
+                .iter()
+                .map(|(name, enabled)| (name.to_string(), enabled)),
+        );
+
+        Ok(server)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -153,6 +205,8 @@ impl Server {
             veritech,
             encryption_key,
             job_processor,
+            feature_flag_defaults: HashMap::new(),
+            telemetry: None,
             shutdown_watch_rx,
             external_shutdown_tx,
             graceful_shutdown_rx,
@@ -160,15 +214,53 @@ impl Server {
         })
     }
 
+    /// Sets whether feature flags which were never toggled are enabled for the jobs run by the
+    /// server.
+    pub fn set_feature_flag_defaults(
+        &mut self,
+        defaults: impl IntoIterator<Item = (String, bool)>,
+    ) {
+        self.feature_flag_defaults = defaults.into_iter().collect();
+    }
+
+    /// Sets the telemetry client of the process, so that its log level can be changed with a
+    /// [`ControlCommand`].
+    pub fn set_telemetry_client(&mut self, telemetry: ApplicationTelemetryClient) {
+        self.telemetry = Some(telemetry);
+    }
+
     pub async fn run(self) -> Result<()> {
         let (tx, rx) = mpsc::unbounded_channel();
+        let concurrency_limit = ConcurrencyLimit::new(self.concurrency_limit);
 
         // Span a task to receive and process jobs from the unbounded channel
         drop(task::spawn(process_job_requests_task(
             rx,
-            self.concurrency_limit,
+            concurrency_limit.clone(),
+        )));
+
+        // Spawn a task to apply the control commands sent to every instance
+        drop(task::spawn(receive_control_commands_task(
+            self.nats.clone(),
+            self.metadata.job_instance.clone(),
+            concurrency_limit,
+            self.telemetry,
+            self.shutdown_watch_rx.clone(),
         )));
 
+        // Spawn a task to apply the change sets whose scheduled apply is due
+        let mut services_context = ServicesContext::new(
+            self.pg_pool.clone(),
+            self.nats.clone(),
+            Self::create_job_processor(self.nats.clone()),
+            self.veritech.clone(),
+            self.encryption_key.clone(),
+            None,
+            None,
+        );
+        services_context.set_feature_flag_defaults(self.feature_flag_defaults.clone());
+        ChangeSetApplyScheduler::new(services_context).start(self.shutdown_watch_rx.clone());
+
         // Run "the main loop" which pulls message from a subscription off NATS and forwards each
         // request to an unbounded channel
         receive_job_requests_task(
@@ -179,6 +271,7 @@ impl Server {
             self.veritech,
             self.job_processor,
             self.encryption_key,
+            self.feature_flag_defaults,
             self.shutdown_watch_rx,
         )
         .await;
@@ -217,8 +310,20 @@ impl Server {
     }
 
     #[instrument(name = "pinga.init.create_veritech_client", skip_all)]
-    fn create_veritech_client(nats: NatsClient) -> VeritechClient {
-        VeritechClient::new(nats)
+    async fn create_veritech_client(
+        veritech_config: &VeritechClientConfig,
+        nats: NatsClient,
+    ) -> Result<VeritechClient> {
+        let nats = match &veritech_config.nats {
+            Some(nats_config) => Self::connect_to_nats(nats_config).await?,
+            None => nats,
+        };
+        let client = VeritechClient::new(nats).with_request_options(veritech_config.requests);
+        if veritech_config.requests.failover {
+            Ok(client.with_server_tracking().await?)
+        } else {
+            Ok(client)
+        }
     }
 
     #[instrument(name = "pinga.init.create_job_processor", skip_all)]
@@ -274,6 +379,7 @@ impl Subscriber {
         veritech: veritech_client::Client,
         job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
         encryption_key: Arc<veritech_client::EncryptionKey>,
+        feature_flag_defaults: HashMap<String, bool>,
     ) -> Result<impl Stream<Item = JobItem>> {
         let subject = nats_jobs_subject(nats.metadata().subject_prefix());
         debug!(
@@ -281,7 +387,7 @@ impl Subscriber {
             "subscribing for job requests"
         );
 
-        let services_context = ServicesContext::new(
+        let mut services_context = ServicesContext::new(
             pg_pool,
             nats.clone(),
             job_processor,
@@ -290,6 +396,7 @@ impl Subscriber {
             None,
             None,
         );
+        services_context.set_feature_flag_defaults(feature_flag_defaults);
 
         // Make non blocking context here, and update it for each job
         // Since the any blocking job should block on its child jobs
@@ -319,6 +426,7 @@ async fn receive_job_requests_task(
     veritech: veritech_client::Client,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     encryption_key: Arc<veritech_client::EncryptionKey>,
+    feature_flag_defaults: HashMap<String, bool>,
     shutdown_watch_rx: watch::Receiver<()>,
 ) {
     if let Err(err) = receive_job_requests(
@@ -329,6 +437,7 @@ async fn receive_job_requests_task(
         veritech,
         job_processor,
         encryption_key,
+        feature_flag_defaults,
         shutdown_watch_rx,
     )
     .await
@@ -346,6 +455,7 @@ async fn receive_job_requests(
     veritech: veritech_client::Client,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     encryption_key: Arc<veritech_client::EncryptionKey>,
+    feature_flag_defaults: HashMap<String, bool>,
     mut shutdown_watch_rx: watch::Receiver<()>,
 ) -> Result<()> {
     let mut requests = Subscriber::jobs(
@@ -355,6 +465,7 @@ async fn receive_job_requests(
         veritech,
         job_processor,
         encryption_key,
+        feature_flag_defaults,
     )
     .await?
     .take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
@@ -364,20 +475,31 @@ async fn receive_job_requests(
     while let Some(job) = requests.next().await {
         if let Err(_job) = tx.send(job) {
             error!("process_job_requests rx has already closed");
+        } else {
+            JOB_QUEUE_DEPTH.increment(&[]);
         }
     }
 
     Ok(())
 }
 
-async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_limit: usize) {
-    UnboundedReceiverStream::new(rx)
-        .for_each_concurrent(concurrency_limit, |job| async move {
-            // Got the next message from the subscriber
-            trace!("pulled request into an available concurrent task");
+async fn process_job_requests_task(
+    mut rx: UnboundedReceiver<JobItem>,
+    concurrency_limit: ConcurrencyLimit,
+) {
+    // Only pull the next job once it can run under the concurrency limit, which may change while
+    // jobs are running
+    while let Some(permit) = concurrency_limit.acquire().await {
+        let Some(job) = rx.recv().await else {
+            break;
+        };
+        // Got the next message from the subscriber
+        trace!("pulled request into an available concurrent task");
+        JOB_QUEUE_DEPTH.decrement(&[]);
 
-            match job.request {
-                Ok(request) => {
+        match job.request {
+            Ok(request) => {
+                task::spawn(async move {
                     // Spawn a task and process the request
                     let join_handle = task::spawn(execute_job_task(
                         job.metadata,
@@ -396,13 +518,94 @@ async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_l
                             "execute-job-task failed to execute to completion"
                         );
                     };
-                }
-                Err(err) => {
-                    warn!(error = ?err, "next job request had an error, job will not be executed");
-                }
+                    drop(permit);
+                });
             }
-        })
-        .await;
+            Err(err) => {
+                warn!(error = ?err, "next job request had an error, job will not be executed");
+            }
+        }
+    }
+}
+
+async fn receive_control_commands_task(
+    nats: NatsClient,
+    instance_id: String,
+    concurrency_limit: ConcurrencyLimit,
+    telemetry: Option<ApplicationTelemetryClient>,
+    shutdown_watch_rx: watch::Receiver<()>,
+) {
+    if let Err(err) = receive_control_commands(
+        nats,
+        instance_id,
+        concurrency_limit,
+        telemetry,
+        shutdown_watch_rx,
+    )
+    .await
+    {
+        warn!(error = ?err, "processing control commands failed");
+    }
+}
+
+async fn receive_control_commands(
+    nats: NatsClient,
+    instance_id: String,
+    concurrency_limit: ConcurrencyLimit,
+    mut telemetry: Option<ApplicationTelemetryClient>,
+    mut shutdown_watch_rx: watch::Receiver<()>,
+) -> Result<()> {
+    let subject = nats_control_subject(nats.metadata().subject_prefix(), ControlService::Pinga);
+    debug!(
+        messaging.destination = &subject.as_str(),
+        "subscribing for control commands"
+    );
+    let mut commands = Subscription::<ControlCommand>::create(subject)
+        .check_for_reply_mailbox()
+        .start(&nats)
+        .await?
+        .take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
+
+    while let Some(request) = commands.next().await {
+        let (command, reply_mailbox) = match request {
+            Ok(request) => request.into_parts(),
+            Err(err) => {
+                warn!(error = ?err, "next control command had an error, it will not be applied");
+                continue;
+            }
+        };
+
+        let name = command.name();
+        let result = apply_control_command(command, &concurrency_limit, telemetry.as_mut()).await;
+        match &result {
+            Ok(()) => info!(command = name, "applied control command"),
+            Err(err) => warn!(error = ?err, command = name, "rejected control command"),
+        }
+
+        if let Some(reply_mailbox) = reply_mailbox {
+            let ack = ControlAck::new(ControlService::Pinga, &instance_id, result);
+            if let Err(err) = nats.publish(reply_mailbox, serde_json::to_vec(&ack)?).await {
+                warn!(error = ?err, "failed to acknowledge control command");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn apply_control_command(
+    command: ControlCommand,
+    concurrency_limit: &ConcurrencyLimit,
+    telemetry: Option<&mut ApplicationTelemetryClient>,
+) -> ControlResult<()> {
+    match (command, telemetry) {
+        (ControlCommand::SetConcurrencyLimit { limit }, _) => concurrency_limit.set(limit),
+        (ControlCommand::SetLogLevel { level }, Some(telemetry)) => level.apply(telemetry).await,
+        (command, _) => Err(ControlError::Unsupported(
+            ControlService::Pinga,
+            command.name(),
+        )),
+    }
 }
 
 #[instrument(
@@ -431,7 +634,11 @@ async fn execute_job_task(
     request: Request<JobInfo>,
 ) {
     let span = Span::current();
+    // Continue the trace of whatever dispatched the job, before anything starts a child span
+    request.payload.trace_context.apply_to(&span);
     let id = request.payload.id.clone();
+    let kind = request.payload.kind.clone();
+    let started_at = Instant::now();
 
     let arg_str = serde_json::to_string(&request.payload.arg)
         .unwrap_or_else(|_| "arg failed to serialize".to_string());
@@ -469,6 +676,20 @@ async fn execute_job_task(
             new_err
         }
     };
+    JOB_PROCESSING_DURATION.observe_duration(
+        &[
+            ("job_kind", &kind),
+            (
+                "outcome",
+                if reply_message.is_ok() {
+                    "success"
+                } else {
+                    "failure"
+                },
+            ),
+        ],
+        started_at.elapsed(),
+    );
 
     if let Some(reply_channel) = maybe_reply_channel {
         if let Ok(message) = serde_json::to_vec(&reply_message) {
@@ -511,24 +732,54 @@ async fn execute_job(
         tracing::Span::current().record("job_info.blocking", job_info.blocking);
     }
 
-    let job =
-        match job_info.kind.as_str() {
-            stringify!(DependentValuesUpdate) => {
-                Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
-                    as Box<dyn JobConsumer + Send + Sync>
-            }
-            stringify!(FixesJob) => Box::new(FixesJob::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>,
-            stringify!(RefreshJob) => Box::new(RefreshJob::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>,
-            kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
-        };
+    let job = match job_info.kind.as_str() {
+        stringify!(DependentValuesUpdate) => {
+            Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
+                as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(FixesJob) => {
+            Box::new(FixesJob::try_from(job_info.clone())?) as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(GarbageCollectionJob) => {
+            Box::new(GarbageCollectionJob::try_from(job_info.clone())?)
+                as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(RefreshJob) => {
+            Box::new(RefreshJob::try_from(job_info.clone())?) as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(UsageAggregationJob) => {
+            Box::new(UsageAggregationJob::try_from(job_info.clone())?)
+                as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(WebhookDeliveryJob) => Box::new(WebhookDeliveryJob::try_from(job_info.clone())?)
+            as Box<dyn JobConsumer + Send + Sync>,
+        kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
+    };
 
     info!("Processing job");
 
-    if let Err(err) = job.run_job(ctx_builder.clone()).await {
-        // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
-        record_job_failure(ctx_builder, job, err).await?;
+    let mut attempt = 1;
+    loop {
+        match job.run_job(ctx_builder.clone()).await {
+            Ok(()) => break,
+            // A failed run rolls back everything it did, so a transient failure can be retried
+            Err(err) if attempt < JOB_RETRY_MAX_ATTEMPTS && err.is_retryable() => {
+                let backoff = JOB_RETRY_BASE_BACKOFF * 2u32.pow(attempt - 1);
+                warn!(
+                    error = ?err,
+                    job.attempt = attempt,
+                    ?backoff,
+                    "job failed with a transient error, retrying"
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(err) => {
+                // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
+                record_job_failure(ctx_builder, &job_info, job, err).await?;
+                break;
+            }
+        }
     }
 
     info!("Finished processing job");
@@ -538,6 +789,7 @@ async fn execute_job(
 
 async fn record_job_failure(
     ctx_builder: DalContextBuilder,
+    job_info: &JobInfo,
     job: Box<dyn JobConsumer + Send + Sync>,
     err: JobConsumerError,
 ) -> Result<()> {
@@ -548,6 +800,8 @@ async fn record_job_failure(
     let ctx = ctx_builder.build(access_builder.build(visibility)).await?;
 
     JobFailure::new(&ctx, job.type_name(), err.to_string()).await?;
+    // Keep the job around so that it can be replayed
+    DeadLetteredJob::record(&ctx, job_info, err.to_string()).await?;
 
     ctx.commit().await?;
 
@@ -15,7 +15,9 @@
 mod server;
 pub use server::{
     build_service, build_service_for_tests, detect_and_configure_development,
-    job_processor::JobProcessorClientCloser, job_processor::JobProcessorConnector, service, Config,
-    ConfigError, ConfigFile, IncomingStream, JobQueueProcessor, MigrationMode, NatsProcessor,
-    Server, StandardConfig, StandardConfigFile,
+    job_processor::JobProcessorClientCloser, job_processor::JobProcessorConnector, ndjson_response,
+    ndjson_response_with_timeouts, openapi, service, ApiError, ApiErrorCode, BodyLimitsConfig,
+    ClientAddr, Config, ConfigError, ConfigFile, IncomingStream, JobQueueProcessor, MigrationMode,
+    NatsProcessor, NdjsonTimeouts, RateLimitConfig, RateLimitLayer, SdfShutdownHandle, Server,
+    StandardConfig, StandardConfigFile, TokenRateLimit, NDJSON_CONTENT_TYPE,
 };
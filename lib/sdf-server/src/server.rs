@@ -1,21 +1,35 @@
+pub use api_error::{ApiError, ApiErrorCode};
 pub use config::{
     detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
     IncomingStream, StandardConfig, StandardConfigFile,
 };
 pub use dal::{JobQueueProcessor, MigrationMode, NatsProcessor};
+pub use ndjson::{
+    ndjson_response, ndjson_response_with_timeouts, NdjsonTimeouts, NDJSON_CONTENT_TYPE,
+};
+pub use rate_limit::{
+    BucketConfig, ClientAddr, RateLimit, RateLimitConfig, RateLimitLayer, RouteGroupLimits,
+    TokenRateLimit,
+};
 pub use routes::{routes, AppError};
-pub use server::{build_service, build_service_for_tests, Server};
+pub use server::{build_service, build_service_for_tests, SdfShutdownHandle, Server};
 pub use uds::{UdsIncomingStream, UdsIncomingStreamError};
+pub use upload::{BodyLimitsConfig, Upload, UploadError};
 
+pub mod api_error;
 mod config;
 pub(crate) mod extract;
 pub(crate) mod job_processor;
+mod ndjson;
+pub mod openapi;
+mod rate_limit;
 mod routes;
 mod server;
 pub mod service;
 mod state;
 pub mod tracking;
 mod uds;
+mod upload;
 
 macro_rules! impl_default_error_into_response {
     (
@@ -0,0 +1,363 @@
+//! The error envelope shared by sdf services.
+//!
+//! Service errors convert into an [`ApiError`], which pairs the error message with a stable,
+//! machine-readable [`ApiErrorCode`] and maps that code onto an HTTP status:
+//!
+//! ```json
+//! { "error": { "message": "component not found", "code": "NOT_FOUND", "statusCode": 404 } }
+//! ```
+//!
+//! Errors which carry data the client can act on, such as the current value on a conflict, add it
+//! under `details`.
+
+use std::time::Duration;
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use dal::{
+    error_category::categorize, AttributeContextError, AttributeValueError, CategorizedError,
+    ChangeSetApplyScheduleError, ChangeSetError, ChangeSetReviewError, CommentError,
+    ComponentError, ComponentLabelError, ComponentSnapshotError, DiagramError, EdgeError,
+    ErrorCategory, NodeError, SavedViewError, SchemaError, SchemaVariantError, SecretError,
+    StandardModelError,
+};
+use serde::Serialize;
+use strum::{AsRefStr, Display};
+
+/// Stable, machine-readable codes which clients can match on instead of parsing messages.
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiErrorCode {
+    /// The request conflicts with the current state of the resource.
+    Conflict,
+    /// The caller is not allowed to perform the request.
+    Forbidden,
+    /// An unexpected failure, not caused by the request itself.
+    Internal,
+    /// The requested resource does not exist or is not visible.
+    NotFound,
+    /// The request body is larger than the server accepts.
+    PayloadTooLarge,
+    /// The caller went over its rate limit; the request can be retried after `Retry-After`.
+    TooManyRequests,
+    /// The request carries no valid credentials.
+    Unauthorized,
+    /// A transient failure, such as a lost connection; the request can be retried as is.
+    Unavailable,
+    /// The request is well-formed but its contents are invalid.
+    Validation,
+}
+
+impl ApiErrorCode {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApiError {
+    code: ApiErrorCode,
+    message: String,
+    details: Option<serde_json::Value>,
+    retry_after: Option<Duration>,
+}
+
+impl ApiError {
+    pub fn new(code: ApiErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            retry_after: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Asks the client to wait before retrying the request, with a `Retry-After` header.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    pub fn code(&self) -> ApiErrorCode {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn details(&self) -> Option<&serde_json::Value> {
+        self.details.as_ref()
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.code.status()
+    }
+
+    /// The JSON envelope of the error, as sent to the client.
+    pub fn body(&self) -> serde_json::Value {
+        let mut error = serde_json::json!({
+            "message": self.message,
+            "code": self.code,
+            "statusCode": self.status().as_u16(),
+        });
+        if let Some(details) = &self.details {
+            error["details"] = details.clone();
+        }
+        serde_json::json!({ "error": error })
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status(), Json(self.body())).into_response();
+        if let Some(retry_after) = self.retry_after {
+            // Clients are asked to wait at least a second, as `Retry-After` has no finer resolution.
+            let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
+    }
+}
+
+/// Errors which have no more specific code take the one of their category, so that transient
+/// failures can be told apart from bugs.
+impl From<ErrorCategory> for ApiErrorCode {
+    fn from(category: ErrorCategory) -> Self {
+        match category {
+            ErrorCategory::Conflict => Self::Conflict,
+            ErrorCategory::Invariant => Self::Internal,
+            ErrorCategory::NotFound => Self::NotFound,
+            ErrorCategory::Transient => Self::Unavailable,
+            ErrorCategory::User => Self::Validation,
+        }
+    }
+}
+
+impl From<&StandardModelError> for ApiErrorCode {
+    fn from(err: &StandardModelError) -> Self {
+        err.category().into()
+    }
+}
+
+impl From<&AttributeValueError> for ApiErrorCode {
+    fn from(err: &AttributeValueError) -> Self {
+        match err {
+            AttributeValueError::MissingForId(_)
+            | AttributeValueError::NotFound(..)
+            | AttributeValueError::PropNotFound(_) => Self::NotFound,
+            AttributeValueError::AttributeContext(
+                AttributeContextError::KeyWithoutParent(..)
+                | AttributeContextError::NestedProviderContext(_),
+            ) => Self::Validation,
+            AttributeValueError::RevisionConflict { .. } => Self::Conflict,
+            AttributeValueError::StandardModelError(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&ChangeSetApplyScheduleError> for ApiErrorCode {
+    fn from(err: &ChangeSetApplyScheduleError) -> Self {
+        match err {
+            ChangeSetApplyScheduleError::AlreadyScheduled(_)
+            | ChangeSetApplyScheduleError::ChangeSetNotOpen(..)
+            | ChangeSetApplyScheduleError::NotScheduled(..) => Self::Conflict,
+            ChangeSetApplyScheduleError::ChangeSet(err) => err.as_ref().into(),
+            ChangeSetApplyScheduleError::ChangeSetNotFound(_) => Self::NotFound,
+            ChangeSetApplyScheduleError::InPast(_) => Self::Validation,
+            ChangeSetApplyScheduleError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&ChangeSetError> for ApiErrorCode {
+    fn from(err: &ChangeSetError) -> Self {
+        match err {
+            ChangeSetError::ApplySchedule(err) => err.into(),
+            ChangeSetError::Component(err) => err.into(),
+            ChangeSetError::InvalidActor(_) => Self::Forbidden,
+            ChangeSetError::Review(err) => err.into(),
+            ChangeSetError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&ChangeSetReviewError> for ApiErrorCode {
+    fn from(err: &ChangeSetReviewError) -> Self {
+        match err {
+            ChangeSetReviewError::ChangeSet(err) => err.as_ref().into(),
+            ChangeSetReviewError::ChangeSetNotFound(_)
+            | ChangeSetReviewError::ReviewerNotFound(_) => Self::NotFound,
+            ChangeSetReviewError::ChangeSetNotOpen(..)
+            | ChangeSetReviewError::MissingApprovals(..)
+            | ChangeSetReviewError::Rejected(..) => Self::Conflict,
+            ChangeSetReviewError::NoUserActor | ChangeSetReviewError::NotReviewer(..) => {
+                Self::Forbidden
+            }
+            ChangeSetReviewError::NoReviewers
+            | ChangeSetReviewError::ReviewerIsAuthor(..)
+            | ChangeSetReviewError::ReviewerIsRequester(..)
+            | ChangeSetReviewError::ReviewerNotInWorkspace(..) => Self::Validation,
+            ChangeSetReviewError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&CommentError> for ApiErrorCode {
+    fn from(err: &CommentError) -> Self {
+        match err {
+            CommentError::ChangeSetNotFound(_) | CommentError::ComponentNotFound(_) => {
+                Self::NotFound
+            }
+            CommentError::EmptyBody | CommentError::InvalidPropPath(_) => Self::Validation,
+            CommentError::NoUserActor | CommentError::NotAuthor(_) => Self::Forbidden,
+            CommentError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&ComponentError> for ApiErrorCode {
+    fn from(err: &ComponentError) -> Self {
+        err.category().into()
+    }
+}
+
+impl From<&ComponentLabelError> for ApiErrorCode {
+    fn from(err: &ComponentLabelError) -> Self {
+        match err {
+            ComponentLabelError::ComponentNotFound(_) => Self::NotFound,
+            ComponentLabelError::EmptySelector
+            | ComponentLabelError::InvalidKey(_)
+            | ComponentLabelError::InvalidSelectorRequirement(_)
+            | ComponentLabelError::InvalidValue(_) => Self::Validation,
+            ComponentLabelError::Component(err) => err.into(),
+            ComponentLabelError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&ComponentSnapshotError> for ApiErrorCode {
+    fn from(err: &ComponentSnapshotError) -> Self {
+        match err {
+            ComponentSnapshotError::ComponentNotFound(_) | ComponentSnapshotError::NotFound(_) => {
+                Self::NotFound
+            }
+            ComponentSnapshotError::SchemaVariantChanged(..) => Self::Conflict,
+            ComponentSnapshotError::Component(err) => err.into(),
+            ComponentSnapshotError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&DiagramError> for ApiErrorCode {
+    fn from(err: &DiagramError) -> Self {
+        match err {
+            DiagramError::ComponentNotFound
+            | DiagramError::EdgeNotFound
+            | DiagramError::NodeNotFound
+            | DiagramError::SchemaNotFound
+            | DiagramError::SchemaVariantNotFound
+            | DiagramError::SocketNotFound => Self::NotFound,
+            DiagramError::IncompatibleSockets(..) | DiagramError::NoNodesSelected => {
+                Self::Validation
+            }
+            DiagramError::Component(err) => err.into(),
+            DiagramError::Node(err) => err.into(),
+            DiagramError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&EdgeError> for ApiErrorCode {
+    fn from(err: &EdgeError) -> Self {
+        err.category().into()
+    }
+}
+
+impl From<&NodeError> for ApiErrorCode {
+    fn from(err: &NodeError) -> Self {
+        match err {
+            NodeError::NotFound(_) => Self::NotFound,
+            NodeError::StandardModelError(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&SavedViewError> for ApiErrorCode {
+    fn from(err: &SavedViewError) -> Self {
+        match err {
+            SavedViewError::EmptyName => Self::Validation,
+            SavedViewError::NoUserActor | SavedViewError::NotOwner(_) => Self::Forbidden,
+            SavedViewError::ComponentLabel(err) => err.into(),
+            SavedViewError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&SchemaError> for ApiErrorCode {
+    fn from(err: &SchemaError) -> Self {
+        match err {
+            SchemaError::NotFound(_) | SchemaError::NotFoundByName(_) => Self::NotFound,
+            SchemaError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&SchemaVariantError> for ApiErrorCode {
+    fn from(err: &SchemaVariantError) -> Self {
+        match err {
+            SchemaVariantError::NotFound(_) => Self::NotFound,
+            SchemaVariantError::InUse(_) | SchemaVariantError::NotEditable(_) => Self::Conflict,
+            SchemaVariantError::CloneRequiresWorkspace
+            | SchemaVariantError::ParentPropNotObject(_)
+            | SchemaVariantError::PropKindNotEditable(_)
+            | SchemaVariantError::PropNotEditable(_)
+            | SchemaVariantError::PropNotInSchemaVariant(_, _) => Self::Validation,
+            SchemaVariantError::StandardModel(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
+
+impl From<&SecretError> for ApiErrorCode {
+    fn from(err: &SecretError) -> Self {
+        match err {
+            SecretError::KeyPairNotFound => Self::NotFound,
+            SecretError::StandardModelError(err) => err.into(),
+            _ => categorize(err).into(),
+        }
+    }
+}
@@ -10,12 +10,23 @@ use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
 use si_data_pg::PgPoolConfig;
 use si_posthog::PosthogConfig;
-use si_settings::{CanonicalFile, CanonicalFileError};
+use si_settings::{
+    require_file, require_non_empty, require_non_zero, CanonicalFile, CanonicalFileError,
+    FeatureFlags,
+};
 use si_std::SensitiveString;
 use telemetry::prelude::*;
 use thiserror::Error;
+use veritech_client::VeritechClientConfig;
+
+use super::rate_limit::RateLimitConfig;
+use super::service::ws::credentials::WsNatsCredentialsConfig;
+use super::upload::BodyLimitsConfig;
 
-pub use dal::{CycloneKeyPair, MigrationMode};
+pub use dal::notification::SmtpConfig;
+pub use dal::{
+    Builtin, CycloneKeyPair, HistoryEventRetentionPolicy, MigrationMode, WorkspaceBackupPolicy,
+};
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
 const DEFAULT_SIGNUP_SECRET: &str = "cool-steam";
@@ -59,12 +70,39 @@ pub struct Config {
     #[builder(default = "NatsConfig::default()")]
     nats: NatsConfig,
 
+    #[builder(default = "VeritechClientConfig::default()")]
+    veritech: VeritechClientConfig,
+
+    #[builder(default = "FeatureFlags::default()")]
+    features: FeatureFlags,
+
     #[builder(default = "PosthogConfig::default()")]
     posthog: PosthogConfig,
 
     #[builder(default = "MigrationMode::default()")]
     migration_mode: MigrationMode,
 
+    #[builder(default)]
+    builtins: Option<Vec<Builtin>>,
+
+    #[builder(default = "HistoryEventRetentionPolicy::default()")]
+    history_event_retention: HistoryEventRetentionPolicy,
+
+    #[builder(default = "WorkspaceBackupPolicy::default()")]
+    workspace_backups: WorkspaceBackupPolicy,
+
+    #[builder(default = "RateLimitConfig::default()")]
+    rate_limit: RateLimitConfig,
+
+    #[builder(default = "BodyLimitsConfig::default()")]
+    body_limits: BodyLimitsConfig,
+
+    #[builder(default)]
+    smtp: Option<SmtpConfig>,
+
+    #[builder(default)]
+    ws_nats_credentials: Option<WsNatsCredentialsConfig>,
+
     jwt_signing_public_key_path: CanonicalFile,
 
     cyclone_encryption_key_path: CanonicalFile,
@@ -99,12 +137,67 @@ impl Config {
         &self.migration_mode
     }
 
+    /// Gets a reference to the builtins to migrate, or `None` to migrate all of them.
+    #[must_use]
+    pub fn builtins(&self) -> Option<&Vec<Builtin>> {
+        self.builtins.as_ref()
+    }
+
+    /// Gets the retention policy of history events.
+    #[must_use]
+    pub fn history_event_retention(&self) -> HistoryEventRetentionPolicy {
+        self.history_event_retention
+    }
+
+    /// Gets the schedule and retention policy of workspace backups.
+    #[must_use]
+    pub fn workspace_backups(&self) -> WorkspaceBackupPolicy {
+        self.workspace_backups
+    }
+
+    /// Gets a reference to the SMTP relay emails are sent through, or `None` if email
+    /// notifications are disabled.
+    #[must_use]
+    pub fn smtp(&self) -> Option<&SmtpConfig> {
+        self.smtp.as_ref()
+    }
+
+    /// Gets a reference to the rate limits of the API.
+    #[must_use]
+    pub fn rate_limit(&self) -> &RateLimitConfig {
+        &self.rate_limit
+    }
+
+    /// Gets a reference to the limits on the size of request bodies.
+    #[must_use]
+    pub fn body_limits(&self) -> &BodyLimitsConfig {
+        &self.body_limits
+    }
+
+    /// Gets a reference to the account scoping the NATS connections of workspace updates, if any.
+    #[must_use]
+    pub fn ws_nats_credentials(&self) -> Option<&WsNatsCredentialsConfig> {
+        self.ws_nats_credentials.as_ref()
+    }
+
     /// Gets a reference to the config's nats.
     #[must_use]
     pub fn nats(&self) -> &NatsConfig {
         &self.nats
     }
 
+    /// Gets a reference to how veritech is reached.
+    #[must_use]
+    pub fn veritech(&self) -> &VeritechClientConfig {
+        &self.veritech
+    }
+
+    /// Gets a reference to the feature flags enabled by configuration.
+    #[must_use]
+    pub fn features(&self) -> &FeatureFlags {
+        &self.features
+    }
+
     /// Gets a reference to the config's jwt signing public key path.
     #[must_use]
     pub fn jwt_signing_public_key_path(&self) -> &Path {
@@ -159,7 +252,25 @@ pub struct ConfigFile {
     #[serde(default)]
     pub nats: NatsConfig,
     #[serde(default)]
+    pub veritech: VeritechClientConfig,
+    #[serde(default)]
+    pub features: FeatureFlags,
+    #[serde(default)]
     pub migration_mode: MigrationMode,
+    #[serde(default)]
+    pub builtins: Option<Vec<Builtin>>,
+    #[serde(default)]
+    pub history_event_retention: HistoryEventRetentionPolicy,
+    #[serde(default)]
+    pub workspace_backups: WorkspaceBackupPolicy,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub body_limits: BodyLimitsConfig,
+    #[serde(default)]
+    pub smtp: Option<SmtpConfig>,
+    #[serde(default)]
+    pub ws_nats_credentials: Option<WsNatsCredentialsConfig>,
     #[serde(default = "default_jwt_signing_public_key_path")]
     pub jwt_signing_public_key_path: String,
     #[serde(default = "default_cyclone_encryption_key_path")]
@@ -179,7 +290,16 @@ impl Default for ConfigFile {
         Self {
             pg: Default::default(),
             nats: Default::default(),
+            veritech: Default::default(),
+            features: Default::default(),
             migration_mode: Default::default(),
+            builtins: None,
+            history_event_retention: Default::default(),
+            workspace_backups: Default::default(),
+            rate_limit: Default::default(),
+            body_limits: Default::default(),
+            smtp: None,
+            ws_nats_credentials: None,
             jwt_signing_public_key_path: default_jwt_signing_public_key_path(),
             cyclone_encryption_key_path: default_cyclone_encryption_key_path(),
             signup_secret: default_signup_secret(),
@@ -192,6 +312,45 @@ impl Default for ConfigFile {
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self) -> Result<()> {
+        require_non_empty("pg.user", &self.pg.user)?;
+        require_non_empty("pg.dbname", &self.pg.dbname)?;
+        require_non_empty("pg.hostname", &self.pg.hostname)?;
+        require_non_zero("pg.port", self.pg.port)?;
+        require_non_zero("pg.pool_max_size", self.pg.pool_max_size)?;
+        require_non_empty("nats.url", &self.nats.url)?;
+        if let Some(veritech_nats) = &self.veritech.nats {
+            require_non_empty("veritech.nats.url", &veritech_nats.url)?;
+        }
+        self.features.validate("features")?;
+        self.rate_limit.validate("rate_limit")?;
+        self.body_limits.validate("body_limits")?;
+        if self.workspace_backups.enabled {
+            require_non_zero(
+                "workspace_backups.interval_hours",
+                self.workspace_backups.interval_hours,
+            )?;
+        }
+        if let Some(smtp) = &self.smtp {
+            require_non_empty("smtp.host", &smtp.host)?;
+            require_non_zero("smtp.port", smtp.port)?;
+            require_non_empty("smtp.from", &smtp.from)?;
+        }
+        if let Some(ws_nats_credentials) = &self.ws_nats_credentials {
+            ws_nats_credentials.validate("ws_nats_credentials")?;
+        }
+        require_non_empty(
+            "jwt_signing_public_key_path",
+            &self.jwt_signing_public_key_path,
+        )?;
+        require_non_empty(
+            "cyclone_encryption_key_path",
+            &self.cyclone_encryption_key_path,
+        )?;
+        require_non_empty("pkgs_path", &self.pkgs_path)?;
+        Ok(())
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
@@ -203,11 +362,26 @@ impl TryFrom<ConfigFile> for Config {
         let mut config = Config::builder();
         config.pg_pool(value.pg);
         config.nats(value.nats);
+        config.veritech(value.veritech);
+        config.features(value.features);
         config.migration_mode(value.migration_mode);
-        config.jwt_signing_public_key_path(value.jwt_signing_public_key_path.try_into()?);
-        config.cyclone_encryption_key_path(value.cyclone_encryption_key_path.try_into()?);
+        config.builtins(value.builtins);
+        config.history_event_retention(value.history_event_retention);
+        config.workspace_backups(value.workspace_backups);
+        config.rate_limit(value.rate_limit);
+        config.body_limits(value.body_limits);
+        config.smtp(value.smtp);
+        config.ws_nats_credentials(value.ws_nats_credentials);
+        config.jwt_signing_public_key_path(require_file(
+            "jwt_signing_public_key_path",
+            value.jwt_signing_public_key_path,
+        )?);
+        config.cyclone_encryption_key_path(require_file(
+            "cyclone_encryption_key_path",
+            value.cyclone_encryption_key_path,
+        )?);
         config.signup_secret(value.signup_secret);
-        config.pkgs_path(value.pkgs_path.try_into()?);
+        config.pkgs_path(require_file("pkgs_path", value.pkgs_path)?);
         config.posthog(value.posthog);
         config.module_index_url(value.module_index_url);
         config.build().map_err(Into::into)
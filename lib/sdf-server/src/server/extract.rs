@@ -2,23 +2,25 @@ use std::{collections::HashMap, fmt};
 
 use axum::{
     async_trait,
-    extract::{FromRequestParts, Query},
-    http::request::Parts,
-    Json,
+    extract::{FromRequestParts, OriginalUri, Query},
+    http::{request::Parts, Extensions, Method},
 };
+use chrono::{DateTime, Utc};
 use dal::{
     context::{self, DalContextBuilder},
-    User, UserClaim,
+    ApiToken, ApiTokenScope, DalContext, SessionRevocation, User, UserClaim, UserPk,
+    API_TOKEN_PREFIX,
 };
-use hyper::StatusCode;
 
+use super::api_error::{ApiError, ApiErrorCode};
+use super::rate_limit::TokenRateLimit;
 use super::state::AppState;
 
 pub struct AccessBuilder(pub context::AccessBuilder);
 
 #[async_trait]
 impl FromRequestParts<AppState> for AccessBuilder {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -29,7 +31,7 @@ impl FromRequestParts<AppState> for AccessBuilder {
 
         Ok(Self(context::AccessBuilder::new(
             tenancy,
-            dal::HistoryActor::from(claim.user_pk),
+            claim.history_actor(),
         )))
     }
 }
@@ -38,7 +40,7 @@ pub struct RawAccessToken(pub String);
 
 #[async_trait]
 impl FromRequestParts<AppState> for RawAccessToken {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -67,7 +69,7 @@ pub struct HandlerContext(pub DalContextBuilder);
 
 #[async_trait]
 impl FromRequestParts<AppState> for HandlerContext {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         _parts: &mut Parts,
@@ -86,7 +88,7 @@ pub struct PosthogClient(pub super::state::PosthogClient);
 
 #[async_trait]
 impl FromRequestParts<AppState> for PosthogClient {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         _parts: &mut Parts,
@@ -100,7 +102,7 @@ pub struct Nats(pub si_data_nats::NatsClient);
 
 #[async_trait]
 impl FromRequestParts<AppState> for Nats {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         _parts: &mut Parts,
@@ -113,14 +115,23 @@ impl FromRequestParts<AppState> for Nats {
 
 pub struct Authorization(pub UserClaim);
 
+/// The claim of a request which has already been authorized, kept in the request extensions so
+/// that every later extractor of the same request reuses it instead of authenticating again.
+#[derive(Clone, Copy)]
+struct AuthorizedClaim(UserClaim);
+
 #[async_trait]
 impl FromRequestParts<AppState> for Authorization {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
+        if let Some(AuthorizedClaim(claim)) = parts.extensions.get::<AuthorizedClaim>() {
+            return Ok(Self(*claim));
+        }
+
         let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
         let mut ctx = builder.build_default().await.map_err(internal_error)?;
         let jwt_public_signing_key = state.jwt_public_signing_key().clone();
@@ -132,14 +143,70 @@ impl FromRequestParts<AppState> for Authorization {
         let authorization = authorization_header_value
             .to_str()
             .map_err(internal_error)?;
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
+
+        let claim = match authorization
+            .strip_prefix("Bearer ")
+            .filter(|token| token.starts_with(API_TOKEN_PREFIX))
+        {
+            Some(raw_api_token) => {
+                let required_scope =
+                    api_token_scope_for_request(parts).ok_or_else(unauthorized_error)?;
+                let api_token = ApiToken::authenticate(&ctx, raw_api_token, required_scope)
+                    .await
+                    .map_err(|_| unauthorized_error())?;
+                // Persist the token's last used time
+                ctx.commit().await.map_err(internal_error)?;
+
+                UserClaim::for_api_token(&api_token)
+            }
+            None => {
+                let (claim, issued_at) = UserClaim::from_bearer_token_with_issued_at(
+                    jwt_public_signing_key,
+                    authorization,
+                )
+                .await
+                .map_err(|_| unauthorized_error())?;
+                ensure_session_not_revoked(&ctx, state, claim.user_pk, issued_at).await?;
+                claim
+            }
+        };
         ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
 
         User::authorize(&ctx, &claim.user_pk)
             .await
             .map_err(|_| unauthorized_error())?;
+        take_token_rate_limit(&mut parts.extensions, authorization)?;
+        parts.extensions.insert(AuthorizedClaim(claim));
+
+        Ok(Self(claim))
+    }
+}
+
+/// The claim of a request made by a user holding the admin permission. Admin requests can only be
+/// authenticated with a session token, never with an [`ApiToken`].
+pub struct AdminAuthorization(pub UserClaim);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminAuthorization {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
+        if claim.api_token_pk.is_some() {
+            return Err(forbidden_error());
+        }
+
+        let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
+        let ctx = builder.build_default().await.map_err(internal_error)?;
+        let is_admin = User::is_admin(&ctx, claim.user_pk)
+            .await
+            .map_err(internal_error)?;
+        if !is_admin {
+            return Err(forbidden_error());
+        }
 
         Ok(Self(claim))
     }
@@ -149,7 +216,7 @@ pub struct WsAuthorization(pub UserClaim);
 
 #[async_trait]
 impl FromRequestParts<AppState> for WsAuthorization {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -164,14 +231,17 @@ impl FromRequestParts<AppState> for WsAuthorization {
             .map_err(|_| unauthorized_error())?;
         let authorization = query.get("token").ok_or_else(unauthorized_error)?;
 
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
+        let (claim, issued_at) =
+            UserClaim::from_bearer_token_with_issued_at(jwt_public_signing_key, authorization)
+                .await
+                .map_err(|_| unauthorized_error())?;
+        ensure_session_not_revoked(&ctx, state, claim.user_pk, issued_at).await?;
         ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
 
         User::authorize(&ctx, &claim.user_pk)
             .await
             .map_err(|_| unauthorized_error())?;
+        take_token_rate_limit(&mut parts.extensions, authorization)?;
 
         Ok(Self(claim))
     }
@@ -181,7 +251,7 @@ pub struct Tenancy(pub dal::Tenancy);
 
 #[async_trait]
 impl FromRequestParts<AppState> for Tenancy {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -192,36 +262,106 @@ impl FromRequestParts<AppState> for Tenancy {
     }
 }
 
-async fn tenancy_from_claim(
-    claim: &UserClaim,
-) -> Result<Tenancy, (StatusCode, Json<serde_json::Value>)> {
+/// The maximum length of an `Idempotency-Key` header value.
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// The optional `Idempotency-Key` header of a mutating request. Retries of a request which carry
+/// the same key return the response of the original request instead of repeating it.
+pub struct IdempotencyKey(pub Option<String>);
+
+#[async_trait]
+impl FromRequestParts<AppState> for IdempotencyKey {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let header_value = match parts.headers.get("Idempotency-Key") {
+            Some(header_value) => header_value,
+            None => return Ok(Self(None)),
+        };
+
+        let key = header_value
+            .to_str()
+            .map_err(|_| bad_request_error("Idempotency-Key header must be visible ASCII"))?
+            .trim();
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            return Err(bad_request_error(format!(
+                "Idempotency-Key header must be between 1 and {MAX_IDEMPOTENCY_KEY_LENGTH} characters"
+            )));
+        }
+
+        Ok(Self(Some(key.to_owned())))
+    }
+}
+
+/// Rejects session tokens which were issued before the user's sessions were last revoked. The
+/// revocation time is looked up in the in-memory cache first, falling back to the database.
+async fn ensure_session_not_revoked(
+    ctx: &DalContext,
+    state: &AppState,
+    user_pk: UserPk,
+    issued_at: Option<DateTime<Utc>>,
+) -> Result<(), ApiError> {
+    let cache = state.session_revocations();
+    let revoked_before = match cache.get(user_pk).await {
+        Some(revoked_before) => revoked_before,
+        None => {
+            let revoked_before = SessionRevocation::revoked_before(ctx, user_pk)
+                .await
+                .map_err(internal_error)?;
+            cache.insert(user_pk, revoked_before).await;
+            revoked_before
+        }
+    };
+
+    if SessionRevocation::is_revoked(revoked_before, issued_at) {
+        return Err(unauthorized_error());
+    }
+    Ok(())
+}
+
+/// Takes the request out of the rate limit bucket of its token, now that the token is known to be
+/// valid. The limit is removed from the request, so that it is only taken from once however many
+/// extractors authorize the request.
+fn take_token_rate_limit(extensions: &mut Extensions, token: &str) -> Result<(), ApiError> {
+    match extensions.remove::<TokenRateLimit>() {
+        Some(limit) => limit.check(token),
+        None => Ok(()),
+    }
+}
+
+/// Determines the [`ApiTokenScope`] needed to serve a request, based on the service it is routed
+/// to (i.e. `/api/<service>/...`) and whether the request method is read-only.
+fn api_token_scope_for_request(parts: &Parts) -> Option<ApiTokenScope> {
+    let path = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map(|original_uri| original_uri.0.path())
+        .unwrap_or_else(|| parts.uri.path());
+    let service = path.strip_prefix("/api/")?.split('/').next()?;
+    let write = !matches!(parts.method, Method::GET | Method::HEAD | Method::OPTIONS);
+
+    ApiTokenScope::for_area(service, write)
+}
+
+async fn tenancy_from_claim(claim: &UserClaim) -> Result<Tenancy, ApiError> {
     Ok(Tenancy(dal::Tenancy::new(claim.workspace_pk)))
 }
 
-fn internal_error(message: impl fmt::Display) -> (StatusCode, Json<serde_json::Value>) {
-    let status_code = StatusCode::INTERNAL_SERVER_ERROR;
-    (
-        status_code,
-        Json(serde_json::json!({
-            "error": {
-                "message": message.to_string(),
-                "statusCode": status_code.as_u16(),
-                "code": 42,
-            },
-        })),
-    )
-}
-
-fn unauthorized_error() -> (StatusCode, Json<serde_json::Value>) {
-    let status_code = StatusCode::UNAUTHORIZED;
-    (
-        status_code,
-        Json(serde_json::json!({
-            "error": {
-                "message": "unauthorized",
-                "statusCode": status_code.as_u16(),
-                "code": 42,
-            },
-        })),
-    )
+fn internal_error(message: impl fmt::Display) -> ApiError {
+    ApiError::new(ApiErrorCode::Internal, message.to_string())
+}
+
+fn bad_request_error(message: impl fmt::Display) -> ApiError {
+    ApiError::new(ApiErrorCode::Validation, message.to_string())
+}
+
+fn forbidden_error() -> ApiError {
+    ApiError::new(ApiErrorCode::Forbidden, "forbidden")
+}
+
+fn unauthorized_error() -> ApiError {
+    ApiError::new(ApiErrorCode::Unauthorized, "unauthorized")
 }
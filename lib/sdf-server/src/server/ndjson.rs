@@ -0,0 +1,179 @@
+//! Newline-delimited JSON responses, which stream large result sets to the client as they are read
+//! from the database instead of building a single JSON body holding all of them.
+//!
+//! Every line holds one object along with the cursor which resumes the stream after it:
+//!
+//! ```json
+//! {"cursor":"01H7ZJ1D2Y8N5QK3W6TB4XV9RM","object":{"name":"mastodon"}}
+//! ```
+//!
+//! Should reading fail partway through, a final line holds the error in the [`ApiError`] envelope
+//! and the stream ends. Clients recover by requesting the stream again with the cursor of the
+//! last object they received.
+//!
+//! The objects are read by a task of their own, which holds the database transaction until the
+//! stream ends. So that a stalled query or a client which stops reading can't hold it forever,
+//! reading stops with an [`ApiErrorCode::Unavailable`] error line once the next object takes
+//! longer than [`NdjsonTimeouts::batch`] to arrive, or once the whole stream has taken longer than
+//! [`NdjsonTimeouts::stream`]. A client too slow to take its lines by then is cut off without one.
+
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    body::StreamBody,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use futures::{stream, Stream, StreamExt};
+use serde::Serialize;
+use telemetry::prelude::*;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+use super::api_error::{ApiError, ApiErrorCode};
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// How many serialized lines are kept for a client before reading waits for it to catch up.
+const LINE_BUFFER: usize = 64;
+
+/// How long a stream may wait on its objects before it is ended with an error line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NdjsonTimeouts {
+    /// How long reading the next object may take, which covers fetching the batch of rows it is
+    /// part of.
+    pub batch: Duration,
+    /// How long the whole stream may take.
+    pub stream: Duration,
+}
+
+impl Default for NdjsonTimeouts {
+    fn default() -> Self {
+        Self {
+            batch: Duration::from_secs(30),
+            stream: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Line<C, T> {
+    cursor: C,
+    object: T,
+}
+
+/// Streams the objects as newline-delimited JSON, pairing each with the cursor returned for it by
+/// `cursor`, within the default [`NdjsonTimeouts`].
+pub fn ndjson_response<S, T, E, C>(
+    objects: S,
+    cursor: impl Fn(&T) -> C + Send + 'static,
+) -> Response
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: Into<ApiError> + Send + 'static,
+    C: Serialize,
+{
+    ndjson_response_with_timeouts(objects, cursor, NdjsonTimeouts::default())
+}
+
+/// Streams the objects as newline-delimited JSON, pairing each with the cursor returned for it by
+/// `cursor`, and ending the stream with an error line once one of the `timeouts` elapses.
+pub fn ndjson_response_with_timeouts<S, T, E, C>(
+    objects: S,
+    cursor: impl Fn(&T) -> C + Send + 'static,
+    timeouts: NdjsonTimeouts,
+) -> Response
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: Into<ApiError> + Send + 'static,
+    C: Serialize,
+{
+    let (lines_tx, lines_rx) = mpsc::channel(LINE_BUFFER);
+    tokio::spawn(write_lines(objects, cursor, timeouts, lines_tx));
+
+    let lines = stream::unfold(lines_rx, |mut lines_rx| async move {
+        let line = lines_rx.recv().await?;
+        Some((Ok::<_, Infallible>(line), lines_rx))
+    });
+
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        StreamBody::new(lines),
+    )
+        .into_response()
+}
+
+async fn write_lines<S, T, E, C>(
+    objects: S,
+    cursor: impl Fn(&T) -> C,
+    timeouts: NdjsonTimeouts,
+    lines_tx: mpsc::Sender<Vec<u8>>,
+) where
+    S: Stream<Item = Result<T, E>>,
+    T: Serialize,
+    E: Into<ApiError>,
+    C: Serialize,
+{
+    let deadline = Instant::now() + timeouts.stream;
+    let mut objects = Box::pin(objects);
+
+    loop {
+        let next = time::timeout_at(
+            deadline.min(Instant::now() + timeouts.batch),
+            objects.next(),
+        )
+        .await;
+        let line = match next {
+            Ok(None) => break,
+            Ok(Some(object)) => object.map_err(Into::into).and_then(|object| {
+                serde_json::to_vec(&Line {
+                    cursor: cursor(&object),
+                    object,
+                })
+                .map_err(|err| ApiError::new(ApiErrorCode::Internal, err.to_string()))
+            }),
+            Err(_) if Instant::now() >= deadline => Err(ApiError::new(
+                ApiErrorCode::Unavailable,
+                format!(
+                    "stream took longer than {:?}, resume it from the last cursor",
+                    timeouts.stream
+                ),
+            )),
+            Err(_) => Err(ApiError::new(
+                ApiErrorCode::Unavailable,
+                format!(
+                    "no object was read within {:?}, resume the stream from the last cursor",
+                    timeouts.batch
+                ),
+            )),
+        };
+
+        match line {
+            Ok(mut line) => {
+                line.push(b'\n');
+                match time::timeout_at(deadline, lines_tx.send(line)).await {
+                    Ok(Ok(())) => {}
+                    // The client went away
+                    Ok(Err(_)) => break,
+                    Err(_) => {
+                        warn!("ending ndjson stream: the client did not keep up with it");
+                        break;
+                    }
+                }
+            }
+            Err(err) => {
+                warn!(code = %err.code(), "ending ndjson stream: {}", err.message());
+                let mut line = serde_json::to_vec(&err.body()).unwrap_or_default();
+                line.push(b'\n');
+                // The deadline may have passed already, so the error line only goes out if there
+                // is room left for it
+                let _ = lines_tx.try_send(line);
+                break;
+            }
+        }
+    }
+}
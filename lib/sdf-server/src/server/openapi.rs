@@ -0,0 +1,466 @@
+//! The OpenAPI description of the sdf routes, which client teams generate their request and
+//! response types from. It is assembled from the `#[utoipa::path]` annotations of the handlers
+//! and served at `/openapi.json`.
+//!
+//! Models which live in the dal are described as opaque objects for now, while their ids are
+//! described as strings.
+
+use axum::routing::get;
+use axum::{Json, Router};
+use utoipa::OpenApi;
+
+use super::service;
+use super::state::AppState;
+use super::upload;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "sdf"),
+    paths(
+        service::admin::compare_func_versions::compare_func_versions,
+        service::admin::create_func_version::create_func_version,
+        service::admin::create_workspace_backup::create_workspace_backup,
+        service::admin::job_queue_stats::job_queue_stats,
+        service::admin::list_dead_lettered_jobs::list_dead_lettered_jobs,
+        service::admin::list_func_versions::list_func_versions,
+        service::admin::list_schema_category_rules::list_schema_category_rules,
+        service::admin::list_workspace_backups::list_workspace_backups,
+        service::admin::list_workspaces::list_workspaces,
+        service::admin::migrate_builtins::migrate_builtins,
+        service::admin::restore_workspace_backup::restore_workspace_backup,
+        service::admin::send_control_command::send_control_command,
+        service::admin::set_admin::set_admin,
+        service::admin::set_feature_flag::set_feature_flag,
+        service::admin::set_func_version_pin::set_func_version_pin,
+        service::admin::set_schema_category_rule::set_schema_category_rule,
+        service::admin::sync_resources::sync_resources,
+        service::api_token::create_api_token::create_api_token,
+        service::api_token::list_api_tokens::list_api_tokens,
+        service::api_token::revoke_api_token::revoke_api_token,
+        service::application::export_docker_compose::export_docker_compose,
+        service::audit::list_audit_logs::list_audit_logs,
+        service::audit::list_history_events::list_history_events,
+        service::audit::stream_audit_logs::stream_audit_logs,
+        service::audit::stream_history_events::stream_history_events,
+        service::blueprint::create_blueprint::create_blueprint,
+        service::blueprint::delete_blueprint::delete_blueprint,
+        service::blueprint::get_blueprint::get_blueprint,
+        service::blueprint::instantiate_blueprint::instantiate_blueprint,
+        service::blueprint::list_blueprints::list_blueprints,
+        service::blueprint::update_blueprint::update_blueprint,
+        service::change_set::list_open_change_sets::list_open_change_sets,
+        service::change_set::create_change_set::create_change_set,
+        service::change_set::get_change_set::get_change_set,
+        service::change_set::get_stats::get_stats,
+        service::change_set::get_qualification_summary::get_qualification_summary,
+        service::change_set::apply_change_set::apply_change_set,
+        service::change_set::apply_change_set2::apply_change_set,
+        service::change_set::request_review::request_review,
+        service::change_set::review_change_set::review_change_set,
+        service::change_set::list_reviews::list_reviews,
+        service::change_set::schedule_apply::schedule_apply,
+        service::change_set::cancel_scheduled_apply::cancel_scheduled_apply,
+        service::change_set::list_scheduled_applies::list_scheduled_applies,
+        service::change_set::update_selected_change_set::update_selected_change_set,
+        service::comment::list_comments::list_comments,
+        service::comment::create_comment::create_comment,
+        service::comment::update_comment::update_comment,
+        service::comment::delete_comment::delete_comment,
+        service::component::get_attribute_value_provenance::get_attribute_value_provenance,
+        service::component::get_components_metadata::get_components_metadata,
+        service::component::list_qualifications::list_qualifications,
+        service::component::list_resources::list_resources,
+        service::component::list_lifecycles::list_lifecycles,
+        service::component::list_labels::list_labels,
+        service::component::set_label::set_label,
+        service::component::remove_label::remove_label,
+        service::component::list_snapshots::list_snapshots,
+        service::component::get_snapshot::get_snapshot,
+        service::component::create_snapshot::create_snapshot,
+        service::component::restore_snapshot::restore_snapshot,
+        service::component::stream_components::stream_components,
+        service::component::get_code::get_code,
+        service::component::list_code_artifacts::list_code_artifacts,
+        service::component::get_code_artifact::get_code_artifact,
+        service::component::get_diff::get_diff,
+        service::component::get_code_diff::get_code_diff,
+        service::component::get_prop_suggestions::get_prop_suggestions,
+        service::component::get_property_editor_schema::get_property_editor_schema,
+        service::component::get_property_editor_values::get_property_editor_values,
+        service::component::update_property_editor_value::update_property_editor_value,
+        service::component::update_properties::update_properties,
+        service::component::insert_property_editor_value::insert_property_editor_value,
+        service::component::get_property_editor_validations::get_property_editor_validations,
+        service::component::set_type::set_type,
+        service::component::set_resource_conflict_policy::set_resource_conflict_policy,
+        service::component::refresh::refresh,
+        service::component::resource_domain_diff::get_diff,
+        service::component::alter_simulation::alter_simulation,
+        service::feature_flag::list_feature_flags::list_feature_flags,
+        service::feature_flag::set_feature_flag::set_feature_flag,
+        service::fix::confirmations::confirmations,
+        service::fix::list::list,
+        service::fix::run::run,
+        service::fix::preview_plan::preview_plan,
+        service::fix::run_plan::run_plan,
+        service::fix::get_plan::get_plan,
+        service::fix::pause_plan::pause_plan,
+        service::fix::resume_plan::resume_plan,
+        service::func::list_funcs::list_funcs,
+        service::func::get_func::get_func,
+        service::func::get_func::get_latest_func_execution,
+        service::func::create_func::create_func,
+        service::func::save_func::save_func,
+        service::func::save_and_exec::save_and_exec,
+        service::func::revert_func::revert_func,
+        service::func::list_input_sources::list_input_sources,
+        service::job::list_dead_lettered_jobs::list_dead_lettered_jobs,
+        service::job::replay_dead_lettered_jobs::replay_dead_lettered_jobs,
+        service::pkg::export_pkg::export_pkg,
+        service::pkg::get_pkg::get_module_by_hash,
+        service::pkg::install_local_pkg::install_local_pkg,
+        service::pkg::install_pkg::install_pkg,
+        service::pkg::list_local_pkgs::list_local_pkgs,
+        service::pkg::list_pkgs::list_pkgs,
+        service::pkg::remote_module_spec::remote_module_spec,
+        service::pkg::uninstall_pkg::uninstall_pkg,
+        service::pkg::upload_pkg::upload_pkg,
+        service::provider::list_all_providers::list_all_providers,
+        service::qualification::get_summary::get_summary,
+        service::saved_view::create_saved_view::create_saved_view,
+        service::saved_view::delete_saved_view::delete_saved_view,
+        service::saved_view::get_saved_view::get_saved_view,
+        service::saved_view::list_saved_views::list_saved_views,
+        service::saved_view::resolve_saved_view::resolve_saved_view,
+        service::saved_view::update_saved_view::update_saved_view,
+        service::schema::create_schema::create_schema,
+        service::schema::list_schemas::list_schemas,
+        service::schema::get_schema::get_schema,
+        service::schema::get_variant_json_schema::get_variant_json_schema,
+        service::schema::import_json_schema::import_json_schema,
+        service::schema::clone_variant::clone_variant,
+        service::schema::add_variant_prop::add_variant_prop,
+        service::schema::remove_variant_prop::remove_variant_prop,
+        service::schema::set_default_variant::set_default_variant,
+        service::diagram::get_diagram::get_diagram,
+        service::diagram::get_node_add_menu::get_node_add_menu,
+        service::diagram::get_schema_catalog::get_schema_catalog,
+        service::diagram::create_node::create_node,
+        service::diagram::set_node_position::set_node_position,
+        service::diagram::move_nodes::move_nodes,
+        service::diagram::delete_nodes::delete_nodes,
+        service::diagram::list_compatible_sockets::list_compatible_sockets,
+        service::diagram::create_connection::create_connection,
+        service::diagram::delete_connection::delete_connection,
+        service::diagram::restore_connection::restore_connection,
+        service::diagram::delete_component::delete_component,
+        service::diagram::delete_component::delete_components,
+        service::diagram::restore_component::restore_component,
+        service::diagram::restore_component::restore_components,
+        service::diagram::connect_component_to_frame::connect_component_to_frame,
+        service::diagram::list_schema_variants::list_schema_variants,
+        service::secret::get_public_key::get_public_key,
+        service::secret::create_secret::create_secret,
+        service::secret::list_secrets::list_secrets,
+        service::session::auth_connect::auth_connect,
+        service::session::restore_authentication::restore_authentication,
+        service::session::load_workspace::load_workspace,
+        service::session::logout_all::logout_all,
+        service::slack::delete_slack_integration::delete_slack_integration,
+        service::slack::get_slack_integration::get_slack_integration,
+        service::slack::send_slack_test_message::send_slack_test_message,
+        service::slack::update_slack_integration::update_slack_integration,
+        service::status::list_active_statuses::list_active_statuses,
+        service::variant_definition::list_variant_defs::list_variant_defs,
+        service::variant_definition::get_variant_def::get_variant_def,
+        service::variant_definition::save_variant_def::save_variant_def,
+        service::variant_definition::create_variant_def::create_variant_def,
+        service::variant_definition::exec_variant_def::exec_variant_def,
+        service::variant_definition::clone_variant_def::clone_variant_def,
+        service::webhook::create_webhook::create_webhook,
+        service::webhook::delete_webhook::delete_webhook,
+        service::webhook::list_webhook_deliveries::list_webhook_deliveries,
+        service::webhook::list_webhooks::list_webhooks,
+        service::webhook::redeliver_webhook_delivery::redeliver_webhook_delivery,
+        service::webhook::update_webhook::update_webhook,
+        service::workspace::get_usage::get_usage,
+        service::workspace::import_workspace::import_workspace,
+        service::workspace_settings::get_workspace_settings::get_workspace_settings,
+        service::workspace_settings::update_workspace_settings::update_workspace_settings,
+        service::ws::presence::list_presence,
+    ),
+    components(schemas(
+        dal::Visibility,
+        service::admin::compare_func_versions::CompareFuncVersionsRequest,
+        service::admin::create_func_version::CreateFuncVersionRequest,
+        service::admin::create_func_version::CreateFuncVersionResponse,
+        service::admin::create_workspace_backup::CreateWorkspaceBackupRequest,
+        service::admin::create_workspace_backup::CreateWorkspaceBackupResponse,
+        service::admin::job_queue_stats::JobQueueStatsResponse,
+        service::admin::list_dead_lettered_jobs::ListDeadLetteredJobsResponse,
+        service::admin::list_func_versions::ListFuncVersionsResponse,
+        service::admin::list_schema_category_rules::ListSchemaCategoryRulesResponse,
+        service::admin::list_workspace_backups::ListWorkspaceBackupsResponse,
+        service::admin::list_workspaces::ListWorkspacesResponse,
+        service::admin::migrate_builtins::MigrateBuiltinsRequest,
+        service::admin::migrate_builtins::MigrateBuiltinsResponse,
+        service::admin::restore_workspace_backup::RestoreWorkspaceBackupRequest,
+        service::admin::restore_workspace_backup::RestoreWorkspaceBackupResponse,
+        service::admin::send_control_command::SendControlCommandRequest,
+        service::admin::send_control_command::SendControlCommandResponse,
+        service::admin::set_admin::SetAdminRequest,
+        service::admin::set_admin::SetAdminResponse,
+        service::admin::set_feature_flag::AdminSetFeatureFlagRequest,
+        service::admin::set_feature_flag::AdminSetFeatureFlagResponse,
+        service::admin::set_func_version_pin::SetFuncVersionPinRequest,
+        service::admin::set_func_version_pin::SetFuncVersionPinResponse,
+        service::admin::set_schema_category_rule::SetSchemaCategoryRuleRequest,
+        service::admin::set_schema_category_rule::SetSchemaCategoryRuleResponse,
+        service::admin::sync_resources::SyncResourcesRequest,
+        service::admin::sync_resources::SyncResourcesResponse,
+        service::api_token::create_api_token::CreateApiTokenRequest,
+        service::api_token::create_api_token::CreateApiTokenResponse,
+        service::api_token::list_api_tokens::ListApiTokensResponse,
+        service::api_token::revoke_api_token::RevokeApiTokenRequest,
+        service::api_token::revoke_api_token::RevokeApiTokenResponse,
+        service::blueprint::create_blueprint::CreateBlueprintRequest,
+        service::blueprint::create_blueprint::CreateBlueprintResponse,
+        service::blueprint::delete_blueprint::DeleteBlueprintRequest,
+        service::blueprint::get_blueprint::GetBlueprintResponse,
+        service::blueprint::instantiate_blueprint::InstantiateBlueprintRequest,
+        service::blueprint::instantiate_blueprint::InstantiateBlueprintResponse,
+        service::blueprint::list_blueprints::ListBlueprintsResponse,
+        service::blueprint::update_blueprint::UpdateBlueprintRequest,
+        service::blueprint::update_blueprint::UpdateBlueprintResponse,
+        service::change_set::apply_change_set::ApplyChangeSetRequest,
+        service::change_set::apply_change_set::ApplyChangeSetResponse,
+        service::change_set::apply_change_set2::ApplyChangeSet2Request,
+        service::change_set::apply_change_set2::FixRunRequest,
+        service::change_set::cancel_scheduled_apply::CancelScheduledApplyRequest,
+        service::change_set::cancel_scheduled_apply::CancelScheduledApplyResponse,
+        service::change_set::create_change_set::CreateChangeSetRequest,
+        service::change_set::create_change_set::CreateChangeSetResponse,
+        service::change_set::get_change_set::GetChangeSetResponse,
+        service::change_set::get_qualification_summary::GetQualificationSummaryResponse,
+        service::change_set::get_stats::GetStatsResponse,
+        service::change_set::list_open_change_sets::ListOpenChangeSetsResponse,
+        service::change_set::list_reviews::ListReviewsResponse,
+        service::change_set::list_scheduled_applies::ListScheduledAppliesResponse,
+        service::change_set::request_review::RequestReviewRequest,
+        service::change_set::request_review::RequestReviewResponse,
+        service::change_set::review_change_set::ReviewChangeSetRequest,
+        service::change_set::review_change_set::ReviewChangeSetResponse,
+        service::change_set::schedule_apply::ScheduleApplyRequest,
+        service::change_set::schedule_apply::ScheduleApplyResponse,
+        service::change_set::update_selected_change_set::UpdateSelectedChangeSetRequest,
+        service::change_set::update_selected_change_set::UpdateSelectedChangeSetResponse,
+        service::comment::create_comment::CreateCommentRequest,
+        service::comment::create_comment::CreateCommentResponse,
+        service::comment::delete_comment::DeleteCommentRequest,
+        service::comment::delete_comment::DeleteCommentResponse,
+        service::comment::list_comments::ListCommentsResponse,
+        service::comment::update_comment::UpdateCommentRequest,
+        service::comment::update_comment::UpdateCommentResponse,
+        service::component::alter_simulation::AlterSimulationRequest,
+        service::component::alter_simulation::AlterSimulationResponse,
+        service::component::create_snapshot::CreateSnapshotRequest,
+        service::component::create_snapshot::CreateSnapshotResponse,
+        service::component::get_code::GetCodeResponse,
+        service::component::list_code_artifacts::ListCodeArtifactsResponse,
+        service::component::list_lifecycles::ComponentLifecycleView,
+        service::component::get_components_metadata::ComponentMetadata,
+        service::component::get_components_metadata::GetComponentsMetadataResponse,
+        service::component::get_code_diff::GetCodeDiffResponse,
+        service::component::get_diff::GetDiffResponse,
+        service::component::get_prop_suggestions::GetPropSuggestionsResponse,
+        service::component::get_snapshot::GetSnapshotResponse,
+        service::component::insert_property_editor_value::InsertPropertyEditorValueRequest,
+        service::component::refresh::RefreshRequest,
+        service::component::remove_label::RemoveLabelRequest,
+        service::component::refresh::RefreshResponse,
+        service::component::resource_domain_diff::GetResourceDomainDiffResponse,
+        service::component::resource_domain_diff::ResourceDomainDiff,
+        service::component::restore_snapshot::RestoreSnapshotRequest,
+        service::component::restore_snapshot::RestoreSnapshotResponse,
+        service::component::set_label::SetLabelRequest,
+        service::component::set_label::SetLabelResponse,
+        service::component::set_resource_conflict_policy::SetResourceConflictPolicyRequest,
+        service::component::set_type::SetTypeRequest,
+        service::component::update_properties::UpdatePropertiesRequest,
+        service::component::update_properties::UpdatePropertiesResponse,
+        service::component::update_property_editor_value::UpdatePropertyEditorValueRequest,
+        service::diagram::connect_component_to_frame::CreateFrameConnectionRequest,
+        service::diagram::connect_component_to_frame::CreateFrameConnectionResponse,
+        service::diagram::create_connection::CreateConnectionRequest,
+        service::diagram::create_connection::CreateConnectionResponse,
+        service::diagram::create_node::CreateNodeRequest,
+        service::diagram::create_node::CreateNodeResponse,
+        service::diagram::delete_component::DeleteComponentRequest,
+        service::diagram::delete_component::DeleteComponentsRequest,
+        service::diagram::delete_connection::DeleteConnectionRequest,
+        service::diagram::delete_nodes::DeleteNodesRequest,
+        service::diagram::delete_nodes::DeleteNodesResponse,
+        service::diagram::get_node_add_menu::GetNodeAddMenuRequest,
+        service::diagram::list_schema_variants::InputProviderView,
+        service::diagram::list_schema_variants::InputSocketView,
+        service::diagram::list_schema_variants::OutputProviderView,
+        service::diagram::list_schema_variants::OutputSocketView,
+        service::diagram::list_schema_variants::SchemaVariantView,
+        service::diagram::move_nodes::MoveNodesRequest,
+        service::diagram::move_nodes::MoveNodesResponse,
+        service::diagram::restore_component::RestoreComponentRequest,
+        service::diagram::restore_component::RestoreComponentsRequest,
+        service::diagram::restore_connection::UndeleteConnectionRequest,
+        service::diagram::set_node_position::SetNodePositionRequest,
+        service::diagram::set_node_position::SetNodePositionResponse,
+        service::feature_flag::list_feature_flags::ListFeatureFlagsResponse,
+        service::feature_flag::set_feature_flag::SetFeatureFlagRequest,
+        service::feature_flag::set_feature_flag::SetFeatureFlagResponse,
+        service::fix::confirmations::ConfirmationsResponse,
+        service::fix::get_plan::GetPlanResponse,
+        service::fix::list::BatchHistoryView,
+        service::fix::pause_plan::PausePlanRequest,
+        service::fix::pause_plan::PausePlanResponse,
+        service::fix::preview_plan::PreviewPlanRequest,
+        service::fix::preview_plan::PreviewPlanResponse,
+        service::fix::resume_plan::ResumePlanRequest,
+        service::fix::resume_plan::ResumePlanResponse,
+        service::fix::run::FixesRunRequest,
+        service::fix::run::FixesRunResponse,
+        service::fix::run_plan::RunPlanRequest,
+        service::fix::run_plan::RunPlanResponse,
+        service::func::FuncVariant,
+        service::func::create_func::CreateFuncRequest,
+        service::func::create_func::CreateFuncResponse,
+        service::func::get_func::GetFuncResponse,
+        service::func::get_func::GetLatestFuncExecutionResponse,
+        service::func::list_funcs::ListFuncsResponse,
+        service::func::list_funcs::ListedFuncView,
+        service::func::list_input_sources::InputSourceProp,
+        service::func::list_input_sources::InputSourceSocket,
+        service::func::list_input_sources::ListInputSourcesResponse,
+        service::func::list_input_sources::OutputSocket,
+        service::func::revert_func::RevertFuncRequest,
+        service::func::revert_func::RevertFuncResponse,
+        service::func::save_func::SaveFuncRequest,
+        service::func::save_func::SaveFuncResponse,
+        service::job::replay_dead_lettered_jobs::ReplayDeadLetteredJobsRequest,
+        service::job::replay_dead_lettered_jobs::ReplayDeadLetteredJobsResponse,
+        service::pkg::PkgView,
+        service::pkg::export_pkg::ExportPkgRequest,
+        service::pkg::export_pkg::ExportPkgResponse,
+        service::pkg::get_pkg::PkgFuncView,
+        service::pkg::get_pkg::PkgGetResponse,
+        service::pkg::install_local_pkg::InstallLocalPkgRequest,
+        service::pkg::install_local_pkg::InstallLocalPkgResponse,
+        service::pkg::install_pkg::InstallPkgRequest,
+        service::pkg::install_pkg::InstallPkgResponse,
+        service::pkg::list_local_pkgs::ListLocalPkgsResponse,
+        service::pkg::list_pkgs::InstalledPkgView,
+        service::pkg::list_pkgs::PkgListResponse,
+        service::pkg::uninstall_pkg::UninstallPkgRequest,
+        service::pkg::uninstall_pkg::UninstallPkgResponse,
+        service::pkg::upload_pkg::UploadPkgResponse,
+        service::provider::list_all_providers::ListAllProviderResponse,
+        service::saved_view::create_saved_view::CreateSavedViewRequest,
+        service::saved_view::create_saved_view::CreateSavedViewResponse,
+        service::saved_view::delete_saved_view::DeleteSavedViewRequest,
+        service::saved_view::get_saved_view::GetSavedViewResponse,
+        service::saved_view::list_saved_views::ListSavedViewsResponse,
+        service::saved_view::update_saved_view::UpdateSavedViewRequest,
+        service::saved_view::update_saved_view::UpdateSavedViewResponse,
+        service::schema::add_variant_prop::AddVariantPropRequest,
+        service::schema::add_variant_prop::AddVariantPropResponse,
+        service::schema::clone_variant::CloneVariantRequest,
+        service::schema::clone_variant::CloneVariantResponse,
+        service::schema::create_schema::CreateSchemaRequest,
+        service::schema::create_schema::CreateSchemaResponse,
+        service::schema::import_json_schema::ImportJsonSchemaRequest,
+        service::schema::import_json_schema::ImportJsonSchemaResponse,
+        service::schema::list_schemas::ListSchemaResponse,
+        service::schema::remove_variant_prop::RemoveVariantPropRequest,
+        service::schema::remove_variant_prop::RemoveVariantPropResponse,
+        service::schema::set_default_variant::SetDefaultVariantRequest,
+        service::schema::set_default_variant::SetDefaultVariantResponse,
+        service::secret::create_secret::CreateSecretRequest,
+        service::secret::create_secret::CreateSecretResponse,
+        service::secret::list_secrets::ListSecretResponse,
+        service::session::auth_connect::AuthConnectRequest,
+        service::session::auth_connect::AuthConnectResponse,
+        service::session::load_workspace::LoadWorkspaceResponse,
+        service::session::logout_all::LogoutAllResponse,
+        service::session::restore_authentication::RestoreAuthenticationResponse,
+        service::slack::get_slack_integration::GetSlackIntegrationResponse,
+        service::slack::update_slack_integration::UpdateSlackIntegrationRequest,
+        service::slack::update_slack_integration::UpdateSlackIntegrationResponse,
+        service::status::list_active_statuses::ActiveStatus,
+        service::variant_definition::clone_variant_def::CloneVariantDefRequest,
+        service::variant_definition::clone_variant_def::CloneVariantDefResponse,
+        service::variant_definition::create_variant_def::CreateVariantDefRequest,
+        service::variant_definition::create_variant_def::CreateVariantDefResponse,
+        service::variant_definition::exec_variant_def::ExecVariantDefResponse,
+        service::variant_definition::get_variant_def::GetVariantDefResponse,
+        service::variant_definition::list_variant_defs::ListVariantDefsResponse,
+        service::variant_definition::list_variant_defs::ListedVariantDef,
+        service::variant_definition::save_variant_def::SaveVariantDefRequest,
+        service::variant_definition::save_variant_def::SaveVariantDefResponse,
+        service::webhook::create_webhook::CreateWebhookRequest,
+        service::webhook::create_webhook::CreateWebhookResponse,
+        service::webhook::delete_webhook::DeleteWebhookRequest,
+        service::webhook::list_webhook_deliveries::ListWebhookDeliveriesResponse,
+        service::webhook::list_webhooks::ListWebhooksResponse,
+        service::webhook::redeliver_webhook_delivery::RedeliverWebhookDeliveryRequest,
+        service::webhook::redeliver_webhook_delivery::RedeliverWebhookDeliveryResponse,
+        service::webhook::update_webhook::UpdateWebhookRequest,
+        service::webhook::update_webhook::UpdateWebhookResponse,
+        service::workspace::import_workspace::ImportWorkspaceResponse,
+        service::workspace_settings::get_workspace_settings::GetWorkspaceSettingsResponse,
+        service::workspace_settings::update_workspace_settings::UpdateWorkspaceSettingsRequest,
+        service::workspace_settings::update_workspace_settings::UpdateWorkspaceSettingsResponse,
+        service::ws::presence::ListPresenceResponse,
+        service::ws::presence::UserPresence,
+        upload::UploadForm,
+    )),
+    tags(
+        (name = "admin"),
+        (name = "api_token"),
+        (name = "application"),
+        (name = "audit"),
+        (name = "blueprint"),
+        (name = "change_set"),
+        (name = "comment"),
+        (name = "component"),
+        (name = "feature_flag"),
+        (name = "fix"),
+        (name = "func"),
+        (name = "job"),
+        (name = "pkg"),
+        (name = "provider"),
+        (name = "qualification"),
+        (name = "saved_view"),
+        (name = "schema"),
+        (name = "diagram"),
+        (name = "secret"),
+        (name = "session"),
+        (name = "slack"),
+        (name = "status"),
+        (name = "variant_def"),
+        (name = "webhook"),
+        (name = "workspace"),
+        (name = "workspace_settings"),
+        (name = "ws"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Serves the OpenAPI description, which is generated once when the routes are built.
+pub fn routes() -> Router<AppState> {
+    let spec = ApiDoc::openapi();
+    Router::new().route(
+        "/openapi.json",
+        get(move || {
+            let spec = spec.clone();
+            async move { Json(spec) }
+        }),
+    )
+}
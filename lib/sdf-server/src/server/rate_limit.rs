@@ -0,0 +1,415 @@
+//! Token bucket rate limiting of the API, so that a misbehaving client cannot hammer expensive
+//! endpoints such as loading the diagram or refreshing qualifications.
+//!
+//! Requests under `/api/` are counted against the route group they target, which is the path
+//! segment following `/api/` (such as `diagram`). Within a group, every client IP and every valid
+//! bearer token has its own bucket, and a request is rejected with `429 Too Many Requests` and a
+//! `Retry-After` header as soon as either of its buckets is empty.
+//!
+//! The bucket of the client IP is taken from by the [`RateLimitLayer`]. The one of the token can
+//! only be taken from once the token has been validated, so the layer leaves a [`TokenRateLimit`]
+//! in the extensions of the request for the authorization extractors to take from. Requests whose
+//! token does not validate are only counted against the bucket of their IP, so that made up
+//! tokens cannot each get a bucket of their own.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    net::IpAddr,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::{
+    extract::{connect_info::Connected, ConnectInfo},
+    http::{HeaderMap, Request},
+    response::{IntoResponse, Response},
+};
+use futures::future::{self, Either, Ready};
+use hyper::server::conn::AddrStream;
+use serde::{Deserialize, Serialize};
+use si_settings::{require_non_zero, SettingsError};
+use telemetry::metrics::{Counter, Gauge};
+use telemetry::prelude::*;
+use tokio::{net::UnixStream, time::Instant};
+use tower::{Layer, Service};
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+
+static RATE_LIMITED_REQUESTS: Counter = Counter::new(
+    "sdf_rate_limited_requests_total",
+    "Total number of requests rejected by the rate limiter",
+);
+static RATE_LIMIT_BUCKETS: Gauge = Gauge::new(
+    "sdf_rate_limit_buckets",
+    "Number of rate limit buckets currently tracked",
+);
+
+/// How often buckets which have refilled completely are forgotten.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The rate limits of the API, which are only enforced once enabled.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// The number of proxies in front of sdf which append the address they were connected from to
+    /// the `X-Forwarded-For` header. The client IP is the entry this many hops from the right of
+    /// the header, since the entries left of it are whatever the client sent. When zero, the
+    /// client IP is the address of the connection.
+    pub trusted_proxy_hops: usize,
+    /// The limits of route groups without limits of their own.
+    pub default: RouteGroupLimits,
+    /// The limits of route groups, by group name.
+    pub groups: HashMap<String, RouteGroupLimits>,
+}
+
+impl RateLimitConfig {
+    /// Checks that every bucket refills and holds at least one request, naming the offending
+    /// setting under `key` otherwise.
+    pub fn validate(&self, key: &str) -> Result<(), SettingsError> {
+        self.default.validate(&format!("{key}.default"))?;
+        for (group, limits) in &self.groups {
+            limits.validate(&format!("{key}.groups.{group}"))?;
+        }
+        Ok(())
+    }
+
+    fn limits(&self, group: &str) -> &RouteGroupLimits {
+        self.groups.get(group).unwrap_or(&self.default)
+    }
+}
+
+/// The limits of a route group, for each bearer token and for each client IP.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RouteGroupLimits {
+    pub per_token: BucketConfig,
+    pub per_ip: BucketConfig,
+}
+
+impl Default for RouteGroupLimits {
+    fn default() -> Self {
+        Self {
+            per_token: BucketConfig {
+                burst: 100,
+                per_second: 20.0,
+            },
+            per_ip: BucketConfig {
+                burst: 200,
+                per_second: 40.0,
+            },
+        }
+    }
+}
+
+impl RouteGroupLimits {
+    fn validate(&self, key: &str) -> Result<(), SettingsError> {
+        self.per_token.validate(&format!("{key}.per_token"))?;
+        self.per_ip.validate(&format!("{key}.per_ip"))
+    }
+}
+
+/// A token bucket holding up to `burst` requests and refilling at `per_second` requests a second.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct BucketConfig {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+impl BucketConfig {
+    fn validate(&self, key: &str) -> Result<(), SettingsError> {
+        require_non_zero(&format!("{key}.burst"), self.burst)?;
+        if self.per_second.is_nan() || self.per_second <= 0.0 {
+            return Err(SettingsError::invalid_value(
+                format!("{key}.per_second"),
+                "must be greater than zero",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The IP of the client of a connection, or `None` for connections over a Unix domain socket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientAddr(pub Option<IpAddr>);
+
+impl Connected<&AddrStream> for ClientAddr {
+    fn connect_info(target: &AddrStream) -> Self {
+        Self(Some(target.remote_addr().ip()))
+    }
+}
+
+impl Connected<&UnixStream> for ClientAddr {
+    fn connect_info(_target: &UnixStream) -> Self {
+        Self(None)
+    }
+}
+
+/// Applies the [`RateLimitConfig`] to the requests of the wrapped service.
+#[derive(Clone, Debug)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            limiter: Arc::new(RateLimiter::new(config)),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RateLimit<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S, B> Service<Request<B>> for RateLimit<S>
+where
+    S: Service<Request<B>, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Response, S::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        if let Some(group) = self.limiter.route_group(&request) {
+            if let Err(retry_after) = self.limiter.check_ip(&group, &request) {
+                return Either::Right(future::ok(too_many_requests(retry_after).into_response()));
+            }
+            request.extensions_mut().insert(TokenRateLimit {
+                limiter: self.limiter.clone(),
+                group,
+            });
+        }
+        Either::Left(self.inner.call(request))
+    }
+}
+
+/// The bucket of the bearer token of a request, in the route group the request targets. It is
+/// taken from once the token has been validated.
+#[derive(Clone, Debug)]
+pub struct TokenRateLimit {
+    limiter: Arc<RateLimiter>,
+    group: String,
+}
+
+impl TokenRateLimit {
+    /// Takes a request out of the bucket of the token, which must be valid, or returns a
+    /// `429 Too Many Requests` error if the bucket is empty.
+    pub fn check(&self, token: &str) -> Result<(), ApiError> {
+        let limit = self.limiter.config.limits(&self.group).per_token;
+        self.limiter
+            .take(&self.group, Client::Token(hash_token(token)), limit)
+            .map_err(too_many_requests)
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> ApiError {
+    ApiError::new(ApiErrorCode::TooManyRequests, "too many requests").with_retry_after(retry_after)
+}
+
+#[derive(Debug)]
+struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<RateLimiterState>,
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    buckets: HashMap<BucketKey, Bucket>,
+    swept_at: Instant,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct BucketKey {
+    group: String,
+    client: Client,
+}
+
+/// Who a bucket counts the requests of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Client {
+    Ip(IpAddr),
+    Token(u64),
+}
+
+impl Client {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Ip(_) => "ip",
+            Self::Token(_) => "token",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    limit: BucketConfig,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn new(limit: BucketConfig, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst.into(),
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.per_second).min(self.limit.burst.into());
+        self.refilled_at = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= f64::from(self.limit.burst)
+    }
+
+    /// How long until the bucket holds a request again, or `None` if it holds one now.
+    fn wait(&self) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.limit.per_second,
+            ))
+        }
+    }
+}
+
+impl RateLimiter {
+    fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(RateLimiterState {
+                buckets: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns the route group of the request, if it is limited.
+    fn route_group<B>(&self, request: &Request<B>) -> Option<String> {
+        if !self.config.enabled {
+            return None;
+        }
+        route_group(request.uri().path()).map(ToOwned::to_owned)
+    }
+
+    /// Takes a request out of the bucket of the client IP of the request, if it has one.
+    fn check_ip<B>(&self, group: &str, request: &Request<B>) -> Result<(), Duration> {
+        match self.client_ip(request) {
+            Some(ip) => self.take(group, Client::Ip(ip), self.config.limits(group).per_ip),
+            None => Ok(()),
+        }
+    }
+
+    /// Takes a request out of the bucket of the client, or returns how long to wait before
+    /// retrying if it is empty.
+    fn take(&self, group: &str, client: Client, limit: BucketConfig) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.sweep(now);
+
+        let key = BucketKey {
+            group: group.to_owned(),
+            client,
+        };
+        let bucket = state
+            .buckets
+            .entry(key)
+            .or_insert_with(|| Bucket::new(limit, now));
+        bucket.refill(now);
+        let result = match bucket.wait() {
+            Some(wait) => {
+                RATE_LIMITED_REQUESTS.increment(&[("group", group), ("key", client.kind())]);
+                debug!(group, key = client.kind(), ?wait, "rate limited request");
+                Err(wait)
+            }
+            None => {
+                bucket.tokens -= 1.0;
+                Ok(())
+            }
+        };
+        RATE_LIMIT_BUCKETS.set(&[], state.buckets.len() as i64);
+        result
+    }
+
+    fn client_ip<B>(&self, request: &Request<B>) -> Option<IpAddr> {
+        if self.config.trusted_proxy_hops > 0 {
+            if let Some(ip) = forwarded_for(request.headers(), self.config.trusted_proxy_hops) {
+                return Some(ip);
+            }
+        }
+        request
+            .extensions()
+            .get::<ConnectInfo<ClientAddr>>()
+            .and_then(|ConnectInfo(ClientAddr(ip))| *ip)
+    }
+}
+
+impl RateLimiterState {
+    /// Forgets the buckets which have refilled completely, as they would be created full again.
+    fn sweep(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.swept_at) < SWEEP_INTERVAL {
+            return;
+        }
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(now);
+            !bucket.is_full()
+        });
+        self.swept_at = now;
+    }
+}
+
+fn route_group(path: &str) -> Option<&str> {
+    path.strip_prefix("/api/")?
+        .split('/')
+        .next()
+        .filter(|group| !group.is_empty())
+}
+
+/// Tokens are kept as a hash, so that they are not held in memory for longer than a request.
+fn hash_token(token: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    token.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The client IP recorded in the `X-Forwarded-For` header by the outermost of the trusted proxies,
+/// which is `hops` entries from the right.
+fn forwarded_for(headers: &HeaderMap, hops: usize) -> Option<IpAddr> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .nth(hops.checked_sub(1)?)?
+        .trim()
+        .parse()
+        .ok()
+}
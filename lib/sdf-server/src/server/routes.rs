@@ -4,7 +4,7 @@ use axum::{
     routing::get,
     Router,
 };
-use hyper::StatusCode;
+use hyper::{header, StatusCode};
 use serde_json::{json, Value};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
@@ -22,31 +22,73 @@ pub fn routes(state: AppState) -> Router {
             "/api/",
             Router::new().route("/", get(system_status_route).layer(CorsLayer::permissive())),
         )
+        .nest("/health", crate::server::service::health::routes())
+        .route("/metrics", get(metrics_route))
+        .merge(crate::server::openapi::routes())
+        .nest("/api/admin", crate::server::service::admin::routes())
+        .nest(
+            "/api/api_token",
+            crate::server::service::api_token::routes(),
+        )
+        .nest(
+            "/api/application",
+            crate::server::service::application::routes(),
+        )
+        .nest("/api/audit", crate::server::service::audit::routes())
+        .nest(
+            "/api/blueprint",
+            crate::server::service::blueprint::routes(),
+        )
         .nest(
             "/api/change_set",
             crate::server::service::change_set::routes(),
         )
+        .nest("/api/comment", crate::server::service::comment::routes())
         .nest(
             "/api/component",
             crate::server::service::component::routes(),
         )
+        .nest(
+            "/api/feature_flag",
+            crate::server::service::feature_flag::routes(),
+        )
         .nest("/api/fix", crate::server::service::fix::routes())
         .nest("/api/func", crate::server::service::func::routes())
+        .nest("/api/graphql", crate::server::service::graphql::routes())
+        .nest("/api/job", crate::server::service::job::routes())
         .nest("/api/pkg", crate::server::service::pkg::routes())
         .nest("/api/provider", crate::server::service::provider::routes())
         .nest(
             "/api/qualification",
             crate::server::service::qualification::routes(),
         )
+        .nest(
+            "/api/saved_view",
+            crate::server::service::saved_view::routes(),
+        )
         .nest("/api/schema", crate::server::service::schema::routes())
+        .nest(
+            "/api/schema_variant",
+            crate::server::service::schema::variant_routes(),
+        )
         .nest("/api/diagram", crate::server::service::diagram::routes())
         .nest("/api/secret", crate::server::service::secret::routes())
         .nest("/api/session", crate::server::service::session::routes())
+        .nest("/api/slack", crate::server::service::slack::routes())
         .nest("/api/status", crate::server::service::status::routes())
         .nest(
             "/api/variant_def",
             crate::server::service::variant_definition::routes(),
         )
+        .nest("/api/webhook", crate::server::service::webhook::routes())
+        .nest(
+            "/api/workspace",
+            crate::server::service::workspace::routes(),
+        )
+        .nest(
+            "/api/workspace_settings",
+            crate::server::service::workspace_settings::routes(),
+        )
         .nest("/api/ws", crate::server::service::ws::routes());
 
     // Load dev routes if we are in dev mode (decided by "opt-level" at the moment).
@@ -59,6 +101,13 @@ async fn system_status_route() -> Json<Value> {
     Json(json!({ "ok": true }))
 }
 
+async fn metrics_route() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, telemetry::metrics::CONTENT_TYPE)],
+        telemetry::metrics::render(),
+    )
+}
+
 #[cfg(debug_assertions)]
 pub fn dev_routes(mut router: Router<AppState>) -> Router<AppState> {
     router = router.nest("/api/dev", crate::server::service::dev::routes());
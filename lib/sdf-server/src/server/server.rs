@@ -1,13 +1,25 @@
-use std::{io, net::SocketAddr, path::Path, path::PathBuf, sync::Arc};
+use std::{io, net::SocketAddr, path::Path, path::PathBuf, sync::Arc, time::Duration};
 
 use crate::server::config::CycloneKeyPair;
-use axum::routing::IntoMakeService;
-use axum::Router;
-use dal::tasks::{StatusReceiver, StatusReceiverError};
+use axum::extract::connect_info::{Connected, IntoMakeServiceWithConnectInfo};
+use axum::{extract::DefaultBodyLimit, http::Request, Router};
+use dal::notification::{
+    EmailChannel, NotificationChannel, SlackChannel, SmtpConfig, SmtpEmailSender,
+};
+use dal::tasks::{
+    Notifier, NotifierError, StatusReceiver, StatusReceiverError, WebhookDispatcher,
+    WebhookDispatcherError,
+};
 use dal::JwtPublicSigningKey;
 use dal::{
-    cyclone_key_pair::CycloneKeyPairError, job::processor::JobQueueProcessor,
-    tasks::ResourceScheduler, ServicesContext,
+    cyclone_key_pair::CycloneKeyPairError,
+    job::processor::JobQueueProcessor,
+    tasks::{
+        AuditLogPruner, BlobGarbageCollector, HistoryEventPruner, QualificationRechecker,
+        ResourceScheduler, UsageAggregationScheduler, WorkspaceBackupScheduler,
+    },
+    AuditRetentionPolicy, Builtin, DataMigrationReport, HistoryEventRetentionPolicy,
+    ServicesContext, WorkspaceBackupPolicy,
 };
 use hyper::server::{accept::Accept, conn::AddrIncoming};
 use si_data_nats::{NatsClient, NatsConfig, NatsError};
@@ -15,16 +27,23 @@ use si_data_pg::{PgError, PgPool, PgPoolConfig, PgPoolError};
 use si_posthog::{PosthogClient, PosthogConfig};
 use si_std::SensitiveString;
 use telemetry::prelude::*;
+use telemetry::propagation::TraceContext;
 use thiserror::Error;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     signal,
     sync::{broadcast, mpsc, oneshot},
+    time,
+};
+use tower_http::trace::{DefaultMakeSpan, MakeSpan, TraceLayer};
+use veritech_client::{
+    Client as VeritechClient, EncryptionKey, EncryptionKeyError, VeritechClientConfig,
 };
-use tower_http::trace::{DefaultMakeSpan, TraceLayer};
-use veritech_client::{Client as VeritechClient, EncryptionKey, EncryptionKeyError};
 
+use super::rate_limit::{ClientAddr, RateLimitConfig, RateLimitLayer};
+use super::service::ws::credentials::{WsNatsCredentials, WsNatsCredentialsError};
 use super::state::AppState;
+use super::upload::BodyLimitsConfig;
 use super::{routes, Config, IncomingStream, UdsIncomingStream, UdsIncomingStreamError};
 
 #[remain::sorted]
@@ -49,6 +68,8 @@ pub enum ServerError {
     #[error(transparent)]
     Nats(#[from] NatsError),
     #[error(transparent)]
+    Notifier(#[from] NotifierError),
+    #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
     PgPool(#[from] Box<PgPoolError>),
@@ -60,6 +81,10 @@ pub enum ServerError {
     StatusReceiver(#[from] StatusReceiverError),
     #[error(transparent)]
     Uds(#[from] UdsIncomingStreamError),
+    #[error(transparent)]
+    WebhookDispatcher(#[from] WebhookDispatcherError),
+    #[error(transparent)]
+    WsNatsCredentials(#[from] WsNatsCredentialsError),
     #[error("wrong incoming stream for {0} server: {1:?}")]
     WrongIncomingStream(&'static str, IncomingStream),
 }
@@ -72,10 +97,14 @@ impl From<PgPoolError> for ServerError {
 
 pub type Result<T, E = ServerError> = std::result::Result<T, E>;
 
+/// How long in-flight HTTP requests are given to complete once a graceful shutdown has started.
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub struct Server<I, S> {
     config: Config,
-    inner: axum::Server<I, IntoMakeService<Router>>,
+    inner: axum::Server<I, IntoMakeServiceWithConnectInfo<Router, ClientAddr>>,
     socket: S,
+    shutdown_tx: mpsc::Sender<ShutdownSource>,
     shutdown_rx: oneshot::Receiver<()>,
 }
 
@@ -92,10 +121,11 @@ impl Server<(), ()> {
         posthog_client: PosthogClient,
         pkgs_path: PathBuf,
         module_index_url: String,
+        ws_nats_credentials: WsNatsCredentials,
     ) -> Result<(Server<AddrIncoming, SocketAddr>, broadcast::Receiver<()>)> {
         match config.incoming_stream() {
             IncomingStream::HTTPSocket(socket_addr) => {
-                let services_context = ServicesContext::new(
+                let mut services_context = ServicesContext::new(
                     pg_pool,
                     nats,
                     job_processor,
@@ -104,16 +134,28 @@ impl Server<(), ()> {
                     Some(pkgs_path),
                     Some(module_index_url),
                 );
+                services_context.set_feature_flag_defaults(
+                    config
+                        .features()
+                        .iter()
+                        .map(|(name, enabled)| (name.to_string(), enabled)),
+                );
 
-                let (service, shutdown_rx, shutdown_broadcast_rx) = build_service(
-                    services_context,
-                    jwt_public_signing_key,
-                    config.signup_secret().clone(),
-                    posthog_client,
-                )?;
+                let (service, shutdown_tx, shutdown_rx, shutdown_broadcast_rx) =
+                    build_service_inner(
+                        services_context,
+                        jwt_public_signing_key,
+                        config.signup_secret().clone(),
+                        posthog_client,
+                        config.rate_limit().clone(),
+                        config.body_limits().clone(),
+                        ws_nats_credentials,
+                        false,
+                    )?;
 
                 info!("binding to HTTP socket; socket_addr={}", &socket_addr);
-                let inner = axum::Server::bind(socket_addr).serve(service.into_make_service());
+                let inner = axum::Server::bind(socket_addr)
+                    .serve(service.into_make_service_with_connect_info::<ClientAddr>());
                 let socket = inner.local_addr();
 
                 Ok((
@@ -121,6 +163,7 @@ impl Server<(), ()> {
                         config,
                         inner,
                         socket,
+                        shutdown_tx,
                         shutdown_rx,
                     },
                     shutdown_broadcast_rx,
@@ -144,10 +187,11 @@ impl Server<(), ()> {
         posthog_client: PosthogClient,
         pkgs_path: PathBuf,
         module_index_url: String,
+        ws_nats_credentials: WsNatsCredentials,
     ) -> Result<(Server<UdsIncomingStream, PathBuf>, broadcast::Receiver<()>)> {
         match config.incoming_stream() {
             IncomingStream::UnixDomainSocket(path) => {
-                let services_context = ServicesContext::new(
+                let mut services_context = ServicesContext::new(
                     pg_pool,
                     nats,
                     job_processor,
@@ -156,17 +200,28 @@ impl Server<(), ()> {
                     Some(pkgs_path),
                     Some(module_index_url),
                 );
+                services_context.set_feature_flag_defaults(
+                    config
+                        .features()
+                        .iter()
+                        .map(|(name, enabled)| (name.to_string(), enabled)),
+                );
 
-                let (service, shutdown_rx, shutdown_broadcast_rx) = build_service(
-                    services_context,
-                    jwt_public_signing_key,
-                    config.signup_secret().clone(),
-                    posthog_client,
-                )?;
+                let (service, shutdown_tx, shutdown_rx, shutdown_broadcast_rx) =
+                    build_service_inner(
+                        services_context,
+                        jwt_public_signing_key,
+                        config.signup_secret().clone(),
+                        posthog_client,
+                        config.rate_limit().clone(),
+                        config.body_limits().clone(),
+                        ws_nats_credentials,
+                        false,
+                    )?;
 
                 info!("binding to Unix domain socket; path={}", path.display());
                 let inner = axum::Server::builder(UdsIncomingStream::create(path).await?)
-                    .serve(service.into_make_service());
+                    .serve(service.into_make_service_with_connect_info::<ClientAddr>());
                 let socket = path.clone();
 
                 Ok((
@@ -174,6 +229,7 @@ impl Server<(), ()> {
                         config,
                         inner,
                         socket,
+                        shutdown_tx,
                         shutdown_rx,
                     },
                     shutdown_broadcast_rx,
@@ -214,11 +270,24 @@ impl Server<(), ()> {
         Ok(JwtPublicSigningKey::load(path).await?)
     }
 
+    /// Loads the account scoping the NATS connections of workspace updates websockets, leaving
+    /// them on the shared connection when none is configured.
+    #[instrument(name = "sdf.init.load_ws_nats_credentials", skip_all)]
+    pub async fn load_ws_nats_credentials(config: &Config) -> Result<WsNatsCredentials> {
+        match config.ws_nats_credentials() {
+            Some(ws_nats_credentials) => {
+                Ok(WsNatsCredentials::load(ws_nats_credentials, config.nats()).await?)
+            }
+            None => Ok(WsNatsCredentials::default()),
+        }
+    }
+
     #[instrument(name = "sdf.init.load_encryption_key", skip_all)]
     pub async fn load_encryption_key(path: impl AsRef<Path>) -> Result<EncryptionKey> {
         Ok(EncryptionKey::load(path).await?)
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[instrument(name = "sdf.init.migrate_database", skip_all)]
     pub async fn migrate_database(
         pg: &PgPool,
@@ -228,6 +297,7 @@ impl Server<(), ()> {
         encryption_key: &EncryptionKey,
         pkgs_path: PathBuf,
         module_index_url: String,
+        selected_builtins: Option<Vec<Builtin>>,
     ) -> Result<()> {
         dal::migrate_all_with_progress(
             pg,
@@ -237,11 +307,34 @@ impl Server<(), ()> {
             encryption_key,
             pkgs_path,
             module_index_url,
+            selected_builtins,
         )
         .await?;
         Ok(())
     }
 
+    #[instrument(name = "sdf.init.migrate_data", skip_all)]
+    pub async fn migrate_data(
+        pg: &PgPool,
+        nats: &NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: &EncryptionKey,
+        target_version: Option<i64>,
+        dry_run: bool,
+    ) -> Result<Vec<DataMigrationReport>> {
+        Ok(dal::migrate_data(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            encryption_key,
+            target_version,
+            dry_run,
+        )
+        .await?)
+    }
+
     /// Start the basic resource refresh scheduler
     pub async fn start_resource_refresh_scheduler(
         pg: PgPool,
@@ -263,6 +356,140 @@ impl Server<(), ()> {
         ResourceScheduler::new(services_context).start(shutdown_broadcast_rx);
     }
 
+    /// Start the task which prunes audit log entries past the retention policy
+    pub async fn start_audit_log_pruner(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        AuditLogPruner::new(services_context, AuditRetentionPolicy::default())
+            .start(shutdown_broadcast_rx);
+    }
+
+    /// Start the task which deletes blobs no longer referenced by any func binding return value
+    pub async fn start_blob_garbage_collector(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        BlobGarbageCollector::new(services_context).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the task which prunes history events past the retention policy
+    pub async fn start_history_event_pruner(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        policy: HistoryEventRetentionPolicy,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        HistoryEventPruner::new(services_context, policy).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the task which backs up every workspace and prunes old backups, unless the policy
+    /// disables scheduled backups
+    pub async fn start_workspace_backup_scheduler(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        policy: WorkspaceBackupPolicy,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        if !policy.enabled {
+            info!("scheduled workspace backups are disabled");
+            return;
+        }
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        WorkspaceBackupScheduler::new(services_context, policy).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the task which has the usage of every workspace aggregated every hour
+    pub async fn start_usage_aggregation_scheduler(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        UsageAggregationScheduler::new(services_context).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the task which re-enqueues qualifications that are due to be checked again
+    pub async fn start_qualification_rechecker(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        QualificationRechecker::new(services_context).start(shutdown_broadcast_rx);
+    }
+
     pub async fn start_status_updater(
         pg: PgPool,
         nats: NatsClient,
@@ -286,6 +513,63 @@ impl Server<(), ()> {
         Ok(())
     }
 
+    /// Start the task which tells users about the events they want to be told about, on Slack and,
+    /// when an SMTP relay is configured, by email
+    pub async fn start_notifier(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        smtp: Option<SmtpConfig>,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        let mut channels: Vec<Arc<dyn NotificationChannel>> = vec![Arc::new(SlackChannel)];
+        match smtp {
+            Some(smtp) => channels.push(Arc::new(EmailChannel::new(Arc::new(
+                SmtpEmailSender::new(smtp),
+            )))),
+            None => info!("no smtp relay configured, email notifications are disabled"),
+        }
+        Notifier::new(services_context, channels)
+            .await?
+            .start(shutdown_broadcast_rx);
+        Ok(())
+    }
+
+    /// Start the task which sends the events of every workspace to its webhooks
+    pub async fn start_webhook_dispatcher(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        WebhookDispatcher::new(services_context)
+            .await?
+            .start(shutdown_broadcast_rx);
+        Ok(())
+    }
+
     #[instrument(name = "sdf.init.create_pg_pool", skip_all)]
     pub async fn create_pg_pool(pg_pool_config: &PgPoolConfig) -> Result<PgPool> {
         let pool = PgPool::new(pg_pool_config).await?;
@@ -300,8 +584,22 @@ impl Server<(), ()> {
         Ok(client)
     }
 
-    pub fn create_veritech_client(nats: NatsClient) -> VeritechClient {
-        VeritechClient::new(nats)
+    /// Creates the veritech client, over its own NATS connection if the config asks for one and
+    /// over the given connection otherwise.
+    pub async fn create_veritech_client(
+        veritech_config: &VeritechClientConfig,
+        nats: NatsClient,
+    ) -> Result<VeritechClient> {
+        let nats = match &veritech_config.nats {
+            Some(nats_config) => Self::connect_to_nats(nats_config).await?,
+            None => nats,
+        };
+        let client = VeritechClient::new(nats).with_request_options(veritech_config.requests);
+        if veritech_config.requests.failover {
+            Ok(client.with_server_tracking().await?)
+        } else {
+            Ok(client)
+        }
     }
 }
 
@@ -310,16 +608,44 @@ where
     I: Accept<Conn = IO, Error = IE>,
     IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
     IE: Into<Box<dyn std::error::Error + Send + Sync>>,
+    ClientAddr: for<'a> Connected<&'a IO>,
 {
+    /// Serves requests until a graceful shutdown is triggered, after which no new connections
+    /// are accepted and in-flight requests are given until a deadline to complete.
     pub async fn run(self) -> Result<()> {
         let shutdown_rx = self.shutdown_rx;
+        let (draining_tx, draining_rx) = oneshot::channel();
 
-        self.inner
-            .with_graceful_shutdown(async {
-                shutdown_rx.await.ok();
-            })
-            .await
-            .map_err(Into::into)
+        let server = self.inner.with_graceful_shutdown(async {
+            shutdown_rx.await.ok();
+            let _ = draining_tx.send(());
+        });
+        tokio::pin!(server);
+
+        tokio::select! {
+            result = &mut server => return result.map_err(Into::into),
+            Ok(()) = draining_rx => {
+                info!("draining in-flight requests");
+            }
+        }
+
+        match time::timeout(GRACEFUL_SHUTDOWN_TIMEOUT, server).await {
+            Ok(result) => result.map_err(Into::into),
+            Err(_) => {
+                warn!(
+                    timeout = ?GRACEFUL_SHUTDOWN_TIMEOUT,
+                    "in-flight requests did not complete before the shutdown deadline"
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Gets a shutdown handle that can trigger the server's graceful shutdown process.
+    pub fn shutdown_handle(&self) -> SdfShutdownHandle {
+        SdfShutdownHandle {
+            shutdown_tx: self.shutdown_tx.clone(),
+        }
     }
 
     /// Gets a reference to the server's config.
@@ -339,13 +665,17 @@ pub fn build_service_for_tests(
     signup_secret: SensitiveString,
     posthog_client: PosthogClient,
 ) -> Result<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
-    build_service_inner(
+    let (routes, _, shutdown_rx, shutdown_broadcast_rx) = build_service_inner(
         services_context,
         jwt_public_signing_key,
         signup_secret,
         posthog_client,
+        RateLimitConfig::default(),
+        BodyLimitsConfig::default(),
+        WsNatsCredentials::default(),
         true,
-    )
+    )?;
+    Ok((routes, shutdown_rx, shutdown_broadcast_rx))
 }
 
 pub fn build_service(
@@ -353,14 +683,20 @@ pub fn build_service(
     jwt_public_signing_key: JwtPublicSigningKey,
     signup_secret: SensitiveString,
     posthog_client: PosthogClient,
+    rate_limit: RateLimitConfig,
+    body_limits: BodyLimitsConfig,
 ) -> Result<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
-    build_service_inner(
+    let (routes, _, shutdown_rx, shutdown_broadcast_rx) = build_service_inner(
         services_context,
         jwt_public_signing_key,
         signup_secret,
         posthog_client,
+        rate_limit,
+        body_limits,
+        WsNatsCredentials::default(),
         false,
-    )
+    )?;
+    Ok((routes, shutdown_rx, shutdown_broadcast_rx))
 }
 
 fn build_service_inner(
@@ -368,8 +704,16 @@ fn build_service_inner(
     jwt_public_signing_key: JwtPublicSigningKey,
     signup_secret: SensitiveString,
     posthog_client: PosthogClient,
+    rate_limit: RateLimitConfig,
+    body_limits: BodyLimitsConfig,
+    ws_nats_credentials: WsNatsCredentials,
     for_tests: bool,
-) -> Result<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
+) -> Result<(
+    Router,
+    mpsc::Sender<ShutdownSource>,
+    oneshot::Receiver<()>,
+    broadcast::Receiver<()>,
+)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
     let (shutdown_broadcast_tx, shutdown_broadcast_rx) = broadcast::channel(1);
 
@@ -379,21 +723,44 @@ fn build_service_inner(
         jwt_public_signing_key,
         posthog_client,
         shutdown_broadcast_tx.clone(),
-        shutdown_tx,
+        shutdown_tx.clone(),
+        body_limits.clone(),
+        ws_nats_credentials,
         for_tests,
     );
 
     let routes = routes(state)
+        .layer(DefaultBodyLimit::max(body_limits.max_body_bytes))
+        .layer(RateLimitLayer::new(rate_limit))
         // TODO(fnichol): customize http tracing further, using:
         // https://docs.rs/tower-http/0.1.1/tower_http/trace/index.html
         .layer(
-            TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::default().include_headers(true)),
+            TraceLayer::new_for_http().make_span_with(MakeSpanWithTraceContext(
+                DefaultMakeSpan::default().include_headers(true),
+            )),
         );
 
     let graceful_shutdown_rx = prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx)?;
 
-    Ok((routes, graceful_shutdown_rx, shutdown_broadcast_rx))
+    Ok((
+        routes,
+        shutdown_tx,
+        graceful_shutdown_rx,
+        shutdown_broadcast_rx,
+    ))
+}
+
+/// Makes the span of an HTTP request, continuing the trace of the client when it sent its trace
+/// context, so that everything the request fans out into joins the client's trace.
+#[derive(Clone, Debug)]
+struct MakeSpanWithTraceContext(DefaultMakeSpan);
+
+impl<B> MakeSpan<B> for MakeSpanWithTraceContext {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        let span = self.0.make_span(request);
+        TraceContext::from_http_headers(request.headers()).apply_to(&span);
+        span
+    }
 }
 
 fn prepare_graceful_shutdown(
@@ -446,6 +813,20 @@ fn prepare_graceful_shutdown(
     Ok(graceful_shutdown_rx)
 }
 
+pub struct SdfShutdownHandle {
+    shutdown_tx: mpsc::Sender<ShutdownSource>,
+}
+
+impl SdfShutdownHandle {
+    pub async fn shutdown(self) {
+        if let Err(err) = self.shutdown_tx.send(ShutdownSource::Handle).await {
+            warn!(error = ?err, "shutdown tx returned error, receiver is likely already closed");
+        }
+    }
+}
+
 #[remain::sorted]
 #[derive(Debug, Eq, PartialEq)]
-pub enum ShutdownSource {}
+pub enum ShutdownSource {
+    Handle,
+}
@@ -1,16 +1,31 @@
+pub mod admin;
+pub mod api_token;
+pub mod application;
+pub mod audit;
+pub mod blueprint;
 pub mod change_set;
+pub mod comment;
 pub mod component;
 pub mod diagram;
+pub mod feature_flag;
 pub mod fix;
 pub mod func;
+pub mod graphql;
+pub mod health;
+pub mod job;
 pub mod pkg;
 pub mod provider;
 pub mod qualification;
+pub mod saved_view;
 pub mod schema;
 pub mod secret;
 pub mod session;
+pub mod slack;
 pub mod status;
 pub mod variant_definition;
+pub mod webhook;
+pub mod workspace;
+pub mod workspace_settings;
 pub mod ws;
 
 /// A module containing dev routes for local development only.
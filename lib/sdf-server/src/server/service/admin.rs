@@ -0,0 +1,149 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{
+    BuiltinsError, ComponentError, DeadLetteredJobError, FeatureFlagError, FuncVersionError,
+    SchemaError, TransactionsError, UserError, WorkspaceBackupError, WorkspaceError, WorkspacePk,
+};
+use si_data_nats::NatsError;
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod compare_func_versions;
+pub mod create_func_version;
+pub mod create_workspace_backup;
+pub mod job_queue_stats;
+pub mod list_dead_lettered_jobs;
+pub mod list_func_versions;
+pub mod list_schema_category_rules;
+pub mod list_workspace_backups;
+pub mod list_workspaces;
+pub mod migrate_builtins;
+pub mod restore_workspace_backup;
+pub mod send_control_command;
+pub mod set_admin;
+pub mod set_feature_flag;
+pub mod set_func_version_pin;
+pub mod set_schema_category_rule;
+pub mod sync_resources;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum AdminError {
+    #[error(transparent)]
+    Builtins(#[from] BuiltinsError),
+    #[error(transparent)]
+    Component(#[from] ComponentError),
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DeadLetteredJob(#[from] DeadLetteredJobError),
+    #[error(transparent)]
+    FeatureFlag(#[from] FeatureFlagError),
+    #[error(transparent)]
+    FuncVersion(#[from] FuncVersionError),
+    #[error(transparent)]
+    Nats(#[from] NatsError),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    WorkspaceBackup(#[from] WorkspaceBackupError),
+    #[error("workspace not found: {0}")]
+    WorkspaceNotFound(WorkspacePk),
+}
+
+pub type AdminResult<T> = std::result::Result<T, AdminError>;
+
+impl From<AdminError> for ApiError {
+    fn from(err: AdminError) -> Self {
+        let code = match &err {
+            AdminError::Builtins(BuiltinsError::UnknownBuiltin(_))
+            | AdminError::FeatureFlag(FeatureFlagError::InvalidName(_))
+            | AdminError::Schema(SchemaError::InvalidCategory(_)) => ApiErrorCode::Validation,
+            AdminError::FuncVersion(
+                FuncVersionError::FuncNotFound(_) | FuncVersionError::NotFound(_, _),
+            )
+            | AdminError::WorkspaceBackup(WorkspaceBackupError::NotFound(_))
+            | AdminError::WorkspaceNotFound(_) => ApiErrorCode::NotFound,
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// Operational endpoints, which act across every workspace. Every handler requires the caller to
+/// hold the admin permission.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/compare_func_versions",
+            post(compare_func_versions::compare_func_versions),
+        )
+        .route(
+            "/create_func_version",
+            post(create_func_version::create_func_version),
+        )
+        .route(
+            "/create_workspace_backup",
+            post(create_workspace_backup::create_workspace_backup),
+        )
+        .route("/job_queue_stats", get(job_queue_stats::job_queue_stats))
+        .route(
+            "/list_dead_lettered_jobs",
+            get(list_dead_lettered_jobs::list_dead_lettered_jobs),
+        )
+        .route(
+            "/list_func_versions",
+            get(list_func_versions::list_func_versions),
+        )
+        .route(
+            "/list_schema_category_rules",
+            get(list_schema_category_rules::list_schema_category_rules),
+        )
+        .route(
+            "/list_workspace_backups",
+            get(list_workspace_backups::list_workspace_backups),
+        )
+        .route("/list_workspaces", get(list_workspaces::list_workspaces))
+        .route(
+            "/migrate_builtins",
+            post(migrate_builtins::migrate_builtins),
+        )
+        .route(
+            "/restore_workspace_backup",
+            post(restore_workspace_backup::restore_workspace_backup),
+        )
+        .route(
+            "/send_control_command",
+            post(send_control_command::send_control_command),
+        )
+        .route("/set_admin", post(set_admin::set_admin))
+        .route(
+            "/set_feature_flag",
+            post(set_feature_flag::set_feature_flag),
+        )
+        .route(
+            "/set_func_version_pin",
+            post(set_func_version_pin::set_func_version_pin),
+        )
+        .route(
+            "/set_schema_category_rule",
+            post(set_schema_category_rule::set_schema_category_rule),
+        )
+        .route("/sync_resources", post(sync_resources::sync_resources))
+}
@@ -0,0 +1,64 @@
+use axum::Json;
+use dal::{
+    context::AccessBuilder, FuncId, FuncVersion, FuncVersionComparison, SchemaVariantId, Tenancy,
+    Workspace, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareFuncVersionsRequest {
+    #[schema(value_type = String)]
+    pub func_id: FuncId,
+    #[schema(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+    /// Only compare the values of the components of this schema variant.
+    #[schema(value_type = Option<String>)]
+    pub schema_variant_id: Option<SchemaVariantId>,
+    /// The version to compare from, or the current code of the func if `None`.
+    pub from_version: Option<i64>,
+    pub to_version: i64,
+}
+
+/// Executes two versions of a func for every value it computes on head in a workspace, and reports
+/// which results would change if the workspace moved to the other version. The values themselves
+/// are left untouched.
+#[utoipa::path(
+    post,
+    path = "/api/admin/compare_func_versions",
+    request_body = CompareFuncVersionsRequest,
+    responses((status = 200, body = Object)),
+    tag = "admin"
+)]
+pub async fn compare_func_versions(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<CompareFuncVersionsRequest>,
+) -> AdminResult<Json<FuncVersionComparison>> {
+    builder.set_read_only();
+    let ctx = builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(request.workspace_pk),
+            claim.history_actor(),
+        ))
+        .await?;
+
+    Workspace::get_by_pk(&ctx, &request.workspace_pk)
+        .await?
+        .ok_or(AdminError::WorkspaceNotFound(request.workspace_pk))?;
+
+    let comparison = FuncVersion::compare(
+        &ctx,
+        request.func_id,
+        request.from_version,
+        request.to_version,
+        request.schema_variant_id,
+    )
+    .await?;
+
+    Ok(Json(comparison))
+}
@@ -0,0 +1,55 @@
+use axum::Json;
+use dal::{FuncId, FuncVersion, Tenancy, Workspace, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFuncVersionRequest {
+    #[schema(value_type = String)]
+    pub func_id: FuncId,
+    /// The workspace to snapshot the code of the func from, or the builtin workspace if `None`.
+    #[schema(value_type = Option<String>)]
+    pub workspace_pk: Option<WorkspacePk>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFuncVersionResponse {
+    #[schema(value_type = Object)]
+    pub version: FuncVersion,
+}
+
+/// Snapshots the current code of a func, as found on head, as its next version.
+#[utoipa::path(
+    post,
+    path = "/api/admin/create_func_version",
+    request_body = CreateFuncVersionRequest,
+    responses((status = 200, body = CreateFuncVersionResponse)),
+    tag = "admin"
+)]
+pub async fn create_func_version(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<CreateFuncVersionRequest>,
+) -> AdminResult<Json<CreateFuncVersionResponse>> {
+    let mut ctx = builder.build_default().await?;
+    ctx.update_history_actor(claim.history_actor());
+
+    let workspace = match request.workspace_pk {
+        Some(workspace_pk) => Workspace::get_by_pk(&ctx, &workspace_pk)
+            .await?
+            .ok_or(AdminError::WorkspaceNotFound(workspace_pk))?,
+        None => Workspace::builtin(&ctx).await?,
+    };
+    ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+
+    let version = FuncVersion::new(&ctx, request.func_id).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateFuncVersionResponse { version }))
+}
@@ -0,0 +1,56 @@
+use axum::Json;
+use dal::{
+    context::AccessBuilder, Tenancy, Workspace, WorkspaceBackup, WorkspaceBackupTrigger,
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkspaceBackupRequest {
+    #[schema(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkspaceBackupResponse {
+    #[schema(value_type = Object)]
+    pub backup: WorkspaceBackup,
+}
+
+/// Backs up head of the workspace right away, outside of the schedule of backups.
+#[utoipa::path(
+    post,
+    path = "/api/admin/create_workspace_backup",
+    request_body = CreateWorkspaceBackupRequest,
+    responses((status = 200, body = CreateWorkspaceBackupResponse)),
+    tag = "admin"
+)]
+pub async fn create_workspace_backup(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<CreateWorkspaceBackupRequest>,
+) -> AdminResult<Json<CreateWorkspaceBackupResponse>> {
+    let ctx = builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(request.workspace_pk),
+            claim.history_actor(),
+        ))
+        .await?;
+
+    WorkspaceBackup::begin_consistent_read(&ctx).await?;
+    Workspace::get_by_pk(&ctx, &request.workspace_pk)
+        .await?
+        .ok_or(AdminError::WorkspaceNotFound(request.workspace_pk))?;
+
+    let backup = WorkspaceBackup::capture(&ctx, WorkspaceBackupTrigger::Manual).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateWorkspaceBackupResponse { backup }))
+}
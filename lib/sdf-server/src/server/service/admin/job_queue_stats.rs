@@ -0,0 +1,34 @@
+use axum::Json;
+use dal::{DeadLetteredJob, DeadLetteredJobStats};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JobQueueStatsResponse {
+    /// The dead lettered jobs of every workspace, by kind. How many jobs are waiting to be
+    /// executed is reported by `pinga` in its own metrics.
+    #[schema(value_type = Vec<Object>)]
+    pub dead_lettered: Vec<DeadLetteredJobStats>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/job_queue_stats",
+    responses((status = 200, body = JobQueueStatsResponse)),
+    tag = "admin"
+)]
+pub async fn job_queue_stats(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(_claim): AdminAuthorization,
+) -> AdminResult<Json<JobQueueStatsResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build_default().await?;
+
+    let dead_lettered = DeadLetteredJob::stats(&ctx).await?;
+
+    Ok(Json(JobQueueStatsResponse { dead_lettered }))
+}
@@ -0,0 +1,44 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{DeadLetteredJob, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeadLetteredJobsRequest {
+    /// Only include the jobs of this workspace.
+    #[param(value_type = Option<String>)]
+    pub workspace_pk: Option<WorkspacePk>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeadLetteredJobsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<DeadLetteredJob>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/list_dead_lettered_jobs",
+    params(ListDeadLetteredJobsRequest),
+    responses((status = 200, body = ListDeadLetteredJobsResponse)),
+    tag = "admin"
+)]
+pub async fn list_dead_lettered_jobs(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(_claim): AdminAuthorization,
+    Query(request): Query<ListDeadLetteredJobsRequest>,
+) -> AdminResult<Json<ListDeadLetteredJobsResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build_default().await?;
+
+    let list = DeadLetteredJob::list_all(&ctx, request.workspace_pk).await?;
+
+    Ok(Json(ListDeadLetteredJobsResponse { list }))
+}
@@ -0,0 +1,47 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{FuncId, FuncVersion, FuncVersionPin};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFuncVersionsRequest {
+    #[param(value_type = String)]
+    pub func_id: FuncId,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFuncVersionsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub versions: Vec<FuncVersion>,
+    #[schema(value_type = Vec<Object>)]
+    pub pins: Vec<FuncVersionPin>,
+}
+
+/// Lists the versions of a func and where they are rolled out.
+#[utoipa::path(
+    get,
+    path = "/api/admin/list_func_versions",
+    params(ListFuncVersionsRequest),
+    responses((status = 200, body = ListFuncVersionsResponse)),
+    tag = "admin"
+)]
+pub async fn list_func_versions(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(_claim): AdminAuthorization,
+    Query(request): Query<ListFuncVersionsRequest>,
+) -> AdminResult<Json<ListFuncVersionsResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build_default().await?;
+
+    let versions = FuncVersion::list_for_func(&ctx, request.func_id).await?;
+    let pins = FuncVersionPin::list_for_func(&ctx, request.func_id).await?;
+
+    Ok(Json(ListFuncVersionsResponse { versions, pins }))
+}
@@ -0,0 +1,47 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{SchemaCategoryRule, Workspace, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSchemaCategoryRulesRequest {
+    #[param(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSchemaCategoryRulesResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub rules: Vec<SchemaCategoryRule>,
+}
+
+/// Lists the rules scoping which schema categories a workspace sees.
+#[utoipa::path(
+    get,
+    path = "/api/admin/list_schema_category_rules",
+    params(ListSchemaCategoryRulesRequest),
+    responses((status = 200, body = ListSchemaCategoryRulesResponse)),
+    tag = "admin"
+)]
+pub async fn list_schema_category_rules(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(_claim): AdminAuthorization,
+    Query(request): Query<ListSchemaCategoryRulesRequest>,
+) -> AdminResult<Json<ListSchemaCategoryRulesResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build_default().await?;
+
+    Workspace::get_by_pk(&ctx, &request.workspace_pk)
+        .await?
+        .ok_or(AdminError::WorkspaceNotFound(request.workspace_pk))?;
+    let rules = SchemaCategoryRule::list_for_workspace(&ctx, request.workspace_pk).await?;
+
+    Ok(Json(ListSchemaCategoryRulesResponse { rules }))
+}
@@ -0,0 +1,53 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{context::AccessBuilder, Tenancy, Workspace, WorkspaceBackup, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspaceBackupsRequest {
+    #[param(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspaceBackupsResponse {
+    /// The backups of the workspace, newest first.
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<WorkspaceBackup>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/list_workspace_backups",
+    params(ListWorkspaceBackupsRequest),
+    responses((status = 200, body = ListWorkspaceBackupsResponse)),
+    tag = "admin"
+)]
+pub async fn list_workspace_backups(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Query(request): Query<ListWorkspaceBackupsRequest>,
+) -> AdminResult<Json<ListWorkspaceBackupsResponse>> {
+    builder.set_read_only();
+    let ctx = builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(request.workspace_pk),
+            claim.history_actor(),
+        ))
+        .await?;
+
+    Workspace::get_by_pk(&ctx, &request.workspace_pk)
+        .await?
+        .ok_or(AdminError::WorkspaceNotFound(request.workspace_pk))?;
+
+    let list = WorkspaceBackup::list(&ctx).await?;
+
+    Ok(Json(ListWorkspaceBackupsResponse { list }))
+}
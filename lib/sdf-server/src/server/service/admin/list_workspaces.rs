@@ -0,0 +1,32 @@
+use axum::Json;
+use dal::Workspace;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspacesResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<Workspace>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/list_workspaces",
+    responses((status = 200, body = ListWorkspacesResponse)),
+    tag = "admin"
+)]
+pub async fn list_workspaces(
+    HandlerContext(mut builder): HandlerContext,
+    AdminAuthorization(_claim): AdminAuthorization,
+) -> AdminResult<Json<ListWorkspacesResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build_default().await?;
+
+    let list = Workspace::list_all(&ctx).await?;
+
+    Ok(Json(ListWorkspacesResponse { list }))
+}
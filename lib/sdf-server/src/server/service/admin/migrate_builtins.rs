@@ -0,0 +1,55 @@
+use axum::Json;
+use dal::{builtins, migrate_builtins_only, Tenancy, Workspace};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateBuiltinsRequest {
+    /// The builtins to migrate again, such as `["docker", "coreos"]`, or all of them if empty.
+    #[serde(default)]
+    pub builtins: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateBuiltinsResponse {
+    pub success: bool,
+}
+
+/// Migrates the builtins again, in the builtin workspace from which every workspace sees them.
+/// Builtins which have not changed since they were last migrated are skipped.
+#[utoipa::path(
+    post,
+    path = "/api/admin/migrate_builtins",
+    request_body = MigrateBuiltinsRequest,
+    responses((status = 200, body = MigrateBuiltinsResponse)),
+    tag = "admin"
+)]
+pub async fn migrate_builtins(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<MigrateBuiltinsRequest>,
+) -> AdminResult<Json<MigrateBuiltinsResponse>> {
+    let mut ctx = builder.build_default().await?;
+    ctx.update_history_actor(claim.history_actor());
+
+    let workspace = Workspace::builtin(&ctx).await?;
+    ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+
+    info!(builtins = ?request.builtins, "migrating builtins on admin request");
+    if request.builtins.is_empty() {
+        builtins::migrate(&ctx, None, None).await?;
+    } else {
+        let names: Vec<&str> = request.builtins.iter().map(String::as_str).collect();
+        migrate_builtins_only(&ctx, &names).await?;
+    }
+
+    ctx.blocking_commit().await?;
+
+    Ok(Json(MigrateBuiltinsResponse { success: true }))
+}
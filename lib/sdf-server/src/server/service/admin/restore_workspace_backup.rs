@@ -0,0 +1,66 @@
+use axum::Json;
+use dal::{
+    context::AccessBuilder, Tenancy, User, Workspace, WorkspaceBackup, WorkspaceBackupError,
+    WorkspaceBackupPk, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreWorkspaceBackupRequest {
+    /// The workspace the backup was taken of.
+    #[schema(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+    #[schema(value_type = String)]
+    pub workspace_backup_pk: WorkspaceBackupPk,
+    /// The name of the workspace to restore the backup into.
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreWorkspaceBackupResponse {
+    #[schema(value_type = Object)]
+    pub workspace: Workspace,
+}
+
+/// Restores a backup into a new workspace, which the caller is made a member of. The workspace
+/// the backup was taken of is left alone.
+#[utoipa::path(
+    post,
+    path = "/api/admin/restore_workspace_backup",
+    request_body = RestoreWorkspaceBackupRequest,
+    responses((status = 200, body = RestoreWorkspaceBackupResponse)),
+    tag = "admin"
+)]
+pub async fn restore_workspace_backup(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<RestoreWorkspaceBackupRequest>,
+) -> AdminResult<Json<RestoreWorkspaceBackupResponse>> {
+    let mut ctx = builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(request.workspace_pk),
+            claim.history_actor(),
+        ))
+        .await?;
+
+    let backup = WorkspaceBackup::get_by_pk(&ctx, request.workspace_backup_pk)
+        .await?
+        .ok_or(WorkspaceBackupError::NotFound(request.workspace_backup_pk))?;
+    let workspace = backup
+        .restore_into_new_workspace(&mut ctx, &request.name)
+        .await?;
+
+    if let Some(user) = User::get_by_pk(&ctx, claim.user_pk).await? {
+        user.associate_workspace(&ctx, *workspace.pk()).await?;
+    }
+
+    ctx.commit().await?;
+
+    Ok(Json(RestoreWorkspaceBackupResponse { workspace }))
+}
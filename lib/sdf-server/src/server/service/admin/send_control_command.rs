@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use axum::Json;
+use control_plane::{nats_control_subject, ControlAck, ControlCommand, ControlService};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use tokio::time;
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+/// How long the instances of a service are given to acknowledge a command, unless the request
+/// says otherwise.
+const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SendControlCommandRequest {
+    /// The service whose every instance is sent the command.
+    #[schema(value_type = String)]
+    pub service: ControlService,
+    #[schema(value_type = Object)]
+    pub command: ControlCommand,
+    /// How long to wait for acknowledgements, in milliseconds.
+    pub ack_timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SendControlCommandResponse {
+    /// The acknowledgements of the instances which replied in time. An instance missing from the
+    /// list may still have applied the command.
+    #[schema(value_type = Vec<Object>)]
+    pub acks: Vec<ControlAck>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/send_control_command",
+    request_body = SendControlCommandRequest,
+    responses((status = 200, body = SendControlCommandResponse)),
+    tag = "admin"
+)]
+pub async fn send_control_command(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(_claim): AdminAuthorization,
+    Json(request): Json<SendControlCommandRequest>,
+) -> AdminResult<Json<SendControlCommandResponse>> {
+    let nats = builder.nats_conn();
+    let subject = nats_control_subject(nats.metadata().subject_prefix(), request.service);
+    let ack_timeout = request
+        .ack_timeout_ms
+        .map_or(DEFAULT_ACK_TIMEOUT, Duration::from_millis);
+
+    let mut replies = nats
+        .request_multi(subject, serde_json::to_vec(&request.command)?)
+        .await?;
+    let deadline = time::Instant::now() + ack_timeout;
+    let mut acks = Vec::new();
+    while let Ok(Some(reply)) = time::timeout_at(deadline, replies.next()).await {
+        match serde_json::from_slice(reply?.data()) {
+            Ok(ack) => acks.push(ack),
+            Err(err) => warn!(error = ?err, "failed to deserialize control command ack"),
+        }
+    }
+    replies.unsubscribe().await?;
+
+    Ok(Json(SendControlCommandResponse { acks }))
+}
@@ -0,0 +1,44 @@
+use axum::Json;
+use dal::{User, UserPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAdminRequest {
+    #[schema(value_type = String)]
+    pub user_pk: UserPk,
+    /// Grants the admin permission to the user, or takes it away.
+    pub admin: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAdminResponse {
+    pub success: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/set_admin",
+    request_body = SetAdminRequest,
+    responses((status = 200, body = SetAdminResponse)),
+    tag = "admin"
+)]
+pub async fn set_admin(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<SetAdminRequest>,
+) -> AdminResult<Json<SetAdminResponse>> {
+    let mut ctx = builder.build_default().await?;
+    ctx.update_history_actor(claim.history_actor());
+
+    User::set_admin(&ctx, request.user_pk, request.admin).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetAdminResponse { success: true }))
+}
@@ -0,0 +1,55 @@
+use axum::Json;
+use dal::{FeatureFlag, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSetFeatureFlagRequest {
+    pub name: String,
+    /// The workspace to toggle the flag for, or `None` to toggle it globally.
+    #[schema(value_type = Option<String>)]
+    pub workspace_pk: Option<WorkspacePk>,
+    /// Toggles the flag, or removes its toggle if `None`.
+    pub enabled: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSetFeatureFlagResponse {
+    #[schema(value_type = Option<Object>)]
+    pub flag: Option<FeatureFlag>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/set_feature_flag",
+    request_body = AdminSetFeatureFlagRequest,
+    responses((status = 200, body = AdminSetFeatureFlagResponse)),
+    tag = "admin"
+)]
+pub async fn set_feature_flag(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<AdminSetFeatureFlagRequest>,
+) -> AdminResult<Json<AdminSetFeatureFlagResponse>> {
+    let mut ctx = builder.build_default().await?;
+    ctx.update_history_actor(claim.history_actor());
+
+    let flag = match request.enabled {
+        Some(enabled) => {
+            Some(FeatureFlag::set(&ctx, &request.name, request.workspace_pk, enabled).await?)
+        }
+        None => {
+            FeatureFlag::unset(&ctx, &request.name, request.workspace_pk).await?;
+            None
+        }
+    };
+
+    ctx.commit().await?;
+
+    Ok(Json(AdminSetFeatureFlagResponse { flag }))
+}
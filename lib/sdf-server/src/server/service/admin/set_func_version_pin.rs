@@ -0,0 +1,74 @@
+use axum::Json;
+use dal::{FuncId, FuncVersionPin, SchemaVariantId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::AdminResult;
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFuncVersionPinRequest {
+    #[schema(value_type = String)]
+    pub func_id: FuncId,
+    /// The schema variant to pin the func for, or `None` for every schema variant.
+    #[schema(value_type = Option<String>)]
+    pub schema_variant_id: Option<SchemaVariantId>,
+    /// The workspace to pin the func for, or `None` for every workspace.
+    #[schema(value_type = Option<String>)]
+    pub workspace_pk: Option<WorkspacePk>,
+    /// Pins the version, or removes the pin if `None`.
+    pub version: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFuncVersionPinResponse {
+    #[schema(value_type = Option<Object>)]
+    pub pin: Option<FuncVersionPin>,
+}
+
+/// Rolls a version of a func out to a schema variant, a workspace, both or everyone. Values are
+/// computed with the pinned version from then on.
+#[utoipa::path(
+    post,
+    path = "/api/admin/set_func_version_pin",
+    request_body = SetFuncVersionPinRequest,
+    responses((status = 200, body = SetFuncVersionPinResponse)),
+    tag = "admin"
+)]
+pub async fn set_func_version_pin(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<SetFuncVersionPinRequest>,
+) -> AdminResult<Json<SetFuncVersionPinResponse>> {
+    let mut ctx = builder.build_default().await?;
+    ctx.update_history_actor(claim.history_actor());
+
+    let pin = match request.version {
+        Some(version) => Some(
+            FuncVersionPin::set(
+                &ctx,
+                request.func_id,
+                request.schema_variant_id,
+                request.workspace_pk,
+                version,
+            )
+            .await?,
+        ),
+        None => {
+            FuncVersionPin::unset(
+                &ctx,
+                request.func_id,
+                request.schema_variant_id,
+                request.workspace_pk,
+            )
+            .await?;
+            None
+        }
+    };
+
+    ctx.commit().await?;
+
+    Ok(Json(SetFuncVersionPinResponse { pin }))
+}
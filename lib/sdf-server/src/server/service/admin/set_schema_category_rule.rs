@@ -0,0 +1,61 @@
+use axum::Json;
+use dal::{SchemaCategoryRule, SchemaCategoryRuleKind, Workspace, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSchemaCategoryRuleRequest {
+    #[schema(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+    /// The category of schemas, which also covers the categories nested under it.
+    pub category: String,
+    /// Allows or denies the category, or removes its rule if `None`.
+    #[schema(value_type = Option<String>)]
+    pub kind: Option<SchemaCategoryRuleKind>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSchemaCategoryRuleResponse {
+    #[schema(value_type = Option<Object>)]
+    pub rule: Option<SchemaCategoryRule>,
+}
+
+/// Scopes which schema categories a workspace sees in its schema lists and node add menu.
+#[utoipa::path(
+    post,
+    path = "/api/admin/set_schema_category_rule",
+    request_body = SetSchemaCategoryRuleRequest,
+    responses((status = 200, body = SetSchemaCategoryRuleResponse)),
+    tag = "admin"
+)]
+pub async fn set_schema_category_rule(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<SetSchemaCategoryRuleRequest>,
+) -> AdminResult<Json<SetSchemaCategoryRuleResponse>> {
+    let mut ctx = builder.build_default().await?;
+    ctx.update_history_actor(claim.history_actor());
+
+    Workspace::get_by_pk(&ctx, &request.workspace_pk)
+        .await?
+        .ok_or(AdminError::WorkspaceNotFound(request.workspace_pk))?;
+
+    let rule = match request.kind {
+        Some(kind) => Some(
+            SchemaCategoryRule::set(&ctx, request.workspace_pk, &request.category, kind).await?,
+        ),
+        None => {
+            SchemaCategoryRule::unset(&ctx, request.workspace_pk, &request.category).await?;
+            None
+        }
+    };
+
+    ctx.commit().await?;
+
+    Ok(Json(SetSchemaCategoryRuleResponse { rule }))
+}
@@ -0,0 +1,76 @@
+use axum::Json;
+use dal::{
+    context::AccessBuilder, job::definition::RefreshJob, Component, ComponentError, StandardModel,
+    Tenancy, Workspace, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{AdminError, AdminResult};
+use crate::server::extract::{AdminAuthorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResourcesRequest {
+    #[schema(value_type = String)]
+    pub workspace_pk: WorkspacePk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResourcesResponse {
+    /// How many components will have their resource refreshed.
+    pub component_count: usize,
+}
+
+/// Refreshes the resources of every component of the workspace on head, like the workspace's
+/// users can do from the diagram.
+#[utoipa::path(
+    post,
+    path = "/api/admin/sync_resources",
+    request_body = SyncResourcesRequest,
+    responses((status = 200, body = SyncResourcesResponse)),
+    tag = "admin"
+)]
+pub async fn sync_resources(
+    HandlerContext(builder): HandlerContext,
+    AdminAuthorization(claim): AdminAuthorization,
+    Json(request): Json<SyncResourcesRequest>,
+) -> AdminResult<Json<SyncResourcesResponse>> {
+    let ctx = builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(request.workspace_pk),
+            claim.history_actor(),
+        ))
+        .await?;
+
+    Workspace::get_by_pk(&ctx, &request.workspace_pk)
+        .await?
+        .ok_or(AdminError::WorkspaceNotFound(request.workspace_pk))?;
+
+    let component_ids: Vec<_> = ctx
+        .run_with_deleted_visibility(|ctx| async move {
+            let component_ids = Component::list(&ctx)
+                .await?
+                .into_iter()
+                .filter(|c| c.visibility().deleted_at.is_none() || c.needs_destroy())
+                .map(|c| *c.id())
+                .collect();
+            Ok::<_, ComponentError>(component_ids)
+        })
+        .await?;
+    let component_count = component_ids.len();
+
+    if !component_ids.is_empty() {
+        ctx.enqueue_job(RefreshJob::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            component_ids,
+        ))
+        .await?;
+    }
+
+    ctx.commit().await?;
+
+    Ok(Json(SyncResourcesResponse { component_count }))
+}
@@ -0,0 +1,58 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Json;
+use axum::Router;
+use dal::{ApiTokenError as DalApiTokenError, ApiTokenPk, TransactionsError};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod create_api_token;
+pub mod list_api_tokens;
+pub mod revoke_api_token;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ApiTokenError {
+    #[error(transparent)]
+    ApiToken(#[from] DalApiTokenError),
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error("api token not found: {0}")]
+    NotFound(ApiTokenPk),
+}
+
+pub type ApiTokenResult<T> = std::result::Result<T, ApiTokenError>;
+
+impl IntoResponse for ApiTokenError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            ApiTokenError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": error_message,
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/create_api_token",
+            post(create_api_token::create_api_token),
+        )
+        .route("/list_api_tokens", get(list_api_tokens::list_api_tokens))
+        .route(
+            "/revoke_api_token",
+            post(revoke_api_token::revoke_api_token),
+        )
+}
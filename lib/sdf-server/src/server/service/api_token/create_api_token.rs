@@ -0,0 +1,55 @@
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::{ApiToken, ApiTokenScope};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ApiTokenResult;
+use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    #[schema(value_type = Vec<String>)]
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenResponse {
+    #[schema(value_type = Object)]
+    pub api_token: ApiToken,
+    /// The raw token, which is only ever returned here.
+    pub token: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/api_token/create_api_token",
+    request_body = CreateApiTokenRequest,
+    responses((status = 200, body = CreateApiTokenResponse)),
+    tag = "api_token"
+)]
+pub async fn create_api_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Authorization(claim): Authorization,
+    Json(request): Json<CreateApiTokenRequest>,
+) -> ApiTokenResult<Json<CreateApiTokenResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (api_token, token) = ApiToken::new(
+        &ctx,
+        claim.user_pk,
+        request.name,
+        request.scopes,
+        request.expires_at,
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateApiTokenResponse { api_token, token }))
+}
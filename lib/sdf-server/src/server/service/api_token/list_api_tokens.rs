@@ -0,0 +1,31 @@
+use axum::Json;
+use dal::ApiToken;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ApiTokenResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListApiTokensResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<ApiToken>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/api_token/list_api_tokens",
+    responses((status = 200, body = ListApiTokensResponse)),
+    tag = "api_token"
+)]
+pub async fn list_api_tokens(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> ApiTokenResult<Json<ListApiTokensResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = ApiToken::list(&ctx).await?;
+
+    Ok(Json(ListApiTokensResponse { list }))
+}
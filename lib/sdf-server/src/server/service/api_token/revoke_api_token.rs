@@ -0,0 +1,45 @@
+use axum::Json;
+use dal::{ApiToken, ApiTokenPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{ApiTokenError, ApiTokenResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiTokenRequest {
+    #[schema(value_type = String)]
+    pub pk: ApiTokenPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiTokenResponse {
+    #[schema(value_type = Object)]
+    pub api_token: ApiToken,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/api_token/revoke_api_token",
+    request_body = RevokeApiTokenRequest,
+    responses((status = 200, body = RevokeApiTokenResponse)),
+    tag = "api_token"
+)]
+pub async fn revoke_api_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<RevokeApiTokenRequest>,
+) -> ApiTokenResult<Json<RevokeApiTokenResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut api_token = ApiToken::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(ApiTokenError::NotFound(request.pk))?;
+    api_token.revoke(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(RevokeApiTokenResponse { api_token }))
+}
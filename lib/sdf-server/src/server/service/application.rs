@@ -0,0 +1,54 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use dal::error_category::categorize;
+use dal::export::docker_compose::DockerComposeExportError;
+use dal::TransactionsError;
+use thiserror::Error;
+
+use crate::server::{
+    api_error::{ApiError, ApiErrorCode},
+    state::AppState,
+};
+
+pub mod export_docker_compose;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ApplicationError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DockerComposeExport(#[from] DockerComposeExportError),
+}
+
+pub type ApplicationResult<T> = std::result::Result<T, ApplicationError>;
+
+impl From<ApplicationError> for ApiError {
+    fn from(err: ApplicationError) -> Self {
+        let code = match &err {
+            ApplicationError::DockerComposeExport(DockerComposeExportError::NotFound(_)) => {
+                ApiErrorCode::NotFound
+            }
+            ApplicationError::DockerComposeExport(DockerComposeExportError::NotAnApplication(
+                _,
+            )) => ApiErrorCode::Validation,
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for ApplicationError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// The routes addressing a single application frame by id, nested under `/api/application`.
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/:application_id/export/docker_compose",
+        get(export_docker_compose::export_docker_compose),
+    )
+}
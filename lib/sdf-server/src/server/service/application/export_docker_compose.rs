@@ -0,0 +1,41 @@
+use axum::extract::{Path, Query};
+use axum::http::header;
+use axum::response::IntoResponse;
+use dal::{export::docker_compose, ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::ApplicationResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDockerComposeRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Returns the application as a Docker Compose YAML document.
+#[utoipa::path(
+    get,
+    path = "/api/application/{application_id}/export/docker_compose",
+    params(
+        ("application_id" = String, Path, description = "The id of the application frame"),
+        ExportDockerComposeRequest,
+    ),
+    responses((status = 200, body = String, content_type = "application/yaml")),
+    tag = "application"
+)]
+pub async fn export_docker_compose(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(application_id): Path<ComponentId>,
+    Query(request): Query<ExportDockerComposeRequest>,
+) -> ApplicationResult<impl IntoResponse> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let yaml = docker_compose::export(&ctx, application_id).await?;
+
+    Ok(([(header::CONTENT_TYPE, "application/yaml")], yaml))
+}
@@ -0,0 +1,110 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Json;
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{
+    ApiToken, ApiTokenError, ApiTokenPk, AuditLogError, DalContext, HistoryActor,
+    HistoryEventError, TransactionsError, UserPk,
+};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod list_audit_logs;
+pub mod list_history_events;
+pub mod stream_audit_logs;
+pub mod stream_history_events;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error(transparent)]
+    ApiToken(#[from] ApiTokenError),
+    #[error("api token not found: {0}")]
+    ApiTokenNotFound(ApiTokenPk),
+    #[error(transparent)]
+    AuditLog(#[from] AuditLogError),
+    #[error("only one of actor and apiTokenPk can be given")]
+    ConflictingActorFilters,
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+}
+
+pub type AuditResult<T> = std::result::Result<T, AuditError>;
+
+impl IntoResponse for AuditError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            AuditError::ApiTokenNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            AuditError::ConflictingActorFilters => (StatusCode::BAD_REQUEST, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": error_message,
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+// Errors which end a stream are sent in the body of the stream, where only the shared envelope
+// applies.
+impl From<AuditError> for ApiError {
+    fn from(err: AuditError) -> Self {
+        let code = match &err {
+            AuditError::ApiTokenNotFound(_) => ApiErrorCode::NotFound,
+            AuditError::ConflictingActorFilters => ApiErrorCode::Validation,
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+/// Resolves the actor filter of a request, which names either a user or an API token.
+async fn actor_filter(
+    ctx: &DalContext,
+    actor: Option<UserPk>,
+    api_token_pk: Option<ApiTokenPk>,
+) -> AuditResult<Option<HistoryActor>> {
+    Ok(match (actor, api_token_pk) {
+        (Some(_), Some(_)) => return Err(AuditError::ConflictingActorFilters),
+        (Some(user_pk), None) => Some(HistoryActor::User(user_pk)),
+        (None, Some(api_token_pk)) => {
+            let api_token = ApiToken::get_by_pk(ctx, api_token_pk)
+                .await?
+                .ok_or(AuditError::ApiTokenNotFound(api_token_pk))?;
+            Some(HistoryActor::ApiToken {
+                pk: api_token.pk(),
+                user_pk: *api_token.user_pk(),
+            })
+        }
+        (None, None) => None,
+    })
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list_audit_logs", get(list_audit_logs::list_audit_logs))
+        .route(
+            "/list_history_events",
+            get(list_history_events::list_history_events),
+        )
+        .route(
+            "/stream_audit_logs",
+            get(stream_audit_logs::stream_audit_logs),
+        )
+        .route(
+            "/stream_history_events",
+            get(stream_history_events::stream_history_events),
+        )
+}
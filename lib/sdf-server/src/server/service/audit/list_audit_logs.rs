@@ -0,0 +1,53 @@
+use axum::extract::Query;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::history_event::HistoryEventPk;
+use dal::{AuditAction, AuditLog, AuditLogFilter, AuditLogPage, HistoryActor, UserPk};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::AuditResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAuditLogsRequest {
+    /// Only include entries for actions performed by this user.
+    #[param(value_type = Option<String>)]
+    pub actor: Option<UserPk>,
+    #[param(value_type = Option<String>)]
+    pub action: Option<AuditAction>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<HistoryEventPk>,
+    pub page_size: Option<u32>,
+}
+
+pub type ListAuditLogsResponse = AuditLogPage;
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/list_audit_logs",
+    params(ListAuditLogsRequest),
+    responses((status = 200, body = Object)),
+    tag = "audit"
+)]
+pub async fn list_audit_logs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListAuditLogsRequest>,
+) -> AuditResult<Json<ListAuditLogsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let filter = AuditLogFilter {
+        actor: request.actor.map(HistoryActor::User),
+        action: request.action,
+        from: request.from,
+        to: request.to,
+    };
+    let page = AuditLog::list(&ctx, &filter, request.cursor, request.page_size).await?;
+
+    Ok(Json(page))
+}
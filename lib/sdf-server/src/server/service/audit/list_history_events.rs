@@ -0,0 +1,59 @@
+use axum::extract::Query;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::history_event::HistoryEventPk;
+use dal::{ApiTokenPk, HistoryEvent, HistoryEventFilter, HistoryEventPage, UserPk};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{actor_filter, AuditResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHistoryEventsRequest {
+    /// Only include events for changes made by this user, through a session.
+    #[param(value_type = Option<String>)]
+    pub actor: Option<UserPk>,
+    /// Only include events for changes made through this API token.
+    #[param(value_type = Option<String>)]
+    pub api_token_pk: Option<ApiTokenPk>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<HistoryEventPk>,
+    pub page_size: Option<u32>,
+}
+
+pub type ListHistoryEventsResponse = HistoryEventPage;
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/list_history_events",
+    params(ListHistoryEventsRequest),
+    responses((status = 200, body = Object)),
+    tag = "audit"
+)]
+pub async fn list_history_events(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListHistoryEventsRequest>,
+) -> AuditResult<Json<ListHistoryEventsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let actor = actor_filter(&ctx, request.actor, request.api_token_pk).await?;
+
+    let filter = HistoryEventFilter {
+        actor,
+        entity_type: request.entity_type,
+        entity_id: request.entity_id,
+        from: request.from,
+        to: request.to,
+    };
+    let page = HistoryEvent::list(&ctx, &filter, request.cursor, request.page_size).await?;
+
+    Ok(Json(page))
+}
@@ -0,0 +1,56 @@
+use axum::extract::Query;
+use axum::response::Response;
+use chrono::{DateTime, Utc};
+use dal::history_event::HistoryEventPk;
+use dal::{AuditAction, AuditLog, AuditLogFilter, HistoryActor, UserPk};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{AuditError, AuditResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::ndjson::ndjson_response;
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamAuditLogsRequest {
+    /// Only include entries for actions performed by this user.
+    #[param(value_type = Option<String>)]
+    pub actor: Option<UserPk>,
+    #[param(value_type = Option<String>)]
+    pub action: Option<AuditAction>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Resume the stream after the entry with this pk.
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<HistoryEventPk>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/stream_audit_logs",
+    params(StreamAuditLogsRequest),
+    responses((status = 200, body = String, content_type = "application/x-ndjson")),
+    tag = "audit"
+)]
+pub async fn stream_audit_logs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<StreamAuditLogsRequest>,
+) -> AuditResult<Response> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let filter = AuditLogFilter {
+        actor: request.actor.map(HistoryActor::User),
+        action: request.action,
+        from: request.from,
+        to: request.to,
+    };
+    let entries = AuditLog::stream(&ctx, &filter, request.cursor).await?;
+
+    Ok(ndjson_response(
+        entries.map_err(AuditError::from),
+        AuditLog::pk,
+    ))
+}
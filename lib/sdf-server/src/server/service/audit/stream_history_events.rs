@@ -0,0 +1,62 @@
+use axum::extract::Query;
+use axum::response::Response;
+use chrono::{DateTime, Utc};
+use dal::history_event::HistoryEventPk;
+use dal::{ApiTokenPk, HistoryEvent, HistoryEventFilter, UserPk};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{actor_filter, AuditError, AuditResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::ndjson::ndjson_response;
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamHistoryEventsRequest {
+    /// Only include events for changes made by this user, through a session.
+    #[param(value_type = Option<String>)]
+    pub actor: Option<UserPk>,
+    /// Only include events for changes made through this API token.
+    #[param(value_type = Option<String>)]
+    pub api_token_pk: Option<ApiTokenPk>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Resume the stream after the event with this pk.
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<HistoryEventPk>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/stream_history_events",
+    params(StreamHistoryEventsRequest),
+    responses((status = 200, body = String, content_type = "application/x-ndjson")),
+    tag = "audit"
+)]
+pub async fn stream_history_events(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<StreamHistoryEventsRequest>,
+) -> AuditResult<Response> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let actor = actor_filter(&ctx, request.actor, request.api_token_pk).await?;
+
+    let filter = HistoryEventFilter {
+        actor,
+        entity_type: request.entity_type,
+        entity_id: request.entity_id,
+        from: request.from,
+        to: request.to,
+    };
+    let events = HistoryEvent::stream(&ctx, &filter, request.cursor).await?;
+
+    Ok(ndjson_response(
+        events.map_err(AuditError::from),
+        |event: &HistoryEvent| event.pk,
+    ))
+}
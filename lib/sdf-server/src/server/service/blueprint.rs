@@ -0,0 +1,102 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{
+    BlueprintError as DalBlueprintError, BlueprintPk, ChangeSetError, ComponentLabelError,
+    TransactionsError, WsEventError,
+};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod create_blueprint;
+pub mod delete_blueprint;
+pub mod get_blueprint;
+pub mod instantiate_blueprint;
+pub mod list_blueprints;
+pub mod update_blueprint;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum BlueprintError {
+    #[error(transparent)]
+    Blueprint(#[from] DalBlueprintError),
+    #[error(transparent)]
+    ChangeSet(#[from] ChangeSetError),
+    #[error(transparent)]
+    ComponentLabel(#[from] ComponentLabelError),
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    Http(#[from] axum::http::Error),
+    #[error("blueprint not found: {0}")]
+    NotFound(BlueprintPk),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type BlueprintResult<T> = std::result::Result<T, BlueprintError>;
+
+impl From<BlueprintError> for ApiError {
+    fn from(err: BlueprintError) -> Self {
+        let code = match &err {
+            BlueprintError::NotFound(_)
+            | BlueprintError::Blueprint(
+                DalBlueprintError::ComponentNotFound(_)
+                | DalBlueprintError::NodeNotFound(_)
+                | DalBlueprintError::NotFound(_),
+            ) => ApiErrorCode::NotFound,
+            BlueprintError::Blueprint(
+                DalBlueprintError::DuplicateComponentKey(_)
+                | DalBlueprintError::DuplicateVariable(_)
+                | DalBlueprintError::Empty
+                | DalBlueprintError::InvalidVariableName(_)
+                | DalBlueprintError::MissingVariable(_)
+                | DalBlueprintError::SocketNotFoundForComponent(..)
+                | DalBlueprintError::UnknownComponentKey(_)
+                | DalBlueprintError::UnknownVariable(_),
+            ) => ApiErrorCode::Validation,
+            BlueprintError::Blueprint(DalBlueprintError::Component(err)) => err.into(),
+            BlueprintError::Blueprint(DalBlueprintError::Diagram(err)) => err.into(),
+            BlueprintError::Blueprint(DalBlueprintError::Schema(err)) => err.into(),
+            BlueprintError::ChangeSet(err) => err.into(),
+            BlueprintError::ComponentLabel(err) => err.into(),
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for BlueprintError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// Reading blueprints requires the `blueprint:read` scope of API tokens, and changing or
+/// instantiating them the `blueprint:write` scope.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:blueprint_pk/instantiate",
+            post(instantiate_blueprint::instantiate_blueprint),
+        )
+        .route(
+            "/create_blueprint",
+            post(create_blueprint::create_blueprint),
+        )
+        .route(
+            "/delete_blueprint",
+            post(delete_blueprint::delete_blueprint),
+        )
+        .route("/get_blueprint", get(get_blueprint::get_blueprint))
+        .route("/list_blueprints", get(list_blueprints::list_blueprints))
+        .route(
+            "/update_blueprint",
+            post(update_blueprint::update_blueprint),
+        )
+}
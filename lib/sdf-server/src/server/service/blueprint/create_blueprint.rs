@@ -0,0 +1,74 @@
+use axum::Json;
+use dal::{Blueprint, BlueprintVariable, ComponentId, LabelSelector, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::BlueprintResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBlueprintRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// The components to capture, along with the edges between them.
+    #[serde(default)]
+    #[schema(value_type = Vec<String>)]
+    pub component_ids: Vec<ComponentId>,
+    /// Also capture the components whose labels match this selector, such as
+    /// `label.env=prod AND label.team=payments`.
+    pub selector: Option<String>,
+    /// The variables of the blueprint. Wherever the default of a variable appears in the names
+    /// and values of the components, it is replaced with `{{name}}`.
+    #[serde(default)]
+    #[schema(value_type = Vec<Object>)]
+    pub variables: Vec<BlueprintVariable>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBlueprintResponse {
+    #[schema(value_type = Object)]
+    pub blueprint: Blueprint,
+}
+
+/// Captures the components, as seen in the change set of the request, into a new blueprint of
+/// the workspace.
+#[utoipa::path(
+    post,
+    path = "/api/blueprint/create_blueprint",
+    request_body = CreateBlueprintRequest,
+    responses((status = 200, body = CreateBlueprintResponse)),
+    tag = "blueprint"
+)]
+pub async fn create_blueprint(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<CreateBlueprintRequest>,
+) -> BlueprintResult<Json<CreateBlueprintResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut component_ids = request.component_ids;
+    if let Some(selector) = &request.selector {
+        for component_id in selector.parse::<LabelSelector>()?.select(&ctx).await? {
+            if !component_ids.contains(&component_id) {
+                component_ids.push(component_id);
+            }
+        }
+    }
+
+    let blueprint = Blueprint::capture(
+        &ctx,
+        &request.name,
+        request.description,
+        &component_ids,
+        request.variables,
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateBlueprintResponse { blueprint }))
+}
@@ -0,0 +1,40 @@
+use axum::Json;
+use dal::{Blueprint, BlueprintPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{BlueprintError, BlueprintResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteBlueprintRequest {
+    #[schema(value_type = String)]
+    pub pk: BlueprintPk,
+}
+
+/// Deletes the blueprint. Components already created from it are left as they are.
+#[utoipa::path(
+    post,
+    path = "/api/blueprint/delete_blueprint",
+    request_body = DeleteBlueprintRequest,
+    responses((status = 200, description = "The blueprint was deleted")),
+    tag = "blueprint"
+)]
+pub async fn delete_blueprint(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<DeleteBlueprintRequest>,
+) -> BlueprintResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Blueprint::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(BlueprintError::NotFound(request.pk))?
+        .delete(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
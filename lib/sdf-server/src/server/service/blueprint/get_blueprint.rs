@@ -0,0 +1,44 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{Blueprint, BlueprintPk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{BlueprintError, BlueprintResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlueprintRequest {
+    #[param(value_type = String)]
+    pub pk: BlueprintPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlueprintResponse {
+    #[schema(value_type = Object)]
+    pub blueprint: Blueprint,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blueprint/get_blueprint",
+    params(GetBlueprintRequest),
+    responses((status = 200, body = GetBlueprintResponse)),
+    tag = "blueprint"
+)]
+pub async fn get_blueprint(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetBlueprintRequest>,
+) -> BlueprintResult<Json<GetBlueprintResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let blueprint = Blueprint::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(BlueprintError::NotFound(request.pk))?;
+
+    Ok(Json(GetBlueprintResponse { blueprint }))
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use axum::extract::Path;
+use axum::{response::IntoResponse, Json};
+use dal::{Blueprint, BlueprintPk, ChangeSet, InstantiatedComponent, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::{BlueprintError, BlueprintResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiateBlueprintRequest {
+    /// The value of each variable, overriding its default.
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub values: HashMap<String, Value>,
+    /// Where the top left of the blueprint is placed on the diagram.
+    pub x: f64,
+    pub y: f64,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiateBlueprintResponse {
+    /// The components created, along with the key of the blueprint component each was created
+    /// from.
+    #[schema(value_type = Vec<Object>)]
+    pub components: Vec<InstantiatedComponent>,
+}
+
+/// Creates the components of the blueprint, and the edges between them, in the change set of the
+/// request. A change set is created when the request is made on head.
+#[utoipa::path(
+    post,
+    path = "/api/blueprint/{blueprint_pk}/instantiate",
+    params(("blueprint_pk" = String, Path, description = "The pk of the blueprint")),
+    request_body = InstantiateBlueprintRequest,
+    responses((status = 200, body = InstantiateBlueprintResponse)),
+    tag = "blueprint"
+)]
+pub async fn instantiate_blueprint(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(blueprint_pk): Path<BlueprintPk>,
+    Json(request): Json<InstantiateBlueprintRequest>,
+) -> BlueprintResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let blueprint = Blueprint::get_by_pk(&ctx, blueprint_pk)
+        .await?
+        .ok_or(BlueprintError::NotFound(blueprint_pk))?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let components = blueprint
+        .instantiate(&ctx, request.values, request.x, request.y)
+        .await?;
+
+    WsEvent::component_created(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(
+        response.body(serde_json::to_string(&InstantiateBlueprintResponse {
+            components,
+        })?)?,
+    )
+}
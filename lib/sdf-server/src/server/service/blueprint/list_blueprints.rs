@@ -0,0 +1,31 @@
+use axum::Json;
+use dal::Blueprint;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::BlueprintResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBlueprintsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<Blueprint>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blueprint/list_blueprints",
+    responses((status = 200, body = ListBlueprintsResponse)),
+    tag = "blueprint"
+)]
+pub async fn list_blueprints(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> BlueprintResult<Json<ListBlueprintsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = Blueprint::list(&ctx).await?;
+
+    Ok(Json(ListBlueprintsResponse { list }))
+}
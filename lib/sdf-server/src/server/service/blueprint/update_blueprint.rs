@@ -0,0 +1,52 @@
+use axum::Json;
+use dal::{Blueprint, BlueprintPk, BlueprintSpec};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{BlueprintError, BlueprintResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBlueprintRequest {
+    #[schema(value_type = String)]
+    pub pk: BlueprintPk,
+    pub name: String,
+    pub description: Option<String>,
+    /// The components, edges and variables of the blueprint, replacing the previous ones.
+    #[schema(value_type = Object)]
+    pub spec: BlueprintSpec,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBlueprintResponse {
+    #[schema(value_type = Object)]
+    pub blueprint: Blueprint,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/blueprint/update_blueprint",
+    request_body = UpdateBlueprintRequest,
+    responses((status = 200, body = UpdateBlueprintResponse)),
+    tag = "blueprint"
+)]
+pub async fn update_blueprint(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<UpdateBlueprintRequest>,
+) -> BlueprintResult<Json<UpdateBlueprintResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut blueprint = Blueprint::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(BlueprintError::NotFound(request.pk))?;
+    blueprint
+        .update(&ctx, &request.name, request.description, request.spec)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateBlueprintResponse { blueprint }))
+}
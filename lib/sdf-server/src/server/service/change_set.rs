@@ -1,35 +1,57 @@
 use axum::{
-    http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Router,
 };
+use dal::error_category::categorize;
 use dal::{
-    change_status::ChangeStatusError, ChangeSetError as DalChangeSetError,
-    ComponentError as DalComponentError, FixError, StandardModelError, TransactionsError,
-    UserError, UserPk,
+    change_status::ChangeStatusError, ChangeSetApplyScheduleError,
+    ChangeSetError as DalChangeSetError, ChangeSetPk, ChangeSetReviewError, ChangeSetStatus,
+    ComponentError as DalComponentError, FixError, IdempotencyError, StandardModelError,
+    TransactionsError, UserError, UserPk,
 };
 use module_index_client::IndexClientError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::{server::state::AppState, service::pkg::PkgError};
+use crate::{
+    server::{
+        api_error::{ApiError, ApiErrorCode},
+        state::AppState,
+    },
+    service::pkg::PkgError,
+};
 
 pub mod apply_change_set;
 pub mod apply_change_set2;
+pub mod cancel_scheduled_apply;
 pub mod create_change_set;
 pub mod get_change_set;
+pub mod get_qualification_summary;
 pub mod get_stats;
 pub mod list_open_change_sets;
+pub mod list_reviews;
+pub mod list_scheduled_applies;
+pub mod request_review;
+pub mod review_change_set;
+pub mod schedule_apply;
 pub mod update_selected_change_set;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ChangeSetError {
+    #[error("change set {0} has no scheduled apply")]
+    ApplyNotScheduled(ChangeSetPk),
     #[error(transparent)]
     ChangeSet(#[from] DalChangeSetError),
+    #[error(transparent)]
+    ChangeSetApplySchedule(#[from] ChangeSetApplyScheduleError),
     #[error("change set not found")]
     ChangeSetNotFound,
+    #[error("change set {0} is {1}, only open change sets can be applied")]
+    ChangeSetNotOpen(ChangeSetPk, ChangeSetStatus),
+    #[error(transparent)]
+    ChangeSetReview(#[from] ChangeSetReviewError),
     #[error(transparent)]
     ChangeStatusError(#[from] ChangeStatusError),
     #[error(transparent)]
@@ -41,6 +63,8 @@ pub enum ChangeSetError {
     #[error(transparent)]
     Fix(#[from] FixError),
     #[error(transparent)]
+    Idempotency(#[from] IdempotencyError),
+    #[error(transparent)]
     IndexClient(#[from] IndexClientError),
     #[error("invalid user {0}")]
     InvalidUser(UserPk),
@@ -62,18 +86,30 @@ pub enum ChangeSetError {
 
 pub type ChangeSetResult<T> = std::result::Result<T, ChangeSetError>;
 
-impl IntoResponse for ChangeSetError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            ChangeSetError::ChangeSetNotFound => (StatusCode::NOT_FOUND, self.to_string()),
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+impl From<ChangeSetError> for ApiError {
+    fn from(err: ChangeSetError) -> Self {
+        let code = match &err {
+            ChangeSetError::ApplyNotScheduled(_) | ChangeSetError::ChangeSetNotFound => {
+                ApiErrorCode::NotFound
+            }
+            ChangeSetError::ChangeSetNotOpen(..) => ApiErrorCode::Conflict,
+            ChangeSetError::InvalidUser(_) | ChangeSetError::InvalidUserSystemInit => {
+                ApiErrorCode::Forbidden
+            }
+            ChangeSetError::ChangeSet(err) => err.into(),
+            ChangeSetError::ChangeSetApplySchedule(err) => err.into(),
+            ChangeSetError::ChangeSetReview(err) => err.into(),
+            ChangeSetError::Component(err) => err.into(),
+            ChangeSetError::StandardModel(err) => err.into(),
+            _ => categorize(&err).into(),
         };
+        ApiError::new(code, err.to_string())
+    }
+}
 
-        let body = Json(
-            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
-        );
-
-        (status, body).into_response()
+impl IntoResponse for ChangeSetError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
     }
 }
 
@@ -89,6 +125,10 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/get_change_set", get(get_change_set::get_change_set))
         .route("/get_stats", get(get_stats::get_stats))
+        .route(
+            "/get_qualification_summary",
+            get(get_qualification_summary::get_qualification_summary),
+        )
         .route(
             "/apply_change_set",
             post(apply_change_set::apply_change_set),
@@ -97,6 +137,21 @@ pub fn routes() -> Router<AppState> {
             "/apply_change_set2",
             post(apply_change_set2::apply_change_set),
         )
+        .route("/request_review", post(request_review::request_review))
+        .route(
+            "/review_change_set",
+            post(review_change_set::review_change_set),
+        )
+        .route("/list_reviews", get(list_reviews::list_reviews))
+        .route("/schedule_apply", post(schedule_apply::schedule_apply))
+        .route(
+            "/cancel_scheduled_apply",
+            post(cancel_scheduled_apply::cancel_scheduled_apply),
+        )
+        .route(
+            "/list_scheduled_applies",
+            get(list_scheduled_applies::list_scheduled_applies),
+        )
         .route(
             "/update_selected_change_set",
             post(update_selected_change_set::update_selected_change_set),
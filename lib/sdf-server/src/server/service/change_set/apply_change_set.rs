@@ -1,36 +1,63 @@
 use super::ChangeSetResult;
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{AccessBuilder, HandlerContext, IdempotencyKey, PosthogClient};
 use crate::server::service::change_set::ChangeSetError;
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::Json;
-use dal::{ChangeSet, ChangeSetPk};
+use dal::{ChangeSet, ChangeSetPk, ChangeSetStatus, IdempotencyRecord};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+const IDEMPOTENCY_ENDPOINT: &str = "change_set/apply_change_set";
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyChangeSetRequest {
+    #[schema(value_type = String)]
     pub change_set_pk: ChangeSetPk,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyChangeSetResponse {
+    #[schema(value_type = Object)]
     pub change_set: ChangeSet,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/change_set/apply_change_set",
+    request_body = ApplyChangeSetRequest,
+    responses((status = 200, body = ApplyChangeSetResponse)),
+    tag = "change_set"
+)]
 pub async fn apply_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    IdempotencyKey(idempotency_key): IdempotencyKey,
     Json(request): Json<ApplyChangeSetRequest>,
 ) -> ChangeSetResult<Json<ApplyChangeSetResponse>> {
     let mut ctx = builder.build_head(access_builder).await?;
 
+    if let Some(idempotency_key) = &idempotency_key {
+        if let Some(response) =
+            IdempotencyRecord::find_response(&ctx, idempotency_key, IDEMPOTENCY_ENDPOINT).await?
+        {
+            return Ok(Json(response));
+        }
+    }
+
     let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
         .await?
         .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    if change_set.status != ChangeSetStatus::Open {
+        return Err(ChangeSetError::ChangeSetNotOpen(
+            change_set.pk,
+            change_set.status,
+        ));
+    }
     change_set.apply(&mut ctx).await?;
 
     track(
@@ -43,6 +70,22 @@ pub async fn apply_change_set(
         }),
     );
 
+    let response = ApplyChangeSetResponse { change_set };
+    if let Some(idempotency_key) = &idempotency_key {
+        if let Some(original) = IdempotencyRecord::store_response(
+            &ctx,
+            idempotency_key,
+            IDEMPOTENCY_ENDPOINT,
+            &response,
+        )
+        .await?
+        {
+            // A concurrent retry of this request finished first, so ours is discarded
+            ctx.rollback().await?;
+            return Ok(Json(original));
+        }
+    }
+
     ctx.commit().await?;
 
     /* temporarily disabling workspace backups
@@ -52,5 +95,5 @@ pub async fn apply_change_set(
     );
     */
 
-    Ok(Json(ApplyChangeSetResponse { change_set }))
+    Ok(Json(response))
 }
@@ -6,45 +6,64 @@ use axum::extract::OriginalUri;
 use axum::Json;
 use dal::job::definition::{FixItem, FixesJob};
 use dal::{
-    ActionPrototypeId, AttributeValueId, ChangeSet, ChangeSetPk, ComponentId, Fix, FixBatch,
-    HistoryActor, StandardModel, User,
+    ActionPrototypeId, AttributeValueId, ChangeSet, ChangeSetPk, ChangeSetStatus, ComponentId, Fix,
+    FixBatch, HistoryActor, StandardModel, User,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 //use telemetry::tracing::{info_span, Instrument, log::warn};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FixRunRequest {
+    #[schema(value_type = String)]
     pub attribute_value_id: AttributeValueId,
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    #[schema(value_type = String)]
     pub action_prototype_id: ActionPrototypeId,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct ApplyChangeSetRequest {
+pub struct ApplyChangeSet2Request {
+    #[schema(value_type = String)]
     pub change_set_pk: ChangeSetPk,
     pub list: Vec<FixRunRequest>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyChangeSetResponse {
+    #[schema(value_type = Object)]
     pub change_set: ChangeSet,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/change_set/apply_change_set2",
+    request_body = ApplyChangeSet2Request,
+    responses((status = 200, body = ApplyChangeSetResponse)),
+    tag = "change_set"
+)]
 pub async fn apply_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
-    Json(request): Json<ApplyChangeSetRequest>,
+    Json(request): Json<ApplyChangeSet2Request>,
 ) -> ChangeSetResult<Json<ApplyChangeSetResponse>> {
     let mut ctx = builder.build_head(access_builder).await?;
 
     let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
         .await?
         .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    if change_set.status != ChangeSetStatus::Open {
+        return Err(ChangeSetError::ChangeSetNotOpen(
+            change_set.pk,
+            change_set.status,
+        ));
+    }
     change_set.apply_raw(&mut ctx, false).await?;
 
     track(
@@ -60,9 +79,11 @@ pub async fn apply_change_set(
     ctx.blocking_commit().await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(ChangeSetError::InvalidUser(*user_pk))?,
+        HistoryActor::ApiToken { user_pk, .. } | HistoryActor::User(user_pk) => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(ChangeSetError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => return Err(ChangeSetError::InvalidUserSystemInit),
     };
@@ -0,0 +1,46 @@
+use axum::Json;
+use dal::{ChangeSetApplySchedule, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelScheduledApplyRequest {
+    #[schema(value_type = String)]
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelScheduledApplyResponse {
+    #[schema(value_type = Object)]
+    pub schedule: ChangeSetApplySchedule,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/change_set/cancel_scheduled_apply",
+    request_body = CancelScheduledApplyRequest,
+    responses((status = 200, body = CancelScheduledApplyResponse)),
+    tag = "change_set"
+)]
+pub async fn cancel_scheduled_apply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<CancelScheduledApplyRequest>,
+) -> ChangeSetResult<Json<CancelScheduledApplyResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut schedule =
+        ChangeSetApplySchedule::find_pending_for_change_set(&ctx, request.change_set_pk)
+            .await?
+            .ok_or(ChangeSetError::ApplyNotScheduled(request.change_set_pk))?;
+    schedule.cancel(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CancelScheduledApplyResponse { schedule }))
+}
@@ -2,23 +2,32 @@ use axum::extract::OriginalUri;
 use axum::Json;
 use dal::ChangeSet;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::ChangeSetResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateChangeSetRequest {
     pub change_set_name: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateChangeSetResponse {
+    #[schema(value_type = Object)]
     pub change_set: ChangeSet,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/change_set/create_change_set",
+    request_body = CreateChangeSetRequest,
+    responses((status = 200, body = CreateChangeSetResponse)),
+    tag = "change_set"
+)]
 pub async fn create_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -4,19 +4,30 @@ use axum::extract::Query;
 use axum::Json;
 use dal::{ChangeSet, ChangeSetPk};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetChangeSetRequest {
+    #[param(value_type = String)]
     pub pk: ChangeSetPk,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetChangeSetResponse {
+    #[schema(value_type = Object)]
     pub change_set: ChangeSet,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/change_set/get_change_set",
+    params(GetChangeSetRequest),
+    responses((status = 200, body = GetChangeSetResponse)),
+    tag = "change_set"
+)]
 pub async fn get_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -0,0 +1,45 @@
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+use axum::extract::Query;
+use axum::Json;
+use dal::qualification::QualificationSummary;
+use dal::{ChangeSet, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQualificationSummaryRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQualificationSummaryResponse {
+    #[schema(value_type = Object)]
+    pub summary: QualificationSummary,
+}
+
+/// Summarize the qualifications of every component in the _current_ change set. The summary is
+/// also published whenever it changes.
+#[utoipa::path(
+    get,
+    path = "/api/change_set/get_qualification_summary",
+    params(GetQualificationSummaryRequest),
+    responses((status = 200, body = GetQualificationSummaryResponse)),
+    tag = "change_set"
+)]
+pub async fn get_qualification_summary(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetQualificationSummaryRequest>,
+) -> ChangeSetResult<Json<GetQualificationSummaryResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let summary = ChangeSet::qualification_summary(&ctx).await?;
+
+    Ok(Json(GetQualificationSummaryResponse { summary }))
+}
@@ -6,21 +6,31 @@ use axum::Json;
 use dal::change_status::ComponentChangeStatus;
 use dal::Visibility;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetStatsRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetStatsResponse {
+    #[schema(value_type = Object)]
     pub component_stats: ComponentChangeStatus,
 }
 
 /// Gather statistics for the _current_ change set.
+#[utoipa::path(
+    get,
+    path = "/api/change_set/get_stats",
+    params(GetStatsRequest),
+    responses((status = 200, body = GetStatsResponse)),
+    tag = "change_set"
+)]
 pub async fn get_stats(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -3,13 +3,21 @@ use crate::server::extract::{AccessBuilder, HandlerContext};
 use axum::Json;
 use dal::{ChangeSet, ChangeSetPk, LabelList};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListOpenChangeSetsResponse {
+    #[schema(value_type = Object)]
     pub list: LabelList<ChangeSetPk>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/change_set/list_open_change_sets",
+    responses((status = 200, body = ListOpenChangeSetsResponse)),
+    tag = "change_set"
+)]
 pub async fn list_open_change_sets(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -0,0 +1,42 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{ChangeSetPk, ChangeSetReview};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListReviewsRequest {
+    #[param(value_type = String)]
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListReviewsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub reviews: Vec<ChangeSetReview>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/change_set/list_reviews",
+    params(ListReviewsRequest),
+    responses((status = 200, body = ListReviewsResponse)),
+    tag = "change_set"
+)]
+pub async fn list_reviews(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListReviewsRequest>,
+) -> ChangeSetResult<Json<ListReviewsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let reviews = ChangeSetReview::list_for_change_set(&ctx, request.change_set_pk).await?;
+
+    Ok(Json(ListReviewsResponse { reviews }))
+}
@@ -0,0 +1,31 @@
+use axum::Json;
+use dal::ChangeSetApplySchedule;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListScheduledAppliesResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub schedules: Vec<ChangeSetApplySchedule>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/change_set/list_scheduled_applies",
+    responses((status = 200, body = ListScheduledAppliesResponse)),
+    tag = "change_set"
+)]
+pub async fn list_scheduled_applies(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> ChangeSetResult<Json<ListScheduledAppliesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let schedules = ChangeSetApplySchedule::list_pending(&ctx).await?;
+
+    Ok(Json(ListScheduledAppliesResponse { schedules }))
+}
@@ -0,0 +1,45 @@
+use axum::Json;
+use dal::{ChangeSetPk, ChangeSetReview, UserPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestReviewRequest {
+    #[schema(value_type = String)]
+    pub change_set_pk: ChangeSetPk,
+    #[schema(value_type = Vec<String>)]
+    pub reviewer_user_pks: Vec<UserPk>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestReviewResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub reviews: Vec<ChangeSetReview>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/change_set/request_review",
+    request_body = RequestReviewRequest,
+    responses((status = 200, body = RequestReviewResponse)),
+    tag = "change_set"
+)]
+pub async fn request_review(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<RequestReviewRequest>,
+) -> ChangeSetResult<Json<RequestReviewResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let reviews =
+        ChangeSetReview::request(&ctx, request.change_set_pk, request.reviewer_user_pks).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(RequestReviewResponse { reviews }))
+}
@@ -0,0 +1,48 @@
+use axum::Json;
+use dal::{ChangeSetPk, ChangeSetReview};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewChangeSetRequest {
+    #[schema(value_type = String)]
+    pub change_set_pk: ChangeSetPk,
+    pub approve: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewChangeSetResponse {
+    #[schema(value_type = Object)]
+    pub review: ChangeSetReview,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/change_set/review_change_set",
+    request_body = ReviewChangeSetRequest,
+    responses((status = 200, body = ReviewChangeSetResponse)),
+    tag = "change_set"
+)]
+pub async fn review_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<ReviewChangeSetRequest>,
+) -> ChangeSetResult<Json<ReviewChangeSetResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let review = if request.approve {
+        ChangeSetReview::approve(&ctx, request.change_set_pk, request.comment).await?
+    } else {
+        ChangeSetReview::reject(&ctx, request.change_set_pk, request.comment).await?
+    };
+
+    ctx.commit().await?;
+
+    Ok(Json(ReviewChangeSetResponse { review }))
+}
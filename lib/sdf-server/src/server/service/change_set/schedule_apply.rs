@@ -0,0 +1,49 @@
+use axum::Json;
+use chrono::{DateTime, FixedOffset};
+use dal::{ChangeSet, ChangeSetApplySchedule, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleApplyRequest {
+    #[schema(value_type = String)]
+    pub change_set_pk: ChangeSetPk,
+    /// When to apply the change set, with the offset of the time zone it is scheduled in (e.g.
+    /// `2023-09-01T22:00:00+02:00`).
+    pub apply_at: DateTime<FixedOffset>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleApplyResponse {
+    #[schema(value_type = Object)]
+    pub schedule: ChangeSetApplySchedule,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/change_set/schedule_apply",
+    request_body = ScheduleApplyRequest,
+    responses((status = 200, body = ScheduleApplyResponse)),
+    tag = "change_set"
+)]
+pub async fn schedule_apply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<ScheduleApplyRequest>,
+) -> ChangeSetResult<Json<ScheduleApplyResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let schedule = change_set.schedule_apply(&ctx, request.apply_at).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ScheduleApplyResponse { schedule }))
+}
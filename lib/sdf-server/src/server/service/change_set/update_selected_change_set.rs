@@ -1,22 +1,32 @@
 use axum::Json;
 use dal::{ChangeSet, ChangeSetPk};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::{ChangeSetError, ChangeSetResult};
 use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateSelectedChangeSetRequest {
+    #[schema(value_type = String)]
     pub next_change_set_pk: ChangeSetPk,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateSelectedChangeSetResponse {
+    #[schema(value_type = Object)]
     pub change_set: ChangeSet,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/change_set/update_selected_change_set",
+    request_body = UpdateSelectedChangeSetRequest,
+    responses((status = 200, body = UpdateSelectedChangeSetResponse)),
+    tag = "change_set"
+)]
 pub async fn update_selected_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
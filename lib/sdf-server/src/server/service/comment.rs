@@ -0,0 +1,60 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{CommentError as DalCommentError, CommentId, TransactionsError, WsEventError};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod create_comment;
+pub mod delete_comment;
+pub mod list_comments;
+pub mod update_comment;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum CommentError {
+    #[error(transparent)]
+    Comment(#[from] DalCommentError),
+    #[error("comment not found: {0}")]
+    CommentNotFound(CommentId),
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error("a comment target needs either a component id, optionally with a prop path, or a change set pk")]
+    InvalidTarget,
+    #[error(transparent)]
+    StandardModel(#[from] dal::StandardModelError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type CommentResult<T> = std::result::Result<T, CommentError>;
+
+impl From<CommentError> for ApiError {
+    fn from(err: CommentError) -> Self {
+        let code = match &err {
+            CommentError::CommentNotFound(_) => ApiErrorCode::NotFound,
+            CommentError::InvalidTarget => ApiErrorCode::Validation,
+            CommentError::Comment(err) => err.into(),
+            CommentError::StandardModel(err) => err.into(),
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for CommentError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list_comments", get(list_comments::list_comments))
+        .route("/create_comment", post(create_comment::create_comment))
+        .route("/update_comment", post(update_comment::update_comment))
+        .route("/delete_comment", post(delete_comment::delete_comment))
+}
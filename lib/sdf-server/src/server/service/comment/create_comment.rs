@@ -0,0 +1,45 @@
+use axum::Json;
+use dal::{Comment, CommentTarget, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::CommentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCommentRequest {
+    #[schema(value_type = Object)]
+    pub target: CommentTarget,
+    pub body: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCommentResponse {
+    #[schema(value_type = Object)]
+    pub comment: Comment,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/comment/create_comment",
+    request_body = CreateCommentRequest,
+    responses((status = 200, body = CreateCommentResponse)),
+    tag = "comment"
+)]
+pub async fn create_comment(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<CreateCommentRequest>,
+) -> CommentResult<Json<CreateCommentResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let comment = Comment::new(&ctx, request.target, request.body).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateCommentResponse { comment }))
+}
@@ -0,0 +1,47 @@
+use axum::Json;
+use dal::{Comment, CommentId, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{CommentError, CommentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCommentRequest {
+    #[schema(value_type = String)]
+    pub comment_id: CommentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCommentResponse {
+    #[schema(value_type = Object)]
+    pub comment: Comment,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/comment/delete_comment",
+    request_body = DeleteCommentRequest,
+    responses((status = 200, body = DeleteCommentResponse)),
+    tag = "comment"
+)]
+pub async fn delete_comment(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<DeleteCommentRequest>,
+) -> CommentResult<Json<DeleteCommentResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut comment = Comment::get_by_id(&ctx, &request.comment_id)
+        .await?
+        .ok_or(CommentError::CommentNotFound(request.comment_id))?;
+    comment.remove(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(DeleteCommentResponse { comment }))
+}
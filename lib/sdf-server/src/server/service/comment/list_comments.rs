@@ -0,0 +1,61 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{ChangeSetPk, Comment, CommentTarget, ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{CommentError, CommentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCommentsRequest {
+    #[param(value_type = Option<String>)]
+    pub component_id: Option<ComponentId>,
+    pub prop_path: Option<String>,
+    #[param(value_type = Option<String>)]
+    pub change_set_pk: Option<ChangeSetPk>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCommentsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub comments: Vec<Comment>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/comment/list_comments",
+    params(ListCommentsRequest),
+    responses((status = 200, body = ListCommentsResponse)),
+    tag = "comment"
+)]
+pub async fn list_comments(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListCommentsRequest>,
+) -> CommentResult<Json<ListCommentsResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let target = match (
+        request.component_id,
+        request.prop_path,
+        request.change_set_pk,
+    ) {
+        (Some(component_id), None, None) => CommentTarget::Component { component_id },
+        (Some(component_id), Some(prop_path), None) => CommentTarget::Prop {
+            component_id,
+            prop_path,
+        },
+        (None, None, Some(change_set_pk)) => CommentTarget::ChangeSet { change_set_pk },
+        _ => return Err(CommentError::InvalidTarget),
+    };
+    let comments = Comment::list_for_target(&ctx, &target).await?;
+
+    Ok(Json(ListCommentsResponse { comments }))
+}
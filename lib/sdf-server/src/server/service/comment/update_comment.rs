@@ -0,0 +1,48 @@
+use axum::Json;
+use dal::{Comment, CommentId, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{CommentError, CommentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCommentRequest {
+    #[schema(value_type = String)]
+    pub comment_id: CommentId,
+    pub body: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCommentResponse {
+    #[schema(value_type = Object)]
+    pub comment: Comment,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/comment/update_comment",
+    request_body = UpdateCommentRequest,
+    responses((status = 200, body = UpdateCommentResponse)),
+    tag = "comment"
+)]
+pub async fn update_comment(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<UpdateCommentRequest>,
+) -> CommentResult<Json<UpdateCommentResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut comment = Comment::get_by_id(&ctx, &request.comment_id)
+        .await?
+        .ok_or(CommentError::CommentNotFound(request.comment_id))?;
+    comment.edit(&ctx, request.body).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateCommentResponse { comment }))
+}
@@ -1,34 +1,58 @@
 use axum::{
-    http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Router,
 };
 use dal::change_status::ChangeStatusError;
+use dal::error_category::categorize;
 use dal::{
     node::NodeError, property_editor::PropertyEditorError, AttributeContextBuilderError,
     AttributePrototypeArgumentError, AttributePrototypeError, AttributeValueError, ChangeSetError,
-    ComponentError as DalComponentError, ComponentId, DiagramError, ExternalProviderError,
+    ComponentError as DalComponentError, ComponentId, ComponentLabelError, ComponentLifecycleError,
+    ComponentSnapshotError, ComponentSnapshotPk, DiagramError, ExternalProviderError,
     FuncBindingError, FuncError, InternalProviderError, PropId, ReconciliationPrototypeError,
-    SchemaError as DalSchemaError, StandardModelError, TransactionsError, WsEventError,
+    SchemaError as DalSchemaError, StandardModelError, SuggestionPrototypeError, TransactionsError,
+    WsEventError,
 };
 use thiserror::Error;
 
-use crate::{server::state::AppState, service::schema::SchemaError};
+use crate::{
+    server::{
+        api_error::{ApiError, ApiErrorCode},
+        state::AppState,
+    },
+    service::schema::SchemaError,
+};
 
 pub mod alter_simulation;
+pub mod create_snapshot;
+pub mod get_attribute_value_provenance;
 pub mod get_code;
+pub mod get_code_artifact;
+pub mod get_code_diff;
 pub mod get_components_metadata;
 pub mod get_diff;
+pub mod get_prop_suggestions;
 pub mod get_property_editor_schema;
 pub mod get_property_editor_validations;
 pub mod get_property_editor_values;
+pub mod get_snapshot;
 pub mod insert_property_editor_value;
+pub mod list_code_artifacts;
+pub mod list_labels;
+pub mod list_lifecycles;
 pub mod list_qualifications;
 pub mod list_resources;
+pub mod list_snapshots;
 pub mod refresh;
+pub mod remove_label;
 pub mod resource_domain_diff;
+pub mod restore_snapshot;
+pub mod set_label;
+pub mod set_resource_conflict_policy;
 pub mod set_type;
+pub mod stream_components;
+pub mod update_properties;
 pub mod update_property_editor_value;
 
 #[remain::sorted]
@@ -50,12 +74,23 @@ pub enum ComponentError {
     ChangeSet(#[from] ChangeSetError),
     #[error("change status error: {0}")]
     ChangeStatus(#[from] ChangeStatusError),
+    #[error("no artifact named {name} was generated for code generation {code_generation}")]
+    CodeArtifactNotFound {
+        code_generation: String,
+        name: String,
+    },
     #[error("component error: {0}")]
     Component(#[from] DalComponentError),
+    #[error("component label error: {0}")]
+    ComponentLabel(#[from] ComponentLabelError),
+    #[error("component lifecycle error: {0}")]
+    ComponentLifecycle(#[from] ComponentLifecycleError),
     #[error("component name not found")]
     ComponentNameNotFound,
     #[error("component not found for id: {0}")]
     ComponentNotFound(ComponentId),
+    #[error("component snapshot error: {0}")]
+    ComponentSnapshot(#[from] ComponentSnapshotError),
     #[error("dal schema error: {0}")]
     DalSchema(#[from] DalSchemaError),
     #[error("diagram error: {0}")]
@@ -76,6 +111,8 @@ pub enum ComponentError {
     InvalidRequest,
     #[error("invalid visibility")]
     InvalidVisibility,
+    #[error("component {0} has no label {1:?}")]
+    LabelNotFound(ComponentId, String),
     #[error(transparent)]
     Nats(#[from] si_data_nats::NatsError),
     #[error("node error: {0}")]
@@ -96,8 +133,12 @@ pub enum ComponentError {
     SchemaVariantNotFound,
     #[error("serde json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("component snapshot not found: {0}")]
+    SnapshotNotFound(ComponentSnapshotPk),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
+    #[error("suggestion prototype error: {0}")]
+    SuggestionPrototype(#[from] SuggestionPrototypeError),
     #[error("system id is required: ident_nil_v1() was provided")]
     SystemIdRequired,
     #[error(transparent)]
@@ -108,19 +149,56 @@ pub enum ComponentError {
 
 pub type ComponentResult<T> = std::result::Result<T, ComponentError>;
 
-impl IntoResponse for ComponentError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            ComponentError::SchemaNotFound => (StatusCode::NOT_FOUND, self.to_string()),
-            ComponentError::InvalidVisibility => (StatusCode::NOT_FOUND, self.to_string()),
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+impl From<ComponentError> for ApiError {
+    fn from(err: ComponentError) -> Self {
+        let code = match &err {
+            ComponentError::AttributePrototypeNotFound
+            | ComponentError::AttributeValueNotFound
+            | ComponentError::CodeArtifactNotFound { .. }
+            | ComponentError::ComponentNameNotFound
+            | ComponentError::ComponentNotFound(_)
+            | ComponentError::InvalidVisibility
+            | ComponentError::LabelNotFound(..)
+            | ComponentError::PropNotFound(_)
+            | ComponentError::SchemaNotFound
+            | ComponentError::SchemaVariantNotFound
+            | ComponentError::SnapshotNotFound(_) => ApiErrorCode::NotFound,
+            ComponentError::InvalidRequest | ComponentError::SystemIdRequired => {
+                ApiErrorCode::Validation
+            }
+            ComponentError::AttributeValue(err) => err.into(),
+            ComponentError::ChangeSet(err) => err.into(),
+            ComponentError::Component(err) => err.into(),
+            ComponentError::ComponentLabel(err) => err.into(),
+            ComponentError::ComponentSnapshot(err) => err.into(),
+            ComponentError::DalSchema(err) => err.into(),
+            ComponentError::Diagram(err) => err.into(),
+            ComponentError::Node(err) => err.into(),
+            ComponentError::StandardModel(err) => err.into(),
+            _ => categorize(&err).into(),
         };
+        let api_error = ApiError::new(code, err.to_string());
 
-        let body = Json(
-            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
-        );
+        // Hand the current value back, so the client can merge it with its change and retry
+        match err {
+            ComponentError::AttributeValue(AttributeValueError::RevisionConflict {
+                attribute_value_id,
+                current_revision,
+                current_value,
+                ..
+            }) => api_error.with_details(serde_json::json!({
+                "attributeValueId": attribute_value_id,
+                "currentRevision": current_revision,
+                "currentValue": current_value,
+            })),
+            _ => api_error,
+        }
+    }
+}
 
-        (status, body).into_response()
+impl IntoResponse for ComponentError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
     }
 }
 
@@ -135,8 +213,39 @@ pub fn routes() -> Router<AppState> {
             get(list_qualifications::list_qualifications),
         )
         .route("/list_resources", get(list_resources::list_resources))
+        .route("/list_lifecycles", get(list_lifecycles::list_lifecycles))
+        .route("/list_labels", get(list_labels::list_labels))
+        .route("/set_label", post(set_label::set_label))
+        .route("/remove_label", post(remove_label::remove_label))
+        .route("/list_snapshots", get(list_snapshots::list_snapshots))
+        .route("/get_snapshot", get(get_snapshot::get_snapshot))
+        .route("/create_snapshot", post(create_snapshot::create_snapshot))
+        .route(
+            "/restore_snapshot",
+            post(restore_snapshot::restore_snapshot),
+        )
+        .route(
+            "/stream_components",
+            get(stream_components::stream_components),
+        )
         .route("/get_code", get(get_code::get_code))
+        .route(
+            "/list_code_artifacts",
+            get(list_code_artifacts::list_code_artifacts),
+        )
+        .route(
+            "/get_code_artifact",
+            get(get_code_artifact::get_code_artifact),
+        )
         .route("/get_diff", get(get_diff::get_diff))
+        .route(
+            "/:component_id/code_diff",
+            get(get_code_diff::get_code_diff),
+        )
+        .route(
+            "/get_prop_suggestions",
+            get(get_prop_suggestions::get_prop_suggestions),
+        )
         .route(
             "/get_property_editor_schema",
             get(get_property_editor_schema::get_property_editor_schema),
@@ -149,6 +258,10 @@ pub fn routes() -> Router<AppState> {
             "/update_property_editor_value",
             post(update_property_editor_value::update_property_editor_value),
         )
+        .route(
+            "/update_properties",
+            post(update_properties::update_properties),
+        )
         .route(
             "/insert_property_editor_value",
             post(insert_property_editor_value::insert_property_editor_value),
@@ -157,7 +270,15 @@ pub fn routes() -> Router<AppState> {
             "/get_property_editor_validations",
             get(get_property_editor_validations::get_property_editor_validations),
         )
+        .route(
+            "/get_attribute_value_provenance",
+            get(get_attribute_value_provenance::get_attribute_value_provenance),
+        )
         .route("/set_type", post(set_type::set_type))
+        .route(
+            "/set_resource_conflict_policy",
+            post(set_resource_conflict_policy::set_resource_conflict_policy),
+        )
         .route("/refresh", post(refresh::refresh))
         .route("/resource_domain_diff", get(resource_domain_diff::get_diff))
         .route(
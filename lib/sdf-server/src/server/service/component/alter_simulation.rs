@@ -4,30 +4,41 @@ use dal::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AlterSimulationRequest {
+    #[schema(value_type = HashMap<String, Object>)]
     pub attribute_values: HashMap<AttributeValueId, serde_json::Value>,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AlterSimulationResponse {
     success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/component/alter_simulation",
+    request_body = AlterSimulationRequest,
+    responses((status = 200, body = AlterSimulationResponse)),
+    tag = "component"
+)]
 pub async fn alter_simulation(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Json(request): Json<AlterSimulationRequest>,
 ) -> ComponentResult<Json<AlterSimulationResponse>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut change_set = ChangeSet::new(&ctx, "fix-simulation", None).await?;
     ctx.update_visibility(Visibility::new_change_set(change_set.pk, false));
@@ -0,0 +1,47 @@
+use axum::Json;
+use dal::{ComponentId, ComponentSnapshot, ComponentSnapshotTrigger, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSnapshotRequest {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSnapshotResponse {
+    #[schema(value_type = Object)]
+    pub snapshot: ComponentSnapshot,
+}
+
+/// Takes a snapshot of a component, as seen in the change set of the request.
+#[utoipa::path(
+    post,
+    path = "/api/component/create_snapshot",
+    request_body = CreateSnapshotRequest,
+    responses((status = 200, body = CreateSnapshotResponse)),
+    tag = "component"
+)]
+pub async fn create_snapshot(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<CreateSnapshotRequest>,
+) -> ComponentResult<Json<CreateSnapshotResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let snapshot =
+        ComponentSnapshot::capture(&ctx, request.component_id, ComponentSnapshotTrigger::Manual)
+            .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateSnapshotResponse { snapshot }))
+}
@@ -0,0 +1,40 @@
+use axum::extract::{Json, Query};
+use dal::{AttributeValue, AttributeValueId, AttributeValueProvenance, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAttributeValueProvenanceRequest {
+    #[param(value_type = String)]
+    pub attribute_value_id: AttributeValueId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type GetAttributeValueProvenanceResponse = AttributeValueProvenance;
+
+/// Explains where a value came from: the function which produced it, the arguments it was run
+/// with, and the upstream values, sockets and edges which fed those arguments.
+#[utoipa::path(
+    get,
+    path = "/api/component/get_attribute_value_provenance",
+    params(GetAttributeValueProvenanceRequest),
+    responses((status = 200, body = Object)),
+    tag = "component"
+)]
+pub async fn get_attribute_value_provenance(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetAttributeValueProvenanceRequest>,
+) -> ComponentResult<Json<GetAttributeValueProvenanceResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let provenance = AttributeValue::provenance(&ctx, request.attribute_value_id).await?;
+
+    Ok(Json(provenance))
+}
@@ -1,24 +1,35 @@
 use axum::{extract::Query, Json};
 use dal::{CodeView, Component, ComponentId, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetCodeRequest {
+    #[param(value_type = String)]
     pub component_id: ComponentId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetCodeResponse {
+    #[schema(value_type = Vec<Object>)]
     pub code_views: Vec<CodeView>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/component/get_code",
+    params(GetCodeRequest),
+    responses((status = 200, body = GetCodeResponse)),
+    tag = "component"
+)]
 pub async fn get_code(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -0,0 +1,69 @@
+use axum::{
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use dal::{Component, ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{ComponentError, ComponentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCodeArtifactRequest {
+    #[param(value_type = String)]
+    pub component_id: ComponentId,
+    /// The key of the "/root/code" entry the artifact was generated for.
+    pub code_generation: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Downloads the content of an artifact, served with the mime type it was generated with.
+#[utoipa::path(
+    get,
+    path = "/api/component/get_code_artifact",
+    params(GetCodeArtifactRequest),
+    responses(
+        (status = 200, body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 404, description = "No such artifact was generated for the component"),
+    ),
+    tag = "component"
+)]
+pub async fn get_code_artifact(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetCodeArtifactRequest>,
+) -> ComponentResult<Response> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let artifact = Component::find_code_artifact(
+        &ctx,
+        request.component_id,
+        &request.code_generation,
+        &request.name,
+    )
+    .await?
+    .ok_or(ComponentError::CodeArtifactNotFound {
+        code_generation: request.code_generation,
+        name: request.name,
+    })?;
+
+    // Quotes would end the file name early, so they are dropped from it
+    let content_disposition = format!(
+        "attachment; filename=\"{}\"",
+        artifact.name.replace(['"', '\\'], "")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, artifact.mime_type),
+            (header::CONTENT_DISPOSITION, content_disposition),
+        ],
+        artifact.content,
+    )
+        .into_response())
+}
@@ -0,0 +1,56 @@
+use axum::extract::{Path, Query};
+use axum::Json;
+use dal::component::code::{CodeGenerated, CodeGeneratedDiff};
+use dal::{ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCodeDiffRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCodeDiffResponse {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    #[schema(value_type = Vec<Object>)]
+    pub diffs: Vec<CodeGeneratedDiff>,
+}
+
+/// Returns the unified diffs of the code and artifacts generated for a component which changed
+/// between head and the _current_ change set.
+#[utoipa::path(
+    get,
+    path = "/api/component/{component_id}/code_diff",
+    params(
+        ("component_id" = String, Path, description = "The id of the component"),
+        GetCodeDiffRequest,
+    ),
+    responses((status = 200, body = GetCodeDiffResponse)),
+    tag = "component"
+)]
+pub async fn get_code_diff(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(component_id): Path<ComponentId>,
+    Query(request): Query<GetCodeDiffRequest>,
+) -> ComponentResult<Json<GetCodeDiffResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    let head_ctx = ctx.clone_with_head();
+
+    let diffs = CodeGenerated::diff_for_component(&ctx, &head_ctx, component_id).await?;
+
+    Ok(Json(GetCodeDiffResponse {
+        component_id,
+        diffs,
+    }))
+}
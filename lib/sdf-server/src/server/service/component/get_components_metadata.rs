@@ -4,37 +4,48 @@ use dal::{
     qualification::QualificationSubCheckStatus, Component, ComponentId, StandardModel, Visibility,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::{ComponentError, ComponentResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetComponentsMetadataRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ComponentMetadata {
     pub schema_name: String,
     pub schema_link: Option<String>,
     pub qualified: Option<bool>,
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetComponentsMetadataResponse {
     pub data: Vec<ComponentMetadata>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/component/get_components_metadata",
+    params(GetComponentsMetadataRequest),
+    responses((status = 200, body = GetComponentsMetadataResponse)),
+    tag = "component"
+)]
 pub async fn get_components_metadata(
-    HandlerContext(builder): HandlerContext,
+    HandlerContext(mut builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Query(request): Query<GetComponentsMetadataRequest>,
 ) -> ComponentResult<Json<GetComponentsMetadataResponse>> {
+    builder.set_read_only();
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let components = Component::list(&ctx).await?;
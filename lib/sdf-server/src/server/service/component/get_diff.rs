@@ -2,29 +2,41 @@ use axum::{extract::Query, Json};
 use dal::component::diff::ComponentDiff;
 use dal::{ComponentId, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetDiffRequest {
+    #[param(value_type = String)]
     pub component_id: ComponentId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetDiffResponse {
+    #[schema(value_type = Object)]
     pub component_diff: ComponentDiff,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/component/get_diff",
+    params(GetDiffRequest),
+    responses((status = 200, body = GetDiffResponse)),
+    tag = "component"
+)]
 pub async fn get_diff(
-    HandlerContext(builder): HandlerContext,
+    HandlerContext(mut builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Query(request): Query<GetDiffRequest>,
 ) -> ComponentResult<Json<GetDiffResponse>> {
+    builder.set_read_only();
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let component_diff = ComponentDiff::new(&ctx, request.component_id).await?;
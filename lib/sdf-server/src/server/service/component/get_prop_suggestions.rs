@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use dal::suggestion_prototype::filter_candidates;
+use dal::{Component, ComponentId, PropId, StandardModel, SuggestionPrototype, Visibility};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+use super::{ComponentError, ComponentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::state::{PropSuggestionCache, PropSuggestionCacheKey};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPropSuggestionsRequest {
+    #[param(value_type = String)]
+    pub component_id: ComponentId,
+    #[param(value_type = String)]
+    pub prop_id: PropId,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPropSuggestionsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub suggestions: Vec<Value>,
+}
+
+/// Returns the values suggested for a prop of a component which start with the given prefix.
+///
+/// The candidates suggested for a prop are cached for a few seconds, so clients may ask again on
+/// every keystroke without executing its suggestion funcs each time.
+#[utoipa::path(
+    get,
+    path = "/api/component/get_prop_suggestions",
+    params(GetPropSuggestionsRequest),
+    responses((status = 200, body = GetPropSuggestionsResponse)),
+    tag = "component"
+)]
+pub async fn get_prop_suggestions(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    State(prop_suggestions): State<PropSuggestionCache>,
+    Query(request): Query<GetPropSuggestionsRequest>,
+) -> ComponentResult<Json<GetPropSuggestionsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let is_component_in_tenancy = Component::is_in_tenancy(&ctx, request.component_id).await?;
+    let is_component_in_visibility = Component::get_by_id(&ctx, &request.component_id)
+        .await?
+        .is_some();
+    if is_component_in_tenancy && !is_component_in_visibility {
+        return Err(ComponentError::InvalidVisibility);
+    }
+
+    let key = PropSuggestionCacheKey {
+        workspace_pk: ctx.tenancy().workspace_pk(),
+        change_set_pk: ctx.visibility().change_set_pk,
+        component_id: request.component_id,
+        prop_id: request.prop_id,
+    };
+    let candidates = match prop_suggestions.get(&key).await {
+        Some(candidates) => candidates,
+        None => {
+            let candidates = Arc::new(
+                SuggestionPrototype::candidates_for_prop(
+                    &ctx,
+                    request.component_id,
+                    request.prop_id,
+                )
+                .await?,
+            );
+            prop_suggestions.insert(key, candidates.clone()).await;
+            candidates
+        }
+    };
+
+    Ok(Json(GetPropSuggestionsResponse {
+        suggestions: filter_candidates(&candidates, &request.prefix),
+    }))
+}
@@ -3,13 +3,16 @@ use axum::Json;
 use dal::property_editor::schema::PropertyEditorSchema;
 use dal::{Component, ComponentId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 
 use super::{ComponentError, ComponentResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPropertyEditorSchemaRequest {
+    #[param(value_type = String)]
     pub component_id: ComponentId,
     #[serde(flatten)]
     pub visibility: Visibility,
@@ -17,6 +20,13 @@ pub struct GetPropertyEditorSchemaRequest {
 
 pub type GetPropertyEditorSchemaResponse = PropertyEditorSchema;
 
+#[utoipa::path(
+    get,
+    path = "/api/component/get_property_editor_schema",
+    params(GetPropertyEditorSchemaRequest),
+    responses((status = 200, body = Object)),
+    tag = "component"
+)]
 pub async fn get_property_editor_schema(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
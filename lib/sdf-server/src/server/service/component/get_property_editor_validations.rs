@@ -2,13 +2,16 @@ use axum::extract::{Json, Query};
 use dal::property_editor::validations::PropertyEditorValidations;
 use dal::{Component, ComponentId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 
 use super::{ComponentError, ComponentResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPropertyEditorValidationsRequest {
+    #[param(value_type = String)]
     pub component_id: ComponentId,
     #[serde(flatten)]
     pub visibility: Visibility,
@@ -16,6 +19,13 @@ pub struct GetPropertyEditorValidationsRequest {
 
 pub type GetPropertyEditorValidationsResponse = PropertyEditorValidations;
 
+#[utoipa::path(
+    get,
+    path = "/api/component/get_property_editor_validations",
+    params(GetPropertyEditorValidationsRequest),
+    responses((status = 200, body = Object)),
+    tag = "component"
+)]
 pub async fn get_property_editor_validations(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
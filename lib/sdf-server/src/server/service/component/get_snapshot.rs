@@ -0,0 +1,45 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{ComponentSnapshot, ComponentSnapshotPk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{ComponentError, ComponentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSnapshotRequest {
+    #[param(value_type = String)]
+    pub pk: ComponentSnapshotPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSnapshotResponse {
+    #[schema(value_type = Object)]
+    pub snapshot: ComponentSnapshot,
+}
+
+/// Gets a snapshot of a component, with the whole attribute value tree it captured.
+#[utoipa::path(
+    get,
+    path = "/api/component/get_snapshot",
+    params(GetSnapshotRequest),
+    responses((status = 200, body = GetSnapshotResponse)),
+    tag = "component"
+)]
+pub async fn get_snapshot(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetSnapshotRequest>,
+) -> ComponentResult<Json<GetSnapshotResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let snapshot = ComponentSnapshot::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(ComponentError::SnapshotNotFound(request.pk))?;
+
+    Ok(Json(GetSnapshotResponse { snapshot }))
+}
@@ -4,28 +4,42 @@ use dal::{
     WsEvent,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InsertPropertyEditorValueRequest {
+    #[schema(value_type = String)]
     pub parent_attribute_value_id: AttributeValueId,
+    #[schema(value_type = String)]
     pub prop_id: PropId,
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    #[schema(value_type = Option<Object>)]
     pub value: Option<serde_json::Value>,
     pub key: Option<String>,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/component/insert_property_editor_value",
+    request_body = InsertPropertyEditorValueRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "component"
+)]
 pub async fn insert_property_editor_value(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Json(request): Json<InsertPropertyEditorValueRequest>,
 ) -> ComponentResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
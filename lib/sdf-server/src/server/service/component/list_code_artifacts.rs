@@ -0,0 +1,43 @@
+use axum::{extract::Query, Json};
+use dal::{component::code::CodeArtifactView, Component, ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCodeArtifactsRequest {
+    #[param(value_type = String)]
+    pub component_id: ComponentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCodeArtifactsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub artifacts: Vec<CodeArtifactView>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/component/list_code_artifacts",
+    params(ListCodeArtifactsRequest),
+    responses((status = 200, body = ListCodeArtifactsResponse)),
+    tag = "component"
+)]
+pub async fn list_code_artifacts(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListCodeArtifactsRequest>,
+) -> ComponentResult<Json<ListCodeArtifactsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let artifacts = Component::list_code_artifacts(&ctx, request.component_id).await?;
+
+    Ok(Json(ListCodeArtifactsResponse { artifacts }))
+}
@@ -0,0 +1,44 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{ComponentId, ComponentLabel, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLabelsRequest {
+    /// Only list the labels of this component.
+    #[param(value_type = Option<String>)]
+    pub component_id: Option<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type ListLabelsResponse = Vec<ComponentLabel>;
+
+/// Lists the labels of a component, or of every component.
+#[utoipa::path(
+    get,
+    path = "/api/component/list_labels",
+    params(ListLabelsRequest),
+    responses((status = 200, body = Vec<Object>)),
+    tag = "component"
+)]
+pub async fn list_labels(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListLabelsRequest>,
+) -> ComponentResult<Json<ListLabelsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let labels = match request.component_id {
+        Some(component_id) => ComponentLabel::list_for_component(&ctx, component_id).await?,
+        None => ComponentLabel::list(&ctx).await?,
+    };
+
+    Ok(Json(labels))
+}
@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::Query;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::{
+    Component, ComponentId, ComponentLifecycle, ComponentLifecycleState, LabelSelector,
+    StandardModel, Visibility,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLifecyclesRequest {
+    /// Only list the components in this lifecycle state.
+    #[param(value_type = Option<String>)]
+    pub state: Option<ComponentLifecycleState>,
+    /// Only list the components whose labels match this selector, such as
+    /// `label.env=prod AND label.team=payments`.
+    pub selector: Option<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentLifecycleView {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    pub component_name: String,
+    #[schema(value_type = String)]
+    pub state: ComponentLifecycleState,
+    #[schema(value_type = Option<String>)]
+    pub previous_state: Option<ComponentLifecycleState>,
+    /// When the component entered its state, unless it never left the "Draft" state.
+    #[schema(value_type = Option<String>)]
+    pub changed_at: Option<DateTime<Utc>>,
+}
+
+pub type ListLifecyclesResponse = Vec<ComponentLifecycleView>;
+
+/// Lists the lifecycle state of the components, optionally filtered by state and labels.
+#[utoipa::path(
+    get,
+    path = "/api/component/list_lifecycles",
+    params(ListLifecyclesRequest),
+    responses((status = 200, body = Vec<ComponentLifecycleView>)),
+    tag = "component"
+)]
+pub async fn list_lifecycles(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListLifecyclesRequest>,
+) -> ComponentResult<Json<ListLifecyclesResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut lifecycles: HashMap<ComponentId, ComponentLifecycle> = ComponentLifecycle::list(&ctx)
+        .await?
+        .into_iter()
+        .map(|lifecycle| (*lifecycle.component_id(), lifecycle))
+        .collect();
+
+    let selected: Option<HashSet<ComponentId>> = match &request.selector {
+        Some(selector) => Some(
+            selector
+                .parse::<LabelSelector>()?
+                .select(&ctx)
+                .await?
+                .into_iter()
+                .collect(),
+        ),
+        None => None,
+    };
+
+    let mut views = Vec::new();
+    for component in Component::list(&ctx).await? {
+        if selected
+            .as_ref()
+            .map_or(false, |selected| !selected.contains(component.id()))
+        {
+            continue;
+        }
+        let lifecycle = lifecycles.remove(component.id());
+        let state = lifecycle
+            .as_ref()
+            .map(|lifecycle| *lifecycle.state())
+            .unwrap_or_default();
+        if request.state.map_or(false, |filter| filter != state) {
+            continue;
+        }
+
+        views.push(ComponentLifecycleView {
+            component_id: *component.id(),
+            component_name: component.name(&ctx).await?,
+            state,
+            previous_state: lifecycle
+                .as_ref()
+                .map(|lifecycle| *lifecycle.previous_state()),
+            changed_at: lifecycle.map(|lifecycle| lifecycle.timestamp().updated_at),
+        });
+    }
+
+    Ok(Json(views))
+}
@@ -2,13 +2,16 @@ use axum::extract::Query;
 use axum::Json;
 use dal::{qualification::QualificationView, Component, ComponentId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 
 use super::{ComponentError, ComponentResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListQualificationsRequest {
+    #[param(value_type = String)]
     pub component_id: ComponentId,
     #[serde(flatten)]
     pub visibility: Visibility,
@@ -16,6 +19,13 @@ pub struct ListQualificationsRequest {
 
 pub type QualificationResponse = Vec<QualificationView>;
 
+#[utoipa::path(
+    get,
+    path = "/api/component/list_qualifications",
+    params(ListQualificationsRequest),
+    responses((status = 200, body = Vec<Object>)),
+    tag = "component"
+)]
 pub async fn list_qualifications(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
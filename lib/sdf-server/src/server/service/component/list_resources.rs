@@ -1,27 +1,48 @@
 use axum::extract::Query;
 use axum::Json;
-use dal::{ComponentId, ResourceView, Visibility};
+use dal::{ComponentId, LabelSelector, ResourceView, Visibility};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use utoipa::IntoParams;
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListResourcesRequest {
+    /// Only list the resources of the components whose labels match this selector, such as
+    /// `label.env=prod AND label.team=payments`.
+    pub selector: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
 pub type ListResourcesResponse = HashMap<ComponentId, ResourceView>;
 
+#[utoipa::path(
+    get,
+    path = "/api/component/list_resources",
+    params(ListResourcesRequest),
+    responses((status = 200, body = HashMap<String, Object>)),
+    tag = "component"
+)]
 pub async fn list_resources(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Query(request): Query<ListResourcesRequest>,
 ) -> ComponentResult<Json<ListResourcesResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
-    let resources = ResourceView::list_with_deleted(&ctx).await?;
+    let mut resources = ResourceView::list_with_deleted(&ctx).await?;
+    if let Some(selector) = &request.selector {
+        let selected: HashSet<ComponentId> = selector
+            .parse::<LabelSelector>()?
+            .select(&ctx)
+            .await?
+            .into_iter()
+            .collect();
+        resources.retain(|component_id, _| selected.contains(component_id));
+    }
     Ok(Json(resources))
 }
@@ -0,0 +1,38 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{ComponentId, ComponentSnapshot};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSnapshotsRequest {
+    #[param(value_type = String)]
+    pub component_id: ComponentId,
+}
+
+pub type ListSnapshotsResponse = Vec<ComponentSnapshot>;
+
+/// Lists the snapshots of a component, taken in any change set, from newest to oldest.
+#[utoipa::path(
+    get,
+    path = "/api/component/list_snapshots",
+    params(ListSnapshotsRequest),
+    responses((status = 200, body = Vec<Object>)),
+    tag = "component"
+)]
+pub async fn list_snapshots(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListSnapshotsRequest>,
+) -> ComponentResult<Json<ListSnapshotsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let snapshots = ComponentSnapshot::list_for_component(&ctx, request.component_id).await?;
+
+    Ok(Json(snapshots))
+}
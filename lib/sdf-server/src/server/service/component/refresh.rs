@@ -5,25 +5,34 @@ use dal::{
     job::definition::RefreshJob, Component, ComponentId, StandardModel, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::{ComponentError, ComponentResult};
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshRequest {
+    #[schema(value_type = Option<String>)]
     pub component_id: Option<ComponentId>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshResponse {
     pub success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/component/refresh",
+    request_body = RefreshRequest,
+    responses((status = 200, body = RefreshResponse)),
+    tag = "component"
+)]
 pub async fn refresh(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -0,0 +1,69 @@
+use axum::{response::IntoResponse, Json};
+use dal::{ChangeSet, ComponentId, ComponentLabel, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{ComponentError, ComponentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveLabelRequest {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    pub key: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Removes a label from a component.
+#[utoipa::path(
+    post,
+    path = "/api/component/remove_label",
+    request_body = RemoveLabelRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "component"
+)]
+pub async fn remove_label(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<RemoveLabelRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    if !ComponentLabel::remove(&ctx, request.component_id, &request.key).await? {
+        return Err(ComponentError::LabelNotFound(
+            request.component_id,
+            request.key,
+        ));
+    }
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(response.body(axum::body::Empty::new())?)
+}
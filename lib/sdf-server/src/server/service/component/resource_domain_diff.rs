@@ -1,47 +1,49 @@
 use axum::{extract::Query, Json};
-use dal::func::backend::js_reconciliation::{
-    ReconciliationDiff, ReconciliationDiffDomain, ReconciliationResult,
-};
+use dal::func::backend::js_reconciliation::{ReconciliationDiff, ReconciliationResult};
 use dal::{
-    AttributeReadContext, AttributeValue, AttributeView, Component, ComponentId,
-    ExternalProviderId, Func, FuncBinding, FuncError, InternalProviderId, Prop,
-    ReconciliationPrototype, ReconciliationPrototypeContext, StandardModel, Visibility,
+    Component, ComponentId, FuncBinding, ReconciliationPrototype, ReconciliationPrototypeContext,
+    StandardModel, Visibility,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use telemetry::prelude::*;
+use utoipa::{IntoParams, ToSchema};
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 use crate::service::component::ComponentError;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetResourceDomainDiffRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourceDomainDiff {
+    #[schema(value_type = HashMap<String, Object>)]
     diff: HashMap<String, ReconciliationDiff>,
+    #[schema(value_type = Option<Object>)]
     reconciliation: Option<ReconciliationResult>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetResourceDomainDiffResponse {
+    #[schema(value_type = HashMap<String, ResourceDomainDiff>)]
     diffs: HashMap<ComponentId, ResourceDomainDiff>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
-#[serde(rename_all = "camelCase")]
-struct DiffValue {
-    diff: bool,
-    new_value: Option<serde_json::Value>,
-}
-
+#[utoipa::path(
+    get,
+    path = "/api/component/resource_domain_diff",
+    params(GetResourceDomainDiffRequest),
+    responses((status = 200, body = GetResourceDomainDiffResponse)),
+    tag = "component"
+)]
 pub async fn get_diff(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -61,91 +63,7 @@ pub async fn get_diff(
             return Ok(Json(GetResourceDomainDiffResponse::default()));
         }
 
-        let props = Prop::find_by_attr(ctx, "schema_variant_id", schema_variant.id()).await?;
-
-        let mut diff = HashMap::new();
-
-        for prop in props {
-            let (domain_prop_id, resource_prop_id) = match prop.refers_to_prop_id() {
-                None => continue,
-                Some(prop_id) => (*prop_id, *prop.id()),
-            };
-
-            let context = AttributeReadContext {
-                prop_id: Some(resource_prop_id),
-                internal_provider_id: Some(InternalProviderId::NONE),
-                external_provider_id: Some(ExternalProviderId::NONE),
-                component_id: Some(*component.id()),
-            };
-            let resource_prop_av = AttributeValue::find_for_context(ctx, context)
-                .await?
-                .ok_or(ComponentError::AttributeValueNotFound)?;
-
-            let view_context = AttributeReadContext {
-                prop_id: None,
-                internal_provider_id: Some(InternalProviderId::NONE),
-                external_provider_id: Some(ExternalProviderId::NONE),
-                component_id: Some(*component.id()),
-            };
-
-            let resource_prop_view =
-                AttributeView::new(ctx, view_context, Some(*resource_prop_av.id())).await?;
-
-            let context = AttributeReadContext {
-                prop_id: Some(domain_prop_id),
-                internal_provider_id: Some(InternalProviderId::NONE),
-                external_provider_id: Some(ExternalProviderId::NONE),
-                component_id: Some(*component.id()),
-            };
-
-            let domain_prop_av = AttributeValue::find_for_context(ctx, context)
-                .await?
-                .ok_or(ComponentError::AttributeValueNotFound)?;
-
-            let domain_prop_view =
-                AttributeView::new(ctx, view_context, Some(*domain_prop_av.id())).await?;
-
-            if let Some(func_id) = prop.diff_func_id() {
-                let func = Func::get_by_id(ctx, func_id)
-                    .await?
-                    .ok_or(FuncError::NotFound(*func_id))?;
-                let func_binding = FuncBinding::new(
-                    ctx,
-                    serde_json::json!({
-                        "first": domain_prop_view.value(),
-                        "second": resource_prop_view.value(),
-                    }),
-                    *func.id(),
-                    *func.backend_kind(),
-                )
-                .await?;
-                let diff_value = func_binding
-                    .execute(ctx)
-                    .await?
-                    .value()
-                    .cloned()
-                    .unwrap_or(serde_json::Value::Null);
-
-                let diff_value = DiffValue::deserialize(&diff_value)?;
-
-                // TODO: Should we treat unset as equal or not?
-                if diff_value.diff {
-                    diff.insert(
-                        prop.path().with_replaced_sep("/"),
-                        ReconciliationDiff {
-                            normalized_resource: diff_value.new_value,
-                            resource: resource_prop_view.value().clone(),
-                            domain: ReconciliationDiffDomain {
-                                id: *domain_prop_av.id(),
-                                value: domain_prop_view.value().clone(),
-                            },
-                        },
-                    );
-                }
-            } else {
-                warn!("Prop {} does not have diff functions set, therefore can't be diffed with prop {domain_prop_id:?}", prop.path().as_str());
-            }
-        }
+        let diff = Component::resource_domain_diff(ctx, *component.id()).await?;
 
         let context = ReconciliationPrototypeContext {
             component_id: *component.id(),
@@ -0,0 +1,75 @@
+use axum::{response::IntoResponse, Json};
+use dal::{AttributeValueId, ChangeSet, Component, ComponentSnapshotPk, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSnapshotRequest {
+    #[schema(value_type = String)]
+    pub snapshot_pk: ComponentSnapshotPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSnapshotResponse {
+    #[schema(value_type = Vec<String>)]
+    pub attribute_value_ids: Vec<AttributeValueId>,
+}
+
+/// Restores the component of a snapshot to how it was when the snapshot was taken, in the change
+/// set of the request. The component is snapshotted first, so that the restore can be undone.
+#[utoipa::path(
+    post,
+    path = "/api/component/restore_snapshot",
+    request_body = RestoreSnapshotRequest,
+    responses((status = 200, body = RestoreSnapshotResponse)),
+    tag = "component"
+)]
+pub async fn restore_snapshot(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<RestoreSnapshotRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let attribute_value_ids = Component::restore_from_snapshot(&ctx, request.snapshot_pk).await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(
+        response.body(serde_json::to_string(&RestoreSnapshotResponse {
+            attribute_value_ids,
+        })?)?,
+    )
+}
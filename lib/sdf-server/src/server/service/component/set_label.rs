@@ -0,0 +1,73 @@
+use axum::{response::IntoResponse, Json};
+use dal::{ChangeSet, ComponentId, ComponentLabel, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLabelRequest {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    pub key: String,
+    pub value: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLabelResponse {
+    #[schema(value_type = Object)]
+    pub label: ComponentLabel,
+}
+
+/// Sets a label of a component, replacing its current value, if any.
+#[utoipa::path(
+    post,
+    path = "/api/component/set_label",
+    request_body = SetLabelRequest,
+    responses((status = 200, body = SetLabelResponse)),
+    tag = "component"
+)]
+pub async fn set_label(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<SetLabelRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let label =
+        ComponentLabel::set(&ctx, request.component_id, &request.key, &request.value).await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(response.body(serde_json::to_string(&SetLabelResponse { label })?)?)
+}
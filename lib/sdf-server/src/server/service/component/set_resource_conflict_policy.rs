@@ -0,0 +1,90 @@
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+
+use dal::{
+    ChangeSet, Component, ComponentId, ResourceConflictPolicy, StandardModel, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use crate::service::component::ComponentError;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetResourceConflictPolicyRequest {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    #[schema(value_type = String)]
+    pub policy: ResourceConflictPolicy,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Sets what happens to the model of a component when a refresh finds that its resource was
+/// changed out-of-band.
+#[utoipa::path(
+    post,
+    path = "/api/component/set_resource_conflict_policy",
+    request_body = SetResourceConflictPolicyRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "component"
+)]
+pub async fn set_resource_conflict_policy(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<SetResourceConflictPolicyRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let mut component = Component::get_by_id(&ctx, &request.component_id)
+        .await?
+        .ok_or(ComponentError::ComponentNotFound(request.component_id))?;
+    component
+        .set_resource_conflict_policy(&ctx, request.policy)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "set_resource_conflict_policy",
+        serde_json::json!({
+                    "component_id": component.id(),
+                    "resource_conflict_policy": request.policy,
+        }),
+    );
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(response.body(axum::body::Empty::new())?)
+}
@@ -3,21 +3,32 @@ use axum::{response::IntoResponse, Json};
 
 use dal::{ChangeSet, Component, ComponentId, ComponentType, StandardModel, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::ComponentResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 use crate::service::component::ComponentError;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SetTypeRequest {
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    #[schema(value_type = Option<Object>)]
     pub value: Option<serde_json::Value>,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/component/set_type",
+    request_body = SetTypeRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "component"
+)]
 pub async fn set_type(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -26,6 +37,7 @@ pub async fn set_type(
     Json(request): Json<SetTypeRequest>,
 ) -> ComponentResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
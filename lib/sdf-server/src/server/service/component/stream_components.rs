@@ -0,0 +1,42 @@
+use axum::extract::Query;
+use axum::response::Response;
+use dal::{Component, ComponentId, StandardModel, Visibility};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{ComponentError, ComponentResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::ndjson::ndjson_response;
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamComponentsRequest {
+    /// Resume the stream after the component with this id.
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/component/stream_components",
+    params(StreamComponentsRequest),
+    responses((status = 200, body = String, content_type = "application/x-ndjson")),
+    tag = "component"
+)]
+pub async fn stream_components(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<StreamComponentsRequest>,
+) -> ComponentResult<Response> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    let components = Component::stream(&ctx, request.cursor).await?;
+
+    Ok(ndjson_response(
+        components.map_err(ComponentError::from),
+        |component: &Component| *component.id(),
+    ))
+}
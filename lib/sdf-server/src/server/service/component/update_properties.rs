@@ -0,0 +1,116 @@
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+use dal::{
+    component::AttributeUpdate, AttributeValueId, ChangeSet, Component, ComponentId, StandardModel,
+    Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use crate::service::component::ComponentError;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePropertiesRequest {
+    #[schema(value_type = String)]
+    pub component_id: ComponentId,
+    #[schema(value_type = Vec<Object>)]
+    pub updates: Vec<AttributeUpdate>,
+    pub client_request_id: Option<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePropertiesResponse {
+    #[schema(value_type = Vec<String>)]
+    pub attribute_value_ids: Vec<AttributeValueId>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/component/update_properties",
+    request_body = UpdatePropertiesRequest,
+    responses((status = 200, body = UpdatePropertiesResponse)),
+    tag = "component"
+)]
+pub async fn update_properties(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UpdatePropertiesRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    if request.updates.is_empty() {
+        return Err(ComponentError::InvalidRequest);
+    }
+
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let component = Component::get_by_id(&ctx, &request.component_id)
+        .await?
+        .ok_or(ComponentError::ComponentNotFound(request.component_id))?;
+
+    let component_schema = component
+        .schema(&ctx)
+        .await?
+        .ok_or(ComponentError::SchemaNotFound)?;
+
+    let json_pointers: Vec<String> = request
+        .updates
+        .iter()
+        .map(|update| update.json_pointer.clone())
+        .collect();
+    let attribute_value_ids =
+        Component::update_attributes_bulk(&ctx, request.component_id, request.updates).await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "property_values_updated",
+        serde_json::json!({
+            "component_id": component.id(),
+            "component_schema_name": component_schema.name(),
+            "json_pointers": json_pointers,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(
+        response.body(serde_json::to_string(&UpdatePropertiesResponse {
+            attribute_value_ids,
+        })?)?,
+    )
+}
@@ -1,31 +1,36 @@
-use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
-use axum::Json;
 use axum::Router;
+use dal::error_category::categorize;
 use dal::provider::external::ExternalProviderError as DalExternalProviderError;
 use dal::socket::{SocketError, SocketId};
 use dal::{
     node::NodeId, schema::variant::SchemaVariantError, AttributeValueError, ChangeSetError,
-    ComponentError, ComponentType, DiagramError as DalDiagramError, EdgeError,
-    InternalProviderError, NodeError, NodeKind, NodeMenuError, SchemaError as DalSchemaError,
-    SchemaVariantId, StandardModelError, TransactionsError,
+    ChangeSetPk, ComponentError, ComponentType, DiagramError as DalDiagramError, EdgeError,
+    IdempotencyError, InternalProviderError, NodeError, NodeKind, NodeMenuError,
+    SchemaError as DalSchemaError, SchemaVariantId, StandardModelError, TransactionsError,
 };
 use dal::{AttributeReadContext, WsEventError};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::server::api_error::{ApiError, ApiErrorCode};
 use crate::server::state::AppState;
 use crate::service::schema::SchemaError;
 
-mod connect_component_to_frame;
+pub mod connect_component_to_frame;
 pub mod create_connection;
 pub mod create_node;
 pub mod delete_component;
 pub mod delete_connection;
+pub mod delete_nodes;
 pub mod get_diagram;
 pub mod get_node_add_menu;
+pub mod get_schema_catalog;
+pub mod list_compatible_sockets;
 pub mod list_schema_variants;
-mod restore_component;
+pub mod move_nodes;
+pub mod restore_component;
 pub mod restore_connection;
 pub mod set_node_position;
 
@@ -63,6 +68,8 @@ pub enum DiagramError {
     #[error("invalid header name {0}")]
     Hyper(#[from] hyper::http::Error),
     #[error(transparent)]
+    Idempotency(#[from] IdempotencyError),
+    #[error(transparent)]
     InternalProvider(#[from] InternalProviderError),
     #[error("internal provider not found for socket id: {0}")]
     InternalProviderNotFoundForSocket(SocketId),
@@ -112,18 +119,67 @@ pub enum DiagramError {
 
 pub type DiagramResult<T> = Result<T, DiagramError>;
 
-impl IntoResponse for DiagramError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            DiagramError::SchemaNotFound => (StatusCode::NOT_FOUND, self.to_string()),
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-        };
+/// The response of a diagram mutation, along with the change set the client is forced onto if
+/// the mutation was made on head. Both are stored together so that idempotent retries return the
+/// same header and body as the original request.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedChangeSetResponse<T> {
+    pub force_changeset_pk: Option<ChangeSetPk>,
+    pub body: T,
+}
 
-        let body = Json(
-            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
-        );
+impl<T: Serialize> ForcedChangeSetResponse<T> {
+    pub fn build(self) -> DiagramResult<Response> {
+        let mut response = axum::response::Response::builder();
+        if let Some(force_changeset_pk) = self.force_changeset_pk {
+            response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+        }
+        Ok(response
+            .body(serde_json::to_string(&self.body)?)?
+            .into_response())
+    }
+}
 
-        (status, body).into_response()
+impl From<DiagramError> for ApiError {
+    fn from(err: DiagramError) -> Self {
+        let code = match &err {
+            DiagramError::AttributeValueNotFoundForContext(_)
+            | DiagramError::ComponentNotFound
+            | DiagramError::EdgeNotFound
+            | DiagramError::ExternalProviderNotFoundForSocket(_)
+            | DiagramError::FrameInternalProviderNotFoundForSchemaVariant(_)
+            | DiagramError::FrameSocketNotFound(_)
+            | DiagramError::InternalProviderNotFoundForSocket(_)
+            | DiagramError::NodeNotFound(_)
+            | DiagramError::ParentNodeNotFound(_)
+            | DiagramError::SchemaNotFound
+            | DiagramError::SchemaVariantNotFound
+            | DiagramError::SocketNotFound => ApiErrorCode::NotFound,
+            DiagramError::InvalidComponentTypeForFrame(_)
+            | DiagramError::InvalidParentNode(_)
+            | DiagramError::NodeMenu(NodeMenuError::ParentNotAFrame(_))
+            | DiagramError::InvalidRequest
+            | DiagramError::InvalidSystem => ApiErrorCode::Validation,
+            DiagramError::NotAuthorized => ApiErrorCode::Forbidden,
+            DiagramError::ChangeSet(err) => err.into(),
+            DiagramError::Component(err) => err.into(),
+            DiagramError::DalSchema(err) => err.into(),
+            DiagramError::DiagramError(err) => err.into(),
+            DiagramError::Edge(err) => err.into(),
+            DiagramError::Node(err) => err.into(),
+            DiagramError::NodeMenu(NodeMenuError::ComponentNotFound(_)) => ApiErrorCode::NotFound,
+            DiagramError::SchemaVariant(err) => err.into(),
+            DiagramError::StandardModel(err) => err.into(),
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for DiagramError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
     }
 }
 
@@ -134,11 +190,21 @@ pub fn routes() -> Router<AppState> {
             "/get_node_add_menu",
             post(get_node_add_menu::get_node_add_menu),
         )
+        .route(
+            "/get_schema_catalog",
+            get(get_schema_catalog::get_schema_catalog),
+        )
         .route("/create_node", post(create_node::create_node))
         .route(
             "/set_node_position",
             post(set_node_position::set_node_position),
         )
+        .route("/nodes/move", post(move_nodes::move_nodes))
+        .route("/nodes/delete", post(delete_nodes::delete_nodes))
+        .route(
+            "/list_compatible_sockets",
+            get(list_compatible_sockets::list_compatible_sockets),
+        )
         .route(
             "/create_connection",
             post(create_connection::create_connection),
@@ -10,24 +10,29 @@ use dal::{
 };
 use dal::{ComponentType, Socket};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 
 use super::{DiagramError, DiagramResult};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFrameConnectionRequest {
+    #[schema(value_type = String)]
     pub child_node_id: NodeId,
+    #[schema(value_type = String)]
     pub parent_node_id: NodeId,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFrameConnectionResponse {
+    #[schema(value_type = Object)]
     pub connection: Connection,
 }
 
@@ -215,6 +220,13 @@ pub async fn connect_component_sockets_to_frame(
 /// Create a [`Connection`](dal::Connection) with a _to_ [`Socket`](dal::Socket) and
 /// [`Node`](dal::Node) and a _from_ [`Socket`](dal::Socket) and [`Node`](dal::Node).
 /// Creating a change set if on head.
+#[utoipa::path(
+    post,
+    path = "/api/diagram/connect_component_to_frame",
+    request_body = CreateFrameConnectionRequest,
+    responses((status = 200, body = CreateFrameConnectionResponse)),
+    tag = "diagram"
+)]
 pub async fn connect_component_to_frame(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -223,6 +235,7 @@ pub async fn connect_component_to_frame(
     Json(request): Json<CreateFrameConnectionRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
@@ -3,43 +3,71 @@ use axum::{response::IntoResponse, Json};
 use dal::edge::EdgeKind;
 use dal::{
     job::definition::DependentValuesUpdate, node::NodeId, socket::SocketId, AttributeReadContext,
-    AttributeValue, ChangeSet, Connection, ExternalProvider, Node, Socket, StandardModel,
-    Visibility, WsEvent,
+    AttributeValue, ChangeSet, Connection, ExternalProvider, IdempotencyRecord, Node, Socket,
+    StandardModel, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use super::{DiagramError, DiagramResult};
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use super::{DiagramError, DiagramResult, ForcedChangeSetResponse};
+use crate::server::extract::{AccessBuilder, HandlerContext, IdempotencyKey, PosthogClient};
 use crate::server::tracking::track;
 
-#[derive(Deserialize, Serialize, Debug)]
+const IDEMPOTENCY_ENDPOINT: &str = "diagram/create_connection";
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateConnectionRequest {
+    #[schema(value_type = String)]
     pub from_node_id: NodeId,
+    #[schema(value_type = String)]
     pub from_socket_id: SocketId,
+    #[schema(value_type = String)]
     pub to_node_id: NodeId,
+    #[schema(value_type = String)]
     pub to_socket_id: SocketId,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateConnectionResponse {
+    #[schema(value_type = Object)]
     pub connection: Connection,
 }
 
 /// Create a [`Connection`](dal::Connection) with a _to_ [`Socket`](dal::Socket) and
 /// [`Node`](dal::Node) and a _from_ [`Socket`](dal::Socket) and [`Node`](dal::Node).
 /// Creating change set if on head
+#[utoipa::path(
+    post,
+    path = "/api/diagram/create_connection",
+    request_body = CreateConnectionRequest,
+    responses((status = 200, body = CreateConnectionResponse)),
+    tag = "diagram"
+)]
 pub async fn create_connection(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    IdempotencyKey(idempotency_key): IdempotencyKey,
     Json(request): Json<CreateConnectionRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
+
+    if let Some(idempotency_key) = &idempotency_key {
+        if let Some(response) = IdempotencyRecord::find_response::<
+            ForcedChangeSetResponse<CreateConnectionResponse>,
+        >(&ctx, idempotency_key, IDEMPOTENCY_ENDPOINT)
+        .await?
+        {
+            return response.build();
+        }
+    }
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
@@ -146,15 +174,26 @@ pub async fn create_connection(
         }),
     );
 
+    let response = ForcedChangeSetResponse {
+        force_changeset_pk,
+        body: CreateConnectionResponse { connection },
+    };
+    if let Some(idempotency_key) = &idempotency_key {
+        if let Some(original) = IdempotencyRecord::store_response(
+            &ctx,
+            idempotency_key,
+            IDEMPOTENCY_ENDPOINT,
+            &response,
+        )
+        .await?
+        {
+            // A concurrent retry of this request finished first, so ours is discarded
+            ctx.rollback().await?;
+            return original.build();
+        }
+    }
+
     ctx.commit().await?;
 
-    let mut response = axum::response::Response::builder();
-    if let Some(force_changeset_pk) = force_changeset_pk {
-        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
-    }
-    Ok(
-        response.body(serde_json::to_string(&CreateConnectionResponse {
-            connection,
-        })?)?,
-    )
+    response.build()
 }
@@ -1,46 +1,78 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use utoipa::ToSchema;
 
 use dal::edge::EdgeKind;
 use dal::node::NodeId;
 use dal::socket::SocketEdgeKind;
 use dal::{
-    generate_name, ChangeSet, Component, ComponentId, Connection, Node, Schema, SchemaId, Socket,
-    StandardModel, Visibility, WsEvent,
+    generate_name, ChangeSet, Component, ComponentId, Connection, DalContext, IdempotencyRecord,
+    Node, Schema, SchemaId, Socket, StandardModel, Visibility, WsEvent,
 };
 
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{AccessBuilder, HandlerContext, IdempotencyKey, PosthogClient};
 use crate::server::tracking::track;
 use crate::service::diagram::connect_component_to_frame::connect_component_sockets_to_frame;
-use crate::service::diagram::{DiagramError, DiagramResult};
+use crate::service::diagram::{DiagramError, DiagramResult, ForcedChangeSetResponse};
 
-#[derive(Deserialize, Serialize, Debug)]
+const IDEMPOTENCY_ENDPOINT: &str = "diagram/create_node";
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateNodeRequest {
+    #[schema(value_type = String)]
     pub schema_id: SchemaId,
+    #[schema(value_type = Option<String>)]
     pub parent_id: Option<NodeId>,
     pub x: String,
     pub y: String,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateNodeResponse {
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    #[schema(value_type = String)]
     pub node_id: NodeId,
+    /// Set when the node was requested in a frame but could not be attached to it, in which case
+    /// it was created outside of the frame.
+    #[serde(default)]
+    pub frame_attach_failed: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/diagram/create_node",
+    request_body = CreateNodeRequest,
+    responses((status = 200, body = CreateNodeResponse)),
+    tag = "diagram"
+)]
 pub async fn create_node(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    IdempotencyKey(idempotency_key): IdempotencyKey,
     Json(request): Json<CreateNodeRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
+
+    if let Some(idempotency_key) = &idempotency_key {
+        if let Some(response) = IdempotencyRecord::find_response::<
+            ForcedChangeSetResponse<CreateNodeResponse>,
+        >(&ctx, idempotency_key, IDEMPOTENCY_ENDPOINT)
+        .await?
+        {
+            return response.build();
+        }
+    }
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
@@ -58,7 +90,7 @@ pub async fn create_node(
             .await?;
     };
 
-    let name = generate_name();
+    let name = generate_name(&ctx);
     let schema = Schema::get_by_id(&ctx, &request.schema_id)
         .await?
         .ok_or(DiagramError::SchemaNotFound)?;
@@ -78,29 +110,26 @@ pub async fn create_node(
     )
     .await?;
 
-    if let Some(frame_id) = request.parent_id {
-        let component_socket = Socket::find_frame_socket_for_node(
-            &ctx,
-            *node.id(),
-            SocketEdgeKind::ConfigurationOutput,
-        )
-        .await?;
-        let frame_socket =
-            Socket::find_frame_socket_for_node(&ctx, frame_id, SocketEdgeKind::ConfigurationInput)
-                .await?;
-
-        let _connection = Connection::new(
-            &ctx,
-            *node.id(),
-            *component_socket.id(),
-            frame_id,
-            *frame_socket.id(),
-            EdgeKind::Symbolic,
-        )
-        .await?;
-
-        connect_component_sockets_to_frame(&ctx, frame_id, *node.id()).await?;
+    // Failing to attach the node to its frame only discards the attachment, leaving the node
+    // created outside of the frame, where the user can drop it into the frame again. The response
+    // tells the client so, instead of it finding the node elsewhere.
+    let mut frame_attach_failed = false;
+    let attached_to_frame = match request.parent_id {
+        Some(frame_id) => match ctx
+            .run_in_savepoint(|| attach_to_frame(&ctx, *node.id(), frame_id))
+            .await
+        {
+            Ok(sockets) => Some((frame_id, sockets)),
+            Err(err) => {
+                warn!(error = ?err, %frame_id, "could not attach new node to its frame");
+                frame_attach_failed = true;
+                None
+            }
+        },
+        None => None,
+    };
 
+    if let Some((frame_id, (component_socket, frame_socket))) = attached_to_frame {
         let child_comp = Node::get_by_id(&ctx, node.id())
             .await?
             .ok_or(DiagramError::NodeNotFound(*node.id()))?
@@ -162,14 +191,59 @@ pub async fn create_node(
         }),
     );
 
+    let response = ForcedChangeSetResponse {
+        force_changeset_pk,
+        body: CreateNodeResponse {
+            component_id: *component.id(),
+            node_id: *node.id(),
+            frame_attach_failed,
+        },
+    };
+    if let Some(idempotency_key) = &idempotency_key {
+        if let Some(original) = IdempotencyRecord::store_response(
+            &ctx,
+            idempotency_key,
+            IDEMPOTENCY_ENDPOINT,
+            &response,
+        )
+        .await?
+        {
+            // A concurrent retry of this request finished first, so ours is discarded
+            ctx.rollback().await?;
+            return original.build();
+        }
+    }
+
     ctx.commit().await?;
 
-    let mut response = axum::response::Response::builder();
-    if let Some(force_changeset_pk) = force_changeset_pk {
-        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
-    }
-    Ok(response.body(serde_json::to_string(&CreateNodeResponse {
-        component_id: *component.id(),
-        node_id: *node.id(),
-    })?)?)
+    response.build()
+}
+
+/// Connects the node to the frame it was created in, returning its own frame socket and the one of
+/// the frame.
+async fn attach_to_frame(
+    ctx: &DalContext,
+    node_id: NodeId,
+    frame_id: NodeId,
+) -> DiagramResult<(Socket, Socket)> {
+    let component_socket =
+        Socket::find_frame_socket_for_node(ctx, node_id, SocketEdgeKind::ConfigurationOutput)
+            .await?;
+    let frame_socket =
+        Socket::find_frame_socket_for_node(ctx, frame_id, SocketEdgeKind::ConfigurationInput)
+            .await?;
+
+    let _connection = Connection::new(
+        ctx,
+        node_id,
+        *component_socket.id(),
+        frame_id,
+        *frame_socket.id(),
+        EdgeKind::Symbolic,
+    )
+    .await?;
+
+    connect_component_sockets_to_frame(ctx, frame_id, node_id).await?;
+
+    Ok((component_socket, frame_socket))
 }
@@ -2,15 +2,18 @@ use axum::{extract::OriginalUri, http::uri::Uri};
 use axum::{response::IntoResponse, Json};
 use dal::{ChangeSet, Component, ComponentId, DalContext, StandardModel, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::{DiagramError, DiagramResult};
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteComponentRequest {
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -52,6 +55,13 @@ async fn delete_single_component(
 }
 
 /// Delete a [`Component`](dal::Component) via its componentId. Creates change-set if on head
+#[utoipa::path(
+    post,
+    path = "/api/diagram/delete_component",
+    request_body = DeleteComponentRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "diagram"
+)]
 pub async fn delete_component(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -60,6 +70,7 @@ pub async fn delete_component(
     Json(request): Json<DeleteComponentRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
@@ -88,15 +99,24 @@ pub async fn delete_component(
     Ok(response.body(axum::body::Empty::new())?)
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteComponentsRequest {
+    #[schema(value_type = Vec<String>)]
     pub component_ids: Vec<ComponentId>,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
 /// Delete a set of [`Component`](dal::Component)s via their componentId. Creates change-set if on head
+#[utoipa::path(
+    post,
+    path = "/api/diagram/delete_components",
+    request_body = DeleteComponentsRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "diagram"
+)]
 pub async fn delete_components(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -105,6 +125,7 @@ pub async fn delete_components(
     Json(request): Json<DeleteComponentsRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
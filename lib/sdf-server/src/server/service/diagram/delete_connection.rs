@@ -3,6 +3,7 @@ use axum::{response::IntoResponse, Json};
 use dal::edge::EdgeId;
 use dal::{ChangeSet, Connection, Edge, Node, Socket, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::DiagramResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
@@ -10,15 +11,24 @@ use crate::server::tracking::track;
 use crate::service::diagram::DiagramError;
 use dal::standard_model::StandardModel;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteConnectionRequest {
+    #[schema(value_type = String)]
     pub edge_id: EdgeId,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
 /// Delete a [`Connection`](dal::Connection) via its EdgeId. Creating change-set if on head.
+#[utoipa::path(
+    post,
+    path = "/api/diagram/delete_connection",
+    request_body = DeleteConnectionRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "diagram"
+)]
 pub async fn delete_connection(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -27,6 +37,7 @@ pub async fn delete_connection(
     Json(request): Json<DeleteConnectionRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
@@ -0,0 +1,69 @@
+use axum::response::Response;
+use axum::Json;
+use dal::{ChangeSet, ComponentId, Diagram, NodeId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{DiagramResult, ForcedChangeSetResponse};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteNodesRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+    pub client_request_id: Option<String>,
+    #[schema(value_type = Vec<String>)]
+    pub node_ids: Vec<NodeId>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteNodesResponse {
+    #[schema(value_type = Vec<String>)]
+    pub component_ids: Vec<ComponentId>,
+}
+
+/// Deletes the components of a selection of nodes in a single transaction, publishing a single
+/// `NodesDeleted` event. Creates a change set if on head.
+#[utoipa::path(
+    post,
+    path = "/api/diagram/nodes/delete",
+    request_body = DeleteNodesRequest,
+    responses((status = 200, body = DeleteNodesResponse)),
+    tag = "diagram"
+)]
+pub async fn delete_nodes(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<DeleteNodesRequest>,
+) -> DiagramResult<Response> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let component_ids = Diagram::delete_nodes(&ctx, &request.node_ids).await?;
+
+    ctx.commit().await?;
+
+    ForcedChangeSetResponse {
+        force_changeset_pk,
+        body: DeleteNodesResponse { component_ids },
+    }
+    .build()
+}
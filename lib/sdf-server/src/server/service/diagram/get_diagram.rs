@@ -1,11 +1,13 @@
 use axum::{extract::Query, Json};
 use dal::{Diagram, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 
 use super::DiagramResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetDiagramRequest {
     #[serde(flatten)]
@@ -14,11 +16,19 @@ pub struct GetDiagramRequest {
 
 pub type GetDiagramResponse = Diagram;
 
+#[utoipa::path(
+    get,
+    path = "/api/diagram/get_diagram",
+    params(GetDiagramRequest),
+    responses((status = 200, body = Object)),
+    tag = "diagram"
+)]
 pub async fn get_diagram(
-    HandlerContext(builder): HandlerContext,
+    HandlerContext(mut builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Query(request): Query<GetDiagramRequest>,
 ) -> DiagramResult<Json<GetDiagramResponse>> {
+    builder.set_read_only();
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let response = Diagram::assemble(&ctx).await?;
@@ -2,11 +2,12 @@ use axum::Json;
 use dal::node_menu::GenerateMenuItem;
 use dal::Visibility;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::DiagramResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetNodeAddMenuRequest {
     #[serde(flatten)]
@@ -15,6 +16,13 @@ pub struct GetNodeAddMenuRequest {
 
 pub type GetNodeAddMenuResponse = serde_json::Value;
 
+#[utoipa::path(
+    post,
+    path = "/api/diagram/get_node_add_menu",
+    request_body = GetNodeAddMenuRequest,
+    responses((status = 200, body = Object)),
+    tag = "diagram"
+)]
 pub async fn get_node_add_menu(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
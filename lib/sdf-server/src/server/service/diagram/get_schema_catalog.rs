@@ -0,0 +1,60 @@
+use axum::{extract::Query, Json};
+use dal::node::NodeId;
+use dal::node_menu::{SchemaCatalog, SchemaCatalogFilter};
+use dal::{Component, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{DiagramError, DiagramResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSchemaCatalogRequest {
+    /// Only include the schemas whose name, category or description contains every word.
+    pub search: Option<String>,
+    /// Only include the schemas which fit in the frame of this node.
+    #[param(value_type = Option<String>)]
+    pub parent_node_id: Option<NodeId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type GetSchemaCatalogResponse = SchemaCatalog;
+
+/// Returns the schemas which can be added to the diagram, grouped by category, for the node add
+/// menu.
+#[utoipa::path(
+    get,
+    path = "/api/diagram/get_schema_catalog",
+    params(GetSchemaCatalogRequest),
+    responses((status = 200, body = Object)),
+    tag = "diagram"
+)]
+pub async fn get_schema_catalog(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetSchemaCatalogRequest>,
+) -> DiagramResult<Json<GetSchemaCatalogResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let parent_component_id = match request.parent_node_id {
+        Some(parent_node_id) => Some(
+            *Component::find_for_node(&ctx, parent_node_id)
+                .await?
+                .ok_or(DiagramError::ParentNodeNotFound(parent_node_id))?
+                .id(),
+        ),
+        None => None,
+    };
+    let filter = SchemaCatalogFilter {
+        search: request.search,
+        parent_component_id,
+        include_ui_hidden: false,
+    };
+    let response = SchemaCatalog::assemble(&ctx, &filter).await?;
+
+    Ok(Json(response))
+}
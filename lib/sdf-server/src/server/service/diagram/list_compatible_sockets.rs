@@ -0,0 +1,43 @@
+use axum::{extract::Query, Json};
+use dal::diagram::connection::Vertex;
+use dal::{node::NodeId, socket::SocketId, Connection, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::DiagramResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCompatibleSocketsRequest {
+    #[param(value_type = String)]
+    pub node_id: NodeId,
+    #[param(value_type = String)]
+    pub socket_id: SocketId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type ListCompatibleSocketsResponse = Vec<Vertex>;
+
+#[utoipa::path(
+    get,
+    path = "/api/diagram/list_compatible_sockets",
+    params(ListCompatibleSocketsRequest),
+    responses((status = 200, body = Vec<Object>)),
+    tag = "diagram"
+)]
+pub async fn list_compatible_sockets(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListCompatibleSocketsRequest>,
+) -> DiagramResult<Json<ListCompatibleSocketsResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let response =
+        Connection::list_compatible_sockets(&ctx, request.node_id, request.socket_id).await?;
+
+    Ok(Json(response))
+}
@@ -5,11 +5,13 @@ use dal::{
     StandardModel, Visibility,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::{DiagramError, DiagramResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSchemaVariantsRequest {
     #[serde(flatten)]
@@ -18,44 +20,54 @@ pub struct ListSchemaVariantsRequest {
 
 pub type ProviderMetadata = String;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputProviderView {
+    #[schema(value_type = String)]
     id: ExternalProviderId,
+    #[schema(value_type = String)]
     ty: ProviderMetadata,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputSocketView {
+    #[schema(value_type = String)]
     id: SocketId,
     name: String,
+    #[schema(value_type = String)]
     diagram_kind: DiagramKind,
     provider: OutputProviderView,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InputProviderView {
+    #[schema(value_type = String)]
     id: InternalProviderId,
+    #[schema(value_type = String)]
     ty: ProviderMetadata,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InputSocketView {
+    #[schema(value_type = String)]
     id: SocketId,
     name: String,
+    #[schema(value_type = String)]
     diagram_kind: DiagramKind,
     provider: InputProviderView,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaVariantView {
+    #[schema(value_type = String)]
     id: SchemaVariantId,
     name: String,
     schema_name: String,
+    #[schema(value_type = String)]
     schema_id: SchemaId,
     color: String,
     input_sockets: Vec<InputSocketView>,
@@ -63,6 +75,13 @@ pub struct SchemaVariantView {
 }
 pub type ListSchemaVariantsResponse = Vec<SchemaVariantView>;
 
+#[utoipa::path(
+    get,
+    path = "/api/diagram/list_schema_variants",
+    params(ListSchemaVariantsRequest),
+    responses((status = 200, body = Vec<SchemaVariantView>)),
+    tag = "diagram"
+)]
 pub async fn list_schema_variants(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
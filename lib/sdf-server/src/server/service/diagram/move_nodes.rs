@@ -0,0 +1,58 @@
+use axum::Json;
+use dal::{Diagram, NodeId, NodePosition, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{DiagramError, DiagramResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveNodesRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+    pub client_request_id: Option<String>,
+    #[schema(value_type = Vec<String>)]
+    pub node_ids: Vec<NodeId>,
+    pub delta_x: f64,
+    pub delta_y: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveNodesResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub positions: Vec<NodePosition>,
+}
+
+/// Moves a selection of nodes by the same delta in a single transaction, publishing a single
+/// `NodesMoved` event.
+#[utoipa::path(
+    post,
+    path = "/api/diagram/nodes/move",
+    request_body = MoveNodesRequest,
+    responses((status = 200, body = MoveNodesResponse)),
+    tag = "diagram"
+)]
+pub async fn move_nodes(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<MoveNodesRequest>,
+) -> DiagramResult<Json<MoveNodesResponse>> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
+
+    let request = &request;
+    let positions = ctx
+        .run_with_retries(|ctx| async move {
+            let positions =
+                Diagram::move_nodes(&ctx, &request.node_ids, request.delta_x, request.delta_y)
+                    .await?;
+            Ok::<_, DiagramError>(positions)
+        })
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(MoveNodesResponse { positions }))
+}
@@ -2,6 +2,7 @@ use axum::Json;
 use axum::{extract::OriginalUri, http::uri::Uri, response::IntoResponse};
 use dal::{ChangeSet, Component, ComponentId, DalContext, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::DiagramResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
@@ -9,10 +10,12 @@ use crate::server::tracking::track;
 use crate::service::diagram::DiagramError;
 use dal::standard_model::StandardModel;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RestoreComponentRequest {
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -59,6 +62,13 @@ async fn restore_single_component(
 }
 
 /// Restore a [`Component`](dal::Component) via its componentId. Creating change set if on head.
+#[utoipa::path(
+    post,
+    path = "/api/diagram/restore_component",
+    request_body = RestoreComponentRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "diagram"
+)]
 pub async fn restore_component(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -67,6 +77,7 @@ pub async fn restore_component(
     Json(request): Json<RestoreComponentRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
@@ -95,15 +106,24 @@ pub async fn restore_component(
     Ok(response.body(axum::body::Empty::new())?)
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RestoreComponentsRequest {
+    #[schema(value_type = Vec<String>)]
     pub component_ids: Vec<ComponentId>,
+    pub client_request_id: Option<String>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
 /// Restore a set of [`Component`](dal::Component)s via their componentId. Creating change set if on head.
+#[utoipa::path(
+    post,
+    path = "/api/diagram/restore_components",
+    request_body = RestoreComponentsRequest,
+    responses((status = 200, description = "Empty body")),
+    tag = "diagram"
+)]
 pub async fn restore_components(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -112,6 +132,7 @@ pub async fn restore_components(
     Json(request): Json<RestoreComponentsRequest>,
 ) -> DiagramResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
     let mut force_changeset_pk = None;
     if ctx.visibility().is_head() {
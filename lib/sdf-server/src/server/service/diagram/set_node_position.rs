@@ -6,12 +6,15 @@ use dal::node::NodeId;
 use dal::socket::SocketEdgeKind;
 use dal::{Node, StandardModel, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SetNodePositionRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
+    pub client_request_id: Option<String>,
+    #[schema(value_type = String)]
     pub node_id: NodeId,
     pub x: String,
     pub y: String,
@@ -19,75 +22,92 @@ pub struct SetNodePositionRequest {
     pub height: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SetNodePositionResponse {
+    #[schema(value_type = Object)]
     pub node: Node,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/diagram/set_node_position",
+    request_body = SetNodePositionRequest,
+    responses((status = 200, body = SetNodePositionResponse)),
+    tag = "diagram"
+)]
 pub async fn set_node_position(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Json(request): Json<SetNodePositionRequest>,
 ) -> DiagramResult<Json<SetNodePositionResponse>> {
     let visibility = Visibility::new_change_set(request.visibility.change_set_pk, true);
-    let ctx = builder.build(request_ctx.build(visibility)).await?;
+    let mut ctx = builder.build(request_ctx.build(visibility)).await?;
+    ctx.set_client_request_id(request.client_request_id.clone());
 
-    let mut node = Node::get_by_id(&ctx, &request.node_id)
-        .await?
-        .ok_or(DiagramError::NodeNotFound(request.node_id))?;
+    let request = &request;
+    let node = ctx
+        .run_with_retries(|ctx| async move {
+            let mut node = Node::get_by_id(&ctx, &request.node_id)
+                .await?
+                .ok_or(DiagramError::NodeNotFound(request.node_id))?;
 
-    let (width, height) = {
-        let component = dal::Component::find_for_node(&ctx, request.node_id)
-            .await?
-            .ok_or(DiagramError::ComponentNotFound)?;
+            let (width, height) = {
+                let component = dal::Component::find_for_node(&ctx, request.node_id)
+                    .await?
+                    .ok_or(DiagramError::ComponentNotFound)?;
 
-        let sockets = component
-            .schema_variant(&ctx)
-            .await?
-            .ok_or(DiagramError::SchemaVariantNotFound)?
-            .sockets(&ctx)
-            .await?;
+                let sockets = component
+                    .schema_variant(&ctx)
+                    .await?
+                    .ok_or(DiagramError::SchemaVariantNotFound)?
+                    .sockets(&ctx)
+                    .await?;
 
-        let mut size = (None, None);
+                let mut size = (None, None);
 
-        for s in sockets {
-            // If component is a frame, we set the size as either the one from the request or the previous one
-            // If we don't do it like this upsert_by_node_id will delete the size on None instead of keeping it as is
-            if s.name() == "Frame" && *s.edge_kind() == SocketEdgeKind::ConfigurationInput {
-                size = (
-                    request
-                        .width
-                        .or_else(|| node.width().map(|v| v.to_string())),
-                    request
-                        .height
-                        .or_else(|| node.height().map(|v| v.to_string())),
-                );
-                break;
-            }
-        }
+                for s in sockets {
+                    // If component is a frame, we set the size as either the one from the request or the previous one
+                    // If we don't do it like this upsert_by_node_id will delete the size on None instead of keeping it as is
+                    if s.name() == "Frame" && *s.edge_kind() == SocketEdgeKind::ConfigurationInput {
+                        size = (
+                            request
+                                .width
+                                .clone()
+                                .or_else(|| node.width().map(|v| v.to_string())),
+                            request
+                                .height
+                                .clone()
+                                .or_else(|| node.height().map(|v| v.to_string())),
+                        );
+                        break;
+                    }
+                }
 
-        size
-    };
+                size
+            };
 
-    {
-        if node.visibility().deleted_at.is_some() {
-            node.set_geometry(&ctx, &request.x, &request.y, width, height)
-                .await?;
-        } else {
-            let ctx_without_deleted = &ctx.clone_with_new_visibility(Visibility::new_change_set(
-                ctx.visibility().change_set_pk,
-                false,
-            ));
+            {
+                if node.visibility().deleted_at.is_some() {
+                    node.set_geometry(&ctx, &request.x, &request.y, width, height)
+                        .await?;
+                } else {
+                    let ctx_without_deleted = &ctx.clone_with_new_visibility(
+                        Visibility::new_change_set(ctx.visibility().change_set_pk, false),
+                    );
+
+                    node.set_geometry(ctx_without_deleted, &request.x, &request.y, width, height)
+                        .await?;
+                };
+            }
 
-            node.set_geometry(ctx_without_deleted, &request.x, &request.y, width, height)
+            WsEvent::change_set_written(&ctx)
+                .await?
+                .publish_on_commit(&ctx)
                 .await?;
-        };
-    }
 
-    WsEvent::change_set_written(&ctx)
-        .await?
-        .publish_on_commit(&ctx)
+            Ok::<_, DiagramError>(node)
+        })
         .await?;
 
     ctx.commit().await?;
@@ -0,0 +1,53 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{FeatureFlagError as DalFeatureFlagError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod list_feature_flags;
+pub mod set_feature_flag;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum FeatureFlagError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    FeatureFlag(#[from] DalFeatureFlagError),
+}
+
+pub type FeatureFlagResult<T> = std::result::Result<T, FeatureFlagError>;
+
+impl From<FeatureFlagError> for ApiError {
+    fn from(err: FeatureFlagError) -> Self {
+        let code = match &err {
+            FeatureFlagError::FeatureFlag(DalFeatureFlagError::InvalidName(_)) => {
+                ApiErrorCode::Validation
+            }
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for FeatureFlagError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/list_feature_flags",
+            get(list_feature_flags::list_feature_flags),
+        )
+        .route(
+            "/set_feature_flag",
+            post(set_feature_flag::set_feature_flag),
+        )
+}
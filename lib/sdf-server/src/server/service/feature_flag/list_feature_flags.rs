@@ -0,0 +1,32 @@
+use axum::Json;
+use dal::FeatureFlag;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::FeatureFlagResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFeatureFlagsResponse {
+    /// The global toggles, followed by the toggles of the workspace.
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<FeatureFlag>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/feature_flag/list_feature_flags",
+    responses((status = 200, body = ListFeatureFlagsResponse)),
+    tag = "feature_flag"
+)]
+pub async fn list_feature_flags(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> FeatureFlagResult<Json<ListFeatureFlagsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = FeatureFlag::list(&ctx).await?;
+
+    Ok(Json(ListFeatureFlagsResponse { list }))
+}
@@ -0,0 +1,56 @@
+use axum::Json;
+use dal::{FeatureFlag, FeatureFlagError as DalFeatureFlagError};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::FeatureFlagResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeatureFlagRequest {
+    pub name: String,
+    /// Toggles the flag for the workspace, or removes its toggle if `None`, so that the global
+    /// toggle applies again.
+    pub enabled: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeatureFlagResponse {
+    /// Whether the flag is now enabled for the workspace.
+    pub enabled: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/feature_flag/set_feature_flag",
+    request_body = SetFeatureFlagRequest,
+    responses((status = 200, body = SetFeatureFlagResponse)),
+    tag = "feature_flag"
+)]
+pub async fn set_feature_flag(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<SetFeatureFlagRequest>,
+) -> FeatureFlagResult<Json<SetFeatureFlagResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .ok_or(DalFeatureFlagError::NoWorkspaceInTenancy)?;
+    match request.enabled {
+        Some(enabled) => {
+            FeatureFlag::set(&ctx, &request.name, Some(workspace_pk), enabled).await?;
+        }
+        None => {
+            FeatureFlag::unset(&ctx, &request.name, Some(workspace_pk)).await?;
+        }
+    }
+    let enabled = ctx.feature_enabled(&request.name).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetFeatureFlagResponse { enabled }))
+}
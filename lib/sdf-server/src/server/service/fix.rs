@@ -9,15 +9,20 @@ use thiserror::Error;
 use dal::fix::FixError as DalFixError;
 use dal::schema::SchemaError as DalSchemaError;
 use dal::{
-    ComponentError, ComponentId, FixResolverError, FuncBindingReturnValueError, StandardModelError,
-    TransactionsError, UserError, UserPk,
+    ComponentError, ComponentId, FixPlanError, FixPlanPk, FixResolverError,
+    FuncBindingReturnValueError, StandardModelError, TransactionsError, UserError, UserPk,
 };
 
 use crate::server::state::AppState;
 
 pub mod confirmations;
+pub mod get_plan;
 pub mod list;
+pub mod pause_plan;
+pub mod preview_plan;
+pub mod resume_plan;
 pub mod run;
+pub mod run_plan;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -31,6 +36,10 @@ pub enum FixError {
     #[error(transparent)]
     DalSchema(#[from] DalSchemaError),
     #[error(transparent)]
+    FixPlan(#[from] FixPlanError),
+    #[error("fix plan {0} not found")]
+    FixPlanNotFound(FixPlanPk),
+    #[error(transparent)]
     FixResolver(#[from] FixResolverError),
     #[error(transparent)]
     FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
@@ -69,4 +78,9 @@ pub fn routes() -> Router<AppState> {
         .route("/confirmations", get(confirmations::confirmations))
         .route("/list", get(list::list))
         .route("/run", post(run::run))
+        .route("/preview_plan", post(preview_plan::preview_plan))
+        .route("/run_plan", post(run_plan::run_plan))
+        .route("/get_plan", get(get_plan::get_plan))
+        .route("/pause_plan", post(pause_plan::pause_plan))
+        .route("/resume_plan", post(resume_plan::resume_plan))
 }
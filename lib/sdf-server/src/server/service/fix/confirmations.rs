@@ -4,25 +4,36 @@ use dal::component::confirmation::view::ConfirmationView as DalConfirmationView;
 use dal::component::confirmation::view::RecommendationView as DalRecommendationView;
 use dal::{Component, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::FixResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfirmationsRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfirmationsResponse {
+    #[schema(value_type = Vec<Object>)]
     pub confirmations: Vec<DalConfirmationView>,
+    #[schema(value_type = Vec<Object>)]
     pub recommendations: Vec<DalRecommendationView>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/fix/confirmations",
+    params(ConfirmationsRequest),
+    responses((status = 200, body = ConfirmationsResponse)),
+    tag = "fix"
+)]
 pub async fn confirmations(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -0,0 +1,45 @@
+use axum::{extract::Query, Json};
+use dal::{FixPlan, FixPlanPk, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPlanRequest {
+    #[param(value_type = String)]
+    pub plan_pk: FixPlanPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPlanResponse {
+    #[schema(value_type = Object)]
+    pub plan: FixPlan,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/fix/get_plan",
+    params(GetPlanRequest),
+    responses((status = 200, body = GetPlanResponse)),
+    tag = "fix"
+)]
+pub async fn get_plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetPlanRequest>,
+) -> FixResult<Json<GetPlanResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let plan = FixPlan::get_by_pk(&ctx, request.plan_pk)
+        .await?
+        .ok_or(FixError::FixPlanNotFound(request.plan_pk))?;
+
+    Ok(Json(GetPlanResponse { plan }))
+}
@@ -4,23 +4,28 @@ use dal::fix::FixHistoryView;
 use dal::{FixBatch, FixBatchId, FixCompletionStatus};
 use dal::{StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::FixResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListFixesRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchHistoryView {
+    #[schema(value_type = String)]
     pub id: FixBatchId,
+    #[schema(value_type = Option<String>)]
     pub status: Option<FixCompletionStatus>,
     author: String,
+    #[schema(value_type = Vec<Object>)]
     fixes: Vec<FixHistoryView>,
     started_at: Option<String>,
     finished_at: Option<String>,
@@ -28,6 +33,13 @@ pub struct BatchHistoryView {
 
 pub type ListFixesResponse = Vec<BatchHistoryView>;
 
+#[utoipa::path(
+    get,
+    path = "/api/fix/list",
+    params(ListFixesRequest),
+    responses((status = 200, body = Vec<BatchHistoryView>)),
+    tag = "fix"
+)]
 pub async fn list(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
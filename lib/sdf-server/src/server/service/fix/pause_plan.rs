@@ -0,0 +1,47 @@
+use axum::Json;
+use dal::{FixPlan, FixPlanPk, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PausePlanRequest {
+    #[schema(value_type = String)]
+    pub plan_pk: FixPlanPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PausePlanResponse {
+    #[schema(value_type = Object)]
+    pub plan: FixPlan,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/fix/pause_plan",
+    request_body = PausePlanRequest,
+    responses((status = 200, body = PausePlanResponse)),
+    tag = "fix"
+)]
+pub async fn pause_plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<PausePlanRequest>,
+) -> FixResult<Json<PausePlanResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut plan = FixPlan::get_by_pk(&ctx, request.plan_pk)
+        .await?
+        .ok_or(FixError::FixPlanNotFound(request.plan_pk))?;
+    plan.pause(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(PausePlanResponse { plan }))
+}
@@ -0,0 +1,59 @@
+use axum::Json;
+use dal::{DalContext, FixPlan, FixPlanItem, FixPlanPhase, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::run::FixRunRequest;
+use super::FixResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewPlanRequest {
+    pub list: Vec<FixRunRequest>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewPlanResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub phases: Vec<FixPlanPhase>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/fix/preview_plan",
+    request_body = PreviewPlanRequest,
+    responses((status = 200, body = PreviewPlanResponse)),
+    tag = "fix"
+)]
+pub async fn preview_plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<PreviewPlanRequest>,
+) -> FixResult<Json<PreviewPlanResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let items = plan_items(&ctx, request.list).await?;
+    let phases = FixPlan::preview(&ctx, items).await?;
+
+    Ok(Json(PreviewPlanResponse { phases }))
+}
+
+pub async fn plan_items(ctx: &DalContext, list: Vec<FixRunRequest>) -> FixResult<Vec<FixPlanItem>> {
+    let mut items = Vec::with_capacity(list.len());
+    for fix_run_request in list {
+        items.push(
+            FixPlanItem::new(
+                ctx,
+                fix_run_request.attribute_value_id,
+                fix_run_request.component_id,
+                fix_run_request.action_prototype_id,
+            )
+            .await?,
+        );
+    }
+    Ok(items)
+}
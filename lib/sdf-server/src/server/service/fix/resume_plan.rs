@@ -0,0 +1,47 @@
+use axum::Json;
+use dal::{FixPlan, FixPlanPk, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumePlanRequest {
+    #[schema(value_type = String)]
+    pub plan_pk: FixPlanPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumePlanResponse {
+    #[schema(value_type = Object)]
+    pub plan: FixPlan,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/fix/resume_plan",
+    request_body = ResumePlanRequest,
+    responses((status = 200, body = ResumePlanResponse)),
+    tag = "fix"
+)]
+pub async fn resume_plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<ResumePlanRequest>,
+) -> FixResult<Json<ResumePlanResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut plan = FixPlan::get_by_pk(&ctx, request.plan_pk)
+        .await?
+        .ok_or(FixError::FixPlanNotFound(request.plan_pk))?;
+    plan.resume(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ResumePlanResponse { plan }))
+}
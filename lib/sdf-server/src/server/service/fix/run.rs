@@ -1,6 +1,7 @@
 use axum::extract::OriginalUri;
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::{FixError, FixResult};
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
@@ -11,15 +12,18 @@ use dal::{
     StandardModel, User, Visibility,
 };
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FixRunRequest {
+    #[schema(value_type = String)]
     pub attribute_value_id: AttributeValueId,
+    #[schema(value_type = String)]
     pub component_id: ComponentId,
+    #[schema(value_type = String)]
     pub action_prototype_id: ActionPrototypeId,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FixesRunRequest {
     pub list: Vec<FixRunRequest>,
@@ -27,12 +31,20 @@ pub struct FixesRunRequest {
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FixesRunResponse {
+    #[schema(value_type = String)]
     pub id: FixBatchId,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/fix/run",
+    request_body = FixesRunRequest,
+    responses((status = 200, body = FixesRunResponse)),
+    tag = "fix"
+)]
 pub async fn run(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -43,9 +55,11 @@ pub async fn run(
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(FixError::InvalidUser(*user_pk))?,
+        HistoryActor::ApiToken { user_pk, .. } | HistoryActor::User(user_pk) => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(FixError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => return Err(FixError::InvalidUserSystemInit),
     };
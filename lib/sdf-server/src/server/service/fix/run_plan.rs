@@ -0,0 +1,71 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{FixPlan, HistoryActor, User, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::preview_plan::plan_items;
+use super::run::FixRunRequest;
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPlanRequest {
+    pub list: Vec<FixRunRequest>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPlanResponse {
+    #[schema(value_type = Object)]
+    pub plan: FixPlan,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/fix/run_plan",
+    request_body = RunPlanRequest,
+    responses((status = 200, body = RunPlanResponse)),
+    tag = "fix"
+)]
+pub async fn run_plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RunPlanRequest>,
+) -> FixResult<Json<RunPlanResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let user = match ctx.history_actor() {
+        HistoryActor::ApiToken { user_pk, .. } | HistoryActor::User(user_pk) => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(FixError::InvalidUser(*user_pk))?
+        }
+
+        HistoryActor::SystemInit => return Err(FixError::InvalidUserSystemInit),
+    };
+    let items = plan_items(&ctx, request.list).await?;
+    let mut plan = FixPlan::new(&ctx, user.email(), items).await?;
+    plan.start(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "run_fix_plan",
+        serde_json::json!({
+            "fix_plan_pk": plan.pk(),
+            "number_of_phases": plan.phases().len(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(RunPlanResponse { plan }))
+}
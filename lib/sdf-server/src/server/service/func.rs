@@ -28,6 +28,7 @@ use dal::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
+use utoipa::ToSchema;
 
 pub mod create_func;
 pub mod get_func;
@@ -182,7 +183,7 @@ impl_default_error_into_response!(FuncError);
 // Variants don't map 1:1 onto FuncBackendKind, since some JsAttribute functions
 // are a special case (Qualification, CodeGeneration etc)
 #[remain::sorted]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, ToSchema)]
 pub enum FuncVariant {
     Action,
     Attribute,
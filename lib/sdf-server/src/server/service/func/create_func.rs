@@ -12,6 +12,7 @@ use dal::{
     Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 #[remain::sorted]
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -52,19 +53,21 @@ pub enum CreateFuncOptions {
     },
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFuncRequest {
     variant: FuncVariant,
     name: Option<String>,
+    #[schema(value_type = Option<Object>)]
     options: Option<CreateFuncOptions>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFuncResponse {
+    #[schema(value_type = String)]
     pub id: FuncId,
     pub handler: Option<String>,
     pub variant: FuncVariant,
@@ -93,7 +96,7 @@ async fn create_func_stub(
     code: &str,
     handler: &str,
 ) -> FuncResult<Func> {
-    let name = name.unwrap_or(generate_name());
+    let name = name.unwrap_or_else(|| generate_name(ctx));
     if Func::find_by_name(ctx, &name).await?.is_some() {
         return Err(FuncError::FuncNameExists(name));
     }
@@ -324,6 +327,13 @@ async fn create_attribute_func(
     Ok(func)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/func/create_func",
+    request_body = CreateFuncRequest,
+    responses((status = 200, body = CreateFuncResponse)),
+    tag = "func"
+)]
 pub async fn create_func(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
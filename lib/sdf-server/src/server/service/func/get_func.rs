@@ -4,37 +4,48 @@ use axum::{extract::Query, Json};
 use dal::func::execution::{FuncExecution, FuncExecutionState};
 use dal::{Func, FuncId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use veritech_client::{FunctionResultFailure, OutputStream};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetLatestFuncExecutionRequest {
+    #[param(value_type = String)]
     pub id: FuncId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetLatestFuncExecutionResponse {
+    #[schema(value_type = String)]
     pub id: FuncId,
+    #[schema(value_type = String)]
     pub state: FuncExecutionState,
+    #[schema(value_type = Option<Object>)]
     pub value: Option<serde_json::Value>,
+    #[schema(value_type = Option<Vec<Object>>)]
     pub output_stream: Option<Vec<OutputStream>>,
+    #[schema(value_type = Option<Object>)]
     pub function_failure: Option<FunctionResultFailure>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetFuncRequest {
+    #[param(value_type = String)]
     pub id: FuncId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetFuncResponse {
+    #[schema(value_type = String)]
     pub id: FuncId,
     pub handler: Option<String>,
     pub variant: FuncVariant,
@@ -45,9 +56,17 @@ pub struct GetFuncResponse {
     pub types: String,
     pub is_builtin: bool,
     pub is_revertible: bool,
+    #[schema(value_type = Option<Object>)]
     pub associations: Option<FuncAssociations>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/func/get_func",
+    params(GetFuncRequest),
+    responses((status = 200, body = GetFuncResponse)),
+    tag = "func"
+)]
 pub async fn get_func(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -62,6 +81,13 @@ pub async fn get_func(
     Ok(Json(super::get_func_view(&ctx, &func).await?))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/func/get_func_last_execution",
+    params(GetLatestFuncExecutionRequest),
+    responses((status = 200, body = GetLatestFuncExecutionResponse)),
+    tag = "func"
+)]
 pub async fn get_latest_func_execution(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -3,17 +3,20 @@ use crate::server::extract::{AccessBuilder, HandlerContext};
 use axum::{extract::Query, Json};
 use dal::{Func, FuncBackendKind, FuncId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListFuncsRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListedFuncView {
+    #[schema(value_type = String)]
     pub id: FuncId,
     pub handler: Option<String>,
     pub variant: FuncVariant,
@@ -22,12 +25,19 @@ pub struct ListedFuncView {
     pub is_builtin: bool,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListFuncsResponse {
     pub funcs: Vec<ListedFuncView>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/func/list_funcs",
+    params(ListFuncsRequest),
+    responses((status = 200, body = ListFuncsResponse)),
+    tag = "func"
+)]
 pub async fn list_funcs(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
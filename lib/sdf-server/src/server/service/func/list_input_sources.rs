@@ -7,43 +7,54 @@ use dal::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InputSourceSocket {
+    #[schema(value_type = String)]
     pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = String)]
     pub internal_provider_id: InternalProviderId,
     pub name: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputSocket {
+    #[schema(value_type = String)]
     pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = String)]
     pub external_provider_id: ExternalProviderId,
     pub name: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InputSourceProp {
+    #[schema(value_type = String)]
     pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = Option<String>)]
     pub internal_provider_id: Option<InternalProviderId>,
+    #[schema(value_type = String)]
     pub prop_id: PropId,
+    #[schema(value_type = String)]
     pub kind: PropKind,
     pub name: String,
     pub path: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListInputSourcesRequest {
+    #[param(value_type = Option<String>)]
     schema_variant_id: Option<SchemaVariantId>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListInputSourcesResponse {
     pub input_sockets: Vec<InputSourceSocket>,
@@ -86,6 +97,13 @@ fn prop_tree_to_list(prop_tree: &PropTree) -> Vec<InputSourceProp> {
     prop_sources
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/func/list_input_sources",
+    params(ListInputSourcesRequest),
+    responses((status = 200, body = ListInputSourcesResponse)),
+    tag = "func"
+)]
 pub async fn list_input_sources(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
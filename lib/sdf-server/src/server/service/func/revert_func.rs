@@ -2,24 +2,33 @@ use axum::Json;
 use dal::func::argument::FuncArgument;
 use dal::{AttributePrototype, Func, FuncBackendKind, FuncId, StandardModel, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::{FuncError, FuncResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RevertFuncRequest {
+    #[schema(value_type = String)]
     pub id: FuncId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RevertFuncResponse {
     pub success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/func/revert_func",
+    request_body = RevertFuncRequest,
+    responses((status = 200, body = RevertFuncResponse)),
+    tag = "func"
+)]
 pub async fn revert_func(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
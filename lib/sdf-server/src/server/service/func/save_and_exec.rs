@@ -160,6 +160,13 @@ async fn run_actions(ctx: &DalContext, func: &Func) -> FuncResult<()> {
     Ok(())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/func/save_and_exec",
+    request_body = SaveFuncRequest,
+    responses((status = 200, body = SaveFuncResponse)),
+    tag = "func"
+)]
 pub async fn save_and_exec(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
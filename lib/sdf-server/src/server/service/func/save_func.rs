@@ -2,6 +2,7 @@ use axum::extract::OriginalUri;
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use utoipa::ToSchema;
 
 use super::{
     AttributePrototypeArgumentView, AttributePrototypeView, FuncArgumentView, FuncAssociations,
@@ -22,23 +23,26 @@ use dal::{
 };
 use dal::{FuncBackendResponseType, FuncDescription, PropKind, SchemaVariant, ValidationPrototype};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveFuncRequest {
+    #[schema(value_type = String)]
     pub id: FuncId,
     pub handler: Option<String>,
     pub display_name: Option<String>,
     pub name: String,
     pub description: Option<String>,
     pub code: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub associations: Option<FuncAssociations>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveFuncResponse {
+    #[schema(value_type = Option<Object>)]
     pub associations: Option<FuncAssociations>,
     pub success: bool,
     pub is_revertible: bool,
@@ -737,6 +741,13 @@ pub async fn do_save_func(
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/func/save_func",
+    request_body = SaveFuncRequest,
+    responses((status = 200, body = SaveFuncResponse)),
+    tag = "func"
+)]
 pub async fn save_func<'a>(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -0,0 +1,61 @@
+//! A read-only GraphQL facade over the dal read models, for clients that want to walk components
+//! and their relations in a single request rather than calling one route per model.
+//!
+//! Queries run against the change set given by the request's visibility, like the other read
+//! routes. Relations are resolved through per-request [`DataLoaders`](async_graphql::dataloader)
+//! so that a query over many components does not turn into a query per component.
+
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use dal::error_category::categorize;
+use dal::TransactionsError;
+use thiserror::Error;
+
+use crate::server::api_error::ApiError;
+use crate::server::state::AppState;
+
+pub mod execute;
+pub mod loader;
+pub mod schema;
+
+/// How deeply a query may nest relations, e.g. component, edges, head, edges...
+const MAX_DEPTH: usize = 8;
+/// How many fields a query may resolve, counting the fields of every nested object.
+const MAX_COMPLEXITY: usize = 512;
+
+pub type GraphqlSchema = Schema<schema::QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Builds the schema served by the routes. It is built once and shared by every request.
+pub fn schema() -> GraphqlSchema {
+    Schema::build(schema::QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum GraphqlError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+}
+
+pub type GraphqlResult<T> = std::result::Result<T, GraphqlError>;
+
+impl From<GraphqlError> for ApiError {
+    fn from(err: GraphqlError) -> Self {
+        ApiError::new(categorize(&err).into(), err.to_string())
+    }
+}
+
+impl IntoResponse for GraphqlError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", post(execute::execute))
+}
@@ -0,0 +1,33 @@
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::extract::{Query, State};
+use dal::Visibility;
+use serde::{Deserialize, Serialize};
+
+use super::loader::Loaders;
+use super::{GraphqlResult, GraphqlSchema};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn execute(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    State(schema): State<GraphqlSchema>,
+    Query(request): Query<ExecuteRequest>,
+    graphql_request: GraphQLRequest,
+) -> GraphqlResult<GraphQLResponse> {
+    builder.set_read_only();
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let loaders = Loaders::new(&ctx);
+    let response = schema
+        .execute(graphql_request.into_inner().data(loaders).data(ctx))
+        .await;
+
+    Ok(response.into())
+}
@@ -0,0 +1,228 @@
+//! The [`Loaders`](Loader) batching the lookups made while resolving a query, so that resolving
+//! a field of many objects costs one query rather than one query per object.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_trait::async_trait;
+use dal::func::binding_return_value::FuncBindingReturnValueId;
+use dal::{
+    AttributeValue, AttributeValueError, Component, ComponentError, ComponentId, DalContext, Edge,
+    EdgeError, FuncBindingReturnValue, FuncBindingReturnValueError, Prop, PropError,
+    QualificationView, SchemaVariantId, StandardModel,
+};
+
+/// The loaders of a single request, all reading through the request's [`DalContext`].
+pub struct Loaders {
+    pub components: DataLoader<ComponentLoader>,
+    pub component_names: DataLoader<ComponentNameLoader>,
+    pub schema_variant_ids: DataLoader<SchemaVariantIdLoader>,
+    pub props: DataLoader<PropLoader>,
+    pub attribute_values: DataLoader<AttributeValueLoader>,
+    pub attribute_value_values: DataLoader<AttributeValueValueLoader>,
+    pub edges: DataLoader<EdgeLoader>,
+    pub qualifications: DataLoader<QualificationLoader>,
+}
+
+impl Loaders {
+    pub fn new(ctx: &DalContext) -> Self {
+        Self {
+            components: DataLoader::new(ComponentLoader(ctx.clone()), tokio::spawn),
+            component_names: DataLoader::new(ComponentNameLoader(ctx.clone()), tokio::spawn),
+            schema_variant_ids: DataLoader::new(SchemaVariantIdLoader(ctx.clone()), tokio::spawn),
+            props: DataLoader::new(PropLoader(ctx.clone()), tokio::spawn),
+            attribute_values: DataLoader::new(AttributeValueLoader(ctx.clone()), tokio::spawn),
+            attribute_value_values: DataLoader::new(
+                AttributeValueValueLoader(ctx.clone()),
+                tokio::spawn,
+            ),
+            edges: DataLoader::new(EdgeLoader(ctx.clone()), tokio::spawn),
+            qualifications: DataLoader::new(QualificationLoader(ctx.clone()), tokio::spawn),
+        }
+    }
+}
+
+/// Loads [`Components`](Component) by id.
+pub struct ComponentLoader(DalContext);
+
+#[async_trait]
+impl Loader<ComponentId> for ComponentLoader {
+    type Value = Component;
+    type Error = Arc<ComponentError>;
+
+    async fn load(
+        &self,
+        keys: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Self::Value>, Self::Error> {
+        let components = Component::list_by_ids(&self.0, keys).await?;
+        Ok(components
+            .into_iter()
+            .map(|component| (*component.id(), component))
+            .collect())
+    }
+}
+
+/// Loads the names of [`Components`](Component). Names live in the components' attribute values
+/// and are looked up one component at a time, though each only once per request.
+pub struct ComponentNameLoader(DalContext);
+
+#[async_trait]
+impl Loader<ComponentId> for ComponentNameLoader {
+    type Value = String;
+    type Error = Arc<ComponentError>;
+
+    async fn load(
+        &self,
+        keys: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Self::Value>, Self::Error> {
+        let mut names = HashMap::with_capacity(keys.len());
+        for component_id in keys {
+            names.insert(
+                *component_id,
+                Component::find_name(&self.0, *component_id).await?,
+            );
+        }
+        Ok(names)
+    }
+}
+
+/// Loads the [`SchemaVariantIds`](SchemaVariantId) of [`Components`](Component).
+pub struct SchemaVariantIdLoader(DalContext);
+
+#[async_trait]
+impl Loader<ComponentId> for SchemaVariantIdLoader {
+    type Value = SchemaVariantId;
+    type Error = Arc<ComponentError>;
+
+    async fn load(
+        &self,
+        keys: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Self::Value>, Self::Error> {
+        Ok(Component::schema_variant_ids(&self.0, keys).await?)
+    }
+}
+
+/// Loads the [`Props`](Prop) of [`SchemaVariants`](dal::SchemaVariant).
+pub struct PropLoader(DalContext);
+
+#[async_trait]
+impl Loader<SchemaVariantId> for PropLoader {
+    type Value = Vec<Prop>;
+    type Error = Arc<PropError>;
+
+    async fn load(
+        &self,
+        keys: &[SchemaVariantId],
+    ) -> Result<HashMap<SchemaVariantId, Self::Value>, Self::Error> {
+        let mut props: HashMap<SchemaVariantId, Vec<Prop>> =
+            keys.iter().map(|key| (*key, Vec::new())).collect();
+        for prop in Prop::list_for_schema_variants(&self.0, keys).await? {
+            if let Some(variant_props) = props.get_mut(&prop.schema_variant_id()) {
+                variant_props.push(prop);
+            }
+        }
+        Ok(props)
+    }
+}
+
+/// Loads the [`AttributeValues`](AttributeValue) set for [`Components`](Component).
+pub struct AttributeValueLoader(DalContext);
+
+#[async_trait]
+impl Loader<ComponentId> for AttributeValueLoader {
+    type Value = Vec<AttributeValue>;
+    type Error = Arc<AttributeValueError>;
+
+    async fn load(
+        &self,
+        keys: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Self::Value>, Self::Error> {
+        let mut attribute_values: HashMap<ComponentId, Vec<AttributeValue>> =
+            keys.iter().map(|key| (*key, Vec::new())).collect();
+        for attribute_value in AttributeValue::list_for_components(&self.0, keys).await? {
+            if let Some(component_values) =
+                attribute_values.get_mut(&attribute_value.context.component_id())
+            {
+                component_values.push(attribute_value);
+            }
+        }
+        Ok(attribute_values)
+    }
+}
+
+/// Loads the values of [`AttributeValues`](AttributeValue), by the
+/// [`FuncBindingReturnValue`] holding them.
+pub struct AttributeValueValueLoader(DalContext);
+
+#[async_trait]
+impl Loader<FuncBindingReturnValueId> for AttributeValueValueLoader {
+    type Value = Option<serde_json::Value>;
+    type Error = Arc<FuncBindingReturnValueError>;
+
+    async fn load(
+        &self,
+        keys: &[FuncBindingReturnValueId],
+    ) -> Result<HashMap<FuncBindingReturnValueId, Self::Value>, Self::Error> {
+        let mut values = HashMap::new();
+        for mut return_value in FuncBindingReturnValue::list_by_ids(&self.0, keys).await? {
+            return_value.decrypt(&self.0).await?;
+            values.insert(*return_value.id(), return_value.value().cloned());
+        }
+        Ok(values)
+    }
+}
+
+/// Loads the [`Edges`](Edge) connected to [`Components`](Component), whichever end they are on.
+pub struct EdgeLoader(DalContext);
+
+#[async_trait]
+impl Loader<ComponentId> for EdgeLoader {
+    type Value = Vec<Edge>;
+    type Error = Arc<EdgeError>;
+
+    async fn load(
+        &self,
+        keys: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Self::Value>, Self::Error> {
+        let mut edges: HashMap<ComponentId, Vec<Edge>> =
+            keys.iter().map(|key| (*key, Vec::new())).collect();
+        for edge in Edge::list_for_components(&self.0, keys).await? {
+            let head_component_id: ComponentId = edge.head_object_id().into();
+            let tail_component_id: ComponentId = edge.tail_object_id().into();
+            if let Some(component_edges) = edges.get_mut(&head_component_id) {
+                component_edges.push(edge.clone());
+            }
+            if tail_component_id != head_component_id {
+                if let Some(component_edges) = edges.get_mut(&tail_component_id) {
+                    component_edges.push(edge);
+                }
+            }
+        }
+        Ok(edges)
+    }
+}
+
+/// Loads the [`QualificationViews`](QualificationView) of [`Components`](Component).
+/// Qualifications are assembled one component at a time, though each only once per request.
+pub struct QualificationLoader(DalContext);
+
+#[async_trait]
+impl Loader<ComponentId> for QualificationLoader {
+    type Value = Vec<QualificationView>;
+    type Error = Arc<ComponentError>;
+
+    async fn load(
+        &self,
+        keys: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Self::Value>, Self::Error> {
+        let mut qualifications = HashMap::with_capacity(keys.len());
+        for component_id in keys {
+            qualifications.insert(
+                *component_id,
+                Component::list_qualifications(&self.0, *component_id).await?,
+            );
+        }
+        Ok(qualifications)
+    }
+}
@@ -0,0 +1,286 @@
+//! The objects of the GraphQL schema. Each one wraps a dal read model and resolves its relations
+//! through the request's [`Loaders`].
+
+use async_graphql::{Context, Json, Object, Result, ID};
+use chrono::{DateTime, Utc};
+use dal::{
+    AttributeValue, ChangeSet, Component, ComponentId, DalContext, Edge, Prop, QualificationView,
+    StandardModel,
+};
+
+use super::loader::Loaders;
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The open change sets of the workspace, newest first.
+    async fn change_sets(&self, ctx: &Context<'_>) -> Result<Vec<ChangeSetObject>> {
+        let dal_ctx = ctx.data::<DalContext>()?;
+        Ok(ChangeSet::list_open_objects(dal_ctx)
+            .await?
+            .into_iter()
+            .map(ChangeSetObject)
+            .collect())
+    }
+
+    /// The components visible in the requested change set.
+    async fn components(&self, ctx: &Context<'_>) -> Result<Vec<ComponentObject>> {
+        let dal_ctx = ctx.data::<DalContext>()?;
+        Ok(Component::list(dal_ctx)
+            .await?
+            .into_iter()
+            .map(ComponentObject)
+            .collect())
+    }
+
+    async fn component(&self, ctx: &Context<'_>, id: ID) -> Result<Option<ComponentObject>> {
+        let component_id: ComponentId = ulid::Ulid::from_string(&id)?.into();
+        load_component(ctx, component_id).await
+    }
+}
+
+async fn load_component(
+    ctx: &Context<'_>,
+    component_id: ComponentId,
+) -> Result<Option<ComponentObject>> {
+    let loaders = ctx.data::<Loaders>()?;
+    Ok(loaders
+        .components
+        .load_one(component_id)
+        .await?
+        .map(ComponentObject))
+}
+
+pub struct ComponentObject(Component);
+
+#[Object(name = "Component")]
+impl ComponentObject {
+    async fn id(&self) -> ID {
+        self.0.id().into()
+    }
+
+    async fn kind(&self) -> String {
+        self.0.kind().to_string()
+    }
+
+    async fn name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loaders = ctx.data::<Loaders>()?;
+        Ok(loaders.component_names.load_one(*self.0.id()).await?)
+    }
+
+    async fn needs_destroy(&self) -> bool {
+        self.0.needs_destroy()
+    }
+
+    async fn deleted(&self) -> bool {
+        self.0.visibility().deleted_at.is_some()
+    }
+
+    async fn schema_variant_id(&self, ctx: &Context<'_>) -> Result<Option<ID>> {
+        let loaders = ctx.data::<Loaders>()?;
+        Ok(loaders
+            .schema_variant_ids
+            .load_one(*self.0.id())
+            .await?
+            .map(Into::into))
+    }
+
+    /// The props of the component's schema variant.
+    async fn props(&self, ctx: &Context<'_>) -> Result<Vec<PropObject>> {
+        let loaders = ctx.data::<Loaders>()?;
+        let schema_variant_id = match loaders.schema_variant_ids.load_one(*self.0.id()).await? {
+            Some(schema_variant_id) => schema_variant_id,
+            None => return Ok(Vec::new()),
+        };
+        Ok(loaders
+            .props
+            .load_one(schema_variant_id)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(PropObject)
+            .collect())
+    }
+
+    async fn attribute_values(&self, ctx: &Context<'_>) -> Result<Vec<AttributeValueObject>> {
+        let loaders = ctx.data::<Loaders>()?;
+        Ok(loaders
+            .attribute_values
+            .load_one(*self.0.id())
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(AttributeValueObject)
+            .collect())
+    }
+
+    /// The edges the component is the head or the tail of.
+    async fn edges(&self, ctx: &Context<'_>) -> Result<Vec<EdgeObject>> {
+        let loaders = ctx.data::<Loaders>()?;
+        Ok(loaders
+            .edges
+            .load_one(*self.0.id())
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(EdgeObject)
+            .collect())
+    }
+
+    async fn qualifications(&self, ctx: &Context<'_>) -> Result<Vec<QualificationObject>> {
+        let loaders = ctx.data::<Loaders>()?;
+        Ok(loaders
+            .qualifications
+            .load_one(*self.0.id())
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(QualificationObject)
+            .collect())
+    }
+}
+
+pub struct PropObject(Prop);
+
+#[Object(name = "Prop")]
+impl PropObject {
+    async fn id(&self) -> ID {
+        self.0.id().into()
+    }
+
+    async fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn kind(&self) -> String {
+        self.0.kind().to_string()
+    }
+
+    async fn path(&self) -> String {
+        self.0.path().as_str().to_owned()
+    }
+
+    async fn hidden(&self) -> bool {
+        self.0.hidden()
+    }
+
+    async fn doc_link(&self) -> Option<&str> {
+        self.0.doc_link()
+    }
+}
+
+pub struct AttributeValueObject(AttributeValue);
+
+#[Object(name = "AttributeValue")]
+impl AttributeValueObject {
+    async fn id(&self) -> ID {
+        self.0.id().into()
+    }
+
+    async fn prop_id(&self) -> ID {
+        self.0.context.prop_id().into()
+    }
+
+    /// The key of the value within its parent map, if the parent is a map.
+    async fn key(&self) -> Option<&str> {
+        self.0.key()
+    }
+
+    async fn value(&self, ctx: &Context<'_>) -> Result<Option<Json<serde_json::Value>>> {
+        let loaders = ctx.data::<Loaders>()?;
+        Ok(loaders
+            .attribute_value_values
+            .load_one(self.0.func_binding_return_value_id())
+            .await?
+            .flatten()
+            .map(Json))
+    }
+}
+
+pub struct EdgeObject(Edge);
+
+#[Object(name = "Edge")]
+impl EdgeObject {
+    async fn id(&self) -> ID {
+        self.0.id().into()
+    }
+
+    async fn kind(&self) -> String {
+        self.0.kind().to_string()
+    }
+
+    async fn head_socket_id(&self) -> ID {
+        self.0.head_socket_id().into()
+    }
+
+    async fn tail_socket_id(&self) -> ID {
+        self.0.tail_socket_id().into()
+    }
+
+    async fn head(&self, ctx: &Context<'_>) -> Result<Option<ComponentObject>> {
+        load_component(ctx, self.0.head_object_id().into()).await
+    }
+
+    async fn tail(&self, ctx: &Context<'_>) -> Result<Option<ComponentObject>> {
+        load_component(ctx, self.0.tail_object_id().into()).await
+    }
+}
+
+pub struct ChangeSetObject(ChangeSet);
+
+#[Object(name = "ChangeSet")]
+impl ChangeSetObject {
+    async fn pk(&self) -> ID {
+        self.0.pk.into()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn note(&self) -> Option<&str> {
+        self.0.note.as_deref()
+    }
+
+    async fn status(&self) -> String {
+        self.0.status.to_string()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.timestamp.created_at
+    }
+}
+
+pub struct QualificationObject(QualificationView);
+
+#[Object(name = "Qualification")]
+impl QualificationObject {
+    async fn qualification_name(&self) -> &str {
+        &self.0.qualification_name
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn link(&self) -> Option<&str> {
+        self.0.link.as_deref()
+    }
+
+    /// The status of the last run, or `None` if the qualification has not run yet.
+    async fn status(&self) -> Option<String> {
+        self.0
+            .result
+            .as_ref()
+            .map(|result| result.status.to_string())
+    }
+
+    async fn last_checked(&self) -> Option<DateTime<Utc>> {
+        self.0.last_checked
+    }
+}
@@ -0,0 +1,107 @@
+use std::{future::Future, time::Duration};
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use hyper::StatusCode;
+use serde::Serialize;
+
+use crate::server::state::{AppState, ServicesContext};
+
+/// How long each dependency gets to answer before it is considered unavailable.
+const DEPENDENCY_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyStatus {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DependencyStatus {
+    async fn check<E: ToString>(check: impl Future<Output = Result<(), E>>) -> Self {
+        match tokio::time::timeout(DEPENDENCY_CHECK_TIMEOUT, check).await {
+            Ok(Ok(())) => Self {
+                ok: true,
+                error: None,
+            },
+            Ok(Err(err)) => Self {
+                ok: false,
+                error: Some(err.to_string()),
+            },
+            Err(_) => Self {
+                ok: false,
+                error: Some(format!(
+                    "timed out after {}s",
+                    DEPENDENCY_CHECK_TIMEOUT.as_secs()
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependencies {
+    pub pg: DependencyStatus,
+    pub nats: DependencyStatus,
+    pub veritech: DependencyStatus,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyResponse {
+    pub ok: bool,
+    pub dependencies: Dependencies,
+}
+
+/// Reports that the process is up and serving requests, without checking any dependency.
+pub async fn live() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "ok": true }))
+}
+
+/// Reports whether every dependency needed to serve requests is reachable, responding with
+/// `503 Service Unavailable` if any of them is not.
+pub async fn ready(State(services_context): State<ServicesContext>) -> Response {
+    let (pg, nats, veritech) = tokio::join!(
+        DependencyStatus::check(services_context.pg_pool().test_connection()),
+        DependencyStatus::check(
+            services_context
+                .nats_conn()
+                .flush_timeout(DEPENDENCY_CHECK_TIMEOUT)
+        ),
+        DependencyStatus::check(async {
+            services_context
+                .veritech()
+                .liveness(DEPENDENCY_CHECK_TIMEOUT)
+                .await
+                .map(|_| ())
+        }),
+    );
+
+    let ok = pg.ok && nats.ok && veritech.ok;
+    let status = if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadyResponse {
+            ok,
+            dependencies: Dependencies { pg, nats, veritech },
+        }),
+    )
+        .into_response()
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/live", get(live))
+        .route("/ready", get(ready))
+}
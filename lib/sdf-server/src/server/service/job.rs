@@ -0,0 +1,53 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{DeadLetteredJobError, DeadLetteredJobPk, TransactionsError};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod list_dead_lettered_jobs;
+pub mod replay_dead_lettered_jobs;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DeadLetteredJob(#[from] DeadLetteredJobError),
+    #[error("dead lettered job not found: {0}")]
+    DeadLetteredJobNotFound(DeadLetteredJobPk),
+}
+
+pub type JobResult<T> = std::result::Result<T, JobError>;
+
+impl From<JobError> for ApiError {
+    fn from(err: JobError) -> Self {
+        let code = match &err {
+            JobError::DeadLetteredJobNotFound(_) => ApiErrorCode::NotFound,
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for JobError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/list_dead_lettered_jobs",
+            get(list_dead_lettered_jobs::list_dead_lettered_jobs),
+        )
+        .route(
+            "/replay_dead_lettered_jobs",
+            post(replay_dead_lettered_jobs::replay_dead_lettered_jobs),
+        )
+}
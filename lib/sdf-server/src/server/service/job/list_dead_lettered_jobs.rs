@@ -0,0 +1,32 @@
+use axum::Json;
+use dal::DeadLetteredJob;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::JobResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeadLetteredJobsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<DeadLetteredJob>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/job/list_dead_lettered_jobs",
+    responses((status = 200, body = ListDeadLetteredJobsResponse)),
+    tag = "job"
+)]
+pub async fn list_dead_lettered_jobs(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> JobResult<Json<ListDeadLetteredJobsResponse>> {
+    builder.set_read_only();
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = DeadLetteredJob::list(&ctx).await?;
+
+    Ok(Json(ListDeadLetteredJobsResponse { list }))
+}
@@ -0,0 +1,49 @@
+use axum::Json;
+use dal::{DeadLetteredJob, DeadLetteredJobPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{JobError, JobResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayDeadLetteredJobsRequest {
+    #[schema(value_type = Vec<String>)]
+    pub pks: Vec<DeadLetteredJobPk>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayDeadLetteredJobsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<DeadLetteredJob>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/job/replay_dead_lettered_jobs",
+    request_body = ReplayDeadLetteredJobsRequest,
+    responses((status = 200, body = ReplayDeadLetteredJobsResponse)),
+    tag = "job"
+)]
+pub async fn replay_dead_lettered_jobs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<ReplayDeadLetteredJobsRequest>,
+) -> JobResult<Json<ReplayDeadLetteredJobsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut list = Vec::with_capacity(request.pks.len());
+    for pk in request.pks {
+        let mut dead_lettered_job = DeadLetteredJob::get_by_pk(&ctx, pk)
+            .await?
+            .ok_or(JobError::DeadLetteredJobNotFound(pk))?;
+        dead_lettered_job.replay(&ctx).await?;
+        list.push(dead_lettered_job);
+    }
+
+    ctx.commit().await?;
+
+    Ok(Json(ReplayDeadLetteredJobsResponse { list }))
+}
@@ -1,5 +1,6 @@
 use crate::server::{impl_default_error_into_response, state::AppState};
 use axum::{
+    extract::DefaultBodyLimit,
     response::Response,
     routing::{get, post},
     Json, Router,
@@ -15,15 +16,20 @@ use si_settings::{safe_canonically_join, CanonicalFileError};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tokio::fs::read_dir;
+use utoipa::ToSchema;
 
 const PKG_EXTENSION: &str = "sipkg";
 const MAX_NAME_SEARCH_ATTEMPTS: usize = 100;
 
 pub mod export_pkg;
 pub mod get_pkg;
+pub mod install_local_pkg;
 pub mod install_pkg;
+pub mod list_local_pkgs;
 pub mod list_pkgs;
 pub mod remote_module_spec;
+pub mod uninstall_pkg;
+pub mod upload_pkg;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -87,7 +93,7 @@ pub type PkgResult<T> = Result<T, PkgError>;
 
 impl_default_error_into_response!(PkgError);
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkgView {
     name: String,
@@ -179,10 +185,21 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/export_pkg", post(export_pkg::export_pkg))
         .route("/get_module_by_hash", get(get_pkg::get_module_by_hash))
+        .route(
+            "/install_local_pkg",
+            post(install_local_pkg::install_local_pkg),
+        )
         .route("/install_pkg", post(install_pkg::install_pkg))
+        .route("/list_local_pkgs", get(list_local_pkgs::list_local_pkgs))
         .route("/list_pkgs", get(list_pkgs::list_pkgs))
         .route(
             "/remote_module_spec",
             get(remote_module_spec::remote_module_spec),
         )
+        .route("/uninstall_pkg", post(uninstall_pkg::uninstall_pkg))
+        // Uploads enforce their own limit as they are streamed.
+        .route(
+            "/upload_pkg",
+            post(upload_pkg::upload_pkg).layer(DefaultBodyLimit::disable()),
+        )
 }
@@ -3,28 +3,37 @@ use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient, RawAc
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::Json;
-use dal::{HistoryActor, SchemaVariantId, User, Visibility, WsEvent};
+use dal::{SchemaVariantId, User, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportPkgRequest {
     pub name: String,
     pub version: String,
     pub description: Option<String>,
+    #[schema(value_type = Vec<String>)]
     pub schema_variants: Vec<SchemaVariantId>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportPkgResponse {
     pub success: bool,
     pub full_path: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/pkg/export_pkg",
+    request_body = ExportPkgRequest,
+    responses((status = 200, body = ExportPkgResponse)),
+    tag = "pkg"
+)]
 pub async fn export_pkg(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -52,9 +61,9 @@ pub async fn export_pkg(
         None => return Err(PkgError::ModuleIndexNotConfigured),
     };
 
-    let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk).await?,
-        _ => None,
+    let user = match ctx.history_actor().user_pk() {
+        Some(user_pk) => User::get_by_pk(&ctx, user_pk).await?,
+        None => None,
     };
 
     let (created_by_name, created_by_email) = user
@@ -3,13 +3,15 @@ use chrono::{DateTime, Utc};
 use dal::{installed_pkg::InstalledPkg, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
 use std::cmp::{Ord, PartialOrd};
+use utoipa::{IntoParams, ToSchema};
 
 use super::{pkg_open, PkgError, PkgResult};
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct PkgGetRequest {
     pub hash: String,
@@ -17,7 +19,7 @@ pub struct PkgGetRequest {
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkgFuncView {
     pub name: String,
@@ -37,7 +39,7 @@ impl PartialOrd for PkgFuncView {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkgGetResponse {
     pub name: String,
@@ -48,10 +50,18 @@ pub struct PkgGetResponse {
     pub created_by: String,
     pub schemas: Vec<String>,
     pub funcs: Vec<PkgFuncView>,
+    #[schema(value_type = Object)]
     pub spec: serde_json::Value,
     pub installed: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/pkg/get_module_by_hash",
+    params(PkgGetRequest),
+    responses((status = 200, body = PkgGetResponse)),
+    tag = "pkg"
+)]
 pub async fn get_module_by_hash(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
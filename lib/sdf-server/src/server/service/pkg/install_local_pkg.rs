@@ -0,0 +1,61 @@
+use super::{pkg_open, PkgResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{pkg::import_pkg_from_pkg, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLocalPkgRequest {
+    /// The file name of a package in the packages directory.
+    pub name: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLocalPkgResponse {
+    pub success: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pkg/install_local_pkg",
+    request_body = InstallLocalPkgRequest,
+    responses((status = 200, body = InstallLocalPkgResponse)),
+    tag = "pkg"
+)]
+pub async fn install_local_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<InstallLocalPkgRequest>,
+) -> PkgResult<Json<InstallLocalPkgResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let pkg = pkg_open(&builder, &request.name).await?;
+    import_pkg_from_pkg(&ctx, &pkg, &request.name, None).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "install_local_pkg",
+        serde_json::json!({
+                    "pkg_name": request.name,
+        }),
+    );
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit().await?;
+
+    Ok(Json(InstallLocalPkgResponse { success: true }))
+}
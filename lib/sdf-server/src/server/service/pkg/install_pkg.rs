@@ -12,21 +12,30 @@ use module_index_client::IndexClient;
 use serde::{Deserialize, Serialize};
 use si_pkg::SiPkg;
 use ulid::Ulid;
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InstallPkgRequest {
+    #[schema(value_type = String)]
     pub id: Ulid,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InstallPkgResponse {
     pub success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/pkg/install_pkg",
+    request_body = InstallPkgRequest,
+    responses((status = 200, body = InstallPkgResponse)),
+    tag = "pkg"
+)]
 pub async fn install_pkg(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
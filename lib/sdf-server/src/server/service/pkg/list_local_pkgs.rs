@@ -0,0 +1,53 @@
+use super::{get_pkgs_path, list_pkg_dir_entries, PkgResult, PKG_EXTENSION};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::{extract::Query, Json};
+use dal::{installed_pkg::InstalledPkg, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLocalPkgsRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLocalPkgsResponse {
+    pub pkgs: Vec<super::PkgView>,
+}
+
+/// Lists the packages available in the packages directory, and whether each is installed.
+#[utoipa::path(
+    get,
+    path = "/api/pkg/list_local_pkgs",
+    params(ListLocalPkgsRequest),
+    responses((status = 200, body = ListLocalPkgsResponse)),
+    tag = "pkg"
+)]
+pub async fn list_local_pkgs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListLocalPkgsRequest>,
+) -> PkgResult<Json<ListLocalPkgsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let installed_pkgs = InstalledPkg::list(&ctx).await?;
+    let pkgs = list_pkg_dir_entries(get_pkgs_path(&builder).await?)
+        .await?
+        .into_iter()
+        .filter(|name| name.ends_with(&format!(".{PKG_EXTENSION}")))
+        .map(|name| {
+            let installed_pkg = installed_pkgs.iter().find(|pkg| pkg.name() == name);
+            super::PkgView {
+                installed: installed_pkg.is_some(),
+                hash: installed_pkg.map(|pkg| pkg.root_hash().to_owned()),
+                name,
+            }
+        })
+        .collect();
+
+    Ok(Json(ListLocalPkgsResponse { pkgs }))
+}
@@ -5,27 +5,36 @@ use axum::extract::OriginalUri;
 use axum::{extract::Query, Json};
 use dal::{installed_pkg::InstalledPkg, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct PkgListRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkgListResponse {
-    pub pkgs: Vec<PkgView>,
+    pub pkgs: Vec<InstalledPkgView>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct PkgView {
+pub struct InstalledPkgView {
     name: String,
     hash: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/pkg/list_pkgs",
+    params(PkgListRequest),
+    responses((status = 200, body = PkgListResponse)),
+    tag = "pkg"
+)]
 pub async fn list_pkgs(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -37,9 +46,9 @@ pub async fn list_pkgs(
 
     let installed_pkgs = InstalledPkg::list(&ctx).await?;
 
-    let pkgs: Vec<PkgView> = installed_pkgs
+    let pkgs: Vec<InstalledPkgView> = installed_pkgs
         .iter()
-        .map(|pkg| PkgView {
+        .map(|pkg| InstalledPkgView {
             name: pkg.name().to_owned(),
             hash: pkg.root_hash().to_string(),
         })
@@ -12,10 +12,13 @@ use module_index_client::IndexClient;
 use serde::{Deserialize, Serialize};
 use si_pkg::SiPkg;
 use ulid::Ulid;
+use utoipa::IntoParams;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteModuleDetailsRequest {
+    #[param(value_type = String)]
     pub id: Ulid,
     #[serde(flatten)]
     pub visibility: Visibility,
@@ -23,6 +26,13 @@ pub struct RemoteModuleDetailsRequest {
 
 pub type RemoteModuleDetailsResponse = si_pkg::PkgSpec;
 
+#[utoipa::path(
+    get,
+    path = "/api/pkg/remote_module_spec",
+    params(RemoteModuleDetailsRequest),
+    responses((status = 200, body = Object)),
+    tag = "pkg"
+)]
 pub async fn remote_module_spec(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -0,0 +1,60 @@
+use super::PkgResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{installed_pkg::InstalledPkgId, pkg::uninstall_pkg, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallPkgRequest {
+    #[schema(value_type = String)]
+    pub id: InstalledPkgId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallPkgResponse {
+    pub success: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pkg/uninstall_pkg",
+    request_body = UninstallPkgRequest,
+    responses((status = 200, body = UninstallPkgResponse)),
+    tag = "pkg"
+)]
+pub async fn uninstall_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UninstallPkgRequest>,
+) -> PkgResult<Json<UninstallPkgResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    uninstall_pkg(&ctx, request.id).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "uninstall_pkg",
+        serde_json::json!({
+                    "installed_pkg_id": request.id,
+        }),
+    );
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit().await?;
+
+    Ok(Json(UninstallPkgResponse { success: true }))
+}
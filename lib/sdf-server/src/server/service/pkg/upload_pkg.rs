@@ -0,0 +1,72 @@
+use super::PkgResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use crate::server::Upload;
+use axum::extract::{OriginalUri, Query};
+use axum::Json;
+use dal::{pkg::import_pkg_from_pkg, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use si_pkg::SiPkg;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadPkgRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadPkgResponse {
+    pub name: String,
+    /// The hex encoded blake3 hash of the uploaded file.
+    pub checksum: String,
+}
+
+/// Installs a package uploaded as the `file` field of a multipart form.
+#[utoipa::path(
+    post,
+    path = "/api/pkg/upload_pkg",
+    params(UploadPkgRequest),
+    request_body(content = UploadForm, content_type = "multipart/form-data"),
+    responses((status = 200, body = UploadPkgResponse)),
+    tag = "pkg"
+)]
+pub async fn upload_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Query(request): Query<UploadPkgRequest>,
+    upload: Upload,
+) -> PkgResult<Json<UploadPkgResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let pkg = SiPkg::load_from_file(upload.path()).await?;
+    let pkg_name = pkg.metadata()?.name().to_owned();
+    import_pkg_from_pkg(&ctx, &pkg, &pkg_name, None).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "upload_pkg",
+        serde_json::json!({
+                    "pkg_name": pkg_name,
+                    "size": upload.size(),
+        }),
+    );
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit().await?;
+
+    Ok(Json(UploadPkgResponse {
+        name: pkg_name,
+        checksum: upload.checksum(),
+    }))
+}
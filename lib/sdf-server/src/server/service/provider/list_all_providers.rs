@@ -2,25 +2,37 @@ use axum::extract::Query;
 use axum::Json;
 use dal::{ExternalProvider, InternalProvider, SchemaVariantId, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::server::extract::{AccessBuilder, HandlerContext};
 use crate::service::provider::ProviderResult;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListAllProviderRequest {
+    #[param(value_type = String)]
     pub schema_variant_id: SchemaVariantId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListAllProviderResponse {
+    #[schema(value_type = Vec<Object>)]
     pub internal_providers: Vec<InternalProvider>,
+    #[schema(value_type = Vec<Object>)]
     pub external_providers: Vec<ExternalProvider>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/provider/list_all_providers",
+    params(ListAllProviderRequest),
+    responses((status = 200, body = ListAllProviderResponse)),
+    tag = "provider"
+)]
 pub async fn list_all_providers(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
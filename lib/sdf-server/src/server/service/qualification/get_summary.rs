@@ -1,6 +1,7 @@
 use axum::extract::Query;
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 
 use dal::qualification::QualificationSummary;
 use dal::Visibility;
@@ -8,7 +9,8 @@ use dal::Visibility;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 use crate::service::qualification::QualificationResult;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSummaryRequest {
     #[serde(flatten)]
@@ -17,6 +19,13 @@ pub struct GetSummaryRequest {
 
 pub type GetSummaryResponse = QualificationSummary;
 
+#[utoipa::path(
+    get,
+    path = "/api/qualification/get_summary",
+    params(GetSummaryRequest),
+    responses((status = 200, body = Object)),
+    tag = "qualification"
+)]
 pub async fn get_summary(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
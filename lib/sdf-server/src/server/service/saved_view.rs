@@ -0,0 +1,70 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{SavedViewError as DalSavedViewError, SavedViewPk, TransactionsError};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod create_saved_view;
+pub mod delete_saved_view;
+pub mod get_saved_view;
+pub mod list_saved_views;
+pub mod resolve_saved_view;
+pub mod update_saved_view;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SavedViewError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error("saved view not found: {0}")]
+    NotFound(SavedViewPk),
+    #[error(transparent)]
+    SavedView(#[from] DalSavedViewError),
+}
+
+pub type SavedViewResult<T> = std::result::Result<T, SavedViewError>;
+
+impl From<SavedViewError> for ApiError {
+    fn from(err: SavedViewError) -> Self {
+        let code = match &err {
+            SavedViewError::NotFound(_) => ApiErrorCode::NotFound,
+            SavedViewError::SavedView(err) => err.into(),
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for SavedViewError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// Reading and resolving saved views requires the `saved_view:read` scope of API tokens, and
+/// changing them the `saved_view:write` scope.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/create_saved_view",
+            post(create_saved_view::create_saved_view),
+        )
+        .route(
+            "/delete_saved_view",
+            post(delete_saved_view::delete_saved_view),
+        )
+        .route("/get_saved_view", get(get_saved_view::get_saved_view))
+        .route("/list_saved_views", get(list_saved_views::list_saved_views))
+        .route(
+            "/resolve_saved_view",
+            get(resolve_saved_view::resolve_saved_view),
+        )
+        .route(
+            "/update_saved_view",
+            post(update_saved_view::update_saved_view),
+        )
+}
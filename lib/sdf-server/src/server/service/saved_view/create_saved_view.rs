@@ -0,0 +1,55 @@
+use axum::Json;
+use dal::SavedView;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::SavedViewResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSavedViewRequest {
+    pub name: String,
+    /// The label selector of the view, such as `label.env=prod AND label.team=payments`.
+    pub selector: String,
+    /// The diagram layout the view was saved with, as known by the client.
+    pub layout_snapshot_ref: Option<String>,
+    /// Whether the other users of the workspace can use the view.
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSavedViewResponse {
+    #[schema(value_type = Object)]
+    pub saved_view: SavedView,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/saved_view/create_saved_view",
+    request_body = CreateSavedViewRequest,
+    responses((status = 200, body = CreateSavedViewResponse)),
+    tag = "saved_view"
+)]
+pub async fn create_saved_view(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<CreateSavedViewRequest>,
+) -> SavedViewResult<Json<CreateSavedViewResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let saved_view = SavedView::new(
+        &ctx,
+        &request.name,
+        &request.selector,
+        request.layout_snapshot_ref,
+        request.shared,
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateSavedViewResponse { saved_view }))
+}
@@ -0,0 +1,40 @@
+use axum::Json;
+use dal::{SavedView, SavedViewPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{SavedViewError, SavedViewResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSavedViewRequest {
+    #[schema(value_type = String)]
+    pub pk: SavedViewPk,
+}
+
+/// Deletes the saved view. Only its owner can delete it.
+#[utoipa::path(
+    post,
+    path = "/api/saved_view/delete_saved_view",
+    request_body = DeleteSavedViewRequest,
+    responses((status = 200, description = "The saved view was deleted")),
+    tag = "saved_view"
+)]
+pub async fn delete_saved_view(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<DeleteSavedViewRequest>,
+) -> SavedViewResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    SavedView::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(SavedViewError::NotFound(request.pk))?
+        .delete(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
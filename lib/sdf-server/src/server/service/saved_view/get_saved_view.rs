@@ -0,0 +1,44 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{SavedView, SavedViewPk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{SavedViewError, SavedViewResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSavedViewRequest {
+    #[param(value_type = String)]
+    pub pk: SavedViewPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSavedViewResponse {
+    #[schema(value_type = Object)]
+    pub saved_view: SavedView,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/saved_view/get_saved_view",
+    params(GetSavedViewRequest),
+    responses((status = 200, body = GetSavedViewResponse)),
+    tag = "saved_view"
+)]
+pub async fn get_saved_view(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetSavedViewRequest>,
+) -> SavedViewResult<Json<GetSavedViewResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let saved_view = SavedView::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(SavedViewError::NotFound(request.pk))?;
+
+    Ok(Json(GetSavedViewResponse { saved_view }))
+}
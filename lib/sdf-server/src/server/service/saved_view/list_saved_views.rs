@@ -0,0 +1,32 @@
+use axum::Json;
+use dal::SavedView;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::SavedViewResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSavedViewsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<SavedView>,
+}
+
+/// Lists the saved views of the user and the ones shared with the workspace.
+#[utoipa::path(
+    get,
+    path = "/api/saved_view/list_saved_views",
+    responses((status = 200, body = ListSavedViewsResponse)),
+    tag = "saved_view"
+)]
+pub async fn list_saved_views(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> SavedViewResult<Json<ListSavedViewsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = SavedView::list(&ctx).await?;
+
+    Ok(Json(ListSavedViewsResponse { list }))
+}
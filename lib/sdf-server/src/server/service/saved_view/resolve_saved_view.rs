@@ -0,0 +1,45 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{SavedView, SavedViewPk, SavedViewResolution, Visibility};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::{SavedViewError, SavedViewResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveSavedViewRequest {
+    #[param(value_type = String)]
+    pub pk: SavedViewPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type ResolveSavedViewResponse = SavedViewResolution;
+
+/// Resolves the saved view into the components it selects in the change set, and their nodes
+/// on the diagram.
+#[utoipa::path(
+    get,
+    path = "/api/saved_view/resolve_saved_view",
+    params(ResolveSavedViewRequest),
+    responses((status = 200, body = Object)),
+    tag = "saved_view"
+)]
+pub async fn resolve_saved_view(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ResolveSavedViewRequest>,
+) -> SavedViewResult<Json<ResolveSavedViewResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let resolution = SavedView::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(SavedViewError::NotFound(request.pk))?
+        .resolve(&ctx)
+        .await?;
+
+    Ok(Json(resolution))
+}
@@ -0,0 +1,60 @@
+use axum::Json;
+use dal::{SavedView, SavedViewPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{SavedViewError, SavedViewResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSavedViewRequest {
+    #[schema(value_type = String)]
+    pub pk: SavedViewPk,
+    pub name: String,
+    pub selector: String,
+    pub layout_snapshot_ref: Option<String>,
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSavedViewResponse {
+    #[schema(value_type = Object)]
+    pub saved_view: SavedView,
+}
+
+/// Replaces the name, selector, layout reference and sharing of the saved view. Only its owner
+/// can update it.
+#[utoipa::path(
+    post,
+    path = "/api/saved_view/update_saved_view",
+    request_body = UpdateSavedViewRequest,
+    responses((status = 200, body = UpdateSavedViewResponse)),
+    tag = "saved_view"
+)]
+pub async fn update_saved_view(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<UpdateSavedViewRequest>,
+) -> SavedViewResult<Json<UpdateSavedViewResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut saved_view = SavedView::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(SavedViewError::NotFound(request.pk))?;
+    saved_view
+        .update(
+            &ctx,
+            &request.name,
+            &request.selector,
+            request.layout_snapshot_ref,
+            request.shared,
+        )
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateSavedViewResponse { saved_view }))
+}
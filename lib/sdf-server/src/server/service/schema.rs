@@ -1,22 +1,36 @@
-use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
-use axum::Json;
 use axum::Router;
-use dal::{SchemaError as DalSchemaError, StandardModelError, TransactionsError, WsEventError};
+use dal::error_category::categorize;
+use dal::schema::variant::json_schema::import::JsonSchemaImportError;
+use dal::{
+    SchemaError as DalSchemaError, SchemaId, SchemaVariantError, SchemaVariantId,
+    StandardModelError, TransactionsError, WsEventError,
+};
 use thiserror::Error;
 
-use crate::server::state::AppState;
+use crate::server::{
+    api_error::{ApiError, ApiErrorCode},
+    state::AppState,
+};
 
+pub mod add_variant_prop;
+pub mod clone_variant;
 pub mod create_schema;
 pub mod get_schema;
+pub mod get_variant_json_schema;
+pub mod import_json_schema;
 pub mod list_schemas;
+pub mod remove_variant_prop;
+pub mod set_default_variant;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum SchemaError {
     #[error(transparent)]
     ContextTransaction(#[from] TransactionsError),
+    #[error("json schema import error: {0}")]
+    JsonSchemaImport(#[from] Box<JsonSchemaImportError>),
     #[error(transparent)]
     Nats(#[from] si_data_nats::NatsError),
     #[error(transparent)]
@@ -25,6 +39,12 @@ pub enum SchemaError {
     Schema(#[from] DalSchemaError),
     #[error("schema not found")]
     SchemaNotFound,
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] SchemaVariantError),
+    #[error("schema variant not found: {0}")]
+    SchemaVariantNotFound(SchemaVariantId),
+    #[error("schema variant {0} does not belong to schema {1}")]
+    SchemaVariantNotInSchema(SchemaVariantId, SchemaId),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error("wsevent error: {0}")]
@@ -33,18 +53,30 @@ pub enum SchemaError {
 
 pub type SchemaResult<T> = std::result::Result<T, SchemaError>;
 
-impl IntoResponse for SchemaError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            SchemaError::SchemaNotFound => (StatusCode::NOT_FOUND, self.to_string()),
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+impl From<SchemaError> for ApiError {
+    fn from(err: SchemaError) -> Self {
+        let code = match &err {
+            SchemaError::SchemaNotFound | SchemaError::SchemaVariantNotFound(_) => {
+                ApiErrorCode::NotFound
+            }
+            SchemaError::SchemaVariantNotInSchema(_, _) => ApiErrorCode::Validation,
+            SchemaError::JsonSchemaImport(err) => match err.as_ref() {
+                JsonSchemaImportError::OpenApiSchemaNotFound(_)
+                | JsonSchemaImportError::RootNotObject => ApiErrorCode::Validation,
+                _ => ApiErrorCode::Internal,
+            },
+            SchemaError::Schema(err) => err.into(),
+            SchemaError::SchemaVariant(err) => err.into(),
+            SchemaError::StandardModel(err) => err.into(),
+            _ => categorize(&err).into(),
         };
+        ApiError::new(code, err.to_string())
+    }
+}
 
-        let body = Json(
-            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
-        );
-
-        (status, body).into_response()
+impl IntoResponse for SchemaError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
     }
 }
 
@@ -53,4 +85,29 @@ pub fn routes() -> Router<AppState> {
         .route("/create_schema", post(create_schema::create_schema))
         .route("/list_schemas", get(list_schemas::list_schemas))
         .route("/get_schema", get(get_schema::get_schema))
+        .route("/clone_variant", post(clone_variant::clone_variant))
+        .route(
+            "/import_json_schema",
+            post(import_json_schema::import_json_schema),
+        )
+        .route(
+            "/add_variant_prop",
+            post(add_variant_prop::add_variant_prop),
+        )
+        .route(
+            "/remove_variant_prop",
+            post(remove_variant_prop::remove_variant_prop),
+        )
+        .route(
+            "/set_default_variant",
+            post(set_default_variant::set_default_variant),
+        )
+}
+
+/// The routes addressing a single schema variant by id, nested under `/api/schema_variant`.
+pub fn variant_routes() -> Router<AppState> {
+    Router::new().route(
+        "/:schema_variant_id/json_schema",
+        get(get_variant_json_schema::get_variant_json_schema),
+    )
 }
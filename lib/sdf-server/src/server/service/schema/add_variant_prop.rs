@@ -0,0 +1,61 @@
+use super::SchemaResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::{Prop, PropId, PropKind, SchemaVariant, SchemaVariantId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddVariantPropRequest {
+    #[schema(value_type = String)]
+    pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = String)]
+    pub parent_prop_id: PropId,
+    pub name: String,
+    #[schema(value_type = String)]
+    pub kind: PropKind,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddVariantPropResponse {
+    #[schema(value_type = Object)]
+    pub prop: Prop,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schema/add_variant_prop",
+    request_body = AddVariantPropRequest,
+    responses((status = 200, body = AddVariantPropResponse)),
+    tag = "schema"
+)]
+pub async fn add_variant_prop(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<AddVariantPropRequest>,
+) -> SchemaResult<Json<AddVariantPropResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let prop = SchemaVariant::add_prop(
+        &ctx,
+        request.schema_variant_id,
+        &request.name,
+        request.kind,
+        request.parent_prop_id,
+    )
+    .await?;
+    let response = AddVariantPropResponse { prop };
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(response))
+}
@@ -0,0 +1,49 @@
+use super::SchemaResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::{SchemaVariant, SchemaVariantId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneVariantRequest {
+    #[schema(value_type = String)]
+    pub schema_variant_id: SchemaVariantId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneVariantResponse {
+    #[schema(value_type = Object)]
+    pub schema_variant: SchemaVariant,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schema/clone_variant",
+    request_body = CloneVariantRequest,
+    responses((status = 200, body = CloneVariantResponse)),
+    tag = "schema"
+)]
+pub async fn clone_variant(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<CloneVariantRequest>,
+) -> SchemaResult<Json<CloneVariantResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let schema_variant = SchemaVariant::clone_for_edit(&ctx, request.schema_variant_id).await?;
+    let response = CloneVariantResponse { schema_variant };
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(response))
+}
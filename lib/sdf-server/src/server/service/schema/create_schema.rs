@@ -3,8 +3,9 @@ use crate::server::extract::{AccessBuilder, HandlerContext};
 use axum::Json;
 use dal::{component::ComponentKind, Schema, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSchemaRequest {
     pub name: String,
@@ -12,12 +13,20 @@ pub struct CreateSchemaRequest {
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSchemaResponse {
+    #[schema(value_type = Object)]
     pub schema: Schema,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/schema/create_schema",
+    request_body = CreateSchemaRequest,
+    responses((status = 200, body = CreateSchemaResponse)),
+    tag = "schema"
+)]
 pub async fn create_schema(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
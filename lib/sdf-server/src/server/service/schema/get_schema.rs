@@ -1,13 +1,16 @@
 use axum::{extract::Query, Json};
 use dal::{Schema, SchemaId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 
 use super::{SchemaError, SchemaResult};
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSchemaRequest {
+    #[param(value_type = String)]
     pub schema_id: SchemaId,
     #[serde(flatten)]
     pub visibility: Visibility,
@@ -15,6 +18,13 @@ pub struct GetSchemaRequest {
 
 pub type GetSchemaResponse = Schema;
 
+#[utoipa::path(
+    get,
+    path = "/api/schema/get_schema",
+    params(GetSchemaRequest),
+    responses((status = 200, body = Object)),
+    tag = "schema"
+)]
 pub async fn get_schema(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
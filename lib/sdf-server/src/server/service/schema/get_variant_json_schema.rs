@@ -0,0 +1,46 @@
+use axum::extract::{Path, Query};
+use axum::Json;
+use dal::{SchemaVariant, SchemaVariantId, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::IntoParams;
+
+use super::{SchemaError, SchemaResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVariantJsonSchemaRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type GetVariantJsonSchemaResponse = Value;
+
+/// Returns a draft 2020-12 JSON Schema document describing the properties of the components of a
+/// schema variant, for tools which validate payloads before sending them to SI.
+#[utoipa::path(
+    get,
+    path = "/api/schema_variant/{schema_variant_id}/json_schema",
+    params(
+        ("schema_variant_id" = String, Path, description = "The id of the schema variant"),
+        GetVariantJsonSchemaRequest,
+    ),
+    responses((status = 200, body = Object)),
+    tag = "schema"
+)]
+pub async fn get_variant_json_schema(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(schema_variant_id): Path<SchemaVariantId>,
+    Query(request): Query<GetVariantJsonSchemaRequest>,
+) -> SchemaResult<Json<GetVariantJsonSchemaResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let schema_variant = SchemaVariant::get_by_id(&ctx, &schema_variant_id)
+        .await?
+        .ok_or(SchemaError::SchemaVariantNotFound(schema_variant_id))?;
+
+    Ok(Json(schema_variant.to_json_schema(&ctx).await?))
+}
@@ -0,0 +1,76 @@
+use super::SchemaResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::schema::variant::definition::SchemaVariantDefinitionId;
+use dal::schema::variant::json_schema::import::{JsonSchemaImport, UnsupportedConstruct};
+use dal::{SchemaVariantId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJsonSchemaRequest {
+    pub name: String,
+    pub category: String,
+    pub color: String,
+    /// A JSON Schema document, or an OpenAPI document when `openapiSchemaName` is set.
+    #[schema(value_type = Object)]
+    pub document: Value,
+    /// The name of the component schema to import from an OpenAPI document.
+    pub openapi_schema_name: Option<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJsonSchemaResponse {
+    #[schema(value_type = String)]
+    pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = String)]
+    pub variant_def_id: SchemaVariantDefinitionId,
+    /// The constructs of the document which were not imported as they were written.
+    #[schema(value_type = Vec<Object>)]
+    pub unsupported: Vec<UnsupportedConstruct>,
+}
+
+/// Creates an asset, and its schema variant, from a JSON Schema document or from a component
+/// schema of an OpenAPI document.
+#[utoipa::path(
+    post,
+    path = "/api/schema/import_json_schema",
+    request_body = ImportJsonSchemaRequest,
+    responses((status = 200, body = ImportJsonSchemaResponse)),
+    tag = "schema"
+)]
+pub async fn import_json_schema(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<ImportJsonSchemaRequest>,
+) -> SchemaResult<Json<ImportJsonSchemaResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let import = match &request.openapi_schema_name {
+        Some(schema_name) => JsonSchemaImport::from_openapi(&request.document, schema_name),
+        None => JsonSchemaImport::from_json_schema(&request.document),
+    }
+    .map_err(Box::new)?;
+    let (variant_def_id, schema_variant_id) = import
+        .create_schema_variant(&ctx, &request.name, &request.category, &request.color)
+        .await
+        .map_err(Box::new)?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ImportJsonSchemaResponse {
+        schema_variant_id,
+        variant_def_id,
+        unsupported: import.unsupported,
+    }))
+}
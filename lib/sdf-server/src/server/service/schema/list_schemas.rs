@@ -1,24 +1,34 @@
 use axum::extract::Query;
 use axum::Json;
-use dal::{Schema, StandardModel, Visibility};
+use dal::{Schema, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::SchemaResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSchemaRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSchemaResponse {
+    #[schema(value_type = Vec<Object>)]
     pub list: Vec<Schema>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/schema/list_schemas",
+    params(ListSchemaRequest),
+    responses((status = 200, body = ListSchemaResponse)),
+    tag = "schema"
+)]
 pub async fn list_schemas(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -26,7 +36,7 @@ pub async fn list_schemas(
 ) -> SchemaResult<Json<ListSchemaResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
-    let list = Schema::list(&ctx).await?;
+    let list = Schema::list_visible(&ctx).await?;
 
     let response = ListSchemaResponse { list };
     Ok(Json(response))
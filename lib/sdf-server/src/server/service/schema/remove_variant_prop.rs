@@ -0,0 +1,49 @@
+use super::SchemaResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::{PropId, SchemaVariant, SchemaVariantId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveVariantPropRequest {
+    #[schema(value_type = String)]
+    pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = String)]
+    pub prop_id: PropId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveVariantPropResponse {
+    pub success: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schema/remove_variant_prop",
+    request_body = RemoveVariantPropRequest,
+    responses((status = 200, body = RemoveVariantPropResponse)),
+    tag = "schema"
+)]
+pub async fn remove_variant_prop(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<RemoveVariantPropRequest>,
+) -> SchemaResult<Json<RemoveVariantPropResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    SchemaVariant::remove_prop(&ctx, request.schema_variant_id, request.prop_id).await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(RemoveVariantPropResponse { success: true }))
+}
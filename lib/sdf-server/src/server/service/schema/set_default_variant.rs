@@ -0,0 +1,69 @@
+use super::{SchemaError, SchemaResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::{Schema, SchemaId, SchemaVariantId, StandardModel, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDefaultVariantRequest {
+    #[schema(value_type = String)]
+    pub schema_id: SchemaId,
+    #[schema(value_type = String)]
+    pub schema_variant_id: SchemaVariantId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDefaultVariantResponse {
+    #[schema(value_type = Object)]
+    pub schema: Schema,
+}
+
+/// Makes the given variant the one new components of the [`Schema`] are created from.
+#[utoipa::path(
+    post,
+    path = "/api/schema/set_default_variant",
+    request_body = SetDefaultVariantRequest,
+    responses((status = 200, body = SetDefaultVariantResponse)),
+    tag = "schema"
+)]
+pub async fn set_default_variant(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<SetDefaultVariantRequest>,
+) -> SchemaResult<Json<SetDefaultVariantResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut schema = Schema::get_by_id(&ctx, &request.schema_id)
+        .await?
+        .ok_or(SchemaError::SchemaNotFound)?;
+    let variant_belongs_to_schema = schema
+        .variants(&ctx)
+        .await?
+        .iter()
+        .any(|variant| *variant.id() == request.schema_variant_id);
+    if !variant_belongs_to_schema {
+        return Err(SchemaError::SchemaVariantNotInSchema(
+            request.schema_variant_id,
+            request.schema_id,
+        ));
+    }
+
+    schema
+        .set_default_schema_variant_id(&ctx, Some(request.schema_variant_id))
+        .await?;
+    let response = SetDefaultVariantResponse { schema };
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(response))
+}
@@ -1,13 +1,13 @@
-use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
-use axum::Json;
 use axum::Router;
+use dal::error_category::categorize;
 use dal::{
     KeyPairError, StandardModelError, TransactionsError, UserError, WorkspacePk, WsEventError,
 };
 use thiserror::Error;
 
+use crate::server::api_error::{ApiError, ApiErrorCode};
 use crate::server::state::AppState;
 
 pub mod create_secret;
@@ -39,20 +39,21 @@ pub enum SecretError {
 
 pub type SecretResult<T> = std::result::Result<T, SecretError>;
 
+impl From<SecretError> for ApiError {
+    fn from(err: SecretError) -> Self {
+        let code = match &err {
+            SecretError::WorkspaceNotFound(_) => ApiErrorCode::NotFound,
+            SecretError::Secret(err) => err.into(),
+            SecretError::StandardModel(err) => err.into(),
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
 impl IntoResponse for SecretError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
-        //SecretError::SecretNotFound => (StatusCode::NOT_FOUND, self.to_string()),
-
-        let body = Json(serde_json::json!({
-            "error": {
-                "message": error_message,
-                "code": 42,
-                "statusCode": status.as_u16()
-            }
-        }));
-
-        (status, body).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -4,31 +4,45 @@ use dal::{
     SecretVersion, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
 use super::SecretResult;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSecretRequest {
     pub name: String,
+    #[schema(value_type = String)]
     pub object_type: SecretObjectType,
+    #[schema(value_type = String)]
     pub kind: SecretKind,
     pub crypted: Vec<u8>,
+    #[schema(value_type = String)]
     pub key_pair_pk: KeyPairPk,
+    #[schema(value_type = String)]
     pub version: SecretVersion,
+    #[schema(value_type = String)]
     pub algorithm: SecretAlgorithm,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSecretResponse {
+    #[schema(value_type = Object)]
     pub secret: Secret,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/secret/create_secret",
+    request_body = CreateSecretRequest,
+    responses((status = 200, body = CreateSecretResponse)),
+    tag = "secret"
+)]
 pub async fn create_secret(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_tx): AccessBuilder,
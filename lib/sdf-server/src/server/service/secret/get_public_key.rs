@@ -6,6 +6,12 @@ use crate::server::extract::{AccessBuilder, HandlerContext};
 
 pub type GetPublicKeyResponse = PublicKey;
 
+#[utoipa::path(
+    get,
+    path = "/api/secret/get_public_key",
+    responses((status = 200, body = Object)),
+    tag = "secret"
+)]
 pub async fn get_public_key(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -2,23 +2,33 @@ use axum::extract::Query;
 use axum::Json;
 use dal::{secret::SecretView, Secret, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::SecretResult;
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSecretRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSecretResponse {
+    #[schema(value_type = Vec<Object>)]
     pub list: Vec<SecretView>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/secret/list_secrets",
+    params(ListSecretRequest),
+    responses((status = 200, body = ListSecretResponse)),
+    tag = "secret"
+)]
 pub async fn list_secrets(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -13,6 +13,7 @@ use crate::server::state::AppState;
 
 pub mod auth_connect;
 pub mod load_workspace;
+pub mod logout_all;
 pub mod restore_authentication;
 
 #[remain::sorted]
@@ -22,6 +23,8 @@ pub enum SessionError {
     AuthApiError(String),
     #[error(transparent)]
     ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DalSession(#[from] dal::SessionError),
     #[error("Invalid user: {0}")]
     InvalidUser(UserPk),
     #[error("Invalid workspace: {0}")]
@@ -71,4 +74,5 @@ pub fn routes() -> Router<AppState> {
             get(restore_authentication::restore_authentication),
         )
         .route("/load_workspace", get(load_workspace::load_workspace))
+        .route("/logout_all", post(logout_all::logout_all))
 }
@@ -4,17 +4,20 @@ use axum::Json;
 use dal::{HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AuthConnectRequest {
     pub code: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AuthConnectResponse {
+    #[schema(value_type = Object)]
     pub user: User,
+    #[schema(value_type = Object)]
     pub workspace: Workspace,
     pub token: String,
 }
@@ -57,6 +60,13 @@ pub struct AuthApiConnectResponse {
     pub token: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/session/connect",
+    request_body = AuthConnectRequest,
+    responses((status = 200, body = AuthConnectResponse)),
+    tag = "session"
+)]
 pub async fn auth_connect(
     HandlerContext(builder): HandlerContext,
     Json(request): Json<AuthConnectRequest>,
@@ -5,13 +5,21 @@ use axum::extract::OriginalUri;
 use axum::Json;
 use dal::Workspace;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoadWorkspaceResponse {
+    #[schema(value_type = Object)]
     pub workspace: Workspace,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/session/load_workspace",
+    responses((status = 200, body = LoadWorkspaceResponse)),
+    tag = "session"
+)]
 pub async fn load_workspace(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
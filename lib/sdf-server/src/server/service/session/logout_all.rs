@@ -0,0 +1,43 @@
+use axum::extract::State;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::SessionRevocation;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::SessionResult;
+use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
+use crate::server::state::SessionRevocationCache;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutAllResponse {
+    pub revoked_before: DateTime<Utc>,
+}
+
+/// Invalidates all of the current user's outstanding session tokens, including the one used to
+/// make this request.
+#[utoipa::path(
+    post,
+    path = "/api/session/logout_all",
+    responses((status = 200, body = LogoutAllResponse)),
+    tag = "session"
+)]
+pub async fn logout_all(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Authorization(claim): Authorization,
+    State(session_revocations): State<SessionRevocationCache>,
+) -> SessionResult<Json<LogoutAllResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let revoked_before = SessionRevocation::revoke_all_for_user(&ctx, claim.user_pk).await?;
+
+    ctx.commit().await?;
+
+    session_revocations
+        .insert(claim.user_pk, Some(revoked_before))
+        .await;
+
+    Ok(Json(LogoutAllResponse { revoked_before }))
+}
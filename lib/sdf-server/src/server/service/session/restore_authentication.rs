@@ -1,17 +1,26 @@
 use axum::Json;
 use dal::{User, Workspace};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::{SessionError, SessionResult};
 use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RestoreAuthenticationResponse {
+    #[schema(value_type = Object)]
     pub user: User,
+    #[schema(value_type = Object)]
     pub workspace: Workspace,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/session/restore_authentication",
+    responses((status = 200, body = RestoreAuthenticationResponse)),
+    tag = "session"
+)]
 pub async fn restore_authentication(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -0,0 +1,68 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::notification::SlackError as DalSlackError;
+use dal::TransactionsError;
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod delete_slack_integration;
+pub mod get_slack_integration;
+pub mod send_slack_test_message;
+pub mod update_slack_integration;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SlackError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    Slack(#[from] DalSlackError),
+}
+
+pub type SlackResult<T> = std::result::Result<T, SlackError>;
+
+impl From<SlackError> for ApiError {
+    fn from(err: SlackError) -> Self {
+        let code = match &err {
+            SlackError::Slack(DalSlackError::NoIntegration) => ApiErrorCode::NotFound,
+            // Slack refusing a message means the integration is misconfigured
+            SlackError::Slack(DalSlackError::Api(_) | DalSlackError::InvalidIntegration(_)) => {
+                ApiErrorCode::Validation
+            }
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for SlackError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// Reading the Slack integration of a workspace requires the `slack:read` scope of API tokens, and
+/// changing it or sending a test message the `slack:write` scope.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/delete_slack_integration",
+            post(delete_slack_integration::delete_slack_integration),
+        )
+        .route(
+            "/get_slack_integration",
+            get(get_slack_integration::get_slack_integration),
+        )
+        .route(
+            "/send_slack_test_message",
+            post(send_slack_test_message::send_slack_test_message),
+        )
+        .route(
+            "/update_slack_integration",
+            post(update_slack_integration::update_slack_integration),
+        )
+}
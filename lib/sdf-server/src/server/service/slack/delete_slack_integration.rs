@@ -0,0 +1,28 @@
+use dal::notification::{SlackError as DalSlackError, SlackIntegration};
+
+use super::SlackResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+/// Deletes the Slack integration of the workspace, so that nothing is posted to Slack anymore.
+#[utoipa::path(
+    post,
+    path = "/api/slack/delete_slack_integration",
+    responses((status = 200, description = "The Slack integration was deleted")),
+    tag = "slack"
+)]
+pub async fn delete_slack_integration(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> SlackResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    SlackIntegration::get(&ctx)
+        .await?
+        .ok_or(DalSlackError::NoIntegration)?
+        .delete(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
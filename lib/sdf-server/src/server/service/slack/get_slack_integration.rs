@@ -0,0 +1,41 @@
+use axum::Json;
+use dal::notification::SlackIntegration;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::SlackResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSlackIntegrationResponse {
+    /// The integration of the workspace, or `null` when it has none.
+    #[schema(value_type = Option<Object>)]
+    pub integration: Option<SlackIntegration>,
+    /// Whether messages are posted with a bot token, rather than an incoming webhook.
+    pub uses_bot_token: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/slack/get_slack_integration",
+    responses((status = 200, body = GetSlackIntegrationResponse)),
+    tag = "slack"
+)]
+pub async fn get_slack_integration(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> SlackResult<Json<GetSlackIntegrationResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let integration = SlackIntegration::get(&ctx).await?;
+    let uses_bot_token = integration
+        .as_ref()
+        .map(SlackIntegration::uses_bot_token)
+        .unwrap_or_default();
+
+    Ok(Json(GetSlackIntegrationResponse {
+        integration,
+        uses_bot_token,
+    }))
+}
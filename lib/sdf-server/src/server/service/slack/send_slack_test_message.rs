@@ -0,0 +1,27 @@
+use dal::notification::{SlackError as DalSlackError, SlackIntegration};
+
+use super::SlackResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+/// Posts a test message with the Slack integration of the workspace, answering with the reason
+/// Slack gave if it refused it.
+#[utoipa::path(
+    post,
+    path = "/api/slack/send_slack_test_message",
+    responses((status = 200, description = "The test message was posted")),
+    tag = "slack"
+)]
+pub async fn send_slack_test_message(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> SlackResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    SlackIntegration::get(&ctx)
+        .await?
+        .ok_or(DalSlackError::NoIntegration)?
+        .send_test_message()
+        .await?;
+
+    Ok(())
+}
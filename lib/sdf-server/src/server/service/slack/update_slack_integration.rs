@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use axum::Json;
+use dal::notification::slack::DEFAULT_SLACK_KINDS;
+use dal::notification::SlackIntegration;
+use dal::NotificationKind;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::SlackResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSlackIntegrationRequest {
+    /// The incoming webhook to post messages to. Give either this or a bot token.
+    pub webhook_url: Option<String>,
+    /// The bot token to post messages with, to the channel.
+    pub bot_token: Option<String>,
+    pub channel: Option<String>,
+    /// The kinds of notifications to post (e.g. `qualificationFailed`).
+    #[serde(default = "default_kinds")]
+    #[schema(value_type = Vec<String>)]
+    pub kinds: Vec<NotificationKind>,
+    /// Message templates by kind of notification, in which `{{name}}`, `{{subject}}` and
+    /// `{{body}}` are replaced with those of the notification.
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub templates: HashMap<NotificationKind, String>,
+}
+
+fn default_kinds() -> Vec<NotificationKind> {
+    DEFAULT_SLACK_KINDS.to_vec()
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSlackIntegrationResponse {
+    #[schema(value_type = Object)]
+    pub integration: SlackIntegration,
+}
+
+/// Sets the Slack integration of the workspace, replacing the previous one along with its
+/// credentials.
+#[utoipa::path(
+    post,
+    path = "/api/slack/update_slack_integration",
+    request_body = UpdateSlackIntegrationRequest,
+    responses((status = 200, body = UpdateSlackIntegrationResponse)),
+    tag = "slack"
+)]
+pub async fn update_slack_integration(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<UpdateSlackIntegrationRequest>,
+) -> SlackResult<Json<UpdateSlackIntegrationResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let integration = SlackIntegration::set(
+        &ctx,
+        request.webhook_url,
+        request.bot_token,
+        request.channel,
+        request.kinds,
+        request.templates,
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateSlackIntegrationResponse { integration }))
+}
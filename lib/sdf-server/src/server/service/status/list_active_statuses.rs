@@ -4,25 +4,37 @@ use dal::{
     ChangeSetPk, StatusUpdate, Visibility,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::server::extract::{AccessBuilder, HandlerContext};
 
 use super::StatusResult;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListActiveStatusesRequest {
+    #[param(value_type = String)]
     pub change_set_pk: ChangeSetPk,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ActiveStatus {
+    #[schema(value_type = String)]
     pub pk: StatusUpdatePk,
+    #[schema(value_type = Object)]
     pub data: StatusUpdateData,
 }
 pub type ListActiveStatusesResponse = Vec<ActiveStatus>;
 
+#[utoipa::path(
+    get,
+    path = "/api/status/list-active-statuses",
+    params(ListActiveStatusesRequest),
+    responses((status = 200, body = Vec<ActiveStatus>)),
+    tag = "status"
+)]
 pub async fn list_active_statuses(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -14,22 +14,32 @@ use dal::{
 };
 use serde::{Deserialize, Serialize};
 use std::env::var;
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CloneVariantDefRequest {
+    #[schema(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CloneVariantDefResponse {
+    #[schema(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     pub success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/variant_def/clone_variant_def",
+    request_body = CloneVariantDefRequest,
+    responses((status = 200, body = CloneVariantDefResponse)),
+    tag = "variant_def"
+)]
 pub async fn clone_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -48,7 +58,11 @@ pub async fn clone_variant_def(
     // Generate a unique name and make sure it's not in use
     let mut name;
     loop {
-        name = format!("{} Clone {}", variant_def.name(), generate_unique_id(4));
+        name = format!(
+            "{} Clone {}",
+            variant_def.name(),
+            generate_unique_id(&ctx, 4)
+        );
         match Schema::find_by_name(&ctx, &name).await {
             Ok(_) => continue,
             Err(SchemaError::NotFoundByName(_)) | Err(SchemaError::NoDefaultVariant(_)) => break,
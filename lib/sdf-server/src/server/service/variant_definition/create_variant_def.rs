@@ -9,13 +9,14 @@ use dal::{
     Func, FuncBackendKind, FuncBackendResponseType, StandardModel, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 const DEFAULT_ASSET_CODE: &str = r#"function createAsset() {
   const asset = new AssetBuilder();
   return asset.build()
 }"#;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateVariantDefRequest {
     pub name: String,
@@ -28,13 +29,21 @@ pub struct CreateVariantDefRequest {
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateVariantDefResponse {
+    #[schema(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     pub success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/variant_def/create_variant_def",
+    request_body = CreateVariantDefRequest,
+    responses((status = 200, body = CreateVariantDefResponse)),
+    tag = "variant_def"
+)]
 pub async fn create_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -13,21 +13,31 @@ use dal::{
     schema::variant::definition::{
         SchemaVariantDefinition, SchemaVariantDefinitionJson, SchemaVariantDefinitionMetadataJson,
     },
-    Func, FuncBinding, HistoryActor, SchemaVariantId, StandardModel, User, WsEvent,
+    Func, FuncBinding, SchemaVariantId, StandardModel, User, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 use si_pkg::{FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, PkgSpec, SiPkg};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 pub type ExecVariantDefRequest = super::save_variant_def::SaveVariantDefRequest;
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecVariantDefResponse {
     pub success: bool,
+    #[schema(value_type = String)]
     pub schema_variant_id: SchemaVariantId,
+    #[schema(value_type = Object)]
     pub func_exec_response: serde_json::Value,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/variant_def/exec_variant_def",
+    request_body = SaveVariantDefRequest,
+    responses((status = 200, body = ExecVariantDefResponse)),
+    tag = "variant_def"
+)]
 pub async fn exec_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -40,9 +50,9 @@ pub async fn exec_variant_def(
     // Ensure we save all details before "exec"
     super::save_variant_def(&ctx, &request).await?;
 
-    let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk).await?,
-        _ => None,
+    let user = match ctx.history_actor().user_pk() {
+        Some(user_pk) => User::get_by_pk(&ctx, user_pk).await?,
+        None => None,
     };
     let user_email = user
         .map(|user| user.email().to_owned())
@@ -10,18 +10,22 @@ use dal::{
     ComponentType, Func, SchemaVariant, SchemaVariantId, StandardModel, Timestamp, Visibility,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct GetVariantDefRequest {
+    #[param(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetVariantDefResponse {
+    #[schema(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     pub name: String,
     pub menu_name: Option<String>,
@@ -31,7 +35,9 @@ pub struct GetVariantDefResponse {
     pub description: Option<String>,
     pub code: String,
     pub handler: String,
+    #[schema(value_type = Option<String>)]
     pub schema_variant_id: Option<SchemaVariantId>,
+    #[schema(value_type = String)]
     pub component_type: ComponentType,
     pub funcs: Vec<ListedFuncView>,
     pub types: String,
@@ -64,6 +70,13 @@ impl From<SchemaVariantDefinition> for GetVariantDefResponse {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/variant_def/get_variant_def",
+    params(GetVariantDefRequest),
+    responses((status = 200, body = GetVariantDefResponse)),
+    tag = "variant_def"
+)]
 pub async fn get_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -8,17 +8,20 @@ use dal::{
     StandardModel, Timestamp, Visibility,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
 pub struct ListVariantDefsRequest {
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListedVariantDef {
+    #[schema(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     pub name: String,
     pub menu_name: Option<String>,
@@ -28,12 +31,19 @@ pub struct ListedVariantDef {
     pub timestamp: Timestamp,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListVariantDefsResponse {
     pub variant_defs: Vec<ListedVariantDef>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/variant_def/list_variant_defs",
+    params(ListVariantDefsRequest),
+    responses((status = 200, body = ListVariantDefsResponse)),
+    tag = "variant_def"
+)]
 pub async fn list_variant_defs(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -6,10 +6,12 @@ use axum::Json;
 use dal::ComponentType;
 use dal::{schema::variant::definition::SchemaVariantDefinitionId, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveVariantDefRequest {
+    #[schema(value_type = String)]
     pub id: SchemaVariantDefinitionId,
     pub name: String,
     pub menu_name: Option<String>,
@@ -19,17 +21,25 @@ pub struct SaveVariantDefRequest {
     pub code: String,
     pub handler: String,
     pub description: Option<String>,
+    #[schema(value_type = String)]
     pub component_type: ComponentType,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveVariantDefResponse {
     pub success: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/variant_def/save_variant_def",
+    request_body = SaveVariantDefRequest,
+    responses((status = 200, body = SaveVariantDefResponse)),
+    tag = "variant_def"
+)]
 pub async fn save_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -0,0 +1,69 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{TransactionsError, WebhookError as DalWebhookError, WebhookPk};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod create_webhook;
+pub mod delete_webhook;
+pub mod list_webhook_deliveries;
+pub mod list_webhooks;
+pub mod redeliver_webhook_delivery;
+pub mod update_webhook;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error("webhook not found: {0}")]
+    NotFound(WebhookPk),
+    #[error(transparent)]
+    Webhook(#[from] DalWebhookError),
+}
+
+pub type WebhookResult<T> = std::result::Result<T, WebhookError>;
+
+impl From<WebhookError> for ApiError {
+    fn from(err: WebhookError) -> Self {
+        let code = match &err {
+            WebhookError::NotFound(_)
+            | WebhookError::Webhook(
+                DalWebhookError::NotFound(_) | DalWebhookError::DeliveryNotFound(_),
+            ) => ApiErrorCode::NotFound,
+            WebhookError::Webhook(
+                DalWebhookError::InvalidEventKind(_) | DalWebhookError::InvalidUrl(..),
+            ) => ApiErrorCode::Validation,
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for WebhookError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// Reading webhooks and their deliveries requires the `webhook:read` scope of API tokens, and
+/// changing them or redelivering events the `webhook:write` scope.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/create_webhook", post(create_webhook::create_webhook))
+        .route("/delete_webhook", post(delete_webhook::delete_webhook))
+        .route(
+            "/list_webhook_deliveries",
+            get(list_webhook_deliveries::list_webhook_deliveries),
+        )
+        .route("/list_webhooks", get(list_webhooks::list_webhooks))
+        .route(
+            "/redeliver_webhook_delivery",
+            post(redeliver_webhook_delivery::redeliver_webhook_delivery),
+        )
+        .route("/update_webhook", post(update_webhook::update_webhook))
+}
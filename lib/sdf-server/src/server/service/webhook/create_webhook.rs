@@ -0,0 +1,46 @@
+use axum::Json;
+use dal::Webhook;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::WebhookResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// The kinds of events to send (e.g. `ChangeSetApplied`), or every kind when empty.
+    #[serde(default)]
+    pub event_kinds: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookResponse {
+    #[schema(value_type = Object)]
+    pub webhook: Webhook,
+    /// The secret deliveries are signed with, which is only ever returned here.
+    pub secret: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhook/create_webhook",
+    request_body = CreateWebhookRequest,
+    responses((status = 200, body = CreateWebhookResponse)),
+    tag = "webhook"
+)]
+pub async fn create_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<CreateWebhookRequest>,
+) -> WebhookResult<Json<CreateWebhookResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (webhook, secret) = Webhook::new(&ctx, request.url, request.event_kinds).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateWebhookResponse { webhook, secret }))
+}
@@ -0,0 +1,40 @@
+use axum::Json;
+use dal::{Webhook, WebhookPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{WebhookError, WebhookResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteWebhookRequest {
+    #[schema(value_type = String)]
+    pub pk: WebhookPk,
+}
+
+/// Deletes the webhook, along with the history of its deliveries.
+#[utoipa::path(
+    post,
+    path = "/api/webhook/delete_webhook",
+    request_body = DeleteWebhookRequest,
+    responses((status = 200, description = "The webhook was deleted")),
+    tag = "webhook"
+)]
+pub async fn delete_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<DeleteWebhookRequest>,
+) -> WebhookResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Webhook::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(WebhookError::NotFound(request.pk))?
+        .delete(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
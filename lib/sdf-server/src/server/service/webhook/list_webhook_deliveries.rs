@@ -0,0 +1,47 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{Webhook, WebhookDelivery, WebhookPk};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{WebhookError, WebhookResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebhookDeliveriesRequest {
+    #[param(value_type = String)]
+    pub webhook_pk: WebhookPk,
+    /// How many of the most recent deliveries to list.
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebhookDeliveriesResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<WebhookDelivery>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhook/list_webhook_deliveries",
+    params(ListWebhookDeliveriesRequest),
+    responses((status = 200, body = ListWebhookDeliveriesResponse)),
+    tag = "webhook"
+)]
+pub async fn list_webhook_deliveries(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListWebhookDeliveriesRequest>,
+) -> WebhookResult<Json<ListWebhookDeliveriesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let webhook = Webhook::get_by_pk(&ctx, request.webhook_pk)
+        .await?
+        .ok_or(WebhookError::NotFound(request.webhook_pk))?;
+    let list = WebhookDelivery::list_for_webhook(&ctx, webhook.pk(), request.limit).await?;
+
+    Ok(Json(ListWebhookDeliveriesResponse { list }))
+}
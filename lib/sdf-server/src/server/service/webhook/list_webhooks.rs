@@ -0,0 +1,31 @@
+use axum::Json;
+use dal::Webhook;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::WebhookResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebhooksResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub list: Vec<Webhook>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhook/list_webhooks",
+    responses((status = 200, body = ListWebhooksResponse)),
+    tag = "webhook"
+)]
+pub async fn list_webhooks(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> WebhookResult<Json<ListWebhooksResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = Webhook::list(&ctx).await?;
+
+    Ok(Json(ListWebhooksResponse { list }))
+}
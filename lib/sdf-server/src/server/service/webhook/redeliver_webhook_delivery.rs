@@ -0,0 +1,44 @@
+use axum::Json;
+use dal::{WebhookDelivery, WebhookDeliveryPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::WebhookResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedeliverWebhookDeliveryRequest {
+    #[schema(value_type = String)]
+    pub pk: WebhookDeliveryPk,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedeliverWebhookDeliveryResponse {
+    /// The new delivery, which keeps the history of the original one intact.
+    #[schema(value_type = Object)]
+    pub delivery: WebhookDelivery,
+}
+
+/// Sends the event of a past delivery to its webhook again, whatever became of the original one.
+#[utoipa::path(
+    post,
+    path = "/api/webhook/redeliver_webhook_delivery",
+    request_body = RedeliverWebhookDeliveryRequest,
+    responses((status = 200, body = RedeliverWebhookDeliveryResponse)),
+    tag = "webhook"
+)]
+pub async fn redeliver_webhook_delivery(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<RedeliverWebhookDeliveryRequest>,
+) -> WebhookResult<Json<RedeliverWebhookDeliveryResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let delivery = WebhookDelivery::redeliver(&ctx, request.pk).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(RedeliverWebhookDeliveryResponse { delivery }))
+}
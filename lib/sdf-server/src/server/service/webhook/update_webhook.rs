@@ -0,0 +1,52 @@
+use axum::Json;
+use dal::{Webhook, WebhookPk};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{WebhookError, WebhookResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookRequest {
+    #[schema(value_type = String)]
+    pub pk: WebhookPk,
+    pub url: String,
+    /// The kinds of events to send (e.g. `ChangeSetApplied`), or every kind when empty.
+    #[serde(default)]
+    pub event_kinds: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookResponse {
+    #[schema(value_type = Object)]
+    pub webhook: Webhook,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhook/update_webhook",
+    request_body = UpdateWebhookRequest,
+    responses((status = 200, body = UpdateWebhookResponse)),
+    tag = "webhook"
+)]
+pub async fn update_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> WebhookResult<Json<UpdateWebhookResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut webhook = Webhook::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(WebhookError::NotFound(request.pk))?;
+    webhook
+        .update(&ctx, request.url, request.event_kinds, request.enabled)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateWebhookResponse { webhook }))
+}
@@ -0,0 +1,62 @@
+use axum::extract::DefaultBodyLimit;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{ChangeSetError as DalChangeSetError, TransactionsError, UsageError, WsEventError};
+use si_pkg::SiPkgError;
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod get_usage;
+pub mod import_workspace;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WorkspaceError {
+    #[error(transparent)]
+    ChangeSet(#[from] DalChangeSetError),
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DalPkg(#[from] dal::pkg::PkgError),
+    #[error("invalid workspace export: {0}")]
+    SiPkg(#[from] SiPkgError),
+    #[error(transparent)]
+    Usage(#[from] UsageError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type WorkspaceResult<T> = std::result::Result<T, WorkspaceError>;
+
+impl From<WorkspaceError> for ApiError {
+    fn from(err: WorkspaceError) -> Self {
+        let code = match &err {
+            WorkspaceError::SiPkg(_) | WorkspaceError::Usage(UsageError::InvalidRange(..)) => {
+                ApiErrorCode::Validation
+            }
+            WorkspaceError::ChangeSet(err) => err.into(),
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for WorkspaceError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/get_usage", get(get_usage::get_usage))
+        // Uploads enforce their own limit as they are streamed.
+        .route(
+            "/import_workspace",
+            post(import_workspace::import_workspace).layer(DefaultBodyLimit::disable()),
+        )
+}
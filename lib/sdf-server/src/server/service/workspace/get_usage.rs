@@ -0,0 +1,50 @@
+use axum::extract::Query;
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use dal::{Usage, UsageBucket, UsageReport};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use super::WorkspaceResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUsageRequest {
+    /// Defaults to a day before `to` for hourly buckets, and to 30 days before it for daily ones.
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now.
+    pub to: Option<DateTime<Utc>>,
+    /// Either "hour", the default, or "day".
+    #[param(value_type = Option<String>)]
+    pub bucket: Option<UsageBucket>,
+}
+
+pub type GetUsageResponse = UsageReport;
+
+/// Reports what the workspace used over a range of time, summed up by hour or by day.
+#[utoipa::path(
+    get,
+    path = "/api/workspace/get_usage",
+    params(GetUsageRequest),
+    responses((status = 200, body = Object)),
+    tag = "workspace"
+)]
+pub async fn get_usage(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetUsageRequest>,
+) -> WorkspaceResult<Json<GetUsageResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let bucket = request.bucket.unwrap_or_default();
+    let to = request.to.unwrap_or_else(Utc::now);
+    let from = request.from.unwrap_or_else(|| match bucket {
+        UsageBucket::Day => to - Duration::days(30),
+        UsageBucket::Hour => to - Duration::days(1),
+    });
+    let report = Usage::report(&ctx, from, to, bucket).await?;
+
+    Ok(Json(report))
+}
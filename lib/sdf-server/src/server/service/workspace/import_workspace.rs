@@ -0,0 +1,65 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{pkg::import_pkg_from_pkg, ChangeSet, Visibility};
+use serde::{Deserialize, Serialize};
+use si_pkg::SiPkg;
+use utoipa::ToSchema;
+
+use super::WorkspaceResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use crate::server::Upload;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWorkspaceResponse {
+    /// The change set the export was imported into, to be reviewed and applied.
+    #[schema(value_type = Object)]
+    pub change_set: ChangeSet,
+    /// The hex encoded blake3 hash of the uploaded file.
+    pub checksum: String,
+}
+
+/// Imports a workspace export, such as a workspace backup module, uploaded as the `file` field of
+/// a multipart form. The export is imported into a new change set rather than onto head.
+#[utoipa::path(
+    post,
+    path = "/api/workspace/import_workspace",
+    request_body(content = UploadForm, content_type = "multipart/form-data"),
+    responses((status = 200, body = ImportWorkspaceResponse)),
+    tag = "workspace"
+)]
+pub async fn import_workspace(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    upload: Upload,
+) -> WorkspaceResult<Json<ImportWorkspaceResponse>> {
+    let mut ctx = builder.build_head(access_builder).await?;
+
+    let pkg = SiPkg::load_from_file(upload.path()).await?;
+    let metadata = pkg.metadata()?;
+
+    let change_set = ChangeSet::new(&ctx, format!("Import of {}", metadata.name()), None).await?;
+    ctx.update_visibility(Visibility::new_change_set(change_set.pk, false));
+    import_pkg_from_pkg(&ctx, &pkg, metadata.name(), None).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "import_workspace",
+        serde_json::json!({
+                    "pkg_name": metadata.name(),
+                    "size": upload.size(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ImportWorkspaceResponse {
+        change_set,
+        checksum: upload.checksum(),
+    }))
+}
@@ -0,0 +1,56 @@
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use dal::error_category::categorize;
+use dal::{TransactionsError, WorkspaceSettingsError as DalWorkspaceSettingsError};
+use thiserror::Error;
+
+use crate::server::api_error::{ApiError, ApiErrorCode};
+use crate::server::state::AppState;
+
+pub mod get_workspace_settings;
+pub mod update_workspace_settings;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WorkspaceSettingsError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    WorkspaceSettings(#[from] DalWorkspaceSettingsError),
+}
+
+pub type WorkspaceSettingsResult<T> = std::result::Result<T, WorkspaceSettingsError>;
+
+impl From<WorkspaceSettingsError> for ApiError {
+    fn from(err: WorkspaceSettingsError) -> Self {
+        let code = match &err {
+            WorkspaceSettingsError::WorkspaceSettings(
+                DalWorkspaceSettingsError::InvalidValue(..)
+                | DalWorkspaceSettingsError::UnknownKey(_),
+            ) => ApiErrorCode::Validation,
+            _ => categorize(&err).into(),
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for WorkspaceSettingsError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+/// Reading the settings requires the `workspace_settings:read` scope of API tokens, and updating
+/// them the `workspace_settings:write` scope.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/get_workspace_settings",
+            get(get_workspace_settings::get_workspace_settings),
+        )
+        .route(
+            "/update_workspace_settings",
+            post(update_workspace_settings::update_workspace_settings),
+        )
+}
@@ -0,0 +1,32 @@
+use axum::Json;
+use dal::WorkspaceSettings;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::WorkspaceSettingsResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetWorkspaceSettingsResponse {
+    /// Every setting of the workspace, including the ones it left to their default.
+    #[schema(value_type = Object)]
+    pub settings: WorkspaceSettings,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/workspace_settings/get_workspace_settings",
+    responses((status = 200, body = GetWorkspaceSettingsResponse)),
+    tag = "workspace_settings"
+)]
+pub async fn get_workspace_settings(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> WorkspaceSettingsResult<Json<GetWorkspaceSettingsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let settings = ctx.workspace_settings().await?;
+
+    Ok(Json(GetWorkspaceSettingsResponse { settings }))
+}
@@ -0,0 +1,48 @@
+use axum::Json;
+use dal::WorkspaceSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+use super::WorkspaceSettingsResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceSettingsRequest {
+    /// The settings to change, by key. A `null` value resets a setting to its default, and
+    /// settings left out are not changed.
+    #[schema(value_type = Object)]
+    pub settings: Map<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceSettingsResponse {
+    /// Every setting of the workspace, once updated.
+    #[schema(value_type = Object)]
+    pub settings: WorkspaceSettings,
+}
+
+/// Updates the settings of the workspace. Every change is validated before any of them is stored,
+/// so a request with an unknown key or an invalid value changes nothing.
+#[utoipa::path(
+    post,
+    path = "/api/workspace_settings/update_workspace_settings",
+    request_body = UpdateWorkspaceSettingsRequest,
+    responses((status = 200, body = UpdateWorkspaceSettingsResponse)),
+    tag = "workspace_settings"
+)]
+pub async fn update_workspace_settings(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<UpdateWorkspaceSettingsRequest>,
+) -> WorkspaceSettingsResult<Json<UpdateWorkspaceSettingsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let settings = WorkspaceSettings::update(&ctx, &request.settings).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateWorkspaceSettingsResponse { settings }))
+}
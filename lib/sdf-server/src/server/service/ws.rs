@@ -18,6 +18,9 @@ pub enum WsError {
     Transactions(#[from] TransactionsError),
 }
 
+pub mod credentials;
+pub mod outbound;
+pub mod presence;
 pub mod workspace_updates;
 
 impl IntoResponse for WsError {
@@ -37,8 +40,10 @@ impl IntoResponse for WsError {
 }
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route(
-        "/workspace_updates",
-        get(workspace_updates::workspace_updates),
-    )
+    Router::new()
+        .route(
+            "/workspace_updates",
+            get(workspace_updates::workspace_updates),
+        )
+        .route("/presence", get(presence::list_presence))
 }
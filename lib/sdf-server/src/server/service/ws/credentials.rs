@@ -0,0 +1,140 @@
+//! This module scopes the NATS connection of every workspace updates websocket to the subjects of
+//! its workspace.
+//!
+//! When sdf is configured with the seed of a NATS account, each session connects to NATS as a user
+//! of its own, whose credentials only allow it to subscribe to the events of its workspace and to
+//! publish the presence of its user there. The NATS server enforces these permissions, so a
+//! session can't reach the events of another workspace even through a bug of the bridge. Without
+//! an account, sessions share the connection of sdf.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use dal::{UserPk, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use si_data_nats::{
+    credentials::{CredentialsError, CredentialsIssuer, NatsPermissions},
+    EventSubject, NatsClient, NatsConfig, NatsError, SubjectError,
+};
+use si_settings::{require_non_empty, require_non_zero, SettingsError};
+use thiserror::Error;
+
+use super::presence::presence_subject;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WsNatsCredentialsError {
+    #[error("error issuing nats credentials: {0}")]
+    Credentials(#[from] CredentialsError),
+    #[error("error reading nats account seed from {1}: {0}")]
+    Io(#[source] std::io::Error, PathBuf),
+    #[error("error connecting to nats with scoped credentials: {0}")]
+    Nats(#[from] NatsError),
+    #[error("nats subject error: {0}")]
+    Subject(#[from] SubjectError),
+}
+
+/// The account which issues the credentials of workspace updates websockets.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct WsNatsCredentialsConfig {
+    /// The file holding the seed of the account, which the NATS server must trust.
+    pub account_seed_path: String,
+    /// How long the credentials of a session are valid, in seconds. A session outliving its
+    /// credentials is disconnected by the NATS server, and the client reconnects.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+impl WsNatsCredentialsConfig {
+    /// Checks the account is set, naming the offending setting under `key` otherwise.
+    pub fn validate(&self, key: &str) -> Result<(), SettingsError> {
+        require_non_empty(&format!("{key}.account_seed_path"), &self.account_seed_path)?;
+        require_non_zero(&format!("{key}.ttl_secs"), self.ttl_secs)?;
+        Ok(())
+    }
+}
+
+fn default_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+/// Connects workspace updates websockets to NATS, with credentials scoped to their workspace when
+/// configured to.
+#[derive(Clone, Debug, Default)]
+pub struct WsNatsCredentials(Option<Arc<ScopedConnections>>);
+
+#[derive(Debug)]
+struct ScopedConnections {
+    issuer: CredentialsIssuer,
+    url: String,
+    subject_prefix: Option<String>,
+    ttl: Duration,
+}
+
+impl WsNatsCredentials {
+    /// Connects every session as its own user, issued by the given account for the given time.
+    pub fn scoped(issuer: CredentialsIssuer, nats: &NatsConfig, ttl: Duration) -> Self {
+        Self(Some(Arc::new(ScopedConnections {
+            issuer,
+            url: nats.url.clone(),
+            subject_prefix: nats.subject_prefix.clone(),
+            ttl,
+        })))
+    }
+
+    /// Reads the seed of the configured account, and scopes the sessions with it.
+    pub async fn load(
+        config: &WsNatsCredentialsConfig,
+        nats: &NatsConfig,
+    ) -> Result<Self, WsNatsCredentialsError> {
+        let seed = tokio::fs::read_to_string(&config.account_seed_path)
+            .await
+            .map_err(|err| {
+                WsNatsCredentialsError::Io(err, PathBuf::from(&config.account_seed_path))
+            })?;
+        let issuer = CredentialsIssuer::from_account_seed(&seed)?;
+        Ok(Self::scoped(
+            issuer,
+            nats,
+            Duration::from_secs(config.ttl_secs),
+        ))
+    }
+
+    /// Returns the connection of a session of the workspace, which is a new connection of a user
+    /// only allowed the subjects of the workspace when scoped, and the shared connection of sdf
+    /// otherwise.
+    pub async fn connect(
+        &self,
+        shared: &NatsClient,
+        workspace_pk: WorkspacePk,
+        user_pk: UserPk,
+    ) -> Result<NatsClient, WsNatsCredentialsError> {
+        let Some(scoped) = &self.0 else {
+            return Ok(shared.clone());
+        };
+
+        let credentials = scoped.issuer.issue(
+            &format!("sdf-ws-{workspace_pk}-{user_pk}"),
+            &workspace_permissions(workspace_pk)?,
+            scoped.ttl,
+        )?;
+        Ok(NatsClient::connect_with_options(
+            &scoped.url,
+            scoped.subject_prefix.clone(),
+            credentials.options(),
+        )
+        .await?)
+    }
+}
+
+/// The subjects a session of the workspace needs: the events of the workspace, in any change set,
+/// and the presence of its user, on the subjects presence is published on.
+pub fn workspace_permissions(workspace_pk: WorkspacePk) -> Result<NatsPermissions, SubjectError> {
+    let presence = presence_subject(workspace_pk)?;
+    Ok(NatsPermissions {
+        publish: vec![presence.publishable()?, presence.legacy_subject()],
+        subscribe: vec![EventSubject::builder()
+            .workspace(workspace_pk)
+            .build()?
+            .to_string()],
+    })
+}
@@ -0,0 +1,228 @@
+//! The bounded queue of messages waiting to be sent down a single websocket, so that a slow client
+//! cannot make sdf buffer the events of its workspace without limit.
+//!
+//! Events which only say that something changed are coalesced while they wait: a newer
+//! `AttributeValueUpdated` for the same attribute value replaces the queued one (keeping the
+//! oldest `oldValue`), and so do repeated events such as `CodeGenerated` for the same component.
+//! Presence updates may be dropped outright when the queue is full, as the next heartbeat carries
+//! the same information. Should the queue fill up with messages which can be neither coalesced
+//! nor dropped, the client is disconnected with [`SLOW_CLIENT_CLOSE_CODE`].
+
+use std::{borrow::Cow, collections::VecDeque, sync::Arc};
+
+use axum::extract::ws::CloseFrame;
+use serde_json::Value;
+use telemetry::metrics::{Counter, Gauge};
+use tokio::sync::{Mutex, Notify};
+
+static WS_OUTBOUND_QUEUE_DEPTH: Gauge = Gauge::new(
+    "sdf_ws_outbound_queue_depth",
+    "Number of messages waiting to be sent down websockets, across every connection",
+);
+static WS_OUTBOUND_MESSAGES_COALESCED: Counter = Counter::new(
+    "sdf_ws_outbound_messages_coalesced_total",
+    "Total number of websocket messages replaced by a newer message about the same thing",
+);
+static WS_OUTBOUND_MESSAGES_DROPPED: Counter = Counter::new(
+    "sdf_ws_outbound_messages_dropped_total",
+    "Total number of websocket messages dropped because their queue was full",
+);
+static WS_SLOW_CLIENT_DISCONNECTS: Counter = Counter::new(
+    "sdf_ws_slow_client_disconnects_total",
+    "Total number of websocket clients disconnected for not keeping up with their messages",
+);
+
+/// How many messages may wait to be sent down a single websocket.
+pub const OUTBOUND_QUEUE_CAPACITY: usize = 1024;
+/// The close code sent to clients which do not keep up with their messages, from the range
+/// reserved for private use.
+pub const SLOW_CLIENT_CLOSE_CODE: u16 = 4008;
+const SLOW_CLIENT_CLOSE_REASON: &str = "slow client: too many messages waiting to be sent";
+
+/// What is next to be sent down the websocket.
+#[derive(Debug)]
+pub enum Outbound {
+    Message(String),
+    Close(CloseFrame<'static>),
+}
+
+/// What became of a message pushed onto an [`OutboundQueue`].
+#[remain::sorted]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pushed {
+    /// The message replaced a queued message about the same thing.
+    Coalesced,
+    /// The queue was full and the message could be dropped.
+    Dropped,
+    /// The queue was full of messages which could be neither coalesced nor dropped. The client
+    /// has been disconnected.
+    Full,
+    Queued,
+}
+
+#[derive(Debug)]
+struct Entry {
+    coalesce_key: Option<String>,
+    droppable: bool,
+    text: String,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: VecDeque<Entry>,
+    close: Option<CloseFrame<'static>>,
+}
+
+/// The queue of messages waiting to be sent down a single websocket, shared by the task receiving
+/// them from [NATS](https://nats.io) and the task sending them.
+#[derive(Debug, Clone)]
+pub struct OutboundQueue {
+    inner: Arc<Mutex<Inner>>,
+    notify: Arc<Notify>,
+    capacity: usize,
+}
+
+impl OutboundQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::default())),
+            notify: Arc::new(Notify::new()),
+            capacity,
+        }
+    }
+
+    /// Queues a message, coalescing it with a queued message about the same thing or dropping it
+    /// if it is full. When nothing can make room for the message, every queued message is
+    /// discarded in favor of closing the websocket.
+    pub async fn push(&self, text: String) -> Pushed {
+        let (coalesce_key, droppable) = match serde_json::from_str::<Value>(&text) {
+            Ok(event) => (coalesce_key(&event), droppable(&event)),
+            Err(_) => (None, false),
+        };
+        let mut inner = self.inner.lock().await;
+        if inner.close.is_some() {
+            return Pushed::Dropped;
+        }
+
+        if let Some(key) = &coalesce_key {
+            if let Some(queued) = inner
+                .entries
+                .iter_mut()
+                .find(|entry| entry.coalesce_key.as_ref() == Some(key))
+            {
+                queued.text = coalesce(&queued.text, text);
+                WS_OUTBOUND_MESSAGES_COALESCED.increment(&[]);
+                return Pushed::Coalesced;
+            }
+        }
+
+        if inner.entries.len() >= self.capacity {
+            if droppable {
+                WS_OUTBOUND_MESSAGES_DROPPED.increment(&[]);
+                return Pushed::Dropped;
+            }
+            match inner.entries.iter().position(|entry| entry.droppable) {
+                Some(index) => {
+                    inner.entries.remove(index);
+                    WS_OUTBOUND_QUEUE_DEPTH.decrement(&[]);
+                    WS_OUTBOUND_MESSAGES_DROPPED.increment(&[]);
+                }
+                None => {
+                    WS_OUTBOUND_QUEUE_DEPTH.add(&[], -(inner.entries.len() as i64));
+                    inner.entries.clear();
+                    inner.close = Some(CloseFrame {
+                        code: SLOW_CLIENT_CLOSE_CODE,
+                        reason: Cow::Borrowed(SLOW_CLIENT_CLOSE_REASON),
+                    });
+                    WS_SLOW_CLIENT_DISCONNECTS.increment(&[]);
+                    self.notify.notify_one();
+                    return Pushed::Full;
+                }
+            }
+        }
+
+        inner.entries.push_back(Entry {
+            coalesce_key,
+            droppable,
+            text,
+        });
+        WS_OUTBOUND_QUEUE_DEPTH.increment(&[]);
+        self.notify.notify_one();
+        Pushed::Queued
+    }
+
+    /// Waits for what is next to be sent down the websocket. Once the queue asks for the websocket
+    /// to be closed, it has nothing else to send.
+    pub async fn pop(&self) -> Outbound {
+        loop {
+            {
+                let mut inner = self.inner.lock().await;
+                if let Some(entry) = inner.entries.pop_front() {
+                    WS_OUTBOUND_QUEUE_DEPTH.decrement(&[]);
+                    return Outbound::Message(entry.text);
+                }
+                if let Some(close) = &inner.close {
+                    return Outbound::Close(close.clone());
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+}
+
+impl Drop for Inner {
+    /// The messages still waiting when their websocket goes away are never sent.
+    fn drop(&mut self) {
+        WS_OUTBOUND_QUEUE_DEPTH.add(&[], -(self.entries.len() as i64));
+    }
+}
+
+/// The kind of the payload of an event, such as `"AttributeValueUpdated"`.
+fn kind(event: &Value) -> Option<&str> {
+    event.pointer("/payload/kind").and_then(Value::as_str)
+}
+
+/// Events about the same thing, of which only the latest matters, share a key.
+fn coalesce_key(event: &Value) -> Option<String> {
+    let change_set_pk = event.get("change_set_pk")?.as_str()?;
+    let kind = kind(event)?;
+    let id = match kind {
+        "AttributeValueUpdated" => event.pointer("/payload/data/attributeValueId")?.as_str()?,
+        "CodeGenerated" | "ResourceRefreshed" => {
+            event.pointer("/payload/data/componentId")?.as_str()?
+        }
+        "PresenceUpdated" => event.pointer("/payload/data/sessionId")?.as_str()?,
+        "ChangeSetWritten" | "ConfirmationsUpdated" => "",
+        _ => return None,
+    };
+    Some(format!("{change_set_pk}/{kind}/{id}"))
+}
+
+/// Presence updates can be dropped, as the next heartbeat of their session repeats them.
+fn droppable(event: &Value) -> bool {
+    kind(event) == Some("PresenceUpdated")
+}
+
+/// Returns the newer of two events about the same thing. An `AttributeValueUpdated` keeps the
+/// `oldValue` of the queued event, so that the client still sees the whole change.
+fn coalesce(queued: &str, newer: String) -> String {
+    let (Ok(queued), Ok(mut merged)) = (
+        serde_json::from_str::<Value>(queued),
+        serde_json::from_str::<Value>(&newer),
+    ) else {
+        return newer;
+    };
+    if kind(&merged) != Some("AttributeValueUpdated") {
+        return newer;
+    }
+
+    let old_value = queued
+        .pointer("/payload/data/oldValue")
+        .cloned()
+        .unwrap_or(Value::Null);
+    match merged.pointer_mut("/payload/data/oldValue") {
+        Some(value) => *value = old_value,
+        None => return newer,
+    }
+    serde_json::to_string(&merged).unwrap_or(newer)
+}
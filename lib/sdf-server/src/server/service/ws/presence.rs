@@ -0,0 +1,211 @@
+//! Presence tells the users of a workspace who else is around: which change set they are in, what
+//! they have selected and where their cursor is on the diagram.
+//!
+//! Presence is ephemeral and never persisted. Each websocket session publishes its presence on the
+//! [`presence_subject()`] of its workspace, which the workspace updates websocket already
+//! fans out to every connected client, and every sdf instance keeps the presence it sees in a
+//! [`PresenceRegistry`] to answer snapshot requests.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use dal::{ChangeSetPk, ComponentId, UserPk, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use si_data_nats::{subject::NO_BILLING_ACCOUNT, EventSubject, SubjectError};
+use tokio::sync::Mutex;
+use ulid::Ulid;
+use utoipa::ToSchema;
+
+use crate::server::extract::Authorization;
+
+/// How often a websocket session republishes its presence, so that other sdf instances know it is
+/// still there even when its user is idle.
+pub const PRESENCE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(20);
+
+/// How long presence is kept without hearing from its session. This covers sessions which went
+/// away without saying so, such as when sdf shuts down or a network partition occurs.
+const PRESENCE_TTL_SECONDS: i64 = 60;
+
+/// The event kind token of the [`EventSubject`] presence is published on.
+pub const PRESENCE_SUBJECT_KIND: &str = "presence";
+
+/// The [`EventSubject`] the presence of the users of a workspace is published on. Presence spans
+/// change sets, so it is published outside of any.
+pub fn presence_subject(workspace_pk: WorkspacePk) -> Result<EventSubject, SubjectError> {
+    EventSubject::builder()
+        .billing_account(NO_BILLING_ACCOUNT)
+        .workspace(workspace_pk)
+        .change_set(ChangeSetPk::NONE)
+        .event_kind(PRESENCE_SUBJECT_KIND)
+        .build()
+}
+
+/// A position on the diagram.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// What a client shares about itself, sent whenever its change set, selection or cursor changes.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresenceUpdate {
+    pub change_set_pk: Option<ChangeSetPk>,
+    #[serde(default)]
+    pub selected_component_ids: Vec<ComponentId>,
+    pub cursor: Option<CursorPosition>,
+}
+
+/// Messages sent by clients over the workspace updates websocket.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "data")]
+pub enum WsClientMessage {
+    PresenceUpdate(PresenceUpdate),
+}
+
+/// The presence of a single websocket session. A user with several tabs open has one per tab.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPresence {
+    pub session_id: String,
+    #[schema(value_type = String)]
+    pub user_pk: UserPk,
+    #[serde(flatten)]
+    pub update: PresenceUpdate,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl UserPresence {
+    pub fn new(user_pk: UserPk) -> Self {
+        Self {
+            session_id: Ulid::new().to_string(),
+            user_pk,
+            update: PresenceUpdate::default(),
+            last_seen_at: Utc::now(),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        Utc::now() - self.last_seen_at > chrono::Duration::seconds(PRESENCE_TTL_SECONDS)
+    }
+}
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "data")]
+pub enum PresencePayload {
+    PresenceJoined(UserPresence),
+    PresenceLeft(UserPresence),
+    PresenceUpdated(UserPresence),
+}
+
+impl PresencePayload {
+    pub fn presence(&self) -> &UserPresence {
+        match self {
+            Self::PresenceJoined(presence)
+            | Self::PresenceLeft(presence)
+            | Self::PresenceUpdated(presence) => presence,
+        }
+    }
+}
+
+/// A presence message, shaped like a [`WsEvent`](dal::WsEvent) so that clients handle it like any
+/// other event coming down the websocket.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PresenceEvent {
+    version: i64,
+    workspace_pk: WorkspacePk,
+    change_set_pk: ChangeSetPk,
+    payload: PresencePayload,
+}
+
+impl PresenceEvent {
+    pub fn new(workspace_pk: WorkspacePk, payload: PresencePayload) -> Self {
+        Self {
+            version: 1,
+            workspace_pk,
+            change_set_pk: payload
+                .presence()
+                .update
+                .change_set_pk
+                .unwrap_or(ChangeSetPk::NONE),
+            payload,
+        }
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn payload(&self) -> &PresencePayload {
+        &self.payload
+    }
+}
+
+/// The presence seen by this sdf instance, by workspace and websocket session.
+#[derive(Clone, Debug, Default)]
+pub struct PresenceRegistry(Arc<Mutex<HashMap<WorkspacePk, HashMap<String, UserPresence>>>>);
+
+impl PresenceRegistry {
+    /// Records the presence carried by an event, forgetting it if its session left.
+    pub async fn apply(&self, event: &PresenceEvent) {
+        let mut inner = self.0.lock().await;
+        let sessions = inner.entry(event.workspace_pk).or_default();
+
+        match &event.payload {
+            PresencePayload::PresenceJoined(presence)
+            | PresencePayload::PresenceUpdated(presence) => {
+                sessions.insert(presence.session_id.clone(), presence.clone());
+            }
+            PresencePayload::PresenceLeft(presence) => {
+                sessions.remove(&presence.session_id);
+            }
+        }
+
+        if sessions.is_empty() {
+            inner.remove(&event.workspace_pk);
+        }
+    }
+
+    /// Lists the presence of every live session in a workspace, pruning stale sessions.
+    pub async fn list(&self, workspace_pk: WorkspacePk) -> Vec<UserPresence> {
+        let mut inner = self.0.lock().await;
+        let Some(sessions) = inner.get_mut(&workspace_pk) else {
+            return Vec::new();
+        };
+
+        sessions.retain(|_, presence| !presence.is_stale());
+        let mut presence: Vec<UserPresence> = sessions.values().cloned().collect();
+        if presence.is_empty() {
+            inner.remove(&workspace_pk);
+        }
+        presence.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+
+        presence
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPresenceResponse {
+    pub presence: Vec<UserPresence>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ws/presence",
+    responses((status = 200, body = ListPresenceResponse)),
+    tag = "ws"
+)]
+pub async fn list_presence(
+    Authorization(claim): Authorization,
+    State(presence_registry): State<PresenceRegistry>,
+) -> Json<ListPresenceResponse> {
+    let presence = presence_registry.list(claim.workspace_pk).await;
+
+    Json(ListPresenceResponse { presence })
+}
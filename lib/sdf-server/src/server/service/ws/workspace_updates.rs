@@ -3,32 +3,38 @@ use axum::{
     extract::{ws::WebSocket, State, WebSocketUpgrade},
     response::IntoResponse,
 };
-use dal::WorkspacePk;
+use dal::UserClaim;
 use si_data_nats::NatsClient;
 use telemetry::prelude::*;
 use tokio::sync::broadcast;
 
+use super::credentials::WsNatsCredentials;
+use super::presence::PresenceRegistry;
 use crate::server::{
     extract::{Nats, WsAuthorization},
     state::ShutdownBroadcast,
 };
 
-#[instrument(skip(wsu, nats))]
+#[instrument(skip(wsu, nats, presence_registry, ws_nats_credentials))]
 #[allow(clippy::unused_async)]
 pub async fn workspace_updates(
     wsu: WebSocketUpgrade,
     Nats(nats): Nats,
     WsAuthorization(claim): WsAuthorization,
     State(shutdown_broadcast): State<ShutdownBroadcast>,
+    State(presence_registry): State<PresenceRegistry>,
+    State(ws_nats_credentials): State<WsNatsCredentials>,
 ) -> Result<impl IntoResponse, WsError> {
     async fn handle_socket(
         socket: WebSocket,
         nats: NatsClient,
+        ws_nats_credentials: WsNatsCredentials,
+        presence_registry: PresenceRegistry,
         mut shutdown: broadcast::Receiver<()>,
-        workspace_pk: WorkspacePk,
+        claim: UserClaim,
     ) {
         tokio::select! {
-            _ = run_workspace_updates_proto(socket, nats, workspace_pk) => {
+            _ = run_workspace_updates_proto(socket, nats, ws_nats_credentials, presence_registry, claim) => {
                 trace!("finished workspace_updates proto");
             }
             _ = shutdown.recv() => {
@@ -41,15 +47,29 @@ pub async fn workspace_updates(
     }
 
     let shutdown = shutdown_broadcast.subscribe();
-    Ok(wsu.on_upgrade(move |socket| handle_socket(socket, nats, shutdown, claim.workspace_pk)))
+    Ok(wsu.on_upgrade(move |socket| {
+        handle_socket(
+            socket,
+            nats,
+            ws_nats_credentials,
+            presence_registry,
+            shutdown,
+            claim,
+        )
+    }))
 }
 
 async fn run_workspace_updates_proto(
     mut socket: WebSocket,
     nats: NatsClient,
-    workspace_pk: WorkspacePk,
+    ws_nats_credentials: WsNatsCredentials,
+    presence_registry: PresenceRegistry,
+    claim: UserClaim,
 ) {
-    let proto = match workspace_updates::run(nats, workspace_pk).start().await {
+    let proto = match workspace_updates::run(nats, ws_nats_credentials, presence_registry, claim)
+        .start()
+        .await
+    {
         Ok(started) => started,
         Err(err) => {
             // This is likely due to nats failing to subscribe to the required topic, which is
@@ -74,18 +94,41 @@ async fn run_workspace_updates_proto(
 }
 
 mod workspace_updates {
-    use std::error::Error;
+    use std::{error::Error, time::Duration};
 
     use axum::extract::ws::{self, WebSocket};
-    use dal::WorkspacePk;
-    use futures::TryStreamExt;
-    use si_data_nats::{NatsClient, NatsError, Subscription};
+    use chrono::Utc;
+    use dal::{UserClaim, WorkspacePk};
+    use futures::{Sink, SinkExt, StreamExt, TryStreamExt};
+    use si_data_nats::{
+        EventSubject, NatsClient, NatsError, SubjectError, SubjectToken, Subscription,
+    };
     use telemetry::prelude::*;
     use thiserror::Error;
     use tokio_tungstenite::tungstenite;
 
-    pub fn run(nats: NatsClient, workspace_pk: WorkspacePk) -> WorkspaceUpdates {
-        WorkspaceUpdates { nats, workspace_pk }
+    use super::super::credentials::{WsNatsCredentials, WsNatsCredentialsError};
+    use super::super::outbound::{Outbound, OutboundQueue, Pushed, OUTBOUND_QUEUE_CAPACITY};
+    use super::super::presence::{
+        presence_subject, PresenceEvent, PresencePayload, PresenceRegistry, UserPresence,
+        WsClientMessage, PRESENCE_HEARTBEAT_INTERVAL, PRESENCE_SUBJECT_KIND,
+    };
+
+    /// How long a slow client is given to take the frame closing its websocket.
+    const SLOW_CLIENT_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn run(
+        nats: NatsClient,
+        ws_nats_credentials: WsNatsCredentials,
+        presence_registry: PresenceRegistry,
+        claim: UserClaim,
+    ) -> WorkspaceUpdates {
+        WorkspaceUpdates {
+            nats,
+            ws_nats_credentials,
+            presence_registry,
+            claim,
+        }
     }
 
     #[remain::sorted]
@@ -93,8 +136,16 @@ mod workspace_updates {
     pub enum WorkspaceUpdatesError {
         #[error("axum error: {0}")]
         Axum(#[from] axum::Error),
+        #[error(transparent)]
+        NatsCredentials(#[from] WsNatsCredentialsError),
         #[error("error processing nats message from subscription")]
         NatsIo(#[source] NatsError),
+        #[error("failed to publish presence to subject {1}")]
+        PresencePublish(#[source] NatsError, String),
+        #[error("error serializing presence: {0}")]
+        SerdeJson(#[from] serde_json::Error),
+        #[error("nats subject error: {0}")]
+        Subject(#[from] SubjectError),
         #[error("failed to subscribe to subject {1}")]
         Subscribe(#[source] NatsError, String),
         #[error("error when closing websocket")]
@@ -108,35 +159,89 @@ mod workspace_updates {
     #[derive(Debug)]
     pub struct WorkspaceUpdates {
         nats: NatsClient,
-        workspace_pk: WorkspacePk,
+        ws_nats_credentials: WsNatsCredentials,
+        presence_registry: PresenceRegistry,
+        claim: UserClaim,
     }
 
     impl WorkspaceUpdates {
         pub async fn start(self) -> Result<WorkspaceUpdatesStarted> {
-            let subject = format!("si.workspace_pk.{}.>", self.workspace_pk);
-            let subscription = self
-                .nats
+            let workspace_pk = self.claim.workspace_pk;
+            let nats = self
+                .ws_nats_credentials
+                .connect(&self.nats, workspace_pk, self.claim.user_pk)
+                .await?;
+            // Every event of the workspace, in any change set
+            let subject = EventSubject::builder()
+                .workspace(workspace_pk)
+                .build()?
+                .to_string();
+            let subscription = nats
                 .subscribe(&subject)
                 .await
                 .map_err(|err| WorkspaceUpdatesError::Subscribe(err, subject))?;
 
-            Ok(WorkspaceUpdatesStarted { subscription })
+            let started = WorkspaceUpdatesStarted {
+                subscription,
+                nats,
+                presence_registry: self.presence_registry,
+                workspace_pk,
+                workspace_token: SubjectToken::value(workspace_pk)?,
+                presence: UserPresence::new(self.claim.user_pk),
+            };
+            started
+                .publish_presence(PresencePayload::PresenceJoined(started.presence.clone()))
+                .await?;
+
+            Ok(started)
         }
     }
 
     #[derive(Debug)]
     pub struct WorkspaceUpdatesStarted {
         subscription: Subscription,
+        nats: NatsClient,
+        presence_registry: PresenceRegistry,
+        workspace_pk: WorkspacePk,
+        workspace_token: SubjectToken,
+        presence: UserPresence,
     }
 
     impl WorkspaceUpdatesStarted {
         pub async fn process(mut self, ws: &mut WebSocket) -> Result<WorkspaceUpdatesClosing> {
+            let result = self.forward(ws).await;
+
+            // However the session ended, let everyone know that we are gone. Should this fail,
+            // the presence of this session will expire on its own.
+            if let Err(err) = self
+                .publish_presence(PresencePayload::PresenceLeft(self.presence.clone()))
+                .await
+            {
+                warn!(error = ?err, "failed to publish presence departure");
+            }
+
+            result
+        }
+
+        async fn forward(&mut self, ws: &mut WebSocket) -> Result<WorkspaceUpdatesClosing> {
+            let mut heartbeat = tokio::time::interval(PRESENCE_HEARTBEAT_INTERVAL);
+            // The first tick completes immediately, and we have just announced ourselves
+            heartbeat.tick().await;
+
+            // Messages wait in a bounded queue rather than in the nats subscription, so that a
+            // slow client is noticed instead of buffering the events of its workspace forever
+            let (mut sink, mut stream) = ws.split();
+            let queue = OutboundQueue::new(OUTBOUND_QUEUE_CAPACITY);
+            let send = send_outbound(&mut sink, queue.clone());
+            tokio::pin!(send);
+
             // Send all messages down the WebSocket until and unless an error is encountered, the
             // client websocket connection is closed, or the nats subscription naturally closes
             loop {
                 tokio::select! {
-                    msg = ws.recv() => {
+                    msg = stream.next() => {
                         match msg {
+                            Some(Ok(ws::Message::Text(text))) => self.handle_client_message(&text).await?,
                             Some(Ok(_)) => {},
                             Some(Err(err)) => {
                                 self.subscription.shutdown();
@@ -148,27 +253,51 @@ mod workspace_updates {
                             }
                         }
                     }
+                    _ = heartbeat.tick() => {
+                        self.presence.last_seen_at = Utc::now();
+                        self.publish_presence(PresencePayload::PresenceUpdated(self.presence.clone())).await?;
+                    }
+                    // Sending only stops once the websocket is closed or fails
+                    sent = &mut send => {
+                        self.subscription.shutdown();
+                        return sent;
+                    }
                     nats_msg = self.subscription.try_next() => {
                         if let Some(nats_msg) = nats_msg.map_err(WorkspaceUpdatesError::NatsIo)? {
-                            let msg = ws::Message::Text(String::from_utf8_lossy(nats_msg.data()).to_string());
-
-                            if let Err(err) = ws.send(msg).await {
-                                match err
-                                    .source()
-                                    .and_then(|err| err.downcast_ref::<tungstenite::Error>())
-                                {
-                                    Some(ws_err) => match ws_err {
-                                        // If the websocket has cleanly closed, we should cleanly finish as
-                                        // well--this is not an error condition
-                                        tungstenite::Error::ConnectionClosed
-                                        | tungstenite::Error::AlreadyClosed => {
-                                            trace!("websocket has cleanly closed, ending");
-                                            return Ok(WorkspaceUpdatesClosing { ws_is_closed: true });
-                                        }
-                                        _ => return Err(WorkspaceUpdatesError::WsSendIo(err)),
-                                    },
-                                    None => return Err(WorkspaceUpdatesError::WsSendIo(err)),
-                                }
+                            let subject = nats_msg.subject().parse::<EventSubject>().ok();
+                            // Never forward a message from outside the workspace of the session,
+                            // whatever its subscription let through
+                            let workspace = subject.as_ref().map(EventSubject::workspace);
+                            if workspace != Some(&self.workspace_token) {
+                                warn!(
+                                    workspace_pk = %self.workspace_pk,
+                                    subject = nats_msg.subject(),
+                                    "dropping message from outside of the workspace",
+                                );
+                                continue;
+                            }
+                            let is_presence = subject.map_or(false, |subject| {
+                                subject.event_kind().as_value() == Some(PRESENCE_SUBJECT_KIND)
+                            });
+                            if is_presence && !self.track_presence(nats_msg.data()).await {
+                                // Clients do not need to hear about their own presence
+                                continue;
+                            }
+
+                            let text = String::from_utf8_lossy(nats_msg.data()).to_string();
+                            if queue.push(text).await == Pushed::Full {
+                                warn!(
+                                    workspace_pk = %self.workspace_pk,
+                                    session_id = %self.presence.session_id,
+                                    "disconnecting slow websocket client",
+                                );
+                                self.subscription.shutdown();
+                                // The queue now only holds the close frame, which the client gets
+                                // a moment to take
+                                return match tokio::time::timeout(SLOW_CLIENT_CLOSE_TIMEOUT, send).await {
+                                    Ok(sent) => sent,
+                                    Err(_) => Ok(WorkspaceUpdatesClosing { ws_is_closed: true }),
+                                };
                             }
                         } else {
                             break;
@@ -181,6 +310,87 @@ mod workspace_updates {
                 ws_is_closed: false,
             })
         }
+
+        async fn handle_client_message(&mut self, text: &str) -> Result<()> {
+            match serde_json::from_str::<WsClientMessage>(text) {
+                Ok(WsClientMessage::PresenceUpdate(update)) => {
+                    self.presence.update = update;
+                    self.presence.last_seen_at = Utc::now();
+                    self.publish_presence(PresencePayload::PresenceUpdated(self.presence.clone()))
+                        .await?;
+                }
+                Err(err) => {
+                    // A misbehaving client should not end its own session
+                    debug!(error = ?err, "ignoring unrecognized websocket message");
+                }
+            }
+            Ok(())
+        }
+
+        /// Records presence seen on the subscription, which includes presence published by other
+        /// sdf instances. Returns whether it should be forwarded to the client.
+        async fn track_presence(&self, data: &[u8]) -> bool {
+            match serde_json::from_slice::<PresenceEvent>(data) {
+                Ok(event) => {
+                    self.presence_registry.apply(&event).await;
+                    event.payload().presence().session_id != self.presence.session_id
+                }
+                Err(err) => {
+                    warn!(error = ?err, "failed to deserialize presence event");
+                    true
+                }
+            }
+        }
+
+        async fn publish_presence(&self, payload: PresencePayload) -> Result<()> {
+            let event = PresenceEvent::new(self.workspace_pk, payload);
+            // Record our own presence right away rather than waiting for it to come back around
+            self.presence_registry.apply(&event).await;
+
+            let subject = presence_subject(self.workspace_pk)?;
+            self.nats
+                .publish_event(&subject, serde_json::to_vec(&event)?)
+                .await
+                .map_err(|err| WorkspaceUpdatesError::PresencePublish(err, subject.to_string()))
+        }
+    }
+
+    /// Sends the messages of the queue down the websocket until the queue asks for it to be
+    /// closed, or it fails.
+    async fn send_outbound(
+        sink: &mut (impl Sink<ws::Message, Error = axum::Error> + Unpin),
+        queue: OutboundQueue,
+    ) -> Result<WorkspaceUpdatesClosing> {
+        loop {
+            let msg = match queue.pop().await {
+                Outbound::Message(text) => ws::Message::Text(text),
+                Outbound::Close(frame) => {
+                    sink.send(ws::Message::Close(Some(frame)))
+                        .await
+                        .map_err(WorkspaceUpdatesError::WsClose)?;
+                    return Ok(WorkspaceUpdatesClosing { ws_is_closed: true });
+                }
+            };
+
+            if let Err(err) = sink.send(msg).await {
+                match err
+                    .source()
+                    .and_then(|err| err.downcast_ref::<tungstenite::Error>())
+                {
+                    Some(ws_err) => match ws_err {
+                        // If the websocket has cleanly closed, we should cleanly finish as
+                        // well--this is not an error condition
+                        tungstenite::Error::ConnectionClosed
+                        | tungstenite::Error::AlreadyClosed => {
+                            trace!("websocket has cleanly closed, ending");
+                            return Ok(WorkspaceUpdatesClosing { ws_is_closed: true });
+                        }
+                        _ => return Err(WorkspaceUpdatesError::WsSendIo(err)),
+                    },
+                    None => return Err(WorkspaceUpdatesError::WsSendIo(err)),
+                }
+            }
+        }
     }
 
     #[derive(Debug)]
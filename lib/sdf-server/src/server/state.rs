@@ -1,11 +1,22 @@
-use std::{ops::Deref, sync::Arc};
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use axum::extract::FromRef;
-use dal::JwtPublicSigningKey;
+use chrono::{DateTime, Utc};
+use dal::{ChangeSetPk, ComponentId, JwtPublicSigningKey, PropId, UserPk, WorkspacePk};
+use serde_json::Value;
 use si_std::SensitiveString;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::{broadcast, mpsc, Mutex};
 
 use super::server::ShutdownSource;
+use super::service::graphql::{self, GraphqlSchema};
+use super::service::ws::credentials::WsNatsCredentials;
+use super::service::ws::presence::PresenceRegistry;
+use super::upload::BodyLimitsConfig;
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
@@ -14,6 +25,12 @@ pub struct AppState {
     jwt_public_signing_key: JwtPublicSigningKey,
     posthog_client: PosthogClient,
     shutdown_broadcast: ShutdownBroadcast,
+    session_revocations: SessionRevocationCache,
+    prop_suggestions: PropSuggestionCache,
+    presence_registry: PresenceRegistry,
+    ws_nats_credentials: WsNatsCredentials,
+    graphql_schema: GraphqlSchema,
+    body_limits: BodyLimitsConfig,
     for_tests: bool,
 
     // TODO(fnichol): we're likely going to use this, but we can't allow it to be dropped because
@@ -23,6 +40,7 @@ pub struct AppState {
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         services_context: impl Into<ServicesContext>,
         signup_secret: impl Into<SignupSecret>,
@@ -30,6 +48,8 @@ impl AppState {
         posthog_client: impl Into<PosthogClient>,
         shutdown_broadcast_tx: broadcast::Sender<()>,
         tmp_shutdown_tx: mpsc::Sender<ShutdownSource>,
+        body_limits: BodyLimitsConfig,
+        ws_nats_credentials: WsNatsCredentials,
         for_tests: bool,
     ) -> Self {
         Self {
@@ -38,6 +58,12 @@ impl AppState {
             jwt_public_signing_key: jwt_public_signing_key.into(),
             posthog_client: posthog_client.into(),
             shutdown_broadcast: ShutdownBroadcast(shutdown_broadcast_tx),
+            session_revocations: SessionRevocationCache::default(),
+            prop_suggestions: PropSuggestionCache::default(),
+            presence_registry: PresenceRegistry::default(),
+            ws_nats_credentials,
+            graphql_schema: graphql::schema(),
+            body_limits,
             for_tests,
             _tmp_shutdown_tx: Arc::new(tmp_shutdown_tx),
         }
@@ -55,6 +81,26 @@ impl AppState {
         &self.jwt_public_signing_key
     }
 
+    pub fn session_revocations(&self) -> &SessionRevocationCache {
+        &self.session_revocations
+    }
+
+    pub fn prop_suggestions(&self) -> &PropSuggestionCache {
+        &self.prop_suggestions
+    }
+
+    pub fn presence_registry(&self) -> &PresenceRegistry {
+        &self.presence_registry
+    }
+
+    pub fn graphql_schema(&self) -> &GraphqlSchema {
+        &self.graphql_schema
+    }
+
+    pub fn body_limits(&self) -> &BodyLimitsConfig {
+        &self.body_limits
+    }
+
     pub fn for_tests(&self) -> bool {
         self.for_tests
     }
@@ -144,3 +190,167 @@ impl From<broadcast::Sender<()>> for ShutdownBroadcast {
         Self(value)
     }
 }
+
+/// The maximum number of users whose session revocation state is kept in memory.
+const SESSION_REVOCATION_CACHE_CAPACITY: usize = 4096;
+
+/// How long a cached session revocation lookup is trusted before the database is consulted again.
+/// This bounds how long a revocation made by another sdf instance can take to be honored.
+const SESSION_REVOCATION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// An in-memory, least-recently-used cache of per-user session revocation times, which avoids a
+/// database lookup on every authenticated request.
+#[derive(Clone, Debug, Default)]
+pub struct SessionRevocationCache(Arc<Mutex<SessionRevocationCacheInner>>);
+
+#[derive(Debug, Default)]
+struct SessionRevocationCacheInner {
+    entries: HashMap<UserPk, SessionRevocationCacheEntry>,
+    clock: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SessionRevocationCacheEntry {
+    revoked_before: Option<DateTime<Utc>>,
+    fetched_at: Instant,
+    last_used: u64,
+}
+
+impl SessionRevocationCache {
+    /// Returns the cached revocation time for a user, or `None` if there is no fresh entry.
+    pub async fn get(&self, user_pk: UserPk) -> Option<Option<DateTime<Utc>>> {
+        let mut inner = self.0.lock().await;
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        match inner.entries.get_mut(&user_pk) {
+            Some(entry) if entry.fetched_at.elapsed() < SESSION_REVOCATION_CACHE_TTL => {
+                entry.last_used = clock;
+                Some(entry.revoked_before)
+            }
+            Some(_) => {
+                inner.entries.remove(&user_pk);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches the revocation time for a user, evicting the least recently used entry if the cache
+    /// is full.
+    pub async fn insert(&self, user_pk: UserPk, revoked_before: Option<DateTime<Utc>>) {
+        let mut inner = self.0.lock().await;
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        if inner.entries.len() >= SESSION_REVOCATION_CACHE_CAPACITY
+            && !inner.entries.contains_key(&user_pk)
+        {
+            if let Some(lru_user_pk) = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(user_pk, _)| *user_pk)
+            {
+                inner.entries.remove(&lru_user_pk);
+            }
+        }
+
+        inner.entries.insert(
+            user_pk,
+            SessionRevocationCacheEntry {
+                revoked_before,
+                fetched_at: Instant::now(),
+                last_used: clock,
+            },
+        );
+    }
+}
+
+/// The maximum number of props whose suggested candidates are kept in memory.
+const PROP_SUGGESTION_CACHE_CAPACITY: usize = 1024;
+
+/// How long the candidates suggested for a prop are reused before its suggestion funcs are
+/// executed again. Long enough to answer every keystroke of a user typing into a field, short
+/// enough that candidates which depend on other properties of the component catch up quickly.
+const PROP_SUGGESTION_CACHE_TTL: Duration = Duration::from_secs(15);
+
+/// The component and prop candidates were suggested for, in the workspace and change set they
+/// were suggested in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PropSuggestionCacheKey {
+    pub workspace_pk: Option<WorkspacePk>,
+    pub change_set_pk: ChangeSetPk,
+    pub component_id: ComponentId,
+    pub prop_id: PropId,
+}
+
+/// An in-memory, least-recently-used cache of the candidates suggested for props, which lets
+/// clients ask for suggestions as the user types without executing the suggestion funcs again for
+/// every keystroke. The candidates are filtered by prefix on every request.
+#[derive(Clone, Debug, Default)]
+pub struct PropSuggestionCache(Arc<Mutex<PropSuggestionCacheInner>>);
+
+#[derive(Debug, Default)]
+struct PropSuggestionCacheInner {
+    entries: HashMap<PropSuggestionCacheKey, PropSuggestionCacheEntry>,
+    clock: u64,
+}
+
+#[derive(Clone, Debug)]
+struct PropSuggestionCacheEntry {
+    candidates: Arc<Vec<Value>>,
+    fetched_at: Instant,
+    last_used: u64,
+}
+
+impl PropSuggestionCache {
+    /// Returns the cached candidates for a prop, or `None` if there is no fresh entry.
+    pub async fn get(&self, key: &PropSuggestionCacheKey) -> Option<Arc<Vec<Value>>> {
+        let mut inner = self.0.lock().await;
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        match inner.entries.get_mut(key) {
+            Some(entry) if entry.fetched_at.elapsed() < PROP_SUGGESTION_CACHE_TTL => {
+                entry.last_used = clock;
+                Some(entry.candidates.clone())
+            }
+            Some(_) => {
+                inner.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches the candidates for a prop, evicting the least recently used entry if the cache is
+    /// full.
+    pub async fn insert(&self, key: PropSuggestionCacheKey, candidates: Arc<Vec<Value>>) {
+        let mut inner = self.0.lock().await;
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        if inner.entries.len() >= PROP_SUGGESTION_CACHE_CAPACITY
+            && !inner.entries.contains_key(&key)
+        {
+            if let Some(lru_key) = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+            {
+                inner.entries.remove(&lru_key);
+            }
+        }
+
+        inner.entries.insert(
+            key,
+            PropSuggestionCacheEntry {
+                candidates,
+                fetched_at: Instant::now(),
+                last_used: clock,
+            },
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Limits on the size of request bodies, and the [`Upload`] extractor which receives large files
+//! without buffering them in memory.
+//!
+//! Most endpoints buffer their bodies, which are limited to
+//! [`max_body_bytes`](BodyLimitsConfig::max_body_bytes). Upload endpoints instead take a
+//! `multipart/form-data` body with a `file` field, which is streamed to a temporary file as it is
+//! received and is limited to [`max_upload_bytes`](BodyLimitsConfig::max_upload_bytes). An
+//! optional `checksum` field holds the hex encoded blake3 hash of the file, which is checked once
+//! the file is received.
+
+use std::path::{Path, PathBuf};
+
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{
+        multipart::{Field, MultipartError},
+        rejection::MultipartRejection,
+        FromRequest, Multipart,
+    },
+    http::Request,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use si_settings::{require_non_zero, SettingsError};
+use tempfile::TempPath;
+use thiserror::Error;
+use tokio::{fs::File, io::AsyncWriteExt};
+use utoipa::ToSchema;
+
+use super::api_error::{ApiError, ApiErrorCode};
+use super::state::AppState;
+
+/// The name of the multipart field holding the uploaded file.
+const FILE_FIELD: &str = "file";
+/// The name of the multipart field holding the expected checksum of the file.
+const CHECKSUM_FIELD: &str = "checksum";
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum UploadError {
+    #[error("checksum mismatch: expected {expected}, received a file hashing to {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("invalid checksum: {0}")]
+    InvalidChecksum(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("multipart error: {0}")]
+    Multipart(#[from] MultipartError),
+    #[error(transparent)]
+    MultipartRejection(#[from] MultipartRejection),
+    #[error("no `{FILE_FIELD}` field in upload")]
+    NoFile,
+    #[error("upload is larger than the limit of {0} bytes")]
+    TooLarge(u64),
+    #[error("unexpected field in upload: {0}")]
+    UnexpectedField(String),
+}
+
+impl From<UploadError> for ApiError {
+    fn from(err: UploadError) -> Self {
+        let code = match &err {
+            UploadError::TooLarge(_) => ApiErrorCode::PayloadTooLarge,
+            UploadError::ChecksumMismatch { .. }
+            | UploadError::InvalidChecksum(_)
+            | UploadError::Multipart(_)
+            | UploadError::MultipartRejection(_)
+            | UploadError::NoFile
+            | UploadError::UnexpectedField(_) => ApiErrorCode::Validation,
+            UploadError::Io(_) => ApiErrorCode::Internal,
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl IntoResponse for UploadError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+pub type UploadResult<T> = Result<T, UploadError>;
+
+/// The limits on the size of request bodies.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct BodyLimitsConfig {
+    /// The largest body accepted by endpoints which buffer their body, such as JSON endpoints.
+    pub max_body_bytes: usize,
+    /// The largest file accepted by upload endpoints.
+    pub max_upload_bytes: u64,
+    /// Where uploads are spooled while they are received, or the system temporary directory if
+    /// unset.
+    pub spool_dir: Option<PathBuf>,
+}
+
+impl Default for BodyLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 2 * 1024 * 1024,
+            max_upload_bytes: 512 * 1024 * 1024,
+            spool_dir: None,
+        }
+    }
+}
+
+impl BodyLimitsConfig {
+    /// Checks that both limits allow a body through, naming the offending setting under `key`
+    /// otherwise.
+    pub fn validate(&self, key: &str) -> Result<(), SettingsError> {
+        require_non_zero(&format!("{key}.max_body_bytes"), self.max_body_bytes)?;
+        require_non_zero(&format!("{key}.max_upload_bytes"), self.max_upload_bytes)?;
+        Ok(())
+    }
+}
+
+/// The form taken by upload endpoints, as described in the API specification.
+#[derive(ToSchema)]
+pub struct UploadForm {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    /// The hex encoded blake3 hash of the file.
+    pub checksum: Option<String>,
+}
+
+/// A file received by an upload endpoint. The file is spooled to a temporary file, which is
+/// removed when the upload is dropped.
+#[derive(Debug)]
+pub struct Upload {
+    file_name: Option<String>,
+    path: TempPath,
+    size: u64,
+    checksum: blake3::Hash,
+}
+
+impl Upload {
+    /// The name of the file on the client, if the client sent one.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// The temporary file holding the upload.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The hex encoded blake3 hash of the upload.
+    pub fn checksum(&self) -> String {
+        self.checksum.to_hex().to_string()
+    }
+
+    async fn spool(mut field: Field<'_>, limits: &BodyLimitsConfig) -> UploadResult<Self> {
+        let file_name = field.file_name().map(ToOwned::to_owned);
+        let named_file = match &limits.spool_dir {
+            Some(spool_dir) => tempfile::Builder::new()
+                .prefix("sdf-upload-")
+                .tempfile_in(spool_dir)?,
+            None => tempfile::Builder::new().prefix("sdf-upload-").tempfile()?,
+        };
+        // The path is held until the end, so that the file is removed if the upload fails.
+        let (file, path) = named_file.into_parts();
+        let mut file = File::from_std(file);
+
+        let mut size = 0;
+        let mut hasher = blake3::Hasher::new();
+        while let Some(chunk) = field.chunk().await? {
+            size += chunk.len() as u64;
+            if size > limits.max_upload_bytes {
+                return Err(UploadError::TooLarge(limits.max_upload_bytes));
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+
+        Ok(Self {
+            file_name,
+            path,
+            size,
+            checksum: hasher.finalize(),
+        })
+    }
+
+    fn verify_checksum(&self, expected: &str) -> UploadResult<()> {
+        let expected = expected.trim();
+        let expected_hash: blake3::Hash = expected
+            .parse()
+            .map_err(|_| UploadError::InvalidChecksum(expected.to_owned()))?;
+        if expected_hash != self.checksum {
+            return Err(UploadError::ChecksumMismatch {
+                expected: expected.to_owned(),
+                actual: self.checksum(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FromRequest<AppState, Body> for Upload {
+    type Rejection = UploadError;
+
+    async fn from_request(request: Request<Body>, state: &AppState) -> UploadResult<Self> {
+        let mut multipart = Multipart::from_request(request, state).await?;
+
+        let mut upload = None;
+        let mut expected_checksum = None;
+        while let Some(field) = multipart.next_field().await? {
+            match field.name() {
+                Some(FILE_FIELD) if upload.is_none() => {
+                    upload = Some(Self::spool(field, state.body_limits()).await?);
+                }
+                Some(CHECKSUM_FIELD) if expected_checksum.is_none() => {
+                    expected_checksum = Some(field.text().await?);
+                }
+                name => {
+                    return Err(UploadError::UnexpectedField(
+                        name.unwrap_or_default().to_owned(),
+                    ))
+                }
+            }
+        }
+
+        let upload = upload.ok_or(UploadError::NoFile)?;
+        if let Some(expected_checksum) = expected_checksum {
+            upload.verify_checksum(&expected_checksum)?;
+        }
+        Ok(upload)
+    }
+}
@@ -1,4 +1,9 @@
-use axum::{http::Method, Router};
+use axum::{
+    body::Body,
+    http::{self, Method, Request, StatusCode},
+    Router,
+};
+use dal::ChangeSetStatus;
 use dal_test::{
     sdf_test, test_harness::create_change_set as dal_create_change_set, AuthTokenRef,
     DalContextHead,
@@ -10,6 +15,8 @@ use sdf_server::service::change_set::{
     list_open_change_sets::ListOpenChangeSetsResponse,
 };
 
+use tower::ServiceExt;
+
 use crate::service_tests::{
     api_request_auth_empty, api_request_auth_json_body, api_request_auth_query,
 };
@@ -87,3 +94,89 @@ async fn apply_change_set(
     )
     .await;
 }
+
+#[sdf_test]
+async fn apply_change_set_twice_conflicts(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+    let request = ApplyChangeSetRequest {
+        change_set_pk: change_set.pk,
+    };
+
+    let _response: ApplyChangeSetResponse = api_request_auth_json_body(
+        app.clone(),
+        Method::POST,
+        "/api/change_set/apply_change_set",
+        auth_token,
+        &request,
+    )
+    .await;
+
+    let api_request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/change_set/apply_change_set")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(Body::from(
+            serde_json::to_vec(&request).expect("cannot turn request to json"),
+        ))
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    assert_eq!(StatusCode::CONFLICT, response.status());
+
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("response is not json");
+    assert_eq!(serde_json::json!("CONFLICT"), body["error"]["code"],);
+}
+
+#[sdf_test]
+async fn apply_change_set_retry_with_idempotency_key(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+    let request = ApplyChangeSetRequest {
+        change_set_pk: change_set.pk,
+    };
+
+    let mut responses = Vec::new();
+    for _ in 0..2 {
+        let api_request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/change_set/apply_change_set")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+            .header("Idempotency-Key", "apply-change-set-retry")
+            .body(Body::from(
+                serde_json::to_vec(&request).expect("cannot turn request to json"),
+            ))
+            .expect("cannot create api request");
+        let response = app
+            .clone()
+            .oneshot(api_request)
+            .await
+            .expect("cannot send request");
+        assert_eq!(StatusCode::OK, response.status());
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("cannot read body");
+        let body: ApplyChangeSetResponse =
+            serde_json::from_slice(&body).expect("response is not json");
+        responses.push(body);
+    }
+
+    assert_eq!(ChangeSetStatus::Applied, responses[1].change_set.status);
+    assert_eq!(
+        responses[0].change_set.timestamp.updated_at,
+        responses[1].change_set.timestamp.updated_at
+    );
+}
@@ -1,13 +1,20 @@
-use axum::Router;
-use dal::{StandardModel, Visibility};
+use axum::{
+    body::Body,
+    http::{self, Method, Request, StatusCode},
+    Router,
+};
+use dal::{ComponentId, StandardModel, Visibility};
 use dal_test::{
     sdf_test,
     test_harness::{create_component_for_schema_variant, create_schema, create_schema_variant},
     AuthTokenRef, DalContextHead,
 };
-use sdf_server::service::component::get_components_metadata::{
-    GetComponentsMetadataRequest, GetComponentsMetadataResponse,
+use sdf_server::service::component::{
+    get_components_metadata::{GetComponentsMetadataRequest, GetComponentsMetadataResponse},
+    stream_components::StreamComponentsRequest,
 };
+use sdf_server::NDJSON_CONTENT_TYPE;
+use tower::ServiceExt;
 
 use crate::service_tests::api_request_auth_query;
 
@@ -45,3 +52,84 @@ async fn get_components_metadata(
 
     assert_eq!(response.data[0].schema_name, schema.name());
 }
+
+async fn stream_components(
+    app: Router,
+    auth_token: &str,
+    request: &StreamComponentsRequest,
+) -> Vec<serde_json::Value> {
+    let params = serde_url_params::to_string(request).expect("cannot serialize params");
+    let api_request = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/component/stream_components?{params}"))
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(Body::empty())
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        Some(NDJSON_CONTENT_TYPE),
+        response
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+    );
+
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    body.split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).expect("line is not valid json"))
+        .collect()
+}
+
+#[sdf_test]
+async fn stream_components_resumes_after_cursor(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let visibility = Visibility::new_head(false);
+    let schema = create_schema(&ctx).await;
+    let mut schema_variant = create_schema_variant(&ctx, *schema.id()).await;
+    schema_variant
+        .finalize(&ctx, None)
+        .await
+        .expect("could not finalize schema variant");
+    for _ in 0..3 {
+        create_component_for_schema_variant(&ctx, schema_variant.id()).await;
+    }
+    ctx.blocking_commit()
+        .await
+        .expect("cannot commit transaction");
+
+    let lines = stream_components(
+        app.clone(),
+        auth_token,
+        &StreamComponentsRequest {
+            cursor: None,
+            visibility,
+        },
+    )
+    .await;
+    assert_eq!(3, lines.len());
+    let cursors: Vec<ComponentId> = lines
+        .iter()
+        .map(|line| serde_json::from_value(line["cursor"].clone()).expect("invalid cursor"))
+        .collect();
+    for line in &lines {
+        assert_eq!(line["cursor"], line["object"]["id"]);
+    }
+
+    let resumed = stream_components(
+        app,
+        auth_token,
+        &StreamComponentsRequest {
+            cursor: Some(cursors[0]),
+            visibility,
+        },
+    )
+    .await;
+    assert_eq!(lines[1..], resumed[..]);
+}
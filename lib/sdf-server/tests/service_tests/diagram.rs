@@ -0,0 +1,92 @@
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{node::NodeId, ChangeSet, Node, StandardModel, Visibility};
+use dal_test::{
+    sdf_test,
+    test_harness::{create_schema, create_schema_variant},
+    AuthTokenRef, DalContextHead,
+};
+use sdf_server::service::diagram::{
+    create_node::{CreateNodeRequest, CreateNodeResponse},
+    delete_nodes::DeleteNodesRequest,
+};
+
+use crate::service_tests::{api_request_auth_json_body, api_request_auth_json_body_error};
+
+#[sdf_test]
+async fn delete_nodes_without_a_selection_is_rejected(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    ctx.commit().await.expect("cannot commit txn");
+    let request = DeleteNodesRequest {
+        visibility: Visibility::new_head(false),
+        client_request_id: None,
+        node_ids: Vec::new(),
+    };
+
+    let (status, body) = api_request_auth_json_body_error(
+        app,
+        Method::POST,
+        "/api/diagram/nodes/delete",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, status);
+    assert_eq!("VALIDATION", body["error"]["code"]);
+}
+
+#[sdf_test]
+async fn create_node_reports_a_failed_frame_attach(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let mut schema = create_schema(&ctx).await;
+    let mut schema_variant = create_schema_variant(&ctx, *schema.id()).await;
+    schema_variant
+        .finalize(&ctx, None)
+        .await
+        .expect("could not finalize schema variant");
+    schema
+        .set_default_schema_variant_id(&ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None)
+        .await
+        .expect("could not create change set");
+    ctx.blocking_commit()
+        .await
+        .expect("cannot commit transaction");
+    let visibility = Visibility::new(change_set.pk, None);
+
+    // There is no such frame to attach the node to.
+    let request = CreateNodeRequest {
+        schema_id: *schema.id(),
+        parent_id: Some(NodeId::generate()),
+        x: "0".to_string(),
+        y: "0".to_string(),
+        client_request_id: None,
+        visibility,
+    };
+    let response: CreateNodeResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/diagram/create_node",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert!(response.frame_attach_failed);
+
+    // The node was still created, outside of the frame.
+    let ctx = ctx.clone_with_new_visibility(visibility);
+    let node = Node::get_by_id(&ctx, &response.node_id)
+        .await
+        .expect("could not get node");
+    assert!(node.is_some());
+}
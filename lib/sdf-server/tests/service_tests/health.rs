@@ -0,0 +1,22 @@
+use axum::{http::Method, Router};
+use dal_test::{sdf_test, AuthTokenRef};
+
+use crate::service_tests::api_request_auth_empty;
+
+#[sdf_test]
+async fn ready_reports_every_dependency(app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
+    let response: serde_json::Value =
+        api_request_auth_empty(app, Method::GET, "/health/ready", auth_token).await;
+
+    assert_eq!(
+        serde_json::json!({
+            "ok": true,
+            "dependencies": {
+                "pg": { "ok": true },
+                "nats": { "ok": true },
+                "veritech": { "ok": true },
+            },
+        }),
+        response,
+    );
+}
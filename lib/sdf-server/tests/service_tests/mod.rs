@@ -8,10 +8,17 @@ use tower::ServiceExt;
 
 mod change_set;
 mod component;
+mod diagram;
+mod health;
+mod ndjson;
+mod openapi;
+mod rate_limit;
 mod scenario;
 mod schema;
 mod secret;
 mod session;
+mod upload;
+mod ws;
 
 pub async fn api_request_auth_query<Req: Serialize, Res: DeserializeOwned>(
     app: Router,
@@ -97,6 +104,35 @@ pub async fn api_request_auth_json_body<Req: Serialize, Res: DeserializeOwned>(
     serde_json::from_value(body_json).expect("response is not a valid rust struct")
 }
 
+/// Sends a request which is expected to fail, returning the status and the error envelope of the
+/// response.
+pub async fn api_request_auth_json_body_error<Req: Serialize>(
+    app: Router,
+    method: Method,
+    uri: impl AsRef<str>,
+    auth_token: impl AsRef<str>,
+    request: &Req,
+) -> (StatusCode, serde_json::Value) {
+    let auth_token = auth_token.as_ref();
+    let uri = uri.as_ref();
+    let api_request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(Body::from(
+            serde_json::to_vec(&serde_json::json!(&request)).expect("cannot turn request to json"),
+        ))
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    let body_json = serde_json::from_slice(&body).expect("response is not valid json");
+    (status, body_json)
+}
+
 pub async fn api_request_auth_empty<Res: DeserializeOwned>(
     app: Router,
     method: Method,
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use futures::{stream, Stream, StreamExt};
+use sdf_server::{ndjson_response_with_timeouts, ApiError, ApiErrorCode, NdjsonTimeouts};
+use serde_json::json;
+use tower::ServiceExt;
+
+fn objects_app<S>(
+    objects: impl Fn() -> S + Clone + Send + Sync + 'static,
+    timeouts: NdjsonTimeouts,
+) -> Router
+where
+    S: Stream<Item = Result<serde_json::Value, ApiError>> + Send + 'static,
+{
+    Router::new().route(
+        "/objects",
+        get(move || async move {
+            ndjson_response_with_timeouts(
+                objects(),
+                |object: &serde_json::Value| object["id"].clone(),
+                timeouts,
+            )
+        }),
+    )
+}
+
+async fn get_lines(app: Router) -> Vec<serde_json::Value> {
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/objects")
+        .body(Body::empty())
+        .expect("cannot create request");
+    let response: Response = app.oneshot(request).await.expect("cannot send request");
+    assert_eq!(StatusCode::OK, response.status());
+
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    body.split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).expect("line is not valid json"))
+        .collect()
+}
+
+#[tokio::test]
+async fn error_ends_the_stream_with_an_error_line() {
+    let app = objects_app(
+        || {
+            stream::iter(vec![
+                Ok(json!({ "id": 1, "name": "mastodon" })),
+                Ok(json!({ "id": 2, "name": "gojira" })),
+                Err(ApiError::new(
+                    ApiErrorCode::Internal,
+                    "the database went away",
+                )),
+                Ok(json!({ "id": 3, "name": "baroness" })),
+            ])
+        },
+        NdjsonTimeouts::default(),
+    );
+
+    let lines = get_lines(app).await;
+    assert_eq!(3, lines.len());
+    assert_eq!(json!(1), lines[0]["cursor"]);
+    assert_eq!(json!("mastodon"), lines[0]["object"]["name"]);
+    assert_eq!(json!(2), lines[1]["cursor"]);
+    assert_eq!(json!("INTERNAL"), lines[2]["error"]["code"]);
+    assert_eq!(
+        json!("the database went away"),
+        lines[2]["error"]["message"]
+    );
+}
+
+#[tokio::test]
+async fn stalled_stream_ends_with_an_error_line() {
+    let app = objects_app(
+        || stream::iter(vec![Ok(json!({ "id": 1 }))]).chain(stream::pending()),
+        NdjsonTimeouts {
+            batch: Duration::from_millis(50),
+            stream: Duration::from_secs(30),
+        },
+    );
+
+    let lines = tokio::time::timeout(Duration::from_secs(5), get_lines(app))
+        .await
+        .expect("stream did not time out");
+    assert_eq!(2, lines.len());
+    assert_eq!(json!(1), lines[0]["cursor"]);
+    assert_eq!(json!("UNAVAILABLE"), lines[1]["error"]["code"]);
+    assert_eq!(json!(503), lines[1]["error"]["statusCode"]);
+}
+
+#[tokio::test]
+async fn stream_past_its_deadline_ends_with_an_error_line() {
+    // An object every 10ms, forever: never stalled, but never done either
+    let app = objects_app(
+        || {
+            stream::unfold(0, |id| async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Some((Ok(json!({ "id": id })), id + 1))
+            })
+        },
+        NdjsonTimeouts {
+            batch: Duration::from_secs(1),
+            stream: Duration::from_millis(200),
+        },
+    );
+
+    let lines = tokio::time::timeout(Duration::from_secs(5), get_lines(app))
+        .await
+        .expect("stream did not time out");
+    let (error, objects) = lines.split_last().expect("no lines");
+    assert!(!objects.is_empty());
+    assert_eq!(json!("UNAVAILABLE"), error["error"]["code"]);
+    for (id, line) in objects.iter().enumerate() {
+        assert_eq!(json!(id), line["cursor"]);
+    }
+}
@@ -0,0 +1,47 @@
+use std::{env, fs, path::Path};
+
+use axum::{http::Method, Router};
+use dal_test::{sdf_test, AuthTokenRef};
+use sdf_server::openapi::ApiDoc;
+use utoipa::OpenApi;
+
+use crate::service_tests::api_request_auth_empty;
+
+/// The spec checked into the repository, so that changes to the API show up in review.
+const SNAPSHOT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+#[test]
+#[allow(clippy::disallowed_methods)] // Used to update the snapshot in development
+fn spec_matches_snapshot() {
+    let spec = ApiDoc::openapi()
+        .to_pretty_json()
+        .expect("cannot serialize spec");
+
+    if env::var("SI_UPDATE_OPENAPI_SNAPSHOT").is_ok() {
+        fs::write(SNAPSHOT_PATH, format!("{spec}\n")).expect("cannot write snapshot");
+        return;
+    }
+
+    if !Path::new(SNAPSHOT_PATH).exists() {
+        panic!(
+            "no snapshot at {SNAPSHOT_PATH}, run this test with SI_UPDATE_OPENAPI_SNAPSHOT=1 to write it"
+        );
+    }
+    let snapshot = fs::read_to_string(SNAPSHOT_PATH).expect("cannot read snapshot");
+    assert_eq!(
+        snapshot.trim_end(),
+        spec,
+        "the spec changed, run this test with SI_UPDATE_OPENAPI_SNAPSHOT=1 and commit the snapshot"
+    );
+}
+
+#[sdf_test]
+async fn serves_spec(app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
+    let response: serde_json::Value =
+        api_request_auth_empty(app, Method::GET, "/openapi.json", auth_token).await;
+
+    assert_eq!(
+        serde_json::to_value(ApiDoc::openapi()).expect("cannot serialize spec"),
+        response,
+    );
+}
@@ -0,0 +1,192 @@
+use std::net::IpAddr;
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{self, HeaderMap, Method, Request, StatusCode},
+    response::Response,
+    routing::get,
+    Extension, Router,
+};
+use sdf_server::{ApiError, ClientAddr, RateLimitConfig, RateLimitLayer, TokenRateLimit};
+use tower::ServiceExt;
+
+/// Stands in for the authorization extractors, which take from the bucket of a token once it is
+/// validated. Tokens are valid here when they start with `valid_`.
+async fn authorize(
+    Extension(limit): Extension<TokenRateLimit>,
+    headers: HeaderMap,
+) -> Result<&'static str, ApiError> {
+    let token = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if token.starts_with("valid_") {
+        limit.check(token)?;
+    }
+    Ok("ok")
+}
+
+fn limited_app(config: RateLimitConfig) -> Router {
+    Router::new()
+        .route("/api/diagram/get_diagram", get(authorize))
+        .route("/api/schema/list_schemas", get(authorize))
+        .route("/health/ready", get(|| async { "ok" }))
+        .layer(RateLimitLayer::new(config))
+}
+
+fn enabled_config() -> RateLimitConfig {
+    RateLimitConfig {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+async fn get_response(app: &Router, request: http::request::Builder) -> Response {
+    let request = request
+        .method(Method::GET)
+        .body(Body::empty())
+        .expect("cannot create request");
+    app.clone()
+        .oneshot(request)
+        .await
+        .expect("cannot send request")
+}
+
+fn request(uri: &str, auth_token: &str, client_ip: &str) -> http::request::Builder {
+    let client_ip: IpAddr = client_ip.parse().expect("cannot parse client ip");
+    Request::builder()
+        .uri(uri)
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .extension(ConnectInfo(ClientAddr(Some(client_ip))))
+}
+
+async fn get_status(app: &Router, request: http::request::Builder) -> (StatusCode, Option<String>) {
+    let response = get_response(app, request).await;
+    let retry_after = response
+        .headers()
+        .get(http::header::RETRY_AFTER)
+        .map(|value| value.to_str().expect("retry-after is ascii").to_owned());
+    (response.status(), retry_after)
+}
+
+#[tokio::test]
+async fn rejects_requests_past_the_burst_of_a_token() {
+    let mut config = enabled_config();
+    config.default.per_token.burst = 2;
+    config.default.per_token.per_second = 0.01;
+    let app = limited_app(config);
+    let diagram = |token| request("/api/diagram/get_diagram", token, "203.0.113.7");
+
+    for _ in 0..2 {
+        let (status, _) = get_status(&app, diagram("valid_mastodon")).await;
+        assert_eq!(StatusCode::OK, status);
+    }
+
+    let (status, retry_after) = get_status(&app, diagram("valid_mastodon")).await;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, status);
+    let retry_after: u64 = retry_after
+        .expect("no retry-after header")
+        .parse()
+        .expect("retry-after is not a number of seconds");
+    assert!(retry_after >= 1);
+    let body = hyper::body::to_bytes(
+        get_response(&app, diagram("valid_mastodon"))
+            .await
+            .into_body(),
+    )
+    .await
+    .expect("cannot read body");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response is not valid json");
+    assert_eq!("TOO_MANY_REQUESTS", body["error"]["code"]);
+
+    // Other tokens and other route groups have buckets of their own, and only the API is limited.
+    let (status, _) = get_status(&app, diagram("valid_tusk")).await;
+    assert_eq!(StatusCode::OK, status);
+    let (status, _) = get_status(
+        &app,
+        request("/api/schema/list_schemas", "valid_mastodon", "203.0.113.7"),
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status);
+    let (status, _) = get_status(
+        &app,
+        request("/health/ready", "valid_mastodon", "203.0.113.7"),
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status);
+}
+
+#[tokio::test]
+async fn invalid_tokens_only_count_against_their_ip() {
+    let mut config = enabled_config();
+    config.default.per_token.burst = 1;
+    config.default.per_ip.burst = 3;
+    config.default.per_ip.per_second = 0.01;
+    let app = limited_app(config);
+
+    // Making up a new token for every request does not get around the bucket of the IP
+    for token in ["mammoth", "mastodon", "tusk"] {
+        let (status, _) = get_status(
+            &app,
+            request("/api/diagram/get_diagram", token, "203.0.113.7"),
+        )
+        .await;
+        assert_eq!(StatusCode::OK, status);
+    }
+    let (status, _) = get_status(
+        &app,
+        request("/api/diagram/get_diagram", "trunk", "203.0.113.7"),
+    )
+    .await;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, status);
+
+    let (status, _) = get_status(
+        &app,
+        request("/api/diagram/get_diagram", "trunk", "203.0.113.8"),
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status);
+}
+
+#[tokio::test]
+async fn client_ip_is_taken_from_the_trusted_proxy_hops() {
+    let mut config = enabled_config();
+    config.trusted_proxy_hops = 1;
+    config.default.per_ip.burst = 1;
+    config.default.per_ip.per_second = 0.01;
+    let app = limited_app(config);
+    let forwarded = |forwarded_for| {
+        request("/api/diagram/get_diagram", "mastodon", "10.0.0.1")
+            .header("x-forwarded-for", forwarded_for)
+    };
+
+    let (status, _) = get_status(&app, forwarded("198.51.100.1, 203.0.113.7")).await;
+    assert_eq!(StatusCode::OK, status);
+    // The entries left of the one the proxy appended are made up by the client
+    let (status, _) = get_status(&app, forwarded("198.51.100.2, 203.0.113.7")).await;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, status);
+    let (status, _) = get_status(&app, forwarded("198.51.100.1, 203.0.113.8")).await;
+    assert_eq!(StatusCode::OK, status);
+}
+
+#[tokio::test]
+async fn disabled_limits_are_not_enforced() {
+    let mut config = RateLimitConfig::default();
+    config.default.per_token.burst = 1;
+    config.default.per_ip.burst = 1;
+    let app = Router::new()
+        .route("/api/diagram/get_diagram", get(|| async { "ok" }))
+        .layer(RateLimitLayer::new(config));
+
+    for _ in 0..3 {
+        let (status, _) = get_status(
+            &app,
+            request("/api/diagram/get_diagram", "valid_mastodon", "203.0.113.7"),
+        )
+        .await;
+        assert_eq!(StatusCode::OK, status);
+    }
+}
@@ -23,7 +23,6 @@ use dal::{
     DalContext, Diagram, FixBatchId, NodeId, Prop, PropKind, Schema, SchemaId, SchemaVariantId,
     Socket, StandardModel, Visibility,
 };
-use names::{Generator, Name};
 use sdf_server::service::component::refresh::{RefreshRequest, RefreshResponse};
 use sdf_server::service::dev::{AuthorSingleSchemaRequest, AuthorSingleSchemaResponse};
 use sdf_server::service::diagram::delete_component::DeleteComponentRequest;
@@ -147,8 +146,8 @@ impl ScenarioHarness {
         }
     }
 
-    pub fn generate_fake_name() -> String {
-        Generator::with_naming(Name::Numbered).next().unwrap()
+    pub fn generate_fake_name(ctx: &DalContext) -> String {
+        dal_test::helpers::generate_fake_name(ctx)
     }
 
     /// Add [`Schemas`](dal::Schema) to the [`harness`](Self) that were not added in [`Self::new`].
@@ -313,6 +312,8 @@ impl ScenarioHarness {
             component_id,
             value,
             key: property_value.key.clone(),
+            expected_revision: property_value.revision(),
+            client_request_id: None,
             visibility: *ctx.visibility(),
         };
         self.query_post_no_response("/api/component/update_property_editor_value", &request)
@@ -335,6 +336,7 @@ impl ScenarioHarness {
             component_id,
             value,
             key: property_value.key.clone(),
+            client_request_id: None,
             visibility: *ctx.visibility(),
         };
         self.query_post_no_response("/api/component/insert_property_editor_value", &request)
@@ -401,6 +403,7 @@ impl ScenarioHarness {
             from_socket_id: *source_socket.id(),
             to_node_id: destination_node_id,
             to_socket_id: *destination_socket.id(),
+            client_request_id: None,
             visibility: *ctx.visibility(),
         };
         let _response: CreateConnectionResponse = self
@@ -502,16 +505,19 @@ impl ScenarioHarness {
             parent_id: frame_node_id,
             x: "0".to_string(),
             y: "0".to_string(),
+            client_request_id: None,
             visibility: *visibility,
         };
         let create_node_response: CreateNodeResponse =
             self.query_post("/api/diagram/create_node", &request).await;
+        assert!(!create_node_response.frame_attach_failed);
         create_node_response.into()
     }
 
     pub async fn delete_component(&self, visibility: &Visibility, component_id: ComponentId) {
         let request = DeleteComponentRequest {
             component_id,
+            client_request_id: None,
             visibility: *visibility,
         };
         self.query_post_no_response("/api/diagram/delete_component", &request)
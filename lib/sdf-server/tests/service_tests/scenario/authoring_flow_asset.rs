@@ -18,8 +18,9 @@ async fn authoring_flow_asset(
     let schema_name = "si-demo-schema";
 
     // Enter a new change set. We will not go through the routes for this.
+    let change_set_name = ScenarioHarness::generate_fake_name(&ctx);
     harness
-        .create_change_set_and_update_ctx(&mut ctx, ScenarioHarness::generate_fake_name())
+        .create_change_set_and_update_ctx(&mut ctx, change_set_name)
         .await;
 
     // Create the asset
@@ -43,12 +43,13 @@ async fn model_and_fix_flow_whiskers(
     )
     .await;
 
-    let random_name = ScenarioHarness::generate_fake_name();
+    let random_name = ScenarioHarness::generate_fake_name(&ctx);
     println!("Starting test run for: {}", random_name);
 
     // Enter a new change set. We will not go through the routes for this.
+    let change_set_name = ScenarioHarness::generate_fake_name(&ctx);
     harness
-        .create_change_set_and_update_ctx(&mut ctx, ScenarioHarness::generate_fake_name())
+        .create_change_set_and_update_ctx(&mut ctx, change_set_name)
         .await;
 
     // Create all AWS components.
@@ -612,8 +613,9 @@ async fn model_and_fix_flow_whiskers(
     //     .to_value());
 
     // Create a new change set to delete the components
+    let change_set_name = ScenarioHarness::generate_fake_name(&ctx);
     harness
-        .create_change_set_and_update_ctx(&mut ctx, ScenarioHarness::generate_fake_name())
+        .create_change_set_and_update_ctx(&mut ctx, change_set_name)
         .await;
 
     // delete AWS components
@@ -1,13 +1,13 @@
-use axum::Router;
+use axum::{http::StatusCode, Router};
 use dal::{
-    EncryptedSecret, SecretAlgorithm, SecretKind, SecretObjectType, SecretVersion, StandardModel,
-    Visibility, WorkspaceSignup,
+    key_pair::KeyPairPk, EncryptedSecret, SecretAlgorithm, SecretKind, SecretObjectType,
+    SecretVersion, StandardModel, Visibility, WorkspaceSignup,
 };
 use dal_test::{sdf_test, test_harness::encrypt_message, AuthTokenRef, DalContextHead};
 use hyper::Method;
 use sdf_server::service::secret::create_secret::{CreateSecretRequest, CreateSecretResponse};
 
-use crate::service_tests::api_request_auth_json_body;
+use crate::service_tests::{api_request_auth_json_body, api_request_auth_json_body_error};
 
 #[sdf_test]
 async fn create_secret(
@@ -62,3 +62,47 @@ async fn create_secret(
         serde_json::to_value(&decrypted_secret).expect("failed to serial decrypted into Value");
     assert_eq!(decrypted_value["message"], message);
 }
+
+#[sdf_test]
+async fn create_secret_for_unknown_key_pair_is_not_found(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    let crypted = encrypt_message(&ctx, nw.key_pair.pk(), &serde_json::json!({})).await;
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = CreateSecretRequest {
+        name: "fallen-leaves".to_string(),
+        object_type: SecretObjectType::Credential,
+        kind: SecretKind::DockerHub,
+        crypted,
+        key_pair_pk: KeyPairPk::generate(),
+        version: SecretVersion::V1,
+        algorithm: SecretAlgorithm::Sealedbox,
+        visibility: Visibility::new_head(false),
+    };
+
+    let (status, body) = api_request_auth_json_body_error(
+        app.clone(),
+        Method::POST,
+        "/api/secret/create_secret",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::NOT_FOUND, status);
+    assert_eq!("NOT_FOUND", body["error"]["code"]);
+
+    let (status, body) = api_request_auth_json_body_error(
+        app,
+        Method::POST,
+        "/api/secret/create_secret",
+        "not-a-token",
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::UNAUTHORIZED, status);
+    assert_eq!("UNAUTHORIZED", body["error"]["code"]);
+}
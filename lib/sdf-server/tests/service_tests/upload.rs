@@ -0,0 +1,72 @@
+use axum::{
+    body::Body,
+    http::{self, Method, Request, StatusCode},
+    Router,
+};
+use dal::Visibility;
+use dal_test::{sdf_test, AuthTokenRef};
+use tower::ServiceExt;
+
+const BOUNDARY: &str = "sdf-upload-test-boundary";
+
+fn multipart_body(fields: &[(&str, &str)]) -> String {
+    let mut body = String::new();
+    for (name, value) in fields {
+        body.push_str(&format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"; filename=\"{name}\"\r\n\r\n{value}\r\n"
+        ));
+    }
+    body.push_str(&format!("--{BOUNDARY}--\r\n"));
+    body
+}
+
+async fn upload_pkg(
+    app: Router,
+    auth_token: &str,
+    fields: &[(&str, &str)],
+) -> (StatusCode, serde_json::Value) {
+    let params =
+        serde_url_params::to_string(&Visibility::new_head(false)).expect("cannot serialize params");
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/api/pkg/upload_pkg?{params}"))
+        .header(
+            http::header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(Body::from(multipart_body(fields)))
+        .expect("cannot create api request");
+    let response = app.oneshot(request).await.expect("cannot send request");
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    let body_json = serde_json::from_slice(&body).expect("response is not valid json");
+    (status, body_json)
+}
+
+#[sdf_test]
+async fn upload_with_mismatched_checksum_is_rejected(
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let checksum = blake3::hash(b"something else").to_hex().to_string();
+    let (status, body) = upload_pkg(
+        app,
+        auth_token,
+        &[("file", "not really a package"), ("checksum", &checksum)],
+    )
+    .await;
+
+    assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, status);
+    assert_eq!("VALIDATION", body["error"]["code"]);
+}
+
+#[sdf_test]
+async fn upload_without_file_is_rejected(app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
+    let (status, body) = upload_pkg(app, auth_token, &[]).await;
+
+    assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, status);
+    assert_eq!("VALIDATION", body["error"]["code"]);
+}
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use pretty_assertions_sorted::assert_eq;
+use sdf_server::service::ws::outbound::{Outbound, OutboundQueue, Pushed, SLOW_CLIENT_CLOSE_CODE};
+use serde_json::json;
+
+fn event(change_set_pk: &str, kind: &str, data: serde_json::Value) -> String {
+    json!({
+        "version": 1,
+        "workspace_pk": "01H7ZJ1D2Y8N5QK3W6TB4XV9RM",
+        "change_set_pk": change_set_pk,
+        "payload": { "kind": kind, "data": data },
+    })
+    .to_string()
+}
+
+fn attribute_value_updated(attribute_value_id: &str, old_value: i64, new_value: i64) -> String {
+    event(
+        "head",
+        "AttributeValueUpdated",
+        json!({
+            "attributeValueId": attribute_value_id,
+            "oldValue": old_value,
+            "newValue": new_value,
+        }),
+    )
+}
+
+fn component_created(component_id: &str) -> String {
+    event(
+        "head",
+        "ComponentCreated",
+        json!({ "componentId": component_id }),
+    )
+}
+
+fn presence_updated(session_id: &str) -> String {
+    event(
+        "head",
+        "PresenceUpdated",
+        json!({ "sessionId": session_id }),
+    )
+}
+
+async fn pop(queue: &OutboundQueue) -> Outbound {
+    tokio::time::timeout(Duration::from_secs(1), queue.pop())
+        .await
+        .expect("nothing to send")
+}
+
+async fn pop_message(queue: &OutboundQueue) -> serde_json::Value {
+    match pop(queue).await {
+        Outbound::Message(text) => serde_json::from_str(&text).expect("message is not valid json"),
+        Outbound::Close(frame) => panic!("expected a message, got a close frame: {frame:?}"),
+    }
+}
+
+#[tokio::test]
+async fn attribute_value_updates_coalesce_keeping_the_oldest_old_value() {
+    let queue = OutboundQueue::new(16);
+
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(attribute_value_updated("mastodon", 1, 2)).await
+    );
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(attribute_value_updated("gojira", 1, 2)).await
+    );
+    assert_eq!(
+        Pushed::Coalesced,
+        queue.push(attribute_value_updated("mastodon", 2, 3)).await
+    );
+    // The same attribute value in another change set is another thing altogether
+    assert_eq!(
+        Pushed::Queued,
+        queue
+            .push(event(
+                "change set",
+                "AttributeValueUpdated",
+                json!({ "attributeValueId": "mastodon", "oldValue": 2, "newValue": 4 }),
+            ))
+            .await
+    );
+
+    let mastodon = pop_message(&queue).await;
+    assert_eq!(
+        json!("mastodon"),
+        mastodon["payload"]["data"]["attributeValueId"]
+    );
+    assert_eq!(json!(1), mastodon["payload"]["data"]["oldValue"]);
+    assert_eq!(json!(3), mastodon["payload"]["data"]["newValue"]);
+    let gojira = pop_message(&queue).await;
+    assert_eq!(
+        json!("gojira"),
+        gojira["payload"]["data"]["attributeValueId"]
+    );
+    let other_change_set = pop_message(&queue).await;
+    assert_eq!(json!("change set"), other_change_set["change_set_pk"]);
+    assert_eq!(json!(2), other_change_set["payload"]["data"]["oldValue"]);
+}
+
+#[tokio::test]
+async fn presence_updates_make_room_when_full() {
+    let queue = OutboundQueue::new(2);
+
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(presence_updated("mastodon")).await
+    );
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(component_created("gojira")).await
+    );
+    // A full queue drops a newer presence update...
+    assert_eq!(
+        Pushed::Dropped,
+        queue.push(presence_updated("baroness")).await
+    );
+    // ...and a queued one to make room for an event which can't be dropped
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(component_created("kylesa")).await
+    );
+
+    let gojira = pop_message(&queue).await;
+    assert_eq!(json!("gojira"), gojira["payload"]["data"]["componentId"]);
+    let kylesa = pop_message(&queue).await;
+    assert_eq!(json!("kylesa"), kylesa["payload"]["data"]["componentId"]);
+}
+
+#[tokio::test]
+async fn slow_consumer_is_disconnected() {
+    let queue = OutboundQueue::new(2);
+
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(component_created("mastodon")).await
+    );
+    assert_eq!(
+        Pushed::Queued,
+        queue.push(component_created("gojira")).await
+    );
+    assert_eq!(
+        Pushed::Full,
+        queue.push(component_created("baroness")).await
+    );
+    // Once disconnected, nothing else is queued
+    assert_eq!(
+        Pushed::Dropped,
+        queue.push(component_created("kylesa")).await
+    );
+
+    // The messages still waiting are discarded in favor of the close frame
+    for _ in 0..2 {
+        match pop(&queue).await {
+            Outbound::Close(frame) => assert_eq!(SLOW_CLIENT_CLOSE_CODE, frame.code),
+            Outbound::Message(text) => panic!("expected a close frame, got a message: {text}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn consumer_keeping_up_is_not_disconnected() {
+    let queue = OutboundQueue::new(1);
+
+    for id in 0..8 {
+        let component_id = format!("component {id}");
+        assert_eq!(
+            Pushed::Queued,
+            queue.push(component_created(&component_id)).await
+        );
+        let message = pop_message(&queue).await;
+        assert_eq!(
+            json!(component_id),
+            message["payload"]["data"]["componentId"]
+        );
+    }
+}
@@ -1,11 +1,15 @@
+mod backup;
 mod check;
 mod configure;
 mod delete;
 mod install;
 mod launch;
+mod logs;
 mod report;
 mod restart;
+mod restore;
 mod start;
 mod status;
 mod stop;
 mod update;
+mod upgrade;
@@ -0,0 +1,202 @@
+use crate::containers::DockerClient;
+use crate::key_management::{get_si_data_dir, get_user_email};
+use crate::state::AppState;
+use crate::{CliResult, SiCliError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub(super) const MANIFEST_FILE_NAME: &str = "manifest.json";
+/// Where the database dump lives, both in the archive and in the directory shared with the
+/// one-shot postgres container.
+pub(super) const DATABASE_DUMP: &str = "postgres/si.dump";
+pub(super) const PKGS_DIR: &str = "pkgs";
+/// The key material in the SI data dir which the database contents are encrypted or signed with.
+pub(super) const KEY_FILES: &[&str] = &[
+    "cyclone_encryption.key",
+    "decryption.key",
+    "jwt_signing_public_key.pem",
+];
+pub(super) const POSTGRES_CONTAINER: &str = "local-postgres-1";
+pub(super) const POSTGRES_IMAGE: &str = "systeminit/postgres:stable";
+/// Where the one-shot postgres container finds the backup directory.
+pub(super) const CONTAINER_BACKUP_DIR: &str = "/backup";
+
+/// Describes the contents of a backup archive, so they can be checked before restoring.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct BackupManifest {
+    pub(super) created_at: String,
+    pub(super) si_version: String,
+    /// The blake3 hash of each file in the archive, by its path within the archive.
+    pub(super) files: BTreeMap<String, String>,
+}
+
+impl AppState {
+    pub async fn backup(&self, docker: &DockerClient, output: Option<PathBuf>) -> CliResult<()> {
+        self.track(
+            get_user_email().await?,
+            serde_json::json!({"command-name": "backup"}),
+        );
+        invoke(self, docker, self.is_preview(), output).await?;
+        Ok(())
+    }
+}
+
+pub(super) fn hash_file(path: &Path) -> CliResult<String> {
+    let mut hasher = blake3::Hasher::new();
+    let mut file = File::open(path)?;
+    let mut buf = [0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Lists the files below `dir`, as paths relative to `root` using `/` separators.
+pub(super) fn list_files(root: &Path, dir: &Path) -> CliResult<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(list_files(root, &path)?);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative: Vec<_> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            files.push(relative.join("/"));
+        }
+    }
+    Ok(files)
+}
+
+/// Fails unless the postgres container is running, as both backup and restore go through it.
+pub(super) async fn ensure_postgres_running(docker: &DockerClient) -> CliResult<()> {
+    let running = docker
+        .get_existing_container(POSTGRES_CONTAINER.to_string())
+        .await?
+        .and_then(|container| container.state)
+        .map_or(false, |state| state == "running");
+    if !running {
+        return Err(SiCliError::ContainerNotRunning(
+            POSTGRES_CONTAINER.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn invoke(
+    app: &AppState,
+    docker: &DockerClient,
+    is_preview: bool,
+    output: Option<PathBuf>,
+) -> CliResult<()> {
+    let si_data_dir = get_si_data_dir().await?;
+    let now = chrono::Utc::now();
+    let output = output.unwrap_or_else(|| {
+        PathBuf::from(format!("si-backup-{}.tar.zst", now.format("%Y%m%d%H%M%S")))
+    });
+
+    if is_preview {
+        println!("Backed up the following to {}:", output.display());
+        println!("the si database, dumped from {POSTGRES_CONTAINER}");
+        for key_file in KEY_FILES {
+            println!("{}", si_data_dir.join(key_file).display());
+        }
+        println!("{}", si_data_dir.join(PKGS_DIR).display());
+        return Ok(());
+    }
+
+    ensure_postgres_running(docker).await?;
+
+    // The staging directory lives in the SI data dir, which the container engine can already
+    // mount, and is removed once dropped
+    let staging = tempfile::TempDir::new_in(&si_data_dir)?;
+    std::fs::create_dir_all(staging.path().join("postgres"))?;
+
+    println!("Dumping the si database");
+    let (succeeded, dump_output) = docker
+        .run_one_shot(
+            POSTGRES_IMAGE,
+            vec![
+                "pg_dump".to_string(),
+                "--host=localhost".to_string(),
+                "--username=si".to_string(),
+                "--format=custom".to_string(),
+                format!("--file={CONTAINER_BACKUP_DIR}/{DATABASE_DUMP}"),
+                "si".to_string(),
+            ],
+            vec!["PGPASSWORD=bugbear".to_string()],
+            vec![format!(
+                "{}:{CONTAINER_BACKUP_DIR}:Z",
+                staging.path().display()
+            )],
+            POSTGRES_CONTAINER,
+        )
+        .await?;
+    if !succeeded {
+        return Err(SiCliError::DatabaseCommand(
+            "pg_dump".to_string(),
+            dump_output,
+        ));
+    }
+
+    for key_file in KEY_FILES {
+        let source = si_data_dir.join(key_file);
+        if source.exists() {
+            std::fs::create_dir_all(staging.path().join("keys"))?;
+            std::fs::copy(&source, staging.path().join("keys").join(key_file))?;
+        }
+    }
+
+    let staging_path = staging.path().to_owned();
+    let pkgs_dir = si_data_dir.join(PKGS_DIR);
+    let si_version = app.version().to_string();
+    let archive_path = output.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut files = BTreeMap::new();
+        for file in list_files(&staging_path, &staging_path)? {
+            files.insert(file.clone(), hash_file(&staging_path.join(&file))?);
+        }
+        if pkgs_dir.is_dir() {
+            for file in list_files(&si_data_dir, &pkgs_dir)? {
+                files.insert(file.clone(), hash_file(&si_data_dir.join(&file))?);
+            }
+        }
+        let manifest = BackupManifest {
+            created_at: now.to_rfc3339(),
+            si_version,
+            files,
+        };
+        std::fs::write(
+            staging_path.join(MANIFEST_FILE_NAME),
+            serde_json::to_vec_pretty(&manifest)?,
+        )?;
+
+        let encoder = zstd::stream::write::Encoder::new(File::create(&archive_path)?, 0)?;
+        let mut archive = tar::Builder::new(encoder);
+        archive.append_path_with_name(staging_path.join(MANIFEST_FILE_NAME), MANIFEST_FILE_NAME)?;
+        for file in manifest.files.keys() {
+            let source = if file.starts_with(&format!("{PKGS_DIR}/")) {
+                si_data_dir.join(file)
+            } else {
+                staging_path.join(file)
+            };
+            archive.append_path_with_name(source, file)?;
+        }
+        archive.into_inner()?.finish()?;
+        Ok::<(), SiCliError>(())
+    })
+    .await??;
+
+    println!("Backup written to {}", output.display());
+
+    Ok(())
+}
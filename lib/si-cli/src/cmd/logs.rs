@@ -0,0 +1,144 @@
+use crate::containers::DockerClient;
+use crate::key_management::get_user_email;
+use crate::state::AppState;
+use crate::{CliResult, SiCliError, CONTAINER_NAMES};
+use colored::{Color, Colorize};
+use docker_api::opts::LogsOpts;
+use futures::StreamExt;
+use tokio::sync::mpsc;
+
+const PREFIX_COLORS: &[Color] = &[
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::BrightCyan,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+];
+
+impl AppState {
+    pub async fn logs(
+        &self,
+        docker: &DockerClient,
+        services: Vec<String>,
+        follow: bool,
+        since: Option<String>,
+        tail: usize,
+    ) -> CliResult<()> {
+        self.track(
+            get_user_email().await?,
+            serde_json::json!({"command-name": "logs"}),
+        );
+        invoke(docker, services, follow, since, tail).await?;
+        Ok(())
+    }
+}
+
+/// Parses a duration such as `30s`, `10m`, `2h` or `1d`.
+fn parse_since(since: &str) -> CliResult<chrono::Duration> {
+    let invalid = || SiCliError::InvalidDuration(since.to_string());
+    let since = since.trim();
+    let split_at = since
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = since.split_at(split_at);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "s" => Ok(chrono::Duration::seconds(amount)),
+        "m" => Ok(chrono::Duration::minutes(amount)),
+        "h" => Ok(chrono::Duration::hours(amount)),
+        "d" => Ok(chrono::Duration::days(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+async fn invoke(
+    docker: &DockerClient,
+    services: Vec<String>,
+    follow: bool,
+    since: Option<String>,
+    tail: usize,
+) -> CliResult<()> {
+    let since = since
+        .as_deref()
+        .map(parse_since)
+        .transpose()?
+        .map(|since| chrono::Utc::now() - since);
+
+    let names: Vec<&str> = CONTAINER_NAMES
+        .iter()
+        .copied()
+        .filter(|name| services.is_empty() || services.iter().any(|service| service == name))
+        .collect();
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    for (index, name) in names.iter().enumerate() {
+        let container_identifier = format!("local-{0}-1", name);
+        let id = match docker
+            .get_existing_container(container_identifier.clone())
+            .await?
+            .and_then(|container| container.id)
+        {
+            Some(id) => id,
+            None => {
+                println!("Container {container_identifier} does not exist, skipping its logs");
+                continue;
+            }
+        };
+
+        let prefix = format!("{name:>width$} |")
+            .color(PREFIX_COLORS[index % PREFIX_COLORS.len()])
+            .to_string();
+        let mut logs_opts = LogsOpts::builder()
+            .follow(follow)
+            .n_lines(tail)
+            .stdout(true)
+            .stderr(true);
+        if let Some(since) = &since {
+            logs_opts = logs_opts.since(since);
+        }
+        let logs_opts = logs_opts.build();
+
+        let container = docker.containers().get(id);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut logs_stream = container.logs(&logs_opts);
+            let mut partial_line = Vec::new();
+            while let Some(chunk) = logs_stream.next().await {
+                match chunk {
+                    Ok(chunk) => {
+                        partial_line.extend_from_slice(&chunk.to_vec());
+                        while let Some(end) = partial_line.iter().position(|byte| *byte == b'\n') {
+                            let line: Vec<u8> = partial_line.drain(..=end).collect();
+                            let line = String::from_utf8_lossy(&line[..end]).into_owned();
+                            if tx.send(format!("{prefix} {line}")).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(format!("{prefix} error reading logs: {e}"));
+                        return;
+                    }
+                }
+            }
+            if !partial_line.is_empty() {
+                let line = String::from_utf8_lossy(&partial_line).into_owned();
+                let _ = tx.send(format!("{prefix} {line}"));
+            }
+        });
+    }
+    // Only the spawned tasks hold senders now, so the loop below ends once all of the log streams
+    // do, which never happens when following
+    drop(tx);
+
+    while let Some(line) = rx.recv().await {
+        println!("{line}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use super::backup::{
+    ensure_postgres_running, hash_file, list_files, BackupManifest, CONTAINER_BACKUP_DIR,
+    DATABASE_DUMP, KEY_FILES, MANIFEST_FILE_NAME, PKGS_DIR, POSTGRES_CONTAINER, POSTGRES_IMAGE,
+};
+use crate::containers::DockerClient;
+use crate::key_management::{get_si_data_dir, get_user_email};
+use crate::state::AppState;
+use crate::{CliResult, SiCliError};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+impl AppState {
+    pub async fn restore(&self, docker: &DockerClient, file: PathBuf) -> CliResult<()> {
+        self.track(
+            get_user_email().await?,
+            serde_json::json!({"command-name": "restore"}),
+        );
+        invoke(docker, self.is_preview(), file).await?;
+        Ok(())
+    }
+}
+
+/// Checks that the unpacked archive holds exactly the files of its manifest, with their
+/// recorded hashes.
+fn verify(unpacked: &Path) -> CliResult<BackupManifest> {
+    let manifest: BackupManifest = match std::fs::read(unpacked.join(MANIFEST_FILE_NAME)) {
+        Ok(contents) => serde_json::from_slice(&contents)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(SiCliError::BackupIntegrity(format!(
+                "{MANIFEST_FILE_NAME} is missing"
+            )));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    for (file, expected_hash) in &manifest.files {
+        let path = unpacked.join(file);
+        if !path.is_file() {
+            return Err(SiCliError::BackupIntegrity(format!("{file} is missing")));
+        }
+        if hash_file(&path)? != *expected_hash {
+            return Err(SiCliError::BackupIntegrity(format!(
+                "{file} does not match its recorded hash"
+            )));
+        }
+    }
+    for file in list_files(unpacked, unpacked)? {
+        if file != MANIFEST_FILE_NAME && !manifest.files.contains_key(&file) {
+            return Err(SiCliError::BackupIntegrity(format!(
+                "{file} is not listed in {MANIFEST_FILE_NAME}"
+            )));
+        }
+    }
+    if !manifest.files.contains_key(DATABASE_DUMP) {
+        return Err(SiCliError::BackupIntegrity(format!(
+            "{DATABASE_DUMP} is missing"
+        )));
+    }
+
+    Ok(manifest)
+}
+
+async fn invoke(docker: &DockerClient, is_preview: bool, file: PathBuf) -> CliResult<()> {
+    let si_data_dir = get_si_data_dir().await?;
+
+    // Unpacked into the SI data dir, so the container engine can mount the database dump
+    let staging = tempfile::TempDir::new_in(&si_data_dir)?;
+    let staging_path = staging.path().to_owned();
+    println!("Checking backup {}", file.display());
+    let manifest = tokio::task::spawn_blocking(move || {
+        let decoder = zstd::stream::read::Decoder::new(File::open(file)?)?;
+        tar::Archive::new(decoder).unpack(&staging_path)?;
+        verify(&staging_path)
+    })
+    .await??;
+
+    if is_preview {
+        println!(
+            "Restored the following from the backup taken at {} with si {}:",
+            manifest.created_at, manifest.si_version
+        );
+        for file in manifest.files.keys() {
+            println!("{file}");
+        }
+        return Ok(());
+    }
+
+    ensure_postgres_running(docker).await?;
+
+    println!("Restoring the si database");
+    let (succeeded, restore_output) = docker
+        .run_one_shot(
+            POSTGRES_IMAGE,
+            vec![
+                "pg_restore".to_string(),
+                "--host=localhost".to_string(),
+                "--username=si".to_string(),
+                "--dbname=si".to_string(),
+                "--clean".to_string(),
+                "--if-exists".to_string(),
+                "--no-owner".to_string(),
+                format!("{CONTAINER_BACKUP_DIR}/{DATABASE_DUMP}"),
+            ],
+            vec!["PGPASSWORD=bugbear".to_string()],
+            vec![format!(
+                "{}:{CONTAINER_BACKUP_DIR}:Z",
+                staging.path().display()
+            )],
+            POSTGRES_CONTAINER,
+        )
+        .await?;
+    if !succeeded {
+        return Err(SiCliError::DatabaseCommand(
+            "pg_restore".to_string(),
+            restore_output,
+        ));
+    }
+
+    for key_file in KEY_FILES {
+        let source = staging.path().join("keys").join(key_file);
+        if source.exists() {
+            println!("Restoring {key_file}");
+            tokio::fs::copy(&source, si_data_dir.join(key_file)).await?;
+        }
+    }
+
+    let pkgs_dir = si_data_dir.join(PKGS_DIR);
+    if pkgs_dir.exists() {
+        tokio::fs::remove_dir_all(&pkgs_dir).await?;
+    }
+    for file in manifest.files.keys() {
+        if file.starts_with(&format!("{PKGS_DIR}/")) {
+            let destination = si_data_dir.join(file);
+            if let Some(parent) = destination.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::copy(staging.path().join(file), destination).await?;
+        }
+    }
+
+    println!("Backup restored, run `si restart` so every component picks it up");
+
+    Ok(())
+}
@@ -1,15 +1,21 @@
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::*;
+use docker_api::models::ContainerSummary;
+use std::time::Duration;
+use tokio::net::TcpStream;
 
 use crate::containers::DockerClient;
 use crate::key_management::get_user_email;
 use crate::state::AppState;
-use crate::{CliResult, CONTAINER_NAMES};
+use crate::{CliResult, SiCliError, CONTAINER_NAMES};
 
 const RUNNING: &str = "    ✅    ";
 const NOT_RUNNING: &str = "    ❌    ";
 const WAITING: &str = "    🕒    ";
 
+/// How long a published port or health endpoint gets to answer before it is considered down.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
 impl AppState {
     pub async fn status(
         &self,
@@ -30,22 +36,81 @@ impl AppState {
 struct Status {
     name: String,
     state: ContainerState,
+    port: PortState,
     version: String,
 }
 
 #[derive(Debug, PartialEq)]
 enum ContainerState {
     Running,
-    NotRunning,
+    /// The container exists but is not running, with the state reported by the engine, such as
+    /// `exited`, or it does not exist at all.
+    NotRunning(String),
+    /// The container is running but its service does not answer yet.
     Waiting,
 }
 
-async fn invoke(app: &AppState, docker: &DockerClient, show_logs: bool, log_lines: usize) -> CliResult<()> {
+#[derive(Debug, PartialEq)]
+enum PortState {
+    /// The service does not publish a port on the host.
+    None,
+    /// The service publishes its port on the host, but the container does not.
+    NotPublished(u16),
+    Open(u16),
+    Closed(u16),
+}
+
+/// Returns the host and port a service is expected to publish, if any.
+fn published_port(app: &AppState, name: &str) -> Option<(String, u16)> {
+    match name {
+        "sdf" => Some(("127.0.0.1".to_string(), 5156)),
+        "web" => Some((app.web_host(), u16::try_from(app.web_port()).ok()?)),
+        "postgres" => Some(("127.0.0.1".to_string(), 5432)),
+        "nats" => Some(("127.0.0.1".to_string(), 4222)),
+        _ => None,
+    }
+}
+
+async fn probe_port(container: Option<&ContainerSummary>, host: &str, port: u16) -> PortState {
+    let is_published = container
+        .and_then(|container| container.ports.as_ref())
+        .map(|ports| {
+            ports
+                .iter()
+                .any(|p| p.public_port.map(|public_port| public_port as i64) == Some(port.into()))
+        })
+        .unwrap_or(false);
+    if !is_published {
+        return PortState::NotPublished(port);
+    }
+
+    match tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => PortState::Open(port),
+        _ => PortState::Closed(port),
+    }
+}
+
+async fn is_reachable(url: &str) -> bool {
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    match client.get(url).send().await {
+        Ok(resp) => resp.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+async fn invoke(
+    app: &AppState,
+    docker: &DockerClient,
+    show_logs: bool,
+    log_lines: usize,
+) -> CliResult<()> {
     println!("Checking the status of System Initiative Software");
 
     let mut container_status = Vec::new();
 
-    let mut all_running = true;
     for name in CONTAINER_NAMES.iter() {
         let image_name = format!("systeminit/{0}:stable", name);
         let container_identifier = format!("local-{0}-1", name);
@@ -53,20 +118,24 @@ async fn invoke(app: &AppState, docker: &DockerClient, show_logs: bool, log_line
             .get_existing_container(container_identifier.clone())
             .await?;
         let mut version = "".to_string();
-        let mut state = ContainerState::NotRunning;
-        if let Some(container) = existing_container {
-            version = container
+        let mut state = ContainerState::NotRunning("missing".to_string());
+        if let Some(container) = &existing_container {
+            if let Some(image_version) = container
                 .labels
-                .unwrap()
-                .get("org.opencontainers.image.version")
-                .unwrap()
-                .to_string();
-            let raw_state = container.state.unwrap();
-            if raw_state == "running" {
-                state = ContainerState::Running;
-            } else {
-                all_running = false;
+                .as_ref()
+                .and_then(|labels| labels.get("org.opencontainers.image.version"))
+            {
+                version = image_version.to_string();
             }
+            let raw_state = container
+                .state
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string());
+            state = if raw_state == "running" {
+                ContainerState::Running
+            } else {
+                ContainerState::NotRunning(raw_state)
+            };
         }
 
         if show_logs {
@@ -76,18 +145,26 @@ async fn invoke(app: &AppState, docker: &DockerClient, show_logs: bool, log_line
                 .await?;
         }
 
-        if container_identifier == "local-web-1" {
+        let port = match published_port(app, name) {
+            Some((host, port)) => probe_port(existing_container.as_ref(), &host, port).await,
+            None => PortState::None,
+        };
+        if let PortState::Closed(_) = port {
+            if state == ContainerState::Running {
+                state = ContainerState::Waiting;
+            }
+        }
+
+        if *name == "web" {
             let web_path = format!("http://{0}:{1}/", app.web_host(), app.web_port());
-            let resp = reqwest::get(web_path).await;
-            if resp.is_err() && state == ContainerState::Running {
+            if !is_reachable(&web_path).await && state == ContainerState::Running {
                 state = ContainerState::Waiting;
             }
         }
 
-        if container_identifier == "local-sdf-1" {
-            let sdf_path = "http://localhost:5156/api/";
-            let resp = reqwest::get(sdf_path).await;
-            if resp.is_err() && state == ContainerState::Running {
+        if *name == "sdf" {
+            let sdf_path = "http://localhost:5156/health/ready";
+            if !is_reachable(sdf_path).await && state == ContainerState::Running {
                 state = ContainerState::Waiting;
             }
         }
@@ -95,6 +172,7 @@ async fn invoke(app: &AppState, docker: &DockerClient, show_logs: bool, log_line
         container_status.push(Status {
             name: image_name,
             state,
+            port,
             version,
         })
     }
@@ -107,24 +185,38 @@ async fn invoke(app: &AppState, docker: &DockerClient, show_logs: bool, log_line
         .set_header(vec![
             Cell::new("Container Image").add_attribute(Attribute::Bold),
             Cell::new("State").add_attribute(Attribute::Bold),
+            Cell::new("Port").add_attribute(Attribute::Bold),
             Cell::new("Container Version").add_attribute(Attribute::Bold),
         ]);
+    let mut down = Vec::new();
     for container_status in container_status {
+        let state = match &container_status.state {
+            ContainerState::Running => RUNNING.to_string(),
+            ContainerState::NotRunning(raw_state) => format!("{NOT_RUNNING}{raw_state}"),
+            ContainerState::Waiting => WAITING.to_string(),
+        };
+        let port = match container_status.port {
+            PortState::None => "".to_string(),
+            PortState::NotPublished(port) => format!("{port} (not published)"),
+            PortState::Open(port) => format!("{port} open"),
+            PortState::Closed(port) => format!("{port} closed"),
+        };
+        if container_status.state != ContainerState::Running {
+            down.push(container_status.name.clone());
+        }
         table.add_row(vec![
             Cell::new(container_status.name).add_attribute(Attribute::Bold),
-            Cell::new(match container_status.state {
-                ContainerState::Running => RUNNING,
-                ContainerState::NotRunning => NOT_RUNNING,
-                ContainerState::Waiting => WAITING,
-            }),
+            Cell::new(state),
+            Cell::new(port),
             Cell::new(container_status.version),
         ]);
     }
     println!("{table}");
 
-    if all_running {
-        println!("\nAll system components working as expected...")
+    if !down.is_empty() {
+        return Err(SiCliError::SystemUnhealthy(down.join(", ")));
     }
+    println!("\nAll system components working as expected...");
 
     Ok(())
 }
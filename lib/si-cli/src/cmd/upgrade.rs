@@ -0,0 +1,152 @@
+use crate::containers::DockerClient;
+use crate::key_management::{get_si_data_dir, get_user_email};
+use crate::state::AppState;
+use crate::{CliResult, SiCliError, CONTAINER_NAMES};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The tag the containers are always started from, which an upgrade points at the new images.
+const STARTED_TAG: &str = "stable";
+const ROLLBACK_FILE_NAME: &str = "upgrade_rollback.json";
+
+/// The images the containers were running before the last upgrade.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RollbackRecord {
+    /// The version the containers were upgraded to.
+    upgraded_to: String,
+    /// The image id each container was running, by container name.
+    previous_images: BTreeMap<String, String>,
+}
+
+impl AppState {
+    pub async fn upgrade(
+        &self,
+        docker: &DockerClient,
+        version: String,
+        rollback: bool,
+    ) -> CliResult<()> {
+        self.track(
+            get_user_email().await?,
+            serde_json::json!({"command-name": "upgrade-system", "rollback": rollback}),
+        );
+        if rollback {
+            invoke_rollback(self, docker, self.is_preview()).await?;
+        } else {
+            invoke(self, docker, self.is_preview(), version).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn rollback_file() -> CliResult<PathBuf> {
+    Ok(get_si_data_dir().await?.join(ROLLBACK_FILE_NAME))
+}
+
+async fn invoke(
+    app: &AppState,
+    docker: &DockerClient,
+    is_preview: bool,
+    version: String,
+) -> CliResult<()> {
+    if is_preview {
+        println!("Upgraded the following containers to {version}:");
+        for name in CONTAINER_NAMES.iter() {
+            println!("systeminit/{name}:{version}");
+        }
+        return Ok(());
+    }
+
+    for name in CONTAINER_NAMES.iter() {
+        let image = format!("systeminit/{name}");
+        println!("Pulling {image}:{version}");
+        docker.pull_image(&image, &version).await?;
+    }
+
+    let mut previous_images = BTreeMap::new();
+    for name in CONTAINER_NAMES.iter() {
+        let container_name = format!("local-{0}-1", name);
+        if let Some(image_id) = docker
+            .get_existing_container(container_name)
+            .await?
+            .and_then(|container| container.image_id)
+        {
+            previous_images.insert(name.to_string(), image_id);
+        }
+    }
+    let record = RollbackRecord {
+        upgraded_to: version.clone(),
+        previous_images,
+    };
+    tokio::fs::write(rollback_file().await?, serde_json::to_vec_pretty(&record)?).await?;
+
+    if version != STARTED_TAG {
+        for name in CONTAINER_NAMES.iter() {
+            let image = format!("systeminit/{name}");
+            docker
+                .tag_image(&format!("{image}:{version}"), &image, STARTED_TAG)
+                .await?;
+        }
+    }
+
+    recreate_containers(app, docker).await?;
+    println!("All system components upgraded to {version}, run `si upgrade --rollback` to undo");
+
+    Ok(())
+}
+
+async fn invoke_rollback(app: &AppState, docker: &DockerClient, is_preview: bool) -> CliResult<()> {
+    let rollback_file = rollback_file().await?;
+    let record: RollbackRecord = match tokio::fs::read(&rollback_file).await {
+        Ok(contents) => serde_json::from_slice(&contents)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(SiCliError::NothingToRollBack);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    if is_preview {
+        println!(
+            "Rolled back the following containers from {}:",
+            record.upgraded_to
+        );
+        for (name, image_id) in &record.previous_images {
+            println!("systeminit/{name} to {image_id}");
+        }
+        return Ok(());
+    }
+
+    for (name, image_id) in &record.previous_images {
+        docker
+            .tag_image(image_id, &format!("systeminit/{name}"), STARTED_TAG)
+            .await?;
+    }
+
+    recreate_containers(app, docker).await?;
+    tokio::fs::remove_file(&rollback_file).await?;
+    println!(
+        "All system components rolled back from {}",
+        record.upgraded_to
+    );
+
+    Ok(())
+}
+
+/// Stops and deletes the containers, then starts them again from the images currently tagged
+/// `stable`. Deleting a container keeps its volumes, and the SI data dir is mounted again on start.
+async fn recreate_containers(app: &AppState, docker: &DockerClient) -> CliResult<()> {
+    app.stop(docker).await?;
+    for name in CONTAINER_NAMES.iter() {
+        let container_name = format!("local-{0}-1", name);
+        if let Some(container_summary) = docker
+            .get_existing_container(container_name.clone())
+            .await?
+        {
+            docker
+                .delete_container(container_summary, container_name)
+                .await?;
+        }
+    }
+    app.start(docker).await
+}
@@ -2,7 +2,8 @@ use crate::SiCliError;
 use crate::{CliResult, CONTAINER_NAMES};
 use docker_api::models::{ContainerSummary, ImageSummary, PingInfo};
 use docker_api::opts::{
-    ContainerFilter, ContainerListOpts, ImageListOpts, ImageRemoveOpts, LogsOpts, PullOpts,
+    ContainerCreateOpts, ContainerFilter, ContainerListOpts, ImageListOpts, ImageRemoveOpts,
+    LogsOpts, PullOpts, TagOpts,
 };
 use docker_api::Docker;
 use futures::StreamExt;
@@ -40,6 +41,10 @@ impl DockerClient {
         self.docker.containers()
     }
 
+    pub(crate) fn networks(&self) -> docker_api::Networks {
+        self.docker.networks()
+    }
+
     pub(crate) async fn ping(&self) -> CliResult<PingInfo> {
         self.docker.ping().await.map_err(Into::into)
     }
@@ -179,6 +184,62 @@ impl DockerClient {
         Ok(())
     }
 
+    /// Pulls `image:tag`, failing on the first error reported while pulling.
+    pub(crate) async fn pull_image(&self, image: &str, tag: &str) -> CliResult<()> {
+        let pull_opts = PullOpts::builder().image(image).tag(tag).build();
+        let images = self.docker.images();
+        let mut stream = images.pull(&pull_opts);
+        while let Some(pull_result) = stream.next().await {
+            pull_result?;
+        }
+
+        Ok(())
+    }
+
+    /// Tags the image `source`, which is either a name or an id, as `repo:tag`.
+    pub(crate) async fn tag_image(&self, source: &str, repo: &str, tag: &str) -> CliResult<()> {
+        let tag_opts = TagOpts::builder().repo(repo).tag(tag).build();
+        self.docker.images().get(source).tag(&tag_opts).await?;
+
+        Ok(())
+    }
+
+    /// Runs `command` in a throwaway container of `image` which shares the network of the
+    /// `network_container` container, then deletes it. Returns whether the command succeeded
+    /// along with everything it wrote to stdout and stderr.
+    pub(crate) async fn run_one_shot(
+        &self,
+        image: &str,
+        command: Vec<String>,
+        env: Vec<String>,
+        volumes: Vec<String>,
+        network_container: &str,
+    ) -> CliResult<(bool, String)> {
+        let create_opts = ContainerCreateOpts::builder()
+            .image(image)
+            .command(command)
+            .env(env)
+            .volumes(volumes)
+            .network_mode(format!("container:{network_container}"))
+            .build();
+        let container = self.docker.containers().create(&create_opts).await?;
+        container.start().await?;
+        let wait_response = container.wait().await;
+
+        let logs_opts = LogsOpts::builder().stdout(true).stderr(true).build();
+        let output: Vec<u8> = container
+            .logs(&logs_opts)
+            .filter_map(|chunk| async move { chunk.ok().map(|chunk| chunk.to_vec()) })
+            .concat()
+            .await;
+        container.delete().await?;
+
+        Ok((
+            wait_response?.status_code == 0,
+            String::from_utf8_lossy(&output).into_owned(),
+        ))
+    }
+
     pub(crate) async fn delete_container(
         &self,
         container_summary: ContainerSummary,
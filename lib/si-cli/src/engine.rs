@@ -0,0 +1,80 @@
+//! The container engines which can run the System Initiative components.
+//!
+//! `si start` describes each component as a [`ContainerSpec`] and hands them to the
+//! [`ContainerEngine`] selected with `--engine`.
+
+use async_trait::async_trait;
+
+use crate::CliResult;
+
+mod compose;
+mod docker;
+mod podman;
+
+pub use compose::ComposeEngine;
+pub use docker::DockerEngine;
+pub use podman::PodmanEngine;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EngineKind {
+    /// Generates a docker-compose file and runs it with `docker compose`.
+    Compose,
+    /// Drives the Docker engine API.
+    Docker,
+    /// Drives the Docker compatible API of Podman.
+    Podman,
+}
+
+/// A port of a container published on the host.
+#[derive(Clone, Debug)]
+pub struct PublishedPort {
+    pub container_port: u32,
+    /// The address to bind on the host, or all addresses if `None`.
+    pub host_ip: Option<String>,
+    pub host_port: u32,
+}
+
+/// How to run one of the System Initiative components.
+#[derive(Clone, Debug)]
+pub struct ContainerSpec {
+    /// The name of the component, such as `sdf`, which the other components reach it by.
+    pub name: &'static str,
+    pub command: Vec<String>,
+    pub env: Vec<String>,
+    /// The names of the components this one connects to.
+    pub links: Vec<&'static str>,
+    pub volumes: Vec<String>,
+    pub ports: Vec<PublishedPort>,
+    /// Whether a stopped container is deleted and created again rather than restarted, so that
+    /// it picks up its environment again.
+    pub recreate_when_stopped: bool,
+}
+
+impl ContainerSpec {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            command: Vec::new(),
+            env: Vec::new(),
+            links: Vec::new(),
+            volumes: Vec::new(),
+            ports: Vec::new(),
+            recreate_when_stopped: false,
+        }
+    }
+
+    pub fn image(&self) -> String {
+        format!("systeminit/{0}:stable", self.name)
+    }
+
+    pub fn container_name(&self) -> String {
+        format!("local-{0}-1", self.name)
+    }
+}
+
+#[async_trait]
+pub trait ContainerEngine: Send + Sync {
+    /// Starts the containers which are not running yet, in the order of `specs`. In preview mode,
+    /// only reports what would be started.
+    async fn start(&self, specs: &[ContainerSpec], is_preview: bool) -> CliResult<()>;
+}
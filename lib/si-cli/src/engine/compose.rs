@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::process::Command;
+
+use super::{ContainerEngine, ContainerSpec};
+use crate::{CliResult, SiCliError};
+
+/// The compose project name, which gives the containers the same `local-<name>-1` names as the
+/// other engines.
+const PROJECT_NAME: &str = "local";
+
+/// Writes the containers to a docker-compose file and brings them up with `docker compose`.
+#[derive(Clone, Debug)]
+pub struct ComposeEngine {
+    compose_file: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+struct ComposeFile<'a> {
+    services: BTreeMap<&'a str, ComposeService>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComposeService {
+    image: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    command: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    environment: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    volumes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ports: Vec<String>,
+}
+
+impl ComposeEngine {
+    pub fn new(compose_file: impl Into<PathBuf>) -> Self {
+        Self {
+            compose_file: compose_file.into(),
+        }
+    }
+
+    fn render(specs: &[ContainerSpec]) -> CliResult<String> {
+        let services = specs
+            .iter()
+            .map(|spec| {
+                let service = ComposeService {
+                    image: spec.image(),
+                    command: spec.command.clone(),
+                    environment: spec.env.clone(),
+                    depends_on: spec.links.iter().map(|link| link.to_string()).collect(),
+                    volumes: spec.volumes.clone(),
+                    ports: spec
+                        .ports
+                        .iter()
+                        .map(|port| match &port.host_ip {
+                            Some(host_ip) => {
+                                format!("{host_ip}:{}:{}", port.host_port, port.container_port)
+                            }
+                            None => format!("{}:{}", port.host_port, port.container_port),
+                        })
+                        .collect(),
+                };
+                (spec.name, service)
+            })
+            .collect();
+
+        Ok(serde_yaml::to_string(&ComposeFile { services })?)
+    }
+}
+
+#[async_trait]
+impl ContainerEngine for ComposeEngine {
+    async fn start(&self, specs: &[ContainerSpec], is_preview: bool) -> CliResult<()> {
+        let compose_file = Self::render(specs)?;
+        if is_preview {
+            println!(
+                "Compose file {0}:\n{compose_file}",
+                self.compose_file.display()
+            );
+            return Ok(());
+        }
+
+        println!("Writing compose file {0}", self.compose_file.display());
+        tokio::fs::write(&self.compose_file, compose_file).await?;
+
+        let status = Command::new("docker")
+            .arg("compose")
+            .arg("--project-name")
+            .arg(PROJECT_NAME)
+            .arg("--file")
+            .arg(&self.compose_file)
+            .args(["up", "--detach"])
+            .status()
+            .await?;
+        if !status.success() {
+            return Err(SiCliError::Compose(status.code()));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use async_trait::async_trait;
+use docker_api::opts::{ContainerConnectionOpts, ContainerCreateOpts, HostPort, PublishPort};
+
+use super::{ContainerEngine, ContainerSpec};
+use crate::containers::DockerClient;
+use crate::CliResult;
+
+/// Runs the containers through the Docker engine API, connecting them with links.
+#[derive(Clone, Debug)]
+pub struct DockerEngine {
+    docker: DockerClient,
+}
+
+impl DockerEngine {
+    pub fn new(docker: DockerClient) -> Self {
+        Self { docker }
+    }
+}
+
+#[async_trait]
+impl ContainerEngine for DockerEngine {
+    async fn start(&self, specs: &[ContainerSpec], is_preview: bool) -> CliResult<()> {
+        start_containers(&self.docker, specs, is_preview, None).await
+    }
+}
+
+/// Starts the containers through a Docker compatible API. They are connected with links, or by
+/// joining `network` with their component name as an alias if given.
+pub(super) async fn start_containers(
+    docker: &DockerClient,
+    specs: &[ContainerSpec],
+    is_preview: bool,
+    network: Option<&str>,
+) -> CliResult<()> {
+    for spec in specs {
+        let image = spec.image();
+        let container_name = spec.container_name();
+        let container_summary = docker
+            .get_existing_container(container_name.clone())
+            .await?;
+        if let Some(existing) = container_summary {
+            // it means we have an existing container
+            // If it's running, we have nothing to do here
+            if existing.state.as_deref() == Some("running") {
+                continue;
+            }
+
+            let existing_container = docker.containers().get(existing.id.as_ref().unwrap());
+            if !spec.recreate_when_stopped {
+                println!("Starting existing {0}", container_name.clone());
+                existing_container.start().await?;
+                continue;
+            }
+            if !is_preview {
+                println!("Deleting existing container {0}", container_name.clone());
+                existing_container.delete().await?;
+            }
+        }
+
+        if is_preview {
+            println!("{0} as {1}", image, container_name.clone());
+            continue;
+        }
+        println!("Starting {0} as {1}", image, container_name.clone());
+
+        let mut create_opts = ContainerCreateOpts::builder()
+            .name(container_name.clone())
+            .image(image);
+        if !spec.command.is_empty() {
+            create_opts = create_opts.command(spec.command.clone());
+        }
+        if !spec.env.is_empty() {
+            create_opts = create_opts.env(spec.env.clone());
+        }
+        if network.is_none() && !spec.links.is_empty() {
+            create_opts = create_opts.links(
+                spec.links
+                    .iter()
+                    .map(|link| format!("local-{link}-1:{link}")),
+            );
+        }
+        if !spec.volumes.is_empty() {
+            create_opts = create_opts.volumes(spec.volumes.clone());
+        }
+        if !spec.ports.is_empty() {
+            create_opts = create_opts.network_mode("bridge");
+            for port in &spec.ports {
+                let host_port = match &port.host_ip {
+                    Some(host_ip) => HostPort::with_ip(port.host_port, host_ip.clone()),
+                    None => HostPort::new(port.host_port),
+                };
+                create_opts = create_opts.expose(PublishPort::tcp(port.container_port), host_port);
+            }
+        }
+
+        let container = docker.containers().create(&create_opts.build()).await?;
+        if let Some(network) = network {
+            let connection_opts = ContainerConnectionOpts::builder(container.id().to_string())
+                .aliases([spec.name])
+                .build();
+            docker
+                .networks()
+                .get(network)
+                .connect(&connection_opts)
+                .await?;
+        }
+        container.start().await?;
+    }
+
+    Ok(())
+}
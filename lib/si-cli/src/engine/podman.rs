@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use docker_api::opts::NetworkCreateOpts;
+
+use super::{docker::start_containers, ContainerEngine, ContainerSpec};
+use crate::containers::DockerClient;
+use crate::CliResult;
+
+/// The network the containers join under Podman, which does not support links.
+const NETWORK_NAME: &str = "si";
+
+/// Runs the containers through the Docker compatible API of Podman, on a shared network where
+/// each container is reachable by its component name.
+#[derive(Clone, Debug)]
+pub struct PodmanEngine {
+    docker: DockerClient,
+}
+
+impl PodmanEngine {
+    /// Creates an engine for the API served on the Podman socket, such as
+    /// `$XDG_RUNTIME_DIR/podman/podman.sock`.
+    pub fn new(docker: DockerClient) -> Self {
+        Self { docker }
+    }
+
+    async fn ensure_network(&self) -> CliResult<()> {
+        if self
+            .docker
+            .networks()
+            .get(NETWORK_NAME)
+            .inspect()
+            .await
+            .is_err()
+        {
+            println!("Creating network {NETWORK_NAME}");
+            self.docker
+                .networks()
+                .create(&NetworkCreateOpts::builder(NETWORK_NAME).build())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ContainerEngine for PodmanEngine {
+    async fn start(&self, specs: &[ContainerSpec], is_preview: bool) -> CliResult<()> {
+        if !is_preview {
+            self.ensure_network().await?;
+        }
+        start_containers(&self.docker, specs, is_preview, Some(NETWORK_NAME)).await
+    }
+}
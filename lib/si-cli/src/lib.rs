@@ -3,6 +3,7 @@ use thiserror::Error;
 
 pub mod cmd;
 mod containers;
+pub mod engine;
 mod key_management;
 pub mod state;
 
@@ -15,8 +16,16 @@ pub const CONTAINER_NAMES: &[&str] = &[
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SiCliError {
+    #[error("backup failed its integrity check: {0}")]
+    BackupIntegrity(String),
+    #[error("docker compose failed with exit code {0:?}")]
+    Compose(Option<i32>),
+    #[error("container {0} is not running")]
+    ContainerNotRunning(String),
     #[error("ctrl+c")]
     CtrlC,
+    #[error("{0} failed: {1}")]
+    DatabaseCommand(String, String),
     #[error("docker api: {0}")]
     Docker(#[from] docker_api::Error),
     #[error("container search failed: {0}")]
@@ -29,14 +38,24 @@ pub enum SiCliError {
     IncorrectInstallMode(String),
     #[error("aborting installation")]
     Installation,
+    #[error("invalid duration {0}, expected a number followed by s, m, h or d")]
+    InvalidDuration(String),
     #[error("io: {0}")]
     Io(#[from] std::io::Error),
     #[error("join: {0}")]
     Join(#[from] tokio::task::JoinError),
     #[error("Unable to find local data dir. Expected format `$HOME/.local/share` or `$HOME/Library/Application Support`")]
     MissingDataDir(),
+    #[error("no upgrade to roll back")]
+    NothingToRollBack,
     #[error("reqwest: {0}")]
     Reqwest(#[from] reqwest::Error),
+    #[error("serde json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("serde yaml: {0}")]
+    SerdeYaml(#[from] serde_yaml::Error),
+    #[error("system components are not healthy: {0}")]
+    SystemUnhealthy(String),
     #[error("toml deserialize error: {0}")]
     TomlDeserialize(#[from] toml::de::Error),
     #[error("unable to download update, status = {0}")]
@@ -4,6 +4,8 @@ use std::ops::Deref;
 use std::sync::Arc;
 use telemetry::tracing;
 
+use crate::engine::EngineKind;
+
 pub struct AppState {
     posthog_client: PosthogClient,
     version: Arc<str>,
@@ -11,6 +13,7 @@ pub struct AppState {
     is_preview: bool,
     web_host: String,
     web_port: u32,
+    engine: EngineKind,
 }
 
 impl AppState {
@@ -21,6 +24,7 @@ impl AppState {
         is_preview: bool,
         web_host: String,
         web_port: u32,
+        engine: EngineKind,
     ) -> Self {
         Self {
             posthog_client: posthog_client.into(),
@@ -29,6 +33,7 @@ impl AppState {
             is_preview,
             web_host,
             web_port,
+            engine,
         }
     }
 
@@ -52,6 +57,10 @@ impl AppState {
         self.web_port
     }
 
+    pub fn engine(&self) -> EngineKind {
+        self.engine
+    }
+
     pub fn posthog_client(&self) -> &PosthogClient {
         &self.posthog_client
     }
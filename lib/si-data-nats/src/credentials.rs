@@ -0,0 +1,280 @@
+//! Issues the credentials of NATS users which are only allowed some subjects, so that a
+//! connection made on behalf of a single tenant can't read or write the messages of the others.
+//!
+//! Credentials are user JWTs signed by an account the NATS server trusts (with the decentralized
+//! JWT authentication of NATS), whose permissions the server enforces on every publish and
+//! subscription of the user. The issuer holds the seed of the account, and every user gets a new
+//! key pair:
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use si_data_nats::{credentials::{CredentialsIssuer, NatsPermissions}, Client};
+//! # tokio_test::block_on(async {
+//! let seed = std::fs::read_to_string("/run/sdf/nats_account.nk")?;
+//! let issuer = CredentialsIssuer::from_account_seed(&seed)?;
+//! let credentials = issuer.issue(
+//!     "workspace-updates",
+//!     &NatsPermissions {
+//!         publish: vec![],
+//!         subscribe: vec!["si.*.01H0000000000000000000WKSP.*.*".to_string()],
+//!     },
+//!     Duration::from_secs(3600),
+//! )?;
+//! let nats = Client::connect_with_options("localhost", None, credentials.options()).await?;
+//! # Ok::<(), Box<dyn std::error::Error + 'static>>(()) });
+//! ```
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use nkeys::KeyPair;
+use serde::Serialize;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::Options;
+
+/// The first character of the public key of an account.
+const ACCOUNT_PUBLIC_KEY_PREFIX: char = 'A';
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum CredentialsError {
+    #[error("invalid nkey seed: {0}")]
+    InvalidSeed(#[source] nkeys::error::Error),
+    #[error("the seed of an account is required, got a seed for {0}")]
+    NotAnAccountSeed(String),
+    #[error("error serializing claims: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("error signing claims: {0}")]
+    Sign(#[source] nkeys::error::Error),
+}
+
+pub type CredentialsResult<T> = Result<T, CredentialsError>;
+
+/// The subjects a user is allowed to publish on and to subscribe to, which may have wildcards.
+/// Every other subject is denied.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NatsPermissions {
+    pub publish: Vec<String>,
+    pub subscribe: Vec<String>,
+}
+
+/// Issues user credentials signed by an account.
+#[derive(Clone)]
+pub struct CredentialsIssuer {
+    account: Arc<KeyPair>,
+}
+
+impl CredentialsIssuer {
+    pub fn from_account_seed(seed: &str) -> CredentialsResult<Self> {
+        let account = KeyPair::from_seed(seed.trim()).map_err(CredentialsError::InvalidSeed)?;
+        let public_key = account.public_key();
+        if !public_key.starts_with(ACCOUNT_PUBLIC_KEY_PREFIX) {
+            return Err(CredentialsError::NotAnAccountSeed(public_key));
+        }
+        Ok(Self {
+            account: Arc::new(account),
+        })
+    }
+
+    /// The public key of the account, which the NATS server must trust.
+    pub fn account_public_key(&self) -> String {
+        self.account.public_key()
+    }
+
+    /// Issues the credentials of a new user, which expire after the given time.
+    pub fn issue(
+        &self,
+        name: &str,
+        permissions: &NatsPermissions,
+        ttl: Duration,
+    ) -> CredentialsResult<UserCredentials> {
+        let user = KeyPair::new_user();
+        let issued_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let claims = UserClaims {
+            jti: Ulid::new().to_string(),
+            iat: issued_at,
+            exp: issued_at + ttl.as_secs(),
+            iss: self.account.public_key(),
+            name,
+            sub: user.public_key(),
+            nats: UserNatsClaims {
+                publish: Permission {
+                    allow: &permissions.publish,
+                },
+                subscribe: Permission {
+                    allow: &permissions.subscribe,
+                },
+                subs: -1,
+                data: -1,
+                payload: -1,
+                kind: "user",
+                version: 2,
+            },
+        };
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&JwtHeader {
+                typ: "JWT",
+                alg: "ed25519-nkey",
+            })?),
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?),
+        );
+        let signature = self
+            .account
+            .sign(signing_input.as_bytes())
+            .map_err(CredentialsError::Sign)?;
+
+        Ok(UserCredentials {
+            jwt: format!("{signing_input}.{}", URL_SAFE_NO_PAD.encode(signature)),
+            user: Arc::new(user),
+        })
+    }
+}
+
+impl fmt::Debug for CredentialsIssuer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialsIssuer")
+            .field("account", &self.account.public_key())
+            .finish()
+    }
+}
+
+/// The credentials of a user: its JWT, and the key pair to sign the nonce of the server with.
+#[derive(Clone)]
+pub struct UserCredentials {
+    jwt: String,
+    user: Arc<KeyPair>,
+}
+
+impl UserCredentials {
+    pub fn jwt(&self) -> &str {
+        &self.jwt
+    }
+
+    /// The options to connect to NATS as the user.
+    pub fn options(&self) -> Options {
+        let jwt = self.jwt.clone();
+        let user = self.user.clone();
+        Options::with_jwt(
+            move || Ok(jwt.clone()),
+            // Only key pairs without a seed can't sign, and the user has one
+            move |nonce| user.sign(nonce).unwrap_or_default(),
+        )
+    }
+}
+
+impl fmt::Debug for UserCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserCredentials")
+            .field("user", &self.user.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize)]
+struct JwtHeader {
+    typ: &'static str,
+    alg: &'static str,
+}
+
+#[derive(Serialize)]
+struct UserClaims<'a> {
+    jti: String,
+    iat: u64,
+    exp: u64,
+    iss: String,
+    name: &'a str,
+    sub: String,
+    nats: UserNatsClaims<'a>,
+}
+
+#[derive(Serialize)]
+struct UserNatsClaims<'a> {
+    #[serde(rename = "pub")]
+    publish: Permission<'a>,
+    #[serde(rename = "sub")]
+    subscribe: Permission<'a>,
+    // Limits of -1 leave the user to the limits of its account
+    subs: i64,
+    data: i64,
+    payload: i64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    version: u8,
+}
+
+#[derive(Serialize)]
+struct Permission<'a> {
+    allow: &'a [String],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issues_user_jwts_signed_by_the_account() {
+        let account = KeyPair::new_account();
+        let issuer = CredentialsIssuer::from_account_seed(
+            &account.seed().expect("failed to get account seed"),
+        )
+        .expect("failed to create issuer");
+        let permissions = NatsPermissions {
+            publish: vec!["si._.wksp.cs.presence".to_string()],
+            subscribe: vec!["si.*.wksp.*.*".to_string()],
+        };
+
+        let credentials = issuer
+            .issue("session", &permissions, Duration::from_secs(60))
+            .expect("failed to issue credentials");
+
+        let parts: Vec<&str> = credentials.jwt().split('.').collect();
+        assert_eq!(3, parts.len());
+        let (header, claims) = (parts[0], parts[1]);
+        let signature = URL_SAFE_NO_PAD
+            .decode(parts[2])
+            .expect("failed to decode signature");
+        account
+            .verify(format!("{header}.{claims}").as_bytes(), &signature)
+            .expect("jwt is not signed by the account");
+
+        let claims: serde_json::Value = serde_json::from_slice(
+            &URL_SAFE_NO_PAD
+                .decode(claims)
+                .expect("failed to decode claims"),
+        )
+        .expect("failed to deserialize claims");
+        assert_eq!(account.public_key(), claims["iss"]);
+        assert_eq!("user", claims["nats"]["type"]);
+        assert_eq!(
+            serde_json::json!(["si.*.wksp.*.*"]),
+            claims["nats"]["sub"]["allow"]
+        );
+        assert_eq!(
+            Some(60),
+            claims["exp"]
+                .as_u64()
+                .zip(claims["iat"].as_u64())
+                .map(|(exp, iat)| exp - iat)
+        );
+    }
+
+    #[test]
+    fn requires_an_account_seed() {
+        let user = KeyPair::new_user();
+        assert!(matches!(
+            CredentialsIssuer::from_account_seed(&user.seed().expect("failed to get user seed")),
+            Err(CredentialsError::NotAnAccountSeed(_))
+        ));
+    }
+}
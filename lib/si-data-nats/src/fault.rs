@@ -0,0 +1,165 @@
+//! Faults injected into the messages published and the requests made through a
+//! [`Client`](crate::Client), so that tests can exercise how slow or failing messaging is handled.
+//!
+//! A client has no faults until some are added to its [`NatsFaults`], which are shared by every
+//! clone of the client:
+//!
+//! ```ignore
+//! nats.faults().delay_publishes(Duration::from_millis(200));
+//! ```
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use telemetry::prelude::*;
+
+use crate::Error;
+
+/// The operations faults can be injected into.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NatsOperation {
+    /// Publishing a message, with or without a reply subject.
+    Publish,
+    /// Making a request and waiting for its response.
+    Request,
+}
+
+/// What happens to an operation a fault is injected into.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug)]
+pub enum NatsFault {
+    /// The operation is delayed, then runs as usual.
+    Delay(Duration),
+    /// The operation fails without reaching the server.
+    Fail,
+}
+
+/// Which operations a fault is injected into, counting only the operations of its kind.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug)]
+pub enum NatsFaultTrigger {
+    /// Every operation.
+    Always,
+    /// Every `n`th operation, starting with the `n`th.
+    EveryNth(usize),
+    /// The next `n` operations, and none after them.
+    Next(usize),
+}
+
+#[derive(Debug)]
+struct Rule {
+    operation: NatsOperation,
+    trigger: NatsFaultTrigger,
+    fault: NatsFault,
+    seen: usize,
+}
+
+impl Rule {
+    fn triggers(&mut self) -> bool {
+        self.seen += 1;
+        match self.trigger {
+            NatsFaultTrigger::Always => true,
+            NatsFaultTrigger::EveryNth(n) => n > 0 && self.seen % n == 0,
+            NatsFaultTrigger::Next(n) => self.seen <= n,
+        }
+    }
+}
+
+/// The faults injected into the operations of a [`Client`](crate::Client).
+#[derive(Clone, Debug, Default)]
+pub struct NatsFaults {
+    // Checked before taking the lock, so that clients without faults pay next to nothing for them.
+    armed: Arc<AtomicBool>,
+    rules: Arc<Mutex<Vec<Rule>>>,
+}
+
+impl NatsFaults {
+    /// Injects `fault` into the operations of kind `operation` selected by `trigger`.
+    pub fn add(&self, operation: NatsOperation, trigger: NatsFaultTrigger, fault: NatsFault) {
+        self.rules
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Rule {
+                operation,
+                trigger,
+                fault,
+                seen: 0,
+            });
+        self.armed.store(true, Ordering::Release);
+    }
+
+    /// Fails every `n`th publish.
+    pub fn fail_every_nth_publish(&self, n: usize) {
+        self.add(
+            NatsOperation::Publish,
+            NatsFaultTrigger::EveryNth(n),
+            NatsFault::Fail,
+        );
+    }
+
+    /// Fails the next `n` requests.
+    pub fn fail_next_requests(&self, n: usize) {
+        self.add(
+            NatsOperation::Request,
+            NatsFaultTrigger::Next(n),
+            NatsFault::Fail,
+        );
+    }
+
+    /// Delays every publish by `delay`.
+    pub fn delay_publishes(&self, delay: Duration) {
+        self.add(
+            NatsOperation::Publish,
+            NatsFaultTrigger::Always,
+            NatsFault::Delay(delay),
+        );
+    }
+
+    /// Removes every fault, letting all operations through.
+    pub fn clear(&self) {
+        self.rules
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        self.armed.store(false, Ordering::Release);
+    }
+
+    /// Applies the faults triggered by an operation of kind `operation`, returning the error it
+    /// should fail with, if any.
+    pub(crate) async fn inject(&self, operation: NatsOperation) -> Result<(), Error> {
+        if !self.armed.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let mut delay = Duration::ZERO;
+        let mut failed = false;
+        {
+            let mut rules = self.rules.lock().unwrap_or_else(PoisonError::into_inner);
+            for rule in rules.iter_mut() {
+                if rule.operation != operation || !rule.triggers() {
+                    continue;
+                }
+                match rule.fault {
+                    NatsFault::Delay(rule_delay) => delay += rule_delay,
+                    NatsFault::Fail => failed = true,
+                }
+            }
+        }
+
+        if !delay.is_zero() {
+            debug!(?operation, ?delay, "delaying operation with injected fault");
+            tokio::time::sleep(delay).await;
+        }
+        if failed {
+            debug!(?operation, "failing operation with injected fault");
+            return Err(Error::Injected);
+        }
+        Ok(())
+    }
+}
@@ -11,25 +11,40 @@ use std::{fmt::Debug, io, sync::Arc, time::Duration};
 
 use crossbeam_channel::RecvError;
 use serde::{Deserialize, Serialize};
+use si_error::{CategorizedError, ErrorCategory};
+use telemetry::metrics::Counter;
 use telemetry::prelude::*;
+use telemetry::propagation::TraceContext;
 use thiserror::Error;
 use tokio::{
     sync::Mutex,
     task::{self, spawn_blocking},
 };
 
+pub mod credentials;
+mod fault;
 pub mod jetstream;
 mod message;
 mod options;
+pub mod subject;
 mod subscription;
 
+pub use fault::{NatsFault, NatsFaultTrigger, NatsFaults, NatsOperation};
 pub use message::Message;
 pub use nats::{header::HeaderMap, rustls};
 pub use options::Options;
-pub use subscription::Subscription;
+pub use subject::{EventSubject, EventSubjectBuilder, SubjectError, SubjectToken};
+pub use subscription::{OpenSubscription, Subscription};
+
+use subscription::SubscriptionTracker;
 
 pub type NatsError = Error;
 
+static MESSAGES_PUBLISHED: Counter = Counter::new(
+    "si_nats_messages_published_total",
+    "Total number of messages published to NATS",
+);
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum Error {
@@ -37,18 +52,67 @@ pub enum Error {
     Async(#[from] task::JoinError),
     #[error("crossbeam select error: {0}")]
     CrossBeamChannel(#[from] RecvError),
+    #[error("injected fault")]
+    Injected,
     #[error("nats client error: {0}")]
     Nats(#[from] io::Error),
     #[error("error serializing object: {0}")]
     Serialize(#[source] serde_json::Error),
+    #[error("subject error: {0}")]
+    Subject(#[from] SubjectError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The trace context a message was published with, if any.
+pub fn trace_context_from_headers(headers: Option<&HeaderMap>) -> TraceContext {
+    TraceContext::from_headers(header_pairs(headers))
+}
+
+/// Adds the trace context of the current span to the headers of a message being published, so
+/// that its receivers can continue the trace.
+fn with_trace_context(headers: Option<&HeaderMap>) -> Option<HeaderMap> {
+    let trace_context = TraceContext::current();
+    if trace_context.is_empty() {
+        return headers.cloned();
+    }
+    let mut pairs: Vec<(&str, &str)> = header_pairs(headers).collect();
+    pairs.extend(trace_context.iter());
+    Some(pairs.iter().collect())
+}
+
+fn header_pairs(headers: Option<&HeaderMap>) -> impl Iterator<Item = (&str, &str)> {
+    headers
+        .into_iter()
+        .flat_map(|headers| headers.iter())
+        .flat_map(|(name, values)| {
+            values
+                .iter()
+                .map(move |value| (name.as_str(), value.as_str()))
+        })
+}
+
+impl CategorizedError for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            // Injected faults stand in for a server which is unavailable
+            Self::Injected => ErrorCategory::Transient,
+            Self::Nats(err) => err.category(),
+            Self::Serialize(err) => err.category(),
+            Self::Async(_) | Self::CrossBeamChannel(_) | Self::Subject(_) => {
+                ErrorCategory::Invariant
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct NatsConfig {
     pub url: String,
     pub subject_prefix: Option<String>,
+    /// Subscriptions which are held for longer than this are reported as possibly leaked. No
+    /// warnings are logged if unset, as most subscriptions live as long as their service.
+    pub subscription_warn_after_secs: Option<u64>,
 }
 
 impl Default for NatsConfig {
@@ -56,6 +120,7 @@ impl Default for NatsConfig {
         Self {
             url: "localhost".to_string(),
             subject_prefix: None,
+            subscription_warn_after_secs: None,
         }
     }
 }
@@ -88,12 +153,18 @@ pub struct Client {
 impl Client {
     #[instrument(name = "client::new", skip_all, level = "debug")]
     pub async fn new(config: &NatsConfig) -> Result<Self> {
-        Self::connect_with_options(
+        let client = Self::connect_with_options(
             &config.url,
             config.subject_prefix.clone(),
             Options::default(),
         )
-        .await
+        .await?;
+        client
+            .metadata
+            .subscriptions
+            .set_warn_after(config.subscription_warn_after_secs.map(Duration::from_secs));
+
+        Ok(client)
     }
 
     #[instrument(
@@ -174,6 +245,8 @@ impl Client {
             messaging_url: nats_url.clone(),
             net_transport: "ip_tcp",
             subject_prefix,
+            subscriptions: Arc::new(SubscriptionTracker::default()),
+            faults: NatsFaults::default(),
         };
 
         let span = Span::current();
@@ -207,6 +280,10 @@ impl Client {
     /// let sub = nc.subscribe("foo").await?;
     /// # Ok::<(), Box<dyn std::error::Error + 'static>>(()) });
     /// ```
+    pub async fn subscribe(&self, subject: impl Into<String>) -> Result<Subscription> {
+        self.subscribe_for(subject.into(), Span::current()).await
+    }
+
     #[instrument(
         name = "client.subscribe",
         skip_all,
@@ -227,10 +304,9 @@ impl Client {
             otel.status_message = Empty,
         )
     )]
-    pub async fn subscribe(&self, subject: impl Into<String>) -> Result<Subscription> {
+    async fn subscribe_for(&self, subject: String, owner: Span) -> Result<Subscription> {
         let span = Span::current();
 
-        let subject = subject.into();
         span.record("messaging.destination", subject.as_str());
         span.record("otel.name", format!("{} receive", &subject).as_str());
         let inner = self.inner.clone();
@@ -245,6 +321,7 @@ impl Client {
             subject,
             self.metadata.clone(),
             current_span_for_debug!(),
+            owner,
         ))
     }
 
@@ -258,6 +335,15 @@ impl Client {
     /// let sub = nc.queue_subscribe("foo", "production").await?;
     /// # Ok::<(), Box<dyn std::error::Error + 'static>>(()) });
     /// ```
+    pub async fn queue_subscribe(
+        &self,
+        subject: impl Into<String>,
+        queue: impl Into<String>,
+    ) -> Result<Subscription> {
+        self.queue_subscribe_for(subject.into(), queue.into(), Span::current())
+            .await
+    }
+
     #[instrument(
         name = "client.queue_subscribe",
         skip_all,
@@ -278,15 +364,14 @@ impl Client {
             otel.status_message = Empty,
         )
     )]
-    pub async fn queue_subscribe(
+    async fn queue_subscribe_for(
         &self,
-        subject: impl Into<String>,
-        queue: impl Into<String>,
+        subject: String,
+        queue: String,
+        owner: Span,
     ) -> Result<Subscription> {
         let span = Span::current();
 
-        let subject = subject.into();
-        let queue = queue.into();
         span.record("messaging.destination", subject.as_str());
         span.record("messaging.subscription.queue", queue.as_str());
         span.record("otel.name", format!("{} receive", &subject).as_str());
@@ -302,6 +387,7 @@ impl Client {
             subject,
             self.metadata.clone(),
             current_span_for_debug!(),
+            owner,
         ))
     }
 
@@ -320,6 +406,19 @@ impl Client {
             .await
     }
 
+    /// Publish a message on the given [`EventSubject`], which cannot have wildcards, and on its
+    /// [legacy subject](EventSubject::legacy_subject()) for the consumers which have not moved to
+    /// the [subject taxonomy](subject) yet.
+    pub async fn publish_event(
+        &self,
+        subject: &EventSubject,
+        msg: impl Into<Vec<u8>>,
+    ) -> Result<()> {
+        let msg = msg.into();
+        self.publish(subject.publishable()?, msg.clone()).await?;
+        self.publish(subject.legacy_subject(), msg).await
+    }
+
     /// Publish a message on the given subject with a reply subject for responses.
     ///
     /// # Examples
@@ -357,6 +456,11 @@ impl Client {
         msg: impl Into<Vec<u8>>,
     ) -> Result<()> {
         let span = Span::current();
+        self.metadata
+            .faults
+            .inject(NatsOperation::Publish)
+            .await
+            .map_err(|err| span.record_err(err))?;
 
         let subject = subject.into();
         let reply = reply.into();
@@ -426,6 +530,11 @@ impl Client {
         msg: impl Into<Vec<u8>>,
     ) -> Result<Message> {
         let span = Span::current();
+        self.metadata
+            .faults
+            .inject(NatsOperation::Request)
+            .await
+            .map_err(|err| span.record_err(err))?;
 
         let subject = subject.into();
         let msg = msg.into();
@@ -481,6 +590,11 @@ impl Client {
         timeout: Duration,
     ) -> Result<Message> {
         let span = Span::current();
+        self.metadata
+            .faults
+            .inject(NatsOperation::Request)
+            .await
+            .map_err(|err| span.record_err(err))?;
 
         let subject = subject.into();
         let msg = msg.into();
@@ -508,6 +622,15 @@ impl Client {
     /// for msg in nc.request_multi("foo", "Help").await?.take(1).next().await {}
     /// # Ok::<(), Box<dyn std::error::Error + 'static>>(()) });
     /// ```
+    pub async fn request_multi(
+        &self,
+        subject: impl Into<String>,
+        msg: impl Into<Vec<u8>>,
+    ) -> Result<Subscription> {
+        self.request_multi_for(subject.into(), msg.into(), Span::current())
+            .await
+    }
+
     #[instrument(
         name = "client.request_multi",
         skip_all,
@@ -526,16 +649,15 @@ impl Client {
             otel.status_message = Empty,
         )
     )]
-    pub async fn request_multi(
+    async fn request_multi_for(
         &self,
-        subject: impl Into<String>,
-        msg: impl Into<Vec<u8>>,
+        subject: String,
+        msg: Vec<u8>,
+        owner: Span,
     ) -> Result<Subscription> {
         let span = Span::current();
 
-        let subject = subject.into();
         let sub_span_subject = subject.clone();
-        let msg = msg.into();
         span.record("messaging.destination", subject.as_str());
         span.record("otel.name", format!("{} send", &subject).as_str());
         let inner = self.inner.clone();
@@ -569,6 +691,7 @@ impl Client {
             subject,
             self.metadata.clone(),
             current_span_for_debug!(),
+            owner,
         ))
     }
 
@@ -868,9 +991,14 @@ impl Client {
         msg: impl Into<Vec<u8>>,
     ) -> Result<()> {
         let span = Span::current();
+        self.metadata
+            .faults
+            .inject(NatsOperation::Publish)
+            .await
+            .map_err(|err| span.record_err(err))?;
 
         let subject = subject.into();
-        let headers = headers.map(HeaderMap::clone);
+        let headers = with_trace_context(headers);
         let reply = reply.map(Into::into);
         let msg = msg.into();
         span.record("messaging.destination", subject.as_str());
@@ -882,6 +1010,7 @@ impl Client {
         .await
         .map_err(|err| span.record_err(Error::Async(err)))?
         .map_err(|err| span.record_err(Error::Nats(err)))?;
+        MESSAGES_PUBLISHED.increment(&[]);
 
         span.record_ok();
         Ok(())
@@ -906,6 +1035,18 @@ impl Client {
     pub fn metadata(&self) -> &ConnectionMetadata {
         self.metadata.as_ref()
     }
+
+    /// Lists the subscriptions of this client and its clones which have not been dropped yet,
+    /// oldest first, along with the span which created each of them.
+    /// The faults injected into the messages published and the requests made through this
+    /// client, which only tests are expected to add.
+    pub fn faults(&self) -> &NatsFaults {
+        &self.metadata.faults
+    }
+
+    pub fn dump_subscriptions(&self) -> Vec<OpenSubscription> {
+        self.metadata.subscriptions.dump()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -916,6 +1057,8 @@ pub struct ConnectionMetadata {
     messaging_url: String,
     subject_prefix: Option<String>,
     net_transport: &'static str,
+    subscriptions: Arc<SubscriptionTracker>,
+    faults: NatsFaults,
 }
 
 impl ConnectionMetadata {
@@ -995,6 +1138,28 @@ impl NatsTxn {
         Ok(())
     }
 
+    /// Publishes the object on the given [`EventSubject`], which cannot have wildcards, and on its
+    /// [legacy subject](EventSubject::legacy_subject()) when the transaction is committed. See
+    /// [`Client::publish_event()`].
+    pub async fn publish_event<T>(&self, subject: &EventSubject, object: &T) -> Result<()>
+    where
+        T: Serialize + Debug,
+    {
+        self.publish(subject.publishable()?, object).await?;
+        self.publish(subject.legacy_subject(), object).await
+    }
+
+    /// Returns the number of messages waiting to be published when the transaction is committed.
+    pub async fn pending_len(&self) -> usize {
+        self.pending_publish.lock().await.len()
+    }
+
+    /// Discards the messages which were published after the first `len` ones, such as when
+    /// rolling back to a savepoint.
+    pub async fn truncate_pending(&self, len: usize) {
+        self.pending_publish.lock().await.truncate(len);
+    }
+
     #[instrument(
         name = "transaction.commit_into_conn",
         skip_all,
@@ -1,11 +1,12 @@
 use std::{fmt, sync::Arc};
 
 use telemetry::prelude::*;
+use telemetry::propagation::TraceContext;
 use tokio::task::spawn_blocking;
 
 use super::{
     jetstream::{AckKind, JetStreamMessageInfo},
-    ConnectionMetadata, Error, HeaderMap, Result,
+    trace_context_from_headers, ConnectionMetadata, Error, HeaderMap, Result,
 };
 
 #[derive(Clone)]
@@ -43,6 +44,12 @@ impl Message {
         self.inner.headers.as_ref()
     }
 
+    /// Gets the trace context the message was published with, to continue its trace.
+    #[must_use]
+    pub fn trace_context(&self) -> TraceContext {
+        trace_context_from_headers(self.headers())
+    }
+
     /// Consumes the message and returns the inner data.
     #[must_use]
     pub fn into_data(self) -> Vec<u8> {
@@ -0,0 +1,306 @@
+//! The taxonomy of the [NATS](https://nats.io) subjects on which tenant scoped events are
+//! published:
+//!
+//! ```text
+//! si.{billing_account}.{workspace}.{change_set}.{event_kind}
+//! ```
+//!
+//! An [`EventSubject`] is built with [`EventSubject::builder()`], where every token which is not
+//! set is the `*` wildcard, and parsed from a subject with [`str::parse()`]. Only subjects without
+//! wildcards can be published on.
+//!
+//! Events used to be published on `si.workspace_pk.{workspace}.{event_kind}` subjects. Until every
+//! consumer has moved to the taxonomy, [`Client::publish_event()`](crate::Client::publish_event)
+//! and [`NatsTxn::publish_event()`](crate::NatsTxn::publish_event) publish each event on its
+//! [legacy subject](EventSubject::legacy_subject()) as well, and legacy subjects can still be
+//! parsed.
+
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+/// The first token of every [`EventSubject`].
+pub const EVENT_SUBJECT_ROOT: &str = "si";
+/// The billing account token for tenants which do not belong to a billing account.
+pub const NO_BILLING_ACCOUNT: &str = "_";
+/// The second token of the subjects events were published on before the taxonomy.
+const LEGACY_WORKSPACE_TOKEN: &str = "workspace_pk";
+
+#[remain::sorted]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SubjectError {
+    #[error("invalid subject token {0:?}: tokens cannot be empty or contain '.', '*', '>' or whitespace")]
+    InvalidToken(String),
+    #[error("not an event subject: {0}")]
+    NotAnEventSubject(String),
+    #[error("cannot publish on a subject with wildcards: {0}")]
+    Wildcard(String),
+}
+
+pub type SubjectResult<T> = Result<T, SubjectError>;
+
+/// A single token of an [`EventSubject`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SubjectToken {
+    /// Matches any single token.
+    #[default]
+    Any,
+    Value(String),
+}
+
+impl SubjectToken {
+    /// Creates a token with the given value, which cannot be empty or contain separators,
+    /// wildcards or whitespace.
+    pub fn value(value: impl fmt::Display) -> SubjectResult<Self> {
+        let value = value.to_string();
+        if value.is_empty()
+            || value
+                .chars()
+                .any(|c| matches!(c, '.' | '*' | '>') || c.is_whitespace())
+        {
+            return Err(SubjectError::InvalidToken(value));
+        }
+        Ok(Self::Value(value))
+    }
+
+    /// Returns the value of the token, unless it is a wildcard.
+    pub fn as_value(&self) -> Option<&str> {
+        match self {
+            Self::Any => None,
+            Self::Value(value) => Some(value),
+        }
+    }
+
+    pub fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+}
+
+impl fmt::Display for SubjectToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => f.write_str("*"),
+            Self::Value(value) => f.write_str(value),
+        }
+    }
+}
+
+impl FromStr for SubjectToken {
+    type Err = SubjectError;
+
+    fn from_str(s: &str) -> SubjectResult<Self> {
+        match s {
+            "*" => Ok(Self::Any),
+            value => Self::value(value),
+        }
+    }
+}
+
+/// A subject of the `si.{billing_account}.{workspace}.{change_set}.{event_kind}` taxonomy, either
+/// to publish on or, with wildcards, to subscribe to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EventSubject {
+    billing_account: SubjectToken,
+    workspace: SubjectToken,
+    change_set: SubjectToken,
+    event_kind: SubjectToken,
+}
+
+impl EventSubject {
+    pub fn builder() -> EventSubjectBuilder {
+        EventSubjectBuilder::default()
+    }
+
+    pub fn billing_account(&self) -> &SubjectToken {
+        &self.billing_account
+    }
+
+    pub fn workspace(&self) -> &SubjectToken {
+        &self.workspace
+    }
+
+    pub fn change_set(&self) -> &SubjectToken {
+        &self.change_set
+    }
+
+    pub fn event_kind(&self) -> &SubjectToken {
+        &self.event_kind
+    }
+
+    /// Whether any token is a wildcard, in which case the subject can only be subscribed to.
+    pub fn has_wildcards(&self) -> bool {
+        [
+            &self.billing_account,
+            &self.workspace,
+            &self.change_set,
+            &self.event_kind,
+        ]
+        .into_iter()
+        .any(SubjectToken::is_any)
+    }
+
+    /// Returns the subject, or an error if it has wildcards and so cannot be published on.
+    pub fn publishable(&self) -> SubjectResult<String> {
+        if self.has_wildcards() {
+            return Err(SubjectError::Wildcard(self.to_string()));
+        }
+        Ok(self.to_string())
+    }
+
+    /// The `si.workspace_pk.{workspace}.{event_kind}` subject which events were published on
+    /// before the taxonomy, which has no billing account or change set tokens.
+    pub fn legacy_subject(&self) -> String {
+        format!(
+            "{EVENT_SUBJECT_ROOT}.{LEGACY_WORKSPACE_TOKEN}.{}.{}",
+            self.workspace, self.event_kind
+        )
+    }
+}
+
+impl fmt::Display for EventSubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{EVENT_SUBJECT_ROOT}.{}.{}.{}.{}",
+            self.billing_account, self.workspace, self.change_set, self.event_kind
+        )
+    }
+}
+
+impl FromStr for EventSubject {
+    type Err = SubjectError;
+
+    /// Parses a subject of the taxonomy or a legacy subject, whose billing account and change set
+    /// are unknown and so parsed as wildcards.
+    fn from_str(s: &str) -> SubjectResult<Self> {
+        let not_an_event_subject = || SubjectError::NotAnEventSubject(s.to_string());
+        let tokens: Vec<&str> = s.split('.').collect();
+        match tokens.as_slice() {
+            [EVENT_SUBJECT_ROOT, LEGACY_WORKSPACE_TOKEN, workspace, event_kind] => Ok(Self {
+                billing_account: SubjectToken::Any,
+                workspace: workspace.parse().map_err(|_| not_an_event_subject())?,
+                change_set: SubjectToken::Any,
+                event_kind: event_kind.parse().map_err(|_| not_an_event_subject())?,
+            }),
+            [EVENT_SUBJECT_ROOT, billing_account, workspace, change_set, event_kind] => Ok(Self {
+                billing_account: billing_account
+                    .parse()
+                    .map_err(|_| not_an_event_subject())?,
+                workspace: workspace.parse().map_err(|_| not_an_event_subject())?,
+                change_set: change_set.parse().map_err(|_| not_an_event_subject())?,
+                event_kind: event_kind.parse().map_err(|_| not_an_event_subject())?,
+            }),
+            _ => Err(not_an_event_subject()),
+        }
+    }
+}
+
+/// Builds an [`EventSubject`]. Tokens which are not set are wildcards.
+#[derive(Clone, Debug, Default)]
+pub struct EventSubjectBuilder {
+    billing_account: Option<String>,
+    workspace: Option<String>,
+    change_set: Option<String>,
+    event_kind: Option<String>,
+}
+
+impl EventSubjectBuilder {
+    pub fn billing_account(mut self, billing_account: impl fmt::Display) -> Self {
+        self.billing_account = Some(billing_account.to_string());
+        self
+    }
+
+    pub fn workspace(mut self, workspace: impl fmt::Display) -> Self {
+        self.workspace = Some(workspace.to_string());
+        self
+    }
+
+    pub fn change_set(mut self, change_set: impl fmt::Display) -> Self {
+        self.change_set = Some(change_set.to_string());
+        self
+    }
+
+    pub fn event_kind(mut self, event_kind: impl fmt::Display) -> Self {
+        self.event_kind = Some(event_kind.to_string());
+        self
+    }
+
+    pub fn build(self) -> SubjectResult<EventSubject> {
+        fn token(value: Option<String>) -> SubjectResult<SubjectToken> {
+            value.map_or(Ok(SubjectToken::Any), SubjectToken::value)
+        }
+
+        Ok(EventSubject {
+            billing_account: token(self.billing_account)?,
+            workspace: token(self.workspace)?,
+            change_set: token(self.change_set)?,
+            event_kind: token(self.event_kind)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_and_parse() {
+        let subject = EventSubject::builder()
+            .billing_account(NO_BILLING_ACCOUNT)
+            .workspace("01H0000000000000000000WKSP")
+            .change_set("01H00000000000000000000CS1")
+            .event_kind("event")
+            .build()
+            .expect("could not build subject");
+        assert_eq!(
+            "si._.01H0000000000000000000WKSP.01H00000000000000000000CS1.event",
+            subject.to_string()
+        );
+        assert_eq!(Ok(subject.to_string()), subject.publishable());
+        assert_eq!(Ok(subject.clone()), subject.to_string().parse());
+        assert_eq!(
+            "si.workspace_pk.01H0000000000000000000WKSP.event",
+            subject.legacy_subject()
+        );
+    }
+
+    #[test]
+    fn unset_tokens_are_wildcards() {
+        let subject = EventSubject::builder()
+            .event_kind("presence")
+            .build()
+            .expect("could not build subject");
+        assert_eq!("si.*.*.*.presence", subject.to_string());
+        assert!(matches!(
+            subject.publishable(),
+            Err(SubjectError::Wildcard(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        for token in ["", "a.b", "a*", ">", "a b"] {
+            assert_eq!(
+                Err(SubjectError::InvalidToken(token.to_string())),
+                EventSubject::builder().workspace(token).build()
+            );
+        }
+        assert!("si.a.b.>".parse::<EventSubject>().is_err());
+        assert!("si.a.b.c".parse::<EventSubject>().is_err());
+        assert!("other.a.b.c.d".parse::<EventSubject>().is_err());
+    }
+
+    #[test]
+    fn parse_legacy_subject() {
+        let subject: EventSubject = "si.workspace_pk.01H0000000000000000000WKSP.event"
+            .parse()
+            .expect("could not parse legacy subject");
+        assert!(subject.billing_account().is_any());
+        assert!(subject.change_set().is_any());
+        assert_eq!(
+            Some("01H0000000000000000000WKSP"),
+            subject.workspace().as_value()
+        );
+        assert_eq!(Some("event"), subject.event_kind().as_value());
+    }
+}
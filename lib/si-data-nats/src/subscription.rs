@@ -1,16 +1,35 @@
 use std::{
+    collections::HashMap,
     future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 use futures::{FutureExt, Stream};
+use telemetry::metrics::{Counter, Gauge};
 use telemetry::prelude::*;
 use tokio::task::{spawn_blocking, JoinHandle};
 
 use super::{ConnectionMetadata, Error, Message, Result};
 
+static MESSAGES_CONSUMED: Counter = Counter::new(
+    "si_nats_messages_consumed_total",
+    "Total number of messages consumed from NATS subscriptions",
+);
+static OPEN_SUBSCRIPTIONS: Gauge = Gauge::new(
+    "si_nats_subscriptions",
+    "Number of NATS subscriptions which have not been dropped",
+);
+static SUBSCRIPTIONS_HELD_TOO_LONG: Counter = Counter::new(
+    "si_nats_subscriptions_held_too_long_total",
+    "Total number of subscriptions held longer than the subscription warning threshold",
+);
+
 /// A `Subscription` receives `Message`s published to specific NATS `Subject`s.
 #[derive(Debug)]
 pub struct Subscription {
@@ -22,6 +41,7 @@ pub struct Subscription {
     shutdown_rx: crossbeam_channel::Receiver<()>,
     metadata: Arc<SubscriptionMessageMetadata>,
     sub_span: Span,
+    _tracking: SubscriptionGuard,
 }
 
 impl Subscription {
@@ -30,6 +50,7 @@ impl Subscription {
         subject: String,
         connection_metadata: Arc<ConnectionMetadata>,
         sub_span: Span,
+        owner: Span,
     ) -> Self {
         // We don't use the tx side explicitly, but rather rely on the behavior when this
         // Subscription is dropped, then tx is closed and the rx side running in a thread will get
@@ -41,6 +62,7 @@ impl Subscription {
         // but with cloneable tx and rx ends.
         let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(0);
 
+        let tracking = connection_metadata.subscriptions.track(&subject, owner);
         let metadata = SubscriptionMessageMetadata {
             connection_metadata,
             messaging_destination: subject.clone(),
@@ -58,6 +80,7 @@ impl Subscription {
             shutdown_rx,
             metadata: Arc::new(metadata),
             sub_span,
+            _tracking: tracking,
         }
     }
 
@@ -275,7 +298,10 @@ impl Stream for Subscription {
         // Poll the `NextMessage` future
         match Pin::new(&mut next).poll(cx) {
             // We got a new message, pass it on
-            Poll::Ready(Ok(Some(ready))) => Poll::Ready(Some(Ok(ready))),
+            Poll::Ready(Ok(Some(ready))) => {
+                MESSAGES_CONSUMED.increment(&[]);
+                Poll::Ready(Some(Ok(ready)))
+            }
             // `NextMessage` yielded `None`, meaning the inner subscription is done, so close this
             // stream out
             Poll::Ready(Ok(None)) => Poll::Ready(None),
@@ -396,3 +422,156 @@ impl SubscriptionMessageMetadata {
         self.connection_metadata.clone()
     }
 }
+
+/// A subscription which has not been dropped yet, as listed by
+/// [`Client::dump_subscriptions`](crate::Client::dump_subscriptions).
+#[derive(Clone, Debug)]
+pub struct OpenSubscription {
+    pub id: u64,
+    pub subject: String,
+    /// The name of the span which was current when the subscription was created, if any.
+    pub owner: Option<&'static str>,
+    /// The target of the owner span, usually its module path.
+    pub owner_target: Option<&'static str>,
+    /// How long the subscription has been open.
+    pub age: Duration,
+}
+
+#[derive(Debug)]
+struct TrackedSubscription {
+    subject: String,
+    owner: Span,
+    subscribed_at: Instant,
+    warned: bool,
+}
+
+impl TrackedSubscription {
+    fn warn(&self, id: u64, age: Duration) {
+        SUBSCRIPTIONS_HELD_TOO_LONG.increment(&[]);
+        warn!(
+            parent: &self.owner,
+            messaging.subscription.id = id,
+            messaging.subscription.age_secs = age.as_secs(),
+            messaging.subscription.owner = self.owner.metadata().map(|metadata| metadata.name()),
+            messaging.subject = %self.subject,
+            "nats subscription held too long; it may have leaked",
+        );
+    }
+}
+
+/// Tracks the subscriptions of a client and its clones, to find the ones which are held too long
+/// or never dropped.
+#[derive(Debug, Default)]
+pub(crate) struct SubscriptionTracker {
+    /// The warning threshold in milliseconds, where zero disables warnings.
+    warn_after_millis: AtomicU64,
+    next_id: AtomicU64,
+    subscriptions: Mutex<HashMap<u64, TrackedSubscription>>,
+}
+
+impl SubscriptionTracker {
+    pub(crate) fn set_warn_after(&self, warn_after: Option<Duration>) {
+        let millis = warn_after.map_or(0, |duration| {
+            u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+        });
+        self.warn_after_millis.store(millis, Ordering::Relaxed);
+    }
+
+    fn warn_after(&self) -> Option<Duration> {
+        match self.warn_after_millis.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(Duration::from_millis(millis)),
+        }
+    }
+
+    /// Tracks a subscription until the returned guard is dropped, warning about any other
+    /// subscription which has been held too long along the way.
+    fn track(self: &Arc<Self>, subject: &str, owner: Span) -> SubscriptionGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let now = Instant::now();
+        let mut subscriptions = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(warn_after) = self.warn_after() {
+            for (id, subscription) in subscriptions.iter_mut() {
+                let age = now.saturating_duration_since(subscription.subscribed_at);
+                if !subscription.warned && age >= warn_after {
+                    subscription.warned = true;
+                    subscription.warn(*id, age);
+                }
+            }
+        }
+        subscriptions.insert(
+            id,
+            TrackedSubscription {
+                subject: subject.to_owned(),
+                owner,
+                subscribed_at: now,
+                warned: false,
+            },
+        );
+        OPEN_SUBSCRIPTIONS.increment(&[]);
+
+        SubscriptionGuard {
+            id,
+            tracker: self.clone(),
+        }
+    }
+
+    fn release(&self, id: u64) {
+        let subscription = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        if let Some(subscription) = subscription {
+            OPEN_SUBSCRIPTIONS.decrement(&[]);
+            let age = subscription.subscribed_at.elapsed();
+            if let Some(warn_after) = self.warn_after() {
+                if !subscription.warned && age >= warn_after {
+                    subscription.warn(id, age);
+                }
+            }
+        }
+    }
+
+    /// Lists the subscriptions which have not been dropped yet, oldest first.
+    pub(crate) fn dump(&self) -> Vec<OpenSubscription> {
+        let now = Instant::now();
+        let mut subscriptions: Vec<OpenSubscription> = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(id, subscription)| OpenSubscription {
+                id: *id,
+                subject: subscription.subject.clone(),
+                owner: subscription
+                    .owner
+                    .metadata()
+                    .map(|metadata| metadata.name()),
+                owner_target: subscription
+                    .owner
+                    .metadata()
+                    .map(|metadata| metadata.target()),
+                age: now.saturating_duration_since(subscription.subscribed_at),
+            })
+            .collect();
+        subscriptions.sort_by(|a, b| b.age.cmp(&a.age));
+        subscriptions
+    }
+}
+
+/// Stops tracking a subscription when dropped.
+#[derive(Debug)]
+struct SubscriptionGuard {
+    id: u64,
+    tracker: Arc<SubscriptionTracker>,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.tracker.release(self.id);
+    }
+}
@@ -0,0 +1,156 @@
+//! Content addressed storage for large blobs, such as generated code and the artifacts generated
+//! alongside it, which would otherwise bloat the rows holding them. Blobs are keyed by the
+//! [`BlobHash`] of their content, so storing the same content twice stores it once, and rows refer
+//! to a blob with its [reference](BlobHash::to_reference()).
+//!
+//! [`PgBlobStore`] keeps blobs in the `blobs` table of the database. Other backends, such as an
+//! S3 compatible object store, implement [`BlobStore`].
+
+use std::{fmt, str::FromStr};
+
+use async_trait::async_trait;
+
+use crate::{PgError, PgSharedTransaction};
+
+/// Tells a blob reference apart from inline content.
+const REFERENCE_PREFIX: &str = "blake3:";
+
+#[remain::sorted]
+#[derive(thiserror::Error, Debug)]
+pub enum BlobError {
+    #[error("invalid blob hash: {0}")]
+    InvalidHash(String),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+}
+
+/// The BLAKE3 hash of the content of a blob.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlobHash(blake3::Hash);
+
+impl BlobHash {
+    /// Computes the hash of the given content.
+    #[must_use]
+    pub fn new(content: &[u8]) -> Self {
+        Self(blake3::hash(content))
+    }
+
+    /// Returns the string rows use to refer to the blob: the hash, prefixed with `blake3:`.
+    #[must_use]
+    pub fn to_reference(&self) -> String {
+        format!("{REFERENCE_PREFIX}{self}")
+    }
+
+    /// Parses a string written by [`Self::to_reference()`], returning `None` for any other
+    /// string, such as inline content.
+    #[must_use]
+    pub fn from_reference(reference: &str) -> Option<Self> {
+        reference.strip_prefix(REFERENCE_PREFIX)?.parse().ok()
+    }
+}
+
+impl fmt::Display for BlobHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for BlobHash {
+    type Err = BlobError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(
+            blake3::Hash::from_str(s).map_err(|_| BlobError::InvalidHash(s.to_owned()))?,
+        ))
+    }
+}
+
+/// A store of content addressed blobs.
+#[async_trait]
+pub trait BlobStore {
+    /// Stores the content, unless it is already stored, and returns its hash.
+    async fn put(&self, content: &[u8]) -> Result<BlobHash, BlobError>;
+
+    /// Returns the content of the blob, if it is stored.
+    async fn get(&self, hash: &BlobHash) -> Result<Option<Vec<u8>>, BlobError>;
+
+    /// Deletes the given blobs, returning how many were stored.
+    async fn delete(&self, hashes: &[BlobHash]) -> Result<u64, BlobError>;
+}
+
+/// A [`BlobStore`] keeping blobs in the `blobs` table. Blobs are stored as part of the
+/// transaction, so they are only visible once the rows referring to them are committed.
+///
+/// Storing a blob again refreshes its `stored_at` timestamp, which garbage collection relies on to
+/// leave alone the blobs which may be referred to by transactions that have not committed yet.
+#[derive(Debug)]
+pub struct PgBlobStore<'a> {
+    txn: &'a PgSharedTransaction,
+}
+
+impl<'a> PgBlobStore<'a> {
+    pub fn new(txn: &'a PgSharedTransaction) -> Self {
+        Self { txn }
+    }
+}
+
+#[async_trait]
+impl<'a> BlobStore for PgBlobStore<'a> {
+    async fn put(&self, content: &[u8]) -> Result<BlobHash, BlobError> {
+        let hash = BlobHash::new(content);
+        self.txn
+            .execute(
+                "INSERT INTO blobs (hash, content, size) VALUES ($1, $2, $3)
+                 ON CONFLICT (hash) DO UPDATE SET stored_at = CLOCK_TIMESTAMP()",
+                &[&hash.to_string(), &content, &(content.len() as i64)],
+            )
+            .await?;
+        Ok(hash)
+    }
+
+    async fn get(&self, hash: &BlobHash) -> Result<Option<Vec<u8>>, BlobError> {
+        let row = self
+            .txn
+            .query_opt(
+                "SELECT content FROM blobs WHERE hash = $1",
+                &[&hash.to_string()],
+            )
+            .await?;
+        match row {
+            Some(row) => Ok(Some(row.try_get("content")?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete(&self, hashes: &[BlobHash]) -> Result<u64, BlobError> {
+        let hashes: Vec<String> = hashes.iter().map(ToString::to_string).collect();
+        Ok(self
+            .txn
+            .execute("DELETE FROM blobs WHERE hash = ANY($1)", &[&hashes])
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_round_trip() {
+        let hash = BlobHash::new(b"resource \"aws_instance\" \"web\" {}");
+        let reference = hash.to_reference();
+
+        assert!(reference.starts_with("blake3:"));
+        assert_eq!(Some(hash), BlobHash::from_reference(&reference));
+    }
+
+    #[test]
+    fn inline_content_is_not_a_reference() {
+        assert_eq!(None, BlobHash::from_reference("{\"kind\": \"Pod\"}"));
+        assert_eq!(None, BlobHash::from_reference("blake3:not-a-hash"));
+        assert_eq!(
+            None,
+            BlobHash::from_reference(&BlobHash::new(b"no prefix").to_string())
+        );
+    }
+}
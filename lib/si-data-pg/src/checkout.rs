@@ -0,0 +1,199 @@
+//! Tracking of the connections checked out of a [`PgPool`](crate::PgPool), to find the ones which
+//! are held too long or never returned before the pool is exhausted.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use deadpool_postgres::Pool;
+use telemetry::{
+    metrics::{Counter, Gauge},
+    prelude::*,
+};
+
+static POOL_CONNECTIONS: Gauge = Gauge::new(
+    "si_pg_pool_connections",
+    "Number of connections in the PostgreSQL pool, by role, server and state",
+);
+static CHECKOUTS_HELD_TOO_LONG: Counter = Counter::new(
+    "si_pg_pool_checkouts_held_too_long_total",
+    "Total number of connections held longer than the checkout warning threshold",
+);
+
+/// A connection which is currently checked out of a [`PgPool`](crate::PgPool).
+#[derive(Clone, Debug)]
+pub struct PgCheckout {
+    pub id: u64,
+    /// The name of the span which was current when the connection was checked out, if any.
+    pub owner: Option<&'static str>,
+    /// The target of the owner span, usually its module path.
+    pub owner_target: Option<&'static str>,
+    /// The address of the database server, which differs from the primary's for read replicas.
+    pub net_peer_ip: String,
+    /// How long the connection has been checked out.
+    pub age: Duration,
+}
+
+#[derive(Debug)]
+struct Checkout {
+    owner: Span,
+    net_peer_ip: String,
+    checked_out_at: Instant,
+    warned: bool,
+}
+
+impl Checkout {
+    fn warn(&self, id: u64, age: Duration) {
+        CHECKOUTS_HELD_TOO_LONG.increment(&[]);
+        warn!(
+            parent: &self.owner,
+            db.checkout.id = id,
+            db.checkout.age_secs = age.as_secs(),
+            db.checkout.owner = self.owner.metadata().map(|metadata| metadata.name()),
+            net.peer.ip = %self.net_peer_ip,
+            "pg pool connection held too long; it may have leaked",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CheckoutTracker {
+    db_name: String,
+    warn_after: Option<Duration>,
+    next_id: AtomicU64,
+    checkouts: Mutex<HashMap<u64, Checkout>>,
+}
+
+impl CheckoutTracker {
+    pub(crate) fn new(db_name: String, warn_after: Option<Duration>) -> Self {
+        Self {
+            db_name,
+            warn_after,
+            next_id: AtomicU64::new(0),
+            checkouts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tracks a connection checked out of `pool` until the returned guard is dropped, warning
+    /// about any other connection which has been held too long along the way.
+    ///
+    /// The `role` of the pool is either `"primary"` or `"replica"`, which together with
+    /// `net_peer_ip` tells the pools apart in the connection gauges.
+    pub(crate) fn check_out(
+        self: &Arc<Self>,
+        owner: Span,
+        role: &'static str,
+        net_peer_ip: &str,
+        pool: Pool,
+    ) -> CheckoutGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let now = Instant::now();
+        {
+            let mut checkouts = self
+                .checkouts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(warn_after) = self.warn_after {
+                for (id, checkout) in checkouts.iter_mut() {
+                    let age = now.saturating_duration_since(checkout.checked_out_at);
+                    if !checkout.warned && age >= warn_after {
+                        checkout.warned = true;
+                        checkout.warn(*id, age);
+                    }
+                }
+            }
+            checkouts.insert(
+                id,
+                Checkout {
+                    owner,
+                    net_peer_ip: net_peer_ip.to_owned(),
+                    checked_out_at: now,
+                    warned: false,
+                },
+            );
+        }
+        self.record_pool_gauges(&pool, role, net_peer_ip);
+
+        CheckoutGuard {
+            id,
+            tracker: self.clone(),
+            role,
+            net_peer_ip: net_peer_ip.to_owned(),
+            pool,
+        }
+    }
+
+    fn release(&self, id: u64, role: &'static str, net_peer_ip: &str, pool: &Pool) {
+        let checkout = self
+            .checkouts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        if let (Some(checkout), Some(warn_after)) = (checkout, self.warn_after) {
+            let age = checkout.checked_out_at.elapsed();
+            if !checkout.warned && age >= warn_after {
+                checkout.warn(id, age);
+            }
+        }
+        self.record_pool_gauges(pool, role, net_peer_ip);
+    }
+
+    fn record_pool_gauges(&self, pool: &Pool, role: &str, net_peer_ip: &str) {
+        let status = pool.status();
+        let idle = status.available.max(0);
+        let size = i64::try_from(status.size).unwrap_or(i64::MAX);
+        let idle = i64::try_from(idle).unwrap_or(i64::MAX);
+        let labels = |state| {
+            [
+                ("db", self.db_name.as_str()),
+                ("role", role),
+                ("peer", net_peer_ip),
+                ("state", state),
+            ]
+        };
+        POOL_CONNECTIONS.set(&labels("idle"), idle);
+        POOL_CONNECTIONS.set(&labels("in_use"), size.saturating_sub(idle));
+    }
+
+    /// Lists the connections which are currently checked out, oldest first.
+    pub(crate) fn dump(&self) -> Vec<PgCheckout> {
+        let now = Instant::now();
+        let mut checkouts: Vec<PgCheckout> = self
+            .checkouts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(id, checkout)| PgCheckout {
+                id: *id,
+                owner: checkout.owner.metadata().map(|metadata| metadata.name()),
+                owner_target: checkout.owner.metadata().map(|metadata| metadata.target()),
+                net_peer_ip: checkout.net_peer_ip.clone(),
+                age: now.saturating_duration_since(checkout.checked_out_at),
+            })
+            .collect();
+        checkouts.sort_by(|a, b| b.age.cmp(&a.age));
+        checkouts
+    }
+}
+
+/// Stops tracking a checked out connection when dropped, which is when the connection returns to
+/// the pool.
+pub(crate) struct CheckoutGuard {
+    id: u64,
+    tracker: Arc<CheckoutTracker>,
+    role: &'static str,
+    net_peer_ip: String,
+    pool: Pool,
+}
+
+impl Drop for CheckoutGuard {
+    fn drop(&mut self) {
+        self.tracker
+            .release(self.id, self.role, &self.net_peer_ip, &self.pool);
+    }
+}
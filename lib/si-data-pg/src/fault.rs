@@ -0,0 +1,242 @@
+//! Faults injected into the statements and commits made through a [`PgPool`](crate::PgPool), so
+//! that tests can exercise how failures such as serialization failures are handled.
+//!
+//! A pool has no faults until some are added to its [`PgFaults`], which are shared by every
+//! clone of the pool:
+//!
+//! ```ignore
+//! pg_pool
+//!     .faults()
+//!     .fail_next_commits(1, SqlState::T_R_SERIALIZATION_FAILURE);
+//! ```
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use telemetry::prelude::*;
+use tokio_postgres::error::SqlState;
+
+use crate::PgError;
+
+/// The operations faults can be injected into.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PgOperation {
+    /// Committing a transaction.
+    Commit,
+    /// Running a statement, whether or not it returns rows.
+    Query,
+}
+
+/// What happens to an operation a fault is injected into.
+#[remain::sorted]
+#[derive(Clone, Debug)]
+pub enum PgFault {
+    /// The operation is delayed, then runs as usual.
+    Delay(Duration),
+    /// The operation fails with the given state, without reaching the database.
+    Fail(SqlState),
+}
+
+/// Which operations a fault is injected into, counting only the operations of its kind.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug)]
+pub enum PgFaultTrigger {
+    /// Every operation.
+    Always,
+    /// Every `n`th operation, starting with the `n`th.
+    EveryNth(usize),
+    /// The next `n` operations, and none after them.
+    Next(usize),
+}
+
+#[derive(Debug)]
+struct Rule {
+    operation: PgOperation,
+    trigger: PgFaultTrigger,
+    fault: PgFault,
+    seen: usize,
+}
+
+impl Rule {
+    fn triggers(&mut self) -> bool {
+        self.seen += 1;
+        match self.trigger {
+            PgFaultTrigger::Always => true,
+            PgFaultTrigger::EveryNth(n) => n > 0 && self.seen % n == 0,
+            PgFaultTrigger::Next(n) => self.seen <= n,
+        }
+    }
+}
+
+/// The faults injected into the operations of a [`PgPool`](crate::PgPool).
+#[derive(Clone, Debug, Default)]
+pub struct PgFaults {
+    // Checked before taking the lock, so that pools without faults pay next to nothing for them.
+    armed: Arc<AtomicBool>,
+    rules: Arc<Mutex<Vec<Rule>>>,
+}
+
+impl PgFaults {
+    /// Injects `fault` into the operations of kind `operation` selected by `trigger`.
+    pub fn add(&self, operation: PgOperation, trigger: PgFaultTrigger, fault: PgFault) {
+        self.rules
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Rule {
+                operation,
+                trigger,
+                fault,
+                seen: 0,
+            });
+        self.armed.store(true, Ordering::Release);
+    }
+
+    /// Fails every `n`th statement with the given state.
+    pub fn fail_every_nth_query(&self, n: usize, code: SqlState) {
+        self.add(
+            PgOperation::Query,
+            PgFaultTrigger::EveryNth(n),
+            PgFault::Fail(code),
+        );
+    }
+
+    /// Fails the next `n` statements with the given state.
+    pub fn fail_next_queries(&self, n: usize, code: SqlState) {
+        self.add(
+            PgOperation::Query,
+            PgFaultTrigger::Next(n),
+            PgFault::Fail(code),
+        );
+    }
+
+    /// Fails the next `n` commits with the given state, rolling their transactions back.
+    pub fn fail_next_commits(&self, n: usize, code: SqlState) {
+        self.add(
+            PgOperation::Commit,
+            PgFaultTrigger::Next(n),
+            PgFault::Fail(code),
+        );
+    }
+
+    /// Delays every statement by `delay`.
+    pub fn delay_queries(&self, delay: Duration) {
+        self.add(
+            PgOperation::Query,
+            PgFaultTrigger::Always,
+            PgFault::Delay(delay),
+        );
+    }
+
+    /// Removes every fault, letting all operations through.
+    pub fn clear(&self) {
+        self.rules
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        self.armed.store(false, Ordering::Release);
+    }
+
+    /// Applies the faults triggered by an operation of kind `operation`, returning the error it
+    /// should fail with, if any.
+    pub(crate) async fn inject(&self, operation: PgOperation) -> Result<(), PgError> {
+        if !self.armed.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let mut delay = Duration::ZERO;
+        let mut failure = None;
+        {
+            let mut rules = self.rules.lock().unwrap_or_else(PoisonError::into_inner);
+            for rule in rules.iter_mut() {
+                if rule.operation != operation || !rule.triggers() {
+                    continue;
+                }
+                match &rule.fault {
+                    PgFault::Delay(rule_delay) => delay += *rule_delay,
+                    PgFault::Fail(code) => {
+                        failure.get_or_insert_with(|| code.clone());
+                    }
+                }
+            }
+        }
+
+        if !delay.is_zero() {
+            debug!(?operation, ?delay, "delaying operation with injected fault");
+            tokio::time::sleep(delay).await;
+        }
+        match failure {
+            Some(code) => {
+                debug!(
+                    ?operation,
+                    code = code.code(),
+                    "failing operation with injected fault"
+                );
+                Err(PgError::Injected(code))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use si_error::{CategorizedError, ErrorCategory};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fails_every_nth_query_only() {
+        let faults = PgFaults::default();
+        faults.fail_every_nth_query(2, SqlState::CONNECTION_FAILURE);
+
+        let mut outcomes = Vec::new();
+        for _ in 0..4 {
+            outcomes.push(faults.inject(PgOperation::Query).await.is_err());
+        }
+        assert_eq!(vec![false, true, false, true], outcomes);
+        assert!(faults.inject(PgOperation::Commit).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn injected_failures_are_categorized() {
+        let faults = PgFaults::default();
+        for (code, category) in [
+            (SqlState::CONNECTION_FAILURE, ErrorCategory::Transient),
+            (SqlState::UNIQUE_VIOLATION, ErrorCategory::Conflict),
+            (SqlState::INVALID_TEXT_REPRESENTATION, ErrorCategory::User),
+            (SqlState::UNDEFINED_TABLE, ErrorCategory::Invariant),
+        ] {
+            faults.fail_every_nth_query(1, code);
+            let err = faults
+                .inject(PgOperation::Query)
+                .await
+                .expect_err("query should fail");
+            assert_eq!(category, err.category());
+            faults.clear();
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_next_commits_as_conflicts() {
+        let faults = PgFaults::default();
+        faults.fail_next_commits(1, SqlState::T_R_SERIALIZATION_FAILURE);
+
+        let err = faults
+            .inject(PgOperation::Commit)
+            .await
+            .expect_err("first commit should fail");
+        assert!(err.is_transaction_conflict());
+        assert!(err.is_retryable());
+        assert!(faults.inject(PgOperation::Commit).await.is_ok());
+
+        faults.fail_next_commits(1, SqlState::T_R_SERIALIZATION_FAILURE);
+        faults.clear();
+        assert!(faults.inject(PgOperation::Commit).await.is_ok());
+    }
+}
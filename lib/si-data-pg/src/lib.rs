@@ -11,8 +11,11 @@ use std::{
     cmp,
     fmt::{self, Debug},
     net::ToSocketAddrs,
-    sync::Arc,
-    time::Duration,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use bytes::Buf;
@@ -21,9 +24,10 @@ use deadpool_postgres::{
     Config, ConfigError, CreatePoolError, Manager, ManagerConfig, Pool, PoolConfig, PoolError,
     RecyclingMethod, Transaction, TransactionBuilder,
 };
-use futures::{Stream, StreamExt};
+use futures::{Stream, StreamExt, TryStreamExt};
 use ouroboros::self_referencing;
 use serde::{Deserialize, Serialize};
+use si_error::{CategorizedError, ErrorCategory};
 use si_std::{ResultExt, SensitiveString};
 use telemetry::prelude::*;
 use tokio::sync::Mutex;
@@ -34,8 +38,20 @@ use tokio_postgres::{
     SimpleQueryMessage, Statement, ToStatement,
 };
 
+pub use blob::{BlobError, BlobHash, BlobStore, PgBlobStore};
+pub use checkout::PgCheckout;
+pub use fault::{PgFault, PgFaultTrigger, PgFaults, PgOperation};
+pub use stats::{PgPoolStats, SlowQuery};
 pub use tokio_postgres::error::SqlState;
 
+use checkout::{CheckoutGuard, CheckoutTracker};
+use stats::QueryMetrics;
+
+mod blob;
+mod checkout;
+mod fault;
+mod stats;
+
 const MIGRATION_LOCK_NUMBER: i64 = 42;
 const MAX_POOL_SIZE_MINIMUM: usize = 32;
 
@@ -44,6 +60,8 @@ const TEST_QUERY: &str = "SELECT 1";
 #[remain::sorted]
 #[derive(thiserror::Error, Debug)]
 pub enum PgError {
+    #[error("injected fault: {}", .0.code())]
+    Injected(SqlState),
     #[error(transparent)]
     Pg(#[from] tokio_postgres::Error),
     #[error("transaction not exclusively referenced when commit attempted; arc_strong_count={0}")]
@@ -54,6 +72,132 @@ pub enum PgError {
     TxnRollbackNotExclusive(usize),
 }
 
+impl PgError {
+    /// Whether the error is a serialization failure or a deadlock, which go away when the
+    /// transaction is retried.
+    pub fn is_transaction_conflict(&self) -> bool {
+        match self {
+            Self::Injected(code) => is_transaction_conflict_code(code),
+            Self::Pg(err) => err.code().map_or(false, is_transaction_conflict_code),
+            _ => false,
+        }
+    }
+}
+
+/// Whether the error, or any of the errors which caused it, is a serialization failure or a
+/// deadlock reported by PostgreSQL.
+pub fn is_transaction_conflict(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        let conflict = if let Some(err) = err.downcast_ref::<PgError>() {
+            err.is_transaction_conflict()
+        } else if let Some(err) = err.downcast_ref::<tokio_postgres::Error>() {
+            err.code().map_or(false, is_transaction_conflict_code)
+        } else if let Some(err) = err.downcast_ref::<tokio_postgres::error::DbError>() {
+            is_transaction_conflict_code(err.code())
+        } else {
+            false
+        };
+        if conflict {
+            return true;
+        }
+        current = err.source();
+    }
+    false
+}
+
+fn is_transaction_conflict_code(code: &SqlState) -> bool {
+    *code == SqlState::T_R_SERIALIZATION_FAILURE || *code == SqlState::T_R_DEADLOCK_DETECTED
+}
+
+/// Categorizes the error if it is one reported by PostgreSQL or by the pool, leaving the errors
+/// which caused it to the caller.
+pub fn error_category(err: &(dyn std::error::Error + 'static)) -> Option<ErrorCategory> {
+    if let Some(err) = err.downcast_ref::<PgError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<PgPoolError>() {
+        Some(err.category())
+    } else if let Some(err) = err.downcast_ref::<tokio_postgres::Error>() {
+        Some(err.category())
+    } else {
+        err.downcast_ref::<tokio_postgres::error::DbError>()
+            .map(|err| sql_state_category(err.code()))
+    }
+}
+
+/// Categorizes the errors reported by PostgreSQL by their class: lost connections, transaction
+/// conflicts and exhausted resources are transient, unique and foreign key violations are
+/// conflicts, and invalid data and check violations are user errors.
+fn sql_state_category(code: &SqlState) -> ErrorCategory {
+    if is_transaction_conflict_code(code)
+        || *code == SqlState::LOCK_NOT_AVAILABLE
+        || *code == SqlState::ADMIN_SHUTDOWN
+        || *code == SqlState::CRASH_SHUTDOWN
+        || *code == SqlState::CANNOT_CONNECT_NOW
+    {
+        return ErrorCategory::Transient;
+    }
+    if *code == SqlState::UNIQUE_VIOLATION || *code == SqlState::FOREIGN_KEY_VIOLATION {
+        return ErrorCategory::Conflict;
+    }
+    if *code == SqlState::CHECK_VIOLATION || *code == SqlState::NOT_NULL_VIOLATION {
+        return ErrorCategory::User;
+    }
+    match &code.code()[..2] {
+        // Connection exceptions and insufficient resources
+        "08" | "53" => ErrorCategory::Transient,
+        // Data exceptions
+        "22" => ErrorCategory::User,
+        _ => ErrorCategory::Invariant,
+    }
+}
+
+impl CategorizedError for tokio_postgres::Error {
+    fn category(&self) -> ErrorCategory {
+        if self.is_closed() {
+            return ErrorCategory::Transient;
+        }
+        match self.code() {
+            Some(code) => sql_state_category(code),
+            None => match std::error::Error::source(self)
+                .and_then(|source| source.downcast_ref::<std::io::Error>())
+            {
+                Some(err) => err.category(),
+                None => ErrorCategory::Invariant,
+            },
+        }
+    }
+}
+
+impl CategorizedError for PgError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Injected(code) => sql_state_category(code),
+            Self::Pg(err) => err.category(),
+            Self::TxnCommitNotExclusive(_) | Self::TxnRollbackNotExclusive(_) => {
+                ErrorCategory::Invariant
+            }
+        }
+    }
+}
+
+impl CategorizedError for PgPoolError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Pg(err) => err.category(),
+            Self::PoolError(PoolError::Backend(err)) | Self::TokioPg(err) => err.category(),
+            // Timeouts waiting for a connection, or a pool closed by a shutdown
+            Self::PoolError(_) | Self::ResolveHostnameNoEntries => ErrorCategory::Transient,
+            Self::ResolveHostname(err) => err.category(),
+            Self::CreatePoolError(_)
+            | Self::DeadpoolConfig(_)
+            | Self::Refinery(_)
+            | Self::TestConnectionResult(..)
+            | Self::TokioJoin(_) => ErrorCategory::Invariant,
+        }
+    }
+}
+
 #[remain::sorted]
 #[derive(thiserror::Error, Debug)]
 pub enum PgPoolError {
@@ -95,6 +239,27 @@ pub struct PgPoolConfig {
     pub pool_timeout_wait_secs: Option<u64>,
     pub pool_timeout_create_secs: Option<u64>,
     pub pool_timeout_recycle_secs: Option<u64>,
+    /// Read replicas of the primary database, which serve connections from
+    /// [`PgPool::get_read`]. They share the user, password and database name of the primary.
+    pub read_replicas: Vec<PgReadReplicaConfig>,
+    /// Whether connections prepare each distinct statement once and reuse it, rather than
+    /// preparing it again on every execution.
+    pub statement_cache: bool,
+    /// Statements which take at least this long are kept in the slow query log of
+    /// [`PgPool::stats`].
+    pub slow_query_threshold_ms: u64,
+    /// How many of the most recent slow statements are kept.
+    pub slow_query_log_size: usize,
+    /// Connections which are checked out of the pool for longer than this are reported as
+    /// possibly leaked. No warnings are logged if unset.
+    pub checkout_warn_after_secs: Option<u64>,
+}
+
+/// The location of a read replica of the primary database.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PgReadReplicaConfig {
+    pub hostname: String,
+    pub port: u16,
 }
 
 impl Default for PgPoolConfig {
@@ -112,6 +277,11 @@ impl Default for PgPoolConfig {
             pool_timeout_wait_secs: None,
             pool_timeout_create_secs: None,
             pool_timeout_recycle_secs: None,
+            read_replicas: Vec::new(),
+            statement_cache: true,
+            slow_query_threshold_ms: 100,
+            slow_query_log_size: 32,
+            checkout_warn_after_secs: Some(60),
         }
     }
 }
@@ -120,16 +290,26 @@ impl Default for PgPoolConfig {
 pub struct PgPool {
     pool: Pool,
     metadata: Arc<ConnectionMetadata>,
+    read_replicas: Arc<Vec<ReadReplica>>,
+    next_read_replica: Arc<AtomicUsize>,
+    checkouts: Arc<CheckoutTracker>,
 }
 
 impl std::fmt::Debug for PgPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PgPool")
             .field("metadata", &self.metadata)
+            .field("read_replicas", &self.read_replicas.len())
             .finish_non_exhaustive()
     }
 }
 
+/// A connection pool to one read replica of the primary database.
+struct ReadReplica {
+    pool: Pool,
+    metadata: Arc<ConnectionMetadata>,
+}
+
 #[derive(Clone, Debug)]
 struct ConnectionMetadata {
     db_system: &'static str,
@@ -140,6 +320,9 @@ struct ConnectionMetadata {
     net_peer_ip: String,
     net_peer_port: u16,
     net_transport: &'static str,
+    statement_cache: bool,
+    metrics: Arc<QueryMetrics>,
+    faults: PgFaults,
 }
 
 impl PgPool {
@@ -159,53 +342,37 @@ impl PgPool {
         )
     )]
     pub async fn new(settings: &PgPoolConfig) -> PgPoolResult<Self> {
-        let mut cfg = Config::new();
-        cfg.hosts = Some(vec![settings.hostname.clone()]);
-        cfg.port = Some(settings.port);
-        cfg.user = Some(settings.user.clone());
-        cfg.password = Some(settings.password.clone().into());
-        cfg.dbname = Some(settings.dbname.clone());
-        cfg.application_name = Some(settings.application_name.clone());
-        cfg.manager = Some(ManagerConfig {
-            recycling_method: RecyclingMethod::Fast,
-        });
-        let mut pool_config = PoolConfig::new(settings.pool_max_size);
-        if let Some(secs) = settings.pool_timeout_wait_secs {
-            pool_config.timeouts.wait = Some(Duration::from_secs(secs));
-        }
-        if let Some(secs) = settings.pool_timeout_create_secs {
-            pool_config.timeouts.create = Some(Duration::from_secs(secs));
-        }
-        if let Some(secs) = settings.pool_timeout_recycle_secs {
-            pool_config.timeouts.recycle = Some(Duration::from_secs(secs));
+        let metrics = Arc::new(QueryMetrics::new(
+            Duration::from_millis(settings.slow_query_threshold_ms),
+            settings.slow_query_log_size,
+        ));
+        let faults = PgFaults::default();
+        let pool = create_pool(settings, &settings.hostname, settings.port)?;
+        let metadata = connection_metadata(
+            settings,
+            &settings.hostname,
+            settings.port,
+            metrics.clone(),
+            faults.clone(),
+        )
+        .await?;
+
+        let mut read_replicas = Vec::with_capacity(settings.read_replicas.len());
+        for replica in &settings.read_replicas {
+            read_replicas.push(ReadReplica {
+                pool: create_pool(settings, &replica.hostname, replica.port)?,
+                metadata: Arc::new(
+                    connection_metadata(
+                        settings,
+                        &replica.hostname,
+                        replica.port,
+                        metrics.clone(),
+                        faults.clone(),
+                    )
+                    .await?,
+                ),
+            });
         }
-        debug!(db.pool_config = ?pool_config);
-        cfg.pool = Some(pool_config);
-        let pool = cfg.create_pool(Some(deadpool_postgres::Runtime::Tokio1), NoTls)?;
-
-        let resolving_hostname = format!("{}:{}", settings.hostname, settings.port);
-        let net_peer_ip = tokio::task::spawn_blocking(move || {
-            resolving_hostname
-                .to_socket_addrs()
-                .map_err(PgPoolError::ResolveHostname)
-                .and_then(|mut iter| iter.next().ok_or(PgPoolError::ResolveHostnameNoEntries))
-                .map(|socket_addr| socket_addr.ip().to_string())
-        })
-        .await??;
-
-        let metadata = ConnectionMetadata {
-            db_system: "postgresql",
-            db_connection_string: format!(
-                "postgresql://{}:{}/{}?application_name={}",
-                settings.hostname, settings.port, settings.dbname, settings.application_name
-            ),
-            db_name: settings.dbname.clone(),
-            db_user: settings.user.clone(),
-            db_pool_max_size: settings.pool_max_size,
-            net_peer_ip,
-            net_peer_port: settings.port,
-            net_transport: "ip_tcp",
-        };
 
         let span = Span::current();
         span.record("db.system", metadata.db_system);
@@ -223,6 +390,12 @@ impl PgPool {
         let pg_pool = Self {
             pool,
             metadata: Arc::new(metadata),
+            read_replicas: Arc::new(read_replicas),
+            next_read_replica: Arc::new(AtomicUsize::new(0)),
+            checkouts: Arc::new(CheckoutTracker::new(
+                settings.dbname.clone(),
+                settings.checkout_warn_after_secs.map(Duration::from_secs),
+            )),
         };
 
         // Warm up the pool and test that we can connect to the database. Note that this is only
@@ -283,12 +456,52 @@ impl PgPool {
         Ok(())
     }
 
+    /// Closes the pool, dropping its idle connections and failing any further attempts to get a
+    /// connection. Connections which are currently in use are dropped when they are returned.
+    pub fn close(&self) {
+        self.pool.close();
+        for replica in self.read_replicas.iter() {
+            replica.pool.close();
+        }
+    }
+
     /// Gets the database name for connections in the pool.
     pub fn db_name(&self) -> &str {
         &self.metadata.db_name
     }
 
+    /// The faults injected into the statements and commits made through this pool, which only
+    /// tests are expected to add.
+    pub fn faults(&self) -> &PgFaults {
+        &self.metadata.faults
+    }
+
+    /// Returns a snapshot of the metrics of the statements executed by connections of the pool
+    /// and its read replicas.
+    pub fn stats(&self) -> PgPoolStats {
+        self.metadata.metrics.snapshot()
+    }
+
+    /// Lists the connections which are currently checked out of the pool and its read replicas,
+    /// oldest first, along with the span which checked each of them out.
+    pub fn dump_checkouts(&self) -> Vec<PgCheckout> {
+        self.checkouts.dump()
+    }
+
+    /// Drops the cached prepared statements of every connection, which may no longer be valid
+    /// once the schema changes.
+    fn clear_statement_caches(&self) {
+        self.pool.manager().statement_caches.clear();
+        for replica in self.read_replicas.iter() {
+            replica.pool.manager().statement_caches.clear();
+        }
+    }
+
     /// Retrieve object from pool or wait for one to become available.
+    pub async fn get(&self) -> PgPoolResult<InstrumentedClient> {
+        self.get_for(Span::current()).await
+    }
+
     #[instrument(
         name = "pool.get",
         skip_all,
@@ -306,7 +519,7 @@ impl PgPool {
             net.transport = %self.metadata.net_transport,
         )
     )]
-    pub async fn get(&self) -> PgPoolResult<InstrumentedClient> {
+    async fn get_for(&self, owner: Span) -> PgPoolResult<InstrumentedClient> {
         let pool_status = self.pool.status();
         let span = Span::current();
         span.record("db.pool.max_size", pool_status.max_size);
@@ -318,9 +531,68 @@ impl PgPool {
         Ok(InstrumentedClient {
             inner,
             metadata: self.metadata.clone(),
+            _checkout: self.checkouts.check_out(
+                owner,
+                "primary",
+                &self.metadata.net_peer_ip,
+                self.pool.clone(),
+            ),
         })
     }
 
+    /// Retrieve an object suitable for read-only queries, from one of the read replicas in turn.
+    ///
+    /// Replicas lag behind the primary, so reads may not observe the most recent writes. Falls
+    /// back to the primary if there are no read replicas or the chosen one is unavailable.
+    pub async fn get_read(&self) -> PgPoolResult<InstrumentedClient> {
+        self.get_read_for(Span::current()).await
+    }
+
+    #[instrument(
+        name = "pool.get_read",
+        skip_all,
+        level = "debug",
+        fields(
+            db.read_replica = Empty,
+            net.peer.ip = Empty,
+            net.peer.port = Empty,
+        )
+    )]
+    async fn get_read_for(&self, owner: Span) -> PgPoolResult<InstrumentedClient> {
+        if self.read_replicas.is_empty() {
+            return self.get_for(owner).await;
+        }
+
+        let index =
+            self.next_read_replica.fetch_add(1, Ordering::Relaxed) % self.read_replicas.len();
+        let replica = &self.read_replicas[index];
+        let span = Span::current();
+        span.record("db.read_replica", index);
+        span.record("net.peer.ip", replica.metadata.net_peer_ip.as_str());
+        span.record("net.peer.port", replica.metadata.net_peer_port);
+
+        match replica.pool.get().await {
+            Ok(inner) => Ok(InstrumentedClient {
+                inner,
+                metadata: replica.metadata.clone(),
+                _checkout: self.checkouts.check_out(
+                    owner,
+                    "replica",
+                    &replica.metadata.net_peer_ip,
+                    replica.pool.clone(),
+                ),
+            }),
+            Err(err) => {
+                warn!(
+                    error = %err,
+                    net.peer.ip = %replica.metadata.net_peer_ip,
+                    "failed to get read replica connection, falling back to primary"
+                );
+                self.get_for(owner).await
+            }
+        }
+    }
+
     #[instrument(
         name = "pool.migrate",
         skip_all,
@@ -345,6 +617,7 @@ impl PgPool {
             Ok(_) => {
                 conn.query_one("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK_NUMBER])
                     .await?;
+                self.clear_statement_caches();
                 Ok(())
             }
             Err(e) => {
@@ -361,6 +634,7 @@ impl PgPool {
         conn.execute("DROP SCHEMA IF EXISTS public CASCADE", &[])
             .await?;
         conn.execute("CREATE SCHEMA public", &[]).await?;
+        self.clear_statement_caches();
         Ok(())
     }
 }
@@ -386,6 +660,8 @@ macro_rules! current_span_for_debug {
 pub struct InstrumentedClient {
     inner: Object<Manager>,
     metadata: Arc<ConnectionMetadata>,
+    // Declared after `inner` so that it is dropped once the connection is back in the pool
+    _checkout: CheckoutGuard,
 }
 
 impl InstrumentedClient {
@@ -570,16 +846,23 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Vec<PgRow>, PgError> {
-        let r = self
-            .inner
-            .query(statement, params)
-            .await
-            .map(|rows| {
-                rows.into_iter()
-                    .map(|inner| PgRow { inner })
-                    .collect::<Vec<_>>()
-            })
-            .map_err(Into::into);
+        self.metadata.faults.inject(PgOperation::Query).await?;
+        let start = Instant::now();
+        let r = match self.statement(statement).await {
+            Ok(prepared) => self.inner.query(&prepared, params).await,
+            Err(err) => Err(err),
+        }
+        .map(|rows| {
+            rows.into_iter()
+                .map(|inner| PgRow { inner })
+                .collect::<Vec<_>>()
+        })
+        .map_err(Into::into);
+        self.metadata.metrics.record(
+            statement,
+            start.elapsed(),
+            r.as_ref().ok().map(|rows| rows.len() as u64),
+        );
         if let Ok(ref rows) = r {
             Span::current().record("db.rows", rows.len());
         }
@@ -622,12 +905,17 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<PgRow, PgError> {
-        let r = self
-            .inner
-            .query_one(statement, params)
-            .await
-            .map(|inner| PgRow { inner })
-            .map_err(Into::into);
+        self.metadata.faults.inject(PgOperation::Query).await?;
+        let start = Instant::now();
+        let r = match self.statement(statement).await {
+            Ok(prepared) => self.inner.query_one(&prepared, params).await,
+            Err(err) => Err(err),
+        }
+        .map(|inner| PgRow { inner })
+        .map_err(Into::into);
+        self.metadata
+            .metrics
+            .record(statement, start.elapsed(), r.as_ref().ok().map(|_| 1));
         if r.is_ok() {
             Span::current().record("db.rows", 1);
         }
@@ -670,12 +958,19 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Option<PgRow>, PgError> {
-        let r = self
-            .inner
-            .query_opt(statement, params)
-            .await
-            .map(|maybe| maybe.map(|inner| PgRow { inner }))
-            .map_err(Into::into);
+        self.metadata.faults.inject(PgOperation::Query).await?;
+        let start = Instant::now();
+        let r = match self.statement(statement).await {
+            Ok(prepared) => self.inner.query_opt(&prepared, params).await,
+            Err(err) => Err(err),
+        }
+        .map(|maybe| maybe.map(|inner| PgRow { inner }))
+        .map_err(Into::into);
+        self.metadata.metrics.record(
+            statement,
+            start.elapsed(),
+            r.as_ref().ok().map(|maybe| u64::from(maybe.is_some())),
+        );
         if let Ok(ref maybe) = r {
             Span::current().record(
                 "db.rows",
@@ -728,6 +1023,7 @@ impl InstrumentedClient {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         self.inner
             .query_raw(statement, params)
             .await
@@ -773,10 +1069,16 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<u64, PgError> {
-        self.inner
-            .execute(statement, params)
-            .await
-            .map_err(Into::into)
+        self.metadata.faults.inject(PgOperation::Query).await?;
+        let start = Instant::now();
+        let r = match self.statement(statement).await {
+            Ok(prepared) => self.inner.execute(&prepared, params).await,
+            Err(err) => Err(err),
+        };
+        self.metadata
+            .metrics
+            .record(statement, start.elapsed(), r.as_ref().ok().copied());
+        r.map_err(Into::into)
     }
 
     /// The maximally flexible version of [`execute`].
@@ -815,6 +1117,7 @@ impl InstrumentedClient {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         self.inner
             .execute_raw(statement, params)
             .await
@@ -955,6 +1258,15 @@ impl InstrumentedClient {
         self.inner.cancel_token()
     }
 
+    /// Prepares a statement, reusing the connection's cached statement if the pool caches them.
+    async fn statement(&self, query: &str) -> Result<Statement, tokio_postgres::Error> {
+        if self.metadata.statement_cache {
+            self.inner.prepare_cached(query).await
+        } else {
+            self.inner.prepare(query).await
+        }
+    }
+
     /// Clears the client's type information cache.
     ///
     /// When user-defined types are used in a query, the client loads their definitions from the
@@ -1086,6 +1398,7 @@ impl<'a> InstrumentedTransaction<'a> {
         )
     )]
     pub async fn commit(self) -> Result<(), PgError> {
+        self.metadata.faults.inject(PgOperation::Commit).await?;
         let _ = &self;
         Span::current().follows_from(&self.tx_span);
 
@@ -1230,19 +1543,27 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Vec<PgRow>, PgError> {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         // info!(tx_span = ?self.tx_span, statement = &statement, "query");
         Span::current().follows_from(&self.tx_span);
-        let r = self
-            .inner
-            .query(statement, params)
-            .instrument(self.tx_span.clone())
-            .await
-            .map(|rows| {
-                rows.into_iter()
-                    .map(|inner| PgRow { inner })
-                    .collect::<Vec<_>>()
-            })
-            .map_err(Into::into);
+        let start = Instant::now();
+        let r = async {
+            let prepared = self.statement(statement).await?;
+            self.inner.query(&prepared, params).await
+        }
+        .instrument(self.tx_span.clone())
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .map(|inner| PgRow { inner })
+                .collect::<Vec<_>>()
+        })
+        .map_err(Into::into);
+        self.metadata.metrics.record(
+            statement,
+            start.elapsed(),
+            r.as_ref().ok().map(|rows| rows.len() as u64),
+        );
         if let Ok(ref rows) = r {
             Span::current().record("db.rows", rows.len());
         }
@@ -1285,14 +1606,20 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<PgRow, PgError> {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         Span::current().follows_from(&self.tx_span);
-        let r = self
-            .inner
-            .query_one(statement, params)
-            .instrument(self.tx_span.clone())
-            .await
-            .map(|inner| PgRow { inner })
-            .map_err(Into::into);
+        let start = Instant::now();
+        let r = async {
+            let prepared = self.statement(statement).await?;
+            self.inner.query_one(&prepared, params).await
+        }
+        .instrument(self.tx_span.clone())
+        .await
+        .map(|inner| PgRow { inner })
+        .map_err(Into::into);
+        self.metadata
+            .metrics
+            .record(statement, start.elapsed(), r.as_ref().ok().map(|_| 1));
         if r.is_ok() {
             Span::current().record("db.rows", 1);
         }
@@ -1335,14 +1662,22 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Option<PgRow>, PgError> {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         Span::current().follows_from(&self.tx_span);
-        let r = self
-            .inner
-            .query_opt(statement, params)
-            .instrument(self.tx_span.clone())
-            .await
-            .map(|maybe| maybe.map(|inner| PgRow { inner }))
-            .map_err(Into::into);
+        let start = Instant::now();
+        let r = async {
+            let prepared = self.statement(statement).await?;
+            self.inner.query_opt(&prepared, params).await
+        }
+        .instrument(self.tx_span.clone())
+        .await
+        .map(|maybe| maybe.map(|inner| PgRow { inner }))
+        .map_err(Into::into);
+        self.metadata.metrics.record(
+            statement,
+            start.elapsed(),
+            r.as_ref().ok().map(|maybe| u64::from(maybe.is_some())),
+        );
         if let Ok(ref maybe) = r {
             Span::current().record(
                 "db.rows",
@@ -1395,6 +1730,7 @@ impl<'a> InstrumentedTransaction<'a> {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         Span::current().follows_from(&self.tx_span);
         self.inner
             .query_raw(statement, params)
@@ -1442,12 +1778,19 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<u64, PgError> {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         Span::current().follows_from(&self.tx_span);
-        self.inner
-            .execute(statement, params)
-            .instrument(self.tx_span.clone())
-            .await
-            .map_err(Into::into)
+        let start = Instant::now();
+        let r = async {
+            let prepared = self.statement(statement).await?;
+            self.inner.execute(&prepared, params).await
+        }
+        .instrument(self.tx_span.clone())
+        .await;
+        self.metadata
+            .metrics
+            .record(statement, start.elapsed(), r.as_ref().ok().copied());
+        r.map_err(Into::into)
     }
 
     /// The maximally flexible version of [`execute`].
@@ -1486,6 +1829,7 @@ impl<'a> InstrumentedTransaction<'a> {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        self.metadata.faults.inject(PgOperation::Query).await?;
         Span::current().follows_from(&self.tx_span);
         self.inner
             .execute_raw(statement, params)
@@ -1889,6 +2233,15 @@ impl<'a> InstrumentedTransaction<'a> {
         Span::current().follows_from(&self.tx_span);
         self.tx_span.in_scope(|| self.inner.client())
     }
+
+    /// Prepares a statement, reusing the connection's cached statement if the pool caches them.
+    async fn statement(&self, query: &str) -> Result<Statement, tokio_postgres::Error> {
+        if self.metadata.statement_cache {
+            self.inner.prepare_cached(query).await
+        } else {
+            self.inner.prepare(query).await
+        }
+    }
 }
 
 impl<'a> fmt::Debug for InstrumentedTransaction<'a> {
@@ -2632,6 +2985,35 @@ impl PgSharedTransaction {
             }
         }
     }
+
+    /// Opens a server-side cursor over the rows of a query, which are then fetched `batch_size`
+    /// rows at a time rather than all at once.
+    ///
+    /// The cursor holds a copy of the transaction until it is dropped, so the transaction cannot
+    /// be committed while the cursor is open.
+    ///
+    /// # Panics
+    ///
+    /// - If the number of parameters provided does not match the number expected.
+    /// - If the internal transaction has already been consumed which is an internal correctness
+    ///   bug
+    pub async fn cursor<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        batch_size: i32,
+    ) -> Result<PgCursor, PgError>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let portal = self.bind(statement, params).await?;
+        Ok(PgCursor {
+            txn: self.clone(),
+            portal,
+            batch_size: batch_size.max(1),
+            exhausted: false,
+        })
+    }
 }
 
 impl fmt::Debug for PgSharedTransaction {
@@ -2643,6 +3025,57 @@ impl fmt::Debug for PgSharedTransaction {
     }
 }
 
+/// A server-side cursor over the rows of a query, opened with [`PgSharedTransaction::cursor`].
+pub struct PgCursor {
+    txn: PgSharedTransaction,
+    portal: Portal,
+    batch_size: i32,
+    exhausted: bool,
+}
+
+impl PgCursor {
+    /// Fetches the next batch of rows, which is empty once every row has been fetched.
+    pub async fn next_batch(&mut self) -> Result<Vec<PgRow>, PgError> {
+        if self.exhausted {
+            return Ok(Vec::new());
+        }
+        let rows = self.txn.query_portal(&self.portal, self.batch_size).await?;
+        // A short batch means the portal has run out of rows, which saves a round trip to learn
+        // that the next batch is empty.
+        if rows.len() < self.batch_size as usize {
+            self.exhausted = true;
+        }
+        Ok(rows)
+    }
+
+    /// Turns the cursor into a stream of its rows, fetching a batch whenever the previous one has
+    /// been consumed.
+    pub fn into_stream(self) -> impl Stream<Item = Result<PgRow, PgError>> + Send + 'static {
+        futures::stream::try_unfold(self, |mut cursor| async move {
+            let rows = cursor.next_batch().await?;
+            if rows.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some((
+                    futures::stream::iter(rows.into_iter().map(Ok)),
+                    cursor,
+                )))
+            }
+        })
+        .try_flatten()
+    }
+}
+
+impl fmt::Debug for PgCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgCursor")
+            .field("txn", &self.txn)
+            .field("batch_size", &self.batch_size)
+            .field("exhausted", &self.exhausted)
+            .finish_non_exhaustive()
+    }
+}
+
 #[self_referencing]
 struct PgOwnedTransaction {
     conn: InstrumentedClient,
@@ -2671,6 +3104,68 @@ impl PgOwnedTransaction {
     }
 }
 
+fn create_pool(settings: &PgPoolConfig, hostname: &str, port: u16) -> PgPoolResult<Pool> {
+    let mut cfg = Config::new();
+    cfg.hosts = Some(vec![hostname.to_owned()]);
+    cfg.port = Some(port);
+    cfg.user = Some(settings.user.clone());
+    cfg.password = Some(settings.password.clone().into());
+    cfg.dbname = Some(settings.dbname.clone());
+    cfg.application_name = Some(settings.application_name.clone());
+    cfg.manager = Some(ManagerConfig {
+        recycling_method: RecyclingMethod::Fast,
+    });
+    let mut pool_config = PoolConfig::new(settings.pool_max_size);
+    if let Some(secs) = settings.pool_timeout_wait_secs {
+        pool_config.timeouts.wait = Some(Duration::from_secs(secs));
+    }
+    if let Some(secs) = settings.pool_timeout_create_secs {
+        pool_config.timeouts.create = Some(Duration::from_secs(secs));
+    }
+    if let Some(secs) = settings.pool_timeout_recycle_secs {
+        pool_config.timeouts.recycle = Some(Duration::from_secs(secs));
+    }
+    debug!(db.pool_config = ?pool_config);
+    cfg.pool = Some(pool_config);
+
+    Ok(cfg.create_pool(Some(deadpool_postgres::Runtime::Tokio1), NoTls)?)
+}
+
+async fn connection_metadata(
+    settings: &PgPoolConfig,
+    hostname: &str,
+    port: u16,
+    metrics: Arc<QueryMetrics>,
+    faults: PgFaults,
+) -> PgPoolResult<ConnectionMetadata> {
+    let resolving_hostname = format!("{hostname}:{port}");
+    let net_peer_ip = tokio::task::spawn_blocking(move || {
+        resolving_hostname
+            .to_socket_addrs()
+            .map_err(PgPoolError::ResolveHostname)
+            .and_then(|mut iter| iter.next().ok_or(PgPoolError::ResolveHostnameNoEntries))
+            .map(|socket_addr| socket_addr.ip().to_string())
+    })
+    .await??;
+
+    Ok(ConnectionMetadata {
+        db_system: "postgresql",
+        db_connection_string: format!(
+            "postgresql://{}:{}/{}?application_name={}",
+            hostname, port, settings.dbname, settings.application_name
+        ),
+        db_name: settings.dbname.clone(),
+        db_user: settings.user.clone(),
+        db_pool_max_size: settings.pool_max_size,
+        net_peer_ip,
+        net_peer_port: port,
+        net_transport: "ip_tcp",
+        statement_cache: settings.statement_cache,
+        metrics,
+        faults,
+    })
+}
+
 async fn test_connection_task(check_pool: PgPool) {
     let _result = check_pool.test_connection().await;
 }
@@ -0,0 +1,204 @@
+//! Query metrics recorded by the connections of a [`PgPool`](crate::PgPool) and reported by
+//! [`PgPool::stats`](crate::PgPool::stats).
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use telemetry::metrics::{Histogram, DEFAULT_LATENCY_BUCKETS};
+
+static QUERY_DURATION: Histogram = Histogram::new(
+    "si_pg_query_duration_seconds",
+    "Duration of statements executed against PostgreSQL",
+);
+
+/// A snapshot of the query metrics of a [`PgPool`](crate::PgPool) and its read replicas.
+#[derive(Clone, Debug)]
+pub struct PgPoolStats {
+    /// The number of statements executed, including the ones which failed.
+    pub queries: u64,
+    /// The number of statements which failed.
+    pub errors: u64,
+    /// The number of rows returned or modified by successful statements.
+    pub rows: u64,
+    /// The time spent executing statements, including preparing them.
+    pub total_duration: Duration,
+    /// The number of statements per duration bucket, as pairs of the bucket's upper bound in
+    /// seconds and its count. The last bucket is unbounded.
+    pub duration_buckets: Vec<(f64, u64)>,
+    /// The most recent statements which were slower than the slow query threshold, slowest first.
+    pub slowest_queries: Vec<SlowQuery>,
+}
+
+/// A statement which was slower than the slow query threshold.
+#[derive(Clone, Debug)]
+pub struct SlowQuery {
+    pub statement: String,
+    pub duration: Duration,
+    /// The number of rows returned or modified, or `None` if the statement failed.
+    pub rows: Option<u64>,
+    /// How long ago the statement finished, as of the snapshot.
+    pub age: Duration,
+}
+
+#[derive(Debug)]
+struct RecordedSlowQuery {
+    statement: String,
+    duration: Duration,
+    rows: Option<u64>,
+    finished_at: Instant,
+}
+
+/// Counts the statements executed by the connections of a pool, keeping the most recent slow
+/// ones in a bounded ring buffer.
+#[derive(Debug)]
+pub(crate) struct QueryMetrics {
+    queries: AtomicU64,
+    errors: AtomicU64,
+    rows: AtomicU64,
+    total_duration_micros: AtomicU64,
+    duration_buckets: Vec<AtomicU64>,
+    slow_query_threshold: Duration,
+    slow_query_log_size: usize,
+    slow_queries: Mutex<VecDeque<RecordedSlowQuery>>,
+}
+
+impl QueryMetrics {
+    pub(crate) fn new(slow_query_threshold: Duration, slow_query_log_size: usize) -> Self {
+        Self {
+            queries: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            rows: AtomicU64::new(0),
+            total_duration_micros: AtomicU64::new(0),
+            duration_buckets: (0..=DEFAULT_LATENCY_BUCKETS.len())
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            slow_query_threshold,
+            slow_query_log_size,
+            slow_queries: Mutex::new(VecDeque::with_capacity(slow_query_log_size)),
+        }
+    }
+
+    /// Records an executed statement, where `rows` is `None` if it failed.
+    pub(crate) fn record(&self, statement: &str, duration: Duration, rows: Option<u64>) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        match rows {
+            Some(rows) => {
+                self.rows.fetch_add(rows, Ordering::Relaxed);
+            }
+            None => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.total_duration_micros.fetch_add(
+            u64::try_from(duration.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+
+        let seconds = duration.as_secs_f64();
+        let bucket = DEFAULT_LATENCY_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(DEFAULT_LATENCY_BUCKETS.len());
+        self.duration_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        QUERY_DURATION.observe(
+            &[("outcome", if rows.is_some() { "ok" } else { "error" })],
+            seconds,
+        );
+
+        if duration >= self.slow_query_threshold && self.slow_query_log_size > 0 {
+            let mut slow_queries = self
+                .slow_queries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if slow_queries.len() == self.slow_query_log_size {
+                slow_queries.pop_front();
+            }
+            slow_queries.push_back(RecordedSlowQuery {
+                statement: statement.to_owned(),
+                duration,
+                rows,
+                finished_at: Instant::now(),
+            });
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> PgPoolStats {
+        let now = Instant::now();
+        let mut slowest_queries: Vec<SlowQuery> = self
+            .slow_queries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|query| SlowQuery {
+                statement: query.statement.clone(),
+                duration: query.duration,
+                rows: query.rows,
+                age: now.saturating_duration_since(query.finished_at),
+            })
+            .collect();
+        slowest_queries.sort_by(|a, b| b.duration.cmp(&a.duration));
+
+        PgPoolStats {
+            queries: self.queries.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            rows: self.rows.load(Ordering::Relaxed),
+            total_duration: Duration::from_micros(
+                self.total_duration_micros.load(Ordering::Relaxed),
+            ),
+            duration_buckets: DEFAULT_LATENCY_BUCKETS
+                .iter()
+                .copied()
+                .chain(std::iter::once(f64::INFINITY))
+                .zip(
+                    self.duration_buckets
+                        .iter()
+                        .map(|count| count.load(Ordering::Relaxed)),
+                )
+                .collect(),
+            slowest_queries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_most_recent_slow_queries_slowest_first() {
+        let metrics = QueryMetrics::new(Duration::from_millis(100), 2);
+
+        metrics.record("SELECT 1", Duration::from_millis(1), Some(1));
+        metrics.record("SELECT 2", Duration::from_millis(300), Some(2));
+        metrics.record("SELECT 3", Duration::from_millis(200), None);
+        metrics.record("SELECT 4", Duration::from_millis(500), Some(0));
+
+        let stats = metrics.snapshot();
+        assert_eq!(4, stats.queries);
+        assert_eq!(1, stats.errors);
+        assert_eq!(3, stats.rows);
+        assert_eq!(Duration::from_millis(1001), stats.total_duration);
+        assert_eq!(
+            4,
+            stats
+                .duration_buckets
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<u64>()
+        );
+        assert_eq!(
+            vec!["SELECT 4", "SELECT 3"],
+            stats
+                .slowest_queries
+                .iter()
+                .map(|query| query.statement.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! The categories of the errors of the SI crates, which tell callers whether a failure is worth
+//! retrying and how to report it, without matching on the variants of every error enum.
+//!
+//! Error enums implement [`CategorizedError`], usually by delegating to the errors they wrap, so
+//! that a serialization failure reported by PostgreSQL is still [`Transient`] once it has been
+//! wrapped by a few layers of model errors.
+//!
+//! [`Transient`]: ErrorCategory::Transient
+
+#![warn(
+    clippy::unwrap_in_result,
+    clippy::unwrap_used,
+    clippy::panic,
+    clippy::missing_panics_doc,
+    clippy::panic_in_result_fn
+)]
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+/// What kind of failure an error is.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCategory {
+    /// The operation conflicts with the current state of what it acts on, such as a unique name
+    /// which is already taken.
+    Conflict,
+    /// Something which should always hold did not: a bug, or data which is not in the shape it
+    /// should be. The default for errors which say nothing more specific.
+    Invariant,
+    /// What the operation acts on does not exist, or is not visible.
+    NotFound,
+    /// The failure is expected to go away on its own, such as a lost connection or a transaction
+    /// conflict, so the operation can be retried as is.
+    Transient,
+    /// The input of the operation is invalid, and retrying it as is will fail again.
+    User,
+}
+
+impl ErrorCategory {
+    /// Whether retrying the operation as is may succeed.
+    pub fn is_retryable(self) -> bool {
+        self == Self::Transient
+    }
+}
+
+/// An error which knows its [`ErrorCategory`].
+pub trait CategorizedError {
+    fn category(&self) -> ErrorCategory;
+
+    /// Whether retrying the operation which failed with this error as is may succeed.
+    fn is_retryable(&self) -> bool {
+        self.category().is_retryable()
+    }
+}
+
+impl<T: CategorizedError + ?Sized> CategorizedError for Box<T> {
+    fn category(&self) -> ErrorCategory {
+        (**self).category()
+    }
+}
+
+impl CategorizedError for io::Error {
+    fn category(&self) -> ErrorCategory {
+        match self.kind() {
+            io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::WouldBlock => ErrorCategory::Transient,
+            io::ErrorKind::AlreadyExists => ErrorCategory::Conflict,
+            io::ErrorKind::NotFound => ErrorCategory::NotFound,
+            io::ErrorKind::InvalidInput => ErrorCategory::User,
+            _ => ErrorCategory::Invariant,
+        }
+    }
+}
+
+/// JSON which does not (de)serialize is a mismatch between the data and the types reading it,
+/// except when the I/O underneath failed.
+impl CategorizedError for serde_json::Error {
+    fn category(&self) -> ErrorCategory {
+        match self.io_error_kind() {
+            Some(kind) => io::Error::from(kind).category(),
+            None => ErrorCategory::Invariant,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_transient_errors_are_retryable() {
+        assert!(ErrorCategory::Transient.is_retryable());
+        for category in [
+            ErrorCategory::Conflict,
+            ErrorCategory::Invariant,
+            ErrorCategory::NotFound,
+            ErrorCategory::User,
+        ] {
+            assert!(!category.is_retryable());
+        }
+    }
+
+    #[test]
+    fn io_errors() {
+        let err = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert_eq!(ErrorCategory::Transient, err.category());
+        assert!(Box::new(err).is_retryable());
+        assert_eq!(
+            ErrorCategory::Invariant,
+            io::Error::new(io::ErrorKind::Other, "oh no").category()
+        );
+    }
+
+    #[test]
+    fn serde_json_errors() {
+        let err = serde_json::from_str::<u8>("\"not a number\"").expect_err("should not parse");
+        assert_eq!(ErrorCategory::Invariant, err.category());
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, SettingsError};
+
+/// Feature flags toggled by configuration, keyed by name. Flags which are not configured are
+/// disabled.
+///
+/// In a config file, flags live in a table:
+///
+/// ```toml
+/// [features]
+/// new_attribute_engine = true
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct FeatureFlags(BTreeMap<String, bool>);
+
+impl FeatureFlags {
+    /// Returns whether the flag is configured and enabled.
+    #[must_use]
+    pub fn is_enabled(&self, name: impl AsRef<str>) -> bool {
+        self.0.get(name.as_ref()).copied().unwrap_or(false)
+    }
+
+    /// Returns the flags which are configured, enabled or not.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.0
+            .iter()
+            .map(|(name, enabled)| (name.as_str(), *enabled))
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, enabled: bool) {
+        self.0.insert(name.into(), enabled);
+    }
+
+    /// Checks that every flag name is made of lowercase ascii letters, digits and underscores, so
+    /// that flags can also be set through environment variables. The `key` is the one the flags
+    /// were loaded from and is used to name the offending flag.
+    pub fn validate(&self, key: &str) -> Result<()> {
+        for name in self.0.keys() {
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                return Err(SettingsError::invalid_value(
+                    format!("{key}.{name}"),
+                    "feature flag names must only contain lowercase letters, digits and underscores",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unconfigured_flags_are_disabled() {
+        let mut flags = FeatureFlags::default();
+        flags.set("new_attribute_engine", true);
+        flags.set("new_diagram_kinds", false);
+
+        assert!(flags.is_enabled("new_attribute_engine"));
+        assert!(!flags.is_enabled("new_diagram_kinds"));
+        assert!(!flags.is_enabled("poop_canoe"));
+    }
+
+    #[test]
+    fn validate_names_the_offending_flag() {
+        let mut flags = FeatureFlags::default();
+        flags.set("new_attribute_engine", true);
+        assert!(flags.validate("features").is_ok());
+
+        flags.set("New-Diagram", true);
+        let err = flags
+            .validate("features")
+            .expect_err("invalid flag name should fail validation");
+        assert!(err.to_string().contains("features.New-Diagram"));
+    }
+}
@@ -5,18 +5,58 @@ use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 mod canonical_file;
+mod feature_flags;
 
 pub use canonical_file::{safe_canonically_join, CanonicalFile, CanonicalFileError};
+pub use feature_flags::FeatureFlags;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SettingsError {
     #[error(transparent)]
     ConfigFile(#[from] config_file::ConfigFileError),
+    #[error("invalid value for `{key}`: {message}")]
+    InvalidValue { key: String, message: String },
+}
+
+impl SettingsError {
+    /// Creates an error for a setting whose value is not valid, naming the offending key as it
+    /// appears in config files, such as `pg.port`.
+    pub fn invalid_value(key: impl Into<String>, message: impl ToString) -> Self {
+        Self::InvalidValue {
+            key: key.into(),
+            message: message.to_string(),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, SettingsError>;
 
+/// Fails with an error naming `key` if the setting is empty or only whitespace.
+pub fn require_non_empty(key: &str, value: impl AsRef<str>) -> Result<()> {
+    if value.as_ref().trim().is_empty() {
+        return Err(SettingsError::invalid_value(key, "must not be empty"));
+    }
+    Ok(())
+}
+
+/// Resolves a file path setting, failing with an error naming `key` if the file cannot be found.
+pub fn require_file(key: &str, path: String) -> Result<CanonicalFile> {
+    path.try_into()
+        .map_err(|err: CanonicalFileError| SettingsError::invalid_value(key, err))
+}
+
+/// Fails with an error naming `key` if the setting is zero.
+pub fn require_non_zero<T: Default + PartialEq>(key: &str, value: T) -> Result<()> {
+    if value == T::default() {
+        return Err(SettingsError::invalid_value(
+            key,
+            "must be greater than zero",
+        ));
+    }
+    Ok(())
+}
+
 pub trait StandardConfig: Sized {
     type Builder: Default;
 
@@ -32,6 +72,17 @@ pub trait StandardConfigFile:
 {
     type Error: From<SettingsError>;
 
+    /// Checks the loaded settings, returning a [`SettingsError::InvalidValue`] naming the first
+    /// offending key. Called by [`layered_load`](Self::layered_load) once every source has been
+    /// merged.
+    fn validate(&self) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Loads the settings of a service by merging, from lowest to highest precedence: the
+    /// defaults, the config file, environment variables prefixed with `SI_<APP_NAME>` and the
+    /// values set by `set_func`, typically from command line arguments. The merged settings are
+    /// then validated.
     fn layered_load<F>(
         app_name: impl AsRef<str>,
         set_func: F,
@@ -40,14 +91,16 @@ pub trait StandardConfigFile:
         F: FnOnce(&mut ConfigMap),
     {
         let app_name = app_name.as_ref();
-        config_file::layered_load(
+        let config: Self = config_file::layered_load(
             app_name,
             "toml",
             &Some(format!("SI_{}_CONFIG", app_name.to_uppercase())),
             &Some(format!("SI_{}", app_name.to_uppercase())),
             set_func,
         )
-        .map_err(SettingsError::ConfigFile)
-        .map_err(Into::into)
+        .map_err(SettingsError::ConfigFile)?;
+        config.validate()?;
+
+        Ok(config)
     }
 }
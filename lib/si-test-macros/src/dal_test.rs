@@ -84,6 +84,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> DalTestFnSetup {
                                 let var = var.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "TestClock" => {
+                                let var = expander.setup_test_clock();
+                                let var = var.as_ref();
+                                expander.push_arg(parse_quote! {#var.clone()});
+                            }
                             "VeritechShutdownHandle" => {
                                 let var = expander.setup_veritech_shutdown_handle();
                                 let var = var.as_ref();
@@ -145,6 +150,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> DalTestFnSetup {
                                     let var = var.as_ref();
                                     expander.push_arg(parse_quote! {&#var});
                                 }
+                                "TestClock" => {
+                                    let var = expander.setup_test_clock();
+                                    let var = var.as_ref();
+                                    expander.push_arg(parse_quote! {&#var});
+                                }
                                 "WorkspaceSignup" => {
                                     let var = expander.setup_workspace_signup();
                                     let var = var.0.as_ref();
@@ -204,6 +214,7 @@ struct DalTestFnSetupExpander {
     veritech_shutdown_handle: Option<Arc<Ident>>,
     start_veritech_server: Option<()>,
     services_context: Option<Arc<Ident>>,
+    test_clock: Option<Arc<Ident>>,
     dal_context_builder: Option<Arc<Ident>>,
     workspace_signup: Option<(Arc<Ident>, Arc<Ident>)>,
     workspace_pk: Option<Arc<Ident>>,
@@ -230,6 +241,7 @@ impl DalTestFnSetupExpander {
             veritech_shutdown_handle: None,
             start_veritech_server: None,
             services_context: None,
+            test_clock: None,
             dal_context_builder: None,
             workspace_signup: None,
             workspace_pk: None,
@@ -350,6 +362,14 @@ impl FnSetupExpander for DalTestFnSetupExpander {
         self.services_context = value;
     }
 
+    fn test_clock(&self) -> Option<&Arc<Ident>> {
+        self.test_clock.as_ref()
+    }
+
+    fn set_test_clock(&mut self, value: Option<Arc<Ident>>) {
+        self.test_clock = value;
+    }
+
     fn dal_context_builder(&self) -> Option<&Arc<Ident>> {
         self.dal_context_builder.as_ref()
     }
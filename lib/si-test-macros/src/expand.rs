@@ -2,7 +2,9 @@ use std::sync::Arc;
 
 use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 use quote::quote;
-use syn::{punctuated::Punctuated, token::Comma, Expr, ItemFn, ReturnType};
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Expr, FnArg, ItemFn, PatType, ReturnType,
+};
 
 use crate::{
     Args, LOG_ENV_VAR, RT_DEFAULT_THREAD_STACK_SIZE, RT_DEFAULT_WORKER_THREADS, SPAN_EVENTS_ENV_VAR,
@@ -12,6 +14,86 @@ pub(crate) trait FnSetup {
     fn into_parts(self) -> (TokenStream, Punctuated<Expr, Comma>);
 }
 
+/// Expands a test declaring `cases(...)` into a module holding one test per case, each binding the
+/// values of its case to the `#[case]` arguments of the test. Tests without cases are expanded
+/// as they are.
+pub(crate) fn expand_cases(
+    mut item: ItemFn,
+    args: Args,
+    expand: impl Fn(ItemFn, Args) -> TokenStream,
+) -> TokenStream {
+    let mut case_params: Vec<PatType> = Vec::new();
+    let mut params = Punctuated::new();
+    for param in std::mem::take(&mut item.sig.inputs) {
+        match param {
+            FnArg::Typed(mut pat_type)
+                if pat_type
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("case")) =>
+            {
+                pat_type.attrs.retain(|attr| !attr.path().is_ident("case"));
+                case_params.push(pat_type);
+            }
+            param => params.push(param),
+        }
+    }
+    item.sig.inputs = params;
+
+    if args.cases.is_empty() {
+        if !case_params.is_empty() {
+            panic!("test has `#[case]` arguments but no `cases(...)` to provide them");
+        }
+        return expand(item, args);
+    }
+
+    let mut tests = TokenStream::new();
+    for case in &args.cases {
+        if case.values.len() != case_params.len() {
+            panic!(
+                "case `{}` has {} values, but the test has {} `#[case]` arguments",
+                case.name,
+                case.values.len(),
+                case_params.len()
+            );
+        }
+        let bindings = case_params
+            .iter()
+            .zip(case.values.iter())
+            .map(|(param, value)| {
+                let pat = &param.pat;
+                let ty = &param.ty;
+                quote! {let #pat: #ty = #value;}
+            });
+        let body = &item.block;
+
+        let mut case_item = item.clone();
+        case_item.sig.ident = case.name.clone();
+        case_item.block = parse_quote!({
+            #(#bindings)*
+            #body
+        });
+        tests.extend(expand(
+            case_item,
+            Args {
+                vars: args.vars.clone(),
+                cases: Vec::new(),
+            },
+        ));
+    }
+
+    let vis = &item.vis;
+    let test_name = &item.sig.ident;
+    quote! {
+        #vis mod #test_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #tests
+        }
+    }
+}
+
 pub(crate) fn expand_test(item: ItemFn, _args: Args, fn_setup: impl FnSetup) -> TokenStream {
     if item.sig.asyncness.is_none() {
         panic!("test function must be async--blocking tests not supported");
@@ -236,6 +318,9 @@ pub(crate) trait FnSetupExpander {
     fn services_context(&self) -> Option<&Arc<Ident>>;
     fn set_services_context(&mut self, value: Option<Arc<Ident>>);
 
+    fn test_clock(&self) -> Option<&Arc<Ident>>;
+    fn set_test_clock(&mut self, value: Option<Arc<Ident>>);
+
     fn dal_context_builder(&self) -> Option<&Arc<Ident>>;
     fn set_dal_context_builder(&mut self, value: Option<Arc<Ident>>);
 
@@ -444,6 +529,23 @@ pub(crate) trait FnSetupExpander {
         self.services_context().unwrap().clone()
     }
 
+    fn setup_test_clock(&mut self) -> Arc<Ident> {
+        if let Some(ident) = self.test_clock() {
+            return ident.clone();
+        }
+
+        let test_context = self.setup_test_context();
+        let test_context = test_context.as_ref();
+
+        let var = Ident::new("test_clock", Span::call_site());
+        self.code_extend(quote! {
+            let #var = #test_context.clock();
+        });
+        self.set_test_clock(Some(Arc::new(var)));
+
+        self.test_clock().unwrap().clone()
+    }
+
     fn setup_dal_context_builder(&mut self) -> Arc<Ident> {
         if let Some(ident) = self.dal_context_builder() {
             return ident.clone();
@@ -8,10 +8,11 @@ use std::collections::HashSet;
 
 use proc_macro::TokenStream;
 use syn::{
+    parenthesized,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
-    Ident, ItemFn, Path, Token,
+    token, Expr, Ident, ItemFn, Path, Token,
 };
 
 const LOG_ENV_VAR: &str = "SI_TEST_LOG";
@@ -20,18 +21,49 @@ const SPAN_EVENTS_ENV_VAR: &str = "SI_TEST_LOG_SPAN_EVENTS";
 const RT_DEFAULT_WORKER_THREADS: usize = 2;
 const RT_DEFAULT_THREAD_STACK_SIZE: usize = 2 * 1024 * 1024 * 3;
 
-#[allow(dead_code)] // We aren't current using args on the macro, but when we do we can drop this
-                    // line
 struct Args {
+    #[allow(dead_code)] // We aren't current using bare args on the macro, but when we do we can
+    // drop this line
     pub(crate) vars: HashSet<Ident>,
+    pub(crate) cases: Vec<Case>,
 }
 
 impl Parse for Args {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let vars = Punctuated::<Ident, Token![,]>::parse_terminated(input)?;
-        Ok(Self {
-            vars: vars.into_iter().collect(),
-        })
+        let mut vars = HashSet::new();
+        let mut cases = Vec::new();
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if ident == "cases" && input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                cases.extend(Punctuated::<Case, Token![,]>::parse_terminated(&content)?);
+            } else {
+                vars.insert(ident);
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self { vars, cases })
+    }
+}
+
+/// A named case of a parameterized test, such as `string(PropKind::String, "mastodon")`, holding
+/// a value for each of the `#[case]` arguments of the test.
+struct Case {
+    pub(crate) name: Ident,
+    pub(crate) values: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for Case {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let content;
+        parenthesized!(content in input);
+        let values = Punctuated::parse_terminated(&content)?;
+        Ok(Self { name, values })
     }
 }
 
@@ -131,6 +163,30 @@ fn path_as_string(path: &Path) -> String {
 /// }
 /// ```
 ///
+/// ## Parameterized Tests
+///
+/// A test can be run for several cases, each of which provides a value for every argument marked
+/// with `#[case]`. Each case expands to its own test, named after the case, in a module named
+/// after the test function (`set_value::string` and `set_value::integer` below), and the case
+/// arguments can be used alongside any of the other supported arguments:
+///
+/// ```ignore
+/// use dal::{DalContext, PropKind};
+/// use crate::dal::test;
+///
+/// #[test(cases(
+///     string(PropKind::String, serde_json::json!["mastodon"]),
+///     integer(PropKind::Integer, serde_json::json![42]),
+/// ))]
+/// async fn set_value(
+///     ctx: &DalContext,
+///     #[case] kind: PropKind,
+///     #[case] value: serde_json::Value,
+/// ) {
+///     // ...
+/// }
+/// ```
+///
 /// # Owned Types
 ///
 /// The following types can be used as test function arguments as owned types, provided by the
@@ -150,6 +206,7 @@ fn path_as_string(path: &Path) -> String {
 /// * `pinga_handle: PingaShutdownHandle`: the shutdown handle for the Pinga server running
 ///    alongside each test
 /// * `services_ctx: ServicesContext`: a services context object, used to create DAL contexts
+/// * `clock: TestClock`: the clock of the services, which only moves when the test advances it
 /// * `veritech_handle: VeritechShutdownHandle`: the shutdown handle for the Veritech server
 ///    running alongside each test
 /// * `wid: WorkspacePk: the workspace PK created for this test
@@ -166,6 +223,7 @@ fn path_as_string(path: &Path) -> String {
 /// * `builder: &DalContextBuilder`: a reference to the builder to create DAL context objects
 /// * `services_ctx: &ServicesContext`: a reference to a services context object, used to create
 ///    DAL contexts
+/// * `clock: &TestClock`: a reference to the clock of the services
 /// * `nw: &WorkspaceSignup`: a reference to the full "new-workspace" data structure,
 ///    created for this test
 ///
@@ -212,7 +270,7 @@ fn path_as_string(path: &Path) -> String {
 pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as Args);
     let item = parse_macro_input!(input as ItemFn);
-    dal_test::expand(item, args).into()
+    expand::expand_cases(item, args, dal_test::expand).into()
 }
 
 /// A procedural macro which helps to streamline, setup, and manage SDF-related tests.
@@ -326,6 +384,7 @@ pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// * `pinga_handle: PingaShutdownHandle`: the shutdown handle for the Pinga server running
 ///    alongside each test
 /// * `services_ctx: ServicesContext`: a services context object, used to create DAL contexts
+/// * `clock: TestClock`: the clock of the services, which only moves when the test advances it
 /// * `veritech_handle: VeritechShutdownHandle`: the shutdown handle for the Veritech server
 ///    running alongside each test
 /// * `wid: WorkspacePk: the workspace PK created for this test
@@ -342,6 +401,7 @@ pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// * `builder: &DalContextBuilder`: a reference to the builder to create DAL context objects
 /// * `services_ctx: &ServicesContext`: a reference to a services context object, used to create
 ///    DAL contexts
+/// * `clock: &TestClock`: a reference to the clock of the services
 /// * `nw: &WorkspaceSignup`: a reference to the full "new-workspace" data structure,
 ///    created for this test
 ///
@@ -388,5 +448,5 @@ pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
 pub fn sdf_test(attr: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as Args);
     let item = parse_macro_input!(input as ItemFn);
-    sdf_test::expand(item, args).into()
+    expand::expand_cases(item, args, sdf_test::expand).into()
 }
@@ -17,6 +17,9 @@ use tokio::sync::mpsc;
 pub use opentelemetry::{self, trace::SpanKind};
 pub use tracing;
 
+pub mod metrics;
+pub mod propagation;
+
 pub mod prelude {
     pub use super::{FormattedSpanKind, SpanExt, SpanKind};
     pub use tracing::{
@@ -0,0 +1,498 @@
+//! A minimal, process-wide metrics registry which renders in the
+//! [Prometheus text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+//!
+//! Metrics are declared as statics and recorded from anywhere in the process:
+//!
+//! ```
+//! use telemetry::metrics::Counter;
+//!
+//! static REQUESTS: Counter = Counter::new("si_requests_total", "Total number of requests");
+//!
+//! REQUESTS.increment(&[("route", "/api/ping")]);
+//! assert!(telemetry::metrics::render().contains("si_requests_total{route=\"/api/ping\"} 1"));
+//! ```
+//!
+//! Every series (a metric with one set of label values) is registered the first time it is
+//! recorded and is an atomic value from then on, so recording never waits on other metrics. Hot
+//! paths can look a series up once and keep it, which also renders it before anything is recorded:
+//!
+//! ```
+//! use telemetry::metrics::Gauge;
+//!
+//! static QUEUE_DEPTH: Gauge = Gauge::new("si_queue_depth", "Number of queued items");
+//!
+//! let queue_depth = QUEUE_DEPTH.with_labels(&[("queue", "jobs")]);
+//! assert!(telemetry::metrics::render().contains("si_queue_depth{queue=\"jobs\"} 0"));
+//! queue_depth.increment();
+//! assert!(telemetry::metrics::render().contains("si_queue_depth{queue=\"jobs\"} 1"));
+//! ```
+
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex, Once, PoisonError, RwLock,
+    },
+    time::Duration,
+};
+
+/// The content type of the output of [`render`].
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Histogram buckets, in seconds, suited to request and execution latencies.
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+/// The metrics which have recorded at least one series. Only locked when a metric is first used
+/// and when rendering.
+static REGISTRY: Mutex<Vec<&'static dyn RenderFamily>> = Mutex::new(Vec::new());
+
+type Labels = Vec<(&'static str, String)>;
+
+/// The series of one metric, keyed by their label values.
+#[derive(Debug)]
+struct Family<S> {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    registered: Once,
+    series: RwLock<Vec<(Labels, Arc<S>)>>,
+}
+
+impl<S: Series> Family<S> {
+    const fn new(name: &'static str, help: &'static str, kind: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind,
+            registered: Once::new(),
+            series: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Looks up the series with the given labels, registering it (and this metric, the first
+    /// time) if it has not been recorded yet.
+    fn series(
+        &'static self,
+        labels: &[(&'static str, &str)],
+        new_series: impl FnOnce() -> S,
+    ) -> Arc<S> {
+        self.registered.call_once(|| {
+            REGISTRY
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(self);
+        });
+
+        if let Some(series) = find_series(
+            &self.series.read().unwrap_or_else(PoisonError::into_inner),
+            labels,
+        ) {
+            return series;
+        }
+
+        let mut all_series = self.series.write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have registered the series while the lock was released
+        if let Some(series) = find_series(&all_series, labels) {
+            return series;
+        }
+        let series = Arc::new(new_series());
+        let labels = labels
+            .iter()
+            .map(|(key, value)| (*key, (*value).to_owned()))
+            .collect();
+        all_series.push((labels, series.clone()));
+        series
+    }
+}
+
+fn find_series<S>(
+    all_series: &[(Labels, Arc<S>)],
+    labels: &[(&'static str, &str)],
+) -> Option<Arc<S>> {
+    all_series
+        .iter()
+        .find(|(series_labels, _)| {
+            series_labels.len() == labels.len()
+                && series_labels.iter().zip(labels).all(
+                    |((key, value), (other_key, other_value))| {
+                        key == other_key && value == other_value
+                    },
+                )
+        })
+        .map(|(_, series)| series.clone())
+}
+
+/// A metric which can render all of its series, whatever their kind.
+trait RenderFamily: Sync {
+    fn name(&self) -> &'static str;
+
+    fn render(&self, out: &mut String);
+}
+
+impl<S: Series> RenderFamily for Family<S> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn render(&self, out: &mut String) {
+        let mut all_series = self
+            .series
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        all_series.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.kind);
+        for (labels, series) in &all_series {
+            series.render(self.name, labels, out);
+        }
+    }
+}
+
+/// The value of one series.
+trait Series: Send + Sync + 'static {
+    fn render(&self, name: &str, labels: &Labels, out: &mut String);
+}
+
+#[derive(Debug)]
+struct CounterValue(AtomicU64);
+
+impl Series for CounterValue {
+    fn render(&self, name: &str, labels: &Labels, out: &mut String) {
+        let value = self.0.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{name}{} {value}", format_labels(labels, None));
+    }
+}
+
+#[derive(Debug)]
+struct GaugeValue(AtomicI64);
+
+impl Series for GaugeValue {
+    fn render(&self, name: &str, labels: &Labels, out: &mut String) {
+        let value = self.0.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{name}{} {value}", format_labels(labels, None));
+    }
+}
+
+#[derive(Debug)]
+struct HistogramValue {
+    bounds: &'static [f64],
+    /// The count of observations in each bucket, which are only made cumulative when rendering.
+    buckets: Vec<AtomicU64>,
+    /// The bits of the `f64` sum of the observations.
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl HistogramValue {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0.0_f64.to_bits()),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, value: f64) {
+        if let Some(index) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sum| {
+                Some((f64::from_bits(sum) + value).to_bits())
+            });
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Series for HistogramValue {
+    fn render(&self, name: &str, labels: &Labels, out: &mut String) {
+        let mut cumulative = 0;
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let le = bound.to_string();
+            let _ = writeln!(
+                out,
+                "{name}_bucket{} {cumulative}",
+                format_labels(labels, Some(&le))
+            );
+        }
+        // Observations may have landed since the buckets were read, and the buckets must never
+        // add up to more than the total
+        let count = self.count.load(Ordering::Relaxed).max(cumulative);
+        let sum = f64::from_bits(self.sum.load(Ordering::Relaxed));
+        let _ = writeln!(
+            out,
+            "{name}_bucket{} {count}",
+            format_labels(labels, Some("+Inf"))
+        );
+        let _ = writeln!(out, "{name}_sum{} {sum}", format_labels(labels, None));
+        let _ = writeln!(out, "{name}_count{} {count}", format_labels(labels, None));
+    }
+}
+
+/// A monotonically increasing count, such as the number of requests served.
+#[derive(Debug)]
+pub struct Counter {
+    family: Family<CounterValue>,
+}
+
+impl Counter {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            family: Family::new(name, help, "counter"),
+        }
+    }
+
+    /// Looks up the series of this counter with the given labels, to record to it directly.
+    pub fn with_labels(&'static self, labels: &[(&'static str, &str)]) -> CounterSeries {
+        CounterSeries(
+            self.family
+                .series(labels, || CounterValue(AtomicU64::new(0))),
+        )
+    }
+
+    pub fn increment(&'static self, labels: &[(&'static str, &str)]) {
+        self.with_labels(labels).increment();
+    }
+
+    pub fn increment_by(&'static self, labels: &[(&'static str, &str)], value: u64) {
+        self.with_labels(labels).increment_by(value);
+    }
+}
+
+/// One series of a [`Counter`].
+#[derive(Clone, Debug)]
+pub struct CounterSeries(Arc<CounterValue>);
+
+impl CounterSeries {
+    pub fn increment(&self) {
+        self.increment_by(1);
+    }
+
+    pub fn increment_by(&self, value: u64) {
+        self.0 .0.fetch_add(value, Ordering::Relaxed);
+    }
+}
+
+/// A value which can go up and down, such as the number of queued jobs.
+#[derive(Debug)]
+pub struct Gauge {
+    family: Family<GaugeValue>,
+}
+
+impl Gauge {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            family: Family::new(name, help, "gauge"),
+        }
+    }
+
+    /// Looks up the series of this gauge with the given labels, to record to it directly.
+    pub fn with_labels(&'static self, labels: &[(&'static str, &str)]) -> GaugeSeries {
+        GaugeSeries(self.family.series(labels, || GaugeValue(AtomicI64::new(0))))
+    }
+
+    pub fn increment(&'static self, labels: &[(&'static str, &str)]) {
+        self.with_labels(labels).increment();
+    }
+
+    pub fn decrement(&'static self, labels: &[(&'static str, &str)]) {
+        self.with_labels(labels).decrement();
+    }
+
+    pub fn add(&'static self, labels: &[(&'static str, &str)], delta: i64) {
+        self.with_labels(labels).add(delta);
+    }
+
+    pub fn set(&'static self, labels: &[(&'static str, &str)], value: i64) {
+        self.with_labels(labels).set(value);
+    }
+}
+
+/// One series of a [`Gauge`].
+#[derive(Clone, Debug)]
+pub struct GaugeSeries(Arc<GaugeValue>);
+
+impl GaugeSeries {
+    pub fn increment(&self) {
+        self.add(1);
+    }
+
+    pub fn decrement(&self) {
+        self.add(-1);
+    }
+
+    pub fn add(&self, delta: i64) {
+        self.0 .0.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: i64) {
+        self.0 .0.store(value, Ordering::Relaxed);
+    }
+}
+
+/// A distribution of observed values, such as latencies, counted into buckets.
+#[derive(Debug)]
+pub struct Histogram {
+    family: Family<HistogramValue>,
+    bounds: &'static [f64],
+}
+
+impl Histogram {
+    /// Creates a histogram using the [`DEFAULT_LATENCY_BUCKETS`].
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self::with_buckets(name, help, DEFAULT_LATENCY_BUCKETS)
+    }
+
+    /// Creates a histogram with the given upper bounds, which must be sorted in increasing order.
+    pub const fn with_buckets(
+        name: &'static str,
+        help: &'static str,
+        bounds: &'static [f64],
+    ) -> Self {
+        Self {
+            family: Family::new(name, help, "histogram"),
+            bounds,
+        }
+    }
+
+    /// Looks up the series of this histogram with the given labels, to record to it directly.
+    pub fn with_labels(&'static self, labels: &[(&'static str, &str)]) -> HistogramSeries {
+        HistogramSeries(
+            self.family
+                .series(labels, || HistogramValue::new(self.bounds)),
+        )
+    }
+
+    pub fn observe(&'static self, labels: &[(&'static str, &str)], value: f64) {
+        self.with_labels(labels).observe(value);
+    }
+
+    /// Observes a duration, in seconds.
+    pub fn observe_duration(&'static self, labels: &[(&'static str, &str)], duration: Duration) {
+        self.with_labels(labels).observe_duration(duration);
+    }
+}
+
+/// One series of a [`Histogram`].
+#[derive(Clone, Debug)]
+pub struct HistogramSeries(Arc<HistogramValue>);
+
+impl HistogramSeries {
+    pub fn observe(&self, value: f64) {
+        self.0.observe(value);
+    }
+
+    /// Observes a duration, in seconds.
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+}
+
+/// Renders every recorded metric in the Prometheus text exposition format.
+pub fn render() -> String {
+    let mut families = REGISTRY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    families.sort_by_key(|family| family.name());
+
+    let mut out = String::new();
+    for family in families {
+        family.render(&mut out);
+    }
+    out
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_gauges() {
+        static COUNTER: Counter = Counter::new("test_counter_total", "A test counter");
+        static GAUGE: Gauge = Gauge::new("test_gauge", "A test gauge");
+
+        COUNTER.increment(&[("kind", "a")]);
+        COUNTER.increment_by(&[("kind", "a")], 2);
+        COUNTER.increment(&[("kind", "b\"quoted\"")]);
+        GAUGE.increment(&[]);
+        GAUGE.increment(&[]);
+        GAUGE.decrement(&[]);
+
+        let rendered = render();
+        assert!(rendered.contains("# TYPE test_counter_total counter\n"));
+        assert!(rendered.contains("test_counter_total{kind=\"a\"} 3\n"));
+        assert!(rendered.contains("test_counter_total{kind=\"b\\\"quoted\\\"\"} 1\n"));
+        assert!(rendered.contains("# TYPE test_gauge gauge\ntest_gauge 1\n"));
+    }
+
+    #[test]
+    fn renders_cumulative_histogram_buckets() {
+        static HISTOGRAM: Histogram =
+            Histogram::with_buckets("test_histogram_seconds", "A test histogram", &[0.1, 1.0]);
+
+        HISTOGRAM.observe(&[], 0.05);
+        HISTOGRAM.observe(&[], 0.5);
+        HISTOGRAM.observe(&[], 5.0);
+
+        let rendered = render();
+        assert!(rendered.contains("test_histogram_seconds_bucket{le=\"0.1\"} 1\n"));
+        assert!(rendered.contains("test_histogram_seconds_bucket{le=\"1\"} 2\n"));
+        assert!(rendered.contains("test_histogram_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(rendered.contains("test_histogram_seconds_sum 5.55\n"));
+        assert!(rendered.contains("test_histogram_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn records_concurrently_to_shared_series() {
+        static COUNTER: Counter = Counter::new("test_concurrent_total", "A test counter");
+
+        let series = COUNTER.with_labels(&[("kind", "shared")]);
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let series = series.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        series.increment();
+                        COUNTER.increment(&[("kind", "shared")]);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().expect("recording thread panicked");
+        }
+
+        assert!(render().contains("test_concurrent_total{kind=\"shared\"} 16000\n"));
+    }
+}
@@ -0,0 +1,164 @@
+//! Carries the trace context of a span across process boundaries, so that the spans of a single
+//! user action stitch into one distributed trace as it fans out from sdf into jobs and function
+//! executions.
+//!
+//! A [`TraceContext`] holds the fields of the globally configured propagator (the W3C
+//! `traceparent` and `tracestate` for SI services). It is captured from a span on one side,
+//! carried in HTTP or NATS headers, or in a serialized payload, and applied as the parent of a
+//! span on the other side:
+//!
+//! ```
+//! use telemetry::{prelude::*, propagation::TraceContext};
+//!
+//! // Without a tracer, there is nothing to propagate
+//! let trace_context = TraceContext::current();
+//! assert!(trace_context.is_empty());
+//!
+//! let span = info_span!("job.process");
+//! trace_context.apply_to(&span);
+//! ```
+
+use std::collections::BTreeMap;
+
+use opentelemetry::{
+    global,
+    propagation::{Extractor, Injector},
+};
+use serde::{Deserialize, Serialize};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// The propagated fields of the trace context of a span.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct TraceContext(BTreeMap<String, String>);
+
+impl TraceContext {
+    /// Captures the trace context of the current span.
+    pub fn current() -> Self {
+        Self::for_span(&Span::current())
+    }
+
+    /// Captures the trace context of a span.
+    pub fn for_span(span: &Span) -> Self {
+        let mut trace_context = Self::default();
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&span.context(), &mut trace_context)
+        });
+        trace_context
+    }
+
+    /// Collects the propagated fields out of a set of headers, ignoring every other header.
+    pub fn from_headers<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let fields: Vec<String> = global::get_text_map_propagator(|propagator| {
+            propagator.fields().map(str::to_lowercase).collect()
+        });
+        Self(
+            headers
+                .into_iter()
+                .filter(|(name, _)| fields.contains(&name.to_lowercase()))
+                .map(|(name, value)| (name.to_lowercase(), value.to_owned()))
+                .collect(),
+        )
+    }
+
+    /// Collects the propagated fields out of the headers of an HTTP request.
+    pub fn from_http_headers(headers: &http::HeaderMap) -> Self {
+        Self::from_headers(
+            headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+        )
+    }
+
+    /// Adds the propagated fields to the headers of an HTTP request.
+    pub fn inject_into_http_headers(&self, headers: &mut http::HeaderMap) {
+        for (name, value) in self.iter() {
+            if let (Ok(name), Ok(value)) = (
+                http::header::HeaderName::try_from(name),
+                http::HeaderValue::try_from(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    /// Whether there is no trace to continue, such as when OpenTelemetry is disabled.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The propagated fields, by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Makes the trace context the parent of a span, continuing its trace. This must be done
+    /// before the span is entered.
+    pub fn apply_to(&self, span: &Span) {
+        if self.is_empty() {
+            return;
+        }
+        let parent = global::get_text_map_propagator(|propagator| propagator.extract(self));
+        span.set_parent(parent);
+    }
+}
+
+impl Injector for TraceContext {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_owned(), value);
+    }
+}
+
+impl Extractor for TraceContext {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+
+    use super::*;
+
+    const TRACEPARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    #[test]
+    fn from_headers_keeps_only_propagated_fields() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let trace_context = TraceContext::from_headers([
+            ("Traceparent", TRACEPARENT),
+            ("X-Idempotency-Key", "01H0000000000000000000000"),
+        ]);
+
+        assert_eq!(
+            vec![("traceparent", TRACEPARENT)],
+            trace_context.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn round_trips_through_http_headers_and_serde() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let trace_context = TraceContext::from_headers([("traceparent", TRACEPARENT)]);
+
+        let mut headers = http::HeaderMap::new();
+        trace_context.inject_into_http_headers(&mut headers);
+        assert_eq!(trace_context, TraceContext::from_http_headers(&headers));
+
+        let json = serde_json::to_value(&trace_context).expect("failed to serialize");
+        assert_eq!(serde_json::json!({ "traceparent": TRACEPARENT }), json);
+        assert_eq!(
+            trace_context,
+            serde_json::from_value(json).expect("failed to deserialize")
+        );
+    }
+}
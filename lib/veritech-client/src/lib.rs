@@ -1,37 +1,70 @@
-use futures::{StreamExt, TryStreamExt};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError, Weak},
+    time::Duration,
+};
+
+use futures::{stream::FuturesUnordered, FutureExt, StreamExt, TryStreamExt};
 use nats_subscriber::{SubscriberError, Subscription};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use telemetry::prelude::*;
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::{
+    sync::mpsc,
+    task::JoinHandle,
+    time::{self, Instant},
+};
 
 use veritech_core::{
-    nats_action_run_subject, nats_reconciliation_subject, nats_resolver_function_subject,
+    nats_action_run_subject, nats_heartbeat_subject, nats_liveness_subject,
+    nats_reconciliation_subject, nats_resolver_function_subject,
     nats_schema_variant_definition_subject, nats_subject, nats_validation_subject,
     reply_mailbox_for_output, reply_mailbox_for_result, FINAL_MESSAGE_HEADER_KEY,
+    HEARTBEAT_INTERVAL, IDEMPOTENCY_KEY_HEADER_KEY,
 };
 
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, ComponentKind, ComponentView, EncryptionKey,
-    EncryptionKeyError, FunctionResult, FunctionResultFailure, OutputStream, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionComponent, ResolverFunctionRequest,
-    ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
-    ValidationRequest, ValidationResultSuccess,
+    ActionRunRequest, ActionRunResultSuccess, Artifact, ComponentKind, ComponentView,
+    EncryptionKey, EncryptionKeyError, FunctionResult, FunctionResultFailure, LivenessStatus,
+    LivenessStatusParseError, OutputStream, ReconciliationRequest, ReconciliationResultSuccess,
+    ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
+    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, SensitiveContainer, ValidationRequest,
+    ValidationResultSuccess,
 };
-use si_data_nats::NatsClient;
+use si_data_nats::{NatsClient, NatsConfig, NatsError};
+
+pub use recording::{RecordingMode, Recordings};
+
+mod recording;
+
+/// A server is considered gone once it has missed this many heartbeats in a row.
+const MISSED_HEARTBEATS_LIMIT: u32 = 3;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ClientError {
+    #[error("no result before the request deadline of {0:?}")]
+    DeadlineExceeded(Duration),
+    #[error(transparent)]
+    InvalidLivenessStatus(#[from] LivenessStatusParseError),
+    #[error("failed to deserialize json message")]
+    JSONDeserialize(#[source] serde_json::Error),
     #[error("failed to serialize json message")]
     JSONSerialize(#[source] serde_json::Error),
     #[error("nats error")]
     Nats(#[from] si_data_nats::NatsError),
+    #[error("no recording of the request with key {0}")]
+    NoRecording(String),
     #[error("no function result from cyclone; bug!")]
     NoResult,
     #[error("unable to publish message: {0:?}")]
     PublishingFailed(si_data_nats::Message),
+    #[error("failed to read or write recording")]
+    Recording(#[source] std::io::Error),
     #[error("root connection closed")]
     RootConnectionClosed,
     #[error(transparent)]
@@ -40,20 +73,123 @@ pub enum ClientError {
 
 pub type ClientResult<T> = Result<T, ClientError>;
 
+/// How a service reaches veritech.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct VeritechClientConfig {
+    /// A NATS connection dedicated to veritech requests. The service's own NATS connection is used
+    /// when unset.
+    #[serde(default)]
+    pub nats: Option<NatsConfig>,
+    /// The options applied to every request.
+    #[serde(default)]
+    pub requests: RequestOptions,
+}
+
+/// How long a request may take, and how it is resubmitted to the servers of other subject
+/// prefixes when its own server does not answer.
+///
+/// Every submission of a request carries the same idempotency key, so that a server which receives
+/// the request twice only executes it once. Servers don't share the keys they have seen though, so
+/// only resolver functions and validations, which are safe to execute more than once, are hedged
+/// and failed over. Action runs, reconciliations and schema variant definitions are only ever
+/// submitted to the server of the client's subject prefix.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RequestOptions {
+    /// Gives up on a request with [`ClientError::DeadlineExceeded`] once it has waited this many
+    /// milliseconds for a result. Requests wait indefinitely when unset.
+    pub deadline_ms: Option<u64>,
+    /// Submits the request to the server of another subject prefix as well once it has waited
+    /// this many milliseconds for a result, taking whichever result arrives first.
+    pub hedge_after_ms: Option<u64>,
+    /// Resubmits the request to the server of another subject prefix when its server fails to
+    /// take the request or stops sending heartbeats. Only takes effect once the client tracks
+    /// servers, see [`Client::with_server_tracking`].
+    pub failover: bool,
+}
+
+impl RequestOptions {
+    fn deadline(&self) -> Option<Duration> {
+        self.deadline_ms.map(Duration::from_millis)
+    }
+
+    fn hedge_after(&self) -> Option<Duration> {
+        self.hedge_after_ms.map(Duration::from_millis)
+    }
+
+    /// Keeps the deadline of the options, without hedging or failover.
+    fn submit_once(&self) -> Self {
+        Self {
+            deadline_ms: self.deadline_ms,
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Client {
     nats: NatsClient,
+    options: RequestOptions,
+    servers: Option<Arc<Servers>>,
+    recordings: Option<Recordings>,
 }
 
 impl Client {
     pub fn new(nats: NatsClient) -> Self {
-        Self { nats }
+        Self {
+            nats,
+            options: RequestOptions::default(),
+            servers: None,
+            recordings: None,
+        }
+    }
+
+    /// Returns a copy of the client which applies the given options to its requests.
+    #[must_use]
+    pub fn with_request_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns a copy of the client which records the results of its requests, or answers them
+    /// from the recordings, depending on the [`RecordingMode`].
+    #[must_use]
+    pub fn with_recordings(mut self, recordings: Recordings) -> Self {
+        self.recordings = Some(recordings);
+        self
+    }
+
+    /// Starts tracking the servers of every subject prefix through their heartbeats, which
+    /// allows requests to be hedged and to fail over to those servers.
+    pub async fn with_server_tracking(mut self) -> Result<Self, NatsError> {
+        let heartbeats = self.nats.subscribe(nats_heartbeat_subject()).await?;
+        let servers = Arc::new(Servers::default());
+        tokio::spawn(track_servers_task(heartbeats, Arc::downgrade(&servers)));
+        self.servers = Some(servers);
+        Ok(self)
     }
 
     fn nats_subject_prefix(&self) -> Option<&str> {
         self.nats.metadata().subject_prefix()
     }
 
+    /// Pings a veritech server over NATS, failing if none replies within the given timeout.
+    #[instrument(name = "client.liveness", skip_all)]
+    pub async fn liveness(&self, timeout: Duration) -> ClientResult<LivenessStatus> {
+        let reply = self
+            .nats
+            .request_timeout(
+                nats_liveness_subject(self.nats_subject_prefix()),
+                Vec::new(),
+                timeout,
+            )
+            .await?;
+
+        Ok(LivenessStatus::from_str(&String::from_utf8_lossy(
+            reply.data(),
+        ))?)
+    }
+
     #[instrument(name = "client.execute_resolver_function", skip_all)]
     pub async fn execute_resolver_function(
         &self,
@@ -61,7 +197,8 @@ impl Client {
         request: &ResolverFunctionRequest,
     ) -> ClientResult<FunctionResult<ResolverFunctionResultSuccess>> {
         self.execute_request(
-            nats_resolver_function_subject(self.nats_subject_prefix()),
+            nats_resolver_function_subject,
+            self.options,
             output_tx,
             request,
         )
@@ -75,8 +212,10 @@ impl Client {
         request: &ResolverFunctionRequest,
         subject_suffix: impl AsRef<str>,
     ) -> ClientResult<FunctionResult<ResolverFunctionResultSuccess>> {
+        let subject_suffix = subject_suffix.as_ref().to_owned();
         self.execute_request(
-            nats_subject(self.nats_subject_prefix(), subject_suffix),
+            move |prefix| nats_subject(prefix, &subject_suffix),
+            self.options,
             output_tx,
             request,
         )
@@ -89,12 +228,8 @@ impl Client {
         output_tx: mpsc::Sender<OutputStream>,
         request: &ValidationRequest,
     ) -> ClientResult<FunctionResult<ValidationResultSuccess>> {
-        self.execute_request(
-            nats_validation_subject(self.nats_subject_prefix()),
-            output_tx,
-            request,
-        )
-        .await
+        self.execute_request(nats_validation_subject, self.options, output_tx, request)
+            .await
     }
 
     #[instrument(name = "client.execute_validation_with_subject", skip_all)]
@@ -104,8 +239,10 @@ impl Client {
         request: &ValidationResultSuccess,
         subject_suffix: impl AsRef<str>,
     ) -> ClientResult<FunctionResult<ValidationResultSuccess>> {
+        let subject_suffix = subject_suffix.as_ref().to_owned();
         self.execute_request(
-            nats_subject(self.nats_subject_prefix(), subject_suffix),
+            move |prefix| nats_subject(prefix, &subject_suffix),
+            self.options,
             output_tx,
             request,
         )
@@ -119,7 +256,8 @@ impl Client {
         request: &ActionRunRequest,
     ) -> ClientResult<FunctionResult<ActionRunResultSuccess>> {
         self.execute_request(
-            nats_action_run_subject(self.nats_subject_prefix()),
+            nats_action_run_subject,
+            self.options.submit_once(),
             output_tx,
             request,
         )
@@ -133,8 +271,10 @@ impl Client {
         request: &ActionRunRequest,
         subject_suffix: impl AsRef<str>,
     ) -> ClientResult<FunctionResult<ActionRunResultSuccess>> {
+        let subject_suffix = subject_suffix.as_ref().to_owned();
         self.execute_request(
-            nats_subject(self.nats_subject_prefix(), subject_suffix),
+            move |prefix| nats_subject(prefix, &subject_suffix),
+            self.options.submit_once(),
             output_tx,
             request,
         )
@@ -148,7 +288,8 @@ impl Client {
         request: &ReconciliationRequest,
     ) -> ClientResult<FunctionResult<ReconciliationResultSuccess>> {
         self.execute_request(
-            nats_reconciliation_subject(self.nats_subject_prefix()),
+            nats_reconciliation_subject,
+            self.options.submit_once(),
             output_tx,
             request,
         )
@@ -162,8 +303,10 @@ impl Client {
         request: &ReconciliationRequest,
         subject_suffix: impl AsRef<str>,
     ) -> ClientResult<FunctionResult<ReconciliationResultSuccess>> {
+        let subject_suffix = subject_suffix.as_ref().to_owned();
         self.execute_request(
-            nats_subject(self.nats_subject_prefix(), subject_suffix),
+            move |prefix| nats_subject(prefix, &subject_suffix),
+            self.options.submit_once(),
             output_tx,
             request,
         )
@@ -177,7 +320,8 @@ impl Client {
         request: &SchemaVariantDefinitionRequest,
     ) -> ClientResult<FunctionResult<SchemaVariantDefinitionResultSuccess>> {
         self.execute_request(
-            nats_schema_variant_definition_subject(self.nats_subject_prefix()),
+            nats_schema_variant_definition_subject,
+            self.options.submit_once(),
             output_tx,
             request,
         )
@@ -191,25 +335,186 @@ impl Client {
         request: &SchemaVariantDefinitionRequest,
         subject_suffix: impl AsRef<str>,
     ) -> ClientResult<FunctionResult<SchemaVariantDefinitionResultSuccess>> {
+        let subject_suffix = subject_suffix.as_ref().to_owned();
         self.execute_request(
-            nats_subject(self.nats_subject_prefix(), subject_suffix),
+            move |prefix| nats_subject(prefix, &subject_suffix),
+            self.options.submit_once(),
             output_tx,
             request,
         )
         .await
     }
 
+    /// Executes a request, or answers it from the [`Recordings`] when replaying them.
     async fn execute_request<R, S>(
         &self,
-        subject: impl Into<String>,
+        subject: impl Fn(Option<&str>) -> String,
+        options: RequestOptions,
         output_tx: mpsc::Sender<OutputStream>,
         request: &R,
     ) -> ClientResult<FunctionResult<S>>
     where
         R: Serialize,
-        S: DeserializeOwned,
+        S: DeserializeOwned + Send + 'static,
+    {
+        let recordings = match &self.recordings {
+            Some(recordings) => recordings,
+            None => {
+                return self
+                    .execute_live(subject, options, output_tx, request)
+                    .await
+            }
+        };
+
+        // Recordings are shared by every subject prefix, so they are keyed without one
+        let recorded_subject = subject(None);
+        let request = serde_json::to_value(request).map_err(ClientError::JSONSerialize)?;
+        let result = match recordings.mode() {
+            RecordingMode::Record => {
+                let result: FunctionResult<serde_json::Value> = self
+                    .execute_live(subject, options, output_tx, &request)
+                    .await?;
+                let result = serde_json::to_value(result).map_err(ClientError::JSONSerialize)?;
+                recordings
+                    .record(&recorded_subject, &request, &result)
+                    .await?;
+                result
+            }
+            RecordingMode::Replay => recordings.replay(&recorded_subject, &request).await?,
+        };
+        serde_json::from_value(result).map_err(ClientError::JSONDeserialize)
+    }
+
+    /// Submits a request to the server of the client's subject prefix and waits for its result,
+    /// resubmitting it to the servers of other subject prefixes as the [`RequestOptions`] allow.
+    ///
+    /// The output of every submission is forwarded to `output_tx`, so a hedged request may
+    /// forward the output of more than one execution.
+    async fn execute_live<R, S>(
+        &self,
+        subject: impl Fn(Option<&str>) -> String,
+        options: RequestOptions,
+        output_tx: mpsc::Sender<OutputStream>,
+        request: &R,
+    ) -> ClientResult<FunctionResult<S>>
+    where
+        R: Serialize,
+        S: DeserializeOwned + Send + 'static,
     {
         let msg = serde_json::to_vec(request).map_err(ClientError::JSONSerialize)?;
+        let idempotency_key = ulid::Ulid::new().to_string();
+        let deadline = options.deadline();
+        let deadline_at = deadline.map(|deadline| Instant::now() + deadline);
+        let hedge_after = options.hedge_after();
+
+        let own_prefix = self.nats_subject_prefix().map(ToOwned::to_owned);
+        let mut submitted = vec![own_prefix.clone()];
+        let mut attempts = FuturesUnordered::new();
+        attempts.push(self.submission(
+            own_prefix,
+            &subject,
+            output_tx.clone(),
+            msg.clone(),
+            &idempotency_key,
+        ));
+        let mut next_hedge_at = hedge_after.map(|hedge_after| Instant::now() + hedge_after);
+        let mut next_check_at = Instant::now() + HEARTBEAT_INTERVAL;
+
+        loop {
+            // The prefixes with live servers which have not been submitted to yet, most recently
+            // heard from first
+            let alternative = self
+                .servers
+                .as_ref()
+                .and_then(|servers| servers.alternatives(&submitted).into_iter().next());
+            let can_hedge = next_hedge_at.is_some() && alternative.is_some();
+            let can_fail_over = options.failover && alternative.is_some();
+
+            let resubmit_to = tokio::select! {
+                Some((prefix, result)) = attempts.next() => match result {
+                    Ok(result) => return Ok(result),
+                    Err(err) if can_fail_over => {
+                        warn!(
+                            error = ?err,
+                            prefix = ?prefix,
+                            "request failed, failing over to another subject prefix",
+                        );
+                        alternative
+                    }
+                    Err(err) if attempts.is_empty() => return Err(err),
+                    Err(err) => {
+                        warn!(error = ?err, prefix = ?prefix, "hedged request failed");
+                        None
+                    }
+                },
+                _ = sleep_until(deadline_at), if deadline_at.is_some() => {
+                    return Err(ClientError::DeadlineExceeded(deadline.unwrap_or_default()));
+                }
+                _ = sleep_until(next_hedge_at), if can_hedge => {
+                    next_hedge_at = hedge_after.map(|hedge_after| Instant::now() + hedge_after);
+                    debug!("hedging request on another subject prefix");
+                    alternative
+                }
+                _ = time::sleep_until(next_check_at), if can_fail_over => {
+                    next_check_at = Instant::now() + HEARTBEAT_INTERVAL;
+                    // Only the latest submission is watched, as the request has already failed
+                    // over from the servers of the others
+                    let watched = submitted.last().cloned().flatten();
+                    match &self.servers {
+                        Some(servers) if !servers.is_alive(watched.as_deref()) => {
+                            warn!(
+                                prefix = ?watched,
+                                "server stopped sending heartbeats, failing over to another subject prefix",
+                            );
+                            alternative
+                        }
+                        _ => None,
+                    }
+                }
+            };
+
+            if let Some(prefix) = resubmit_to {
+                submitted.push(prefix.clone());
+                attempts.push(self.submission(
+                    prefix,
+                    &subject,
+                    output_tx.clone(),
+                    msg.clone(),
+                    &idempotency_key,
+                ));
+            }
+        }
+    }
+
+    /// Submits the request to the server of a subject prefix, resolving to the prefix along with
+    /// the result.
+    fn submission<'a, S>(
+        &'a self,
+        prefix: Option<String>,
+        subject: &impl Fn(Option<&str>) -> String,
+        output_tx: mpsc::Sender<OutputStream>,
+        msg: Vec<u8>,
+        idempotency_key: &'a str,
+    ) -> Submission<'a, S>
+    where
+        S: DeserializeOwned + Send + 'static,
+    {
+        let subject = subject(prefix.as_deref());
+        self.submit(subject, output_tx, msg, idempotency_key)
+            .map(move |result| (prefix, result))
+            .boxed()
+    }
+
+    async fn submit<S>(
+        &self,
+        subject: String,
+        output_tx: mpsc::Sender<OutputStream>,
+        msg: Vec<u8>,
+        idempotency_key: &str,
+    ) -> ClientResult<FunctionResult<S>>
+    where
+        S: DeserializeOwned,
+    {
         let reply_mailbox_root = self.nats.new_inbox();
 
         // Construct a subscription stream for the result
@@ -236,10 +541,12 @@ impl Client {
             .await?;
 
         // Spawn a task to forward output to the sender provided by the caller
-        tokio::spawn(forward_output_task(output_subscription, output_tx));
+        let mut output_forwarder = OutputForwarder(Some(tokio::spawn(forward_output_task(
+            output_subscription,
+            output_tx,
+        ))));
 
         // Submit the request message
-        let subject = subject.into();
         trace!(
             messaging.destination = &subject.as_str(),
             "publishing message"
@@ -248,11 +555,19 @@ impl Client {
         // Root reply mailbox will receive a reply if nobody is listening to the channel `subject`
         let mut root_subscription = self.nats.subscribe(reply_mailbox_root.clone()).await?;
 
+        let headers = [(IDEMPOTENCY_KEY_HEADER_KEY, idempotency_key)]
+            .iter()
+            .collect();
         self.nats
-            .publish_with_reply_or_headers(subject, Some(reply_mailbox_root.clone()), None, msg)
+            .publish_with_reply_or_headers(
+                subject,
+                Some(reply_mailbox_root.clone()),
+                Some(&headers),
+                msg,
+            )
             .await?;
 
-        tokio::select! {
+        let result = tokio::select! {
             // Wait for one message on the result reply mailbox
             result = result_subscription.try_next() => {
                 root_subscription.unsubscribe().await?;
@@ -283,10 +598,106 @@ impl Client {
                 // will return with an error
                 Err(ClientError::PublishingFailed(reply.ok_or(ClientError::RootConnectionClosed)??))
             }
+        };
+
+        // The server finished with the request, so the forwarder will see its final message
+        output_forwarder.detach();
+        result
+    }
+}
+
+type Submission<'a, S> =
+    Pin<Box<dyn Future<Output = (Option<String>, ClientResult<FunctionResult<S>>)> + Send + 'a>>;
+
+/// Waits until the given instant, or forever if there is none.
+async fn sleep_until(at: Option<Instant>) {
+    match at {
+        Some(at) => time::sleep_until(at).await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Aborts the task forwarding the output of a submission if the submission is abandoned, as its
+/// server may never send the final output message which ends the task.
+struct OutputForwarder(Option<JoinHandle<()>>);
+
+impl OutputForwarder {
+    fn detach(&mut self) {
+        self.0.take();
+    }
+}
+
+impl Drop for OutputForwarder {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            handle.abort();
         }
     }
 }
 
+/// The servers heard from through their heartbeats, by the subject prefix they serve.
+#[derive(Debug, Default)]
+struct Servers {
+    heard_at: Mutex<HashMap<Option<String>, Instant>>,
+}
+
+impl Servers {
+    fn record(&self, prefix: Option<String>) {
+        self.heard_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(prefix, Instant::now());
+    }
+
+    /// Whether the server of a prefix is still sending heartbeats. A prefix which has never been
+    /// heard from is assumed to be alive, as its server may not send heartbeats at all.
+    fn is_alive(&self, prefix: Option<&str>) -> bool {
+        let heard_at = self.heard_at.lock().unwrap_or_else(PoisonError::into_inner);
+        match heard_at.get(&prefix.map(ToOwned::to_owned)) {
+            Some(heard_at) => heard_at.elapsed() < HEARTBEAT_INTERVAL * MISSED_HEARTBEATS_LIMIT,
+            None => true,
+        }
+    }
+
+    /// The prefixes whose servers are alive, other than the excluded ones, most recently heard
+    /// from first.
+    fn alternatives(&self, excluded: &[Option<String>]) -> Vec<Option<String>> {
+        let heard_at = self.heard_at.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut alternatives: Vec<_> = heard_at
+            .iter()
+            .filter(|(prefix, heard_at)| {
+                !excluded.contains(prefix)
+                    && heard_at.elapsed() < HEARTBEAT_INTERVAL * MISSED_HEARTBEATS_LIMIT
+            })
+            .collect();
+        alternatives.sort_by(|(_, a), (_, b)| b.cmp(a));
+        alternatives
+            .into_iter()
+            .map(|(prefix, _)| prefix.clone())
+            .collect()
+    }
+}
+
+async fn track_servers_task(mut heartbeats: si_data_nats::Subscription, servers: Weak<Servers>) {
+    while let Some(heartbeat) = heartbeats.next().await {
+        // Stop once every client sharing the servers has been dropped
+        let servers = match servers.upgrade() {
+            Some(servers) => servers,
+            None => break,
+        };
+        match heartbeat {
+            Ok(heartbeat) => {
+                let prefix = String::from_utf8_lossy(heartbeat.data());
+                servers.record((!prefix.is_empty()).then(|| prefix.into_owned()));
+            }
+            Err(err) => warn!(error = ?err, "server tracker received an error on its subscription"),
+        }
+    }
+    if let Err(err) = heartbeats.unsubscribe().await {
+        warn!(error = ?err, "error when unsubscribing from heartbeat subscription");
+    }
+}
+
 async fn forward_output_task(
     mut output_subscription: Subscription<OutputStream>,
     output_tx: mpsc::Sender<OutputStream>,
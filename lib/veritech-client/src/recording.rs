@@ -0,0 +1,151 @@
+//! Recordings of the results of the requests made through a [`Client`](crate::Client), so that
+//! tests can replay them instead of executing functions.
+//!
+//! Each recording is a JSON file named after a hash of the request's subject and body. The
+//! execution id of the request is left out of the hash, and the replayed result carries the
+//! execution id of the request it answers, so that a request made again with a new execution id
+//! still finds its recording. Output streamed while the function executed is not recorded, so
+//! replayed requests forward no output.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+
+use crate::{ClientError, ClientResult};
+
+/// The fields which may hold the execution id of a request or a result, depending on how it is
+/// serialized.
+const EXECUTION_ID_FIELDS: &[&str] = &["executionId", "execution_id"];
+
+/// Whether requests are executed and their results recorded, or answered from the recordings.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecordingMode {
+    /// Executes every request, then writes its result to the recordings, replacing any previous
+    /// recording of the same request.
+    Record,
+    /// Answers every request from the recordings, failing with [`ClientError::NoRecording`] for a
+    /// request which was never recorded.
+    Replay,
+}
+
+/// A directory of recorded results.
+#[derive(Clone, Debug)]
+pub struct Recordings {
+    dir: PathBuf,
+    mode: RecordingMode,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Recording {
+    subject: String,
+    request: Value,
+    result: Value,
+}
+
+impl Recordings {
+    pub fn new(dir: impl Into<PathBuf>, mode: RecordingMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    pub fn mode(&self) -> RecordingMode {
+        self.mode
+    }
+
+    /// Returns the key of a request to the given subject, which is stable across executions of
+    /// the same request.
+    pub fn key(subject: &str, request: &Value) -> String {
+        let mut request = request.clone();
+        if let Some(request) = request.as_object_mut() {
+            for field in EXECUTION_ID_FIELDS {
+                request.remove(*field);
+            }
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(subject.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(request.to_string().as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// Loads the recorded result of a request, carrying the execution id of the request.
+    pub(crate) async fn replay(&self, subject: &str, request: &Value) -> ClientResult<Value> {
+        let key = Self::key(subject, request);
+        let contents = match tokio::fs::read(self.path(&key)).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ClientError::NoRecording(key));
+            }
+            Err(err) => return Err(ClientError::Recording(err)),
+        };
+        let recording: Recording =
+            serde_json::from_slice(&contents).map_err(ClientError::JSONDeserialize)?;
+        debug!(%key, %subject, "replaying recorded result");
+
+        let mut result = recording.result;
+        if let Some(execution_id) = execution_id(request) {
+            // Results are serialized as `{"Success": {..}}` or `{"Failure": {..}}`
+            for outcome in result
+                .as_object_mut()
+                .into_iter()
+                .flat_map(|r| r.values_mut())
+            {
+                if let Some(outcome) = outcome.as_object_mut() {
+                    for field in EXECUTION_ID_FIELDS {
+                        if let Some(value) = outcome.get_mut(*field) {
+                            *value = execution_id.clone();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Writes the result of a request to the recordings.
+    pub(crate) async fn record(
+        &self,
+        subject: &str,
+        request: &Value,
+        result: &Value,
+    ) -> ClientResult<()> {
+        let key = Self::key(subject, request);
+        let recording = Recording {
+            subject: subject.to_owned(),
+            request: request.clone(),
+            result: result.clone(),
+        };
+        let contents = serde_json::to_vec_pretty(&recording).map_err(ClientError::JSONSerialize)?;
+
+        // Concurrent tests may record the same request, so the recording is written to a
+        // temporary file first and moved into place, never leaving a partial recording behind
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(ClientError::Recording)?;
+        let tmp_path = self.dir.join(format!(".{key}.{}.tmp", ulid::Ulid::new()));
+        tokio::fs::write(&tmp_path, contents)
+            .await
+            .map_err(ClientError::Recording)?;
+        tokio::fs::rename(&tmp_path, self.path(&key))
+            .await
+            .map_err(ClientError::Recording)?;
+        debug!(%key, %subject, "recorded result");
+        Ok(())
+    }
+}
+
+fn execution_id(request: &Value) -> Option<&Value> {
+    EXECUTION_ID_FIELDS
+        .iter()
+        .find_map(|field| request.get(*field))
+}
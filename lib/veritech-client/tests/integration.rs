@@ -2,16 +2,17 @@ use std::env;
 
 use base64::{engine::general_purpose, Engine};
 use cyclone_core::{
-    ComponentKind, ComponentView, FunctionResult, ResolverFunctionComponent,
+    ActionRunRequest, ComponentKind, ComponentView, FunctionResult, ResolverFunctionComponent,
     ResolverFunctionRequest, ResolverFunctionResponseType, SchemaVariantDefinitionRequest,
     ValidationRequest,
 };
+use futures::StreamExt;
 use si_data_nats::{NatsClient, NatsConfig};
 use test_log::test;
 use tokio::{sync::mpsc, task::JoinHandle};
 use tracing::info;
 use uuid::Uuid;
-use veritech_client::Client;
+use veritech_client::{Client, ClientError, RecordingMode, Recordings, RequestOptions};
 use veritech_server::{
     Config, CycloneSpec, Instance, LocalUdsInstance, Server, ServerError, StandardConfig,
 };
@@ -345,3 +346,145 @@ async fn executes_simple_schema_variant_definition() {
         }
     }
 }
+
+#[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+#[test(tokio::test)]
+async fn request_without_a_result_before_its_deadline_fails() {
+    let prefix = nats_prefix();
+    let nats = nats(prefix.clone()).await;
+    // Stands in for a server which takes the request and then dies
+    let mut requests = nats
+        .subscribe(format!("{prefix}.veritech.fn.validation"))
+        .await
+        .expect("failed to subscribe");
+    let client = Client::new(nats).with_request_options(RequestOptions {
+        deadline_ms: Some(500),
+        ..Default::default()
+    });
+
+    let (tx, _rx) = mpsc::channel(64);
+    let request = ValidationRequest {
+        execution_id: "31337".to_string(),
+        handler: "isThirtyThree".to_string(),
+        value: 33.into(),
+        code_base64: base64_encode(
+            "function isThirtyThree(value) { return { valid: value === 33 }; };",
+        ),
+    };
+
+    let result = client.execute_validation(tx, &request).await;
+    assert!(matches!(result, Err(ClientError::DeadlineExceeded(_))));
+
+    let received = requests
+        .next()
+        .await
+        .expect("subscription closed")
+        .expect("failed to receive request");
+    assert!(received
+        .headers()
+        .expect("request has no headers")
+        .contains_key("X-Idempotency-Key"));
+}
+
+#[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+#[test(tokio::test)]
+async fn action_run_is_not_hedged() {
+    let prefix = nats_prefix();
+    let other_prefix = nats_prefix();
+    let nats = nats(prefix.clone()).await;
+    // Stand in for servers which take the request and never answer
+    let mut requests = nats
+        .subscribe(format!("{prefix}.veritech.fn.actionrun"))
+        .await
+        .expect("failed to subscribe");
+    let mut other_requests = nats
+        .subscribe(format!("{other_prefix}.veritech.fn.actionrun"))
+        .await
+        .expect("failed to subscribe");
+    let client = Client::new(nats.clone())
+        .with_request_options(RequestOptions {
+            deadline_ms: Some(1000),
+            hedge_after_ms: Some(100),
+            failover: true,
+        })
+        .with_server_tracking()
+        .await
+        .expect("failed to track servers");
+    nats.publish("veritech.heartbeat", other_prefix.as_bytes())
+        .await
+        .expect("failed to publish heartbeat");
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let (tx, _rx) = mpsc::channel(64);
+    let request = ActionRunRequest {
+        execution_id: "31337".to_string(),
+        handler: "create".to_string(),
+        code_base64: base64_encode("function create() { return { status: 'ok' }; };"),
+        args: serde_json::json!({}),
+    };
+
+    let result = client.execute_action_run(tx, &request).await;
+    assert!(matches!(result, Err(ClientError::DeadlineExceeded(_))));
+
+    requests
+        .next()
+        .await
+        .expect("subscription closed")
+        .expect("failed to receive request");
+    // Action runs have side effects, so they are only ever submitted to the client's own server
+    let resubmitted =
+        tokio::time::timeout(std::time::Duration::from_millis(100), other_requests.next()).await;
+    assert!(resubmitted.is_err());
+}
+
+#[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+#[test(tokio::test)]
+async fn replays_recorded_results_without_a_server() {
+    let recordings_dir = tempfile::tempdir().expect("failed to create recordings dir");
+    let prefix = nats_prefix();
+    run_veritech_server_for_uds_cyclone(prefix.clone()).await;
+    let recording_client = client(prefix).await.with_recordings(Recordings::new(
+        recordings_dir.path(),
+        RecordingMode::Record,
+    ));
+
+    let mut request = ValidationRequest {
+        execution_id: "31337".to_string(),
+        handler: "isThirtyThree".to_string(),
+        value: 33.into(),
+        code_base64: base64_encode(
+            "function isThirtyThree(value) { return { valid: value === 33 }; };",
+        ),
+    };
+    let (tx, _rx) = mpsc::channel(64);
+    recording_client
+        .execute_validation(tx, &request)
+        .await
+        .expect("failed to execute validation");
+
+    // No server runs for this prefix, so every result must come from the recordings
+    let replaying_client = client(nats_prefix()).await.with_recordings(Recordings::new(
+        recordings_dir.path(),
+        RecordingMode::Replay,
+    ));
+    request.execution_id = "31338".to_string();
+    let (tx, _rx) = mpsc::channel(64);
+    match replaying_client
+        .execute_validation(tx, &request)
+        .await
+        .expect("failed to replay validation")
+    {
+        FunctionResult::Success(success) => {
+            assert_eq!(success.execution_id, "31338");
+            assert!(success.valid);
+        }
+        FunctionResult::Failure(failure) => {
+            panic!("function did not succeed and should have: {failure:?}")
+        }
+    }
+
+    request.value = 34.into();
+    let (tx, _rx) = mpsc::channel(64);
+    let result = replaying_client.execute_validation(tx, &request).await;
+    assert!(matches!(result, Err(ClientError::NoRecording(_))));
+}
@@ -11,7 +11,11 @@
     clippy::module_name_repetitions
 )]
 
+use std::time::Duration;
+
 const NATS_ACTION_RUN_DEFAULT_SUBJECT: &str = "veritech.fn.actionrun";
+const NATS_HEARTBEAT_SUBJECT: &str = "veritech.heartbeat";
+const NATS_LIVENESS_DEFAULT_SUBJECT: &str = "veritech.liveness";
 const NATS_CONCILIATION_DEFAULT_SUBJECT: &str = "veritech.fn.reconciliation";
 const NATS_RESOLVER_FUNCTION_DEFAULT_SUBJECT: &str = "veritech.fn.resolverfunction";
 const NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT: &str = "veritech.fn.schemavariantdefinition";
@@ -19,6 +23,13 @@ const NATS_VALIDATION_DEFAULT_SUBJECT: &str = "veritech.fn.validation";
 
 pub const FINAL_MESSAGE_HEADER_KEY: &str = "X-Final-Message";
 
+/// The header holding a key which is unique to a request, so that a server can detect a request
+/// which it has already received.
+pub const IDEMPOTENCY_KEY_HEADER_KEY: &str = "X-Idempotency-Key";
+
+/// How often a server publishes a heartbeat on [`nats_heartbeat_subject`].
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
 pub fn reply_mailbox_for_output(reply_mailbox: &str) -> String {
     format!("{reply_mailbox}.output")
 }
@@ -47,6 +58,17 @@ pub fn nats_schema_variant_definition_subject(prefix: Option<&str>) -> String {
     nats_subject(prefix, NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT)
 }
 
+pub fn nats_liveness_subject(prefix: Option<&str>) -> String {
+    nats_subject(prefix, NATS_LIVENESS_DEFAULT_SUBJECT)
+}
+
+/// The subject on which servers publish their heartbeats, whose payload is the subject prefix the
+/// server serves (empty for none). Unlike the other subjects it is never prefixed, so that a client
+/// can find the servers of every prefix.
+pub fn nats_heartbeat_subject() -> String {
+    NATS_HEARTBEAT_SUBJECT.to_string()
+}
+
 pub fn nats_subject(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
     let suffix = suffix.as_ref();
     match prefix {
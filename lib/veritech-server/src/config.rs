@@ -16,6 +16,7 @@ use deadpool_cyclone::{
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
+use si_settings::require_non_zero;
 use telemetry::prelude::*;
 use thiserror::Error;
 
@@ -50,6 +51,12 @@ pub struct Config {
     nats: NatsConfig,
 
     cyclone_spec: CycloneSpec,
+
+    #[builder(default)]
+    metrics_socket_addr: Option<SocketAddr>,
+
+    #[builder(default = "default_concurrency_limit()")]
+    concurrency_limit: usize,
 }
 
 #[remain::sorted]
@@ -63,10 +70,25 @@ impl StandardConfig for Config {
     type Builder = ConfigBuilder;
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConfigFile {
     pub nats: NatsConfig,
     pub cyclone: CycloneConfig,
+    #[serde(default)]
+    pub metrics_socket_addr: Option<SocketAddr>,
+    #[serde(default = "default_concurrency_limit")]
+    pub concurrency_limit: usize,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            nats: Default::default(),
+            cyclone: Default::default(),
+            metrics_socket_addr: None,
+            concurrency_limit: default_concurrency_limit(),
+        }
+    }
 }
 
 impl ConfigFile {
@@ -74,6 +96,8 @@ impl ConfigFile {
         Self {
             nats: Default::default(),
             cyclone: CycloneConfig::default_local_http(),
+            metrics_socket_addr: None,
+            concurrency_limit: default_concurrency_limit(),
         }
     }
 
@@ -81,6 +105,8 @@ impl ConfigFile {
         Self {
             nats: Default::default(),
             cyclone: CycloneConfig::default_local_uds(),
+            metrics_socket_addr: None,
+            concurrency_limit: default_concurrency_limit(),
         }
     }
 }
@@ -98,6 +124,9 @@ impl TryFrom<ConfigFile> for Config {
         let mut config = Config::builder();
         config.nats(value.nats);
         config.cyclone_spec(value.cyclone.try_into()?);
+        config.metrics_socket_addr(value.metrics_socket_addr);
+        require_non_zero("concurrency_limit", value.concurrency_limit)?;
+        config.concurrency_limit(value.concurrency_limit);
         config.build().map_err(Into::into)
     }
 }
@@ -114,6 +143,16 @@ impl Config {
         &self.nats
     }
 
+    /// Gets the socket address on which metrics are served, if any.
+    pub fn metrics_socket_addr(&self) -> Option<SocketAddr> {
+        self.metrics_socket_addr
+    }
+
+    /// Gets the most requests this instance executes at once.
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrency_limit
+    }
+
     /// Gets a reference to the config's subject prefix.
     pub fn subject_prefix(&self) -> Option<&str> {
         self.nats.subject_prefix.as_deref()
@@ -438,6 +477,10 @@ fn default_limit_requests() -> Option<u32> {
     Some(1)
 }
 
+fn default_concurrency_limit() -> usize {
+    64
+}
+
 fn default_enable_endpoint() -> bool {
     true
 }
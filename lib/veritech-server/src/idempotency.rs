@@ -0,0 +1,52 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use nats_subscriber::Request;
+use telemetry::{metrics::Counter, prelude::*};
+use tokio::time::Instant;
+use veritech_core::IDEMPOTENCY_KEY_HEADER_KEY;
+
+static DUPLICATE_REQUESTS: Counter = Counter::new(
+    "veritech_duplicate_requests_total",
+    "Total number of requests dropped as duplicates of a request already received",
+);
+
+/// How long the idempotency key of a request is remembered after the request is received.
+const RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// The idempotency keys of recently received requests, shared by every request subscription so
+/// that a request resubmitted by a client is only executed once.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SeenRequests {
+    keys: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl SeenRequests {
+    /// Records the idempotency key of a request, returning whether the request is a duplicate of
+    /// one received within the retention period. Requests without a key are never duplicates.
+    pub(crate) fn is_duplicate<T>(&self, request: &Request<T>) -> bool {
+        let key = match request
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(IDEMPOTENCY_KEY_HEADER_KEY))
+            .and_then(|values| values.iter().next())
+        {
+            Some(key) => key,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        let mut keys = self.keys.lock().unwrap_or_else(PoisonError::into_inner);
+        keys.retain(|_, seen_at| now.saturating_duration_since(*seen_at) < RETENTION);
+        if keys.contains_key(key) {
+            DUPLICATE_REQUESTS.increment(&[]);
+            warn!(idempotency_key = %key, "dropping duplicate request");
+            return true;
+        }
+        keys.insert(key.clone(), now);
+        false
+    }
+}
@@ -1,4 +1,5 @@
 mod config;
+mod idempotency;
 mod publisher;
 mod server;
 mod subscriber;
@@ -1,23 +1,39 @@
+use axum::{http::header, routing::get, Router};
 use chrono::Utc;
+use control_plane::{
+    nats_control_subject, ConcurrencyLimit, ControlAck, ControlCommand, ControlError,
+    ControlResult, ControlService,
+};
 use deadpool_cyclone::{
     instance::cyclone::LocalUdsInstanceSpec, ActionRunRequest, ActionRunResultSuccess,
-    CycloneClient, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Manager,
-    Pool, ProgressMessage, ReconciliationRequest, ReconciliationResultSuccess,
-    ResolverFunctionRequest, ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
+    CycloneClient, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Instance,
+    LivenessStatus, Manager, Object, Pool, ProgressMessage, ReconciliationRequest,
+    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, ValidationRequest,
+    ValidationResultSuccess,
 };
 use futures::{channel::oneshot, join, StreamExt};
-use nats_subscriber::Request;
+use nats_subscriber::{Request, Subscription};
 use si_data_nats::NatsClient;
-use std::io;
+use std::{io, net::SocketAddr, time::Duration};
 use telemetry::prelude::*;
+use telemetry::ApplicationTelemetryClient;
 use thiserror::Error;
 use tokio::{
     signal::unix,
-    sync::{broadcast, mpsc},
+    sync::{broadcast, mpsc, OwnedSemaphorePermit},
+    time,
+};
+use veritech_core::{nats_heartbeat_subject, nats_liveness_subject, HEARTBEAT_INTERVAL};
+
+use crate::{
+    config::CycloneSpec, idempotency::SeenRequests, Config, FunctionSubscriber, Publisher,
+    PublisherError,
 };
 
-use crate::{config::CycloneSpec, Config, FunctionSubscriber, Publisher, PublisherError};
+/// How long the requests still delivered to a draining subscription are given to arrive, and then
+/// how long in-flight requests are given to complete, once a graceful shutdown has started.
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -32,8 +48,12 @@ pub enum ServerError {
     CycloneProgress(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
     #[error("cyclone spec builder error: {0}")]
     CycloneSpec(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
+    #[error("metrics server error: {0}")]
+    MetricsServer(#[from] hyper::Error),
     #[error("error connecting to nats: {0}")]
     NatsConnect(#[source] si_data_nats::NatsError),
+    #[error("nats subscription error: {0}")]
+    NatsSubscribe(#[source] si_data_nats::NatsError),
     #[error("no reply mailbox found")]
     NoReplyMailboxFound,
     #[error(transparent)]
@@ -46,6 +66,8 @@ pub enum ServerError {
     SchemaVariantDefinition(
         #[from] deadpool_cyclone::ExecutionError<SchemaVariantDefinitionResultSuccess>,
     ),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
     #[error(transparent)]
@@ -62,6 +84,9 @@ pub struct Server {
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    metrics_socket_addr: Option<SocketAddr>,
+    concurrency_limit: usize,
+    telemetry: Option<ApplicationTelemetryClient>,
     shutdown_broadcast_tx: broadcast::Sender<()>,
     shutdown_tx: mpsc::Sender<ShutdownSource>,
     shutdown_rx: oneshot::Receiver<()>,
@@ -119,6 +144,9 @@ impl Server {
                     nats,
                     subject_prefix: config.subject_prefix().map(|s| s.to_string()),
                     cyclone_pool,
+                    metrics_socket_addr: config.metrics_socket_addr(),
+                    concurrency_limit: config.concurrency_limit(),
+                    telemetry: None,
                     shutdown_broadcast_tx,
                     shutdown_tx,
                     shutdown_rx: graceful_shutdown_rx,
@@ -131,6 +159,12 @@ impl Server {
         }
     }
 
+    /// Sets the telemetry client of the process, so that its log level can be changed with a
+    /// [`ControlCommand`].
+    pub fn set_telemetry_client(&mut self, telemetry: ApplicationTelemetryClient) {
+        self.telemetry = Some(telemetry);
+    }
+
     /// Gets a shutdown handle that can trigger the server's graceful shutdown process.
     pub fn shutdown_handle(&self) -> VeritechShutdownHandle {
         VeritechShutdownHandle {
@@ -141,46 +175,225 @@ impl Server {
 
 impl Server {
     pub async fn run(self) -> ServerResult<()> {
+        if let Some(socket_addr) = self.metrics_socket_addr {
+            tokio::spawn(serve_metrics_task(
+                socket_addr,
+                self.shutdown_broadcast_tx.subscribe(),
+            ));
+        }
+
+        let (in_flight_tx, mut in_flight_rx) = mpsc::channel(1);
+        let in_flight = InFlight {
+            tx: in_flight_tx,
+            concurrency: ConcurrencyLimit::new(self.concurrency_limit),
+        };
+        let seen_requests = SeenRequests::default();
+
         let _ = join!(
             process_resolver_function_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                in_flight.clone(),
+                seen_requests.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_validation_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                in_flight.clone(),
+                seen_requests.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_action_run_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                in_flight.clone(),
+                seen_requests.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_reconciliation_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                in_flight.clone(),
+                seen_requests.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_schema_variant_definition_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                in_flight.clone(),
+                seen_requests.clone(),
+                self.shutdown_broadcast_tx.subscribe(),
+            ),
+            process_liveness_requests_task(
+                self.nats.clone(),
+                self.subject_prefix.clone(),
+                self.shutdown_broadcast_tx.subscribe(),
+            ),
+            process_control_commands_task(
+                self.nats.clone(),
+                self.subject_prefix.clone(),
+                self.cyclone_pool.clone(),
+                in_flight.concurrency.clone(),
+                self.telemetry,
+                self.shutdown_broadcast_tx.subscribe(),
+            ),
+            publish_heartbeats_task(
+                self.nats.clone(),
+                self.subject_prefix.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
         );
 
         let _ = self.shutdown_rx.await;
-        info!("received graceful shutdown, terminating server instance");
+        info!("received graceful shutdown, draining in-flight requests");
+
+        // Every in-flight request task holds a clone of the sender, so the receiver only yields
+        // `None` once the last of them has completed
+        drop(in_flight);
+        if time::timeout(GRACEFUL_SHUTDOWN_TIMEOUT, in_flight_rx.recv())
+            .await
+            .is_err()
+        {
+            warn!(
+                timeout = ?GRACEFUL_SHUTDOWN_TIMEOUT,
+                "in-flight requests did not complete before the shutdown deadline"
+            );
+        }
+
+        terminate_idle_cyclone_instances(&self.cyclone_pool).await;
+        if let Err(err) = self.nats.close().await {
+            warn!(error = ?err, "failed to close nats connection");
+        }
+        info!("terminating server instance");
 
         Ok(())
     }
 }
 
+/// Shared by the request loops, which admit requests for execution up to the concurrency limit of
+/// the server and so that a shutdown can wait for all in-flight requests to complete.
+#[derive(Clone, Debug)]
+struct InFlight {
+    tx: mpsc::Sender<()>,
+    concurrency: ConcurrencyLimit,
+}
+
+impl InFlight {
+    /// Waits until another request can be executed without going over the concurrency limit.
+    ///
+    /// Meanwhile, the requests still delivered to this instance are buffered by their
+    /// subscriptions, they aren't handed over to the other instances of the queue group.
+    async fn admit(&self) -> Option<Admission> {
+        let permit = self.concurrency.acquire().await?;
+        Some(Admission {
+            _in_flight: self.tx.clone(),
+            _permit: permit,
+        })
+    }
+}
+
+/// Held by every spawned request task for as long as its request executes.
+#[derive(Debug)]
+struct Admission {
+    _in_flight: mpsc::Sender<()>,
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Holds the request a loop has taken off its subscription until it is admitted.
+///
+/// Loops only wait on the concurrency limit once they have a request to execute, so that the ones
+/// over quiet subscriptions don't take up the room a busy one could use.
+#[derive(Debug)]
+struct Admitter<T> {
+    in_flight: InFlight,
+    pending: Option<T>,
+}
+
+impl<T> Admitter<T> {
+    fn new(in_flight: InFlight) -> Self {
+        Self {
+            in_flight,
+            pending: None,
+        }
+    }
+
+    fn is_waiting(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn hold(&mut self, request: T) {
+        self.pending = Some(request);
+    }
+
+    /// Waits for room to execute the held request, returning it along with its admission.
+    ///
+    /// This is cancel safe, the request stays held if the future is dropped before completing.
+    async fn admit(&mut self) -> Option<(T, Admission)> {
+        let admission = self.in_flight.admit().await?;
+        self.pending.take().map(|request| (request, admission))
+    }
+}
+
+/// Takes every idle Cyclone instance out of the pool and terminates it, giving each child process
+/// a chance to exit cleanly, before closing the pool.
+async fn terminate_idle_cyclone_instances(cyclone_pool: &Pool<LocalUdsInstanceSpec>) {
+    let available = usize::try_from(cyclone_pool.status().available).unwrap_or(0);
+    for _ in 0..available {
+        match cyclone_pool.get().await {
+            Ok(instance) => {
+                if let Err(err) = Object::take(instance).terminate().await {
+                    warn!(error = ?err, "failed to terminate cyclone instance");
+                }
+            }
+            Err(err) => {
+                warn!(error = ?err, "failed to take idle cyclone instance from pool");
+                break;
+            }
+        }
+    }
+    cyclone_pool.close();
+}
+
+async fn serve_metrics_task(
+    socket_addr: SocketAddr,
+    shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    if let Err(err) = serve_metrics(socket_addr, shutdown_broadcast_rx).await {
+        warn!(error = ?err, "serving metrics failed");
+    }
+}
+
+async fn serve_metrics(
+    socket_addr: SocketAddr,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) -> ServerResult<()> {
+    let router = Router::new().route(
+        "/metrics",
+        get(|| async {
+            (
+                [(header::CONTENT_TYPE, telemetry::metrics::CONTENT_TYPE)],
+                telemetry::metrics::render(),
+            )
+        }),
+    );
+
+    info!(%socket_addr, "serving metrics");
+    axum::Server::try_bind(&socket_addr)?
+        .serve(router.into_make_service())
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_broadcast_rx.recv().await;
+        })
+        .await?;
+
+    Ok(())
+}
+
 pub struct VeritechShutdownHandle {
     shutdown_tx: mpsc::Sender<ShutdownSource>,
 }
@@ -202,12 +415,16 @@ async fn process_resolver_function_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
     if let Err(err) = process_resolver_function_requests(
         nats,
         subject_prefix,
         cyclone_pool,
+        in_flight,
+        seen_requests,
         shutdown_broadcast_rx,
     )
     .await
@@ -220,29 +437,38 @@ async fn process_resolver_function_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests =
         FunctionSubscriber::resolver_function(&nats, subject_prefix.as_deref()).await?;
+    let mut admitter = Admitter::new(in_flight);
+    let mut drain_deadline = None;
 
     loop {
         tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process resolver function requests task received shutdown");
+            // Got a broadcasted shutdown message, so stop taking new requests and only process the
+            // ones already delivered to this instance
+            _ = shutdown_broadcast_rx.recv(), if drain_deadline.is_none() => {
+                trace!("process resolver function requests task received shutdown, draining");
+                requests.drain().await?;
+                drain_deadline = Some(time::Instant::now() + GRACEFUL_SHUTDOWN_TIMEOUT);
+            }
+            // The subscription did not finish draining in time
+            _ = time::sleep_until(drain_deadline.unwrap_or_else(time::Instant::now)),
+                if drain_deadline.is_some() => {
+                warn!(
+                    "resolver function requests did not drain before the shutdown deadline"
+                );
                 break;
             }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
+            // Got the next message from the subscriber, which waits for room under the concurrency
+            // limit before any other is read
+            request = requests.next(), if !admitter.is_waiting() => {
                 match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(resolver_function_request_task(
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            request,
-                        ));
-                    }
+                    Some(Ok(request)) if seen_requests.is_duplicate(&request) => {}
+                    Some(Ok(request)) => admitter.hold(request),
                     Some(Err(err)) => {
                         warn!(error = ?err, "next resolver function request had error");
                     }
@@ -252,6 +478,18 @@ async fn process_resolver_function_requests(
                     }
                 }
             }
+            // Got room under the concurrency limit for the request taken off the subscriber
+            admitted = admitter.admit(), if admitter.is_waiting() => {
+                if let Some((request, admission)) = admitted {
+                    // Spawn a task an process the request
+                    tokio::spawn(resolver_function_request_task(
+                        nats.clone(),
+                        cyclone_pool.clone(),
+                        admission,
+                        request,
+                    ));
+                }
+            }
             // All other arms are closed, nothing left to do but return
             else => {
                 trace!("returning with all select arms closed");
@@ -260,17 +498,26 @@ async fn process_resolver_function_requests(
         }
     }
 
-    // Unsubscribe from subscription
-    requests.unsubscribe().await?;
+    // A drained subscription has already unsubscribed
+    if drain_deadline.is_none() {
+        requests.unsubscribe().await?;
+    }
 
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.resolver_function_request",
+    skip_all,
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn resolver_function_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    _admission: Admission,
     request: Request<ResolverFunctionRequest>,
 ) {
+    request.trace_context().apply_to(&Span::current());
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = match reply_mailbox {
         Some(reply_mailbox) => reply_mailbox,
@@ -364,10 +611,19 @@ async fn process_validation_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
-    if let Err(err) =
-        process_validation_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx).await
+    if let Err(err) = process_validation_requests(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        in_flight,
+        seen_requests,
+        shutdown_broadcast_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing validation requests failed");
     }
@@ -377,28 +633,37 @@ async fn process_validation_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests = FunctionSubscriber::validation(&nats, subject_prefix.as_deref()).await?;
+    let mut admitter = Admitter::new(in_flight);
+    let mut drain_deadline = None;
 
     loop {
         tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process validation requests task received shutdown");
+            // Got a broadcasted shutdown message, so stop taking new requests and only process the
+            // ones already delivered to this instance
+            _ = shutdown_broadcast_rx.recv(), if drain_deadline.is_none() => {
+                trace!("process validation requests task received shutdown, draining");
+                requests.drain().await?;
+                drain_deadline = Some(time::Instant::now() + GRACEFUL_SHUTDOWN_TIMEOUT);
+            }
+            // The subscription did not finish draining in time
+            _ = time::sleep_until(drain_deadline.unwrap_or_else(time::Instant::now)),
+                if drain_deadline.is_some() => {
+                warn!(
+                    "validation requests did not drain before the shutdown deadline"
+                );
                 break;
             }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
+            // Got the next message from the subscriber, which waits for room under the concurrency
+            // limit before any other is read
+            request = requests.next(), if !admitter.is_waiting() => {
                 match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(validation_request_task(
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            request,
-                        ));
-                    }
+                    Some(Ok(request)) if seen_requests.is_duplicate(&request) => {}
+                    Some(Ok(request)) => admitter.hold(request),
                     Some(Err(err)) => {
                         warn!(error = ?err, "next validation request had error");
                     }
@@ -408,6 +673,18 @@ async fn process_validation_requests(
                     }
                 }
             }
+            // Got room under the concurrency limit for the request taken off the subscriber
+            admitted = admitter.admit(), if admitter.is_waiting() => {
+                if let Some((request, admission)) = admitted {
+                    // Spawn a task an process the request
+                    tokio::spawn(validation_request_task(
+                        nats.clone(),
+                        cyclone_pool.clone(),
+                        admission,
+                        request,
+                    ));
+                }
+            }
             // All other arms are closed, nothing left to do but return
             else => {
                 trace!("returning with all select arms closed");
@@ -416,17 +693,26 @@ async fn process_validation_requests(
         }
     }
 
-    // Unsubscribe from subscription
-    requests.unsubscribe().await?;
+    // A drained subscription has already unsubscribed
+    if drain_deadline.is_none() {
+        requests.unsubscribe().await?;
+    }
 
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.validation_request",
+    skip_all,
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn validation_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    _admission: Admission,
     request: Request<ValidationRequest>,
 ) {
+    request.trace_context().apply_to(&Span::current());
     if let Err(err) = validation_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "validation execution failed");
     }
@@ -477,12 +763,16 @@ async fn process_schema_variant_definition_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
     if let Err(err) = process_schema_variant_definition_requests(
         nats,
         subject_prefix,
         cyclone_pool,
+        in_flight,
+        seen_requests,
         shutdown_broadcast_rx,
     )
     .await
@@ -495,29 +785,40 @@ async fn process_schema_variant_definition_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests =
         FunctionSubscriber::schema_variant_definition(&nats, subject_prefix.as_deref()).await?;
+    let mut admitter = Admitter::new(in_flight);
+    let mut drain_deadline = None;
 
     loop {
         tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process schema_variant_definition requests task received shutdown");
+            // Got a broadcasted shutdown message, so stop taking new requests and only process the
+            // ones already delivered to this instance
+            _ = shutdown_broadcast_rx.recv(), if drain_deadline.is_none() => {
+                trace!(
+                    "process schema_variant_definition requests task received shutdown, draining"
+                );
+                requests.drain().await?;
+                drain_deadline = Some(time::Instant::now() + GRACEFUL_SHUTDOWN_TIMEOUT);
+            }
+            // The subscription did not finish draining in time
+            _ = time::sleep_until(drain_deadline.unwrap_or_else(time::Instant::now)),
+                if drain_deadline.is_some() => {
+                warn!(
+                    "schema variant definition requests did not drain before the shutdown deadline"
+                );
                 break;
             }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
+            // Got the next message from the subscriber, which waits for room under the concurrency
+            // limit before any other is read
+            request = requests.next(), if !admitter.is_waiting() => {
                 match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(schema_variant_definition_request_task(
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            request,
-                        ));
-                    }
+                    Some(Ok(request)) if seen_requests.is_duplicate(&request) => {}
+                    Some(Ok(request)) => admitter.hold(request),
                     Some(Err(err)) => {
                         warn!(error = ?err, "next schema variant definition request had error");
                     }
@@ -527,6 +828,18 @@ async fn process_schema_variant_definition_requests(
                     }
                 }
             }
+            // Got room under the concurrency limit for the request taken off the subscriber
+            admitted = admitter.admit(), if admitter.is_waiting() => {
+                if let Some((request, admission)) = admitted {
+                    // Spawn a task an process the request
+                    tokio::spawn(schema_variant_definition_request_task(
+                        nats.clone(),
+                        cyclone_pool.clone(),
+                        admission,
+                        request,
+                    ));
+                }
+            }
             // All other arms are closed, nothing left to do but return
             else => {
                 trace!("returning with all select arms closed");
@@ -535,17 +848,26 @@ async fn process_schema_variant_definition_requests(
         }
     }
 
-    // Unsubscribe from subscription
-    requests.unsubscribe().await?;
+    // A drained subscription has already unsubscribed
+    if drain_deadline.is_none() {
+        requests.unsubscribe().await?;
+    }
 
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.schema_variant_definition_request",
+    skip_all,
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn schema_variant_definition_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    _admission: Admission,
     request: Request<SchemaVariantDefinitionRequest>,
 ) {
+    request.trace_context().apply_to(&Span::current());
     if let Err(err) = schema_variant_definition_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "schema variant definition execution failed");
     }
@@ -597,10 +919,19 @@ async fn process_action_run_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
-    if let Err(err) =
-        process_action_run_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx).await
+    if let Err(err) = process_action_run_requests(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        in_flight,
+        seen_requests,
+        shutdown_broadcast_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing action run requests failed");
     }
@@ -610,28 +941,37 @@ async fn process_action_run_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests = FunctionSubscriber::action_run(&nats, subject_prefix.as_deref()).await?;
+    let mut admitter = Admitter::new(in_flight);
+    let mut drain_deadline = None;
 
     loop {
         tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process action_run requests task received shutdown");
+            // Got a broadcasted shutdown message, so stop taking new requests and only process the
+            // ones already delivered to this instance
+            _ = shutdown_broadcast_rx.recv(), if drain_deadline.is_none() => {
+                trace!("process action_run requests task received shutdown, draining");
+                requests.drain().await?;
+                drain_deadline = Some(time::Instant::now() + GRACEFUL_SHUTDOWN_TIMEOUT);
+            }
+            // The subscription did not finish draining in time
+            _ = time::sleep_until(drain_deadline.unwrap_or_else(time::Instant::now)),
+                if drain_deadline.is_some() => {
+                warn!(
+                    "action run requests did not drain before the shutdown deadline"
+                );
                 break;
             }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
+            // Got the next message from the subscriber, which waits for room under the concurrency
+            // limit before any other is read
+            request = requests.next(), if !admitter.is_waiting() => {
                 match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(action_run_request_task(
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            request,
-                        ));
-                    }
+                    Some(Ok(request)) if seen_requests.is_duplicate(&request) => {}
+                    Some(Ok(request)) => admitter.hold(request),
                     Some(Err(err)) => {
                         warn!(error = ?err, "next action run request had error");
                     }
@@ -641,6 +981,18 @@ async fn process_action_run_requests(
                     }
                 }
             }
+            // Got room under the concurrency limit for the request taken off the subscriber
+            admitted = admitter.admit(), if admitter.is_waiting() => {
+                if let Some((request, admission)) = admitted {
+                    // Spawn a task an process the request
+                    tokio::spawn(action_run_request_task(
+                        nats.clone(),
+                        cyclone_pool.clone(),
+                        admission,
+                        request,
+                    ));
+                }
+            }
             // All other arms are closed, nothing left to do but return
             else => {
                 trace!("returning with all select arms closed");
@@ -649,17 +1001,26 @@ async fn process_action_run_requests(
         }
     }
 
-    // Unsubscribe from subscription
-    requests.unsubscribe().await?;
+    // A drained subscription has already unsubscribed
+    if drain_deadline.is_none() {
+        requests.unsubscribe().await?;
+    }
 
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.action_run_request",
+    skip_all,
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn action_run_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    _admission: Admission,
     request: Request<ActionRunRequest>,
 ) {
+    request.trace_context().apply_to(&Span::current());
     if let Err(err) = action_run_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "action run execution failed");
     }
@@ -711,11 +1072,19 @@ async fn process_reconciliation_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
-    if let Err(err) =
-        process_reconciliation_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx)
-            .await
+    if let Err(err) = process_reconciliation_requests(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        in_flight,
+        seen_requests,
+        shutdown_broadcast_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing reconciliation requests failed");
     }
@@ -725,28 +1094,37 @@ async fn process_reconciliation_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    in_flight: InFlight,
+    seen_requests: SeenRequests,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests = FunctionSubscriber::reconciliation(&nats, subject_prefix.as_deref()).await?;
+    let mut admitter = Admitter::new(in_flight);
+    let mut drain_deadline = None;
 
     loop {
         tokio::select! {
-            // Got a broadcasted shutdown message
-            _ = shutdown_broadcast_rx.recv() => {
-                trace!("process reconciliation requests task received shutdown");
+            // Got a broadcasted shutdown message, so stop taking new requests and only process the
+            // ones already delivered to this instance
+            _ = shutdown_broadcast_rx.recv(), if drain_deadline.is_none() => {
+                trace!("process reconciliation requests task received shutdown, draining");
+                requests.drain().await?;
+                drain_deadline = Some(time::Instant::now() + GRACEFUL_SHUTDOWN_TIMEOUT);
+            }
+            // The subscription did not finish draining in time
+            _ = time::sleep_until(drain_deadline.unwrap_or_else(time::Instant::now)),
+                if drain_deadline.is_some() => {
+                warn!(
+                    "reconciliation requests did not drain before the shutdown deadline"
+                );
                 break;
             }
-            // Got the next message on from the subscriber
-            request = requests.next() => {
+            // Got the next message from the subscriber, which waits for room under the concurrency
+            // limit before any other is read
+            request = requests.next(), if !admitter.is_waiting() => {
                 match request {
-                    Some(Ok(request)) => {
-                        // Spawn a task an process the request
-                        tokio::spawn(reconciliation_request_task(
-                            nats.clone(),
-                            cyclone_pool.clone(),
-                            request,
-                        ));
-                    }
+                    Some(Ok(request)) if seen_requests.is_duplicate(&request) => {}
+                    Some(Ok(request)) => admitter.hold(request),
                     Some(Err(err)) => {
                         warn!(error = ?err, "next reconciliation request had error");
                     }
@@ -756,6 +1134,18 @@ async fn process_reconciliation_requests(
                     }
                 }
             }
+            // Got room under the concurrency limit for the request taken off the subscriber
+            admitted = admitter.admit(), if admitter.is_waiting() => {
+                if let Some((request, admission)) = admitted {
+                    // Spawn a task an process the request
+                    tokio::spawn(reconciliation_request_task(
+                        nats.clone(),
+                        cyclone_pool.clone(),
+                        admission,
+                        request,
+                    ));
+                }
+            }
             // All other arms are closed, nothing left to do but return
             else => {
                 trace!("returning with all select arms closed");
@@ -764,17 +1154,26 @@ async fn process_reconciliation_requests(
         }
     }
 
-    // Unsubscribe from subscription
-    requests.unsubscribe().await?;
+    // A drained subscription has already unsubscribed
+    if drain_deadline.is_none() {
+        requests.unsubscribe().await?;
+    }
 
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.reconciliation_request",
+    skip_all,
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn reconciliation_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    _admission: Admission,
     request: Request<ReconciliationRequest>,
 ) {
+    request.trace_context().apply_to(&Span::current());
     if let Err(err) = reconciliation_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "reconciliation execution failed");
     }
@@ -822,6 +1221,195 @@ async fn reconciliation_request(
     Ok(())
 }
 
+async fn process_liveness_requests_task(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    if let Err(err) = process_liveness_requests(nats, subject_prefix, shutdown_broadcast_rx).await {
+        warn!(error = ?err, "processing liveness requests failed");
+    }
+}
+
+async fn process_liveness_requests(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) -> ServerResult<()> {
+    let mut requests = nats
+        .subscribe(nats_liveness_subject(subject_prefix.as_deref()))
+        .await
+        .map_err(ServerError::NatsSubscribe)?;
+
+    loop {
+        tokio::select! {
+            _ = shutdown_broadcast_rx.recv() => {
+                trace!("process liveness requests task received shutdown");
+                break;
+            }
+            request = requests.next() => {
+                match request {
+                    Some(Ok(request)) => {
+                        if let Err(err) = request.respond(LivenessStatus::Ok.as_str()).await {
+                            warn!(error = ?err, "failed to respond to liveness request");
+                        }
+                    }
+                    Some(Err(err)) => {
+                        warn!(error = ?err, "next liveness request had error");
+                    }
+                    None => {
+                        trace!("liveness requests subscriber stream has closed");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    requests
+        .unsubscribe()
+        .await
+        .map_err(ServerError::NatsSubscribe)?;
+
+    Ok(())
+}
+
+async fn process_control_commands_task(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    concurrency_limit: ConcurrencyLimit,
+    telemetry: Option<ApplicationTelemetryClient>,
+    shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    if let Err(err) = process_control_commands(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        concurrency_limit,
+        telemetry,
+        shutdown_broadcast_rx,
+    )
+    .await
+    {
+        warn!(error = ?err, "processing control commands failed");
+    }
+}
+
+async fn process_control_commands(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    concurrency_limit: ConcurrencyLimit,
+    mut telemetry: Option<ApplicationTelemetryClient>,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) -> ServerResult<()> {
+    let mut commands = Subscription::<ControlCommand>::create(nats_control_subject(
+        subject_prefix.as_deref(),
+        ControlService::Veritech,
+    ))
+    .check_for_reply_mailbox()
+    .start(&nats)
+    .await?;
+    // Veritech instances have no configured id, so they are told apart by their NATS connection
+    let instance_id = nats.metadata().messaging_consumer_id().to_owned();
+
+    loop {
+        tokio::select! {
+            _ = shutdown_broadcast_rx.recv() => {
+                trace!("process control commands task received shutdown");
+                break;
+            }
+            request = commands.next() => {
+                let (command, reply_mailbox) = match request {
+                    Some(Ok(request)) => request.into_parts(),
+                    Some(Err(err)) => {
+                        warn!(error = ?err, "next control command had error");
+                        continue;
+                    }
+                    None => {
+                        trace!("control commands subscriber stream has closed");
+                        break;
+                    }
+                };
+
+                let name = command.name();
+                let result = apply_control_command(
+                    command,
+                    &cyclone_pool,
+                    &concurrency_limit,
+                    telemetry.as_mut(),
+                )
+                .await;
+                match &result {
+                    Ok(()) => info!(command = name, "applied control command"),
+                    Err(err) => warn!(error = ?err, command = name, "rejected control command"),
+                }
+
+                if let Some(reply_mailbox) = reply_mailbox {
+                    let ack = ControlAck::new(ControlService::Veritech, &instance_id, result);
+                    let payload = serde_json::to_vec(&ack)?;
+                    if let Err(err) = nats.publish(reply_mailbox, payload).await {
+                        warn!(error = ?err, "failed to acknowledge control command");
+                    }
+                }
+            }
+        }
+    }
+
+    commands.unsubscribe().await?;
+
+    Ok(())
+}
+
+async fn apply_control_command(
+    command: ControlCommand,
+    cyclone_pool: &Pool<LocalUdsInstanceSpec>,
+    concurrency_limit: &ConcurrencyLimit,
+    telemetry: Option<&mut ApplicationTelemetryClient>,
+) -> ControlResult<()> {
+    match (command, telemetry) {
+        (ControlCommand::SetConcurrencyLimit { limit }, _) => concurrency_limit.set(limit),
+        (ControlCommand::SetCyclonePoolSize { size }, _) => {
+            if size == 0 {
+                return Err(ControlError::InvalidLimit(size));
+            }
+            // Idle instances over the new size are dropped, busy ones as they are returned
+            cyclone_pool.resize(size);
+            Ok(())
+        }
+        (ControlCommand::SetLogLevel { level }, Some(telemetry)) => level.apply(telemetry).await,
+        (command, _) => Err(ControlError::Unsupported(
+            ControlService::Veritech,
+            command.name(),
+        )),
+    }
+}
+
+async fn publish_heartbeats_task(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    let subject = nats_heartbeat_subject();
+    let payload = subject_prefix.unwrap_or_default();
+    let mut interval = time::interval(HEARTBEAT_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = shutdown_broadcast_rx.recv() => {
+                trace!("publish heartbeats task received shutdown");
+                break;
+            }
+            _ = interval.tick() => {
+                if let Err(err) = nats.publish(&subject, payload.as_bytes()).await {
+                    warn!(error = ?err, "failed to publish heartbeat");
+                }
+            }
+        }
+    }
+}
+
 async fn connect_to_nats(config: &Config) -> ServerResult<NatsClient> {
     info!("connecting to NATS; url={}", config.nats().url);
 
@@ -893,3 +1481,52 @@ impl Default for ShutdownSource {
         Self::Handle
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn saturated_kind_does_not_block_the_others() {
+        let (tx, _rx) = mpsc::channel(1);
+        let in_flight = InFlight {
+            tx,
+            concurrency: ConcurrencyLimit::new(2),
+        };
+        let mut resolvers = Admitter::new(in_flight.clone());
+        let mut validations = Admitter::new(in_flight);
+
+        // Resolver requests take all the room there is while the validation subscription is
+        // quiet, and more of them keep arriving
+        let mut running = Vec::new();
+        for request in ["resolver-1", "resolver-2"] {
+            resolvers.hold(request);
+            let (_, admission) = resolvers.admit().await.expect("failed to admit request");
+            running.push(admission);
+        }
+        resolvers.hold("resolver-3");
+        let mut resolver = Box::pin(resolvers.admit());
+        assert!((&mut resolver).now_or_never().is_none());
+
+        validations.hold("validation-1");
+        let mut validation = Box::pin(validations.admit());
+        assert!((&mut validation).now_or_never().is_none());
+
+        drop(running.pop());
+        let (request, admission) = resolver.await.expect("failed to admit request");
+        assert_eq!("resolver-3", request);
+        running.push(admission);
+        resolvers.hold("resolver-4");
+        let mut resolver = Box::pin(resolvers.admit());
+        assert!((&mut resolver).now_or_never().is_none());
+
+        // The validation request gets the next room, ahead of the resolver requests which arrived
+        // after it
+        drop(running.pop());
+        assert!((&mut resolver).now_or_never().is_none());
+        let (request, _admission) = validation.await.expect("failed to admit request");
+        assert_eq!("validation-1", request);
+    }
+}